@@ -0,0 +1,665 @@
+//! Backup and restore of AIT42 data
+//!
+//! `ait42 backup create` writes a gzipped tarball holding the data directory
+//! (`~/.ait42`: sessions, budget, telemetry), the configuration file, any
+//! `themes/` and `snippets/` next to it, and the list of installed plugins.
+//! A `manifest.json` at the root of the archive records the format version
+//! and a SHA-256 checksum for every file.
+//!
+//! `ait42 backup restore` reads the whole archive into memory, checks the
+//! format version, every checksum and the schema of the configuration and
+//! session files, and only then overwrites anything on disk.
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Version of the archive layout written by this build
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Name of the manifest at the root of the archive
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Archive prefix for files from the data directory
+const DATA_PREFIX: &str = "data";
+
+/// Archive prefix for files from the configuration directory
+const CONFIG_PREFIX: &str = "config";
+
+/// Configuration subdirectories included alongside the config file
+const CONFIG_SUBDIRS: [&str; 2] = ["themes", "snippets"];
+
+/// Data subdirectory holding the backups themselves, never archived
+const BACKUPS_DIR: &str = "backups";
+
+/// Data subdirectory holding the session history
+const SESSIONS_DIR: &str = "sessions";
+
+/// Newest encrypted sessions envelope this build can restore
+const SESSION_ENVELOPE_VERSION: u32 = 1;
+
+/// Locations that make up a backup
+#[derive(Debug, Clone)]
+pub struct BackupPaths {
+    /// AIT42 data directory (`~/.ait42`)
+    pub data_dir: PathBuf,
+    /// Configuration file
+    pub config_file: PathBuf,
+    /// Directory of installed plugins
+    pub plugins_dir: PathBuf,
+}
+
+impl BackupPaths {
+    /// Default locations, with the configuration file given by the caller
+    pub fn new(config_file: PathBuf) -> Result<Self> {
+        let home = directories::BaseDirs::new()
+            .context("Cannot determine home directory")?
+            .home_dir()
+            .to_path_buf();
+
+        Ok(Self {
+            data_dir: home.join(".ait42"),
+            config_file,
+            plugins_dir: std::env::current_dir()
+                .context("Cannot determine current directory")?
+                .join("plugins"),
+        })
+    }
+
+    /// Directory holding the configuration file
+    fn config_dir(&self) -> &Path {
+        self.config_file.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Default location for a new backup
+    pub fn default_archive(&self) -> PathBuf {
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        self.data_dir
+            .join(BACKUPS_DIR)
+            .join(format!("ait42-backup-{}.tar.gz", stamp))
+    }
+
+    /// Where an archive entry is restored to
+    fn destination(&self, name: &str) -> Option<PathBuf> {
+        let relative = safe_relative(name)?;
+        let mut components = relative.components();
+        let prefix = components.next()?.as_os_str().to_str()?;
+        let rest = components.as_path();
+        if rest.as_os_str().is_empty() {
+            return None;
+        }
+
+        match prefix {
+            DATA_PREFIX => Some(self.data_dir.join(rest)),
+            CONFIG_PREFIX if rest == Path::new("config.toml") => Some(self.config_file.clone()),
+            CONFIG_PREFIX => Some(self.config_dir().join(rest)),
+            _ => None,
+        }
+    }
+}
+
+/// One archived file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// Path inside the archive
+    pub path: String,
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the contents
+    pub sha256: String,
+}
+
+/// A plugin installed when the backup was taken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupPlugin {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+}
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub editor_version: String,
+    pub created_at: String,
+    pub files: Vec<BackupFile>,
+    #[serde(default)]
+    pub plugins: Vec<BackupPlugin>,
+}
+
+/// A backup read into memory and verified
+#[derive(Debug)]
+pub struct Backup {
+    pub manifest: BackupManifest,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+/// A stored session, as written by the desktop app's session store
+///
+/// Only the fields every session has are checked; the rest may change
+/// between versions. The fields are read by deserializing alone.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct StoredSession {
+    id: String,
+    r#type: String,
+    task: String,
+    status: String,
+    created_at: String,
+    updated_at: String,
+    instances: Vec<serde::de::IgnoredAny>,
+    chat_history: Vec<serde::de::IgnoredAny>,
+}
+
+/// An encrypted sessions file or line, as written with encryption at rest
+#[derive(Debug, Deserialize)]
+struct SessionEnvelope {
+    encrypted: u32,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Result of restoring a backup
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    /// Files written (or that would be written, in a dry run)
+    pub restored: Vec<PathBuf>,
+    /// Plugins listed in the backup that are not installed
+    pub missing_plugins: Vec<BackupPlugin>,
+}
+
+/// Write a backup of `paths` to `archive`
+pub fn create_backup(paths: &BackupPaths, archive: &Path) -> Result<BackupManifest> {
+    let mut contents = Vec::new();
+
+    if paths.data_dir.is_dir() {
+        let backups = paths.data_dir.join(BACKUPS_DIR);
+        for path in collect_files(&paths.data_dir, Some(&backups))? {
+            let relative = path.strip_prefix(&paths.data_dir)?;
+            contents.push((archive_name(DATA_PREFIX, relative), path));
+        }
+    }
+    if paths.config_file.is_file() {
+        contents.push((format!("{}/config.toml", CONFIG_PREFIX), paths.config_file.clone()));
+    }
+    for subdir in CONFIG_SUBDIRS {
+        let dir = paths.config_dir().join(subdir);
+        if dir.is_dir() {
+            for path in collect_files(&dir, None)? {
+                let relative = path.strip_prefix(paths.config_dir())?;
+                contents.push((archive_name(CONFIG_PREFIX, relative), path));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut blobs = Vec::new();
+    for (name, path) in contents {
+        let data = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        files.push(BackupFile {
+            path: name.clone(),
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+        });
+        blobs.push((name, data));
+    }
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        editor_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        files,
+        plugins: installed_plugins(&paths.plugins_dir),
+    };
+
+    if let Some(parent) = archive.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let partial = archive.with_extension("partial");
+    let file = fs::File::create(&partial)
+        .with_context(|| format!("Failed to create {}", partial.display()))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    append_bytes(&mut builder, MANIFEST_NAME, &serde_json::to_vec_pretty(&manifest)?)?;
+    for (name, data) in &blobs {
+        append_bytes(&mut builder, name, data)?;
+    }
+    builder.into_inner()?.finish()?;
+    fs::rename(&partial, archive)
+        .with_context(|| format!("Failed to write {}", archive.display()))?;
+
+    Ok(manifest)
+}
+
+/// Read `archive` and verify its manifest, checksums and schemas
+pub fn read_backup(archive: &Path) -> Result<Backup> {
+    let file =
+        fs::File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    let mut tar = tar::Archive::new(GzDecoder::new(file));
+
+    let mut manifest = None;
+    let mut files = BTreeMap::new();
+    for entry in tar.entries().context("Not a backup archive")? {
+        let mut entry = entry.context("Corrupt backup archive")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+
+        if name == MANIFEST_NAME {
+            manifest = Some(
+                serde_json::from_slice::<BackupManifest>(&data)
+                    .context("Invalid backup manifest")?,
+            );
+        } else {
+            files.insert(name, data);
+        }
+    }
+
+    let manifest = manifest.context("Backup archive has no manifest")?;
+    let backup = Backup { manifest, files };
+    backup.verify()?;
+    Ok(backup)
+}
+
+impl Backup {
+    /// Check the archive against its manifest before anything is restored
+    fn verify(&self) -> Result<()> {
+        if self.manifest.format_version > BACKUP_FORMAT_VERSION {
+            bail!(
+                "Backup format version {} is newer than supported version {} (created by AIT42 {})",
+                self.manifest.format_version,
+                BACKUP_FORMAT_VERSION,
+                self.manifest.editor_version
+            );
+        }
+
+        for file in &self.manifest.files {
+            let data = self
+                .files
+                .get(&file.path)
+                .with_context(|| format!("Backup is missing {}", file.path))?;
+            if data.len() as u64 != file.size || sha256_hex(data) != file.sha256 {
+                bail!("Checksum mismatch for {}", file.path);
+            }
+            if safe_relative(&file.path).is_none() {
+                bail!("Unsafe path in backup: {}", file.path);
+            }
+            validate_schema(&file.path, data)?;
+        }
+        if let Some(extra) = self
+            .files
+            .keys()
+            .find(|name| !self.manifest.files.iter().any(|f| &f.path == *name))
+        {
+            bail!("Backup contains {} which is not in the manifest", extra);
+        }
+
+        Ok(())
+    }
+
+    /// Write the backup over `paths`
+    pub fn restore(&self, paths: &BackupPaths, dry_run: bool) -> Result<RestoreReport> {
+        let mut report = RestoreReport::default();
+
+        let mut writes = Vec::new();
+        for file in &self.manifest.files {
+            let destination = paths
+                .destination(&file.path)
+                .with_context(|| format!("Unknown location in backup: {}", file.path))?;
+            writes.push((destination, &self.files[&file.path]));
+        }
+
+        for (destination, data) in writes {
+            if !dry_run {
+                write_atomic(&destination, data)?;
+            }
+            report.restored.push(destination);
+        }
+
+        let installed = installed_plugins(&paths.plugins_dir);
+        report.missing_plugins = self
+            .manifest
+            .plugins
+            .iter()
+            .filter(|plugin| !installed.iter().any(|p| p.id == plugin.id))
+            .cloned()
+            .collect();
+
+        Ok(report)
+    }
+}
+
+/// Reject files whose contents the current build cannot read
+fn validate_schema(name: &str, data: &[u8]) -> Result<()> {
+    let text = || std::str::from_utf8(data).with_context(|| format!("{} is not UTF-8", name));
+
+    if name == format!("{}/config.toml", CONFIG_PREFIX) {
+        let config: ait42_config::Config = toml::from_str(text()?)
+            .with_context(|| format!("{} does not match the configuration schema", name))?;
+        ait42_config::ConfigLoader::with_path(PathBuf::new())
+            .validate(&config)
+            .with_context(|| format!("{} is not a valid configuration", name))?;
+    } else if name.starts_with(&format!("{}/{}/", DATA_PREFIX, SESSIONS_DIR)) {
+        if name.ends_with(".json") {
+            validate_sessions(text()?, true)
+                .with_context(|| format!("{} does not match the session schema", name))?;
+        } else if name.ends_with(".jsonl") {
+            for (i, line) in text()?.lines().enumerate() {
+                if !line.trim().is_empty() {
+                    validate_sessions(line, false).with_context(|| {
+                        format!("{}:{} does not match the session schema", name, i + 1)
+                    })?;
+                }
+            }
+        }
+    } else if name.starts_with(DATA_PREFIX) && name.ends_with(".json") {
+        serde_json::from_str::<serde_json::Value>(text()?)
+            .with_context(|| format!("{} is not valid JSON", name))?;
+    }
+    Ok(())
+}
+
+/// Check `content`, a session list or with `list` unset a single session,
+/// either of which may be encrypted
+fn validate_sessions(content: &str, list: bool) -> Result<()> {
+    if content.trim_start().starts_with('{') {
+        if let Ok(envelope) = serde_json::from_str::<SessionEnvelope>(content) {
+            if envelope.encrypted > SESSION_ENVELOPE_VERSION {
+                bail!(
+                    "Encrypted sessions version {} is newer than supported version {}",
+                    envelope.encrypted,
+                    SESSION_ENVELOPE_VERSION
+                );
+            }
+            if envelope.nonce.is_empty() || envelope.ciphertext.is_empty() {
+                bail!("Encrypted sessions are incomplete");
+            }
+            return Ok(());
+        }
+    }
+    if list {
+        serde_json::from_str::<Vec<StoredSession>>(content)?;
+    } else {
+        serde_json::from_str::<StoredSession>(content)?;
+    }
+    Ok(())
+}
+
+/// Plugins found in `plugins_dir`, sorted by id
+fn installed_plugins(plugins_dir: &Path) -> Vec<BackupPlugin> {
+    let Ok(entries) = fs::read_dir(plugins_dir) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<BackupPlugin> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path().join("plugin.json")).ok())
+        .filter_map(|manifest| serde_json::from_str(&manifest).ok())
+        .collect();
+    plugins.sort_by(|a, b| a.id.cmp(&b.id));
+    plugins
+}
+
+/// Regular files under `dir`, skipping `exclude`, in a stable order
+fn collect_files(dir: &Path, exclude: Option<&Path>) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let walker = walkdir::WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| Some(entry.path()) != exclude);
+    for entry in walker {
+        let entry = entry.with_context(|| format!("Failed to read {}", dir.display()))?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+/// Archive name for `relative` under `prefix`, always with `/` separators
+fn archive_name(prefix: &str, relative: &Path) -> String {
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    format!("{}/{}", prefix, parts.join("/"))
+}
+
+/// `name` as a relative path that cannot escape its destination
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(name);
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then_some(path)
+}
+
+fn append_bytes<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, data)
+        .with_context(|| format!("Failed to archive {}", name))
+}
+
+/// Replace `path` with `data` without leaving a partial file behind
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".restore");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, data).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to restore {}", path.display()))
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SESSION: &str = r#"[{"id":"s1","type":"competition","task":"t","status":"completed","createdAt":"2025-01-01T00:00:00Z","updatedAt":"2025-01-01T00:00:00Z","instances":[],"chatHistory":[]}]"#;
+
+    fn paths(root: &Path) -> BackupPaths {
+        BackupPaths {
+            data_dir: root.join("home/.ait42"),
+            config_file: root.join("config/config.toml"),
+            plugins_dir: root.join("plugins"),
+        }
+    }
+
+    fn populate(paths: &BackupPaths) {
+        let sessions = paths.data_dir.join("sessions");
+        fs::create_dir_all(&sessions).unwrap();
+        fs::write(sessions.join("abc.json"), SESSION).unwrap();
+        fs::create_dir_all(paths.data_dir.join("budget")).unwrap();
+        fs::write(paths.data_dir.join("budget/spend.json"), "{}").unwrap();
+        fs::create_dir_all(paths.data_dir.join(BACKUPS_DIR)).unwrap();
+        fs::write(paths.data_dir.join("backups/old.tar.gz"), "old").unwrap();
+
+        let config = toml::to_string(&ait42_config::Config::default()).unwrap();
+        fs::create_dir_all(paths.config_dir().join("themes")).unwrap();
+        fs::write(&paths.config_file, config).unwrap();
+        fs::write(paths.config_dir().join("themes/dusk.toml"), "name = 'dusk'").unwrap();
+
+        let plugin = paths.plugins_dir.join("hello");
+        fs::create_dir_all(&plugin).unwrap();
+        fs::write(
+            plugin.join("plugin.json"),
+            r#"{"id":"hello","name":"Hello","version":"1.0.0","author":"a","description":"d","entry_point":"main.js"}"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let source = TempDir::new().unwrap();
+        let source_paths = paths(source.path());
+        populate(&source_paths);
+        let archive = source.path().join("backup.tar.gz");
+
+        let manifest = create_backup(&source_paths, &archive).unwrap();
+        let names: Vec<_> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "data/budget/spend.json",
+                "data/sessions/abc.json",
+                "config/config.toml",
+                "config/themes/dusk.toml",
+            ]
+        );
+        assert_eq!(manifest.plugins[0].id, "hello");
+
+        let target = TempDir::new().unwrap();
+        let target_paths = paths(target.path());
+        let backup = read_backup(&archive).unwrap();
+
+        let report = backup.restore(&target_paths, true).unwrap();
+        assert_eq!(report.restored.len(), 4);
+        assert!(!target_paths.config_file.exists());
+
+        let report = backup.restore(&target_paths, false).unwrap();
+        assert_eq!(report.missing_plugins.len(), 1);
+        assert_eq!(
+            fs::read_to_string(target_paths.data_dir.join("sessions/abc.json")).unwrap(),
+            SESSION
+        );
+        assert_eq!(
+            fs::read(&target_paths.config_file).unwrap(),
+            fs::read(&source_paths.config_file).unwrap()
+        );
+        assert!(target_paths.config_dir().join("themes/dusk.toml").exists());
+    }
+
+    fn rewrite(
+        archive: &Path,
+        edit: impl FnOnce(&mut BackupManifest, &mut BTreeMap<String, Vec<u8>>),
+    ) {
+        let Backup {
+            mut manifest,
+            mut files,
+        } = read_backup(archive).unwrap();
+        edit(&mut manifest, &mut files);
+
+        let file = fs::File::create(archive).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        append_bytes(&mut builder, MANIFEST_NAME, &serde_json::to_vec(&manifest).unwrap()).unwrap();
+        for (name, data) in &files {
+            append_bytes(&mut builder, name, data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_restore_rejects_tampered_backup() {
+        let dir = TempDir::new().unwrap();
+        let paths = paths(dir.path());
+        populate(&paths);
+        let archive = dir.path().join("backup.tar.gz");
+        create_backup(&paths, &archive).unwrap();
+
+        rewrite(&archive, |_, files| {
+            files.insert("data/sessions/abc.json".to_string(), b"[]".to_vec());
+        });
+        let err = read_backup(&archive).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn test_restore_rejects_newer_format_and_bad_schema() {
+        let dir = TempDir::new().unwrap();
+        let paths = paths(dir.path());
+        populate(&paths);
+        let archive = dir.path().join("backup.tar.gz");
+
+        create_backup(&paths, &archive).unwrap();
+        rewrite(&archive, |manifest, _| {
+            manifest.format_version = BACKUP_FORMAT_VERSION + 1;
+        });
+        let err = read_backup(&archive).unwrap_err();
+        assert!(err.to_string().contains("newer than supported"));
+
+        fs::write(&paths.config_file, "[editor]\ntab_size = 0\n").unwrap();
+        create_backup(&paths, &archive).unwrap();
+        let err = read_backup(&archive).unwrap_err();
+        assert!(err.to_string().contains("config/config.toml"));
+    }
+
+    #[test]
+    fn test_restore_rejects_wrong_session_structure() {
+        let dir = TempDir::new().unwrap();
+        let paths = paths(dir.path());
+        populate(&paths);
+        let archive = dir.path().join("backup.tar.gz");
+        let sessions = paths.data_dir.join("sessions");
+
+        // Valid JSON, but not a session list
+        fs::write(sessions.join("abc.json"), r#"[{"id":"s1"}]"#).unwrap();
+        create_backup(&paths, &archive).unwrap();
+        let err = read_backup(&archive).unwrap_err();
+        assert!(err.to_string().contains("sessions/abc.json"));
+
+        fs::write(sessions.join("abc.json"), SESSION).unwrap();
+        let line = &SESSION[1..SESSION.len() - 1];
+        fs::write(sessions.join("def.jsonl"), format!("{}\n{{\"id\":2}}\n", line)).unwrap();
+        create_backup(&paths, &archive).unwrap();
+        let err = read_backup(&archive).unwrap_err();
+        assert!(err.to_string().contains("sessions/def.jsonl:2"));
+
+        // Encrypted sessions are checked by their envelope
+        let envelope = r#"{"encrypted":1,"algorithm":"AES-256-GCM","nonce":"bm9uY2U=","ciphertext":"Y2lwaGVy"}"#;
+        fs::write(sessions.join("def.jsonl"), format!("{}\n{}\n", line, envelope)).unwrap();
+        create_backup(&paths, &archive).unwrap();
+        read_backup(&archive).unwrap();
+
+        fs::write(sessions.join("def.jsonl"), envelope.replace(":1,", ":2,")).unwrap();
+        create_backup(&paths, &archive).unwrap();
+        let err = read_backup(&archive).unwrap_err();
+        assert!(format!("{:#}", err).contains("newer than supported"));
+    }
+
+    #[test]
+    fn test_destination_rejects_escapes() {
+        let paths = paths(Path::new("/root"));
+        assert_eq!(
+            paths.destination("data/sessions/a.json"),
+            Some(PathBuf::from("/root/home/.ait42/sessions/a.json"))
+        );
+        assert_eq!(
+            paths.destination("config/config.toml"),
+            Some(PathBuf::from("/root/config/config.toml"))
+        );
+        assert_eq!(paths.destination("data/../../etc/passwd"), None);
+        assert_eq!(paths.destination("/etc/passwd"), None);
+        assert_eq!(paths.destination("other/file"), None);
+        assert_eq!(paths.destination("data"), None);
+    }
+}
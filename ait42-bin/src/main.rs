@@ -19,10 +19,28 @@
 //!
 //! # Open directory
 //! ait42 /path/to/project
+//!
+//! # Import VS Code settings and keybindings
+//! ait42 config import-vscode
+//!
+//! # Import options and mappings from a .vimrc
+//! ait42 config import-vimrc
+//!
+//! # Back up sessions, configuration and the plugin list, then restore them
+//! ait42 backup create
+//! ait42 backup restore ~/.ait42/backups/ait42-backup-20250101-120000.tar.gz
+//!
+//! # Check the configured release channel and offer to install an update
+//! ait42 --check-for-updates
+//!
+//! # Print the time each startup phase took to the first frame, after exit
+//! ait42 --profile-startup src/main.rs
 //! ```
 
+mod backup;
+
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 use tracing::{info, Level};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
@@ -31,6 +49,9 @@ use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// File or directory to open
     #[arg(value_name = "PATH")]
     path: Option<PathBuf>,
@@ -50,36 +71,168 @@ struct Args {
     /// Log file path
     #[arg(long, value_name = "FILE")]
     log_file: Option<PathBuf>,
+
+    /// Check for a newer release, show its notes and offer to install it
+    #[arg(long)]
+    check_for_updates: bool,
+
+    /// Print how long each startup phase took to the first frame, after exit
+    #[arg(long)]
+    profile_startup: bool,
+}
+
+/// Commands run instead of opening the editor
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage the configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Back up or restore AIT42 data
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Import settings and keybindings from VS Code
+    ImportVscode {
+        /// settings.json to import (default: VS Code's user settings)
+        #[arg(long, value_name = "FILE")]
+        settings: Option<PathBuf>,
+
+        /// keybindings.json to import (default: VS Code's user keybindings)
+        #[arg(long, value_name = "FILE")]
+        keybindings: Option<PathBuf>,
+
+        /// Report what would be imported without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Import options and mappings from a .vimrc
+    ImportVimrc {
+        /// vimrc to import (default: ~/.vimrc, ~/.vim/vimrc or Neovim's init.vim)
+        #[arg(value_name = "FILE")]
+        vimrc: Option<PathBuf>,
+
+        /// Report what would be imported without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BackupCommand {
+    /// Archive sessions, configuration, themes, snippets and the plugin list
+    Create {
+        /// Archive to write (default: ~/.ait42/backups/ait42-backup-<timestamp>.tar.gz)
+        #[arg(value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Verify a backup archive and restore it, overwriting current data
+    Restore {
+        /// Archive to restore
+        #[arg(value_name = "FILE")]
+        archive: PathBuf,
+
+        /// Verify the archive and list what would be restored without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let mut profile = ait42_tui::StartupProfile::new();
     let args = Args::parse();
 
     // Initialize logging
     setup_logging(&args)?;
+    profile.mark("logging");
+
+    match &args.command {
+        Some(Command::Config { command }) => return run_config_command(&args, command).await,
+        Some(Command::Backup { command }) => return run_backup_command(&args, command),
+        None => {}
+    }
+    if args.check_for_updates {
+        return check_for_updates(&args).await;
+    }
 
     info!("Starting AIT42 Editor v{}", env!("CARGO_PKG_VERSION"));
     info!("Rust version: {}", env!("CARGO_PKG_RUST_VERSION"));
 
     // Load configuration
-    let _config = load_config(&args).await?;
+    let config = load_config(&args).await?;
     info!("Configuration loaded successfully");
+    profile.mark("config");
 
-    // Determine target path
-    let target_path = resolve_target_path(args.path)?;
-    info!("Target path: {}", target_path.display());
+    // Buffers left unsaved by a crash are offered before anything else
+    let recovered = offer_recovery()?;
+    profile.mark("recovery");
+
+    // Without a path, the previous session may be restored instead
+    let session = if args.path.is_none() && config.editor.restore_session {
+        offer_session_restore()?
+    } else {
+        None
+    };
+    let target_path = match &session {
+        Some(_) => None,
+        None => {
+            let target_path = resolve_target_path(args.path.clone())?;
+            info!("Target path: {}", target_path.display());
+            Some(target_path)
+        }
+    };
+    profile.mark("session");
 
     // Start TUI application
     info!("Starting TUI application...");
-    ait42_tui::run_with_file(target_path)
+    let profile = run_tui(session, target_path, recovered, profile)
         .await
         .context("TUI application error")?;
+    if args.profile_startup {
+        eprint!("{}", profile.report());
+    }
 
     info!("AIT42 Editor shutdown complete");
     Ok(())
 }
 
+/// Open the session or file and the recovered buffers in the TUI and run
+/// it until it exits
+///
+/// Returns `profile` with the phases up to the first frame added, once
+/// the terminal is restored.
+async fn run_tui(
+    session: Option<ait42_tui::Session>,
+    target_path: Option<PathBuf>,
+    recovered: Vec<ait42_core::RecoveryFile>,
+    mut profile: ait42_tui::StartupProfile,
+) -> Result<ait42_tui::StartupProfile> {
+    let mut app = ait42_tui::TuiApp::new().await?;
+    profile.mark("terminal");
+    if let Some(session) = &session {
+        app.restore_session(session);
+    }
+    if let Some(path) = target_path {
+        app.load_file(path)?;
+    }
+    app.recover(&recovered)?;
+    profile.mark("open");
+
+    app.set_startup_profile(profile);
+    app.run().await?;
+    Ok(app.take_startup_profile().unwrap_or_default())
+}
+
 /// Setup logging based on CLI arguments
 fn setup_logging(args: &Args) -> Result<()> {
     let log_level = if args.debug {
@@ -141,6 +294,272 @@ async fn load_config(args: &Args) -> Result<ait42_config::Config> {
     Ok(config)
 }
 
+/// Run a `config` subcommand
+async fn run_config_command(args: &Args, command: &ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::ImportVscode {
+            settings,
+            keybindings,
+            dry_run,
+        } => import_vscode(args, settings.as_deref(), keybindings.as_deref(), *dry_run).await,
+        ConfigCommand::ImportVimrc { vimrc, dry_run } => {
+            import_vimrc(args, vimrc.as_deref(), *dry_run).await
+        }
+    }
+}
+
+/// Run a `backup` subcommand
+fn run_backup_command(args: &Args, command: &BackupCommand) -> Result<()> {
+    let paths = backup::BackupPaths::new(config_loader(args)?.path().to_path_buf())?;
+
+    match command {
+        BackupCommand::Create { output } => {
+            let archive = output.clone().unwrap_or_else(|| paths.default_archive());
+            let manifest = backup::create_backup(&paths, &archive)?;
+            println!(
+                "Backed up {} files and {} plugins to {}",
+                manifest.files.len(),
+                manifest.plugins.len(),
+                archive.display()
+            );
+        }
+        BackupCommand::Restore { archive, dry_run } => {
+            let backup = backup::read_backup(archive)
+                .with_context(|| format!("Cannot restore {}", archive.display()))?;
+            let report = backup.restore(&paths, *dry_run)?;
+
+            println!(
+                "Backup from {} (AIT42 {})",
+                backup.manifest.created_at, backup.manifest.editor_version
+            );
+            for path in &report.restored {
+                println!("  {}", path.display());
+            }
+            if !report.missing_plugins.is_empty() {
+                println!("Plugins to reinstall:");
+                for plugin in &report.missing_plugins {
+                    println!("  {} {} ({})", plugin.name, plugin.version, plugin.id);
+                }
+            }
+            if *dry_run {
+                println!("Dry run: {} files not restored", report.restored.len());
+            } else {
+                println!("Restored {} files", report.restored.len());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Loader for the configuration file given with `--config`, or the default
+fn config_loader(args: &Args) -> Result<ait42_config::ConfigLoader> {
+    use ait42_config::ConfigLoader;
+
+    match &args.config {
+        Some(path) => Ok(ConfigLoader::with_path(path.clone())),
+        None => ConfigLoader::new().context("Failed to create config loader"),
+    }
+}
+
+/// Print an import report and save the imported configuration
+async fn finish_import(
+    loader: &ait42_config::ConfigLoader,
+    config: &ait42_config::Config,
+    reports: &[(PathBuf, ait42_config::ImportReport)],
+    dry_run: bool,
+) -> Result<()> {
+    for (path, report) in reports {
+        println!("{}", path.display());
+        println!("  Imported ({}):", report.applied.len());
+        for entry in &report.applied {
+            println!("    {}", entry);
+        }
+        println!("  Not imported ({}):", report.unmapped.len());
+        for entry in &report.unmapped {
+            println!("    {}", entry);
+        }
+    }
+
+    if dry_run {
+        println!("Dry run: {} not changed", loader.path().display());
+    } else {
+        loader.save(config).await.context("Failed to save config file")?;
+        println!("Saved {}", loader.path().display());
+    }
+    Ok(())
+}
+
+/// Import a vimrc into the configuration file
+async fn import_vimrc(args: &Args, vimrc: Option<&std::path::Path>, dry_run: bool) -> Result<()> {
+    use ait42_config::vimrc;
+
+    let path = match vimrc {
+        Some(path) => path.to_path_buf(),
+        None => vimrc::default_paths()
+            .into_iter()
+            .find(|path| path.exists())
+            .context("No vimrc found. Pass the path to import.")?,
+    };
+    let loader = config_loader(args)?;
+    let mut config = loader.load().await.context("Failed to load config file")?;
+
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let report = vimrc::import_vimrc(&contents, &mut config);
+
+    finish_import(&loader, &config, &[(path, report)], dry_run).await
+}
+
+/// Import VS Code settings and keybindings into the configuration file
+async fn import_vscode(
+    args: &Args,
+    settings: Option<&std::path::Path>,
+    keybindings: Option<&std::path::Path>,
+    dry_run: bool,
+) -> Result<()> {
+    use ait42_config::vscode;
+
+    let loader = config_loader(args)?;
+    let mut config = loader.load().await.context("Failed to load config file")?;
+
+    // Explicit paths must exist; VS Code's own files are imported if present
+    let user_dir = vscode::default_user_dir();
+    let resolve = |explicit: Option<&std::path::Path>, name: &str| -> Result<Option<PathBuf>> {
+        match explicit {
+            Some(path) if !path.exists() => anyhow::bail!("File not found: {}", path.display()),
+            Some(path) => Ok(Some(path.to_path_buf())),
+            None => Ok(user_dir.as_ref().map(|dir| dir.join(name)).filter(|p| p.exists())),
+        }
+    };
+    let settings = resolve(settings, "settings.json")?;
+    let keybindings = resolve(keybindings, "keybindings.json")?;
+    if settings.is_none() && keybindings.is_none() {
+        anyhow::bail!("No VS Code settings found. Pass --settings or --keybindings.");
+    }
+
+    let mut reports = Vec::new();
+    if let Some(path) = &settings {
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let report = vscode::import_settings(&json, &mut config)
+            .with_context(|| format!("Failed to import {}", path.display()))?;
+        reports.push((path.clone(), report));
+    }
+    if let Some(path) = &keybindings {
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let report = vscode::import_keybindings(&json, &mut config)
+            .with_context(|| format!("Failed to import {}", path.display()))?;
+        reports.push((path.clone(), report));
+    }
+
+    finish_import(&loader, &config, &reports, dry_run).await
+}
+
+/// Check the configured channel for an update and install it if confirmed
+async fn check_for_updates(args: &Args) -> Result<()> {
+    use std::io::Write;
+
+    let config = load_config(args).await?;
+    let client =
+        ait42_update::UpdateClient::new(&config.updates, "ait42", env!("CARGO_PKG_VERSION"))?;
+    let Some(release) = client.check().await? else {
+        println!(
+            "AIT42 {} is up to date ({} channel)",
+            env!("CARGO_PKG_VERSION"),
+            client.channel()
+        );
+        return Ok(());
+    };
+
+    println!(
+        "AIT42 {} is available (installed: {}, {} channel)",
+        release.version,
+        env!("CARGO_PKG_VERSION"),
+        client.channel()
+    );
+    if let Some(date) = &release.pub_date {
+        println!("Published {}", date);
+    }
+    if !release.notes.is_empty() {
+        println!("\n{}\n", release.notes.trim_end());
+    }
+
+    print!("Install {}? [y/N] ", release.version);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        println!("Not installed");
+        return Ok(());
+    }
+
+    let contents = client.download(&release).await?;
+    let exe = std::env::current_exe().context("Failed to locate the ait42 executable")?;
+    ait42_update::install_executable(&contents, &exe)?;
+    println!("Installed AIT42 {} to {}", release.version, exe.display());
+    Ok(())
+}
+
+/// Previous session of the working directory, if the user wants it back
+fn offer_session_restore() -> Result<Option<ait42_tui::Session>> {
+    use std::io::Write;
+
+    let path = ait42_tui::Session::default_path()?;
+    let workspace = std::env::current_dir().context("Failed to get current directory")?;
+    let Some(session) = ait42_tui::Session::load_for(&path, &workspace) else {
+        return Ok(None);
+    };
+
+    print!("Restore the previous session ({} tabs)? [Y/n] ", session.tabs.len());
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "" | "y" | "Y" | "yes").then_some(session))
+}
+
+/// Buffers left unsaved by a session that crashed, if the user wants them
+/// back
+///
+/// Declining keeps the copies for the next start; discarding deletes them.
+fn offer_recovery() -> Result<Vec<ait42_core::RecoveryFile>> {
+    use std::io::Write;
+
+    let Some(dir) = ait42_core::Autosave::default_dir() else {
+        return Ok(Vec::new());
+    };
+    let files = ait42_core::autosave::recoverable(&dir);
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    println!("Unsaved changes were found from a session that did not exit normally:");
+    for file in &files {
+        match &file.path {
+            Some(path) => println!("  {}", path.display()),
+            None => println!("  untitled buffer"),
+        }
+    }
+    print!("Recover them? [Y/n/d(iscard)] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    match answer.trim() {
+        "" | "y" | "Y" | "yes" => Ok(files),
+        "d" | "D" | "discard" => {
+            for file in &files {
+                file.discard()?;
+            }
+            println!("Discarded {} unsaved buffers", files.len());
+            Ok(Vec::new())
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
 /// Resolve target path from arguments or current directory
 fn resolve_target_path(path: Option<PathBuf>) -> Result<PathBuf> {
     let target = path.unwrap_or_else(|| PathBuf::from("."));
@@ -194,6 +613,51 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_config_commands() {
+        let args = Args::parse_from(["ait42", "config", "import-vscode", "--dry-run"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Config {
+                command: ConfigCommand::ImportVscode { dry_run: true, .. }
+            })
+        ));
+
+        let args = Args::parse_from(["ait42", "config", "import-vimrc", "/tmp/vimrc"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Config {
+                command: ConfigCommand::ImportVimrc { vimrc: Some(_), dry_run: false }
+            })
+        ));
+
+        let args = Args::parse_from(["ait42", "backup", "create"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Backup {
+                command: BackupCommand::Create { output: None }
+            })
+        ));
+
+        let args = Args::parse_from(["ait42", "backup", "restore", "b.tar.gz", "--dry-run"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Backup {
+                command: BackupCommand::Restore { dry_run: true, .. }
+            })
+        ));
+
+        let args = Args::parse_from(["ait42", "--check-for-updates"]);
+        assert!(args.check_for_updates);
+
+        let args = Args::parse_from(["ait42", "--profile-startup", "src/main.rs"]);
+        assert!(args.profile_startup);
+
+        let args = Args::parse_from(["ait42", "src/main.rs"]);
+        assert!(args.command.is_none());
+        assert_eq!(args.path, Some(PathBuf::from("src/main.rs")));
+    }
+
     #[test]
     fn test_resolve_invalid_path() {
         let result = resolve_target_path(Some(PathBuf::from("/nonexistent/path")));
@@ -130,8 +130,8 @@ impl Coordinator {
         }
     }
 
-    /// Execute with a single agent
-    async fn execute_single(&mut self, agent: &str, task: &str) -> Result<ExecutionResult> {
+    /// Execute with a single named agent
+    pub async fn execute_single(&mut self, agent: &str, task: &str) -> Result<ExecutionResult> {
         info!("Executing task with agent: {}", agent);
 
         let start_time = SystemTime::now();
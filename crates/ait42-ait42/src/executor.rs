@@ -54,14 +54,8 @@ impl AgentExecutor {
             return Err(AIT42Error::AgentNotFound(agent.to_string()));
         }
 
-        // Use coordinator to execute
-        let mut results = self.coordinator.execute_task(task).await?;
-
-        if results.is_empty() {
-            return Err(AIT42Error::ExecutionFailed("No execution results returned".to_string()));
-        }
-
-        Ok(results.remove(0))
+        // Run the named agent rather than letting the coordinator pick one
+        self.coordinator.execute_single(agent, task).await
     }
 
     /// Execute multiple agents in parallel
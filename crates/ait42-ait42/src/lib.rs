@@ -5,12 +5,18 @@
 //! # Features
 //!
 //! - **Agent Registry**: Discover and manage 49 AI agents
+//! - **Agent Manager**: Reload agents when their files change
 //! - **Tmux Session Management**: Run agents in isolated tmux sessions
 //! - **Coordinator**: Intelligent agent selection and orchestration
+//! - **Recommendation**: Rank agents for a task description
 //! - **Executor**: Single, parallel, and sequential execution modes
 //! - **Output Streaming**: Real-time output from running agents
 //! - **Command Palette**: Integration with editor commands
 //! - **Editor Bridge**: Run agents on buffer content and selections
+//! - **Code Review**: Review a diff and read the findings back as diagnostics
+//! - **Outcome Tracking**: Per-agent rates of kept and discarded work
+//! - **Version Pinning**: Record the agent definitions a session used
+//! - **Competition Scoring**: Rank competition instances to pick a winner
 //!
 //! # Quick Start
 //!
@@ -39,7 +45,13 @@ pub mod coordinator;
 pub mod editor_integration;
 pub mod error;
 pub mod executor;
+pub mod manager;
+pub mod outcomes;
+pub mod pinning;
+pub mod recommend;
 pub mod registry;
+pub mod review;
+pub mod scoring;
 pub mod stream;
 pub mod tmux;
 
@@ -50,7 +62,13 @@ pub use coordinator::{Coordinator, ExecutionResult};
 pub use editor_integration::EditorAgentBridge;
 pub use error::{AIT42Error, Result};
 pub use executor::{AgentExecutor, ExecutionMode};
-pub use registry::{AgentCategory, AgentMetadata, AgentRegistry};
+pub use manager::AgentManager;
+pub use outcomes::{AgentStats, Outcome, OutcomeLog, OutcomeRecord};
+pub use pinning::{AgentChange, AgentPin, ChangeStatus};
+pub use recommend::Recommendation;
+pub use registry::{AgentCategory, AgentMetadata, AgentRegistry, AgentSource};
+pub use review::{ReviewFinding, ReviewReport, ReviewSeverity};
+pub use scoring::{Candidate, Evaluation, Score};
 pub use stream::{OutputStream, SessionStream, StreamEvent, StreamManager};
 pub use tmux::{SessionStatus, TmuxManager, TmuxSession};
 
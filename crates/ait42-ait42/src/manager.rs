@@ -0,0 +1,262 @@
+//! Agent manager with hot reloading
+//!
+//! Owns the [`AgentRegistry`] of an agents directory and keeps it current:
+//! once [`AgentManager::watch`] is called, a [`FileWatcher`] follows the
+//! directory, and the project's agents directory if there is one, and the
+//! agents are reloaded whenever a markdown file below them is created,
+//! changed, renamed or removed. [`watch_agent_dirs`] does the same for
+//! registries kept elsewhere.
+
+use crate::error::{AIT42Error, Result};
+use crate::registry::{AgentMetadata, AgentRegistry};
+use ait42_fs::{FileEvent, FileWatcher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Discovers the agents of a directory and reloads them when they change
+#[derive(Debug)]
+pub struct AgentManager {
+    registry: Arc<RwLock<AgentRegistry>>,
+    agents_dir: PathBuf,
+    project_dir: Option<PathBuf>,
+    watch_task: Option<JoinHandle<()>>,
+}
+
+impl AgentManager {
+    /// Create a manager for `agents_dir`; no agents are loaded yet
+    pub fn new(agents_dir: PathBuf) -> Self {
+        Self {
+            registry: Arc::new(RwLock::new(AgentRegistry::new(agents_dir.clone()))),
+            agents_dir,
+            project_dir: None,
+            watch_task: None,
+        }
+    }
+
+    /// Directory the agents are loaded from
+    pub fn agents_dir(&self) -> &Path {
+        &self.agents_dir
+    }
+
+    /// Load the project's agents from `dir` over the global ones
+    ///
+    /// Takes effect on the next load; a running watch follows the new
+    /// directory from now on.
+    pub fn set_project_dir(&mut self, dir: Option<PathBuf>) -> Result<()> {
+        self.registry
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .set_project_dir(dir.clone());
+        self.project_dir = dir;
+        if self.is_watching() {
+            self.unwatch();
+            self.watch()?;
+        }
+        Ok(())
+    }
+
+    /// (Re)load all agents; returns how many were loaded
+    pub fn load_agents(&self) -> Result<usize> {
+        Self::reload(&self.registry)
+    }
+
+    fn reload(registry: &RwLock<AgentRegistry>) -> Result<usize> {
+        let mut registry = registry.write().unwrap_or_else(|e| e.into_inner());
+        registry.load_agents()?;
+        Ok(registry.count())
+    }
+
+    /// Current agents
+    pub fn registry(&self) -> RwLockReadGuard<'_, AgentRegistry> {
+        self.registry.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get agent by name
+    pub fn get(&self, name: &str) -> Option<AgentMetadata> {
+        self.registry().get(name).cloned()
+    }
+
+    /// All agents, sorted by name
+    pub fn list(&self) -> Vec<AgentMetadata> {
+        let mut agents: Vec<AgentMetadata> = self.registry().list().into_iter().cloned().collect();
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+        agents
+    }
+
+    /// Number of loaded agents
+    pub fn count(&self) -> usize {
+        self.registry().count()
+    }
+
+    /// Whether agent files are being watched
+    pub fn is_watching(&self) -> bool {
+        self.watch_task.is_some()
+    }
+
+    /// Reload the agents whenever files below the agents directories change
+    ///
+    /// Must be called within a tokio runtime. Bursts of events (an editor
+    /// saving a file, a `git checkout`) cause a single reload. A project
+    /// agents directory created after this call is not followed until the
+    /// watch is restarted.
+    pub fn watch(&mut self) -> Result<()> {
+        if self.is_watching() {
+            return Ok(());
+        }
+
+        let mut dirs = vec![self.agents_dir.as_path()];
+        dirs.extend(self.project_dir.as_deref().filter(|dir| dir.is_dir()));
+        let registry = Arc::clone(&self.registry);
+        self.watch_task = Some(watch_agent_dirs(&dirs, move || match Self::reload(&registry) {
+            Ok(count) => info!("Reloaded {} agents", count),
+            Err(e) => warn!("Failed to reload agents: {}", e),
+        })?);
+        Ok(())
+    }
+
+    /// Stop reloading agents on changes
+    pub fn unwatch(&mut self) {
+        if let Some(task) = self.watch_task.take() {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for AgentManager {
+    fn drop(&mut self) {
+        self.unwatch();
+    }
+}
+
+/// Call `reload` whenever agent files below `dirs` change
+///
+/// Must be called within a tokio runtime. Bursts of events cause a single
+/// call; abort the returned task to stop watching.
+pub fn watch_agent_dirs<F>(dirs: &[&Path], reload: F) -> Result<JoinHandle<()>>
+where
+    F: Fn() + Send + 'static,
+{
+    let mut watcher = FileWatcher::new().map_err(|e| {
+        AIT42Error::ConfigError(format!("Failed to watch agents directories: {}", e))
+    })?;
+    for dir in dirs {
+        watcher.watch(dir, true).map_err(|e| {
+            AIT42Error::ConfigError(format!(
+                "Failed to watch agents directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+        info!("Watching agents in {}", dir.display());
+    }
+
+    Ok(tokio::spawn(async move {
+        while let Some(event) = watcher.next_event().await {
+            if !is_agent_event(&event) {
+                continue;
+            }
+            debug!("Agent file changed: {}", event.path().display());
+            while watcher.try_next_event().is_some() {}
+            reload();
+        }
+    }))
+}
+
+/// Whether `event` may change the agents: markdown files, and directories
+/// (which have no extension) that can hold them
+fn is_agent_event(event: &FileEvent) -> bool {
+    let is_agent_path = |path: &Path| {
+        path.extension().map_or(true, |ext| ext == "md")
+            && !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+    };
+    match event {
+        FileEvent::Renamed(from, to) => is_agent_path(from) || is_agent_path(to),
+        _ => is_agent_path(event.path()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::AgentSource;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn agent(name: &str) -> String {
+        format!("---\nname: {}\ndescription: Agent {}\n---\n", name, name)
+    }
+
+    #[test]
+    fn test_load_agents() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("backend")).unwrap();
+        fs::write(temp_dir.path().join("backend/api-developer.md"), agent("api-developer"))
+            .unwrap();
+
+        let manager = AgentManager::new(temp_dir.path().to_path_buf());
+        assert_eq!(manager.count(), 0);
+        assert_eq!(manager.load_agents().unwrap(), 1);
+        assert_eq!(manager.get("api-developer").unwrap().description, "Agent api-developer");
+
+        fs::write(temp_dir.path().join("bug-fixer.md"), agent("bug-fixer")).unwrap();
+        manager.load_agents().unwrap();
+        let names: Vec<String> = manager.list().into_iter().map(|a| a.name).collect();
+        assert_eq!(names, vec!["api-developer", "bug-fixer"]);
+    }
+
+    #[test]
+    fn test_load_project_agents() {
+        let global = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        let project_dir = AgentRegistry::project_agents_dir(project.path());
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(global.path().join("bug-fixer.md"), agent("bug-fixer")).unwrap();
+        fs::write(project_dir.join("migrator.md"), agent("migrator")).unwrap();
+
+        let mut manager = AgentManager::new(global.path().to_path_buf());
+        manager.set_project_dir(Some(project_dir)).unwrap();
+        assert_eq!(manager.load_agents().unwrap(), 2);
+        assert_eq!(manager.get("migrator").unwrap().source, AgentSource::Project);
+
+        manager.set_project_dir(None).unwrap();
+        assert_eq!(manager.load_agents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_is_agent_event() {
+        assert!(is_agent_event(&FileEvent::Modified(PathBuf::from("/a/agent.md"))));
+        assert!(is_agent_event(&FileEvent::Deleted(PathBuf::from("/a/backend"))));
+        assert!(is_agent_event(&FileEvent::Renamed(
+            PathBuf::from("/a/agent.md"),
+            PathBuf::from("/a/agent.md.bak")
+        )));
+        assert!(!is_agent_event(&FileEvent::Modified(PathBuf::from("/a/notes.txt"))));
+        assert!(!is_agent_event(&FileEvent::Created(PathBuf::from("/a/.agent.md.swp"))));
+    }
+
+    #[tokio::test]
+    #[ignore] // Depends on file system notification timing
+    async fn test_watch_reloads_agents() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = AgentManager::new(temp_dir.path().to_path_buf());
+        manager.load_agents().unwrap();
+        manager.watch().unwrap();
+        assert!(manager.is_watching());
+
+        fs::write(temp_dir.path().join("bug-fixer.md"), agent("bug-fixer")).unwrap();
+        for _ in 0..50 {
+            if manager.count() == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(manager.get("bug-fixer").is_some());
+
+        manager.unwatch();
+        assert!(!manager.is_watching());
+    }
+}
@@ -0,0 +1,229 @@
+//! Agent outcome tracking
+//!
+//! Records what happened to the work of each agent run: kept, partially
+//! applied, or discarded. Outcomes are not reported by the agents; callers
+//! infer them from winner selection and from what is left of a worktree's
+//! changes when it is deleted. [`OutcomeLog::stats`] aggregates them into
+//! per-agent success rates.
+
+use crate::error::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What became of an agent's changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// All changes were kept
+    Kept,
+    /// Some changes were carried over
+    Partial,
+    /// Nothing was kept
+    Discarded,
+}
+
+impl Outcome {
+    /// Outcome for a run that changed `changed` files, `adopted` of which
+    /// ended up in the main checkout
+    pub fn from_adoption(changed: usize, adopted: usize) -> Self {
+        match adopted {
+            0 => Outcome::Discarded,
+            n if n >= changed => Outcome::Kept,
+            _ => Outcome::Partial,
+        }
+    }
+}
+
+/// Outcome of one agent run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutcomeRecord {
+    pub agent: String,
+    /// Session (competition, ensemble or debate) the run belonged to
+    pub session_id: String,
+    pub instance_id: u32,
+    pub outcome: Outcome,
+    /// Unix timestamp in seconds
+    pub recorded_at: u64,
+}
+
+impl OutcomeRecord {
+    /// Record stamped with the current time
+    pub fn new(agent: &str, session_id: &str, instance_id: u32, outcome: Outcome) -> Self {
+        Self {
+            agent: agent.to_string(),
+            session_id: session_id.to_string(),
+            instance_id,
+            outcome,
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Success metrics of one agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentStats {
+    /// Runs with a known outcome
+    pub runs: u32,
+    pub kept: u32,
+    pub partial: u32,
+    pub discarded: u32,
+    /// Share of runs whose work was kept, with partial runs counting half
+    pub success_rate: f64,
+}
+
+impl AgentStats {
+    fn add(&mut self, outcome: Outcome) {
+        self.runs += 1;
+        match outcome {
+            Outcome::Kept => self.kept += 1,
+            Outcome::Partial => self.partial += 1,
+            Outcome::Discarded => self.discarded += 1,
+        }
+        self.success_rate = (self.kept as f64 + self.partial as f64 / 2.0) / self.runs as f64;
+    }
+}
+
+/// Outcomes stored in a JSON file
+#[derive(Debug, Clone)]
+pub struct OutcomeLog {
+    path: PathBuf,
+    records: Vec<OutcomeRecord>,
+}
+
+impl OutcomeLog {
+    /// Load the log at `path`; a missing file is an empty log
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let records = match std::fs::read_to_string(&path) {
+            Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content)?,
+            Ok(_) => Vec::new(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, records })
+    }
+
+    /// Write the log back to its file
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.records)?)?;
+        Ok(())
+    }
+
+    /// File the log is stored in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All records, oldest first
+    pub fn records(&self) -> &[OutcomeRecord] {
+        &self.records
+    }
+
+    /// Record the outcome of a run, replacing an earlier one for the same run
+    ///
+    /// Evidence that work was kept wins over a later discard: a worktree is
+    /// usually deleted after its winning changes have been merged, and that
+    /// deletion must not turn the run into a failure. Returns whether the
+    /// log changed.
+    pub fn record(&mut self, record: OutcomeRecord) -> bool {
+        let existing = self
+            .records
+            .iter_mut()
+            .find(|r| r.session_id == record.session_id && r.instance_id == record.instance_id);
+        match existing {
+            None => {
+                self.records.push(record);
+                true
+            }
+            Some(existing) => {
+                let downgrade =
+                    record.outcome == Outcome::Discarded && existing.outcome != Outcome::Discarded;
+                if downgrade || existing.outcome == record.outcome {
+                    return false;
+                }
+                *existing = record;
+                true
+            }
+        }
+    }
+
+    /// Outcome recorded for a run
+    pub fn outcome(&self, session_id: &str, instance_id: u32) -> Option<Outcome> {
+        self.records
+            .iter()
+            .find(|r| r.session_id == session_id && r.instance_id == instance_id)
+            .map(|r| r.outcome)
+    }
+
+    /// Metrics per agent
+    pub fn stats(&self) -> BTreeMap<String, AgentStats> {
+        let mut stats: BTreeMap<String, AgentStats> = BTreeMap::new();
+        for record in &self.records {
+            stats
+                .entry(record.agent.clone())
+                .or_default()
+                .add(record.outcome);
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_outcome_from_adoption() {
+        assert_eq!(Outcome::from_adoption(0, 0), Outcome::Discarded);
+        assert_eq!(Outcome::from_adoption(3, 0), Outcome::Discarded);
+        assert_eq!(Outcome::from_adoption(3, 1), Outcome::Partial);
+        assert_eq!(Outcome::from_adoption(3, 3), Outcome::Kept);
+    }
+
+    #[test]
+    fn test_kept_is_not_downgraded() {
+        let dir = TempDir::new().unwrap();
+        let mut log = OutcomeLog::load(dir.path().join("outcomes.json")).unwrap();
+
+        assert!(log.record(OutcomeRecord::new("a", "s1", 1, Outcome::Kept)));
+        assert!(!log.record(OutcomeRecord::new("a", "s1", 1, Outcome::Discarded)));
+        assert_eq!(log.outcome("s1", 1), Some(Outcome::Kept));
+
+        // A discarded run that later turns out to be partly merged is upgraded
+        assert!(log.record(OutcomeRecord::new("b", "s1", 2, Outcome::Discarded)));
+        assert!(log.record(OutcomeRecord::new("b", "s1", 2, Outcome::Partial)));
+        assert_eq!(log.outcome("s1", 2), Some(Outcome::Partial));
+        assert_eq!(log.records().len(), 2);
+    }
+
+    #[test]
+    fn test_stats_and_persistence() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("telemetry").join("outcomes.json");
+        let mut log = OutcomeLog::load(&path).unwrap();
+        log.record(OutcomeRecord::new("a", "s1", 1, Outcome::Kept));
+        log.record(OutcomeRecord::new("a", "s2", 1, Outcome::Partial));
+        log.record(OutcomeRecord::new("a", "s3", 1, Outcome::Discarded));
+        log.record(OutcomeRecord::new("a", "s4", 1, Outcome::Discarded));
+        log.record(OutcomeRecord::new("b", "s1", 2, Outcome::Discarded));
+        log.save().unwrap();
+
+        let stats = OutcomeLog::load(&path).unwrap().stats();
+        let a = &stats["a"];
+        assert_eq!((a.runs, a.kept, a.partial, a.discarded), (4, 1, 1, 2));
+        assert!((a.success_rate - 0.375).abs() < f64::EPSILON);
+        assert_eq!(stats["b"].success_rate, 0.0);
+    }
+}
@@ -0,0 +1,152 @@
+//! Agent version pinning
+//!
+//! Sessions record the definition of each agent they ran as an
+//! [`AgentPin`]: the `version` declared in its frontmatter and the whole
+//! definition file, prompt included. [`AgentPin::compare`] checks a pin
+//! against the agent's current definition, so re-running a session can warn
+//! that it would use a different prompt and show how it changed.
+
+use crate::error::Result;
+use crate::registry::{AgentMetadata, AgentRegistry};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::fs;
+use std::path::PathBuf;
+
+/// Definition of an agent as a session used it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentPin {
+    pub name: String,
+    /// Version declared by the definition, if any
+    pub version: Option<String>,
+    /// Definition file
+    pub path: PathBuf,
+    /// Content of the definition file
+    pub definition: String,
+}
+
+impl AgentPin {
+    /// Pin the current definition of `agent`
+    pub fn capture(agent: &AgentMetadata) -> Result<Self> {
+        Ok(Self {
+            name: agent.name.clone(),
+            version: agent.version.clone(),
+            path: agent.file_path.clone(),
+            definition: fs::read_to_string(&agent.file_path)?,
+        })
+    }
+
+    /// Pin the current definition of the agent called `name`, if the
+    /// registry has one that can be read
+    pub fn from_registry(registry: &AgentRegistry, name: &str) -> Option<Self> {
+        registry
+            .get(name)
+            .and_then(|agent| Self::capture(agent).ok())
+    }
+
+    /// How `current`, the agent's definition now, differs from the pinned one
+    ///
+    /// `current` is `None` when the agent no longer exists.
+    pub fn compare(&self, current: Option<&AgentPin>) -> AgentChange {
+        let status = match current {
+            None => ChangeStatus::Removed,
+            Some(current) if current.definition == self.definition => ChangeStatus::Unchanged,
+            Some(_) => ChangeStatus::Changed,
+        };
+        let diff = match (status, current) {
+            (ChangeStatus::Changed, Some(current)) => {
+                TextDiff::from_lines(self.definition.as_str(), current.definition.as_str())
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(&self.label("pinned"), &current.label("current"))
+                    .to_string()
+            }
+            _ => String::new(),
+        };
+        AgentChange {
+            name: self.name.clone(),
+            status,
+            pinned_version: self.version.clone(),
+            current_version: current.and_then(|c| c.version.clone()),
+            diff,
+        }
+    }
+
+    /// Diff header naming the definition, e.g. `reviewer@1.2.0 (pinned)`
+    fn label(&self, which: &str) -> String {
+        match &self.version {
+            Some(version) => format!("{}@{} ({})", self.name, version, which),
+            None => format!("{} ({})", self.name, which),
+        }
+    }
+}
+
+/// Whether an agent's definition changed since it was pinned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeStatus {
+    Unchanged,
+    Changed,
+    /// The agent no longer exists
+    Removed,
+}
+
+/// Difference between a pinned agent definition and the current one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentChange {
+    pub name: String,
+    pub status: ChangeStatus,
+    pub pinned_version: Option<String>,
+    pub current_version: Option<String>,
+    /// Unified diff from the pinned definition to the current one; empty
+    /// unless the definition changed
+    pub diff: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_agent(dir: &TempDir, version: &str, prompt: &str) {
+        let content = format!(
+            "---\nname: reviewer\ndescription: Reviews\nversion: {}\n---\n\n{}\n",
+            version, prompt
+        );
+        fs::write(dir.path().join("reviewer.md"), content).unwrap();
+    }
+
+    #[test]
+    fn test_compare_with_current_definition() {
+        let dir = TempDir::new().unwrap();
+        write_agent(&dir, "1.0.0", "Review the diff.");
+        let mut registry = AgentRegistry::load_from_directory(dir.path()).unwrap();
+        let pin = AgentPin::from_registry(&registry, "reviewer").unwrap();
+        assert_eq!(pin.version.as_deref(), Some("1.0.0"));
+        assert!(pin.definition.contains("Review the diff."));
+
+        let change = pin.compare(AgentPin::from_registry(&registry, "reviewer").as_ref());
+        assert_eq!(change.status, ChangeStatus::Unchanged);
+        assert!(change.diff.is_empty());
+
+        write_agent(&dir, "1.1.0", "Review the diff for security issues.");
+        registry.load_agents().unwrap();
+        let change = pin.compare(AgentPin::from_registry(&registry, "reviewer").as_ref());
+        assert_eq!(change.status, ChangeStatus::Changed);
+        assert_eq!(change.pinned_version.as_deref(), Some("1.0.0"));
+        assert_eq!(change.current_version.as_deref(), Some("1.1.0"));
+        assert!(change.diff.contains("--- reviewer@1.0.0 (pinned)"));
+        assert!(change.diff.contains("+++ reviewer@1.1.0 (current)"));
+        assert!(change.diff.contains("\n-Review the diff.\n"));
+        assert!(change
+            .diff
+            .contains("\n+Review the diff for security issues.\n"));
+
+        let change = pin.compare(None);
+        assert_eq!(change.status, ChangeStatus::Removed);
+        assert_eq!(change.current_version, None);
+    }
+}
@@ -0,0 +1,392 @@
+//! Agent recommendation
+//!
+//! Ranks agents for a task description by matching the task's words against
+//! each agent's name, category, description and capabilities. The ranking
+//! needs no network access; callers that can reach an LLM may pass the best
+//! candidates through [`llm_prompt`] and [`apply_llm_ranking`] to have a
+//! model re-rank them.
+
+use crate::error::{AIT42Error, Result};
+use crate::registry::{AgentCategory, AgentMetadata};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Words that say nothing about which agent fits
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "into", "add", "make", "our", "use",
+    "using", "all", "new", "need", "needs", "should", "some", "when", "will", "can", "have",
+    "please", "want", "are", "its", "not", "but", "also", "way", "get", "set",
+];
+
+/// Task words that point at a category even when no agent mentions them
+const CATEGORY_HINTS: &[(AgentCategory, &[&str])] = &[
+    (
+        AgentCategory::Backend,
+        &[
+            "api", "server", "endpoint", "backend", "database", "rest", "graphql", "sql",
+        ],
+    ),
+    (
+        AgentCategory::Frontend,
+        &[
+            "frontend",
+            "react",
+            "component",
+            "css",
+            "page",
+            "layout",
+            "button",
+            "ui",
+            "ux",
+        ],
+    ),
+    (
+        AgentCategory::Testing,
+        &[
+            "test",
+            "coverage",
+            "unit",
+            "integration",
+            "e2e",
+            "flaky",
+            "mock",
+        ],
+    ),
+    (
+        AgentCategory::Documentation,
+        &[
+            "doc",
+            "docs",
+            "readme",
+            "documentation",
+            "guide",
+            "tutorial",
+            "comment",
+        ],
+    ),
+    (
+        AgentCategory::Security,
+        &[
+            "security",
+            "vulnerability",
+            "auth",
+            "xss",
+            "csrf",
+            "injection",
+            "secret",
+            "owasp",
+        ],
+    ),
+    (
+        AgentCategory::Infrastructure,
+        &[
+            "deploy",
+            "docker",
+            "kubernetes",
+            "cicd",
+            "pipeline",
+            "terraform",
+            "infra",
+            "cloud",
+        ],
+    ),
+    (
+        AgentCategory::Planning,
+        &[
+            "design",
+            "architecture",
+            "plan",
+            "requirement",
+            "spec",
+            "roadmap",
+        ],
+    ),
+    (
+        AgentCategory::QualityAssurance,
+        &[
+            "review",
+            "refactor",
+            "quality",
+            "lint",
+            "performance",
+            "complexity",
+            "smell",
+        ],
+    ),
+    (
+        AgentCategory::Operations,
+        &[
+            "monitor", "incident", "alert", "backup", "outage", "metric", "log",
+        ],
+    ),
+];
+
+/// Weight of a task word found in the agent's name
+const NAME_WEIGHT: u32 = 3;
+/// Weight of a task word that hints at the agent's category
+const CATEGORY_WEIGHT: u32 = 2;
+/// Weight of a task word found in the description or capabilities
+const TEXT_WEIGHT: u32 = 1;
+
+/// An agent suggested for a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub agent: String,
+    /// Relevance from 0 to 1
+    pub score: f64,
+    /// Why the agent was chosen
+    pub rationale: String,
+}
+
+fn stem(word: &str) -> &str {
+    ["ing", "ed", "s"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix).filter(|stem| stem.len() >= 3))
+        .unwrap_or(word)
+}
+
+/// Distinct stemmed words of `text`, skipping stop words and short words
+fn words(text: &str) -> BTreeSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 2 && !STOP_WORDS.contains(w))
+        .map(|w| stem(w).to_string())
+        .collect()
+}
+
+fn category_hints(category: &AgentCategory) -> BTreeSet<String> {
+    CATEGORY_HINTS
+        .iter()
+        .filter(|(c, _)| c == category)
+        .flat_map(|(_, hints)| hints.iter().map(|hint| stem(hint).to_string()))
+        .collect()
+}
+
+fn quoted(words: &[&str]) -> String {
+    words
+        .iter()
+        .map(|w| format!("\"{}\"", w))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Score one agent against the task words
+fn score(agent: &AgentMetadata, task: &BTreeSet<String>) -> Option<(u32, String)> {
+    let name = words(&agent.name.replace('-', " "));
+    let hints = category_hints(&agent.category);
+    let text = words(&format!("{} {}", agent.description, agent.capabilities.join(" ")));
+
+    let (mut in_name, mut in_category, mut in_text) = (Vec::new(), Vec::new(), Vec::new());
+    let mut total = 0;
+    for word in task {
+        // Each task word counts once, in the field where it weighs most
+        if name.contains(word) {
+            in_name.push(word.as_str());
+            total += NAME_WEIGHT;
+        } else if hints.contains(word) {
+            in_category.push(word.as_str());
+            total += CATEGORY_WEIGHT;
+        } else if text.contains(word) {
+            in_text.push(word.as_str());
+            total += TEXT_WEIGHT;
+        }
+    }
+    if total == 0 {
+        return None;
+    }
+
+    let mut reasons = Vec::new();
+    if !in_name.is_empty() {
+        reasons.push(format!("name matches {}", quoted(&in_name)));
+    }
+    if !in_category.is_empty() {
+        reasons.push(format!("{:?} agents handle {}", agent.category, quoted(&in_category)));
+    }
+    if !in_text.is_empty() {
+        reasons.push(format!("description covers {}", quoted(&in_text)));
+    }
+    let mut rationale = reasons.join("; ");
+    if let Some(first) = rationale.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    Some((total, rationale))
+}
+
+/// Rank `agents` for `task`, best first, returning at most `limit`
+///
+/// Agents that share no words with the task are left out, so the result
+/// may be empty.
+pub fn recommend(agents: &[&AgentMetadata], task: &str, limit: usize) -> Vec<Recommendation> {
+    let task_words = words(task);
+    if task_words.is_empty() {
+        return Vec::new();
+    }
+    let best_possible = (task_words.len() as u32 * NAME_WEIGHT) as f64;
+
+    let mut ranked: Vec<(u32, &AgentMetadata, String)> = agents
+        .iter()
+        .filter_map(|agent| score(agent, &task_words).map(|(s, why)| (s, *agent, why)))
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(score, agent, rationale)| Recommendation {
+            agent: agent.name.clone(),
+            score: (score as f64 / best_possible).min(1.0),
+            rationale,
+        })
+        .collect()
+}
+
+/// Prompt asking an LLM to pick the best of `candidates` for `task`
+pub fn llm_prompt(task: &str, candidates: &[&AgentMetadata]) -> String {
+    let list: String = candidates
+        .iter()
+        .map(|agent| format!("- {}: {}\n", agent.name, agent.description))
+        .collect();
+    format!(
+        "Choose the agents best suited to the task below from this list.\n\n\
+         Agents:\n{list}\n\
+         Task:\n{task}\n\n\
+         Reply with a JSON array only, best agent first, at most 5 entries. \
+         Each entry is an object with \"agent\" (a name from the list) and \
+         \"rationale\" (one sentence on why it fits).",
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmChoice {
+    agent: String,
+    #[serde(default)]
+    rationale: String,
+}
+
+/// Read the agents an LLM chose from `response`
+///
+/// Names that are not among `candidates` are dropped. Scores fall off
+/// linearly with rank.
+pub fn apply_llm_ranking(
+    response: &str,
+    candidates: &[&AgentMetadata],
+) -> Result<Vec<Recommendation>> {
+    let start = response.find('[');
+    let end = response.rfind(']');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => {
+            return Err(AIT42Error::ExecutionFailed(
+                "LLM response contains no agent list".to_string(),
+            ))
+        }
+    };
+    let choices: Vec<LlmChoice> = serde_json::from_str(json)?;
+
+    let mut seen = BTreeSet::new();
+    let known: Vec<LlmChoice> = choices
+        .into_iter()
+        .filter(|choice| candidates.iter().any(|agent| agent.name == choice.agent))
+        .filter(|choice| seen.insert(choice.agent.clone()))
+        .collect();
+    let count = known.len() as f64;
+
+    Ok(known
+        .into_iter()
+        .enumerate()
+        .map(|(rank, choice)| Recommendation {
+            agent: choice.agent,
+            score: 1.0 - rank as f64 / count,
+            rationale: choice.rationale.trim().to_string(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::AgentSource;
+    use std::path::PathBuf;
+
+    fn agent(name: &str, category: AgentCategory, description: &str) -> AgentMetadata {
+        AgentMetadata {
+            name: name.to_string(),
+            description: description.to_string(),
+            category,
+            capabilities: Vec::new(),
+            tools: Vec::new(),
+            model: "sonnet".to_string(),
+            version: None,
+            file_path: PathBuf::from(format!("{}.md", name)),
+            source: AgentSource::Global,
+        }
+    }
+
+    fn agents() -> Vec<AgentMetadata> {
+        vec![
+            agent("test-generator", AgentCategory::Testing, "Generates unit and integration tests"),
+            agent(
+                "backend-developer",
+                AgentCategory::Backend,
+                "Implements services and business logic",
+            ),
+            agent(
+                "security-tester",
+                AgentCategory::Security,
+                "Finds vulnerabilities such as SQL injection",
+            ),
+            agent("tech-writer", AgentCategory::Documentation, "Writes guides"),
+        ]
+    }
+
+    #[test]
+    fn test_ranks_by_name_category_and_description() {
+        let agents = agents();
+        let refs: Vec<&AgentMetadata> = agents.iter().collect();
+
+        let ranked = recommend(&refs, "Add tests for the login endpoint", 3);
+        assert_eq!(ranked[0].agent, "test-generator");
+        assert!(ranked[0].rationale.starts_with("Name matches \"test\""));
+        assert_eq!(ranked[1].agent, "backend-developer");
+        assert!(ranked[1]
+            .rationale
+            .contains("Backend agents handle \"endpoint\""));
+        assert!(ranked[0].score > ranked[1].score);
+        assert!(ranked.iter().all(|r| r.score > 0.0 && r.score <= 1.0));
+
+        let ranked = recommend(&refs, "check for SQL injection", 5);
+        assert_eq!(ranked[0].agent, "security-tester");
+    }
+
+    #[test]
+    fn test_unrelated_task_has_no_recommendations() {
+        let agents = agents();
+        let refs: Vec<&AgentMetadata> = agents.iter().collect();
+        assert!(recommend(&refs, "the and for", 5).is_empty());
+        assert!(recommend(&refs, "bake sourdough", 5).is_empty());
+        assert_eq!(recommend(&refs, "tests", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_llm_ranking() {
+        let agents = agents();
+        let refs: Vec<&AgentMetadata> = agents.iter().collect();
+        assert!(llm_prompt("write docs", &refs).contains("- tech-writer: Writes guides"));
+
+        let response = r#"Here you go:
+[{"agent": "tech-writer", "rationale": " Writes docs. "},
+ {"agent": "made-up"},
+ {"agent": "tech-writer"},
+ {"agent": "test-generator", "rationale": "Examples as tests"}]"#;
+        let ranked = apply_llm_ranking(response, &refs).unwrap();
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].agent, "tech-writer");
+        assert_eq!(ranked[0].rationale, "Writes docs.");
+        assert_eq!(ranked[0].score, 1.0);
+        assert_eq!(ranked[1].score, 0.5);
+
+        assert!(apply_llm_ranking("no idea", &refs).is_err());
+    }
+}
@@ -1,6 +1,31 @@
 //! Agent registry for discovering and managing AIT42 agents
+//!
+//! Agents are markdown files anywhere below the agents directory, described
+//! by a frontmatter block:
+//!
+//! ```text
+//! ---
+//! name: backend-developer
+//! description: "Backend API implementation specialist"
+//! category: backend
+//! tools: Read, Write, Edit
+//! model: sonnet
+//! ---
+//! ```
+//!
+//! `name` and `description` are required. Without a known `category` it is
+//! inferred from the name, and `tools` is either a comma separated list or a
+//! YAML list. Markdown files without frontmatter (READMEs, shared notes) are
+//! not agents; files with broken frontmatter are skipped and reported by
+//! [`AgentRegistry::load_errors`].
+//!
+//! Besides the global agents directory, a project may keep its own agents in
+//! [`PROJECT_AGENTS_DIR`]. They are loaded after the global ones, and a
+//! project agent replaces the global agent of the same name.
 
 use crate::error::{AIT42Error, Result};
+use crate::recommend::{self, Recommendation};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -45,6 +70,20 @@ impl FromStr for AgentCategory {
     }
 }
 
+/// Directory of a project's own agents, relative to the project root
+pub const PROJECT_AGENTS_DIR: &str = ".ait42/agents";
+
+/// Where an agent definition was loaded from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentSource {
+    /// The global agents directory
+    #[default]
+    Global,
+    /// The project's [`PROJECT_AGENTS_DIR`]
+    Project,
+}
+
 /// Metadata for an AI agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentMetadata {
@@ -54,7 +93,12 @@ pub struct AgentMetadata {
     pub capabilities: Vec<String>,
     pub tools: Vec<String>,
     pub model: String,
+    /// Version declared by the `version` frontmatter field
+    #[serde(default)]
+    pub version: Option<String>,
     pub file_path: PathBuf,
+    #[serde(default)]
+    pub source: AgentSource,
 }
 
 /// Registry of available AI agents
@@ -62,6 +106,12 @@ pub struct AgentMetadata {
 pub struct AgentRegistry {
     agents: HashMap<String, AgentMetadata>,
     agents_dir: PathBuf,
+    /// Agents directory of the open project
+    project_dir: Option<PathBuf>,
+    /// Files of global agents replaced by project agents, by name
+    shadowed: HashMap<String, PathBuf>,
+    /// Agent files that failed to parse, with the reason
+    errors: HashMap<PathBuf, String>,
 }
 
 impl AgentRegistry {
@@ -70,9 +120,35 @@ impl AgentRegistry {
         Self {
             agents: HashMap::new(),
             agents_dir,
+            project_dir: None,
+            shadowed: HashMap::new(),
+            errors: HashMap::new(),
         }
     }
 
+    /// Directory the agents are loaded from
+    pub fn agents_dir(&self) -> &Path {
+        &self.agents_dir
+    }
+
+    /// Agents directory of the project at `root`
+    pub fn project_agents_dir(root: &Path) -> PathBuf {
+        root.join(PROJECT_AGENTS_DIR)
+    }
+
+    /// Directory of project agents loaded over the global ones, if any
+    pub fn project_dir(&self) -> Option<&Path> {
+        self.project_dir.as_deref()
+    }
+
+    /// Load project agents from `dir` as well, from the next load on
+    ///
+    /// The directory need not exist; a project without one has no agents
+    /// of its own.
+    pub fn set_project_dir(&mut self, dir: Option<PathBuf>) {
+        self.project_dir = dir;
+    }
+
     /// Load agents from directory
     pub fn load_from_directory(agents_dir: &Path) -> Result<Self> {
         let mut registry = Self::new(agents_dir.to_path_buf());
@@ -80,54 +156,147 @@ impl AgentRegistry {
         Ok(registry)
     }
 
-    /// Load all agent metadata files
-    fn load_agents(&mut self) -> Result<()> {
+    /// (Re)load all agent files below the agents directory, then those of
+    /// the project
+    ///
+    /// Replaces the agents loaded before. Files that fail to parse are
+    /// logged and skipped; see [`Self::load_errors`]. A missing project
+    /// directory is not an error.
+    pub fn load_agents(&mut self) -> Result<()> {
         info!("Loading agents from: {}", self.agents_dir.display());
 
-        let entries = fs::read_dir(&self.agents_dir).map_err(|e| {
+        let mut files = Vec::new();
+        Self::collect_agent_files(&self.agents_dir, &mut files).map_err(|e| {
             AIT42Error::ConfigError(format!(
                 "Failed to read agents directory {}: {}",
                 self.agents_dir.display(),
                 e
             ))
         })?;
+        files.sort();
+
+        let mut project_files = Vec::new();
+        if let Some(project_dir) = self.project_dir.as_ref().filter(|dir| dir.is_dir()) {
+            info!("Loading project agents from: {}", project_dir.display());
+            Self::collect_agent_files(project_dir, &mut project_files).map_err(|e| {
+                AIT42Error::ConfigError(format!(
+                    "Failed to read project agents directory {}: {}",
+                    project_dir.display(),
+                    e
+                ))
+            })?;
+            project_files.sort();
+        }
 
-        let mut loaded = 0;
-        let mut errors = Vec::new();
+        self.agents.clear();
+        self.shadowed.clear();
+        self.errors.clear();
+
+        let sources = [
+            (files, AgentSource::Global),
+            (project_files, AgentSource::Project),
+        ];
+        for (files, source) in sources {
+            for path in files {
+                self.load_agent_file(path, source);
+            }
+        }
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        info!("Loaded {} agents successfully", self.agents.len());
+        if !self.shadowed.is_empty() {
+            info!("{} project agents replace global ones", self.shadowed.len());
+        }
+        if !self.errors.is_empty() {
+            warn!("Failed to load {} agents", self.errors.len());
+        }
 
-            if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                match self.parse_agent_file(&path) {
-                    Ok(metadata) => {
-                        debug!("Loaded agent: {}", metadata.name);
-                        self.agents.insert(metadata.name.clone(), metadata);
-                        loaded += 1;
-                    }
-                    Err(e) => {
-                        warn!("Failed to parse {}: {}", path.display(), e);
-                        errors.push(e);
-                    }
-                }
+        Ok(())
+    }
+
+    /// Add the agent defined in `path`, recording why if it cannot be read
+    fn load_agent_file(&mut self, path: PathBuf, source: AgentSource) {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read {}: {}", path.display(), e);
+                self.errors.insert(path, e.to_string());
+                return;
             }
+        };
+        if !content.starts_with("---") {
+            debug!("Skipping {}: no frontmatter", path.display());
+            return;
         }
 
-        info!("Loaded {} agents successfully", loaded);
-        if !errors.is_empty() {
-            warn!("Failed to load {} agents", errors.len());
+        let metadata = match self.parse_agent(&path, &content) {
+            Ok(metadata) => AgentMetadata { source, ..metadata },
+            Err(e) => {
+                warn!("Failed to parse {}: {}", path.display(), e);
+                self.errors.insert(path, e.to_string());
+                return;
+            }
+        };
+        debug!("Loaded agent: {}", metadata.name);
+        if let Some(previous) = self.agents.get(&metadata.name) {
+            if previous.source == AgentSource::Global && source == AgentSource::Project {
+                debug!("Project agent {} replaces the global one", metadata.name);
+                self.shadowed
+                    .insert(metadata.name.clone(), previous.file_path.clone());
+            } else {
+                warn!(
+                    "Agent {} in {} replaces the one in {}",
+                    metadata.name,
+                    path.display(),
+                    previous.file_path.display()
+                );
+            }
         }
+        self.agents.insert(metadata.name.clone(), metadata);
+    }
+
+    /// Markdown files below `dir`, skipping hidden entries
+    ///
+    /// Only a failure to read `dir` itself is an error; unreadable
+    /// subdirectories are logged and skipped.
+    fn collect_agent_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
 
+            if entry.file_type()?.is_dir() {
+                if let Err(e) = Self::collect_agent_files(&path, files) {
+                    warn!("Skipping {}: {}", path.display(), e);
+                }
+            } else if path.extension().and_then(|s| s.to_str()) == Some("md") {
+                files.push(path);
+            }
+        }
         Ok(())
     }
 
-    /// Parse agent metadata from markdown file
-    fn parse_agent_file(&self, path: &Path) -> Result<AgentMetadata> {
-        let content = fs::read_to_string(path)?;
+    /// File of the global agent that the project agent `name` replaces
+    pub fn shadowed(&self, name: &str) -> Option<&Path> {
+        self.shadowed.get(name).map(PathBuf::as_path)
+    }
+
+    /// Agent files that failed to parse in the last load, with the reason
+    pub fn load_errors(&self) -> Vec<(&Path, &str)> {
+        let mut errors: Vec<(&Path, &str)> = self
+            .errors
+            .iter()
+            .map(|(path, error)| (path.as_path(), error.as_str()))
+            .collect();
+        errors.sort();
+        errors
+    }
 
+    /// Parse agent metadata from the content of the markdown file at `path`
+    fn parse_agent(&self, path: &Path, content: &str) -> Result<AgentMetadata> {
         // Parse YAML frontmatter
-        let frontmatter = self.extract_frontmatter(&content)?;
+        let frontmatter = self.extract_frontmatter(content)?;
 
         let name = frontmatter
             .get("name")
@@ -141,18 +310,32 @@ impl AgentRegistry {
             .ok_or_else(|| AIT42Error::InvalidMetadata("Missing 'description' field".to_string()))?
             .to_string();
 
-        // Infer category from agent name
-        let category = self.infer_category(&name);
+        // Declared category, or inferred from agent name
+        let category = match frontmatter.get("category").and_then(|v| v.as_str()) {
+            Some(category) => AgentCategory::from_str(category).unwrap_or_else(|e| {
+                warn!("{} in {}, inferring from name", e, path.display());
+                self.infer_category(&name)
+            }),
+            None => self.infer_category(&name),
+        };
 
         // Extract capabilities from content
-        let capabilities = self.extract_capabilities(&content);
-
-        // Extract tools
-        let tools = frontmatter
-            .get("tools")
-            .and_then(|v| v.as_str())
-            .map(|s| s.split(',').map(|t| t.trim().to_string()).collect())
-            .unwrap_or_default();
+        let capabilities = self.extract_capabilities(content);
+
+        // Extract tools, as a comma separated string or a list
+        let tools = match frontmatter.get("tools") {
+            Some(serde_json::Value::String(s)) => s
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|t| t.as_str())
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        };
 
         let model = frontmatter
             .get("model")
@@ -160,6 +343,12 @@ impl AgentRegistry {
             .unwrap_or("sonnet")
             .to_string();
 
+        let version = frontmatter
+            .get("version")
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string);
+
         Ok(AgentMetadata {
             name,
             description,
@@ -167,7 +356,9 @@ impl AgentRegistry {
             capabilities,
             tools,
             model,
+            version,
             file_path: path.to_path_buf(),
+            source: AgentSource::Global,
         })
     }
 
@@ -175,29 +366,60 @@ impl AgentRegistry {
     fn extract_frontmatter(&self, content: &str) -> Result<HashMap<String, serde_json::Value>> {
         let lines: Vec<&str> = content.lines().collect();
 
-        if lines.first() != Some(&"---") {
+        if lines.first().map(|l| l.trim_end()) != Some("---") {
             return Err(AIT42Error::InvalidMetadata("No frontmatter delimiter found".to_string()));
         }
 
         let end_idx = lines
             .iter()
             .skip(1)
-            .position(|&line| line == "---")
+            .position(|line| line.trim_end() == "---")
             .ok_or_else(|| {
                 AIT42Error::InvalidMetadata("No frontmatter end delimiter found".to_string())
             })?
             + 1;
 
-        let yaml_content = lines[1..end_idx].join("\n");
-
-        // Simple YAML parser (key: value format)
+        // Simple YAML parser: `key: value`, `key: [a, b]` and `key:`
+        // followed by `- item` lines
+        let unquote = |s: &str| s.trim().trim_matches('"').trim_matches('\'').to_string();
         let mut map = HashMap::new();
-        for line in yaml_content.lines() {
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim().to_string();
-                let value = value.trim().trim_matches('"').to_string();
-                map.insert(key, serde_json::Value::String(value));
+        let mut list_key: Option<String> = None;
+        for line in &lines[1..end_idx] {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if let (Some(key), Some(item)) = (&list_key, trimmed.strip_prefix('-')) {
+                if let Some(serde_json::Value::Array(items)) = map.get_mut(key) {
+                    items.push(serde_json::Value::String(unquote(item)));
+                }
+                continue;
             }
+
+            let (key, value) = trimmed.split_once(':').ok_or_else(|| {
+                AIT42Error::InvalidMetadata(format!("Invalid frontmatter line: {}", trimmed))
+            })?;
+            let key = key.trim().to_string();
+            let value = value.trim();
+
+            list_key = None;
+            let value = if value.is_empty() {
+                list_key = Some(key.clone());
+                serde_json::Value::Array(Vec::new())
+            } else if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                serde_json::Value::Array(
+                    inner
+                        .split(',')
+                        .map(unquote)
+                        .filter(|item| !item.is_empty())
+                        .map(serde_json::Value::String)
+                        .collect(),
+                )
+            } else {
+                serde_json::Value::String(unquote(value))
+            };
+            map.insert(key, value);
         }
 
         Ok(map)
@@ -331,6 +553,11 @@ impl AgentRegistry {
         score
     }
 
+    /// Rank agents for a task description, best first
+    pub fn recommend(&self, task: &str, limit: usize) -> Vec<Recommendation> {
+        recommend::recommend(&self.list(), task, limit)
+    }
+
     /// Get total number of agents
     pub fn count(&self) -> usize {
         self.agents.len()
@@ -370,6 +597,7 @@ name: test-agent
 description: "A test agent"
 tools: Read, Write
 model: sonnet
+version: "1.2.0"
 ---
 
 <capabilities>
@@ -382,10 +610,114 @@ model: sonnet
         file.write_all(content.as_bytes()).unwrap();
 
         let registry = AgentRegistry::new(temp_dir.path().to_path_buf());
-        let metadata = registry.parse_agent_file(&agent_path).unwrap();
+        let metadata = registry
+            .parse_agent(&agent_path, &fs::read_to_string(&agent_path).unwrap())
+            .unwrap();
 
         assert_eq!(metadata.name, "test-agent");
         assert_eq!(metadata.description, "A test agent");
         assert_eq!(metadata.capabilities.len(), 2);
+        assert_eq!(metadata.category, AgentCategory::Testing);
+        assert_eq!(metadata.tools, vec!["Read", "Write"]);
+        assert_eq!(metadata.version.as_deref(), Some("1.2.0"));
+    }
+
+    #[test]
+    fn test_parse_category_and_tool_list() {
+        let registry = AgentRegistry::new(PathBuf::from("/tmp"));
+        let content = "---\r\nname: api-helper\r\ndescription: Helps\r\ncategory: security\r\n\
+                       tools:\r\n  - Read\r\n  - \"Grep\"\r\n---\r\nBody\r\n";
+
+        let metadata = registry
+            .parse_agent(Path::new("api-helper.md"), content)
+            .unwrap();
+        assert_eq!(metadata.category, AgentCategory::Security);
+        assert_eq!(metadata.tools, vec!["Read", "Grep"]);
+
+        let inline =
+            "---\nname: ui-helper\ndescription: Helps\ncategory: bogus\ntools: [Read, Edit]\n---\n";
+        let metadata = registry
+            .parse_agent(Path::new("ui-helper.md"), inline)
+            .unwrap();
+        assert_eq!(metadata.category, AgentCategory::Frontend);
+        assert_eq!(metadata.tools, vec!["Read", "Edit"]);
+        assert_eq!(metadata.version, None);
+    }
+
+    #[test]
+    fn test_load_agents_recursively_skipping_malformed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let agent = |name: &str| format!("---\nname: {}\ndescription: Agent {}\n---\n", name, name);
+        fs::create_dir_all(temp_dir.path().join("backend/nested")).unwrap();
+        fs::create_dir_all(temp_dir.path().join(".hidden")).unwrap();
+        fs::write(temp_dir.path().join("top.md"), agent("top")).unwrap();
+        fs::write(temp_dir.path().join("backend/nested/deep.md"), agent("deep")).unwrap();
+        fs::write(temp_dir.path().join(".hidden/secret.md"), agent("secret")).unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# Agents\n").unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), agent("notes")).unwrap();
+        fs::write(temp_dir.path().join("broken.md"), "---\nname: broken\n---\n").unwrap();
+        fs::write(temp_dir.path().join("unclosed.md"), "---\nname: unclosed\n").unwrap();
+
+        let mut registry = AgentRegistry::load_from_directory(temp_dir.path()).unwrap();
+        let mut names: Vec<&str> = registry.list().iter().map(|a| a.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["deep", "top"]);
+
+        let errors = registry.load_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].0, temp_dir.path().join("broken.md"));
+        assert!(errors[0].1.contains("description"));
+
+        // Reloading picks up changes and forgets removed agents
+        fs::remove_file(temp_dir.path().join("top.md")).unwrap();
+        fs::write(temp_dir.path().join("broken.md"), agent("fixed")).unwrap();
+        registry.load_agents().unwrap();
+        let mut names: Vec<&str> = registry.list().iter().map(|a| a.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["deep", "fixed"]);
+        assert_eq!(registry.load_errors().len(), 1);
+
+        assert!(AgentRegistry::load_from_directory(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_project_agents_shadow_global_ones() {
+        let global = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        let agent = |name: &str, description: &str| {
+            format!("---\nname: {}\ndescription: {}\n---\n", name, description)
+        };
+        let project_dir = AgentRegistry::project_agents_dir(project.path());
+        fs::write(global.path().join("reviewer.md"), agent("reviewer", "Global")).unwrap();
+        fs::write(global.path().join("tester.md"), agent("tester", "Global")).unwrap();
+
+        // A project without agents of its own sees the global ones
+        let mut registry = AgentRegistry::new(global.path().to_path_buf());
+        registry.set_project_dir(Some(project_dir.clone()));
+        registry.load_agents().unwrap();
+        assert_eq!(registry.count(), 2);
+        assert_eq!(registry.get("reviewer").unwrap().source, AgentSource::Global);
+
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("reviewer.md"), agent("reviewer", "Project")).unwrap();
+        fs::write(project_dir.join("migrator.md"), agent("migrator", "Project")).unwrap();
+        registry.load_agents().unwrap();
+
+        assert_eq!(registry.count(), 3);
+        let reviewer = registry.get("reviewer").unwrap();
+        assert_eq!(reviewer.description, "Project");
+        assert_eq!(reviewer.source, AgentSource::Project);
+        assert_eq!(
+            registry.shadowed("reviewer"),
+            Some(global.path().join("reviewer.md").as_path())
+        );
+        assert_eq!(registry.get("migrator").unwrap().source, AgentSource::Project);
+        assert_eq!(registry.shadowed("migrator"), None);
+        assert_eq!(registry.get("tester").unwrap().source, AgentSource::Global);
+
+        registry.set_project_dir(None);
+        registry.load_agents().unwrap();
+        assert_eq!(registry.get("reviewer").unwrap().description, "Global");
+        assert_eq!(registry.shadowed("reviewer"), None);
     }
 }
@@ -0,0 +1,302 @@
+//! Code review of a diff by an agent
+//!
+//! [`review_task`] turns a diff into a task for the code-review agent, asking
+//! for its findings as a JSON array, and [`ReviewReport::from_output`] reads
+//! them back out of the agent's output. Findings carry a file and line so
+//! they can be shown next to compiler and language server diagnostics.
+
+use crate::error::{AIT42Error, Result};
+use claude_output::json_blocks;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Agent that reviews diffs
+pub const REVIEW_AGENT: &str = "code-reviewer";
+
+/// How serious a finding is
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Info,
+}
+
+impl ReviewSeverity {
+    /// Read a severity, accepting the synonyms agents tend to use
+    ///
+    /// Unknown words are treated as informational.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "critical" | "blocker" | "fatal" => ReviewSeverity::Critical,
+            "high" | "error" | "major" => ReviewSeverity::High,
+            "medium" | "warning" | "warn" | "moderate" => ReviewSeverity::Medium,
+            "low" | "minor" => ReviewSeverity::Low,
+            _ => ReviewSeverity::Info,
+        }
+    }
+
+    /// LSP `DiagnosticSeverity` (1 = error, 2 = warning, 3 = information, 4 = hint)
+    pub fn lsp_severity(self) -> u8 {
+        match self {
+            ReviewSeverity::Critical | ReviewSeverity::High => 1,
+            ReviewSeverity::Medium => 2,
+            ReviewSeverity::Low => 3,
+            ReviewSeverity::Info => 4,
+        }
+    }
+}
+
+impl fmt::Display for ReviewSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReviewSeverity::Critical => "critical",
+            ReviewSeverity::High => "high",
+            ReviewSeverity::Medium => "medium",
+            ReviewSeverity::Low => "low",
+            ReviewSeverity::Info => "info",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One problem found by the reviewer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewFinding {
+    /// Path relative to the repository root
+    pub file: String,
+    /// 1-based line in the new version of the file, if the finding has one
+    pub line: Option<u32>,
+    pub severity: ReviewSeverity,
+    pub message: String,
+    /// Proposed fix
+    pub suggestion: Option<String>,
+}
+
+/// Finding as written by the agent, before normalization
+#[derive(Debug, Deserialize)]
+struct RawFinding {
+    file: String,
+    #[serde(default)]
+    line: Option<serde_json::Value>,
+    #[serde(default)]
+    severity: Option<String>,
+    message: String,
+    #[serde(default)]
+    suggestion: Option<String>,
+}
+
+impl From<RawFinding> for ReviewFinding {
+    fn from(raw: RawFinding) -> Self {
+        // Agents write lines as numbers, strings, or "12-15" ranges
+        let line = raw.line.and_then(|value| match value {
+            serde_json::Value::Number(n) => n.as_u64().map(|n| n as u32),
+            serde_json::Value::String(s) => s
+                .split(|c: char| !c.is_ascii_digit())
+                .find(|part| !part.is_empty())
+                .and_then(|part| part.parse().ok()),
+            _ => None,
+        });
+        let file = raw.file.trim();
+        let file = file
+            .strip_prefix("a/")
+            .or_else(|| file.strip_prefix("b/"))
+            .or_else(|| file.strip_prefix("./"))
+            .unwrap_or(file);
+
+        Self {
+            file: file.to_string(),
+            line: line.filter(|&line| line > 0),
+            severity: raw
+                .severity
+                .as_deref()
+                .map(ReviewSeverity::parse)
+                .unwrap_or(ReviewSeverity::Info),
+            message: raw.message.trim().to_string(),
+            suggestion: raw
+                .suggestion
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+        }
+    }
+}
+
+/// Build the task asking the review agent to look at `diff`
+///
+/// `label` names the range under review, e.g. `main...HEAD`.
+pub fn review_task(diff: &str, label: &str) -> String {
+    format!(
+        "Review the changes in {label} shown in the diff below. Look for bugs, \
+         security problems, missing error handling and unclear code; ignore \
+         style issues a formatter would fix.\n\n\
+         End your reply with a ```json fenced block holding an array with one \
+         object per finding. Each object has the keys \"file\" (path as shown \
+         in the diff, without the a/ or b/ prefix), \"line\" (line number in \
+         the new version of the file), \"severity\" (one of critical, high, \
+         medium, low, info), \"message\" (what is wrong) and \"suggestion\" \
+         (how to fix it, or null). Use an empty array if there is nothing to \
+         report.\n\n\
+         Diff:\n{diff}",
+    )
+}
+
+/// Findings of one review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewReport {
+    /// Range that was reviewed, e.g. `main...HEAD`
+    pub range: String,
+    /// Agent that wrote the review
+    pub agent: String,
+    /// Most severe first
+    pub findings: Vec<ReviewFinding>,
+}
+
+impl ReviewReport {
+    /// Read the findings from the agent's output
+    ///
+    /// Uses the last ```json block, so an echo of the task earlier in the
+    /// output is never mistaken for the answer.
+    pub fn from_output(range: &str, agent: &str, output: &str) -> Result<Self> {
+        let json = json_blocks(output).pop().ok_or_else(|| {
+            AIT42Error::ExecutionFailed("review output contains no JSON findings".to_string())
+        })?;
+        let raw: Vec<RawFinding> = serde_json::from_str(json)?;
+
+        let mut findings: Vec<ReviewFinding> = raw.into_iter().map(Into::into).collect();
+        findings.sort_by(|a, b| (a.severity, &a.file, a.line).cmp(&(b.severity, &b.file, b.line)));
+
+        Ok(Self {
+            range: range.to_string(),
+            agent: agent.to_string(),
+            findings,
+        })
+    }
+
+    /// Number of findings at `severity`
+    pub fn count(&self, severity: ReviewSeverity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    /// One-line summary, e.g. `3 findings (1 high, 2 low)`
+    pub fn summary(&self) -> String {
+        if self.findings.is_empty() {
+            return "no findings".to_string();
+        }
+        let counts: Vec<String> = [
+            ReviewSeverity::Critical,
+            ReviewSeverity::High,
+            ReviewSeverity::Medium,
+            ReviewSeverity::Low,
+            ReviewSeverity::Info,
+        ]
+        .into_iter()
+        .filter_map(|severity| match self.count(severity) {
+            0 => None,
+            n => Some(format!("{} {}", n, severity)),
+        })
+        .collect();
+        let noun = if self.findings.len() == 1 {
+            "finding"
+        } else {
+            "findings"
+        };
+        format!("{} {} ({})", self.findings.len(), noun, counts.join(", "))
+    }
+
+    /// The report as Markdown, for attaching to a session
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Code review: {}\n\nReviewed by `{}`: {}.\n",
+            self.range,
+            self.agent,
+            self.summary()
+        );
+        for finding in &self.findings {
+            let location = match finding.line {
+                Some(line) => format!("{}:{}", finding.file, line),
+                None => finding.file.clone(),
+            };
+            out.push_str(&format!(
+                "\n- **{}** `{}`: {}\n",
+                finding.severity, location, finding.message
+            ));
+            if let Some(suggestion) = &finding.suggestion {
+                out.push_str(&format!("  - Suggestion: {}\n", suggestion));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = r#"Looked at 2 files.
+
+```json
+[
+  {"file": "b/src/db.rs", "line": "40-42", "severity": "Low", "message": "Unused import", "suggestion": ""},
+  {"file": "src/api.rs", "line": 12, "severity": "critical", "message": "SQL built from user input", "suggestion": "Use a bound parameter"},
+  {"file": "README.md", "severity": "nit", "message": "Typo"}
+]
+```
+"#;
+
+    #[test]
+    fn test_parse_findings() {
+        let report = ReviewReport::from_output("main...HEAD", REVIEW_AGENT, OUTPUT).unwrap();
+        assert_eq!(report.findings.len(), 3);
+
+        let first = &report.findings[0];
+        assert_eq!(first.file, "src/api.rs");
+        assert_eq!(first.line, Some(12));
+        assert_eq!(first.severity, ReviewSeverity::Critical);
+        assert_eq!(first.suggestion.as_deref(), Some("Use a bound parameter"));
+
+        let second = &report.findings[1];
+        assert_eq!(second.file, "src/db.rs");
+        assert_eq!(second.line, Some(40));
+        assert_eq!(second.suggestion, None);
+
+        assert_eq!(report.findings[2].severity, ReviewSeverity::Info);
+        assert_eq!(report.findings[2].line, None);
+        assert_eq!(report.summary(), "3 findings (1 critical, 1 low, 1 info)");
+    }
+
+    #[test]
+    fn test_last_json_block_wins() {
+        let output = format!("{}\n```json\n[]\n```\n", OUTPUT);
+        let report = ReviewReport::from_output("a..b", REVIEW_AGENT, &output).unwrap();
+        assert!(report.findings.is_empty());
+        assert_eq!(report.summary(), "no findings");
+
+        assert!(ReviewReport::from_output("a..b", REVIEW_AGENT, "Looks good").is_err());
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(ReviewSeverity::parse(" Warning "), ReviewSeverity::Medium);
+        assert_eq!(ReviewSeverity::parse("blocker"), ReviewSeverity::Critical);
+        assert_eq!(ReviewSeverity::High.lsp_severity(), 1);
+        assert_eq!(ReviewSeverity::Info.lsp_severity(), 4);
+    }
+
+    #[test]
+    fn test_markdown() {
+        let report = ReviewReport::from_output("main...HEAD", REVIEW_AGENT, OUTPUT).unwrap();
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("# Code review: main...HEAD"));
+        assert!(markdown.contains("- **critical** `src/api.rs:12`: SQL built from user input"));
+        assert!(markdown.contains("  - Suggestion: Use a bound parameter"));
+        assert!(markdown.contains("`README.md`: Typo"));
+    }
+}
@@ -0,0 +1,351 @@
+//! Competition scoring
+//!
+//! Ranks the instances of a competition once they have all finished. Each
+//! instance is scored on whether its tests pass, how small its diff is and,
+//! optionally, an LLM's judgement of its changes (see [`evaluation_prompt`]
+//! and [`parse_evaluations`]). A criterion nobody was measured on drops out
+//! and the other weights are scaled up, so a project without tests is ranked
+//! on diff size and the LLM alone.
+//!
+//! To compare the instances at a glance, an LLM can also describe each
+//! one's changes in a paragraph (see [`summary_prompt`] and
+//! [`parse_summaries`]).
+
+use crate::error::{AIT42Error, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Weight of passing tests
+pub const TESTS_WEIGHT: f64 = 0.5;
+/// Weight of a small diff
+pub const DIFF_SIZE_WEIGHT: f64 = 0.2;
+/// Weight of the LLM evaluation
+pub const LLM_WEIGHT: f64 = 0.3;
+
+/// What was measured for one instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub instance_id: u32,
+    /// Whether the instance finished without failing
+    pub completed: bool,
+    /// Lines added and removed since the worktree was created
+    pub lines_changed: usize,
+    /// Whether the test command passed; `None` if tests were not run
+    pub tests_passed: Option<bool>,
+    /// LLM rating from 0 to 10
+    pub llm_score: Option<f64>,
+}
+
+impl Candidate {
+    /// Only finished instances that changed something can win
+    fn eligible(&self) -> bool {
+        self.completed && self.lines_changed > 0
+    }
+}
+
+/// Score of one instance, each part from 0 to 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Score {
+    pub instance_id: u32,
+    /// Weighted total of the measured parts
+    pub total: f64,
+    pub tests: Option<f64>,
+    pub diff_size: Option<f64>,
+    pub llm: Option<f64>,
+    /// Whether the instance can be chosen as winner
+    pub eligible: bool,
+}
+
+/// Score `candidates`, best first
+///
+/// Eligible instances always rank above the others; ties go to the lower
+/// instance ID. The diff size part is relative: the smallest eligible diff
+/// scores 1 and a diff twice its size 0.5.
+pub fn rank(candidates: &[Candidate]) -> Vec<Score> {
+    let smallest = candidates
+        .iter()
+        .filter(|c| c.eligible())
+        .map(|c| c.lines_changed)
+        .min();
+    let tests_measured = candidates.iter().any(|c| c.tests_passed.is_some());
+    let llm_measured = candidates.iter().any(|c| c.llm_score.is_some());
+
+    let mut scores: Vec<Score> = candidates
+        .iter()
+        .map(|candidate| {
+            let eligible = candidate.eligible();
+            let tests = tests_measured
+                .then(|| f64::from(u8::from(eligible && candidate.tests_passed == Some(true))));
+            let diff_size = smallest.map(|smallest| {
+                if eligible {
+                    smallest as f64 / candidate.lines_changed as f64
+                } else {
+                    0.0
+                }
+            });
+            let llm = llm_measured.then(|| {
+                let score = candidate.llm_score.unwrap_or(0.0).clamp(0.0, 10.0) / 10.0;
+                if eligible {
+                    score
+                } else {
+                    0.0
+                }
+            });
+
+            let parts = [
+                (tests, TESTS_WEIGHT),
+                (diff_size, DIFF_SIZE_WEIGHT),
+                (llm, LLM_WEIGHT),
+            ];
+            let weight: f64 = parts
+                .iter()
+                .filter(|(p, _)| p.is_some())
+                .map(|(_, w)| w)
+                .sum();
+            let weighted: f64 = parts.iter().filter_map(|(p, w)| p.map(|p| p * w)).sum();
+            Score {
+                instance_id: candidate.instance_id,
+                total: if weight > 0.0 { weighted / weight } else { 0.0 },
+                tests,
+                diff_size,
+                llm,
+                eligible,
+            }
+        })
+        .collect();
+
+    scores.sort_by(|a, b| {
+        b.eligible
+            .cmp(&a.eligible)
+            .then(b.total.total_cmp(&a.total))
+            .then(a.instance_id.cmp(&b.instance_id))
+    });
+    scores
+}
+
+/// The instance that should win, if any is eligible
+pub fn winner(scores: &[Score]) -> Option<u32> {
+    scores
+        .iter()
+        .find(|score| score.eligible)
+        .map(|score| score.instance_id)
+}
+
+/// `diffs` as numbered solutions for a prompt
+fn solutions(diffs: &[(u32, String)]) -> String {
+    diffs
+        .iter()
+        .map(|(instance_id, diff)| format!("## Solution {}\n\n{}\n", instance_id, diff.trim_end()))
+        .collect()
+}
+
+/// Prompt asking an LLM to rate each instance's diff for `task`
+///
+/// `diffs` pairs instance IDs with their (budgeted) diffs.
+pub fn evaluation_prompt(task: &str, diffs: &[(u32, String)]) -> String {
+    let solutions = solutions(diffs);
+    format!(
+        "Several developers worked on the same task independently. Rate how \
+         well each solution below completes the task: correctness, \
+         completeness, and code quality.\n\n\
+         Task:\n{task}\n\n\
+         {solutions}\n\
+         Reply with a JSON array only, one object per solution, with \
+         \"instance\" (the solution number), \"score\" (0 to 10) and \
+         \"reason\" (one sentence).",
+    )
+}
+
+/// An LLM's rating of one instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluation {
+    pub instance_id: u32,
+    /// From 0 to 10
+    pub score: f64,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEvaluation {
+    instance: u32,
+    score: f64,
+    #[serde(default)]
+    reason: String,
+}
+
+/// The JSON array in an LLM's `response`, which should hold `what`
+fn json_array<'a>(response: &'a str, what: &str) -> Result<&'a str> {
+    let start = response.find('[');
+    let end = response.rfind(']');
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => Ok(&response[start..=end]),
+        _ => Err(AIT42Error::ExecutionFailed(format!("LLM response contains no {}", what))),
+    }
+}
+
+/// Read the ratings from an LLM's `response`
+///
+/// Ratings of instances not in `instance_ids` and repeated ratings are
+/// dropped; scores are clamped to 0..=10.
+pub fn parse_evaluations(response: &str, instance_ids: &[u32]) -> Result<Vec<Evaluation>> {
+    let raw: Vec<RawEvaluation> = serde_json::from_str(json_array(response, "evaluations")?)?;
+
+    let mut seen = BTreeSet::new();
+    Ok(raw
+        .into_iter()
+        .filter(|e| instance_ids.contains(&e.instance) && seen.insert(e.instance))
+        .map(|e| Evaluation {
+            instance_id: e.instance,
+            score: e.score.clamp(0.0, 10.0),
+            reason: e.reason.trim().to_string(),
+        })
+        .collect())
+}
+
+/// Prompt asking an LLM to describe what each instance's diff does
+///
+/// `diffs` pairs instance IDs with their (budgeted) diffs.
+pub fn summary_prompt(task: &str, diffs: &[(u32, String)]) -> String {
+    let solutions = solutions(diffs);
+    format!(
+        "Several developers worked on the same task independently. For each \
+         solution below, write one paragraph a reviewer can read instead of \
+         the diff: what it changes and how it approaches the task, and what \
+         sets it apart from the others.\n\n\
+         Task:\n{task}\n\n\
+         {solutions}\n\
+         Reply with a JSON array only, one object per solution, with \
+         \"instance\" (the solution number) and \"summary\" (the paragraph).",
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSummary {
+    instance: u32,
+    summary: String,
+}
+
+/// Read the summaries from an LLM's `response`, as `(instance ID, summary)`
+///
+/// Summaries of instances not in `instance_ids`, repeated and empty ones are
+/// dropped.
+pub fn parse_summaries(response: &str, instance_ids: &[u32]) -> Result<Vec<(u32, String)>> {
+    let raw: Vec<RawSummary> = serde_json::from_str(json_array(response, "summaries")?)?;
+
+    let mut seen = BTreeSet::new();
+    Ok(raw
+        .into_iter()
+        .map(|s| (s.instance, s.summary.trim().to_string()))
+        .filter(|(id, summary)| {
+            !summary.is_empty() && instance_ids.contains(id) && seen.insert(*id)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(instance_id: u32, lines_changed: usize, tests_passed: Option<bool>) -> Candidate {
+        Candidate {
+            instance_id,
+            completed: true,
+            lines_changed,
+            tests_passed,
+            llm_score: None,
+        }
+    }
+
+    #[test]
+    fn test_passing_tests_outweigh_a_small_diff() {
+        let scores = rank(&[
+            candidate(1, 10, Some(false)),
+            candidate(2, 40, Some(true)),
+            candidate(3, 0, Some(true)),
+        ]);
+        let order: Vec<u32> = scores.iter().map(|s| s.instance_id).collect();
+        assert_eq!(order, [2, 1, 3]);
+        assert_eq!(winner(&scores), Some(2));
+
+        assert_eq!(scores[0].tests, Some(1.0));
+        assert_eq!(scores[0].diff_size, Some(0.25));
+        assert_eq!(scores[0].llm, None);
+        assert!((scores[0].total - (0.5 + 0.2 * 0.25) / 0.7).abs() < 1e-9);
+        // Changing nothing cannot win, however well its tests pass
+        assert!(!scores[2].eligible);
+        assert_eq!(scores[2].tests, Some(0.0));
+    }
+
+    #[test]
+    fn test_unmeasured_criteria_drop_out() {
+        let failed = Candidate {
+            completed: false,
+            ..candidate(2, 5, None)
+        };
+        let rated = Candidate {
+            llm_score: Some(12.0),
+            ..candidate(3, 20, None)
+        };
+        let scores = rank(&[candidate(1, 10, None), failed.clone(), rated]);
+
+        assert_eq!(scores[0].instance_id, 3);
+        assert_eq!(scores[0].tests, None);
+        assert_eq!(scores[0].llm, Some(1.0));
+        assert!((scores[0].total - (0.2 * 0.5 + 0.3) / 0.5).abs() < 1e-9);
+        assert_eq!(scores[1].instance_id, 1);
+        assert_eq!(scores[1].diff_size, Some(1.0));
+        assert_eq!(scores[2].total, 0.0);
+
+        assert_eq!(winner(&rank(&[failed])), None);
+        assert!(rank(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_evaluation_prompt_and_parse() {
+        let prompt = evaluation_prompt("Fix the login bug", &[(1, "+a\n".to_string())]);
+        assert!(prompt.contains("Task:\nFix the login bug"));
+        assert!(prompt.contains("## Solution 1\n\n+a\n"));
+
+        let response = "Here you go:\n[{\"instance\": 2, \"score\": 7.5, \"reason\": \" Clean \"},\
+                        {\"instance\": 1, \"score\": 11},\
+                        {\"instance\": 2, \"score\": 1},\
+                        {\"instance\": 9, \"score\": 5}]";
+        let evaluations = parse_evaluations(response, &[1, 2]).unwrap();
+        assert_eq!(
+            evaluations,
+            vec![
+                Evaluation {
+                    instance_id: 2,
+                    score: 7.5,
+                    reason: "Clean".to_string(),
+                },
+                Evaluation {
+                    instance_id: 1,
+                    score: 10.0,
+                    reason: String::new(),
+                },
+            ]
+        );
+        assert!(parse_evaluations("No idea", &[1]).is_err());
+    }
+
+    #[test]
+    fn test_summary_prompt_and_parse() {
+        let prompt = summary_prompt("Fix the login bug", &[(1, "+a\n".to_string())]);
+        assert!(prompt.contains("Task:\nFix the login bug"));
+        assert!(prompt.contains("## Solution 1\n\n+a\n"));
+
+        let response = "```json\n[{\"instance\": 2, \"summary\": \" Retries the login. \"},\
+                        {\"instance\": 1, \"summary\": \"\"},\
+                        {\"instance\": 2, \"summary\": \"Again\"},\
+                        {\"instance\": 9, \"summary\": \"Unknown\"}]\n```";
+        assert_eq!(
+            parse_summaries(response, &[1, 2]).unwrap(),
+            vec![(2, "Retries the login.".to_string())]
+        );
+        assert!(parse_summaries("No idea", &[1]).is_err());
+    }
+}
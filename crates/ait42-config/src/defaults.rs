@@ -23,6 +23,8 @@ pub fn minimal_config() -> Config {
             show_whitespace: false,
             cursor_style: "block".to_string(),
             scroll_offset: 0,
+            font_size: 14,
+            restore_session: false,
         },
         theme: ThemeConfig {
             name: "default".to_string(),
@@ -34,9 +36,28 @@ pub fn minimal_config() -> Config {
             agents_path: std::path::PathBuf::from(".claude/agents"),
             tmux_enabled: false,
             auto_coordinator: false,
+            auto_mode: AutoModeConfig::default(),
+            budget: BudgetConfig::default(),
+            llm_provider: LlmProviderConfig::default(),
+            injection_scan: InjectionScanConfig::default(),
+            edit_guard: EditGuardConfig::default(),
+            alerts: Vec::new(),
             default_agent: None,
             agent_settings: std::collections::HashMap::new(),
         },
+        checkpoints: CheckpointConfig::default(),
+        sessions: SessionsConfig::default(),
+        updates: UpdatesConfig::default(),
+        todos: TodosConfig::default(),
+        clipboard: ClipboardConfig::default(),
+        focus: FocusConfig::default(),
+        syntax: SyntaxConfig::default(),
+        preload: PreloadConfig::default(),
+        remote: RemoteConfig::default(),
+        mcp: McpConfig::default(),
+        competition: CompetitionConfig::default(),
+        issues: IssuesConfig::default(),
+        features: std::collections::HashMap::new(),
     }
 }
 
@@ -48,7 +69,8 @@ pub fn example_config_toml() -> String {
 # Tab size in spaces
 tab_size = 4
 
-# Auto-save delay in milliseconds (0 = disabled)
+# Milliseconds after the last edit before unsaved buffers are copied
+# for crash recovery (0 = disabled)
 auto_save_delay = 5000
 
 # Show line numbers
@@ -75,6 +97,13 @@ cursor_style = "block"
 # Scroll offset (lines to keep visible above/below cursor)
 scroll_offset = 5
 
+# Font size in points (GUI only)
+font_size = 14
+
+# Save open tabs and panels on exit and offer to restore them when
+# launched without arguments (TUI only)
+restore_session = true
+
 [theme]
 # Theme name: "monokai", "gruvbox-dark"
 name = "monokai"
@@ -88,10 +117,23 @@ name = "monokai"
 # Keybinding mode: "vim", "emacs", "default"
 mode = "vim"
 
+# Global shortcut opening the quick task window; empty disables it
+quick_task = "CmdOrCtrl+Shift+Space"
+
 # Custom key bindings
 # [keybindings.custom]
 # "Ctrl+s" = "save"
 # "Ctrl+q" = "quit"
+# "Space w" = "save"
+# "insert:j k" = "enter_normal_mode"
+
+# Key bindings by mode: normal, insert, visual, command, sidebar
+# [keybindings.keymap.normal]
+# "Ctrl+s" = "save"
+# "Space w" = "save"
+# "q" = "noop"
+# [keybindings.keymap.insert]
+# "j k" = "enter_normal_mode"
 
 [lsp.rust]
 command = "rust-analyzer"
@@ -120,6 +162,234 @@ auto_coordinator = true
 
 # Default agent to use (optional)
 # default_agent = "Coordinator"
+
+[ait42.auto_mode]
+# Subtask counts that select how the Coordinator runs a task:
+# one agent, agents in parallel, a competition, or an ensemble
+single_max_subtasks = 1
+parallel_max_subtasks = 3
+ensemble_min_subtasks = 8
+
+# Debate the task first when the analysis confidence is below this
+debate_below_confidence = 0.5
+
+# Model and per-instance timeout for competitions, ensembles and debates
+model = "sonnet"
+timeout_secs = 300
+
+# Estimated cost of one agent run; plans above max_cost_usd are scaled down
+cost_per_run_usd = 0.5
+# max_cost_usd = 3.0
+
+# Rules checked before the thresholds above, first match wins.
+# Unset conditions match any task.
+# [[ait42.auto_mode.rules]]
+# mode = "debate"
+# complexity = ["Exponential"]
+# max_confidence = 0.7
+
+[ait42.budget]
+# Estimated spend limits in USD for LLM estimations, chats and competitions.
+# Going over one needs an explicit override.
+# daily_limit_usd = 10.0
+# session_limit_usd = 3.0
+
+# Warn once this fraction of a limit is spent
+warn_at = 0.8
+
+# Token prices used for the estimates
+input_usd_per_mtok = 3.0
+output_usd_per_mtok = 15.0
+
+# Tokens an agent or Claude Code instance is expected to produce per run
+agent_run_tokens = 20000
+
+[ait42.llm_provider]
+# Backend for complexity estimation: "anthropic", "openai" or "ollama".
+# Keys are read from ANTHROPIC_API_KEY / OPENAI_API_KEY; ollama needs none.
+name = "anthropic"
+# model = "claude-sonnet-4-5-20250929"
+# base_url = "http://localhost:11434"
+
+[ait42.injection_scan]
+# Workspace content attached to agent tasks is scanned for prompt injection:
+# instructions addressed to the model, hidden Unicode and base64 blobs.
+# "off", "flag" (warn the agent) or "strip" (remove what was found)
+policy = "flag"
+
+# Shortest base64 run reported, in characters
+max_blob_chars = 1024
+
+[ait42.edit_guard]
+# Edits agents apply without review (e.g. through the MCP server) need your
+# approval when they write outside the workspace, touch a protected path or
+# carry a binary payload
+enabled = true
+protected_paths = [
+    ".github/workflows/**",
+    ".env",
+    ".env.*",
+    "**/*.pem",
+    "**/*.key",
+    "**/id_rsa*",
+    "**/secrets.*",
+    ".git/**",
+]
+
+# Shortest base64 run treated as a binary payload, in characters
+max_blob_chars = 4096
+
+# Alerts on agent output, matched line by line as it streams in. Actions:
+# "notify", "badge" (only mark the agent) or "cancel" (stop the agent)
+# [[ait42.alerts]]
+# name = "Rate limited"
+# pattern = "rate limit"
+# action = "cancel"
+#
+# [[ait42.alerts]]
+# name = "Tests failed"
+# pattern = "test result: FAILED|cargo test .* failed"
+# regex = true
+
+[checkpoints]
+# Periodically commit the working tree to refs/ait42/checkpoints
+enabled = false
+
+# Seconds between automatic checkpoints
+interval_secs = 300
+
+[sessions]
+# Days a deleted session can be restored before it is purged
+deleted_retention_days = 30
+
+# Purge finished sessions this many days after their last update
+# max_age_days = 90
+
+# Purge expired sessions at startup and once a day
+auto_purge = true
+
+# Encrypt sessions files with a key kept in the OS keychain
+encrypt_at_rest = false
+
+# Storage backend: "json" (one file per workspace) or "jsonl" (one session
+# per line, easy to inspect and diff on air-gapped machines)
+backend = "json"
+
+# Directory sessions are stored in (default: ~/.ait42/sessions)
+# path = "/var/lib/ait42/sessions"
+
+# Shared store that local sessions are replicated to by sync_sessions
+# [sessions.shared]
+# backend = "jsonl"
+# path = "/mnt/team/ait42-sessions"
+
+[updates]
+# Release channel: "stable" or "beta"
+channel = "stable"
+
+# Check for a new release at startup
+check_on_startup = true
+
+[todos]
+# Comment tags listed in the TODO panel
+tags = ["TODO", "FIXME", "HACK"]
+
+[clipboard]
+# Copied snippets kept in the clipboard history
+history_size = 50
+
+# Keep the history across restarts, in ~/.ait42/clipboard.json
+persist = false
+
+[focus]
+# Focus the terminal while a command run from the editor executes
+on_terminal_command = true
+
+# Focus the results panel once an agent run completes
+on_agent_complete = true
+
+[syntax]
+# Color nested brackets by their depth (TUI only)
+rainbow_delimiters = false
+
+# Tint the background of the function or block under the cursor (TUI only)
+scope_highlight = false
+
+# Overrides by file extension
+# [syntax.filetypes.md]
+# rainbow_delimiters = false
+
+[preload]
+# Read the files an opened file imports, and the files usually committed
+# with it, in the background so switching to them is instant (TUI only)
+enabled = true
+max_files = 8
+
+# Memory the preloaded files may take in total, in megabytes
+max_memory_mb = 32
+
+# Commits touching the opened file searched for co-edited files
+history_commits = 50
+
+[remote]
+# WebSocket server for CI bots and chatops to open files, run agents and
+# follow their output
+enabled = false
+
+# Address to listen on; keep it on localhost unless the network is trusted
+bind = "127.0.0.1:7421"
+
+# Token clients must present (default: AIT42_REMOTE_TOKEN, or a random one)
+# token = "change-me"
+
+[mcp]
+# MCP server at http://<bind>/mcp exposing open buffers, diagnostics, git
+# status and sessions, and tools to edit buffers and run agents
+enabled = false
+
+# Address to listen on; keep it on localhost unless the network is trusted
+bind = "127.0.0.1:7422"
+
+# Token clients must present (default: AIT42_MCP_TOKEN, or a random one)
+# token = "change-me"
+
+[competition]
+# Score the instances (tests, diff size, optional LLM rating) when they all
+# finish, and mark the best one as winner
+auto_select_winner = true
+
+# Command checking each worktree's tests (default: detected from the project)
+# test_command = "cargo test"
+
+# Time allowed for the test command, in seconds
+test_timeout_secs = 600
+
+# Also have an LLM rate each diff (needs ANTHROPIC_API_KEY)
+llm_evaluation = false
+
+# Have an LLM describe each instance's diff in a paragraph when they all
+# finish (needs ANTHROPIC_API_KEY)
+summarize_diffs = true
+
+[issues]
+# Issue trackers to pick tasks from and post session reports to. API tokens
+# are kept in the OS keychain (set them from the editor) or read from
+# GITHUB_TOKEN and JIRA_API_TOKEN.
+
+# GitHub repository (default: the origin remote, if on github.com)
+# github_repo = "owner/name"
+github_api_url = "https://api.github.com"
+
+# Jira site, project and the account email the API token belongs to
+# jira_url = "https://example.atlassian.net"
+# jira_project = "PROJ"
+# jira_email = "you@example.com"
+
+[features]
+# Experimental features, see the list in Settings
+# ensemble_mode = true
+# observer_mode = true
+# dap_debugging = false
 "##
     .to_string()
 }
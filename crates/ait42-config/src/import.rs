@@ -0,0 +1,22 @@
+//! Import Reports
+//!
+//! Shared result type of the importers for other editors' configuration.
+
+/// What an import changed and what it skipped
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// Applied entries, e.g. `editor.tabSize -> editor.tab_size = 2`
+    pub applied: Vec<String>,
+    /// Entries without an AIT42 equivalent, with the reason
+    pub unmapped: Vec<String>,
+}
+
+impl ImportReport {
+    pub(crate) fn apply(&mut self, from: &str, to: &str, value: impl std::fmt::Display) {
+        self.applied.push(format!("{} -> {} = {}", from, to, value));
+    }
+
+    pub(crate) fn skip(&mut self, entry: &str, reason: &str) {
+        self.unmapped.push(format!("{} ({})", entry, reason));
+    }
+}
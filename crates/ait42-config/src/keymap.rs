@@ -0,0 +1,374 @@
+//! Key Binding Notation
+//!
+//! Parses the bindings of `[keybindings]`. A chord is a key with optional
+//! `Ctrl+`, `Alt+` and `Shift+` modifiers (`Ctrl+s`, `Alt+Up`, `Ctrl++`);
+//! a sequence is a chord, or a prefix chord followed by one more, separated
+//! by a space (`Space w`). Modifier and key names are case-insensitive, while
+//! single characters are kept as written, so `G` and `g` are different keys.
+//!
+//! Bindings come from two tables. `[keybindings.keymap.<mode>]` maps
+//! sequences to command names per mode; the older `[keybindings.custom]`
+//! table prefixes the sequence with the mode instead (`"insert:j k"`) and
+//! defaults to normal mode.
+
+use crate::schema::KeyBindingConfig;
+use crate::{ConfigError, Result};
+use std::fmt;
+
+/// Key without modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// Printable character; `Space` is `Char(' ')`
+    Char(char),
+    /// Function key `F1` to `F12`
+    F(u8),
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
+impl Key {
+    /// Named keys, matched case-insensitively
+    const NAMED: [(&'static str, Key); 15] = [
+        ("space", Key::Char(' ')),
+        ("enter", Key::Enter),
+        ("esc", Key::Esc),
+        ("tab", Key::Tab),
+        ("backspace", Key::Backspace),
+        ("delete", Key::Delete),
+        ("up", Key::Up),
+        ("down", Key::Down),
+        ("left", Key::Left),
+        ("right", Key::Right),
+        ("home", Key::Home),
+        ("end", Key::End),
+        ("pageup", Key::PageUp),
+        ("pagedown", Key::PageDown),
+        ("escape", Key::Esc),
+    ];
+
+    fn parse(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(Key::Char(c));
+        }
+
+        let lower = name.to_lowercase();
+        if let Some((_, key)) = Self::NAMED.iter().find(|(named, _)| *named == lower) {
+            return Some(*key);
+        }
+        lower
+            .strip_prefix('f')
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|n| (1..=12).contains(n))
+            .map(Key::F)
+    }
+}
+
+/// Key with its modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl KeyChord {
+    /// Parse a chord like `Ctrl+Shift+p`
+    pub fn parse(chord: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            ConfigError::ValidationError(format!("Invalid key \"{}\": {}", chord, reason))
+        };
+
+        // A "+" after the last modifier binds the plus key itself
+        let (modifiers, key) = match chord.strip_suffix("++") {
+            _ if chord == "+" => ("", "+"),
+            Some(modifiers) => (modifiers, "+"),
+            None => match chord.rsplit_once('+') {
+                Some((modifiers, key)) if !key.is_empty() => (modifiers, key),
+                Some(_) => return Err(invalid("missing key after \"+\"")),
+                None => ("", chord),
+            },
+        };
+
+        let mut parsed = Self {
+            key: Key::parse(key).ok_or_else(|| invalid(&format!("unknown key \"{}\"", key)))?,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        };
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            let flag = match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => &mut parsed.ctrl,
+                "alt" | "meta" => &mut parsed.alt,
+                "shift" => &mut parsed.shift,
+                _ => return Err(invalid(&format!("unknown modifier \"{}\"", modifier))),
+            };
+            *flag = true;
+        }
+        Ok(parsed)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (set, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.alt, "Alt+"),
+            (self.shift, "Shift+"),
+        ] {
+            if set {
+                f.write_str(name)?;
+            }
+        }
+        match self.key {
+            Key::Char(' ') => f.write_str("Space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Parse a sequence of one or two chords separated by a space
+pub fn parse_sequence(sequence: &str) -> Result<Vec<KeyChord>> {
+    let chords = sequence
+        .split(' ')
+        .filter(|chord| !chord.is_empty())
+        .map(KeyChord::parse)
+        .collect::<Result<Vec<_>>>()?;
+    match chords.len() {
+        1 | 2 => Ok(chords),
+        0 => Err(ConfigError::ValidationError("Empty key binding".to_string())),
+        _ => Err(ConfigError::ValidationError(format!(
+            "Invalid key \"{}\": sequences are a key or a prefix key and one more",
+            sequence
+        ))),
+    }
+}
+
+/// Mode a binding applies in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum KeymapMode {
+    Normal,
+    Insert,
+    Visual,
+    Command,
+    /// File tree, when it has focus
+    Sidebar,
+}
+
+impl KeymapMode {
+    pub const ALL: [KeymapMode; 5] = [
+        KeymapMode::Normal,
+        KeymapMode::Insert,
+        KeymapMode::Visual,
+        KeymapMode::Command,
+        KeymapMode::Sidebar,
+    ];
+
+    /// Name used in the configuration
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeymapMode::Normal => "normal",
+            KeymapMode::Insert => "insert",
+            KeymapMode::Visual => "visual",
+            KeymapMode::Command => "command",
+            KeymapMode::Sidebar => "sidebar",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+/// One validated binding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapEntry {
+    pub mode: KeymapMode,
+    /// A key, or a prefix key followed by one more
+    pub keys: Vec<KeyChord>,
+    /// Command name from [`KeyBindingConfig::COMMANDS`]
+    pub command: String,
+}
+
+impl KeymapEntry {
+    fn parse(mode: KeymapMode, keys: &str, command: &str, table: &str) -> Result<Self> {
+        let context =
+            |e: ConfigError| ConfigError::ValidationError(format!("{} in {}", message(e), table));
+
+        let chords = parse_sequence(keys).map_err(context)?;
+        if mode == KeymapMode::Sidebar && chords.len() > 1 {
+            return Err(context(ConfigError::ValidationError(format!(
+                "Invalid key \"{}\": sidebar bindings are a single key",
+                keys
+            ))));
+        }
+        if !KeyBindingConfig::COMMANDS.contains(&command) {
+            return Err(ConfigError::ValidationError(format!(
+                "Unknown command \"{}\" bound to \"{}\" in {}",
+                command, keys, table
+            )));
+        }
+        Ok(Self {
+            mode,
+            keys: chords,
+            command: command.to_string(),
+        })
+    }
+}
+
+fn message(error: ConfigError) -> String {
+    match error {
+        ConfigError::ValidationError(message) => message,
+        other => other.to_string(),
+    }
+}
+
+impl KeyBindingConfig {
+    /// Parse and validate the custom bindings
+    ///
+    /// `[keybindings.custom]` entries come first and `keymap` entries after
+    /// them, so a `keymap` binding wins when both bind the same keys. Entries
+    /// of a table are sorted, which keeps the result stable across loads.
+    pub fn bindings(&self) -> Result<Vec<KeymapEntry>> {
+        let mut custom: Vec<_> = self.custom.iter().collect();
+        custom.sort();
+
+        let mut entries = Vec::new();
+        for (keys, command) in custom {
+            let (mode, keys) = match keys.split_once(':') {
+                Some((mode, rest)) if !rest.is_empty() => match KeymapMode::parse(mode) {
+                    Some(mode) => (mode, rest),
+                    None => (KeymapMode::Normal, keys.as_str()),
+                },
+                _ => (KeymapMode::Normal, keys.as_str()),
+            };
+            entries.push(KeymapEntry::parse(mode, keys, command, "[keybindings.custom]")?);
+        }
+
+        let mut modes: Vec<_> = self.keymap.iter().collect();
+        modes.sort_by(|a, b| a.0.cmp(b.0));
+        for (mode_name, bindings) in modes {
+            let table = format!("[keybindings.keymap.{}]", mode_name);
+            let mode = KeymapMode::parse(mode_name).ok_or_else(|| {
+                let names: Vec<_> = KeymapMode::ALL.iter().map(|mode| mode.name()).collect();
+                ConfigError::ValidationError(format!(
+                    "Invalid keymap mode in {} (must be one of: {})",
+                    table,
+                    names.join(", ")
+                ))
+            })?;
+
+            let mut bindings: Vec<_> = bindings.iter().collect();
+            bindings.sort();
+            for (keys, command) in bindings {
+                entries.push(KeymapEntry::parse(mode, keys, command, &table)?);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn chord(key: Key, ctrl: bool, alt: bool, shift: bool) -> KeyChord {
+        KeyChord {
+            key,
+            ctrl,
+            alt,
+            shift,
+        }
+    }
+
+    #[test]
+    fn test_parse_chord() {
+        assert_eq!(KeyChord::parse("g").unwrap(), chord(Key::Char('g'), false, false, false));
+        assert_eq!(KeyChord::parse("G").unwrap(), chord(Key::Char('G'), false, false, false));
+        assert_eq!(
+            KeyChord::parse("ctrl+Shift+p").unwrap(),
+            chord(Key::Char('p'), true, false, true)
+        );
+        assert_eq!(KeyChord::parse("Alt+Up").unwrap(), chord(Key::Up, false, true, false));
+        assert_eq!(KeyChord::parse("Ctrl++").unwrap(), chord(Key::Char('+'), true, false, false));
+        assert_eq!(KeyChord::parse("+").unwrap(), chord(Key::Char('+'), false, false, false));
+        assert_eq!(KeyChord::parse("f5").unwrap().key, Key::F(5));
+        assert_eq!(KeyChord::parse("Space").unwrap().key, Key::Char(' '));
+
+        assert!(KeyChord::parse("Hyper+a").is_err());
+        assert!(KeyChord::parse("Ctrl+Foo").is_err());
+        assert!(KeyChord::parse("F13").is_err());
+        assert!(KeyChord::parse("Ctrl+").is_err());
+    }
+
+    #[test]
+    fn test_chord_display_round_trips() {
+        for notation in ["Ctrl+Shift+p", "Alt+Up", "Space", "F5", "G", "Ctrl++"] {
+            let parsed = KeyChord::parse(notation).unwrap();
+            assert_eq!(parsed.to_string(), notation);
+        }
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        assert_eq!(parse_sequence("Space w").unwrap().len(), 2);
+        assert_eq!(parse_sequence("Ctrl+s").unwrap().len(), 1);
+        assert!(parse_sequence("").is_err());
+        assert!(parse_sequence("g g g").is_err());
+    }
+
+    #[test]
+    fn test_bindings_from_both_tables() {
+        let mut config = KeyBindingConfig::vim();
+        config
+            .custom
+            .insert("insert:j k".to_string(), "enter_normal_mode".to_string());
+        config
+            .custom
+            .insert(":".to_string(), "open_command_palette".to_string());
+        config.keymap.insert(
+            "visual".to_string(),
+            HashMap::from([("Ctrl+s".to_string(), "save".to_string())]),
+        );
+
+        let entries = config.bindings().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].mode, KeymapMode::Normal);
+        assert_eq!(entries[0].keys, vec![chord(Key::Char(':'), false, false, false)]);
+        assert_eq!(entries[1].mode, KeymapMode::Insert);
+        assert_eq!(entries[1].command, "enter_normal_mode");
+        assert_eq!(entries[2].mode, KeymapMode::Visual);
+        assert_eq!(entries[2].keys, vec![chord(Key::Char('s'), true, false, false)]);
+    }
+
+    #[test]
+    fn test_bindings_validation() {
+        let invalid = |mode: &str, keys: &str, command: &str| {
+            let mut config = KeyBindingConfig::vim();
+            config
+                .keymap
+                .insert(mode.to_string(), HashMap::from([(keys.to_string(), command.to_string())]));
+            config.bindings().unwrap_err().to_string()
+        };
+
+        assert!(invalid("replace", "x", "save").contains("Invalid keymap mode"));
+        assert!(invalid("normal", "x", "explode").contains("Unknown command \"explode\""));
+        assert!(invalid("normal", "Ctrl+Nope", "save").contains("[keybindings.keymap.normal]"));
+        assert!(invalid("sidebar", "g g", "save").contains("single key"));
+    }
+}
@@ -3,16 +3,23 @@
 //! Handles loading and managing editor configuration from files and defaults.
 
 pub mod defaults;
+pub mod import;
+pub mod keymap;
 pub mod loader;
 pub mod schema;
+pub mod vimrc;
+pub mod vscode;
 pub mod watch;
 
 // Re-exports
 pub use defaults::default_config;
+pub use import::ImportReport;
+pub use keymap::{Key, KeyChord, KeymapEntry, KeymapMode};
 pub use loader::ConfigLoader;
 pub use schema::{
-    AIT42Config, Config as EditorConfiguration, EditorConfig, KeyBindingConfig, LspServerConfig,
-    ThemeConfig,
+    AIT42Config, AlertRuleConfig, AutoModeConfig, BudgetConfig, CheckpointConfig, ClipboardConfig, CompetitionConfig, Config as EditorConfiguration, EditorConfig, FocusConfig, KeyBindingConfig,
+    EditGuardConfig, InjectionScanConfig, IssuesConfig, LlmProviderConfig, LspServerConfig, McpConfig, ModeRule, RemoteConfig, SessionsConfig,
+    PreloadConfig, SharedSessionsConfig, SyntaxConfig, SyntaxOverride, ThemeConfig, TodosConfig, UpdatesConfig,
 };
 pub use watch::ConfigWatcher;
 
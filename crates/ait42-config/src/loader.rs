@@ -2,7 +2,13 @@
 //!
 //! Handles loading and saving configuration files.
 
-use crate::{Config, ConfigError, Result};
+use crate::{
+    schema::{
+        AlertRuleConfig, InjectionScanConfig, LlmProviderConfig, ModeRule, SessionsConfig,
+        UpdatesConfig,
+    },
+    Config, ConfigError, Result,
+};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::{debug, info, warn};
@@ -89,6 +95,14 @@ impl ConfigLoader {
             )));
         }
 
+        // Validate font size
+        if !(6..=72).contains(&config.editor.font_size) {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid font size: {} (must be 6-72)",
+                config.editor.font_size
+            )));
+        }
+
         // Validate cursor style
         let valid_cursor_styles = ["block", "line", "underline"];
         if !valid_cursor_styles.contains(&config.editor.cursor_style.as_str()) {
@@ -109,6 +123,232 @@ impl ConfigLoader {
             )));
         }
 
+        // Validate custom key bindings
+        config.keybindings.bindings()?;
+
+        // Validate auto mode thresholds
+        let auto_mode = &config.ait42.auto_mode;
+        if auto_mode.single_max_subtasks > auto_mode.parallel_max_subtasks
+            || auto_mode.parallel_max_subtasks >= auto_mode.ensemble_min_subtasks
+        {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid auto mode thresholds: single_max_subtasks ({}) <= parallel_max_subtasks ({}) < ensemble_min_subtasks ({}) must hold",
+                auto_mode.single_max_subtasks,
+                auto_mode.parallel_max_subtasks,
+                auto_mode.ensemble_min_subtasks
+            )));
+        }
+        if !(0.0..=1.0).contains(&auto_mode.debate_below_confidence) {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid debate_below_confidence: {} (must be 0.0-1.0)",
+                auto_mode.debate_below_confidence
+            )));
+        }
+        for (index, rule) in auto_mode.rules.iter().enumerate() {
+            if !ModeRule::MODES.contains(&rule.mode.as_str()) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid mode in auto mode rule {}: {} (must be one of: {})",
+                    index + 1,
+                    rule.mode,
+                    ModeRule::MODES.join(", ")
+                )));
+            }
+            let subtasks_reversed = matches!(
+                (rule.min_subtasks, rule.max_subtasks),
+                (Some(min), Some(max)) if min > max
+            );
+            let confidence_reversed = matches!(
+                (rule.min_confidence, rule.max_confidence),
+                (Some(min), Some(max)) if min > max
+            );
+            if subtasks_reversed || confidence_reversed {
+                return Err(ConfigError::ValidationError(format!(
+                    "Auto mode rule {} can never match: minimum above maximum",
+                    index + 1
+                )));
+            }
+        }
+
+        // Validate budget limits
+        let budget = &config.ait42.budget;
+        for (name, limit) in [
+            ("daily_limit_usd", budget.daily_limit_usd),
+            ("session_limit_usd", budget.session_limit_usd),
+        ] {
+            if let Some(limit) = limit.filter(|limit| *limit <= 0.0) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid {}: {} (must be positive)",
+                    name, limit
+                )));
+            }
+        }
+        if !(0.0..=1.0).contains(&budget.warn_at) {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid budget warn_at: {} (must be 0.0-1.0)",
+                budget.warn_at
+            )));
+        }
+
+        // Validate LLM provider
+        let provider = &config.ait42.llm_provider;
+        if !LlmProviderConfig::PROVIDERS.contains(&provider.name.as_str()) {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid LLM provider: {} (must be one of: {})",
+                provider.name,
+                LlmProviderConfig::PROVIDERS.join(", ")
+            )));
+        }
+
+        // Validate injection scan policy
+        let injection_scan = &config.ait42.injection_scan;
+        if !InjectionScanConfig::POLICIES.contains(&injection_scan.policy.as_str()) {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid injection scan policy: {} (must be one of: {})",
+                injection_scan.policy,
+                InjectionScanConfig::POLICIES.join(", ")
+            )));
+        }
+        if injection_scan.max_blob_chars == 0 {
+            return Err(ConfigError::ValidationError(
+                "Invalid injection scan max_blob_chars: 0 (must be positive)".to_string(),
+            ));
+        }
+
+        // Validate edit guard
+        let edit_guard = &config.ait42.edit_guard;
+        let protected_paths = &edit_guard.protected_paths;
+        if protected_paths.iter().any(|p| p.trim().is_empty()) {
+            return Err(ConfigError::ValidationError(
+                "Invalid edit guard protected path: empty pattern".to_string(),
+            ));
+        }
+        if edit_guard.max_blob_chars == 0 {
+            return Err(ConfigError::ValidationError(
+                "Invalid edit guard max_blob_chars: 0 (must be positive)".to_string(),
+            ));
+        }
+
+        // Validate alert rules; regexes are checked when the rules are used
+        for rule in &config.ait42.alerts {
+            if rule.pattern.is_empty() {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid alert {}: empty pattern",
+                    rule.name
+                )));
+            }
+            if !AlertRuleConfig::ACTIONS.contains(&rule.action.as_str()) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid alert action: {} (must be one of: {})",
+                    rule.action,
+                    AlertRuleConfig::ACTIONS.join(", ")
+                )));
+            }
+        }
+
+        // Validate update channel
+        if !UpdatesConfig::CHANNELS.contains(&config.updates.channel.as_str()) {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid update channel: {} (must be one of: {})",
+                config.updates.channel,
+                UpdatesConfig::CHANNELS.join(", ")
+            )));
+        }
+
+        // Validate session backends
+        let sessions = &config.sessions;
+        let shared_backend = sessions.shared.as_ref().map(|shared| &shared.backend);
+        for backend in std::iter::once(&sessions.backend).chain(shared_backend) {
+            if !SessionsConfig::BACKENDS.contains(&backend.as_str()) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid session backend: {} (must be one of: {})",
+                    backend,
+                    SessionsConfig::BACKENDS.join(", ")
+                )));
+            }
+        }
+        if let Some(shared) = &sessions.shared {
+            if shared.path.as_os_str().is_empty() || sessions.path.as_ref() == Some(&shared.path) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid shared session path: {:?} (must be set and differ from the local path)",
+                    shared.path
+                )));
+            }
+        }
+
+        // Validate TODO tags
+        for tag in &config.todos.tags {
+            if tag.is_empty() || !tag.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid TODO tag: {:?} (must be letters, digits or _)",
+                    tag
+                )));
+            }
+        }
+
+        // Validate clipboard history size
+        if config.clipboard.history_size == 0 || config.clipboard.history_size > 1000 {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid clipboard history size: {} (must be 1-1000)",
+                config.clipboard.history_size
+            )));
+        }
+
+        // Validate remote-control server
+        if config.remote.bind.parse::<std::net::SocketAddr>().is_err() {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid remote bind address: {} (must be ip:port)",
+                config.remote.bind
+            )));
+        }
+        if matches!(&config.remote.token, Some(token) if token.trim().is_empty()) {
+            return Err(ConfigError::ValidationError(
+                "Invalid remote token: must not be empty".to_string(),
+            ));
+        }
+
+        // Validate MCP server
+        if config.mcp.bind.parse::<std::net::SocketAddr>().is_err() {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid MCP bind address: {} (must be ip:port)",
+                config.mcp.bind
+            )));
+        }
+        if matches!(&config.mcp.token, Some(token) if token.trim().is_empty()) {
+            return Err(ConfigError::ValidationError(
+                "Invalid MCP token: must not be empty".to_string(),
+            ));
+        }
+
+        // Validate competition scoring
+        if config.competition.test_timeout_secs == 0 {
+            return Err(ConfigError::ValidationError(
+                "Invalid competition test timeout: must be greater than 0".to_string(),
+            ));
+        }
+        if matches!(&config.competition.test_command, Some(command) if command.trim().is_empty()) {
+            return Err(ConfigError::ValidationError(
+                "Invalid competition test command: must not be empty".to_string(),
+            ));
+        }
+
+        // Validate issue trackers
+        if !config.issues.github_api_url.starts_with("http") {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid GitHub API URL: {} (must be http(s)://...)",
+                config.issues.github_api_url
+            )));
+        }
+        if matches!(&config.issues.github_repo, Some(repo) if repo.split('/').count() != 2) {
+            return Err(ConfigError::ValidationError(
+                "Invalid GitHub repository: must be owner/name".to_string(),
+            ));
+        }
+        if matches!(&config.issues.jira_url, Some(url) if !url.starts_with("http")) {
+            return Err(ConfigError::ValidationError(
+                "Invalid Jira URL: must be http(s)://...".to_string(),
+            ));
+        }
+
         // Validate agents path exists (warning only)
         if !config.ait42.agents_path.exists() {
             warn!("Agents path does not exist: {}", config.ait42.agents_path.display());
@@ -195,6 +435,7 @@ impl Default for ConfigLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::SharedSessionsConfig;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -243,6 +484,11 @@ mod tests {
         config.editor.tab_size = 20;
         assert!(loader.validate(&config).is_err());
 
+        // Invalid font size
+        let mut config = Config::default();
+        config.editor.font_size = 2;
+        assert!(loader.validate(&config).is_err());
+
         // Invalid cursor style
         let mut config = Config::default();
         config.editor.cursor_style = "invalid".to_string();
@@ -252,6 +498,139 @@ mod tests {
         let mut config = Config::default();
         config.keybindings.mode = "invalid".to_string();
         assert!(loader.validate(&config).is_err());
+
+        // Custom binding to an unknown command
+        let mut config = Config::default();
+        config
+            .keybindings
+            .custom
+            .insert("Ctrl+s".to_string(), "explode".to_string());
+        assert!(loader.validate(&config).is_err());
+
+        // Overlapping auto mode thresholds
+        let mut config = Config::default();
+        config.ait42.auto_mode.parallel_max_subtasks = 8;
+        assert!(loader.validate(&config).is_err());
+
+        let mut config = Config::default();
+        config.ait42.auto_mode.debate_below_confidence = 1.5;
+        assert!(loader.validate(&config).is_err());
+
+        // Auto mode rule with an unknown mode or empty range
+        let rule = ModeRule {
+            mode: "debate".to_string(),
+            complexity: Vec::new(),
+            min_subtasks: Some(5),
+            max_subtasks: None,
+            min_confidence: None,
+            max_confidence: None,
+            instances: None,
+        };
+        let mut config = Config::default();
+        config.ait42.auto_mode.rules.push(rule.clone());
+        assert!(loader.validate(&config).is_ok());
+
+        config.ait42.auto_mode.rules[0].max_subtasks = Some(2);
+        assert!(loader.validate(&config).is_err());
+
+        config.ait42.auto_mode.rules[0] = ModeRule {
+            mode: "swarm".to_string(),
+            ..rule
+        };
+        assert!(loader.validate(&config).is_err());
+
+        // Non-positive budget limit
+        let mut config = Config::default();
+        config.ait42.budget.daily_limit_usd = Some(0.0);
+        assert!(loader.validate(&config).is_err());
+
+        let mut config = Config::default();
+        config.ait42.budget.warn_at = 2.0;
+        assert!(loader.validate(&config).is_err());
+
+        // Unknown LLM provider
+        let mut config = Config::default();
+        config.ait42.llm_provider.name = "gemini".to_string();
+        assert!(loader.validate(&config).is_err());
+
+        // Unknown injection scan policy
+        let mut config = Config::default();
+        config.ait42.injection_scan.policy = "block".to_string();
+        assert!(loader.validate(&config).is_err());
+
+        // Empty protected path
+        let mut config = Config::default();
+        config.ait42.edit_guard.protected_paths = vec![" ".to_string()];
+        assert!(loader.validate(&config).is_err());
+
+        // Alert without a pattern or with an unknown action
+        let rule = AlertRuleConfig {
+            name: "Rate limited".to_string(),
+            pattern: "rate limit".to_string(),
+            regex: false,
+            case_sensitive: false,
+            action: "cancel".to_string(),
+        };
+        let mut config = Config::default();
+        config.ait42.alerts.push(rule.clone());
+        assert!(loader.validate(&config).is_ok());
+
+        config.ait42.alerts[0].pattern.clear();
+        assert!(loader.validate(&config).is_err());
+
+        config.ait42.alerts[0] = AlertRuleConfig {
+            action: "page".to_string(),
+            ..rule
+        };
+        assert!(loader.validate(&config).is_err());
+
+        // Unknown update channel
+        let mut config = Config::default();
+        config.updates.channel = "nightly".to_string();
+        assert!(loader.validate(&config).is_err());
+
+        // Unknown session backend, shared store without a path
+        let mut config = Config::default();
+        config.sessions.backend = "sqlite".to_string();
+        assert!(loader.validate(&config).is_err());
+
+        let mut config = Config::default();
+        config.sessions.shared = Some(SharedSessionsConfig {
+            backend: "jsonl".to_string(),
+            path: PathBuf::new(),
+        });
+        assert!(loader.validate(&config).is_err());
+
+        // TODO tag that is not a word
+        let mut config = Config::default();
+        config.todos.tags.push("TO DO".to_string());
+        assert!(loader.validate(&config).is_err());
+
+        // Empty clipboard history
+        let mut config = Config::default();
+        config.clipboard.history_size = 0;
+        assert!(loader.validate(&config).is_err());
+
+        // Remote server on a host name, or with an empty token
+        let mut config = Config::default();
+        config.remote.bind = "localhost:7421".to_string();
+        assert!(loader.validate(&config).is_err());
+
+        let mut config = Config::default();
+        config.remote.token = Some(" ".to_string());
+        assert!(loader.validate(&config).is_err());
+
+        let mut config = Config::default();
+        config.mcp.bind = "7422".to_string();
+        assert!(loader.validate(&config).is_err());
+
+        let mut config = Config::default();
+        config.competition.test_timeout_secs = 0;
+        assert!(loader.validate(&config).is_err());
+
+        let mut config = Config::default();
+        config.issues.github_repo = Some("owner".to_string());
+        assert!(loader.validate(&config).is_err());
     }
 
     #[tokio::test]
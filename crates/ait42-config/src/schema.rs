@@ -2,6 +2,7 @@
 //!
 //! Defines the structure of the editor configuration.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -23,6 +24,46 @@ pub struct Config {
 
     #[serde(default)]
     pub ait42: AIT42Config,
+
+    #[serde(default)]
+    pub checkpoints: CheckpointConfig,
+
+    #[serde(default)]
+    pub sessions: SessionsConfig,
+
+    #[serde(default)]
+    pub updates: UpdatesConfig,
+
+    #[serde(default)]
+    pub todos: TodosConfig,
+
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+
+    #[serde(default)]
+    pub focus: FocusConfig,
+
+    #[serde(default)]
+    pub syntax: SyntaxConfig,
+
+    #[serde(default)]
+    pub preload: PreloadConfig,
+
+    #[serde(default)]
+    pub remote: RemoteConfig,
+
+    #[serde(default)]
+    pub mcp: McpConfig,
+
+    #[serde(default)]
+    pub competition: CompetitionConfig,
+
+    #[serde(default)]
+    pub issues: IssuesConfig,
+
+    /// Experimental features switched on or off, by flag name
+    #[serde(default)]
+    pub features: HashMap<String, bool>,
 }
 
 impl Default for Config {
@@ -33,6 +74,19 @@ impl Default for Config {
             keybindings: KeyBindingConfig::default(),
             lsp: default_lsp_config(),
             ait42: AIT42Config::default(),
+            checkpoints: CheckpointConfig::default(),
+            sessions: SessionsConfig::default(),
+            updates: UpdatesConfig::default(),
+            todos: TodosConfig::default(),
+            clipboard: ClipboardConfig::default(),
+            focus: FocusConfig::default(),
+            syntax: SyntaxConfig::default(),
+            preload: PreloadConfig::default(),
+            remote: RemoteConfig::default(),
+            mcp: McpConfig::default(),
+            competition: CompetitionConfig::default(),
+            issues: IssuesConfig::default(),
+            features: HashMap::new(),
         }
     }
 }
@@ -44,7 +98,8 @@ pub struct EditorConfig {
     #[serde(default = "default_tab_size")]
     pub tab_size: usize,
 
-    /// Auto-save delay in milliseconds (0 = disabled)
+    /// Auto-save delay in milliseconds (0 = disabled): unsaved buffers are
+    /// copied for crash recovery once left unchanged this long
     #[serde(default = "default_auto_save_delay")]
     pub auto_save_delay: u64,
 
@@ -79,6 +134,15 @@ pub struct EditorConfig {
     /// Scroll offset (lines to keep visible above/below cursor)
     #[serde(default = "default_scroll_offset")]
     pub scroll_offset: usize,
+
+    /// Font size in points (GUI only)
+    #[serde(default = "default_font_size")]
+    pub font_size: u16,
+
+    /// Save open tabs and panels on exit and offer to restore them when
+    /// launched without arguments (TUI only)
+    #[serde(default = "default_true")]
+    pub restore_session: bool,
 }
 
 impl Default for EditorConfig {
@@ -94,6 +158,8 @@ impl Default for EditorConfig {
             show_whitespace: false,
             cursor_style: "block".to_string(),
             scroll_offset: 5,
+            font_size: default_font_size(),
+            restore_session: true,
         }
     }
 }
@@ -160,8 +226,24 @@ pub struct KeyBindingConfig {
     pub mode: String,
 
     /// Custom key bindings
+    ///
+    /// Keys of a sequence are separated by spaces, e.g. `"Space f"`. Insert
+    /// mode bindings are prefixed with `insert:`; others apply in normal mode.
     #[serde(default)]
     pub custom: HashMap<String, String>,
+
+    /// Key bindings by mode (`normal`, `insert`, `visual`, `command`,
+    /// `sidebar`), each mapping key sequences to command names
+    ///
+    /// Bindings replace the preset's binding of the same keys; bind a key to
+    /// `noop` to disable it. See [`crate::keymap`] for the key notation.
+    #[serde(default)]
+    pub keymap: HashMap<String, HashMap<String, String>>,
+
+    /// Global shortcut opening the quick task window, even when the editor
+    /// is in the background, e.g. `"CmdOrCtrl+Shift+Space"`; empty disables it
+    #[serde(default = "default_quick_task_shortcut")]
+    pub quick_task: String,
 }
 
 impl Default for KeyBindingConfig {
@@ -171,10 +253,103 @@ impl Default for KeyBindingConfig {
 }
 
 impl KeyBindingConfig {
+    /// Commands that can be bound to keys
+    pub const COMMANDS: &'static [&'static str] = &[
+        "enter_insert_mode",
+        "enter_visual_mode",
+        "enter_command_mode",
+        "enter_normal_mode",
+        "move_left",
+        "move_right",
+        "move_up",
+        "move_down",
+        "move_word_forward",
+        "move_word_backward",
+        "move_line_start",
+        "move_line_end",
+        "move_page_up",
+        "move_page_down",
+        "move_file_start",
+        "move_file_end",
+        "insert_newline",
+        "delete_char",
+        "delete_line",
+        "delete_word",
+        "backspace",
+        "undo",
+        "redo",
+        "move_item_up",
+        "move_item_down",
+        "swap_argument_backward",
+        "swap_argument_forward",
+        "raise_node",
+        "join_lines",
+        "split_join_list",
+        "select_enclosing_node",
+        "fold",
+        "unfold",
+        "toggle_fold",
+        "fold_all",
+        "unfold_all",
+        "record_macro",
+        "replay_macro",
+        "set_mark",
+        "jump_to_mark",
+        "select_register",
+        "paste",
+        "terminal_copy_mode",
+        "search",
+        "search_next",
+        "search_previous",
+        "replace",
+        "search_workspace",
+        "open_command_palette",
+        "submit_command_line",
+        "command_history_previous",
+        "command_history_next",
+        "command_history_search",
+        "repeat_palette_command",
+        "show_keybindings",
+        "show_stashes",
+        "show_git_log",
+        "show_agent_launcher",
+        "convert_line_endings",
+        "convert_encoding",
+        "save",
+        "quit",
+        "force_quit",
+        "new_tab",
+        "close_tab",
+        "next_tab",
+        "prev_tab",
+        "toggle_sidebar",
+        "toggle_terminal",
+        "focus_sidebar",
+        "focus_editor",
+        "focus_terminal",
+        "focus_next_panel",
+        "run_in_terminal",
+        "toggle_results",
+        "focus_results",
+        "return_focus",
+        "grow_sidebar",
+        "shrink_sidebar",
+        "grow_terminal",
+        "shrink_terminal",
+        "reset_panel_sizes",
+        "sidebar_move_up",
+        "sidebar_move_down",
+        "sidebar_select",
+        "sidebar_toggle_expand",
+        "noop",
+    ];
+
     pub fn vim() -> Self {
         Self {
             mode: "vim".to_string(),
             custom: HashMap::new(),
+            keymap: HashMap::new(),
+            quick_task: default_quick_task_shortcut(),
         }
     }
 
@@ -182,6 +357,8 @@ impl KeyBindingConfig {
         Self {
             mode: "emacs".to_string(),
             custom: HashMap::new(),
+            keymap: HashMap::new(),
+            quick_task: default_quick_task_shortcut(),
         }
     }
 
@@ -189,6 +366,8 @@ impl KeyBindingConfig {
         Self {
             mode: "default".to_string(),
             custom: HashMap::new(),
+            keymap: HashMap::new(),
+            quick_task: default_quick_task_shortcut(),
         }
     }
 }
@@ -223,6 +402,30 @@ pub struct AIT42Config {
     #[serde(default = "default_true")]
     pub auto_coordinator: bool,
 
+    /// Thresholds the Coordinator uses to pick an execution mode
+    #[serde(default)]
+    pub auto_mode: AutoModeConfig,
+
+    /// Spend limits for LLM estimations, chats and competitions
+    #[serde(default)]
+    pub budget: BudgetConfig,
+
+    /// LLM backend used for complexity estimation
+    #[serde(default)]
+    pub llm_provider: LlmProviderConfig,
+
+    /// Prompt injection scanning of workspace content sent to agents
+    #[serde(default)]
+    pub injection_scan: InjectionScanConfig,
+
+    /// Checks on edits applied by agents without review
+    #[serde(default)]
+    pub edit_guard: EditGuardConfig,
+
+    /// Rules raising alerts on patterns in agent output
+    #[serde(default)]
+    pub alerts: Vec<AlertRuleConfig>,
+
     /// Default agent to use
     #[serde(default)]
     pub default_agent: Option<String>,
@@ -238,12 +441,691 @@ impl Default for AIT42Config {
             agents_path: PathBuf::from("../.claude/agents"),
             tmux_enabled: true,
             auto_coordinator: true,
+            auto_mode: AutoModeConfig::default(),
+            budget: BudgetConfig::default(),
+            llm_provider: LlmProviderConfig::default(),
+            injection_scan: InjectionScanConfig::default(),
+            edit_guard: EditGuardConfig::default(),
+            alerts: Vec::new(),
             default_agent: None,
             agent_settings: HashMap::new(),
         }
     }
 }
 
+/// Execution mode policy of the Coordinator
+///
+/// The task's estimated subtask count selects the mode: up to
+/// `single_max_subtasks` one agent runs it, up to `parallel_max_subtasks`
+/// the recommended agents run side by side, from `ensemble_min_subtasks`
+/// instances split the work, and in between they compete. An analysis less
+/// confident than `debate_below_confidence` goes to a debate instead.
+///
+/// `rules` are checked first, in order; the first rule matching the analysis
+/// decides the mode. With `max_cost_usd` set, plans whose estimated cost is
+/// higher are scaled down to fit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoModeConfig {
+    #[serde(default = "default_single_max_subtasks")]
+    pub single_max_subtasks: usize,
+
+    #[serde(default = "default_parallel_max_subtasks")]
+    pub parallel_max_subtasks: usize,
+
+    #[serde(default = "default_ensemble_min_subtasks")]
+    pub ensemble_min_subtasks: usize,
+
+    /// Confidence (0.0-1.0) below which the task is debated first
+    #[serde(default = "default_debate_below_confidence")]
+    pub debate_below_confidence: f64,
+
+    /// Model for competition, ensemble and debate instances
+    #[serde(default = "default_auto_mode_model")]
+    pub model: String,
+
+    /// Timeout per instance or debate round in seconds
+    #[serde(default = "default_auto_mode_timeout")]
+    pub timeout_secs: u64,
+
+    /// Estimated cost of one agent or instance run in USD
+    #[serde(default = "default_cost_per_run")]
+    pub cost_per_run_usd: f64,
+
+    /// Highest estimated cost a plan may have, unlimited if unset
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+
+    /// Rules overriding the thresholds, first match wins
+    #[serde(default)]
+    pub rules: Vec<ModeRule>,
+}
+
+/// Execution mode for tasks matching all of the rule's conditions
+///
+/// Conditions left unset match any task.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModeRule {
+    /// single, parallel, competition, ensemble or debate
+    pub mode: String,
+
+    /// Complexity classes the rule applies to, e.g. "Quadratic"
+    #[serde(default)]
+    pub complexity: Vec<String>,
+
+    #[serde(default)]
+    pub min_subtasks: Option<usize>,
+
+    #[serde(default)]
+    pub max_subtasks: Option<usize>,
+
+    #[serde(default)]
+    pub min_confidence: Option<f64>,
+
+    #[serde(default)]
+    pub max_confidence: Option<f64>,
+
+    /// Instances or agents to run instead of the recommended count
+    #[serde(default)]
+    pub instances: Option<usize>,
+}
+
+impl ModeRule {
+    /// Modes a rule can select
+    pub const MODES: [&'static str; 5] = ["single", "parallel", "competition", "ensemble", "debate"];
+}
+
+impl Default for AutoModeConfig {
+    fn default() -> Self {
+        Self {
+            single_max_subtasks: default_single_max_subtasks(),
+            parallel_max_subtasks: default_parallel_max_subtasks(),
+            ensemble_min_subtasks: default_ensemble_min_subtasks(),
+            debate_below_confidence: default_debate_below_confidence(),
+            model: default_auto_mode_model(),
+            timeout_secs: default_auto_mode_timeout(),
+            cost_per_run_usd: default_cost_per_run(),
+            max_cost_usd: None,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Spend limits for AI operations
+///
+/// Costs are estimated from the tokens an operation sends and is expected to
+/// produce, priced per million tokens. Operations that would go over a limit
+/// are refused until the user approves an override.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetConfig {
+    /// Highest estimated spend per calendar day in USD, unlimited if unset
+    #[serde(default)]
+    pub daily_limit_usd: Option<f64>,
+
+    /// Highest estimated spend per session in USD, unlimited if unset
+    #[serde(default)]
+    pub session_limit_usd: Option<f64>,
+
+    /// Fraction of a limit (0.0-1.0) at which a warning is raised
+    #[serde(default = "default_budget_warn_at")]
+    pub warn_at: f64,
+
+    #[serde(default = "default_input_usd_per_mtok")]
+    pub input_usd_per_mtok: f64,
+
+    #[serde(default = "default_output_usd_per_mtok")]
+    pub output_usd_per_mtok: f64,
+
+    /// Tokens an agent or Claude Code instance is expected to produce per run
+    #[serde(default = "default_agent_run_tokens")]
+    pub agent_run_tokens: u64,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            daily_limit_usd: None,
+            session_limit_usd: None,
+            warn_at: default_budget_warn_at(),
+            input_usd_per_mtok: default_input_usd_per_mtok(),
+            output_usd_per_mtok: default_output_usd_per_mtok(),
+            agent_run_tokens: default_agent_run_tokens(),
+        }
+    }
+}
+
+/// LLM backend for complexity estimation
+///
+/// Credentials come from the environment: `ANTHROPIC_API_KEY` for
+/// "anthropic", `OPENAI_API_KEY` for "openai"; "ollama" needs none.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LlmProviderConfig {
+    /// Provider: "anthropic", "openai" or "ollama"
+    #[serde(default = "default_llm_provider")]
+    pub name: String,
+
+    /// Model to use, the provider's default if unset
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// API endpoint, e.g. an OpenAI-compatible server or a remote Ollama
+    /// host; the provider's default if unset
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+impl LlmProviderConfig {
+    /// Supported providers
+    pub const PROVIDERS: [&'static str; 3] = ["anthropic", "openai", "ollama"];
+}
+
+impl Default for LlmProviderConfig {
+    fn default() -> Self {
+        Self {
+            name: default_llm_provider(),
+            model: None,
+            base_url: None,
+        }
+    }
+}
+
+/// Prompt injection scanning of agent context
+///
+/// File content, notes and diffs are scanned before they are attached to an
+/// agent task, for instructions addressed to the model, hidden Unicode and
+/// oversized base64 blobs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct InjectionScanConfig {
+    /// "off", "flag" (send with a warning to the agent) or "strip" (remove
+    /// the suspicious parts)
+    #[serde(default = "default_injection_policy")]
+    pub policy: String,
+
+    /// Shortest base64 run reported, in characters
+    #[serde(default = "default_max_blob_chars")]
+    pub max_blob_chars: usize,
+}
+
+impl InjectionScanConfig {
+    /// Supported policies
+    pub const POLICIES: [&'static str; 3] = ["off", "flag", "strip"];
+}
+
+impl Default for InjectionScanConfig {
+    fn default() -> Self {
+        Self {
+            policy: default_injection_policy(),
+            max_blob_chars: default_max_blob_chars(),
+        }
+    }
+}
+
+/// Checks on edits applied by agents without review
+///
+/// Edits that write outside the workspace, touch a protected path or carry a
+/// binary payload are only applied after the user approves them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EditGuardConfig {
+    /// Ask before applying risky edits; when off they are applied as is
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Globs, relative to the workspace, of paths agents may not edit
+    /// unapproved
+    #[serde(default = "default_protected_paths")]
+    pub protected_paths: Vec<String>,
+
+    /// Shortest base64 run treated as a binary payload, in characters
+    #[serde(default = "default_edit_blob_chars")]
+    pub max_blob_chars: usize,
+}
+
+impl Default for EditGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            protected_paths: default_protected_paths(),
+            max_blob_chars: default_edit_blob_chars(),
+        }
+    }
+}
+
+/// Alert on a pattern in agent output
+///
+/// Output is matched line by line as it streams in, and a rule fires at most
+/// once per execution or competition instance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AlertRuleConfig {
+    /// Name shown in the alert
+    pub name: String,
+
+    /// Text to look for, or a regular expression with `regex`
+    pub pattern: String,
+
+    #[serde(default)]
+    pub regex: bool,
+
+    /// Match case; otherwise case is ignored
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// "notify" (raise a notification), "badge" (only mark the agent with a
+    /// warning) or "cancel" (stop the agent)
+    #[serde(default = "default_alert_action")]
+    pub action: String,
+}
+
+impl AlertRuleConfig {
+    /// Supported actions
+    pub const ACTIONS: [&'static str; 3] = ["notify", "badge", "cancel"];
+}
+
+/// Working tree checkpoint settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CheckpointConfig {
+    /// Periodically commit the working tree to the checkpoint ref
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Seconds between automatic checkpoints
+    #[serde(default = "default_checkpoint_interval")]
+    pub interval_secs: u64,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 300,
+        }
+    }
+}
+
+/// Retention of competition, ensemble and debate sessions
+///
+/// Deleted sessions are kept for a while so they can be restored, then
+/// purged for good.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionsConfig {
+    /// Days a deleted session can be restored before it is purged
+    #[serde(default = "default_deleted_retention_days")]
+    pub deleted_retention_days: u32,
+
+    /// Days after their last update that finished sessions are purged, kept
+    /// forever if unset
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+
+    /// Purge expired sessions at startup and once a day
+    #[serde(default = "default_true")]
+    pub auto_purge: bool,
+
+    /// Encrypt sessions files with a key kept in the OS keychain
+    #[serde(default)]
+    pub encrypt_at_rest: bool,
+
+    /// Storage backend: "json" (one file per workspace) or "jsonl" (one
+    /// session per line)
+    #[serde(default = "default_sessions_backend")]
+    pub backend: String,
+
+    /// Directory sessions are stored in, `~/.ait42/sessions` if unset
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+
+    /// Shared store that `sync_sessions` replicates local sessions to
+    #[serde(default)]
+    pub shared: Option<SharedSessionsConfig>,
+}
+
+impl SessionsConfig {
+    /// Available storage backends
+    pub const BACKENDS: [&'static str; 2] = ["json", "jsonl"];
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        Self {
+            deleted_retention_days: default_deleted_retention_days(),
+            max_age_days: None,
+            auto_purge: true,
+            encrypt_at_rest: false,
+            backend: default_sessions_backend(),
+            path: None,
+            shared: None,
+        }
+    }
+}
+
+/// Session store shared by a team, such as a directory on a network drive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SharedSessionsConfig {
+    /// Storage backend, one of [`SessionsConfig::BACKENDS`]
+    #[serde(default = "default_sessions_backend")]
+    pub backend: String,
+
+    /// Directory of the shared store
+    pub path: PathBuf,
+}
+
+/// Update checks for the editor itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UpdatesConfig {
+    /// Release channel: "stable" or "beta" (beta also offers stable releases)
+    #[serde(default = "default_update_channel")]
+    pub channel: String,
+
+    /// Check for a new release at startup
+    #[serde(default = "default_true")]
+    pub check_on_startup: bool,
+
+    /// URL the channel manifests (`<channel>.json`) are served from
+    #[serde(default = "default_update_endpoint")]
+    pub endpoint: String,
+}
+
+impl UpdatesConfig {
+    /// Channels releases are published to
+    pub const CHANNELS: [&'static str; 2] = ["stable", "beta"];
+}
+
+impl Default for UpdatesConfig {
+    fn default() -> Self {
+        Self {
+            channel: default_update_channel(),
+            check_on_startup: true,
+            endpoint: default_update_endpoint(),
+        }
+    }
+}
+
+/// Tagged comments listed in the TODO panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodosConfig {
+    /// Tags to look for in comments, matched case-sensitively as whole words
+    #[serde(default = "default_todo_tags")]
+    pub tags: Vec<String>,
+}
+
+impl Default for TodosConfig {
+    fn default() -> Self {
+        Self {
+            tags: default_todo_tags(),
+        }
+    }
+}
+
+/// History of copied text that can be pasted again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardConfig {
+    /// Entries kept, oldest dropped first
+    #[serde(default = "default_clipboard_history_size")]
+    pub history_size: usize,
+
+    /// Keep the history across restarts
+    #[serde(default)]
+    pub persist: bool,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            history_size: default_clipboard_history_size(),
+            persist: false,
+        }
+    }
+}
+
+/// Panels focused automatically when something happens outside of them
+///
+/// Turn these off to keep focus where it is; the panels still open.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusConfig {
+    /// Focus the terminal while a command run from the editor executes
+    #[serde(default = "default_true")]
+    pub on_terminal_command: bool,
+
+    /// Focus the results panel once an agent run completes
+    #[serde(default = "default_true")]
+    pub on_agent_complete: bool,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            on_terminal_command: true,
+            on_agent_complete: true,
+        }
+    }
+}
+
+/// Structural highlighting in the TUI editor
+///
+/// Both are off by default. Entries in `filetypes`, keyed by file extension,
+/// turn either on or off for that kind of file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyntaxConfig {
+    /// Color nested brackets by their depth
+    #[serde(default)]
+    pub rainbow_delimiters: bool,
+
+    /// Tint the background of the function or block under the cursor
+    #[serde(default)]
+    pub scope_highlight: bool,
+
+    /// Overrides by file extension, e.g. `[syntax.filetypes.md]`
+    #[serde(default)]
+    pub filetypes: HashMap<String, SyntaxOverride>,
+}
+
+/// Structural highlighting settings for one file extension
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyntaxOverride {
+    /// Overrides [`SyntaxConfig::rainbow_delimiters`]
+    #[serde(default)]
+    pub rainbow_delimiters: Option<bool>,
+
+    /// Overrides [`SyntaxConfig::scope_highlight`]
+    #[serde(default)]
+    pub scope_highlight: Option<bool>,
+}
+
+impl SyntaxConfig {
+    /// Whether brackets are colored in files with extension `ext`
+    pub fn rainbow_delimiters(&self, ext: Option<&str>) -> bool {
+        self.filetype(ext)
+            .and_then(|o| o.rainbow_delimiters)
+            .unwrap_or(self.rainbow_delimiters)
+    }
+
+    /// Whether the scope under the cursor is tinted in files with extension
+    /// `ext`
+    pub fn scope_highlight(&self, ext: Option<&str>) -> bool {
+        self.filetype(ext)
+            .and_then(|o| o.scope_highlight)
+            .unwrap_or(self.scope_highlight)
+    }
+
+    fn filetype(&self, ext: Option<&str>) -> Option<&SyntaxOverride> {
+        self.filetypes.get(&ext?.to_ascii_lowercase())
+    }
+}
+
+/// Files read ahead into memory when a file is opened in the TUI
+///
+/// Candidates are the files the opened one imports and the files most often
+/// committed together with it. Warm buffers are dropped, oldest first, once
+/// they exceed `max_memory_mb` in total.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreloadConfig {
+    /// Read likely next files in the background
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Files read ahead for each opened file
+    #[serde(default = "default_preload_max_files")]
+    pub max_files: usize,
+
+    /// Memory warm buffers may take in total, in megabytes
+    #[serde(default = "default_preload_max_memory_mb")]
+    pub max_memory_mb: usize,
+
+    /// Commits touching the opened file searched for co-edited files
+    #[serde(default = "default_preload_history_commits")]
+    pub history_commits: usize,
+}
+
+impl Default for PreloadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_files: default_preload_max_files(),
+            max_memory_mb: default_preload_max_memory_mb(),
+            history_commits: default_preload_history_commits(),
+        }
+    }
+}
+
+/// Remote-control server driving the editor over WebSocket
+///
+/// Off by default. The token can also be given in the `AIT42_REMOTE_TOKEN`
+/// environment variable; without either, a random one is generated each
+/// time the server starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RemoteConfig {
+    /// Start the server with the editor
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Address to listen on; keep it on localhost unless the network is trusted
+    #[serde(default = "default_remote_bind")]
+    pub bind: String,
+
+    /// Token clients must present
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_remote_bind(),
+            token: None,
+        }
+    }
+}
+
+/// MCP (Model Context Protocol) server exposing the editor to external agents
+///
+/// Off by default. The token can also be given in the `AIT42_MCP_TOKEN`
+/// environment variable; without either, a random one is generated each
+/// time the server starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct McpConfig {
+    /// Start the server with the editor
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Address to listen on; keep it on localhost unless the network is trusted
+    #[serde(default = "default_mcp_bind")]
+    pub bind: String,
+
+    /// Token clients must present
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_mcp_bind(),
+            token: None,
+        }
+    }
+}
+
+/// Scoring of competition instances once they have all finished
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CompetitionConfig {
+    /// Score the instances and mark the best as winner when they finish
+    #[serde(default = "default_true")]
+    pub auto_select_winner: bool,
+
+    /// Command run in each worktree to check its tests; detected from the
+    /// project files (Cargo.toml, package.json, ...) when unset
+    #[serde(default)]
+    pub test_command: Option<String>,
+
+    /// Time allowed for the test command, in seconds
+    #[serde(default = "default_competition_test_timeout")]
+    pub test_timeout_secs: u64,
+
+    /// Also have an LLM rate each instance's diff (needs ANTHROPIC_API_KEY)
+    #[serde(default)]
+    pub llm_evaluation: bool,
+
+    /// Have an LLM describe each instance's diff in a paragraph when they
+    /// finish (needs ANTHROPIC_API_KEY); diff stats are recorded regardless
+    #[serde(default = "default_true")]
+    pub summarize_diffs: bool,
+}
+
+impl Default for CompetitionConfig {
+    fn default() -> Self {
+        Self {
+            auto_select_winner: true,
+            test_command: None,
+            test_timeout_secs: default_competition_test_timeout(),
+            llm_evaluation: false,
+            summarize_diffs: true,
+        }
+    }
+}
+
+/// Issue trackers tasks can be picked from and session reports posted to
+///
+/// API tokens are not stored here but in the OS keychain (set from the
+/// editor), or in the `GITHUB_TOKEN` and `JIRA_API_TOKEN` environment
+/// variables.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IssuesConfig {
+    /// GitHub repository, `owner/name`; defaults to the workspace's origin
+    /// remote when it is on github.com
+    #[serde(default)]
+    pub github_repo: Option<String>,
+
+    /// GitHub API endpoint, for GitHub Enterprise Server
+    #[serde(default = "default_github_api_url")]
+    pub github_api_url: String,
+
+    /// Jira site, e.g. `https://example.atlassian.net`
+    #[serde(default)]
+    pub jira_url: Option<String>,
+
+    /// Jira project key issues are listed from; without it, open issues
+    /// assigned to the token's user are listed
+    #[serde(default)]
+    pub jira_project: Option<String>,
+
+    /// Account email the Jira API token belongs to (Jira Cloud); without it
+    /// the token is sent as a personal access token (Jira Data Center)
+    #[serde(default)]
+    pub jira_email: Option<String>,
+}
+
+impl Default for IssuesConfig {
+    fn default() -> Self {
+        Self {
+            github_repo: None,
+            github_api_url: default_github_api_url(),
+            jira_url: None,
+            jira_project: None,
+            jira_email: None,
+        }
+    }
+}
+
 // Default value functions for serde
 fn default_tab_size() -> usize {
     4
@@ -265,6 +1147,10 @@ fn default_scroll_offset() -> usize {
     5
 }
 
+fn default_font_size() -> u16 {
+    14
+}
+
 fn default_theme() -> String {
     "monokai".to_string()
 }
@@ -273,10 +1159,150 @@ fn default_keybinding_mode() -> String {
     "vim".to_string()
 }
 
+fn default_quick_task_shortcut() -> String {
+    "CmdOrCtrl+Shift+Space".to_string()
+}
+
+fn default_checkpoint_interval() -> u64 {
+    300
+}
+
+fn default_deleted_retention_days() -> u32 {
+    30
+}
+
+fn default_sessions_backend() -> String {
+    "json".to_string()
+}
+
+fn default_update_channel() -> String {
+    "stable".to_string()
+}
+
+fn default_update_endpoint() -> String {
+    "https://raw.githubusercontent.com/RenTonoduka/AIT42-Editor/main/updates".to_string()
+}
+
+fn default_todo_tags() -> Vec<String> {
+    vec!["TODO".to_string(), "FIXME".to_string(), "HACK".to_string()]
+}
+
+fn default_clipboard_history_size() -> usize {
+    50
+}
+
+fn default_preload_max_files() -> usize {
+    8
+}
+
+fn default_preload_max_memory_mb() -> usize {
+    32
+}
+
+fn default_preload_history_commits() -> usize {
+    50
+}
+
+fn default_remote_bind() -> String {
+    "127.0.0.1:7421".to_string()
+}
+
+fn default_mcp_bind() -> String {
+    "127.0.0.1:7422".to_string()
+}
+
+fn default_competition_test_timeout() -> u64 {
+    600
+}
+
+fn default_github_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
 fn default_agents_path() -> PathBuf {
     PathBuf::from("../.claude/agents")
 }
 
+fn default_single_max_subtasks() -> usize {
+    1
+}
+
+fn default_parallel_max_subtasks() -> usize {
+    3
+}
+
+fn default_ensemble_min_subtasks() -> usize {
+    8
+}
+
+fn default_debate_below_confidence() -> f64 {
+    0.5
+}
+
+fn default_auto_mode_model() -> String {
+    "sonnet".to_string()
+}
+
+fn default_auto_mode_timeout() -> u64 {
+    300
+}
+
+fn default_cost_per_run() -> f64 {
+    0.5
+}
+
+fn default_injection_policy() -> String {
+    "flag".to_string()
+}
+
+fn default_max_blob_chars() -> usize {
+    1024
+}
+
+fn default_protected_paths() -> Vec<String> {
+    [
+        ".github/workflows/**",
+        ".env",
+        ".env.*",
+        "**/*.pem",
+        "**/*.key",
+        "**/id_rsa*",
+        "**/secrets.*",
+        ".git/**",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_edit_blob_chars() -> usize {
+    4096
+}
+
+fn default_alert_action() -> String {
+    "notify".to_string()
+}
+
+fn default_llm_provider() -> String {
+    "anthropic".to_string()
+}
+
+fn default_budget_warn_at() -> f64 {
+    0.8
+}
+
+fn default_input_usd_per_mtok() -> f64 {
+    3.0
+}
+
+fn default_output_usd_per_mtok() -> f64 {
+    15.0
+}
+
+fn default_agent_run_tokens() -> u64 {
+    20_000
+}
+
 /// Default LSP configuration
 pub fn default_lsp_config() -> HashMap<String, LspServerConfig> {
     let mut config = HashMap::new();
@@ -392,6 +1418,151 @@ mod tests {
         assert!(config.tmux_enabled);
         assert!(config.auto_coordinator);
         assert_eq!(config.agents_path, PathBuf::from("../.claude/agents"));
+        assert_eq!(config.auto_mode, AutoModeConfig::default());
+        assert_eq!(config.budget, BudgetConfig::default());
+        assert_eq!(config.llm_provider.name, "anthropic");
+        assert_eq!(config.injection_scan.policy, "flag");
+        assert!(config.edit_guard.enabled);
+        assert!(config
+            .edit_guard
+            .protected_paths
+            .contains(&".github/workflows/**".to_string()));
+    }
+
+    #[test]
+    fn test_alert_rules_config() {
+        let config: Config = toml::from_str(
+            "[[ait42.alerts]]\nname = \"Errors\"\npattern = \"ERROR\"\n\n\
+             [[ait42.alerts]]\nname = \"Limit\"\npattern = \"rate.limit\"\nregex = true\n\
+             action = \"cancel\"\n",
+        )
+        .unwrap();
+        let alerts = &config.ait42.alerts;
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].action, "notify");
+        assert!(!alerts[0].regex && !alerts[0].case_sensitive);
+        assert!(alerts[1].regex);
+        assert_eq!(alerts[1].action, "cancel");
+    }
+
+    #[test]
+    fn test_llm_provider_config() {
+        let config: Config = toml::from_str(
+            "[ait42.llm_provider]\nname = \"ollama\"\nbase_url = \"http://gpu:11434\"\n",
+        )
+        .unwrap();
+        let provider = &config.ait42.llm_provider;
+        assert_eq!(provider.name, "ollama");
+        assert_eq!(provider.model, None);
+        assert_eq!(provider.base_url.as_deref(), Some("http://gpu:11434"));
+    }
+
+    #[test]
+    fn test_auto_mode_config() {
+        let config: Config =
+            toml::from_str("[ait42.auto_mode]\nensemble_min_subtasks = 10\nmodel = \"opus\"\n")
+                .unwrap();
+        let auto_mode = &config.ait42.auto_mode;
+        assert_eq!(auto_mode.ensemble_min_subtasks, 10);
+        assert_eq!(auto_mode.model, "opus");
+        assert_eq!(auto_mode.single_max_subtasks, 1);
+        assert_eq!(auto_mode.parallel_max_subtasks, 3);
+        assert_eq!(auto_mode.debate_below_confidence, 0.5);
+        assert_eq!(auto_mode.timeout_secs, 300);
+        assert_eq!(auto_mode.max_cost_usd, None);
+        assert!(auto_mode.rules.is_empty());
+    }
+
+    #[test]
+    fn test_mode_rules() {
+        let toml = r#"
+[ait42.auto_mode]
+max_cost_usd = 2.0
+
+[[ait42.auto_mode.rules]]
+mode = "debate"
+complexity = ["Exponential"]
+max_confidence = 0.7
+
+[[ait42.auto_mode.rules]]
+mode = "competition"
+min_subtasks = 4
+instances = 3
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let auto_mode = &config.ait42.auto_mode;
+        assert_eq!(auto_mode.max_cost_usd, Some(2.0));
+        assert_eq!(auto_mode.rules.len(), 2);
+        assert_eq!(auto_mode.rules[0].complexity, ["Exponential"]);
+        assert_eq!(auto_mode.rules[0].max_confidence, Some(0.7));
+        assert_eq!(auto_mode.rules[1].instances, Some(3));
+        assert_eq!(auto_mode.rules[1].max_subtasks, None);
+
+        let round_trip: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_trip.ait42.auto_mode, *auto_mode);
+    }
+
+    #[test]
+    fn test_budget_config() {
+        let budget = BudgetConfig::default();
+        assert_eq!(budget.daily_limit_usd, None);
+        assert_eq!(budget.session_limit_usd, None);
+        assert_eq!(budget.warn_at, 0.8);
+
+        let config: Config =
+            toml::from_str("[ait42.budget]
+daily_limit_usd = 10.0
+warn_at = 0.9
+").unwrap();
+        let budget = &config.ait42.budget;
+        assert_eq!(budget.daily_limit_usd, Some(10.0));
+        assert_eq!(budget.session_limit_usd, None);
+        assert_eq!(budget.warn_at, 0.9);
+        assert_eq!(budget.output_usd_per_mtok, 15.0);
+        assert_eq!(budget.agent_run_tokens, 20_000);
+    }
+
+    #[test]
+    fn test_syntax_config() {
+        let config = Config::default();
+        assert!(!config.syntax.rainbow_delimiters(Some("rs")));
+
+        let config: Config = toml::from_str(
+            "[syntax]\nrainbow_delimiters = true\n\n\
+             [syntax.filetypes.md]\nrainbow_delimiters = false\nscope_highlight = true\n",
+        )
+        .unwrap();
+        let syntax = &config.syntax;
+        assert!(syntax.rainbow_delimiters(Some("rs")));
+        assert!(syntax.rainbow_delimiters(None));
+        assert!(!syntax.rainbow_delimiters(Some("md")));
+        assert!(syntax.scope_highlight(Some("MD")));
+        assert!(!syntax.scope_highlight(Some("rs")));
+    }
+
+    #[test]
+    fn test_preload_config() {
+        let config = Config::default();
+        assert!(config.preload.enabled);
+        assert_eq!(config.preload.max_files, 8);
+        assert_eq!(config.preload.max_memory_mb, 32);
+
+        let config: Config =
+            toml::from_str("[preload]\nenabled = false\nmax_memory_mb = 4\n").unwrap();
+        assert!(!config.preload.enabled);
+        assert_eq!(config.preload.max_memory_mb, 4);
+        assert_eq!(config.preload.history_commits, 50);
+    }
+
+    #[test]
+    fn test_checkpoint_config() {
+        let config = CheckpointConfig::default();
+        assert!(!config.enabled);
+        assert_eq!(config.interval_secs, 300);
+
+        let config: Config = toml::from_str("[checkpoints]\nenabled = true\n").unwrap();
+        assert!(config.checkpoints.enabled);
+        assert_eq!(config.checkpoints.interval_secs, 300);
     }
 
     #[test]
@@ -427,6 +1598,9 @@ name = "gruvbox-dark"
 
 [keybindings]
 mode = "emacs"
+
+[keybindings.keymap.normal]
+"Space w" = "save"
 "#;
 
         let config: Config = toml::from_str(toml).unwrap();
@@ -435,5 +1609,13 @@ mode = "emacs"
         assert!(!config.editor.line_numbers);
         assert_eq!(config.theme.name, "gruvbox-dark");
         assert_eq!(config.keybindings.mode, "emacs");
+        assert_eq!(config.keybindings.quick_task, "CmdOrCtrl+Shift+Space");
+        assert_eq!(config.keybindings.keymap["normal"]["Space w"], "save");
+        assert_eq!(config.updates.channel, "stable");
+        assert!(config.updates.check_on_startup);
+        assert_eq!(config.todos.tags, ["TODO", "FIXME", "HACK"]);
+        assert_eq!(config.clipboard.history_size, 50);
+        assert!(!config.clipboard.persist);
+        assert_eq!(config.focus, FocusConfig::default());
     }
 }
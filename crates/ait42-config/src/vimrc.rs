@@ -0,0 +1,482 @@
+//! Vim Configuration Import
+//!
+//! Translates a practical subset of a `.vimrc` into [`Config`]: `set`
+//! options with an editor equivalent, `colorscheme`, `let mapleader`, and
+//! normal and insert mode mappings whose right-hand side is a known editor
+//! command. Anything else is listed in the [`ImportReport`].
+//!
+//! Mappings become custom key bindings. `<leader>` is expanded to the
+//! configured leader, keys of a sequence are separated by spaces, and insert
+//! mode bindings get an `insert:` prefix. Sequences of more than two keys
+//! cannot be bound and are skipped.
+
+use crate::{keymap, Config, ImportReport, ThemeConfig};
+use std::path::PathBuf;
+
+/// Vim's default leader key
+const DEFAULT_LEADER: &str = "\\";
+
+/// Right-hand sides of mappings with an AIT42 equivalent
+const COMMANDS: &[(&str, &str)] = &[
+    (":w<CR>", "save"),
+    (":update<CR>", "save"),
+    (":q<CR>", "quit"),
+    (":q!<CR>", "force_quit"),
+    (":qa!<CR>", "force_quit"),
+    ("u", "undo"),
+    ("<C-R>", "redo"),
+    ("/", "search"),
+    ("n", "search_next"),
+    ("N", "search_previous"),
+    ("dd", "delete_line"),
+    ("J", "join_lines"),
+    ("i", "enter_insert_mode"),
+    ("v", "enter_visual_mode"),
+    (":", "enter_command_mode"),
+    ("<Esc>", "enter_normal_mode"),
+    ("0", "move_line_start"),
+    ("^", "move_line_start"),
+    ("$", "move_line_end"),
+    ("gg", "move_file_start"),
+    ("G", "move_file_end"),
+    ("w", "move_word_forward"),
+    ("b", "move_word_backward"),
+    ("<C-U>", "move_page_up"),
+    ("<C-D>", "move_page_down"),
+    (":tabnew<CR>", "new_tab"),
+    (":tabclose<CR>", "close_tab"),
+    ("gt", "next_tab"),
+    (":tabnext<CR>", "next_tab"),
+    ("gT", "prev_tab"),
+    (":tabprevious<CR>", "prev_tab"),
+    (":NERDTreeToggle<CR>", "toggle_sidebar"),
+    (":Lexplore<CR>", "toggle_sidebar"),
+    (":terminal<CR>", "toggle_terminal"),
+    ("<C-W>w", "focus_next_panel"),
+    ("za", "toggle_fold"),
+    ("zc", "fold"),
+    ("zo", "unfold"),
+    ("zM", "fold_all"),
+    ("zR", "unfold_all"),
+    ("p", "paste"),
+];
+
+/// Default vimrc locations, in the order Vim and Neovim read them
+pub fn default_paths() -> Vec<PathBuf> {
+    let Some(dirs) = directories::BaseDirs::new() else {
+        return Vec::new();
+    };
+    let home = dirs.home_dir();
+    vec![
+        home.join(".vimrc"),
+        home.join(".vim").join("vimrc"),
+        home.join(".config").join("nvim").join("init.vim"),
+    ]
+}
+
+/// Apply the recognized settings and mappings of a vimrc
+pub fn import_vimrc(vimrc: &str, config: &mut Config) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut leader = DEFAULT_LEADER.to_string();
+    let mut tabstop = None;
+    let mut shiftwidth = None;
+
+    for line in join_continuations(vimrc) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('"') {
+            continue;
+        }
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match command {
+            "set" | "se" | "setlocal" | "setl" => {
+                for option in strip_comment(rest).split_whitespace() {
+                    set_option(option, config, &mut report, &mut tabstop, &mut shiftwidth);
+                }
+            }
+            "colorscheme" | "colo" => {
+                let name = strip_comment(rest).to_lowercase();
+                let mapped = if name.contains("monokai") || name == "molokai" {
+                    Some(ThemeConfig::monokai())
+                } else if name.starts_with("gruvbox") {
+                    Some(ThemeConfig::gruvbox_dark())
+                } else {
+                    None
+                };
+                match mapped {
+                    Some(theme) => {
+                        report.apply(line, "theme.name", &theme.name);
+                        config.theme = theme;
+                    }
+                    None => report.skip(line, "no matching theme"),
+                }
+            }
+            "let" => match parse_leader(rest) {
+                Some(key) => {
+                    report.apply(line, "<leader>", &key);
+                    leader = key;
+                }
+                None => report.skip(line, "only mapleader is supported"),
+            },
+            "nnoremap" | "nnor" | "nmap" | "nm" | "noremap" | "no" | "map" => {
+                map(line, rest, None, &leader, config, &mut report)
+            }
+            "inoremap" | "ino" | "imap" | "im" => {
+                map(line, rest, Some("insert"), &leader, config, &mut report)
+            }
+            // Always on in AIT42
+            "syntax" | "sy" | "filetype" | "filet" => {}
+            _ => report.skip(line, "unsupported command"),
+        }
+    }
+
+    // Vim indents by shiftwidth, which falls back to tabstop when 0
+    if let Some(width) = shiftwidth.filter(|w| *w > 0).or(tabstop) {
+        if (1..=16).contains(&width) {
+            config.editor.tab_size = width;
+            report.apply("shiftwidth/tabstop", "editor.tab_size", width);
+        } else {
+            report.skip(&format!("shiftwidth/tabstop = {}", width), "tab size must be 1-16");
+        }
+    }
+
+    if !report.applied.is_empty() && config.keybindings.mode != "vim" {
+        config.keybindings.mode = "vim".to_string();
+        report.apply("vimrc", "keybindings.mode", "vim");
+    }
+
+    report
+}
+
+/// Apply one `set` option such as `ts=2`, `nowrap` or `number`
+fn set_option(
+    option: &str,
+    config: &mut Config,
+    report: &mut ImportReport,
+    tabstop: &mut Option<usize>,
+    shiftwidth: &mut Option<usize>,
+) {
+    let editor = &mut config.editor;
+    let entry = format!("set {}", option);
+    let (name, value) = match option.split_once(['=', ':']) {
+        Some((name, value)) => (name, Some(value)),
+        None => (option, None),
+    };
+    let (name, enabled) = match name.strip_prefix("no") {
+        Some(stripped) if value.is_none() => (stripped, false),
+        _ => (name.trim_end_matches('!'), true),
+    };
+    let number = value.and_then(|v| v.parse::<usize>().ok());
+
+    match (name, value) {
+        ("tabstop" | "ts", Some(_)) => match number {
+            Some(n) => *tabstop = Some(n),
+            None => report.skip(&entry, "not a number"),
+        },
+        ("shiftwidth" | "sw", Some(_)) => match number {
+            Some(n) => *shiftwidth = Some(n),
+            None => report.skip(&entry, "not a number"),
+        },
+        ("scrolloff" | "so", Some(_)) => match number {
+            Some(n) => {
+                editor.scroll_offset = n;
+                report.apply(&entry, "editor.scroll_offset", n);
+            }
+            None => report.skip(&entry, "not a number"),
+        },
+        ("expandtab" | "et", None) => {
+            editor.insert_spaces = enabled;
+            report.apply(&entry, "editor.insert_spaces", enabled);
+        }
+        ("number" | "nu", None) => {
+            editor.line_numbers = enabled;
+            report.apply(&entry, "editor.line_numbers", enabled);
+        }
+        ("relativenumber" | "rnu", None) => {
+            editor.relative_line_numbers = enabled;
+            report.apply(&entry, "editor.relative_line_numbers", enabled);
+        }
+        ("wrap", None) => {
+            editor.word_wrap = enabled;
+            report.apply(&entry, "editor.word_wrap", enabled);
+        }
+        ("cursorline" | "cul", None) => {
+            editor.highlight_current_line = enabled;
+            report.apply(&entry, "editor.highlight_current_line", enabled);
+        }
+        ("list", None) => {
+            editor.show_whitespace = enabled;
+            report.apply(&entry, "editor.show_whitespace", enabled);
+        }
+        _ => report.skip(&entry, "unsupported option"),
+    }
+}
+
+/// Add a mapping like `<leader>w :w<CR>` to the custom bindings
+fn map(
+    line: &str,
+    rest: &str,
+    mode: Option<&str>,
+    leader: &str,
+    config: &mut Config,
+    report: &mut ImportReport,
+) {
+    // Map arguments such as <silent> don't change what the mapping does
+    let mut parts = rest.split_whitespace().skip_while(|part| {
+        matches!(
+            part.to_lowercase().as_str(),
+            "<silent>" | "<buffer>" | "<nowait>" | "<unique>" | "<special>"
+        )
+    });
+    let (Some(lhs), Some(first)) = (parts.next(), parts.next()) else {
+        report.skip(line, "incomplete mapping");
+        return;
+    };
+    let rhs: String = std::iter::once(first).chain(parts).collect::<Vec<_>>().join(" ");
+
+    // Insert mode mappings usually leave insert mode to run the command
+    let mut command_keys = normalize_keys(&rhs);
+    if mode == Some("insert") && command_keys != "<Esc>" {
+        for prefix in ["<Esc>", "<C-O>"] {
+            if let Some(stripped) = command_keys.strip_prefix(prefix) {
+                command_keys = stripped.to_string();
+            }
+        }
+    }
+    let Some((_, command)) = COMMANDS.iter().find(|(keys, _)| *keys == command_keys) else {
+        report.skip(line, "no equivalent command");
+        return;
+    };
+    let Some(key) = convert_keys(lhs, leader) else {
+        report.skip(line, "unsupported key");
+        return;
+    };
+    if keymap::parse_sequence(&key).is_err() {
+        report.skip(line, "unsupported key sequence");
+        return;
+    }
+    let key = match mode {
+        Some(mode) => format!("{}:{}", mode, key),
+        None => key,
+    };
+
+    report.apply(line, &format!("keybindings.custom.\"{}\"", key), command);
+    config.keybindings.custom.insert(key, command.to_string());
+}
+
+/// Normalize the case of special keys, e.g. `<cr>` to `<CR>`
+fn normalize_keys(keys: &str) -> String {
+    let mut normalized = String::with_capacity(keys.len());
+    let mut rest = keys;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        normalized.push_str(&rest[..start]);
+        let special = &rest[start + 1..start + end];
+        normalized.push('<');
+        normalized.push_str(&match special.split_once('-') {
+            Some((modifier, key)) if !key.is_empty() => {
+                format!("{}-{}", modifier.to_uppercase(), key.to_uppercase())
+            }
+            _ => match special.to_lowercase().as_str() {
+                "cr" | "enter" | "return" => "CR".to_string(),
+                "esc" => "Esc".to_string(),
+                "leader" => "leader".to_string(),
+                other => other.to_string(),
+            },
+        });
+        normalized.push('>');
+        rest = &rest[start + end + 1..];
+    }
+    normalized.push_str(rest);
+    normalized
+}
+
+/// Convert a mapping's keys to AIT42 notation
+///
+/// Keys of a sequence are separated by spaces: `<C-s>` becomes `Ctrl+s` and
+/// `<leader>w` with a `,` leader becomes `, w`.
+fn convert_keys(lhs: &str, leader: &str) -> Option<String> {
+    let mut keys = Vec::new();
+    let mut rest = lhs;
+    while let Some(c) = rest.chars().next() {
+        if let Some(end) = rest.find('>').filter(|_| c == '<') {
+            keys.push(convert_special_key(&rest[1..end], leader)?);
+            rest = &rest[end + 1..];
+        } else {
+            keys.push(c.to_string());
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    (!keys.is_empty()).then(|| keys.join(" "))
+}
+
+/// Convert a key written as `<...>`, without the brackets
+fn convert_special_key(special: &str, leader: &str) -> Option<String> {
+    if let Some((modifier, key)) = special.split_once('-') {
+        if key.chars().count() != 1 {
+            return None;
+        }
+        let modifier = match modifier.to_lowercase().as_str() {
+            "c" => "Ctrl",
+            "s" => "Shift",
+            "a" | "m" => "Alt",
+            _ => return None,
+        };
+        return Some(format!("{}+{}", modifier, key.to_lowercase()));
+    }
+    let key = match special.to_lowercase().as_str() {
+        "leader" => leader,
+        "space" => "Space",
+        "tab" => "Tab",
+        "cr" | "enter" | "return" => "Enter",
+        "esc" => "Esc",
+        "bs" => "Backspace",
+        "del" => "Delete",
+        "up" => "Up",
+        "down" => "Down",
+        "left" => "Left",
+        "right" => "Right",
+        f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok() => {
+            return Some(f.to_uppercase())
+        }
+        _ => return None,
+    };
+    Some(key.to_string())
+}
+
+/// Value of `let mapleader = ...`
+fn parse_leader(rest: &str) -> Option<String> {
+    let (name, value) = rest.split_once('=')?;
+    if !matches!(name.trim(), "mapleader" | "g:mapleader") {
+        return None;
+    }
+    let value = value.trim();
+    let quoted = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))?;
+    match quoted {
+        "\\<Space>" | " " => Some("Space".to_string()),
+        "\\\\" | "\\" => Some("\\".to_string()),
+        key if key.chars().count() == 1 => Some(key.to_string()),
+        _ => None,
+    }
+}
+
+/// Drop a trailing `" comment` of an option or color scheme
+fn strip_comment(rest: &str) -> &str {
+    rest.split('"').next().unwrap_or_default().trim_end()
+}
+
+/// Join lines continued with a leading backslash
+fn join_continuations(vimrc: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in vimrc.lines() {
+        match (line.trim_start().strip_prefix('\\'), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_options() {
+        let mut config = Config::default();
+        config.keybindings.mode = "default".to_string();
+        let report = import_vimrc(
+            r#"
+" Basics
+set nocompatible
+set ts=8 sw=2 expandtab
+set number relativenumber nowrap
+set scrolloff=8 " keep context
+set cursorline list
+syntax on
+colorscheme gruvbox
+"#,
+            &mut config,
+        );
+
+        assert_eq!(config.editor.tab_size, 2);
+        assert!(config.editor.insert_spaces);
+        assert!(config.editor.line_numbers);
+        assert!(config.editor.relative_line_numbers);
+        assert!(!config.editor.word_wrap);
+        assert_eq!(config.editor.scroll_offset, 8);
+        assert!(config.editor.highlight_current_line);
+        assert!(config.editor.show_whitespace);
+        assert_eq!(config.theme.name, "gruvbox-dark");
+        assert_eq!(config.keybindings.mode, "vim");
+        assert_eq!(report.unmapped, ["set nocompatible (unsupported option)"]);
+    }
+
+    #[test]
+    fn test_shiftwidth_falls_back_to_tabstop() {
+        let mut config = Config::default();
+        import_vimrc("set tabstop=3 shiftwidth=0", &mut config);
+        assert_eq!(config.editor.tab_size, 3);
+    }
+
+    #[test]
+    fn test_import_mappings() {
+        let mut config = Config::default();
+        let report = import_vimrc(
+            r#"
+let mapleader = ","
+nnoremap <leader>w :w<cr>
+nnoremap <silent> <C-s> :w<CR>
+nmap <leader>n :NERDTreeToggle<CR>
+inoremap jk <Esc>
+inoremap <C-s> <Esc>:w<CR>
+nnoremap <leader>f :Files<CR>
+nnoremap <C-w><C-q> :q<CR>
+nmap <Plug>(save) :w<CR>
+nnoremap <leader>ww :w<CR>
+"#,
+            &mut config,
+        );
+
+        let custom = &config.keybindings.custom;
+        assert_eq!(custom[", w"], "save");
+        assert_eq!(custom["Ctrl+s"], "save");
+        assert_eq!(custom[", n"], "toggle_sidebar");
+        assert_eq!(custom["insert:j k"], "enter_normal_mode");
+        assert_eq!(custom["insert:Ctrl+s"], "save");
+        assert_eq!(custom["Ctrl+w Ctrl+q"], "quit");
+        assert_eq!(custom.len(), 6);
+        assert_eq!(
+            report.unmapped,
+            [
+                "nnoremap <leader>f :Files<CR> (no equivalent command)",
+                "nmap <Plug>(save) :w<CR> (unsupported key)",
+                "nnoremap <leader>ww :w<CR> (unsupported key sequence)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_leader() {
+        assert_eq!(parse_leader(r#"mapleader = "\<Space>""#).as_deref(), Some("Space"));
+        assert_eq!(parse_leader("g:mapleader=','").as_deref(), Some(","));
+        assert_eq!(parse_leader(r#"mapleader = "\\""#).as_deref(), Some("\\"));
+        assert_eq!(parse_leader("g:loaded_netrw = 1"), None);
+    }
+
+    #[test]
+    fn test_convert_keys() {
+        assert_eq!(convert_keys("<C-p>", "\\").as_deref(), Some("Ctrl+p"));
+        assert_eq!(convert_keys("<leader>ff", "Space").as_deref(), Some("Space f f"));
+        assert_eq!(convert_keys("<F5>", "\\").as_deref(), Some("F5"));
+        assert_eq!(convert_keys("<C-w><C-q>", "\\").as_deref(), Some("Ctrl+w Ctrl+q"));
+        assert_eq!(convert_keys("a<b", "\\").as_deref(), Some("a < b"));
+        assert_eq!(convert_keys("<Plug>(foo)", "\\"), None);
+    }
+}
@@ -0,0 +1,423 @@
+//! VS Code Settings Import
+//!
+//! Maps a VS Code `settings.json` and `keybindings.json` onto [`Config`].
+//! Both files are JSON with comments and trailing commas. Options with an
+//! AIT42 equivalent are applied; everything else is listed in the
+//! [`ImportReport`] so the user can see what did not carry over.
+
+use crate::{Config, ConfigError, ImportReport, Result, ThemeConfig};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// VS Code commands with an AIT42 equivalent
+const COMMANDS: &[(&str, &str)] = &[
+    ("workbench.action.files.save", "save"),
+    ("workbench.action.quit", "quit"),
+    ("undo", "undo"),
+    ("redo", "redo"),
+    ("actions.find", "search"),
+    ("editor.action.nextMatchFindAction", "search_next"),
+    ("editor.action.previousMatchFindAction", "search_previous"),
+    ("workbench.action.showCommands", "open_command_palette"),
+    ("workbench.action.openGlobalKeybindings", "show_keybindings"),
+    ("workbench.action.toggleSidebarVisibility", "toggle_sidebar"),
+    ("workbench.action.terminal.toggleTerminal", "toggle_terminal"),
+    ("workbench.view.explorer", "focus_sidebar"),
+    ("workbench.action.focusActiveEditorGroup", "focus_editor"),
+    ("workbench.action.terminal.focus", "focus_terminal"),
+    ("workbench.action.files.newUntitledFile", "new_tab"),
+    ("workbench.action.closeActiveEditor", "close_tab"),
+    ("workbench.action.nextEditor", "next_tab"),
+    ("workbench.action.previousEditor", "prev_tab"),
+    ("editor.action.moveLinesUpAction", "move_item_up"),
+    ("editor.action.moveLinesDownAction", "move_item_down"),
+    ("editor.action.deleteLines", "delete_line"),
+    ("editor.action.joinLines", "join_lines"),
+    ("editor.action.smartSelect.expand", "select_enclosing_node"),
+    ("editor.fold", "fold"),
+    ("editor.unfold", "unfold"),
+    ("editor.toggleFold", "toggle_fold"),
+    ("editor.foldAll", "fold_all"),
+    ("editor.unfoldAll", "unfold_all"),
+    ("cursorHome", "move_line_start"),
+    ("cursorEnd", "move_line_end"),
+    ("cursorTop", "move_file_start"),
+    ("cursorBottom", "move_file_end"),
+    ("cursorWordLeft", "move_word_backward"),
+    ("cursorWordRight", "move_word_forward"),
+];
+
+/// Default VS Code user settings directory
+///
+/// `~/.config/Code/User` on Linux, `~/Library/Application Support/Code/User`
+/// on macOS and `%APPDATA%\Code\User` on Windows.
+pub fn default_user_dir() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|dirs| dirs.config_dir().join("Code").join("User"))
+}
+
+/// Apply the recognized options of a `settings.json`
+pub fn import_settings(json: &str, config: &mut Config) -> Result<ImportReport> {
+    let settings = parse_jsonc(json)?;
+    let settings = settings
+        .as_object()
+        .ok_or_else(|| ConfigError::ParseError("settings.json must be an object".to_string()))?;
+
+    let mut report = ImportReport::default();
+    let editor = &mut config.editor;
+
+    for (key, value) in settings {
+        match (key.as_str(), value) {
+            ("editor.tabSize", Value::Number(n)) => match n.as_u64() {
+                Some(size @ 1..=16) => {
+                    editor.tab_size = size as usize;
+                    report.apply(key, "editor.tab_size", size);
+                }
+                _ => report.skip(key, "tab size must be 1-16"),
+            },
+            ("editor.insertSpaces", Value::Bool(b)) => {
+                editor.insert_spaces = *b;
+                report.apply(key, "editor.insert_spaces", b);
+            }
+            ("editor.wordWrap", Value::String(wrap)) => {
+                editor.word_wrap = wrap != "off";
+                report.apply(key, "editor.word_wrap", editor.word_wrap);
+            }
+            ("editor.lineNumbers", Value::String(numbers)) => match numbers.as_str() {
+                "on" | "off" | "relative" => {
+                    editor.line_numbers = numbers != "off";
+                    editor.relative_line_numbers = numbers == "relative";
+                    report.apply(key, "editor.line_numbers", editor.line_numbers);
+                    report.apply(key, "editor.relative_line_numbers", editor.relative_line_numbers);
+                }
+                _ => report.skip(key, "unsupported value"),
+            },
+            ("editor.renderWhitespace", Value::String(whitespace)) => {
+                editor.show_whitespace = whitespace != "none";
+                report.apply(key, "editor.show_whitespace", editor.show_whitespace);
+            }
+            ("editor.renderLineHighlight", Value::String(highlight)) => {
+                editor.highlight_current_line = highlight != "none";
+                report.apply(key, "editor.highlight_current_line", editor.highlight_current_line);
+            }
+            ("editor.cursorStyle", Value::String(style)) => {
+                // line-thin, block-outline and underline-thin become their base style
+                let style = style.split('-').next().unwrap_or_default();
+                if ["block", "line", "underline"].contains(&style) {
+                    editor.cursor_style = style.to_string();
+                    report.apply(key, "editor.cursor_style", style);
+                } else {
+                    report.skip(key, "unsupported value");
+                }
+            }
+            ("editor.cursorSurroundingLines", Value::Number(n)) => match n.as_u64() {
+                Some(lines) => {
+                    editor.scroll_offset = lines as usize;
+                    report.apply(key, "editor.scroll_offset", lines);
+                }
+                None => report.skip(key, "unsupported value"),
+            },
+            ("editor.fontSize", Value::Number(n)) => match n.as_f64().map(|size| size.round()) {
+                Some(size) if (6.0..=72.0).contains(&size) => {
+                    editor.font_size = size as u16;
+                    report.apply(key, "editor.font_size", editor.font_size);
+                }
+                _ => report.skip(key, "font size must be 6-72"),
+            },
+            ("files.autoSave", Value::String(mode)) => {
+                if mode == "afterDelay" {
+                    editor.auto_save_delay = settings
+                        .get("files.autoSaveDelay")
+                        .and_then(Value::as_u64)
+                        .unwrap_or(1000);
+                } else {
+                    // onFocusChange and onWindowChange have no equivalent
+                    editor.auto_save_delay = 0;
+                }
+                report.apply(key, "editor.auto_save_delay", editor.auto_save_delay);
+            }
+            // Read together with files.autoSave
+            ("files.autoSaveDelay", _) => {}
+            ("workbench.colorTheme", Value::String(theme)) => {
+                let family = theme.to_lowercase();
+                let mapped = if family.contains("monokai") {
+                    Some(ThemeConfig::monokai())
+                } else if family.contains("gruvbox") {
+                    Some(ThemeConfig::gruvbox_dark())
+                } else {
+                    None
+                };
+                match mapped {
+                    Some(mapped) => {
+                        report.apply(key, "theme.name", &mapped.name);
+                        config.theme = mapped;
+                    }
+                    None => report.skip(&format!("{} = {}", key, theme), "no matching theme"),
+                }
+            }
+            (
+                "editor.tabSize" | "editor.insertSpaces" | "editor.wordWrap" | "editor.lineNumbers"
+                | "editor.renderWhitespace" | "editor.renderLineHighlight" | "editor.cursorStyle"
+                | "editor.cursorSurroundingLines" | "editor.fontSize" | "files.autoSave"
+                | "workbench.colorTheme",
+                _,
+            ) => report.skip(key, "unsupported value"),
+            _ if key.starts_with('[') => report.skip(key, "language-specific settings"),
+            _ => report.skip(key, "no equivalent"),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Add the recognized bindings of a `keybindings.json` to the custom bindings
+pub fn import_keybindings(json: &str, config: &mut Config) -> Result<ImportReport> {
+    let bindings = parse_jsonc(json)?;
+    let bindings = bindings
+        .as_array()
+        .ok_or_else(|| ConfigError::ParseError("keybindings.json must be an array".to_string()))?;
+
+    let mut report = ImportReport::default();
+    for binding in bindings {
+        let key = binding.get("key").and_then(Value::as_str).unwrap_or_default();
+        let command = binding.get("command").and_then(Value::as_str).unwrap_or_default();
+        let entry = format!("{}: {}", key, command);
+
+        if command.starts_with('-') {
+            report.skip(&entry, "removes a default binding");
+            continue;
+        }
+        let Some((_, target)) = COMMANDS.iter().find(|(vscode, _)| *vscode == command) else {
+            report.skip(&entry, "no equivalent command");
+            continue;
+        };
+        match convert_key(key) {
+            Some(converted) => {
+                report.apply(&entry, &format!("keybindings.custom.\"{}\"", converted), target);
+                config.keybindings.custom.insert(converted, target.to_string());
+            }
+            None => report.skip(&entry, "unsupported key"),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Convert a VS Code key like `ctrl+shift+p` to AIT42's `Ctrl+Shift+p`
+///
+/// Chords and the Cmd/Win modifier cannot be bound in a terminal.
+fn convert_key(key: &str) -> Option<String> {
+    let key = key.trim().to_lowercase();
+    if key.is_empty() || key.contains(' ') {
+        return None;
+    }
+
+    let mut parts: Vec<&str> = key.split('+').collect();
+    // "ctrl++" binds the plus key
+    if key.ends_with("++") {
+        parts.truncate(parts.len() - 2);
+        parts.push("+");
+    }
+    let (base, modifiers) = parts.split_last()?;
+
+    let mut converted = Vec::new();
+    for modifier in modifiers {
+        converted.push(match *modifier {
+            "ctrl" => "Ctrl",
+            "shift" => "Shift",
+            "alt" => "Alt",
+            _ => return None,
+        });
+    }
+    let base = match *base {
+        "up" => "Up".to_string(),
+        "down" => "Down".to_string(),
+        "left" => "Left".to_string(),
+        "right" => "Right".to_string(),
+        "pageup" => "PageUp".to_string(),
+        "pagedown" => "PageDown".to_string(),
+        "home" => "Home".to_string(),
+        "end" => "End".to_string(),
+        "enter" => "Enter".to_string(),
+        "escape" => "Esc".to_string(),
+        "tab" => "Tab".to_string(),
+        "space" => "Space".to_string(),
+        "backspace" => "Backspace".to_string(),
+        "delete" => "Delete".to_string(),
+        f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok() => f.to_uppercase(),
+        c if c.chars().count() == 1 => c.to_string(),
+        _ => return None,
+    };
+
+    converted.push(&base);
+    Some(converted.join("+"))
+}
+
+/// Parse JSON with `//` and `/* */` comments and trailing commas
+fn parse_jsonc(text: &str) -> Result<Value> {
+    let mut json = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (',', _) => {
+                // Drop the comma if only whitespace or comments follow before a closing bracket
+                let rest: String = chars.clone().collect();
+                let next = strip_leading_comments(&rest).chars().next();
+                if !matches!(next, Some('}') | Some(']')) {
+                    json.push(c);
+                }
+            }
+            _ => json.push(c),
+        }
+    }
+
+    serde_json::from_str(&json).map_err(|e| ConfigError::ParseError(e.to_string()))
+}
+
+fn strip_leading_comments(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start();
+        if let Some(rest) = text.strip_prefix("//") {
+            text = rest.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(rest) = text.strip_prefix("/*") {
+            text = rest.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            return text;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jsonc() {
+        let value = parse_jsonc(
+            r#"{
+                // comment
+                "a": "http://x/*y*/", /* block */
+                "b": [1, 2,],
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(value["a"], "http://x/*y*/");
+        assert_eq!(value["b"], serde_json::json!([1, 2]));
+        assert!(parse_jsonc("{").is_err());
+    }
+
+    #[test]
+    fn test_import_settings() {
+        let mut config = Config::default();
+        let report = import_settings(
+            r#"{
+                "editor.tabSize": 2,
+                "editor.insertSpaces": false,
+                "editor.wordWrap": "on",
+                "editor.lineNumbers": "relative",
+                "editor.cursorStyle": "line-thin",
+                "editor.fontSize": 13,
+                "files.autoSave": "afterDelay",
+                "files.autoSaveDelay": 2000,
+                "workbench.colorTheme": "Monokai Pro",
+                "editor.fontFamily": "Fira Code",
+                "[rust]": { "editor.tabSize": 4 },
+            }"#,
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(config.editor.tab_size, 2);
+        assert!(!config.editor.insert_spaces);
+        assert!(config.editor.word_wrap);
+        assert!(config.editor.line_numbers);
+        assert!(config.editor.relative_line_numbers);
+        assert_eq!(config.editor.cursor_style, "line");
+        assert_eq!(config.editor.font_size, 13);
+        assert_eq!(config.editor.auto_save_delay, 2000);
+        assert_eq!(config.theme.name, "monokai");
+        assert!(report.applied.contains(&"editor.tabSize -> editor.tab_size = 2".to_string()));
+        let mut unmapped = report.unmapped;
+        unmapped.sort();
+        assert_eq!(
+            unmapped,
+            ["[rust] (language-specific settings)", "editor.fontFamily (no equivalent)"]
+        );
+    }
+
+    #[test]
+    fn test_import_settings_rejects_out_of_range_values() {
+        let mut config = Config::default();
+        let report = import_settings(
+            r#"{ "editor.tabSize": 40, "editor.wordWrap": true, "workbench.colorTheme": "Solarized Light" }"#,
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(config.editor.tab_size, 4);
+        assert!(report.applied.is_empty());
+        assert_eq!(report.unmapped.len(), 3);
+        assert!(import_settings("[]", &mut config).is_err());
+    }
+
+    #[test]
+    fn test_import_keybindings() {
+        let mut config = Config::default();
+        let report = import_keybindings(
+            r#"[
+                { "key": "ctrl+shift+p", "command": "workbench.action.showCommands" },
+                { "key": "alt+up", "command": "editor.action.moveLinesUpAction", "when": "editorTextFocus" },
+                { "key": "ctrl+k ctrl+s", "command": "workbench.action.openGlobalKeybindings" },
+                { "key": "cmd+s", "command": "workbench.action.files.save" },
+                { "key": "ctrl+b", "command": "-workbench.action.toggleSidebarVisibility" },
+                { "key": "ctrl+e", "command": "extension.doSomething" },
+            ]"#,
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(config.keybindings.custom["Ctrl+Shift+p"], "open_command_palette");
+        assert_eq!(config.keybindings.custom["Alt+Up"], "move_item_up");
+        assert_eq!(config.keybindings.custom.len(), 2);
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(report.unmapped.len(), 4);
+    }
+
+    #[test]
+    fn test_convert_key() {
+        assert_eq!(convert_key("ctrl+s").as_deref(), Some("Ctrl+s"));
+        assert_eq!(convert_key("shift+alt+f12").as_deref(), Some("Shift+Alt+F12"));
+        assert_eq!(convert_key("ctrl++").as_deref(), Some("Ctrl++"));
+        assert_eq!(convert_key("escape").as_deref(), Some("Esc"));
+        assert_eq!(convert_key("meta+s"), None);
+        assert_eq!(convert_key("ctrl+k ctrl+c"), None);
+        assert_eq!(convert_key(""), None);
+    }
+}
@@ -0,0 +1,304 @@
+//! Autosave and Crash Recovery
+//!
+//! Copies of unsaved buffers are written to a recovery directory once
+//! editing pauses for the autosave delay (`editor.auto_save_delay`), and
+//! removed again when the buffer is saved, closed or the editor exits
+//! normally. Copies still there at startup were left by a session that
+//! ended without saving, and can be reopened with [`recoverable`].
+
+use crate::buffer::{Buffer, BufferId};
+use crate::error::{EditorError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Recovery directory, in the config directory
+pub const RECOVERY_DIR: &str = "recovery";
+
+/// Unsaved contents of a buffer, as written by [`Autosave`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryFile {
+    /// File the buffer was editing, `None` for untitled buffers
+    pub path: Option<PathBuf>,
+    pub content: String,
+    /// Seconds since the Unix epoch when the copy was written
+    pub saved_at: u64,
+    /// Where the copy is stored
+    #[serde(skip)]
+    pub location: PathBuf,
+}
+
+impl RecoveryFile {
+    /// Modified buffer holding the recovered contents
+    pub fn to_buffer(&self) -> Buffer {
+        Buffer::unsaved(self.path.clone(), &self.content)
+    }
+
+    /// Delete the copy, once recovered or no longer wanted
+    pub fn discard(&self) -> Result<()> {
+        remove_if_exists(&self.location)
+    }
+}
+
+/// Copies left in `dir` by sessions that did not exit normally, oldest
+/// first
+///
+/// Unreadable copies are skipped.
+pub fn recoverable(dir: &Path) -> Vec<RecoveryFile> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<RecoveryFile> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|location| {
+            let content = std::fs::read_to_string(&location).ok()?;
+            let mut file: RecoveryFile = serde_json::from_str(&content)
+                .map_err(|e| warn!("Ignoring invalid recovery file {}: {}", location.display(), e))
+                .ok()?;
+            file.location = location;
+            Some(file)
+        })
+        .collect();
+    files.sort_by_key(|file| file.saved_at);
+    files
+}
+
+/// Last known state of a buffer seen by [`Autosave`]
+#[derive(Debug, Clone, Copy)]
+struct Tracked {
+    version: u64,
+    /// When `version` was first seen
+    changed: Instant,
+    /// Version of the copy on disk, if one was written
+    written: Option<u64>,
+}
+
+/// Writes recovery copies of modified buffers after a debounce delay
+///
+/// The editor reports its open buffers to [`Autosave::observe_all`]
+/// periodically; a copy is written once a buffer has gone unchanged for
+/// the delay, so typing never waits on the disk.
+#[derive(Debug)]
+pub struct Autosave {
+    dir: PathBuf,
+    delay: Duration,
+    buffers: HashMap<BufferId, Tracked>,
+}
+
+impl Autosave {
+    /// Write copies to `dir` once a buffer is left unchanged for `delay`
+    pub fn new(dir: impl Into<PathBuf>, delay: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            delay,
+            buffers: HashMap::new(),
+        }
+    }
+
+    /// Autosave with a delay in milliseconds, `None` if it is 0 (disabled)
+    pub fn from_delay_ms(dir: impl Into<PathBuf>, delay_ms: u64) -> Option<Self> {
+        (delay_ms > 0).then(|| Self::new(dir, Duration::from_millis(delay_ms)))
+    }
+
+    /// Recovery directory in the config directory, if it can be found
+    pub fn default_dir() -> Option<PathBuf> {
+        ait42_config::ConfigLoader::default_config_dir()
+            .ok()
+            .map(|dir| dir.join(RECOVERY_DIR))
+    }
+
+    /// Directory the copies are written to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Note the state of `buffer`, writing its copy if it is due
+    ///
+    /// Returns whether a copy was written. The copy of a buffer that is no
+    /// longer modified is removed.
+    pub fn observe(&mut self, buffer: &Buffer, now: Instant) -> Result<bool> {
+        if !buffer.is_dirty() {
+            self.forget(buffer.id())?;
+            return Ok(false);
+        }
+
+        let tracked = self.buffers.entry(buffer.id()).or_insert(Tracked {
+            version: buffer.version(),
+            changed: now,
+            written: None,
+        });
+        if tracked.version != buffer.version() {
+            tracked.version = buffer.version();
+            tracked.changed = now;
+        }
+        if tracked.written == Some(tracked.version)
+            || now.saturating_duration_since(tracked.changed) < self.delay
+        {
+            return Ok(false);
+        }
+
+        let version = tracked.version;
+        self.write(buffer)?;
+        if let Some(tracked) = self.buffers.get_mut(&buffer.id()) {
+            tracked.written = Some(version);
+        }
+        Ok(true)
+    }
+
+    /// Observe every open buffer, removing the copies of closed ones
+    ///
+    /// Returns how many copies were written.
+    pub fn observe_all<'a>(
+        &mut self,
+        buffers: impl IntoIterator<Item = &'a Buffer>,
+        now: Instant,
+    ) -> Result<usize> {
+        let mut open = Vec::new();
+        let mut written = 0;
+        for buffer in buffers {
+            open.push(buffer.id());
+            if self.observe(buffer, now)? {
+                written += 1;
+            }
+        }
+
+        let closed: Vec<BufferId> = self
+            .buffers
+            .keys()
+            .filter(|id| !open.contains(id))
+            .copied()
+            .collect();
+        for id in closed {
+            self.forget(id)?;
+        }
+        Ok(written)
+    }
+
+    /// Stop tracking a buffer, removing its copy
+    pub fn forget(&mut self, id: BufferId) -> Result<()> {
+        match self.buffers.remove(&id) {
+            Some(Tracked {
+                written: Some(_), ..
+            }) => remove_if_exists(&self.location(id)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove every copy this session wrote, when the editor exits normally
+    pub fn clear(&mut self) -> Result<()> {
+        let ids: Vec<BufferId> = self.buffers.keys().copied().collect();
+        for id in ids {
+            self.forget(id)?;
+        }
+        Ok(())
+    }
+
+    fn location(&self, id: BufferId) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    /// Write the copy of `buffer`, replacing the previous one atomically
+    fn write(&self, buffer: &Buffer) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let file = RecoveryFile {
+            path: buffer.path().map(Path::to_path_buf),
+            content: buffer.to_string(),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            location: PathBuf::new(),
+        };
+        let json = serde_json::to_string(&file)
+            .map_err(|e| EditorError::Other(format!("Cannot serialize recovery file: {}", e)))?;
+
+        let location = self.location(buffer.id());
+        let temp_path = location.with_extension("tmp");
+        std::fs::write(&temp_path, json)?;
+        std::fs::rename(&temp_path, &location)?;
+        debug!("Wrote recovery copy {}", location.display());
+        Ok(())
+    }
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(500);
+
+    fn edited(path: Option<&str>, text: &str) -> Buffer {
+        let mut buffer = Buffer::unsaved(path.map(PathBuf::from), "");
+        buffer.insert(0, text).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_writes_after_debounce() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut autosave = Autosave::new(dir.path(), DELAY);
+        let start = Instant::now();
+        let mut buffer = edited(Some("/project/main.rs"), "fn main() {}");
+
+        assert!(!autosave.observe(&buffer, start).unwrap());
+        assert!(recoverable(dir.path()).is_empty());
+
+        // Another edit restarts the delay
+        buffer.insert(0, "// entry\n").unwrap();
+        assert!(!autosave.observe(&buffer, start + DELAY).unwrap());
+        assert!(autosave.observe(&buffer, start + DELAY * 2).unwrap());
+        // An unchanged buffer is not written again
+        assert!(!autosave.observe(&buffer, start + DELAY * 4).unwrap());
+
+        let files = recoverable(dir.path());
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path.as_deref(), Some(Path::new("/project/main.rs")));
+        assert_eq!(files[0].content, "// entry\nfn main() {}");
+
+        let recovered = files[0].to_buffer();
+        assert!(recovered.is_dirty());
+        assert_eq!(recovered.to_string(), "// entry\nfn main() {}");
+        assert_eq!(recovered.language(), Some("rs"));
+        files[0].discard().unwrap();
+        assert!(recoverable(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_removes_copies_of_saved_and_closed_buffers() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut autosave = Autosave::new(dir.path(), Duration::ZERO);
+        let now = Instant::now();
+        let mut saved = edited(None, "draft");
+        let closed = edited(None, "scratch");
+
+        assert_eq!(autosave.observe_all([&saved, &closed], now).unwrap(), 2);
+        assert_eq!(recoverable(dir.path()).len(), 2);
+
+        saved.mark_clean();
+        assert_eq!(autosave.observe_all([&saved], now).unwrap(), 0);
+        assert!(recoverable(dir.path()).is_empty());
+
+        let unsaved = edited(None, "notes");
+        autosave.observe(&unsaved, now).unwrap();
+        autosave.clear().unwrap();
+        assert!(recoverable(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_with_zero_delay() {
+        assert!(Autosave::from_delay_ms("recovery", 0).is_none());
+        let autosave = Autosave::from_delay_ms("recovery", 2000).unwrap();
+        assert_eq!(autosave.delay, Duration::from_secs(2));
+        assert!(recoverable(Path::new("/nonexistent/recovery")).is_empty());
+    }
+}
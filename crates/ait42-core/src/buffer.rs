@@ -9,7 +9,9 @@ use std::ops::Range;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::encoding::Encoding;
 use crate::error::{EditorError, Result};
+use crate::width;
 
 pub type BufferId = Uuid;
 
@@ -39,6 +41,31 @@ impl LineEnding {
             Self::CrLf => "\r\n",
         }
     }
+
+    /// Name shown in the status bar
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Lf => "LF",
+            Self::CrLf => "CRLF",
+        }
+    }
+
+    /// The other line ending
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Lf => Self::CrLf,
+            Self::CrLf => Self::Lf,
+        }
+    }
+
+    /// `text` with every line break, LF or CRLF, replaced by this ending
+    pub fn convert(&self, text: &str) -> String {
+        let lf = text.replace("\r\n", "\n");
+        match self {
+            Self::Lf => lf,
+            Self::CrLf => lf.replace('\n', "\r\n"),
+        }
+    }
 }
 
 /// Text buffer with efficient rope-based storage
@@ -52,6 +79,9 @@ pub struct Buffer {
     version: u64,
     dirty: bool,
     line_ending: LineEnding,
+    encoding: Encoding,
+    /// Whether the file starts with a byte order mark
+    bom: bool,
     file_path: Option<PathBuf>,
     language: Option<String>,
 }
@@ -65,6 +95,8 @@ impl Buffer {
             version: 0,
             dirty: false,
             line_ending: LineEnding::Lf,
+            encoding: Encoding::Utf8,
+            bom: false,
             file_path: None,
             language: None,
         }
@@ -79,14 +111,20 @@ impl Buffer {
             version: 0,
             dirty: false,
             line_ending,
+            encoding: Encoding::Utf8,
+            bom: false,
             file_path: None,
             language,
         }
     }
 
     /// Create buffer from file
+    ///
+    /// The encoding is detected (see [`Encoding::decode`]) and kept for
+    /// saving.
     pub fn from_file(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        let decoded = Encoding::decode(&std::fs::read(path)?);
+        let content = decoded.text;
         let line_ending = LineEnding::detect(&content);
 
         // Detect language from file extension
@@ -101,11 +139,37 @@ impl Buffer {
             version: 0,
             dirty: false,
             line_ending,
+            encoding: decoded.encoding,
+            bom: decoded.bom,
             file_path: Some(path.to_path_buf()),
             language,
         })
     }
 
+    /// Create a modified buffer holding unsaved `content` for `path`
+    ///
+    /// Used to reopen edits recovered after a crash; saving writes them to
+    /// `path`.
+    pub fn unsaved(path: Option<PathBuf>, content: &str) -> Self {
+        let language = path
+            .as_deref()
+            .and_then(Path::extension)
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_string());
+
+        Self {
+            id: Uuid::new_v4(),
+            content: Rope::from_str(content),
+            version: 0,
+            dirty: true,
+            line_ending: LineEnding::detect(content),
+            encoding: Encoding::Utf8,
+            bom: false,
+            file_path: path,
+            language,
+        }
+    }
+
     /// Get buffer ID
     #[inline]
     pub fn id(&self) -> BufferId {
@@ -142,6 +206,49 @@ impl Buffer {
         self.line_ending
     }
 
+    /// Set the line ending style without touching the text
+    ///
+    /// See [`ConvertLineEndingsCommand`](crate::ConvertLineEndingsCommand)
+    /// to rewrite existing line breaks.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    /// Get the encoding the buffer is saved in
+    #[inline]
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Whether the file is saved with a byte order mark
+    #[inline]
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Save the buffer in `encoding` from now on
+    ///
+    /// UTF-16 is written with a byte order mark, so the file is detected as
+    /// UTF-16 when reopened.
+    ///
+    /// # Errors
+    /// Returns error if the text has characters `encoding` cannot represent
+    pub fn set_encoding(&mut self, encoding: Encoding) -> Result<()> {
+        if encoding == self.encoding {
+            return Ok(());
+        }
+        if !encoding.can_encode(&self.to_string()) {
+            return Err(EditorError::Encoding(format!(
+                "Buffer has characters that cannot be written as {}",
+                encoding
+            )));
+        }
+        self.encoding = encoding;
+        self.bom = matches!(encoding, Encoding::Utf16Le | Encoding::Utf16Be);
+        self.dirty = true;
+        Ok(())
+    }
+
     /// Insert text at byte offset
     ///
     /// # Errors
@@ -152,12 +259,10 @@ impl Buffer {
             return Err(EditorError::InvalidPosition(pos));
         }
 
-        // Validate UTF-8 boundary
-        if self.content.try_byte_to_char(pos).is_err() {
-            return Err(EditorError::Utf8Boundary(pos));
-        }
+        // Rope indexes by char; the byte offset must sit on a char boundary
+        let char_idx = self.byte_to_char_boundary(pos)?;
 
-        self.content.insert(pos, text);
+        self.content.insert(char_idx, text);
         self.version += 1;
         self.dirty = true;
 
@@ -175,14 +280,10 @@ impl Buffer {
         }
 
         // Validate UTF-8 boundaries
-        if self.content.try_byte_to_char(range.start).is_err() {
-            return Err(EditorError::Utf8Boundary(range.start));
-        }
-        if self.content.try_byte_to_char(range.end).is_err() {
-            return Err(EditorError::Utf8Boundary(range.end));
-        }
+        let start = self.byte_to_char_boundary(range.start)?;
+        let end = self.byte_to_char_boundary(range.end)?;
 
-        self.content.remove(range);
+        self.content.remove(start..end);
         self.version += 1;
         self.dirty = true;
 
@@ -199,21 +300,30 @@ impl Buffer {
         }
 
         // Validate UTF-8 boundaries
-        if self.content.try_byte_to_char(range.start).is_err() {
-            return Err(EditorError::Utf8Boundary(range.start));
-        }
-        if self.content.try_byte_to_char(range.end).is_err() {
-            return Err(EditorError::Utf8Boundary(range.end));
-        }
+        let start = self.byte_to_char_boundary(range.start)?;
+        let end = self.byte_to_char_boundary(range.end)?;
 
-        self.content.remove(range.clone());
-        self.content.insert(range.start, text);
+        self.content.remove(start..end);
+        self.content.insert(start, text);
         self.version += 1;
         self.dirty = true;
 
         Ok(())
     }
 
+    /// Convert a byte offset to a char index, rejecting offsets inside a char
+    fn byte_to_char_boundary(&self, pos: usize) -> Result<usize> {
+        let char_idx = self
+            .content
+            .try_byte_to_char(pos)
+            .map_err(|_| EditorError::Utf8Boundary(pos))?;
+
+        if self.content.char_to_byte(char_idx) != pos {
+            return Err(EditorError::Utf8Boundary(pos));
+        }
+        Ok(char_idx)
+    }
+
     /// Get line by index (0-based)
     ///
     /// Returns None if line index is out of bounds.
@@ -311,6 +421,32 @@ impl Buffer {
         (line, col)
     }
 
+    /// Convert byte position to (line, display column)
+    ///
+    /// The column counts terminal cells, so wide characters count twice.
+    pub fn pos_to_display_col(&self, pos: usize) -> (usize, usize) {
+        let pos = pos.min(self.content.len_bytes());
+        let line = self.content.byte_to_line(pos);
+        let line_start = self.content.line_to_byte(line);
+        let line_text = self.content.line(line).to_string();
+
+        (line, width::byte_to_display_col(&line_text, pos - line_start))
+    }
+
+    /// Convert (line, display column) to byte position
+    ///
+    /// Columns inside a wide character snap to its start and columns past
+    /// the end of the line clamp to the line end.
+    pub fn display_col_to_pos(&self, line: usize, col: usize) -> Option<usize> {
+        if line >= self.content.len_lines() {
+            return None;
+        }
+
+        let line_start = self.content.line_to_byte(line);
+        let line_text = self.content.line(line).to_string();
+        Some(line_start + width::display_col_to_byte(&line_text, col))
+    }
+
     /// Get text slice as string
     pub fn slice(&self, range: Range<usize>) -> Result<String> {
         let len = self.content.len_bytes();
@@ -318,7 +454,9 @@ impl Buffer {
             return Err(EditorError::InvalidRange(range));
         }
 
-        Ok(self.content.slice(range).to_string())
+        let start = self.byte_to_char_boundary(range.start)?;
+        let end = self.byte_to_char_boundary(range.end)?;
+        Ok(self.content.slice(start..end).to_string())
     }
 
     /// Get entire buffer content as string
@@ -351,7 +489,7 @@ impl Buffer {
     pub fn save_as(&mut self, path: &Path) -> Result<()> {
         // Atomic write: write to temp file, then rename
         let temp_path = path.with_extension(".tmp");
-        let content = self.to_string();
+        let content = self.encoding.encode(&self.to_string(), self.bom)?;
         std::fs::write(&temp_path, content)?;
         std::fs::rename(&temp_path, path)?;
 
@@ -581,6 +719,24 @@ mod tests {
         assert!(buffer.insert(100, "test").is_err());
     }
 
+    #[test]
+    fn test_buffer_edit_multibyte() {
+        let mut buffer = Buffer::from_string("日本".to_string(), None);
+        let end = buffer.len_bytes();
+
+        buffer.insert(end, "語").unwrap();
+        assert_eq!(buffer.to_string(), "日本語");
+
+        buffer.delete(0.."日".len()).unwrap();
+        assert_eq!(buffer.to_string(), "本語");
+
+        buffer.replace(0.."本".len(), "ab").unwrap();
+        assert_eq!(buffer.to_string(), "ab語");
+
+        // Offsets inside a multi-byte char are rejected
+        assert!(matches!(buffer.insert(3, "x"), Err(EditorError::Utf8Boundary(3))));
+    }
+
     #[test]
     fn test_buffer_delete() {
         let mut buffer = Buffer::from_string("Hello World".to_string(), None);
@@ -622,6 +778,18 @@ mod tests {
         assert_eq!(buffer.pos_to_line_col(11), (1, 5));
     }
 
+    #[test]
+    fn test_buffer_display_columns() {
+        let buffer = Buffer::from_string("日本\nab".to_string(), None);
+        let hon = "日".len();
+
+        assert_eq!(buffer.pos_to_display_col(hon), (0, 2));
+        assert_eq!(buffer.pos_to_display_col(buffer.len_bytes()), (1, 2));
+        assert_eq!(buffer.display_col_to_pos(0, 3), Some(hon));
+        assert_eq!(buffer.display_col_to_pos(0, 10), Some("日本".len()));
+        assert_eq!(buffer.display_col_to_pos(5, 0), None);
+    }
+
     #[test]
     fn test_buffer_slice() {
         let buffer = Buffer::from_string("Hello World".to_string(), None);
@@ -635,6 +803,39 @@ mod tests {
         assert_eq!(LineEnding::detect("Hello\r\nWorld"), LineEnding::CrLf);
     }
 
+    #[test]
+    fn test_line_ending_conversion() {
+        assert_eq!(LineEnding::CrLf.convert("a\nb\r\nc"), "a\r\nb\r\nc");
+        assert_eq!(LineEnding::Lf.convert("a\nb\r\nc"), "a\nb\nc");
+        assert_eq!(LineEnding::Lf.toggled().name(), "CRLF");
+    }
+
+    #[test]
+    fn test_save_keeps_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("utf16.txt");
+        let bytes = Encoding::Utf16Le.encode("héllo\r\n", true).unwrap();
+        std::fs::write(&path, &bytes).unwrap();
+
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        assert_eq!(buffer.to_string(), "héllo\r\n");
+        assert_eq!(buffer.encoding(), Encoding::Utf16Le);
+        assert!(buffer.has_bom());
+        assert_eq!(buffer.line_ending(), LineEnding::CrLf);
+        buffer.save().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+
+        buffer.set_encoding(Encoding::Latin1).unwrap();
+        assert!(buffer.is_dirty());
+        buffer.save().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"h\xE9llo\r\n");
+
+        buffer.insert(0, "日本").unwrap();
+        assert!(buffer.set_encoding(Encoding::Utf16Be).is_ok());
+        assert!(buffer.set_encoding(Encoding::Latin1).is_err());
+        assert_eq!(buffer.encoding(), Encoding::Utf16Be);
+    }
+
     #[test]
     fn test_buffer_manager_new_buffer() {
         let mut manager = BufferManager::new();
@@ -3,8 +3,9 @@
 //! Implements the Command pattern for undoable/redoable operations.
 
 use std::ops::Range;
+use std::time::{Duration, Instant};
 
-use crate::buffer::{Buffer, BufferId};
+use crate::buffer::{Buffer, BufferId, LineEnding};
 use crate::error::Result;
 
 /// A command that can be executed and undone
@@ -41,6 +42,44 @@ pub trait Command: Send + Sync + std::fmt::Debug {
     fn merge_with(&mut self, _other: &dyn Command) -> bool {
         false
     }
+
+    /// Describe the edit for undo coalescing
+    ///
+    /// Simple insertions and deletions return their location so consecutive
+    /// keystrokes can be folded into one undo step. Other commands return
+    /// `None` and always start a new step.
+    fn edit_kind(&self) -> Option<EditKind> {
+        None
+    }
+}
+
+/// Location of a simple edit, used to decide whether edits are contiguous
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditKind {
+    /// `len` bytes inserted at `pos`
+    Insert { pos: usize, len: usize },
+    /// Bytes in `range` deleted
+    Delete { range: Range<usize> },
+}
+
+impl EditKind {
+    /// Whether this edit directly continues `prev`
+    ///
+    /// Typing continues at the end of the previous insertion; backspace ends
+    /// where the previous deletion started and forward delete starts at the
+    /// same offset. Anything else (including a switch between inserting and
+    /// deleting) is a cursor jump and breaks the run.
+    pub fn continues(&self, prev: &EditKind) -> bool {
+        match (prev, self) {
+            (EditKind::Insert { pos, len }, EditKind::Insert { pos: next, .. }) => {
+                *next == pos + len
+            }
+            (EditKind::Delete { range }, EditKind::Delete { range: next }) => {
+                next.end == range.start || next.start == range.start
+            }
+            _ => false,
+        }
+    }
 }
 
 /// Insert text at position
@@ -75,6 +114,13 @@ impl Command for InsertCommand {
         "Insert text"
     }
 
+    fn edit_kind(&self) -> Option<EditKind> {
+        Some(EditKind::Insert {
+            pos: self.pos,
+            len: self.text.len(),
+        })
+    }
+
     fn merge_with(&mut self, _other: &dyn Command) -> bool {
         // TODO: Implement merge logic using a different approach
         // Downcasting requires 'static lifetime which trait methods can't enforce
@@ -114,6 +160,12 @@ impl Command for DeleteCommand {
     fn description(&self) -> &str {
         "Delete text"
     }
+
+    fn edit_kind(&self) -> Option<EditKind> {
+        Some(EditKind::Delete {
+            range: self.range.clone(),
+        })
+    }
 }
 
 /// Replace text in range
@@ -153,15 +205,153 @@ impl Command for ReplaceCommand {
     }
 }
 
+/// Rewrite every line break in the buffer to one line ending
+#[derive(Debug, Clone)]
+pub struct ConvertLineEndingsCommand {
+    buffer_id: BufferId,
+    line_ending: LineEnding,
+    /// Text and line ending before execute, for undo
+    old: Option<(String, LineEnding)>,
+}
+
+impl ConvertLineEndingsCommand {
+    pub fn new(buffer_id: BufferId, line_ending: LineEnding) -> Self {
+        Self {
+            buffer_id,
+            line_ending,
+            old: None,
+        }
+    }
+
+    /// Buffer the command applies to
+    pub fn buffer_id(&self) -> BufferId {
+        self.buffer_id
+    }
+}
+
+impl Command for ConvertLineEndingsCommand {
+    fn execute(&mut self, buffer: &mut Buffer) -> Result<()> {
+        let text = buffer.to_string();
+        let converted = self.line_ending.convert(&text);
+        if converted != text {
+            buffer.replace(0..text.len(), &converted)?;
+        }
+        self.old = Some((text, buffer.line_ending()));
+        buffer.set_line_ending(self.line_ending);
+        Ok(())
+    }
+
+    fn undo(&mut self, buffer: &mut Buffer) -> Result<()> {
+        let Some((text, line_ending)) = &self.old else {
+            return Ok(());
+        };
+        if buffer.to_string() != *text {
+            buffer.replace(0..buffer.len_bytes(), text)?;
+        }
+        buffer.set_line_ending(*line_ending);
+        Ok(())
+    }
+
+    fn description(&self) -> &str {
+        "Convert line endings"
+    }
+}
+
+/// Several commands undone and redone as one step
+///
+/// Built either implicitly, by coalescing consecutive keystrokes, or
+/// explicitly with [`CommandHistory::begin_group`] for complex commands.
+#[derive(Debug)]
+pub struct CommandGroup {
+    description: String,
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl CommandGroup {
+    /// Create an empty group
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Append an already executed command
+    pub fn push(&mut self, cmd: Box<dyn Command>) {
+        self.commands.push(cmd);
+    }
+
+    /// Number of commands in the group
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Check if the group is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl Command for CommandGroup {
+    fn execute(&mut self, buffer: &mut Buffer) -> Result<()> {
+        for cmd in &mut self.commands {
+            cmd.execute(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self, buffer: &mut Buffer) -> Result<()> {
+        for cmd in self.commands.iter_mut().rev() {
+            cmd.undo(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn can_undo(&self) -> bool {
+        self.commands.iter().all(|cmd| cmd.can_undo())
+    }
+
+    fn merge_with(&mut self, other: &dyn Command) -> bool {
+        self.commands
+            .last_mut()
+            .is_some_and(|last| last.merge_with(other))
+    }
+}
+
+/// Open run of coalesced edits at the top of the undo stack
+#[derive(Debug, Clone)]
+struct CoalesceRun {
+    session: u64,
+    last: EditKind,
+    at: Instant,
+}
+
 /// Command history for undo/redo
 ///
-/// Maintains two stacks: undo and redo.
-/// Executing a new command clears the redo stack.
+/// Maintains two stacks of undo steps. Executing a new command clears the
+/// redo stack.
+///
+/// Each step is a [`CommandGroup`]. Edits pushed with
+/// [`push_coalescing`](Self::push_coalescing) fold into the previous step
+/// while they belong to the same insert session, stay contiguous, and
+/// arrive within the coalesce timeout.
 #[derive(Debug)]
 pub struct CommandHistory {
-    undo_stack: Vec<Box<dyn Command>>,
-    redo_stack: Vec<Box<dyn Command>>,
+    undo_stack: Vec<CommandGroup>,
+    redo_stack: Vec<CommandGroup>,
     max_history: usize,
+    /// Explicit group being recorded and its nesting depth
+    open_group: Option<CommandGroup>,
+    group_depth: usize,
+    /// Coalescing state of the top undo step
+    run: Option<CoalesceRun>,
+    coalesce_timeout: Duration,
 }
 
 impl CommandHistory {
@@ -176,22 +366,82 @@ impl CommandHistory {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             max_history,
+            open_group: None,
+            group_depth: 0,
+            run: None,
+            coalesce_timeout: Duration::from_secs(1),
         }
     }
 
-    /// Push command to undo stack
+    /// Set the idle time after which typing starts a new undo step
+    pub fn set_coalesce_timeout(&mut self, timeout: Duration) {
+        self.coalesce_timeout = timeout;
+    }
+
+    /// Push command to undo stack as its own step
     ///
     /// Clears redo stack as new commands invalidate redo history.
     pub fn push(&mut self, cmd: Box<dyn Command>) {
-        // Try to merge with last command
-        if let Some(last) = self.undo_stack.last_mut() {
-            if last.merge_with(cmd.as_ref()) {
+        self.push_at(cmd, None, Instant::now());
+    }
+
+    /// Push an edit made during insert session `session`
+    ///
+    /// Consecutive contiguous edits of the same session are undone together.
+    pub fn push_coalescing(&mut self, cmd: Box<dyn Command>, session: u64) {
+        self.push_at(cmd, Some(session), Instant::now());
+    }
+
+    fn push_at(&mut self, cmd: Box<dyn Command>, session: Option<u64>, now: Instant) {
+        self.redo_stack.clear();
+
+        // Explicit groups swallow everything until they are closed
+        if let Some(group) = self.open_group.as_mut() {
+            group.push(cmd);
+            return;
+        }
+
+        let kind = cmd.edit_kind();
+        let continues = match (&self.run, session, &kind) {
+            (Some(run), Some(session), Some(kind)) => {
+                run.session == session
+                    && now.saturating_duration_since(run.at) <= self.coalesce_timeout
+                    && kind.continues(&run.last)
+            }
+            _ => false,
+        };
+
+        if let Some(top) = self.undo_stack.last_mut() {
+            if continues {
+                top.push(cmd);
+                self.run = Self::next_run(session, kind, now);
+                return;
+            }
+
+            // Try to merge with last command
+            if top.merge_with(cmd.as_ref()) {
+                self.run = Self::next_run(session, kind, now);
                 return;
             }
         }
 
-        self.undo_stack.push(cmd);
-        self.redo_stack.clear();
+        let mut group = CommandGroup::new(cmd.description());
+        group.push(cmd);
+        self.push_step(group);
+
+        self.run = Self::next_run(session, kind, now);
+    }
+
+    fn next_run(session: Option<u64>, kind: Option<EditKind>, at: Instant) -> Option<CoalesceRun> {
+        Some(CoalesceRun {
+            session: session?,
+            last: kind?,
+            at,
+        })
+    }
+
+    fn push_step(&mut self, group: CommandGroup) {
+        self.undo_stack.push(group);
 
         // Limit history size
         if self.undo_stack.len() > self.max_history {
@@ -199,13 +449,64 @@ impl CommandHistory {
         }
     }
 
+    /// Start an explicit undo group
+    ///
+    /// Every command pushed until the matching [`end_group`](Self::end_group)
+    /// becomes a single undo step. Groups nest; only the outermost
+    /// description is kept.
+    pub fn begin_group(&mut self, description: impl Into<String>) {
+        if self.group_depth == 0 {
+            self.open_group = Some(CommandGroup::new(description));
+            self.run = None;
+        }
+        self.group_depth += 1;
+    }
+
+    /// Close the innermost explicit undo group
+    pub fn end_group(&mut self) {
+        if self.group_depth == 0 {
+            return;
+        }
+        self.group_depth -= 1;
+
+        if self.group_depth == 0 {
+            if let Some(group) = self.open_group.take() {
+                if !group.is_empty() {
+                    self.push_step(group);
+                }
+            }
+        }
+    }
+
+    /// Check if an explicit group is being recorded
+    #[inline]
+    pub fn is_grouping(&self) -> bool {
+        self.group_depth > 0
+    }
+
+    /// End the current coalescing run so the next edit starts a new step
+    ///
+    /// Call on cursor jumps and when leaving insert mode.
+    pub fn break_coalescing(&mut self) {
+        self.run = None;
+    }
+
+    /// Close any open group and coalescing run
+    fn seal(&mut self) {
+        while self.is_grouping() {
+            self.end_group();
+        }
+        self.run = None;
+    }
+
     /// Undo last command
     ///
     /// Returns the undone command if successful.
     pub fn undo(&mut self, buffer: &mut Buffer) -> Result<bool> {
-        if let Some(mut cmd) = self.undo_stack.pop() {
-            cmd.undo(buffer)?;
-            self.redo_stack.push(cmd);
+        self.seal();
+        if let Some(mut step) = self.undo_stack.pop() {
+            step.undo(buffer)?;
+            self.redo_stack.push(step);
             Ok(true)
         } else {
             Ok(false)
@@ -216,9 +517,10 @@ impl CommandHistory {
     ///
     /// Returns the redone command if successful.
     pub fn redo(&mut self, buffer: &mut Buffer) -> Result<bool> {
-        if let Some(mut cmd) = self.redo_stack.pop() {
-            cmd.execute(buffer)?;
-            self.undo_stack.push(cmd);
+        self.seal();
+        if let Some(mut step) = self.redo_stack.pop() {
+            step.execute(buffer)?;
+            self.undo_stack.push(step);
             Ok(true)
         } else {
             Ok(false)
@@ -228,7 +530,7 @@ impl CommandHistory {
     /// Check if undo is available
     #[inline]
     pub fn can_undo(&self) -> bool {
-        !self.undo_stack.is_empty()
+        !self.undo_stack.is_empty() || self.open_group.as_ref().is_some_and(|g| !g.is_empty())
     }
 
     /// Check if redo is available
@@ -253,6 +555,9 @@ impl CommandHistory {
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.open_group = None;
+        self.group_depth = 0;
+        self.run = None;
     }
 }
 
@@ -350,4 +655,135 @@ mod tests {
 
         assert!(!history.can_redo());
     }
+
+    /// Execute `cmd` on `buffer` and record it the way `EditorState` does
+    fn type_at(
+        history: &mut CommandHistory,
+        buffer: &mut Buffer,
+        pos: usize,
+        text: &str,
+        session: u64,
+        now: Instant,
+    ) {
+        let mut cmd = Box::new(InsertCommand::new(buffer.id(), pos, text));
+        cmd.execute(buffer).unwrap();
+        history.push_at(cmd, Some(session), now);
+    }
+
+    #[test]
+    fn test_insert_session_coalesces() {
+        let mut buffer = Buffer::new();
+        let mut history = CommandHistory::new();
+        let now = Instant::now();
+
+        for (i, ch) in ["H", "e", "y"].iter().enumerate() {
+            type_at(&mut history, &mut buffer, i, ch, 1, now);
+        }
+        assert_eq!(buffer.to_string(), "Hey");
+        assert_eq!(history.undo_len(), 1);
+
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "");
+
+        history.redo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "Hey");
+    }
+
+    #[test]
+    fn test_coalescing_breaks_on_jump_session_and_timeout() {
+        let mut buffer = Buffer::new();
+        let mut history = CommandHistory::new();
+        let now = Instant::now();
+
+        type_at(&mut history, &mut buffer, 0, "ab", 1, now);
+        // Cursor jump back to the start
+        type_at(&mut history, &mut buffer, 0, "x", 1, now);
+        assert_eq!(history.undo_len(), 2);
+
+        // New insert session at a contiguous position
+        type_at(&mut history, &mut buffer, 3, "c", 2, now);
+        assert_eq!(history.undo_len(), 3);
+
+        // Same session but after the idle timeout
+        let later = now + Duration::from_secs(5);
+        type_at(&mut history, &mut buffer, 4, "d", 2, later);
+        assert_eq!(history.undo_len(), 4);
+
+        // Explicit break
+        history.break_coalescing();
+        type_at(&mut history, &mut buffer, 5, "e", 2, later);
+        assert_eq!(history.undo_len(), 5);
+        assert_eq!(buffer.to_string(), "xabcde");
+    }
+
+    #[test]
+    fn test_backspace_run_coalesces() {
+        let mut buffer = Buffer::from_string("hello".to_string(), None);
+        let mut history = CommandHistory::new();
+        let now = Instant::now();
+
+        for end in (3..=5).rev() {
+            let mut cmd = Box::new(DeleteCommand::new(buffer.id(), end - 1..end));
+            cmd.execute(&mut buffer).unwrap();
+            history.push_at(cmd, Some(1), now);
+        }
+        assert_eq!(buffer.to_string(), "he");
+        assert_eq!(history.undo_len(), 1);
+
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "hello");
+    }
+
+    #[test]
+    fn test_explicit_group() {
+        let mut buffer = Buffer::from_string("one two".to_string(), None);
+        let mut history = CommandHistory::new();
+
+        history.begin_group("Swap words");
+        let mut first = Box::new(ReplaceCommand::new(buffer.id(), 4..7, "one".to_string()));
+        first.execute(&mut buffer).unwrap();
+        history.push(first);
+
+        // Nested groups fold into the outer one
+        history.begin_group("inner");
+        let mut second = Box::new(ReplaceCommand::new(buffer.id(), 0..3, "two".to_string()));
+        second.execute(&mut buffer).unwrap();
+        history.push(second);
+        history.end_group();
+        assert!(history.is_grouping());
+        history.end_group();
+
+        assert_eq!(buffer.to_string(), "two one");
+        assert_eq!(history.undo_len(), 1);
+        assert_eq!(history.undo_stack[0].description(), "Swap words");
+
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "one two");
+    }
+
+    #[test]
+    fn test_empty_group_is_dropped() {
+        let mut history = CommandHistory::new();
+        history.begin_group("noop");
+        history.end_group();
+        assert!(!history.can_undo());
+        assert_eq!(history.undo_len(), 0);
+    }
+
+    #[test]
+    fn test_convert_line_endings() {
+        let mut buffer = Buffer::from_string("a\nb\r\nc\n".to_string(), None);
+        let mut history = CommandHistory::new();
+
+        let mut cmd = Box::new(ConvertLineEndingsCommand::new(buffer.id(), LineEnding::CrLf));
+        cmd.execute(&mut buffer).unwrap();
+        history.push(cmd);
+        assert_eq!(buffer.to_string(), "a\r\nb\r\nc\r\n");
+        assert_eq!(buffer.line_ending(), LineEnding::CrLf);
+
+        history.undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "a\nb\r\nc\n");
+        history.redo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "a\r\nb\r\nc\r\n");
+    }
 }
@@ -20,6 +20,20 @@ impl CursorPosition {
     }
 }
 
+/// Index of the grapheme starting at (or containing) byte offset `pos`
+///
+/// Offsets at the end of the text map to `graphemes.len()`.
+fn grapheme_index(graphemes: &[&str], pos: usize) -> usize {
+    let mut byte_pos = 0;
+    for (idx, g) in graphemes.iter().enumerate() {
+        if byte_pos >= pos {
+            return idx;
+        }
+        byte_pos += g.len();
+    }
+    graphemes.len()
+}
+
 /// Editor cursor with position and optional selection anchor
 ///
 /// The cursor is always at a byte offset. When a selection is active,
@@ -30,7 +44,7 @@ pub struct Cursor {
     position: usize,
     /// Selection anchor (byte offset) - None means no selection
     anchor: Option<usize>,
-    /// Preferred column for vertical movement (preserves column across lines)
+    /// Preferred display column for vertical movement (preserves column across lines)
     preferred_col: Option<usize>,
 }
 
@@ -62,9 +76,31 @@ impl Cursor {
         CursorPosition::new(line, col)
     }
 
+    /// Get cursor position as (line, display column)
+    ///
+    /// Use this for anything drawn on the terminal grid; wide characters
+    /// occupy two columns and combining marks none.
+    pub fn display_position(&self, buffer: &Buffer) -> CursorPosition {
+        let (line, col) = buffer.pos_to_display_col(self.position);
+        CursorPosition::new(line, col)
+    }
+
     /// Move to specific line and column
     pub fn move_to(&mut self, buffer: &Buffer, line: usize, col: usize) -> Result<()> {
         if let Some(pos) = buffer.line_col_to_pos(line, col) {
+            self.position = pos;
+            self.preferred_col = Some(buffer.pos_to_display_col(pos).1);
+            Ok(())
+        } else {
+            Err(EditorError::InvalidLineCol { line, col })
+        }
+    }
+
+    /// Move to specific line and display column
+    ///
+    /// Columns inside a wide character snap to its start.
+    pub fn move_to_display(&mut self, buffer: &Buffer, line: usize, col: usize) -> Result<()> {
+        if let Some(pos) = buffer.display_col_to_pos(line, col) {
             self.position = pos;
             self.preferred_col = Some(col);
             Ok(())
@@ -79,16 +115,7 @@ impl Cursor {
         let graphemes: Vec<&str> = text.graphemes(true).collect();
 
         // Find current grapheme index
-        let mut byte_pos = 0;
-        let mut grapheme_idx = 0;
-
-        for (idx, g) in graphemes.iter().enumerate() {
-            if byte_pos >= self.position {
-                grapheme_idx = idx;
-                break;
-            }
-            byte_pos += g.len();
-        }
+        let grapheme_idx = grapheme_index(&graphemes, self.position);
 
         // Move left by count
         let new_idx = grapheme_idx.saturating_sub(count);
@@ -104,16 +131,7 @@ impl Cursor {
         let graphemes: Vec<&str> = text.graphemes(true).collect();
 
         // Find current grapheme index
-        let mut byte_pos = 0;
-        let mut grapheme_idx = 0;
-
-        for (idx, g) in graphemes.iter().enumerate() {
-            if byte_pos >= self.position {
-                grapheme_idx = idx;
-                break;
-            }
-            byte_pos += g.len();
-        }
+        let grapheme_idx = grapheme_index(&graphemes, self.position);
 
         // Move right by count
         let new_idx = (grapheme_idx + count).min(graphemes.len());
@@ -125,56 +143,26 @@ impl Cursor {
 
     /// Move cursor up by `count` lines
     ///
-    /// Preserves preferred column across lines of different lengths.
+    /// Preserves preferred display column across lines of different lengths.
     pub fn move_up(&mut self, buffer: &Buffer, count: usize) {
-        let (mut line, col) = buffer.pos_to_line_col(self.position);
-
-        // Set preferred column if not set
-        if self.preferred_col.is_none() {
-            self.preferred_col = Some(col);
-        }
-
-        // Move up
-        line = line.saturating_sub(count);
-
-        // Use preferred column or current column
-        let target_col = self.preferred_col.unwrap_or(col);
-
-        // Clamp to line length
-        if let Some(line_text) = buffer.line(line) {
-            let line_len = line_text.graphemes(true).count();
-            let actual_col = target_col.min(line_len);
-
-            if let Some(pos) = buffer.line_col_to_pos(line, actual_col) {
-                self.position = pos;
-            }
-        }
+        let (line, col) = buffer.pos_to_display_col(self.position);
+        self.move_vertical(buffer, line.saturating_sub(count), col);
     }
 
     /// Move cursor down by `count` lines
     pub fn move_down(&mut self, buffer: &Buffer, count: usize) {
-        let (mut line, col) = buffer.pos_to_line_col(self.position);
-
-        // Set preferred column if not set
-        if self.preferred_col.is_none() {
-            self.preferred_col = Some(col);
-        }
-
-        // Move down
+        let (line, col) = buffer.pos_to_display_col(self.position);
         let max_line = buffer.len_lines().saturating_sub(1);
-        line = (line + count).min(max_line);
-
-        // Use preferred column or current column
-        let target_col = self.preferred_col.unwrap_or(col);
+        self.move_vertical(buffer, (line + count).min(max_line), col);
+    }
 
-        // Clamp to line length
-        if let Some(line_text) = buffer.line(line) {
-            let line_len = line_text.graphemes(true).count();
-            let actual_col = target_col.min(line_len);
+    fn move_vertical(&mut self, buffer: &Buffer, line: usize, col: usize) {
+        // Set preferred column if not set
+        let target_col = *self.preferred_col.get_or_insert(col);
 
-            if let Some(pos) = buffer.line_col_to_pos(line, actual_col) {
-                self.position = pos;
-            }
+        // Clamps to line length and snaps out of wide characters
+        if let Some(pos) = buffer.display_col_to_pos(line, target_col) {
+            self.position = pos;
         }
     }
 
@@ -190,14 +178,10 @@ impl Cursor {
     /// Move to end of current line
     pub fn move_to_line_end(&mut self, buffer: &Buffer) {
         let (line, _) = buffer.pos_to_line_col(self.position);
-        if let Some(line_text) = buffer.line(line) {
-            // Strip trailing newline before counting characters
-            let text_without_newline = line_text.trim_end_matches(&['\n', '\r'][..]);
-            let line_len = text_without_newline.graphemes(true).count();
-            if let Some(pos) = buffer.line_col_to_pos(line, line_len) {
-                self.position = pos;
-                self.preferred_col = None;
-            }
+        // Display columns clamp before the line ending
+        if let Some(pos) = buffer.display_col_to_pos(line, usize::MAX) {
+            self.position = pos;
+            self.preferred_col = None;
         }
     }
 
@@ -443,6 +427,61 @@ mod tests {
         assert_eq!(line, 0);
     }
 
+    #[test]
+    fn test_cursor_vertical_keeps_display_column() {
+        // "日本" is 4 cells wide, so column 4 on line 0 lines up with "ab|cd"
+        let buffer = Buffer::from_string("日本語\nabcdef".to_string(), None);
+        let mut cursor = Cursor::new("日本".len());
+
+        assert_eq!(cursor.display_position(&buffer), CursorPosition::new(0, 4));
+        assert_eq!(cursor.position(&buffer), CursorPosition::new(0, 2));
+
+        cursor.move_down(&buffer, 1);
+        assert_eq!(cursor.display_position(&buffer), CursorPosition::new(1, 4));
+
+        cursor.move_up(&buffer, 1);
+        assert_eq!(cursor.pos(), "日本".len());
+    }
+
+    #[test]
+    fn test_cursor_vertical_snaps_out_of_wide_char() {
+        let buffer = Buffer::from_string("abc\n日本".to_string(), None);
+        let mut cursor = Cursor::new(1);
+
+        // Column 1 lands in the right half of 日, which snaps to its start
+        cursor.move_down(&buffer, 1);
+        assert_eq!(cursor.display_position(&buffer), CursorPosition::new(1, 0));
+
+        // The preferred column survives the round trip
+        cursor.move_up(&buffer, 1);
+        assert_eq!(cursor.pos(), 1);
+    }
+
+    #[test]
+    fn test_cursor_move_left_from_buffer_end() {
+        let buffer = Buffer::from_string("ab日".to_string(), None);
+        let mut cursor = Cursor::new(buffer.len_bytes());
+
+        cursor.move_left(&buffer, 1);
+        assert_eq!(cursor.pos(), 2);
+
+        cursor.move_right(&buffer, 5);
+        assert_eq!(cursor.pos(), buffer.len_bytes());
+    }
+
+    #[test]
+    fn test_cursor_grapheme_movement() {
+        let buffer = Buffer::from_string("e\u{301}x".to_string(), None);
+        let mut cursor = Cursor::new(0);
+
+        cursor.move_right(&buffer, 1);
+        assert_eq!(cursor.pos(), "e\u{301}".len());
+        assert_eq!(cursor.display_position(&buffer).col, 1);
+
+        cursor.move_to_line_end(&buffer);
+        assert_eq!(cursor.pos(), buffer.len_bytes());
+    }
+
     #[test]
     fn test_cursor_line_start_end() {
         let buffer = Buffer::from_string("Hello World".to_string(), None);
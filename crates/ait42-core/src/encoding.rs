@@ -0,0 +1,270 @@
+//! Text Encodings
+//!
+//! Files are decoded to UTF-8 for editing and encoded back in their original
+//! encoding when saved, byte order mark included, so opening and saving a
+//! file leaves it unchanged. UTF-8, UTF-16 (either byte order) and Latin-1
+//! are supported; Latin-1 maps every byte to a char, so it is the fallback
+//! for anything that decodes as neither UTF-8 nor UTF-16.
+
+use crate::error::{EditorError, Result};
+use std::fmt;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Encoding of a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1
+    Latin1,
+}
+
+/// Text decoded from a file, with what is needed to encode it back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub text: String,
+    pub encoding: Encoding,
+    /// Whether the file starts with a byte order mark
+    pub bom: bool,
+}
+
+impl Encoding {
+    /// All supported encodings
+    pub const ALL: [Encoding; 4] = [
+        Encoding::Utf8,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Latin1,
+    ];
+
+    /// Name shown in the status bar and accepted by [`Encoding::from_name`]
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Latin1 => "Latin-1",
+        }
+    }
+
+    /// Encoding by name, ignoring case and dashes (`utf8`, `UTF-16le`,
+    /// `latin1`, `iso-8859-1`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase().replace(['-', '_'], "");
+        match name.as_str() {
+            "utf8" => Some(Encoding::Utf8),
+            "utf16" | "utf16le" => Some(Encoding::Utf16Le),
+            "utf16be" => Some(Encoding::Utf16Be),
+            "latin1" | "iso88591" => Some(Encoding::Latin1),
+            _ => None,
+        }
+    }
+
+    /// Byte order mark written at the start of files in this encoding
+    pub fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => UTF8_BOM,
+            Encoding::Utf16Le => UTF16_LE_BOM,
+            Encoding::Utf16Be => UTF16_BE_BOM,
+            Encoding::Latin1 => &[],
+        }
+    }
+
+    /// Decode `bytes`, detecting their encoding
+    ///
+    /// A byte order mark decides the encoding; without one, bytes are UTF-16
+    /// if they look like ASCII text with every other byte zero (which is
+    /// also valid UTF-8), UTF-8 if valid, and Latin-1 otherwise.
+    pub fn decode(bytes: &[u8]) -> Decoded {
+        let with_bom = [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be]
+            .into_iter()
+            .find(|encoding| bytes.starts_with(encoding.bom()));
+        if let Some(encoding) = with_bom {
+            if let Some(text) = encoding.decode_exact(&bytes[encoding.bom().len()..]) {
+                return Decoded {
+                    text,
+                    encoding,
+                    bom: true,
+                };
+            }
+        }
+
+        let encoding = match utf16_without_bom(bytes) {
+            Some(encoding) => encoding,
+            None if std::str::from_utf8(bytes).is_ok() => Encoding::Utf8,
+            None => Encoding::Latin1,
+        };
+        match encoding.decode_exact(bytes) {
+            Some(text) => Decoded {
+                text,
+                encoding,
+                bom: false,
+            },
+            None => Decoded {
+                text: decode_latin1(bytes),
+                encoding: Encoding::Latin1,
+                bom: false,
+            },
+        }
+    }
+
+    /// Decode `bytes` (without byte order mark), `None` if they are not
+    /// valid in this encoding
+    fn decode_exact(self, bytes: &[u8]) -> Option<String> {
+        match self {
+            Encoding::Utf8 => String::from_utf8(bytes.to_vec()).ok(),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                if bytes.len() % 2 != 0 {
+                    return None;
+                }
+                let units = bytes.chunks_exact(2).map(|pair| {
+                    let pair = [pair[0], pair[1]];
+                    if self == Encoding::Utf16Le {
+                        u16::from_le_bytes(pair)
+                    } else {
+                        u16::from_be_bytes(pair)
+                    }
+                });
+                char::decode_utf16(units)
+                    .collect::<std::result::Result<String, _>>()
+                    .ok()
+            }
+            Encoding::Latin1 => Some(decode_latin1(bytes)),
+        }
+    }
+
+    /// Whether every char of `text` can be written in this encoding
+    pub fn can_encode(self, text: &str) -> bool {
+        self != Encoding::Latin1 || text.chars().all(|c| u32::from(c) <= 0xFF)
+    }
+
+    /// Encode `text`, starting with a byte order mark if `bom` is set
+    ///
+    /// # Errors
+    /// Returns an error if `text` has a char the encoding cannot represent
+    pub fn encode(self, text: &str, bom: bool) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if bom {
+            bytes.extend_from_slice(self.bom());
+        }
+        match self {
+            Encoding::Utf8 => bytes.extend_from_slice(text.as_bytes()),
+            Encoding::Utf16Le => {
+                bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            }
+            Encoding::Utf16Be => {
+                bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+            }
+            Encoding::Latin1 => {
+                for c in text.chars() {
+                    let byte = u8::try_from(u32::from(c)).map_err(|_| {
+                        EditorError::Encoding(format!("'{}' cannot be written as {}", c, self))
+                    })?;
+                    bytes.push(byte);
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| char::from(byte)).collect()
+}
+
+/// UTF-16 byte order of text without a byte order mark, guessed from where
+/// its zero bytes are
+fn utf16_without_bom(bytes: &[u8]) -> Option<Encoding> {
+    if bytes.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let zeros_at = |offset: usize| {
+        bytes
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count()
+    };
+    // ASCII text has a zero high byte in nearly every unit
+    if zeros_at(1) * 10 >= pairs * 9 && zeros_at(0) == 0 {
+        Some(Encoding::Utf16Le)
+    } else if zeros_at(0) * 10 >= pairs * 9 && zeros_at(1) == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(bytes: &[u8]) -> Decoded {
+        let decoded = Encoding::decode(bytes);
+        assert_eq!(decoded.encoding.encode(&decoded.text, decoded.bom).unwrap(), bytes);
+        decoded
+    }
+
+    #[test]
+    fn test_detects_and_round_trips() {
+        let decoded = round_trip("héllo\n".as_bytes());
+        assert_eq!((decoded.encoding, decoded.bom), (Encoding::Utf8, false));
+
+        let decoded = round_trip(b"\xEF\xBB\xBFbom\n");
+        assert_eq!((decoded.encoding, decoded.bom), (Encoding::Utf8, true));
+        assert_eq!(decoded.text, "bom\n");
+
+        let decoded = round_trip(b"\xFF\xFEh\0i\0");
+        assert_eq!((decoded.encoding, decoded.bom), (Encoding::Utf16Le, true));
+        assert_eq!(decoded.text, "hi");
+
+        let decoded = round_trip(b"\xFE\xFF\0h\0i");
+        assert_eq!((decoded.encoding, decoded.bom), (Encoding::Utf16Be, true));
+
+        let decoded = round_trip(b"h\0i\0\n\0");
+        assert_eq!((decoded.encoding, decoded.bom), (Encoding::Utf16Le, false));
+        assert_eq!(decoded.text, "hi\n");
+
+        let decoded = round_trip(b"caf\xE9\n");
+        assert_eq!(decoded.encoding, Encoding::Latin1);
+        assert_eq!(decoded.text, "café\n");
+    }
+
+    #[test]
+    fn test_invalid_utf16_falls_back_to_latin1() {
+        // Unpaired surrogate after a UTF-16LE byte order mark
+        let decoded = round_trip(b"\xFF\xFE\x00\xD8");
+        assert_eq!(decoded.encoding, Encoding::Latin1);
+    }
+
+    #[test]
+    fn test_encode_rejects_unrepresentable_chars() {
+        assert!(Encoding::Latin1.can_encode("café"));
+        assert!(!Encoding::Latin1.can_encode("日本"));
+        assert!(Encoding::Latin1.encode("日本", false).is_err());
+        assert_eq!(Encoding::Utf16Be.encode("a", true).unwrap(), b"\xFE\xFF\0a");
+    }
+
+    #[test]
+    fn test_names() {
+        for encoding in Encoding::ALL {
+            assert_eq!(Encoding::from_name(encoding.name()), Some(encoding));
+        }
+        assert_eq!(Encoding::from_name("iso-8859-1"), Some(Encoding::Latin1));
+        assert_eq!(Encoding::from_name("utf16"), Some(Encoding::Utf16Le));
+        assert_eq!(Encoding::from_name("ebcdic"), None);
+    }
+}
@@ -50,6 +50,21 @@ pub enum EditorError {
     #[error("Invalid grapheme cluster at position {0}")]
     InvalidGrapheme(usize),
 
+    #[error("Syntax error: {0}")]
+    Syntax(String),
+
+    #[error("Cannot refactor: {0}")]
+    Refactor(String),
+
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Encoding error: {0}")]
+    Encoding(String),
+
+    #[error("Assertion failed: {0}")]
+    Assertion(String),
+
     #[error("{0}")]
     Other(String),
 }
@@ -0,0 +1,378 @@
+//! Code Folding
+//!
+//! Fold ranges come from indentation: a line followed by more deeply
+//! indented lines starts a fold over them, blank lines between them included
+//! and blank lines after them left out. [`FoldMap`] keeps the folds closed in
+//! one buffer and maps buffer lines to the lines left visible, so views skip
+//! folded regions when drawing and moving the cursor.
+
+use std::ops::Range;
+
+/// Columns a tab indents by
+const TAB_WIDTH: usize = 4;
+
+/// Foldable range of lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fold {
+    /// First line, which stays visible when the fold is closed
+    pub start: usize,
+    /// Last line, inclusive
+    pub end: usize,
+}
+
+impl Fold {
+    /// Whether `line` is in the fold, its first line included
+    pub fn contains(&self, line: usize) -> bool {
+        self.start <= line && line <= self.end
+    }
+
+    /// Whether closing the fold hides `line`
+    pub fn hides(&self, line: usize) -> bool {
+        self.start < line && line <= self.end
+    }
+
+    /// Number of lines hidden when the fold is closed
+    pub fn hidden_lines(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Indentation of `line` in columns, `None` if it is blank
+fn indent_width(line: &str) -> Option<usize> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    let width = line
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum();
+    Some(width)
+}
+
+/// Folds of `text` by indentation, ordered by first line
+///
+/// Folds nest: an indented block inside a fold has a fold of its own.
+pub fn indent_folds(text: &str) -> Vec<Fold> {
+    let mut folds = Vec::new();
+    // Lines that may start a fold, with their indentation
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut last_content = 0;
+
+    for (line, content) in text.lines().enumerate() {
+        let Some(indent) = indent_width(content) else {
+            continue;
+        };
+        while let Some(&(start, level)) = open.last() {
+            if indent > level {
+                break;
+            }
+            open.pop();
+            if last_content > start {
+                folds.push(Fold {
+                    start,
+                    end: last_content,
+                });
+            }
+        }
+        open.push((line, indent));
+        last_content = line;
+    }
+    for (start, _) in open {
+        if last_content > start {
+            folds.push(Fold {
+                start,
+                end: last_content,
+            });
+        }
+    }
+
+    folds.sort_by_key(|fold| fold.start);
+    folds
+}
+
+/// Closed folds of a buffer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldMap {
+    /// Ordered by first line
+    closed: Vec<Fold>,
+}
+
+impl FoldMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Closed folds, ordered by first line
+    pub fn closed(&self) -> &[Fold] {
+        &self.closed
+    }
+
+    /// Whether no fold is closed
+    pub fn is_empty(&self) -> bool {
+        self.closed.is_empty()
+    }
+
+    fn close(&mut self, fold: Fold) {
+        let index = self
+            .closed
+            .partition_point(|closed| closed.start < fold.start);
+        self.closed.insert(index, fold);
+    }
+
+    /// Close the innermost open fold of `text` containing `line`
+    ///
+    /// Closing again closes the fold around it. Returns whether a fold was
+    /// closed.
+    pub fn fold_at(&mut self, text: &str, line: usize) -> bool {
+        let fold = indent_folds(text)
+            .into_iter()
+            .filter(|fold| fold.contains(line) && !self.closed.contains(fold))
+            .min_by_key(Fold::hidden_lines);
+        match fold {
+            Some(fold) => {
+                self.close(fold);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Open the outermost closed fold containing `line`
+    ///
+    /// Folds inside it stay closed. Returns whether a fold was opened.
+    pub fn unfold_at(&mut self, line: usize) -> bool {
+        let outermost = self
+            .closed
+            .iter()
+            .enumerate()
+            .filter(|(_, fold)| fold.contains(line))
+            .max_by_key(|(_, fold)| fold.hidden_lines())
+            .map(|(index, _)| index);
+        match outermost {
+            Some(index) => {
+                self.closed.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Open the fold shown at `line` if there is one, close the innermost
+    /// fold containing it otherwise
+    pub fn toggle_at(&mut self, text: &str, line: usize) -> bool {
+        if self.folded_at(line).is_some() {
+            self.unfold_at(line)
+        } else {
+            self.fold_at(text, line)
+        }
+    }
+
+    /// Close every fold of `text`
+    pub fn fold_all(&mut self, text: &str) {
+        self.closed = indent_folds(text);
+    }
+
+    /// Open every fold
+    pub fn unfold_all(&mut self) {
+        self.closed.clear();
+    }
+
+    /// Open the folds hiding `line`, returning whether there were any
+    pub fn reveal(&mut self, line: usize) -> bool {
+        let before = self.closed.len();
+        self.closed.retain(|fold| !fold.hides(line));
+        self.closed.len() != before
+    }
+
+    /// Whether `line` is hidden by a closed fold
+    pub fn is_hidden(&self, line: usize) -> bool {
+        self.closed
+            .iter()
+            .take_while(|fold| fold.start < line)
+            .any(|fold| fold.hides(line))
+    }
+
+    /// Closed fold shown as `line`, the outermost if several start there
+    pub fn folded_at(&self, line: usize) -> Option<Fold> {
+        if self.is_hidden(line) {
+            return None;
+        }
+        self.closed
+            .iter()
+            .filter(|fold| fold.start == line)
+            .max_by_key(|fold| fold.hidden_lines())
+            .copied()
+    }
+
+    /// First visible line after `line`
+    ///
+    /// May be past the last line of the buffer when folds reach its end.
+    pub fn next_visible(&self, line: usize) -> usize {
+        let mut next = line + 1;
+        while let Some(end) = self
+            .closed
+            .iter()
+            .filter(|fold| fold.hides(next))
+            .map(|fold| fold.end)
+            .max()
+        {
+            next = end + 1;
+        }
+        next
+    }
+
+    /// Last visible line before `line`
+    pub fn prev_visible(&self, line: usize) -> Option<usize> {
+        let mut prev = line.checked_sub(1)?;
+        while let Some(start) = self
+            .closed
+            .iter()
+            .filter(|fold| fold.hides(prev))
+            .map(|fold| fold.start)
+            .min()
+        {
+            prev = start;
+        }
+        Some(prev)
+    }
+
+    /// `line`, or the line shown in its place if it is hidden
+    pub fn visible_line(&self, line: usize) -> usize {
+        if self.is_hidden(line) {
+            self.prev_visible(line).unwrap_or(0)
+        } else {
+            line
+        }
+    }
+
+    /// Number of visible lines in `lines`
+    pub fn visible_count(&self, lines: Range<usize>) -> usize {
+        let mut count = 0;
+        let mut line = lines.start;
+        if self.is_hidden(line) {
+            line = self.next_visible(line);
+        }
+        while line < lines.end {
+            count += 1;
+            line = self.next_visible(line);
+        }
+        count
+    }
+
+    /// Follow an edit at `line` that added `delta` lines (removed them if
+    /// negative), leaving `text`
+    ///
+    /// Folds below the edit move with their lines; a closed fold that no
+    /// longer starts an indented block is opened, and one whose block grew
+    /// or shrank takes its new extent.
+    pub fn edited(&mut self, text: &str, line: usize, delta: isize) {
+        if self.closed.is_empty() {
+            return;
+        }
+        let shift = |n: usize| n.saturating_add_signed(delta).max(line);
+        for fold in &mut self.closed {
+            if fold.start > line {
+                fold.start = shift(fold.start);
+            }
+            if fold.end > line {
+                fold.end = shift(fold.end);
+            }
+        }
+
+        let folds = indent_folds(text);
+        self.closed.retain_mut(|closed| {
+            match folds.iter().find(|fold| fold.start == closed.start) {
+                Some(fold) => {
+                    closed.end = fold.end;
+                    true
+                }
+                None => false,
+            }
+        });
+        self.closed.sort_by_key(|fold| fold.start);
+        self.closed.dedup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str =
+        "fn a() {\n    if x {\n        y();\n\n        z();\n    }\n\n}\nfn b() {}\n";
+
+    fn fold(start: usize, end: usize) -> Fold {
+        Fold { start, end }
+    }
+
+    #[test]
+    fn test_indent_folds() {
+        assert_eq!(indent_folds(SOURCE), [fold(0, 5), fold(1, 4)]);
+        assert_eq!(indent_folds("a\n\tb\nc\n"), [fold(0, 1)]);
+        assert!(indent_folds("a\nb\n").is_empty());
+        assert_eq!(indent_folds("a\n  b\n    c\n"), [fold(0, 2), fold(1, 2)]);
+    }
+
+    #[test]
+    fn test_fold_and_unfold_at_cursor() {
+        let mut folds = FoldMap::new();
+        assert!(folds.fold_at(SOURCE, 2));
+        assert_eq!(folds.closed(), [fold(1, 4)]);
+        assert!(folds.fold_at(SOURCE, 2));
+        assert_eq!(folds.closed(), [fold(0, 5), fold(1, 4)]);
+        assert!(!folds.fold_at(SOURCE, 2));
+        assert!(!folds.fold_at(SOURCE, 8));
+
+        assert_eq!(folds.folded_at(0), Some(fold(0, 5)));
+        assert_eq!(folds.folded_at(1), None);
+
+        // Opening shows the outer fold with the inner one still closed
+        assert!(folds.unfold_at(2));
+        assert_eq!(folds.closed(), [fold(1, 4)]);
+        assert!(folds.toggle_at(SOURCE, 1));
+        assert!(folds.is_empty());
+        assert!(folds.toggle_at(SOURCE, 1));
+        assert_eq!(folds.closed(), [fold(1, 4)]);
+    }
+
+    #[test]
+    fn test_visible_lines() {
+        let mut folds = FoldMap::new();
+        folds.fold_all(SOURCE);
+        assert!(!folds.is_hidden(0));
+        assert!(folds.is_hidden(3));
+        assert!(!folds.is_hidden(6));
+        assert_eq!(folds.next_visible(0), 6);
+        assert_eq!(folds.prev_visible(6), Some(0));
+        assert_eq!(folds.prev_visible(0), None);
+        assert_eq!(folds.visible_line(4), 0);
+        assert_eq!(folds.visible_count(0..9), 4);
+        assert_eq!(folds.visible_count(2..9), 3);
+
+        assert!(folds.reveal(3));
+        assert!(folds.is_empty());
+        folds.unfold_all();
+        assert_eq!(folds.visible_count(0..9), 9);
+    }
+
+    #[test]
+    fn test_edits_move_folds() {
+        let mut folds = FoldMap::new();
+        folds.fold_at(SOURCE, 2);
+
+        // A line added above moves the fold down
+        let text = format!("use x;\n{}", SOURCE);
+        folds.edited(&text, 0, 1);
+        assert_eq!(folds.closed(), [fold(2, 5)]);
+
+        // A line added inside extends it
+        let text = text.replace("y();", "y();\n        w();");
+        folds.edited(&text, 3, 1);
+        assert_eq!(folds.closed(), [fold(2, 6)]);
+
+        // Removing the block opens it
+        folds.edited("use x;\nfn a() {}\n", 1, -6);
+        assert!(folds.is_empty());
+    }
+}
@@ -6,6 +6,15 @@
 //! - Edit operations and undo/redo
 //! - Editor state management
 //! - Mode system (Vim-style modal editing)
+//! - Tree-sitter syntax trees and structural editing
+//! - Indentation-based code folding
+//! - Literal and regex search and replace
+//! - Minimal line/word diffs for applying proposed file content
+//! - Suppressions and LLM explanations as quick fixes for diagnostics
+//! - Semantic diffs grouping changes by function, type and method
+//! - Scripting facade for plugins and tests
+//! - Autosave of unsaved buffers for crash recovery
+//! - Encoding detection and conversion (UTF-8, UTF-16, Latin-1)
 //!
 //! # Architecture
 //!
@@ -39,27 +48,56 @@
 //! ```
 
 // Public modules
+pub mod autosave;
 pub mod buffer;
 pub mod command;
 pub mod cursor;
+pub mod encoding;
 pub mod error;
+pub mod fold;
+pub mod marks;
+pub mod minimal_diff;
 pub mod mode;
+pub mod quick_fix;
+pub mod refactor;
+pub mod script;
+pub mod search;
+pub mod semantic_diff;
 pub mod selection;
 pub mod state;
+pub mod structural;
+pub mod syntax;
 pub mod view;
+pub mod width;
 
 // Private modules (implementation details)
 mod editor;
 mod history;
 
 // Re-exports for convenience
+pub use autosave::{Autosave, RecoveryFile};
 pub use buffer::{Buffer, BufferId, BufferManager, LineEnding};
-pub use command::{Command, CommandHistory, DeleteCommand, InsertCommand, ReplaceCommand};
+pub use command::{
+    Command, CommandGroup, CommandHistory, ConvertLineEndingsCommand, DeleteCommand, EditKind,
+    InsertCommand, ReplaceCommand,
+};
 pub use cursor::{Cursor, CursorPosition, CursorSet};
+pub use encoding::{Decoded, Encoding};
 pub use error::{EditorError, Result};
+pub use fold::{Fold, FoldMap};
+pub use marks::{GlobalMark, GlobalMarks, LocalMarks, Mark};
+pub use minimal_diff::{minimal_commands, minimal_edits};
 pub use mode::{Mode, ModeManager};
+pub use refactor::RefactorProposal;
+pub use script::{Motion, Script, ScriptStep};
+pub use search::{BufferMatches, ReplaceAllCommand, SearchMatch, SearchOptions, SearchQuery};
+pub use semantic_diff::{
+    semantic_diff, ItemChange, ItemChangeKind, LineChange, LineTag, SemanticDiff,
+};
 pub use selection::{Selection, SelectionRange};
 pub use state::EditorState;
+pub use structural::StructuralEdit;
+pub use syntax::{Language, SyntaxTree};
 pub use view::ViewState;
 
 // Re-export for backward compatibility
@@ -0,0 +1,233 @@
+//! Marks
+//!
+//! Positions kept under a letter, as in Vim. A lowercase mark `a`–`z`
+//! belongs to one buffer; an uppercase mark `A`–`Z` remembers its file too,
+//! so jumping to it opens the file. [`LocalMarks`] holds the marks of a
+//! buffer and [`GlobalMarks`] those of a workspace; both follow edits that
+//! add or remove lines so marks stay on the lines they were set on.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Whether `name` names a mark of one buffer
+pub fn is_local(name: char) -> bool {
+    name.is_ascii_lowercase()
+}
+
+/// Whether `name` names a mark remembering its file
+pub fn is_global(name: char) -> bool {
+    name.is_ascii_uppercase()
+}
+
+/// Position of a mark
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mark {
+    /// Line (0-indexed)
+    pub line: usize,
+    /// Column (0-indexed)
+    #[serde(default)]
+    pub column: usize,
+}
+
+impl Mark {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+
+    /// Follow an edit at `line` that added `delta` lines (removed them if
+    /// negative)
+    ///
+    /// A mark below the edit moves with its line; one on a removed line
+    /// moves up to the edit.
+    fn edited(&mut self, line: usize, delta: isize) {
+        if self.line > line {
+            self.line = self.line.saturating_add_signed(delta).max(line);
+        }
+    }
+}
+
+/// Lowercase marks of a buffer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalMarks {
+    marks: BTreeMap<char, Mark>,
+}
+
+impl LocalMarks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set mark `name` at `mark`
+    ///
+    /// Returns whether `name` names a local mark.
+    pub fn set(&mut self, name: char, mark: Mark) -> bool {
+        if !is_local(name) {
+            return false;
+        }
+        self.marks.insert(name, mark);
+        true
+    }
+
+    /// Position of mark `name`
+    pub fn get(&self, name: char) -> Option<Mark> {
+        self.marks.get(&name).copied()
+    }
+
+    /// Marks set, by name
+    pub fn iter(&self) -> impl Iterator<Item = (char, Mark)> + '_ {
+        self.marks.iter().map(|(name, mark)| (*name, *mark))
+    }
+
+    /// Follow an edit at `line` that added `delta` lines (removed them if
+    /// negative)
+    pub fn edited(&mut self, line: usize, delta: isize) {
+        for mark in self.marks.values_mut() {
+            mark.edited(line, delta);
+        }
+    }
+}
+
+/// Uppercase mark, with the file it is in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalMark {
+    /// File the mark is in
+    pub path: PathBuf,
+    /// Position in the file
+    #[serde(flatten)]
+    pub mark: Mark,
+}
+
+/// Uppercase marks of a workspace
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalMarks {
+    marks: BTreeMap<char, GlobalMark>,
+}
+
+impl GlobalMarks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether no mark is set
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    /// Set mark `name` at `mark` in `path`, replacing it wherever it was
+    ///
+    /// Returns whether `name` names a global mark.
+    pub fn set(&mut self, name: char, path: &Path, mark: Mark) -> bool {
+        if !is_global(name) {
+            return false;
+        }
+        self.marks.insert(
+            name,
+            GlobalMark {
+                path: path.to_path_buf(),
+                mark,
+            },
+        );
+        true
+    }
+
+    /// Mark `name` with its file
+    pub fn get(&self, name: char) -> Option<&GlobalMark> {
+        self.marks.get(&name)
+    }
+
+    /// Marks set, by name
+    pub fn iter(&self) -> impl Iterator<Item = (char, &GlobalMark)> + '_ {
+        self.marks.iter().map(|(name, mark)| (*name, mark))
+    }
+
+    /// Follow an edit of `path` at `line` that added `delta` lines (removed
+    /// them if negative)
+    pub fn edited(&mut self, path: &Path, line: usize, delta: isize) {
+        for global in self.marks.values_mut() {
+            if global.path == path {
+                global.mark.edited(line, delta);
+            }
+        }
+    }
+}
+
+impl FromIterator<(char, GlobalMark)> for GlobalMarks {
+    /// Marks from `(name, mark)` pairs, leaving out names of no global mark
+    fn from_iter<I: IntoIterator<Item = (char, GlobalMark)>>(iter: I) -> Self {
+        let marks = iter
+            .into_iter()
+            .filter(|(name, _)| is_global(*name))
+            .collect();
+        Self { marks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_marks() {
+        let mut marks = LocalMarks::new();
+        assert!(!marks.set('A', Mark::new(1, 0)));
+        assert!(!marks.set('1', Mark::new(1, 0)));
+        assert!(marks.set('a', Mark::new(2, 4)));
+        assert!(marks.set('b', Mark::new(8, 0)));
+        assert_eq!(marks.get('a'), Some(Mark::new(2, 4)));
+        assert_eq!(marks.get('c'), None);
+
+        // Replacing keeps one position per mark
+        assert!(marks.set('a', Mark::new(3, 1)));
+        assert_eq!(marks.iter().count(), 2);
+        assert_eq!(marks.get('a'), Some(Mark::new(3, 1)));
+    }
+
+    #[test]
+    fn test_marks_follow_edits() {
+        let mut marks = LocalMarks::new();
+        marks.set('a', Mark::new(2, 4));
+        marks.set('b', Mark::new(8, 1));
+
+        // Lines added at the first mark move only the one below
+        marks.edited(2, 3);
+        assert_eq!(marks.get('a'), Some(Mark::new(2, 4)));
+        assert_eq!(marks.get('b'), Some(Mark::new(11, 1)));
+
+        // Lines added above move both
+        marks.edited(0, 1);
+        assert_eq!(marks.get('a'), Some(Mark::new(3, 4)));
+        assert_eq!(marks.get('b'), Some(Mark::new(12, 1)));
+
+        // A mark on a removed line moves up to the edit
+        marks.edited(5, -10);
+        assert_eq!(marks.get('a'), Some(Mark::new(3, 4)));
+        assert_eq!(marks.get('b'), Some(Mark::new(5, 1)));
+    }
+
+    #[test]
+    fn test_global_marks() {
+        let mut marks = GlobalMarks::new();
+        assert!(marks.is_empty());
+        assert!(!marks.set('a', Path::new("src/main.rs"), Mark::new(1, 0)));
+        assert!(marks.set('A', Path::new("src/main.rs"), Mark::new(4, 2)));
+        assert!(marks.set('B', Path::new("README.md"), Mark::new(4, 0)));
+
+        // Edits move only the marks of the edited file
+        marks.edited(Path::new("src/main.rs"), 0, 2);
+        let a = marks.get('A').unwrap();
+        assert_eq!((a.path.as_path(), a.mark), (Path::new("src/main.rs"), Mark::new(6, 2)));
+        assert_eq!(marks.get('B').unwrap().mark, Mark::new(4, 0));
+
+        // Setting a mark again moves it to the other file
+        marks.set('A', Path::new("README.md"), Mark::new(0, 0));
+        assert_eq!(marks.get('A').unwrap().path, Path::new("README.md"));
+
+        let collected: GlobalMarks = marks
+            .iter()
+            .map(|(name, mark)| (name, mark.clone()))
+            .chain([('x', marks.get('B').unwrap().clone())])
+            .collect();
+        assert_eq!(collected, marks);
+    }
+}
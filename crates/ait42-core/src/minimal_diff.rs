@@ -0,0 +1,217 @@
+//! Minimal Diff
+//!
+//! LLM responses replace whole files. Replacing the buffer wholesale throws
+//! away formatting the model did not mean to touch, makes the change one
+//! giant undo step and conflicts with any concurrent edit. Instead, the
+//! current and proposed content are diffed by line, changed lines are
+//! narrowed down to the changed words, and only those hunks are applied,
+//! each as its own [`ReplaceCommand`].
+
+use std::ops::Range;
+
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+
+use crate::buffer::BufferId;
+use crate::command::ReplaceCommand;
+use crate::structural::StructuralEdit;
+
+/// Edits turning `original` into `proposed`, in ascending order
+///
+/// Ranges refer to `original` and do not overlap. Hunks that change the
+/// same number of lines are narrowed down to the changed words; hunks that
+/// add or remove lines replace whole lines.
+pub fn minimal_edits(original: &str, proposed: &str) -> Vec<StructuralEdit> {
+    let old_lines: Vec<&str> = original.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = proposed.split_inclusive('\n').collect();
+
+    let mut edits = Vec::new();
+    for (old, new) in changed(&old_lines, &new_lines) {
+        let old_range = byte_range(&old_lines, old.clone());
+        let new_range = byte_range(&new_lines, new.clone());
+
+        if old.len() == new.len() {
+            let old_text = &original[old_range.clone()];
+            let new_text = &proposed[new_range.clone()];
+            let old_words = words(old_text);
+            let new_words = words(new_text);
+            for (old, new) in changed(&old_words, &new_words) {
+                let old = offset(byte_range(&old_words, old), old_range.start);
+                let new = offset(byte_range(&new_words, new), new_range.start);
+                edits.push(edit(old, &proposed[new]));
+            }
+        } else {
+            edits.push(edit(old_range, &proposed[new_range]));
+        }
+    }
+    edits
+}
+
+/// Commands applying [`minimal_edits`] to buffer `buffer_id`
+///
+/// The commands are ordered back to front, so each range is still valid
+/// when its command executes after the ones before it. Undoing them in
+/// reverse order restores the original.
+pub fn minimal_commands(
+    buffer_id: BufferId,
+    original: &str,
+    proposed: &str,
+) -> Vec<ReplaceCommand> {
+    minimal_edits(original, proposed)
+        .into_iter()
+        .rev()
+        .map(|edit| edit.into_command(buffer_id))
+        .collect()
+}
+
+fn edit(range: Range<usize>, replacement: &str) -> StructuralEdit {
+    StructuralEdit {
+        cursor: range.start + replacement.len(),
+        range,
+        replacement: replacement.to_string(),
+    }
+}
+
+/// Token ranges of each run of changes between `old` and `new`
+fn changed(old: &[&str], new: &[&str]) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut runs: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    let mut current: Option<(Range<usize>, Range<usize>)> = None;
+    for op in capture_diff_slices(Algorithm::Myers, old, new) {
+        if op.tag() == DiffTag::Equal {
+            runs.extend(current.take());
+            continue;
+        }
+        let (old_range, new_range) = (op.old_range(), op.new_range());
+        match current.as_mut() {
+            Some((old, new)) => {
+                old.end = old_range.end;
+                new.end = new_range.end;
+            }
+            None => current = Some((old_range, new_range)),
+        }
+    }
+    runs.extend(current);
+    runs
+}
+
+/// Byte range covered by the tokens in `range`
+fn byte_range(tokens: &[&str], range: Range<usize>) -> Range<usize> {
+    let start: usize = tokens[..range.start].iter().map(|t| t.len()).sum();
+    let len: usize = tokens[range].iter().map(|t| t.len()).sum();
+    start..start + len
+}
+
+fn offset(range: Range<usize>, by: usize) -> Range<usize> {
+    range.start + by..range.end + by
+}
+
+/// Split `text` into words, whitespace runs and single punctuation chars
+fn words(text: &str) -> Vec<&str> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Space,
+        Other,
+    }
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            Class::Word
+        } else if c.is_whitespace() {
+            Class::Space
+        } else {
+            Class::Other
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let joins_next = match chars.peek() {
+            Some(&(_, next)) => class(c) != Class::Other && class(c) == class(next),
+            None => false,
+        };
+        if !joins_next {
+            tokens.push(&text[start..i + c.len_utf8()]);
+            start = i + c.len_utf8();
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::Buffer;
+    use crate::command::Command;
+
+    /// Apply ascending, non-overlapping edits to `source`
+    fn apply(source: &str, edits: &[StructuralEdit]) -> String {
+        edits
+            .iter()
+            .rev()
+            .fold(source.to_string(), |text, edit| edit.apply(&text))
+    }
+
+    #[test]
+    fn test_changed_words_only() {
+        let original = "fn total(items: &[u32]) -> u32 {\n    items.iter().sum()\n}\n";
+        let proposed = "fn total(values: &[u32]) -> u32 {\n    values.iter().sum()\n}\n";
+
+        let edits = minimal_edits(original, proposed);
+        assert_eq!(edits.len(), 2);
+        assert_eq!(&original[edits[0].range.clone()], "items");
+        assert_eq!(edits[0].replacement, "values");
+        assert_eq!(apply(original, &edits), proposed);
+    }
+
+    #[test]
+    fn test_unrelated_formatting_is_kept() {
+        // Only the middle line differs; the odd spacing elsewhere is not touched
+        let original = "let  a = 1;\nlet b = 2;\nlet   c = 3;\n";
+        let proposed = "let  a = 1;\nlet b = 20;\nlet   c = 3;\n";
+
+        let edits = minimal_edits(original, proposed);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(&original[edits[0].range.clone()], "2");
+        assert_eq!(edits[0].replacement, "20");
+    }
+
+    #[test]
+    fn test_added_and_removed_lines() {
+        let original = "a\nb\nc\nd\n";
+        let proposed = "a\nnew\nc\n";
+
+        let edits = minimal_edits(original, proposed);
+        assert_eq!(apply(original, &edits), proposed);
+        assert!(minimal_edits(original, original).is_empty());
+        assert_eq!(apply("", &minimal_edits("", "x\n")), "x\n");
+        assert_eq!(apply("x", &minimal_edits("x", "")), "");
+    }
+
+    #[test]
+    fn test_multibyte_text() {
+        let original = "// größe: 1\nlet s = \"héllo\";\n";
+        let proposed = "// größe: 2\nlet s = \"héllo wörld\";\n";
+        assert_eq!(apply(original, &minimal_edits(original, proposed)), proposed);
+    }
+
+    #[test]
+    fn test_commands_undo_hunk_by_hunk() {
+        let original = "one\ntwo\nthree\n";
+        let proposed = "ONE\ntwo\nTHREE\n";
+        let mut buffer = Buffer::from_string(original.to_string(), None);
+
+        let mut commands = minimal_commands(buffer.id(), original, proposed);
+        assert_eq!(commands.len(), 2);
+        for command in &mut commands {
+            command.execute(&mut buffer).unwrap();
+        }
+        assert_eq!(buffer.to_string(), proposed);
+
+        // Each hunk is its own undo step
+        commands.last_mut().unwrap().undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "one\ntwo\nTHREE\n");
+        commands[0].undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), original);
+    }
+}
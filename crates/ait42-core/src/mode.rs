@@ -48,6 +48,7 @@ impl Default for Mode {
 pub struct ModeManager {
     current: Mode,
     previous: Option<Mode>,
+    insert_session: u64,
 }
 
 impl ModeManager {
@@ -56,6 +57,7 @@ impl ModeManager {
         Self {
             current: Mode::Normal,
             previous: None,
+            insert_session: 0,
         }
     }
 
@@ -78,9 +80,21 @@ impl ModeManager {
         if self.current != mode {
             self.previous = Some(self.current);
             self.current = mode;
+            if mode == Mode::Insert {
+                self.insert_session += 1;
+            }
         }
     }
 
+    /// Counter identifying the current (or most recent) insert session
+    ///
+    /// Incremented on every entry into Insert mode; undo coalescing never
+    /// joins edits from different sessions.
+    #[inline]
+    pub fn insert_session(&self) -> u64 {
+        self.insert_session
+    }
+
     /// Enter Insert mode
     pub fn enter_insert(&mut self) {
         self.switch_to(Mode::Insert);
@@ -122,6 +136,9 @@ impl ModeManager {
         if let Some(prev) = self.previous {
             self.current = prev;
             self.previous = None;
+            if prev == Mode::Insert {
+                self.insert_session += 1;
+            }
         }
     }
 
@@ -206,4 +223,20 @@ mod tests {
         assert!(!mgr.is_normal());
         assert!(mgr.is_insert());
     }
+
+    #[test]
+    fn test_insert_session_counter() {
+        let mut mgr = ModeManager::new();
+        assert_eq!(mgr.insert_session(), 0);
+
+        mgr.enter_insert();
+        assert_eq!(mgr.insert_session(), 1);
+        // Staying in Insert is the same session
+        mgr.enter_insert();
+        assert_eq!(mgr.insert_session(), 1);
+
+        mgr.exit_insert();
+        mgr.enter_insert();
+        assert_eq!(mgr.insert_session(), 2);
+    }
 }
@@ -0,0 +1,281 @@
+//! Diagnostic Quick Fixes
+//!
+//! Fixes for a diagnostic that do not come from the language server:
+//! a suppression comment in the syntax of the tool that reported it, and the
+//! prompt and response handling for asking an LLM to explain the diagnostic
+//! and propose a patch. The patch is a [`RefactorProposal`], so it is
+//! reviewed and applied like an assisted refactor.
+
+use crate::error::Result;
+use crate::refactor::{
+    code_fence, extract_code_block, refactor_error, RefactorProposal, MAX_AI_SOURCE_BYTES,
+};
+use crate::structural::StructuralEdit;
+use crate::syntax::Language;
+
+/// Diagnostic a quick fix is for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// 0-based line the diagnostic starts on
+    pub line: usize,
+    /// Lint or error code, such as `unused_variables` or `E501`
+    pub code: Option<String>,
+    /// Tool that reported it, such as `rustc`, `clippy` or `eslint`
+    pub source: Option<String>,
+}
+
+/// Where a suppression comment goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placement {
+    /// On a line of its own above the diagnostic, indented like it
+    LineAbove,
+    /// At the end of the diagnostic's line
+    EndOfLine,
+}
+
+/// Suppression comment (or attribute) for `diagnostic`, with where it goes
+///
+/// `None` when the tool has no way to suppress it, as for compiler errors.
+fn suppression(extension: &str, diagnostic: &Diagnostic) -> Option<(String, Placement)> {
+    let source = diagnostic.source.as_deref().unwrap_or("").to_lowercase();
+    let code = diagnostic.code.as_deref().filter(|code| !code.is_empty());
+
+    match extension {
+        "rs" => {
+            // Only lints can be allowed; errors (E0308) and rust-analyzer's
+            // own diagnostics (inactive-code) cannot
+            let lint = code.filter(|code| {
+                code.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == ':')
+            })?;
+            Some((format!("#[allow({})]", lint), Placement::LineAbove))
+        }
+        "py" | "pyi" => {
+            let comment = match (source.as_str(), code) {
+                ("pyright" | "basedpyright", Some(code)) => format!("# pyright: ignore[{}]", code),
+                ("pyright" | "basedpyright", None) => "# pyright: ignore".to_string(),
+                ("mypy", Some(code)) => format!("# type: ignore[{}]", code),
+                ("mypy", None) => "# type: ignore".to_string(),
+                ("pylint", Some(code)) => format!("# pylint: disable={}", code),
+                (_, Some(code)) => format!("# noqa: {}", code),
+                (_, None) => "# noqa".to_string(),
+            };
+            Some((comment, Placement::EndOfLine))
+        }
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => {
+            let comment = match (source.as_str(), code) {
+                ("eslint", Some(code)) => format!("// eslint-disable-next-line {}", code),
+                ("eslint", None) => "// eslint-disable-next-line".to_string(),
+                ("ts" | "typescript" | "deno-ts", _) => "// @ts-expect-error".to_string(),
+                _ => return None,
+            };
+            Some((comment, Placement::LineAbove))
+        }
+        _ => None,
+    }
+}
+
+/// Edit suppressing `diagnostic` in a file with extension `extension`
+///
+/// Rust lints get an `#[allow]` attribute on the item or statement they are
+/// reported on; Python tools an end-of-line comment (`# noqa`, `# type:
+/// ignore`, `# pyright: ignore`, `# pylint: disable`); ESLint and TypeScript
+/// a comment on the line above. Returns `None` when the diagnostic cannot be
+/// suppressed or its line is out of range.
+pub fn suppression_edit(
+    source: &str,
+    extension: &str,
+    diagnostic: &Diagnostic,
+) -> Option<StructuralEdit> {
+    let (comment, placement) = suppression(extension, diagnostic)?;
+
+    let line_start = if diagnostic.line == 0 {
+        0
+    } else {
+        source
+            .match_indices('\n')
+            .nth(diagnostic.line - 1)
+            .map(|(i, _)| i + 1)?
+    };
+    let rest = &source[line_start..];
+    let line = rest.split('\n').next().unwrap_or("");
+
+    let (at, replacement) = match placement {
+        Placement::LineAbove => {
+            let indent_len = line.len() - line.trim_start().len();
+            let newline = if line.ends_with('\r') { "\r\n" } else { "\n" };
+            let text = format!("{}{}{}", &line[..indent_len], comment, newline);
+            (line_start, text)
+        }
+        Placement::EndOfLine => {
+            let content = line.trim_end_matches('\r');
+            let at = line_start + content.trim_end().len();
+            (at, format!("  {}", comment))
+        }
+    };
+    Some(StructuralEdit {
+        range: at..at,
+        cursor: at + replacement.len(),
+        replacement,
+    })
+}
+
+/// Build the prompt asking the LLM to explain `diagnostic` and fix it
+///
+/// The model is asked for a short explanation followed by the complete
+/// updated file in one fenced code block; [`Explanation::from_response`]
+/// reads both back.
+pub fn explain_prompt(
+    source: &str,
+    diagnostic: &Diagnostic,
+    language: Option<Language>,
+) -> Result<String> {
+    if source.len() > MAX_AI_SOURCE_BYTES {
+        return Err(refactor_error(format!(
+            "file is too large to explain a diagnostic in ({} bytes, limit {})",
+            source.len(),
+            MAX_AI_SOURCE_BYTES
+        )));
+    }
+
+    let fence = code_fence(language);
+    let line_number = diagnostic.line + 1;
+    let line = source.lines().nth(diagnostic.line).unwrap_or("");
+    let reported_by = match (&diagnostic.source, &diagnostic.code) {
+        (Some(source), Some(code)) => format!(" ({} {})", source, code),
+        (Some(name), None) | (None, Some(name)) => format!(" ({})", name),
+        (None, None) => String::new(),
+    };
+
+    Ok(format!(
+        "You are helping fix a diagnostic in an editor.\n\
+         Diagnostic on line {line_number}{reported_by}: {message}\n\
+         Line {line_number}:\n```{fence}\n{line}\n```\n\n\
+         Full file:\n```{fence}\n{source}\n```\n\n\
+         First explain in a few sentences, in plain prose, what the diagnostic means and \
+         why it is reported here. Then respond with the complete updated file that fixes it \
+         in a single fenced code block. Keep the existing style and do not change unrelated \
+         code. If it cannot be fixed within this file, explain why and leave out the code block.",
+        message = diagnostic.message,
+    ))
+}
+
+/// LLM explanation of a diagnostic, with the fix it proposed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub explanation: String,
+    /// Proposed fix for review, `None` if the model gave none
+    pub proposal: Option<RefactorProposal>,
+}
+
+impl Explanation {
+    /// Parse the model response for `original`
+    ///
+    /// The explanation is the prose before the first fenced code block; a
+    /// block that turns out to change nothing is not offered as a fix.
+    /// `label` names the file in the diff header.
+    pub fn from_response(original: &str, response: &str, label: &str) -> Self {
+        let explanation = response
+            .find("```")
+            .map_or(response, |open| &response[..open])
+            .trim()
+            .to_string();
+        let proposal = extract_code_block(response)
+            .and_then(|_| RefactorProposal::from_response(original, response, label).ok());
+        Self {
+            explanation,
+            proposal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(line: usize, code: Option<&str>, source: Option<&str>) -> Diagnostic {
+        Diagnostic {
+            message: "problem".to_string(),
+            line,
+            code: code.map(str::to_string),
+            source: source.map(str::to_string),
+        }
+    }
+
+    fn suppress(source: &str, extension: &str, diagnostic: &Diagnostic) -> Option<String> {
+        suppression_edit(source, extension, diagnostic).map(|edit| edit.apply(source))
+    }
+
+    #[test]
+    fn test_suppress_rust_lints_only() {
+        let source = "fn main() {\n    let x = 1;\n}\n";
+        let unused = diagnostic(1, Some("unused_variables"), Some("rustc"));
+        assert_eq!(
+            suppress(source, "rs", &unused).unwrap(),
+            "fn main() {\n    #[allow(unused_variables)]\n    let x = 1;\n}\n"
+        );
+
+        let clippy = diagnostic(0, Some("clippy::needless_return"), Some("clippy"));
+        assert!(suppress(source, "rs", &clippy)
+            .unwrap()
+            .starts_with("#[allow(clippy::needless_return)]\nfn main()"));
+
+        assert_eq!(suppress(source, "rs", &diagnostic(1, Some("E0308"), None)), None);
+        assert_eq!(suppress(source, "rs", &diagnostic(1, Some("inactive-code"), None)), None);
+        assert_eq!(suppress(source, "rs", &diagnostic(9, Some("dead_code"), None)), None);
+    }
+
+    #[test]
+    fn test_suppress_python_at_end_of_line() {
+        let source = "import os  \r\nx = 1\n";
+        assert_eq!(
+            suppress(source, "py", &diagnostic(0, Some("F401"), Some("Ruff"))).unwrap(),
+            "import os  # noqa: F401  \r\nx = 1\n"
+        );
+        assert_eq!(
+            suppress(source, "py", &diagnostic(1, Some("assignment"), Some("mypy"))).unwrap(),
+            "import os  \r\nx = 1  # type: ignore[assignment]\n"
+        );
+        let pyright = diagnostic(1, Some("reportGeneralTypeIssues"), Some("Pyright"));
+        assert!(suppress(source, "py", &pyright)
+            .unwrap()
+            .contains("x = 1  # pyright: ignore[reportGeneralTypeIssues]\n"));
+    }
+
+    #[test]
+    fn test_suppress_javascript_on_line_above() {
+        let source = "function f() {\n  let unused;\n}\n";
+        assert_eq!(
+            suppress(source, "js", &diagnostic(1, Some("no-unused-vars"), Some("eslint"))).unwrap(),
+            "function f() {\n  // eslint-disable-next-line no-unused-vars\n  let unused;\n}\n"
+        );
+        assert!(suppress(source, "ts", &diagnostic(1, Some("2322"), Some("typescript")))
+            .unwrap()
+            .contains("  // @ts-expect-error\n  let unused;"));
+        assert_eq!(suppress(source, "js", &diagnostic(1, None, Some("biome"))), None);
+        assert_eq!(suppress(source, "txt", &diagnostic(1, None, None)), None);
+    }
+
+    #[test]
+    fn test_explain_prompt_and_response() {
+        let source = "fn main() {\n    let x = 1;\n}\n";
+        let unused = diagnostic(1, Some("unused_variables"), Some("rustc"));
+        let prompt = explain_prompt(source, &unused, Some(Language::Rust)).unwrap();
+        assert!(prompt.contains("Diagnostic on line 2 (rustc unused_variables): problem"));
+        assert!(prompt.contains("```rust\n    let x = 1;\n```"));
+
+        let huge = "x".repeat(MAX_AI_SOURCE_BYTES + 1);
+        assert!(explain_prompt(&huge, &unused, None).is_err());
+
+        let response = "`x` is never read.\n\n```rust\nfn main() {\n    let _x = 1;\n}\n```\n";
+        let explanation = Explanation::from_response(source, response, "src/main.rs");
+        assert_eq!(explanation.explanation, "`x` is never read.");
+        let proposal = explanation.proposal.unwrap();
+        assert!(proposal.diff.contains("+    let _x = 1;"));
+
+        let explanation = Explanation::from_response(source, "Needs a change elsewhere.", "x");
+        assert_eq!(explanation.explanation, "Needs a change elsewhere.");
+        assert_eq!(explanation.proposal, None);
+    }
+}
@@ -0,0 +1,722 @@
+//! Refactoring
+//!
+//! Heuristic extract-variable and extract-function refactorings for
+//! languages whose language server offers no code actions, plus the prompt
+//! and response handling for an LLM-assisted variant.
+//!
+//! The heuristics work on the tree-sitter syntax tree rather than on types,
+//! so they are deliberately conservative: they refuse selections they cannot
+//! extract safely instead of guessing.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use similar::TextDiff;
+use tree_sitter::Node;
+
+use crate::error::{EditorError, Result};
+use crate::minimal_diff::minimal_edits;
+use crate::structural::{StructuralEdit, INDENT_UNIT};
+use crate::syntax::{Language, SyntaxTree};
+
+/// Largest source file sent to the LLM for an assisted refactor
+pub const MAX_AI_SOURCE_BYTES: usize = 64 * 1024;
+
+pub(crate) fn refactor_error(message: impl Into<String>) -> EditorError {
+    EditorError::Refactor(message.into())
+}
+
+/// Check that `name` is a plain identifier in all supported languages
+fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(refactor_error(format!("`{}` is not a valid identifier", name)))
+    }
+}
+
+/// Shrink `range` so it does not start or end with whitespace
+fn trim_range(source: &str, range: Range<usize>) -> Range<usize> {
+    let text = &source[range.clone()];
+    let start = range.start + (text.len() - text.trim_start().len());
+    let end = range.start + text.trim_end().len();
+    start..end.max(start)
+}
+
+fn line_start(source: &str, pos: usize) -> usize {
+    source[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// End of the line containing `pos`, including its newline
+fn line_end(source: &str, pos: usize) -> usize {
+    source[pos..]
+        .find('\n')
+        .map_or(source.len(), |i| pos + i + 1)
+}
+
+fn indent_at(source: &str, line_start: usize) -> &str {
+    let line = &source[line_start..];
+    let len = line.len() - line.trim_start_matches([' ', '\t']).len();
+    &line[..len]
+}
+
+/// Statement containing `node`: the ancestor whose parent is a block
+fn enclosing_statement<'t>(language: Language, node: Node<'t>) -> Option<Node<'t>> {
+    let mut current = node;
+    loop {
+        let parent = current.parent()?;
+        if language.block_kinds().contains(&parent.kind()) {
+            return Some(current);
+        }
+        current = parent;
+    }
+}
+
+/// Extract the selected expression into a local variable named `name`
+///
+/// The declaration goes on its own line above the statement containing the
+/// selection, at that statement's indentation.
+pub fn extract_variable(
+    tree: &SyntaxTree,
+    source: &str,
+    range: Range<usize>,
+    name: &str,
+) -> Result<StructuralEdit> {
+    validate_name(name)?;
+    let language = tree.language();
+    let range = trim_range(source, range);
+    if range.is_empty() {
+        return Err(refactor_error("nothing selected"));
+    }
+
+    let node = tree
+        .named_node_for_range(range.clone())
+        .ok_or_else(|| refactor_error("selection is outside the syntax tree"))?;
+    let statement = enclosing_statement(language, node)
+        .ok_or_else(|| refactor_error("selection is not inside a statement"))?;
+    if statement.byte_range() == range {
+        return Err(refactor_error("selection is a whole statement"));
+    }
+
+    let expression = &source[range.clone()];
+    let declaration = match language {
+        Language::Rust => format!("let {} = {};", name, expression),
+        Language::Python => format!("{} = {}", name, expression),
+        Language::JavaScript => format!("const {} = {};", name, expression),
+    };
+
+    // Own line when the statement starts a line, otherwise just before it
+    let start = line_start(source, statement.start_byte());
+    let (insert_at, prefix) = if source[start..statement.start_byte()].trim().is_empty() {
+        (start, format!("{}{}\n", indent_at(source, start), declaration))
+    } else {
+        (statement.start_byte(), format!("{} ", declaration))
+    };
+
+    let replacement = format!("{}{}{}", prefix, &source[insert_at..range.start], name);
+    Ok(StructuralEdit {
+        cursor: insert_at + replacement.len() - name.len(),
+        range: insert_at..range.end,
+        replacement,
+    })
+}
+
+/// Whether identifier `node` is being bound (declared) rather than used
+fn is_binding(language: Language, node: Node<'_>) -> bool {
+    let specs = language.binding_kinds();
+    if let Some(parent) = node.parent() {
+        if specs
+            .iter()
+            .any(|(kind, field)| field.is_none() && *kind == parent.kind())
+        {
+            return true;
+        }
+    }
+
+    let mut ancestor = node.parent();
+    while let Some(current) = ancestor {
+        if language.block_kinds().contains(&current.kind()) || current.kind() == "type" {
+            return false;
+        }
+        for (kind, field) in specs {
+            let Some(field) = field else { continue };
+            if *kind != current.kind() {
+                continue;
+            }
+            if let Some(target) = current.child_by_field_name(field) {
+                if target.byte_range().contains(&node.start_byte()) {
+                    return true;
+                }
+            }
+        }
+        ancestor = current.parent();
+    }
+    false
+}
+
+/// Identifier nodes inside `node`, in source order
+fn identifiers<'t>(node: Node<'t>, out: &mut Vec<Node<'t>>) {
+    if node.kind() == "identifier" {
+        out.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        identifiers(child, out);
+    }
+}
+
+/// Whether `node` contains a receiver (`self`/`this`) reference
+fn uses_receiver(language: Language, source: &str, node: Node<'_>) -> bool {
+    match (language, node.kind()) {
+        (Language::Rust, "self") | (Language::JavaScript, "this") => return true,
+        (Language::Python, "identifier") if &source[node.byte_range()] == "self" => return true,
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .children(&mut cursor)
+        .any(|child| uses_receiver(language, source, child));
+    found
+}
+
+/// Declared Rust type of a binding, when spelled out in the source
+fn rust_binding_type(source: &str, binding: Node<'_>) -> Option<String> {
+    let parent = binding.parent()?;
+    match parent.kind() {
+        "parameter" | "let_declaration"
+            if parent.child_by_field_name("pattern")?.id() == binding.id() =>
+        {
+            let ty = parent.child_by_field_name("type")?;
+            Some(source[ty.byte_range()].to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Remove the common indentation of `text` and indent it by `indent`
+fn reindent(text: &str, indent: &str) -> String {
+    let common = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(indent);
+            out.push_str(&line[common..]);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Extract the selected lines into a new top-level function named `name`
+///
+/// The selection is widened to whole lines. Locals declared earlier in the
+/// enclosing function and used by the selection become parameters (Rust
+/// parameter types are copied from the declaration when it spells one out,
+/// otherwise `_` is left to fill in). The new function is placed after the
+/// top-level item containing the selection, and the lines are replaced by a
+/// call. Selections that use `self`/`this` or define values needed after
+/// them are rejected.
+pub fn extract_function(
+    tree: &SyntaxTree,
+    source: &str,
+    range: Range<usize>,
+    name: &str,
+) -> Result<StructuralEdit> {
+    validate_name(name)?;
+    let language = tree.language();
+    let trimmed = trim_range(source, range);
+    if trimmed.is_empty() {
+        return Err(refactor_error("nothing selected"));
+    }
+
+    let start = line_start(source, trimmed.start);
+    let end = line_end(source, trimmed.end - 1);
+
+    // Innermost item containing the selection, and its top-level ancestor
+    let node = tree
+        .named_node_for_range(trimmed.clone())
+        .ok_or_else(|| refactor_error("selection is outside the syntax tree"))?;
+    let mut function = None;
+    let mut top = node;
+    let mut current = Some(node);
+    while let Some(n) = current {
+        if function.is_none() && language.is_item(n.kind()) {
+            function = Some(n);
+        }
+        if n.parent().is_some_and(|p| p.parent().is_none()) {
+            top = n;
+        }
+        current = n.parent();
+    }
+    let function = function
+        .filter(|f| f.start_byte() < start && f.end_byte() >= trimmed.end)
+        .ok_or_else(|| refactor_error("selection must be inside a function body"))?;
+
+    let region = tree
+        .root()
+        .descendant_for_byte_range(trimmed.start, trimmed.end)
+        .ok_or_else(|| refactor_error("selection is outside the syntax tree"))?;
+    let mut selected = Vec::new();
+    collect_in_range(region, trimmed.clone(), &mut selected);
+    if selected
+        .iter()
+        .any(|node| uses_receiver(language, source, *node))
+    {
+        return Err(refactor_error("selection uses self; extract a method instead"));
+    }
+
+    // Bindings visible before the selection
+    let mut before = Vec::new();
+    identifiers(function, &mut before);
+    let declared: Vec<Node<'_>> = before
+        .into_iter()
+        .filter(|id| id.end_byte() <= start && is_binding(language, *id))
+        .collect();
+    let is_declared = |name: &str| {
+        declared
+            .iter()
+            .rev()
+            .find(|id| &source[id.byte_range()] == name)
+            .copied()
+    };
+
+    let mut used = Vec::new();
+    for node in &selected {
+        identifiers(*node, &mut used);
+    }
+
+    let mut params: Vec<(String, Option<String>)> = Vec::new();
+    let mut local: HashSet<&str> = HashSet::new();
+    for id in &used {
+        let text = &source[id.byte_range()];
+        if is_binding(language, *id) {
+            local.insert(text);
+            continue;
+        }
+        if local.contains(text) || params.iter().any(|(p, _)| p == text) {
+            continue;
+        }
+        if let Some(binding) = is_declared(text) {
+            let ty = match language {
+                Language::Rust => {
+                    Some(rust_binding_type(source, binding).unwrap_or_else(|| "_".to_string()))
+                }
+                _ => None,
+            };
+            params.push((text.to_string(), ty));
+        }
+    }
+
+    // Values defined in the selection must not be needed afterwards
+    let mut after = Vec::new();
+    identifiers(function, &mut after);
+    if let Some(escaping) = after
+        .iter()
+        .filter(|id| id.start_byte() >= end)
+        .map(|id| &source[id.byte_range()])
+        .find(|text| local.contains(text))
+    {
+        return Err(refactor_error(format!(
+            "selection defines `{}`, which is used afterwards",
+            escaping
+        )));
+    }
+
+    let args: Vec<&str> = params.iter().map(|(p, _)| p.as_str()).collect();
+    let signature: Vec<String> = params
+        .iter()
+        .map(|(p, ty)| match ty {
+            Some(ty) => format!("{}: {}", p, ty),
+            None => p.clone(),
+        })
+        .collect();
+
+    let top_indent = indent_at(source, line_start(source, top.start_byte())).to_string();
+    let body_indent = format!("{}{}", top_indent, INDENT_UNIT);
+    let body = reindent(&source[start..end], &body_indent);
+    let (definition, separator) = match language {
+        Language::Rust => (
+            format!(
+                "{}fn {}({}) {{\n{}{}}}",
+                top_indent,
+                name,
+                signature.join(", "),
+                body,
+                top_indent
+            ),
+            "\n\n",
+        ),
+        Language::Python => (
+            format!(
+                "{}def {}({}):\n{}",
+                top_indent,
+                name,
+                signature.join(", "),
+                body.trim_end_matches('\n')
+            ),
+            "\n\n\n",
+        ),
+        Language::JavaScript => (
+            format!(
+                "{}function {}({}) {{\n{}{}}}",
+                top_indent,
+                name,
+                signature.join(", "),
+                body,
+                top_indent
+            ),
+            "\n\n",
+        ),
+    };
+
+    let call_indent = indent_at(source, start);
+    let terminator = if language == Language::Python {
+        ""
+    } else {
+        ";"
+    };
+    let call = format!("{}{}({}){}\n", call_indent, name, args.join(", "), terminator);
+
+    let replacement =
+        format!("{}{}{}{}", call, &source[end..top.end_byte()], separator, definition);
+    Ok(StructuralEdit {
+        range: start..top.end_byte(),
+        replacement,
+        cursor: start + call_indent.len(),
+    })
+}
+
+/// Top-most nodes under `node` that lie entirely inside `range`
+fn collect_in_range<'t>(node: Node<'t>, range: Range<usize>, out: &mut Vec<Node<'t>>) {
+    if node.start_byte() >= range.start && node.end_byte() <= range.end {
+        out.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.end_byte() > range.start && child.start_byte() < range.end {
+            collect_in_range(child, range.clone(), out);
+        }
+    }
+}
+
+/// Build the prompt for an LLM-assisted refactor of `range`
+///
+/// The model is asked to return the complete updated file in one fenced code
+/// block; [`RefactorProposal::from_response`] turns that into a diff.
+pub fn ai_refactor_prompt(
+    source: &str,
+    range: Range<usize>,
+    instruction: &str,
+    language: Option<Language>,
+) -> Result<String> {
+    if source.len() > MAX_AI_SOURCE_BYTES {
+        return Err(refactor_error(format!(
+            "file is too large for an assisted refactor ({} bytes, limit {})",
+            source.len(),
+            MAX_AI_SOURCE_BYTES
+        )));
+    }
+
+    let first_line = source[..range.start].matches('\n').count() + 1;
+    let last_line = first_line + source[range.clone()].trim_end().matches('\n').count();
+    let fence = code_fence(language);
+
+    Ok(format!(
+        "You are refactoring code in an editor.\n\
+         Instruction: {instruction}\n\n\
+         Apply the instruction to lines {first_line}-{last_line} of the file below. \
+         Keep behaviour identical, keep the existing style and indentation, and do not \
+         change unrelated code.\n\n\
+         Selected code:\n```{fence}\n{selection}\n```\n\n\
+         Full file:\n```{fence}\n{source}\n```\n\n\
+         Respond with the complete updated file in a single fenced code block and nothing else.",
+        selection = &source[range],
+    ))
+}
+
+/// Info string of fenced code blocks in `language`
+pub(crate) fn code_fence(language: Option<Language>) -> &'static str {
+    language.map_or("", |l| match l {
+        Language::Rust => "rust",
+        Language::Python => "python",
+        Language::JavaScript => "javascript",
+    })
+}
+
+/// LLM-proposed refactor, kept for review before it is applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefactorProposal {
+    /// Source the proposal was made against
+    pub original: String,
+    /// Proposed new source
+    pub proposed: String,
+    /// Unified diff from `original` to `proposed`
+    pub diff: String,
+}
+
+impl RefactorProposal {
+    /// Parse the model response for `original`
+    ///
+    /// Uses the first fenced code block when present, otherwise the whole
+    /// response. `label` names the file in the diff header.
+    pub fn from_response(original: &str, response: &str, label: &str) -> Result<Self> {
+        let mut proposed = extract_code_block(response)
+            .unwrap_or_else(|| response.trim())
+            .to_string();
+        if original.ends_with('\n') && !proposed.ends_with('\n') {
+            proposed.push('\n');
+        }
+
+        if proposed.trim().is_empty() {
+            return Err(refactor_error("model returned no code"));
+        }
+        if proposed == original {
+            return Err(refactor_error("model proposed no changes"));
+        }
+
+        let diff = TextDiff::from_lines(original, proposed.as_str())
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("a/{}", label), &format!("b/{}", label))
+            .to_string();
+
+        Ok(Self {
+            original: original.to_string(),
+            proposed,
+            diff,
+        })
+    }
+
+    /// Changed hunks turning `original` into `proposed`, in ascending order
+    ///
+    /// Unlike [`RefactorProposal::to_edit`], unrelated code between the
+    /// changes is left alone.
+    pub fn to_edits(&self) -> Vec<StructuralEdit> {
+        minimal_edits(&self.original, &self.proposed)
+    }
+
+    /// Smallest single replacement turning `original` into `proposed`
+    pub fn to_edit(&self) -> StructuralEdit {
+        let prefix = common_prefix(&self.original, &self.proposed);
+        let suffix = common_suffix(&self.original[prefix..], &self.proposed[prefix..]);
+
+        let range = prefix..self.original.len() - suffix;
+        let replacement = self.proposed[prefix..self.proposed.len() - suffix].to_string();
+        StructuralEdit {
+            cursor: prefix,
+            range,
+            replacement,
+        }
+    }
+}
+
+/// Contents of the first fenced code block in `text`
+pub(crate) fn extract_code_block(text: &str) -> Option<&str> {
+    let open = text.find("```")?;
+    let after_fence = &text[open + 3..];
+    // Skip the info string (language tag)
+    let body_start = after_fence.find('\n')? + 1;
+    let body = &after_fence[body_start..];
+    let close = body.find("\n```").map_or(body.len(), |i| i + 1);
+    Some(&body[..close])
+}
+
+/// Length in bytes of the common prefix, on a char boundary
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, ca), cb)| ca != cb)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i)
+}
+
+/// Length in bytes of the common suffix, on a char boundary
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(ca, cb)| ca == cb)
+        .map(|(c, _)| c.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(language: Language, source: &str) -> SyntaxTree {
+        SyntaxTree::parse(language, source).unwrap()
+    }
+
+    fn range_of(source: &str, needle: &str) -> Range<usize> {
+        let start = source.find(needle).unwrap();
+        start..start + needle.len()
+    }
+
+    #[test]
+    fn test_extract_variable_rust() {
+        let source = "fn f(a: i32) {\n    println!(\"{}\", a * 2 + 1);\n}\n";
+        let tree = parse(Language::Rust, source);
+
+        let edit = extract_variable(&tree, source, range_of(source, "a * 2"), "doubled").unwrap();
+        let result = edit.apply(source);
+        assert_eq!(
+            result,
+            "fn f(a: i32) {\n    let doubled = a * 2;\n    println!(\"{}\", doubled + 1);\n}\n"
+        );
+        assert_eq!(&result[edit.cursor..edit.cursor + 7], "doubled");
+    }
+
+    #[test]
+    fn test_extract_variable_python_and_js() {
+        let source = "def f(x):\n    return g(x + 1)\n";
+        let tree = parse(Language::Python, source);
+        let edit = extract_variable(&tree, source, range_of(source, "x + 1"), "y").unwrap();
+        assert_eq!(edit.apply(source), "def f(x):\n    y = x + 1\n    return g(y)\n");
+
+        let source = "function f(x) {\n  return g(x + 1);\n}\n";
+        let tree = parse(Language::JavaScript, source);
+        let edit = extract_variable(&tree, source, range_of(source, "x + 1"), "y").unwrap();
+        assert_eq!(edit.apply(source), "function f(x) {\n  const y = x + 1;\n  return g(y);\n}\n");
+    }
+
+    #[test]
+    fn test_extract_variable_rejects_bad_input() {
+        let source = "fn f() { g(); }";
+        let tree = parse(Language::Rust, source);
+        assert!(extract_variable(&tree, source, range_of(source, "g()"), "1x").is_err());
+        assert!(extract_variable(&tree, source, 9..9, "x").is_err());
+    }
+
+    #[test]
+    fn test_extract_function_rust() {
+        let source = "\
+fn main() {
+    let total: u32 = 10;
+    let name = \"x\";
+    if total > 5 {
+        println!(\"{} {}\", name, total);
+    }
+    done();
+}
+";
+        let tree = parse(Language::Rust, source);
+        let selection =
+            range_of(source, "if total > 5 {\n        println!(\"{} {}\", name, total);\n    }");
+
+        let edit = extract_function(&tree, source, selection, "report").unwrap();
+        assert_eq!(
+            edit.apply(source),
+            "\
+fn main() {
+    let total: u32 = 10;
+    let name = \"x\";
+    report(total, name);
+    done();
+}
+
+fn report(total: u32, name: _) {
+    if total > 5 {
+        println!(\"{} {}\", name, total);
+    }
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_extract_function_python() {
+        let source = "\
+class A:
+    def run(self, items):
+        count = 0
+        for item in items:
+            print(item, count)
+        return count
+";
+        let tree = parse(Language::Python, source);
+        let selection = range_of(source, "for item in items:\n            print(item, count)");
+
+        let edit = extract_function(&tree, source, selection, "show").unwrap();
+        assert_eq!(
+            edit.apply(source),
+            "\
+class A:
+    def run(self, items):
+        count = 0
+        show(items, count)
+        return count
+
+
+def show(items, count):
+    for item in items:
+        print(item, count)
+"
+        );
+    }
+
+    #[test]
+    fn test_extract_function_rejections() {
+        let source = "impl S {\n    fn f(&self) {\n        self.g();\n    }\n}\n";
+        let tree = parse(Language::Rust, source);
+        let err = extract_function(&tree, source, range_of(source, "self.g();"), "h");
+        assert!(err.unwrap_err().to_string().contains("self"));
+
+        let source = "fn f() {\n    let x = 1;\n    use_it(x);\n}\n";
+        let tree = parse(Language::Rust, source);
+        let err = extract_function(&tree, source, range_of(source, "let x = 1;"), "h");
+        assert!(err.unwrap_err().to_string().contains("`x`"));
+    }
+
+    #[test]
+    fn test_ai_prompt_mentions_selection() {
+        let source = "fn a() {}\nfn b() {\n    work();\n}\n";
+        let prompt = ai_refactor_prompt(
+            source,
+            range_of(source, "    work();"),
+            "Rename work to run",
+            Some(Language::Rust),
+        )
+        .unwrap();
+        assert!(prompt.contains("Rename work to run"));
+        assert!(prompt.contains("lines 3-3"));
+        assert!(prompt.contains("```rust"));
+
+        let huge = "x".repeat(MAX_AI_SOURCE_BYTES + 1);
+        assert!(ai_refactor_prompt(&huge, 0..1, "noop", None).is_err());
+    }
+
+    #[test]
+    fn test_proposal_from_response() {
+        let original = "fn b() {\n    work();\n}\n";
+        let response = "Here you go:\n```rust\nfn b() {\n    run();\n}\n```\n";
+
+        let proposal = RefactorProposal::from_response(original, response, "src/lib.rs").unwrap();
+        assert_eq!(proposal.proposed, "fn b() {\n    run();\n}\n");
+        assert!(proposal.diff.contains("--- a/src/lib.rs"));
+        assert!(proposal.diff.contains("-    work();"));
+        assert!(proposal.diff.contains("+    run();"));
+
+        let edit = proposal.to_edit();
+        assert_eq!(edit.replacement, "run");
+        assert_eq!(edit.apply(original), proposal.proposed);
+
+        let edits = proposal.to_edits();
+        assert_eq!(edits.len(), 1);
+        assert_eq!((&edits[0].range, &edits[0].replacement), (&edit.range, &edit.replacement));
+
+        assert!(RefactorProposal::from_response(original, original, "x").is_err());
+    }
+}
@@ -0,0 +1,794 @@
+//! Scripting API
+//!
+//! High-level facade over [`EditorState`] for plugins and tests. A [`Script`]
+//! opens buffers, edits at the cursor, moves, searches, runs named editor
+//! commands and asserts on the result, going through the same command and
+//! undo machinery as interactive editing.
+//!
+//! Steps can be driven three ways:
+//! - method calls on [`Script`]
+//! - [`ScriptStep`] values, which (de)serialize as `{"op": "insert", ...}` so
+//!   a plugin runtime can pass them across its sandbox boundary as JSON
+//! - the line-based test DSL parsed by [`parse`]
+//!
+//! # DSL
+//!
+//! ```text
+//! # comments and blank lines are ignored
+//! text rs "fn main() {}\n"     # scratch buffer, optional language
+//! open src/main.rs
+//! move line_end
+//! move down 2
+//! move 3:4                     # line:col, both 0-based
+//! insert "let x = 1;"
+//! delete 3                     # graphemes after the cursor
+//! search "needle"
+//! run join_lines
+//! assert_content "fn main() {}\n"
+//! assert_cursor 0:3
+//! ```
+//!
+//! Quoted strings accept `\n`, `\t`, `\"` and `\\`; an unquoted argument is
+//! taken verbatim up to the end of the line.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::buffer::{Buffer, BufferId};
+use crate::command::{DeleteCommand, InsertCommand};
+use crate::cursor::{Cursor, CursorPosition};
+use crate::error::{EditorError, Result};
+use crate::mode::Mode;
+use crate::state::EditorState;
+use crate::structural::{self, Direction, StructuralEdit};
+use crate::syntax::{Language, SyntaxTree};
+
+/// Cursor motion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    WordForward,
+    WordBackward,
+    FileStart,
+    FileEnd,
+    /// Absolute position (0-based line and character column)
+    To {
+        line: usize,
+        col: usize,
+    },
+}
+
+impl Motion {
+    /// Parse the snake_case name of a relative motion
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "left" => Motion::Left,
+            "right" => Motion::Right,
+            "up" => Motion::Up,
+            "down" => Motion::Down,
+            "line_start" => Motion::LineStart,
+            "line_end" => Motion::LineEnd,
+            "word_forward" => Motion::WordForward,
+            "word_backward" => Motion::WordBackward,
+            "file_start" => Motion::FileStart,
+            "file_end" => Motion::FileEnd,
+            _ => return None,
+        })
+    }
+
+    fn apply(self, cursor: &mut Cursor, buffer: &Buffer) -> Result<()> {
+        match self {
+            Motion::Left => cursor.move_left(buffer, 1),
+            Motion::Right => cursor.move_right(buffer, 1),
+            Motion::Up => cursor.move_up(buffer, 1),
+            Motion::Down => cursor.move_down(buffer, 1),
+            Motion::LineStart => cursor.move_to_line_start(buffer),
+            Motion::LineEnd => cursor.move_to_line_end(buffer),
+            Motion::WordForward => cursor.move_word_forward(buffer),
+            Motion::WordBackward => cursor.move_word_backward(buffer),
+            Motion::FileStart => cursor.move_to_buffer_start(),
+            Motion::FileEnd => cursor.move_to_buffer_end(buffer),
+            Motion::To { line, col } => cursor.move_to(buffer, line, col)?,
+        }
+        Ok(())
+    }
+}
+
+fn one() -> usize {
+    1
+}
+
+/// A single scripted operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ScriptStep {
+    /// Open a file and make it the active buffer
+    Open { path: PathBuf },
+    /// Open a scratch buffer with the given content
+    Text {
+        content: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    /// Insert text at the cursor
+    Insert { text: String },
+    /// Delete graphemes after the cursor
+    Delete {
+        #[serde(default = "one")]
+        count: usize,
+    },
+    /// Move the cursor
+    Move {
+        motion: Motion,
+        #[serde(default = "one")]
+        count: usize,
+    },
+    /// Move the cursor to the next match, wrapping around
+    Search { pattern: String },
+    /// Run a named editor command (see [`Script::run_command`])
+    Run { command: String },
+    /// Fail unless the active buffer contains exactly `expected`
+    AssertContent { expected: String },
+    /// Fail unless the cursor is at `line`:`col` (0-based)
+    AssertCursor { line: usize, col: usize },
+}
+
+/// Scripting facade over an [`EditorState`]
+#[derive(Debug)]
+pub struct Script<'a> {
+    state: &'a mut EditorState,
+    last_search: Option<String>,
+}
+
+impl<'a> Script<'a> {
+    /// Drive `state`
+    pub fn new(state: &'a mut EditorState) -> Self {
+        Self {
+            state,
+            last_search: None,
+        }
+    }
+
+    /// Underlying editor state
+    pub fn state(&self) -> &EditorState {
+        self.state
+    }
+
+    /// Open a file and make it the active buffer
+    pub fn open(&mut self, path: &Path) -> Result<BufferId> {
+        let buffer = Buffer::from_file(path)?;
+        Ok(self.activate(buffer))
+    }
+
+    /// Open a scratch buffer and make it the active buffer
+    ///
+    /// `language` is a file extension such as `rs`, used for syntax-aware
+    /// commands.
+    pub fn open_text(&mut self, content: &str, language: Option<&str>) -> BufferId {
+        let buffer = Buffer::from_string(content.to_string(), language.map(str::to_string));
+        self.activate(buffer)
+    }
+
+    fn activate(&mut self, buffer: Buffer) -> BufferId {
+        let id = self.state.open_buffer(buffer);
+        // Opening never fails to find the buffer it just added
+        let _ = self.state.switch_buffer(id);
+        id
+    }
+
+    /// Content of the active buffer
+    pub fn content(&self) -> Result<String> {
+        Ok(self.buffer()?.to_string())
+    }
+
+    /// Cursor position in the active buffer (0-based line and column)
+    pub fn cursor(&self) -> Result<CursorPosition> {
+        let cursor = self.state.cursor().ok_or(EditorError::NoActiveBuffer)?;
+        Ok(cursor.position(self.buffer()?))
+    }
+
+    fn buffer(&self) -> Result<&Buffer> {
+        self.state
+            .active_buffer()
+            .ok_or(EditorError::NoActiveBuffer)
+    }
+
+    fn cursor_pos(&self) -> Result<usize> {
+        Ok(self
+            .state
+            .cursor()
+            .ok_or(EditorError::NoActiveBuffer)?
+            .pos())
+    }
+
+    fn set_cursor_pos(&mut self, pos: usize) -> Result<()> {
+        let cursor = self.state.cursor_mut().ok_or(EditorError::NoActiveBuffer)?;
+        cursor.set_pos(pos);
+        Ok(())
+    }
+
+    /// Insert text at the cursor and move the cursor past it
+    pub fn insert(&mut self, text: &str) -> Result<()> {
+        let id = self.buffer()?.id();
+        let pos = self.cursor_pos()?;
+        self.state
+            .execute_command(Box::new(InsertCommand::new(id, pos, text)))?;
+        self.set_cursor_pos(pos + text.len())
+    }
+
+    /// Delete `count` graphemes after the cursor
+    pub fn delete(&mut self, count: usize) -> Result<()> {
+        let buffer = self.buffer()?;
+        let start = self.cursor_pos()?;
+        let mut end = Cursor::new(start);
+        end.move_right(buffer, count);
+        let end = end.pos();
+        if start < end {
+            let cmd = DeleteCommand::new(buffer.id(), start..end);
+            self.state.execute_command(Box::new(cmd))?;
+        }
+        Ok(())
+    }
+
+    /// Delete `count` graphemes before the cursor
+    pub fn backspace(&mut self, count: usize) -> Result<()> {
+        let buffer = self.buffer()?;
+        let end = self.cursor_pos()?;
+        let mut start = Cursor::new(end);
+        start.move_left(buffer, count);
+        let start = start.pos();
+        if start < end {
+            let cmd = DeleteCommand::new(buffer.id(), start..end);
+            self.state.execute_command(Box::new(cmd))?;
+            self.set_cursor_pos(start)?;
+        }
+        Ok(())
+    }
+
+    /// Move the cursor `count` times
+    ///
+    /// Moves end the current run of coalesced edits, as they do when typing.
+    pub fn move_cursor(&mut self, motion: Motion, count: usize) -> Result<()> {
+        let buffer = self
+            .state
+            .active_buffer()
+            .ok_or(EditorError::NoActiveBuffer)?;
+        let mut cursor = self
+            .state
+            .cursor()
+            .ok_or(EditorError::NoActiveBuffer)?
+            .clone();
+        for _ in 0..count.max(1) {
+            motion.apply(&mut cursor, buffer)?;
+        }
+        *self.state.cursor_mut().ok_or(EditorError::NoActiveBuffer)? = cursor;
+        self.state.break_undo_coalescing();
+        Ok(())
+    }
+
+    /// Move the cursor to the next match of `pattern` after it
+    ///
+    /// The search wraps around to the start of the buffer. Returns the new
+    /// cursor position, or `None` (leaving the cursor in place) when the
+    /// pattern does not occur.
+    pub fn search(&mut self, pattern: &str) -> Result<Option<CursorPosition>> {
+        if pattern.is_empty() {
+            return Err(EditorError::InvalidCommand("empty search pattern".to_string()));
+        }
+        self.last_search = Some(pattern.to_string());
+
+        let text = self.content()?;
+        let pos = self.cursor_pos()?;
+        let from = text[pos..]
+            .char_indices()
+            .nth(1)
+            .map_or(text.len(), |(offset, _)| pos + offset);
+        let found = text[from..]
+            .find(pattern)
+            .map(|offset| from + offset)
+            .or_else(|| text.find(pattern));
+
+        match found {
+            Some(pos) => {
+                self.set_cursor_pos(pos)?;
+                self.state.break_undo_coalescing();
+                self.cursor().map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Run a named editor command
+    ///
+    /// Names match the commands of `keybindings.custom`: `undo`, `redo`,
+    /// `save`, `search_next`, `enter_insert_mode`, `enter_normal_mode`,
+    /// `enter_visual_mode`, `insert_newline`, `delete_char`, `backspace`,
+    /// `delete_line`, `join_lines`, `move_item_up`, `move_item_down`,
+    /// `swap_argument_backward`, `swap_argument_forward`, `raise_node`,
+    /// `split_join_list`, and `move_` followed by a [`Motion`] name
+    /// (`move_line_end`, `move_word_forward`, ...).
+    pub fn run_command(&mut self, name: &str) -> Result<()> {
+        if let Some(motion) = name.strip_prefix("move_").and_then(Motion::from_name) {
+            return self.move_cursor(motion, 1);
+        }
+
+        match name {
+            "undo" => {
+                self.state.undo()?;
+                self.clamp_cursor()
+            }
+            "redo" => {
+                self.state.redo()?;
+                self.clamp_cursor()
+            }
+            "save" => {
+                let id = self.buffer()?.id();
+                self.state.buffer_manager.save(id)
+            }
+            "search_next" => {
+                let pattern = self.last_search.clone().ok_or_else(|| {
+                    EditorError::InvalidCommand("search_next before any search".to_string())
+                })?;
+                self.search(&pattern).map(|_| ())
+            }
+            "enter_insert_mode" => {
+                self.state.mode.enter_insert();
+                Ok(())
+            }
+            "enter_normal_mode" => {
+                self.state.mode.switch_to(Mode::Normal);
+                Ok(())
+            }
+            "enter_visual_mode" => {
+                self.state.mode.enter_visual();
+                Ok(())
+            }
+            "insert_newline" => {
+                let newline = self.buffer()?.line_ending().as_str();
+                self.insert(newline)
+            }
+            "delete_char" => self.delete(1),
+            "backspace" => self.backspace(1),
+            "delete_line" => self.delete_line(),
+            "join_lines" => {
+                let buffer = self.buffer()?;
+                let line = self.cursor()?.line;
+                let edit =
+                    structural::join_lines(&buffer.to_string(), line, Language::for_buffer(buffer));
+                self.apply_structural(edit)
+            }
+            "move_item_up" => self.structural_edit(|tree, source, range| {
+                structural::move_item(tree, source, range.start, Direction::Backward)
+            }),
+            "move_item_down" => self.structural_edit(|tree, source, range| {
+                structural::move_item(tree, source, range.start, Direction::Forward)
+            }),
+            "swap_argument_backward" => self.structural_edit(|tree, source, range| {
+                structural::swap_argument(tree, source, range.start, Direction::Backward)
+            }),
+            "swap_argument_forward" => self.structural_edit(|tree, source, range| {
+                structural::swap_argument(tree, source, range.start, Direction::Forward)
+            }),
+            "raise_node" => self.structural_edit(structural::raise),
+            "split_join_list" => self.structural_edit(|tree, source, range| {
+                structural::toggle_list_split(tree, source, range.start)
+            }),
+            _ => Err(EditorError::InvalidCommand(name.to_string())),
+        }
+    }
+
+    fn delete_line(&mut self) -> Result<()> {
+        let buffer = self.buffer()?;
+        let line = self.cursor()?.line;
+        let start = buffer.line_col_to_pos(line, 0).unwrap_or(0);
+        let end = buffer
+            .line_col_to_pos(line + 1, 0)
+            .unwrap_or_else(|| buffer.len_bytes());
+        let cmd = DeleteCommand::new(buffer.id(), start..end);
+        self.state.execute_command(Box::new(cmd))?;
+        self.clamp_cursor()
+    }
+
+    /// Run a syntax-aware operation on the selection or cursor
+    ///
+    /// Fails when the buffer has no grammar or the operation does not apply
+    /// at the cursor, so scripts notice an edit that did nothing.
+    fn structural_edit(
+        &mut self,
+        op: impl FnOnce(&SyntaxTree, &str, std::ops::Range<usize>) -> Option<StructuralEdit>,
+    ) -> Result<()> {
+        let buffer = self.buffer()?;
+        let tree = SyntaxTree::for_buffer(buffer)?.ok_or_else(|| {
+            EditorError::Syntax(format!("no grammar for {:?}", buffer.language()))
+        })?;
+        let cursor = self.state.cursor().ok_or(EditorError::NoActiveBuffer)?;
+        let range = cursor.selection().unwrap_or(cursor.pos()..cursor.pos());
+        let edit = op(&tree, &buffer.to_string(), range);
+        self.apply_structural(edit)
+    }
+
+    fn apply_structural(&mut self, edit: Option<StructuralEdit>) -> Result<()> {
+        let edit = edit.ok_or_else(|| {
+            EditorError::InvalidCommand("structural edit not applicable here".to_string())
+        })?;
+        let id = self.buffer()?.id();
+        let cursor = edit.cursor;
+        self.state.break_undo_coalescing();
+        self.state
+            .execute_command(Box::new(edit.into_command(id)))?;
+        self.state.break_undo_coalescing();
+        self.set_cursor_pos(cursor)
+    }
+
+    fn clamp_cursor(&mut self) -> Result<()> {
+        let len = self.buffer()?.len_bytes();
+        let pos = self.cursor_pos()?.min(len);
+        self.set_cursor_pos(pos)
+    }
+
+    /// Fail unless the active buffer contains exactly `expected`
+    pub fn assert_content(&self, expected: &str) -> Result<()> {
+        let actual = self.content()?;
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(EditorError::Assertion(format!(
+                "expected content {:?}, found {:?}",
+                expected, actual
+            )))
+        }
+    }
+
+    /// Fail unless the cursor is at `line`:`col` (0-based)
+    pub fn assert_cursor(&self, line: usize, col: usize) -> Result<()> {
+        let actual = self.cursor()?;
+        if actual == CursorPosition::new(line, col) {
+            Ok(())
+        } else {
+            Err(EditorError::Assertion(format!(
+                "expected cursor at {}:{}, found {}:{}",
+                line, col, actual.line, actual.col
+            )))
+        }
+    }
+
+    /// Execute one step
+    pub fn step(&mut self, step: &ScriptStep) -> Result<()> {
+        match step {
+            ScriptStep::Open { path } => self.open(path).map(|_| ()),
+            ScriptStep::Text { content, language } => {
+                self.open_text(content, language.as_deref());
+                Ok(())
+            }
+            ScriptStep::Insert { text } => self.insert(text),
+            ScriptStep::Delete { count } => self.delete(*count),
+            ScriptStep::Move { motion, count } => self.move_cursor(*motion, *count),
+            ScriptStep::Search { pattern } => self.search(pattern).map(|_| ()),
+            ScriptStep::Run { command } => self.run_command(command),
+            ScriptStep::AssertContent { expected } => self.assert_content(expected),
+            ScriptStep::AssertCursor { line, col } => self.assert_cursor(*line, *col),
+        }
+    }
+
+    /// Execute steps in order, stopping at the first failure
+    ///
+    /// The error names the 1-based number of the failing step.
+    pub fn run(&mut self, steps: &[ScriptStep]) -> Result<()> {
+        for (index, step) in steps.iter().enumerate() {
+            self.step(step)
+                .map_err(|e| EditorError::Other(format!("step {}: {}", index + 1, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Parse and execute a DSL script
+    pub fn run_source(&mut self, source: &str) -> Result<()> {
+        let steps = parse(source)?;
+        self.run(&steps)
+    }
+}
+
+/// Parse a DSL script into steps
+pub fn parse(source: &str) -> Result<Vec<ScriptStep>> {
+    let mut steps = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let step = parse_step(line)
+            .map_err(|msg| EditorError::InvalidCommand(format!("line {}: {}", index + 1, msg)))?;
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+fn parse_step(line: &str) -> std::result::Result<ScriptStep, String> {
+    let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+
+    Ok(match keyword {
+        "open" => ScriptStep::Open {
+            path: PathBuf::from(parse_string(required(rest)?)?),
+        },
+        "text" => {
+            let (language, content) = if rest.starts_with('"') {
+                (None, rest)
+            } else {
+                let (language, content) = rest
+                    .split_once(char::is_whitespace)
+                    .ok_or("expected `text [language] \"content\"`")?;
+                (Some(language.to_string()), content.trim())
+            };
+            ScriptStep::Text {
+                content: parse_string(required(content)?)?,
+                language,
+            }
+        }
+        "insert" => ScriptStep::Insert {
+            text: parse_string(required(rest)?)?,
+        },
+        "delete" => ScriptStep::Delete {
+            count: parse_count(rest)?,
+        },
+        "move" => {
+            let (target, count) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let motion = match parse_position(target) {
+                Some((line, col)) => Motion::To { line, col },
+                None => Motion::from_name(required(target)?)
+                    .ok_or_else(|| format!("unknown motion `{}`", target))?,
+            };
+            ScriptStep::Move {
+                motion,
+                count: parse_count(count.trim())?,
+            }
+        }
+        "search" => ScriptStep::Search {
+            pattern: parse_string(required(rest)?)?,
+        },
+        "run" => ScriptStep::Run {
+            command: required(rest)?.to_string(),
+        },
+        "assert_content" => ScriptStep::AssertContent {
+            expected: parse_string(required(rest)?)?,
+        },
+        "assert_cursor" => {
+            let (line, col) =
+                parse_position(rest).ok_or_else(|| format!("expected line:col, got `{}`", rest))?;
+            ScriptStep::AssertCursor { line, col }
+        }
+        _ => return Err(format!("unknown step `{}`", keyword)),
+    })
+}
+
+fn required(arg: &str) -> std::result::Result<&str, String> {
+    if arg.is_empty() {
+        Err("missing argument".to_string())
+    } else {
+        Ok(arg)
+    }
+}
+
+fn parse_count(arg: &str) -> std::result::Result<usize, String> {
+    if arg.is_empty() {
+        return Ok(1);
+    }
+    arg.parse()
+        .map_err(|_| format!("expected a count, got `{}`", arg))
+}
+
+fn parse_position(arg: &str) -> Option<(usize, usize)> {
+    let (line, col) = arg.split_once(':')?;
+    Some((line.parse().ok()?, col.parse().ok()?))
+}
+
+/// Unquote a `"..."` argument, or take an unquoted one verbatim
+fn parse_string(arg: &str) -> std::result::Result<String, String> {
+    let Some(quoted) = arg.strip_prefix('"') else {
+        return Ok(arg.to_string());
+    };
+
+    let mut out = String::new();
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                let trailing = chars.as_str().trim();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    return Err(format!("unexpected `{}` after string", trailing));
+                }
+                return Ok(out);
+            }
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('"') => out.push('"'),
+                Some('\\') => out.push('\\'),
+                Some(other) => return Err(format!("unknown escape `\\{}`", other)),
+                None => break,
+            },
+            _ => out.push(ch),
+        }
+    }
+    Err("unterminated string".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(content: &str) -> EditorState {
+        let mut state = EditorState::new();
+        Script::new(&mut state).open_text(content, None);
+        state
+    }
+
+    #[test]
+    fn test_insert_moves_cursor() {
+        let mut state = scratch("world");
+        let mut script = Script::new(&mut state);
+
+        script.insert("hello ").unwrap();
+        script.assert_content("hello world").unwrap();
+        script.assert_cursor(0, 6).unwrap();
+    }
+
+    #[test]
+    fn test_move_and_delete() {
+        let mut state = scratch("one\ntwo\nthree");
+        let mut script = Script::new(&mut state);
+
+        script.move_cursor(Motion::Down, 2).unwrap();
+        script.assert_cursor(2, 0).unwrap();
+        script.delete(2).unwrap();
+        script.assert_content("one\ntwo\nree").unwrap();
+
+        script
+            .move_cursor(Motion::To { line: 0, col: 3 }, 1)
+            .unwrap();
+        script.backspace(1).unwrap();
+        script.assert_content("on\ntwo\nree").unwrap();
+        script.assert_cursor(0, 2).unwrap();
+    }
+
+    #[test]
+    fn test_search_wraps() {
+        let mut state = scratch("foo bar foo");
+        let mut script = Script::new(&mut state);
+
+        assert_eq!(script.search("foo").unwrap(), Some(CursorPosition::new(0, 8)));
+        script.run_command("search_next").unwrap();
+        script.assert_cursor(0, 0).unwrap();
+        assert_eq!(script.search("baz").unwrap(), None);
+        script.assert_cursor(0, 0).unwrap();
+    }
+
+    #[test]
+    fn test_run_command_undo() {
+        let mut state = scratch("");
+        let mut script = Script::new(&mut state);
+
+        script.insert("abc").unwrap();
+        script.run_command("undo").unwrap();
+        script.assert_content("").unwrap();
+        script.assert_cursor(0, 0).unwrap();
+        script.run_command("redo").unwrap();
+        script.assert_content("abc").unwrap();
+    }
+
+    #[test]
+    fn test_run_command_delete_line() {
+        let mut state = scratch("a\nb\nc");
+        let mut script = Script::new(&mut state);
+
+        script.run_command("move_down").unwrap();
+        script.run_command("delete_line").unwrap();
+        script.assert_content("a\nc").unwrap();
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let mut state = scratch("");
+        let err = Script::new(&mut state)
+            .run_command("frobnicate")
+            .unwrap_err();
+        assert!(matches!(err, EditorError::InvalidCommand(_)));
+    }
+
+    #[test]
+    fn test_assertion_failure() {
+        let mut state = scratch("abc");
+        let err = Script::new(&mut state).assert_content("abd").unwrap_err();
+        assert!(matches!(err, EditorError::Assertion(_)));
+    }
+
+    #[test]
+    fn test_no_active_buffer() {
+        let mut state = EditorState::new();
+        let err = Script::new(&mut state).insert("x").unwrap_err();
+        assert!(matches!(err, EditorError::NoActiveBuffer));
+    }
+
+    #[test]
+    fn test_parse_dsl() {
+        let steps = parse(
+            "# setup\n\
+             text rs \"fn main() {}\\n\"\n\
+             move 0:3\n\
+             move word_forward 2\n\
+             insert \"say \\\"hi\\\"\"  # trailing comment\n\
+             search needle with spaces\n\
+             run join_lines\n\
+             assert_cursor 1:0\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            steps,
+            vec![
+                ScriptStep::Text {
+                    content: "fn main() {}\n".to_string(),
+                    language: Some("rs".to_string()),
+                },
+                ScriptStep::Move {
+                    motion: Motion::To { line: 0, col: 3 },
+                    count: 1,
+                },
+                ScriptStep::Move {
+                    motion: Motion::WordForward,
+                    count: 2,
+                },
+                ScriptStep::Insert {
+                    text: "say \"hi\"".to_string(),
+                },
+                ScriptStep::Search {
+                    pattern: "needle with spaces".to_string(),
+                },
+                ScriptStep::Run {
+                    command: "join_lines".to_string(),
+                },
+                ScriptStep::AssertCursor { line: 1, col: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_name_line() {
+        let err = parse("insert \"ok\"\nmove sideways").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid command: line 2: unknown motion `sideways`");
+        assert!(parse("insert \"open").is_err());
+        assert!(parse("delete many").is_err());
+    }
+
+    #[test]
+    fn test_steps_from_json() {
+        let steps: Vec<ScriptStep> = serde_json::from_str(
+            r#"[
+                {"op": "text", "content": "ab"},
+                {"op": "move", "motion": "file_end"},
+                {"op": "insert", "text": "c"},
+                {"op": "assert_content", "expected": "abc"}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut state = EditorState::new();
+        Script::new(&mut state).run(&steps).unwrap();
+    }
+
+    #[test]
+    fn test_run_reports_failing_step() {
+        let mut state = EditorState::new();
+        let err = Script::new(&mut state)
+            .run_source("text \"ab\"\nassert_content \"ab\"\nassert_cursor 0:1")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "step 3: Assertion failed: expected cursor at 0:1, found 0:0");
+    }
+}
@@ -0,0 +1,384 @@
+//! Search and Replace
+//!
+//! Literal and regex search within a buffer and across all open buffers.
+//! Matches are byte ranges into the buffer text, the same unit as cursor
+//! positions. Replacements in regex mode may refer to capture groups
+//! (`$1`, `${name}`); literal replacements are inserted verbatim.
+
+use std::ops::Range;
+use std::path::PathBuf;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::buffer::{Buffer, BufferId, BufferManager};
+use crate::command::Command;
+use crate::error::{EditorError, Result};
+
+/// How a search pattern is interpreted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Treat the pattern as a regular expression instead of literal text
+    pub regex: bool,
+    /// Ignore case when matching
+    pub case_insensitive: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+}
+
+impl SearchOptions {
+    /// Options with smart case: case-insensitive unless the pattern has an
+    /// uppercase letter
+    pub fn smart_case(pattern: &str, regex: bool) -> Self {
+        Self {
+            regex,
+            case_insensitive: !pattern.chars().any(char::is_uppercase),
+            whole_word: false,
+        }
+    }
+}
+
+/// A single match in a buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Byte range of the match
+    pub range: Range<usize>,
+    /// Line the match starts on (0-indexed)
+    pub line: usize,
+}
+
+/// Matches in one buffer, from a search across buffers
+#[derive(Debug, Clone)]
+pub struct BufferMatches {
+    pub buffer_id: BufferId,
+    pub path: Option<PathBuf>,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Compiled search pattern
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    pattern: String,
+    options: SearchOptions,
+    regex: Regex,
+}
+
+impl SearchQuery {
+    /// Compile `pattern`
+    ///
+    /// # Errors
+    /// Returns [`EditorError::InvalidPattern`] if the pattern is empty or not
+    /// a valid regex.
+    pub fn new(pattern: &str, options: SearchOptions) -> Result<Self> {
+        if pattern.is_empty() {
+            return Err(EditorError::InvalidPattern("empty pattern".to_string()));
+        }
+
+        let mut source = if options.regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        if options.whole_word {
+            source = format!(r"\b(?:{})\b", source);
+        }
+
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(options.case_insensitive)
+            .multi_line(true)
+            .build()
+            .map_err(|e| EditorError::InvalidPattern(e.to_string()))?;
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            options,
+            regex,
+        })
+    }
+
+    /// Pattern as typed
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Options the pattern was compiled with
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// All matches in `text`, in order
+    pub fn find_all(&self, text: &str) -> Vec<SearchMatch> {
+        let mut line = 0;
+        let mut counted = 0;
+        self.regex
+            .find_iter(text)
+            .map(|m| {
+                line += text[counted..m.start()].matches('\n').count();
+                counted = m.start();
+                SearchMatch {
+                    range: m.range(),
+                    line,
+                }
+            })
+            .collect()
+    }
+
+    /// All matches in a buffer
+    pub fn find_in_buffer(&self, buffer: &Buffer) -> Vec<SearchMatch> {
+        self.find_all(&buffer.to_string())
+    }
+
+    /// First match starting after `pos`, wrapping to the start of the text
+    pub fn next_match(&self, text: &str, pos: usize) -> Option<Range<usize>> {
+        let mut matches = self.regex.find_iter(text).filter(|m| !m.is_empty());
+        let first = matches.next()?;
+        if first.start() > pos {
+            return Some(first.range());
+        }
+        Some(matches.find(|m| m.start() > pos).unwrap_or(first).range())
+    }
+
+    /// Last match starting before `pos`, wrapping to the end of the text
+    pub fn prev_match(&self, text: &str, pos: usize) -> Option<Range<usize>> {
+        let mut last = None;
+        let mut before = None;
+        for m in self.regex.find_iter(text).filter(|m| !m.is_empty()) {
+            if m.start() < pos {
+                before = Some(m.range());
+            }
+            last = Some(m.range());
+        }
+        before.or(last)
+    }
+
+    /// Replacements for every match in `text` within `scope`
+    ///
+    /// Returns `(range, new_text)` pairs in order. A match counts as within
+    /// the scope only if it lies entirely inside it.
+    pub fn replacements(
+        &self,
+        text: &str,
+        replacement: &str,
+        scope: Option<Range<usize>>,
+    ) -> Vec<(Range<usize>, String)> {
+        let in_scope = |range: &Range<usize>| {
+            scope
+                .as_ref()
+                .map_or(true, |s| range.start >= s.start && range.end <= s.end)
+        };
+
+        self.regex
+            .captures_iter(text)
+            .filter_map(|caps| {
+                let range = caps.get(0)?.range();
+                if !in_scope(&range) {
+                    return None;
+                }
+                let new_text = if self.options.regex {
+                    let mut expanded = String::new();
+                    caps.expand(replacement, &mut expanded);
+                    expanded
+                } else {
+                    replacement.to_string()
+                };
+                Some((range, new_text))
+            })
+            .collect()
+    }
+}
+
+/// Search every open buffer, skipping those without matches
+pub fn search_buffers(manager: &BufferManager, query: &SearchQuery) -> Vec<BufferMatches> {
+    manager
+        .buffer_ids()
+        .into_iter()
+        .filter_map(|id| {
+            let buffer = manager.get(id)?;
+            let matches = query.find_in_buffer(buffer);
+            (!matches.is_empty()).then(|| BufferMatches {
+                buffer_id: id,
+                path: buffer.path().map(PathBuf::from),
+                matches,
+            })
+        })
+        .collect()
+}
+
+/// Replace every match in a buffer as one undoable step
+#[derive(Debug, Clone)]
+pub struct ReplaceAllCommand {
+    buffer_id: BufferId,
+    query: SearchQuery,
+    replacement: String,
+    scope: Option<Range<usize>>,
+    /// Range of each inserted text after execute, with the text it replaced
+    applied: Vec<(Range<usize>, String)>,
+}
+
+impl ReplaceAllCommand {
+    pub fn new(buffer_id: BufferId, query: SearchQuery, replacement: impl Into<String>) -> Self {
+        Self {
+            buffer_id,
+            query,
+            replacement: replacement.into(),
+            scope: None,
+            applied: Vec::new(),
+        }
+    }
+
+    /// Only replace matches inside `range`, such as a selection
+    pub fn within(mut self, range: Range<usize>) -> Self {
+        self.scope = Some(range);
+        self
+    }
+
+    /// Buffer the command applies to
+    pub fn buffer_id(&self) -> BufferId {
+        self.buffer_id
+    }
+
+    /// Number of matches replaced by the last execute
+    pub fn replaced(&self) -> usize {
+        self.applied.len()
+    }
+}
+
+impl Command for ReplaceAllCommand {
+    fn execute(&mut self, buffer: &mut Buffer) -> Result<()> {
+        let text = buffer.to_string();
+        let replacements = self
+            .query
+            .replacements(&text, &self.replacement, self.scope.clone());
+
+        // Record where each replacement ends up once the earlier ones are in
+        let mut applied = Vec::with_capacity(replacements.len());
+        let mut shift = 0isize;
+        for (range, new_text) in &replacements {
+            let start = range.start.saturating_add_signed(shift);
+            applied.push((start..start + new_text.len(), text[range.clone()].to_string()));
+            shift += new_text.len() as isize - range.len() as isize;
+        }
+
+        // Back to front, so earlier ranges stay valid
+        for (range, new_text) in replacements.iter().rev() {
+            buffer.replace(range.clone(), new_text)?;
+        }
+        self.applied = applied;
+        Ok(())
+    }
+
+    fn undo(&mut self, buffer: &mut Buffer) -> Result<()> {
+        for (range, old_text) in self.applied.iter().rev() {
+            buffer.replace(range.clone(), old_text)?;
+        }
+        Ok(())
+    }
+
+    fn description(&self) -> &str {
+        "Replace all"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(pattern: &str, options: SearchOptions) -> SearchQuery {
+        SearchQuery::new(pattern, options).unwrap()
+    }
+
+    #[test]
+    fn test_literal_and_regex_search() {
+        let text = "fn a.b() {}\nlet ab = a.b;\n";
+
+        // Literal patterns escape regex syntax
+        let literal = query("a.b", SearchOptions::default());
+        let matches = literal.find_all(text);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].range, 3..6);
+        assert_eq!(matches[1].line, 1);
+
+        let regex = query(
+            "a.?b",
+            SearchOptions {
+                regex: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(regex.find_all(text).len(), 3);
+
+        let word = query(
+            "ab",
+            SearchOptions {
+                whole_word: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(word.find_all(text).len(), 1);
+
+        assert!(SearchOptions::smart_case("foo", false).case_insensitive);
+        assert!(!SearchOptions::smart_case("Foo", false).case_insensitive);
+        assert!(SearchQuery::new("", SearchOptions::default()).is_err());
+        assert!(SearchQuery::new(
+            "(",
+            SearchOptions {
+                regex: true,
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_next_and_prev_wrap() {
+        let text = "x ab ab ab";
+        let q = query("ab", SearchOptions::default());
+
+        assert_eq!(q.next_match(text, 0), Some(2..4));
+        assert_eq!(q.next_match(text, 2), Some(5..7));
+        assert_eq!(q.next_match(text, 8), Some(2..4));
+        assert_eq!(q.prev_match(text, 5), Some(2..4));
+        assert_eq!(q.prev_match(text, 2), Some(8..10));
+        assert_eq!(q.next_match("none", 0), None);
+    }
+
+    #[test]
+    fn test_replace_all_with_captures_and_undo() {
+        let original = "let x = foo(1);\nlet y = foo(22);\n";
+        let mut buffer = Buffer::from_string(original.to_string(), None);
+        let q = query(
+            r"foo\((\d+)\)",
+            SearchOptions {
+                regex: true,
+                ..Default::default()
+            },
+        );
+
+        let mut cmd = ReplaceAllCommand::new(buffer.id(), q, "bar($1, $1)");
+        cmd.execute(&mut buffer).unwrap();
+        assert_eq!(cmd.replaced(), 2);
+        assert_eq!(buffer.to_string(), "let x = bar(1, 1);\nlet y = bar(22, 22);\n");
+
+        cmd.undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), original);
+
+        // Literal replacements keep `$` as is
+        let q = query("foo", SearchOptions::default());
+        let mut cmd = ReplaceAllCommand::new(buffer.id(), q, "$1").within(0..16);
+        cmd.execute(&mut buffer).unwrap();
+        assert_eq!(cmd.replaced(), 1);
+        assert_eq!(buffer.to_string(), "let x = $1(1);\nlet y = foo(22);\n");
+    }
+
+    #[test]
+    fn test_search_buffers() {
+        let mut manager = BufferManager::new();
+        let first =
+            manager.add_buffer(Buffer::from_string("needle\nhay\nneedle".to_string(), None));
+        manager.add_buffer(Buffer::from_string("hay".to_string(), None));
+
+        let results = search_buffers(&manager, &query("needle", SearchOptions::default()));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].buffer_id, first);
+        assert_eq!(results[0].matches[1].line, 2);
+    }
+}
@@ -4,7 +4,9 @@
 
 use std::ops::Range;
 
+use crate::buffer::Buffer;
 use crate::cursor::CursorPosition;
+use crate::width;
 
 /// Selection range in buffer
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -48,6 +50,36 @@ impl SelectionRange {
         let end_pos = line_col_to_pos(self.end.line, self.end.col)?;
         Some(start_pos..end_pos)
     }
+
+    /// Display columns covered by this selection on `line`
+    ///
+    /// Returns `None` when the selection does not touch the line. Lines fully
+    /// inside a multi-line selection span their whole display width.
+    pub fn display_span(&self, buffer: &Buffer, line: usize) -> Option<Range<usize>> {
+        let range = self.normalized();
+        if line < range.start.line || line > range.end.line {
+            return None;
+        }
+
+        let display_col = |pos: &CursorPosition| {
+            buffer
+                .line_col_to_pos(pos.line, pos.col)
+                .map(|byte| buffer.pos_to_display_col(byte).1)
+        };
+
+        let start = if line == range.start.line {
+            display_col(&range.start)?
+        } else {
+            0
+        };
+        let end = if line == range.end.line {
+            display_col(&range.end)?
+        } else {
+            width::str_width(&buffer.line(line)?)
+        };
+
+        Some(start..end)
+    }
 }
 
 /// Text selection state
@@ -187,6 +219,26 @@ mod tests {
         assert!(!sel.is_empty());
     }
 
+    #[test]
+    fn test_selection_display_span_wide_chars() {
+        let buffer = Buffer::from_string("aあいb\nxyz".to_string(), None);
+        // Characters 1..3 are あい, four cells starting at column 1
+        let range = SelectionRange::new(CursorPosition::new(0, 1), CursorPosition::new(0, 3));
+
+        assert_eq!(range.display_span(&buffer, 0), Some(1..5));
+        assert_eq!(range.display_span(&buffer, 1), None);
+    }
+
+    #[test]
+    fn test_selection_display_span_multiline() {
+        let buffer = Buffer::from_string("日本\nabc\nxyz".to_string(), None);
+        let range = SelectionRange::new(CursorPosition::new(2, 1), CursorPosition::new(0, 1));
+
+        assert_eq!(range.display_span(&buffer, 0), Some(2..4));
+        assert_eq!(range.display_span(&buffer, 1), Some(0..3));
+        assert_eq!(range.display_span(&buffer, 2), Some(0..1));
+    }
+
     #[test]
     fn test_selection_clear() {
         let mut sel = Selection::new();
@@ -0,0 +1,688 @@
+//! Semantic Diff
+//!
+//! Line diffs of large generated changes are hard to review: a moved
+//! function shows up as one big deletion plus one big insertion, and edits
+//! to a dozen functions blur into one stream of hunks. The semantic diff
+//! parses both versions, matches items (functions, types, methods, ...) by
+//! kind and qualified name, and reports per item whether it was added,
+//! removed, modified, moved or renamed. Modified items carry their own line
+//! diff; changes outside any item are collected separately.
+//!
+//! Members of containers (impl blocks, traits, modules, classes) are
+//! reported individually, qualified with the container name, e.g.
+//! `Parser::parse` or `<Token as Display>::fmt`.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use similar::{capture_diff_slices, Algorithm, ChangeTag, DiffTag, TextDiff};
+use tree_sitter::Node;
+
+use crate::error::Result;
+use crate::structural::{is_item_node, item_range};
+use crate::syntax::{Language, SyntaxTree};
+
+/// Unchanged lines shown around the changes in a modified item
+const CONTEXT_LINES: usize = 3;
+
+/// How an item changed between the two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemChangeKind {
+    /// Only in the new version
+    Added,
+    /// Only in the old version
+    Removed,
+    /// In both versions with different text
+    Modified,
+    /// Same text at a different position or in a different container
+    Moved,
+    /// Same text apart from the item name
+    Renamed,
+}
+
+/// Kind of a line in a line diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineTag {
+    /// Unchanged line around a change
+    Context,
+    /// Line only in the new version
+    Added,
+    /// Line only in the old version
+    Removed,
+}
+
+/// A line in a line diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineChange {
+    /// Whether the line was added, removed or is context
+    pub tag: LineTag,
+    /// Line text without the line ending
+    pub text: String,
+    /// Zero-based line in the old version, `None` for added lines
+    pub old_line: Option<usize>,
+    /// Zero-based line in the new version, `None` for removed lines
+    pub new_line: Option<usize>,
+}
+
+/// Change to a single item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemChange {
+    /// How the item changed
+    pub kind: ItemChangeKind,
+    /// Tree-sitter node kind, e.g. `function_item`
+    pub item_kind: &'static str,
+    /// Qualified name in the new version, or the old one for removed items
+    pub name: String,
+    /// Qualified name in the old version, if it differs
+    pub old_name: Option<String>,
+    /// Zero-based line range in the old version
+    pub old_lines: Option<Range<usize>>,
+    /// Zero-based line range in the new version
+    pub new_lines: Option<Range<usize>>,
+    /// Line diff of a modified item with a few lines of context, or all
+    /// lines of an added or removed item
+    pub lines: Vec<LineChange>,
+}
+
+/// Changes between two versions of a file, grouped by item
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SemanticDiff {
+    /// Changed items in file order
+    pub changes: Vec<ItemChange>,
+    /// Changed lines outside any item, such as imports in Python or
+    /// top-level statements
+    pub other: Vec<LineChange>,
+}
+
+impl SemanticDiff {
+    /// Check if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.other.is_empty()
+    }
+}
+
+/// Diff `old` and `new`, both written in `language`, item by item
+pub fn semantic_diff(language: Language, old: &str, new: &str) -> Result<SemanticDiff> {
+    let old_tree = SyntaxTree::parse(language, old)?;
+    let new_tree = SyntaxTree::parse(language, new)?;
+    let old = Version::new(old, &old_tree);
+    let new = Version::new(new, &new_tree);
+
+    // Items match by kind and qualified name, repeated names by occurrence
+    let old_keys = keys(&old.items);
+    let new_index: HashMap<_, usize> = keys(&new.items)
+        .into_iter()
+        .enumerate()
+        .map(|(j, key)| (key, j))
+        .collect();
+    let mut old_match = vec![None; old.items.len()];
+    let mut new_match = vec![None; new.items.len()];
+    for (i, key) in old_keys.iter().enumerate() {
+        if let Some(&j) = new_index.get(key) {
+            old_match[i] = Some(j);
+            new_match[j] = Some(i);
+        }
+    }
+
+    // Matched items that left the longest common order have moved
+    let old_order: Vec<usize> = old_match.iter().flatten().copied().collect();
+    let new_order: Vec<usize> = (0..new.items.len())
+        .filter(|&j| new_match[j].is_some())
+        .collect();
+    let mut reordered = vec![false; new.items.len()];
+    for op in capture_diff_slices(Algorithm::Myers, &old_order, &new_order) {
+        if op.tag() != DiffTag::Equal {
+            for &j in &new_order[op.new_range()] {
+                reordered[j] = true;
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (j, item) in new.items.iter().enumerate() {
+        let Some(i) = new_match[j] else {
+            continue;
+        };
+        let old_item = &old.items[i];
+        if old.text(old_item) != new.text(item) {
+            let mut modified = change(old_item, ItemChangeKind::Modified, Some(item));
+            modified.lines = line_diff(&old, old_item, &new, item);
+            changes.push(modified);
+        } else if reordered[j] {
+            changes.push(change(old_item, ItemChangeKind::Moved, Some(item)));
+        }
+    }
+
+    // Unmatched items with the same text moved to another container; with
+    // the same text apart from their name they were renamed
+    for (j, item) in new.items.iter().enumerate() {
+        if new_match[j].is_some() {
+            continue;
+        }
+        let candidates = || (0..old.items.len()).filter(|&i| old_match[i].is_none());
+        let found = candidates()
+            .find(|&i| {
+                old.items[i].kind == item.kind
+                    && old.node_text(&old.items[i]) == new.node_text(item)
+            })
+            .map(|i| (i, ItemChangeKind::Moved))
+            .or_else(|| {
+                let unnamed = new.without_name(item)?;
+                candidates()
+                    .find(|&i| {
+                        old.items[i].kind == item.kind
+                            && old.without_name(&old.items[i]).as_ref() == Some(&unnamed)
+                    })
+                    .map(|i| (i, ItemChangeKind::Renamed))
+            });
+
+        match found {
+            Some((i, kind)) => {
+                old_match[i] = Some(j);
+                changes.push(change(&old.items[i], kind, Some(item)));
+            }
+            None => {
+                let mut added = change(item, ItemChangeKind::Added, None);
+                added.lines = new.item_lines(item, LineTag::Added);
+                changes.push(added);
+            }
+        }
+    }
+    for (i, item) in old.items.iter().enumerate() {
+        if old_match[i].is_none() {
+            let mut removed = change(item, ItemChangeKind::Removed, None);
+            removed.lines = old.item_lines(item, LineTag::Removed);
+            changes.push(removed);
+        }
+    }
+
+    // Removed items go before items added in their place
+    changes.sort_by_key(|change| {
+        let lines = change.new_lines.as_ref().or(change.old_lines.as_ref());
+        (lines.map(|lines| lines.start), change.new_lines.is_some())
+    });
+
+    Ok(SemanticDiff {
+        changes,
+        other: other_changes(&old, &new),
+    })
+}
+
+/// Item found in a syntax tree
+#[derive(Debug)]
+struct Item {
+    /// Node kind, looking through wrappers
+    kind: &'static str,
+    /// Qualified name
+    name: String,
+    /// Byte range of the name identifier, if the item has one
+    name_range: Option<Range<usize>>,
+    /// Byte range of the item node, including wrappers such as decorators
+    node: Range<usize>,
+    /// Byte range including attached comments and attributes
+    range: Range<usize>,
+    /// Lines of `range`
+    lines: Range<usize>,
+}
+
+/// One side of the diff
+struct Version<'s> {
+    source: &'s str,
+    line_starts: Vec<usize>,
+    items: Vec<Item>,
+}
+
+impl<'s> Version<'s> {
+    fn new(source: &'s str, tree: &SyntaxTree) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut version = Self {
+            source,
+            line_starts,
+            items: Vec::new(),
+        };
+        let mut items = Vec::new();
+        version.collect_items(tree.language(), tree.root(), "", &mut items);
+        version.items = items;
+        version
+    }
+
+    /// Push the items in `parent` to `items`, members of containers instead
+    /// of the container itself
+    fn collect_items(
+        &self,
+        language: Language,
+        parent: Node<'_>,
+        scope: &str,
+        items: &mut Vec<Item>,
+    ) {
+        let mut cursor = parent.walk();
+        for node in parent.named_children(&mut cursor) {
+            let Some(item) = unwrap_item(language, node) else {
+                continue;
+            };
+            let (name, name_range) = item_name(self.source, item);
+            let name = if scope.is_empty() {
+                name
+            } else {
+                format!("{}{}{}", scope, language.path_separator(), name)
+            };
+
+            if language.container_kinds().contains(&item.kind()) {
+                if let Some(body) = item.child_by_field_name("body") {
+                    let before = items.len();
+                    self.collect_items(language, body, &name, items);
+                    if items.len() > before {
+                        continue;
+                    }
+                }
+            }
+
+            let range = item_range(language, self.source, node);
+            items.push(Item {
+                kind: item.kind(),
+                name,
+                name_range,
+                node: node.byte_range(),
+                lines: self.line_of(range.start)..self.line_of(range.end.saturating_sub(1)) + 1,
+                range,
+            });
+        }
+    }
+
+    /// Zero-based line containing byte `offset`
+    fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    /// Text of the whole lines in `lines`
+    fn line_text(&self, lines: &Range<usize>) -> &'s str {
+        let start = self.line_starts[lines.start];
+        let end = self
+            .line_starts
+            .get(lines.end)
+            .copied()
+            .unwrap_or(self.source.len());
+        &self.source[start..end]
+    }
+
+    /// Item text including attached comments
+    fn text(&self, item: &Item) -> &'s str {
+        &self.source[item.range.clone()]
+    }
+
+    /// Item text without attached comments
+    fn node_text(&self, item: &Item) -> &'s str {
+        &self.source[item.node.clone()]
+    }
+
+    /// Item text without attached comments, with the name cut out
+    fn without_name(&self, item: &Item) -> Option<String> {
+        let name = item.name_range.clone()?;
+        Some(format!(
+            "{}{}",
+            &self.source[item.node.start..name.start],
+            &self.source[name.end..item.node.end]
+        ))
+    }
+
+    /// All lines of `item`, as added or removed lines
+    fn item_lines(&self, item: &Item, tag: LineTag) -> Vec<LineChange> {
+        self.line_text(&item.lines)
+            .lines()
+            .zip(item.lines.clone())
+            .map(|(text, line)| LineChange {
+                tag,
+                text: text.to_string(),
+                old_line: (tag == LineTag::Removed).then_some(line),
+                new_line: (tag == LineTag::Added).then_some(line),
+            })
+            .collect()
+    }
+
+    /// Lines with each item's lines collapsed into one `None`
+    fn outside_items(&self) -> Vec<(usize, Option<&'s str>)> {
+        let mut owner = vec![None; self.line_starts.len()];
+        for (i, item) in self.items.iter().enumerate() {
+            for line in item.lines.clone() {
+                owner[line] = Some(i);
+            }
+        }
+
+        let mut lines = Vec::new();
+        for (line, text) in self.source.lines().enumerate() {
+            match owner[line] {
+                Some(i) if line > 0 && owner[line - 1] == Some(i) => {}
+                Some(_) => lines.push((line, None)),
+                None => lines.push((line, Some(text))),
+            }
+        }
+        lines
+    }
+}
+
+/// Change of `item`, which is in the old version unless `kind` is `Added`
+///
+/// `new` is the matching item in the new version, if any.
+fn change(item: &Item, kind: ItemChangeKind, new: Option<&Item>) -> ItemChange {
+    if kind == ItemChangeKind::Added {
+        return ItemChange {
+            kind,
+            item_kind: item.kind,
+            name: item.name.clone(),
+            old_name: None,
+            old_lines: None,
+            new_lines: Some(item.lines.clone()),
+            lines: Vec::new(),
+        };
+    }
+
+    let name = new.map_or(&item.name, |new| &new.name);
+    ItemChange {
+        kind,
+        item_kind: item.kind,
+        name: name.clone(),
+        old_name: (*name != item.name).then(|| item.name.clone()),
+        old_lines: Some(item.lines.clone()),
+        new_lines: new.map(|new| new.lines.clone()),
+        lines: Vec::new(),
+    }
+}
+
+/// The item `node` stands for, looking through wrappers
+fn unwrap_item(language: Language, node: Node<'_>) -> Option<Node<'_>> {
+    if !is_item_node(language, node) {
+        return None;
+    }
+    if language.is_item(node.kind()) {
+        return Some(node);
+    }
+    let inner = node
+        .child_by_field_name("definition")
+        .or_else(|| node.child_by_field_name("declaration"))?;
+    language.is_item(inner.kind()).then_some(inner)
+}
+
+/// Name of `item` and the byte range of its identifier
+fn item_name(source: &str, item: Node<'_>) -> (String, Option<Range<usize>>) {
+    let text = |node: Node<'_>| {
+        source[node.byte_range()]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    match item.kind() {
+        "impl_item" => {
+            let ty = item
+                .child_by_field_name("type")
+                .map(text)
+                .unwrap_or_default();
+            let name = match item.child_by_field_name("trait") {
+                Some(tr) => format!("<{} as {}>", ty, text(tr)),
+                None => ty,
+            };
+            (name, None)
+        }
+        "use_declaration" => {
+            let path = item
+                .child_by_field_name("argument")
+                .map(text)
+                .unwrap_or_default();
+            (format!("use {}", path), None)
+        }
+        kind => {
+            let name = item.child_by_field_name("name").or_else(|| {
+                // `const x = ...` names its declarators
+                let mut cursor = item.walk();
+                let declarator = item.named_children(&mut cursor).next()?;
+                declarator.child_by_field_name("name")
+            });
+            match name {
+                Some(name) => (text(name), Some(name.byte_range())),
+                None => (kind.to_string(), None),
+            }
+        }
+    }
+}
+
+/// Keys identifying items across versions
+fn keys(items: &[Item]) -> Vec<(&'static str, &str, usize)> {
+    let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
+    items
+        .iter()
+        .map(|item| {
+            let occurrence = seen.entry((item.kind, item.name.as_str())).or_default();
+            *occurrence += 1;
+            (item.kind, item.name.as_str(), *occurrence)
+        })
+        .collect()
+}
+
+/// Line diff of the lines of a modified item
+fn line_diff(
+    old: &Version<'_>,
+    old_item: &Item,
+    new: &Version<'_>,
+    new_item: &Item,
+) -> Vec<LineChange> {
+    let old_text = old.line_text(&old_item.lines);
+    let new_text = new.line_text(&new_item.lines);
+    let diff = TextDiff::from_lines(old_text, new_text);
+
+    let mut lines = Vec::new();
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                lines.push(LineChange {
+                    tag: match change.tag() {
+                        ChangeTag::Equal => LineTag::Context,
+                        ChangeTag::Insert => LineTag::Added,
+                        ChangeTag::Delete => LineTag::Removed,
+                    },
+                    text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+                    old_line: change.old_index().map(|i| i + old_item.lines.start),
+                    new_line: change.new_index().map(|i| i + new_item.lines.start),
+                });
+            }
+        }
+    }
+    lines
+}
+
+/// Changed lines outside any item
+fn other_changes(old: &Version<'_>, new: &Version<'_>) -> Vec<LineChange> {
+    let old_lines = old.outside_items();
+    let new_lines = new.outside_items();
+    let old_texts: Vec<_> = old_lines.iter().map(|&(_, text)| text).collect();
+    let new_texts: Vec<_> = new_lines.iter().map(|&(_, text)| text).collect();
+
+    let mut changes = Vec::new();
+    for op in capture_diff_slices(Algorithm::Myers, &old_texts, &new_texts) {
+        if op.tag() == DiffTag::Equal {
+            continue;
+        }
+        for &(line, text) in &old_lines[op.old_range()] {
+            if let Some(text) = text {
+                changes.push(LineChange {
+                    tag: LineTag::Removed,
+                    text: text.to_string(),
+                    old_line: Some(line),
+                    new_line: None,
+                });
+            }
+        }
+        for &(line, text) in &new_lines[op.new_range()] {
+            if let Some(text) = text {
+                changes.push(LineChange {
+                    tag: LineTag::Added,
+                    text: text.to_string(),
+                    old_line: None,
+                    new_line: Some(line),
+                });
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> SemanticDiff {
+        semantic_diff(Language::Rust, old, new).unwrap()
+    }
+
+    #[test]
+    fn test_modified_item_has_line_diff() {
+        let old = "fn a() {\n    1\n}\n\nfn b() {\n    2\n}\n";
+        let new = "fn a() {\n    1\n}\n\nfn b() {\n    3\n}\n";
+
+        let diff = diff(old, new);
+        assert_eq!(diff.changes.len(), 1);
+        let change = &diff.changes[0];
+        assert_eq!(change.kind, ItemChangeKind::Modified);
+        assert_eq!(change.name, "b");
+        assert_eq!(change.item_kind, "function_item");
+        assert_eq!(change.new_lines, Some(4..7));
+
+        let changed: Vec<_> = change
+            .lines
+            .iter()
+            .filter(|line| line.tag != LineTag::Context)
+            .map(|line| (line.tag, line.text.as_str(), line.old_line, line.new_line))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                (LineTag::Removed, "    2", Some(5), None),
+                (LineTag::Added, "    3", None, Some(5)),
+            ]
+        );
+        assert!(diff.other.is_empty());
+    }
+
+    #[test]
+    fn test_pure_move() {
+        let old = "/// First\nfn a() {}\n\nfn b() {}\n\nfn c() {}\n";
+        let new = "fn b() {}\n\nfn c() {}\n\n/// First\nfn a() {}\n";
+
+        let diff = diff(old, new);
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].kind, ItemChangeKind::Moved);
+        assert_eq!(diff.changes[0].name, "a");
+        assert_eq!(diff.changes[0].old_lines, Some(0..2));
+        assert_eq!(diff.changes[0].new_lines, Some(4..6));
+        assert!(diff.changes[0].lines.is_empty());
+        assert!(diff.other.is_empty());
+    }
+
+    #[test]
+    fn test_rename() {
+        let old = "/// Sum of `x`\nfn total(x: &[u32]) -> u32 {\n    x.iter().sum()\n}\n";
+        let new = "/// Sum of `x`\nfn sum(x: &[u32]) -> u32 {\n    x.iter().sum()\n}\n";
+
+        let diff = diff(old, new);
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].kind, ItemChangeKind::Renamed);
+        assert_eq!(diff.changes[0].name, "sum");
+        assert_eq!(diff.changes[0].old_name.as_deref(), Some("total"));
+    }
+
+    #[test]
+    fn test_members_are_qualified() {
+        let old = "\
+struct Parser;
+
+impl Parser {
+    fn parse(&self) {}
+
+    fn peek(&self) {}
+}
+
+impl Display for Parser {
+    fn fmt(&self) {}
+}
+";
+        let new = "\
+struct Parser;
+
+impl Parser {
+    fn parse(&self) {}
+}
+
+impl Display for Parser {
+    fn fmt(&self) { todo!() }
+}
+
+impl Lexer {
+    fn peek(&self) {}
+}
+";
+        let diff = diff(old, new);
+        let summary: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|change| (change.kind, change.name.as_str(), change.old_name.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ItemChangeKind::Modified, "<Parser as Display>::fmt", None),
+                (ItemChangeKind::Moved, "Lexer::peek", Some("Parser::peek")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_added_removed_and_other_changes() {
+        let old = "import os\n\ndef old():\n    pass\n\nprint('a')\n";
+        let new = "import sys\n\n@cached\ndef new():\n    return 1\n\nprint('a')\n";
+
+        let diff = semantic_diff(Language::Python, old, new).unwrap();
+        let summary: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|change| (change.kind, change.name.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ItemChangeKind::Removed, "old"),
+                (ItemChangeKind::Added, "new")
+            ]
+        );
+        assert_eq!(diff.changes[1].new_lines, Some(2..5));
+        let added: Vec<_> = diff.changes[1]
+            .lines
+            .iter()
+            .map(|line| (line.tag, line.text.as_str(), line.new_line))
+            .collect();
+        assert_eq!(
+            added,
+            vec![
+                (LineTag::Added, "@cached", Some(2)),
+                (LineTag::Added, "def new():", Some(3)),
+                (LineTag::Added, "    return 1", Some(4)),
+            ]
+        );
+
+        let other: Vec<_> = diff
+            .other
+            .iter()
+            .map(|line| (line.tag, line.text.as_str()))
+            .collect();
+        assert_eq!(
+            other,
+            vec![
+                (LineTag::Removed, "import os"),
+                (LineTag::Added, "import sys")
+            ]
+        );
+        assert!(semantic_diff(Language::Python, old, old)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -9,6 +9,7 @@ use crate::command::{Command, CommandHistory};
 use crate::cursor::{Cursor, CursorSet};
 use crate::error::Result;
 use crate::mode::{Mode, ModeManager};
+use crate::search::{ReplaceAllCommand, SearchQuery};
 use crate::selection::{Selection, SelectionRange};
 use crate::view::ViewState;
 
@@ -146,7 +147,8 @@ impl EditorState {
 
     /// Execute command on active buffer
     ///
-    /// Commands are automatically added to undo history.
+    /// Commands are automatically added to undo history. Edits made in
+    /// Insert mode coalesce into one undo step per insert session.
     pub fn execute_command(&mut self, mut cmd: Box<dyn Command>) -> Result<()> {
         let buffer = self
             .active_buffer_mut()
@@ -157,8 +159,12 @@ impl EditorState {
 
         // Add to history if undoable
         if cmd.can_undo() {
+            let session = self.mode.is_insert().then(|| self.mode.insert_session());
             if let Some(history) = self.history_mut() {
-                history.push(cmd);
+                match session {
+                    Some(session) => history.push_coalescing(cmd, session),
+                    None => history.push(cmd),
+                }
             }
         }
 
@@ -179,6 +185,62 @@ impl EditorState {
         Ok(())
     }
 
+    /// Replace every match of `query` in every open buffer
+    ///
+    /// Each changed buffer gets a single undo step. Cursors past the new end
+    /// of a buffer are clamped. Returns the number of replacements made.
+    pub fn replace_all(&mut self, query: &SearchQuery, replacement: &str) -> Result<usize> {
+        let mut total = 0;
+        for id in self.buffer_manager.buffer_ids() {
+            let Some(buffer) = self.buffer_manager.get_mut(id) else {
+                continue;
+            };
+            let mut cmd = ReplaceAllCommand::new(id, query.clone(), replacement);
+            cmd.execute(buffer)?;
+            if cmd.replaced() == 0 {
+                continue;
+            }
+            total += cmd.replaced();
+
+            let len = buffer.len_bytes();
+            if let Some(cursors) = self.cursors.get_mut(&id) {
+                for cursor in cursors.cursors_mut() {
+                    cursor.set_pos(cursor.pos().min(len));
+                }
+            }
+            if let Some(history) = self.histories.get_mut(&id) {
+                history.push(Box::new(cmd));
+            }
+        }
+        Ok(total)
+    }
+
+    /// Start an explicit undo group on the active buffer
+    ///
+    /// Commands executed until [`end_undo_group`](Self::end_undo_group) undo
+    /// as a single step.
+    pub fn begin_undo_group(&mut self, description: impl Into<String>) {
+        if let Some(history) = self.history_mut() {
+            history.begin_group(description);
+        }
+    }
+
+    /// Close the innermost undo group on the active buffer
+    pub fn end_undo_group(&mut self) {
+        if let Some(history) = self.history_mut() {
+            history.end_group();
+        }
+    }
+
+    /// Stop coalescing edits into the current undo step
+    ///
+    /// Callers invoke this on cursor jumps that do not change the buffer.
+    pub fn break_undo_coalescing(&mut self) {
+        if let Some(history) = self.history_mut() {
+            history.break_coalescing();
+        }
+    }
+
     /// Undo last command on active buffer
     ///
     /// Returns true if undo was successful, false if nothing to undo.
@@ -314,4 +376,74 @@ mod tests {
         assert_eq!(dirty.len(), 1);
         assert_eq!(dirty[0], id);
     }
+
+    #[test]
+    fn test_editor_state_insert_session_undo() {
+        use crate::command::InsertCommand;
+
+        let mut state = EditorState::new();
+        let id = state.open_buffer(Buffer::new());
+
+        state.mode.enter_insert();
+        for (pos, ch) in ["a", "b", "c"].iter().enumerate() {
+            state
+                .execute_command(Box::new(InsertCommand::new(id, pos, *ch)))
+                .unwrap();
+        }
+        state.mode.exit_insert();
+
+        state.mode.enter_insert();
+        state
+            .execute_command(Box::new(InsertCommand::new(id, 3, "d")))
+            .unwrap();
+        state.mode.exit_insert();
+
+        assert_eq!(state.active_buffer().unwrap().to_string(), "abcd");
+        assert!(state.undo().unwrap());
+        assert_eq!(state.active_buffer().unwrap().to_string(), "abc");
+        assert!(state.undo().unwrap());
+        assert_eq!(state.active_buffer().unwrap().to_string(), "");
+        assert!(!state.undo().unwrap());
+    }
+
+    #[test]
+    fn test_editor_state_replace_all() {
+        use crate::search::SearchOptions;
+
+        let mut state = EditorState::new();
+        let first = state.open_buffer(Buffer::from_string("old old".to_string(), None));
+        let second = state.open_buffer(Buffer::from_string("new old".to_string(), None));
+
+        let query = SearchQuery::new("old", SearchOptions::default()).unwrap();
+        assert_eq!(state.replace_all(&query, "new").unwrap(), 3);
+        assert_eq!(state.buffer_manager.get(first).unwrap().to_string(), "new new");
+
+        // Undo restores only the active buffer, in one step
+        assert!(state.undo().unwrap());
+        assert_eq!(state.buffer_manager.get(first).unwrap().to_string(), "old old");
+        assert_eq!(state.buffer_manager.get(second).unwrap().to_string(), "new new");
+        assert!(!state.can_undo());
+    }
+
+    #[test]
+    fn test_editor_state_undo_group() {
+        use crate::command::InsertCommand;
+
+        let mut state = EditorState::new();
+        let id = state.open_buffer(Buffer::new());
+
+        // Normal-mode edits are separate steps unless grouped
+        state.begin_undo_group("Insert pair");
+        state
+            .execute_command(Box::new(InsertCommand::new(id, 0, "()")))
+            .unwrap();
+        state
+            .execute_command(Box::new(InsertCommand::new(id, 1, "x")))
+            .unwrap();
+        state.end_undo_group();
+
+        assert_eq!(state.active_buffer().unwrap().to_string(), "(x)");
+        assert!(state.undo().unwrap());
+        assert_eq!(state.active_buffer().unwrap().to_string(), "");
+    }
 }
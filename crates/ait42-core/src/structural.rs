@@ -0,0 +1,563 @@
+//! Structural Editing
+//!
+//! Syntax-aware edits computed from a [`SyntaxTree`].
+//!
+//! Every operation is a pure function of the source text and returns a
+//! [`StructuralEdit`]: one replacement plus the cursor position afterwards.
+//! Callers apply it through [`StructuralEdit::into_command`] so structural
+//! edits undo like any other edit. `None` means the operation does not apply
+//! at the given position.
+
+use std::ops::Range;
+
+use tree_sitter::Node;
+
+use crate::buffer::BufferId;
+use crate::command::ReplaceCommand;
+use crate::syntax::{Language, SyntaxTree};
+
+/// Indentation added for each item of a split list
+pub(crate) const INDENT_UNIT: &str = "    ";
+
+/// Direction for move and swap operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards the start of the buffer
+    Backward,
+    /// Towards the end of the buffer
+    Forward,
+}
+
+/// A single text replacement produced by a structural operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuralEdit {
+    /// Byte range to replace
+    pub range: Range<usize>,
+    /// Replacement text
+    pub replacement: String,
+    /// Cursor byte offset after the edit is applied
+    pub cursor: usize,
+}
+
+impl StructuralEdit {
+    /// Convert into an undoable command for `buffer_id`
+    pub fn into_command(self, buffer_id: BufferId) -> ReplaceCommand {
+        ReplaceCommand::new(buffer_id, self.range, self.replacement)
+    }
+
+    /// Apply the edit to a string (used for previews and tests)
+    pub fn apply(&self, source: &str) -> String {
+        let mut out = String::with_capacity(source.len() + self.replacement.len());
+        out.push_str(&source[..self.range.start]);
+        out.push_str(&self.replacement);
+        out.push_str(&source[self.range.end..]);
+        out
+    }
+}
+
+/// Swap two non-overlapping ranges, keeping the text between them
+///
+/// `pos` lies inside the range that moves; the returned cursor follows it.
+fn swap_ranges(
+    source: &str,
+    first: Range<usize>,
+    second: Range<usize>,
+    pos: usize,
+) -> StructuralEdit {
+    let gap = &source[first.end..second.start];
+    let replacement = format!("{}{}{}", &source[second.clone()], gap, &source[first.clone()]);
+
+    let cursor = if first.contains(&pos) {
+        // First range now sits after the second range and the gap
+        first.start + second.len() + gap.len() + (pos - first.start)
+    } else {
+        first.start + (pos.saturating_sub(second.start))
+    };
+
+    StructuralEdit {
+        range: first.start..second.end,
+        replacement,
+        cursor,
+    }
+}
+
+/// Next or previous named sibling that is not a comment
+fn sibling<'t>(language: Language, node: Node<'t>, direction: Direction) -> Option<Node<'t>> {
+    let mut current = node;
+    loop {
+        current = match direction {
+            Direction::Backward => current.prev_named_sibling()?,
+            Direction::Forward => current.next_named_sibling()?,
+        };
+        if !language.is_comment(current.kind()) {
+            return Some(current);
+        }
+    }
+}
+
+/// Innermost movable item containing `pos`
+fn item_at<'t>(tree: &'t SyntaxTree, pos: usize) -> Option<Node<'t>> {
+    let language = tree.language();
+    let mut node = tree.root().descendant_for_byte_range(pos, pos)?;
+
+    while !language.is_item(node.kind()) {
+        node = node.parent()?;
+    }
+    while let Some(parent) = node.parent() {
+        if !language.wrapper_kinds().contains(&parent.kind()) {
+            break;
+        }
+        node = parent;
+    }
+    Some(node)
+}
+
+/// Check whether a sibling node is a movable item, looking through wrappers
+pub(crate) fn is_item_node(language: Language, node: Node<'_>) -> bool {
+    language.is_item(node.kind()) || language.wrapper_kinds().contains(&node.kind())
+}
+
+/// Item range extended over attached doc comments and attributes
+pub(crate) fn item_range(language: Language, source: &str, item: Node<'_>) -> Range<usize> {
+    let mut start = item.start_byte();
+    let mut prev = item.prev_named_sibling();
+
+    while let Some(node) = prev {
+        if !language.attached_kinds().contains(&node.kind()) {
+            break;
+        }
+        // A blank line detaches a comment from the item below it
+        if source[node.end_byte()..start].matches('\n').count() > 1 {
+            break;
+        }
+        start = node.start_byte();
+        prev = node.prev_named_sibling();
+    }
+
+    start..item.end_byte()
+}
+
+/// Move the item (function, type, impl block, ...) under the cursor past its
+/// neighbouring item
+///
+/// Doc comments and attributes directly above an item move with it.
+pub fn move_item(
+    tree: &SyntaxTree,
+    source: &str,
+    pos: usize,
+    direction: Direction,
+) -> Option<StructuralEdit> {
+    let language = tree.language();
+    let item = item_at(tree, pos)?;
+
+    let mut neighbour = item;
+    loop {
+        neighbour = match direction {
+            Direction::Backward => neighbour.prev_named_sibling()?,
+            Direction::Forward => neighbour.next_named_sibling()?,
+        };
+        if !language.attached_kinds().contains(&neighbour.kind()) {
+            break;
+        }
+    }
+    if !is_item_node(language, neighbour) {
+        return None;
+    }
+
+    let current = item_range(language, source, item);
+    let other = item_range(language, source, neighbour);
+
+    Some(match direction {
+        Direction::Backward => swap_ranges(source, other, current, pos),
+        Direction::Forward => swap_ranges(source, current, other, pos),
+    })
+}
+
+/// Element of a delimited list containing `pos`
+fn list_element_at<'t>(tree: &'t SyntaxTree, pos: usize) -> Option<Node<'t>> {
+    let language = tree.language();
+    let mut node = tree.root().descendant_for_byte_range(pos, pos)?;
+
+    loop {
+        let parent = node.parent()?;
+        if language.is_list(parent.kind()) && node.is_named() {
+            return Some(node);
+        }
+        node = parent;
+    }
+}
+
+/// Swap the argument (or parameter, or list element) under the cursor with
+/// its neighbour
+pub fn swap_argument(
+    tree: &SyntaxTree,
+    source: &str,
+    pos: usize,
+    direction: Direction,
+) -> Option<StructuralEdit> {
+    let element = list_element_at(tree, pos)?;
+    let neighbour = sibling(tree.language(), element, direction)?;
+
+    let current = element.byte_range();
+    let other = neighbour.byte_range();
+
+    Some(match direction {
+        Direction::Backward => swap_ranges(source, other, current, pos),
+        Direction::Forward => swap_ranges(source, current, other, pos),
+    })
+}
+
+/// Replace the expression enclosing `range` with the node at `range`
+///
+/// With an empty range the innermost named node at the cursor is raised, so
+/// `foo(bar(x))` with the cursor on `x` becomes `foo(x)`.
+pub fn raise(tree: &SyntaxTree, source: &str, range: Range<usize>) -> Option<StructuralEdit> {
+    let language = tree.language();
+    let node = tree.named_node_for_range(range)?;
+    let node_range = node.byte_range();
+
+    // Nearest ancestor that actually spans more text, looking through lists
+    let mut parent = node.parent()?;
+    while parent.byte_range() == node_range || language.is_list(parent.kind()) {
+        parent = parent.parent()?;
+    }
+
+    let kind = parent.kind();
+    if parent.parent().is_none()
+        || language.block_kinds().contains(&kind)
+        || language.is_item(kind)
+        || kind.ends_with("statement")
+    {
+        return None;
+    }
+
+    Some(StructuralEdit {
+        range: parent.byte_range(),
+        replacement: source[node_range].to_string(),
+        cursor: parent.start_byte(),
+    })
+}
+
+/// Byte range of the smallest syntax node strictly enclosing `range`
+pub fn select_enclosing(tree: &SyntaxTree, range: Range<usize>) -> Option<Range<usize>> {
+    tree.enclosing_range(range)
+}
+
+/// Byte range of `line` without its line ending
+fn line_range(source: &str, line: usize) -> Option<Range<usize>> {
+    let mut start = 0;
+    for _ in 0..line {
+        start += source[start..].find('\n')? + 1;
+    }
+    let end = source[start..]
+        .find('\n')
+        .map_or(source.len(), |offset| start + offset);
+    let end = if source[..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    };
+    Some(start..end)
+}
+
+/// Join `line` with the line below it
+///
+/// Indentation of the joined line is dropped and at most one space is put
+/// between the two halves: none before closing delimiters or punctuation and
+/// none after an opening delimiter. A trailing comma before a closing
+/// delimiter is removed, and the comment leader of a continued line comment
+/// is stripped when `language` is known.
+pub fn join_lines(source: &str, line: usize, language: Option<Language>) -> Option<StructuralEdit> {
+    let current = line_range(source, line)?;
+    let next = line_range(source, line + 1)?;
+
+    let left = source[current.clone()].trim_end();
+    let mut right_start =
+        next.start + (source[next.clone()].len() - source[next.clone()].trim_start().len());
+    let mut left_end = current.start + left.len();
+
+    if let Some(language) = language {
+        let leader = language.line_comment();
+        let left_is_comment = left.trim_start().starts_with(leader);
+        if left_is_comment && source[right_start..next.end].starts_with(leader) {
+            right_start += leader.len();
+            right_start += source[right_start..next.end].len()
+                - source[right_start..next.end].trim_start().len();
+        }
+    }
+
+    let right = &source[right_start..next.end];
+    let first = right.chars().next();
+    let last = source[current.start..left_end].chars().last();
+
+    let closes = matches!(first, Some(')' | ']' | '}'));
+    if closes && last == Some(',') {
+        left_end -= 1;
+    }
+    let last = source[current.start..left_end].chars().last();
+
+    let tight = matches!(
+        (last, first),
+        (None, _)
+            | (_, None)
+            | (Some('(' | '['), _)
+            | (Some('{'), Some('}'))
+            | (_, Some(')' | ']' | '.' | ',' | ';' | '?'))
+    );
+
+    Some(StructuralEdit {
+        range: left_end..right_start,
+        replacement: if tight {
+            String::new()
+        } else {
+            " ".to_string()
+        },
+        cursor: left_end,
+    })
+}
+
+/// Split the innermost list at the cursor into one element per line, or join
+/// an already split list back onto a single line
+///
+/// Lists containing comments or anything other than comma-separated elements
+/// are left alone.
+pub fn toggle_list_split(tree: &SyntaxTree, source: &str, pos: usize) -> Option<StructuralEdit> {
+    let language = tree.language();
+    let mut list = tree.root().descendant_for_byte_range(pos, pos)?;
+    while !language.is_list(list.kind()) {
+        list = list.parent()?;
+    }
+
+    let mut cursor = list.walk();
+    let elements: Vec<Node<'_>> = list.named_children(&mut cursor).collect();
+    let (first, last) = (elements.first()?, elements.last()?);
+    if elements.iter().any(|node| language.is_comment(node.kind())) {
+        return None;
+    }
+
+    // Everything between elements must be a single comma
+    for pair in elements.windows(2) {
+        if source[pair[0].end_byte()..pair[1].start_byte()].trim() != "," {
+            return None;
+        }
+    }
+
+    let open = source[list.start_byte()..first.start_byte()].trim();
+    let tail = source[last.end_byte()..list.end_byte()].trim();
+    let had_trailing_comma = tail.starts_with(',');
+    let close = tail.trim_start_matches(',').trim();
+    if open.is_empty() || close.is_empty() {
+        return None;
+    }
+
+    let items: Vec<&str> = elements
+        .iter()
+        .map(|node| &source[node.byte_range()])
+        .collect();
+    let range = list.byte_range();
+
+    let replacement = if source[range.clone()].contains('\n') {
+        // A one-element tuple needs its comma to stay a tuple
+        let keep_comma = had_trailing_comma && items.len() == 1 && list.kind().contains("tuple");
+        format!("{}{}{}{}", open, items.join(", "), if keep_comma { "," } else { "" }, close)
+    } else {
+        let line_start = source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let indent: String = source[line_start..]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+
+        let mut out = format!("{}\n", open);
+        for item in &items {
+            out.push_str(&format!("{}{}{},\n", indent, INDENT_UNIT, item));
+        }
+        out.push_str(&indent);
+        out.push_str(close);
+        out
+    };
+
+    Some(StructuralEdit {
+        range: range.clone(),
+        replacement,
+        cursor: range.start,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust(source: &str) -> SyntaxTree {
+        SyntaxTree::parse(Language::Rust, source).unwrap()
+    }
+
+    #[test]
+    fn test_move_item_with_doc_comment() {
+        let source = "/// A\nfn a() {}\n\n#[inline]\nfn b() {}\n";
+        let tree = rust(source);
+        let pos = source.find("fn b").unwrap() + 3;
+
+        let edit = move_item(&tree, source, pos, Direction::Backward).unwrap();
+        let result = edit.apply(source);
+        assert_eq!(result, "#[inline]\nfn b() {}\n\n/// A\nfn a() {}\n");
+        assert_eq!(&result[edit.cursor..edit.cursor + 3], "b()");
+
+        // Moving the last item forward is a no-op
+        assert!(move_item(&tree, source, pos, Direction::Forward).is_none());
+    }
+
+    #[test]
+    fn test_move_method_within_impl() {
+        let source = "impl S {\n    fn one() {}\n    fn two() {}\n}\n";
+        let tree = rust(source);
+        let pos = source.find("one").unwrap();
+
+        let edit = move_item(&tree, source, pos, Direction::Forward).unwrap();
+        let result = edit.apply(source);
+        assert_eq!(result, "impl S {\n    fn two() {}\n    fn one() {}\n}\n");
+        assert_eq!(&result[edit.cursor..edit.cursor + 3], "one");
+    }
+
+    #[test]
+    fn test_swap_argument() {
+        let source = "fn f() { call(alpha, beta + 1, gamma) }";
+        let tree = rust(source);
+        let pos = source.find("alpha").unwrap() + 2;
+
+        let edit = swap_argument(&tree, source, pos, Direction::Forward).unwrap();
+        let result = edit.apply(source);
+        assert_eq!(result, "fn f() { call(beta + 1, alpha, gamma) }");
+        assert_eq!(&result[edit.cursor - 2..edit.cursor + 3], "alpha");
+
+        assert!(swap_argument(&tree, source, pos, Direction::Backward).is_none());
+    }
+
+    #[test]
+    fn test_swap_python_parameters() {
+        let source = "def f(a, b):\n    pass\n";
+        let tree = SyntaxTree::parse(Language::Python, source).unwrap();
+        let pos = source.find('b').unwrap();
+
+        let edit = swap_argument(&tree, source, pos, Direction::Backward).unwrap();
+        assert_eq!(edit.apply(source), "def f(b, a):\n    pass\n");
+    }
+
+    #[test]
+    fn test_raise() {
+        let source = "fn f() { foo(bar(x)); }";
+        let tree = rust(source);
+        let x = source.find('x').unwrap();
+
+        let edit = raise(&tree, source, x..x).unwrap();
+        assert_eq!(edit.apply(source), "fn f() { foo(x); }");
+
+        let source = "fn f() { let y = a + b * c; }";
+        let tree = rust(source);
+        let b = source.find("b * c").unwrap();
+        let edit = raise(&tree, source, b..b + 5).unwrap();
+        assert_eq!(edit.apply(source), "fn f() { let y = b * c; }");
+    }
+
+    #[test]
+    fn test_raise_stops_at_statements() {
+        let source = "fn f() { foo(); }";
+        let tree = rust(source);
+        let pos = source.find("foo").unwrap();
+        let call = pos..pos + "foo()".len();
+        assert!(raise(&tree, source, call).is_none());
+    }
+
+    #[test]
+    fn test_select_enclosing() {
+        let source = "fn f() { foo(bar) }";
+        let tree = rust(source);
+        let bar = source.find("bar").unwrap();
+
+        let args = select_enclosing(&tree, bar..bar + 3).unwrap();
+        assert_eq!(&source[args], "(bar)");
+    }
+
+    #[test]
+    fn test_join_lines() {
+        let source = "let x = foo(\n    a,\n);\n";
+        let edit = join_lines(source, 0, None).unwrap();
+        let joined = edit.apply(source);
+        assert_eq!(joined, "let x = foo(a,\n);\n");
+
+        // Trailing comma before a closing delimiter is dropped
+        let edit = join_lines(&joined, 0, None).unwrap();
+        assert_eq!(edit.apply(&joined), "let x = foo(a);\n");
+
+        let source = "let y = 1\n    + 2;";
+        assert_eq!(join_lines(source, 0, None).unwrap().apply(source), "let y = 1 + 2;");
+
+        assert!(join_lines("single line", 0, None).is_none());
+    }
+
+    #[test]
+    fn test_join_comment_lines() {
+        let source = "    // first part\n    // second part\n";
+        let edit = join_lines(source, 0, Some(Language::Rust)).unwrap();
+        assert_eq!(edit.apply(source), "    // first part second part\n");
+
+        let source = "# a\n# b\n";
+        let edit = join_lines(source, 0, Some(Language::Python)).unwrap();
+        assert_eq!(edit.apply(source), "# a b\n");
+    }
+
+    #[test]
+    fn test_toggle_list_split() {
+        let source = "fn f() {\n    call(a, b + 1, c)\n}\n";
+        let tree = rust(source);
+        let pos = source.find("b +").unwrap();
+
+        let edit = toggle_list_split(&tree, source, pos).unwrap();
+        let split = edit.apply(source);
+        assert_eq!(
+            split,
+            "fn f() {\n    call(\n        a,\n        b + 1,\n        c,\n    )\n}\n"
+        );
+
+        let tree = rust(&split);
+        let pos = split.find("b +").unwrap();
+        let edit = toggle_list_split(&tree, &split, pos).unwrap();
+        assert_eq!(edit.apply(&split), source);
+    }
+
+    #[test]
+    fn test_toggle_keeps_single_tuple_comma() {
+        let source = "x = (\n    a,\n)\n";
+        let tree = SyntaxTree::parse(Language::Python, source).unwrap();
+        let pos = source.find('a').unwrap();
+
+        let edit = toggle_list_split(&tree, source, pos).unwrap();
+        assert_eq!(edit.apply(source), "x = (a,)\n");
+    }
+
+    #[test]
+    fn test_toggle_skips_lists_with_comments() {
+        let source = "fn f() { call(\n    a, // first\n    b,\n) }";
+        let tree = rust(source);
+        let pos = source.find('b').unwrap();
+        assert!(toggle_list_split(&tree, source, pos).is_none());
+    }
+
+    #[test]
+    fn test_edit_into_command_is_undoable() {
+        use crate::buffer::Buffer;
+        use crate::command::Command;
+
+        let source = "fn f() { call(a, b) }";
+        let mut buffer = Buffer::from_string(source.to_string(), Some("rs".to_string()));
+        let tree = SyntaxTree::for_buffer(&buffer).unwrap().unwrap();
+        let pos = source.find("(a").unwrap() + 1;
+
+        let edit = swap_argument(&tree, source, pos, Direction::Forward).unwrap();
+        let mut cmd = edit.into_command(buffer.id());
+        cmd.execute(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), "fn f() { call(b, a) }");
+
+        cmd.undo(&mut buffer).unwrap();
+        assert_eq!(buffer.to_string(), source);
+    }
+}
@@ -0,0 +1,330 @@
+//! Syntax Trees
+//!
+//! Thin tree-sitter layer used by syntax-aware editing features.
+//!
+//! Trees are parsed on demand from the buffer text. Byte offsets in the tree
+//! match buffer positions, so node ranges can be used directly as edit ranges.
+
+use std::ops::Range;
+use std::path::Path;
+
+use tree_sitter::{Node, Parser, Tree};
+
+use crate::buffer::Buffer;
+use crate::error::{EditorError, Result};
+
+/// Languages with a bundled tree-sitter grammar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+}
+
+impl Language {
+    /// Detect language from a file extension (without the dot)
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "rs" => Some(Language::Rust),
+            "py" | "pyi" => Some(Language::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Language::JavaScript),
+            _ => None,
+        }
+    }
+
+    /// Detect language from a file path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Detect language of a buffer
+    pub fn for_buffer(buffer: &Buffer) -> Option<Self> {
+        buffer.language().and_then(Self::from_extension)
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::Python => "Python",
+            Language::JavaScript => "JavaScript",
+        }
+    }
+
+    /// Tree-sitter grammar
+    pub fn grammar(&self) -> tree_sitter::Language {
+        match self {
+            Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Language::Python => tree_sitter_python::LANGUAGE.into(),
+            Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+        }
+    }
+
+    /// Line comment leader
+    pub fn line_comment(&self) -> &'static str {
+        match self {
+            Language::Rust | Language::JavaScript => "//",
+            Language::Python => "#",
+        }
+    }
+
+    /// Top-level or member definitions that can be moved as a unit
+    pub(crate) fn item_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "function_item",
+                "function_signature_item",
+                "struct_item",
+                "enum_item",
+                "union_item",
+                "impl_item",
+                "trait_item",
+                "mod_item",
+                "const_item",
+                "static_item",
+                "type_item",
+                "macro_definition",
+                "use_declaration",
+            ],
+            Language::Python => &["function_definition", "class_definition"],
+            Language::JavaScript => &[
+                "function_declaration",
+                "generator_function_declaration",
+                "class_declaration",
+                "method_definition",
+                "lexical_declaration",
+            ],
+        }
+    }
+
+    /// Nodes that wrap an item together with its decorations
+    pub(crate) fn wrapper_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[],
+            Language::Python => &["decorated_definition"],
+            Language::JavaScript => &["export_statement"],
+        }
+    }
+
+    /// Preceding siblings that belong to the following item
+    pub(crate) fn attached_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["line_comment", "block_comment", "attribute_item"],
+            Language::Python | Language::JavaScript => &["comment"],
+        }
+    }
+
+    /// Items whose `body` field holds member items
+    pub(crate) fn container_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["impl_item", "trait_item", "mod_item"],
+            Language::Python => &["class_definition"],
+            Language::JavaScript => &["class_declaration"],
+        }
+    }
+
+    /// Separator between a container and member name, as in `Type::method`
+    pub(crate) fn path_separator(&self) -> &'static str {
+        match self {
+            Language::Rust => "::",
+            Language::Python | Language::JavaScript => ".",
+        }
+    }
+
+    /// Comma-separated, delimited lists (arguments, parameters, literals)
+    pub(crate) fn list_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "arguments",
+                "parameters",
+                "type_arguments",
+                "type_parameters",
+                "tuple_expression",
+                "array_expression",
+                "tuple_pattern",
+                "tuple_type",
+            ],
+            Language::Python => &[
+                "argument_list",
+                "parameters",
+                "list",
+                "tuple",
+                "set",
+                "dictionary",
+            ],
+            Language::JavaScript => &["arguments", "formal_parameters", "array", "object"],
+        }
+    }
+
+    /// Statement containers that an expression can never be raised into
+    pub(crate) fn block_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["source_file", "block", "declaration_list"],
+            Language::Python => &["module", "block"],
+            Language::JavaScript => &["program", "statement_block", "class_body"],
+        }
+    }
+
+    /// Where identifiers are bound, as `(node kind, field)`
+    ///
+    /// With a field, any identifier inside that field of the node is a
+    /// binding; without one, only direct children of the node are.
+    pub(crate) fn binding_kinds(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Language::Rust => &[
+                ("let_declaration", Some("pattern")),
+                ("parameter", Some("pattern")),
+                ("for_expression", Some("pattern")),
+                ("closure_parameters", None),
+            ],
+            Language::Python => &[
+                ("assignment", Some("left")),
+                ("for_statement", Some("left")),
+                ("parameters", None),
+                ("default_parameter", Some("name")),
+                ("typed_parameter", None),
+                ("typed_default_parameter", Some("name")),
+                ("list_splat_pattern", None),
+                ("dictionary_splat_pattern", None),
+            ],
+            Language::JavaScript => &[
+                ("variable_declarator", Some("name")),
+                ("formal_parameters", None),
+                ("assignment_pattern", Some("left")),
+                ("for_in_statement", Some("left")),
+            ],
+        }
+    }
+
+    pub(crate) fn is_item(&self, kind: &str) -> bool {
+        self.item_kinds().contains(&kind)
+    }
+
+    pub(crate) fn is_list(&self, kind: &str) -> bool {
+        self.list_kinds().contains(&kind)
+    }
+
+    pub(crate) fn is_comment(&self, kind: &str) -> bool {
+        kind.contains("comment")
+    }
+}
+
+/// Parsed syntax tree of a buffer snapshot
+#[derive(Debug, Clone)]
+pub struct SyntaxTree {
+    language: Language,
+    tree: Tree,
+}
+
+impl SyntaxTree {
+    /// Parse `source` with the grammar of `language`
+    pub fn parse(language: Language, source: &str) -> Result<Self> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language.grammar())
+            .map_err(|e| EditorError::Syntax(e.to_string()))?;
+
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| EditorError::Syntax(format!("failed to parse {}", language.name())))?;
+
+        Ok(Self { language, tree })
+    }
+
+    /// Parse the current contents of `buffer`
+    ///
+    /// Returns `Ok(None)` when the buffer language has no grammar.
+    pub fn for_buffer(buffer: &Buffer) -> Result<Option<Self>> {
+        match Language::for_buffer(buffer) {
+            Some(language) => Self::parse(language, &buffer.to_string()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Language the tree was parsed with
+    #[inline]
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Root node
+    #[inline]
+    pub fn root(&self) -> Node<'_> {
+        self.tree.root_node()
+    }
+
+    /// Check if the tree contains parse errors
+    pub fn has_errors(&self) -> bool {
+        self.root().has_error()
+    }
+
+    /// Smallest named node covering `range`
+    pub fn named_node_for_range(&self, range: Range<usize>) -> Option<Node<'_>> {
+        self.root()
+            .named_descendant_for_byte_range(range.start, range.end)
+    }
+
+    /// Range of the smallest named node strictly enclosing `range`
+    ///
+    /// Calling this repeatedly with its own result walks up the tree, which
+    /// is how "expand selection" is built.
+    pub fn enclosing_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        let mut node = self.named_node_for_range(range.clone())?;
+        loop {
+            let node_range = node.byte_range();
+            if node_range.start <= range.start && node_range.end >= range.end && node_range != range
+            {
+                return Some(node_range);
+            }
+            node = node.parent()?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_detection() {
+        assert_eq!(Language::from_extension("rs"), Some(Language::Rust));
+        assert_eq!(Language::from_extension("pyi"), Some(Language::Python));
+        assert_eq!(Language::from_path(Path::new("src/app.mjs")), Some(Language::JavaScript));
+        assert_eq!(Language::from_extension("txt"), None);
+
+        let buffer = Buffer::from_string("fn main() {}".to_string(), Some("rs".to_string()));
+        assert_eq!(Language::for_buffer(&buffer), Some(Language::Rust));
+    }
+
+    #[test]
+    fn test_parse_all_grammars() {
+        let rust = SyntaxTree::parse(Language::Rust, "fn main() {}").unwrap();
+        assert_eq!(rust.root().kind(), "source_file");
+        assert!(!rust.has_errors());
+
+        let python = SyntaxTree::parse(Language::Python, "def f():\n    pass\n").unwrap();
+        assert_eq!(python.root().kind(), "module");
+
+        let js = SyntaxTree::parse(Language::JavaScript, "function f() {}").unwrap();
+        assert_eq!(js.root().kind(), "program");
+    }
+
+    #[test]
+    fn test_enclosing_range_expands() {
+        let source = "fn f() { foo(bar, 1) }";
+        let tree = SyntaxTree::parse(Language::Rust, source).unwrap();
+
+        let bar = source.find("bar").unwrap();
+        // Cursor inside `bar` selects the identifier first
+        let first = tree.enclosing_range(bar + 1..bar + 1).unwrap();
+        assert_eq!(&source[first.clone()], "bar");
+
+        let second = tree.enclosing_range(first).unwrap();
+        assert_eq!(&source[second.clone()], "(bar, 1)");
+
+        let third = tree.enclosing_range(second).unwrap();
+        assert_eq!(&source[third], "foo(bar, 1)");
+    }
+}
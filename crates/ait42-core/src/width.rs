@@ -0,0 +1,154 @@
+//! Display Width
+//!
+//! Grapheme- and width-aware column math for terminal rendering.
+//!
+//! Buffer positions are byte offsets, but a terminal cell grid needs display
+//! columns: CJK ideographs and most emoji occupy two cells, combining marks
+//! occupy none, and a grapheme cluster must never be split across cells.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Display width of a single grapheme cluster
+///
+/// Control characters (including line endings) have zero width.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    if grapheme.chars().all(char::is_control) {
+        return 0;
+    }
+    UnicodeWidthStr::width(grapheme)
+}
+
+/// Display width of a string in terminal cells
+pub fn str_width(s: &str) -> usize {
+    s.graphemes(true).map(grapheme_width).sum()
+}
+
+/// Display column of byte offset `byte` within `line`
+///
+/// Offsets inside a grapheme cluster report the column of that cluster.
+pub fn byte_to_display_col(line: &str, byte: usize) -> usize {
+    let mut col = 0;
+    for (idx, g) in line.grapheme_indices(true) {
+        if idx + g.len() > byte {
+            break;
+        }
+        col += grapheme_width(g);
+    }
+    col
+}
+
+/// Byte offset within `line` of the grapheme covering display column `col`
+///
+/// Columns inside a wide character snap to its start; columns past the end of
+/// the line clamp to the end (before any line ending).
+pub fn display_col_to_byte(line: &str, col: usize) -> usize {
+    let mut width = 0;
+    for (idx, g) in line.grapheme_indices(true) {
+        let w = grapheme_width(g);
+        if g.chars().all(|c| c == '\n' || c == '\r') || width + w > col {
+            return idx;
+        }
+        width += w;
+    }
+    line.len()
+}
+
+/// Visible part of `line` for a viewport starting at display column `start`
+/// and `width` cells wide
+///
+/// Returns the slice plus the number of blank cells to draw before it, which
+/// is non-zero when a wide character straddles the left edge.
+pub fn slice_by_display(line: &str, start: usize, width: usize) -> (&str, usize) {
+    let mut col = 0;
+    let mut used = 0;
+    let mut padding = 0;
+    let mut begin = None;
+    let mut end = line.len();
+
+    for (idx, g) in line.grapheme_indices(true) {
+        let w = grapheme_width(g);
+
+        if begin.is_none() {
+            if col < start {
+                // A wide char cut by the scroll offset is skipped and padded
+                if col + w > start {
+                    padding = col + w - start;
+                }
+                col += w;
+                continue;
+            }
+            begin = Some(idx);
+            used = padding;
+        }
+
+        if used + w > width {
+            end = idx;
+            break;
+        }
+        used += w;
+    }
+
+    let padding = padding.min(width);
+    match begin {
+        Some(b) => (&line[b..end], padding),
+        None => ("", padding),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_width() {
+        assert_eq!(str_width("hello"), 5);
+        assert_eq!(str_width(""), 0);
+    }
+
+    #[test]
+    fn test_cjk_width() {
+        assert_eq!(str_width("日本語"), 6);
+        assert_eq!(str_width("aあb"), 4);
+    }
+
+    #[test]
+    fn test_combining_and_control() {
+        // e + combining acute accent is one cell
+        assert_eq!(str_width("e\u{301}"), 1);
+        assert_eq!(str_width("ab\n"), 2);
+        assert_eq!(grapheme_width("\r\n"), 0);
+    }
+
+    #[test]
+    fn test_byte_to_display_col() {
+        let line = "aあb";
+        assert_eq!(byte_to_display_col(line, 0), 0);
+        assert_eq!(byte_to_display_col(line, 1), 1);
+        // Inside あ (3 bytes) still reports its start column
+        assert_eq!(byte_to_display_col(line, 2), 1);
+        assert_eq!(byte_to_display_col(line, 4), 3);
+        assert_eq!(byte_to_display_col(line, 5), 4);
+    }
+
+    #[test]
+    fn test_display_col_to_byte() {
+        let line = "aあb\n";
+        assert_eq!(display_col_to_byte(line, 0), 0);
+        assert_eq!(display_col_to_byte(line, 1), 1);
+        // Column 2 is the right half of あ
+        assert_eq!(display_col_to_byte(line, 2), 1);
+        assert_eq!(display_col_to_byte(line, 3), 4);
+        // Clamps before the newline
+        assert_eq!(display_col_to_byte(line, 99), 5);
+    }
+
+    #[test]
+    fn test_slice_by_display() {
+        assert_eq!(slice_by_display("hello world", 6, 5), ("world", 0));
+        assert_eq!(slice_by_display("日本語", 0, 4), ("日本", 0));
+        // Starting in the middle of 日 pads one cell and shows the rest
+        assert_eq!(slice_by_display("日本語", 1, 4), ("本", 1));
+        assert_eq!(slice_by_display("abc", 10, 5), ("", 0));
+    }
+}
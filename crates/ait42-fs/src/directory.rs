@@ -3,7 +3,7 @@
 //! Operations for listing and searching directories.
 
 use crate::{FileNode, FsError, Result};
-use ignore::WalkBuilder;
+use crate::walk::Walker;
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
@@ -68,7 +68,7 @@ pub async fn list_directory(path: &Path) -> Result<DirectoryListing> {
 
 /// Find files matching a glob pattern
 ///
-/// Uses the `ignore` crate which respects .gitignore files.
+/// Walks the tree in parallel with [`Walker`], respecting .gitignore files.
 ///
 /// # Arguments
 /// * `root` - Root directory to search from
@@ -88,35 +88,27 @@ pub async fn find_files(root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
     let root = root.to_path_buf();
     let pattern = pattern.to_string();
 
-    // Run in blocking task since ignore crate is synchronous
+    // Run in blocking task since the walker blocks until done
     let result = tokio::task::spawn_blocking(move || {
-        let mut matches = Vec::new();
         let glob_pattern = glob::Pattern::new(&pattern)
             .map_err(|e| FsError::InvalidPath(format!("Invalid pattern: {}", e)))?;
 
-        for entry in WalkBuilder::new(&root)
-            .hidden(false) // Include hidden files
-            .git_ignore(true) // Respect .gitignore
-            .build()
-        {
-            let entry = entry.map_err(|e| FsError::InvalidPath(format!("Walk error: {}", e)))?;
-            let path = entry.path();
-
-            if path.is_file() {
-                // Match against filename or relative path
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if glob_pattern.matches(name) {
-                        matches.push(path.to_path_buf());
-                    }
-                } else if let Ok(rel_path) = path.strip_prefix(&root) {
-                    if let Some(rel_str) = rel_path.to_str() {
-                        if glob_pattern.matches(rel_str) {
-                            matches.push(path.to_path_buf());
-                        }
-                    }
-                }
-            }
-        }
+        // Match against filename or relative path
+        let walk = Walker::new(&root).run()?;
+        let matches = walk
+            .entries
+            .into_iter()
+            .filter(|entry| !entry.is_dir)
+            .filter(|entry| {
+                entry
+                    .path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| glob_pattern.matches(name))
+                    || glob_pattern.matches(&entry.relative)
+            })
+            .map(|entry| entry.path)
+            .collect();
 
         Ok::<Vec<PathBuf>, FsError>(matches)
     })
@@ -0,0 +1,406 @@
+//! Workspace Grep
+//!
+//! Searches every file under a root for a pattern, the way ripgrep does:
+//! directories are walked in parallel, `.gitignore`/`.ignore` rules are
+//! respected, and binary files are skipped. Matches are streamed to a
+//! callback one file at a time as they are found, so callers can show the
+//! first results before the walk finishes.
+
+use crate::{FsError, Result};
+use ignore::{overrides::OverrideBuilder, WalkBuilder, WalkState};
+use regex::{Regex, RegexBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::{Component, Path};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Bytes looked at for a NUL when deciding whether a file is binary
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/// Longest line text reported, in bytes; longer lines are cut
+const MAX_LINE_LEN: usize = 500;
+
+/// How a workspace search is run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrepOptions {
+    /// Treat the pattern as a regular expression instead of literal text
+    pub regex: bool,
+    /// Match case; by default case is ignored unless the pattern has an
+    /// uppercase letter
+    pub case_sensitive: Option<bool>,
+    /// Only match whole words
+    pub whole_word: bool,
+    /// Globs files must match, `!` excluding, as in `rg -g`
+    pub globs: Vec<String>,
+    /// Search hidden files and directories
+    pub hidden: bool,
+    /// Stop after this many matching lines
+    pub max_results: usize,
+    /// Files larger than this are skipped
+    pub max_file_size: u64,
+}
+
+impl Default for GrepOptions {
+    fn default() -> Self {
+        Self {
+            regex: false,
+            case_sensitive: None,
+            whole_word: false,
+            globs: Vec::new(),
+            hidden: false,
+            max_results: 2000,
+            max_file_size: 4 * 1024 * 1024,
+        }
+    }
+}
+
+/// A matching line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column of the first match, in characters
+    pub column: usize,
+    /// Line text, without the line ending
+    pub text: String,
+    /// Byte ranges of the matches within `text`
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Matching lines of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileMatches {
+    /// Path relative to the searched root, with `/` separators
+    pub path: String,
+    pub matches: Vec<GrepMatch>,
+}
+
+/// Totals of a finished search
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepSummary {
+    pub files_searched: usize,
+    pub files_matched: usize,
+    /// Matching lines reported
+    pub matches: usize,
+    /// Files skipped because they look binary
+    pub binary_skipped: usize,
+    /// Whether the search stopped at `max_results` or was cancelled
+    pub truncated: bool,
+}
+
+/// Compiled workspace search
+#[derive(Debug, Clone)]
+pub struct Grep {
+    regex: Regex,
+    options: GrepOptions,
+}
+
+impl Grep {
+    /// Compile `pattern`
+    ///
+    /// # Errors
+    /// Returns [`FsError::InvalidPattern`] if the pattern is empty or not a
+    /// valid regex.
+    pub fn new(pattern: &str, options: GrepOptions) -> Result<Self> {
+        if pattern.is_empty() {
+            return Err(FsError::InvalidPattern("empty pattern".to_string()));
+        }
+
+        let mut source = if options.regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        if options.whole_word {
+            source = format!(r"\b(?:{})\b", source);
+        }
+        let case_sensitive = options
+            .case_sensitive
+            .unwrap_or_else(|| pattern.chars().any(char::is_uppercase));
+
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|e| FsError::InvalidPattern(e.to_string()))?;
+        Ok(Self { regex, options })
+    }
+
+    pub fn options(&self) -> &GrepOptions {
+        &self.options
+    }
+
+    /// Matching lines of `content`
+    pub fn search_text(&self, content: &str) -> Vec<GrepMatch> {
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| self.search_line(index + 1, line))
+            .collect()
+    }
+
+    fn search_line(&self, line_number: usize, line: &str) -> Option<GrepMatch> {
+        let ranges: Vec<Range<usize>> = self
+            .regex
+            .find_iter(line)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect();
+        let first = ranges.first()?.start;
+
+        let mut end = line.len().min(MAX_LINE_LEN);
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        let ranges = ranges
+            .into_iter()
+            .filter(|r| r.start < end)
+            .map(|r| r.start..r.end.min(end))
+            .collect();
+        Some(GrepMatch {
+            line: line_number,
+            column: line[..first].chars().count() + 1,
+            text: line[..end].to_string(),
+            ranges,
+        })
+    }
+
+    /// Search every file under `root`, calling `on_file` from the walker
+    /// threads for each file with matches
+    ///
+    /// `on_file` returns `false` to stop the search. Files whose matches
+    /// would go over `max_results` are reported with the matches that fit.
+    pub fn run(
+        &self,
+        root: &Path,
+        on_file: impl Fn(FileMatches) -> bool + Sync,
+    ) -> Result<GrepSummary> {
+        debug!("Searching {} for {}", root.display(), self.regex.as_str());
+
+        let mut overrides = OverrideBuilder::new(root);
+        for glob in &self.options.globs {
+            overrides
+                .add(glob)
+                .map_err(|e| FsError::InvalidPattern(format!("{}: {}", glob, e)))?;
+        }
+        let overrides = overrides
+            .build()
+            .map_err(|e| FsError::InvalidPattern(e.to_string()))?;
+
+        let summary = Mutex::new(GrepSummary::default());
+        let stopped = AtomicBool::new(false);
+        let (summary_ref, stopped, on_file) = (&summary, &stopped, &on_file);
+
+        WalkBuilder::new(root)
+            .hidden(!self.options.hidden)
+            .git_ignore(true)
+            .overrides(overrides)
+            .max_filesize(Some(self.options.max_file_size))
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build_parallel()
+            .run(|| {
+                Box::new(move |entry| {
+                    if stopped.load(Ordering::Relaxed) {
+                        return WalkState::Quit;
+                    }
+                    let entry = match entry {
+                        Ok(entry) => entry,
+                        Err(e) => {
+                            debug!("Skipping unreadable entry: {}", e);
+                            return WalkState::Continue;
+                        }
+                    };
+                    if !entry.file_type().is_some_and(|t| t.is_file()) {
+                        return WalkState::Continue;
+                    }
+
+                    let (content, binary) = match read_text(entry.path()) {
+                        Ok(Some(content)) => (content, false),
+                        Ok(None) => (String::new(), true),
+                        Err(e) => {
+                            debug!("Skipping {}: {}", entry.path().display(), e);
+                            return WalkState::Continue;
+                        }
+                    };
+                    let mut matches = self.search_text(&content);
+
+                    // Report under the lock, so nothing is reported once
+                    // the search was stopped
+                    let mut summary = summary_ref.lock().unwrap_or_else(|e| e.into_inner());
+                    if stopped.load(Ordering::Relaxed) {
+                        return WalkState::Quit;
+                    }
+                    summary.files_searched += 1;
+                    summary.binary_skipped += usize::from(binary);
+                    if matches.is_empty() {
+                        return WalkState::Continue;
+                    }
+
+                    let room = self.options.max_results.saturating_sub(summary.matches);
+                    let truncated = matches.len() > room;
+                    matches.truncate(room);
+                    let mut keep_going = true;
+                    if !matches.is_empty() {
+                        summary.files_matched += 1;
+                        summary.matches += matches.len();
+                        keep_going = on_file(FileMatches {
+                            path: relative_path(root, entry.path()),
+                            matches,
+                        });
+                    }
+                    if truncated || !keep_going {
+                        stopped.store(true, Ordering::Relaxed);
+                        summary.truncated = true;
+                        return WalkState::Quit;
+                    }
+                    WalkState::Continue
+                })
+            });
+
+        Ok(summary.into_inner().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Search every file under `root` and return the matches by path
+    pub fn collect(&self, root: &Path) -> Result<(Vec<FileMatches>, GrepSummary)> {
+        let files = Mutex::new(Vec::new());
+        let summary = self.run(root, |file| {
+            files.lock().unwrap_or_else(|e| e.into_inner()).push(file);
+            true
+        })?;
+        let mut files = files.into_inner().unwrap_or_else(|e| e.into_inner());
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok((files, summary))
+    }
+}
+
+/// Text of a file, `None` if it looks binary
+///
+/// Invalid UTF-8 is replaced rather than skipped, so files in other
+/// encodings can still match on their ASCII parts.
+fn read_text(path: &Path) -> Result<Option<String>> {
+    let bytes = std::fs::read(path)?;
+    let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_LEN)];
+    if sniff.contains(&0) {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+/// `path` relative to `root`, with `/` separators
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::AtomicUsize;
+    use tempfile::TempDir;
+
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {\n    let needle = 1;\n}\n").unwrap();
+        fs::write(root.join("src/nested/lib.rs"), "// Needle\nneedle needle\n").unwrap();
+        fs::write(root.join("target/out.rs"), "needle\n").unwrap();
+        fs::write(root.join("image.bin"), b"needle\0\x01\x02").unwrap();
+        // The walker only applies .gitignore inside a repository
+        fs::create_dir_all(root.join(".git")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_search_text() {
+        let grep = Grep::new("ab", GrepOptions::default()).unwrap();
+        let matches = grep.search_text("xx\nü ab AB\r\nnone");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].column, 3);
+        assert_eq!(matches[0].text, "ü ab AB");
+        assert_eq!(matches[0].ranges, vec![3..5, 6..8]);
+
+        let sensitive = Grep::new("AB", GrepOptions::default()).unwrap();
+        assert_eq!(sensitive.search_text("ab AB")[0].ranges, vec![3..5]);
+
+        let options = GrepOptions {
+            regex: true,
+            ..Default::default()
+        };
+        assert!(Grep::new("(", options).is_err());
+        assert!(Grep::new("", GrepOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_run_respects_ignore_rules_and_skips_binary() {
+        let dir = workspace();
+        let grep = Grep::new("needle", GrepOptions::default()).unwrap();
+        let (files, summary) = grep.collect(dir.path()).unwrap();
+
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/main.rs", "src/nested/lib.rs"]);
+        assert_eq!(files[1].matches.len(), 2);
+        assert_eq!(files[1].matches[1].ranges.len(), 2);
+        assert_eq!(summary.files_matched, 2);
+        assert_eq!(summary.matches, 3);
+        assert_eq!(summary.binary_skipped, 1);
+        assert!(!summary.truncated);
+
+        let options = GrepOptions {
+            globs: vec!["!src/nested/**".to_string()],
+            ..Default::default()
+        };
+        let (files, _) = Grep::new("needle", options)
+            .unwrap()
+            .collect(dir.path())
+            .unwrap();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_run_stops_at_max_results() {
+        let dir = workspace();
+        let options = GrepOptions {
+            max_results: 2,
+            ..Default::default()
+        };
+        let (files, summary) = Grep::new("needle", options)
+            .unwrap()
+            .collect(dir.path())
+            .unwrap();
+
+        assert_eq!(files.iter().map(|f| f.matches.len()).sum::<usize>(), 2);
+        assert_eq!(summary.matches, 2);
+        assert!(summary.truncated);
+
+        // Stopping from the callback also ends the search
+        let grep = Grep::new("needle", GrepOptions::default()).unwrap();
+        let calls = AtomicUsize::new(0);
+        let summary = grep
+            .run(dir.path(), |_| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                false
+            })
+            .unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert!(summary.truncated);
+    }
+}
@@ -4,13 +4,21 @@
 
 pub mod directory;
 pub mod file;
+pub mod grep;
 pub mod sync;
+pub mod todos;
+pub mod tree;
+pub mod walk;
 pub mod watcher;
 
 // Re-exports
 pub use directory::{find_files, list_directory, DirectoryListing};
 pub use file::{FileHandle, FileMetadata};
+pub use grep::{FileMatches, Grep, GrepMatch, GrepOptions, GrepSummary};
 pub use sync::FileSynchronizer;
+pub use todos::{TodoIndex, TodoItem};
+pub use tree::{FileTree, FileTreeBuilder, TreeChange, TreeEntry, TreeRow};
+pub use walk::{CancelToken, SymlinkPolicy, Walk, WalkEntry, Walker};
 pub use watcher::{FileEvent, FileWatcher};
 
 use serde::{Deserialize, Serialize};
@@ -34,6 +42,9 @@ pub enum FsError {
 
     #[error("Invalid path: {0}")]
     InvalidPath(String),
+
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String),
 }
 
 pub type Result<T> = std::result::Result<T, FsError>;
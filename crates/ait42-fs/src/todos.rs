@@ -0,0 +1,313 @@
+//! TODO Comments
+//!
+//! Indexes TODO/FIXME/HACK style comments across a workspace. A tag only
+//! counts inside a comment, after a comment leader such as `//` or `#` on
+//! the same line, and as a whole word: `TODO` matches `// TODO: x` and
+//! `# TODO(ren) x`, not `TODOS` or `todo_list`.
+
+use crate::walk::{SymlinkPolicy, Walker};
+use crate::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use tracing::debug;
+
+/// Files larger than this are not scanned
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Comment leaders a tag has to follow on its line
+const COMMENT_LEADERS: [&str; 6] = ["//", "#", "/*", "--", "<!--", ";"];
+
+/// A tagged comment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoItem {
+    /// Path relative to the scanned root, with `/` separators
+    pub path: String,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column of the tag, in characters
+    pub column: usize,
+    /// Tag that matched, e.g. "FIXME"
+    pub tag: String,
+    /// Name in parentheses after the tag, as in `TODO(ren)`
+    pub author: Option<String>,
+    /// Rest of the comment
+    pub text: String,
+}
+
+/// Tagged comments in `content`, for the file at relative `path`
+pub fn scan_text(path: &str, content: &str, tags: &[String]) -> Vec<TodoItem> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| scan_line(line, tags).map(|item| (index, item)))
+        .map(|(index, (column, tag, author, text))| TodoItem {
+            path: path.to_string(),
+            line: index + 1,
+            column,
+            tag,
+            author,
+            text,
+        })
+        .collect()
+}
+
+/// First tag in a comment on `line`: column, tag, author and text
+fn scan_line(line: &str, tags: &[String]) -> Option<(usize, String, Option<String>, String)> {
+    let comment = comment_start(line)?;
+    let body = &line[comment..];
+
+    for (offset, _) in body.char_indices() {
+        let rest = &body[offset..];
+        let Some(tag) = tags.iter().find(|tag| rest.starts_with(tag.as_str())) else {
+            continue;
+        };
+        let before = body[..offset].chars().next_back();
+        let after = rest[tag.len()..].chars().next();
+        if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+            continue;
+        }
+
+        let mut text = &rest[tag.len()..];
+        let mut author = None;
+        if let Some(inner) = text.strip_prefix('(') {
+            if let Some(end) = inner.find(')') {
+                author = Some(inner[..end].trim().to_string()).filter(|a| !a.is_empty());
+                text = &inner[end + 1..];
+            }
+        }
+        let text = text.trim_start_matches(':').trim();
+        let text = text
+            .strip_suffix("*/")
+            .or_else(|| text.strip_suffix("-->"))
+            .unwrap_or(text)
+            .trim();
+
+        let column = line[..comment + offset].chars().count() + 1;
+        return Some((column, tag.clone(), author, text.to_string()));
+    }
+    None
+}
+
+/// Byte offset of the comment on `line`, if it has one
+///
+/// Continuation lines of block comments (` * text`) count as comments.
+fn comment_start(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('*') {
+        return Some(line.len() - trimmed.len());
+    }
+    COMMENT_LEADERS
+        .iter()
+        .filter_map(|leader| line.find(leader))
+        .min()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Tagged comments of a workspace, by file
+///
+/// Built once with [`TodoIndex::scan`], then kept current by rescanning
+/// single files with [`TodoIndex::update_file`] when they are saved.
+#[derive(Debug, Clone)]
+pub struct TodoIndex {
+    root: PathBuf,
+    tags: Vec<String>,
+    files: BTreeMap<String, Vec<TodoItem>>,
+}
+
+impl TodoIndex {
+    /// Scan every file under `root`, respecting .gitignore
+    pub fn scan(root: &Path, tags: &[String]) -> Result<Self> {
+        debug!("Scanning {} for {}", root.display(), tags.join(", "));
+
+        let mut index = Self {
+            root: root.to_path_buf(),
+            tags: tags.to_vec(),
+            files: BTreeMap::new(),
+        };
+        let walk = Walker::new(root).symlinks(SymlinkPolicy::Skip).run()?;
+        for path in walk.files() {
+            if let Err(e) = index.update_file(path) {
+                debug!("Skipping {}: {}", path.display(), e);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Rescan one file, dropping its entries if it was deleted
+    ///
+    /// Files outside the root are ignored.
+    pub fn update_file(&mut self, path: &Path) -> Result<()> {
+        let Some(relative) = self.relative_path(path) else {
+            return Ok(());
+        };
+        let items = match read_text(&self.root.join(&relative))? {
+            Some(content) => scan_text(&relative, &content, &self.tags),
+            None => Vec::new(),
+        };
+        if items.is_empty() {
+            self.files.remove(&relative);
+        } else {
+            self.files.insert(relative, items);
+        }
+        Ok(())
+    }
+
+    /// All items, ordered by path and line
+    pub fn items(&self) -> Vec<TodoItem> {
+        self.files.values().flatten().cloned().collect()
+    }
+
+    /// Items of the file at relative `path`
+    pub fn items_in(&self, path: &str) -> &[TodoItem] {
+        self.files.get(path).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<String> {
+        let relative = if path.is_absolute() {
+            path.strip_prefix(&self.root).ok()?
+        } else {
+            path
+        };
+        let mut parts = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy()),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        Some(parts.join("/"))
+    }
+}
+
+/// Content of a text file, `None` for missing, large or binary files
+fn read_text(path: &Path) -> Result<Option<String>> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if !metadata.is_file() || metadata.len() > MAX_FILE_SIZE {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(path)?;
+    if bytes.contains(&0) {
+        return Ok(None);
+    }
+    Ok(String::from_utf8(bytes).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tags() -> Vec<String> {
+        ["TODO", "FIXME", "HACK"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn test_scan_text() {
+        let content = [
+            "fn main() {",
+            "    // TODO: handle errors",
+            "    let todo_list = 1; // FIXME(ren) off by one",
+            "}",
+            "# HACK",
+        ]
+        .join("\n");
+        let items = scan_text("src/main.rs", &content, &tags());
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[0],
+            TodoItem {
+                path: "src/main.rs".to_string(),
+                line: 2,
+                column: 8,
+                tag: "TODO".to_string(),
+                author: None,
+                text: "handle errors".to_string(),
+            }
+        );
+        assert_eq!(items[1].tag, "FIXME");
+        assert_eq!(items[1].author.as_deref(), Some("ren"));
+        assert_eq!(items[1].text, "off by one");
+        assert_eq!((items[2].line, items[2].tag.as_str(), items[2].text.as_str()), (5, "HACK", ""));
+    }
+
+    #[test]
+    fn test_tags_only_in_comments() {
+        let tags = tags();
+        assert!(scan_line("let TODO = 1;", &tags).is_none());
+        assert!(scan_line("// TODOS are fine", &tags).is_none());
+        assert!(scan_line("// XTODO", &tags).is_none());
+        assert_eq!(scan_line(" * TODO: more", &tags).unwrap().3, "more");
+        assert_eq!(scan_line("/* FIXME broken */", &tags).unwrap().3, "broken");
+        assert_eq!(scan_line("<!-- TODO: docs -->", &tags).unwrap().3, "docs");
+        assert!(scan_line("-- NOTE: check", &["NOTE".to_string()]).is_some());
+    }
+
+    #[test]
+    fn test_index_scan_and_update() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/a.rs"), "// TODO: one\n").unwrap();
+        std::fs::write(dir.path().join("b.py"), "x = 1\n# FIXME two\n").unwrap();
+        std::fs::write(dir.path().join("c.txt"), "nothing here\n").unwrap();
+        std::fs::write(dir.path().join("d.bin"), b"// TODO\0").unwrap();
+
+        let mut index = TodoIndex::scan(dir.path(), &tags()).unwrap();
+        let items = index.items();
+        assert_eq!(items.len(), 2);
+        assert_eq!((items[0].path.as_str(), items[0].line), ("b.py", 2));
+        assert_eq!(items[1].path, "src/a.rs");
+
+        let a = dir.path().join("src/a.rs");
+        std::fs::write(&a, "// TODO: one\n// HACK: three\n").unwrap();
+        index.update_file(&a).unwrap();
+        assert_eq!(index.items_in("src/a.rs").len(), 2);
+
+        std::fs::remove_file(&a).unwrap();
+        index.update_file(&a).unwrap();
+        assert!(index.items_in("src/a.rs").is_empty());
+        assert_eq!(index.items().len(), 1);
+
+        index.update_file(Path::new("/elsewhere/x.rs")).unwrap();
+        index.update_file(Path::new("../x.rs")).unwrap();
+        assert_eq!(index.items().len(), 1);
+
+        std::fs::write(&a, "// FIXME: back\n").unwrap();
+        index.update_file(Path::new("src/a.rs")).unwrap();
+        assert_eq!(index.items_in("src/a.rs")[0].text, "back");
+    }
+
+    #[test]
+    fn test_index_respects_gitignore() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/gen.rs"), "// TODO generated\n").unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "// TODO real\n").unwrap();
+
+        let index = TodoIndex::scan(dir.path(), &tags()).unwrap();
+        let items = index.items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, "lib.rs");
+    }
+}
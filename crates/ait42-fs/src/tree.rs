@@ -0,0 +1,539 @@
+//! Lazy File Tree
+//!
+//! A file tree that reads a directory only when it is expanded, so opening
+//! a huge repository costs one directory listing instead of a full walk.
+//! Listings leave out what `.gitignore` and `.ait42ignore` files exclude, as
+//! well as the `.git` directory, and are cached together with the metadata
+//! of their entries until the directory is refreshed.
+//!
+//! Refreshing reports what changed as [`TreeChange`]s, so a sidebar can
+//! patch the rows it shows instead of rebuilding them.
+
+use crate::{FsError, Result};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tracing::debug;
+
+/// Ignore file read in addition to `.gitignore`, with the same syntax
+pub const IGNORE_FILE: &str = ".ait42ignore";
+
+/// A file or directory in the tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeEntry {
+    pub name: String,
+    /// Path relative to the tree root, with `/` separators
+    pub path: String,
+    pub is_dir: bool,
+    pub is_hidden: bool,
+    pub is_symlink: bool,
+    /// Size in bytes, 0 for directories
+    pub size: u64,
+    /// Modification time in milliseconds since the Unix epoch
+    pub modified: Option<u64>,
+}
+
+/// Difference between two listings of a directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TreeChange {
+    Added { entry: TreeEntry },
+    Removed { path: String },
+    Modified { entry: TreeEntry },
+}
+
+/// Entry as shown in the tree, below every expanded directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeRow {
+    pub entry: TreeEntry,
+    /// 0 for entries of the root
+    pub depth: usize,
+    pub expanded: bool,
+}
+
+/// Options of a [`FileTree`]
+#[derive(Debug, Clone)]
+pub struct FileTreeBuilder {
+    root: PathBuf,
+    show_hidden: bool,
+    git_ignore: bool,
+    ignore_files: Vec<String>,
+}
+
+impl FileTreeBuilder {
+    /// Tree of `root`, showing hidden files and honoring ignore files
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            show_hidden: true,
+            git_ignore: true,
+            ignore_files: vec![IGNORE_FILE.to_string()],
+        }
+    }
+
+    /// Whether to list entries whose name starts with a dot
+    pub fn show_hidden(mut self, show: bool) -> Self {
+        self.show_hidden = show;
+        self
+    }
+
+    /// Whether to leave out what `.gitignore` files exclude
+    pub fn git_ignore(mut self, enabled: bool) -> Self {
+        self.git_ignore = enabled;
+        self
+    }
+
+    /// Read another ignore file, such as `.dockerignore`
+    pub fn ignore_file(mut self, name: impl Into<String>) -> Self {
+        self.ignore_files.push(name.into());
+        self
+    }
+
+    /// Tree with the root directory listed
+    pub fn build(self) -> Result<FileTree> {
+        if !self.root.is_dir() {
+            return Err(FsError::InvalidPath(format!("Not a directory: {}", self.root.display())));
+        }
+
+        let mut tree = FileTree {
+            options: self,
+            listings: HashMap::new(),
+            expanded: HashSet::new(),
+        };
+        tree.expand("")?;
+        Ok(tree)
+    }
+}
+
+/// Directory tree listed one directory at a time
+#[derive(Debug, Clone)]
+pub struct FileTree {
+    options: FileTreeBuilder,
+    /// Cached listings by relative directory path, "" for the root
+    listings: HashMap<String, Vec<TreeEntry>>,
+    expanded: HashSet<String>,
+}
+
+impl FileTree {
+    /// Root directory of the tree
+    pub fn root(&self) -> &Path {
+        &self.options.root
+    }
+
+    /// Cached listing of `dir`, `None` if it was never expanded
+    pub fn children(&self, dir: &str) -> Option<&[TreeEntry]> {
+        self.listings.get(dir.trim_matches('/')).map(Vec::as_slice)
+    }
+
+    /// Whether `dir` is expanded
+    pub fn is_expanded(&self, dir: &str) -> bool {
+        self.expanded.contains(dir.trim_matches('/'))
+    }
+
+    /// Expand `dir`, listing it unless its listing is cached
+    pub fn expand(&mut self, dir: &str) -> Result<&[TreeEntry]> {
+        let dir = normalize(dir)?;
+        if !self.listings.contains_key(&dir) {
+            let listing = self.read_listing(&dir)?;
+            self.listings.insert(dir.clone(), listing);
+        }
+        self.expanded.insert(dir.clone());
+        Ok(&self.listings[&dir])
+    }
+
+    /// Collapse `dir`, keeping its listing cached
+    ///
+    /// The root cannot be collapsed.
+    pub fn collapse(&mut self, dir: &str) {
+        let dir = dir.trim_matches('/');
+        if !dir.is_empty() {
+            self.expanded.remove(dir);
+        }
+    }
+
+    /// Entries below the root and every expanded directory, in display order
+    pub fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        self.push_rows("", 0, &mut rows);
+        rows
+    }
+
+    fn push_rows(&self, dir: &str, depth: usize, rows: &mut Vec<TreeRow>) {
+        let Some(listing) = self.listings.get(dir) else {
+            return;
+        };
+        for entry in listing {
+            let expanded = entry.is_dir && self.expanded.contains(&entry.path);
+            rows.push(TreeRow {
+                entry: entry.clone(),
+                depth,
+                expanded,
+            });
+            if expanded {
+                self.push_rows(&entry.path, depth + 1, rows);
+            }
+        }
+    }
+
+    /// List `dir` again, returning what changed since it was cached
+    ///
+    /// A directory that no longer exists is dropped along with everything
+    /// below it, as are subdirectories that disappeared.
+    pub fn refresh(&mut self, dir: &str) -> Result<Vec<TreeChange>> {
+        let dir = normalize(dir)?;
+        let Some(old) = self.listings.get(&dir) else {
+            return Ok(Vec::new());
+        };
+
+        let new = match self.read_listing(&dir) {
+            Ok(listing) => listing,
+            Err(FsError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound && !dir.is_empty() => {
+                let changes = old
+                    .iter()
+                    .map(|entry| TreeChange::Removed {
+                        path: entry.path.clone(),
+                    })
+                    .collect();
+                self.forget(&dir);
+                return Ok(changes);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let changes = diff(old, &new);
+        for change in &changes {
+            let path = match change {
+                TreeChange::Removed { path } => path,
+                TreeChange::Modified { entry } if !entry.is_dir => &entry.path,
+                _ => continue,
+            };
+            self.forget(path);
+        }
+        self.listings.insert(dir, new);
+        Ok(changes)
+    }
+
+    /// Refresh every cached directory
+    pub fn refresh_all(&mut self) -> Result<Vec<TreeChange>> {
+        let mut dirs: Vec<String> = self.listings.keys().cloned().collect();
+        // Parents first, so directories dropped with them are skipped
+        dirs.sort();
+
+        let mut changes = Vec::new();
+        for dir in dirs {
+            if self.listings.contains_key(&dir) {
+                changes.extend(self.refresh(&dir)?);
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Refresh the directory containing `path` after a change to it
+    ///
+    /// `path` may be absolute or relative to the root. Changes outside the
+    /// tree or in directories that were never listed are ignored.
+    pub fn path_changed(&mut self, path: &Path) -> Result<Vec<TreeChange>> {
+        let relative = path.strip_prefix(self.root()).unwrap_or(path);
+        if relative.is_absolute() {
+            return Ok(Vec::new());
+        }
+        let relative = normalize(&relative.to_string_lossy())?;
+        let parent = relative.rsplit_once('/').map_or("", |(parent, _)| parent);
+        self.refresh(parent)
+    }
+
+    /// Drop the cached listings of `dir` and everything below it
+    fn forget(&mut self, dir: &str) {
+        let prefix = format!("{}/", dir);
+        self.listings
+            .retain(|path, _| path != dir && !path.starts_with(&prefix));
+        self.expanded
+            .retain(|path| path != dir && !path.starts_with(&prefix));
+    }
+
+    /// Entries of `dir`, directories first, then by name
+    fn read_listing(&self, dir: &str) -> Result<Vec<TreeEntry>> {
+        let path = self.options.root.join(dir);
+        if !std::fs::metadata(&path)?.is_dir() {
+            return Err(FsError::InvalidPath(format!("Not a directory: {}", dir)));
+        }
+
+        let mut walker = WalkBuilder::new(&path);
+        walker
+            .max_depth(Some(1))
+            .hidden(!self.options.show_hidden)
+            .git_ignore(self.options.git_ignore)
+            .git_exclude(self.options.git_ignore)
+            .git_global(self.options.git_ignore)
+            .ignore(false)
+            .parents(true)
+            .require_git(false)
+            .follow_links(false)
+            .filter_entry(|entry| entry.file_name() != ".git");
+        for name in &self.options.ignore_files {
+            walker.add_custom_ignore_filename(name);
+        }
+
+        let mut listing = Vec::new();
+        for result in walker.build() {
+            let entry = match result {
+                Ok(entry) if entry.depth() > 0 => entry,
+                Ok(_) => continue,
+                Err(e) => {
+                    debug!("Skipping entry of {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_symlink = entry.path_is_symlink();
+            // Symlinks are described by their target unless it is missing
+            let metadata = match std::fs::metadata(entry.path())
+                .or_else(|_| std::fs::symlink_metadata(entry.path()))
+            {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    debug!("Skipping {}: {}", entry.path().display(), e);
+                    continue;
+                }
+            };
+
+            listing.push(TreeEntry {
+                path: if dir.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}/{}", dir, name)
+                },
+                is_hidden: name.starts_with('.'),
+                name,
+                is_dir: metadata.is_dir(),
+                is_symlink,
+                size: if metadata.is_dir() { 0 } else { metadata.len() },
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_millis() as u64),
+            });
+        }
+
+        listing.sort_by(|a, b| {
+            b.is_dir
+                .cmp(&a.is_dir)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(listing)
+    }
+}
+
+/// Changes turning listing `old` into `new`: removals first, then additions
+/// and modifications in the order of `new`
+pub fn diff(old: &[TreeEntry], new: &[TreeEntry]) -> Vec<TreeChange> {
+    let old_entries: HashMap<&str, &TreeEntry> = old
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry))
+        .collect();
+    let new_paths: HashSet<&str> = new.iter().map(|entry| entry.path.as_str()).collect();
+
+    let removed = old
+        .iter()
+        .filter(|entry| !new_paths.contains(entry.path.as_str()))
+        .map(|entry| TreeChange::Removed {
+            path: entry.path.clone(),
+        });
+    let added_or_modified =
+        new.iter()
+            .filter_map(|entry| match old_entries.get(entry.path.as_str()) {
+                None => Some(TreeChange::Added {
+                    entry: entry.clone(),
+                }),
+                Some(old) if *old != entry => Some(TreeChange::Modified {
+                    entry: entry.clone(),
+                }),
+                Some(_) => None,
+            });
+    removed.chain(added_or_modified).collect()
+}
+
+/// `dir` relative to the root with `/` separators, rejecting `..`
+fn normalize(dir: &str) -> Result<String> {
+    let mut parts = Vec::new();
+    for component in Path::new(dir).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::CurDir => {}
+            _ => return Err(FsError::InvalidPath(format!("Not relative to the root: {}", dir))),
+        }
+    }
+    Ok(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn names(entries: &[TreeEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    fn sample() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("src/generated.rs"), "").unwrap();
+        fs::write(dir.path().join("README.md"), "# Sample\n").unwrap();
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+        fs::write(dir.path().join(".gitignore"), "/target\n").unwrap();
+        fs::write(dir.path().join(".ait42ignore"), "generated.rs\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_lazy_listing_with_ignore_rules() {
+        let dir = sample();
+        let mut tree = FileTreeBuilder::new(dir.path()).build().unwrap();
+
+        // Directories first; ignored entries and .git are left out
+        assert_eq!(
+            names(tree.children("").unwrap()),
+            ["src", ".ait42ignore", ".gitignore", "b.txt", "README.md"]
+        );
+        assert!(tree.children("src").is_none());
+
+        // Rules of ignore files above the listed directory apply too
+        let src = tree.expand("src").unwrap();
+        assert_eq!(names(src), ["nested", "main.rs"]);
+        assert_eq!(src[1].path, "src/main.rs");
+        assert_eq!(src[1].size, 13);
+
+        let hidden = FileTreeBuilder::new(dir.path())
+            .show_hidden(false)
+            .git_ignore(false)
+            .build()
+            .unwrap();
+        assert_eq!(names(hidden.children("").unwrap()), ["src", "target", "b.txt", "README.md"]);
+
+        assert!(tree.expand("../outside").is_err());
+        assert!(FileTreeBuilder::new(dir.path().join("b.txt"))
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_rows_follow_expansion() {
+        let dir = sample();
+        let mut tree = FileTreeBuilder::new(dir.path())
+            .show_hidden(false)
+            .build()
+            .unwrap();
+        tree.expand("src").unwrap();
+        tree.expand("src/nested").unwrap();
+
+        let rows = tree.rows();
+        let shown: Vec<(usize, &str)> = rows
+            .iter()
+            .map(|row| (row.depth, row.entry.path.as_str()))
+            .collect();
+        assert_eq!(
+            shown,
+            [
+                (0, "src"),
+                (1, "src/nested"),
+                (1, "src/main.rs"),
+                (0, "b.txt"),
+                (0, "README.md")
+            ]
+        );
+
+        // Collapsing keeps the listing cached
+        tree.collapse("src");
+        assert_eq!(tree.rows().len(), 3);
+        assert!(!tree.is_expanded("src"));
+        assert!(tree.children("src").is_some());
+    }
+
+    #[test]
+    fn test_refresh_reports_changes() {
+        let dir = sample();
+        let mut tree = FileTreeBuilder::new(dir.path())
+            .show_hidden(false)
+            .build()
+            .unwrap();
+        tree.expand("src").unwrap();
+        tree.expand("src/nested").unwrap();
+
+        fs::write(dir.path().join("new.txt"), "").unwrap();
+        fs::write(dir.path().join("b.txt"), "changed").unwrap();
+        fs::remove_file(dir.path().join("README.md")).unwrap();
+
+        let changes = tree.path_changed(&dir.path().join("new.txt")).unwrap();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            changes[0],
+            TreeChange::Removed {
+                path: "README.md".to_string()
+            }
+        );
+        assert!(matches!(&changes[1], TreeChange::Modified { entry } if entry.path == "b.txt"));
+        assert!(matches!(&changes[2], TreeChange::Added { entry } if entry.path == "new.txt"));
+        assert!(tree.refresh("").unwrap().is_empty());
+
+        // Removing a directory drops the listings below it
+        fs::remove_dir_all(dir.path().join("src")).unwrap();
+        let changes = tree.refresh_all().unwrap();
+        assert_eq!(
+            changes,
+            [TreeChange::Removed {
+                path: "src".to_string()
+            }]
+        );
+        assert!(tree.children("src").is_none());
+        assert!(tree.children("src/nested").is_none());
+        assert!(!tree.is_expanded("src/nested"));
+
+        // Changes outside the tree are ignored
+        assert!(tree
+            .path_changed(Path::new("/elsewhere/file"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let entry = |path: &str, size| TreeEntry {
+            name: path.to_string(),
+            path: path.to_string(),
+            is_dir: false,
+            is_hidden: false,
+            is_symlink: false,
+            size,
+            modified: None,
+        };
+        let old = [entry("a", 1), entry("b", 1)];
+        let new = [entry("b", 2), entry("c", 1)];
+
+        assert_eq!(
+            diff(&old, &new),
+            [
+                TreeChange::Removed {
+                    path: "a".to_string()
+                },
+                TreeChange::Modified {
+                    entry: entry("b", 2)
+                },
+                TreeChange::Added {
+                    entry: entry("c", 1)
+                },
+            ]
+        );
+        assert!(diff(&new, &new).is_empty());
+    }
+}
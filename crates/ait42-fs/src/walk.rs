@@ -0,0 +1,353 @@
+//! Parallel Directory Walk
+//!
+//! Lists a whole subtree on several threads, for callers that need all of
+//! it at once: recursive sidebar expansion, file finding and workspace
+//! indexing. The walk can be limited in depth and entry count, told what to
+//! do with symbolic links, and cancelled from another thread. Ignore files
+//! are honored as in [`crate::tree`], and `.git` is never listed.
+
+use crate::tree::IGNORE_FILE;
+use crate::{FsError, Result};
+use ignore::{WalkBuilder, WalkState};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// What a walk does with symbolic links
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// Leave links out
+    Skip,
+    /// List links, without descending into linked directories
+    #[default]
+    List,
+    /// Descend into linked directories; links that loop are skipped
+    Follow,
+}
+
+/// Cancels a walk from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop every walk holding this token as soon as possible
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A file or directory found by a walk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkEntry {
+    pub path: PathBuf,
+    /// Path relative to the walk root, with `/` separators
+    pub relative: String,
+    /// 1 for entries of the root
+    pub depth: usize,
+    /// Also set for links to directories
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+/// Entries found by a walk
+#[derive(Debug, Clone, Default)]
+pub struct Walk {
+    /// Parents before their children, siblings by name
+    pub entries: Vec<WalkEntry>,
+    /// Whether the walk stopped at the entry limit
+    pub truncated: bool,
+    /// Whether the walk was cancelled before it finished
+    pub cancelled: bool,
+}
+
+impl Walk {
+    /// Paths of the files found
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.path.as_path())
+    }
+}
+
+/// Options of a parallel walk
+#[derive(Debug, Clone)]
+pub struct Walker {
+    root: PathBuf,
+    max_depth: Option<usize>,
+    max_entries: Option<usize>,
+    symlinks: SymlinkPolicy,
+    show_hidden: bool,
+    git_ignore: bool,
+    threads: usize,
+    cancel: CancelToken,
+}
+
+impl Walker {
+    /// Walk of everything under `root`, hidden files included and ignore
+    /// files honored
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_depth: None,
+            max_entries: None,
+            symlinks: SymlinkPolicy::default(),
+            show_hidden: true,
+            git_ignore: true,
+            threads: 0,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Go no deeper than `depth`; 1 lists only the entries of the root
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Stop after `count` entries
+    ///
+    /// Which entries are kept depends on thread timing, except that every
+    /// kept entry's parent directory is kept too.
+    pub fn max_entries(mut self, count: usize) -> Self {
+        self.max_entries = Some(count);
+        self
+    }
+
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
+        self
+    }
+
+    /// Whether to list entries whose name starts with a dot
+    pub fn show_hidden(mut self, show: bool) -> Self {
+        self.show_hidden = show;
+        self
+    }
+
+    /// Whether to leave out what `.gitignore` and `.ait42ignore` exclude
+    pub fn git_ignore(mut self, enabled: bool) -> Self {
+        self.git_ignore = enabled;
+        self
+    }
+
+    /// Threads to walk on; 0 picks a number from the available cores
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Stop the walk when `token` is cancelled
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Walk the tree, blocking until done, truncated or cancelled
+    pub fn run(&self) -> Result<Walk> {
+        if !self.root.is_dir() {
+            return Err(FsError::InvalidPath(format!("Not a directory: {}", self.root.display())));
+        }
+        debug!("Walking {}", self.root.display());
+
+        let mut builder = WalkBuilder::new(&self.root);
+        builder
+            .max_depth(self.max_depth)
+            .hidden(!self.show_hidden)
+            .git_ignore(self.git_ignore)
+            .git_exclude(self.git_ignore)
+            .git_global(self.git_ignore)
+            .ignore(false)
+            .parents(true)
+            .require_git(false)
+            .follow_links(self.symlinks == SymlinkPolicy::Follow)
+            .threads(self.threads)
+            .filter_entry(|entry| entry.file_name() != ".git");
+        if self.git_ignore {
+            builder.add_custom_ignore_filename(IGNORE_FILE);
+        }
+
+        let entries = Mutex::new(Vec::new());
+        let truncated = AtomicBool::new(false);
+        let (entries_ref, truncated_ref) = (&entries, &truncated);
+        builder.build_parallel().run(|| {
+            Box::new(move |result| {
+                if self.cancel.is_cancelled() {
+                    return WalkState::Quit;
+                }
+                let entry = match result {
+                    Ok(entry) if entry.depth() > 0 => entry,
+                    Ok(_) => return WalkState::Continue,
+                    Err(e) => {
+                        debug!("Skipping entry: {}", e);
+                        return WalkState::Continue;
+                    }
+                };
+                let is_symlink = entry.path_is_symlink();
+                if is_symlink && self.symlinks == SymlinkPolicy::Skip {
+                    return WalkState::Skip;
+                }
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir())
+                    || (is_symlink && entry.path().is_dir());
+
+                let mut entries = entries_ref.lock().unwrap_or_else(|e| e.into_inner());
+                if self.max_entries.is_some_and(|max| entries.len() >= max) {
+                    truncated_ref.store(true, Ordering::Relaxed);
+                    return WalkState::Quit;
+                }
+                entries.push(WalkEntry {
+                    relative: relative_path(&self.root, entry.path()),
+                    path: entry.into_path(),
+                    depth: 0,
+                    is_dir,
+                    is_symlink,
+                });
+                WalkState::Continue
+            })
+        });
+
+        let mut entries = entries.into_inner().unwrap_or_else(|e| e.into_inner());
+        // Paths compare by component, so parents sort before their children
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        for entry in &mut entries {
+            entry.depth = entry.relative.split('/').count();
+        }
+        Ok(Walk {
+            entries,
+            truncated: truncated.into_inner(),
+            cancelled: self.cancel.is_cancelled(),
+        })
+    }
+}
+
+/// `path` relative to `root`, with `/` separators
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// `a/b/c/deep.txt`, `a/one.txt`, `top.txt`, `.hidden`, and an ignored
+    /// `target/out.txt`
+    fn sample() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a/b/c")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::write(root.join("a/b/c/deep.txt"), "").unwrap();
+        fs::write(root.join("a/one.txt"), "").unwrap();
+        fs::write(root.join("top.txt"), "").unwrap();
+        fs::write(root.join(".hidden"), "").unwrap();
+        fs::write(root.join("target/out.txt"), "").unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        dir
+    }
+
+    fn relative(walk: &Walk) -> Vec<&str> {
+        walk.entries.iter().map(|e| e.relative.as_str()).collect()
+    }
+
+    #[test]
+    fn test_walks_everything_in_tree_order() {
+        let dir = sample();
+        let walk = Walker::new(dir.path()).run().unwrap();
+        assert_eq!(
+            relative(&walk),
+            [
+                ".gitignore",
+                ".hidden",
+                "a",
+                "a/b",
+                "a/b/c",
+                "a/b/c/deep.txt",
+                "a/one.txt",
+                "top.txt"
+            ]
+        );
+        assert!(!walk.truncated && !walk.cancelled);
+        assert_eq!(walk.entries[4].depth, 3);
+        assert!(walk.entries[4].is_dir);
+        assert_eq!(walk.files().count(), 5);
+
+        let walk = Walker::new(dir.path())
+            .show_hidden(false)
+            .git_ignore(false)
+            .run()
+            .unwrap();
+        assert!(relative(&walk).contains(&"target/out.txt"));
+        assert!(!relative(&walk).contains(&".hidden"));
+    }
+
+    #[test]
+    fn test_depth_and_entry_limits() {
+        let dir = sample();
+        let walk = Walker::new(dir.path()).max_depth(1).run().unwrap();
+        assert_eq!(relative(&walk), [".gitignore", ".hidden", "a", "top.txt"]);
+
+        let walk = Walker::new(dir.path()).max_entries(3).run().unwrap();
+        assert_eq!(walk.entries.len(), 3);
+        assert!(walk.truncated);
+        // Entries are only found below directories already listed
+        for entry in &walk.entries {
+            if let Some((parent, _)) = entry.relative.rsplit_once('/') {
+                assert!(relative(&walk).contains(&parent));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cancelled_walk() {
+        let dir = sample();
+        let token = CancelToken::new();
+        token.cancel();
+        let walk = Walker::new(dir.path()).cancel_token(token).run().unwrap();
+        assert!(walk.cancelled);
+        assert!(walk.entries.is_empty());
+        assert!(Walker::new(dir.path().join("top.txt")).run().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policies() {
+        let dir = sample();
+        std::os::unix::fs::symlink(dir.path().join("a"), dir.path().join("link")).unwrap();
+
+        let walk = Walker::new(dir.path()).run().unwrap();
+        let link = walk.entries.iter().find(|e| e.relative == "link").unwrap();
+        assert!(link.is_symlink && link.is_dir);
+        assert!(!relative(&walk).contains(&"link/one.txt"));
+
+        let walk = Walker::new(dir.path())
+            .symlinks(SymlinkPolicy::Follow)
+            .run()
+            .unwrap();
+        assert!(relative(&walk).contains(&"link/one.txt"));
+
+        let walk = Walker::new(dir.path())
+            .symlinks(SymlinkPolicy::Skip)
+            .run()
+            .unwrap();
+        assert!(!relative(&walk).contains(&"link"));
+    }
+}
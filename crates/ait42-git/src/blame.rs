@@ -0,0 +1,169 @@
+//! Blame
+//!
+//! Reads `git blame --line-porcelain` for a file in the working tree, so
+//! every line can be annotated with the commit that last changed it. Lines
+//! changed since HEAD are attributed to git's all-zero "not committed yet"
+//! commit.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// Object ID git blames uncommitted lines on
+const UNCOMMITTED: &str = "0000000000000000000000000000000000000000";
+
+/// Commit that last changed a line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BlameLine {
+    /// Zero-based line in the working tree file
+    pub line: usize,
+    /// Full object ID
+    pub sha: String,
+    /// Author name
+    pub author: String,
+    /// Author email
+    pub email: String,
+    /// Author date as a Unix timestamp
+    pub timestamp: i64,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+impl BlameLine {
+    /// Abbreviated object ID
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+
+    /// Whether the line was changed since HEAD
+    pub fn is_uncommitted(&self) -> bool {
+        self.sha == UNCOMMITTED
+    }
+}
+
+/// Blame each line of `path` in the working tree
+///
+/// `path` is relative to the repository root, or absolute inside it.
+/// Fails for files git does not track.
+pub fn blame(repo: &Repository, path: &Path) -> Result<Vec<BlameLine>> {
+    let relative = repo.relative_path(path)?;
+    let output = repo.run(&[
+        OsStr::new("blame"),
+        OsStr::new("--line-porcelain"),
+        OsStr::new("--"),
+        relative.as_os_str(),
+    ])?;
+    parse(&output)
+}
+
+fn parse(output: &str) -> Result<Vec<BlameLine>> {
+    let mut lines = Vec::new();
+    let mut current: Option<BlameLine> = None;
+
+    for line in output.lines() {
+        // The line's content ends its record
+        if line.starts_with('\t') {
+            let blamed = current
+                .take()
+                .ok_or_else(|| GitError::Parse(line.to_string()))?;
+            lines.push(blamed);
+            continue;
+        }
+
+        let Some(blamed) = current.as_mut() else {
+            current = Some(parse_header(line)?);
+            continue;
+        };
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => blamed.author = value.to_string(),
+            "author-mail" => {
+                blamed.email = value
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string();
+            }
+            "author-time" => {
+                blamed.timestamp = value
+                    .parse()
+                    .map_err(|_| GitError::Parse(line.to_string()))?;
+            }
+            "summary" => blamed.summary = value.to_string(),
+            _ => {}
+        }
+    }
+    Ok(lines)
+}
+
+/// Start of a record: `<sha> <original line> <final line> [<group size>]`
+fn parse_header(line: &str) -> Result<BlameLine> {
+    let mut fields = line.split(' ');
+    let (Some(sha), Some(_), Some(final_line)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(GitError::Parse(line.to_string()));
+    };
+    let final_line: usize = final_line
+        .parse()
+        .map_err(|_| GitError::Parse(line.to_string()))?;
+
+    Ok(BlameLine {
+        line: final_line.saturating_sub(1),
+        sha: sha.to_string(),
+        author: String::new(),
+        email: String::new(),
+        timestamp: 0,
+        summary: String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+
+    #[test]
+    fn test_blame_attributes_lines_to_commits() {
+        let (_dir, repo) = init_repo();
+        let first = commit_file(&repo, "a.txt", "one\ntwo\n", "add a");
+        let second = commit_file(&repo, "a.txt", "one\n2\nthree\n", "change a");
+
+        let lines = blame(&repo, Path::new("a.txt")).unwrap();
+        let shas: Vec<&str> = lines.iter().map(|l| l.sha.as_str()).collect();
+        assert_eq!(shas, vec![first.as_str(), second.as_str(), second.as_str()]);
+        assert_eq!(lines.iter().map(|l| l.line).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(lines[0].author, "Test");
+        assert_eq!(lines[0].email, "test@example.com");
+        assert_eq!(lines[1].summary, "change a");
+        assert!(lines[1].timestamp > 0);
+        assert!(!lines[1].is_uncommitted());
+    }
+
+    #[test]
+    fn test_blame_uncommitted_and_absolute_paths() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "add a");
+        std::fs::write(repo.root().join("a.txt"), "one\nnew\n").unwrap();
+
+        let lines = blame(&repo, &repo.root().join("a.txt")).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(!lines[0].is_uncommitted());
+        assert!(lines[1].is_uncommitted());
+        assert_eq!(lines[1].short_sha(), "0000000");
+    }
+
+    #[test]
+    fn test_blame_rejects_outside_and_untracked_paths() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "add a");
+        std::fs::write(repo.root().join("new.txt"), "x\n").unwrap();
+
+        assert!(matches!(
+            blame(&repo, Path::new("../a.txt")),
+            Err(GitError::InvalidOperation(_))
+        ));
+        assert!(blame(&repo, Path::new("new.txt")).is_err());
+    }
+}
@@ -0,0 +1,486 @@
+//! Changelog
+//!
+//! Builds a CHANGELOG.md section from the Conventional Commits made since the
+//! last tag. [`Changelog::since_last_tag`] reads the commits,
+//! [`Changelog::bump`] suggests the next semantic version and
+//! [`Changelog::render`] groups the commits under Markdown headings. An LLM
+//! may reword the entries ([`polish_prompt`], [`parse_polished`]) as long as
+//! it keeps the headings, and [`insert_section`] places the result above the
+//! previous releases.
+
+use crate::commit::COMMIT_TYPES;
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Headings of the changelog sections, in order, with the commit types they
+/// collect; types not listed (`style`, `test`, `build`, `ci`, `chore`) are
+/// left out unless they break something
+const SECTIONS: &[(&str, &[&str])] = &[
+    ("Features", &["feat"]),
+    ("Bug Fixes", &["fix"]),
+    ("Performance", &["perf"]),
+    ("Refactoring", &["refactor"]),
+    ("Documentation", &["docs"]),
+    ("Reverts", &["revert"]),
+];
+
+/// Heading of breaking changes, listed first
+const BREAKING_HEADING: &str = "Breaking Changes";
+
+/// Heading of commits that do not follow Conventional Commits
+const OTHER_HEADING: &str = "Other Changes";
+
+/// Subject line of a Conventional Commit, `type(scope)!: description`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalSubject<'a> {
+    pub kind: &'a str,
+    pub scope: Option<&'a str>,
+    /// Marked breaking with `!`
+    pub breaking: bool,
+    pub description: &'a str,
+}
+
+impl<'a> ConventionalSubject<'a> {
+    /// Parse `subject`; `None` if it does not follow Conventional Commits
+    pub fn parse(subject: &'a str) -> Option<Self> {
+        let (prefix, description) = subject.split_once(": ")?;
+        let description = description.trim();
+        let (prefix, breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => {
+                let scope = scope.strip_suffix(')').filter(|s| !s.is_empty())?;
+                (kind, Some(scope))
+            }
+            None => (prefix, None),
+        };
+        (COMMIT_TYPES.contains(&kind) && !description.is_empty()).then_some(Self {
+            kind,
+            scope,
+            breaking,
+            description,
+        })
+    }
+}
+
+/// Semantic version component to increase
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+/// A commit as listed in the changelog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangelogEntry {
+    /// Full object ID
+    pub sha: String,
+    /// Commit type; `None` for commits that are not Conventional Commits
+    pub kind: Option<String>,
+    pub scope: Option<String>,
+    /// Subject without the type and scope
+    pub description: String,
+    /// Marked with `!` or a `BREAKING CHANGE:` footer
+    pub breaking: bool,
+}
+
+impl ChangelogEntry {
+    /// Entry for a commit with `subject` and `body`
+    pub fn new(sha: &str, subject: &str, body: &str) -> Self {
+        let breaking_footer = body.lines().any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+        match ConventionalSubject::parse(subject) {
+            Some(parsed) => Self {
+                sha: sha.to_string(),
+                kind: Some(parsed.kind.to_string()),
+                scope: parsed.scope.map(str::to_string),
+                description: parsed.description.to_string(),
+                breaking: parsed.breaking || breaking_footer,
+            },
+            None => Self {
+                sha: sha.to_string(),
+                kind: None,
+                scope: None,
+                description: subject.trim().to_string(),
+                breaking: breaking_footer,
+            },
+        }
+    }
+
+    /// Markdown list item, e.g. `- **lsp:** restart crashed servers (1a2b3c4)`
+    fn line(&self) -> String {
+        let scope = self
+            .scope
+            .as_ref()
+            .map(|scope| format!("**{}:** ", scope))
+            .unwrap_or_default();
+        let short_sha = &self.sha[..self.sha.len().min(7)];
+        format!("- {}{} ({})", scope, self.description, short_sha)
+    }
+}
+
+/// Commits of the next release
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changelog {
+    /// Tag the release follows; `None` if the history has no tags
+    pub previous_tag: Option<String>,
+    /// Commits since the tag, newest first, merges left out
+    pub entries: Vec<ChangelogEntry>,
+}
+
+impl Changelog {
+    /// Commits reachable from HEAD since the closest tag
+    pub fn since_last_tag(repo: &Repository) -> Result<Self> {
+        let previous_tag = repo.latest_tag()?;
+        if repo.head()?.is_none() {
+            return Ok(Self {
+                previous_tag,
+                entries: Vec::new(),
+            });
+        }
+
+        let range = match &previous_tag {
+            Some(tag) => format!("{}..HEAD", tag),
+            None => "HEAD".to_string(),
+        };
+        let log = repo.run(&[
+            "log",
+            "--no-merges",
+            "--format=%H%x1f%s%x1f%b%x1e",
+            range.as_str(),
+        ])?;
+
+        let entries = log
+            .split('\x1e')
+            .map(|record| record.trim_start_matches('\n'))
+            .filter(|record| !record.is_empty())
+            .map(|record| {
+                let fields: Vec<&str> = record.splitn(3, '\x1f').collect();
+                match fields[..] {
+                    [sha, subject, body] => Ok(ChangelogEntry::new(sha, subject, body)),
+                    _ => Err(GitError::Parse(record.to_string())),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            previous_tag,
+            entries,
+        })
+    }
+
+    /// Version increase the commits call for; `None` if there are none
+    ///
+    /// Breaking changes bump the major version, features the minor one and
+    /// anything else the patch. Before 1.0.0 a breaking change only bumps the
+    /// minor version, as semantic versioning allows.
+    pub fn bump(&self) -> Option<Bump> {
+        let bump = self
+            .entries
+            .iter()
+            .map(|entry| {
+                if entry.breaking {
+                    Bump::Major
+                } else if entry.kind.as_deref() == Some("feat") {
+                    Bump::Minor
+                } else {
+                    Bump::Patch
+                }
+            })
+            .max()?;
+        let unstable = self
+            .previous_version()
+            .is_some_and(|(_, [major, _, _])| major == 0);
+        Some(if unstable && bump == Bump::Major {
+            Bump::Minor
+        } else {
+            bump
+        })
+    }
+
+    /// Version after applying `bump` to the previous tag, keeping its `v`
+    /// prefix; `None` if the tag is not a `MAJOR.MINOR.PATCH` version
+    pub fn next_version(&self, bump: Bump) -> Option<String> {
+        let (prefix, [major, minor, patch]) = self.previous_version()?;
+        let [major, minor, patch] = match bump {
+            Bump::Major => [major + 1, 0, 0],
+            Bump::Minor => [major, minor + 1, 0],
+            Bump::Patch => [major, minor, patch + 1],
+        };
+        Some(format!("{}{}.{}.{}", prefix, major, minor, patch))
+    }
+
+    /// Prefix and numbers of the previous tag, e.g. `("v", [1, 2, 3])`
+    fn previous_version(&self) -> Option<(&str, [u64; 3])> {
+        let tag = self.previous_tag.as_deref()?;
+        let (prefix, version) = match tag.strip_prefix('v') {
+            Some(version) => ("v", version),
+            None => ("", tag),
+        };
+        let mut numbers = version.split('.').map(|n| n.parse::<u64>().ok());
+        match (numbers.next(), numbers.next(), numbers.next(), numbers.next()) {
+            (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) => {
+                Some((prefix, [major, minor, patch]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Markdown section for `version` released on `date`
+    ///
+    /// Breaking changes come first, then one heading per commit type that
+    /// has entries, then commits that are not Conventional Commits.
+    pub fn render(&self, version: &str, date: &str) -> String {
+        let mut groups: Vec<(&str, Vec<&ChangelogEntry>)> = Vec::new();
+        groups.push((BREAKING_HEADING, self.entries.iter().filter(|e| e.breaking).collect()));
+        for (heading, kinds) in SECTIONS {
+            let entries = self
+                .entries
+                .iter()
+                .filter(|e| !e.breaking && e.kind.as_deref().is_some_and(|k| kinds.contains(&k)))
+                .collect();
+            groups.push((*heading, entries));
+        }
+        let other = self
+            .entries
+            .iter()
+            .filter(|e| !e.breaking && e.kind.is_none())
+            .collect();
+        groups.push((OTHER_HEADING, other));
+
+        let mut section = format!("## {} ({})\n", version, date);
+        for (heading, entries) in groups.iter().filter(|(_, entries)| !entries.is_empty()) {
+            section.push_str(&format!("\n### {}\n\n", heading));
+            for entry in entries {
+                section.push_str(&entry.line());
+                section.push('\n');
+            }
+        }
+        section
+    }
+}
+
+/// Prompt asking an LLM to reword the entries of a rendered `section`
+pub fn polish_prompt(section: &str) -> String {
+    format!(
+        "Below is a changelog section generated from commit messages. Rewrite \
+         each entry so it reads well to users of the project: fix grammar and \
+         spelling, use the imperative mood consistently and expand cryptic \
+         abbreviations. Keep every heading, every entry, their order, the \
+         bold scopes and the commit hashes exactly as they are. Do not add or \
+         remove entries.\n\n\
+         Reply with the Markdown section only, without code fences.\n\n\
+         {section}",
+        section = section.trim_end(),
+    )
+}
+
+/// Read a reworded section from an LLM `response`
+///
+/// Returns `None` when the reply changed the headings or the number of
+/// entries, so the caller can fall back to the original `section`.
+pub fn parse_polished(section: &str, response: &str) -> Option<String> {
+    let polished: String = response
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect();
+    let polished = format!("{}\n", polished.trim());
+
+    let outline = |text: &str| -> Vec<String> {
+        text.lines()
+            .filter_map(|line| {
+                if line.starts_with('#') {
+                    Some(line.to_string())
+                } else {
+                    line.starts_with("- ").then(|| "-".to_string())
+                }
+            })
+            .collect()
+    };
+    (outline(&polished) == outline(section)).then_some(polished)
+}
+
+/// Where to put `section` in the current `changelog`, and the text to insert
+///
+/// The section goes above the first release (`## ` heading), after any
+/// title and introduction. An empty changelog gets a `# Changelog` title.
+pub fn insert_section(changelog: &str, section: &str) -> (usize, String) {
+    let section = format!("{}\n", section.trim_end());
+    if changelog.trim().is_empty() {
+        return (0, format!("# Changelog\n\n{}", section));
+    }
+
+    let mut offset = 0;
+    for line in changelog.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            return (offset, format!("{}\n", section));
+        }
+        offset += line.len();
+    }
+    let separator = if changelog.ends_with("\n\n") {
+        ""
+    } else if changelog.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    (changelog.len(), format!("{}{}", separator, section))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+
+    fn changelog(previous_tag: Option<&str>, commits: &[(&str, &str)]) -> Changelog {
+        Changelog {
+            previous_tag: previous_tag.map(str::to_string),
+            entries: commits
+                .iter()
+                .enumerate()
+                .map(|(i, (subject, body))| {
+                    ChangelogEntry::new(&format!("{:040}", i), subject, body)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_subject() {
+        assert_eq!(
+            ConventionalSubject::parse("feat(lsp)!: restart servers"),
+            Some(ConventionalSubject {
+                kind: "feat",
+                scope: Some("lsp"),
+                breaking: true,
+                description: "restart servers",
+            })
+        );
+        assert!(ConventionalSubject::parse("fix: typo").is_some());
+        assert!(ConventionalSubject::parse("fix(): typo").is_none());
+        assert!(ConventionalSubject::parse("feature: typo").is_none());
+        assert!(ConventionalSubject::parse("Update README").is_none());
+
+        let entry = ChangelogEntry::new("abc", "refactor: split", "Body\n\nBREAKING CHANGE: gone");
+        assert!(entry.breaking);
+        assert_eq!(entry.kind.as_deref(), Some("refactor"));
+    }
+
+    #[test]
+    fn test_bump_and_next_version() {
+        let fixes = changelog(Some("v1.2.3"), &[("fix: a", ""), ("chore: b", "")]);
+        assert_eq!(fixes.bump(), Some(Bump::Patch));
+        assert_eq!(fixes.next_version(Bump::Patch).as_deref(), Some("v1.2.4"));
+
+        let feature = changelog(Some("1.2.3"), &[("fix: a", ""), ("feat: b", "")]);
+        assert_eq!(feature.bump(), Some(Bump::Minor));
+        assert_eq!(feature.next_version(Bump::Minor).as_deref(), Some("1.3.0"));
+
+        let breaking = changelog(Some("v1.2.3"), &[("feat!: b", "")]);
+        assert_eq!(breaking.bump(), Some(Bump::Major));
+        assert_eq!(breaking.next_version(Bump::Major).as_deref(), Some("v2.0.0"));
+
+        let unstable = changelog(Some("v0.4.1"), &[("feat!: b", "")]);
+        assert_eq!(unstable.bump(), Some(Bump::Minor));
+
+        assert_eq!(changelog(Some("v1.0.0"), &[]).bump(), None);
+        let untagged = changelog(None, &[("feat: a", "")]);
+        assert_eq!(untagged.bump(), Some(Bump::Minor));
+        assert_eq!(untagged.next_version(Bump::Minor), None);
+        assert_eq!(changelog(Some("release-2"), &[]).next_version(Bump::Patch), None);
+    }
+
+    #[test]
+    fn test_render_groups_commits() {
+        let log = changelog(
+            Some("v1.0.0"),
+            &[
+                ("fix(git): keep the index", ""),
+                ("chore: bump deps", ""),
+                ("Update README", ""),
+                ("feat: stash panel", ""),
+                ("perf!: drop the cache", ""),
+            ],
+        );
+        assert_eq!(
+            log.render("v2.0.0", "2026-01-31"),
+            "## v2.0.0 (2026-01-31)\n\n\
+             ### Breaking Changes\n\n\
+             - drop the cache (0000000)\n\n\
+             ### Features\n\n\
+             - stash panel (0000000)\n\n\
+             ### Bug Fixes\n\n\
+             - **git:** keep the index (0000000)\n\n\
+             ### Other Changes\n\n\
+             - Update README (0000000)\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_polished() {
+        let section = "## v1.1.0 (2026-01-31)\n\n### Features\n\n- add stsh panel (1a2b3c4)\n";
+        let polished =
+            "```markdown\n## v1.1.0 (2026-01-31)\n\n### Features\n\n- Add a stash panel (1a2b3c4)\n```";
+        assert_eq!(
+            parse_polished(section, polished).as_deref(),
+            Some("## v1.1.0 (2026-01-31)\n\n### Features\n\n- Add a stash panel (1a2b3c4)\n")
+        );
+        assert_eq!(parse_polished(section, "## v1.1.0 (2026-01-31)\n\n- Add a stash panel"), None);
+        assert_eq!(
+            parse_polished(section, &format!("{}- Invented entry (0000000)\n", section)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_insert_section() {
+        let section = "## v1.1.0 (2026-01-31)\n\n- b\n";
+        assert_eq!(insert_section("", section), (0, format!("# Changelog\n\n{}", section)));
+
+        let existing = "# Changelog\n\nAll notable changes.\n\n## v1.0.0 (2025-12-01)\n\n- a\n";
+        let (offset, text) = insert_section(existing, section);
+        assert_eq!(offset, existing.find("## v1.0.0").unwrap());
+        let mut updated = existing.to_string();
+        updated.insert_str(offset, &text);
+        assert!(
+            updated.contains("All notable changes.\n\n## v1.1.0 (2026-01-31)\n\n- b\n\n## v1.0.0")
+        );
+
+        let (offset, text) = insert_section("# Changelog", section);
+        assert_eq!((offset, text), (11, format!("\n\n{}", section)));
+    }
+
+    #[test]
+    fn test_since_last_tag() {
+        let (_dir, repo) = init_repo();
+        let empty = Changelog::since_last_tag(&repo).unwrap();
+        assert!(empty.entries.is_empty());
+        assert_eq!(empty.previous_tag, None);
+
+        commit_file(&repo, "a.txt", "1\n", "feat: first");
+        assert_eq!(Changelog::since_last_tag(&repo).unwrap().entries.len(), 1);
+
+        repo.run(&["tag", "v0.1.0"]).unwrap();
+        commit_file(&repo, "a.txt", "2\n", "fix(a): second\n\nBREAKING CHANGE: format");
+        let sha = commit_file(&repo, "a.txt", "3\n", "Tidy up");
+
+        let log = Changelog::since_last_tag(&repo).unwrap();
+        assert_eq!(log.previous_tag.as_deref(), Some("v0.1.0"));
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.entries[0], ChangelogEntry::new(&sha, "Tidy up", ""));
+        assert!(log.entries[1].breaking);
+        assert_eq!(log.entries[1].scope.as_deref(), Some("a"));
+        assert_eq!(log.bump(), Some(Bump::Minor));
+        assert_eq!(log.next_version(Bump::Minor).as_deref(), Some("v0.2.0"));
+    }
+}
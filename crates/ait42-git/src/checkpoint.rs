@@ -0,0 +1,282 @@
+//! Working Tree Checkpoints
+//!
+//! Snapshots of the working tree committed to a shadow ref, as a safety net
+//! for editing sessions where an agent rewrites many files at once.
+//!
+//! Checkpoints are built in a private index file, so the user's index,
+//! branches and HEAD are never touched. Each checkpoint's parent is the
+//! previous checkpoint, which makes the shadow ref a linear history that
+//! `git log refs/ait42/checkpoints` can show directly. Untracked files are
+//! included; ignored files are not.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use tracing::info;
+
+/// Ref holding the checkpoint history
+pub const CHECKPOINT_REF: &str = "refs/ait42/checkpoints";
+
+/// Trailer recording the HEAD commit a checkpoint was taken on
+const HEAD_TRAILER: &str = "Checkpoint-Head";
+
+/// Identity used for checkpoint commits, so they work without user config
+const IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "AIT42 Checkpoint"),
+    ("GIT_AUTHOR_EMAIL", "checkpoint@ait42.local"),
+    ("GIT_COMMITTER_NAME", "AIT42 Checkpoint"),
+    ("GIT_COMMITTER_EMAIL", "checkpoint@ait42.local"),
+];
+
+/// A snapshot of the working tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Checkpoint {
+    /// Checkpoint commit
+    pub sha: String,
+    /// Description given when the checkpoint was taken
+    pub message: String,
+    /// Unix timestamp
+    pub timestamp: i64,
+    /// HEAD commit at the time, if the repository had one
+    pub head: Option<String>,
+}
+
+/// Commit the current working tree as a new checkpoint
+///
+/// Returns `None` when nothing changed since the last checkpoint.
+pub fn create(repo: &Repository, message: &str) -> Result<Option<Checkpoint>> {
+    let tree = snapshot_tree(repo)?;
+    let previous = repo.rev_parse(CHECKPOINT_REF)?;
+
+    if let Some(previous) = &previous {
+        let previous_tree = repo.rev_parse(&format!("{}^{{tree}}", previous))?;
+        if previous_tree.as_deref() == Some(tree.as_str()) {
+            return Ok(None);
+        }
+    }
+
+    let head = repo.head()?;
+    let mut full_message = message.to_string();
+    if let Some(head) = &head {
+        full_message.push_str(&format!("\n\n{}: {}", HEAD_TRAILER, head));
+    }
+
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", full_message.as_str()];
+    if let Some(previous) = &previous {
+        args.extend(["-p", previous.as_str()]);
+    }
+    let identity: Vec<(&str, &OsStr)> = IDENTITY
+        .iter()
+        .map(|(key, value)| (*key, OsStr::new(*value)))
+        .collect();
+    let sha = repo.run_with_env(&args, &identity)?.trim().to_string();
+
+    // Compare-and-swap so concurrent checkpoints cannot drop each other
+    let expected = previous.as_deref().unwrap_or("");
+    repo.run(&[
+        "update-ref",
+        "-m",
+        "checkpoint",
+        CHECKPOINT_REF,
+        &sha,
+        expected,
+    ])?;
+
+    info!("Created checkpoint {} ({})", &sha[..sha.len().min(8)], message);
+    Ok(list(repo, 1)?.pop())
+}
+
+/// Most recent checkpoints, newest first
+pub fn list(repo: &Repository, limit: usize) -> Result<Vec<Checkpoint>> {
+    if repo.rev_parse(CHECKPOINT_REF)?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let limit = limit.to_string();
+    let output = repo.run(&[
+        "log",
+        "--format=%H%x1f%ct%x1f%B%x1e",
+        "-n",
+        limit.as_str(),
+        CHECKPOINT_REF,
+    ])?;
+
+    output
+        .split('\x1e')
+        .map(str::trim)
+        .filter(|record| !record.is_empty())
+        .map(parse_record)
+        .collect()
+}
+
+/// Restore files from a checkpoint into the working tree
+///
+/// Restores `paths`, or the whole tree when empty. The index is left alone,
+/// so restored changes show up as unstaged modifications. The current state
+/// is checkpointed first, which makes the restore itself undoable; that
+/// checkpoint is returned if one was needed.
+///
+/// Files that are tracked but missing from the checkpoint are deleted.
+/// Untracked files that did not exist at checkpoint time are kept.
+pub fn restore(repo: &Repository, sha: &str, paths: &[String]) -> Result<Option<Checkpoint>> {
+    let commit = repo
+        .rev_parse(&format!("{}^{{commit}}", sha))?
+        .ok_or_else(|| GitError::NotFound(format!("checkpoint {}", sha)))?;
+    if !repo.succeeds(&[
+        "merge-base",
+        "--is-ancestor",
+        commit.as_str(),
+        CHECKPOINT_REF,
+    ])? {
+        return Err(GitError::NotFound(format!("checkpoint {}", sha)));
+    }
+
+    let short = &commit[..8];
+    let safety = create(repo, &format!("Before restoring checkpoint {}", short))?;
+
+    let source = format!("--source={}", commit);
+    let mut args = vec!["restore", source.as_str(), "--worktree", "--"];
+    if paths.is_empty() {
+        args.push(".");
+    } else {
+        args.extend(paths.iter().map(String::as_str));
+    }
+    repo.run(&args)?;
+
+    info!("Restored checkpoint {}", short);
+    Ok(safety)
+}
+
+/// Write the working tree, including untracked files, as a tree object
+fn snapshot_tree(repo: &Repository) -> Result<String> {
+    let index = repo.git_dir().join("ait42-checkpoint.index");
+    let _ = std::fs::remove_file(&index);
+    let env = [("GIT_INDEX_FILE", index.as_os_str())];
+
+    let result = (|| {
+        match repo.head()? {
+            Some(head) => repo.run_with_env(&["read-tree", head.as_str()], &env)?,
+            None => repo.run_with_env(&["read-tree", "--empty"], &env)?,
+        };
+        repo.run_with_env(&["add", "-A", "--", "."], &env)?;
+        Ok(repo.run_with_env(&["write-tree"], &env)?.trim().to_string())
+    })();
+
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+fn parse_record(record: &str) -> Result<Checkpoint> {
+    let mut fields = record.splitn(3, '\x1f');
+    let (Some(sha), Some(timestamp), Some(body)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(GitError::Parse(record.to_string()));
+    };
+
+    let timestamp = timestamp
+        .parse()
+        .map_err(|_| GitError::Parse(record.to_string()))?;
+    let head = body.lines().find_map(|line| {
+        line.strip_prefix(HEAD_TRAILER)
+            .and_then(|rest| rest.strip_prefix(": "))
+            .map(str::to_string)
+    });
+
+    Ok(Checkpoint {
+        sha: sha.to_string(),
+        message: body.lines().next().unwrap_or("").to_string(),
+        timestamp,
+        head,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+    use std::fs;
+
+    #[test]
+    fn test_create_leaves_index_and_head_alone() {
+        let (_dir, repo) = init_repo();
+        let head = commit_file(&repo, "a.txt", "one\n", "initial");
+
+        fs::write(repo.root().join("a.txt"), "two\n").unwrap();
+        fs::write(repo.root().join("new.txt"), "new\n").unwrap();
+        let status_before = repo.run(&["status", "--porcelain"]).unwrap();
+
+        let checkpoint = create(&repo, "autosave").unwrap().unwrap();
+        assert_eq!(checkpoint.message, "autosave");
+        assert_eq!(checkpoint.head.as_deref(), Some(head.as_str()));
+
+        assert_eq!(repo.head().unwrap().as_deref(), Some(head.as_str()));
+        assert_eq!(repo.run(&["status", "--porcelain"]).unwrap(), status_before);
+
+        let files = repo
+            .run(&["ls-tree", "--name-only", &checkpoint.sha])
+            .unwrap();
+        assert!(files.contains("new.txt"));
+    }
+
+    #[test]
+    fn test_unchanged_tree_is_skipped() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+
+        assert!(create(&repo, "first").unwrap().is_some());
+        assert!(create(&repo, "second").unwrap().is_none());
+
+        fs::write(repo.root().join("a.txt"), "changed\n").unwrap();
+        assert!(create(&repo, "third").unwrap().is_some());
+
+        let checkpoints = list(&repo, 10).unwrap();
+        let messages: Vec<_> = checkpoints.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, ["third", "first"]);
+    }
+
+    #[test]
+    fn test_restore_is_undoable() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "good\n", "initial");
+        fs::write(repo.root().join("notes.txt"), "draft\n").unwrap();
+        let good = create(&repo, "good").unwrap().unwrap();
+
+        fs::write(repo.root().join("a.txt"), "broken\n").unwrap();
+        fs::remove_file(repo.root().join("notes.txt")).unwrap();
+
+        let safety = restore(&repo, &good.sha, &[]).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "good\n");
+        assert_eq!(fs::read_to_string(repo.root().join("notes.txt")).unwrap(), "draft\n");
+
+        // Index still matches HEAD
+        assert!(repo
+            .run(&["diff", "--cached", "--name-only"])
+            .unwrap()
+            .is_empty());
+
+        restore(&repo, &safety.sha, &["a.txt".to_string()]).unwrap();
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "broken\n");
+    }
+
+    #[test]
+    fn test_restore_rejects_other_commits() {
+        let (_dir, repo) = init_repo();
+        let head = commit_file(&repo, "a.txt", "one\n", "initial");
+        create(&repo, "checkpoint").unwrap();
+
+        assert!(matches!(restore(&repo, &head, &[]), Err(GitError::NotFound(_))));
+        assert!(matches!(restore(&repo, "nope", &[]), Err(GitError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_repository_without_commits() {
+        let (_dir, repo) = init_repo();
+        assert!(list(&repo, 10).unwrap().is_empty());
+
+        fs::write(repo.root().join("a.txt"), "one\n").unwrap();
+        let checkpoint = create(&repo, "first").unwrap().unwrap();
+        assert!(checkpoint.head.is_none());
+    }
+}
@@ -0,0 +1,230 @@
+//! Commits
+//!
+//! Commits the index or the whole working tree, and drafts commit messages
+//! for the index. Drafting is split in two: [`message_prompt`] describes the
+//! staged changes for an LLM, cut down to a byte budget so large changes
+//! stay affordable, and [`CommitMessage::parse`] turns the reply into a
+//! subject line and body bullets for the user to edit before committing.
+
+use crate::changelog::ConventionalSubject;
+use crate::diff::budget_diff;
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Default size of the diff included in a commit message prompt
+pub const DEFAULT_DIFF_BUDGET: usize = 24 * 1024;
+
+/// Conventional Commits types accepted in a subject line
+pub(crate) const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Everything staged for the next commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedChanges {
+    /// Full patch of the index against HEAD
+    pub diff: String,
+    /// `--stat` summary of the same changes
+    pub stat: String,
+}
+
+/// Read the staged changes, failing when nothing is staged
+pub fn staged_changes(repo: &Repository) -> Result<StagedChanges> {
+    let diff = repo.run(&["diff", "--cached", "--no-color", "--no-ext-diff"])?;
+    if diff.trim().is_empty() {
+        return Err(GitError::InvalidOperation("nothing is staged".to_string()));
+    }
+    let stat = repo.run(&["diff", "--cached", "--no-color", "--stat"])?;
+    Ok(StagedChanges { diff, stat })
+}
+
+/// Commit the index and return the new commit
+pub fn commit(repo: &Repository, message: &str) -> Result<String> {
+    if message.trim().is_empty() {
+        return Err(GitError::InvalidOperation("the commit message is empty".to_string()));
+    }
+    repo.run(&["commit", "--quiet", "--cleanup=strip", "-m", message])?;
+    let sha = repo
+        .head()?
+        .ok_or_else(|| GitError::NotFound("HEAD".to_string()))?;
+    info!("Committed {}", &sha[..7]);
+    Ok(sha)
+}
+
+/// Stage every change, new files included, and commit it
+///
+/// Returns `None` when there was nothing to commit.
+pub fn commit_all(repo: &Repository, message: &str) -> Result<Option<String>> {
+    repo.run(&["add", "--all"])?;
+    if repo.succeeds(&["diff", "--cached", "--quiet"])? {
+        return Ok(None);
+    }
+    commit(repo, message).map(Some)
+}
+
+/// Build the prompt asking for a message describing `changes`
+///
+/// Returns the prompt and whether the diff had to be shortened.
+pub fn message_prompt(changes: &StagedChanges, max_diff_bytes: usize) -> (String, bool) {
+    let diff = budget_diff(&changes.diff, max_diff_bytes);
+    let note = if diff.truncated {
+        "Some file diffs were shortened; use the summary for the overall scope.\n"
+    } else {
+        ""
+    };
+
+    let prompt = format!(
+        "Write a git commit message for the staged changes below.\n\n\
+         Format:\n\
+         - First line: a Conventional Commits subject, `type(scope): summary`, \
+         at most 72 characters, imperative mood, no trailing period. \
+         Types: {types}.\n\
+         - Then a blank line and 2-5 bullet points starting with \"- \" that \
+         explain what changed and why.\n\
+         Reply with the commit message only, without code fences.\n\n\
+         {note}\n\
+         Summary:\n{stat}\n\
+         Diff:\n{diff}",
+        types = COMMIT_TYPES.join(", "),
+        note = note,
+        stat = changes.stat.trim_end(),
+        diff = diff.text,
+    );
+    (prompt, diff.truncated)
+}
+
+/// A drafted commit message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitMessage {
+    pub subject: String,
+    pub body: Vec<String>,
+}
+
+impl CommitMessage {
+    /// Parse an LLM reply
+    ///
+    /// Tolerates code fences and `*` bullets; body lines that are not
+    /// bullets are kept as bullets of their own.
+    pub fn parse(response: &str) -> Result<Self> {
+        let mut lines = response
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with("```"));
+
+        let subject = lines
+            .by_ref()
+            .find(|line| !line.is_empty())
+            .map(|line| line.trim_matches('`').trim_end_matches('.').to_string())
+            .ok_or_else(|| GitError::Parse("empty commit message".to_string()))?;
+
+        let body = lines
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.strip_prefix("- ")
+                    .or_else(|| line.strip_prefix("* "))
+                    .unwrap_or(line)
+                    .to_string()
+            })
+            .collect();
+
+        Ok(Self { subject, body })
+    }
+
+    /// Whether the subject follows `type(scope): summary`
+    pub fn is_conventional(&self) -> bool {
+        ConventionalSubject::parse(&self.subject).is_some()
+    }
+}
+
+impl std::fmt::Display for CommitMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.subject)?;
+        if !self.body.is_empty() {
+            writeln!(f)?;
+            for line in &self.body {
+                write!(f, "\n- {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+    use std::fs;
+
+    #[test]
+    fn test_staged_changes_and_commit() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+
+        fs::write(repo.root().join("a.txt"), "two\n").unwrap();
+        assert!(matches!(staged_changes(&repo), Err(GitError::InvalidOperation(_))));
+
+        repo.run(&["add", "a.txt"]).unwrap();
+        let changes = staged_changes(&repo).unwrap();
+        assert!(changes.diff.contains("+two"));
+        assert!(changes.stat.contains("a.txt"));
+
+        let sha = commit(&repo, "fix: update a\n\n- second line").unwrap();
+        assert_eq!(repo.head().unwrap(), Some(sha));
+        let body = repo.run(&["log", "-1", "--format=%B"]).unwrap();
+        assert_eq!(body.trim(), "fix: update a\n\n- second line");
+        assert!(commit(&repo, "  ").is_err());
+    }
+
+    #[test]
+    fn test_commit_all_includes_new_files() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+        assert_eq!(commit_all(&repo, "nothing").unwrap(), None);
+
+        fs::write(repo.root().join("a.txt"), "two\n").unwrap();
+        fs::write(repo.root().join("b.txt"), "new\n").unwrap();
+        let sha = commit_all(&repo, "update").unwrap().unwrap();
+        assert_eq!(repo.head().unwrap(), Some(sha));
+        let files = repo
+            .run(&["show", "--name-only", "--format=", "HEAD"])
+            .unwrap();
+        assert_eq!(files.lines().collect::<Vec<_>>(), ["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_prompt_mentions_truncation() {
+        let changes = StagedChanges {
+            diff: file_diff("a.rs", 500),
+            stat: " a.rs | 500 +\n".to_string(),
+        };
+        let (prompt, truncated) = message_prompt(&changes, 256);
+        assert!(truncated);
+        assert!(prompt.contains("shortened"));
+        assert!(prompt.contains("a.rs | 500 +"));
+
+        let (_, truncated) = message_prompt(&changes, DEFAULT_DIFF_BUDGET);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_parse_message() {
+        let message = CommitMessage::parse(
+            "```\nfeat(git): add stash panel.\n\n- List stashes\n* Apply and drop them\nKeeps the index\n```",
+        )
+        .unwrap();
+        assert_eq!(message.subject, "feat(git): add stash panel");
+        assert_eq!(message.body, ["List stashes", "Apply and drop them", "Keeps the index"]);
+        assert!(message.is_conventional());
+        assert_eq!(
+            message.to_string(),
+            "feat(git): add stash panel\n\n- List stashes\n- Apply and drop them\n- Keeps the index"
+        );
+
+        assert!(CommitMessage::parse("\n\n").is_err());
+        let plain = CommitMessage::parse("Update things").unwrap();
+        assert!(!plain.is_conventional());
+        assert_eq!(plain.to_string(), "Update things");
+    }
+}
@@ -0,0 +1,537 @@
+//! Conflict Resolution
+//!
+//! Parses files containing conflict markers into hunks with the `ours`,
+//! `base` and `theirs` sides, and writes them back once every hunk has a
+//! resolution. Works on any conflicted file, whether it comes from an
+//! operation in [`crate::history`], a plain `git merge` run outside the
+//! editor, or an agent merging branches in its own worktree.
+//!
+//! The base side is only present when the markers were written in `diff3`
+//! style. Operations run through this crate always use it; for other
+//! conflicts [`restore_base`] rewrites the markers from the index.
+//!
+//! [`merge_conflicts`] reads the base, ours and theirs versions of every
+//! conflicted file from the index for a three-way view, and
+//! [`resolve_file`] settles a whole file at once.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+use tracing::info;
+
+/// One conflicting region of a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConflictHunk {
+    /// Label after `<<<<<<<`, usually the branch or commit of our side
+    pub ours_label: String,
+    pub ours: String,
+    /// Common ancestor, if the markers include it
+    pub base: Option<String>,
+    /// Label after `>>>>>>>`
+    pub theirs_label: String,
+    pub theirs: String,
+}
+
+/// Part of a conflicted file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Segment {
+    /// Text both sides agree on
+    Text {
+        text: String,
+    },
+    Conflict(ConflictHunk),
+}
+
+/// How a hunk is resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// Our side followed by theirs
+    Both,
+    /// Text edited by hand
+    Custom {
+        text: String,
+    },
+}
+
+/// A file split into agreed text and conflict hunks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConflictFile {
+    /// Path relative to the repository root
+    pub path: String,
+    pub segments: Vec<Segment>,
+}
+
+/// Versions of a conflicted file, for a three-way view
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MergeConflict {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Common ancestor, or `None` if the file did not exist there
+    pub base: Option<String>,
+    /// Our version, or `None` if our side deleted the file
+    pub ours: Option<String>,
+    /// Their version, or `None` if their side deleted the file
+    pub theirs: Option<String>,
+    /// The working tree file split at its markers; empty if it was deleted
+    pub working: ConflictFile,
+}
+
+/// How a whole conflicted file is resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum FileResolution {
+    /// Our version, deleting the file if our side deleted it
+    Ours,
+    /// Their version, deleting the file if their side deleted it
+    Theirs,
+    /// Content written by hand
+    Custom {
+        content: String,
+    },
+}
+
+enum Section {
+    Text,
+    Ours,
+    Base,
+    Theirs,
+}
+
+impl ConflictFile {
+    /// Split `content` at its conflict markers
+    pub fn parse(path: &str, content: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut hunk = ConflictHunk {
+            ours_label: String::new(),
+            ours: String::new(),
+            base: None,
+            theirs_label: String::new(),
+            theirs: String::new(),
+        };
+        let mut section = Section::Text;
+
+        for line in content.split_inclusive('\n') {
+            let marker = line.trim_end_matches(['\n', '\r']);
+            match section {
+                Section::Text => {
+                    if let Some(label) = marker_label(marker, "<<<<<<<") {
+                        if !text.is_empty() {
+                            segments.push(Segment::Text {
+                                text: std::mem::take(&mut text),
+                            });
+                        }
+                        hunk.ours_label = label;
+                        section = Section::Ours;
+                    } else {
+                        text.push_str(line);
+                    }
+                }
+                Section::Ours | Section::Base if marker == "=======" => section = Section::Theirs,
+                Section::Ours => {
+                    if marker_label(marker, "|||||||").is_some() {
+                        hunk.base = Some(String::new());
+                        section = Section::Base;
+                    } else {
+                        hunk.ours.push_str(line);
+                    }
+                }
+                Section::Base => hunk.base.get_or_insert_with(String::new).push_str(line),
+                Section::Theirs => {
+                    if let Some(label) = marker_label(marker, ">>>>>>>") {
+                        hunk.theirs_label = label;
+                        segments.push(Segment::Conflict(ConflictHunk {
+                            ours_label: std::mem::take(&mut hunk.ours_label),
+                            ours: std::mem::take(&mut hunk.ours),
+                            base: hunk.base.take(),
+                            theirs_label: std::mem::take(&mut hunk.theirs_label),
+                            theirs: std::mem::take(&mut hunk.theirs),
+                        }));
+                        section = Section::Text;
+                    } else {
+                        hunk.theirs.push_str(line);
+                    }
+                }
+            }
+        }
+
+        if !matches!(section, Section::Text) {
+            return Err(GitError::Parse(format!("unterminated conflict marker in {}", path)));
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text { text });
+        }
+        Ok(Self {
+            path: path.to_string(),
+            segments,
+        })
+    }
+
+    /// The conflict hunks, in file order
+    pub fn hunks(&self) -> impl Iterator<Item = &ConflictHunk> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Conflict(hunk) => Some(hunk),
+            Segment::Text { .. } => None,
+        })
+    }
+
+    /// Whether the file still has conflict markers
+    pub fn has_conflicts(&self) -> bool {
+        self.hunks().next().is_some()
+    }
+
+    /// Contents of the file with hunk `i` resolved by `resolutions[i]`
+    pub fn resolve(&self, resolutions: &[Resolution]) -> Result<String> {
+        let count = self.hunks().count();
+        if resolutions.len() != count {
+            return Err(GitError::InvalidOperation(format!(
+                "{} has {} conflicts but {} resolutions were given",
+                self.path,
+                count,
+                resolutions.len()
+            )));
+        }
+
+        let mut resolutions = resolutions.iter();
+        let mut content = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text { text } => content.push_str(text),
+                Segment::Conflict(hunk) => match resolutions.next() {
+                    Some(Resolution::Ours) => content.push_str(&hunk.ours),
+                    Some(Resolution::Theirs) => content.push_str(&hunk.theirs),
+                    Some(Resolution::Both) => {
+                        content.push_str(&hunk.ours);
+                        content.push_str(&hunk.theirs);
+                    }
+                    Some(Resolution::Custom { text }) => content.push_str(text),
+                    None => unreachable!("resolution count checked above"),
+                },
+            }
+        }
+        Ok(content)
+    }
+}
+
+/// Label after `marker`, if `line` is that marker
+fn marker_label(line: &str, marker: &str) -> Option<String> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() || rest.starts_with(' ') {
+        Some(rest.trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Reject paths that leave the repository
+fn check_path(path: &str) -> Result<()> {
+    let inside = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if path.is_empty() || !inside {
+        return Err(GitError::InvalidOperation(format!("{} is not inside the repository", path)));
+    }
+    Ok(())
+}
+
+/// Parse the conflicted file at `path` in the working tree
+pub fn load(repo: &Repository, path: &str) -> Result<ConflictFile> {
+    check_path(path)?;
+    let content = std::fs::read_to_string(repo.root().join(path))?;
+    ConflictFile::parse(path, &content)
+}
+
+/// Rewrite the markers of `path` from the index with the base included
+///
+/// Hand edits to the file are lost, so this is only offered while the file
+/// has conflicts without a base.
+pub fn restore_base(repo: &Repository, path: &str) -> Result<ConflictFile> {
+    check_path(path)?;
+    repo.run(&["checkout", "--conflict=diff3", "--", path])?;
+    load(repo, path)
+}
+
+/// Write the resolved `content` of `path` and stage it
+pub fn mark_resolved(repo: &Repository, path: &str, content: &str) -> Result<()> {
+    check_path(path)?;
+    if ConflictFile::parse(path, content)?.has_conflicts() {
+        return Err(GitError::UnresolvedConflicts(vec![path.to_string()]));
+    }
+    std::fs::write(repo.root().join(path), content)?;
+    repo.run(&["add", "--", path])?;
+    info!("Resolved conflicts in {}", path);
+    Ok(())
+}
+
+/// Unmerged index entries as `(path, stage, object)`, in path order
+fn unmerged_entries(repo: &Repository) -> Result<Vec<(String, u8, String)>> {
+    let listing = repo.run(&["ls-files", "--unmerged", "-z"])?;
+    listing
+        .split('\0')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            // <mode> <object> <stage>\t<path>
+            let parsed = entry.split_once('\t').and_then(|(info, path)| {
+                let mut fields = info.split(' ').skip(1);
+                let object = fields.next()?;
+                let stage = fields.next()?.parse().ok()?;
+                Some((path.to_string(), stage, object.to_string()))
+            });
+            parsed.ok_or_else(|| GitError::Parse(entry.to_string()))
+        })
+        .collect()
+}
+
+/// Conflicted files with their base, ours and theirs versions from the index
+pub fn merge_conflicts(repo: &Repository) -> Result<Vec<MergeConflict>> {
+    let mut conflicts: Vec<MergeConflict> = Vec::new();
+    for (path, stage, object) in unmerged_entries(repo)? {
+        let known = matches!(conflicts.last(), Some(conflict) if conflict.path == path);
+        if !known {
+            conflicts.push(MergeConflict {
+                working: ConflictFile {
+                    path: path.clone(),
+                    segments: Vec::new(),
+                },
+                path,
+                base: None,
+                ours: None,
+                theirs: None,
+            });
+        }
+        let conflict = conflicts.last_mut().expect("pushed above");
+        let content = Some(repo.run(&["cat-file", "blob", &object])?);
+        match stage {
+            1 => conflict.base = content,
+            2 => conflict.ours = content,
+            3 => conflict.theirs = content,
+            _ => return Err(GitError::Parse(format!("stage {} of {}", stage, conflict.path))),
+        }
+    }
+
+    for conflict in &mut conflicts {
+        if repo.root().join(&conflict.path).exists() {
+            conflict.working = load(repo, &conflict.path)?;
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Resolve every conflict in `path` at once and stage the result
+pub fn resolve_file(repo: &Repository, path: &str, resolution: &FileResolution) -> Result<()> {
+    check_path(path)?;
+    let stages: Vec<u8> = unmerged_entries(repo)?
+        .into_iter()
+        .filter(|(entry, _, _)| entry == path)
+        .map(|(_, stage, _)| stage)
+        .collect();
+    if stages.is_empty() {
+        return Err(GitError::NotFound(format!("conflict in {}", path)));
+    }
+
+    let (side, stage) = match resolution {
+        FileResolution::Custom { content } => return mark_resolved(repo, path, content),
+        FileResolution::Ours => ("ours", 2),
+        FileResolution::Theirs => ("theirs", 3),
+    };
+    if stages.contains(&stage) {
+        // Checked out from the index, so binary files survive
+        repo.run(&["checkout", &format!("--{}", side), "--", path])?;
+        repo.run(&["add", "--", path])?;
+    } else {
+        repo.run(&["rm", "--quiet", "--force", "--", path])?;
+    }
+    info!("Resolved conflicts in {} with the {} version", path, side);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{self, OperationStatus};
+    use crate::repo::test_support::*;
+    use std::fs;
+
+    const DIFF3: &str = "\
+top
+<<<<<<< HEAD
+main
+||||||| base
+base
+=======
+feature
+>>>>>>> feature
+middle
+<<<<<<< HEAD
+a
+=======
+b
+>>>>>>> feature
+";
+
+    #[test]
+    fn test_parse_diff3_and_merge_styles() {
+        let file = ConflictFile::parse("a.txt", DIFF3).unwrap();
+        let hunks: Vec<_> = file.hunks().collect();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].ours_label, "HEAD");
+        assert_eq!(hunks[0].ours, "main\n");
+        assert_eq!(hunks[0].base.as_deref(), Some("base\n"));
+        assert_eq!(hunks[0].theirs, "feature\n");
+        assert_eq!(hunks[0].theirs_label, "feature");
+        assert_eq!(hunks[1].base, None);
+        assert_eq!(
+            file.segments[0],
+            Segment::Text {
+                text: "top\n".to_string()
+            }
+        );
+
+        assert!(!ConflictFile::parse("a.txt", "plain\n")
+            .unwrap()
+            .has_conflicts());
+        assert!(matches!(
+            ConflictFile::parse("a.txt", "<<<<<<< HEAD\nmain\n"),
+            Err(GitError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let file = ConflictFile::parse("a.txt", DIFF3).unwrap();
+        assert_eq!(
+            file.resolve(&[Resolution::Both, Resolution::Theirs])
+                .unwrap(),
+            "top\nmain\nfeature\nmiddle\nb\n"
+        );
+        assert_eq!(
+            file.resolve(&[
+                Resolution::Ours,
+                Resolution::Custom {
+                    text: "merged\n".to_string()
+                }
+            ])
+            .unwrap(),
+            "top\nmain\nmiddle\nmerged\n"
+        );
+        assert!(matches!(file.resolve(&[Resolution::Ours]), Err(GitError::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn test_resolve_cherry_pick_conflict() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "base\n", "base");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        let picked = commit_file(&repo, "a.txt", "feature\n", "feature");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        commit_file(&repo, "a.txt", "main\n", "main");
+        assert!(history::cherry_pick(&repo, &picked).unwrap().is_paused());
+
+        let file = load(&repo, "a.txt").unwrap();
+        let hunk = file.hunks().next().unwrap();
+        assert_eq!(hunk.base.as_deref(), Some("base\n"));
+
+        assert!(matches!(
+            mark_resolved(&repo, "a.txt", &fs::read_to_string(repo.root().join("a.txt")).unwrap()),
+            Err(GitError::UnresolvedConflicts(_))
+        ));
+        let content = file.resolve(&[Resolution::Both]).unwrap();
+        mark_resolved(&repo, "a.txt", &content).unwrap();
+        assert!(history::conflicted_files(&repo).unwrap().is_empty());
+        assert_eq!(history::continue_operation(&repo).unwrap(), OperationStatus::Completed);
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "main\nfeature\n");
+    }
+
+    #[test]
+    fn test_restore_base_after_plain_merge() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "base\n", "base");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        commit_file(&repo, "a.txt", "feature\n", "feature");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        commit_file(&repo, "a.txt", "main\n", "main");
+        repo.run(&["config", "merge.conflictStyle", "merge"])
+            .unwrap();
+        assert!(repo.run(&["merge", "feature"]).is_err());
+
+        assert_eq!(load(&repo, "a.txt").unwrap().hunks().next().unwrap().base, None);
+        let file = restore_base(&repo, "a.txt").unwrap();
+        assert_eq!(file.hunks().next().unwrap().base.as_deref(), Some("base\n"));
+        assert!(matches!(load(&repo, "../a.txt"), Err(GitError::InvalidOperation(_))));
+    }
+
+    /// Repository paused in a merge of `feature`, which changed `a.txt` and
+    /// deleted `b.txt` while `main` changed both
+    fn paused_merge() -> (tempfile::TempDir, Repository) {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "base\n", "base a");
+        commit_file(&repo, "b.txt", "base\n", "base b");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        commit_file(&repo, "a.txt", "feature\n", "feature");
+        repo.run(&["rm", "-q", "b.txt"]).unwrap();
+        repo.run(&["commit", "-q", "-m", "remove b"]).unwrap();
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        commit_file(&repo, "a.txt", "main\n", "main a");
+        commit_file(&repo, "b.txt", "main\n", "main b");
+        let status = history::merge(&repo, "feature", "merge").unwrap();
+        assert!(status.is_paused());
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_merge_conflicts_have_all_versions() {
+        let (_dir, repo) = paused_merge();
+        let conflicts = merge_conflicts(&repo).unwrap();
+        assert_eq!(conflicts.len(), 2);
+
+        let a = &conflicts[0];
+        assert_eq!(a.path, "a.txt");
+        assert_eq!(a.base.as_deref(), Some("base\n"));
+        assert_eq!(a.ours.as_deref(), Some("main\n"));
+        assert_eq!(a.theirs.as_deref(), Some("feature\n"));
+        let hunk = a.working.hunks().next().unwrap();
+        assert_eq!((hunk.ours.as_str(), hunk.theirs.as_str()), ("main\n", "feature\n"));
+
+        // Deleted on their side
+        let b = &conflicts[1];
+        assert_eq!(b.path, "b.txt");
+        assert_eq!(b.ours.as_deref(), Some("main\n"));
+        assert_eq!(b.theirs, None);
+        assert!(!b.working.has_conflicts());
+    }
+
+    #[test]
+    fn test_resolve_whole_files() {
+        let (_dir, repo) = paused_merge();
+        assert!(matches!(
+            resolve_file(&repo, "c.txt", &FileResolution::Ours),
+            Err(GitError::NotFound(_))
+        ));
+
+        resolve_file(&repo, "a.txt", &FileResolution::Theirs).unwrap();
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "feature\n");
+        resolve_file(&repo, "b.txt", &FileResolution::Theirs).unwrap();
+        assert!(!repo.root().join("b.txt").exists());
+        assert!(history::conflicted_files(&repo).unwrap().is_empty());
+        assert!(merge_conflicts(&repo).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_file_with_custom_content() {
+        let (_dir, repo) = paused_merge();
+        let custom = FileResolution::Custom {
+            content: "main\nfeature\n".to_string(),
+        };
+        resolve_file(&repo, "a.txt", &custom).unwrap();
+        resolve_file(&repo, "b.txt", &FileResolution::Ours).unwrap();
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "main\nfeature\n");
+        assert_eq!(fs::read_to_string(repo.root().join("b.txt")).unwrap(), "main\n");
+        assert_eq!(history::continue_operation(&repo).unwrap(), OperationStatus::Completed);
+    }
+}
@@ -0,0 +1,502 @@
+//! Diffs
+//!
+//! Produces patches for a range of history and cuts them down to a size
+//! budget before they are handed to an LLM.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Which changes to diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum DiffRange {
+    /// Changes between two commits
+    Commits { from: String, to: String },
+    /// Changes on the current branch since it forked from `base`
+    Branch { base: String },
+    /// Changes in the index
+    Staged,
+}
+
+impl DiffRange {
+    /// Human-readable description, e.g. `main...HEAD`
+    pub fn label(&self) -> String {
+        match self {
+            DiffRange::Commits { from, to } => format!("{}..{}", from, to),
+            DiffRange::Branch { base } => format!("{}...HEAD", base),
+            DiffRange::Staged => "staged changes".to_string(),
+        }
+    }
+
+    fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            "diff".to_string(),
+            "--no-color".to_string(),
+            "--no-ext-diff".to_string(),
+        ];
+        match self {
+            DiffRange::Commits { from, to } => args.extend([from.clone(), to.clone()]),
+            DiffRange::Branch { base } => args.push(format!("{}...HEAD", base)),
+            DiffRange::Staged => args.push("--cached".to_string()),
+        }
+        args
+    }
+}
+
+/// Branch the current branch was most likely started from
+///
+/// The remote's default branch when git knows it, otherwise a local `main`
+/// or `master`.
+pub fn default_base(repo: &Repository) -> Result<String> {
+    if let Ok(remote) = repo.run(&[
+        "symbolic-ref",
+        "--quiet",
+        "--short",
+        "refs/remotes/origin/HEAD",
+    ]) {
+        if !remote.trim().is_empty() {
+            return Ok(remote.trim().to_string());
+        }
+    }
+    for name in ["main", "master"] {
+        if repo.rev_parse(&format!("refs/heads/{}", name))?.is_some() {
+            return Ok(name.to_string());
+        }
+    }
+    Err(GitError::NotFound("base branch".to_string()))
+}
+
+/// Patch for `range`
+pub fn range_diff(repo: &Repository, range: &DiffRange) -> Result<String> {
+    let mut args = range.args();
+    args.push("--".to_string());
+    repo.run(&args)
+}
+
+/// Paths changed in `range`, relative to the repository root
+pub fn changed_files(repo: &Repository, range: &DiffRange) -> Result<Vec<String>> {
+    let mut args = range.args();
+    args.extend(["--name-only".to_string(), "--".to_string()]);
+    Ok(repo.run(&args)?.lines().map(str::to_string).collect())
+}
+
+/// Content of `path` at `rev`, or `None` if the file did not exist there
+///
+/// `path` is relative to the repository root, or absolute inside it.
+pub fn file_at(repo: &Repository, rev: &str, path: &Path) -> Result<Option<String>> {
+    let relative = repo.relative_path(path)?;
+    let spec = format!("{}:{}", rev, relative.to_string_lossy().replace('\\', "/"));
+    if !repo.succeeds(&["cat-file", "-e", &spec])? {
+        return Ok(None);
+    }
+    repo.run(&["cat-file", "blob", &spec]).map(Some)
+}
+
+/// Generated files whose diffs are summarized rather than sent
+const SUMMARIZED_FILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "go.sum",
+];
+
+/// How much of a worktree's work has been carried over to another checkout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Adoption {
+    /// Files the worktree changed since it forked, committed or not
+    pub changed: usize,
+    /// Changed files whose content in the target matches the worktree
+    pub adopted: usize,
+}
+
+/// Compare the work in `worktree` with the files in `target`
+///
+/// Content is compared rather than history, so squash merges, cherry-picks
+/// and copied files all count as adopted.
+pub fn adoption(worktree: &Repository, target: &Repository) -> Result<Adoption> {
+    let base = fork_point(worktree, target)?;
+
+    let mut files: Vec<String> = worktree
+        .run(&["diff", "--name-only", "--no-renames", base.as_str(), "--"])?
+        .lines()
+        .chain(
+            worktree
+                .run(&["ls-files", "--others", "--exclude-standard"])?
+                .lines(),
+        )
+        .map(str::to_string)
+        .collect();
+    files.sort();
+    files.dedup();
+
+    // A file deleted in both reads as None on both sides
+    let adopted = files
+        .iter()
+        .filter(|file| {
+            std::fs::read(worktree.root().join(file)).ok()
+                == std::fs::read(target.root().join(file)).ok()
+        })
+        .count();
+
+    Ok(Adoption {
+        changed: files.len(),
+        adopted,
+    })
+}
+
+/// Size of a worktree's work
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStat {
+    pub files: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStat {
+    /// Lines added or removed
+    pub fn lines(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+/// Size of the work in `worktree` since it forked from `target`, committed
+/// or not, new files included
+///
+/// Binary files count as changed without adding lines.
+pub fn worktree_stat(worktree: &Repository, target: &Repository) -> Result<DiffStat> {
+    let numstat = diff_work(worktree, target, &["--numstat", "--no-renames"])?;
+
+    let mut stat = DiffStat::default();
+    for line in numstat.lines() {
+        let mut fields = line.split('\t');
+        let (Some(added), Some(deleted)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        stat.files += 1;
+        stat.insertions += added.parse::<usize>().unwrap_or(0);
+        stat.deletions += deleted.parse::<usize>().unwrap_or(0);
+    }
+    Ok(stat)
+}
+
+/// Paths the work in `worktree` changed since it forked from `target`,
+/// committed or not, new files included
+pub fn worktree_files(worktree: &Repository, target: &Repository) -> Result<Vec<String>> {
+    let names = diff_work(worktree, target, &["--name-only", "--no-renames"])?;
+    Ok(names.lines().map(str::to_string).collect())
+}
+
+/// Modules `files` belong to: the directory of each, or the file itself at
+/// the repository root
+///
+/// Sorted, each listed once.
+pub fn touched_modules(files: &[String]) -> Vec<String> {
+    let mut modules: Vec<String> = files
+        .iter()
+        .map(|file| match file.rsplit_once('/') {
+            Some((dir, _)) => dir.to_string(),
+            None => file.clone(),
+        })
+        .collect();
+    modules.sort();
+    modules.dedup();
+    modules
+}
+
+/// Patch of the work in `worktree` since it forked from `target`, committed
+/// or not, new files included
+pub fn worktree_diff(worktree: &Repository, target: &Repository) -> Result<String> {
+    diff_work(worktree, target, &["--no-color", "--no-ext-diff"])
+}
+
+/// Run `git diff` with `options` over the work in `worktree` since it forked
+///
+/// Every file of the working tree is staged in a throwaway index first, so
+/// untracked and uncommitted work is included without touching the
+/// worktree's own index.
+fn diff_work(worktree: &Repository, target: &Repository, options: &[&str]) -> Result<String> {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let base = fork_point(worktree, target)?;
+    let mut args = vec!["diff", "--cached"];
+    args.extend_from_slice(options);
+    args.extend([base.as_str(), "--"]);
+
+    let index = worktree.git_dir().join(format!(
+        "ait42-index-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let env = [("GIT_INDEX_FILE", index.as_os_str())];
+    let result = worktree
+        .run_with_env(&["add", "--all"], &env)
+        .and_then(|_| worktree.run_with_env(&args, &env));
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+/// Commit `worktree` forked from, relative to `target`'s HEAD
+fn fork_point(worktree: &Repository, target: &Repository) -> Result<String> {
+    let target_head = target
+        .head()?
+        .ok_or_else(|| GitError::NotFound("HEAD".to_string()))?;
+    Ok(worktree
+        .run(&["merge-base", "HEAD", target_head.as_str()])?
+        .trim()
+        .to_string())
+}
+
+/// A diff cut down to a byte budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetedDiff {
+    pub text: String,
+    /// Whether any file's patch was shortened
+    pub truncated: bool,
+}
+
+/// Shorten `diff` to roughly `max_bytes`
+///
+/// Every file keeps its header. Small files are kept whole and the rest of
+/// the budget is shared evenly among the larger ones, so one huge file
+/// cannot crowd out the others. Lock files are always reduced to a header.
+pub fn budget_diff(diff: &str, max_bytes: usize) -> BudgetedDiff {
+    let mut truncated = false;
+    let sections: Vec<String> = split_files(diff)
+        .into_iter()
+        .map(|section| {
+            if is_summarized(section) {
+                let header = section.lines().next().unwrap_or_default();
+                format!("{}\n[generated file changes omitted]\n", header)
+            } else {
+                section.to_string()
+            }
+        })
+        .collect();
+
+    // Hand out the budget smallest-first; what small files leave unused
+    // raises the share of the larger ones
+    let mut order: Vec<usize> = (0..sections.len()).collect();
+    order.sort_by_key(|&i| sections[i].len());
+    let mut allowed = vec![0; sections.len()];
+    let mut remaining = max_bytes;
+    for (position, &i) in order.iter().enumerate() {
+        let share = remaining / (order.len() - position);
+        allowed[i] = sections[i].len().min(share);
+        remaining -= allowed[i];
+    }
+
+    let mut text = String::new();
+    for (section, allowed) in sections.iter().zip(allowed) {
+        if section.len() <= allowed {
+            text.push_str(section);
+            continue;
+        }
+        truncated = true;
+        let mut kept = 0;
+        let mut lines = section.lines();
+        for line in lines.by_ref() {
+            // Headers survive even when the budget is smaller than they are
+            if kept > 0 && kept + line.len() + 1 > allowed {
+                text.push_str(&format!("[... {} more lines]\n", lines.count() + 1));
+                break;
+            }
+            text.push_str(line);
+            text.push('\n');
+            kept += line.len() + 1;
+        }
+    }
+
+    BudgetedDiff { text, truncated }
+}
+
+fn split_files(diff: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = diff
+        .match_indices("diff --git ")
+        .map(|(i, _)| i)
+        .filter(|&i| i == 0 || diff.as_bytes()[i - 1] == b'\n')
+        .collect();
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).chain(std::iter::once(&diff.len())))
+        .map(|(&start, &end)| &diff[start..end])
+        .filter(|section| !section.is_empty())
+        .collect()
+}
+
+fn is_summarized(section: &str) -> bool {
+    let header = section.lines().next().unwrap_or_default();
+    SUMMARIZED_FILES
+        .iter()
+        .any(|name| header.ends_with(&format!("/{}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+
+    #[test]
+    fn test_file_at() {
+        let (_dir, repo) = init_repo();
+        std::fs::create_dir(repo.root().join("dir")).unwrap();
+        commit_file(&repo, "dir/a.txt", "one\n", "add a");
+        std::fs::write(repo.root().join("dir/a.txt"), "two\n").unwrap();
+
+        let head = file_at(&repo, "HEAD", Path::new("dir/a.txt")).unwrap();
+        assert_eq!(head.as_deref(), Some("one\n"));
+        let absolute = file_at(&repo, "HEAD", &repo.root().join("dir/a.txt")).unwrap();
+        assert_eq!(absolute, head);
+        assert_eq!(file_at(&repo, "HEAD", Path::new("b.txt")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_range_diff() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "a.txt", "a\n", "initial");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        commit_file(&repo, "b.txt", "b\n", "add b");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        let main = commit_file(&repo, "c.txt", "c\n", "add c");
+        repo.run(&["checkout", "-q", "feature"]).unwrap();
+
+        // Work on main after the fork point is not part of the branch
+        assert_eq!(default_base(&repo).unwrap(), "main");
+        let branch = DiffRange::Branch {
+            base: "main".to_string(),
+        };
+        assert_eq!(branch.label(), "main...HEAD");
+        assert_eq!(changed_files(&repo, &branch).unwrap(), ["b.txt"]);
+        assert!(range_diff(&repo, &branch).unwrap().contains("+b"));
+
+        let commits = DiffRange::Commits {
+            from: base,
+            to: main,
+        };
+        assert_eq!(changed_files(&repo, &commits).unwrap(), ["c.txt"]);
+
+        std::fs::write(repo.root().join("d.txt"), "d\n").unwrap();
+        repo.run(&["add", "d.txt"]).unwrap();
+        assert_eq!(changed_files(&repo, &DiffRange::Staged).unwrap(), ["d.txt"]);
+    }
+
+    #[test]
+    fn test_adoption() {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "a\n", "initial");
+        commit_file(&repo, "b.txt", "b\n", "add b");
+
+        let path = dir.path().join("wt");
+        let path_arg = path.to_str().unwrap();
+        repo.run(&["worktree", "add", "-q", "-b", "agent", path_arg])
+            .unwrap();
+        let worktree = Repository::open(&path).unwrap();
+        assert_eq!(
+            adoption(&worktree, &repo).unwrap(),
+            Adoption {
+                changed: 0,
+                adopted: 0
+            }
+        );
+
+        // One committed change, one uncommitted, one new file
+        commit_file(&worktree, "a.txt", "agent a\n", "change a");
+        std::fs::write(path.join("b.txt"), "agent b\n").unwrap();
+        std::fs::write(path.join("c.txt"), "c\n").unwrap();
+        assert_eq!(adoption(&worktree, &repo).unwrap().adopted, 0);
+
+        std::fs::write(repo.root().join("a.txt"), "agent a\n").unwrap();
+        std::fs::write(repo.root().join("c.txt"), "c\n").unwrap();
+        assert_eq!(
+            adoption(&worktree, &repo).unwrap(),
+            Adoption {
+                changed: 3,
+                adopted: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_worktree_stat() {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "a\nb\n", "initial");
+
+        let path = dir.path().join("wt");
+        let path_arg = path.to_str().unwrap();
+        repo.run(&["worktree", "add", "-q", "-b", "agent", path_arg])
+            .unwrap();
+        let worktree = Repository::open(&path).unwrap();
+        assert_eq!(worktree_stat(&worktree, &repo).unwrap(), DiffStat::default());
+
+        // One committed change, one new file; later work on main is ignored
+        commit_file(&worktree, "a.txt", "a\nc\nd\n", "change a");
+        std::fs::write(path.join("b.txt"), "1\n2\n3\n").unwrap();
+        commit_file(&repo, "z.txt", "z\n", "main moves on");
+        let stat = worktree_stat(&worktree, &repo).unwrap();
+        assert_eq!(
+            stat,
+            DiffStat {
+                files: 2,
+                insertions: 5,
+                deletions: 1
+            }
+        );
+        assert_eq!(stat.lines(), 6);
+        assert_eq!(worktree_files(&worktree, &repo).unwrap(), ["a.txt", "b.txt"]);
+
+        let patch = worktree_diff(&worktree, &repo).unwrap();
+        assert!(patch.contains("+c\n+d\n"));
+        assert!(patch.contains("+++ b/b.txt"));
+        assert!(!patch.contains("z.txt"));
+        // The worktree's own index is left alone
+        let status = worktree.run(&["status", "--porcelain"]).unwrap();
+        assert_eq!(status, "?? b.txt\n");
+    }
+
+    #[test]
+    fn test_touched_modules() {
+        let files = [
+            "src/commands/git.rs",
+            "src/main.rs",
+            "src/commands/file.rs",
+            "Cargo.toml",
+        ]
+        .map(str::to_string);
+        assert_eq!(touched_modules(&files), ["Cargo.toml", "src", "src/commands"]);
+        assert!(touched_modules(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_budget_keeps_small_files_whole() {
+        let small = file_diff("small.rs", 3);
+        let large = file_diff("large.rs", 2000);
+        let diff = format!("{}{}", small, large);
+
+        let full = budget_diff(&diff, diff.len());
+        assert!(!full.truncated);
+        assert_eq!(full.text, diff);
+
+        let budgeted = budget_diff(&diff, 1024);
+        assert!(budgeted.truncated);
+        assert!(budgeted.text.starts_with(&small));
+        assert!(budgeted.text.contains("diff --git a/large.rs"));
+        assert!(budgeted.text.contains("more lines]"));
+        assert!(budgeted.text.len() < 1200);
+    }
+
+    #[test]
+    fn test_lock_files_are_summarized() {
+        let diff = format!("{}{}", file_diff("src/main.rs", 2), file_diff("Cargo.lock", 50));
+        let budgeted = budget_diff(&diff, 4096);
+        assert!(budgeted.text.contains("+line 1"));
+        assert!(budgeted.text.contains("[generated file changes omitted]"));
+        assert!(!budgeted.text.contains("+line 40"));
+    }
+}
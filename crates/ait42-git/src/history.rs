@@ -0,0 +1,306 @@
+//! History Operations
+//!
+//! Checkout, merge, cherry-pick and revert, plus pausing and resuming operations
+//! that stop part-way. Cherry-picks, reverts, merges and rebases stop when
+//! they hit a conflict (and rebases also at `edit` steps); the repository is
+//! then left mid-operation until it is continued, skipped or aborted.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use tracing::info;
+
+/// An operation that can stop part-way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Operation {
+    Merge,
+    CherryPick,
+    Revert,
+    Rebase,
+}
+
+impl Operation {
+    /// The git subcommand driving the operation
+    fn command(self) -> &'static str {
+        match self {
+            Operation::Merge => "merge",
+            Operation::CherryPick => "cherry-pick",
+            Operation::Revert => "revert",
+            Operation::Rebase => "rebase",
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.command())
+    }
+}
+
+/// Outcome of a step that may stop part-way
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "state", rename_all = "kebab-case")]
+pub enum OperationStatus {
+    /// Nothing is in progress
+    Completed,
+    /// Waiting for the user
+    Paused {
+        operation: Operation,
+        /// Commit being applied when the operation stopped
+        current: Option<String>,
+        /// Files with unresolved conflicts; empty at a rebase `edit` stop
+        conflicts: Vec<String>,
+    },
+}
+
+impl OperationStatus {
+    /// Whether an operation is waiting to be continued
+    pub fn is_paused(&self) -> bool {
+        matches!(self, OperationStatus::Paused { .. })
+    }
+}
+
+/// Environment for steps that may stop part-way: commands that would open an
+/// editor accept the prepared message instead, and conflicts are written with
+/// the merge base (`diff3` style) so they can be resolved three-way
+const STEP_ENV: [(&str, &str); 4] = [
+    ("GIT_EDITOR", "true"),
+    ("GIT_CONFIG_COUNT", "1"),
+    ("GIT_CONFIG_KEY_0", "merge.conflictStyle"),
+    ("GIT_CONFIG_VALUE_0", "diff3"),
+];
+
+pub(crate) fn step_env() -> Vec<(&'static str, &'static OsStr)> {
+    STEP_ENV
+        .iter()
+        .map(|(key, value)| (*key, OsStr::new(*value)))
+        .collect()
+}
+
+/// Check out a branch or commit
+///
+/// Local changes are carried over when they do not conflict; otherwise git
+/// refuses and nothing changes.
+pub fn checkout(repo: &Repository, rev: &str) -> Result<()> {
+    repo.run(&["checkout", "--quiet", rev, "--"])?;
+    info!("Checked out {}", rev);
+    Ok(())
+}
+
+/// Merge `rev` into the current branch with a merge commit
+pub fn merge(repo: &Repository, rev: &str, message: &str) -> Result<OperationStatus> {
+    run_step(repo, &["merge", "--no-ff", "-m", message, rev])
+}
+
+/// Apply the changes of `sha` on top of HEAD as a new commit
+pub fn cherry_pick(repo: &Repository, sha: &str) -> Result<OperationStatus> {
+    run_step(repo, &["cherry-pick", sha])
+}
+
+/// Commit the inverse of `sha` on top of HEAD
+pub fn revert(repo: &Repository, sha: &str) -> Result<OperationStatus> {
+    run_step(repo, &["revert", "--no-edit", sha])
+}
+
+/// Operation the repository is in the middle of, if any
+pub fn in_progress(repo: &Repository) -> Option<Operation> {
+    let git_dir = repo.git_dir();
+    if git_dir.join("rebase-merge").is_dir() || git_dir.join("rebase-apply").is_dir() {
+        Some(Operation::Rebase)
+    } else if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+        Some(Operation::CherryPick)
+    } else if git_dir.join("REVERT_HEAD").is_file() {
+        Some(Operation::Revert)
+    } else if git_dir.join("MERGE_HEAD").is_file() {
+        Some(Operation::Merge)
+    } else {
+        None
+    }
+}
+
+/// Current operation state, with the files still in conflict
+pub fn status(repo: &Repository) -> Result<OperationStatus> {
+    let Some(operation) = in_progress(repo) else {
+        return Ok(OperationStatus::Completed);
+    };
+
+    Ok(OperationStatus::Paused {
+        operation,
+        current: current_commit(repo, operation),
+        conflicts: conflicted_files(repo)?,
+    })
+}
+
+/// Paths with unresolved merge conflicts
+pub fn conflicted_files(repo: &Repository) -> Result<Vec<String>> {
+    Ok(repo
+        .run(&["diff", "--name-only", "--diff-filter=U"])?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Resume the paused operation once conflicts are resolved and staged
+pub fn continue_operation(repo: &Repository) -> Result<OperationStatus> {
+    let operation = paused_operation(repo)?;
+    let conflicts = conflicted_files(repo)?;
+    if !conflicts.is_empty() {
+        return Err(GitError::UnresolvedConflicts(conflicts));
+    }
+    run_step(repo, &[operation.command(), "--continue"])
+}
+
+/// Drop the current commit of the paused operation and carry on
+pub fn skip(repo: &Repository) -> Result<OperationStatus> {
+    let operation = paused_operation(repo)?;
+    if operation == Operation::Merge {
+        return Err(GitError::InvalidOperation("a merge cannot be skipped".to_string()));
+    }
+    run_step(repo, &[operation.command(), "--skip"])
+}
+
+/// Cancel the paused operation and return to where it started
+pub fn abort(repo: &Repository) -> Result<()> {
+    let operation = paused_operation(repo)?;
+    repo.run(&[operation.command(), "--abort"])?;
+    info!("Aborted {}", operation);
+    Ok(())
+}
+
+fn paused_operation(repo: &Repository) -> Result<Operation> {
+    in_progress(repo).ok_or_else(|| GitError::NotFound("operation in progress".to_string()))
+}
+
+/// Run a step, turning a stop part-way into [`OperationStatus::Paused`]
+///
+/// Failures that leave no operation behind are real errors and are returned
+/// as such.
+pub(crate) fn run_step<S: AsRef<OsStr>>(repo: &Repository, args: &[S]) -> Result<OperationStatus> {
+    match repo.run_with_env(args, &step_env()) {
+        Ok(_) => status(repo),
+        Err(err @ GitError::CommandFailed { .. }) => match status(repo)? {
+            OperationStatus::Completed => Err(err),
+            paused => {
+                info!("Operation paused: {}", err);
+                Ok(paused)
+            }
+        },
+        Err(err) => Err(err),
+    }
+}
+
+fn current_commit(repo: &Repository, operation: Operation) -> Option<String> {
+    let read = |name: &str| {
+        std::fs::read_to_string(repo.git_dir().join(name))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    match operation {
+        Operation::Merge => read("MERGE_HEAD"),
+        Operation::CherryPick => read("CHERRY_PICK_HEAD"),
+        Operation::Revert => read("REVERT_HEAD"),
+        Operation::Rebase => read("rebase-merge/stopped-sha")
+            .or_else(|| read("rebase-apply/original-commit"))
+            .and_then(|sha| repo.rev_parse(&sha).ok().flatten()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+    use std::fs;
+
+    #[test]
+    fn test_checkout_and_revert() {
+        let (_dir, repo) = init_repo();
+        let first = commit_file(&repo, "a.txt", "one\n", "first");
+        let second = commit_file(&repo, "a.txt", "two\n", "second");
+
+        checkout(&repo, &first).unwrap();
+        assert_eq!(repo.head().unwrap(), Some(first));
+        checkout(&repo, "main").unwrap();
+
+        assert_eq!(revert(&repo, &second).unwrap(), OperationStatus::Completed);
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "one\n");
+        assert!(matches!(checkout(&repo, "nope"), Err(GitError::CommandFailed { .. })));
+    }
+
+    #[test]
+    fn test_cherry_pick_conflict_pause_and_continue() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "base\n", "base");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        let picked = commit_file(&repo, "a.txt", "feature\n", "feature");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        commit_file(&repo, "a.txt", "main\n", "main");
+
+        let status = cherry_pick(&repo, &picked).unwrap();
+        assert_eq!(
+            status,
+            OperationStatus::Paused {
+                operation: Operation::CherryPick,
+                current: Some(picked.clone()),
+                conflicts: vec!["a.txt".to_string()],
+            }
+        );
+        assert!(matches!(continue_operation(&repo), Err(GitError::UnresolvedConflicts(_))));
+
+        fs::write(repo.root().join("a.txt"), "resolved\n").unwrap();
+        repo.run(&["add", "a.txt"]).unwrap();
+        assert_eq!(continue_operation(&repo).unwrap(), OperationStatus::Completed);
+        assert!(in_progress(&repo).is_none());
+
+        let subject = repo.run(&["log", "-1", "--format=%s"]).unwrap();
+        assert_eq!(subject.trim(), "feature");
+    }
+
+    #[test]
+    fn test_merge_and_conflict() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "base\n", "base");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        commit_file(&repo, "b.txt", "feature\n", "feature");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+
+        assert_eq!(merge(&repo, "feature", "Merge feature").unwrap(), OperationStatus::Completed);
+        assert_eq!(fs::read_to_string(repo.root().join("b.txt")).unwrap(), "feature\n");
+        let subject = repo.run(&["log", "-1", "--format=%s"]).unwrap();
+        assert_eq!(subject.trim(), "Merge feature");
+
+        repo.run(&["checkout", "-q", "-b", "other", "HEAD~1"])
+            .unwrap();
+        let other = commit_file(&repo, "b.txt", "other\n", "other");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        let status = merge(&repo, "other", "Merge other").unwrap();
+        assert_eq!(
+            status,
+            OperationStatus::Paused {
+                operation: Operation::Merge,
+                current: Some(other),
+                conflicts: vec!["b.txt".to_string()],
+            }
+        );
+        abort(&repo).unwrap();
+    }
+
+    #[test]
+    fn test_abort_restores_head() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "base\n", "base");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        let picked = commit_file(&repo, "a.txt", "feature\n", "feature");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        let head = commit_file(&repo, "a.txt", "main\n", "main");
+
+        assert!(cherry_pick(&repo, &picked).unwrap().is_paused());
+        abort(&repo).unwrap();
+        assert_eq!(status(&repo).unwrap(), OperationStatus::Completed);
+        assert_eq!(repo.head().unwrap(), Some(head));
+        assert!(matches!(abort(&repo), Err(GitError::NotFound(_))));
+    }
+}
@@ -0,0 +1,285 @@
+//! Hunks
+//!
+//! Splits the diff of the working tree against the index, or of the index
+//! against HEAD, into hunks, and stages or unstages a single hunk by applying
+//! it alone to the index with `git apply --cached`. Hunks are addressed by
+//! file and position, so callers should list them again after each change.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// A run of changed lines in one file, with its context
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Hunk {
+    /// File, relative to the repository root
+    pub path: String,
+    /// Position among the hunks of the file
+    pub index: usize,
+    /// First line in the old version, 1-based
+    pub old_start: usize,
+    /// Lines in the old version
+    pub old_lines: usize,
+    /// First line in the new version, 1-based
+    pub new_start: usize,
+    /// Lines in the new version
+    pub new_lines: usize,
+    /// Text after the line ranges, usually the enclosing function
+    pub section: String,
+    /// Lines of the hunk, each starting with ` `, `+`, `-` or `\`
+    pub lines: Vec<String>,
+    /// `diff --git` and following lines up to the first hunk
+    #[serde(skip)]
+    file_header: String,
+}
+
+impl Hunk {
+    /// The `@@` line
+    pub fn header(&self) -> String {
+        let section = if self.section.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", self.section)
+        };
+        format!(
+            "@@ -{},{} +{},{} @@{}",
+            self.old_start, self.old_lines, self.new_start, self.new_lines, section
+        )
+    }
+
+    /// Lines added
+    pub fn added(&self) -> usize {
+        self.lines.iter().filter(|l| l.starts_with('+')).count()
+    }
+
+    /// Lines removed
+    pub fn removed(&self) -> usize {
+        self.lines.iter().filter(|l| l.starts_with('-')).count()
+    }
+
+    /// Patch of the file with only this hunk
+    pub fn patch(&self) -> String {
+        let mut patch = self.file_header.clone();
+        patch.push_str(&self.header());
+        patch.push('\n');
+        for line in &self.lines {
+            patch.push_str(line);
+            patch.push('\n');
+        }
+        patch
+    }
+}
+
+/// Hunks of the working tree against the index, or of the index against
+/// HEAD when `staged` is set, for `path` or for all files
+///
+/// Untracked and binary files have no hunks.
+pub fn hunks(repo: &Repository, staged: bool, path: Option<&Path>) -> Result<Vec<Hunk>> {
+    let relative = path.map(|p| repo.relative_path(p)).transpose()?;
+    let mut args = vec![
+        OsStr::new("-c"),
+        OsStr::new("core.quotePath=false"),
+        OsStr::new("diff"),
+        OsStr::new("--no-color"),
+        OsStr::new("--no-ext-diff"),
+        OsStr::new("--src-prefix=a/"),
+        OsStr::new("--dst-prefix=b/"),
+    ];
+    if staged {
+        args.push(OsStr::new("--cached"));
+    }
+    if let Some(relative) = &relative {
+        args.push(OsStr::new("--"));
+        args.push(relative.as_os_str());
+    }
+    parse(&repo.run(&args)?)
+}
+
+/// Stage hunk `index` of `path`, as listed by [`hunks`] with `staged` unset
+pub fn stage(repo: &Repository, path: &Path, index: usize) -> Result<()> {
+    let hunk = find(repo, false, path, index)?;
+    repo.run_with_input(&["apply", "--cached", "--whitespace=nowarn", "-"], &hunk.patch())?;
+    Ok(())
+}
+
+/// Unstage hunk `index` of `path`, as listed by [`hunks`] with `staged` set
+pub fn unstage(repo: &Repository, path: &Path, index: usize) -> Result<()> {
+    let hunk = find(repo, true, path, index)?;
+    repo.run_with_input(
+        &["apply", "--cached", "--reverse", "--whitespace=nowarn", "-"],
+        &hunk.patch(),
+    )?;
+    Ok(())
+}
+
+fn find(repo: &Repository, staged: bool, path: &Path, index: usize) -> Result<Hunk> {
+    hunks(repo, staged, Some(path))?
+        .into_iter()
+        .find(|hunk| hunk.index == index)
+        .ok_or_else(|| GitError::NotFound(format!("hunk {} of {}", index, path.display())))
+}
+
+fn parse(output: &str) -> Result<Vec<Hunk>> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut file_header = String::new();
+    let mut path = String::new();
+    let mut in_hunk = false;
+    let mut index = 0;
+
+    // Not `lines()`, which would drop the `\r` of CRLF files
+    for line in output.strip_suffix('\n').unwrap_or(output).split('\n') {
+        if line.starts_with("diff --git ") {
+            file_header.clear();
+            path.clear();
+            in_hunk = false;
+            index = 0;
+        }
+        if line.starts_with("@@ ") {
+            let mut hunk = parse_header(line)?;
+            hunk.path = path.clone();
+            hunk.index = index;
+            hunk.file_header = file_header.clone();
+            hunks.push(hunk);
+            in_hunk = true;
+            index += 1;
+            continue;
+        }
+
+        if in_hunk {
+            if let Some(hunk) = hunks.last_mut() {
+                hunk.lines.push(line.to_string());
+            }
+            continue;
+        }
+        file_header.push_str(line);
+        file_header.push('\n');
+        if let Some(new) = line.strip_prefix("+++ b/") {
+            path = new.to_string();
+        } else if let Some(old) = line.strip_prefix("--- a/") {
+            // Deleted files only have an old name
+            path = old.to_string();
+        }
+    }
+    Ok(hunks)
+}
+
+/// `@@ -<start>[,<count>] +<start>[,<count>] @@[ <section>]`
+fn parse_header(line: &str) -> Result<Hunk> {
+    let invalid = || GitError::Parse(line.to_string());
+    let (ranges, section) = line
+        .strip_prefix("@@ -")
+        .and_then(|rest| rest.split_once(" @@"))
+        .ok_or_else(invalid)?;
+    let (old, new) = ranges.split_once(" +").ok_or_else(invalid)?;
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old).ok_or_else(invalid)?;
+    let (new_start, new_lines) = range(new).ok_or_else(invalid)?;
+
+    Ok(Hunk {
+        path: String::new(),
+        index: 0,
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section: section.trim_start().to_string(),
+        lines: Vec::new(),
+        file_header: String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+
+    fn numbered(lines: usize, changed: &[usize]) -> String {
+        (1..=lines)
+            .map(|n| {
+                if changed.contains(&n) {
+                    format!("changed {}\n", n)
+                } else {
+                    format!("line {}\n", n)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stage_and_unstage_single_hunks() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", &numbered(25, &[]), "add a");
+        std::fs::write(repo.root().join("a.txt"), numbered(25, &[2, 18])).unwrap();
+
+        let unstaged = hunks(&repo, false, None).unwrap();
+        assert_eq!(unstaged.len(), 2);
+        assert_eq!(unstaged[1].path, "a.txt");
+        assert_eq!(unstaged[1].index, 1);
+        assert_eq!((unstaged[1].added(), unstaged[1].removed()), (1, 1));
+        assert!(unstaged[1].lines.contains(&"+changed 18".to_string()));
+        assert!(unstaged[1].header().starts_with("@@ -15,7 +15,7 @@"));
+
+        stage(&repo, Path::new("a.txt"), 1).unwrap();
+        let staged = hunks(&repo, true, None).unwrap();
+        assert_eq!(staged.len(), 1);
+        assert!(staged[0].lines.contains(&"+changed 18".to_string()));
+        let unstaged = hunks(&repo, false, Some(Path::new("a.txt"))).unwrap();
+        assert_eq!(unstaged.len(), 1);
+        assert!(unstaged[0].lines.contains(&"+changed 2".to_string()));
+
+        // The working tree keeps both changes either way
+        unstage(&repo, &repo.root().join("a.txt"), 0).unwrap();
+        assert!(hunks(&repo, true, None).unwrap().is_empty());
+        assert_eq!(hunks(&repo, false, None).unwrap().len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(repo.root().join("a.txt")).unwrap(),
+            numbered(25, &[2, 18])
+        );
+    }
+
+    #[test]
+    fn test_new_and_deleted_files() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "old.txt", "gone\n", "add old");
+        std::fs::remove_file(repo.root().join("old.txt")).unwrap();
+        std::fs::write(repo.root().join("new.txt"), "fresh").unwrap();
+        repo.run(&["add", "new.txt"]).unwrap();
+
+        // Deletions are named after the old file
+        let unstaged = hunks(&repo, false, None).unwrap();
+        assert_eq!(unstaged[0].path, "old.txt");
+        stage(&repo, Path::new("old.txt"), 0).unwrap();
+        assert!(repo.run(&["ls-files", "old.txt"]).unwrap().is_empty());
+
+        let staged = hunks(&repo, true, Some(Path::new("new.txt"))).unwrap();
+        assert_eq!(staged[0].lines, ["+fresh", "\\ No newline at end of file"]);
+        unstage(&repo, Path::new("new.txt"), 0).unwrap();
+        assert!(repo.run(&["ls-files", "new.txt"]).unwrap().is_empty());
+        assert!(repo.root().join("new.txt").exists());
+    }
+
+    #[test]
+    fn test_missing_hunk() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "a\n", "add a");
+        assert!(matches!(stage(&repo, Path::new("a.txt"), 0), Err(GitError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_parse_header_without_counts() {
+        let hunk = parse_header("@@ -3 +3,2 @@ fn main() {").unwrap();
+        assert_eq!((hunk.old_start, hunk.old_lines), (3, 1));
+        assert_eq!((hunk.new_start, hunk.new_lines), (3, 2));
+        assert_eq!(hunk.section, "fn main() {");
+        assert_eq!(hunk.header(), "@@ -3,1 +3,2 @@ fn main() {");
+        assert!(parse_header("@@ -x +1 @@").is_err());
+    }
+}
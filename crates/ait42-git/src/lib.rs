@@ -0,0 +1,82 @@
+//! AIT42 Git Integration
+//!
+//! Git operations shared by the TUI and the GUI, implemented on top of the
+//! `git` command-line tool so behaviour matches the user's own git setup
+//! (hooks, config, credential helpers).
+
+pub mod blame;
+pub mod changelog;
+pub mod checkpoint;
+pub mod commit;
+pub mod conflict;
+pub mod diff;
+pub mod history;
+pub mod hunk;
+pub mod log;
+pub mod rebase;
+pub mod repo;
+pub mod stash;
+pub mod status;
+
+// Re-exports
+pub use blame::BlameLine;
+pub use changelog::{Bump, Changelog, ChangelogEntry};
+pub use checkpoint::{Checkpoint, CHECKPOINT_REF};
+pub use commit::{CommitMessage, StagedChanges};
+pub use conflict::{
+    ConflictFile, ConflictHunk, FileResolution, MergeConflict, Resolution, Segment,
+};
+pub use diff::{Adoption, BudgetedDiff, DiffRange, DiffStat};
+pub use history::{Operation, OperationStatus};
+pub use hunk::Hunk;
+pub use log::{Commit, GraphRow};
+pub use rebase::{RebaseAction, RebasePlan, RebaseStep};
+pub use repo::Repository;
+pub use stash::StashEntry;
+pub use status::FileStatus;
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GitError {
+    #[error("Not a git repository: {0}")]
+    NotARepository(PathBuf),
+
+    #[error("git {command} failed: {stderr}")]
+    CommandFailed { command: String, stderr: String },
+
+    #[error("Unexpected git output: {0}")]
+    Parse(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Unresolved conflicts in: {}", .0.join(", "))]
+    UnresolvedConflicts(Vec<String>),
+
+    #[error("Invalid operation: {0}")]
+    InvalidOperation(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, GitError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_types() {
+        let err = GitError::CommandFailed {
+            command: "status".to_string(),
+            stderr: "fatal: bad".to_string(),
+        };
+        assert!(err.to_string().contains("git status failed"));
+
+        let err = GitError::NotARepository(PathBuf::from("/tmp/x"));
+        assert!(err.to_string().contains("/tmp/x"));
+    }
+}
@@ -0,0 +1,275 @@
+//! Commit History
+//!
+//! Reads the commit log together with the graph drawn by `git log --graph`,
+//! so branch topology is rendered the same way git itself renders it.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// Marks the start of commit fields on a graph line
+const RECORD_START: char = '\x1e';
+
+/// A commit in the history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Commit {
+    /// Full object ID
+    pub sha: String,
+    /// Parent object IDs; more than one for merges
+    pub parents: Vec<String>,
+    /// Author name
+    pub author: String,
+    /// Author email
+    pub email: String,
+    /// Author date as a Unix timestamp
+    pub timestamp: i64,
+    /// First line of the message
+    pub subject: String,
+    /// Full names of refs pointing at the commit, as decorated by git, e.g.
+    /// `HEAD -> refs/heads/main` or `tag: refs/tags/v1.0`
+    pub refs: Vec<String>,
+}
+
+impl Commit {
+    /// Abbreviated object ID
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+
+    /// Local branch names among the refs
+    pub fn branches(&self) -> impl Iterator<Item = &str> {
+        self.refs.iter().filter_map(|r| {
+            r.strip_prefix("HEAD -> ")
+                .unwrap_or(r)
+                .strip_prefix("refs/heads/")
+        })
+    }
+
+    /// Refs shortened for display, e.g. `HEAD -> main`, `origin/main`, `v1.0`
+    pub fn labels(&self) -> impl Iterator<Item = String> + '_ {
+        self.refs.iter().map(|r| {
+            ["refs/heads/", "refs/remotes/", "tag: refs/tags/"]
+                .iter()
+                .fold(r.clone(), |label, prefix| label.replace(prefix, ""))
+        })
+    }
+
+    /// Whether HEAD points at this commit
+    pub fn is_head(&self) -> bool {
+        self.refs
+            .iter()
+            .any(|r| r == "HEAD" || r.starts_with("HEAD -> "))
+    }
+}
+
+/// One line of the history graph
+///
+/// Lines that only continue or join branches carry no commit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GraphRow {
+    /// Graph characters drawn by git (`*`, `|`, `/`, `\`)
+    pub graph: String,
+    /// Commit shown on this line
+    pub commit: Option<Commit>,
+}
+
+/// History graph of HEAD, or of all refs when `all` is set
+pub fn graph(repo: &Repository, limit: usize, all: bool) -> Result<Vec<GraphRow>> {
+    if repo.head()?.is_none() && !all {
+        return Ok(Vec::new());
+    }
+
+    let limit = limit.to_string();
+    let mut args = vec![
+        "log",
+        "--graph",
+        "--date-order",
+        "--decorate=full",
+        "--format=%x1e%H%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%D%x1f%s",
+        "-n",
+        limit.as_str(),
+    ];
+    if all {
+        args.push("--all");
+    }
+
+    repo.run(&args)?.lines().map(parse_row).collect()
+}
+
+/// Files most often changed in the same commits as `path`, among the last
+/// `commits` commits touching it
+///
+/// Paths are relative to the repository root, most frequent first, with
+/// ties broken by recency; files deleted since are left out.
+pub fn co_changed(
+    repo: &Repository,
+    path: &Path,
+    commits: usize,
+    limit: usize,
+) -> Result<Vec<PathBuf>> {
+    if repo.head()?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let relative = repo.relative_path(path)?;
+    let commits = commits.to_string();
+    // `--full-diff` lists every file of the commits, not just `path`
+    let output = repo.run(&[
+        OsStr::new("log"),
+        OsStr::new("--full-diff"),
+        OsStr::new("--name-only"),
+        OsStr::new("--format=%x1e"),
+        OsStr::new("-n"),
+        OsStr::new(&commits),
+        OsStr::new("--"),
+        relative.as_os_str(),
+    ])?;
+
+    // Count, and the first commit a file was seen in
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (order, files) in output.split(RECORD_START).skip(1).enumerate() {
+        for file in files.lines().filter(|file| !file.is_empty()) {
+            counts.entry(file).or_insert((0, order)).0 += 1;
+        }
+    }
+
+    let own = relative.to_string_lossy().replace('\\', "/");
+    let mut ranked: Vec<(&str, (usize, usize))> = counts
+        .into_iter()
+        .filter(|(file, _)| *file != own && repo.root().join(file).is_file())
+        .collect();
+    ranked.sort_by(|(a, (a_count, a_order)), (b, (b_count, b_order))| {
+        b_count
+            .cmp(a_count)
+            .then(a_order.cmp(b_order))
+            .then(a.cmp(b))
+    });
+    Ok(ranked
+        .into_iter()
+        .take(limit)
+        .map(|(file, _)| PathBuf::from(file))
+        .collect())
+}
+
+fn parse_row(line: &str) -> Result<GraphRow> {
+    let Some((graph, record)) = line.split_once(RECORD_START) else {
+        return Ok(GraphRow {
+            graph: line.trim_end().to_string(),
+            commit: None,
+        });
+    };
+
+    let fields: Vec<&str> = record.splitn(7, '\x1f').collect();
+    let [sha, parents, author, email, timestamp, refs, subject] = fields[..] else {
+        return Err(GitError::Parse(line.to_string()));
+    };
+
+    Ok(GraphRow {
+        graph: graph.trim_end().to_string(),
+        commit: Some(Commit {
+            sha: sha.to_string(),
+            parents: parents.split_whitespace().map(str::to_string).collect(),
+            author: author.to_string(),
+            email: email.to_string(),
+            timestamp: timestamp
+                .parse()
+                .map_err(|_| GitError::Parse(line.to_string()))?,
+            subject: subject.to_string(),
+            refs: refs
+                .split(", ")
+                .filter(|r| !r.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+
+    #[test]
+    fn test_graph_shows_branches_and_merges() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "a\n", "initial");
+        repo.run(&["checkout", "-q", "-b", "feature"]).unwrap();
+        commit_file(&repo, "b.txt", "b\n", "feature work");
+        repo.run(&["checkout", "-q", "main"]).unwrap();
+        commit_file(&repo, "c.txt", "c\n", "main work");
+        repo.run(&["merge", "-q", "--no-edit", "feature"]).unwrap();
+
+        let rows = graph(&repo, 50, false).unwrap();
+        let commits: Vec<&Commit> = rows.iter().filter_map(|r| r.commit.as_ref()).collect();
+        assert_eq!(commits.len(), 4);
+
+        let merge = commits[0];
+        assert_eq!(merge.parents.len(), 2);
+        assert!(merge.is_head());
+        assert_eq!(merge.branches().collect::<Vec<_>>(), ["main"]);
+        assert_eq!(merge.labels().collect::<Vec<_>>(), ["HEAD -> main"]);
+        assert_eq!(merge.author, "Test");
+
+        // The side branch is drawn in a second column
+        assert!(rows.iter().any(|r| r.commit.is_none()));
+        assert!(rows.iter().any(|r| r.graph.contains('|')));
+    }
+
+    #[test]
+    fn test_empty_repository() {
+        let (_dir, repo) = init_repo();
+        assert!(graph(&repo, 10, false).unwrap().is_empty());
+        assert!(co_changed(&repo, Path::new("a.txt"), 10, 5)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_co_changed_ranks_by_frequency() {
+        let (_dir, repo) = init_repo();
+        let stage = |files: &[(&str, &str)], message: &str| {
+            for (file, content) in files {
+                std::fs::write(repo.root().join(file), content).unwrap();
+            }
+            repo.run(&["add", "-A"]).unwrap();
+            repo.run(&["commit", "-q", "-m", message]).unwrap();
+        };
+        stage(&[("a.rs", "1"), ("b.rs", "1"), ("c.rs", "1")], "initial");
+        stage(&[("a.rs", "2"), ("c.rs", "2")], "a and c");
+        stage(&[("b.rs", "2"), ("d.rs", "1")], "without a");
+        stage(&[("a.rs", "3"), ("e.rs", "1")], "a and e");
+        std::fs::remove_file(repo.root().join("e.rs")).unwrap();
+        stage(&[], "remove e");
+
+        let files = co_changed(&repo, Path::new("a.rs"), 10, 5).unwrap();
+        assert_eq!(files, [PathBuf::from("c.rs"), PathBuf::from("b.rs")]);
+        let files = co_changed(&repo, &repo.root().join("a.rs"), 10, 1).unwrap();
+        assert_eq!(files, [PathBuf::from("c.rs")]);
+        // Only the latest commit touching a
+        assert!(co_changed(&repo, Path::new("a.rs"), 1, 5)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_refs() {
+        let row = parse_row(
+            "* \x1eabc\x1f\x1fA\x1fa@x\x1f5\x1fHEAD -> refs/heads/dev, tag: refs/tags/v1, \
+             refs/remotes/origin/dev, refs/heads/feature/x\x1fsubject",
+        )
+        .unwrap();
+        let commit = row.commit.unwrap();
+        assert_eq!(row.graph, "*");
+        assert!(commit.parents.is_empty());
+        assert_eq!(commit.refs.len(), 4);
+        assert_eq!(commit.branches().collect::<Vec<_>>(), ["dev", "feature/x"]);
+        assert_eq!(
+            commit.labels().collect::<Vec<_>>(),
+            ["HEAD -> dev", "v1", "origin/dev", "feature/x"]
+        );
+    }
+}
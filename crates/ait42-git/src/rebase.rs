@@ -0,0 +1,283 @@
+//! Interactive Rebase
+//!
+//! Builds a rebase plan for the commits after a base commit, lets the caller
+//! reorder steps and change their actions, then runs it with
+//! `git rebase --interactive`. The plan replaces the todo list git would
+//! otherwise open in an editor. A rebase that stops (conflicts or `edit`
+//! steps) is resumed through [`crate::history`].
+
+use crate::history::{self, OperationStatus};
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use tracing::info;
+
+/// What to do with a commit during the rebase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RebaseAction {
+    /// Keep the commit as is
+    Pick,
+    /// Stop after applying the commit so it can be amended
+    Edit,
+    /// Meld into the previous commit, keeping both messages
+    Squash,
+    /// Meld into the previous commit, keeping the previous message
+    Fixup,
+    /// Remove the commit
+    Drop,
+}
+
+impl RebaseAction {
+    /// Todo-list keyword
+    pub fn keyword(self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Edit => "edit",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+
+    fn melds(self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+/// One commit in the plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RebaseStep {
+    pub action: RebaseAction,
+    pub sha: String,
+    pub subject: String,
+}
+
+/// Ordered steps replayed on top of `onto`, oldest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RebasePlan {
+    pub onto: String,
+    pub steps: Vec<RebaseStep>,
+}
+
+impl RebasePlan {
+    /// Plan that picks every commit between `onto` and HEAD unchanged
+    ///
+    /// `onto` must be an ancestor of HEAD. Merge commits are left out, as
+    /// `git rebase` drops them by default.
+    pub fn new(repo: &Repository, onto: &str) -> Result<Self> {
+        let onto = repo
+            .rev_parse(&format!("{}^{{commit}}", onto))?
+            .ok_or_else(|| GitError::NotFound(onto.to_string()))?;
+        if !repo.succeeds(&["merge-base", "--is-ancestor", onto.as_str(), "HEAD"])? {
+            return Err(GitError::InvalidOperation(format!(
+                "{} is not an ancestor of HEAD",
+                &onto[..7]
+            )));
+        }
+
+        let range = format!("{}..HEAD", onto);
+        let steps = repo
+            .run(&[
+                "log",
+                "--reverse",
+                "--no-merges",
+                "--format=%H%x1f%s",
+                range.as_str(),
+            ])?
+            .lines()
+            .map(|line| {
+                let (sha, subject) = line
+                    .split_once('\x1f')
+                    .ok_or_else(|| GitError::Parse(line.to_string()))?;
+                Ok(RebaseStep {
+                    action: RebaseAction::Pick,
+                    sha: sha.to_string(),
+                    subject: subject.to_string(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { onto, steps })
+    }
+
+    /// Change the action of step `index`
+    pub fn set_action(&mut self, index: usize, action: RebaseAction) {
+        if let Some(step) = self.steps.get_mut(index) {
+            step.action = action;
+        }
+    }
+
+    /// Move step `index` by `delta` places and return its new index
+    pub fn move_step(&mut self, index: usize, delta: isize) -> usize {
+        if index >= self.steps.len() {
+            return index;
+        }
+        let target = index.saturating_add_signed(delta).min(self.steps.len() - 1);
+        let step = self.steps.remove(index);
+        self.steps.insert(target, step);
+        target
+    }
+
+    /// Check that git will accept the plan
+    pub fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            return Err(GitError::InvalidOperation("nothing to rebase".to_string()));
+        }
+        let first = self.steps.iter().find(|s| s.action != RebaseAction::Drop);
+        if first.is_some_and(|s| s.action.melds()) {
+            return Err(GitError::InvalidOperation(
+                "the first commit cannot be squashed into its parent".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The plan as a git todo list
+    pub fn todo(&self) -> String {
+        self.steps
+            .iter()
+            .map(|s| format!("{} {} {}\n", s.action.keyword(), s.sha, s.subject))
+            .collect()
+    }
+}
+
+/// Run `plan` and report whether the rebase finished or stopped
+///
+/// Squashed commits keep the combined message without prompting.
+pub fn start(repo: &Repository, plan: &RebasePlan) -> Result<OperationStatus> {
+    plan.validate()?;
+
+    let todo_path = repo.git_dir().join("ait42-rebase-todo");
+    std::fs::write(&todo_path, plan.todo())?;
+
+    // git appends the path of its own todo file to this command
+    let sequence_editor = format!("cp {}", shell_quote(&todo_path.to_string_lossy()));
+    let mut env = history::step_env();
+    env.push(("GIT_SEQUENCE_EDITOR", OsStr::new(&sequence_editor)));
+
+    let result = match repo.run_with_env(&["rebase", "--interactive", plan.onto.as_str()], &env) {
+        Ok(_) => history::status(repo),
+        Err(err @ GitError::CommandFailed { .. }) if history::in_progress(repo).is_some() => {
+            info!("Rebase paused: {}", err);
+            history::status(repo)
+        }
+        Err(err) => Err(err),
+    };
+
+    let _ = std::fs::remove_file(&todo_path);
+    if let Ok(OperationStatus::Completed) = result {
+        info!("Rebased {} commits onto {}", plan.steps.len(), &plan.onto[..7]);
+    }
+    result
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{continue_operation, Operation};
+    use crate::repo::test_support::*;
+    use std::fs;
+
+    fn subjects(repo: &Repository, count: usize) -> Vec<String> {
+        let count = count.to_string();
+        repo.run(&["log", "--format=%s", "-n", count.as_str()])
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_reorder_squash_and_drop() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "base.txt", "base\n", "base");
+        commit_file(&repo, "a.txt", "a\n", "add a");
+        commit_file(&repo, "b.txt", "b\n", "add b");
+        commit_file(&repo, "a.txt", "a2\n", "fix a");
+        commit_file(&repo, "c.txt", "c\n", "add c");
+
+        let mut plan = RebasePlan::new(&repo, &base).unwrap();
+        assert_eq!(plan.steps.len(), 4);
+        assert_eq!(plan.steps[0].subject, "add a");
+
+        // Move "fix a" next to "add a" and meld it in, drop "add c"
+        assert_eq!(plan.move_step(2, -1), 1);
+        plan.set_action(1, RebaseAction::Fixup);
+        plan.set_action(3, RebaseAction::Drop);
+
+        assert_eq!(start(&repo, &plan).unwrap(), OperationStatus::Completed);
+        assert_eq!(subjects(&repo, 3), ["add b", "add a", "base"]);
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "a2\n");
+        assert!(!repo.root().join("c.txt").exists());
+    }
+
+    #[test]
+    fn test_edit_step_pauses_and_resumes() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "base.txt", "base\n", "base");
+        let first = commit_file(&repo, "a.txt", "a\n", "add a");
+        commit_file(&repo, "b.txt", "b\n", "add b");
+
+        let mut plan = RebasePlan::new(&repo, &base).unwrap();
+        plan.set_action(0, RebaseAction::Edit);
+
+        match start(&repo, &plan).unwrap() {
+            OperationStatus::Paused {
+                operation,
+                current,
+                conflicts,
+            } => {
+                assert_eq!(operation, Operation::Rebase);
+                assert_eq!(current, Some(first));
+                assert!(conflicts.is_empty());
+            }
+            status => panic!("expected a pause, got {:?}", status),
+        }
+
+        assert_eq!(continue_operation(&repo).unwrap(), OperationStatus::Completed);
+        assert_eq!(subjects(&repo, 2), ["add b", "add a"]);
+    }
+
+    #[test]
+    fn test_conflicting_reorder_pauses() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "a.txt", "0\n", "base");
+        commit_file(&repo, "a.txt", "1\n", "one");
+        commit_file(&repo, "a.txt", "2\n", "two");
+
+        let mut plan = RebasePlan::new(&repo, &base).unwrap();
+        plan.move_step(1, -1);
+        let status = start(&repo, &plan).unwrap();
+        assert!(
+            matches!(status, OperationStatus::Paused { ref conflicts, .. } if conflicts == &["a.txt"])
+        );
+
+        history::abort(&repo).unwrap();
+        assert_eq!(subjects(&repo, 1), ["two"]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let (_dir, repo) = init_repo();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        assert!(matches!(
+            RebasePlan::new(&repo, &base).unwrap().validate(),
+            Err(GitError::InvalidOperation(_))
+        ));
+
+        commit_file(&repo, "b.txt", "b\n", "b");
+        let mut plan = RebasePlan::new(&repo, &base).unwrap();
+        plan.set_action(0, RebaseAction::Squash);
+        assert!(plan.validate().is_err());
+        assert!(plan.todo().starts_with("squash "));
+
+        assert!(matches!(RebasePlan::new(&repo, "missing"), Err(GitError::NotFound(_))));
+    }
+}
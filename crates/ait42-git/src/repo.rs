@@ -0,0 +1,344 @@
+//! Repository Access
+//!
+//! Locates a repository and runs git commands inside it.
+
+use crate::{GitError, Result};
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use tracing::debug;
+
+/// A git working tree
+#[derive(Debug, Clone)]
+pub struct Repository {
+    root: PathBuf,
+    git_dir: PathBuf,
+}
+
+impl Repository {
+    /// Open the repository containing `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-toplevel", "--absolute-git-dir"])
+            .current_dir(path)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(GitError::NotARepository(path.to_path_buf()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        match (lines.next(), lines.next()) {
+            (Some(root), Some(git_dir)) => Ok(Self {
+                root: PathBuf::from(root),
+                git_dir: PathBuf::from(git_dir),
+            }),
+            _ => Err(GitError::Parse(stdout.to_string())),
+        }
+    }
+
+    /// Top-level directory of the working tree
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The `.git` directory
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// `path` relative to the root, given relative to it or absolute
+    ///
+    /// Fails for paths outside the working tree.
+    pub fn relative_path(&self, path: &Path) -> Result<PathBuf> {
+        let relative = if path.is_absolute() {
+            // The root git reports has its symlinks resolved
+            let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            path.strip_prefix(&self.root)
+                .map(Path::to_path_buf)
+                .unwrap_or(path)
+        } else {
+            path.to_path_buf()
+        };
+
+        let inside = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if relative.as_os_str().is_empty() || !inside {
+            return Err(GitError::InvalidOperation(format!(
+                "{} is not inside the repository",
+                path.display()
+            )));
+        }
+        Ok(relative)
+    }
+
+    /// Run git with `args` and return its stdout
+    pub fn run<S: AsRef<OsStr>>(&self, args: &[S]) -> Result<String> {
+        self.run_with_env(args, &[])
+    }
+
+    /// Run git with extra environment variables and return its stdout
+    pub fn run_with_env<S: AsRef<OsStr>>(
+        &self,
+        args: &[S],
+        env: &[(&str, &OsStr)],
+    ) -> Result<String> {
+        let output = self.output(args, env)?;
+        if !output.status.success() {
+            return Err(command_failed(args, &output));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Run git with `input` on stdin and return its stdout
+    pub fn run_with_input<S: AsRef<OsStr>>(&self, args: &[S], input: &str) -> Result<String> {
+        let mut child = Command::new("git")
+            .args(args)
+            .current_dir(&self.root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        debug!("Running git {}", describe(args));
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(command_failed(args, &output));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Run git and report whether it exited successfully
+    ///
+    /// For commands that answer a question through their exit status, such
+    /// as `merge-base --is-ancestor`.
+    pub fn succeeds<S: AsRef<OsStr>>(&self, args: &[S]) -> Result<bool> {
+        Ok(self.output(args, &[])?.status.success())
+    }
+
+    /// Resolve a revision to a full object ID, or `None` if it does not exist
+    pub fn rev_parse(&self, rev: &str) -> Result<Option<String>> {
+        let output = self.output(&["rev-parse", "--verify", "--quiet", rev], &[])?;
+        if output.status.success() {
+            Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Commit checked out at HEAD, or `None` in a repository without commits
+    pub fn head(&self) -> Result<Option<String>> {
+        self.rev_parse("HEAD^{commit}")
+    }
+
+    /// Value of a config key, or `None` if it is not set
+    pub fn config(&self, key: &str) -> Result<Option<String>> {
+        self.optional(&["config", "--get", key])
+    }
+
+    /// Branch checked out at HEAD, or `None` when HEAD is detached
+    pub fn current_branch(&self) -> Result<Option<String>> {
+        self.optional(&["symbolic-ref", "--quiet", "--short", "HEAD"])
+    }
+
+    /// Closest tag reachable from HEAD, if any
+    pub fn latest_tag(&self) -> Result<Option<String>> {
+        self.optional(&["describe", "--tags", "--abbrev=0"])
+    }
+
+    /// Identity of the repository shared by all clones of its `origin`
+    ///
+    /// The remote URL without scheme, user and `.git` suffix, so SSH and
+    /// HTTPS clones agree. `None` if there is no `origin` remote.
+    pub fn remote_id(&self) -> Result<Option<String>> {
+        Ok(self
+            .config("remote.origin.url")?
+            .map(|url| normalize_remote_url(&url)))
+    }
+
+    /// Trimmed stdout of a command that fails when there is no answer
+    fn optional(&self, args: &[&str]) -> Result<Option<String>> {
+        let output = self.output(args, &[])?;
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((output.status.success() && !stdout.is_empty()).then_some(stdout))
+    }
+
+    fn output<S: AsRef<OsStr>>(&self, args: &[S], env: &[(&str, &OsStr)]) -> Result<Output> {
+        let mut command = Command::new("git");
+        command.args(args).current_dir(&self.root);
+        for (key, value) in env {
+            command.env(key, value);
+        }
+        debug!("Running git {}", describe(args));
+        Ok(command.output()?)
+    }
+}
+
+/// `host/path` of a remote URL, lowercased
+///
+/// `git@github.com:Org/Repo.git`, `https://github.com/org/repo` and
+/// `ssh://git@github.com/org/repo.git` all become `github.com/org/repo`.
+pub fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let (address, scp_like) = match url.split_once("://") {
+        Some((_, address)) => (address, false),
+        None => (url, true),
+    };
+    let address = match address.split_once('@') {
+        Some((user, host)) if !user.contains('/') => host,
+        _ => address,
+    };
+    let address = if scp_like {
+        address.replacen(':', "/", 1)
+    } else {
+        address.to_string()
+    };
+    address.to_lowercase()
+}
+
+fn describe<S: AsRef<OsStr>>(args: &[S]) -> String {
+    args.iter()
+        .map(|arg| arg.as_ref().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn command_failed<S: AsRef<OsStr>>(args: &[S], output: &Output) -> GitError {
+    GitError::CommandFailed {
+        command: args
+            .first()
+            .map(|arg| arg.as_ref().to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    }
+}
+
+/// Repository fixtures for tests, here and in dependent crates with the
+/// `test-support` feature
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Fresh repository with an identity configured
+    pub fn init_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q", "-b", "main"])
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let repo = Repository::open(dir.path()).unwrap();
+        repo.run(&["config", "user.name", "Test"]).unwrap();
+        repo.run(&["config", "user.email", "test@example.com"])
+            .unwrap();
+        repo.run(&["config", "commit.gpgsign", "false"]).unwrap();
+        (dir, repo)
+    }
+
+    /// Write `content` to `path` and commit everything
+    pub fn commit_file(repo: &Repository, path: &str, content: &str, message: &str) -> String {
+        std::fs::write(repo.root().join(path), content).unwrap();
+        repo.run(&["add", "-A"]).unwrap();
+        repo.run(&["commit", "-q", "-m", message]).unwrap();
+        repo.head().unwrap().unwrap()
+    }
+
+    /// Patch adding `lines` lines to `name`
+    pub fn file_diff(name: &str, lines: usize) -> String {
+        let mut diff =
+            format!("diff --git a/{name} b/{name}\n--- a/{name}\n+++ b/{name}\n@@ -1 +1 @@\n");
+        for i in 0..lines {
+            diff.push_str(&format!("+line {}\n", i));
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::*;
+    use super::*;
+
+    #[test]
+    fn test_open_and_head() {
+        let (dir, repo) = init_repo();
+        assert!(repo.head().unwrap().is_none());
+        assert!(repo.git_dir().ends_with(".git"));
+
+        let sha = commit_file(&repo, "a.txt", "a\n", "initial");
+        assert_eq!(sha.len(), 40);
+        assert_eq!(repo.rev_parse("main").unwrap(), Some(sha));
+
+        // Opening from a subdirectory finds the same root
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let nested = Repository::open(dir.path().join("sub")).unwrap();
+        assert_eq!(nested.root(), repo.root());
+    }
+
+    #[test]
+    fn test_config_branch_and_remote() {
+        let (_dir, repo) = init_repo();
+        assert_eq!(repo.config("user.name").unwrap().as_deref(), Some("Test"));
+        assert_eq!(repo.config("user.signingkey").unwrap(), None);
+        assert_eq!(repo.current_branch().unwrap().as_deref(), Some("main"));
+        assert_eq!(repo.remote_id().unwrap(), None);
+
+        repo.run(&["remote", "add", "origin", "git@github.com:Org/Repo.git"])
+            .unwrap();
+        assert_eq!(repo.remote_id().unwrap().as_deref(), Some("github.com/org/repo"));
+
+        let sha = commit_file(&repo, "a.txt", "a\n", "initial");
+        repo.run(&["checkout", "-q", &sha]).unwrap();
+        assert_eq!(repo.current_branch().unwrap(), None);
+    }
+
+    #[test]
+    fn test_normalize_remote_url() {
+        for url in [
+            "git@github.com:org/repo.git",
+            "https://github.com/Org/Repo",
+            "https://token@github.com/org/repo.git/",
+            "ssh://git@github.com/org/repo.git",
+        ] {
+            assert_eq!(normalize_remote_url(url), "github.com/org/repo", "{}", url);
+        }
+        assert_eq!(normalize_remote_url("/srv/git/repo.git"), "/srv/git/repo");
+    }
+
+    #[test]
+    fn test_not_a_repository() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(matches!(Repository::open(dir.path()), Err(GitError::NotARepository(_))));
+    }
+
+    #[test]
+    fn test_run_with_input() {
+        let (_dir, repo) = init_repo();
+        let sha = repo
+            .run_with_input(&["hash-object", "--stdin"], "hello\n")
+            .unwrap();
+        assert_eq!(sha.trim(), "ce013625030ba8dba906f756967f9e9ca394464a");
+        assert!(repo
+            .run_with_input(&["apply", "--cached"], "garbage")
+            .is_err());
+    }
+
+    #[test]
+    fn test_failed_command_reports_stderr() {
+        let (_dir, repo) = init_repo();
+        let err = repo.run(&["checkout", "no-such-branch"]).unwrap_err();
+        assert!(
+            matches!(err, GitError::CommandFailed { ref command, .. } if command == "checkout")
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Stash Management
+//!
+//! List, create, apply and drop stashes. Entries are addressed by their
+//! position in the stash list, as in `stash@{N}`.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// An entry in the stash list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StashEntry {
+    /// Position in the stash list (0 is the most recent)
+    pub index: usize,
+    /// Stash commit
+    pub sha: String,
+    /// Branch the stash was created on
+    pub branch: Option<String>,
+    /// Stash message without the "On <branch>:" prefix
+    pub message: String,
+    /// Unix timestamp
+    pub timestamp: i64,
+}
+
+impl StashEntry {
+    /// Reference name, e.g. `stash@{0}`
+    pub fn reference(&self) -> String {
+        reference(self.index)
+    }
+}
+
+fn reference(index: usize) -> String {
+    format!("stash@{{{}}}", index)
+}
+
+/// List stashes, most recent first
+pub fn list(repo: &Repository) -> Result<Vec<StashEntry>> {
+    let output = repo.run(&["stash", "list", "--format=%H%x1f%ct%x1f%gs"])?;
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(index, line)| parse_entry(index, line))
+        .collect()
+}
+
+/// Stash local changes
+///
+/// Returns `false` when there was nothing to stash.
+pub fn push(repo: &Repository, message: Option<&str>, include_untracked: bool) -> Result<bool> {
+    let before = repo.rev_parse("refs/stash")?;
+
+    let mut args = vec!["stash", "push"];
+    if include_untracked {
+        args.push("--include-untracked");
+    }
+    if let Some(message) = message {
+        args.extend(["-m", message]);
+    }
+    repo.run(&args)?;
+
+    let created = repo.rev_parse("refs/stash")? != before;
+    if created {
+        info!("Stashed local changes");
+    }
+    Ok(created)
+}
+
+/// Apply a stash, keeping it in the list
+pub fn apply(repo: &Repository, index: usize) -> Result<()> {
+    ensure_exists(repo, index)?;
+    repo.run(&["stash", "apply", &reference(index)])?;
+    Ok(())
+}
+
+/// Apply a stash and remove it from the list
+///
+/// The stash is kept if applying it conflicts.
+pub fn pop(repo: &Repository, index: usize) -> Result<()> {
+    ensure_exists(repo, index)?;
+    repo.run(&["stash", "pop", &reference(index)])?;
+    Ok(())
+}
+
+/// Remove a stash without applying it
+pub fn drop(repo: &Repository, index: usize) -> Result<()> {
+    ensure_exists(repo, index)?;
+    repo.run(&["stash", "drop", &reference(index)])?;
+    Ok(())
+}
+
+/// Patch of a stash, including its untracked files
+pub fn show(repo: &Repository, index: usize) -> Result<String> {
+    ensure_exists(repo, index)?;
+    repo.run(&[
+        "stash",
+        "show",
+        "-p",
+        "--include-untracked",
+        &reference(index),
+    ])
+}
+
+fn ensure_exists(repo: &Repository, index: usize) -> Result<()> {
+    match repo.rev_parse(&reference(index))? {
+        Some(_) => Ok(()),
+        None => Err(GitError::NotFound(reference(index))),
+    }
+}
+
+fn parse_entry(index: usize, line: &str) -> Result<StashEntry> {
+    let mut fields = line.splitn(3, '\x1f');
+    let (Some(sha), Some(timestamp), Some(subject)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(GitError::Parse(line.to_string()));
+    };
+
+    let timestamp = timestamp
+        .parse()
+        .map_err(|_| GitError::Parse(line.to_string()))?;
+
+    // "WIP on main: abc123 subject" or "On main: message"
+    let (branch, message) = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "))
+        .and_then(|rest| rest.split_once(": "))
+        .map(|(branch, message)| (Some(branch.to_string()), message.to_string()))
+        .unwrap_or_else(|| (None, subject.to_string()));
+
+    Ok(StashEntry {
+        index,
+        sha: sha.to_string(),
+        branch,
+        message,
+        timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::*;
+    use std::fs;
+
+    #[test]
+    fn test_push_list_and_show() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+
+        assert!(!push(&repo, None, false).unwrap());
+
+        fs::write(repo.root().join("a.txt"), "two\n").unwrap();
+        assert!(push(&repo, Some("parked"), false).unwrap());
+        fs::write(repo.root().join("new.txt"), "new\n").unwrap();
+        assert!(push(&repo, None, true).unwrap());
+
+        let stashes = list(&repo).unwrap();
+        assert_eq!(stashes.len(), 2);
+        assert_eq!(stashes[0].reference(), "stash@{0}");
+        assert_eq!(stashes[1].message, "parked");
+        assert_eq!(stashes[1].branch.as_deref(), Some("main"));
+
+        assert!(show(&repo, 0).unwrap().contains("+new"));
+        assert!(show(&repo, 1).unwrap().contains("+two"));
+        assert!(!repo.root().join("new.txt").exists());
+    }
+
+    #[test]
+    fn test_apply_pop_drop() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+
+        fs::write(repo.root().join("a.txt"), "two\n").unwrap();
+        push(&repo, Some("first"), false).unwrap();
+        fs::write(repo.root().join("a.txt"), "three\n").unwrap();
+        push(&repo, Some("second"), false).unwrap();
+
+        apply(&repo, 1).unwrap();
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "two\n");
+        assert_eq!(list(&repo).unwrap().len(), 2);
+
+        repo.run(&["checkout", "--", "a.txt"]).unwrap();
+        pop(&repo, 0).unwrap();
+        assert_eq!(fs::read_to_string(repo.root().join("a.txt")).unwrap(), "three\n");
+
+        drop(&repo, 0).unwrap();
+        assert!(list(&repo).unwrap().is_empty());
+        assert!(matches!(drop(&repo, 0), Err(GitError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_parse_subjects() {
+        let entry = parse_entry(0, "abc\x1f10\x1fWIP on dev: 1234567 fix").unwrap();
+        assert_eq!(entry.branch.as_deref(), Some("dev"));
+        assert_eq!(entry.message, "1234567 fix");
+
+        let entry = parse_entry(0, "abc\x1f10\x1fcustom").unwrap();
+        assert_eq!(entry.branch, None);
+        assert_eq!(entry.message, "custom");
+    }
+}
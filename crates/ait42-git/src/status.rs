@@ -0,0 +1,135 @@
+//! Working Tree Status
+//!
+//! Status of individual files, as shown next to them in a file tree. The
+//! whole tree can be read once and single paths again after they change.
+
+use crate::repo::Repository;
+use crate::{GitError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How a file differs from HEAD, staged or not
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Untracked,
+    /// Unmerged, with conflicts to resolve
+    Conflicted,
+}
+
+impl FileStatus {
+    /// One-letter badge, as in `git status --short`
+    pub fn badge(self) -> char {
+        match self {
+            FileStatus::Modified => 'M',
+            FileStatus::Added => 'A',
+            FileStatus::Deleted => 'D',
+            FileStatus::Renamed => 'R',
+            FileStatus::Untracked => '?',
+            FileStatus::Conflicted => '!',
+        }
+    }
+
+    fn parse(code: &str) -> Option<Self> {
+        let mut chars = code.chars();
+        let (index, worktree) = (chars.next()?, chars.next()?);
+        Some(match (index, worktree) {
+            ('?', '?') => FileStatus::Untracked,
+            ('!', '!') => return None,
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => FileStatus::Conflicted,
+            ('R', _) | (_, 'R') => FileStatus::Renamed,
+            ('A', _) => FileStatus::Added,
+            ('D', _) | (_, 'D') => FileStatus::Deleted,
+            _ => FileStatus::Modified,
+        })
+    }
+}
+
+/// Status of changed files below `paths`, or of the whole working tree if
+/// `paths` is empty
+///
+/// Keys are relative to the root; unchanged files are left out, and
+/// untracked directories are listed file by file.
+pub fn file_statuses(repo: &Repository, paths: &[&Path]) -> Result<HashMap<PathBuf, FileStatus>> {
+    let mut args = vec![
+        "status".to_string(),
+        "--porcelain=v1".to_string(),
+        "-z".to_string(),
+        "--untracked-files=all".to_string(),
+        "--".to_string(),
+    ];
+    for path in paths {
+        args.push(repo.relative_path(path)?.to_string_lossy().to_string());
+    }
+    let output = repo.run(&args)?;
+
+    let mut statuses = HashMap::new();
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        let (Some(code), Some(path)) = (record.get(..2), record.get(3..)) else {
+            return Err(GitError::Parse(record.to_string()));
+        };
+        let status = FileStatus::parse(code);
+        // Renames and copies are followed by the original path
+        if code.contains(['R', 'C']) {
+            records.next();
+        }
+        if let Some(status) = status {
+            statuses.insert(PathBuf::from(path), status);
+        }
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo::test_support::{commit_file, init_repo};
+
+    #[test]
+    fn test_file_statuses() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "a\n", "initial");
+        commit_file(&repo, "b.txt", "b\n", "add b");
+
+        std::fs::write(repo.root().join("a.txt"), "changed\n").unwrap();
+        std::fs::remove_file(repo.root().join("b.txt")).unwrap();
+        std::fs::create_dir(repo.root().join("new")).unwrap();
+        std::fs::write(repo.root().join("new/c.txt"), "c\n").unwrap();
+        std::fs::write(repo.root().join("staged.txt"), "s\n").unwrap();
+        repo.run(&["add", "staged.txt"]).unwrap();
+
+        let statuses = file_statuses(&repo, &[]).unwrap();
+        assert_eq!(statuses.len(), 4);
+        assert_eq!(statuses[Path::new("a.txt")], FileStatus::Modified);
+        assert_eq!(statuses[Path::new("b.txt")], FileStatus::Deleted);
+        assert_eq!(statuses[Path::new("new/c.txt")], FileStatus::Untracked);
+        assert_eq!(statuses[Path::new("staged.txt")], FileStatus::Added);
+
+        // Only the paths asked for
+        let statuses = file_statuses(&repo, &[&repo.root().join("a.txt")]).unwrap();
+        assert_eq!(statuses.keys().collect::<Vec<_>>(), [Path::new("a.txt")]);
+        assert_eq!(FileStatus::Untracked.badge(), '?');
+    }
+
+    #[test]
+    fn test_renames_and_conflicts() {
+        let (_dir, repo) = init_repo();
+        commit_file(&repo, "old.txt", "content\n", "initial");
+        repo.run(&["mv", "old.txt", "new.txt"]).unwrap();
+
+        let statuses = file_statuses(&repo, &[]).unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[Path::new("new.txt")], FileStatus::Renamed);
+
+        assert_eq!(FileStatus::parse("UU"), Some(FileStatus::Conflicted));
+        assert_eq!(FileStatus::parse("AA"), Some(FileStatus::Conflicted));
+        assert_eq!(FileStatus::parse(" M"), Some(FileStatus::Modified));
+        assert_eq!(FileStatus::parse("!!"), None);
+    }
+}
@@ -1,28 +1,63 @@
 //! LSP Client Implementation
 //!
 //! Provides a client for communicating with Language Server Protocol servers.
+//!
+//! The server is spawned with piped stdio and spoken to in framed JSON-RPC
+//! (see [`crate::transport`]). A background task reads everything the
+//! server sends: responses are matched to their pending request by id,
+//! diagnostics are stored per document, and requests the server makes of
+//! the client (`workspace/configuration` and the like) are answered.
+//!
+//! Requests time out after the configured request timeout and are then
+//! cancelled with `$/cancelRequest`. Features the server did not announce
+//! in its capabilities are not requested at all.
 
+use crate::transport::{self, error_codes, Message, ResponseError};
 use crate::{LspError, Result};
 use lsp_types::*;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
-use std::process::{Child, Command, Stdio};
+use std::process::Stdio;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{ChildStdin, ChildStdout};
-use tokio::sync::{mpsc, Mutex, RwLock};
-use tracing::{debug, error, info};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, BufReader};
+use tokio::process::{Child, ChildStderr, Command};
+use tokio::sync::{oneshot, Mutex, RwLock};
+use tracing::{debug, error, info, warn};
+
+/// How long a request waits for its response unless configured otherwise
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the server gets to exit after the shutdown handshake
+const EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+type Writer = Arc<Mutex<Box<dyn AsyncWrite + Send + Unpin>>>;
+type ResponseSender = oneshot::Sender<std::result::Result<Value, ResponseError>>;
+type PendingRequests = Arc<Mutex<HashMap<u64, ResponseSender>>>;
+type Diagnostics = Arc<RwLock<HashMap<Url, Vec<Diagnostic>>>>;
 
 /// LSP client for a single language server
 pub struct LspClient {
-    stdin: Arc<Mutex<ChildStdin>>,
+    writer: Writer,
     request_id: AtomicU64,
+    request_timeout: Duration,
     capabilities: Arc<RwLock<Option<ServerCapabilities>>>,
-    pending_requests: Arc<Mutex<HashMap<u64, mpsc::Sender<serde_json::Value>>>>,
-    diagnostics: Arc<RwLock<HashMap<Url, Vec<Diagnostic>>>>,
-    server_process: Arc<Mutex<Child>>,
+    pending_requests: PendingRequests,
+    diagnostics: Diagnostics,
+    server_process: Option<Arc<Mutex<Child>>>,
+    process_id: Option<u32>,
+}
+
+/// What the background reader needs to answer the server
+struct Connection {
+    writer: Writer,
+    pending_requests: PendingRequests,
+    diagnostics: Diagnostics,
+    settings: Value,
+    workspace_folders: Option<Vec<WorkspaceFolder>>,
 }
 
 impl LspClient {
@@ -33,14 +68,30 @@ impl LspClient {
     /// * `args` - Command-line arguments for the server
     /// * `root_uri` - Root URI for the workspace
     pub async fn new(server_cmd: &str, args: &[&str], root_uri: Option<Url>) -> Result<Self> {
-        info!("Starting LSP server: {} {:?}", server_cmd, args);
+        let mut builder = LspClientBuilder::new(server_cmd).args(args.iter().copied());
+        builder.root_uri = root_uri;
+        builder.build().await
+    }
+
+    /// Spawn the server described by `options` and initialize it
+    async fn spawn(options: LspClientBuilder) -> Result<Self> {
+        info!("Starting LSP server: {} {:?}", options.command, options.args);
 
-        // Spawn the LSP server process
-        let mut child = Command::new(server_cmd)
-            .args(args)
+        let mut command = Command::new(&options.command);
+        command
+            .args(&options.args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(dir) = options
+            .root_uri
+            .as_ref()
+            .and_then(|uri| uri.to_file_path().ok())
+        {
+            command.current_dir(dir);
+        }
+        let mut child = command
             .spawn()
             .map_err(|e| LspError::ProcessError(format!("Failed to spawn server: {}", e)))?;
 
@@ -52,72 +103,125 @@ impl LspClient {
             .stdout
             .take()
             .ok_or_else(|| LspError::ProcessError("Failed to get stdout".to_string()))?;
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(log_stderr(stderr, options.command.clone()));
+        }
 
-        let stdin = Arc::new(Mutex::new(tokio::process::ChildStdin::from_std(stdin)?));
-        let stdout = tokio::process::ChildStdout::from_std(stdout)?;
+        let mut client = Self::start(stdout, stdin, &options);
+        client.process_id = child.id();
+        client.server_process = Some(Arc::new(Mutex::new(child)));
 
-        let pending_requests = Arc::new(Mutex::new(HashMap::new()));
-        let diagnostics = Arc::new(RwLock::new(HashMap::new()));
-        let capabilities = Arc::new(RwLock::new(None));
+        if let Err(e) = client.initialize(&options).await {
+            client.kill().await;
+            return Err(e);
+        }
+        Ok(client)
+    }
 
-        let client = Self {
-            stdin: stdin.clone(),
-            request_id: AtomicU64::new(1),
-            capabilities: capabilities.clone(),
+    /// Connect to a server over `reader` and `writer` and initialize it
+    async fn connect<R, W>(reader: R, writer: W, options: LspClientBuilder) -> Result<Self>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let client = Self::start(reader, writer, &options);
+        client.initialize(&options).await?;
+        Ok(client)
+    }
+
+    /// Client over `reader` and `writer`, with its reader task running
+    fn start<R, W>(reader: R, writer: W, options: &LspClientBuilder) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let writer: Writer = Arc::new(Mutex::new(Box::new(writer)));
+        let pending_requests = PendingRequests::default();
+        let diagnostics = Diagnostics::default();
+
+        let connection = Connection {
+            writer: writer.clone(),
             pending_requests: pending_requests.clone(),
             diagnostics: diagnostics.clone(),
-            server_process: Arc::new(Mutex::new(child)),
+            settings: options.settings.clone(),
+            workspace_folders: options.workspace_folders(),
         };
+        tokio::spawn(connection.read_loop(BufReader::new(reader)));
 
-        // Spawn background task to handle server responses
-        tokio::spawn(Self::handle_server_output(stdout, pending_requests, diagnostics));
-
-        // Initialize the server
-        client.initialize(root_uri).await?;
-
-        Ok(client)
+        Self {
+            writer,
+            request_id: AtomicU64::new(1),
+            request_timeout: options.request_timeout,
+            capabilities: Arc::new(RwLock::new(None)),
+            pending_requests,
+            diagnostics,
+            server_process: None,
+            process_id: None,
+        }
     }
 
     /// Initialize the LSP server
-    async fn initialize(&self, root_uri: Option<Url>) -> Result<()> {
+    async fn initialize(&self, options: &LspClientBuilder) -> Result<()> {
         debug!("Initializing LSP server");
 
-        let workspace_folders = root_uri.as_ref().map(|uri| {
-            vec![WorkspaceFolder {
-                uri: uri.clone(),
-                name: uri
-                    .path()
-                    .rsplit('/')
-                    .next()
-                    .unwrap_or("workspace")
-                    .to_string(),
-            }]
-        });
-
-        let init_params = InitializeParams {
-            process_id: Some(std::process::id()),
-            workspace_folders,
-            capabilities: ClientCapabilities {
-                text_document: Some(TextDocumentClientCapabilities {
-                    completion: Some(CompletionClientCapabilities {
-                        completion_item: Some(CompletionItemCapability {
-                            snippet_support: Some(true),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    }),
-                    hover: Some(HoverClientCapabilities {
-                        content_format: Some(vec![MarkupKind::Markdown, MarkupKind::PlainText]),
+        let capabilities = ClientCapabilities {
+            workspace: Some(WorkspaceClientCapabilities {
+                configuration: Some(true),
+                workspace_folders: Some(true),
+                did_change_configuration: Some(DynamicRegistrationClientCapabilities {
+                    dynamic_registration: Some(false),
+                }),
+                ..Default::default()
+            }),
+            text_document: Some(TextDocumentClientCapabilities {
+                synchronization: Some(TextDocumentSyncClientCapabilities {
+                    did_save: Some(true),
+                    ..Default::default()
+                }),
+                completion: Some(CompletionClientCapabilities {
+                    completion_item: Some(CompletionItemCapability {
+                        snippet_support: Some(true),
                         ..Default::default()
                     }),
-                    definition: Some(GotoCapability {
-                        link_support: Some(true),
-                        ..Default::default()
+                    ..Default::default()
+                }),
+                hover: Some(HoverClientCapabilities {
+                    content_format: Some(vec![MarkupKind::Markdown, MarkupKind::PlainText]),
+                    ..Default::default()
+                }),
+                definition: Some(GotoCapability {
+                    link_support: Some(true),
+                    ..Default::default()
+                }),
+                publish_diagnostics: Some(PublishDiagnosticsClientCapabilities::default()),
+                // Without resolve support servers send each action's edit
+                // along with it
+                code_action: Some(CodeActionClientCapabilities {
+                    code_action_literal_support: Some(CodeActionLiteralSupport {
+                        code_action_kind: CodeActionKindLiteralSupport {
+                            value_set: vec![CodeActionKind::QUICKFIX.as_str().to_string()],
+                        },
                     }),
+                    is_preferred_support: Some(true),
                     ..Default::default()
                 }),
                 ..Default::default()
-            },
+            }),
+            ..Default::default()
+        };
+
+        // `root_uri` is deprecated in favor of workspace folders, but many
+        // servers still only look at it
+        #[allow(deprecated)]
+        let init_params = InitializeParams {
+            process_id: Some(std::process::id()),
+            root_uri: options.root_uri.clone(),
+            workspace_folders: options.workspace_folders(),
+            client_info: Some(ClientInfo {
+                name: "ait42-editor".to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+            capabilities,
             ..Default::default()
         };
 
@@ -130,11 +234,28 @@ impl LspClient {
         self.send_notification("initialized", InitializedParams {})
             .await?;
 
-        info!("LSP server initialized successfully");
+        if options.has_settings() {
+            let params = DidChangeConfigurationParams {
+                settings: options.settings.clone(),
+            };
+            self.send_notification("workspace/didChangeConfiguration", params)
+                .await?;
+        }
+
+        match response.server_info {
+            Some(server) => info!(
+                "LSP server initialized: {} {}",
+                server.name,
+                server.version.unwrap_or_default()
+            ),
+            None => info!("LSP server initialized successfully"),
+        }
         Ok(())
     }
 
     /// Send a request and wait for response
+    ///
+    /// Gives up after the request timeout, telling the server to cancel.
     async fn send_request<P: Serialize, R: DeserializeOwned>(
         &self,
         method: &str,
@@ -142,156 +263,91 @@ impl LspClient {
     ) -> Result<R> {
         let id = self.request_id.fetch_add(1, Ordering::SeqCst);
 
-        let request = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": id,
-            "method": method,
-            "params": params,
-        });
-
         // Create channel for response
-        let (tx, mut rx) = mpsc::channel(1);
+        let (tx, rx) = oneshot::channel();
         self.pending_requests.lock().await.insert(id, tx);
 
-        // Send request
-        self.send_message(&request).await?;
-
-        // Wait for response
-        let response = rx
-            .recv()
-            .await
-            .ok_or_else(|| LspError::CommunicationError("No response received".to_string()))?;
-
-        // Check for error
-        if let Some(error) = response.get("error") {
-            return Err(LspError::CommunicationError(format!("Server error: {}", error)));
+        let request = Message::Request {
+            id: id.into(),
+            method: method.to_string(),
+            params: serde_json::to_value(params)?,
+        };
+        if let Err(e) = self.send_message(&request).await {
+            self.pending_requests.lock().await.remove(&id);
+            return Err(e);
         }
 
-        // Extract result
-        let result = response
-            .get("result")
-            .ok_or_else(|| LspError::InvalidResponse("Missing result field".to_string()))?;
+        // Wait for response
+        let result = match tokio::time::timeout(self.request_timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => {
+                return Err(LspError::CommunicationError(format!(
+                    "Server exited before answering {}",
+                    method
+                )))
+            }
+            Err(_) => {
+                self.pending_requests.lock().await.remove(&id);
+                let cancel = CancelParams {
+                    id: NumberOrString::Number(id as i32),
+                };
+                if let Err(e) = self.send_notification("$/cancelRequest", cancel).await {
+                    debug!("Failed to cancel {}: {}", method, e);
+                }
+                return Err(LspError::Timeout(method.to_string()));
+            }
+        };
 
-        serde_json::from_value(result.clone()).map_err(Into::into)
+        let result = result.map_err(|error| {
+            LspError::CommunicationError(format!("Server error for {}: {}", method, error))
+        })?;
+        serde_json::from_value(result).map_err(Into::into)
     }
 
     /// Send a notification (no response expected)
     async fn send_notification<P: Serialize>(&self, method: &str, params: P) -> Result<()> {
-        let notification = serde_json::json!({
-            "jsonrpc": "2.0",
-            "method": method,
-            "params": params,
-        });
+        let notification = Message::Notification {
+            method: method.to_string(),
+            params: serde_json::to_value(params)?,
+        };
 
         self.send_message(&notification).await
     }
 
-    /// Send raw JSON-RPC message
-    async fn send_message(&self, message: &serde_json::Value) -> Result<()> {
-        let content = serde_json::to_string(message)?;
-        let header = format!("Content-Length: {}\r\n\r\n", content.len());
-
-        let mut stdin = self.stdin.lock().await;
-        stdin.write_all(header.as_bytes()).await?;
-        stdin.write_all(content.as_bytes()).await?;
-        stdin.flush().await?;
-
-        debug!("Sent LSP message: {}", method_from_message(message));
+    /// Send a JSON-RPC message
+    async fn send_message(&self, message: &Message) -> Result<()> {
+        write(&self.writer, message).await?;
+        debug!("Sent LSP message: {}", method_from_message(&message.to_value()));
         Ok(())
     }
 
-    /// Handle server output in background
-    async fn handle_server_output(
-        stdout: ChildStdout,
-        pending_requests: Arc<Mutex<HashMap<u64, mpsc::Sender<serde_json::Value>>>>,
-        diagnostics: Arc<RwLock<HashMap<Url, Vec<Diagnostic>>>>,
-    ) {
-        let mut reader = BufReader::new(stdout);
-        let mut buffer = String::new();
-
-        loop {
-            buffer.clear();
-
-            // Read headers
-            let mut content_length = 0;
-            loop {
-                if reader.read_line(&mut buffer).await.is_err() {
-                    return;
-                }
-
-                if buffer == "\r\n" {
-                    break;
-                }
-
-                if let Some(len) = buffer.strip_prefix("Content-Length: ") {
-                    content_length = len.trim().parse().unwrap_or(0);
-                }
-
-                buffer.clear();
-            }
-
-            if content_length == 0 {
-                continue;
-            }
-
-            // Read content
-            let mut content = vec![0u8; content_length];
-            if reader.get_mut().read_exact(&mut content).await.is_err() {
-                return;
-            }
-
-            let message: serde_json::Value = match serde_json::from_slice(&content) {
-                Ok(msg) => msg,
-                Err(e) => {
-                    error!("Failed to parse LSP message: {}", e);
-                    continue;
-                }
-            };
-
-            debug!("Received LSP message: {:?}", message);
-
-            // Handle response
-            if let Some(id) = message.get("id").and_then(|v| v.as_u64()) {
-                if let Some(tx) = pending_requests.lock().await.remove(&id) {
-                    let _ = tx.send(message.clone()).await;
-                }
-            }
-
-            // Handle notifications
-            if let Some(method) = message.get("method").and_then(|v| v.as_str()) {
-                if method == "textDocument/publishDiagnostics" {
-                    if let Some(params) = message.get("params") {
-                        Self::handle_diagnostics(params, &diagnostics).await;
-                    }
-                }
-            }
-        }
+    /// Whether the server announced a capability
+    async fn supports(&self, provided: impl FnOnce(&ServerCapabilities) -> bool) -> bool {
+        self.capabilities
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(provided)
     }
 
-    /// Handle diagnostic notifications
-    async fn handle_diagnostics(
-        params: &serde_json::Value,
-        diagnostics: &Arc<RwLock<HashMap<Url, Vec<Diagnostic>>>>,
-    ) {
-        if let Ok(publish_params) =
-            serde_json::from_value::<PublishDiagnosticsParams>(params.clone())
-        {
-            debug!(
-                "Received {} diagnostics for {}",
-                publish_params.diagnostics.len(),
-                publish_params.uri
-            );
-            diagnostics
-                .write()
-                .await
-                .insert(publish_params.uri, publish_params.diagnostics);
-        }
+    /// Which document notifications the server wants
+    async fn document_sync(&self) -> DocumentSync {
+        self.capabilities
+            .read()
+            .await
+            .as_ref()
+            .map(DocumentSync::from_capabilities)
+            .unwrap_or_default()
     }
 
     // === Text Document Synchronization ===
 
     /// Notify server that a document was opened
     pub async fn did_open(&self, uri: Url, text: String, language_id: String) -> Result<()> {
+        if !self.document_sync().await.open_close {
+            return Ok(());
+        }
+
         let params = DidOpenTextDocumentParams {
             text_document: TextDocumentItem {
                 uri,
@@ -305,12 +361,19 @@ impl LspClient {
     }
 
     /// Notify server of document changes
+    ///
+    /// Changes replacing the whole document are accepted by servers that
+    /// sync incrementally as well.
     pub async fn did_change(
         &self,
         uri: Url,
         version: i32,
         changes: Vec<TextDocumentContentChangeEvent>,
     ) -> Result<()> {
+        if self.document_sync().await.change == TextDocumentSyncKind::NONE {
+            return Ok(());
+        }
+
         let params = DidChangeTextDocumentParams {
             text_document: VersionedTextDocumentIdentifier { uri, version },
             content_changes: changes,
@@ -321,10 +384,16 @@ impl LspClient {
     }
 
     /// Notify server that a document was saved
+    ///
+    /// The text is only sent to servers that asked for it.
     pub async fn did_save(&self, uri: Url, text: Option<String>) -> Result<()> {
+        let Some(include_text) = self.document_sync().await.save else {
+            return Ok(());
+        };
+
         let params = DidSaveTextDocumentParams {
             text_document: TextDocumentIdentifier { uri },
-            text,
+            text: text.filter(|_| include_text),
         };
 
         self.send_notification("textDocument/didSave", params).await
@@ -332,6 +401,10 @@ impl LspClient {
 
     /// Notify server that a document was closed
     pub async fn did_close(&self, uri: Url) -> Result<()> {
+        if !self.document_sync().await.open_close {
+            return Ok(());
+        }
+
         let params = DidCloseTextDocumentParams {
             text_document: TextDocumentIdentifier { uri },
         };
@@ -344,6 +417,13 @@ impl LspClient {
 
     /// Request completion suggestions
     pub async fn completion(&self, uri: Url, position: Position) -> Result<Vec<CompletionItem>> {
+        if !self
+            .supports(|caps| caps.completion_provider.is_some())
+            .await
+        {
+            return Ok(Vec::new());
+        }
+
         let params = CompletionParams {
             text_document_position: TextDocumentPositionParams {
                 text_document: TextDocumentIdentifier { uri },
@@ -351,7 +431,10 @@ impl LspClient {
             },
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
-            context: None,
+            context: Some(CompletionContext {
+                trigger_kind: CompletionTriggerKind::INVOKED,
+                trigger_character: None,
+            }),
         };
 
         let response: Option<CompletionResponse> =
@@ -366,6 +449,20 @@ impl LspClient {
 
     /// Request hover information
     pub async fn hover(&self, uri: Url, position: Position) -> Result<Option<Hover>> {
+        let supported = self
+            .supports(|caps| {
+                matches!(
+                    caps.hover_provider,
+                    Some(
+                        HoverProviderCapability::Simple(true) | HoverProviderCapability::Options(_)
+                    )
+                )
+            })
+            .await;
+        if !supported {
+            return Ok(None);
+        }
+
         let params = HoverParams {
             text_document_position_params: TextDocumentPositionParams {
                 text_document: TextDocumentIdentifier { uri },
@@ -383,6 +480,15 @@ impl LspClient {
         uri: Url,
         position: Position,
     ) -> Result<Option<GotoDefinitionResponse>> {
+        let supported = self
+            .supports(|caps| {
+                matches!(caps.definition_provider, Some(OneOf::Left(true) | OneOf::Right(_)))
+            })
+            .await;
+        if !supported {
+            return Ok(None);
+        }
+
         let params = GotoDefinitionParams {
             text_document_position_params: TextDocumentPositionParams {
                 text_document: TextDocumentIdentifier { uri },
@@ -395,6 +501,47 @@ impl LspClient {
         self.send_request("textDocument/definition", params).await
     }
 
+    /// Request the quick fixes for `diagnostics` in `range`
+    ///
+    /// Servers may answer with bare commands as well as code actions.
+    pub async fn code_actions(
+        &self,
+        uri: Url,
+        range: Range,
+        diagnostics: Vec<Diagnostic>,
+    ) -> Result<Vec<CodeActionOrCommand>> {
+        let supported = self
+            .supports(|caps| {
+                matches!(
+                    caps.code_action_provider,
+                    Some(
+                        CodeActionProviderCapability::Simple(true)
+                            | CodeActionProviderCapability::Options(_)
+                    )
+                )
+            })
+            .await;
+        if !supported {
+            return Ok(Vec::new());
+        }
+
+        let params = CodeActionParams {
+            text_document: TextDocumentIdentifier { uri },
+            range,
+            context: CodeActionContext {
+                diagnostics,
+                only: Some(vec![CodeActionKind::QUICKFIX]),
+                trigger_kind: Some(CodeActionTriggerKind::INVOKED),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let response: Option<CodeActionResponse> =
+            self.send_request("textDocument/codeAction", params).await?;
+        Ok(response.unwrap_or_default())
+    }
+
     /// Get diagnostics for a document
     pub async fn diagnostics(&self, uri: &Url) -> Result<Vec<Diagnostic>> {
         Ok(self
@@ -411,41 +558,245 @@ impl LspClient {
         self.capabilities.read().await.clone()
     }
 
+    /// OS process ID of the server, if it runs as a child process
+    pub fn process_id(&self) -> Option<u32> {
+        self.process_id
+    }
+
     /// Shutdown the server
+    ///
+    /// Runs the shutdown handshake and kills the server if it does not exit
+    /// shortly after.
     pub async fn shutdown(&self) -> Result<()> {
         info!("Shutting down LSP server");
 
         // Send shutdown request
-        let _: serde_json::Value = self.send_request("shutdown", ()).await?;
+        let _: Value = self.send_request("shutdown", Value::Null).await?;
 
         // Send exit notification
-        self.send_notification("exit", ()).await?;
+        self.send_notification("exit", Value::Null).await?;
 
-        // Kill the process if still running
-        let mut process = self.server_process.lock().await;
-        let _ = process.kill();
+        if let Some(process) = &self.server_process {
+            let mut process = process.lock().await;
+            if tokio::time::timeout(EXIT_TIMEOUT, process.wait())
+                .await
+                .is_err()
+            {
+                warn!("LSP server did not exit, killing it");
+                let _ = process.kill().await;
+            }
+        }
 
         Ok(())
     }
+
+    /// Kill the server process without the shutdown handshake
+    ///
+    /// Used when the server is unresponsive.
+    pub async fn kill(&self) {
+        if let Some(process) = &self.server_process {
+            let _ = process.lock().await.kill().await;
+        }
+    }
 }
 
-impl Drop for LspClient {
-    fn drop(&mut self) {
-        // Attempt graceful shutdown (fire and forget)
-        let stdin = self.stdin.clone();
-        tokio::spawn(async move {
-            let exit = serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "exit",
-            });
-            let content = serde_json::to_string(&exit).unwrap();
-            let header = format!("Content-Length: {}\r\n\r\n", content.len());
-
-            let mut stdin = stdin.lock().await;
-            let _ = stdin.write_all(header.as_bytes()).await;
-            let _ = stdin.write_all(content.as_bytes()).await;
-            let _ = stdin.flush().await;
-        });
+impl Connection {
+    /// Handle everything the server sends until its output ends
+    async fn read_loop<R: AsyncRead + Unpin>(self, mut reader: BufReader<R>) {
+        loop {
+            let message = match transport::read_message(&mut reader).await {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(LspError::SerializationError(e)) => {
+                    error!("Failed to parse LSP message: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    error!("LSP transport error: {}", e);
+                    break;
+                }
+            };
+
+            debug!("Received LSP message: {:?}", message);
+
+            match Message::from_value(message) {
+                Some(Message::Response { id, result }) => {
+                    let sender = match id.as_u64() {
+                        Some(id) => self.pending_requests.lock().await.remove(&id),
+                        None => None,
+                    };
+                    match sender {
+                        Some(tx) => {
+                            let _ = tx.send(result);
+                        }
+                        None => debug!("Ignoring response to unknown request {}", id),
+                    }
+                }
+                Some(Message::Request { id, method, params }) => {
+                    let response = Message::Response {
+                        id,
+                        result: self.handle_request(&method, params),
+                    };
+                    if let Err(e) = write(&self.writer, &response).await {
+                        warn!("Failed to answer {}: {}", method, e);
+                    }
+                }
+                Some(Message::Notification { method, params }) => {
+                    self.handle_notification(&method, params).await;
+                }
+                None => warn!("Ignoring invalid JSON-RPC message"),
+            }
+        }
+
+        // Dropping the senders fails the requests still waiting
+        self.pending_requests.lock().await.clear();
+        info!("LSP server closed its output");
+    }
+
+    /// Answer a request the server made of the client
+    fn handle_request(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> std::result::Result<Value, ResponseError> {
+        match method {
+            "workspace/configuration" => {
+                let params: ConfigurationParams =
+                    serde_json::from_value(params).map_err(|e| ResponseError {
+                        code: error_codes::INVALID_PARAMS,
+                        message: e.to_string(),
+                        data: None,
+                    })?;
+                Ok(Value::Array(
+                    params
+                        .items
+                        .iter()
+                        .map(|item| configuration_section(&self.settings, item.section.as_deref()))
+                        .collect(),
+                ))
+            }
+            "workspace/workspaceFolders" => {
+                Ok(serde_json::to_value(&self.workspace_folders).unwrap_or(Value::Null))
+            }
+            "workspace/applyEdit" => Ok(serde_json::json!({
+                "applied": false,
+                "failureReason": "Edits from the server are not supported",
+            })),
+            "client/registerCapability"
+            | "client/unregisterCapability"
+            | "window/workDoneProgress/create"
+            | "window/showMessageRequest" => Ok(Value::Null),
+            _ => Err(ResponseError {
+                code: error_codes::METHOD_NOT_FOUND,
+                message: format!("Unhandled method: {}", method),
+                data: None,
+            }),
+        }
+    }
+
+    /// Handle a notification from the server
+    async fn handle_notification(&self, method: &str, params: Value) {
+        match method {
+            "textDocument/publishDiagnostics" => {
+                match serde_json::from_value::<PublishDiagnosticsParams>(params) {
+                    Ok(publish_params) => {
+                        debug!(
+                            "Received {} diagnostics for {}",
+                            publish_params.diagnostics.len(),
+                            publish_params.uri
+                        );
+                        self.diagnostics
+                            .write()
+                            .await
+                            .insert(publish_params.uri, publish_params.diagnostics);
+                    }
+                    Err(e) => warn!("Invalid diagnostics from LSP server: {}", e),
+                }
+            }
+            "window/logMessage" | "window/showMessage" => {
+                if let Ok(params) = serde_json::from_value::<LogMessageParams>(params) {
+                    match params.typ {
+                        MessageType::ERROR => error!("LSP server: {}", params.message),
+                        MessageType::WARNING => warn!("LSP server: {}", params.message),
+                        _ => debug!("LSP server: {}", params.message),
+                    }
+                }
+            }
+            _ => debug!("Ignoring LSP notification {}", method),
+        }
+    }
+}
+
+/// Write `message` to the server
+async fn write(writer: &Writer, message: &Message) -> Result<()> {
+    let mut writer = writer.lock().await;
+    transport::write_message(&mut *writer, &message.to_value()).await
+}
+
+/// Log what the server writes to stderr
+async fn log_stderr(stderr: ChildStderr, command: String) {
+    let mut lines = BufReader::new(stderr).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        debug!("{}: {}", command, line);
+    }
+}
+
+/// Value of the dotted `section` of `settings`, all of them without one
+///
+/// Sections missing from the settings are null, which servers read as
+/// "use your defaults".
+fn configuration_section(settings: &Value, section: Option<&str>) -> Value {
+    let Some(section) = section.filter(|section| !section.is_empty()) else {
+        return settings.clone();
+    };
+    section
+        .split('.')
+        .try_fold(settings, |value, key| value.get(key))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+/// Document notifications a server asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DocumentSync {
+    open_close: bool,
+    change: TextDocumentSyncKind,
+    /// Whether to send `didSave`, with the text if `true`
+    save: Option<bool>,
+}
+
+impl Default for DocumentSync {
+    fn default() -> Self {
+        Self {
+            open_close: false,
+            change: TextDocumentSyncKind::NONE,
+            save: None,
+        }
+    }
+}
+
+impl DocumentSync {
+    fn from_capabilities(capabilities: &ServerCapabilities) -> Self {
+        match &capabilities.text_document_sync {
+            None => Self::default(),
+            // The bare kind is shorthand for open, change, save and close
+            Some(TextDocumentSyncCapability::Kind(kind)) => Self {
+                open_close: *kind != TextDocumentSyncKind::NONE,
+                change: *kind,
+                save: (*kind != TextDocumentSyncKind::NONE).then_some(false),
+            },
+            Some(TextDocumentSyncCapability::Options(options)) => Self {
+                open_close: options.open_close.unwrap_or(false),
+                change: options.change.unwrap_or(TextDocumentSyncKind::NONE),
+                save: match &options.save {
+                    Some(TextDocumentSyncSaveOptions::Supported(true)) => Some(false),
+                    Some(TextDocumentSyncSaveOptions::SaveOptions(save)) => {
+                        Some(save.include_text.unwrap_or(false))
+                    }
+                    _ => None,
+                },
+            },
+        }
     }
 }
 
@@ -454,7 +805,7 @@ fn method_from_message(message: &serde_json::Value) -> &str {
     message
         .get("method")
         .and_then(|v| v.as_str())
-        .unwrap_or("unknown")
+        .unwrap_or("response")
 }
 
 /// Builder for LSP client
@@ -462,6 +813,8 @@ pub struct LspClientBuilder {
     command: String,
     args: Vec<String>,
     root_uri: Option<Url>,
+    settings: Value,
+    request_timeout: Duration,
 }
 
 impl LspClientBuilder {
@@ -470,6 +823,8 @@ impl LspClientBuilder {
             command: command.into(),
             args: Vec::new(),
             root_uri: None,
+            settings: Value::Null,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
         }
     }
 
@@ -492,24 +847,80 @@ impl LspClientBuilder {
         self
     }
 
+    /// Settings keyed by section, such as `{"rust-analyzer": {..}}`
+    ///
+    /// Sent after initialization and served to `workspace/configuration`
+    /// requests.
+    pub fn settings(mut self, settings: Value) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// How long requests wait for their response
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Spawn the server and initialize it
     pub async fn build(self) -> Result<LspClient> {
-        let args: Vec<&str> = self.args.iter().map(|s| s.as_str()).collect();
-        LspClient::new(&self.command, &args, self.root_uri).await
+        LspClient::spawn(self).await
+    }
+
+    /// Initialize a server reached over `reader` and `writer` instead of
+    /// spawning the command, such as one listening on a socket
+    pub async fn connect<R, W>(self, reader: R, writer: W) -> Result<LspClient>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        LspClient::connect(reader, writer, self).await
+    }
+
+    fn has_settings(&self) -> bool {
+        match &self.settings {
+            Value::Null => false,
+            Value::Object(settings) => !settings.is_empty(),
+            _ => true,
+        }
+    }
+
+    fn workspace_folders(&self) -> Option<Vec<WorkspaceFolder>> {
+        self.root_uri.as_ref().map(|uri| {
+            vec![WorkspaceFolder {
+                uri: uri.clone(),
+                name: uri
+                    .path()
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or("workspace")
+                    .to_string(),
+            }]
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transport::{read_message, write_message};
+    use serde_json::json;
+    use tokio::io::DuplexStream;
 
     #[test]
     fn test_builder() {
         let builder = LspClientBuilder::new("rust-analyzer")
             .arg("--stdio")
-            .root_uri(Url::parse("file:///tmp/test").unwrap());
+            .root_uri(Url::parse("file:///tmp/test/").unwrap());
 
         assert_eq!(builder.command, "rust-analyzer");
         assert_eq!(builder.args, vec!["--stdio"]);
+        assert_eq!(builder.request_timeout, DEFAULT_REQUEST_TIMEOUT);
+        assert_eq!(builder.workspace_folders().unwrap()[0].name, "test");
+        assert!(!builder.has_settings());
+        assert!(!builder.settings(json!({})).has_settings());
     }
 
     #[test]
@@ -520,4 +931,228 @@ mod tests {
         });
         assert_eq!(method_from_message(&msg), "textDocument/didOpen");
     }
+
+    #[test]
+    fn test_configuration_section() {
+        let settings = json!({ "rust-analyzer": { "checkOnSave": { "command": "clippy" } } });
+
+        assert_eq!(
+            configuration_section(&settings, Some("rust-analyzer.checkOnSave")),
+            json!({ "command": "clippy" })
+        );
+        assert_eq!(configuration_section(&settings, Some("python")), Value::Null);
+        assert_eq!(configuration_section(&settings, None), settings);
+    }
+
+    #[test]
+    fn test_document_sync() {
+        let mut capabilities = ServerCapabilities::default();
+        assert_eq!(DocumentSync::from_capabilities(&capabilities), DocumentSync::default());
+
+        capabilities.text_document_sync =
+            Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::INCREMENTAL));
+        let sync = DocumentSync::from_capabilities(&capabilities);
+        assert!(sync.open_close);
+        assert_eq!(sync.save, Some(false));
+
+        capabilities.text_document_sync =
+            Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                    include_text: Some(true),
+                })),
+                ..Default::default()
+            }));
+        let sync = DocumentSync::from_capabilities(&capabilities);
+        assert_eq!(sync.change, TextDocumentSyncKind::FULL);
+        assert_eq!(sync.save, Some(true));
+    }
+
+    async fn respond<W: AsyncWrite + Unpin>(writer: &mut W, id: &Value, result: Value) {
+        let response = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+        write_message(writer, &response).await.unwrap();
+    }
+
+    /// Minimal server answering initialize, hover, code actions and
+    /// shutdown, returning everything it received
+    async fn fake_server(stream: DuplexStream, capabilities: Value) -> Vec<Value> {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut reader = tokio::io::BufReader::new(reader);
+        let mut received = Vec::new();
+
+        while let Some(message) = read_message(&mut reader).await.unwrap() {
+            received.push(message.clone());
+            let id = &message["id"];
+            match message["method"].as_str() {
+                Some("initialize") => {
+                    respond(&mut writer, id, json!({ "capabilities": capabilities })).await
+                }
+                Some("textDocument/hover") => {
+                    // Ask for the client's settings first, as many servers do
+                    let request = json!({
+                        "jsonrpc": "2.0",
+                        "id": "config",
+                        "method": "workspace/configuration",
+                        "params": { "items": [{ "section": "fake.hover" }] },
+                    });
+                    write_message(&mut writer, &request).await.unwrap();
+                    let reply = read_message(&mut reader).await.unwrap().unwrap();
+                    let greeting = reply["result"][0]["greeting"].clone();
+                    respond(&mut writer, id, json!({ "contents": greeting })).await;
+                }
+                Some("textDocument/codeAction") => {
+                    let diagnostic = &message["params"]["context"]["diagnostics"][0];
+                    let action = json!({
+                        "title": format!("Fix {}", diagnostic["message"].as_str().unwrap()),
+                        "kind": "quickfix",
+                        "isPreferred": true,
+                        "edit": { "changes": { "file:///tmp/a.rs": [{
+                            "range": diagnostic["range"],
+                            "newText": "fixed",
+                        }] } },
+                    });
+                    respond(&mut writer, id, json!([action])).await;
+                }
+                Some("shutdown") => respond(&mut writer, id, Value::Null).await,
+                Some("exit") => break,
+                // Notifications, and requests left unanswered
+                _ => {}
+            }
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_session() {
+        let (client_end, server_end) = tokio::io::duplex(64 * 1024);
+        let capabilities = json!({ "hoverProvider": true, "textDocumentSync": 1 });
+        let server = tokio::spawn(fake_server(server_end, capabilities));
+
+        let (reader, writer) = tokio::io::split(client_end);
+        let client = LspClientBuilder::new("fake")
+            .settings(json!({ "fake": { "hover": { "greeting": "hello" } } }))
+            .request_timeout(Duration::from_millis(200))
+            .connect(reader, writer)
+            .await
+            .unwrap();
+        assert!(client
+            .capabilities()
+            .await
+            .unwrap()
+            .hover_provider
+            .is_some());
+        assert_eq!(client.process_id(), None);
+
+        let uri = Url::parse("file:///tmp/a.rs").unwrap();
+        client
+            .did_open(uri.clone(), String::new(), "rust".to_string())
+            .await
+            .unwrap();
+        let hover = client
+            .hover(uri.clone(), Position::new(0, 0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(hover.contents, HoverContents::Scalar(MarkedString::String("hello".into())));
+
+        // Not announced by the server, so not requested
+        assert!(client
+            .completion(uri.clone(), Position::new(0, 0))
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Unanswered requests time out and are cancelled
+        let result: Result<Value> = client.send_request("custom/slow", Value::Null).await;
+        assert!(matches!(result, Err(LspError::Timeout(ref method)) if method == "custom/slow"));
+
+        client.shutdown().await.unwrap();
+        let received = server.await.unwrap();
+        let methods: Vec<&str> = received
+            .iter()
+            .filter_map(|m| m["method"].as_str())
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                "initialize",
+                "initialized",
+                "workspace/didChangeConfiguration",
+                "textDocument/didOpen",
+                "textDocument/hover",
+                "custom/slow",
+                "$/cancelRequest",
+                "shutdown",
+                "exit",
+            ]
+        );
+        assert_eq!(received[2]["params"]["settings"]["fake"]["hover"]["greeting"], "hello");
+        assert_eq!(received[7]["params"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_code_actions() {
+        let (client_end, server_end) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(fake_server(server_end, json!({ "codeActionProvider": true })));
+
+        let (reader, writer) = tokio::io::split(client_end);
+        let client = LspClientBuilder::new("fake")
+            .connect(reader, writer)
+            .await
+            .unwrap();
+        let uri = Url::parse("file:///tmp/a.rs").unwrap();
+        let range = Range::new(Position::new(1, 4), Position::new(1, 9));
+        let diagnostic = Diagnostic::new_simple(range, "unused variable".to_string());
+
+        let actions = client
+            .code_actions(uri.clone(), range, vec![diagnostic])
+            .await
+            .unwrap();
+        let [CodeActionOrCommand::CodeAction(action)] = actions.as_slice() else {
+            panic!("expected one code action: {:?}", actions);
+        };
+        assert_eq!(action.title, "Fix unused variable");
+        assert_eq!(action.is_preferred, Some(true));
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(edits[0].range, range);
+        assert_eq!(edits[0].new_text, "fixed");
+
+        client.shutdown().await.unwrap();
+        let received = server.await.unwrap();
+        assert_eq!(received[2]["params"]["context"]["only"], json!(["quickfix"]));
+    }
+
+    #[tokio::test]
+    async fn test_server_exit_fails_pending_requests() {
+        let (client_end, server_end) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let (reader, mut writer) = tokio::io::split(server_end);
+            let mut reader = tokio::io::BufReader::new(reader);
+            let initialize = read_message(&mut reader).await.unwrap().unwrap();
+            let capabilities = json!({ "capabilities": { "hoverProvider": true } });
+            respond(&mut writer, &initialize["id"], capabilities).await;
+            read_message(&mut reader).await.unwrap(); // initialized
+            read_message(&mut reader).await.unwrap(); // hover, never answered
+        });
+
+        let (reader, writer) = tokio::io::split(client_end);
+        let client = LspClientBuilder::new("fake")
+            .connect(reader, writer)
+            .await
+            .unwrap();
+        let uri = Url::parse("file:///tmp/a.rs").unwrap();
+
+        let result = client.hover(uri, Position::new(0, 0)).await;
+        assert!(matches!(result, Err(LspError::CommunicationError(_))));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_spawn_failure() {
+        let result = LspClientBuilder::new("ait42-no-such-language-server")
+            .build()
+            .await;
+        assert!(matches!(result, Err(LspError::ProcessError(_))));
+    }
 }
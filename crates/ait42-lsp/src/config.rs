@@ -2,6 +2,7 @@
 //!
 //! Configuration for LSP servers and their settings.
 
+use crate::watchdog::WatchdogConfig;
 use lsp_types::Url;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,6 +12,18 @@ use std::collections::HashMap;
 pub struct LspConfig {
     /// Server configurations by language ID
     pub servers: HashMap<String, LspServerConfig>,
+
+    /// Resource watchdog thresholds
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+
+    /// Seconds a request waits for the server's response
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+}
+
+fn default_request_timeout_secs() -> u64 {
+    crate::client::DEFAULT_REQUEST_TIMEOUT.as_secs()
 }
 
 /// Configuration for a single LSP server
@@ -118,7 +131,11 @@ impl Default for LspConfig {
             },
         );
 
-        Self { servers }
+        Self {
+            servers,
+            watchdog: WatchdogConfig::default(),
+            request_timeout_secs: default_request_timeout_secs(),
+        }
     }
 }
 
@@ -147,6 +164,11 @@ impl LspConfig {
     pub fn get_server(&self, language: &str) -> Option<&LspServerConfig> {
         self.servers.get(language)
     }
+
+    /// How long a request waits for the server's response
+    pub fn request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.request_timeout_secs)
+    }
 }
 
 #[cfg(test)]
@@ -216,5 +238,23 @@ command = "clippy"
 
         let config = LspConfig::from_toml(toml).unwrap();
         assert_eq!(config.servers.get("rust").unwrap().command, "rust-analyzer");
+        assert_eq!(config.watchdog, WatchdogConfig::default());
+        assert_eq!(config.request_timeout_secs, 10);
+    }
+
+    #[test]
+    fn test_watchdog_thresholds() {
+        let toml = r#"
+[servers]
+
+[watchdog]
+max_cpu_percent = 150.0
+sustained_samples = 3
+"#;
+
+        let config = LspConfig::from_toml(toml).unwrap();
+        assert_eq!(config.watchdog.max_cpu_percent, 150.0);
+        assert_eq!(config.watchdog.sustained_samples, 3);
+        assert!(config.watchdog.enabled);
     }
 }
@@ -7,12 +7,16 @@ pub mod client;
 pub mod config;
 pub mod manager;
 pub mod position;
+pub mod transport;
+pub mod watchdog;
 
 // Re-exports
 pub use client::{LspClient, LspClientBuilder};
 pub use config::{LspConfig, LspServerConfig};
 pub use manager::LspManager;
 pub use position::{buffer_pos_to_lsp, lsp_pos_to_buffer};
+pub use transport::{Message, ResponseError};
+pub use watchdog::{ProcessSampler, ResourceSample, ResourceWatchdog, WatchdogConfig, WatchdogEvent};
 
 use thiserror::Error;
 
@@ -36,11 +40,17 @@ pub enum LspError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
     #[error("Server not initialized")]
     NotInitialized,
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+
+    #[error("Invalid document path: {0}")]
+    InvalidPath(String),
 }
 
 pub type Result<T> = std::result::Result<T, LspError>;
@@ -3,15 +3,22 @@
 //! Manages multiple LSP clients for different programming languages.
 
 use crate::{LspClient, LspClientBuilder, LspConfig, LspError, Result};
-use std::collections::HashMap;
+use lsp_types::Url;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock as StdRwLock};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// How long a restarting server gets to shut down before it is killed
+const RESTART_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
 /// Manages LSP clients for multiple languages
 pub struct LspManager {
     clients: Arc<RwLock<HashMap<String, Arc<LspClient>>>>,
+    detached: Arc<RwLock<HashSet<Url>>>,
+    root: StdRwLock<Option<Url>>,
     config: LspConfig,
 }
 
@@ -20,10 +27,28 @@ impl LspManager {
     pub fn new(config: LspConfig) -> Self {
         Self {
             clients: Arc::new(RwLock::new(HashMap::new())),
+            detached: Arc::new(RwLock::new(HashSet::new())),
+            root: StdRwLock::new(None),
             config,
         }
     }
 
+    /// Set the workspace root servers are started in
+    ///
+    /// Servers configured with their own `root_uri` keep it. Servers that are
+    /// already running are not affected until they are restarted.
+    pub fn set_workspace_root(&self, root: &Path) -> Result<()> {
+        let uri = Url::from_directory_path(root)
+            .map_err(|_| LspError::InvalidPath(root.display().to_string()))?;
+        *self.root.write().unwrap_or_else(|e| e.into_inner()) = Some(uri);
+        Ok(())
+    }
+
+    /// Workspace root servers are started in
+    pub fn workspace_root(&self) -> Option<Url> {
+        self.root.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     /// Start LSP server for a language
     ///
     /// If a server is already running for this language, it will be reused.
@@ -50,14 +75,13 @@ impl LspManager {
         );
 
         // Build and start client
-        let mut builder = LspClientBuilder::new(&server_config.command);
-
-        for arg in &server_config.args {
-            builder = builder.arg(arg);
-        }
+        let mut builder = LspClientBuilder::new(&server_config.command)
+            .args(&server_config.args)
+            .settings(server_config.settings.clone())
+            .request_timeout(self.config.request_timeout());
 
-        if let Some(root_uri) = &server_config.root_uri {
-            builder = builder.root_uri(root_uri.clone());
+        if let Some(root_uri) = server_config.root_uri.clone().or_else(|| self.workspace_root()) {
+            builder = builder.root_uri(root_uri);
         }
 
         let client = builder.build().await?;
@@ -89,6 +113,27 @@ impl LspManager {
         Ok(())
     }
 
+    /// Restart the server for a language
+    ///
+    /// The server gets a few seconds to shut down cleanly and is killed
+    /// otherwise, since restarts are mostly requested for servers that have
+    /// stopped responding. Documents must be reopened afterwards.
+    pub async fn restart_server(&self, language_id: &str) -> Result<()> {
+        let client = self.clients.write().await.remove(language_id);
+
+        if let Some(client) = client {
+            info!("Restarting LSP server for {}", language_id);
+            match tokio::time::timeout(RESTART_SHUTDOWN_TIMEOUT, client.shutdown()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("LSP server for {} failed to shut down: {}", language_id, e),
+                Err(_) => warn!("LSP server for {} did not shut down in time", language_id),
+            }
+            client.kill().await;
+        }
+
+        self.start_server(language_id).await
+    }
+
     /// Get client for a language
     ///
     /// Returns None if no server is running for this language.
@@ -162,6 +207,74 @@ impl LspManager {
         }
     }
 
+    /// Detach a document from its language server
+    ///
+    /// The server is told the document was closed and the document is
+    /// excluded from LSP features until it is attached again. Other documents
+    /// of the same language keep using the server.
+    pub async fn detach_document(&self, path: &Path) -> Result<()> {
+        let uri = file_uri(path)?;
+        if !self.detached.write().await.insert(uri.clone()) {
+            return Ok(());
+        }
+
+        info!("Detaching {} from LSP", path.display());
+        if let Some(language) = self.detect_language(path) {
+            if let Some(client) = self.get_client(&language).await {
+                client.did_close(uri).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Attach a detached document again, reopening it with `text`
+    ///
+    /// Starts the language server if needed.
+    pub async fn attach_document(&self, path: &Path, text: String) -> Result<()> {
+        let uri = file_uri(path)?;
+        self.detached.write().await.remove(&uri);
+
+        info!("Attaching {} to LSP", path.display());
+        let language = match self.detect_language(path) {
+            Some(lang) => lang,
+            None => return Ok(()),
+        };
+
+        if let Some(client) = self.ensure_server_for_file(path).await? {
+            client.did_open(uri, text, language).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if a document is detached from LSP
+    pub async fn is_detached(&self, path: &Path) -> bool {
+        match file_uri(path) {
+            Ok(uri) => self.detached.read().await.contains(&uri),
+            Err(_) => false,
+        }
+    }
+
+    /// Get list of detached documents
+    pub async fn detached_documents(&self) -> Vec<Url> {
+        self.detached.read().await.iter().cloned().collect()
+    }
+
+    /// Get process IDs of running servers by language
+    pub async fn server_processes(&self) -> Vec<(String, u32)> {
+        let clients = self.clients.read().await;
+        clients
+            .iter()
+            .filter_map(|(language, client)| Some((language.clone(), client.process_id()?)))
+            .collect()
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &LspConfig {
+        &self.config
+    }
+
     /// Shutdown all servers
     pub async fn shutdown_all(&self) -> Result<()> {
         info!("Shutting down all LSP servers");
@@ -192,6 +305,11 @@ impl LspManager {
     }
 }
 
+/// Convert a file path to a document URI
+fn file_uri(path: &Path) -> Result<Url> {
+    Url::from_file_path(path).map_err(|_| LspError::InvalidPath(path.display().to_string()))
+}
+
 impl Drop for LspManager {
     fn drop(&mut self) {
         // Spawn background task to shutdown servers
@@ -243,4 +361,46 @@ mod tests {
 
         assert!(!manager.is_running("rust").await);
     }
+
+    #[tokio::test]
+    async fn test_detach_and_attach_document() {
+        let mut config = LspConfig::default();
+        config.servers.clear();
+        let manager = LspManager::new(config);
+
+        let path = std::env::temp_dir().join("detached.rs");
+        let other = std::env::temp_dir().join("other.rs");
+
+        manager.detach_document(&path).await.unwrap();
+        assert!(manager.is_detached(&path).await);
+        assert!(!manager.is_detached(&other).await);
+        assert_eq!(manager.detached_documents().await.len(), 1);
+
+        // No server is configured, so attaching only clears the flag
+        manager.attach_document(&path, String::new()).await.unwrap();
+        assert!(!manager.is_detached(&path).await);
+        assert!(manager.detached_documents().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_workspace_root() {
+        let manager = LspManager::new(LspConfig::default());
+        assert_eq!(manager.workspace_root(), None);
+
+        let root = std::env::temp_dir().join("project");
+        manager.set_workspace_root(&root).unwrap();
+        let uri = manager.workspace_root().unwrap();
+        assert!(uri.path().ends_with("/project/"));
+
+        assert!(manager.set_workspace_root(Path::new("relative")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_relative_path_rejected() {
+        let manager = LspManager::new(LspConfig::default());
+
+        let result = manager.detach_document(Path::new("relative.rs")).await;
+        assert!(matches!(result, Err(LspError::InvalidPath(_))));
+        assert!(!manager.is_detached(Path::new("relative.rs")).await);
+    }
 }
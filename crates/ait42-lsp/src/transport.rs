@@ -0,0 +1,278 @@
+//! JSON-RPC Transport
+//!
+//! Base protocol of the Language Server Protocol: every message is a
+//! JSON-RPC 2.0 object preceded by a `Content-Length` header and a blank
+//! line, exchanged over the server's stdin and stdout.
+
+use crate::{LspError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Messages larger than this are rejected instead of buffered
+const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// JSON-RPC error codes used by the client
+pub mod error_codes {
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+}
+
+/// Error returned by the other side of a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl std::fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+/// JSON-RPC message, as sent in either direction
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Request {
+        id: Value,
+        method: String,
+        params: Value,
+    },
+    Response {
+        id: Value,
+        result: std::result::Result<Value, ResponseError>,
+    },
+    Notification {
+        method: String,
+        params: Value,
+    },
+}
+
+impl Message {
+    /// Classify a JSON-RPC object, `None` if it is not one
+    pub fn from_value(mut value: Value) -> Option<Self> {
+        let object = value.as_object_mut()?;
+        let id = object.remove("id").filter(|id| !id.is_null());
+        let params = object.remove("params").unwrap_or(Value::Null);
+
+        match (object.remove("method"), id) {
+            (Some(Value::String(method)), Some(id)) => Some(Self::Request { id, method, params }),
+            (Some(Value::String(method)), None) => Some(Self::Notification { method, params }),
+            (None, Some(id)) => {
+                let result = match object.remove("error") {
+                    Some(error) => Err(serde_json::from_value(error).ok()?),
+                    None => Ok(object.remove("result").unwrap_or(Value::Null)),
+                };
+                Some(Self::Response { id, result })
+            }
+            _ => None,
+        }
+    }
+
+    /// JSON-RPC object for the message
+    ///
+    /// Null params are left out, as some servers reject `"params": null`.
+    pub fn to_value(&self) -> Value {
+        let mut value = match self {
+            Self::Request { id, method, params } => serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params,
+            }),
+            Self::Response {
+                id,
+                result: Ok(result),
+            } => serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": result,
+            }),
+            Self::Response {
+                id,
+                result: Err(error),
+            } => serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": error,
+            }),
+            Self::Notification { method, params } => serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params,
+            }),
+        };
+        if let Some(object) = value.as_object_mut() {
+            if object.get("params").is_some_and(Value::is_null) {
+                object.remove("params");
+            }
+        }
+        value
+    }
+}
+
+/// Read one framed message, `None` once the stream has ended
+///
+/// Headers other than `Content-Length` (such as `Content-Type`) are skipped.
+pub async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<Value>> {
+    let mut content_length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            // A blank line ends the headers, stray ones between messages are skipped
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| LspError::InvalidResponse(format!("Malformed header: {}", header)))?;
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let length = value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| LspError::InvalidResponse(format!("Invalid length: {}", header)))?;
+            if length > MAX_CONTENT_LENGTH {
+                return Err(LspError::InvalidResponse(format!("Message too large: {}", length)));
+            }
+            content_length = Some(length);
+        }
+    }
+
+    let mut content = vec![0; content_length.unwrap_or(0)];
+    reader.read_exact(&mut content).await?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Write one framed message
+pub async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> Result<()> {
+    let content = serde_json::to_vec(message)?;
+    let header = format!("Content-Length: {}\r\n\r\n", content.len());
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(&content).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let (client, server) = tokio::io::duplex(1024);
+        let (_, mut writer) = tokio::io::split(client);
+        let mut reader = BufReader::new(server);
+
+        let first = serde_json::json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} });
+        let second = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "ünïcode" });
+        write_message(&mut writer, &first).await.unwrap();
+        write_message(&mut writer, &second).await.unwrap();
+        drop(writer);
+
+        // Both messages arrive even though they are buffered together
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(first));
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(second));
+        assert_eq!(read_message(&mut reader).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_headers() {
+        let body = r#"{"jsonrpc":"2.0","method":"exit"}"#;
+        let input = format!(
+            "\r\ncontent-length: {}\r\nContent-Type: {}\r\n\r\n{}",
+            body.len(),
+            "application/vscode-jsonrpc; charset=utf-8",
+            body
+        );
+        let mut reader = BufReader::new(input.as_bytes());
+        let message = read_message(&mut reader).await.unwrap().unwrap();
+        assert_eq!(message["method"], "exit");
+
+        let mut reader = BufReader::new("Content-Length: x\r\n\r\n".as_bytes());
+        assert!(read_message(&mut reader).await.is_err());
+
+        let mut reader = BufReader::new("garbage\r\n\r\n".as_bytes());
+        assert!(read_message(&mut reader).await.is_err());
+    }
+
+    #[test]
+    fn test_classify_messages() {
+        let request = Message::from_value(serde_json::json!({
+            "jsonrpc": "2.0", "id": "a", "method": "workspace/configuration", "params": {}
+        }));
+        assert!(matches!(
+            request,
+            Some(Message::Request { ref method, .. }) if method == "workspace/configuration"
+        ));
+
+        let notification = Message::from_value(serde_json::json!({
+            "jsonrpc": "2.0", "method": "$/progress"
+        }));
+        assert_eq!(
+            notification,
+            Some(Message::Notification {
+                method: "$/progress".to_string(),
+                params: Value::Null
+            })
+        );
+
+        let error = Message::from_value(serde_json::json!({
+            "jsonrpc": "2.0", "id": 3, "error": { "code": -32601, "message": "nope" }
+        }));
+        let Some(Message::Response {
+            id,
+            result: Err(error),
+        }) = error
+        else {
+            panic!("expected an error response");
+        };
+        assert_eq!(id, 3);
+        assert_eq!(error.code, error_codes::METHOD_NOT_FOUND);
+
+        // A null result is still a result
+        let response =
+            Message::from_value(serde_json::json!({ "jsonrpc": "2.0", "id": 4, "result": null }));
+        assert_eq!(
+            response,
+            Some(Message::Response {
+                id: Value::from(4),
+                result: Ok(Value::Null)
+            })
+        );
+        assert!(Message::from_value(serde_json::json!([1, 2])).is_none());
+    }
+
+    #[test]
+    fn test_to_value() {
+        let message = Message::Response {
+            id: Value::from(7),
+            result: Err(ResponseError {
+                code: error_codes::METHOD_NOT_FOUND,
+                message: "Unhandled method".to_string(),
+                data: None,
+            }),
+        };
+        let value = message.to_value();
+        assert_eq!(value["error"]["code"], error_codes::METHOD_NOT_FOUND);
+        assert_eq!(Message::from_value(value), Some(message));
+
+        let exit = Message::Notification {
+            method: "exit".to_string(),
+            params: Value::Null,
+        };
+        assert!(exit.to_value().get("params").is_none());
+    }
+}
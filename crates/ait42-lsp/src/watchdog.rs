@@ -0,0 +1,297 @@
+//! Server Resource Watchdog
+//!
+//! Detects language servers that keep consuming excessive CPU or memory.
+//!
+//! The watchdog is fed periodic samples of each server process and reports a
+//! server once it has stayed over a threshold for several consecutive
+//! samples, so short bursts such as initial indexing are tolerated. A server
+//! is reported once per episode; it must drop back under the thresholds
+//! before it can be reported again.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Watchdog thresholds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    /// Enable the watchdog
+    pub enabled: bool,
+
+    /// Seconds between samples
+    pub interval_secs: u64,
+
+    /// CPU usage limit in percent of one core
+    pub max_cpu_percent: f32,
+
+    /// Resident memory limit in megabytes
+    pub max_memory_mb: u64,
+
+    /// Consecutive samples over a limit before a server is reported
+    pub sustained_samples: u32,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 10,
+            max_cpu_percent: 90.0,
+            max_memory_mb: 4096,
+            sustained_samples: 6,
+        }
+    }
+}
+
+/// Resource usage of a server process at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSample {
+    /// CPU usage in percent of one core
+    pub cpu_percent: f32,
+
+    /// Resident memory in bytes
+    pub memory_bytes: u64,
+}
+
+impl ResourceSample {
+    /// Resident memory in megabytes
+    pub fn memory_mb(&self) -> u64 {
+        self.memory_bytes / (1024 * 1024)
+    }
+}
+
+/// Threshold a server exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Violation {
+    Cpu,
+    Memory,
+}
+
+/// A server that stayed over a threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchdogEvent {
+    /// Language ID of the server
+    pub language: String,
+
+    /// Exceeded threshold
+    pub violation: Violation,
+
+    /// Most recent sample
+    pub sample: ResourceSample,
+}
+
+impl fmt::Display for WatchdogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.violation {
+            Violation::Cpu => write!(
+                f,
+                "{} language server is using {:.0}% CPU",
+                self.language, self.sample.cpu_percent
+            ),
+            Violation::Memory => write!(
+                f,
+                "{} language server is using {} MB of memory",
+                self.language,
+                self.sample.memory_mb()
+            ),
+        }
+    }
+}
+
+/// Tracks consecutive over-threshold samples per server
+#[derive(Debug, Default)]
+pub struct ResourceWatchdog {
+    config: WatchdogConfig,
+    strikes: HashMap<String, u32>,
+    reported: HashSet<String>,
+}
+
+impl ResourceWatchdog {
+    /// Create a watchdog with the given thresholds
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self {
+            config,
+            strikes: HashMap::new(),
+            reported: HashSet::new(),
+        }
+    }
+
+    /// Current thresholds
+    pub fn config(&self) -> &WatchdogConfig {
+        &self.config
+    }
+
+    /// Replace the thresholds
+    ///
+    /// Strike counts are kept, so a server already over the new limits is
+    /// reported on the next sample that completes its run.
+    pub fn set_config(&mut self, config: WatchdogConfig) {
+        self.config = config;
+    }
+
+    /// Record a sample for `language`
+    ///
+    /// Returns an event when the server has just completed a sustained run
+    /// over a threshold.
+    pub fn observe(&mut self, language: &str, sample: ResourceSample) -> Option<WatchdogEvent> {
+        let violation = if sample.memory_mb() > self.config.max_memory_mb {
+            Some(Violation::Memory)
+        } else if sample.cpu_percent > self.config.max_cpu_percent {
+            Some(Violation::Cpu)
+        } else {
+            None
+        };
+
+        let Some(violation) = violation else {
+            self.forget(language);
+            return None;
+        };
+
+        let strikes = self.strikes.entry(language.to_string()).or_insert(0);
+        *strikes += 1;
+        if *strikes < self.config.sustained_samples.max(1) || self.reported.contains(language) {
+            return None;
+        }
+
+        self.reported.insert(language.to_string());
+        Some(WatchdogEvent {
+            language: language.to_string(),
+            violation,
+            sample,
+        })
+    }
+
+    /// Reset the state for `language`, e.g. after its server was restarted
+    pub fn forget(&mut self, language: &str) {
+        self.strikes.remove(language);
+        self.reported.remove(language);
+    }
+}
+
+/// Reads resource usage of server processes from the OS
+pub struct ProcessSampler {
+    system: System,
+}
+
+impl ProcessSampler {
+    /// Create a sampler
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+        }
+    }
+
+    /// Sample the given processes
+    ///
+    /// CPU usage is averaged since the previous call, so the first sample of
+    /// a process reads as zero. Processes that have exited are omitted.
+    pub fn sample(&mut self, pids: &[u32]) -> HashMap<u32, ResourceSample> {
+        let sys_pids: Vec<Pid> = pids.iter().map(|&pid| Pid::from_u32(pid)).collect();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&sys_pids),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+
+        pids.iter()
+            .filter_map(|&pid| {
+                let process = self.system.process(Pid::from_u32(pid))?;
+                Some((
+                    pid,
+                    ResourceSample {
+                        cpu_percent: process.cpu_usage(),
+                        memory_bytes: process.memory(),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+impl Default for ProcessSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu_percent: f32, memory_mb: u64) -> ResourceSample {
+        ResourceSample {
+            cpu_percent,
+            memory_bytes: memory_mb * 1024 * 1024,
+        }
+    }
+
+    fn watchdog() -> ResourceWatchdog {
+        ResourceWatchdog::new(WatchdogConfig {
+            max_cpu_percent: 80.0,
+            max_memory_mb: 1024,
+            sustained_samples: 3,
+            ..WatchdogConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_reports_sustained_cpu_once() {
+        let mut watchdog = watchdog();
+
+        assert!(watchdog.observe("rust", sample(95.0, 100)).is_none());
+        assert!(watchdog.observe("rust", sample(95.0, 100)).is_none());
+
+        let event = watchdog.observe("rust", sample(99.0, 100)).unwrap();
+        assert_eq!(event.language, "rust");
+        assert_eq!(event.violation, Violation::Cpu);
+        assert!(event.to_string().contains("99% CPU"));
+
+        // Still hot, but already reported
+        assert!(watchdog.observe("rust", sample(99.0, 100)).is_none());
+    }
+
+    #[test]
+    fn test_burst_resets_strikes() {
+        let mut watchdog = watchdog();
+
+        watchdog.observe("python", sample(95.0, 100));
+        watchdog.observe("python", sample(95.0, 100));
+        assert!(watchdog.observe("python", sample(10.0, 100)).is_none());
+        assert!(watchdog.observe("python", sample(95.0, 100)).is_none());
+    }
+
+    #[test]
+    fn test_memory_takes_precedence() {
+        let mut watchdog = ResourceWatchdog::new(WatchdogConfig {
+            sustained_samples: 1,
+            max_memory_mb: 1024,
+            ..WatchdogConfig::default()
+        });
+
+        let event = watchdog.observe("go", sample(100.0, 2048)).unwrap();
+        assert_eq!(event.violation, Violation::Memory);
+        assert!(event.to_string().contains("2048 MB"));
+
+        // Cooling down makes the server reportable again
+        watchdog.observe("go", sample(1.0, 10));
+        assert!(watchdog.observe("go", sample(100.0, 2048)).is_some());
+    }
+
+    #[test]
+    fn test_config_from_partial_toml() {
+        let config: WatchdogConfig = toml::from_str("max_cpu_percent = 50.0").unwrap();
+        assert_eq!(config.max_cpu_percent, 50.0);
+        assert_eq!(config.max_memory_mb, WatchdogConfig::default().max_memory_mb);
+    }
+
+    #[test]
+    fn test_sampler_reads_own_process() {
+        let pid = std::process::id();
+        let samples = ProcessSampler::new().sample(&[pid]);
+        assert!(samples[&pid].memory_bytes > 0);
+    }
+}
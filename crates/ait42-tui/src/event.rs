@@ -2,7 +2,10 @@
 //!
 //! Provides async event handling for terminal input (keyboard, mouse, resize).
 
-use crossterm::event::{Event as CrosstermEvent, EventStream, KeyEvent, MouseEvent};
+use crossterm::event::{
+    Event as CrosstermEvent, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+    MouseEvent,
+};
 use futures::StreamExt;
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -19,12 +22,80 @@ pub enum EditorEvent {
     Resize(u16, u16),
     /// Paste text
     Paste(String),
+    /// Composed multi-byte text (e.g. an IME commit)
+    Text(String),
     /// Timer tick for UI refresh
     Tick,
     /// Request to quit
     Quit,
 }
 
+/// How long to wait for the next key of an IME commit burst
+const COMPOSE_WINDOW: Duration = Duration::from_millis(5);
+
+/// Groups IME commits into single text events
+///
+/// Terminals handle IME preedit themselves and write the committed string to
+/// the tty, which crossterm reports as a burst of `Char` key events. Feeding
+/// those one by one splits the commit across undo steps and redraws, so the
+/// composer buffers printable keys and flushes them as one `Text` event when
+/// the burst contains non-ASCII input. Pure ASCII bursts (fast typing, Vim
+/// commands) are replayed as individual key events.
+#[derive(Debug, Default)]
+pub struct InputComposer {
+    keys: Vec<KeyEvent>,
+}
+
+impl InputComposer {
+    /// Create an empty composer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer a key if it can be part of composed text
+    ///
+    /// Returns `false` for keys that must be handled on their own (Ctrl/Alt
+    /// chords, non-character keys, key releases).
+    pub fn push(&mut self, key: KeyEvent) -> bool {
+        let composable = key.kind != KeyEventKind::Release
+            && matches!(key.code, KeyCode::Char(_))
+            && (key.modifiers - KeyModifiers::SHIFT).is_empty();
+
+        if composable {
+            self.keys.push(key);
+        }
+        composable
+    }
+
+    /// Text buffered so far (not yet committed)
+    pub fn preedit(&self) -> String {
+        self.keys
+            .iter()
+            .filter_map(|key| match key.code {
+                KeyCode::Char(ch) => Some(ch),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether nothing is buffered
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Commit the buffered keys as events
+    pub fn flush(&mut self) -> Vec<EditorEvent> {
+        let text = self.preedit();
+        let keys = std::mem::take(&mut self.keys);
+
+        if keys.len() > 1 && !text.is_ascii() {
+            vec![EditorEvent::Text(text)]
+        } else {
+            keys.into_iter().map(EditorEvent::Key).collect()
+        }
+    }
+}
+
 /// Asynchronous event loop for handling terminal events
 pub struct EventLoop {
     rx: mpsc::Receiver<EditorEvent>,
@@ -63,20 +134,37 @@ impl EventLoop {
     fn spawn_input_handler(tx: mpsc::Sender<EditorEvent>) {
         tokio::spawn(async move {
             let mut reader = EventStream::new();
+            let mut composer = InputComposer::new();
 
             loop {
-                match reader.next().await {
+                // While composing, a short pause in input ends the burst
+                let next = if composer.is_empty() {
+                    reader.next().await
+                } else {
+                    match tokio::time::timeout(COMPOSE_WINDOW, reader.next()).await {
+                        Ok(next) => next,
+                        Err(_) => {
+                            if !Self::send_all(&tx, composer.flush()).await {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
+                };
+
+                match next {
+                    Some(Ok(CrosstermEvent::Key(key))) if composer.push(key) => {}
                     Some(Ok(event)) => {
-                        let editor_event = match event {
-                            CrosstermEvent::Key(key) => EditorEvent::Key(key),
-                            CrosstermEvent::Mouse(mouse) => EditorEvent::Mouse(mouse),
-                            CrosstermEvent::Resize(w, h) => EditorEvent::Resize(w, h),
-                            CrosstermEvent::Paste(text) => EditorEvent::Paste(text),
-                            _ => continue,
-                        };
-
-                        if tx.send(editor_event).await.is_err() {
-                            debug!("Event receiver dropped");
+                        let mut events = composer.flush();
+                        events.extend(match event {
+                            CrosstermEvent::Key(key) => Some(EditorEvent::Key(key)),
+                            CrosstermEvent::Mouse(mouse) => Some(EditorEvent::Mouse(mouse)),
+                            CrosstermEvent::Resize(w, h) => Some(EditorEvent::Resize(w, h)),
+                            CrosstermEvent::Paste(text) => Some(EditorEvent::Paste(text)),
+                            _ => None,
+                        });
+
+                        if !Self::send_all(&tx, events).await {
                             break;
                         }
                     }
@@ -84,6 +172,7 @@ impl EventLoop {
                         error!("Error reading terminal event: {}", e);
                     }
                     None => {
+                        Self::send_all(&tx, composer.flush()).await;
                         debug!("Event stream ended");
                         break;
                     }
@@ -92,6 +181,17 @@ impl EventLoop {
         });
     }
 
+    /// Send events in order, returning `false` once the receiver is gone
+    async fn send_all(tx: &mpsc::Sender<EditorEvent>, events: Vec<EditorEvent>) -> bool {
+        for event in events {
+            if tx.send(event).await.is_err() {
+                debug!("Event receiver dropped");
+                return false;
+            }
+        }
+        true
+    }
+
     /// Spawn task to generate periodic tick events
     fn spawn_tick_handler(tx: mpsc::Sender<EditorEvent>, tick_rate: Duration) {
         tokio::spawn(async move {
@@ -133,6 +233,52 @@ mod tests {
         }
     }
 
+    fn char_key(ch: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_composer_groups_ime_commit() {
+        let mut composer = InputComposer::new();
+        for ch in "日本語".chars() {
+            assert!(composer.push(char_key(ch)));
+        }
+        assert_eq!(composer.preedit(), "日本語");
+
+        assert_eq!(composer.flush(), vec![EditorEvent::Text("日本語".to_string())]);
+        assert!(composer.is_empty());
+    }
+
+    #[test]
+    fn test_composer_replays_ascii_keys() {
+        let mut composer = InputComposer::new();
+        composer.push(char_key('j'));
+        composer.push(char_key('k'));
+
+        assert_eq!(
+            composer.flush(),
+            vec![
+                EditorEvent::Key(char_key('j')),
+                EditorEvent::Key(char_key('k'))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_composer_single_multibyte_char_stays_key() {
+        let mut composer = InputComposer::new();
+        composer.push(char_key('あ'));
+        assert_eq!(composer.flush(), vec![EditorEvent::Key(char_key('あ'))]);
+    }
+
+    #[test]
+    fn test_composer_rejects_chords() {
+        let mut composer = InputComposer::new();
+        assert!(!composer.push(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)));
+        assert!(!composer.push(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(composer.push(KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT)));
+    }
+
     #[tokio::test]
     async fn test_event_loop_multiple_ticks() {
         let mut event_loop = EventLoop::new(Duration::from_millis(50));
@@ -0,0 +1,271 @@
+//! File Metadata Cache
+//!
+//! Size, modification time and git status of the files shown in the
+//! sidebar. Each file is read once and kept until the file watcher reports
+//! a change to it, so reloading the sidebar does not stat every entry again
+//! and git status is only re-read for the paths that changed, in one batch
+//! per frame. A change inside `.git` (staging, commits, checkouts) re-reads
+//! the whole status.
+
+use ait42_fs::FileEvent;
+use ait42_git::{status, FileStatus, Repository};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use tracing::debug;
+
+/// Metadata of a file or directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileInfo {
+    pub is_dir: bool,
+    /// Size in bytes, 0 for directories
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Repository of the workspace, opened on first use
+#[derive(Debug, Default)]
+enum Repo {
+    #[default]
+    Unopened,
+    Open(Repository),
+    None,
+}
+
+/// Cached metadata and git status, invalidated by file events
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    info: HashMap<PathBuf, FileInfo>,
+    /// Workspace whose repository provides the git status
+    workspace: Option<PathBuf>,
+    repo: Repo,
+    /// Status of changed files by path relative to the repository root,
+    /// `None` until read
+    statuses: Option<HashMap<PathBuf, FileStatus>>,
+    /// Summarized status of directories, by relative path, until a status
+    /// changes
+    dir_statuses: HashMap<PathBuf, Option<FileStatus>>,
+    /// Paths relative to the root whose status may have changed
+    stale: HashSet<PathBuf>,
+    /// Files read from disk, for tests and debugging
+    reads: usize,
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the git status of the repository containing `workspace`
+    ///
+    /// The repository is opened when a status is first asked for.
+    pub fn set_workspace(&mut self, workspace: PathBuf) {
+        self.workspace = Some(workspace);
+        self.repo = Repo::Unopened;
+        self.statuses = None;
+        self.dir_statuses.clear();
+        self.stale.clear();
+    }
+
+    /// Metadata of `path`, read from disk unless cached
+    pub fn info(&mut self, path: &Path) -> Option<FileInfo> {
+        if let Some(info) = self.info.get(path) {
+            return Some(*info);
+        }
+        let metadata = std::fs::metadata(path).ok()?;
+        self.reads += 1;
+        let info = FileInfo {
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok(),
+        };
+        self.info.insert(path.to_path_buf(), info);
+        Some(info)
+    }
+
+    /// How many files were read from disk
+    pub fn reads(&self) -> usize {
+        self.reads
+    }
+
+    /// Git status of the file at `path`, `None` if it is unchanged or not
+    /// in a repository
+    ///
+    /// For a directory, the status summarizes the files below it: conflicted
+    /// if any is, untracked if all are, modified otherwise.
+    pub fn status(&mut self, path: &Path) -> Option<FileStatus> {
+        self.refresh_statuses();
+        let relative = self.repo_relative(path)?;
+        let statuses = self.statuses.as_ref()?;
+        if let Some(status) = statuses.get(&relative) {
+            return Some(*status);
+        }
+        if let Some(status) = self.dir_statuses.get(&relative) {
+            return *status;
+        }
+
+        let below: Vec<FileStatus> = statuses
+            .iter()
+            .filter(|(path, _)| path.starts_with(&relative))
+            .map(|(_, status)| *status)
+            .collect();
+        let status = if below.is_empty() {
+            None
+        } else if below.contains(&FileStatus::Conflicted) {
+            Some(FileStatus::Conflicted)
+        } else if below.iter().all(|status| *status == FileStatus::Untracked) {
+            Some(FileStatus::Untracked)
+        } else {
+            Some(FileStatus::Modified)
+        };
+        self.dir_statuses.insert(relative, status);
+        status
+    }
+
+    /// Forget what `event` may have changed
+    pub fn file_changed(&mut self, event: &FileEvent) {
+        let paths = match event {
+            FileEvent::Renamed(from, to) => vec![from, to],
+            FileEvent::Created(path) | FileEvent::Modified(path) | FileEvent::Deleted(path) => {
+                vec![path]
+            }
+        };
+        for path in paths {
+            // A removed directory takes everything below it along
+            if matches!(event, FileEvent::Deleted(_) | FileEvent::Renamed(..)) {
+                self.info.retain(|cached, _| !cached.starts_with(path));
+            } else {
+                self.info.remove(path);
+            }
+            // Creating a file also changes its directory's modification time
+            if let Some(parent) = path.parent() {
+                self.info.remove(parent);
+            }
+
+            if path
+                .components()
+                .any(|component| component == Component::Normal(".git".as_ref()))
+            {
+                self.statuses = None;
+            } else if let Some(relative) = self.repo_relative(path) {
+                self.stale.insert(relative);
+            }
+        }
+    }
+
+    /// Read the status of the whole tree if never read, or of the stale
+    /// paths
+    fn refresh_statuses(&mut self) {
+        if matches!(self.repo, Repo::Unopened) {
+            self.repo = match self.workspace.as_deref().map(Repository::open) {
+                Some(Ok(repo)) => Repo::Open(repo),
+                _ => Repo::None,
+            };
+        }
+        let Repo::Open(repo) = &self.repo else {
+            return;
+        };
+
+        let Some(statuses) = &mut self.statuses else {
+            self.stale.clear();
+            self.dir_statuses.clear();
+            match status::file_statuses(repo, &[]) {
+                Ok(statuses) => self.statuses = Some(statuses),
+                Err(e) => {
+                    debug!("Failed to read git status: {}", e);
+                    self.statuses = Some(HashMap::new());
+                }
+            }
+            return;
+        };
+        if self.stale.is_empty() {
+            return;
+        }
+
+        self.dir_statuses.clear();
+        let stale: Vec<PathBuf> = self.stale.drain().collect();
+        let paths: Vec<&Path> = stale.iter().map(PathBuf::as_path).collect();
+        match status::file_statuses(repo, &paths) {
+            Ok(fresh) => {
+                statuses.retain(|path, _| !stale.iter().any(|stale| path.starts_with(stale)));
+                statuses.extend(fresh);
+            }
+            Err(e) => debug!("Failed to read git status: {}", e),
+        }
+    }
+
+    /// `path` relative to the repository root, if it is inside it
+    fn repo_relative(&self, path: &Path) -> Option<PathBuf> {
+        let Repo::Open(repo) = &self.repo else {
+            return None;
+        };
+        if let Ok(relative) = path.strip_prefix(repo.root()) {
+            return Some(relative.to_path_buf());
+        }
+        // Paths below a workspace given without its symlinks resolved
+        let workspace = self.workspace.as_deref()?;
+        let below = path.strip_prefix(workspace).ok()?;
+        let workspace = workspace.canonicalize().ok()?;
+        Some(workspace.strip_prefix(repo.root()).ok()?.join(below))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_git::repo::test_support::init_repo;
+
+    #[test]
+    fn test_info_is_read_once_until_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one").unwrap();
+
+        let mut cache = MetadataCache::new();
+        assert_eq!(cache.info(&path).unwrap().size, 3);
+        std::fs::write(&path, "three").unwrap();
+        assert_eq!(cache.info(&path).unwrap().size, 3);
+        assert_eq!(cache.reads(), 1);
+
+        cache.file_changed(&FileEvent::Modified(path.clone()));
+        assert_eq!(cache.info(&path).unwrap().size, 5);
+        assert!(cache.info(dir.path()).unwrap().is_dir);
+        assert_eq!(cache.reads(), 3);
+
+        cache.file_changed(&FileEvent::Deleted(dir.path().to_path_buf()));
+        std::fs::remove_file(&path).unwrap();
+        assert!(cache.info(&path).is_none());
+    }
+
+    #[test]
+    fn test_status_follows_file_events() {
+        let (_dir, repo) = init_repo();
+        let root = repo.root().to_path_buf();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "a").unwrap();
+        std::fs::write(root.join("b.rs"), "b").unwrap();
+        repo.run(&["add", "-A"]).unwrap();
+        repo.run(&["commit", "-q", "-m", "initial"]).unwrap();
+
+        let mut cache = MetadataCache::new();
+        cache.set_workspace(root.clone());
+        assert_eq!(cache.status(&root.join("src/a.rs")), None);
+
+        // Only the changed path is read again
+        std::fs::write(root.join("src/a.rs"), "changed").unwrap();
+        assert_eq!(cache.status(&root.join("src/a.rs")), None);
+        cache.file_changed(&FileEvent::Modified(root.join("src/a.rs")));
+        assert_eq!(cache.status(&root.join("src/a.rs")), Some(FileStatus::Modified));
+        assert_eq!(cache.status(&root.join("src")), Some(FileStatus::Modified));
+        assert_eq!(cache.status(&root.join("b.rs")), None);
+
+        std::fs::write(root.join("new.rs"), "new").unwrap();
+        cache.file_changed(&FileEvent::Created(root.join("new.rs")));
+        assert_eq!(cache.status(&root.join("new.rs")), Some(FileStatus::Untracked));
+
+        // Staging changes the index, so everything is read again
+        repo.run(&["add", "new.rs"]).unwrap();
+        cache.file_changed(&FileEvent::Modified(root.join(".git/index")));
+        assert_eq!(cache.status(&root.join("new.rs")), Some(FileStatus::Added));
+    }
+}
@@ -0,0 +1,624 @@
+//! Headless Test Harness
+//!
+//! Drives a [`TuiApp`] on a ratatui [`TestBackend`]: key events are fed
+//! through the same handler as the real event loop and every assertion
+//! draws a frame first, so tests check what the user would see rather than
+//! internal state.
+//!
+//! Keys use Vim notation: plain characters, `<Esc>`, `<CR>`, `<Tab>`,
+//! `<BS>`, `<Space>`, arrows (`<Up>`), and modifiers such as `<C-t>`,
+//! `<A-o>` or `<C-S-Tab>`. `<lt>` types a literal `<`.
+//!
+//! ```no_run
+//! use ait42_tui::harness::TuiHarness;
+//!
+//! # fn example() -> anyhow::Result<()> {
+//! let mut harness = TuiHarness::new()?;
+//! harness.keys("ihello<Esc>")?;
+//! harness.assert_row_contains(0, "hello");
+//! harness.assert_status_contains("NORMAL");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    event::EditorEvent,
+    layout::{MIN_HEIGHT, MIN_WIDTH},
+    tui_app::{EditorState, TuiApp},
+};
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use unicode_width::UnicodeWidthStr;
+
+/// Characters typed with Shift on a US layout, as terminals report them
+const SHIFTED_SYMBOLS: &str = "~!@#$%^&*()_+{}|:\"<>?";
+
+/// [`TuiApp`] running on an in-memory terminal
+pub struct TuiHarness {
+    app: TuiApp<TestBackend>,
+}
+
+impl TuiHarness {
+    /// Harness with the minimum supported terminal size (80x24)
+    pub fn new() -> Result<Self> {
+        Self::with_size(MIN_WIDTH, MIN_HEIGHT)
+    }
+
+    /// Harness with a terminal of `width` x `height` cells
+    pub fn with_size(width: u16, height: u16) -> Result<Self> {
+        Ok(Self {
+            app: TuiApp::with_backend(TestBackend::new(width, height))?,
+        })
+    }
+
+    /// Application under test
+    pub fn app(&self) -> &TuiApp<TestBackend> {
+        &self.app
+    }
+
+    /// Mutable application under test
+    pub fn app_mut(&mut self) -> &mut TuiApp<TestBackend> {
+        &mut self.app
+    }
+
+    /// Editor state of the application
+    pub fn state(&self) -> &EditorState {
+        self.app.state()
+    }
+
+    /// Mutable editor state, e.g. to load a file or a sidebar directory
+    pub fn state_mut(&mut self) -> &mut EditorState {
+        self.app.state_mut()
+    }
+
+    /// Feed one event to the application
+    pub fn send(&mut self, event: EditorEvent) -> Result<&mut Self> {
+        self.app.handle_event(event)?;
+        Ok(self)
+    }
+
+    /// Press the keys of a Vim-notation sequence, one event per key
+    pub fn keys(&mut self, keys: &str) -> Result<&mut Self> {
+        for key in parse_keys(keys)? {
+            self.app.handle_event(EditorEvent::Key(key))?;
+        }
+        Ok(self)
+    }
+
+    /// Paste text as a single event
+    pub fn paste(&mut self, text: &str) -> Result<&mut Self> {
+        self.send(EditorEvent::Paste(text.to_string()))
+    }
+
+    /// Resize the terminal
+    pub fn resize(&mut self, width: u16, height: u16) -> Result<&mut Self> {
+        self.app.renderer_mut().backend_mut().resize(width, height);
+        self.send(EditorEvent::Resize(width, height))
+    }
+
+    /// Draw a frame and return its rows
+    ///
+    /// Trailing blanks are trimmed and the blank cell after each wide
+    /// character is dropped, so rows read like the text on screen.
+    pub fn render(&mut self) -> Result<Vec<String>> {
+        self.app.draw()?;
+        let buffer = self.app.renderer().backend().buffer();
+        let area = buffer.area;
+
+        let mut rows = Vec::with_capacity(area.height as usize);
+        for y in area.top()..area.bottom() {
+            let mut row = String::new();
+            let mut skip = 0;
+            for x in area.left()..area.right() {
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                let symbol = buffer.get(x, y).symbol();
+                skip = symbol.width().saturating_sub(1);
+                row.push_str(symbol);
+            }
+            rows.push(row.trim_end().to_string());
+        }
+        Ok(rows)
+    }
+
+    /// Draw a frame and return it as text, one line per row
+    ///
+    /// Suitable for snapshot assertions.
+    pub fn screen(&mut self) -> Result<String> {
+        Ok(self.render()?.join("\n"))
+    }
+
+    /// Draw a frame and return the status line (bottom row)
+    pub fn status_line(&mut self) -> Result<String> {
+        Ok(self.render()?.pop().unwrap_or_default())
+    }
+
+    /// Assert that the screen shows `text` somewhere
+    #[track_caller]
+    pub fn assert_screen_contains(&mut self, text: &str) {
+        let screen = self.screen().expect("failed to draw frame");
+        assert!(screen.contains(text), "expected screen to contain {:?}:\n{}", text, screen);
+    }
+
+    /// Assert that the screen does not show `text`
+    #[track_caller]
+    pub fn assert_screen_lacks(&mut self, text: &str) {
+        let screen = self.screen().expect("failed to draw frame");
+        assert!(!screen.contains(text), "expected screen not to contain {:?}:\n{}", text, screen);
+    }
+
+    /// Assert that row `row` (0-based) shows `text`
+    #[track_caller]
+    pub fn assert_row_contains(&mut self, row: usize, text: &str) {
+        let rows = self.render().expect("failed to draw frame");
+        let line = rows.get(row).map(String::as_str).unwrap_or_default();
+        assert!(
+            line.contains(text),
+            "expected row {} to contain {:?}, found {:?}:\n{}",
+            row,
+            text,
+            line,
+            rows.join("\n")
+        );
+    }
+
+    /// Assert that the status line shows `text`
+    #[track_caller]
+    pub fn assert_status_contains(&mut self, text: &str) {
+        let status = self.status_line().expect("failed to draw frame");
+        assert!(
+            status.contains(text),
+            "expected status line to contain {:?}, found {:?}",
+            text,
+            status
+        );
+    }
+}
+
+/// Parse a Vim-notation key sequence into key events
+pub fn parse_keys(keys: &str) -> Result<Vec<KeyEvent>> {
+    let mut events = Vec::new();
+    let mut rest = keys;
+
+    while let Some(ch) = rest.chars().next() {
+        if ch == '<' {
+            if let Some(end) = rest.find('>') {
+                events.push(parse_special(&rest[1..end])?);
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        events.push(char_key(ch, KeyModifiers::NONE));
+        rest = &rest[ch.len_utf8()..];
+    }
+    Ok(events)
+}
+
+/// Key event for a typed character, adding Shift where a terminal would
+fn char_key(ch: char, modifiers: KeyModifiers) -> KeyEvent {
+    let shifted = ch.is_ascii_uppercase() || SHIFTED_SYMBOLS.contains(ch);
+    let modifiers = if shifted {
+        modifiers | KeyModifiers::SHIFT
+    } else {
+        modifiers
+    };
+    KeyEvent::new(KeyCode::Char(ch), modifiers)
+}
+
+/// Parse the inside of `<...>`
+fn parse_special(name: &str) -> Result<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut key = name;
+    while let Some((prefix, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+        modifiers |= match prefix.to_ascii_uppercase().as_str() {
+            "C" => KeyModifiers::CONTROL,
+            "A" | "M" => KeyModifiers::ALT,
+            "S" => KeyModifiers::SHIFT,
+            _ => bail!("unknown modifier in <{}>", name),
+        };
+        key = rest;
+    }
+
+    let code = match key.to_ascii_lowercase().as_str() {
+        "esc" => KeyCode::Esc,
+        "cr" | "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "bs" | "backspace" => KeyCode::Backspace,
+        "del" => KeyCode::Delete,
+        "space" => return Ok(char_key(' ', modifiers)),
+        "lt" => return Ok(char_key('<', modifiers)),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => return Ok(char_key(ch, modifiers)),
+                _ => return Err(anyhow!("unknown key <{}>", name)),
+            }
+        }
+    };
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybinds::Mode;
+    use ait42_ait42::SessionStatus;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_keys() {
+        let keys = parse_keys("iA<Esc><C-t><C-S-Tab><lt>:").unwrap();
+        assert_eq!(
+            keys,
+            vec![
+                KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT),
+                KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL),
+                KeyEvent::new(KeyCode::Tab, KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+                KeyEvent::new(KeyCode::Char('<'), KeyModifiers::SHIFT),
+                KeyEvent::new(KeyCode::Char(':'), KeyModifiers::SHIFT),
+            ]
+        );
+        assert!(parse_keys("<Nope>").is_err());
+        // An unclosed bracket is an ordinary character
+        assert_eq!(parse_keys("a<b").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_modal_editing() {
+        let mut harness = TuiHarness::new().unwrap();
+        harness.assert_status_contains("NORMAL");
+
+        harness.keys("i").unwrap();
+        harness.assert_status_contains("INSERT");
+
+        harness.keys("hello").unwrap();
+        harness.assert_row_contains(0, "hello");
+        harness.assert_status_contains("1:6");
+
+        harness.keys("<Esc>").unwrap();
+        harness.assert_status_contains("NORMAL");
+        assert_eq!(harness.state().mode(), Mode::Normal);
+
+        // Keys are commands again in normal mode
+        harness.keys("hh").unwrap();
+        harness.assert_status_contains("1:4");
+        harness.keys("u").unwrap();
+        harness.assert_screen_lacks("hello");
+    }
+
+    #[test]
+    fn test_configured_insert_sequence() {
+        let mut config = ait42_config::KeyBindingConfig::vim();
+        config
+            .custom
+            .insert("insert:j k".to_string(), "enter_normal_mode".to_string());
+        let mut harness = TuiHarness::new().unwrap();
+        harness
+            .app_mut()
+            .set_keymap(crate::KeyMap::from_config(&config).unwrap());
+
+        // A "j" that does not start the sequence is typed as usual
+        harness.keys("ijoke").unwrap();
+        harness.assert_row_contains(0, "joke");
+        harness.keys("jk").unwrap();
+        harness.assert_status_contains("NORMAL");
+        harness.assert_row_contains(0, "joke");
+    }
+
+    #[test]
+    fn test_paste_and_undo() {
+        let mut harness = TuiHarness::new().unwrap();
+        harness.keys("i").unwrap().paste("日本語").unwrap();
+        harness.keys("<Esc>").unwrap();
+        harness.assert_row_contains(0, "日本語");
+        harness.assert_status_contains("[+]");
+
+        harness.keys("u").unwrap();
+        harness.assert_screen_lacks("日本語");
+    }
+
+    #[test]
+    fn test_folding() {
+        let mut harness = TuiHarness::new().unwrap();
+        harness.keys("i").unwrap();
+        harness
+            .paste("fn a() {\n    one\n    two\n}\nlast")
+            .unwrap();
+        harness.keys("<Esc>kkk").unwrap();
+
+        harness.keys("zc").unwrap();
+        harness.assert_row_contains(0, "fn a() { ⋯ 2 lines");
+        harness.assert_row_contains(1, "}");
+        harness.assert_status_contains("1:1");
+
+        // Moving down steps over the folded lines
+        harness.keys("j").unwrap();
+        harness.assert_status_contains("4:1");
+        harness.keys("k").unwrap();
+        harness.assert_status_contains("1:1");
+
+        harness.keys("za").unwrap();
+        harness.assert_row_contains(1, "one");
+        harness.keys("zM").unwrap();
+        harness.assert_screen_lacks("two");
+        harness.keys("zR").unwrap();
+        harness.assert_row_contains(2, "two");
+    }
+
+    #[test]
+    fn test_tab_management() {
+        let mut harness = TuiHarness::new().unwrap();
+        harness.keys("ifirst<Esc>").unwrap();
+
+        // A new tab starts empty and leaves the first one intact
+        harness.keys("<C-t>").unwrap();
+        assert_eq!(harness.state().tabs().len(), 2);
+        harness.assert_screen_lacks("first");
+        harness.keys("isecond<Esc>").unwrap();
+        harness.assert_row_contains(0, "second");
+
+        harness.keys("<C-S-Tab>").unwrap();
+        assert_eq!(harness.state().active_tab_index(), 0);
+        harness.assert_row_contains(0, "first");
+        harness.assert_screen_lacks("second");
+
+        harness.keys("<C-Tab>").unwrap();
+        harness.assert_row_contains(0, "second");
+
+        // Closing the active tab shows its neighbour unchanged
+        harness.keys("<C-w>").unwrap();
+        assert_eq!(harness.state().tabs().len(), 1);
+        harness.assert_row_contains(0, "first");
+        harness.assert_screen_lacks("second");
+
+        // The last tab cannot be closed
+        harness.keys("<C-w>").unwrap();
+        assert_eq!(harness.state().tabs().len(), 1);
+    }
+
+    #[test]
+    fn test_sidebar_opens_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("alpha.txt"), "alpha contents\n").unwrap();
+        fs::write(dir.path().join("beta.txt"), "beta contents\n").unwrap();
+
+        let mut harness = TuiHarness::new().unwrap();
+        harness
+            .state_mut()
+            .sidebar_load_directory(&dir.path().to_path_buf())
+            .unwrap();
+
+        harness.keys("<C-e>j<CR>").unwrap();
+        assert_eq!(harness.state().sidebar_selected(), 1);
+        harness.assert_row_contains(0, "beta contents");
+        harness.assert_status_contains("beta.txt");
+
+        harness.keys("k<CR>").unwrap();
+        harness.assert_row_contains(0, "alpha contents");
+        harness.assert_status_contains("alpha.txt");
+        assert_eq!(harness.state().tabs().len(), 3);
+
+        // Back in the editor, j moves the cursor instead of the selection
+        harness.keys("<Esc>j").unwrap();
+        assert_eq!(harness.state().sidebar_selected(), 0);
+        harness.assert_status_contains("2:1");
+    }
+
+    #[test]
+    fn test_resize_panels() {
+        use crate::layout::PanelSizes;
+        use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = TuiHarness::with_size(100, 30).unwrap();
+        harness.app_mut().set_workspace(dir.path().to_path_buf());
+        harness.assert_row_contains(0, "FILES");
+
+        harness.keys("<A-Right>").unwrap();
+        assert_eq!(harness.state().panel_sizes().sidebar_percent, 30);
+        assert_eq!(PanelSizes::load(dir.path()).sidebar_percent, 30);
+
+        // Drag the sidebar edge (its last column) to the middle
+        let mouse = |kind, column| {
+            EditorEvent::Mouse(MouseEvent {
+                kind,
+                column,
+                row: 10,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        harness
+            .send(mouse(MouseEventKind::Down(MouseButton::Left), 29))
+            .unwrap()
+            .send(mouse(MouseEventKind::Drag(MouseButton::Left), 49))
+            .unwrap();
+        assert_eq!(harness.state().panel_sizes().sidebar_percent, 50);
+        assert_eq!(PanelSizes::load(dir.path()).sidebar_percent, 30);
+
+        harness
+            .send(mouse(MouseEventKind::Up(MouseButton::Left), 49))
+            .unwrap();
+        assert_eq!(PanelSizes::load(dir.path()).sidebar_percent, 50);
+
+        // Drags starting away from an edge do nothing
+        harness
+            .send(mouse(MouseEventKind::Down(MouseButton::Left), 70))
+            .unwrap()
+            .send(mouse(MouseEventKind::Drag(MouseButton::Left), 20))
+            .unwrap();
+        assert_eq!(harness.state().panel_sizes().sidebar_percent, 50);
+
+        harness.keys("<A-=>").unwrap();
+        assert_eq!(PanelSizes::load(dir.path()), PanelSizes::default());
+    }
+
+    #[test]
+    fn test_cheat_sheet_overlay() {
+        let mut harness = TuiHarness::new().unwrap();
+        harness.keys("?").unwrap();
+        harness.assert_screen_contains("Keybindings");
+
+        // Typing filters the list instead of running commands
+        harness.keys("new tab").unwrap();
+        harness.assert_screen_contains("New tab");
+        assert_eq!(harness.state().tabs().len(), 1);
+
+        harness.keys("<Esc>").unwrap();
+        harness.assert_screen_lacks("Keybindings");
+    }
+
+    #[test]
+    fn test_command_history() {
+        use crate::input_history::SavedHistory;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = TuiHarness::new().unwrap();
+        harness.app_mut().set_workspace(dir.path().to_path_buf());
+        assert!(harness.state().sidebar_visible());
+
+        // The palette runs the command and leader . repeats it
+        harness.keys("<Space>ptoggle_sidebar<CR>").unwrap();
+        assert!(!harness.state().sidebar_visible());
+        harness.keys("<Space>.").unwrap();
+        assert!(harness.state().sidebar_visible());
+        assert_eq!(harness.state().palette_history(), ["toggle_sidebar"]);
+
+        // Up recalls the last line, Ctrl+R searches older ones
+        harness.keys(":new_tab<CR>:1<CR>").unwrap();
+        assert_eq!(harness.state().tabs().len(), 2);
+        harness.keys(":<Up><Up><CR>").unwrap();
+        assert_eq!(harness.state().tabs().len(), 3);
+        harness.keys(":<C-r>new").unwrap();
+        harness.assert_screen_contains("(reverse-i-search)`new': new_tab");
+        harness.keys("<CR>").unwrap();
+        assert_eq!(harness.state().tabs().len(), 4);
+        assert_eq!(harness.state().ex_history(), ["new_tab", "1", "new_tab"]);
+
+        harness.app_mut().save_history();
+        let saved = SavedHistory::load(dir.path());
+        assert_eq!(saved.palette, ["toggle_sidebar"]);
+        assert_eq!(saved.ex, ["new_tab", "1", "new_tab"]);
+
+        // Histories come back with the workspace
+        let mut harness = TuiHarness::new().unwrap();
+        harness.app_mut().set_workspace(dir.path().to_path_buf());
+        harness.keys("<Space>.").unwrap();
+        assert!(!harness.state().sidebar_visible());
+    }
+
+    #[test]
+    fn test_macros() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("macros.toml");
+        let mut harness = TuiHarness::new().unwrap();
+        harness.app_mut().set_macro_file(Some(path.clone()));
+
+        harness.keys("qa").unwrap();
+        harness.assert_status_contains("recording @a");
+        harness.keys("iab<Esc>q").unwrap();
+        harness.assert_row_contains(0, "ab");
+
+        // A count replays the macro as often, as one undo step
+        harness.keys("2@a").unwrap();
+        harness.assert_row_contains(0, "ababab");
+        harness.keys("u").unwrap();
+        harness.assert_screen_lacks("abab");
+        harness.keys("@@").unwrap();
+        harness.assert_row_contains(0, "abab");
+
+        // Macros come back from the macro file
+        harness.app_mut().save_macros();
+        let mut harness = TuiHarness::new().unwrap();
+        harness.app_mut().set_macro_file(Some(path));
+        harness.keys("@a").unwrap();
+        harness.assert_row_contains(0, "ab");
+    }
+
+    #[test]
+    fn test_marks() {
+        use crate::session::SavedMarks;
+
+        let dir = tempfile::tempdir().unwrap();
+        let alpha = dir.path().join("alpha.txt");
+        let beta = dir.path().join("beta.txt");
+        fs::write(&alpha, "one\ntwo\nthree\n").unwrap();
+        fs::write(&beta, "beta contents\n").unwrap();
+
+        let mut harness = TuiHarness::new().unwrap();
+        harness.app_mut().set_workspace(dir.path().to_path_buf());
+        harness.state_mut().open_location(&alpha, 2, 2).unwrap();
+        harness.keys("mamA").unwrap();
+
+        // Marks stay on their line when lines are added above it
+        harness.keys("k0izero<CR><Esc>`a").unwrap();
+        harness.assert_status_contains("3:2");
+
+        // Lowercase marks belong to their buffer, uppercase ones open theirs
+        harness.state_mut().open_location(&beta, 1, 1).unwrap();
+        harness.keys("'a").unwrap();
+        harness.assert_status_contains("beta.txt");
+        harness.keys("'A").unwrap();
+        harness.assert_status_contains("alpha.txt");
+        harness.assert_status_contains("3:2");
+
+        harness.app_mut().save_marks();
+        let saved = SavedMarks::load(dir.path());
+        assert_eq!(saved.marks["A"].path, Path::new("alpha.txt"));
+        assert_eq!(saved.marks["A"].mark.line, 2);
+
+        // Global marks come back with the workspace
+        let mut harness = TuiHarness::new().unwrap();
+        harness.app_mut().set_workspace(dir.path().to_path_buf());
+        harness.keys("`A").unwrap();
+        harness.assert_status_contains("alpha.txt");
+        harness.assert_status_contains("3:2");
+    }
+
+    #[test]
+    fn test_terminal_copy_mode() {
+        let mut harness = TuiHarness::new().unwrap();
+        let output = ["cargo test".to_string(), "error: one failed".to_string()];
+        harness.state_mut().agent_finished(
+            "tester",
+            "write tests",
+            &SessionStatus::Completed,
+            &output,
+        );
+
+        // Copy mode freezes the agent results, starting on the last line
+        harness.keys("<Space>[").unwrap();
+        harness.assert_screen_contains("-- COPY -- [4/4]");
+        harness.keys("?error<CR>").unwrap();
+        harness.assert_screen_contains("-- COPY -- [3/4]");
+
+        // Yanking closes copy mode and fills the register
+        harness.keys("\"avwy").unwrap();
+        harness.assert_screen_lacks("-- COPY --");
+        assert_eq!(harness.state().registers().get(Some('a')), Some("error:"));
+
+        harness.keys("<C-1>\"ap").unwrap();
+        harness.assert_row_contains(0, "error:");
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut harness = TuiHarness::new().unwrap();
+        assert!(harness.state().is_running());
+        harness.keys("<Space>q").unwrap();
+        assert!(!harness.state().is_running());
+    }
+}
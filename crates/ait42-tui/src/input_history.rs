@@ -0,0 +1,290 @@
+//! Input History
+//!
+//! Commands entered in the command palette, on the `:` command line and in
+//! the terminal panel, oldest first. Each input keeps its own
+//! [`InputHistory`], browsed with Up/Down and searched backwards with
+//! [`ReverseSearch`]. [`SavedHistory`] keeps all three per workspace in
+//! `.ait42/history.toml`, next to the panel sizes.
+
+use crate::terminal_executor::TerminalHistoryEntry;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// History file, relative to the workspace
+const HISTORY_FILE: &str = ".ait42/history.toml";
+
+/// Entries kept per input
+pub const MAX_HISTORY: usize = 1000;
+
+/// Entered commands of one input, with the position while browsing them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// Entry shown while browsing, as an index into `entries`
+    position: Option<usize>,
+    /// Input typed before browsing started, shown again past the newest entry
+    draft: String,
+}
+
+impl InputHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// History of `entries`, oldest first, keeping the newest
+    /// [`MAX_HISTORY`]
+    pub fn from_entries(mut entries: Vec<String>) -> Self {
+        entries.retain(|entry| !entry.trim().is_empty());
+        let excess = entries.len().saturating_sub(MAX_HISTORY);
+        entries.drain(..excess);
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    /// Record an entered command, unless it is blank or repeats the last one
+    ///
+    /// Browsing starts over from the newest entry.
+    pub fn push(&mut self, entry: impl Into<String>) {
+        self.reset();
+        let entry = entry.into();
+        if entry.trim().is_empty() || self.last() == Some(entry.as_str()) {
+            return;
+        }
+        self.entries.push(entry);
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.remove(0);
+        }
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Most recent entry
+    pub fn last(&self) -> Option<&str> {
+        self.entries.last().map(String::as_str)
+    }
+
+    /// Entry `index` steps back, the most recent being 0
+    pub fn recent(&self, index: usize) -> Option<&str> {
+        let index = self.entries.len().checked_sub(index + 1)?;
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Entry at `index`, oldest first
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Step to the next older entry
+    ///
+    /// `input` is what was typed so far; it is kept when browsing starts so
+    /// [`InputHistory::newer`] can return to it. Stays on the oldest entry.
+    pub fn older(&mut self, input: &str) -> Option<&str> {
+        let position = match self.position {
+            None => {
+                self.draft = input.to_string();
+                self.entries.len().checked_sub(1)?
+            }
+            Some(position) => position.saturating_sub(1),
+        };
+        self.position = Some(position);
+        self.get(position)
+    }
+
+    /// Step to the next newer entry, or back to the typed input past the
+    /// newest
+    ///
+    /// `None` when not browsing.
+    pub fn newer(&mut self) -> Option<&str> {
+        let position = self.position? + 1;
+        if position < self.entries.len() {
+            self.position = Some(position);
+            self.get(position)
+        } else {
+            self.position = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// Stop browsing
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+
+    /// Index of the newest entry before `before` containing `query`
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+}
+
+/// Incremental reverse search of an [`InputHistory`] (Ctrl+R)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReverseSearch {
+    /// Text searched for
+    pub query: String,
+    /// Index of the entry found, `None` if nothing matches
+    pub found: Option<usize>,
+}
+
+impl ReverseSearch {
+    /// Search `history` for `query` again from the newest entry
+    pub fn update(&mut self, history: &InputHistory) {
+        self.found = history.search(&self.query, usize::MAX);
+    }
+
+    /// Step to the next older entry containing `query`, staying on the
+    /// current one if there is none
+    pub fn older(&mut self, history: &InputHistory) {
+        let before = self.found.unwrap_or(usize::MAX);
+        if let Some(found) = history.search(&self.query, before) {
+            self.found = Some(found);
+        }
+    }
+
+    /// Entry found in `history`
+    pub fn matched<'a>(&self, history: &'a InputHistory) -> Option<&'a str> {
+        history.get(self.found?)
+    }
+}
+
+/// Histories saved for a workspace
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedHistory {
+    /// Commands run from the command palette
+    #[serde(default)]
+    pub palette: Vec<String>,
+    /// Lines entered on the `:` command line
+    #[serde(default)]
+    pub ex: Vec<String>,
+    /// Commands run in the terminal panel, with when they ran and how they
+    /// exited
+    #[serde(default)]
+    pub terminal: Vec<TerminalHistoryEntry>,
+}
+
+impl SavedHistory {
+    /// File the histories of `workspace` are saved in
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(HISTORY_FILE)
+    }
+
+    /// Histories saved for `workspace`, empty if there are none
+    pub fn load(workspace: &Path) -> Self {
+        let path = Self::path(workspace);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid history {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save the histories for `workspace`
+    pub fn save(&self, workspace: &Path) -> anyhow::Result<()> {
+        let path = Self::path(workspace);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> InputHistory {
+        InputHistory::from_entries(entries.iter().map(|entry| entry.to_string()).collect())
+    }
+
+    #[test]
+    fn test_push_skips_blanks_and_repeats() {
+        let mut history = InputHistory::new();
+        history.push("save");
+        history.push("save");
+        history.push("  ");
+        history.push("quit");
+        assert_eq!(history.entries(), ["save", "quit"]);
+        assert_eq!(history.recent(0), Some("quit"));
+        assert_eq!(history.recent(1), Some("save"));
+        assert_eq!(history.recent(2), None);
+
+        for i in 0..MAX_HISTORY {
+            history.push(i.to_string());
+        }
+        assert_eq!(history.entries().len(), MAX_HISTORY);
+        assert_eq!(history.get(0), Some("0"));
+    }
+
+    #[test]
+    fn test_browse_returns_to_draft() {
+        let mut history = history(&["one", "two"]);
+        assert_eq!(history.newer(), None);
+
+        assert_eq!(history.older("tw"), Some("two"));
+        assert_eq!(history.older("ignored"), Some("one"));
+        assert_eq!(history.older("ignored"), Some("one"));
+        assert_eq!(history.newer(), Some("two"));
+        assert_eq!(history.newer(), Some("tw"));
+        assert_eq!(history.newer(), None);
+
+        assert_eq!(InputHistory::new().older("x"), None);
+    }
+
+    #[test]
+    fn test_reverse_search() {
+        let history = history(&["git status", "cargo test", "git log", "ls"]);
+        let mut search = ReverseSearch {
+            query: "git".to_string(),
+            found: None,
+        };
+        search.update(&history);
+        assert_eq!(search.matched(&history), Some("git log"));
+        search.older(&history);
+        assert_eq!(search.matched(&history), Some("git status"));
+        search.older(&history);
+        assert_eq!(search.matched(&history), Some("git status"));
+
+        search.query = "nothing".to_string();
+        search.update(&history);
+        assert_eq!(search.matched(&history), None);
+    }
+
+    #[test]
+    fn test_saved_per_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(SavedHistory::load(dir.path()), SavedHistory::default());
+
+        let saved = SavedHistory {
+            palette: vec!["toggle_sidebar".to_string()],
+            ex: vec!["w".to_string(), "42".to_string()],
+            terminal: vec![TerminalHistoryEntry {
+                command: "cargo test".to_string(),
+                timestamp: 1_700_000_000,
+                exit_code: Some(101),
+                duration_ms: Some(2300),
+            }],
+        };
+        saved.save(dir.path()).unwrap();
+        assert!(dir.path().join(".ait42/history.toml").exists());
+        assert_eq!(SavedHistory::load(dir.path()), saved);
+
+        // Terminal histories saved as plain commands still load
+        std::fs::write(SavedHistory::path(dir.path()), "terminal = [\"ls\"]\n").unwrap();
+        let saved = SavedHistory::load(dir.path());
+        assert_eq!(saved.terminal[0].command, "ls");
+        assert_eq!(saved.terminal[0].exit_code, None);
+
+        std::fs::write(SavedHistory::path(dir.path()), "palette = 3\n").unwrap();
+        assert_eq!(SavedHistory::load(dir.path()), SavedHistory::default());
+    }
+}
@@ -2,9 +2,11 @@
 //!
 //! Maps keyboard input to editor commands with Vim-like bindings.
 
+use ait42_config::{ConfigError, Key, KeyBindingConfig, KeyChord, KeymapMode};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
 
 /// Editor mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -61,13 +63,58 @@ pub enum EditorCommand {
     Undo,
     Redo,
 
+    // Structural editing
+    MoveItemUp,
+    MoveItemDown,
+    SwapArgumentBackward,
+    SwapArgumentForward,
+    RaiseNode,
+    JoinLines,
+    SplitJoinList,
+    SelectEnclosingNode,
+
+    // Folding
+    Fold,
+    Unfold,
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
+
+    // Macros
+    RecordMacro,
+    ReplayMacro,
+
+    // Marks
+    SetMark,
+    JumpToMark,
+
+    // Registers
+    SelectRegister,
+    Paste,
+    TerminalCopyMode,
+
     // Search
     Search,
     SearchNext,
     SearchPrevious,
+    Replace,
+    SearchWorkspace,
 
     // Commands
     OpenCommandPalette,
+    SubmitCommandLine,
+    CommandHistoryPrevious,
+    CommandHistoryNext,
+    CommandHistorySearch,
+    RepeatPaletteCommand,
+    ShowKeybindings,
+    ShowStashes,
+    StageHunks,
+    ShowGitLog,
+    ToggleBlame,
+    ShowAgentLauncher,
+    ConvertLineEndings,
+    ConvertEncoding,
     Save,
     Quit,
     ForceQuit,
@@ -87,16 +134,234 @@ pub enum EditorCommand {
     FocusTerminal,
     FocusNextPanel,
 
+    // Automatic focus changes
+    RunInTerminal,
+    ToggleResults,
+    FocusResults,
+    ReturnFocus,
+
+    // Panel sizes
+    GrowSidebar,
+    ShrinkSidebar,
+    GrowTerminal,
+    ShrinkTerminal,
+    ResetPanelSizes,
+
     // Phase 10b: Sidebar navigation
     SidebarMoveUp,
     SidebarMoveDown,
     SidebarSelect,
     SidebarToggleExpand,
+    SidebarExpandAll,
 
     // Other
     Noop,
 }
 
+impl EditorCommand {
+    /// Human-readable description used by which-key and the cheat-sheet
+    pub fn description(&self) -> &'static str {
+        use EditorCommand::*;
+
+        match self {
+            EnterInsertMode => "Enter insert mode",
+            EnterVisualMode => "Enter visual mode",
+            EnterCommandMode => "Enter command mode",
+            EnterNormalMode => "Return to normal mode",
+            MoveLeft => "Move cursor left",
+            MoveRight => "Move cursor right",
+            MoveUp => "Move cursor up",
+            MoveDown => "Move cursor down",
+            MoveWordForward => "Next word",
+            MoveWordBackward => "Previous word",
+            MoveLineStart => "Start of line",
+            MoveLineEnd => "End of line",
+            MovePageUp => "Page up",
+            MovePageDown => "Page down",
+            MoveFileStart => "Start of file",
+            MoveFileEnd => "End of file",
+            InsertChar(_) => "Insert character",
+            InsertNewline => "Insert newline",
+            DeleteChar => "Delete character",
+            DeleteLine => "Delete line",
+            DeleteWord => "Delete word",
+            Backspace => "Delete previous character",
+            Undo => "Undo",
+            Redo => "Redo",
+            MoveItemUp => "Move item up",
+            MoveItemDown => "Move item down",
+            SwapArgumentBackward => "Swap argument with previous",
+            SwapArgumentForward => "Swap argument with next",
+            RaiseNode => "Raise expression",
+            JoinLines => "Join lines",
+            SplitJoinList => "Split/join list",
+            SelectEnclosingNode => "Select enclosing node",
+            Fold => "Fold at cursor",
+            Unfold => "Unfold at cursor",
+            ToggleFold => "Toggle fold",
+            FoldAll => "Fold all",
+            UnfoldAll => "Unfold all",
+            RecordMacro => "Record macro / stop recording",
+            ReplayMacro => "Replay macro",
+            SetMark => "Set mark",
+            JumpToMark => "Jump to mark",
+            SelectRegister => "Select register for the next paste",
+            Paste => "Paste register",
+            TerminalCopyMode => "Copy from terminal output",
+            Search => "Search in buffer",
+            SearchNext => "Next search match",
+            SearchPrevious => "Previous search match",
+            Replace => "Search and replace",
+            SearchWorkspace => "Search workspace",
+            OpenCommandPalette => "Command palette",
+            SubmitCommandLine => "Run command line",
+            CommandHistoryPrevious => "Previous command in history",
+            CommandHistoryNext => "Next command in history",
+            CommandHistorySearch => "Search command history",
+            RepeatPaletteCommand => "Repeat last palette command",
+            ShowKeybindings => "Keybinding cheat-sheet",
+            ShowStashes => "Git stashes",
+            StageHunks => "Stage git hunks",
+            ShowGitLog => "Git history",
+            ToggleBlame => "Toggle git blame",
+            ShowAgentLauncher => "Launch agent",
+            ConvertLineEndings => "Switch line endings (LF/CRLF)",
+            ConvertEncoding => "Switch file encoding",
+            Save => "Save file",
+            Quit => "Quit",
+            ForceQuit => "Quit without saving",
+            NewTab => "New tab",
+            CloseTab => "Close tab",
+            NextTab => "Next tab",
+            PrevTab => "Previous tab",
+            SwitchTab(_) => "Switch to tab",
+            ToggleSidebar => "Toggle sidebar",
+            ToggleTerminal => "Toggle terminal",
+            FocusSidebar => "Focus sidebar",
+            FocusEditor => "Focus editor",
+            FocusTerminal => "Focus terminal",
+            FocusNextPanel => "Focus next panel",
+            RunInTerminal => "Run line in terminal",
+            ToggleResults => "Toggle agent results",
+            FocusResults => "Focus agent results",
+            ReturnFocus => "Return to previous focus",
+            GrowSidebar => "Widen sidebar",
+            ShrinkSidebar => "Narrow sidebar",
+            GrowTerminal => "Enlarge terminal",
+            ShrinkTerminal => "Shrink terminal",
+            ResetPanelSizes => "Reset panel sizes",
+            SidebarMoveUp => "Sidebar: move up",
+            SidebarMoveDown => "Sidebar: move down",
+            SidebarSelect => "Sidebar: open entry",
+            SidebarToggleExpand => "Sidebar: expand/collapse",
+            SidebarExpandAll => "Sidebar: expand all below",
+            Noop => "Do nothing",
+        }
+    }
+
+    /// Command with the configuration name `name`, e.g. `"save"`
+    ///
+    /// Names are listed in [`KeyBindingConfig::COMMANDS`]; commands taking an
+    /// argument cannot be bound by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        use EditorCommand::*;
+
+        Some(match name {
+            "enter_insert_mode" => EnterInsertMode,
+            "enter_visual_mode" => EnterVisualMode,
+            "enter_command_mode" => EnterCommandMode,
+            "enter_normal_mode" => EnterNormalMode,
+            "move_left" => MoveLeft,
+            "move_right" => MoveRight,
+            "move_up" => MoveUp,
+            "move_down" => MoveDown,
+            "move_word_forward" => MoveWordForward,
+            "move_word_backward" => MoveWordBackward,
+            "move_line_start" => MoveLineStart,
+            "move_line_end" => MoveLineEnd,
+            "move_page_up" => MovePageUp,
+            "move_page_down" => MovePageDown,
+            "move_file_start" => MoveFileStart,
+            "move_file_end" => MoveFileEnd,
+            "insert_newline" => InsertNewline,
+            "delete_char" => DeleteChar,
+            "delete_line" => DeleteLine,
+            "delete_word" => DeleteWord,
+            "backspace" => Backspace,
+            "undo" => Undo,
+            "redo" => Redo,
+            "move_item_up" => MoveItemUp,
+            "move_item_down" => MoveItemDown,
+            "swap_argument_backward" => SwapArgumentBackward,
+            "swap_argument_forward" => SwapArgumentForward,
+            "raise_node" => RaiseNode,
+            "join_lines" => JoinLines,
+            "split_join_list" => SplitJoinList,
+            "select_enclosing_node" => SelectEnclosingNode,
+            "fold" => Fold,
+            "unfold" => Unfold,
+            "toggle_fold" => ToggleFold,
+            "fold_all" => FoldAll,
+            "unfold_all" => UnfoldAll,
+            "record_macro" => RecordMacro,
+            "replay_macro" => ReplayMacro,
+            "set_mark" => SetMark,
+            "jump_to_mark" => JumpToMark,
+            "select_register" => SelectRegister,
+            "paste" => Paste,
+            "terminal_copy_mode" => TerminalCopyMode,
+            "search" => Search,
+            "search_next" => SearchNext,
+            "search_previous" => SearchPrevious,
+            "replace" => Replace,
+            "search_workspace" => SearchWorkspace,
+            "open_command_palette" => OpenCommandPalette,
+            "submit_command_line" => SubmitCommandLine,
+            "command_history_previous" => CommandHistoryPrevious,
+            "command_history_next" => CommandHistoryNext,
+            "command_history_search" => CommandHistorySearch,
+            "repeat_palette_command" => RepeatPaletteCommand,
+            "show_keybindings" => ShowKeybindings,
+            "show_stashes" => ShowStashes,
+            "stage_hunks" => StageHunks,
+            "show_git_log" => ShowGitLog,
+            "toggle_blame" => ToggleBlame,
+            "show_agent_launcher" => ShowAgentLauncher,
+            "convert_line_endings" => ConvertLineEndings,
+            "convert_encoding" => ConvertEncoding,
+            "save" => Save,
+            "quit" => Quit,
+            "force_quit" => ForceQuit,
+            "new_tab" => NewTab,
+            "close_tab" => CloseTab,
+            "next_tab" => NextTab,
+            "prev_tab" => PrevTab,
+            "toggle_sidebar" => ToggleSidebar,
+            "toggle_terminal" => ToggleTerminal,
+            "focus_sidebar" => FocusSidebar,
+            "focus_editor" => FocusEditor,
+            "focus_terminal" => FocusTerminal,
+            "focus_next_panel" => FocusNextPanel,
+            "run_in_terminal" => RunInTerminal,
+            "toggle_results" => ToggleResults,
+            "focus_results" => FocusResults,
+            "return_focus" => ReturnFocus,
+            "grow_sidebar" => GrowSidebar,
+            "shrink_sidebar" => ShrinkSidebar,
+            "grow_terminal" => GrowTerminal,
+            "shrink_terminal" => ShrinkTerminal,
+            "reset_panel_sizes" => ResetPanelSizes,
+            "sidebar_move_up" => SidebarMoveUp,
+            "sidebar_move_down" => SidebarMoveDown,
+            "sidebar_select" => SidebarSelect,
+            "sidebar_toggle_expand" => SidebarToggleExpand,
+            "sidebar_expand_all" => SidebarExpandAll,
+            "noop" => Noop,
+            _ => return None,
+        })
+    }
+}
+
 /// Key binding (key + modifiers)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct KeyBinding {
@@ -115,6 +380,143 @@ impl KeyBinding {
             modifiers: event.modifiers,
         }
     }
+
+    /// Binding of a configured chord, as the terminal reports it
+    ///
+    /// Uppercase letters carry Shift, and `Shift+g` is the same key as `G`.
+    pub fn from_chord(chord: &KeyChord) -> Self {
+        let mut modifiers = KeyModifiers::NONE;
+        modifiers.set(KeyModifiers::CONTROL, chord.ctrl);
+        modifiers.set(KeyModifiers::ALT, chord.alt);
+        modifiers.set(KeyModifiers::SHIFT, chord.shift);
+
+        let code = match chord.key {
+            Key::Char(c) if chord.shift && c.is_ascii_lowercase() => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            Key::Char(c) => {
+                if c.is_ascii_uppercase() {
+                    modifiers |= KeyModifiers::SHIFT;
+                }
+                KeyCode::Char(c)
+            }
+            Key::F(n) => KeyCode::F(n),
+            Key::Enter => KeyCode::Enter,
+            Key::Esc => KeyCode::Esc,
+            Key::Tab => KeyCode::Tab,
+            Key::Backspace => KeyCode::Backspace,
+            Key::Delete => KeyCode::Delete,
+            Key::Up => KeyCode::Up,
+            Key::Down => KeyCode::Down,
+            Key::Left => KeyCode::Left,
+            Key::Right => KeyCode::Right,
+            Key::Home => KeyCode::Home,
+            Key::End => KeyCode::End,
+            Key::PageUp => KeyCode::PageUp,
+            Key::PageDown => KeyCode::PageDown,
+        };
+        Self { code, modifiers }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        // Shifted characters already carry the shift in the char itself
+        let shifted_char = matches!(self.code, KeyCode::Char(c) if !c.is_ascii_alphabetic()
+            || c.is_ascii_uppercase());
+        if self.modifiers.contains(KeyModifiers::SHIFT) && !shifted_char {
+            write!(f, "Shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Group of bindings reachable after a prefix key (e.g. the Space leader)
+#[derive(Debug, Clone)]
+pub struct PrefixGroup {
+    /// Label shown in the which-key popup title
+    pub label: String,
+    /// Continuations of the prefix
+    pub bindings: HashMap<KeyBinding, EditorCommand>,
+}
+
+/// One row of the which-key popup or cheat-sheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindingEntry {
+    /// Context the binding is active in ("NORMAL", "SIDEBAR", ...)
+    pub context: &'static str,
+    /// Rendered key sequence, e.g. "Space s"
+    pub keys: String,
+    /// Bound command
+    pub command: EditorCommand,
+    /// Description from the command registry
+    pub description: &'static str,
+}
+
+impl KeyBindingEntry {
+    fn new(context: &'static str, keys: String, command: EditorCommand) -> Self {
+        let description = command.description();
+        Self {
+            context,
+            keys,
+            command,
+            description,
+        }
+    }
+}
+
+/// Prefix key that is waiting for its continuation
+#[derive(Debug, Clone)]
+pub struct PendingPrefix {
+    /// The prefix key that was pressed
+    pub key: KeyBinding,
+    /// Mode the prefix was pressed in
+    pub mode: Mode,
+    started: Instant,
+}
+
+impl PendingPrefix {
+    /// Start waiting for a continuation now
+    pub fn new(key: KeyBinding, mode: Mode) -> Self {
+        Self::started_at(key, mode, Instant::now())
+    }
+
+    /// Start waiting for a continuation at the given instant
+    pub fn started_at(key: KeyBinding, mode: Mode, started: Instant) -> Self {
+        Self { key, mode, started }
+    }
+
+    /// Whether the which-key popup should be shown at `now`
+    pub fn timed_out(&self, now: Instant, timeout: Duration) -> bool {
+        now.saturating_duration_since(self.started) >= timeout
+    }
 }
 
 /// Key mapping for all editor modes
@@ -125,6 +527,7 @@ pub struct KeyMap {
     visual_mode: HashMap<KeyBinding, EditorCommand>,
     command_mode: HashMap<KeyBinding, EditorCommand>,
     sidebar_mode: HashMap<KeyBinding, EditorCommand>,
+    prefixes: HashMap<Mode, HashMap<KeyBinding, PrefixGroup>>,
 }
 
 impl KeyMap {
@@ -143,15 +546,102 @@ impl KeyMap {
         Self::setup_command_mode(&mut command_mode);
         Self::setup_sidebar_mode(&mut sidebar_mode);
 
+        let mut prefixes = HashMap::new();
+        prefixes.insert(Mode::Normal, Self::setup_normal_prefixes());
+
         Self {
             normal_mode,
             insert_mode,
             visual_mode,
             command_mode,
             sidebar_mode,
+            prefixes,
         }
     }
 
+    fn setup_normal_prefixes() -> HashMap<KeyBinding, PrefixGroup> {
+        use EditorCommand::*;
+        use KeyCode::*;
+
+        let mut prefixes = HashMap::new();
+
+        // Space leader
+        let mut leader = HashMap::new();
+        leader.insert(kb(Char('s'), NONE), Save);
+        leader.insert(kb(Char('q'), NONE), Quit);
+        leader.insert(kb(Char('p'), NONE), OpenCommandPalette);
+        leader.insert(kb(Char('e'), NONE), ToggleSidebar);
+        leader.insert(kb(Char('t'), NONE), ToggleTerminal);
+        leader.insert(kb(Char('n'), NONE), NewTab);
+        leader.insert(kb(Char('x'), NONE), CloseTab);
+        leader.insert(kb(Char('?'), SHIFT), ShowKeybindings);
+        leader.insert(kb(Char('z'), NONE), ShowStashes);
+        leader.insert(kb(Char('h'), NONE), StageHunks);
+        leader.insert(kb(Char('g'), NONE), ShowGitLog);
+        leader.insert(kb(Char('b'), NONE), ToggleBlame);
+        leader.insert(kb(Char('a'), NONE), ShowAgentLauncher);
+        leader.insert(kb(Char('/'), NONE), SearchWorkspace);
+        leader.insert(kb(Char('l'), NONE), ConvertLineEndings);
+        leader.insert(kb(Char('c'), NONE), ConvertEncoding);
+        leader.insert(kb(Char('.'), NONE), RepeatPaletteCommand);
+        leader.insert(kb(Char('['), NONE), TerminalCopyMode);
+        prefixes.insert(
+            kb(Char(' '), NONE),
+            PrefixGroup {
+                label: "leader".to_string(),
+                bindings: leader,
+            },
+        );
+
+        // Goto
+        let mut goto = HashMap::new();
+        goto.insert(kb(Char('g'), NONE), MoveFileStart);
+        goto.insert(kb(Char('e'), NONE), MoveFileEnd);
+        goto.insert(kb(Char('t'), NONE), NextTab);
+        goto.insert(kb(Char('T'), SHIFT), PrevTab);
+        prefixes.insert(
+            kb(Char('g'), NONE),
+            PrefixGroup {
+                label: "goto".to_string(),
+                bindings: goto,
+            },
+        );
+
+        // Structural editing
+        let mut structure = HashMap::new();
+        structure.insert(kb(Char('k'), NONE), MoveItemUp);
+        structure.insert(kb(Char('j'), NONE), MoveItemDown);
+        structure.insert(kb(Char('h'), NONE), SwapArgumentBackward);
+        structure.insert(kb(Char('l'), NONE), SwapArgumentForward);
+        structure.insert(kb(Char('r'), NONE), RaiseNode);
+        structure.insert(kb(Char('s'), NONE), SplitJoinList);
+        structure.insert(kb(Char('o'), NONE), SelectEnclosingNode);
+        prefixes.insert(
+            kb(Char('s'), NONE),
+            PrefixGroup {
+                label: "structure".to_string(),
+                bindings: structure,
+            },
+        );
+
+        // Folding
+        let mut fold = HashMap::new();
+        fold.insert(kb(Char('a'), NONE), ToggleFold);
+        fold.insert(kb(Char('c'), NONE), Fold);
+        fold.insert(kb(Char('o'), NONE), Unfold);
+        fold.insert(kb(Char('M'), SHIFT), FoldAll);
+        fold.insert(kb(Char('R'), SHIFT), UnfoldAll);
+        prefixes.insert(
+            kb(Char('z'), NONE),
+            PrefixGroup {
+                label: "fold".to_string(),
+                bindings: fold,
+            },
+        );
+
+        prefixes
+    }
+
     fn setup_normal_mode(map: &mut HashMap<KeyBinding, EditorCommand>) {
         use EditorCommand::*;
         use KeyCode::*;
@@ -186,20 +676,36 @@ impl KeyMap {
         // Editing
         map.insert(kb(Char('x'), NONE), DeleteChar);
         map.insert(kb(Char('d'), NONE), DeleteLine);
+        map.insert(kb(Char('J'), SHIFT), JoinLines);
+        map.insert(kb(Char('o'), ALT), SelectEnclosingNode);
 
         // Undo/Redo
         map.insert(kb(Char('u'), NONE), Undo);
         map.insert(kb(Char('r'), CTRL), Redo);
 
+        // Macros
+        map.insert(kb(Char('q'), NONE), RecordMacro);
+        map.insert(kb(Char('@'), SHIFT), ReplayMacro);
+
+        // Marks
+        map.insert(kb(Char('m'), NONE), SetMark);
+        map.insert(kb(Char('`'), NONE), JumpToMark);
+        map.insert(kb(Char('\''), NONE), JumpToMark);
+
+        // Registers
+        map.insert(kb(Char('"'), SHIFT), SelectRegister);
+        map.insert(kb(Char('p'), NONE), Paste);
+
         // Search
         map.insert(kb(Char('/'), NONE), Search);
         map.insert(kb(Char('n'), NONE), SearchNext);
         map.insert(kb(Char('N'), SHIFT), SearchPrevious);
+        map.insert(kb(Char('R'), SHIFT), Replace);
 
         // Commands
         map.insert(kb(Char('p'), CTRL), OpenCommandPalette);
         map.insert(kb(Char('s'), CTRL), Save);
-        map.insert(kb(Char('q'), NONE), Quit);
+        map.insert(kb(Char('?'), SHIFT), ShowKeybindings);
 
         // Phase 10b: Tab management
         map.insert(kb(Char('t'), CTRL), NewTab);
@@ -216,7 +722,20 @@ impl KeyMap {
         map.insert(kb(Char('1'), CTRL), FocusEditor);
         map.insert(kb(Char('2'), CTRL), FocusSidebar);
         map.insert(kb(Char('3'), CTRL), FocusTerminal);
+        map.insert(kb(Char('4'), CTRL), FocusResults);
         map.insert(kb(Tab, NONE), FocusNextPanel);
+
+        // Automatic focus changes
+        map.insert(kb(Enter, ALT), RunInTerminal);
+        map.insert(kb(Char('r'), ALT), ToggleResults);
+        map.insert(kb(Char('o'), CTRL), ReturnFocus);
+
+        // Panel sizes
+        map.insert(kb(Right, ALT), GrowSidebar);
+        map.insert(kb(Left, ALT), ShrinkSidebar);
+        map.insert(kb(Up, ALT), GrowTerminal);
+        map.insert(kb(Down, ALT), ShrinkTerminal);
+        map.insert(kb(Char('='), ALT), ResetPanelSizes);
     }
 
     fn setup_insert_mode(map: &mut HashMap<KeyBinding, EditorCommand>) {
@@ -257,6 +776,16 @@ impl KeyMap {
         map.insert(kb(Right, NONE), MoveRight);
         map.insert(kb(Up, NONE), MoveUp);
         map.insert(kb(Down, NONE), MoveDown);
+
+        // Structural selection
+        map.insert(kb(Char('o'), ALT), SelectEnclosingNode);
+        map.insert(kb(Char('r'), NONE), RaiseNode);
+
+        // Replace within the selection
+        map.insert(kb(Char('R'), SHIFT), Replace);
+
+        // Run the selection in the terminal
+        map.insert(kb(Enter, ALT), RunInTerminal);
     }
 
     fn setup_command_mode(map: &mut HashMap<KeyBinding, EditorCommand>) {
@@ -265,12 +794,17 @@ impl KeyMap {
 
         // Exit command mode
         map.insert(kb(Esc, NONE), EnterNormalMode);
-        map.insert(kb(Enter, NONE), EnterNormalMode);
+        map.insert(kb(Enter, NONE), SubmitCommandLine);
 
         // Navigation
         map.insert(kb(Left, NONE), MoveLeft);
         map.insert(kb(Right, NONE), MoveRight);
 
+        // History
+        map.insert(kb(Up, NONE), CommandHistoryPrevious);
+        map.insert(kb(Down, NONE), CommandHistoryNext);
+        map.insert(kb(Char('r'), CTRL), CommandHistorySearch);
+
         // Editing
         map.insert(kb(KeyCode::Backspace, NONE), EditorCommand::Backspace);
     }
@@ -288,25 +822,25 @@ impl KeyMap {
         // Sidebar actions
         map.insert(kb(Enter, NONE), SidebarSelect);
         map.insert(kb(Char(' '), NONE), SidebarToggleExpand);
+        map.insert(kb(Char('*'), NONE), SidebarExpandAll);
 
         // Focus switching
         map.insert(kb(Esc, NONE), FocusEditor);
         map.insert(kb(Tab, NONE), FocusNextPanel);
+        map.insert(kb(Char('o'), CTRL), ReturnFocus);
 
         // Panel visibility
         map.insert(kb(Char('b'), CTRL), ToggleSidebar);
         map.insert(kb(Char('`'), CTRL), ToggleTerminal);
+
+        // Panel sizes
+        map.insert(kb(Right, ALT), GrowSidebar);
+        map.insert(kb(Left, ALT), ShrinkSidebar);
     }
 
     /// Look up command for key binding in current mode
     pub fn lookup(&self, mode: Mode, key: KeyBinding) -> Option<&EditorCommand> {
-        let map = match mode {
-            Mode::Normal => &self.normal_mode,
-            Mode::Insert => &self.insert_mode,
-            Mode::Visual => &self.visual_mode,
-            Mode::Command => &self.command_mode,
-        };
-        map.get(&key)
+        self.mode_map(mode).get(&key)
     }
 
     /// Look up command for key binding in sidebar mode
@@ -324,6 +858,119 @@ impl KeyMap {
         };
         map.insert(key, command);
     }
+
+    fn mode_map(&self, mode: Mode) -> &HashMap<KeyBinding, EditorCommand> {
+        match mode {
+            Mode::Normal => &self.normal_mode,
+            Mode::Insert => &self.insert_mode,
+            Mode::Visual => &self.visual_mode,
+            Mode::Command => &self.command_mode,
+        }
+    }
+
+    /// Whether `key` starts a multi-key sequence in `mode`
+    pub fn is_prefix(&self, mode: Mode, key: &KeyBinding) -> bool {
+        self.prefixes
+            .get(&mode)
+            .is_some_and(|groups| groups.contains_key(key))
+    }
+
+    /// Look up the command bound to `prefix` followed by `key`
+    pub fn lookup_sequence(
+        &self,
+        mode: Mode,
+        prefix: &KeyBinding,
+        key: &KeyBinding,
+    ) -> Option<&EditorCommand> {
+        self.prefixes
+            .get(&mode)?
+            .get(prefix)?
+            .bindings
+            .get(key)
+    }
+
+    /// Label of a prefix group (e.g. "leader")
+    pub fn prefix_label(&self, mode: Mode, prefix: &KeyBinding) -> Option<&str> {
+        self.prefixes
+            .get(&mode)?
+            .get(prefix)
+            .map(|group| group.label.as_str())
+    }
+
+    /// Continuations available after `prefix`, sorted by key
+    pub fn continuations(&self, mode: Mode, prefix: &KeyBinding) -> Vec<KeyBindingEntry> {
+        let Some(group) = self.prefixes.get(&mode).and_then(|g| g.get(prefix)) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<KeyBindingEntry> = group
+            .bindings
+            .iter()
+            .map(|(key, command)| KeyBindingEntry::new(mode.as_str(), key.to_string(), command.clone()))
+            .collect();
+        entries.sort_by(|a, b| a.keys.cmp(&b.keys));
+        entries
+    }
+
+    /// Add a binding reachable after `prefix`, creating the prefix group if needed
+    pub fn add_prefix_binding(
+        &mut self,
+        mode: Mode,
+        prefix: KeyBinding,
+        key: KeyBinding,
+        command: EditorCommand,
+    ) {
+        self.prefixes
+            .entry(mode)
+            .or_default()
+            .entry(prefix)
+            .or_insert_with(|| PrefixGroup {
+                label: String::new(),
+                bindings: HashMap::new(),
+            })
+            .bindings
+            .insert(key, command);
+    }
+
+    /// Every binding in the key map, used to build the cheat-sheet
+    pub fn all_bindings(&self) -> Vec<KeyBindingEntry> {
+        let mut entries = Vec::new();
+
+        for mode in [Mode::Normal, Mode::Insert, Mode::Visual, Mode::Command] {
+            let mut mode_entries: Vec<KeyBindingEntry> = self
+                .mode_map(mode)
+                .iter()
+                .map(|(key, command)| {
+                    KeyBindingEntry::new(mode.as_str(), key.to_string(), command.clone())
+                })
+                .collect();
+
+            if let Some(groups) = self.prefixes.get(&mode) {
+                for (prefix, group) in groups {
+                    mode_entries.extend(group.bindings.iter().map(|(key, command)| {
+                        KeyBindingEntry::new(
+                            mode.as_str(),
+                            format!("{} {}", prefix, key),
+                            command.clone(),
+                        )
+                    }));
+                }
+            }
+
+            mode_entries.sort_by(|a, b| a.keys.cmp(&b.keys));
+            entries.extend(mode_entries);
+        }
+
+        let mut sidebar_entries: Vec<KeyBindingEntry> = self
+            .sidebar_mode
+            .iter()
+            .map(|(key, command)| KeyBindingEntry::new("SIDEBAR", key.to_string(), command.clone()))
+            .collect();
+        sidebar_entries.sort_by(|a, b| a.keys.cmp(&b.keys));
+        entries.extend(sidebar_entries);
+
+        entries
+    }
 }
 
 impl Default for KeyMap {
@@ -342,19 +989,44 @@ const CTRL: KeyModifiers = KeyModifiers::CONTROL;
 const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
 const ALT: KeyModifiers = KeyModifiers::ALT;
 
-/// Configuration for custom key bindings
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct KeyBindConfig {
-    pub preset: Option<String>,
-    pub custom: Option<HashMap<String, String>>,
-}
-
 impl KeyMap {
-    /// Create from configuration
-    pub fn from_config(config: &KeyBindConfig) -> Self {
-        // For now, just use default
-        // TODO: Implement custom binding parsing
-        Self::default()
+    /// Create the key map of a configuration
+    ///
+    /// Starts from the Vim preset, the only one so far, and applies the
+    /// custom bindings on top, replacing preset bindings of the same keys.
+    pub fn from_config(config: &KeyBindingConfig) -> ait42_config::Result<Self> {
+        let mut keymap = Self::default();
+        for entry in config.bindings()? {
+            let command = EditorCommand::from_name(&entry.command).ok_or_else(|| {
+                ConfigError::ValidationError(format!("Unknown command \"{}\"", entry.command))
+            })?;
+            let keys: Vec<KeyBinding> = entry.keys.iter().map(KeyBinding::from_chord).collect();
+
+            let mode = match entry.mode {
+                KeymapMode::Normal => Mode::Normal,
+                KeymapMode::Insert => Mode::Insert,
+                KeymapMode::Visual => Mode::Visual,
+                KeymapMode::Command => Mode::Command,
+                KeymapMode::Sidebar => {
+                    keymap.sidebar_mode.insert(keys[0].clone(), command);
+                    continue;
+                }
+            };
+            match keys.as_slice() {
+                [key] => {
+                    // A single key bound over a prefix replaces the whole group
+                    if let Some(groups) = keymap.prefixes.get_mut(&mode) {
+                        groups.remove(key);
+                    }
+                    keymap.add_binding(mode, key.clone(), command)
+                }
+                [prefix, key] => {
+                    keymap.add_prefix_binding(mode, prefix.clone(), key.clone(), command)
+                }
+                _ => unreachable!("sequences are validated to one or two keys"),
+            }
+        }
+        Ok(keymap)
     }
 }
 
@@ -421,6 +1093,227 @@ mod tests {
         assert_eq!(cmd, Some(&EditorCommand::Undo));
     }
 
+    #[test]
+    fn test_leader_prefix_sequence() {
+        let keymap = KeyMap::default();
+        let space = KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE);
+        let s_key = KeyBinding::new(KeyCode::Char('s'), KeyModifiers::NONE);
+
+        assert!(keymap.is_prefix(Mode::Normal, &space));
+        assert!(!keymap.is_prefix(Mode::Insert, &space));
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &s_key),
+            Some(&EditorCommand::Save)
+        );
+        assert_eq!(keymap.prefix_label(Mode::Normal, &space), Some("leader"));
+    }
+
+    #[test]
+    fn test_structure_prefix() {
+        let keymap = KeyMap::default();
+        let s_key = KeyBinding::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        let k_key = KeyBinding::new(KeyCode::Char('k'), KeyModifiers::NONE);
+
+        assert_eq!(keymap.prefix_label(Mode::Normal, &s_key), Some("structure"));
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &s_key, &k_key),
+            Some(&EditorCommand::MoveItemUp)
+        );
+
+        let alt_o = KeyBinding::new(KeyCode::Char('o'), KeyModifiers::ALT);
+        assert_eq!(
+            keymap.lookup(Mode::Visual, alt_o),
+            Some(&EditorCommand::SelectEnclosingNode)
+        );
+    }
+
+    #[test]
+    fn test_leader_opens_stashes() {
+        let keymap = KeyMap::default();
+        let space = KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE);
+        let z_key = KeyBinding::new(KeyCode::Char('z'), KeyModifiers::NONE);
+
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &z_key),
+            Some(&EditorCommand::ShowStashes)
+        );
+
+        let g_key = KeyBinding::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &g_key),
+            Some(&EditorCommand::ShowGitLog)
+        );
+
+        let b_key = KeyBinding::new(KeyCode::Char('b'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &b_key),
+            Some(&EditorCommand::ToggleBlame)
+        );
+
+        let h_key = KeyBinding::new(KeyCode::Char('h'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &h_key),
+            Some(&EditorCommand::StageHunks)
+        );
+
+        let a_key = KeyBinding::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &a_key),
+            Some(&EditorCommand::ShowAgentLauncher)
+        );
+
+        let slash_key = KeyBinding::new(KeyCode::Char('/'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &slash_key),
+            Some(&EditorCommand::SearchWorkspace)
+        );
+    }
+
+    #[test]
+    fn test_continuations_have_descriptions() {
+        let keymap = KeyMap::default();
+        let space = KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE);
+
+        let entries = keymap.continuations(Mode::Normal, &space);
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|e| !e.description.is_empty()));
+        assert!(entries
+            .iter()
+            .any(|e| e.keys == "s" && e.description == "Save file"));
+    }
+
+    #[test]
+    fn test_add_prefix_binding() {
+        let mut keymap = KeyMap::default();
+        let ctrl_k = KeyBinding::new(KeyCode::Char('k'), KeyModifiers::CONTROL);
+        let u_key = KeyBinding::new(KeyCode::Char('u'), KeyModifiers::NONE);
+        keymap.add_prefix_binding(Mode::Normal, ctrl_k.clone(), u_key.clone(), EditorCommand::Undo);
+
+        assert!(keymap.is_prefix(Mode::Normal, &ctrl_k));
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &ctrl_k, &u_key),
+            Some(&EditorCommand::Undo)
+        );
+    }
+
+    #[test]
+    fn test_all_bindings_includes_sequences() {
+        let keymap = KeyMap::default();
+        let entries = keymap.all_bindings();
+
+        assert!(entries
+            .iter()
+            .any(|e| e.context == "NORMAL" && e.keys == "Space s"));
+        assert!(entries
+            .iter()
+            .any(|e| e.context == "INSERT" && e.command == EditorCommand::EnterNormalMode));
+        assert!(entries.iter().any(|e| e.context == "SIDEBAR"));
+    }
+
+    #[test]
+    fn test_every_configurable_command_has_a_name() {
+        for name in KeyBindingConfig::COMMANDS {
+            assert!(EditorCommand::from_name(name).is_some(), "{}", name);
+        }
+        assert_eq!(EditorCommand::from_name("insert_char"), None);
+    }
+
+    #[test]
+    fn test_key_binding_from_chord() {
+        let chord = |notation| KeyBinding::from_chord(&KeyChord::parse(notation).unwrap());
+        assert_eq!(chord("Ctrl+s"), kb(KeyCode::Char('s'), CTRL));
+        assert_eq!(chord("G"), kb(KeyCode::Char('G'), SHIFT));
+        assert_eq!(chord("Shift+g"), kb(KeyCode::Char('G'), SHIFT));
+        assert_eq!(chord("Alt+Up"), kb(KeyCode::Up, ALT));
+        assert_eq!(chord("Space"), kb(KeyCode::Char(' '), NONE));
+        assert_eq!(chord("Ctrl+Shift+Tab"), kb(KeyCode::Tab, CTRL | SHIFT));
+    }
+
+    #[test]
+    fn test_keymap_from_config() {
+        let mut config = KeyBindingConfig::vim();
+        config
+            .custom
+            .insert("insert:j k".to_string(), "enter_normal_mode".to_string());
+        config.keymap.insert(
+            "normal".to_string(),
+            HashMap::from([
+                ("Ctrl+s".to_string(), "quit".to_string()),
+                ("Space w".to_string(), "save".to_string()),
+                ("g".to_string(), "move_file_start".to_string()),
+            ]),
+        );
+        config.keymap.insert(
+            "sidebar".to_string(),
+            HashMap::from([("l".to_string(), "sidebar_select".to_string())]),
+        );
+
+        let keymap = KeyMap::from_config(&config).unwrap();
+        let ctrl_s = kb(KeyCode::Char('s'), CTRL);
+        let space = kb(KeyCode::Char(' '), NONE);
+        let j = kb(KeyCode::Char('j'), NONE);
+        let g = kb(KeyCode::Char('g'), NONE);
+
+        assert_eq!(keymap.lookup(Mode::Normal, ctrl_s.clone()), Some(&EditorCommand::Quit));
+        // Insert mode keeps its preset binding
+        assert_eq!(keymap.lookup(Mode::Insert, ctrl_s), Some(&EditorCommand::Save));
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Normal, &space, &kb(KeyCode::Char('w'), NONE)),
+            Some(&EditorCommand::Save)
+        );
+        assert!(keymap.is_prefix(Mode::Insert, &j));
+        assert_eq!(
+            keymap.lookup_sequence(Mode::Insert, &j, &kb(KeyCode::Char('k'), NONE)),
+            Some(&EditorCommand::EnterNormalMode)
+        );
+        // Binding the goto prefix on its own removes the group
+        assert!(!keymap.is_prefix(Mode::Normal, &g));
+        assert_eq!(keymap.lookup(Mode::Normal, g), Some(&EditorCommand::MoveFileStart));
+        assert_eq!(
+            keymap.lookup_sidebar(kb(KeyCode::Char('l'), NONE)),
+            Some(&EditorCommand::SidebarSelect)
+        );
+
+        config
+            .custom
+            .insert("Ctrl+x".to_string(), "explode".to_string());
+        assert!(KeyMap::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_key_binding_display() {
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char('w'), KeyModifiers::CONTROL).to_string(),
+            "Ctrl+W"
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE).to_string(),
+            "Space"
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char('G'), KeyModifiers::SHIFT).to_string(),
+            "G"
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::Tab, KeyModifiers::CONTROL | KeyModifiers::SHIFT).to_string(),
+            "Ctrl+Shift+Tab"
+        );
+    }
+
+    #[test]
+    fn test_pending_prefix_timeout() {
+        let start = Instant::now();
+        let pending = PendingPrefix::started_at(
+            KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            Mode::Normal,
+            start,
+        );
+        let timeout = Duration::from_millis(500);
+
+        assert!(!pending.timed_out(start + Duration::from_millis(100), timeout));
+        assert!(pending.timed_out(start + Duration::from_millis(500), timeout));
+    }
+
     #[test]
     fn test_mode_display() {
         assert_eq!(Mode::Normal.as_str(), "NORMAL");
@@ -1,13 +1,176 @@
 //! Layout Management
 //!
 //! Handles dynamic layout calculation based on terminal size and UI state.
+//!
+//! The sidebar and terminal panel are sized as a percentage of the terminal
+//! so they keep their proportions when it is resized. Each panel has
+//! [`PanelLimits`] that keep it usable and leave room for the editor.
+//! Sizes are saved per workspace in `.ait42/layout.toml`.
 
 use ratatui::layout::{Constraint, Direction, Layout as RatatuiLayout, Rect};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 /// Minimum terminal dimensions
 pub const MIN_WIDTH: u16 = 80;
 pub const MIN_HEIGHT: u16 = 24;
 
+/// Percentage the resize commands grow or shrink a panel by
+pub const RESIZE_STEP: u16 = 5;
+
+/// Panel sizes file, relative to the workspace root
+const PANEL_SIZES_FILE: &str = ".ait42/layout.toml";
+
+/// Panel that can be resized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    /// File tree on the left
+    Sidebar,
+    /// Terminal below the editor
+    Terminal,
+}
+
+impl Panel {
+    /// Size bounds of the panel
+    pub fn limits(self) -> PanelLimits {
+        match self {
+            Panel::Sidebar => PanelLimits {
+                min_cells: 12,
+                min_percent: 10,
+                max_percent: 60,
+            },
+            Panel::Terminal => PanelLimits {
+                min_cells: 3,
+                min_percent: 10,
+                max_percent: 70,
+            },
+        }
+    }
+}
+
+/// Size bounds of a resizable panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelLimits {
+    /// Smallest size in cells, so narrow terminals still show something
+    pub min_cells: u16,
+    /// Smallest share of the terminal, in percent
+    pub min_percent: u16,
+    /// Largest share of the terminal, in percent
+    pub max_percent: u16,
+}
+
+impl PanelLimits {
+    /// `percent` within the limits
+    pub fn clamp_percent(&self, percent: u16) -> u16 {
+        percent.clamp(self.min_percent, self.max_percent)
+    }
+
+    /// Cells taken by a panel of `percent` out of `total` cells
+    ///
+    /// Never less than `min_cells` unless that would exceed the maximum
+    /// share, which always leaves room for the editor.
+    pub fn cells(&self, percent: u16, total: u16) -> u16 {
+        let max = percent_to_cells(self.max_percent, total);
+        percent_to_cells(self.clamp_percent(percent), total)
+            .max(self.min_cells)
+            .min(max)
+    }
+}
+
+/// Cells of `total` covered by `percent`, rounded down
+pub fn percent_to_cells(percent: u16, total: u16) -> u16 {
+    (u32::from(total) * u32::from(percent.min(100)) / 100) as u16
+}
+
+/// Share of `total` covered by `cells`, in percent, rounded to nearest
+pub fn cells_to_percent(cells: u16, total: u16) -> u16 {
+    if total == 0 {
+        return 0;
+    }
+    ((u32::from(cells.min(total)) * 100 + u32::from(total) / 2) / u32::from(total)) as u16
+}
+
+/// Sizes of the resizable panels, in percent of the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelSizes {
+    /// Sidebar width, in percent of the terminal width
+    pub sidebar_percent: u16,
+    /// Terminal panel height, in percent of the terminal height
+    pub terminal_percent: u16,
+}
+
+impl Default for PanelSizes {
+    fn default() -> Self {
+        Self {
+            sidebar_percent: 25,
+            terminal_percent: 30,
+        }
+    }
+}
+
+impl PanelSizes {
+    /// Size of `panel`, in percent
+    pub fn percent(&self, panel: Panel) -> u16 {
+        match panel {
+            Panel::Sidebar => self.sidebar_percent,
+            Panel::Terminal => self.terminal_percent,
+        }
+    }
+
+    /// Set the size of `panel`, clamped to its limits
+    pub fn set_percent(&mut self, panel: Panel, percent: u16) {
+        let percent = panel.limits().clamp_percent(percent);
+        match panel {
+            Panel::Sidebar => self.sidebar_percent = percent,
+            Panel::Terminal => self.terminal_percent = percent,
+        }
+    }
+
+    /// Grow (or shrink, if `delta` is negative) `panel` by `delta` percent
+    pub fn resize(&mut self, panel: Panel, delta: i16) {
+        let percent = self.percent(panel).saturating_add_signed(delta);
+        self.set_percent(panel, percent);
+    }
+
+    /// Sizes with every panel within its limits
+    pub(crate) fn clamped(mut self) -> Self {
+        for panel in [Panel::Sidebar, Panel::Terminal] {
+            self.set_percent(panel, self.percent(panel));
+        }
+        self
+    }
+
+    /// File the sizes of `workspace` are saved in
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(PANEL_SIZES_FILE)
+    }
+
+    /// Sizes saved for `workspace`, the defaults if there are none
+    pub fn load(workspace: &Path) -> Self {
+        let path = Self::path(workspace);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str::<Self>(&content)
+                .map(Self::clamped)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring invalid panel sizes {}: {}", path.display(), e);
+                    Self::default()
+                }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save the sizes for `workspace`
+    pub fn save(&self, workspace: &Path) -> anyhow::Result<()> {
+        let path = Self::path(workspace);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
 /// Editor layout configuration
 #[derive(Debug, Clone)]
 pub struct LayoutConfig {
@@ -15,14 +178,20 @@ pub struct LayoutConfig {
     pub show_line_numbers: bool,
     /// Line number gutter width
     pub line_number_width: u16,
+    /// Show blame annotations left of the line numbers
+    pub show_blame: bool,
+    /// Blame gutter width
+    pub blame_width: u16,
     /// Show command palette
     pub show_command_palette: bool,
     /// Command palette height
     pub command_palette_height: u16,
     /// Show sidebar (file tree)
     pub show_sidebar: bool,
-    /// Sidebar width
-    pub sidebar_width: u16,
+    /// Show terminal panel
+    pub show_terminal: bool,
+    /// Sidebar and terminal panel sizes
+    pub panel_sizes: PanelSizes,
 }
 
 impl Default for LayoutConfig {
@@ -30,10 +199,13 @@ impl Default for LayoutConfig {
         Self {
             show_line_numbers: true,
             line_number_width: 5,
+            show_blame: false,
+            blame_width: 24,
             show_command_palette: false,
             command_palette_height: 10,
             show_sidebar: false,
-            sidebar_width: 30,
+            show_terminal: false,
+            panel_sizes: PanelSizes::default(),
         }
     }
 }
@@ -47,12 +219,16 @@ pub struct EditorLayout {
     pub editor: Rect,
     /// Line number gutter area
     pub line_numbers: Option<Rect>,
+    /// Blame gutter area (when visible)
+    pub blame: Option<Rect>,
     /// Status line area (bottom bar)
     pub statusline: Rect,
     /// Command palette area (when visible)
     pub command_palette: Option<Rect>,
     /// Sidebar area (when visible)
     pub sidebar: Option<Rect>,
+    /// Terminal panel area (when visible)
+    pub terminal: Option<Rect>,
 }
 
 impl EditorLayout {
@@ -75,39 +251,54 @@ impl EditorLayout {
 
         // Split main areas: [sidebar?] [main content]
         let (sidebar, main_area) = if config.show_sidebar {
+            let width = Panel::Sidebar
+                .limits()
+                .cells(config.panel_sizes.sidebar_percent, full.width);
             let chunks = RatatuiLayout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Length(config.sidebar_width), Constraint::Min(0)])
+                .constraints([Constraint::Length(width), Constraint::Min(0)])
                 .split(full);
             (Some(chunks[0]), chunks[1])
         } else {
             (None, full)
         };
 
-        // Split vertically: [editor] [command_palette?] [statusline]
-        let vertical_constraints = if config.show_command_palette {
-            vec![
-                Constraint::Min(0),
-                Constraint::Length(config.command_palette_height),
-                Constraint::Length(1),
-            ]
-        } else {
-            vec![Constraint::Min(0), Constraint::Length(1)]
-        };
+        // Split vertically: [editor] [terminal?] [command_palette?] [statusline]
+        let mut vertical_constraints = vec![Constraint::Min(0)];
+        if config.show_terminal {
+            let height = Panel::Terminal
+                .limits()
+                .cells(config.panel_sizes.terminal_percent, full.height);
+            vertical_constraints.push(Constraint::Length(height));
+        }
+        if config.show_command_palette {
+            vertical_constraints.push(Constraint::Length(config.command_palette_height));
+        }
+        vertical_constraints.push(Constraint::Length(1));
 
         let vertical_chunks = RatatuiLayout::default()
             .direction(Direction::Vertical)
             .constraints(vertical_constraints)
             .split(main_area);
 
+        let last = vertical_chunks.len() - 1;
         let editor_area = vertical_chunks[0];
-        let (command_palette, statusline) = if config.show_command_palette {
-            (Some(vertical_chunks[1]), vertical_chunks[2])
+        let terminal = config.show_terminal.then(|| vertical_chunks[1]);
+        let command_palette = config
+            .show_command_palette
+            .then(|| vertical_chunks[last - 1]);
+        let statusline = vertical_chunks[last];
+
+        // Split editor area horizontally: [blame?] [line_numbers?] [text]
+        let (blame, editor_area) = if config.show_blame {
+            let chunks = RatatuiLayout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(config.blame_width), Constraint::Min(0)])
+                .split(editor_area);
+            (Some(chunks[0]), chunks[1])
         } else {
-            (None, vertical_chunks[1])
+            (None, editor_area)
         };
-
-        // Split editor area horizontally: [line_numbers?] [text]
         let (line_numbers, editor) = if config.show_line_numbers {
             let chunks = RatatuiLayout::default()
                 .direction(Direction::Horizontal)
@@ -125,9 +316,11 @@ impl EditorLayout {
             full,
             editor,
             line_numbers,
+            blame,
             statusline,
             command_palette,
             sidebar,
+            terminal,
         }
     }
 
@@ -142,9 +335,11 @@ impl EditorLayout {
             full: terminal_size,
             editor: chunks[0],
             line_numbers: None,
+            blame: None,
             statusline: chunks[1],
             command_palette: None,
             sidebar: None,
+            terminal: None,
         }
     }
 
@@ -167,6 +362,42 @@ impl EditorLayout {
     pub fn visible_cols(&self) -> usize {
         self.editor.width as usize
     }
+
+    /// Panel whose resize handle is at (`column`, `row`)
+    ///
+    /// The handle of the sidebar is its rightmost column, the handle of the
+    /// terminal panel its top row.
+    pub fn resize_handle_at(&self, column: u16, row: u16) -> Option<Panel> {
+        if let Some(sidebar) = self.sidebar {
+            if sidebar.width > 0
+                && column == sidebar.right() - 1
+                && (sidebar.top()..sidebar.bottom()).contains(&row)
+            {
+                return Some(Panel::Sidebar);
+            }
+        }
+        if let Some(terminal) = self.terminal {
+            if row == terminal.top() && (terminal.left()..terminal.right()).contains(&column) {
+                return Some(Panel::Terminal);
+            }
+        }
+        None
+    }
+
+    /// Size of `panel`, in percent, with its handle dragged to (`column`, `row`)
+    pub fn drag_percent(&self, panel: Panel, column: u16, row: u16) -> u16 {
+        match panel {
+            Panel::Sidebar => {
+                let width = column.saturating_sub(self.full.left()) + 1;
+                cells_to_percent(width, self.full.width)
+            }
+            Panel::Terminal => {
+                // The terminal panel ends where it ends now, above the palette
+                let bottom = self.terminal.map_or(self.statusline.top(), |t| t.bottom());
+                cells_to_percent(bottom.saturating_sub(row), self.full.height)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -206,7 +437,10 @@ mod tests {
         let terminal_size = Rect::new(0, 0, 120, 30);
         let config = LayoutConfig {
             show_sidebar: true,
-            sidebar_width: 30,
+            panel_sizes: PanelSizes {
+                sidebar_percent: 25,
+                ..Default::default()
+            },
             ..Default::default()
         };
 
@@ -216,6 +450,84 @@ mod tests {
         assert_eq!(layout.sidebar.unwrap().width, 30);
     }
 
+    #[test]
+    fn test_layout_with_terminal() {
+        let terminal_size = Rect::new(0, 0, 100, 30);
+        let config = LayoutConfig {
+            show_terminal: true,
+            show_command_palette: true,
+            ..Default::default()
+        };
+
+        let layout = EditorLayout::calculate(terminal_size, &config);
+
+        // 30% of the height, between the editor and the command palette
+        let terminal = layout.terminal.unwrap();
+        assert_eq!(terminal.height, 9);
+        assert_eq!(terminal.top(), layout.editor.bottom());
+        assert_eq!(terminal.bottom(), layout.command_palette.unwrap().top());
+        assert_eq!(layout.statusline.top(), 29);
+    }
+
+    #[test]
+    fn test_panel_limits() {
+        let limits = Panel::Sidebar.limits();
+        assert_eq!(limits.cells(25, 120), 30);
+        assert_eq!(limits.cells(90, 120), 72);
+        // The minimum width wins over a small share of a narrow terminal
+        assert_eq!(limits.cells(10, 80), 12);
+
+        let mut sizes = PanelSizes::default();
+        sizes.resize(Panel::Sidebar, 100);
+        assert_eq!(sizes.sidebar_percent, limits.max_percent);
+        sizes.resize(Panel::Terminal, -100);
+        assert_eq!(sizes.terminal_percent, Panel::Terminal.limits().min_percent);
+
+        assert_eq!(percent_to_cells(50, 81), 40);
+        assert_eq!(cells_to_percent(40, 81), 49);
+        assert_eq!(cells_to_percent(1, 0), 0);
+    }
+
+    #[test]
+    fn test_resize_handles() {
+        let terminal_size = Rect::new(0, 0, 100, 30);
+        let config = LayoutConfig {
+            show_sidebar: true,
+            show_terminal: true,
+            ..Default::default()
+        };
+        let layout = EditorLayout::calculate(terminal_size, &config);
+        let sidebar = layout.sidebar.unwrap();
+        let terminal = layout.terminal.unwrap();
+
+        assert_eq!(layout.resize_handle_at(sidebar.right() - 1, 5), Some(Panel::Sidebar));
+        assert_eq!(layout.resize_handle_at(sidebar.right(), terminal.top()), Some(Panel::Terminal));
+        assert_eq!(layout.resize_handle_at(50, 5), None);
+
+        // Dragging the handles resizes to the pointer
+        assert_eq!(layout.drag_percent(Panel::Sidebar, 39, 5), 40);
+        assert_eq!(layout.drag_percent(Panel::Terminal, 50, terminal.bottom() - 15), 50);
+    }
+
+    #[test]
+    fn test_panel_sizes_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(PanelSizes::load(dir.path()), PanelSizes::default());
+
+        let sizes = PanelSizes {
+            sidebar_percent: 40,
+            terminal_percent: 20,
+        };
+        sizes.save(dir.path()).unwrap();
+        assert_eq!(PanelSizes::load(dir.path()), sizes);
+
+        // Hand-edited sizes are brought back within the limits
+        std::fs::write(PanelSizes::path(dir.path()), "sidebar_percent = 95\n").unwrap();
+        let loaded = PanelSizes::load(dir.path());
+        assert_eq!(loaded.sidebar_percent, Panel::Sidebar.limits().max_percent);
+        assert_eq!(loaded.terminal_percent, PanelSizes::default().terminal_percent);
+    }
+
     #[test]
     fn test_minimal_layout() {
         let terminal_size = Rect::new(0, 0, 50, 10);
@@ -240,6 +552,27 @@ mod tests {
         assert!(layout.line_numbers.is_none());
     }
 
+    #[test]
+    fn test_layout_with_blame() {
+        let terminal_size = Rect::new(0, 0, 100, 30);
+        let config = LayoutConfig {
+            show_blame: true,
+            ..Default::default()
+        };
+
+        let layout = EditorLayout::calculate(terminal_size, &config);
+
+        // Blame sits left of the line numbers
+        let blame = layout.blame.unwrap();
+        let line_numbers = layout.line_numbers.unwrap();
+        assert_eq!(blame.width, 24);
+        assert_eq!(blame.right(), line_numbers.left());
+        assert_eq!(line_numbers.right(), layout.editor.left());
+        assert!(EditorLayout::calculate(terminal_size, &LayoutConfig::default())
+            .blame
+            .is_none());
+    }
+
     #[test]
     fn test_visible_dimensions() {
         let terminal_size = Rect::new(0, 0, 100, 30);
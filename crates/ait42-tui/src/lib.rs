@@ -10,10 +10,13 @@
 //!   ├── Renderer (terminal rendering)
 //!   ├── KeyMap (key bindings)
 //!   ├── Theme (color schemes)
+//!   ├── SyntaxHighlighter (tree-sitter highlighting, with injections)
 //!   └── Widgets
 //!       ├── EditorWidget
 //!       ├── StatusLine
-//!       └── CommandPalette
+//!       ├── CommandPalette
+//!       ├── WhichKeyPopup
+//!       └── CheatSheet
 //! ```
 //!
 //! # Example
@@ -30,10 +33,20 @@
 //! ```
 
 pub mod event;
+pub mod file_metadata;
+pub mod harness;
+pub mod input_history;
 pub mod keybinds;
 pub mod layout;
+pub mod macros;
+pub mod preload;
+pub mod registers;
 pub mod renderer;
+pub mod session;
+pub mod shell_state;
+pub mod startup;
 pub mod state;
+pub mod syntax;
 pub mod terminal_executor;
 pub mod theme;
 pub mod themes;
@@ -42,16 +55,28 @@ pub mod widgets;
 
 // Re-exports
 pub use event::{EditorEvent, EventLoop};
-pub use keybinds::{EditorCommand, KeyBinding, KeyMap, Mode};
-pub use layout::{EditorLayout, LayoutConfig};
-pub use renderer::Renderer;
+pub use file_metadata::{FileInfo, MetadataCache};
+pub use input_history::{InputHistory, ReverseSearch, SavedHistory};
+pub use keybinds::{EditorCommand, KeyBinding, KeyBindingEntry, KeyMap, Mode};
+pub use layout::{EditorLayout, LayoutConfig, Panel, PanelSizes};
+pub use macros::Macros;
+pub use preload::Preloader;
+pub use registers::Registers;
+pub use renderer::{Panels, Renderer};
+pub use session::{SavedMarks, Session, TabSession};
+pub use shell_state::ShellState;
+pub use startup::StartupProfile;
 pub use state::EditorState as Phase10bEditorState;
-pub use terminal_executor::TerminalExecutor;
+pub use syntax::{
+    Delimiter, Grammar, HighlightSpan, StructureHighlight, SyntaxHighlighter, TokenKind,
+};
+pub use terminal_executor::{HistoryFilter, TerminalExecutor, TerminalHistoryEntry};
 pub use theme::Theme;
 pub use themes::{CursorTheme, DefaultTheme, Theme as ThemeTrait};
 pub use tui_app::{EditorState, TuiApp};
 pub use widgets::{
-    editor::ViewState, CommandPalette, EditorWidget, Sidebar, StatusLine, TabBar, TerminalPanel,
+    editor::ViewState, CheatSheet, CommandPalette, EditorWidget, Sidebar, StatusLine, TabBar,
+    TerminalPanel, WhichKeyPopup,
 };
 
 use anyhow::Result;
@@ -78,6 +103,17 @@ pub async fn run_with_file(path: std::path::PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Run TUI with the tabs and panels of a saved session
+pub async fn run_with_session(session: Session) -> Result<()> {
+    info!("Initializing AIT42 TUI with {} restored tabs", session.tabs.len());
+
+    let mut app = TuiApp::new().await?;
+    app.restore_session(&session);
+    app.run().await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
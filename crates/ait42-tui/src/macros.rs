@@ -0,0 +1,297 @@
+//! Macros
+//!
+//! Editor commands recorded into a register with `q{register}` … `q` and
+//! replayed with `{count}@{register}`, `@@` repeating the last one. An
+//! uppercase register appends to its lowercase one, as in Vim. Registers are
+//! kept in `macros.toml` in the config directory, commands saved by their
+//! configuration name and typed text as one string.
+
+use crate::keybinds::EditorCommand;
+use ait42_config::{ConfigLoader, KeyBindingConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Macro file, relative to the config directory
+const MACROS_FILE: &str = "macros.toml";
+
+/// Recorded macros and the one being recorded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Macros {
+    registers: BTreeMap<char, Vec<EditorCommand>>,
+    /// Register being recorded into, with the commands run so far
+    recording: Option<(char, Vec<EditorCommand>)>,
+    /// Register replayed last, repeated by `@@`
+    last_replayed: Option<char>,
+}
+
+impl Macros {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `ch` names a register
+    pub fn is_register(ch: char) -> bool {
+        ch.is_ascii_alphanumeric()
+    }
+
+    /// Start recording into `register`, appending to it if it is uppercase
+    ///
+    /// Returns whether `register` names one.
+    pub fn start_recording(&mut self, register: char) -> bool {
+        if !Self::is_register(register) {
+            return false;
+        }
+        let commands = if register.is_ascii_uppercase() {
+            self.get(register).to_vec()
+        } else {
+            Vec::new()
+        };
+        self.recording = Some((register.to_ascii_lowercase(), commands));
+        true
+    }
+
+    /// Register being recorded into
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    /// Add `command` to the macro being recorded, if any
+    ///
+    /// The commands recording and replaying macros are left out; a replay
+    /// records the commands it runs instead.
+    pub fn record(&mut self, command: &EditorCommand) {
+        if matches!(command, EditorCommand::RecordMacro | EditorCommand::ReplayMacro) {
+            return;
+        }
+        if let Some((_, commands)) = &mut self.recording {
+            commands.push(command.clone());
+        }
+    }
+
+    /// Add typed or pasted `text` to the macro being recorded, if any
+    pub fn record_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.record(&EditorCommand::InsertChar(ch));
+        }
+    }
+
+    /// Stop recording, keeping the macro in its register
+    ///
+    /// Returns the register recorded into.
+    pub fn stop_recording(&mut self) -> Option<char> {
+        let (register, commands) = self.recording.take()?;
+        self.registers.insert(register, commands);
+        Some(register)
+    }
+
+    /// Commands of the macro in `register`, empty if there is none
+    pub fn get(&self, register: char) -> &[EditorCommand] {
+        self.registers
+            .get(&register.to_ascii_lowercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Commands to replay for `register`, `@` standing for the last one
+    /// replayed
+    ///
+    /// `None` if the register is empty.
+    pub fn replay(&mut self, register: char) -> Option<Vec<EditorCommand>> {
+        let register = match register {
+            '@' => self.last_replayed?,
+            _ => register.to_ascii_lowercase(),
+        };
+        let commands = self.registers.get(&register).filter(|c| !c.is_empty())?;
+        self.last_replayed = Some(register);
+        Some(commands.clone())
+    }
+
+    /// Registers holding a macro
+    pub fn registers(&self) -> impl Iterator<Item = char> + '_ {
+        self.registers.keys().copied()
+    }
+
+    /// Whether `other` holds the same macros, recordings in progress aside
+    pub fn same_registers(&self, other: &Macros) -> bool {
+        self.registers == other.registers
+    }
+
+    /// Default macro file, in the config directory
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        Ok(ConfigLoader::default_config_dir()?.join(MACROS_FILE))
+    }
+
+    /// Macros saved in `path`, none if there are none
+    pub fn load(path: &Path) -> Self {
+        let saved: SavedMacros = match std::fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid macros {}: {}", path.display(), e);
+                SavedMacros::default()
+            }),
+            Err(_) => SavedMacros::default(),
+        };
+        Self::from_saved(saved)
+    }
+
+    /// Save the recorded macros to `path`
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(&self.to_saved())?)?;
+        Ok(())
+    }
+
+    fn from_saved(saved: SavedMacros) -> Self {
+        let mut registers = BTreeMap::new();
+        for (name, steps) in saved.registers {
+            let mut chars = name.chars();
+            let (Some(register), None) = (chars.next(), chars.next()) else {
+                tracing::warn!("Ignoring macro in invalid register {:?}", name);
+                continue;
+            };
+            if !Self::is_register(register) {
+                tracing::warn!("Ignoring macro in invalid register {:?}", name);
+                continue;
+            }
+            let mut commands = Vec::new();
+            for step in steps {
+                match step {
+                    SavedStep::Command { command } => match EditorCommand::from_name(&command) {
+                        Some(parsed) => commands.push(parsed),
+                        None => tracing::warn!("Ignoring unknown command in macro: {}", command),
+                    },
+                    SavedStep::Insert { insert } => {
+                        commands.extend(insert.chars().map(EditorCommand::InsertChar))
+                    }
+                }
+            }
+            registers.insert(register.to_ascii_lowercase(), commands);
+        }
+        Self {
+            registers,
+            ..Self::default()
+        }
+    }
+
+    fn to_saved(&self) -> SavedMacros {
+        let registers = self
+            .registers
+            .iter()
+            .map(|(register, commands)| (register.to_string(), saved_steps(commands)))
+            .collect();
+        SavedMacros { registers }
+    }
+}
+
+/// Configuration name of `command`, `None` for commands without one
+fn command_name(command: &EditorCommand) -> Option<&'static str> {
+    KeyBindingConfig::COMMANDS
+        .iter()
+        .copied()
+        .find(|name| EditorCommand::from_name(name).as_ref() == Some(command))
+}
+
+/// Steps saving `commands`, with consecutive characters typed merged
+///
+/// Commands without a configuration name are left out.
+fn saved_steps(commands: &[EditorCommand]) -> Vec<SavedStep> {
+    let mut steps = Vec::new();
+    for command in commands {
+        match (command, steps.last_mut()) {
+            (EditorCommand::InsertChar(ch), Some(SavedStep::Insert { insert })) => insert.push(*ch),
+            (EditorCommand::InsertChar(ch), _) => steps.push(SavedStep::Insert {
+                insert: ch.to_string(),
+            }),
+            _ => match command_name(command) {
+                Some(name) => steps.push(SavedStep::Command {
+                    command: name.to_string(),
+                }),
+                None => tracing::debug!("Not saving {:?} in macro", command),
+            },
+        }
+    }
+    steps
+}
+
+/// Macros as saved, by register
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SavedMacros {
+    #[serde(default)]
+    registers: BTreeMap<String, Vec<SavedStep>>,
+}
+
+/// Step of a saved macro
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SavedStep {
+    /// Command, by configuration name
+    Command { command: String },
+    /// Text typed
+    Insert { insert: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use EditorCommand::*;
+
+    fn record(macros: &mut Macros, register: char, commands: &[EditorCommand]) {
+        assert!(macros.start_recording(register));
+        for command in commands {
+            macros.record(command);
+        }
+        assert_eq!(macros.stop_recording(), Some(register.to_ascii_lowercase()));
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut macros = Macros::new();
+        assert!(!macros.start_recording('!'));
+        assert_eq!(macros.replay('@'), None);
+
+        record(&mut macros, 'a', &[MoveDown, ReplayMacro, DeleteChar]);
+        assert_eq!(macros.recording(), None);
+        assert_eq!(macros.get('a'), [MoveDown, DeleteChar]);
+        assert_eq!(macros.replay('a'), Some(vec![MoveDown, DeleteChar]));
+        assert_eq!(macros.replay('@'), Some(vec![MoveDown, DeleteChar]));
+        assert_eq!(macros.replay('b'), None);
+
+        // Uppercase appends, lowercase starts over
+        record(&mut macros, 'A', &[MoveUp]);
+        assert_eq!(macros.get('a'), [MoveDown, DeleteChar, MoveUp]);
+        record(&mut macros, 'a', &[]);
+        assert_eq!(macros.replay('a'), None);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join(MACROS_FILE);
+
+        let mut macros = Macros::new();
+        assert!(macros.start_recording('q'));
+        macros.record(&EnterInsertMode);
+        macros.record_text("hi");
+        macros.record(&EnterNormalMode);
+        macros.record(&SwitchTab(1));
+        macros.record(&MoveDown);
+        macros.stop_recording();
+        macros.save(&path).unwrap();
+
+        let loaded = Macros::load(&path);
+        assert_eq!(
+            loaded.get('q'),
+            [
+                EnterInsertMode,
+                InsertChar('h'),
+                InsertChar('i'),
+                EnterNormalMode,
+                MoveDown
+            ]
+        );
+
+        std::fs::write(&path, "registers = 1").unwrap();
+        assert_eq!(Macros::load(&path), Macros::new());
+    }
+}
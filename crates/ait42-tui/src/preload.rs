@@ -0,0 +1,558 @@
+//! Buffer Preloading
+//!
+//! When a file is opened, the files it imports and the files most often
+//! committed together with it are read on a background thread into warm
+//! buffers, so switching to one of them does not wait on the disk.
+//!
+//! Imports are found by parsing the file with its tree-sitter grammar:
+//! `mod` and `use crate::` items in Rust, `import` statements in Python and
+//! relative `import`/`require` specifiers in JavaScript. Warm buffers are
+//! kept up to a memory budget, the oldest dropped first, and are only
+//! handed out while the file on disk is unchanged.
+
+use crate::syntax::Grammar;
+use ait42_config::PreloadConfig;
+use ait42_core::Buffer;
+use ait42_git::{log, Repository};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::SystemTime;
+use tracing::debug;
+use tree_sitter::{Node, Parser};
+
+/// Extensions tried for JavaScript specifiers without one
+const JS_EXTENSIONS: &[&str] = &["js", "jsx", "mjs", "cjs", "ts", "tsx"];
+
+/// A file read ahead
+#[derive(Debug)]
+struct Warm {
+    /// Canonical path
+    path: PathBuf,
+    buffer: Buffer,
+    /// Modification time when read
+    modified: Option<SystemTime>,
+}
+
+/// Pool of warm buffers filled in the background
+#[derive(Debug)]
+pub struct Preloader {
+    config: PreloadConfig,
+    warm: HashMap<PathBuf, Warm>,
+    /// Warm paths, oldest first
+    order: VecDeque<PathBuf>,
+    /// Bytes held by warm buffers
+    bytes: usize,
+    sender: Sender<Warm>,
+    receiver: Receiver<Warm>,
+}
+
+impl Preloader {
+    /// Create an empty pool
+    pub fn new(config: PreloadConfig) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            config,
+            warm: HashMap::new(),
+            order: VecDeque::new(),
+            bytes: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Memory warm buffers may take, in bytes
+    fn budget(&self) -> usize {
+        self.config.max_memory_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// Read the likely next files after `path`, whose content is `source`,
+    /// on a background thread
+    ///
+    /// Files in `open` are already in memory and skipped. Files larger
+    /// than a quarter of the budget are not worth evicting others for.
+    pub fn prefetch(&mut self, path: &Path, source: String, open: &[PathBuf]) {
+        if !self.config.enabled || self.config.max_files == 0 {
+            return;
+        }
+
+        let mut skip: HashSet<PathBuf> =
+            open.iter().filter_map(|p| p.canonicalize().ok()).collect();
+        skip.extend(self.warm.keys().cloned());
+        let path = path.to_path_buf();
+        let config = self.config.clone();
+        let max_file_bytes = self.budget() / 4;
+        let sender = self.sender.clone();
+
+        std::thread::spawn(move || {
+            let own = path.canonicalize().unwrap_or_else(|_| path.clone());
+            for candidate in candidates(&path, &source, &config) {
+                let Ok(canonical) = candidate.canonicalize() else {
+                    continue;
+                };
+                if canonical == own || !skip.insert(canonical.clone()) {
+                    continue;
+                }
+                let Ok(metadata) = std::fs::metadata(&canonical) else {
+                    continue;
+                };
+                if metadata.len() as usize > max_file_bytes {
+                    continue;
+                }
+                let buffer = match Buffer::from_file(&canonical) {
+                    Ok(buffer) => buffer,
+                    Err(e) => {
+                        debug!("Not preloading {}: {}", canonical.display(), e);
+                        continue;
+                    }
+                };
+                let warm = Warm {
+                    path: canonical,
+                    buffer,
+                    modified: metadata.modified().ok(),
+                };
+                // The pool is gone
+                if sender.send(warm).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Move buffers read since the last call into the pool, dropping the
+    /// oldest over the budget
+    pub fn receive(&mut self) {
+        while let Ok(warm) = self.receiver.try_recv() {
+            if self.warm.contains_key(&warm.path) {
+                continue;
+            }
+            self.bytes += warm.buffer.len_bytes();
+            self.order.push_back(warm.path.clone());
+            self.warm.insert(warm.path.clone(), warm);
+        }
+
+        while self.bytes > self.budget() {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(warm) = self.warm.remove(&oldest) {
+                self.bytes -= warm.buffer.len_bytes();
+            }
+        }
+    }
+
+    /// Take the warm buffer of `path`, if it was read ahead and the file
+    /// has not changed since
+    pub fn take(&mut self, path: &Path) -> Option<Buffer> {
+        let canonical = path.canonicalize().ok()?;
+        let warm = self.warm.remove(&canonical)?;
+        self.order.retain(|p| *p != canonical);
+        self.bytes -= warm.buffer.len_bytes();
+
+        let modified = std::fs::metadata(&canonical)
+            .and_then(|m| m.modified())
+            .ok();
+        (modified == warm.modified).then_some(warm.buffer)
+    }
+
+    /// Whether `path` has a warm buffer
+    pub fn contains(&self, path: &Path) -> bool {
+        path.canonicalize()
+            .is_ok_and(|canonical| self.warm.contains_key(&canonical))
+    }
+
+    /// Bytes held by warm buffers
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+/// Files likely opened after `path`: its imports, then its co-edited files
+fn candidates(path: &Path, source: &str, config: &PreloadConfig) -> Vec<PathBuf> {
+    let mut files = imports(path, source);
+    match co_edited(path, config) {
+        Ok(co_edited) => files.extend(co_edited),
+        Err(e) => debug!("No co-edited files for {}: {}", path.display(), e),
+    }
+
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    files.truncate(config.max_files);
+    files
+}
+
+/// Files most often committed together with `path`
+fn co_edited(path: &Path, config: &PreloadConfig) -> ait42_git::Result<Vec<PathBuf>> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let repo = Repository::open(path.parent().unwrap_or(&path))?;
+    let files = log::co_changed(&repo, &path, config.history_commits, config.max_files)?;
+    Ok(files
+        .into_iter()
+        .map(|file| repo.root().join(file))
+        .collect())
+}
+
+/// Existing files imported by `path`, whose content is `source`
+pub fn imports(path: &Path, source: &str) -> Vec<PathBuf> {
+    let Some(grammar) = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(Grammar::from_extension)
+    else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&grammar.grammar()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let mut specifiers = Vec::new();
+    collect_imports(grammar, tree.root_node(), source, &mut specifiers);
+    let dir = path.parent().unwrap_or(Path::new(""));
+    specifiers
+        .iter()
+        .filter_map(|specifier| match grammar {
+            Grammar::Rust => resolve_rust(path, specifier),
+            Grammar::Python => resolve_python(dir, specifier),
+            Grammar::JavaScript => resolve_js(dir, specifier),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Import specifiers in `node`, as written: `mod name` or a `use` path for
+/// Rust, a dotted module for Python, a module specifier for JavaScript
+fn collect_imports(grammar: Grammar, node: Node<'_>, source: &str, specifiers: &mut Vec<String>) {
+    let text = |node: Node<'_>| source[node.byte_range()].to_string();
+    match (grammar, node.kind()) {
+        // `mod name;`, not inline modules
+        (Grammar::Rust, "mod_item") => {
+            if node.child_by_field_name("body").is_none() {
+                if let Some(name) = node.child_by_field_name("name") {
+                    specifiers.push(format!("mod {}", text(name)));
+                }
+            }
+            return;
+        }
+        (Grammar::Rust, "use_declaration") => {
+            if let Some(argument) = node.child_by_field_name("argument") {
+                specifiers.push(text(argument));
+            }
+            return;
+        }
+        (Grammar::Python, "import_statement") => {
+            let mut cursor = node.walk();
+            for name in node.children_by_field_name("name", &mut cursor) {
+                let name = name.child_by_field_name("name").unwrap_or(name);
+                specifiers.push(text(name));
+            }
+            return;
+        }
+        (Grammar::Python, "import_from_statement") => {
+            if let Some(module) = node.child_by_field_name("module_name") {
+                specifiers.push(text(module));
+            }
+            return;
+        }
+        (Grammar::JavaScript, "import_statement" | "export_statement") => {
+            if let Some(source_node) = node.child_by_field_name("source") {
+                specifiers.push(text(source_node));
+            }
+        }
+        (Grammar::JavaScript, "call_expression") => {
+            let is_require = node
+                .child_by_field_name("function")
+                .is_some_and(|f| matches!(&source[f.byte_range()], "require" | "import"));
+            let argument = node
+                .child_by_field_name("arguments")
+                .and_then(|args| args.named_child(0))
+                .filter(|arg| arg.kind() == "string");
+            if let (true, Some(argument)) = (is_require, argument) {
+                specifiers.push(text(argument));
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_imports(grammar, child, source, specifiers);
+    }
+}
+
+/// First of `paths` that is a file
+fn first_file(paths: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    paths.into_iter().find(|path| path.is_file())
+}
+
+/// File of a Rust module, `name` or `dir/name`
+fn rust_module(dir: &Path, name: &str) -> Option<PathBuf> {
+    first_file([
+        dir.join(format!("{name}.rs")),
+        dir.join(name).join("mod.rs"),
+    ])
+}
+
+fn resolve_rust(path: &Path, specifier: &str) -> Option<PathBuf> {
+    let dir = path.parent()?;
+    if let Some(name) = specifier.strip_prefix("mod ") {
+        // Modules of `foo.rs` live in `foo/`, those of crate roots and
+        // `mod.rs` next to them
+        let stem = path.file_stem()?.to_str()?;
+        let dir = match stem {
+            "mod" | "lib" | "main" => dir.to_path_buf(),
+            _ => dir.join(stem),
+        };
+        return rust_module(&dir, name);
+    }
+
+    // The deepest module of a `crate::` path that is a file
+    let rest = specifier.strip_prefix("crate::")?;
+    let root = dir
+        .ancestors()
+        .find(|a| a.join("lib.rs").is_file() || a.join("main.rs").is_file())?;
+    let mut module_dir = root.to_path_buf();
+    let mut found = None;
+    for segment in rest.split("::") {
+        if segment.is_empty() || !segment.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            break;
+        }
+        let Some(file) = rust_module(&module_dir, segment) else {
+            break;
+        };
+        module_dir = module_dir.join(segment);
+        found = Some(file);
+    }
+    found
+}
+
+fn resolve_python(dir: &Path, specifier: &str) -> Option<PathBuf> {
+    let module = specifier.trim_start_matches('.');
+    let dots = specifier.len() - module.len();
+    let resolve = |base: &Path| {
+        let module_path = base.join(module.replace('.', "/"));
+        first_file([
+            module_path.with_extension("py"),
+            module_path.join("__init__.py"),
+        ])
+    };
+
+    if dots > 0 {
+        let base = dir.ancestors().nth(dots - 1)?;
+        return if module.is_empty() {
+            first_file([base.join("__init__.py")])
+        } else {
+            resolve(base)
+        };
+    }
+    // Absolute imports resolve from the package root, which is some
+    // ancestor of the file; stop at the repository root
+    for base in dir.ancestors() {
+        if let Some(file) = resolve(base) {
+            return Some(file);
+        }
+        if base.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+fn resolve_js(dir: &Path, specifier: &str) -> Option<PathBuf> {
+    let specifier = specifier.trim_matches(|c| matches!(c, '"' | '\'' | '`'));
+    // Packages resolve through node_modules, which is not worth preloading
+    if !specifier.starts_with('.') {
+        return None;
+    }
+    // Join lexically so `./util` names `web/util`, not `web/./util`
+    let mut path = dir.to_path_buf();
+    for component in Path::new(specifier).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                path.pop();
+            }
+            other => path.push(other),
+        }
+    }
+    let mut paths = vec![path.clone()];
+    for ext in JS_EXTENSIONS {
+        let mut with_ext = path.clone().into_os_string();
+        with_ext.push(format!(".{ext}"));
+        paths.push(PathBuf::from(with_ext));
+    }
+    paths.extend(
+        JS_EXTENSIONS
+            .iter()
+            .map(|ext| path.join(format!("index.{ext}"))),
+    );
+    first_file(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_git::repo::test_support::init_repo;
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    fn write(root: &Path, file: &str, content: &str) -> PathBuf {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn names(root: &Path, paths: &[PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|p| {
+                p.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    /// Receive until `path` is warm, or give up after a few seconds
+    fn wait_for(preloader: &mut Preloader, path: &Path) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            preloader.receive();
+            if preloader.contains(path) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    #[test]
+    fn test_rust_imports() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "src/lib.rs", "");
+        write(root, "src/parser.rs", "");
+        write(root, "src/parser/lexer.rs", "");
+        write(root, "src/util/mod.rs", "");
+        write(root, "src/util/text.rs", "");
+        let source = "mod parser;\nmod util;\nmod missing;\nmod inline {}\n\
+                      use crate::util::text::{wrap, trim};\nuse std::fs;\n";
+        let found = imports(&root.join("src/lib.rs"), source);
+        assert_eq!(names(root, &found), ["src/parser.rs", "src/util/mod.rs", "src/util/text.rs"]);
+
+        // Submodules of a non-root file live in its directory
+        let found = imports(&root.join("src/parser.rs"), "mod lexer;");
+        assert_eq!(names(root, &found), ["src/parser/lexer.rs"]);
+    }
+
+    #[test]
+    fn test_python_and_js_imports() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "pkg/__init__.py", "");
+        write(root, "pkg/models.py", "");
+        write(root, "pkg/views.py", "");
+        write(root, "helpers.py", "");
+        let source = "import os\nimport helpers as h\nfrom . import x\nfrom .models import User\n";
+        let found = imports(&root.join("pkg/views.py"), source);
+        assert_eq!(names(root, &found), ["helpers.py", "pkg/__init__.py", "pkg/models.py"]);
+
+        write(root, "web/util.js", "");
+        write(root, "web/lib/index.ts", "");
+        write(root, "shared/api.js", "");
+        let source = "import { a } from './util';\nconst lib = require('./lib');\n\
+                      import api from '../shared/api';\nimport React from 'react';\n";
+        let found = imports(&root.join("web/app.js"), source);
+        assert_eq!(names(root, &found), ["web/util.js", "web/lib/index.ts", "shared/api.js"]);
+    }
+
+    #[test]
+    fn test_prefetch_imports_and_co_edited_files() {
+        let (dir, repo) = init_repo();
+        let root = dir.path();
+        let main = write(root, "src/main.rs", "mod config;\nfn main() {}\n");
+        let config = write(root, "src/config.rs", "pub struct Config;\n");
+        let readme = write(root, "README.md", "# Project\n");
+        write(root, "other.txt", "unrelated\n");
+        repo.run(&["add", "src", "README.md"]).unwrap();
+        repo.run(&["commit", "-q", "-m", "initial"]).unwrap();
+
+        let mut preloader = Preloader::new(PreloadConfig::default());
+        let source = fs::read_to_string(&main).unwrap();
+        preloader.prefetch(&main, source, &[]);
+        assert!(wait_for(&mut preloader, &config));
+        assert!(wait_for(&mut preloader, &readme));
+        assert!(!preloader.contains(&root.join("other.txt")));
+        assert!(!preloader.contains(&main));
+
+        let buffer = preloader.take(&config).unwrap();
+        assert_eq!(buffer.to_string(), "pub struct Config;\n");
+        assert!(!preloader.contains(&config));
+
+        // Files changed on disk since they were read are not handed out
+        fs::write(&readme, "# Changed\n").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&readme)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert!(preloader.take(&readme).is_none());
+        assert_eq!(preloader.bytes(), 0);
+    }
+
+    #[test]
+    fn test_budget_drops_oldest() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let a = write(root, "a.rs", "");
+        let b = write(root, "b.rs", "");
+        let mut preloader = Preloader::new(PreloadConfig {
+            max_memory_mb: 0,
+            ..PreloadConfig::default()
+        });
+        for path in [&a, &b] {
+            let buffer = Buffer::from_string("x".repeat(10), None);
+            preloader
+                .sender
+                .send(Warm {
+                    path: path.canonicalize().unwrap(),
+                    buffer,
+                    modified: None,
+                })
+                .unwrap();
+        }
+        preloader.receive();
+        assert!(!preloader.contains(&a) && !preloader.contains(&b));
+        assert_eq!(preloader.bytes(), 0);
+
+        preloader.config.max_memory_mb = 1;
+        let buffer = Buffer::from_string("x".repeat(1024 * 1024 - 5), None);
+        for (path, buffer) in [
+            (&a, buffer),
+            (&b, Buffer::from_string("y".repeat(10), None)),
+        ] {
+            preloader
+                .sender
+                .send(Warm {
+                    path: path.canonicalize().unwrap(),
+                    buffer,
+                    modified: None,
+                })
+                .unwrap();
+        }
+        preloader.receive();
+        assert!(!preloader.contains(&a));
+        assert!(preloader.contains(&b));
+        assert_eq!(preloader.bytes(), 10);
+    }
+}
@@ -0,0 +1,75 @@
+//! Registers
+//!
+//! Text yanked in the editor, kept under a character as in Vim. Every yank
+//! also fills the unnamed register `"`, which paste uses unless another is
+//! selected; an uppercase register appends to its lowercase one.
+
+use std::collections::BTreeMap;
+
+/// The register every yank fills
+pub const UNNAMED: char = '"';
+
+/// Yanked text by register
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registers {
+    registers: BTreeMap<char, String>,
+}
+
+impl Registers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `ch` names a register
+    pub fn is_register(ch: char) -> bool {
+        ch.is_ascii_alphanumeric() || ch == UNNAMED
+    }
+
+    /// Keep yanked `text` in `register`, and in the unnamed register
+    pub fn yank(&mut self, register: Option<char>, text: impl Into<String>) {
+        let mut text = text.into();
+        if let Some(register) = register.filter(|&r| r != UNNAMED && Self::is_register(r)) {
+            let named = self
+                .registers
+                .entry(register.to_ascii_lowercase())
+                .or_default();
+            if register.is_ascii_uppercase() {
+                named.push_str(&text);
+                text = named.clone();
+            } else {
+                *named = text.clone();
+            }
+        }
+        self.registers.insert(UNNAMED, text);
+    }
+
+    /// Text in `register`, the unnamed one with `None`
+    pub fn get(&self, register: Option<char>) -> Option<&str> {
+        let register = register.unwrap_or(UNNAMED).to_ascii_lowercase();
+        self.registers.get(&register).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yank_into_registers() {
+        let mut registers = Registers::new();
+        assert_eq!(registers.get(None), None);
+
+        registers.yank(None, "one");
+        assert_eq!(registers.get(None), Some("one"));
+        assert_eq!(registers.get(Some('"')), Some("one"));
+
+        registers.yank(Some('a'), "two");
+        registers.yank(Some('A'), " three");
+        assert_eq!(registers.get(Some('a')), Some("two three"));
+        assert_eq!(registers.get(None), Some("two three"));
+
+        registers.yank(Some('!'), "four");
+        assert_eq!(registers.get(Some('a')), Some("two three"));
+        assert_eq!(registers.get(None), Some("four"));
+    }
+}
@@ -3,26 +3,112 @@
 //! Handles terminal rendering with ratatui.
 
 use crate::{
-    keybinds::Mode,
+    keybinds::{KeyBindingEntry, Mode},
     layout::{EditorLayout, LayoutConfig},
+    syntax::{StructureHighlight, SyntaxHighlighter},
     theme::Theme,
-    widgets::{editor::ViewState, EditorWidget, StatusLine},
+    widgets::{
+        editor::ViewState, AgentLauncher, AgentLauncherState, BlameGutter, CheatSheet,
+        EditorWidget, FileTree, HunkPanel, HunkPanelState, LineChange, LogGraph, LogGraphState,
+        SearchPrompt, SearchPromptState, Sidebar, StashPanel, StashPanelState, StatusLine,
+        TerminalCopyState, TerminalPanel, WhichKeyPopup, WorkspaceSearch, WorkspaceSearchState,
+    },
 };
-use ait42_core::{Buffer, Cursor};
+use ait42_core::{Buffer, Cursor, Selection};
+use ait42_git::{BlameLine, FileStatus};
 use anyhow::Result;
 use crossterm::{
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     execute,
     terminal::{
         disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
         LeaveAlternateScreen,
     },
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io::{self, Stdout};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    style::Style,
+    Terminal,
+};
+use std::{
+    io::{self, Stdout, Write},
+    ops::Range,
+};
+
+/// Popup drawn on top of the editor
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Overlay<'a> {
+    /// Nothing on top of the editor
+    #[default]
+    None,
+    /// Continuations of a pending prefix key
+    WhichKey {
+        prefix: &'a str,
+        label: &'a str,
+        entries: &'a [KeyBindingEntry],
+    },
+    /// Full keybinding cheat-sheet
+    CheatSheet {
+        query: &'a str,
+        entries: &'a [KeyBindingEntry],
+    },
+    /// Stash list and patch viewer
+    Stash(&'a StashPanelState),
+    /// Unstaged or staged hunks, staged one at a time
+    Hunks(&'a HunkPanelState),
+    /// Commit history graph
+    Log(&'a LogGraphState),
+    /// Task prompt with recommended agents
+    AgentLauncher(&'a AgentLauncherState),
+    /// Search or replace prompt over the status line
+    Search(&'a SearchPromptState),
+    /// Project-wide search with its results
+    WorkspaceSearch(&'a WorkspaceSearchState),
+}
+
+/// Contents of the sidebar, bottom panel and gutters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Panels<'a> {
+    /// File tree shown in the sidebar
+    pub file_tree: Option<&'a FileTree>,
+    /// Selected sidebar entry
+    pub sidebar_selected: usize,
+    /// Output shown in the terminal panel
+    pub terminal_output: &'a [String],
+    /// Terminal panel scroll offset from the bottom
+    pub terminal_scroll: usize,
+    /// Whether the last terminal command failed, coloring the prompt
+    pub terminal_failed: bool,
+    /// Agent results, shown in place of the terminal output when set
+    pub results: Option<&'a [String]>,
+    /// Agent results scroll offset from the bottom
+    pub results_scroll: usize,
+    /// Copy mode, shown in place of the terminal output or agent results
+    pub terminal_copy: Option<&'a TerminalCopyState>,
+    /// Blame of the buffer, shown when the layout has a blame gutter
+    pub blame: &'a [BlameLine],
+    /// Change of each buffer line since HEAD, marked next to the line numbers
+    pub line_changes: &'a [Option<LineChange>],
+    /// Git status of the buffer's file, shown after its name in the status line
+    pub file_status: Option<FileStatus>,
+    /// Text typed into the command palette
+    pub command_input: &'a str,
+    /// Prompt shown before the command palette input
+    pub command_prompt: &'a str,
+    /// Query of a command history search and the entry it found
+    pub history_search: Option<(&'a str, Option<&'a str>)>,
+    /// Register a macro is being recorded into, shown in the status line
+    pub recording: Option<char>,
+}
 
 /// Terminal renderer
-pub struct Renderer {
-    terminal: Terminal<CrosstermBackend<Stdout>>,
+///
+/// Draws to the real terminal by default; tests draw to a ratatui
+/// `TestBackend` instead (see [`Renderer::with_backend`]).
+pub struct Renderer<B: Backend = CrosstermBackend<Stdout>> {
+    terminal: Terminal<B>,
+    /// Whether raw mode and the alternate screen were set up on the tty
+    owns_tty: bool,
 }
 
 impl Renderer {
@@ -31,44 +117,130 @@ impl Renderer {
         enable_raw_mode()?;
 
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, Clear(ClearType::All))?;
+        // Bracketed paste lets terminals hand over IME commits and pastes as
+        // a single string instead of a stream of key events. Mouse capture
+        // lets panels be resized by dragging their edges.
+        execute!(
+            stdout,
+            EnterAlternateScreen,
+            EnableBracketedPaste,
+            EnableMouseCapture,
+            Clear(ClearType::All)
+        )?;
 
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend)?;
 
-        Ok(Self { terminal })
+        Ok(Self {
+            terminal,
+            owns_tty: true,
+        })
+    }
+}
+
+impl<B: Backend> Renderer<B> {
+    /// Create a renderer on an existing backend without touching the tty
+    pub fn with_backend(backend: B) -> Result<Self> {
+        Ok(Self {
+            terminal: Terminal::new(backend)?,
+            owns_tty: false,
+        })
+    }
+
+    /// Backend drawn to
+    pub fn backend(&self) -> &B {
+        self.terminal.backend()
+    }
+
+    /// Mutable backend drawn to (e.g. to resize a test backend)
+    pub fn backend_mut(&mut self) -> &mut B {
+        self.terminal.backend_mut()
     }
 
     /// Render the editor UI
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &mut self,
         buffer: &Buffer,
         cursor: &Cursor,
+        selection: &Selection,
         view: &ViewState,
+        highlighter: &SyntaxHighlighter,
+        structure: StructureHighlight,
+        search_matches: &[Range<usize>],
         mode: Mode,
         theme: &Theme,
         layout_config: &LayoutConfig,
+        panels: Panels<'_>,
+        overlay: Overlay<'_>,
     ) -> Result<()> {
         self.terminal.draw(|f| {
             let size = f.size();
             let layout = EditorLayout::calculate(size, layout_config);
 
+            // Render the sidebar, its right edge being the resize handle
+            if let Some(sidebar_area) = layout.sidebar {
+                let empty = FileTree::new(Default::default());
+                let file_tree = panels.file_tree.unwrap_or(&empty);
+                let mut tree_area = sidebar_area;
+                tree_area.width = tree_area.width.saturating_sub(1);
+                f.render_widget(Sidebar::new(file_tree, panels.sidebar_selected, theme), tree_area);
+
+                let border_style = Style::default().fg(theme.border.fg.unwrap_or(theme.foreground));
+                let x = sidebar_area.right().saturating_sub(1);
+                for y in sidebar_area.top()..sidebar_area.bottom() {
+                    f.buffer_mut()
+                        .get_mut(x, y)
+                        .set_char('│')
+                        .set_style(border_style);
+                }
+            }
+
+            // Render the terminal panel, its header being the resize handle
+            if let Some(terminal_area) = layout.terminal {
+                let terminal = match panels.results {
+                    Some(results) => TerminalPanel::new(results, theme)
+                        .title(" AGENT RESULTS ")
+                        .scroll_offset(panels.results_scroll)
+                        .hide_prompt(),
+                    None => TerminalPanel::new(panels.terminal_output, theme)
+                        .scroll_offset(panels.terminal_scroll)
+                        .failed(panels.terminal_failed),
+                };
+                f.render_widget(terminal.copy_mode(panels.terminal_copy), terminal_area);
+            }
+
+            // Render blame annotations if toggled on
+            if let Some(blame_area) = layout.blame {
+                let gutter = BlameGutter::new(panels.blame, buffer, cursor, view, theme);
+                f.render_widget(gutter, blame_area);
+            }
+
             // Render line numbers if configured
             if let Some(line_numbers_area) = layout.line_numbers {
-                let editor_widget = EditorWidget::new(buffer, cursor, view, theme);
+                let editor_widget = EditorWidget::new(buffer, cursor, view, theme)
+                    .line_changes(panels.line_changes);
                 editor_widget.render_line_numbers(line_numbers_area, f.buffer_mut());
             }
 
             // Render main editor
-            let editor_widget =
-                EditorWidget::new(buffer, cursor, view, theme).show_line_numbers(false); // Line numbers rendered separately
+            let editor_widget = EditorWidget::new(buffer, cursor, view, theme)
+                .selection(selection)
+                .highlighter(highlighter)
+                .structure(structure)
+                .search_matches(search_matches)
+                .show_line_numbers(false); // Line numbers rendered separately
             f.render_widget(editor_widget, layout.editor);
 
             // Render status line
             let cursor_pos = cursor.position(buffer);
+            let cursor_cell = cursor.display_position(buffer);
             let status =
                 StatusLine::new(mode, (cursor_pos.line, cursor_pos.col), buffer.len_lines(), theme)
-                    .dirty(buffer.is_dirty());
+                    .dirty(buffer.is_dirty())
+                    .git_status(panels.file_status)
+                    .recording(panels.recording)
+                    .file_format(buffer.encoding(), buffer.has_bom(), buffer.line_ending());
 
             if let Some(path) = buffer.path() {
                 let status = status.file_path(path);
@@ -79,17 +251,64 @@ impl Renderer {
 
             // Render command palette if visible
             if let Some(palette_area) = layout.command_palette {
-                use crate::widgets::command_palette::{default_commands, CommandPalette};
-                let commands = default_commands();
-                let palette = CommandPalette::new("", &commands, theme);
+                use crate::widgets::command_palette::{editor_commands, CommandPalette};
+                let commands = editor_commands();
+                let mut palette = CommandPalette::new(panels.command_input, &commands, theme);
+                if !panels.command_prompt.is_empty() {
+                    palette = palette.prompt(panels.command_prompt);
+                }
+                if let Some((query, matched)) = panels.history_search {
+                    palette = palette.history_search(query, matched);
+                }
                 f.render_widget(palette, palette_area);
             }
 
+            match overlay {
+                Overlay::None => {}
+                Overlay::WhichKey {
+                    prefix,
+                    label,
+                    entries,
+                } => {
+                    let popup = WhichKeyPopup::new(prefix, entries, theme).label(label);
+                    let area = popup.area(layout.editor);
+                    f.render_widget(popup, area);
+                }
+                Overlay::CheatSheet { query, entries } => {
+                    let area = CheatSheet::area(size);
+                    f.render_widget(CheatSheet::new(query, entries, theme), area);
+                }
+                Overlay::Stash(state) => {
+                    let area = CheatSheet::area(size);
+                    f.render_widget(StashPanel::new(state, theme), area);
+                }
+                Overlay::Hunks(state) => {
+                    let area = CheatSheet::area(size);
+                    f.render_widget(HunkPanel::new(state, theme), area);
+                }
+                Overlay::Log(state) => {
+                    let area = CheatSheet::area(size);
+                    f.render_widget(LogGraph::new(state, theme), area);
+                }
+                Overlay::AgentLauncher(state) => {
+                    let area = CheatSheet::area(size);
+                    f.render_widget(AgentLauncher::new(state, theme), area);
+                }
+                Overlay::WorkspaceSearch(state) => {
+                    let area = CheatSheet::area(size);
+                    f.render_widget(WorkspaceSearch::new(state, theme), area);
+                }
+                Overlay::Search(state) => {
+                    let prompt = SearchPrompt::new(state, theme).matches(search_matches.len());
+                    f.render_widget(prompt, layout.statusline);
+                }
+            }
+
             // Set cursor position for terminal
             let cursor_screen_x =
-                layout.editor.x + cursor_pos.col.saturating_sub(view.scroll_col) as u16;
+                layout.editor.x + cursor_cell.col.saturating_sub(view.scroll_col) as u16;
             let cursor_screen_y =
-                layout.editor.y + cursor_pos.line.saturating_sub(view.scroll_line) as u16;
+                layout.editor.y + view.row_of(cursor_cell.line).unwrap_or(0) as u16;
 
             // Only show cursor in insert mode
             if mode == Mode::Insert {
@@ -127,22 +346,58 @@ impl Renderer {
         Ok(())
     }
 
+    /// Put `text` on the system clipboard through the terminal (OSC 52)
+    ///
+    /// Does nothing when drawing to a test backend.
+    pub fn copy_to_clipboard(&mut self, text: &str) -> Result<()> {
+        if !self.owns_tty {
+            return Ok(());
+        }
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+        stdout.flush()?;
+        Ok(())
+    }
+
     /// Restore terminal to normal state
     pub fn restore(&mut self) -> Result<()> {
+        if !self.owns_tty {
+            return Ok(());
+        }
         disable_raw_mode()?;
-        execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
+        execute!(io::stdout(), DisableMouseCapture, DisableBracketedPaste, LeaveAlternateScreen)?;
         self.terminal.show_cursor()?;
         Ok(())
     }
 }
 
-impl Drop for Renderer {
+impl<B: Backend> Drop for Renderer<B> {
     fn drop(&mut self) {
         // Try to restore terminal on drop
         let _ = self.restore();
     }
 }
 
+/// Standard base64 encoding of `bytes`, with padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,4 +419,13 @@ mod tests {
         // Ensure drop doesn't panic
         // This is a basic safety test
     }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64("héllo".as_bytes()), "aMOpbGxv");
+    }
 }
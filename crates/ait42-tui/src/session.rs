@@ -0,0 +1,274 @@
+//! Session Persistence
+//!
+//! Snapshot of the editor saved on exit and offered for restore on the next
+//! launch without arguments: the file of each tab with its cursor and scroll
+//! position, the active tab, and the visibility and sizes of the panels.
+//! The last session is kept in `session.toml` in the config directory and
+//! belongs to the workspace it was saved in.
+//!
+//! Global marks outlive the session: [`SavedMarks`] keeps them per workspace
+//! in `.ait42/marks.toml`, so they are back after a restart whether or not
+//! the session is restored.
+
+use crate::layout::PanelSizes;
+use ait42_config::ConfigLoader;
+use ait42_core::{marks, GlobalMark, GlobalMarks};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Session file, relative to the config directory
+const SESSION_FILE: &str = "session.toml";
+
+/// Global marks file, relative to the workspace root
+const MARKS_FILE: &str = ".ait42/marks.toml";
+
+/// A tab of a saved session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TabSession {
+    /// File open in the tab
+    pub path: PathBuf,
+    /// Cursor line (0-indexed)
+    #[serde(default)]
+    pub line: usize,
+    /// Cursor column (0-indexed)
+    #[serde(default)]
+    pub column: usize,
+    /// First visible line
+    #[serde(default)]
+    pub scroll_line: usize,
+    /// Horizontal scroll offset
+    #[serde(default)]
+    pub scroll_col: usize,
+}
+
+/// Editor state saved on exit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Workspace the session was saved in
+    pub workspace: PathBuf,
+    /// Index of the active tab in `tabs`
+    #[serde(default)]
+    pub active_tab: usize,
+    /// Sidebar visibility
+    #[serde(default)]
+    pub sidebar_visible: bool,
+    /// Terminal panel visibility
+    #[serde(default)]
+    pub terminal_visible: bool,
+    /// Sidebar and terminal panel sizes
+    #[serde(default)]
+    pub panel_sizes: PanelSizes,
+    /// Tabs with a file, in order
+    #[serde(default)]
+    pub tabs: Vec<TabSession>,
+}
+
+impl Session {
+    /// Whether there is nothing to restore
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    /// Default session file, in the config directory
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        Ok(ConfigLoader::default_config_dir()?.join(SESSION_FILE))
+    }
+
+    /// Session saved in `path`, if there is a readable one
+    pub fn load_from(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let mut session: Self = toml::from_str(&content)
+            .map_err(|e| tracing::warn!("Ignoring invalid session {}: {}", path.display(), e))
+            .ok()?;
+        session.panel_sizes = session.panel_sizes.clamped();
+        Some(session)
+    }
+
+    /// Session saved in `path` for `workspace`, unless it has no tabs
+    pub fn load_for(path: &Path, workspace: &Path) -> Option<Self> {
+        Self::load_from(path)
+            .filter(|session| session.workspace == workspace && !session.is_empty())
+    }
+
+    /// Save the session to `path`
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Global marks saved for a workspace
+///
+/// Files in the workspace are saved relative to it, so the marks survive the
+/// workspace moving.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedMarks {
+    /// Marks by name
+    #[serde(default)]
+    pub marks: BTreeMap<String, GlobalMark>,
+}
+
+impl SavedMarks {
+    /// Marks of `workspace` to save
+    pub fn new(marks: &GlobalMarks, workspace: &Path) -> Self {
+        let marks = marks
+            .iter()
+            .map(|(name, global)| {
+                let path = global.path.strip_prefix(workspace).unwrap_or(&global.path);
+                let saved = GlobalMark {
+                    path: path.to_path_buf(),
+                    mark: global.mark,
+                };
+                (name.to_string(), saved)
+            })
+            .collect();
+        Self { marks }
+    }
+
+    /// Saved marks, with their files resolved in `workspace`
+    ///
+    /// Entries not named by a global mark are left out.
+    pub fn marks(&self, workspace: &Path) -> GlobalMarks {
+        let mut resolved = GlobalMarks::new();
+        for (name, saved) in &self.marks {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(name), None) if marks::is_global(name) => {
+                    resolved.set(name, &workspace.join(&saved.path), saved.mark);
+                }
+                _ => tracing::warn!("Ignoring invalid global mark {:?}", name),
+            }
+        }
+        resolved
+    }
+
+    /// File the marks of `workspace` are saved in
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(MARKS_FILE)
+    }
+
+    /// Marks saved for `workspace`, none if there are none
+    pub fn load(workspace: &Path) -> Self {
+        let path = Self::path(workspace);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid marks {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save the marks for `workspace`
+    pub fn save(&self, workspace: &Path) -> anyhow::Result<()> {
+        let path = Self::path(workspace);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_core::Mark;
+
+    fn session(workspace: &Path) -> Session {
+        Session {
+            workspace: workspace.to_path_buf(),
+            active_tab: 1,
+            sidebar_visible: true,
+            terminal_visible: false,
+            panel_sizes: PanelSizes {
+                sidebar_percent: 35,
+                terminal_percent: 20,
+            },
+            tabs: vec![
+                TabSession {
+                    path: workspace.join("README.md"),
+                    line: 3,
+                    column: 7,
+                    scroll_line: 1,
+                    scroll_col: 0,
+                },
+                TabSession {
+                    path: workspace.join("src/main.rs"),
+                    line: 40,
+                    column: 0,
+                    scroll_line: 25,
+                    scroll_col: 4,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config/session.toml");
+        let saved = session(dir.path());
+
+        saved.save_to(&path).unwrap();
+
+        assert_eq!(Session::load_from(&path), Some(saved.clone()));
+        assert_eq!(Session::load_for(&path, dir.path()), Some(saved));
+    }
+
+    #[test]
+    fn test_session_belongs_to_its_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.toml");
+        assert_eq!(Session::load_from(&path), None);
+
+        let mut saved = session(dir.path());
+        saved.save_to(&path).unwrap();
+        assert_eq!(Session::load_for(&path, &dir.path().join("other")), None);
+
+        // A session without files is not offered
+        saved.tabs.clear();
+        saved.save_to(&path).unwrap();
+        assert_eq!(Session::load_for(&path, dir.path()), None);
+
+        std::fs::write(&path, "tabs = 3\n").unwrap();
+        assert_eq!(Session::load_from(&path), None);
+    }
+
+    #[test]
+    fn test_marks_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path();
+        assert_eq!(SavedMarks::load(workspace), SavedMarks::default());
+
+        let mut marks = GlobalMarks::new();
+        marks.set('A', &workspace.join("src/main.rs"), Mark::new(12, 4));
+        marks.set('Z', Path::new("/etc/hosts"), Mark::new(0, 0));
+        let saved = SavedMarks::new(&marks, workspace);
+        assert_eq!(saved.marks["A"].path, Path::new("src/main.rs"));
+        assert_eq!(saved.marks["Z"].path, Path::new("/etc/hosts"));
+
+        saved.save(workspace).unwrap();
+        assert!(workspace.join(".ait42/marks.toml").exists());
+        let loaded = SavedMarks::load(workspace);
+        assert_eq!(loaded, saved);
+        assert_eq!(loaded.marks(workspace), marks);
+
+        // Names of no global mark are left out
+        std::fs::write(
+            SavedMarks::path(workspace),
+            "[marks.a]\npath = \"a.rs\"\nline = 1\n\n[marks.B]\npath = \"b.rs\"\nline = 2\n",
+        )
+        .unwrap();
+        let loaded = SavedMarks::load(workspace).marks(workspace);
+        assert_eq!(loaded.iter().count(), 1);
+        assert_eq!(loaded.get('B').unwrap().mark, Mark::new(2, 0));
+
+        std::fs::write(SavedMarks::path(workspace), "marks = 3\n").unwrap();
+        assert_eq!(SavedMarks::load(workspace), SavedMarks::default());
+    }
+}
@@ -0,0 +1,305 @@
+//! Shell State
+//!
+//! What a shell would remember between the commands run in the terminal
+//! panel: the working directory and the one before it for `cd -`, and the
+//! variables set with `export`, `NAME=value` or `unset`. Commands are split
+//! into words the way a shell would, removing quotes and expanding `~` and
+//! `$VAR` outside single quotes, so the terminal executor behaves predictably
+//! without a PTY.
+
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+
+/// Working directory and variables of the terminal session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellState {
+    cwd: PathBuf,
+    /// Directory `cd -` returns to
+    previous_dir: Option<PathBuf>,
+    /// Variables set in the session, `None` for ones unset there; passed to
+    /// every command run
+    vars: BTreeMap<String, Option<String>>,
+}
+
+impl ShellState {
+    /// Session starting in `cwd`, with the environment of the editor
+    pub fn new(cwd: PathBuf) -> Self {
+        Self {
+            cwd,
+            previous_dir: None,
+            vars: BTreeMap::new(),
+        }
+    }
+
+    /// Working directory
+    pub fn cwd(&self) -> &PathBuf {
+        &self.cwd
+    }
+
+    /// Change the working directory to `dir`, which `cd -` returns from
+    pub fn set_cwd(&mut self, dir: PathBuf) {
+        if dir != self.cwd {
+            self.previous_dir = Some(std::mem::replace(&mut self.cwd, dir));
+        }
+    }
+
+    /// Directory `cd -` returns to
+    pub fn previous_dir(&self) -> Option<&Path> {
+        self.previous_dir.as_deref()
+    }
+
+    /// Value of the variable `name`, from the session, the working
+    /// directories or the editor's environment
+    pub fn var(&self, name: &str) -> Option<String> {
+        if let Some(value) = self.vars.get(name) {
+            return value.clone();
+        }
+        match name {
+            "PWD" => Some(self.cwd.display().to_string()),
+            "OLDPWD" => self
+                .previous_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string()),
+            _ => std::env::var(name).ok(),
+        }
+    }
+
+    /// Set the variable `name` for the rest of the session
+    pub fn set_var(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.vars.insert(name.into(), Some(value.into()));
+    }
+
+    /// Unset the variable `name` for the rest of the session
+    pub fn unset_var(&mut self, name: impl Into<String>) {
+        self.vars.insert(name.into(), None);
+    }
+
+    /// Variables set in the session, with the ones unset as `None`
+    pub fn vars(&self) -> &BTreeMap<String, Option<String>> {
+        &self.vars
+    }
+
+    /// Change to `target` as `cd` would: home without one, the previous
+    /// directory for `-`, relative to the working directory otherwise
+    ///
+    /// Returns the new working directory.
+    pub fn cd(&mut self, target: Option<&str>) -> Result<PathBuf, String> {
+        let target = match target {
+            None => self.var("HOME").ok_or("HOME not set")?,
+            Some("-") => match &self.previous_dir {
+                Some(dir) => dir.display().to_string(),
+                None => return Err("OLDPWD not set".to_string()),
+            },
+            Some(target) => target.to_string(),
+        };
+
+        let dir = self
+            .cwd
+            .join(&target)
+            .canonicalize()
+            .map_err(|e| format!("{}: {}", target, e))?;
+        if !dir.is_dir() {
+            return Err(format!("Not a directory: {}", target));
+        }
+        self.set_cwd(dir.clone());
+        Ok(dir)
+    }
+
+    /// Split `command` into words, removing quotes and expanding `~` and
+    /// variables outside single quotes
+    ///
+    /// Fails on an unterminated quote.
+    pub fn split_words(&self, command: &str) -> Result<Vec<String>, String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        // Whether a word was started, so `""` gives an empty word
+        let mut in_word = false;
+        let mut quote = None;
+        let mut chars = command.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match (quote, ch) {
+                (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+                (Some('\''), _) => word.push(ch),
+                (Some(_), '\\') => match chars.peek() {
+                    Some(&next @ ('$' | '"' | '\\')) => {
+                        word.push(next);
+                        chars.next();
+                    }
+                    _ => word.push(ch),
+                },
+                (_, '$') => {
+                    self.expand_var(&mut chars, &mut word);
+                    // An unquoted variable that is empty gives no word
+                    in_word |= !word.is_empty();
+                }
+                (Some(_), _) => word.push(ch),
+                (None, '\'' | '"') => {
+                    quote = Some(ch);
+                    in_word = true;
+                }
+                (None, '\\') => {
+                    if let Some(next) = chars.next() {
+                        word.push(next);
+                    }
+                    in_word = true;
+                }
+                (None, ' ' | '\t') => {
+                    if in_word {
+                        words.push(std::mem::take(&mut word));
+                        in_word = false;
+                    }
+                }
+                (None, '~') if !in_word && matches!(chars.peek(), None | Some('/' | ' ')) => {
+                    match self.var("HOME") {
+                        Some(home) => word.push_str(&home),
+                        None => word.push(ch),
+                    }
+                    in_word = true;
+                }
+                (None, _) => {
+                    word.push(ch);
+                    in_word = true;
+                }
+            }
+        }
+
+        if quote.is_some() {
+            return Err("Unterminated quote".to_string());
+        }
+        if in_word {
+            words.push(word);
+        }
+        Ok(words)
+    }
+
+    /// Expand the variable after a `$` into `word`, keeping the `$` if no
+    /// name follows
+    fn expand_var(&self, chars: &mut Peekable<Chars>, word: &mut String) {
+        let braced = chars.peek() == Some(&'{');
+        if braced {
+            chars.next();
+        }
+        let mut name = String::new();
+        while let Some(&ch) = chars.peek() {
+            if !(ch.is_ascii_alphanumeric() || ch == '_') {
+                break;
+            }
+            name.push(ch);
+            chars.next();
+        }
+        if braced && chars.next_if_eq(&'}').is_none() {
+            word.push_str("${");
+            word.push_str(&name);
+            return;
+        }
+        if name.is_empty() {
+            word.push('$');
+            if braced {
+                word.push_str("{}");
+            }
+            return;
+        }
+        word.push_str(&self.var(&name).unwrap_or_default());
+    }
+}
+
+/// Name and value of a `NAME=value` assignment
+pub fn parse_assignment(word: &str) -> Option<(&str, &str)> {
+    let (name, value) = word.split_once('=')?;
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    valid.then_some((name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell() -> ShellState {
+        let mut shell = ShellState::new(PathBuf::from("/work"));
+        shell.set_var("HOME", "/home/dev");
+        shell.set_var("NAME", "world");
+        shell
+    }
+
+    #[test]
+    fn test_split_words_removes_quotes() {
+        let shell = shell();
+        assert_eq!(shell.split_words("ls -la  /tmp").unwrap(), ["ls", "-la", "/tmp"]);
+        assert_eq!(
+            shell
+                .split_words(r#"echo "hello world" 'it''s' a\ b"#)
+                .unwrap(),
+            ["echo", "hello world", "its", "a b"]
+        );
+        assert_eq!(shell.split_words(r#"echo "" "it's""#).unwrap(), ["echo", "", "it's"]);
+        assert!(shell.split_words("echo 'open").is_err());
+    }
+
+    #[test]
+    fn test_split_words_expands() {
+        let shell = shell();
+        assert_eq!(
+            shell
+                .split_words("echo $NAME ${NAME}s \"$NAME\" '$NAME' \\$NAME")
+                .unwrap(),
+            ["echo", "world", "worlds", "world", "$NAME", "$NAME"]
+        );
+        assert_eq!(
+            shell
+                .split_words("echo $UNSET_VAR_12345 \"$UNSET_VAR_12345\" $")
+                .unwrap(),
+            ["echo", "", "$"]
+        );
+        assert_eq!(
+            shell.split_words("ls ~ ~/src a~ '~'").unwrap(),
+            ["ls", "/home/dev", "/home/dev/src", "a~", "~"]
+        );
+        assert_eq!(shell.split_words("echo $PWD").unwrap(), ["echo", "/work"]);
+    }
+
+    #[test]
+    fn test_vars() {
+        let mut shell = shell();
+        assert_eq!(shell.var("NAME").as_deref(), Some("world"));
+        shell.unset_var("NAME");
+        assert_eq!(shell.var("NAME"), None);
+        assert_eq!(shell.vars().get("NAME"), Some(&None));
+
+        assert_eq!(parse_assignment("FOO=bar"), Some(("FOO", "bar")));
+        assert_eq!(parse_assignment("_X1="), Some(("_X1", "")));
+        assert_eq!(parse_assignment("1X=bar"), None);
+        assert_eq!(parse_assignment("--flag=x"), None);
+        assert_eq!(parse_assignment("FOO"), None);
+    }
+
+    #[test]
+    fn test_cd() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("file"), "").unwrap();
+
+        let mut shell = ShellState::new(root.clone());
+        assert!(shell.cd(Some("-")).is_err());
+        assert_eq!(shell.cd(Some("sub")).unwrap(), root.join("sub"));
+        assert_eq!(shell.var("OLDPWD"), Some(root.display().to_string()));
+        assert_eq!(shell.cd(Some("-")).unwrap(), root);
+        assert_eq!(shell.previous_dir(), Some(root.join("sub").as_path()));
+
+        assert!(shell.cd(Some("file")).is_err());
+        assert!(shell.cd(Some("missing")).is_err());
+        assert_eq!(shell.cwd(), &root);
+
+        shell.set_var("HOME", root.join("sub").display().to_string());
+        assert_eq!(shell.cd(None).unwrap(), root.join("sub"));
+    }
+}
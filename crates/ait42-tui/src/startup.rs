@@ -0,0 +1,155 @@
+//! Startup Profile
+//!
+//! Time spent in each startup phase up to the first frame on screen. The
+//! binary prints it after exit with `--profile-startup`, next to the time
+//! a typical project should reach its first paint in.
+
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+/// Time from process start to the first frame on a typical project
+pub const FIRST_PAINT_BUDGET: Duration = Duration::from_millis(100);
+
+/// Phase that ends once the first frame is drawn
+pub const FIRST_PAINT: &str = "first paint";
+
+/// Startup phases and how long each took
+#[derive(Debug, Clone)]
+pub struct StartupProfile {
+    start: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl StartupProfile {
+    /// Start timing now
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            last: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// End the phase running since the previous mark, naming it `phase`
+    pub fn mark(&mut self, phase: &'static str) {
+        let now = Instant::now();
+        self.phases.push((phase, now - self.last));
+        self.last = now;
+    }
+
+    /// Phases in the order they ended
+    pub fn phases(&self) -> &[(&'static str, Duration)] {
+        &self.phases
+    }
+
+    /// Time since timing started
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Time from start to the first frame, once drawn
+    pub fn first_paint(&self) -> Option<Duration> {
+        let end = self
+            .phases
+            .iter()
+            .position(|(phase, _)| *phase == FIRST_PAINT)?;
+        Some(
+            self.phases[..=end]
+                .iter()
+                .map(|(_, duration)| *duration)
+                .sum(),
+        )
+    }
+
+    /// Whether the first frame was drawn within [`FIRST_PAINT_BUDGET`]
+    pub fn within_budget(&self) -> bool {
+        self.first_paint()
+            .is_some_and(|duration| duration <= FIRST_PAINT_BUDGET)
+    }
+
+    /// One line per phase, then the first paint against the budget
+    pub fn report(&self) -> String {
+        let mut report = String::from("Startup profile\n");
+        for (phase, duration) in &self.phases {
+            let _ = writeln!(report, "  {:<12} {:>7.1} ms", phase, millis(*duration));
+        }
+        match self.first_paint() {
+            Some(duration) => {
+                let verdict = if self.within_budget() {
+                    "within"
+                } else {
+                    "over"
+                };
+                let _ = writeln!(
+                    report,
+                    "  {:<12} {:>7.1} ms ({} the {} ms budget)",
+                    "total",
+                    millis(duration),
+                    verdict,
+                    FIRST_PAINT_BUDGET.as_millis()
+                );
+            }
+            None => report.push_str("  no frame was drawn\n"),
+        }
+        report
+    }
+}
+
+impl Default for StartupProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(phases: &[(&'static str, u64)]) -> StartupProfile {
+        let mut profile = StartupProfile::new();
+        profile.phases = phases
+            .iter()
+            .map(|(phase, ms)| (*phase, Duration::from_millis(*ms)))
+            .collect();
+        profile
+    }
+
+    #[test]
+    fn test_marks_phases_in_order() {
+        let mut profile = StartupProfile::new();
+        profile.mark("config");
+        profile.mark(FIRST_PAINT);
+        profile.mark("after");
+
+        let names: Vec<&str> = profile.phases().iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(names, ["config", FIRST_PAINT, "after"]);
+        let first_paint = profile.first_paint().unwrap();
+        assert_eq!(first_paint, profile.phases()[0].1 + profile.phases()[1].1);
+        assert!(first_paint <= profile.elapsed());
+    }
+
+    #[test]
+    fn test_first_paint_budget() {
+        let fast = profile(&[("config", 20), ("terminal", 30), (FIRST_PAINT, 40)]);
+        assert_eq!(fast.first_paint(), Some(Duration::from_millis(90)));
+        assert!(fast.within_budget());
+        let report = fast.report();
+        assert!(report.contains("terminal"));
+        assert!(report.contains("90.0 ms (within the 100 ms budget)"));
+
+        let slow = profile(&[("config", 20), (FIRST_PAINT, 90), ("later", 500)]);
+        assert_eq!(slow.first_paint(), Some(Duration::from_millis(110)));
+        assert!(!slow.within_budget());
+        assert!(slow.report().contains("over the 100 ms budget"));
+
+        let unpainted = profile(&[("config", 20)]);
+        assert!(!unpainted.within_budget());
+        assert!(unpainted.report().contains("no frame was drawn"));
+    }
+}
@@ -0,0 +1,874 @@
+//! Syntax Highlighting
+//!
+//! Tree-sitter based highlighting for [`EditorWidget`](crate::EditorWidget).
+//!
+//! The grammar is picked from the buffer's file extension. When the buffer
+//! changes, the edited region is found by comparing the new text with the
+//! last parsed snapshot; the old tree is adjusted to that edit and handed to
+//! the parser, which only re-parses the subtrees the edit touched.
+//!
+//! Embedded languages are highlighted with their own grammar: SQL in string
+//! literals, JavaScript in HTML `<script>` elements and fenced code blocks in
+//! Markdown. After each parse the tree is searched for these injection
+//! sites; each one is parsed with its grammar restricted to the site's
+//! ranges, and its spans replace the host spans there.
+//!
+//! The tree also gives the nesting depth of each bracket, for rainbow
+//! delimiters, and the function or block under the cursor, for scope
+//! highlighting.
+
+use std::ops::Range;
+
+use ait42_core::{Buffer, BufferId, Language};
+use ratatui::style::Style;
+use tracing::warn;
+use tree_sitter::{InputEdit, Node, Parser, Point, Tree};
+
+/// Injections nested deeper than this are not highlighted
+const MAX_INJECTION_DEPTH: usize = 3;
+
+/// Leading keywords marking a string literal as SQL
+const SQL_KEYWORDS: &[&str] = &[
+    "select", "insert", "update", "delete", "with", "create", "alter", "drop",
+];
+
+/// Nodes highlighted as the scope under the cursor
+const SCOPE_KINDS: &[&str] = &[
+    // Rust
+    "function_item",
+    "closure_expression",
+    "impl_item",
+    "trait_item",
+    "block",
+    // Python
+    "function_definition",
+    "class_definition",
+    // JavaScript
+    "function_declaration",
+    "function_expression",
+    "arrow_function",
+    "method_definition",
+    "class_declaration",
+    "statement_block",
+];
+
+use crate::theme::Theme;
+
+/// Token categories colored by the theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Keyword,
+    String,
+    Number,
+    Comment,
+    Function,
+    Type,
+}
+
+impl TokenKind {
+    /// Style of the token in `theme`
+    pub fn style(self, theme: &Theme) -> Style {
+        match self {
+            TokenKind::Keyword => theme.keyword,
+            TokenKind::String => theme.string,
+            TokenKind::Number => theme.number,
+            TokenKind::Comment => theme.comment,
+            TokenKind::Function => theme.function,
+            TokenKind::Type => theme.type_name,
+        }
+    }
+}
+
+/// Grammars used for highlighting
+///
+/// Besides the [`Language`]s used for editing, this includes grammars that
+/// are only highlighted, as whole files or embedded in other files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grammar {
+    Rust,
+    Python,
+    JavaScript,
+    Html,
+    Markdown,
+    Sql,
+}
+
+impl Grammar {
+    /// Detect grammar from a file extension (without the dot)
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "html" | "htm" => Some(Grammar::Html),
+            "md" | "markdown" => Some(Grammar::Markdown),
+            "sql" => Some(Grammar::Sql),
+            _ => Language::from_extension(ext).map(Self::from),
+        }
+    }
+
+    /// Detect grammar from a language name, as in a Markdown fence info
+    /// string
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rust" => Some(Grammar::Rust),
+            "python" => Some(Grammar::Python),
+            "javascript" => Some(Grammar::JavaScript),
+            "markdown" => Some(Grammar::Markdown),
+            ext => Self::from_extension(ext),
+        }
+    }
+
+    /// Editing language with the same grammar, if any
+    pub fn language(self) -> Option<Language> {
+        match self {
+            Grammar::Rust => Some(Language::Rust),
+            Grammar::Python => Some(Language::Python),
+            Grammar::JavaScript => Some(Language::JavaScript),
+            Grammar::Html | Grammar::Markdown | Grammar::Sql => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Grammar::Html => "HTML",
+            Grammar::Markdown => "Markdown",
+            Grammar::Sql => "SQL",
+            _ => self.language().map_or("", |language| language.name()),
+        }
+    }
+
+    /// Tree-sitter grammar
+    pub fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Grammar::Html => tree_sitter_html::LANGUAGE.into(),
+            Grammar::Markdown => tree_sitter_md::LANGUAGE.into(),
+            Grammar::Sql => tree_sitter_sequel::LANGUAGE.into(),
+            Grammar::Rust => Language::Rust.grammar(),
+            Grammar::Python => Language::Python.grammar(),
+            Grammar::JavaScript => Language::JavaScript.grammar(),
+        }
+    }
+}
+
+impl From<Language> for Grammar {
+    fn from(language: Language) -> Self {
+        match language {
+            Language::Rust => Grammar::Rust,
+            Language::Python => Grammar::Python,
+            Language::JavaScript => Grammar::JavaScript,
+        }
+    }
+}
+
+/// Highlighted part of a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightSpan {
+    /// Byte range within the line
+    pub bytes: Range<usize>,
+    pub kind: TokenKind,
+}
+
+/// Structural highlighting switched on for a buffer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StructureHighlight {
+    /// Color brackets by their depth
+    pub rainbow_delimiters: bool,
+    /// Tint the function or block under the cursor
+    pub scope: bool,
+}
+
+/// Bracket within a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delimiter {
+    /// Byte range within the line
+    pub bytes: Range<usize>,
+    /// Number of bracket pairs enclosing it
+    pub depth: usize,
+}
+
+/// Embedded language parsed within part of the buffer
+struct Layer {
+    grammar: Grammar,
+    /// Ranges of the buffer the grammar was restricted to
+    ranges: Vec<tree_sitter::Range>,
+    tree: Tree,
+}
+
+/// Incrementally parsed syntax tree of the displayed buffer
+pub struct SyntaxHighlighter {
+    parser: Parser,
+    /// Parser for injections, so `parser` keeps its incremental state
+    injection_parser: Parser,
+    grammar: Option<Grammar>,
+    tree: Option<Tree>,
+    /// Injections, each after the layer it is embedded in
+    layers: Vec<Layer>,
+    /// Text the tree was parsed from
+    source: String,
+    /// Buffer and version the tree was parsed from
+    parsed: Option<(BufferId, u64)>,
+}
+
+impl SyntaxHighlighter {
+    /// Create a highlighter with no buffer parsed yet
+    pub fn new() -> Self {
+        Self {
+            parser: Parser::new(),
+            injection_parser: Parser::new(),
+            grammar: None,
+            tree: None,
+            layers: Vec::new(),
+            source: String::new(),
+            parsed: None,
+        }
+    }
+
+    /// Language of the parsed buffer, if it is an editing language
+    pub fn language(&self) -> Option<Language> {
+        self.grammar.and_then(Grammar::language)
+    }
+
+    /// Grammar of the parsed buffer, if it has one
+    pub fn grammar(&self) -> Option<Grammar> {
+        self.grammar
+    }
+
+    /// Grammar and byte range of each embedded language, in buffer order
+    /// of the outermost injections
+    pub fn injections(&self) -> Vec<(Grammar, Range<usize>)> {
+        self.layers
+            .iter()
+            .filter_map(|layer| {
+                let first = layer.ranges.first()?;
+                let last = layer.ranges.last()?;
+                Some((layer.grammar, first.start_byte..last.end_byte))
+            })
+            .collect()
+    }
+
+    /// Bring the tree up to date with `buffer`
+    ///
+    /// Does nothing if the buffer has not changed since the last update.
+    /// Edits to the same buffer re-parse incrementally; a different buffer
+    /// or language is parsed from scratch.
+    pub fn update(&mut self, buffer: &Buffer) {
+        let key = (buffer.id(), buffer.version());
+        if self.parsed == Some(key) {
+            return;
+        }
+
+        let grammar = buffer.language().and_then(Grammar::from_extension);
+        let same_buffer = self.parsed.map(|(id, _)| id) == Some(buffer.id());
+        self.parsed = Some(key);
+
+        if grammar != self.grammar || !same_buffer {
+            self.tree = None;
+            self.layers.clear();
+            self.source.clear();
+            self.grammar = grammar;
+            if let Some(grammar) = grammar {
+                if let Err(e) = self.parser.set_language(&grammar.grammar()) {
+                    warn!("Failed to load {} grammar: {}", grammar.name(), e);
+                    self.grammar = None;
+                }
+            }
+        }
+        let Some(grammar) = self.grammar else {
+            return;
+        };
+
+        let source = buffer.to_string();
+        if let Some(tree) = &mut self.tree {
+            tree.edit(&input_edit(&self.source, &source));
+        }
+        self.tree = self.parser.parse(&source, self.tree.as_ref());
+        self.source = source;
+
+        // Injected regions are small, so they are parsed from scratch
+        self.layers.clear();
+        if let Some(tree) = &self.tree {
+            inject(
+                &mut self.injection_parser,
+                grammar,
+                tree.root_node(),
+                &self.source,
+                0,
+                &mut self.layers,
+            );
+        }
+    }
+
+    /// Highlight spans of each line in `lines`, in line order
+    pub fn highlights(&self, lines: Range<usize>) -> Vec<Vec<HighlightSpan>> {
+        let mut spans = vec![Vec::new(); lines.len()];
+        if let Some(tree) = &self.tree {
+            collect(tree.root_node(), &self.source, &lines, &mut spans);
+        }
+        for layer in &self.layers {
+            carve(&layer.ranges, &lines, &mut spans);
+            collect(layer.tree.root_node(), &self.source, &lines, &mut spans);
+        }
+        for line in &mut spans {
+            line.sort_by_key(|span| span.bytes.start);
+        }
+        spans
+    }
+
+    /// Brackets of each line in `lines`, in line order
+    ///
+    /// Only brackets of the buffer's own grammar are included, not those
+    /// of embedded languages.
+    pub fn delimiters(&self, lines: Range<usize>) -> Vec<Vec<Delimiter>> {
+        let mut delimiters = vec![Vec::new(); lines.len()];
+        if let Some(tree) = &self.tree {
+            collect_delimiters(tree.root_node(), 0, &lines, &mut delimiters);
+        }
+        delimiters
+    }
+
+    /// Lines of the innermost function or block containing byte `offset`
+    pub fn scope(&self, offset: usize) -> Option<Range<usize>> {
+        let tree = self.tree.as_ref()?;
+        let mut node = tree.root_node().descendant_for_byte_range(offset, offset);
+        while let Some(current) = node {
+            if SCOPE_KINDS.contains(&current.kind()) {
+                return Some(current.start_position().row..current.end_position().row + 1);
+            }
+            node = current.parent();
+        }
+        None
+    }
+}
+
+impl Default for SyntaxHighlighter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The edit turning `old` into `new`, from their common prefix and suffix
+fn input_edit(old: &str, new: &str) -> InputEdit {
+    let mut prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let mut suffix = old
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+
+    let old_end = old.len() - suffix;
+    let new_end = new.len() - suffix;
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point_at(old, prefix),
+        old_end_position: point_at(old, old_end),
+        new_end_position: point_at(new, new_end),
+    }
+}
+
+/// Row and byte column of `byte` in `text`
+fn point_at(text: &str, byte: usize) -> Point {
+    let before = &text[..byte];
+    let row = before.matches('\n').count();
+    let column = before
+        .rfind('\n')
+        .map_or(byte, |newline| byte - newline - 1);
+    Point { row, column }
+}
+
+/// Parse the injection sites in `node`, written in `grammar`, into `layers`
+fn inject(
+    parser: &mut Parser,
+    grammar: Grammar,
+    node: Node<'_>,
+    source: &str,
+    depth: usize,
+    layers: &mut Vec<Layer>,
+) {
+    let Some((injected, ranges)) = injection(grammar, node, source) else {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            inject(parser, grammar, child, source, depth, layers);
+        }
+        return;
+    };
+    if depth >= MAX_INJECTION_DEPTH {
+        return;
+    }
+
+    if let Err(e) = parser.set_language(&injected.grammar()) {
+        warn!("Failed to load {} grammar: {}", injected.name(), e);
+        return;
+    }
+    if parser.set_included_ranges(&ranges).is_err() {
+        return;
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return;
+    };
+
+    let root = tree.root_node();
+    layers.push(Layer {
+        grammar: injected,
+        ranges,
+        tree: tree.clone(),
+    });
+    inject(parser, injected, root, source, depth + 1, layers);
+}
+
+/// Grammar and ranges of the language embedded in `node`, if any
+fn injection(
+    grammar: Grammar,
+    node: Node<'_>,
+    source: &str,
+) -> Option<(Grammar, Vec<tree_sitter::Range>)> {
+    let children = |kinds: &[&str]| -> Vec<tree_sitter::Range> {
+        let mut cursor = node.walk();
+        node.named_children(&mut cursor)
+            .filter(|child| kinds.contains(&child.kind()))
+            .map(|child| child.range())
+            .collect()
+    };
+
+    let (injected, ranges) = match (grammar, node.kind()) {
+        (Grammar::Html, "script_element") => (Grammar::JavaScript, children(&["raw_text"])),
+        (Grammar::Markdown, "fenced_code_block") => {
+            let mut cursor = node.walk();
+            let info = node
+                .named_children(&mut cursor)
+                .find(|child| child.kind() == "info_string")?;
+            let name = source[info.byte_range()].split_whitespace().next()?;
+            (Grammar::from_name(name)?, children(&["code_fence_content"]))
+        }
+        (
+            Grammar::Rust | Grammar::Python | Grammar::JavaScript,
+            "string_literal" | "raw_string_literal" | "string" | "template_string",
+        ) => {
+            let ranges = children(&["string_content", "string_fragment"]);
+            let text = &source[ranges.first()?.start_byte..];
+            (looks_like_sql(text).then_some(Grammar::Sql)?, ranges)
+        }
+        _ => return None,
+    };
+    (!ranges.is_empty()).then_some((injected, ranges))
+}
+
+/// Whether `text` starts with an SQL statement keyword
+fn looks_like_sql(text: &str) -> bool {
+    let mut words = text.split_whitespace();
+    let Some(first) = words.next() else {
+        return false;
+    };
+    let keyword = SQL_KEYWORDS.iter().any(|k| first.eq_ignore_ascii_case(k));
+    keyword && words.next().is_some()
+}
+
+/// Cut the parts covered by `ranges` out of `spans`
+fn carve(ranges: &[tree_sitter::Range], lines: &Range<usize>, spans: &mut [Vec<HighlightSpan>]) {
+    for range in ranges {
+        let first = range.start_point.row.max(lines.start);
+        let last = (range.end_point.row + 1).min(lines.end);
+        for row in first..last {
+            let start = if row == range.start_point.row {
+                range.start_point.column
+            } else {
+                0
+            };
+            let end = if row == range.end_point.row {
+                range.end_point.column
+            } else {
+                usize::MAX
+            };
+
+            let line = &mut spans[row - lines.start];
+            *line = line
+                .drain(..)
+                .flat_map(|span| {
+                    let before = span.bytes.start..span.bytes.end.min(start);
+                    let after = span.bytes.start.max(end)..span.bytes.end;
+                    [before, after]
+                        .into_iter()
+                        .filter(|bytes| !bytes.is_empty())
+                        .map(move |bytes| HighlightSpan {
+                            bytes,
+                            kind: span.kind,
+                        })
+                })
+                .collect();
+        }
+    }
+}
+
+/// Add the spans of `node` and its descendants within `lines` to `spans`
+fn collect(node: Node<'_>, source: &str, lines: &Range<usize>, spans: &mut [Vec<HighlightSpan>]) {
+    if node.end_position().row < lines.start || node.start_position().row >= lines.end {
+        return;
+    }
+
+    if let Some(kind) = classify(node, source) {
+        push_span(node, kind, source, lines, spans);
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect(child, source, lines, spans);
+    }
+}
+
+/// Add the brackets of `node`'s descendants within `lines`, with `node`
+/// nested `depth` bracket pairs deep
+fn collect_delimiters(
+    node: Node<'_>,
+    depth: usize,
+    lines: &Range<usize>,
+    delimiters: &mut [Vec<Delimiter>],
+) {
+    if node.end_position().row < lines.start || node.start_position().row >= lines.end {
+        return;
+    }
+
+    // Brackets among the children enclose the other children
+    let mut cursor = node.walk();
+    let encloses = node
+        .children(&mut cursor)
+        .any(|child| matches!(child.kind(), "(" | "[" | "{") && !child.is_named());
+    let inner = depth + usize::from(encloses);
+    for child in node.children(&mut cursor) {
+        let is_bracket =
+            !child.is_named() && matches!(child.kind(), "(" | ")" | "[" | "]" | "{" | "}");
+        if !is_bracket {
+            collect_delimiters(child, inner, lines, delimiters);
+            continue;
+        }
+        let start = child.start_position();
+        if lines.contains(&start.row) {
+            delimiters[start.row - lines.start].push(Delimiter {
+                bytes: start.column..start.column + 1,
+                depth,
+            });
+        }
+    }
+}
+
+/// Split the range of `node` into per-line spans
+fn push_span(
+    node: Node<'_>,
+    kind: TokenKind,
+    source: &str,
+    lines: &Range<usize>,
+    spans: &mut [Vec<HighlightSpan>],
+) {
+    let start = node.start_position();
+    let text = &source[node.byte_range()];
+    for (i, segment) in text.split('\n').enumerate() {
+        let row = start.row + i;
+        if row >= lines.end {
+            break;
+        }
+        let column = if i == 0 { start.column } else { 0 };
+        let len = segment.trim_end_matches('\r').len();
+        if row >= lines.start && len > 0 {
+            spans[row - lines.start].push(HighlightSpan {
+                bytes: column..column + len,
+                kind,
+            });
+        }
+    }
+}
+
+/// Token kind of `node`, if it is highlighted as a whole
+fn classify(node: Node<'_>, source: &str) -> Option<TokenKind> {
+    let kind = node.kind();
+    if kind.contains("comment") {
+        return Some(TokenKind::Comment);
+    }
+    // SQL keywords are named nodes
+    if kind.starts_with("keyword_") {
+        return Some(TokenKind::Keyword);
+    }
+    if !node.is_named() {
+        let is_word = kind.chars().all(|c| c.is_ascii_alphabetic() || c == '_');
+        return is_word.then_some(TokenKind::Keyword);
+    }
+
+    match kind {
+        "string_literal" | "raw_string_literal" | "char_literal" | "string" | "template_string"
+        | "regex" => Some(TokenKind::String),
+        "integer_literal" | "float_literal" | "integer" | "float" | "number" => {
+            Some(TokenKind::Number)
+        }
+        "type_identifier" | "primitive_type" => Some(TokenKind::Type),
+        // SQL
+        "literal" => {
+            let numeric = source[node.byte_range()].starts_with(|c: char| c.is_ascii_digit());
+            Some(if numeric {
+                TokenKind::Number
+            } else {
+                TokenKind::String
+            })
+        }
+        // HTML
+        "tag_name" | "doctype" => Some(TokenKind::Keyword),
+        "attribute_name" => Some(TokenKind::Type),
+        "quoted_attribute_value" | "attribute_value" => Some(TokenKind::String),
+        // Markdown; fenced code without an injected grammar stays a string
+        "atx_heading" | "setext_heading" => Some(TokenKind::Keyword),
+        "fenced_code_block_delimiter" | "info_string" => Some(TokenKind::Comment),
+        "code_fence_content" | "indented_code_block" => Some(TokenKind::String),
+        "self" | "crate" | "super" | "mutable_specifier" | "boolean_literal" | "true" | "false"
+        | "none" | "null" | "undefined" | "this" => Some(TokenKind::Keyword),
+        "identifier" | "field_identifier" | "property_identifier" | "attribute" => {
+            is_function_name(node).then_some(TokenKind::Function)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `node` names a function where it is defined, called or invoked
+fn is_function_name(node: Node<'_>) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    let is_field = |parent: Node<'_>, field: &str| parent.child_by_field_name(field) == Some(node);
+
+    match parent.kind() {
+        "function_item"
+        | "function_signature_item"
+        | "function_definition"
+        | "function_declaration"
+        | "generator_function_declaration"
+        | "method_definition" => is_field(parent, "name"),
+        "call_expression" | "call" => is_field(parent, "function"),
+        "macro_invocation" => is_field(parent, "macro"),
+        // `obj.method(...)`: the member is the callee of the enclosing call
+        "field_expression" | "member_expression" | "attribute" => {
+            let member = ["field", "property", "attribute"]
+                .iter()
+                .any(|field| is_field(parent, field));
+            member
+                && parent.parent().is_some_and(|call| {
+                    matches!(call.kind(), "call_expression" | "call")
+                        && call.child_by_field_name("function") == Some(parent)
+                })
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str, ext: &str) -> (SyntaxHighlighter, Buffer) {
+        let buffer = Buffer::from_string(source.to_string(), Some(ext.to_string()));
+        let mut highlighter = SyntaxHighlighter::new();
+        highlighter.update(&buffer);
+        (highlighter, buffer)
+    }
+
+    /// `(text, kind)` of the spans on `line`
+    fn tokens<'a>(
+        highlighter: &SyntaxHighlighter,
+        source: &'a str,
+        line: usize,
+    ) -> Vec<(&'a str, TokenKind)> {
+        let text = source.lines().nth(line).unwrap();
+        highlighter.highlights(line..line + 1)[0]
+            .iter()
+            .map(|span| (&text[span.bytes.clone()], span.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_tokens() {
+        let source = "// add\nfn add(a: u32) -> u32 {\n    let s = \"x\";\n    helper(a + 1)\n}\n";
+        let (highlighter, _) = parse(source, "rs");
+        assert_eq!(highlighter.language(), Some(Language::Rust));
+
+        assert_eq!(tokens(&highlighter, source, 0), vec![("// add", TokenKind::Comment)]);
+        assert_eq!(
+            tokens(&highlighter, source, 1),
+            vec![
+                ("fn", TokenKind::Keyword),
+                ("add", TokenKind::Function),
+                ("u32", TokenKind::Type),
+                ("u32", TokenKind::Type),
+            ]
+        );
+        assert_eq!(
+            tokens(&highlighter, source, 2),
+            vec![("let", TokenKind::Keyword), ("\"x\"", TokenKind::String)]
+        );
+        assert_eq!(
+            tokens(&highlighter, source, 3),
+            vec![("helper", TokenKind::Function), ("1", TokenKind::Number)]
+        );
+    }
+
+    #[test]
+    fn test_multiline_token_split_per_line() {
+        let source = "x = 1\n\"\"\"doc\nstring\"\"\"\n";
+        let (highlighter, _) = parse(source, "py");
+        let spans = highlighter.highlights(1..3);
+        assert_eq!(
+            spans[0],
+            vec![HighlightSpan {
+                bytes: 0..6,
+                kind: TokenKind::String
+            }]
+        );
+        assert_eq!(
+            spans[1],
+            vec![HighlightSpan {
+                bytes: 0..9,
+                kind: TokenKind::String
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unknown_language_has_no_spans() {
+        let (highlighter, _) = parse("fn main() {}", "txt");
+        assert_eq!(highlighter.language(), None);
+        assert_eq!(highlighter.highlights(0..1), vec![Vec::new()]);
+    }
+
+    #[test]
+    fn test_incremental_update_matches_full_parse() {
+        let (mut highlighter, mut buffer) = parse("let a = 1;\nlet b = 2;\n", "js");
+        buffer.insert(11, "function f() {}\n").unwrap();
+        buffer.delete(0..4).unwrap();
+        highlighter.update(&buffer);
+
+        let (fresh, _) = parse(&buffer.to_string(), "js");
+        assert_eq!(highlighter.highlights(0..3), fresh.highlights(0..3));
+        assert_eq!(
+            tokens(&highlighter, &buffer.to_string(), 1),
+            vec![("function", TokenKind::Keyword), ("f", TokenKind::Function)]
+        );
+    }
+
+    #[test]
+    fn test_sql_in_rust_string() {
+        let source =
+            "let q = \"SELECT id FROM users\";\nlet s = \"select 1\";\nlet t = \"hello\";\n";
+        let (highlighter, _) = parse(source, "rs");
+        assert_eq!(highlighter.injections(), vec![(Grammar::Sql, 9..29), (Grammar::Sql, 41..49)]);
+
+        let line = tokens(&highlighter, source, 0);
+        assert_eq!(line.first(), Some(&("let", TokenKind::Keyword)));
+        assert!(line.contains(&("SELECT", TokenKind::Keyword)));
+        assert!(line.contains(&("FROM", TokenKind::Keyword)));
+        // The quotes stay part of the host string
+        assert_eq!(line.last(), Some(&("\"", TokenKind::String)));
+
+        assert_eq!(
+            tokens(&highlighter, source, 2),
+            vec![
+                ("let", TokenKind::Keyword),
+                ("\"hello\"", TokenKind::String)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_javascript_in_html() {
+        let source = "<p class=\"x\">hi</p>\n<script>\nlet x = 1;\n</script>\n";
+        let (highlighter, _) = parse(source, "html");
+        assert_eq!(highlighter.grammar(), Some(Grammar::Html));
+        assert_eq!(highlighter.language(), None);
+
+        assert!(tokens(&highlighter, source, 0).contains(&("class", TokenKind::Type)));
+        assert_eq!(
+            tokens(&highlighter, source, 2),
+            vec![("let", TokenKind::Keyword), ("1", TokenKind::Number)]
+        );
+    }
+
+    #[test]
+    fn test_markdown_code_fence() {
+        let source = "# Title\n\n```rust\nfn main() {}\n```\n\n```\nplain\n```\n";
+        let (highlighter, _) = parse(source, "md");
+        assert_eq!(highlighter.injections().len(), 1);
+
+        assert_eq!(
+            tokens(&highlighter, source, 3),
+            vec![("fn", TokenKind::Keyword), ("main", TokenKind::Function)]
+        );
+        // Fences without a known language are not injected
+        assert_eq!(tokens(&highlighter, source, 7), vec![("plain", TokenKind::String)]);
+    }
+
+    #[test]
+    fn test_delimiter_depth() {
+        let source = "fn f(a: [u8; 2]) {\n    g((a[0]));\n}\n";
+        let (highlighter, _) = parse(source, "rs");
+        let depths = |line: usize| -> Vec<(&str, usize)> {
+            let text = source.lines().nth(line).unwrap();
+            highlighter.delimiters(line..line + 1)[0]
+                .iter()
+                .map(|d| (&text[d.bytes.clone()], d.depth))
+                .collect()
+        };
+
+        assert_eq!(depths(0), vec![("(", 0), ("[", 1), ("]", 1), (")", 0), ("{", 0)]);
+        assert_eq!(depths(1), vec![("(", 1), ("(", 2), ("[", 3), ("]", 3), (")", 2), (")", 1)]);
+        assert_eq!(depths(2), vec![("}", 0)]);
+        assert!(SyntaxHighlighter::new().delimiters(0..1)[0].is_empty());
+    }
+
+    #[test]
+    fn test_scope_under_cursor() {
+        let source = "fn f() {\n    let c = |x| {\n        x\n    };\n}\n\nconst A: u8 = 1;\n";
+        let (highlighter, _) = parse(source, "rs");
+
+        // Innermost block around the closure body
+        let body = source.find("        x").unwrap();
+        assert_eq!(highlighter.scope(body), Some(1..4));
+        let let_line = source.find("let").unwrap();
+        assert_eq!(highlighter.scope(let_line), Some(0..5));
+        // Outside any function
+        let constant = source.find("const").unwrap();
+        assert_eq!(highlighter.scope(constant), None);
+    }
+
+    #[test]
+    fn test_grammar_names() {
+        assert_eq!(Grammar::from_name("Rust"), Some(Grammar::Rust));
+        assert_eq!(Grammar::from_name("js"), Some(Grammar::JavaScript));
+        assert_eq!(Grammar::from_name("sql"), Some(Grammar::Sql));
+        assert_eq!(Grammar::from_name("brainfuck"), None);
+        assert!(looks_like_sql("  insert into t values (1)"));
+        assert!(!looks_like_sql("delete"));
+        assert!(!looks_like_sql("selection is hard"));
+    }
+
+    #[test]
+    fn test_input_edit() {
+        let edit = input_edit("ab\ncd\n", "ab\nxcd\n");
+        assert_eq!(edit.start_byte, 3);
+        assert_eq!(edit.old_end_byte, 3);
+        assert_eq!(edit.new_end_byte, 4);
+        assert_eq!(edit.start_position, Point { row: 1, column: 0 });
+        assert_eq!(edit.new_end_position, Point { row: 1, column: 1 });
+
+        // Never split a multi-byte character
+        let edit = input_edit("é", "è");
+        assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (0, 2, 2));
+    }
+}
@@ -9,9 +9,11 @@
 //! - Real-time stdout/stderr capture
 //! - Command timeout (default: 30 seconds)
 //! - Input sanitization to prevent shell injection
-//! - Working directory management
-//! - Exit code capture and display
+//! - Working directory and session variables (`cd -`, `export`, `unset`)
+//!   with `~` and `$VAR` expansion, kept in a [`ShellState`]
+//! - Exit code and duration capture, shown after each command
 //! - Output buffer management with scrolling
+//! - Command history with timestamps and exit codes, saved per workspace
 //!
 //! # Safety
 //!
@@ -38,10 +40,13 @@
 //! }
 //! ```
 
+use crate::input_history::{InputHistory, MAX_HISTORY};
+use crate::shell_state::{parse_assignment, ShellState};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Stdio;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command as TokioCommand;
 use tokio::time::timeout;
@@ -52,23 +57,181 @@ const MAX_OUTPUT_LINES: usize = 10_000;
 /// Default command timeout (30 seconds)
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Exit code recorded when a command cannot be started
+const NOT_FOUND_EXIT_CODE: i32 = 127;
+
+/// Command run in the terminal, as kept in its history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "SavedEntry")]
+pub struct TerminalHistoryEntry {
+    /// Command line as entered
+    pub command: String,
+    /// When the command last ran, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Exit code of the last run, `None` if it was killed or is still running
+    pub exit_code: Option<i32>,
+    /// How long the last run took in milliseconds, `None` while running
+    pub duration_ms: Option<u64>,
+}
+
+impl TerminalHistoryEntry {
+    /// Entry for `command`, run now
+    pub fn new(command: impl Into<String>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            command: command.into(),
+            timestamp,
+            exit_code: None,
+            duration_ms: None,
+        }
+    }
+
+    /// Whether the command exited with a non-zero code
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|code| code != 0)
+    }
+}
+
+/// Saved history entry; histories saved before timestamps were recorded
+/// hold only the command
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedEntry {
+    Command(String),
+    Entry {
+        command: String,
+        #[serde(default)]
+        timestamp: u64,
+        #[serde(default)]
+        exit_code: Option<i32>,
+        #[serde(default)]
+        duration_ms: Option<u64>,
+    },
+}
+
+impl From<SavedEntry> for TerminalHistoryEntry {
+    fn from(entry: SavedEntry) -> Self {
+        match entry {
+            SavedEntry::Command(command) => Self {
+                command,
+                timestamp: 0,
+                exit_code: None,
+                duration_ms: None,
+            },
+            SavedEntry::Entry {
+                command,
+                timestamp,
+                exit_code,
+                duration_ms,
+            } => Self {
+                command,
+                timestamp,
+                exit_code,
+                duration_ms,
+            },
+        }
+    }
+}
+
+/// How a command run in the terminal ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandStatus {
+    /// `None` if the command was killed, by a signal or for timing out
+    pub exit_code: Option<i32>,
+    /// Wall-clock time from starting the command to its exit
+    pub duration: Duration,
+}
+
+impl CommandStatus {
+    /// Whether the command exited with a non-zero code or was killed
+    pub fn failed(&self) -> bool {
+        self.exit_code != Some(0)
+    }
+
+    /// Line shown after the command's output, e.g. `✗ 1 in 2.3s`
+    pub fn summary(&self) -> String {
+        let mark = if self.failed() { '✗' } else { '✓' };
+        let code = match self.exit_code {
+            Some(code) => code.to_string(),
+            None => "killed".to_string(),
+        };
+        format!("{} {} in {:.1}s", mark, code, self.duration.as_secs_f64())
+    }
+
+    /// Whether `line` of the output is a [`CommandStatus::summary`], and if
+    /// so whether the command failed
+    pub fn parse_summary(line: &str) -> Option<bool> {
+        let (mark, rest) = line.split_once(' ')?;
+        let failed = match mark {
+            "✓" => false,
+            "✗" => true,
+            _ => return None,
+        };
+        let (_, duration) = rest.split_once(" in ")?;
+        duration.strip_suffix('s')?.parse::<f64>().ok()?;
+        Some(failed)
+    }
+}
+
+/// Which entries [`TerminalExecutor::get_command_history`] returns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// Text the command must contain
+    pub query: Option<String>,
+    /// Only commands that exited with a non-zero code
+    pub failed_only: bool,
+    /// Only commands run at or after this time, in seconds since the Unix
+    /// epoch
+    pub since: Option<u64>,
+    /// Most entries returned
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    /// Whether `entry` passes the filter
+    pub fn matches(&self, entry: &TerminalHistoryEntry) -> bool {
+        self.query
+            .as_deref()
+            .map_or(true, |query| entry.command.contains(query))
+            && (!self.failed_only || entry.failed())
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+    }
+}
+
+/// Command line split into words
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParsedCommand {
+    /// Leading `NAME=value` words
+    assignments: Vec<(String, String)>,
+    /// `None` when the command only assigns variables
+    program: Option<String>,
+    args: Vec<String>,
+}
+
 /// Terminal command executor with async support and safety features
 #[derive(Debug)]
 pub struct TerminalExecutor {
     /// Buffered output lines (stdout + stderr combined)
     output_buffer: Vec<String>,
 
-    /// Current working directory for command execution
-    current_dir: PathBuf,
+    /// Working directory and variables for command execution
+    shell: ShellState,
 
     /// Command execution timeout
     timeout_duration: Duration,
 
     /// Command history
-    command_history: Vec<String>,
+    command_history: InputHistory,
+
+    /// When each command in the history last ran and how it exited, in the
+    /// same order
+    history_entries: Vec<TerminalHistoryEntry>,
 
-    /// Maximum history size
-    max_history: usize,
+    /// How the last command run ended
+    last_status: Option<CommandStatus>,
 }
 
 impl TerminalExecutor {
@@ -89,10 +252,11 @@ impl TerminalExecutor {
     pub fn new(working_dir: PathBuf) -> Self {
         Self {
             output_buffer: Vec::with_capacity(1024),
-            current_dir: working_dir,
+            shell: ShellState::new(working_dir),
             timeout_duration: DEFAULT_TIMEOUT,
-            command_history: Vec::with_capacity(100),
-            max_history: 1000,
+            command_history: InputHistory::new(),
+            history_entries: Vec::new(),
+            last_status: None,
         }
     }
 
@@ -133,34 +297,65 @@ impl TerminalExecutor {
 
         // Add command to history
         self.add_to_history(command.to_string());
+        let started = Instant::now();
 
         // Display command prompt
         self.append_output(format!("$ {}", command));
 
         // Parse command into program and arguments
-        let (program, args) = match Self::parse_command(command) {
+        let parsed = match self.parse_command(command) {
             Ok(parsed) => parsed,
             Err(e) => {
                 self.append_output(format!("Error: {}", e));
+                self.finish_command(Some(2), started);
                 return Ok(());
             }
         };
 
+        // `NAME=value` on its own sets the variable for the session
+        let Some(program) = parsed.program else {
+            for (name, value) in parsed.assignments {
+                self.shell.set_var(name, value);
+            }
+            self.finish_command(Some(0), started);
+            return Ok(());
+        };
+
         // Handle built-in commands
-        if let Some(result) = self.handle_builtin_command(&program, &args) {
-            return result;
+        if let Some(exit_code) = self.handle_builtin_command(&program, &parsed.args) {
+            self.finish_command(Some(exit_code), started);
+            return Ok(());
         }
 
-        // Execute external command
-        self.execute_external_command(&program, &args).await
+        // Execute external command, then show how it ended
+        let exit_code = self
+            .execute_external_command(&program, &parsed.args, &parsed.assignments)
+            .await?;
+        let status = self.finish_command(exit_code, started);
+        self.append_output(status.summary());
+        Ok(())
     }
 
-    /// Execute an external command
-    async fn execute_external_command(&mut self, program: &str, args: &[String]) -> Result<()> {
+    /// Execute an external command with the session's variables and `env`,
+    /// returning its exit code
+    async fn execute_external_command(
+        &mut self,
+        program: &str,
+        args: &[String],
+        env: &[(String, String)],
+    ) -> Result<Option<i32>> {
         // Build command
         let mut cmd = TokioCommand::new(program);
+        cmd.env("PWD", self.shell.cwd());
+        for (name, value) in self.shell.vars() {
+            match value {
+                Some(value) => cmd.env(name, value),
+                None => cmd.env_remove(name),
+            };
+        }
         cmd.args(args)
-            .current_dir(&self.current_dir)
+            .envs(env.iter().cloned())
+            .current_dir(self.shell.cwd())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .stdin(Stdio::null());
@@ -170,7 +365,7 @@ impl TerminalExecutor {
             Ok(child) => child,
             Err(e) => {
                 self.append_output(format!("Error: Failed to execute '{}': {}", program, e));
-                return Ok(());
+                return Ok(Some(NOT_FOUND_EXIT_CODE));
             }
         };
 
@@ -188,26 +383,28 @@ impl TerminalExecutor {
         let timeout_duration = self.timeout_duration;
         let mut output_lines = Vec::new();
 
+        // Read until both streams end; either may close first
         let output_future = async {
-            loop {
+            let (mut stdout_done, mut stderr_done) = (false, false);
+            while !(stdout_done && stderr_done) {
                 tokio::select! {
-                    line = stdout_lines.next_line() => {
+                    line = stdout_lines.next_line(), if !stdout_done => {
                         match line {
                             Ok(Some(line)) => output_lines.push(line),
-                            Ok(None) => break,
+                            Ok(None) => stdout_done = true,
                             Err(e) => {
                                 output_lines.push(format!("Error reading stdout: {}", e));
-                                break;
+                                stdout_done = true;
                             }
                         }
                     }
-                    line = stderr_lines.next_line() => {
+                    line = stderr_lines.next_line(), if !stderr_done => {
                         match line {
                             Ok(Some(line)) => output_lines.push(format!("stderr: {}", line)),
-                            Ok(None) => break,
+                            Ok(None) => stderr_done = true,
                             Err(e) => {
                                 output_lines.push(format!("Error reading stderr: {}", e));
-                                break;
+                                stderr_done = true;
                             }
                         }
                     }
@@ -217,6 +414,7 @@ impl TerminalExecutor {
         };
 
         // Wait for command with timeout
+        let mut exit_code = None;
         match timeout(timeout_duration, output_future).await {
             Ok(lines) => {
                 // Add all captured output
@@ -227,11 +425,8 @@ impl TerminalExecutor {
                 // Wait for process to exit
                 match child.wait().await {
                     Ok(status) => {
-                        if let Some(code) = status.code() {
-                            if code != 0 {
-                                self.append_output(format!("Exit code: {}", code));
-                            }
-                        } else {
+                        exit_code = status.code();
+                        if exit_code.is_none() {
                             self.append_output("Process terminated by signal".to_string());
                         }
                     }
@@ -250,113 +445,118 @@ impl TerminalExecutor {
             }
         }
 
-        Ok(())
+        Ok(exit_code)
     }
 
-    /// Handle built-in commands (cd, clear, etc.)
-    fn handle_builtin_command(&mut self, program: &str, args: &[String]) -> Option<Result<()>> {
+    /// Handle built-in commands (cd, clear, etc.), returning their exit code
+    fn handle_builtin_command(&mut self, program: &str, args: &[String]) -> Option<i32> {
         match program {
             "cd" => Some(self.builtin_cd(args)),
             "clear" => {
                 self.clear();
-                Some(Ok(()))
+                Some(0)
             }
             "pwd" => {
-                self.append_output(self.current_dir.display().to_string());
-                Some(Ok(()))
+                self.append_output(self.shell.cwd().display().to_string());
+                Some(0)
+            }
+            "export" => Some(self.builtin_export(args)),
+            "unset" => {
+                for name in args {
+                    self.shell.unset_var(name.as_str());
+                }
+                Some(0)
             }
             "history" => {
-                let history: Vec<String> = self.command_history.iter().enumerate()
+                let history: Vec<String> = self
+                    .command_history
+                    .entries()
+                    .iter()
+                    .enumerate()
                     .map(|(idx, cmd)| format!("{:4} {}", idx + 1, cmd))
                     .collect();
                 for line in history {
                     self.append_output(line);
                 }
-                Some(Ok(()))
+                Some(0)
             }
             _ => None,
         }
     }
 
     /// Built-in cd command
-    fn builtin_cd(&mut self, args: &[String]) -> Result<()> {
-        let target = if args.is_empty() {
-            // cd with no args goes to home
-            std::env::var("HOME").unwrap_or_else(|_| "/".to_string())
-        } else {
-            args[0].clone()
-        };
-
-        let new_dir = if target.starts_with('/') {
-            PathBuf::from(&target)
-        } else {
-            self.current_dir.join(&target)
-        };
-
-        match new_dir.canonicalize() {
-            Ok(canonical) => {
-                if canonical.is_dir() {
-                    self.current_dir = canonical;
-                    self.append_output(format!("Changed directory to: {}", self.current_dir.display()));
-                } else {
-                    self.append_output(format!("Error: Not a directory: {}", target));
-                }
+    ///
+    /// `cd` with no args goes to home, `cd -` back to the previous directory.
+    fn builtin_cd(&mut self, args: &[String]) -> i32 {
+        match self.shell.cd(args.first().map(String::as_str)) {
+            Ok(dir) => {
+                self.append_output(format!("Changed directory to: {}", dir.display()));
+                0
             }
             Err(e) => {
-                self.append_output(format!("Error: {}: {}", target, e));
+                self.append_output(format!("Error: {}", e));
+                1
             }
         }
-
-        Ok(())
     }
 
-    /// Parse command string into program and arguments
+    /// Built-in export command
     ///
-    /// Handles quoted arguments and basic shell syntax
-    fn parse_command(command: &str) -> Result<(String, Vec<String>)> {
-        let mut parts = Vec::new();
-        let mut current = String::new();
-        let mut in_quotes = false;
-        let mut escape_next = false;
-
-        for ch in command.chars() {
-            if escape_next {
-                current.push(ch);
-                escape_next = false;
-                continue;
+    /// Sets `NAME=value` arguments for the session; with no args, lists the
+    /// variables set in the session.
+    fn builtin_export(&mut self, args: &[String]) -> i32 {
+        if args.is_empty() {
+            let lines: Vec<String> = self
+                .shell
+                .vars()
+                .iter()
+                .filter_map(|(name, value)| Some(format!("export {}={:?}", name, value.as_ref()?)))
+                .collect();
+            for line in lines {
+                self.append_output(line);
             }
+            return 0;
+        }
 
-            match ch {
-                '\\' => {
-                    escape_next = true;
-                }
-                '"' | '\'' => {
-                    in_quotes = !in_quotes;
-                }
-                ' ' if !in_quotes => {
-                    if !current.is_empty() {
-                        parts.push(current.clone());
-                        current.clear();
-                    }
-                }
-                _ => {
-                    current.push(ch);
+        let mut exit_code = 0;
+        for arg in args {
+            match parse_assignment(arg) {
+                Some((name, value)) => self.shell.set_var(name, value),
+                // Exporting a variable without a value changes nothing, as
+                // every variable is passed to commands
+                None if parse_assignment(&format!("{}=", arg)).is_some() => {}
+                None => {
+                    self.append_output(format!("Error: export: not a valid identifier: {}", arg));
+                    exit_code = 1;
                 }
             }
         }
+        exit_code
+    }
 
-        if !current.is_empty() {
-            parts.push(current);
-        }
-
-        if parts.is_empty() {
-            anyhow::bail!("Empty command");
+    /// Parse command string into variable assignments, program and
+    /// arguments
+    ///
+    /// Handles quoting and `~`/`$VAR` expansion like a shell
+    fn parse_command(&self, command: &str) -> Result<ParsedCommand> {
+        let words = self
+            .shell
+            .split_words(command)
+            .map_err(anyhow::Error::msg)?;
+        let mut words = words.into_iter().peekable();
+
+        // Leading `NAME=value` words set variables for the command only
+        let mut assignments = Vec::new();
+        while let Some((name, value)) = words.peek().and_then(|word| parse_assignment(word)) {
+            assignments.push((name.to_string(), value.to_string()));
+            words.next();
         }
 
-        let program = parts[0].clone();
-        let args = parts[1..].to_vec();
-
-        Ok((program, args))
+        Ok(ParsedCommand {
+            assignments,
+            program: words.next(),
+            args: words.collect(),
+        })
     }
 
     /// Append output line to buffer
@@ -372,20 +572,37 @@ impl TerminalExecutor {
 
     /// Add command to history
     fn add_to_history(&mut self, command: String) {
-        // Don't add duplicate of last command
-        if let Some(last) = self.command_history.last() {
-            if last == &command {
-                return;
+        if command.trim().is_empty() {
+            return;
+        }
+        let entry = TerminalHistoryEntry::new(command.as_str());
+        if self.command_history.last() == Some(command.as_str()) {
+            // A repeat of the last command only updates when it ran
+            if let Some(last) = self.history_entries.last_mut() {
+                *last = entry;
+            }
+        } else {
+            self.history_entries.push(entry);
+            if self.history_entries.len() > MAX_HISTORY {
+                self.history_entries.remove(0);
             }
         }
-
         self.command_history.push(command);
+    }
 
-        // Trim history if too large
-        if self.command_history.len() > self.max_history {
-            let drain_count = self.command_history.len() - self.max_history;
-            self.command_history.drain(0..drain_count);
+    /// Record how the command last added to the history, run at `started`,
+    /// ended
+    fn finish_command(&mut self, exit_code: Option<i32>, started: Instant) -> CommandStatus {
+        let status = CommandStatus {
+            exit_code,
+            duration: started.elapsed(),
+        };
+        if let Some(last) = self.history_entries.last_mut() {
+            last.exit_code = exit_code;
+            last.duration_ms = Some(status.duration.as_millis() as u64);
         }
+        self.last_status = Some(status);
+        status
     }
 
     /// Get output buffer
@@ -408,12 +625,17 @@ impl TerminalExecutor {
 
     /// Get current working directory
     pub fn current_dir(&self) -> &PathBuf {
-        &self.current_dir
+        self.shell.cwd()
     }
 
     /// Set working directory
     pub fn set_current_dir(&mut self, dir: PathBuf) {
-        self.current_dir = dir;
+        self.shell.set_cwd(dir);
+    }
+
+    /// Working directory and variables of the session
+    pub fn shell(&self) -> &ShellState {
+        &self.shell
     }
 
     /// Set command timeout
@@ -428,13 +650,54 @@ impl TerminalExecutor {
 
     /// Get command history
     pub fn history(&self) -> &[String] {
-        &self.command_history
+        self.command_history.entries()
     }
 
     /// Get history entry by index (most recent = 0)
     pub fn history_entry(&self, idx: usize) -> Option<&str> {
-        let reverse_idx = self.command_history.len().saturating_sub(idx + 1);
-        self.command_history.get(reverse_idx).map(|s| s.as_str())
+        self.command_history.recent(idx)
+    }
+
+    /// How the last command run ended, `None` before any has run
+    pub fn last_status(&self) -> Option<CommandStatus> {
+        self.last_status
+    }
+
+    /// Get command history with when each command ran and how it exited,
+    /// oldest first
+    pub fn history_entries(&self) -> &[TerminalHistoryEntry] {
+        &self.history_entries
+    }
+
+    /// History entries matching `filter`, most recent first
+    pub fn get_command_history(&self, filter: &HistoryFilter) -> Vec<&TerminalHistoryEntry> {
+        self.history_entries
+            .iter()
+            .rev()
+            .filter(|entry| filter.matches(entry))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Replace the command history, e.g. with one saved for the workspace
+    ///
+    /// Blank commands and consecutive repeats are dropped.
+    pub fn set_history(&mut self, mut entries: Vec<TerminalHistoryEntry>) {
+        entries.retain(|entry| !entry.command.trim().is_empty());
+        entries.dedup_by(|later, earlier| {
+            let repeat = later.command == earlier.command;
+            // The entry kept is the latest run
+            if repeat {
+                std::mem::swap(later, earlier);
+            }
+            repeat
+        });
+        let excess = entries.len().saturating_sub(MAX_HISTORY);
+        entries.drain(..excess);
+
+        let commands = entries.iter().map(|entry| entry.command.clone()).collect();
+        self.command_history = InputHistory::from_entries(commands);
+        self.history_entries = entries;
     }
 }
 
@@ -452,16 +715,84 @@ mod tests {
 
     #[test]
     fn test_parse_command() {
-        let (prog, args) = TerminalExecutor::parse_command("ls -la /tmp").unwrap();
-        assert_eq!(prog, "ls");
-        assert_eq!(args, vec!["-la", "/tmp"]);
+        let executor = TerminalExecutor::new(PathBuf::from("/tmp"));
+        let parsed = executor.parse_command("ls -la /tmp").unwrap();
+        assert_eq!(parsed.program.unwrap(), "ls");
+        assert_eq!(parsed.args, vec!["-la", "/tmp"]);
     }
 
     #[test]
     fn test_parse_command_with_quotes() {
-        let (prog, args) = TerminalExecutor::parse_command(r#"echo "hello world""#).unwrap();
-        assert_eq!(prog, "echo");
-        assert_eq!(args, vec!["hello world"]);
+        let executor = TerminalExecutor::new(PathBuf::from("/tmp"));
+        let parsed = executor.parse_command(r#"echo "hello world""#).unwrap();
+        assert_eq!(parsed.program.unwrap(), "echo");
+        assert_eq!(parsed.args, vec!["hello world"]);
+    }
+
+    #[test]
+    fn test_parse_command_with_assignments() {
+        let executor = TerminalExecutor::new(PathBuf::from("/tmp"));
+        let parsed = executor.parse_command("A=1 B='x y' env").unwrap();
+        assert_eq!(
+            parsed.assignments,
+            [
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "x y".to_string())
+            ]
+        );
+        assert_eq!(parsed.program.unwrap(), "env");
+
+        let parsed = executor.parse_command("A=1").unwrap();
+        assert_eq!(parsed.program, None);
+        assert!(executor.parse_command("echo \"open").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_variables() {
+        let mut executor = TerminalExecutor::new(PathBuf::from("/tmp"));
+        executor.execute("export GREETING=hello").await.unwrap();
+        executor.execute("TARGET=world").await.unwrap();
+        executor.execute("echo $GREETING ${TARGET}!").await.unwrap();
+        let output = executor.get_output();
+        assert!(output.iter().any(|line| line == "hello world!"));
+
+        // Variables reach the commands run, with ones set for a command
+        executor.execute("ONCE=1 env").await.unwrap();
+        let output = executor.get_output();
+        assert!(output.iter().any(|line| line == "GREETING=hello"));
+        assert!(output.iter().any(|line| line == "ONCE=1"));
+        assert_eq!(executor.shell().var("ONCE"), None);
+
+        executor.execute("unset GREETING").await.unwrap();
+        executor.clear();
+        executor.execute("env").await.unwrap();
+        let output = executor.get_output();
+        assert!(!output.iter().any(|line| line.starts_with("GREETING=")));
+
+        executor.execute("export").await.unwrap();
+        let output = executor.get_output();
+        assert!(output.iter().any(|line| line == "export TARGET=\"world\""));
+        executor.execute("export 1X=2").await.unwrap();
+        assert!(executor.last_status().unwrap().failed());
+    }
+
+    #[tokio::test]
+    async fn test_builtin_cd_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        let mut executor = TerminalExecutor::new(root.clone());
+
+        executor.execute("cd sub").await.unwrap();
+        assert_eq!(executor.current_dir(), &root.join("sub"));
+        executor.execute("cd -").await.unwrap();
+        assert_eq!(executor.current_dir(), &root);
+        executor.execute("cd $OLDPWD").await.unwrap();
+        assert_eq!(executor.current_dir(), &root.join("sub"));
+
+        executor.execute("cd missing").await.unwrap();
+        assert!(executor.last_status().unwrap().failed());
+        assert_eq!(executor.current_dir(), &root.join("sub"));
     }
 
     #[test]
@@ -487,6 +818,61 @@ mod tests {
         assert_eq!(executor.history_entry(1).unwrap(), "cmd1");
     }
 
+    #[tokio::test]
+    async fn test_history_records_exit_codes() {
+        let mut executor = TerminalExecutor::new(env::temp_dir());
+        executor.execute("pwd").await.unwrap();
+        executor.execute("cd /nonexistent_dir_12345").await.unwrap();
+        executor.execute("nonexistent_command_12345").await.unwrap();
+        executor.execute("nonexistent_command_12345").await.unwrap();
+
+        let entries = executor.history_entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].exit_code, Some(0));
+        assert_eq!(entries[1].exit_code, Some(1));
+        assert_eq!(entries[2].exit_code, Some(NOT_FOUND_EXIT_CODE));
+        assert!(entries[2].timestamp > 0);
+
+        let failed = executor.get_command_history(&HistoryFilter {
+            failed_only: true,
+            ..HistoryFilter::default()
+        });
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].command, "nonexistent_command_12345");
+
+        let filter = HistoryFilter {
+            query: Some("cd".to_string()),
+            limit: Some(1),
+            ..HistoryFilter::default()
+        };
+        assert_eq!(executor.get_command_history(&filter)[0].command, "cd /nonexistent_dir_12345");
+        let filter = HistoryFilter {
+            since: Some(u64::MAX),
+            ..HistoryFilter::default()
+        };
+        assert!(executor.get_command_history(&filter).is_empty());
+    }
+
+    #[test]
+    fn test_set_history_drops_repeats() {
+        let mut executor = TerminalExecutor::new(PathBuf::from("/tmp"));
+        let entry = |command: &str, timestamp| TerminalHistoryEntry {
+            command: command.to_string(),
+            timestamp,
+            exit_code: Some(0),
+            duration_ms: None,
+        };
+        executor.set_history(vec![
+            entry("ls", 1),
+            entry("ls", 2),
+            entry(" ", 3),
+            entry("pwd", 4),
+        ]);
+
+        assert_eq!(executor.history(), ["ls", "pwd"]);
+        assert_eq!(executor.history_entries()[0].timestamp, 2);
+    }
+
     #[tokio::test]
     async fn test_execute_echo() {
         let mut executor = TerminalExecutor::new(PathBuf::from("/tmp"));
@@ -516,6 +902,49 @@ mod tests {
         assert!(executor.get_output().is_empty());
     }
 
+    #[test]
+    fn test_command_status_summary() {
+        let status = CommandStatus {
+            exit_code: Some(1),
+            duration: Duration::from_millis(2300),
+        };
+        assert!(status.failed());
+        assert_eq!(status.summary(), "✗ 1 in 2.3s");
+        assert_eq!(CommandStatus::parse_summary(&status.summary()), Some(true));
+
+        let status = CommandStatus {
+            exit_code: None,
+            duration: Duration::from_secs(30),
+        };
+        assert_eq!(status.summary(), "✗ killed in 30.0s");
+
+        let status = CommandStatus {
+            exit_code: Some(0),
+            duration: Duration::from_millis(40),
+        };
+        assert!(!status.failed());
+        assert_eq!(CommandStatus::parse_summary(&status.summary()), Some(false));
+        assert_eq!(CommandStatus::parse_summary("✓ done"), None);
+        assert_eq!(CommandStatus::parse_summary("$ ls"), None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_shows_status() {
+        let mut executor = TerminalExecutor::new(PathBuf::from("/tmp"));
+        assert_eq!(executor.last_status(), None);
+
+        executor.execute("nonexistent_command_12345").await.unwrap();
+        let status = executor.last_status().unwrap();
+        assert_eq!(status.exit_code, Some(NOT_FOUND_EXIT_CODE));
+        assert_eq!(executor.get_output().last(), Some(&status.summary()));
+        assert!(executor.history_entries()[0].duration_ms.is_some());
+
+        // Built-in commands are recorded without a status line
+        executor.execute("pwd").await.unwrap();
+        assert!(!executor.last_status().unwrap().failed());
+        assert_eq!(executor.get_output().last(), Some(&"/tmp".to_string()));
+    }
+
     #[tokio::test]
     async fn test_invalid_command() {
         let mut executor = TerminalExecutor::new(PathBuf::from("/tmp"));
@@ -2,6 +2,8 @@
 //!
 //! Provides color schemes and styling for the editor UI.
 
+use crate::themes::ThemeVariant;
+use ait42_git::FileStatus;
 use ratatui::style::{Color, Modifier, Style};
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +28,21 @@ pub struct Theme {
     pub string: Style,
     pub number: Style,
     pub function: Style,
+    pub type_name: Style,
+    /// Bracket colors, by nesting depth
+    pub rainbow: Vec<Color>,
+    /// Background of the syntactic scope under the cursor
+    pub scope_background: Color,
+    /// Gutter marks for lines added, modified and deleted since HEAD
+    pub diff_added: Style,
+    pub diff_modified: Style,
+    pub diff_deleted: Style,
+    /// Git status badges of files in the sidebar and tab titles
+    pub git_added: Style,
+    pub git_modified: Style,
+    pub git_deleted: Style,
+    pub git_untracked: Style,
+    pub git_conflicted: Style,
 }
 
 impl Theme {
@@ -75,6 +92,26 @@ impl Theme {
             string: Style::default().fg(Color::Rgb(230, 219, 116)),
             number: Style::default().fg(Color::Rgb(174, 129, 255)),
             function: Style::default().fg(Color::Rgb(166, 226, 46)),
+            type_name: Style::default().fg(Color::Rgb(102, 217, 239)),
+            rainbow: vec![
+                Color::Rgb(230, 219, 116),
+                Color::Rgb(249, 38, 114),
+                Color::Rgb(102, 217, 239),
+                Color::Rgb(166, 226, 46),
+                Color::Rgb(253, 151, 31),
+                Color::Rgb(174, 129, 255),
+            ],
+            scope_background: Color::Rgb(50, 51, 44),
+            diff_added: Style::default().fg(Color::Rgb(166, 226, 46)),
+            diff_modified: Style::default().fg(Color::Rgb(102, 217, 239)),
+            diff_deleted: Style::default().fg(Color::Rgb(249, 38, 114)),
+            git_added: Style::default().fg(Color::Rgb(166, 226, 46)),
+            git_modified: Style::default().fg(Color::Rgb(230, 219, 116)),
+            git_deleted: Style::default().fg(Color::Rgb(249, 38, 114)),
+            git_untracked: Style::default().fg(Color::Rgb(102, 217, 239)),
+            git_conflicted: Style::default()
+                .fg(Color::Rgb(253, 151, 31))
+                .add_modifier(Modifier::BOLD),
         }
     }
 
@@ -124,6 +161,26 @@ impl Theme {
             string: Style::default().fg(Color::Rgb(42, 161, 152)),
             number: Style::default().fg(Color::Rgb(108, 113, 196)),
             function: Style::default().fg(Color::Rgb(38, 139, 210)),
+            type_name: Style::default().fg(Color::Rgb(181, 137, 0)),
+            rainbow: vec![
+                Color::Rgb(181, 137, 0),
+                Color::Rgb(211, 54, 130),
+                Color::Rgb(38, 139, 210),
+                Color::Rgb(42, 161, 152),
+                Color::Rgb(133, 153, 0),
+                Color::Rgb(203, 75, 22),
+            ],
+            scope_background: Color::Rgb(3, 49, 61),
+            diff_added: Style::default().fg(Color::Rgb(133, 153, 0)),
+            diff_modified: Style::default().fg(Color::Rgb(38, 139, 210)),
+            diff_deleted: Style::default().fg(Color::Rgb(220, 50, 47)),
+            git_added: Style::default().fg(Color::Rgb(133, 153, 0)),
+            git_modified: Style::default().fg(Color::Rgb(181, 137, 0)),
+            git_deleted: Style::default().fg(Color::Rgb(220, 50, 47)),
+            git_untracked: Style::default().fg(Color::Rgb(42, 161, 152)),
+            git_conflicted: Style::default()
+                .fg(Color::Rgb(203, 75, 22))
+                .add_modifier(Modifier::BOLD),
         }
     }
 
@@ -173,6 +230,26 @@ impl Theme {
             string: Style::default().fg(Color::Rgb(184, 187, 38)),
             number: Style::default().fg(Color::Rgb(211, 134, 155)),
             function: Style::default().fg(Color::Rgb(142, 192, 124)),
+            type_name: Style::default().fg(Color::Rgb(250, 189, 47)),
+            rainbow: vec![
+                Color::Rgb(250, 189, 47),
+                Color::Rgb(211, 134, 155),
+                Color::Rgb(131, 165, 152),
+                Color::Rgb(142, 192, 124),
+                Color::Rgb(254, 128, 25),
+                Color::Rgb(251, 73, 52),
+            ],
+            scope_background: Color::Rgb(50, 48, 47),
+            diff_added: Style::default().fg(Color::Rgb(184, 187, 38)),
+            diff_modified: Style::default().fg(Color::Rgb(131, 165, 152)),
+            diff_deleted: Style::default().fg(Color::Rgb(251, 73, 52)),
+            git_added: Style::default().fg(Color::Rgb(184, 187, 38)),
+            git_modified: Style::default().fg(Color::Rgb(250, 189, 47)),
+            git_deleted: Style::default().fg(Color::Rgb(251, 73, 52)),
+            git_untracked: Style::default().fg(Color::Rgb(131, 165, 152)),
+            git_conflicted: Style::default()
+                .fg(Color::Rgb(254, 128, 25))
+                .add_modifier(Modifier::BOLD),
         }
     }
 
@@ -190,6 +267,25 @@ impl Theme {
     pub fn available_themes() -> Vec<&'static str> {
         vec!["monokai", "solarized-dark", "gruvbox"]
     }
+
+    /// Style of a bracket nested `depth` pairs deep
+    pub fn delimiter(&self, depth: usize) -> Style {
+        match self.rainbow.len() {
+            0 => Style::default(),
+            len => Style::default().fg(self.rainbow[depth % len]),
+        }
+    }
+
+    /// Style of the badge of a file with git `status`
+    pub fn git_status(&self, status: FileStatus) -> Style {
+        match status {
+            FileStatus::Added | FileStatus::Renamed => self.git_added,
+            FileStatus::Modified => self.git_modified,
+            FileStatus::Deleted => self.git_deleted,
+            FileStatus::Untracked => self.git_untracked,
+            FileStatus::Conflicted => self.git_conflicted,
+        }
+    }
 }
 
 impl Default for Theme {
@@ -243,6 +339,66 @@ impl Theme {
 
         theme
     }
+
+    /// Create widget styles from one of the built-in color schemes
+    pub fn from_variant(variant: ThemeVariant) -> Self {
+        let colors = variant.get_theme();
+        let on_accent = |bg: Color| {
+            Style::default()
+                .bg(bg)
+                .fg(colors.background())
+                .add_modifier(Modifier::BOLD)
+        };
+
+        Self {
+            name: variant.name().to_string(),
+            background: colors.background(),
+            foreground: colors.foreground(),
+            cursor: colors.terminal_cursor(),
+            selection: Style::default()
+                .bg(colors.terminal_selection())
+                .fg(colors.foreground()),
+            line_number: Style::default().fg(colors.foreground_dimmer()),
+            line_number_active: Style::default()
+                .fg(colors.foreground())
+                .add_modifier(Modifier::BOLD),
+            statusline_normal: on_accent(colors.accent_primary()),
+            statusline_insert: on_accent(colors.success()),
+            statusline_visual: on_accent(colors.warning()),
+            statusline_command: on_accent(colors.info()),
+            border: Style::default().fg(colors.border()),
+            search_match: on_accent(colors.warning()),
+            comment: Style::default()
+                .fg(colors.syntax_comment())
+                .add_modifier(Modifier::ITALIC),
+            keyword: Style::default()
+                .fg(colors.syntax_keyword())
+                .add_modifier(Modifier::BOLD),
+            string: Style::default().fg(colors.syntax_string()),
+            number: Style::default().fg(colors.syntax_number()),
+            function: Style::default().fg(colors.syntax_function()),
+            type_name: Style::default().fg(colors.syntax_type()),
+            rainbow: vec![
+                colors.syntax_function(),
+                colors.syntax_keyword(),
+                colors.syntax_type(),
+                colors.syntax_string(),
+                colors.syntax_number(),
+                colors.syntax_constant(),
+            ],
+            scope_background: colors.background_light(),
+            diff_added: Style::default().fg(colors.success()),
+            diff_modified: Style::default().fg(colors.info()),
+            diff_deleted: Style::default().fg(colors.error()),
+            git_added: Style::default().fg(colors.success()),
+            git_modified: Style::default().fg(colors.warning()),
+            git_deleted: Style::default().fg(colors.error()),
+            git_untracked: Style::default().fg(colors.info()),
+            git_conflicted: Style::default()
+                .fg(colors.accent_primary())
+                .add_modifier(Modifier::BOLD),
+        }
+    }
 }
 
 /// Parse color from hex string (#RRGGBB)
@@ -303,6 +459,37 @@ mod tests {
         assert_eq!(theme.background, Color::Rgb(0, 0, 0));
     }
 
+    #[test]
+    fn test_theme_from_variant() {
+        for variant in ThemeVariant::all() {
+            let theme = Theme::from_variant(*variant);
+            let colors = variant.get_theme();
+            assert_eq!(theme.name, variant.name());
+            assert_eq!(theme.background, colors.background());
+            assert_eq!(theme.keyword.fg, Some(colors.syntax_keyword()));
+            assert_eq!(theme.type_name.fg, Some(colors.syntax_type()));
+            assert_ne!(theme.statusline_normal, theme.statusline_insert);
+        }
+    }
+
+    #[test]
+    fn test_delimiter_colors_cycle() {
+        let mut theme = Theme::gruvbox();
+        assert_eq!(theme.delimiter(0), theme.delimiter(theme.rainbow.len()));
+        assert_ne!(theme.delimiter(0), theme.delimiter(1));
+
+        theme.rainbow.clear();
+        assert_eq!(theme.delimiter(3), Style::default());
+    }
+
+    #[test]
+    fn test_git_status_styles() {
+        let theme = Theme::default();
+        assert_eq!(theme.git_status(FileStatus::Renamed), theme.git_added);
+        assert_eq!(theme.git_status(FileStatus::Untracked), theme.git_untracked);
+        assert_ne!(theme.git_modified, theme.git_conflicted);
+    }
+
     #[test]
     fn test_available_themes() {
         let themes = Theme::available_themes();
@@ -4,17 +4,64 @@
 
 use crate::{
     event::{EditorEvent, EventLoop},
-    keybinds::{EditorCommand, KeyBinding, KeyMap, Mode},
-    layout::LayoutConfig,
-    renderer::Renderer,
+    file_metadata::MetadataCache,
+    input_history::{InputHistory, ReverseSearch, SavedHistory},
+    keybinds::{EditorCommand, KeyBinding, KeyBindingEntry, KeyMap, Mode, PendingPrefix},
+    layout::{EditorLayout, LayoutConfig, Panel, PanelSizes, RESIZE_STEP},
+    macros::Macros,
+    preload::Preloader,
+    registers::Registers,
+    renderer::{Overlay, Panels, Renderer},
+    session::{SavedMarks, Session, TabSession},
+    startup::{StartupProfile, FIRST_PAINT},
+    syntax::{StructureHighlight, SyntaxHighlighter},
     theme::Theme,
-    widgets::editor::ViewState,
+    terminal_executor::TerminalExecutor,
+    widgets::{
+        command_palette::{best_match, editor_commands},
+        editor::ViewState, AgentLauncherState, BlameCache, FileEntry, FileTree, HunkPanelState,
+        LineChange, LineChangeCache, LogGraphState, SearchPromptState, StashPanelState,
+        TerminalCopyState, TerminalPanel, WorkspaceSearchState,
+    },
 };
-use ait42_core::{Buffer, Cursor, Editor, EditorConfig};
+use ait42_ait42::{SessionStatus, TmuxManager, TmuxSession};
+use ait42_config::{ConfigLoader, FocusConfig, PreloadConfig, SyntaxConfig};
+use ait42_core::{
+    marks,
+    structural::{self, Direction},
+    Autosave, Buffer, Command, CommandHistory, ConvertLineEndingsCommand, Cursor, CursorPosition,
+    DeleteCommand, Editor, EditorConfig, Encoding, GlobalMarks, InsertCommand, Language, Mark,
+    RecoveryFile, ReplaceAllCommand, SearchQuery, Selection, SelectionRange, StructuralEdit,
+    SyntaxTree,
+};
+use ait42_fs::{FileEvent, FileWatcher, Walk, Walker};
+use ait42_git::BlameLine;
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
-use std::{path::PathBuf, time::Duration};
-use tracing::{debug, error, info};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::backend::{Backend, CrosstermBackend};
+use std::{
+    collections::HashMap,
+    io::Stdout,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, info, warn};
+
+/// Trailing output lines of an agent run kept in the results panel
+const AGENT_RESULT_LINES: usize = 200;
+
+/// Lines kept in the results panel, oldest dropped first
+const MAX_AGENT_RESULTS: usize = 2_000;
+
+/// How often running agents are checked for completion
+const AGENT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Deepest level the sidebar expands to at once
+const SIDEBAR_EXPAND_DEPTH: usize = 8;
+
+/// Entries added to the sidebar by one expansion, at most
+const SIDEBAR_EXPAND_LIMIT: usize = 5_000;
 
 /// Tab information
 #[derive(Debug, Clone)]
@@ -27,6 +74,10 @@ pub struct Tab {
     pub buffer: Buffer,
     /// Is modified
     pub is_modified: bool,
+    /// Cursor, kept while another tab is active
+    pub cursor: Cursor,
+    /// Scroll position, kept while another tab is active
+    pub view: ViewState,
 }
 
 impl Tab {
@@ -37,6 +88,8 @@ impl Tab {
             path,
             buffer,
             is_modified: false,
+            cursor: Cursor::default(),
+            view: ViewState::new(),
         }
     }
 }
@@ -65,6 +118,51 @@ pub enum FocusedPanel {
     Sidebar,
     /// Terminal panel
     Terminal,
+    /// Agent results panel
+    Results,
+}
+
+/// Command line open in command mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandLine {
+    /// `:` command line: `w`, `q`, `q!`, `wq`, a line number or a command name
+    Ex,
+    /// Command palette, running the command that best matches the input
+    Palette,
+}
+
+impl CommandLine {
+    /// Prompt shown before the input
+    pub fn prompt(self) -> &'static str {
+        match self {
+            CommandLine::Ex => ":",
+            CommandLine::Palette => ">",
+        }
+    }
+}
+
+/// Command waiting for the register it applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterPrompt {
+    /// `q`: start recording a macro into the register
+    RecordMacro,
+    /// `@`: replay the macro in the register `count` times
+    ReplayMacro { count: usize },
+    /// `"`: paste from the register next
+    Select,
+    /// `m`: set the mark
+    SetMark,
+    /// `` ` ``: jump to the mark
+    JumpToMark,
+}
+
+/// Focus to restore after the editor moved it on its own
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FocusReturn {
+    /// Panel focused before the first automatic change
+    panel: FocusedPanel,
+    /// Panels opened by the automatic changes, closed again on return
+    opened: Vec<FocusedPanel>,
 }
 
 /// Editor state
@@ -75,14 +173,28 @@ pub struct EditorState {
     buffer: Buffer,
     /// Cursor
     cursor: Cursor,
+    /// Visual selection
+    selection: Selection,
     /// View state (scrolling)
     view: ViewState,
+    /// Undo history of the current buffer
+    history: CommandHistory,
+    /// Bumped on every entry into insert mode to delimit undo steps
+    insert_session: u64,
     /// Current mode
     mode: Mode,
     /// Command palette input
     command_input: String,
     /// Show command palette
     show_command_palette: bool,
+    /// Command line open in command mode
+    command_line: CommandLine,
+    /// Commands run from the command palette
+    palette_history: InputHistory,
+    /// Lines entered on the `:` command line
+    ex_history: InputHistory,
+    /// Open search of the command line history (Ctrl+R)
+    history_search: Option<ReverseSearch>,
     /// Running flag
     running: bool,
 
@@ -101,8 +213,75 @@ pub struct EditorState {
     terminal_visible: bool,
     /// Terminal scroll offset
     terminal_scroll: usize,
+    /// Sidebar and terminal panel sizes
+    panel_sizes: PanelSizes,
     /// Currently focused panel
     focused_panel: FocusedPanel,
+    /// Agent results visibility
+    results_visible: bool,
+    /// Output of finished agent runs
+    agent_results: Vec<String>,
+    /// Agent results scroll offset
+    results_scroll: usize,
+    /// Copy mode over the terminal output or agent results
+    terminal_copy: Option<TerminalCopyState>,
+    /// Whether copy mode waits for the terminal output to open over
+    terminal_copy_requested: bool,
+    /// Whether copy mode is over the agent results
+    copying_results: bool,
+    /// Which events move focus on their own
+    focus_config: FocusConfig,
+    /// Focus to restore after automatic focus changes
+    focus_return: Option<FocusReturn>,
+    /// Commands waiting to run in the terminal panel
+    pending_terminal_commands: Vec<String>,
+
+    /// Prefix key waiting for its continuation
+    pending_prefix: Option<PendingPrefix>,
+    /// Which-key popup visibility (set once the prefix times out)
+    which_key_visible: bool,
+    /// Count typed before a command in normal mode
+    pending_count: Option<usize>,
+    /// Recorded macros
+    macros: Macros,
+    /// Command waiting for its register
+    register_prompt: Option<RegisterPrompt>,
+    /// Yanked text
+    registers: Registers,
+    /// Register selected for the next paste
+    pending_register: Option<char>,
+    /// Uppercase marks, in any file
+    global_marks: GlobalMarks,
+    /// Text waiting to be put on the system clipboard
+    clipboard: Option<String>,
+    /// Keybinding cheat-sheet visibility
+    show_cheat_sheet: bool,
+    /// Cheat-sheet search query
+    cheat_sheet_query: String,
+    /// Open stash panel
+    stash_panel: Option<StashPanelState>,
+    /// Open hunk staging panel
+    hunk_panel: Option<HunkPanelState>,
+    /// Open history panel
+    log_panel: Option<LogGraphState>,
+    /// Open agent launcher
+    agent_launcher: Option<AgentLauncherState>,
+    /// Open search or replace prompt
+    search_prompt: Option<SearchPromptState>,
+    /// Last accepted search, repeated by `n` and `N`
+    last_search: Option<SearchQuery>,
+    /// Open workspace search panel
+    workspace_search: Option<WorkspaceSearchState>,
+    /// Blame gutter visibility
+    blame_visible: bool,
+    /// Blame per buffer revision
+    blame_cache: BlameCache,
+    /// Lines changed since HEAD, per buffer
+    line_changes: LineChangeCache,
+    /// Files read ahead of being opened
+    preloader: Preloader,
+    /// Metadata and git status of sidebar entries
+    file_metadata: MetadataCache,
 }
 
 impl EditorState {
@@ -124,10 +303,17 @@ impl EditorState {
             editor,
             buffer,
             cursor,
+            selection: Selection::new(),
             view,
+            history: CommandHistory::new(),
+            insert_session: 0,
             mode: Mode::Normal,
             command_input: String::new(),
             show_command_palette: false,
+            command_line: CommandLine::Ex,
+            palette_history: InputHistory::new(),
+            ex_history: InputHistory::new(),
+            history_search: None,
             running: true,
             // Phase 10b: Initialize multi-panel state
             tabs: vec![initial_tab],
@@ -137,25 +323,128 @@ impl EditorState {
             sidebar_selected: 0,
             terminal_visible: false,
             terminal_scroll: 0,
+            panel_sizes: PanelSizes::default(),
             focused_panel: FocusedPanel::Editor,
+            results_visible: false,
+            agent_results: Vec::new(),
+            results_scroll: 0,
+            terminal_copy: None,
+            terminal_copy_requested: false,
+            copying_results: false,
+            focus_config: FocusConfig::default(),
+            focus_return: None,
+            pending_terminal_commands: Vec::new(),
+            pending_prefix: None,
+            which_key_visible: false,
+            pending_count: None,
+            macros: Macros::new(),
+            register_prompt: None,
+            global_marks: GlobalMarks::new(),
+            registers: Registers::new(),
+            pending_register: None,
+            clipboard: None,
+            show_cheat_sheet: false,
+            cheat_sheet_query: String::new(),
+            stash_panel: None,
+            hunk_panel: None,
+            log_panel: None,
+            agent_launcher: None,
+            search_prompt: None,
+            last_search: None,
+            workspace_search: None,
+            blame_visible: false,
+            blame_cache: BlameCache::new(),
+            line_changes: LineChangeCache::new(),
+            preloader: Preloader::new(PreloadConfig::default()),
+            file_metadata: MetadataCache::new(),
         })
     }
 
     /// Load file into buffer
     pub fn load_file(&mut self, path: std::path::PathBuf) -> Result<()> {
-        self.buffer = Buffer::from_file(&path)?;
+        self.buffer = self.open_buffer(&path)?;
         self.cursor = Cursor::default();
+        self.selection.clear();
         self.view = ViewState::new();
+        self.history.clear();
+        self.preload_next();
         Ok(())
     }
 
+    /// Buffer of the file at `path`, read ahead if it was preloaded
+    fn open_buffer(&mut self, path: &Path) -> Result<Buffer> {
+        self.preloader.receive();
+        match self.preloader.take(path) {
+            Some(buffer) => {
+                debug!("Opened preloaded file: {:?}", path);
+                Ok(buffer)
+            }
+            None => Ok(Buffer::from_file(path)?),
+        }
+    }
+
+    /// Start reading the files likely opened after the current one
+    fn preload_next(&mut self) {
+        let Some(path) = self.buffer.path().map(Path::to_path_buf) else {
+            return;
+        };
+        let open: Vec<PathBuf> = self
+            .tabs
+            .iter()
+            .filter_map(|tab| tab.path.clone())
+            .collect();
+        self.preloader.prefetch(&path, self.buffer.to_string(), &open);
+    }
+
+    /// Set how many files are read ahead and how much memory they may take
+    pub fn set_preload_config(&mut self, config: PreloadConfig) {
+        self.preloader = Preloader::new(config);
+    }
+
+    /// Move files read ahead in the background into the pool
+    pub fn receive_preloaded(&mut self) {
+        self.preloader.receive();
+    }
+
+    /// Run a command given by the user, recording it into the macro being
+    /// recorded
+    ///
+    /// A count or register typed before it applies to this command only.
+    pub fn run_command(&mut self, command: &EditorCommand) -> Result<()> {
+        self.macros.record(command);
+        let result = self.execute_command(command);
+        self.pending_count = None;
+        self.pending_register = None;
+        result
+    }
+
     /// Handle editor command
     fn execute_command(&mut self, command: &EditorCommand) -> Result<()> {
         use EditorCommand::*;
 
+        // Cursor jumps end the current run of coalesced edits
+        if matches!(
+            command,
+            MoveLeft
+                | MoveRight
+                | MoveUp
+                | MoveDown
+                | MoveLineStart
+                | MoveLineEnd
+                | MoveWordForward
+                | MoveWordBackward
+                | SearchNext
+                | SearchPrevious
+        ) {
+            self.history.break_coalescing();
+        }
+
         match command {
             // Mode transitions
             EnterInsertMode => {
+                if self.mode != Mode::Insert {
+                    self.insert_session += 1;
+                }
                 self.mode = Mode::Insert;
                 debug!("Entered insert mode");
             }
@@ -164,14 +453,17 @@ impl EditorState {
                 debug!("Entered visual mode");
             }
             EnterCommandMode => {
-                self.mode = Mode::Command;
-                self.show_command_palette = true;
-                self.command_input.clear();
+                self.open_command_line(CommandLine::Ex);
                 debug!("Entered command mode");
             }
+            EnterNormalMode if self.mode == Mode::Command && self.history_search.is_some() => {
+                // Esc ends a history search, keeping the command line open
+                self.history_search = None;
+            }
             EnterNormalMode => {
                 self.mode = Mode::Normal;
                 self.show_command_palette = false;
+                self.selection.clear();
                 debug!("Entered normal mode");
             }
 
@@ -190,12 +482,102 @@ impl EditorState {
             InsertNewline => self.insert_newline(),
             DeleteChar => self.delete_char(),
             Backspace => self.backspace(),
+            Undo => self.undo(),
+            Redo => self.redo(),
+
+            // Structural editing
+            MoveItemUp => self.structural_edit(|tree, source, range| {
+                structural::move_item(tree, source, range.start, Direction::Backward)
+            }),
+            MoveItemDown => self.structural_edit(|tree, source, range| {
+                structural::move_item(tree, source, range.start, Direction::Forward)
+            }),
+            SwapArgumentBackward => self.structural_edit(|tree, source, range| {
+                structural::swap_argument(tree, source, range.start, Direction::Backward)
+            }),
+            SwapArgumentForward => self.structural_edit(|tree, source, range| {
+                structural::swap_argument(tree, source, range.start, Direction::Forward)
+            }),
+            RaiseNode => self.structural_edit(structural::raise),
+            SplitJoinList => self.structural_edit(|tree, source, range| {
+                structural::toggle_list_split(tree, source, range.start)
+            }),
+            JoinLines => self.join_lines(),
+            SelectEnclosingNode => self.select_enclosing_node(),
+
+            // Folding
+            Fold => {
+                let line = self.cursor.position(&self.buffer).line;
+                if !self.view.folds.fold_at(&self.buffer.to_string(), line) {
+                    debug!("No fold at line {}", line + 1);
+                }
+                self.leave_folded_lines();
+            }
+            Unfold => {
+                let line = self.cursor.position(&self.buffer).line;
+                self.view.folds.unfold_at(line);
+            }
+            ToggleFold => {
+                let line = self.cursor.position(&self.buffer).line;
+                self.view.folds.toggle_at(&self.buffer.to_string(), line);
+                self.leave_folded_lines();
+            }
+            FoldAll => {
+                self.view.folds.fold_all(&self.buffer.to_string());
+                self.leave_folded_lines();
+            }
+            UnfoldAll => self.view.folds.unfold_all(),
+
+            // Macros
+            RecordMacro => match self.macros.stop_recording() {
+                Some(register) => debug!("Recorded macro @{}", register),
+                None => self.register_prompt = Some(RegisterPrompt::RecordMacro),
+            },
+            ReplayMacro => {
+                let count = self.pending_count.unwrap_or(1);
+                self.register_prompt = Some(RegisterPrompt::ReplayMacro { count });
+            }
+
+            // Marks
+            SetMark => self.register_prompt = Some(RegisterPrompt::SetMark),
+            JumpToMark => self.register_prompt = Some(RegisterPrompt::JumpToMark),
+
+            // Registers
+            SelectRegister => self.register_prompt = Some(RegisterPrompt::Select),
+            Paste => self.paste(self.pending_register),
+            TerminalCopyMode => self.terminal_copy_requested = true,
+
+            // Search
+            Search => self.search_prompt = Some(SearchPromptState::search(self.cursor.pos())),
+            SearchNext => self.search_next(true),
+            SearchPrevious => self.search_next(false),
+            Replace => self.open_replace(),
+            SearchWorkspace => self.open_workspace_search(),
 
             // Commands
             OpenCommandPalette => {
-                self.show_command_palette = !self.show_command_palette;
-                self.command_input.clear();
+                if self.show_command_palette && self.command_line == CommandLine::Palette {
+                    self.execute_command(&EnterNormalMode)?;
+                } else {
+                    self.open_command_line(CommandLine::Palette);
+                }
+            }
+            SubmitCommandLine => self.submit_command_line()?,
+            CommandHistoryPrevious => self.command_history_previous(),
+            CommandHistoryNext => self.command_history_next(),
+            CommandHistorySearch => self.command_history_search(),
+            RepeatPaletteCommand => self.repeat_palette_command()?,
+            ShowKeybindings => {
+                self.show_cheat_sheet = !self.show_cheat_sheet;
+                self.cheat_sheet_query.clear();
             }
+            ShowStashes => self.open_stash_panel(),
+            StageHunks => self.open_hunk_panel(),
+            ShowGitLog => self.open_log_panel(),
+            ToggleBlame => self.toggle_blame(),
+            ShowAgentLauncher => self.open_agent_launcher(),
+            ConvertLineEndings => self.convert_line_endings(),
+            ConvertEncoding => self.convert_encoding(),
             Save => self.save_buffer()?,
             Quit => self.quit(),
             ForceQuit => self.force_quit(),
@@ -215,11 +597,25 @@ impl EditorState {
             FocusTerminal => self.focus_terminal(),
             FocusNextPanel => self.focus_next_panel(),
 
+            // Automatic focus changes
+            RunInTerminal => self.run_in_terminal(),
+            ToggleResults => self.toggle_results(),
+            FocusResults => self.focus_results(),
+            ReturnFocus => self.return_focus(),
+
+            // Panel sizes
+            GrowSidebar => self.resize_panel(Panel::Sidebar, RESIZE_STEP as i16),
+            ShrinkSidebar => self.resize_panel(Panel::Sidebar, -(RESIZE_STEP as i16)),
+            GrowTerminal => self.resize_panel(Panel::Terminal, RESIZE_STEP as i16),
+            ShrinkTerminal => self.resize_panel(Panel::Terminal, -(RESIZE_STEP as i16)),
+            ResetPanelSizes => self.panel_sizes = PanelSizes::default(),
+
             // Phase 10b: Sidebar navigation
             SidebarMoveUp => self.sidebar_move_up(),
             SidebarMoveDown => self.sidebar_move_down(),
             SidebarSelect => self.sidebar_select()?,
             SidebarToggleExpand => self.sidebar_toggle_expand(),
+            SidebarExpandAll => self.sidebar_expand_all(),
 
             _ => debug!("Unimplemented command: {:?}", command),
         }
@@ -228,42 +624,48 @@ impl EditorState {
     }
 
     // Cursor movement implementations
+    //
+    // Horizontal moves step over whole grapheme clusters and vertical moves
+    // keep the display column, so wide and combining characters stay intact.
     fn move_cursor_left(&mut self) {
-        let pos = self.cursor.position(&self.buffer);
-        if pos.col > 0 {
-            let _ = self.cursor.move_to(&self.buffer, pos.line, pos.col - 1);
+        if self.cursor.display_position(&self.buffer).col > 0 {
+            self.cursor.move_left(&self.buffer, 1);
         }
     }
 
     fn move_cursor_right(&mut self) {
-        let pos = self.cursor.position(&self.buffer);
-        // TODO: Check line length
-        let _ = self.cursor.move_to(&self.buffer, pos.line, pos.col + 1);
+        let line = self.cursor.position(&self.buffer).line;
+        let line_end = self.buffer.display_col_to_pos(line, usize::MAX);
+        if line_end.is_some_and(|end| self.cursor.pos() < end) {
+            self.cursor.move_right(&self.buffer, 1);
+        }
     }
 
+    // Vertical moves step over closed folds
     fn move_cursor_up(&mut self) {
-        let pos = self.cursor.position(&self.buffer);
-        if pos.line > 0 {
-            let _ = self.cursor.move_to(&self.buffer, pos.line - 1, pos.col);
-        }
+        let line = self.cursor.position(&self.buffer).line;
+        let count = self
+            .view
+            .folds
+            .prev_visible(line)
+            .map_or(1, |prev| line - prev);
+        self.cursor.move_up(&self.buffer, count);
     }
 
     fn move_cursor_down(&mut self) {
-        let pos = self.cursor.position(&self.buffer);
-        if pos.line < self.buffer.len_lines().saturating_sub(1) {
-            let _ = self.cursor.move_to(&self.buffer, pos.line + 1, pos.col);
+        let line = self.cursor.position(&self.buffer).line;
+        let next = self.view.folds.next_visible(line);
+        if next < self.buffer.len_lines() {
+            self.cursor.move_down(&self.buffer, next - line);
         }
     }
 
     fn move_cursor_line_start(&mut self) {
-        let pos = self.cursor.position(&self.buffer);
-        let _ = self.cursor.move_to(&self.buffer, pos.line, 0);
+        self.cursor.move_to_line_start(&self.buffer);
     }
 
     fn move_cursor_line_end(&mut self) {
-        let pos = self.cursor.position(&self.buffer);
-        // TODO: Get actual line length
-        let _ = self.cursor.move_to(&self.buffer, pos.line, 100);
+        self.cursor.move_to_line_end(&self.buffer);
     }
 
     fn move_cursor_word_forward(&mut self) {
@@ -278,21 +680,36 @@ impl EditorState {
 
     // Editing operations
     fn insert_char(&mut self, ch: char) {
+        let mut utf8 = [0u8; 4];
+        self.insert_text(ch.encode_utf8(&mut utf8));
+    }
+
+    /// Insert text at the cursor (insert mode) or into the command line
+    ///
+    /// Used for single keystrokes as well as composed IME commits and pastes,
+    /// which arrive as one multi-byte string.
+    pub fn insert_text(&mut self, text: &str) {
         if self.mode == Mode::Insert {
-            let pos = self.cursor.position(&self.buffer);
-            // TODO: Implement actual insertion
-            debug!("Insert char '{}' at {}:{}", ch, pos.line, pos.col);
-            self.move_cursor_right();
+            let pos = self.cursor.pos();
+            let cmd = InsertCommand::new(self.buffer.id(), pos, text);
+            if let Err(e) = self.apply_edit(Box::new(cmd)) {
+                error!("Failed to insert text at {}: {}", pos, e);
+                return;
+            }
+            self.cursor.set_pos(pos + text.len());
         } else if self.mode == Mode::Command {
-            self.command_input.push(ch);
+            if let Some(search) = &mut self.history_search {
+                search.query.push_str(text);
+                self.update_history_search();
+            } else {
+                self.command_input.push_str(text);
+            }
         }
     }
 
     fn insert_newline(&mut self) {
         if self.mode == Mode::Insert {
-            // TODO: Implement newline insertion
-            self.move_cursor_down();
-            self.move_cursor_line_start();
+            self.insert_text("\n");
         }
     }
 
@@ -303,16 +720,252 @@ impl EditorState {
 
     fn backspace(&mut self) {
         if self.mode == Mode::Insert {
-            // TODO: Implement backspace
-            self.move_cursor_left();
+            // Remove the whole grapheme before the cursor
+            let end = self.cursor.pos();
+            self.cursor.move_left(&self.buffer, 1);
+            let start = self.cursor.pos();
+            if start < end {
+                let cmd = DeleteCommand::new(self.buffer.id(), start..end);
+                if let Err(e) = self.apply_edit(Box::new(cmd)) {
+                    error!("Failed to delete {}..{}: {}", start, end, e);
+                    self.cursor.set_pos(end);
+                }
+            }
         } else if self.mode == Mode::Command {
-            self.command_input.pop();
+            if let Some(search) = &mut self.history_search {
+                search.query.pop();
+                self.update_history_search();
+            } else {
+                self.command_input.pop();
+            }
+        }
+    }
+
+    /// Execute an edit on the buffer and record it for undo
+    ///
+    /// Edits of one insert session coalesce into a single undo step; in the
+    /// other modes every edit is a step of its own.
+    fn apply_edit(&mut self, mut cmd: Box<dyn Command>) -> ait42_core::Result<()> {
+        let (line, lines) = (self.cursor.position(&self.buffer).line, self.buffer.len_lines());
+        cmd.execute(&mut self.buffer)?;
+        if self.mode == Mode::Insert {
+            self.history.push_coalescing(cmd, self.insert_session);
+        } else {
+            self.history.push(cmd);
+        }
+        self.follow_edit(line, lines);
+        Ok(())
+    }
+
+    fn undo(&mut self) {
+        let (line, lines) = (self.cursor.position(&self.buffer).line, self.buffer.len_lines());
+        match self.history.undo(&mut self.buffer) {
+            Ok(true) => {
+                self.clamp_cursor();
+                self.follow_edit(line, lines);
+            }
+            Ok(false) => debug!("Nothing to undo"),
+            Err(e) => error!("Undo failed: {}", e),
+        }
+    }
+
+    fn redo(&mut self) {
+        let (line, lines) = (self.cursor.position(&self.buffer).line, self.buffer.len_lines());
+        match self.history.redo(&mut self.buffer) {
+            Ok(true) => {
+                self.clamp_cursor();
+                self.follow_edit(line, lines);
+            }
+            Ok(false) => debug!("Nothing to redo"),
+            Err(e) => error!("Redo failed: {}", e),
+        }
+    }
+
+    /// Keep marks and closed folds on their lines after an edit around
+    /// `line` of the buffer, which had `lines` lines before
+    fn follow_edit(&mut self, line: usize, lines: usize) {
+        let delta = self.buffer.len_lines() as isize - lines as isize;
+        if delta != 0 {
+            self.view.marks.edited(line, delta);
+            if let Some(path) = self.buffer.path() {
+                self.global_marks.edited(path, line, delta);
+            }
+        }
+        if self.view.folds.is_empty() {
+            return;
+        }
+        self.view
+            .folds
+            .edited(&self.buffer.to_string(), line, delta);
+    }
+
+    /// Move the cursor out of a closed fold onto the line shown for it
+    fn leave_folded_lines(&mut self) {
+        let line = self.cursor.position(&self.buffer).line;
+        let shown = self.view.folds.visible_line(line);
+        if shown != line {
+            let _ = self.cursor.move_to(&self.buffer, shown, 0);
+        }
+    }
+
+    /// Open the folds hiding the cursor, e.g. after a search moved it there
+    pub fn reveal_cursor(&mut self) {
+        let line = self.cursor.position(&self.buffer).line;
+        self.view.folds.reveal(line);
+    }
+
+    /// Byte range of the visual selection, or the empty range at the cursor
+    fn target_range(&self) -> Range<usize> {
+        let pos = self.cursor.pos();
+        if self.mode != Mode::Visual {
+            return pos..pos;
+        }
+        self.selection
+            .primary()
+            .and_then(|range| {
+                range
+                    .normalized()
+                    .to_byte_range(|line, col| self.buffer.line_col_to_pos(line, col))
+            })
+            .unwrap_or(pos..pos)
+    }
+
+    /// Run a syntax-aware operation and apply its edit as one undo step
+    fn structural_edit(
+        &mut self,
+        op: impl FnOnce(&SyntaxTree, &str, Range<usize>) -> Option<StructuralEdit>,
+    ) {
+        let tree = match SyntaxTree::for_buffer(&self.buffer) {
+            Ok(Some(tree)) => tree,
+            Ok(None) => {
+                debug!("No syntax tree for {:?}", self.buffer.language());
+                return;
+            }
+            Err(e) => {
+                error!("Failed to parse buffer: {}", e);
+                return;
+            }
+        };
+
+        let source = self.buffer.to_string();
+        match op(&tree, &source, self.target_range()) {
+            Some(edit) => self.apply_structural(edit),
+            None => debug!("Structural edit not applicable here"),
         }
     }
 
+    fn apply_structural(&mut self, edit: StructuralEdit) {
+        let cursor = edit.cursor;
+        self.history.break_coalescing();
+        if let Err(e) = self.apply_edit(Box::new(edit.into_command(self.buffer.id()))) {
+            error!("Structural edit failed: {}", e);
+            return;
+        }
+        self.history.break_coalescing();
+        self.cursor.set_pos(cursor);
+        self.selection.clear();
+        if self.mode == Mode::Visual {
+            self.mode = Mode::Normal;
+        }
+    }
+
+    /// Switch every line break between LF and CRLF as one undo step
+    fn convert_line_endings(&mut self) {
+        let line_ending = self.buffer.line_ending().toggled();
+        let position = self.cursor.position(&self.buffer);
+        let cmd = ConvertLineEndingsCommand::new(self.buffer.id(), line_ending);
+        self.history.break_coalescing();
+        if let Err(e) = self.apply_edit(Box::new(cmd)) {
+            error!("Failed to convert line endings: {}", e);
+            return;
+        }
+        self.history.break_coalescing();
+        if self
+            .cursor
+            .move_to(&self.buffer, position.line, position.col)
+            .is_err()
+        {
+            self.clamp_cursor();
+        }
+        info!("Converted line endings to {}", line_ending.name());
+    }
+
+    /// Save the buffer in the next encoding that can represent it
+    fn convert_encoding(&mut self) {
+        let text = self.buffer.to_string();
+        let current = Encoding::ALL
+            .iter()
+            .position(|encoding| *encoding == self.buffer.encoding())
+            .unwrap_or(0);
+        let next = (1..Encoding::ALL.len())
+            .map(|offset| Encoding::ALL[(current + offset) % Encoding::ALL.len()])
+            .find(|encoding| encoding.can_encode(&text));
+        let Some(encoding) = next else {
+            info!("No other encoding can represent the buffer");
+            return;
+        };
+        match self.buffer.set_encoding(encoding) {
+            Ok(()) => info!("File will be saved as {}", encoding),
+            Err(e) => error!("Failed to convert encoding: {}", e),
+        }
+    }
+
+    fn join_lines(&mut self) {
+        let line = self.cursor.position(&self.buffer).line;
+        let language = Language::for_buffer(&self.buffer);
+        let source = self.buffer.to_string();
+        if let Some(edit) = structural::join_lines(&source, line, language) {
+            self.apply_structural(edit);
+        }
+    }
+
+    /// Grow the selection to the enclosing syntax node
+    fn select_enclosing_node(&mut self) {
+        let tree = match SyntaxTree::for_buffer(&self.buffer) {
+            Ok(Some(tree)) => tree,
+            Ok(None) => return,
+            Err(e) => {
+                error!("Failed to parse buffer: {}", e);
+                return;
+            }
+        };
+
+        let Some(range) = structural::select_enclosing(&tree, self.target_range()) else {
+            return;
+        };
+
+        let (start_line, start_col) = self.buffer.pos_to_line_col(range.start);
+        let (end_line, end_col) = self.buffer.pos_to_line_col(range.end);
+        self.selection.clear();
+        self.selection.add_range(SelectionRange::new(
+            CursorPosition::new(start_line, start_col),
+            CursorPosition::new(end_line, end_col),
+        ));
+        self.cursor.set_pos(range.start);
+        self.mode = Mode::Visual;
+    }
+
+    fn clamp_cursor(&mut self) {
+        let pos = self.cursor.pos().min(self.buffer.len_bytes());
+        self.cursor.set_pos(pos);
+    }
+
     fn save_buffer(&mut self) -> Result<()> {
-        // TODO: Implement save
-        info!("Saving buffer");
+        if self.buffer.path().is_none() {
+            // TODO: Prompt for a path
+            info!("Buffer has no file path");
+            return Ok(());
+        }
+        self.buffer.save()?;
+        // Blame compares against the file, which just changed, and HEAD
+        // may have moved since the gutter marks last read it
+        self.blame_cache.invalidate(self.buffer.id());
+        self.line_changes.reload(self.buffer.id());
+        if let Some(path) = self.buffer.path() {
+            self.file_metadata
+                .file_changed(&FileEvent::Modified(path.to_path_buf()));
+        }
+        info!("Saved buffer");
         Ok(())
     }
 
@@ -329,6 +982,170 @@ impl EditorState {
         self.running = false;
     }
 
+    // Command line
+
+    /// Open the `:` command line or the command palette, empty
+    fn open_command_line(&mut self, kind: CommandLine) {
+        self.mode = Mode::Command;
+        self.command_line = kind;
+        self.show_command_palette = true;
+        self.command_input.clear();
+        self.history_search = None;
+        self.ex_history.reset();
+        self.palette_history.reset();
+    }
+
+    /// History of the open command line
+    fn command_line_history(&mut self) -> &mut InputHistory {
+        match self.command_line {
+            CommandLine::Ex => &mut self.ex_history,
+            CommandLine::Palette => &mut self.palette_history,
+        }
+    }
+
+    /// Run the command line input and close it
+    ///
+    /// During a history search the entry found is run.
+    fn submit_command_line(&mut self) -> Result<()> {
+        if let Some(search) = self.history_search.take() {
+            if let Some(entry) = search.matched(self.command_line_history()) {
+                self.command_input = entry.to_string();
+            }
+        }
+        let input = std::mem::take(&mut self.command_input);
+        let input = input.trim();
+        let kind = self.command_line;
+        self.execute_command(&EditorCommand::EnterNormalMode)?;
+
+        match kind {
+            CommandLine::Ex => {
+                self.ex_history.push(input);
+                self.run_ex_command(input)
+            }
+            CommandLine::Palette => self.run_palette_command(input),
+        }
+    }
+
+    /// Run a line of the `:` command line
+    fn run_ex_command(&mut self, line: &str) -> Result<()> {
+        match line {
+            "" => {}
+            "w" => self.save_buffer()?,
+            "q" => self.quit(),
+            "q!" => self.force_quit(),
+            "wq" | "x" => {
+                self.save_buffer()?;
+                self.quit();
+            }
+            _ => {
+                if let Ok(number) = line.parse::<usize>() {
+                    // 1-based, clamped to the last line
+                    let last = self.buffer.len_lines().saturating_sub(1);
+                    let target = number.saturating_sub(1).min(last);
+                    self.cursor.move_to(&self.buffer, target, 0)?;
+                } else if let Some(command) = EditorCommand::from_name(line) {
+                    self.execute_command(&command)?;
+                } else {
+                    info!("Not an editor command: {}", line);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the command the palette shows first for `input`, recording it
+    fn run_palette_command(&mut self, input: &str) -> Result<()> {
+        if input.is_empty() {
+            return Ok(());
+        }
+        let name = match EditorCommand::from_name(input) {
+            Some(_) => input.to_string(),
+            None => match best_match(input, &editor_commands()) {
+                Some(command) => command.name,
+                None => {
+                    info!("No command matches: {}", input);
+                    return Ok(());
+                }
+            },
+        };
+        let Some(command) = EditorCommand::from_name(&name) else {
+            return Ok(());
+        };
+        if command != EditorCommand::RepeatPaletteCommand {
+            self.palette_history.push(name);
+        }
+        self.execute_command(&command)
+    }
+
+    /// Run the last command run from the palette again
+    fn repeat_palette_command(&mut self) -> Result<()> {
+        let command = self
+            .palette_history
+            .last()
+            .and_then(EditorCommand::from_name)
+            .filter(|command| *command != EditorCommand::RepeatPaletteCommand);
+        match command {
+            Some(command) => self.execute_command(&command),
+            None => {
+                debug!("No palette command to repeat");
+                Ok(())
+            }
+        }
+    }
+
+    /// Show the previous history entry (Up), or the next older match
+    /// during a history search
+    fn command_history_previous(&mut self) {
+        if self.history_search.is_some() {
+            self.command_history_search();
+            return;
+        }
+        let input = self.command_input.clone();
+        if let Some(entry) = self.command_line_history().older(&input) {
+            self.command_input = entry.to_string();
+        }
+    }
+
+    /// Show the next history entry (Down), or edit the match found by a
+    /// history search
+    fn command_history_next(&mut self) {
+        if let Some(search) = self.history_search.take() {
+            if let Some(entry) = search.matched(self.command_line_history()) {
+                self.command_input = entry.to_string();
+            }
+            return;
+        }
+        if let Some(entry) = self.command_line_history().newer() {
+            self.command_input = entry.to_string();
+        }
+    }
+
+    /// Start a reverse search of the command line history (Ctrl+R), or
+    /// step to the next older match
+    fn command_history_search(&mut self) {
+        if self.mode != Mode::Command {
+            return;
+        }
+        match self.history_search.take() {
+            Some(mut search) => {
+                search.older(self.command_line_history());
+                self.history_search = Some(search);
+            }
+            None => {
+                self.history_search = Some(ReverseSearch::default());
+                self.update_history_search();
+            }
+        }
+    }
+
+    /// Search the history of the command line again for the query
+    fn update_history_search(&mut self) {
+        if let Some(mut search) = self.history_search.take() {
+            search.update(self.command_line_history());
+            self.history_search = Some(search);
+        }
+    }
+
     // ==========================================
     // Phase 10b: Tab Management
     // ==========================================
@@ -338,12 +1155,25 @@ impl EditorState {
         let buffer = Buffer::new();
         let tab = Tab::new(title, None, buffer);
         self.tabs.push(tab);
-        self.active_tab_index = self.tabs.len() - 1;
-        self.switch_tab(self.active_tab_index)?;
+        self.switch_tab(self.tabs.len() - 1)?;
         info!("Created new tab: {}", self.tabs[self.active_tab_index].title);
         Ok(())
     }
 
+    /// Reopen the unsaved contents of a buffer in a new tab
+    pub fn open_recovered(&mut self, file: &RecoveryFile) -> Result<()> {
+        let title = file
+            .path
+            .as_deref()
+            .map_or_else(|| "untitled".to_string(), tab_title);
+        let mut tab = Tab::new(title, file.path.clone(), file.to_buffer());
+        tab.is_modified = true;
+        self.tabs.push(tab);
+        self.switch_tab(self.tabs.len() - 1)?;
+        info!("Recovered unsaved buffer: {}", self.tabs[self.active_tab_index].title);
+        Ok(())
+    }
+
     /// Close tab at the given index
     pub fn close_tab(&mut self, index: usize) -> Result<()> {
         if self.tabs.len() <= 1 {
@@ -361,14 +1191,22 @@ impl EditorState {
             // TODO: Prompt user for confirmation
         }
 
+        // The closed tab's buffer is dropped, any other keeps its edits
+        if index != self.active_tab_index {
+            self.store_active_tab();
+        }
+
         self.tabs.remove(index);
 
         // Adjust active tab index
+        if index < self.active_tab_index {
+            self.active_tab_index -= 1;
+        }
         if self.active_tab_index >= self.tabs.len() {
             self.active_tab_index = self.tabs.len() - 1;
         }
 
-        self.switch_tab(self.active_tab_index)?;
+        self.load_tab(self.active_tab_index);
         info!("Closed tab at index {}", index);
         Ok(())
     }
@@ -380,20 +1218,33 @@ impl EditorState {
         }
 
         // Save current buffer state to active tab
-        if !self.tabs.is_empty() && self.active_tab_index < self.tabs.len() {
-            self.tabs[self.active_tab_index].buffer = self.buffer.clone();
-        }
-
-        // Switch to new tab
-        self.active_tab_index = index;
-        self.buffer = self.tabs[index].buffer.clone();
-        self.cursor = Cursor::default();
-        self.view = ViewState::new();
+        self.store_active_tab();
 
+        self.load_tab(index);
         debug!("Switched to tab: {}", self.tabs[index].title);
         Ok(())
     }
 
+    /// Keep the buffer, cursor and scroll position in the active tab
+    fn store_active_tab(&mut self) {
+        if let Some(tab) = self.tabs.get_mut(self.active_tab_index) {
+            tab.buffer = self.buffer.clone();
+            tab.cursor = self.cursor.clone();
+            tab.view = self.view.clone();
+        }
+    }
+
+    /// Make the tab at `index` current without saving the current buffer
+    fn load_tab(&mut self, index: usize) {
+        self.active_tab_index = index;
+        let tab = &self.tabs[index];
+        self.buffer = tab.buffer.clone();
+        self.cursor = tab.cursor.clone();
+        self.view = tab.view.clone();
+        self.selection.clear();
+        self.history.clear();
+    }
+
     /// Switch to next tab
     pub fn next_tab(&mut self) {
         if self.tabs.is_empty() {
@@ -451,15 +1302,15 @@ impl EditorState {
             self.sidebar_toggle_expand();
         } else {
             // Open file in new tab
-            let buffer = Buffer::from_file(&item.path)?;
+            let buffer = self.open_buffer(&item.path)?;
             let title = item
                 .name
                 .clone();
             let tab = Tab::new(title, Some(item.path.clone()), buffer);
 
             self.tabs.push(tab);
-            self.active_tab_index = self.tabs.len() - 1;
-            self.switch_tab(self.active_tab_index)?;
+            self.switch_tab(self.tabs.len() - 1)?;
+            self.preload_next();
 
             info!("Opened file: {:?}", item.path);
         }
@@ -473,15 +1324,62 @@ impl EditorState {
             return;
         }
 
-        let item = &mut self.sidebar_items[self.sidebar_selected];
-        if item.is_dir {
-            item.is_expanded = !item.is_expanded;
-            debug!(
-                "Toggled directory '{}': {}",
-                item.name, item.is_expanded
-            );
-            // TODO: Load/unload directory contents
+        let item = &self.sidebar_items[self.sidebar_selected];
+        if !item.is_dir {
+            return;
+        }
+        if item.is_expanded {
+            self.sidebar_collapse(self.sidebar_selected);
+        } else {
+            self.sidebar_expand(self.sidebar_selected, 1);
+        }
+    }
+
+    /// Expand the current sidebar directory and everything below it
+    pub fn sidebar_expand_all(&mut self) {
+        if self.sidebar_selected >= self.sidebar_items.len()
+            || !self.sidebar_items[self.sidebar_selected].is_dir
+        {
+            return;
+        }
+        self.sidebar_collapse(self.sidebar_selected);
+        self.sidebar_expand(self.sidebar_selected, SIDEBAR_EXPAND_DEPTH);
+    }
+
+    /// Remove the entries shown below the directory at `index`
+    fn sidebar_collapse(&mut self, index: usize) {
+        let level = self.sidebar_items[index].level;
+        let end = self.sidebar_items[index + 1..]
+            .iter()
+            .position(|item| item.level <= level)
+            .map_or(self.sidebar_items.len(), |offset| index + 1 + offset);
+        self.sidebar_items.drain(index + 1..end);
+        self.sidebar_items[index].is_expanded = false;
+        debug!("Collapsed directory '{}'", self.sidebar_items[index].name);
+    }
+
+    /// Show up to `depth` levels of the directory at `index` below it
+    fn sidebar_expand(&mut self, index: usize, depth: usize) {
+        let item = &self.sidebar_items[index];
+        let walk = Walker::new(&item.path)
+            .max_depth(depth)
+            .max_entries(SIDEBAR_EXPAND_LIMIT)
+            .run();
+        let walk = match walk {
+            Ok(walk) => walk,
+            Err(e) => {
+                warn!("Failed to expand {:?}: {}", item.path, e);
+                return;
+            }
+        };
+        if walk.truncated {
+            warn!("Showing the first {} entries of {:?}", SIDEBAR_EXPAND_LIMIT, item.path);
         }
+
+        let children = sidebar_children(walk, item.level, depth);
+        debug!("Expanded directory '{}': {} entries", item.name, children.len());
+        self.sidebar_items[index].is_expanded = true;
+        self.sidebar_items.splice(index + 1..index + 1, children);
     }
 
     /// Load directory contents into sidebar
@@ -496,7 +1394,10 @@ impl EditorState {
         for entry in entries.flatten() {
             let path = entry.path();
             let name = entry.file_name().to_string_lossy().to_string();
-            let is_dir = path.is_dir();
+            let is_dir = self
+                .file_metadata
+                .info(&path)
+                .is_some_and(|info| info.is_dir);
 
             items.push(SidebarItem {
                 name,
@@ -521,6 +1422,11 @@ impl EditorState {
         Ok(())
     }
 
+    /// Forget the sidebar metadata a file change may have made stale
+    pub fn file_changed(&mut self, event: &FileEvent) {
+        self.file_metadata.file_changed(event);
+    }
+
     // ==========================================
     // Phase 10b: Panel Visibility & Focus
     // ==========================================
@@ -537,11 +1443,29 @@ impl EditorState {
         debug!("Terminal visible: {}", self.terminal_visible);
     }
 
-    /// Focus next panel in cycle: Editor -> Sidebar -> Terminal -> Editor
+    /// Grow (or shrink, if `delta` is negative) `panel` by `delta` percent
+    pub fn resize_panel(&mut self, panel: Panel, delta: i16) {
+        self.panel_sizes.resize(panel, delta);
+        debug!("{:?} size: {}%", panel, self.panel_sizes.percent(panel));
+    }
+
+    /// Toggle agent results visibility
+    pub fn toggle_results(&mut self) {
+        self.results_visible = !self.results_visible;
+        if !self.results_visible && self.focused_panel == FocusedPanel::Results {
+            self.focused_panel = FocusedPanel::Editor;
+        }
+        debug!("Agent results visible: {}", self.results_visible);
+    }
+
+    /// Focus next panel in cycle: Editor -> Sidebar -> Terminal -> Results -> Editor
     pub fn focus_next_panel(&mut self) {
         self.focused_panel = match self.focused_panel {
             FocusedPanel::Editor if self.sidebar_visible => FocusedPanel::Sidebar,
             FocusedPanel::Sidebar if self.terminal_visible => FocusedPanel::Terminal,
+            FocusedPanel::Sidebar | FocusedPanel::Terminal if self.results_visible => {
+                FocusedPanel::Results
+            }
             _ => FocusedPanel::Editor,
         };
         debug!("Focused panel: {:?}", self.focused_panel);
@@ -569,34 +1493,230 @@ impl EditorState {
         }
     }
 
-    // ==========================================
-    // Phase 10b: Getters for UI
-    // ==========================================
-
-    /// Get all tabs
-    pub fn tabs(&self) -> &[Tab] {
-        &self.tabs
+    /// Focus the agent results panel
+    pub fn focus_results(&mut self) {
+        if self.results_visible {
+            self.focused_panel = FocusedPanel::Results;
+            debug!("Focused panel: Results");
+        }
     }
 
-    /// Get active tab index
-    pub fn active_tab_index(&self) -> usize {
-        self.active_tab_index
+    /// Visibility flag of `panel`, `None` for the always visible editor
+    fn panel_visibility(&mut self, panel: FocusedPanel) -> Option<&mut bool> {
+        match panel {
+            FocusedPanel::Editor => None,
+            FocusedPanel::Sidebar => Some(&mut self.sidebar_visible),
+            FocusedPanel::Terminal => Some(&mut self.terminal_visible),
+            FocusedPanel::Results => Some(&mut self.results_visible),
+        }
     }
 
-    /// Get sidebar visibility
-    pub fn sidebar_visible(&self) -> bool {
-        self.sidebar_visible
-    }
+    /// Show `panel`, focusing it unless `steal` is off
+    ///
+    /// The focus it takes over can be restored with
+    /// [`return_focus`](Self::return_focus).
+    fn auto_focus(&mut self, panel: FocusedPanel, steal: bool) {
+        let opened = match self.panel_visibility(panel) {
+            Some(visible) if !*visible => {
+                *visible = true;
+                true
+            }
+            _ => false,
+        };
+        if !steal || self.focused_panel == panel {
+            return;
+        }
 
-    /// Get sidebar items
-    pub fn sidebar_items(&self) -> &[SidebarItem] {
-        &self.sidebar_items
+        let focus_return = self.focus_return.get_or_insert_with(|| FocusReturn {
+            panel: self.focused_panel,
+            opened: Vec::new(),
+        });
+        if opened {
+            focus_return.opened.push(panel);
+        }
+        self.focused_panel = panel;
+        debug!("Focused panel: {:?} (automatically)", panel);
     }
 
-    /// Get sidebar selected index
-    pub fn sidebar_selected(&self) -> usize {
-        self.sidebar_selected
-    }
+    /// Undo automatic focus changes, closing the panels they opened
+    pub fn return_focus(&mut self) {
+        let Some(focus_return) = self.focus_return.take() else {
+            debug!("No focus to return to");
+            return;
+        };
+        for panel in focus_return.opened {
+            if let Some(visible) = self.panel_visibility(panel) {
+                *visible = false;
+            }
+        }
+        self.focused_panel = match self.panel_visibility(focus_return.panel) {
+            Some(visible) if !*visible => FocusedPanel::Editor,
+            _ => focus_return.panel,
+        };
+        debug!("Focused panel: {:?}", self.focused_panel);
+    }
+
+    /// Queue the current line to run in the terminal, or each line of the
+    /// visual selection
+    fn run_in_terminal(&mut self) {
+        let range = self.target_range();
+        let text = if range.is_empty() {
+            let line = self.cursor.position(&self.buffer).line;
+            self.buffer
+                .line(line)
+                .map(|line| line.to_string())
+                .unwrap_or_default()
+        } else {
+            match self.buffer.slice(range) {
+                Ok(text) => text,
+                Err(e) => {
+                    error!("Failed to read selection: {}", e);
+                    return;
+                }
+            }
+        };
+
+        let commands: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+        if commands.is_empty() {
+            debug!("Nothing to run in the terminal");
+            return;
+        }
+
+        self.pending_terminal_commands.extend(commands);
+        if self.mode == Mode::Visual {
+            self.mode = Mode::Normal;
+            self.selection.clear();
+        }
+        self.auto_focus(FocusedPanel::Terminal, self.focus_config.on_terminal_command);
+    }
+
+    /// Take the commands waiting to run in the terminal
+    pub fn take_terminal_commands(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_terminal_commands)
+    }
+
+    /// Add the outcome of an agent run to the results panel and show it
+    pub fn agent_finished(
+        &mut self,
+        agent: &str,
+        task: &str,
+        status: &SessionStatus,
+        output: &[String],
+    ) {
+        self.agent_results.push(match status {
+            SessionStatus::Failed(reason) => format!("✗ {} failed: {} ({})", agent, task, reason),
+            _ => format!("✓ {} finished: {}", agent, task),
+        });
+        let tail = output.len().saturating_sub(AGENT_RESULT_LINES);
+        self.agent_results.extend(output[tail..].iter().cloned());
+        self.agent_results.push(String::new());
+
+        let excess = self.agent_results.len().saturating_sub(MAX_AGENT_RESULTS);
+        self.agent_results.drain(..excess);
+        self.results_scroll = 0;
+
+        self.auto_focus(FocusedPanel::Results, self.focus_config.on_agent_complete);
+    }
+
+    // ==========================================
+    // Phase 10b: Getters for UI
+    // ==========================================
+
+    /// Get current mode
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Text typed into the command line
+    pub fn command_input(&self) -> &str {
+        &self.command_input
+    }
+
+    /// Command line open in command mode
+    pub fn command_line(&self) -> CommandLine {
+        self.command_line
+    }
+
+    /// Query of the open history search and the entry it found
+    pub fn history_search(&self) -> Option<(&str, Option<&str>)> {
+        let search = self.history_search.as_ref()?;
+        let history = match self.command_line {
+            CommandLine::Ex => &self.ex_history,
+            CommandLine::Palette => &self.palette_history,
+        };
+        Some((search.query.as_str(), search.matched(history)))
+    }
+
+    /// Commands run from the command palette, oldest first
+    pub fn palette_history(&self) -> &[String] {
+        self.palette_history.entries()
+    }
+
+    /// Lines entered on the `:` command line, oldest first
+    pub fn ex_history(&self) -> &[String] {
+        self.ex_history.entries()
+    }
+
+    /// Replace the command palette and `:` command line histories
+    pub fn set_command_history(&mut self, palette: Vec<String>, ex: Vec<String>) {
+        self.palette_history = InputHistory::from_entries(palette);
+        self.ex_history = InputHistory::from_entries(ex);
+    }
+
+    /// Get current buffer
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Get cursor in the current buffer
+    pub fn cursor(&self) -> &Cursor {
+        &self.cursor
+    }
+
+    /// Whether the application keeps running
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Get all tabs
+    pub fn tabs(&self) -> &[Tab] {
+        &self.tabs
+    }
+
+    /// Get active tab index
+    pub fn active_tab_index(&self) -> usize {
+        self.active_tab_index
+    }
+
+    /// Get sidebar visibility
+    pub fn sidebar_visible(&self) -> bool {
+        self.sidebar_visible
+    }
+
+    /// Get sidebar and terminal panel sizes
+    pub fn panel_sizes(&self) -> PanelSizes {
+        self.panel_sizes
+    }
+
+    /// Set sidebar and terminal panel sizes
+    pub fn set_panel_sizes(&mut self, sizes: PanelSizes) {
+        self.panel_sizes = sizes;
+    }
+
+    /// Get sidebar items
+    pub fn sidebar_items(&self) -> &[SidebarItem] {
+        &self.sidebar_items
+    }
+
+    /// Get sidebar selected index
+    pub fn sidebar_selected(&self) -> usize {
+        self.sidebar_selected
+    }
 
     /// Get terminal visibility
     pub fn terminal_visible(&self) -> bool {
@@ -608,91 +1728,1437 @@ impl EditorState {
         self.terminal_scroll
     }
 
+    /// Get agent results visibility
+    pub fn results_visible(&self) -> bool {
+        self.results_visible
+    }
+
+    /// Output of finished agent runs
+    pub fn agent_results(&self) -> &[String] {
+        &self.agent_results
+    }
+
+    /// Agent results scroll offset
+    pub fn results_scroll(&self) -> usize {
+        self.results_scroll
+    }
+
+    /// Whether agent results take the bottom panel rather than the terminal
+    pub fn shows_results(&self) -> bool {
+        self.results_visible
+            && (self.focused_panel == FocusedPanel::Results || !self.terminal_visible)
+    }
+
+    /// Set which events move focus on their own
+    pub fn set_focus_config(&mut self, config: FocusConfig) {
+        self.focus_config = config;
+    }
+
     /// Get focused panel
     pub fn focused_panel(&self) -> FocusedPanel {
         self.focused_panel
     }
+
+    // Which-key and cheat-sheet
+
+    /// Start waiting for the continuation of a prefix key
+    pub fn begin_prefix(&mut self, key: KeyBinding) {
+        self.pending_prefix = Some(PendingPrefix::new(key, self.mode));
+        self.which_key_visible = false;
+    }
+
+    /// Take the pending prefix, hiding the which-key popup
+    pub fn take_pending_prefix(&mut self) -> Option<PendingPrefix> {
+        self.which_key_visible = false;
+        self.pending_prefix.take()
+    }
+
+    /// Pending prefix key, if any
+    pub fn pending_prefix(&self) -> Option<&PendingPrefix> {
+        self.pending_prefix.as_ref()
+    }
+
+    /// Show the which-key popup once the pending prefix has timed out
+    pub fn update_which_key(&mut self, now: Instant, timeout: Duration) {
+        if let Some(pending) = &self.pending_prefix {
+            if pending.timed_out(now, timeout) {
+                self.which_key_visible = true;
+            }
+        }
+    }
+
+    /// Whether the which-key popup is visible
+    pub fn which_key_visible(&self) -> bool {
+        self.which_key_visible
+    }
+
+    /// Add a digit to the count typed before the next command
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let count = self.pending_count.unwrap_or(0);
+        self.pending_count = Some(count.saturating_mul(10).saturating_add(digit as usize));
+    }
+
+    /// Count typed before the next command
+    pub fn pending_count(&self) -> Option<usize> {
+        self.pending_count
+    }
+
+    /// Recorded macros
+    pub fn macros(&self) -> &Macros {
+        &self.macros
+    }
+
+    /// Replace the recorded macros, e.g. with the saved ones
+    pub fn set_macros(&mut self, macros: Macros) {
+        self.macros = macros;
+    }
+
+    /// Command waiting for its register
+    pub fn register_prompt(&self) -> Option<RegisterPrompt> {
+        self.register_prompt
+    }
+
+    /// Complete the waiting command with the register typed
+    ///
+    /// Esc or a key naming no register cancels it; `@` replays the macro
+    /// replayed last.
+    pub fn register_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.register_prompt.take() else {
+            return Ok(());
+        };
+        let KeyCode::Char(register) = key.code else {
+            return Ok(());
+        };
+        match prompt {
+            RegisterPrompt::RecordMacro => {
+                if !self.macros.start_recording(register) {
+                    debug!("Not a macro register: {}", register);
+                }
+            }
+            RegisterPrompt::ReplayMacro { count } => self.replay_macro(register, count)?,
+            RegisterPrompt::Select if Registers::is_register(register) => {
+                self.pending_register = Some(register);
+            }
+            RegisterPrompt::Select => debug!("Not a register: {}", register),
+            RegisterPrompt::SetMark => self.set_mark(register),
+            RegisterPrompt::JumpToMark => self.jump_to_mark(register)?,
+        }
+        Ok(())
+    }
+
+    /// Set mark `name` at the cursor
+    ///
+    /// An uppercase mark needs the buffer to have a file.
+    fn set_mark(&mut self, name: char) {
+        let position = self.cursor.position(&self.buffer);
+        let mark = Mark::new(position.line, position.col);
+        if marks::is_local(name) {
+            self.view.marks.set(name, mark);
+        } else if !marks::is_global(name) {
+            debug!("Not a mark: {}", name);
+        } else if let Some(path) = self.buffer.path() {
+            self.global_marks.set(name, path, mark);
+        } else {
+            debug!("Cannot set mark {} in a buffer without a file", name);
+        }
+    }
+
+    /// Move the cursor to mark `name`, opening its file for an uppercase
+    /// mark
+    fn jump_to_mark(&mut self, name: char) -> Result<()> {
+        if let Some(global) = self.global_marks.get(name).cloned() {
+            let Mark { line, column } = global.mark;
+            return self.open_location(&global.path, line + 1, column + 1);
+        }
+        let Some(Mark { line, column }) = self.view.marks.get(name) else {
+            debug!("Mark not set: {}", name);
+            return Ok(());
+        };
+        if self.cursor.move_to(&self.buffer, line, column).is_err() {
+            let last = self.buffer.len_lines().saturating_sub(1);
+            let _ = self.cursor.move_to(&self.buffer, line.min(last), 0);
+        }
+        Ok(())
+    }
+
+    /// Uppercase marks, in any file
+    pub fn global_marks(&self) -> &GlobalMarks {
+        &self.global_marks
+    }
+
+    /// Replace the uppercase marks, e.g. with the saved ones
+    pub fn set_global_marks(&mut self, marks: GlobalMarks) {
+        self.global_marks = marks;
+    }
+
+    /// Yanked text
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// Insert the text of `register`, the unnamed one with `None`, at the
+    /// cursor as one undo step
+    fn paste(&mut self, register: Option<char>) {
+        let Some(text) = self.registers.get(register).map(str::to_string) else {
+            debug!("Nothing to paste from register {:?}", register);
+            return;
+        };
+        let pos = self.cursor.pos();
+        self.history.break_coalescing();
+        let cmd = InsertCommand::new(self.buffer.id(), pos, text.as_str());
+        if let Err(e) = self.apply_edit(Box::new(cmd)) {
+            error!("Failed to paste at {}: {}", pos, e);
+            return;
+        }
+        self.history.break_coalescing();
+        self.cursor.set_pos(pos + text.len());
+    }
+
+    /// Open copy mode over the lines of the agent results if they are
+    /// shown, over `terminal_output` otherwise, if it was asked for
+    ///
+    /// Copy mode starts where the pane is scrolled to; the terminal panel
+    /// is shown if neither pane is.
+    pub fn open_requested_terminal_copy(&mut self, terminal_output: &[String]) {
+        if !std::mem::take(&mut self.terminal_copy_requested) {
+            return;
+        }
+        if !self.terminal_visible && !self.results_visible {
+            self.terminal_visible = true;
+        }
+        let copy = if self.shows_results() {
+            TerminalCopyState::new(self.agent_results.clone(), self.results_scroll)
+        } else {
+            TerminalCopyState::new(terminal_output.to_vec(), self.terminal_scroll)
+        };
+        self.copying_results = self.shows_results();
+        self.terminal_copy = Some(copy);
+    }
+
+    /// Open terminal copy mode, if any
+    pub fn terminal_copy(&self) -> Option<&TerminalCopyState> {
+        self.terminal_copy.as_ref()
+    }
+
+    /// Handle a key in terminal copy mode
+    ///
+    /// Yanked text goes to its register and the system clipboard. The pane
+    /// stays scrolled where copy mode left it.
+    pub fn terminal_copy_input(&mut self, key: KeyEvent) {
+        let Some(copy) = &mut self.terminal_copy else {
+            return;
+        };
+        let open = copy.handle_key(key);
+        let scroll = copy.scroll_offset();
+        if let Some(yank) = copy.take_yank() {
+            debug!("Yanked {} bytes from the terminal", yank.text.len());
+            self.registers.yank(yank.register, yank.text.as_str());
+            self.clipboard = Some(yank.text);
+        }
+        if !open {
+            self.terminal_copy = None;
+            if self.copying_results {
+                self.results_scroll = scroll;
+            } else {
+                self.terminal_scroll = scroll;
+            }
+        }
+    }
+
+    /// Take the text waiting to be put on the system clipboard
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard.take()
+    }
+
+    /// Replay the macro in `register` `count` times as one undo step
+    ///
+    /// Stops at the first command that fails.
+    fn replay_macro(&mut self, register: char, count: usize) -> Result<()> {
+        let Some(commands) = self.macros.replay(register) else {
+            debug!("No macro in register {}", register);
+            return Ok(());
+        };
+        self.history.break_coalescing();
+        self.history.begin_group(format!("Macro @{}", register));
+        let result = std::iter::repeat(&commands)
+            .take(count)
+            .flatten()
+            .try_for_each(|command| self.run_command(command));
+        self.history.end_group();
+        result
+    }
+
+    /// Whether the cheat-sheet is visible
+    pub fn show_cheat_sheet(&self) -> bool {
+        self.show_cheat_sheet
+    }
+
+    /// Current cheat-sheet search query
+    pub fn cheat_sheet_query(&self) -> &str {
+        &self.cheat_sheet_query
+    }
+
+    /// Feed a key to the open cheat-sheet (search input, Esc closes)
+    pub fn cheat_sheet_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.show_cheat_sheet = false;
+                self.cheat_sheet_query.clear();
+            }
+            KeyCode::Backspace => {
+                self.cheat_sheet_query.pop();
+            }
+            KeyCode::Char(ch) => self.cheat_sheet_query.push(ch),
+            _ => {}
+        }
+    }
+
+    /// Directory used to locate the repository of the current file
+    ///
+    /// Falls back to the working directory for unsaved buffers.
+    fn repository_dir(&self) -> PathBuf {
+        self.buffer
+            .path()
+            .and_then(|path| path.parent())
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Open the stash panel for the repository of the current file
+    pub fn open_stash_panel(&mut self) {
+        match StashPanelState::open(&self.repository_dir()) {
+            Ok(panel) => self.stash_panel = Some(panel),
+            Err(e) => warn!("Cannot open stash panel: {}", e),
+        }
+    }
+
+    /// Open stash panel, if any
+    pub fn stash_panel(&self) -> Option<&StashPanelState> {
+        self.stash_panel.as_ref()
+    }
+
+    /// Feed a key to the open stash panel
+    pub fn stash_panel_input(&mut self, key: KeyEvent) {
+        if let Some(panel) = &mut self.stash_panel {
+            if !panel.handle_key(key) {
+                self.stash_panel = None;
+            }
+        }
+    }
+
+    /// Open the hunk staging panel for the repository of the current file
+    pub fn open_hunk_panel(&mut self) {
+        match HunkPanelState::open(&self.repository_dir()) {
+            Ok(panel) => self.hunk_panel = Some(panel),
+            Err(e) => warn!("Cannot open hunk panel: {}", e),
+        }
+    }
+
+    /// Open hunk staging panel, if any
+    pub fn hunk_panel(&self) -> Option<&HunkPanelState> {
+        self.hunk_panel.as_ref()
+    }
+
+    /// Feed a key to the open hunk staging panel
+    pub fn hunk_panel_input(&mut self, key: KeyEvent) {
+        if let Some(panel) = &mut self.hunk_panel {
+            if !panel.handle_key(key) {
+                self.hunk_panel = None;
+            }
+        }
+    }
+
+    /// Open the agent launcher for the configured AIT42 install
+    pub fn open_agent_launcher(&mut self) {
+        match AgentLauncherState::open() {
+            Ok(launcher) => self.agent_launcher = Some(launcher),
+            Err(e) => warn!("Cannot open agent launcher: {}", e),
+        }
+    }
+
+    /// Open agent launcher, if any
+    pub fn agent_launcher(&self) -> Option<&AgentLauncherState> {
+        self.agent_launcher.as_ref()
+    }
+
+    /// Feed a key to the open agent launcher
+    pub fn agent_launcher_input(&mut self, key: KeyEvent) {
+        if let Some(launcher) = &mut self.agent_launcher {
+            if !launcher.handle_key(key) {
+                self.agent_launcher = None;
+            }
+        }
+    }
+
+    /// Open a replace prompt, limited to the selection in visual mode
+    fn open_replace(&mut self) {
+        let scope = Some(self.target_range()).filter(|range| !range.is_empty());
+        self.search_prompt = Some(SearchPromptState::replace(self.cursor.pos(), scope));
+    }
+
+    /// Open search prompt, if any
+    pub fn search_prompt(&self) -> Option<&SearchPromptState> {
+        self.search_prompt.as_ref()
+    }
+
+    /// Feed a key to the open search prompt
+    ///
+    /// While a search is typed the cursor previews the first match after
+    /// where it started; Esc puts it back.
+    pub fn search_prompt_input(&mut self, key: KeyEvent) {
+        let Some(prompt) = &mut self.search_prompt else {
+            return;
+        };
+        if prompt.handle_key(key) {
+            self.preview_search();
+            return;
+        }
+
+        let prompt = self.search_prompt.take().expect("open search prompt");
+        let Some(query) = prompt.query().filter(|_| prompt.is_accepted()).cloned() else {
+            self.cursor.set_pos(prompt.origin());
+            return;
+        };
+        if let Some(replacement) = prompt.replacement() {
+            self.replace_all(query.clone(), replacement, prompt.scope());
+        }
+        self.last_search = Some(query);
+    }
+
+    /// Add typed or pasted text to the open search prompt
+    pub fn search_prompt_text(&mut self, text: &str) {
+        if let Some(prompt) = &mut self.search_prompt {
+            prompt.insert_text(text);
+            self.preview_search();
+        }
+    }
+
+    fn preview_search(&mut self) {
+        let Some(prompt) = &self.search_prompt else {
+            return;
+        };
+        if prompt.replacement().is_some() {
+            return;
+        }
+        let origin = prompt.origin();
+        let target = prompt
+            .query()
+            .and_then(|query| query.next_match(&self.buffer.to_string(), origin))
+            .map_or(origin, |range| range.start);
+        self.cursor.set_pos(target);
+    }
+
+    /// Move to the next (or previous) match of the last search, wrapping
+    fn search_next(&mut self, forward: bool) {
+        let Some(query) = &self.last_search else {
+            debug!("No previous search");
+            return;
+        };
+        let text = self.buffer.to_string();
+        let pos = self.cursor.pos();
+        let found = if forward {
+            query.next_match(&text, pos)
+        } else {
+            query.prev_match(&text, pos)
+        };
+        match found {
+            Some(range) => self.cursor.set_pos(range.start),
+            None => info!("Pattern not found: {}", query.pattern()),
+        }
+    }
+
+    /// Replace every match in the buffer (or `scope`) as one undo step
+    fn replace_all(&mut self, query: SearchQuery, replacement: &str, scope: Option<Range<usize>>) {
+        let mut cmd = ReplaceAllCommand::new(self.buffer.id(), query, replacement);
+        if let Some(scope) = scope {
+            cmd = cmd.within(scope);
+        }
+        if let Err(e) = cmd.execute(&mut self.buffer) {
+            error!("Replace failed: {}", e);
+            return;
+        }
+        info!("Replaced {} matches", cmd.replaced());
+        if cmd.replaced() > 0 {
+            self.history.break_coalescing();
+            self.history.push(Box::new(cmd));
+        }
+        self.clamp_cursor();
+        self.selection.clear();
+        if self.mode == Mode::Visual {
+            self.mode = Mode::Normal;
+        }
+    }
+
+    /// Byte ranges to highlight: matches of the pattern being typed, or of
+    /// the last search once the prompt is closed
+    pub fn search_matches(&self) -> Vec<Range<usize>> {
+        let query = match &self.search_prompt {
+            Some(prompt) => prompt.query(),
+            None => self.last_search.as_ref(),
+        };
+        query
+            .map(|query| {
+                query
+                    .find_all(&self.buffer.to_string())
+                    .into_iter()
+                    .map(|m| m.range)
+                    .filter(|range| !range.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Open the history panel for the repository of the current file
+    pub fn open_log_panel(&mut self) {
+        match LogGraphState::open(&self.repository_dir()) {
+            Ok(panel) => self.log_panel = Some(panel),
+            Err(e) => warn!("Cannot open history panel: {}", e),
+        }
+    }
+
+    /// Open history panel, if any
+    pub fn log_panel(&self) -> Option<&LogGraphState> {
+        self.log_panel.as_ref()
+    }
+
+    /// Feed a key to the open history panel
+    pub fn log_panel_input(&mut self, key: KeyEvent) {
+        if let Some(panel) = &mut self.log_panel {
+            if !panel.handle_key(key) {
+                self.log_panel = None;
+            }
+        }
+    }
+
+    /// Show or hide the blame gutter
+    pub fn toggle_blame(&mut self) {
+        self.blame_visible = !self.blame_visible;
+    }
+
+    /// Whether the blame gutter is visible
+    pub fn blame_visible(&self) -> bool {
+        self.blame_visible
+    }
+
+    /// Load the blame of the current buffer revision, if the gutter is visible
+    pub fn refresh_blame(&mut self) {
+        if self.blame_visible {
+            self.blame_cache.get(&self.buffer);
+        }
+    }
+
+    /// Blame of the current buffer, empty until loaded by [`Self::refresh_blame`]
+    pub fn blame(&self) -> &[BlameLine] {
+        self.blame_cache.cached(&self.buffer).unwrap_or_default()
+    }
+
+    /// Diff the current buffer revision against HEAD, if not done yet
+    pub fn refresh_line_changes(&mut self) {
+        self.line_changes.update(&self.buffer);
+    }
+
+    /// Change of each line of the current buffer since HEAD, as of the last
+    /// [`Self::refresh_line_changes`]
+    pub fn line_changes(&self) -> &[Option<LineChange>] {
+        self.line_changes.changes(&self.buffer)
+    }
+
+    /// Open the workspace search panel for the working directory
+    pub fn open_workspace_search(&mut self) {
+        let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        self.workspace_search = Some(WorkspaceSearchState::new(root));
+    }
+
+    /// Open workspace search panel, if any
+    pub fn workspace_search(&self) -> Option<&WorkspaceSearchState> {
+        self.workspace_search.as_ref()
+    }
+
+    /// Feed a key to the open workspace search panel, opening the chosen
+    /// match
+    pub fn workspace_search_input(&mut self, key: KeyEvent) {
+        let Some(search) = &mut self.workspace_search else {
+            return;
+        };
+        let keep_open = search.handle_key(key);
+        let chosen = search.take_open();
+        if !keep_open {
+            self.workspace_search = None;
+        }
+        if let Some((path, line, column)) = chosen {
+            if let Err(e) = self.open_location(&path, line, column) {
+                warn!("Cannot open {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Open `path` with the cursor at a 1-based line and column, switching
+    /// to its tab if it is already open
+    pub fn open_location(&mut self, path: &Path, line: usize, column: usize) -> Result<()> {
+        match self
+            .tabs
+            .iter()
+            .position(|tab| tab.path.as_deref() == Some(path))
+        {
+            Some(index) => self.switch_tab(index)?,
+            None => {
+                let buffer = self.open_buffer(path)?;
+                self.tabs
+                    .push(Tab::new(tab_title(path), Some(path.to_path_buf()), buffer));
+                self.switch_tab(self.tabs.len() - 1)?;
+                self.preload_next();
+                info!("Opened file: {:?}", path);
+            }
+        }
+
+        let line = line.saturating_sub(1);
+        if self
+            .cursor
+            .move_to(&self.buffer, line, column.saturating_sub(1))
+            .is_err()
+        {
+            // The file changed since it was searched
+            let last = self.buffer.len_lines().saturating_sub(1);
+            let _ = self.cursor.move_to(&self.buffer, line.min(last), 0);
+        }
+        Ok(())
+    }
+
+    // ==========================================
+    // Session
+    // ==========================================
+
+    /// Snapshot of the tabs with a file and of the panels, for `workspace`
+    pub fn session(&self, workspace: &Path) -> Session {
+        let mut tabs = Vec::new();
+        let mut active_tab = 0;
+        for (index, tab) in self.tabs.iter().enumerate() {
+            let Some(path) = &tab.path else {
+                continue;
+            };
+            let (buffer, cursor, view) = if index == self.active_tab_index {
+                active_tab = tabs.len();
+                (&self.buffer, &self.cursor, &self.view)
+            } else {
+                (&tab.buffer, &tab.cursor, &tab.view)
+            };
+            let position = cursor.position(buffer);
+            tabs.push(TabSession {
+                path: path.clone(),
+                line: position.line,
+                column: position.col,
+                scroll_line: view.scroll_line,
+                scroll_col: view.scroll_col,
+            });
+        }
+
+        Session {
+            workspace: workspace.to_path_buf(),
+            active_tab,
+            sidebar_visible: self.sidebar_visible,
+            terminal_visible: self.terminal_visible,
+            panel_sizes: self.panel_sizes,
+            tabs,
+        }
+    }
+
+    /// Reopen the tabs and panels of a saved session
+    ///
+    /// Files that can no longer be read are skipped; if none can, the
+    /// current tabs are kept.
+    pub fn restore_session(&mut self, session: &Session) {
+        let mut tabs = Vec::new();
+        let mut active = 0;
+        for (index, saved) in session.tabs.iter().enumerate() {
+            let buffer = match Buffer::from_file(&saved.path) {
+                Ok(buffer) => buffer,
+                Err(e) => {
+                    warn!("Not restoring {}: {}", saved.path.display(), e);
+                    continue;
+                }
+            };
+            if index == session.active_tab {
+                active = tabs.len();
+            }
+
+            let mut tab = Tab::new(tab_title(&saved.path), Some(saved.path.clone()), buffer);
+            // The file may have changed since the session was saved
+            let last = tab.buffer.len_lines().saturating_sub(1);
+            if tab
+                .cursor
+                .move_to(&tab.buffer, saved.line, saved.column)
+                .is_err()
+            {
+                let _ = tab.cursor.move_to(&tab.buffer, saved.line.min(last), 0);
+            }
+            tab.view.scroll_line = saved.scroll_line.min(last);
+            tab.view.scroll_col = saved.scroll_col;
+            tabs.push(tab);
+        }
+
+        if !tabs.is_empty() {
+            info!("Restored {} tabs", tabs.len());
+            self.tabs = tabs;
+            self.load_tab(active);
+        }
+        self.sidebar_visible = session.sidebar_visible;
+        self.terminal_visible = session.terminal_visible;
+        self.set_panel_sizes(session.panel_sizes);
+    }
+}
+
+/// Title of the tab showing `path`
+fn tab_title(path: &Path) -> String {
+    path.file_name()
+        .map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string())
+}
+
+/// Sidebar items for the entries of a walk below a directory at `level`
+///
+/// Each directory lists its subdirectories first, as the top level does.
+/// Directories above the walk's `depth` are loaded, so they show expanded.
+fn sidebar_children(walk: Walk, level: usize, depth: usize) -> Vec<SidebarItem> {
+    let mut entries = walk.entries;
+    entries.sort_by_cached_key(|entry| {
+        let mut parts: Vec<(bool, String)> = entry
+            .relative
+            .split('/')
+            .map(|part| (false, part.to_string()))
+            .collect();
+        if let Some(last) = parts.last_mut() {
+            last.0 = !entry.is_dir;
+        }
+        parts
+    });
+    entries
+        .into_iter()
+        .map(|entry| SidebarItem {
+            name: tab_title(&entry.path),
+            is_expanded: entry.is_dir && !entry.is_symlink && entry.depth < depth,
+            is_dir: entry.is_dir,
+            level: level + entry.depth,
+            path: entry.path,
+        })
+        .collect()
+}
+
+/// Agent started from the launcher, watched until its tmux session ends
+#[derive(Debug, Clone)]
+struct AgentRun {
+    agent: String,
+    task: String,
+    session: String,
+    /// AIT42 install the session was started from
+    ait42_root: PathBuf,
 }
 
 /// TUI Application
-pub struct TuiApp {
+///
+/// Generic over the ratatui backend so the same input handling and drawing
+/// can run headless on a `TestBackend` (see [`crate::harness`]).
+pub struct TuiApp<B: Backend = CrosstermBackend<Stdout>> {
     state: EditorState,
-    renderer: Renderer,
-    event_loop: EventLoop,
+    renderer: Renderer<B>,
     keybinds: KeyMap,
     theme: Theme,
+    /// Syntax tree of the current buffer, re-parsed before each frame
+    highlighter: SyntaxHighlighter,
+    /// Rainbow delimiters and scope highlighting, per file extension
+    syntax_config: SyntaxConfig,
     layout_config: LayoutConfig,
+    /// Workspace panel sizes are saved for, if any
+    workspace: Option<PathBuf>,
+    /// Panel sizes as last saved, to skip saving unchanged sizes
+    saved_panel_sizes: PanelSizes,
+    /// Command histories as last loaded or saved, to skip saving unchanged
+    /// histories
+    saved_history: SavedHistory,
+    /// Global marks as last loaded or saved, to skip saving unchanged marks
+    saved_marks: GlobalMarks,
+    /// File the session is saved to on exit, if sessions are restored
+    session_file: Option<PathBuf>,
+    /// File macros are loaded from and saved to, if any
+    macro_file: Option<PathBuf>,
+    /// Macros as last loaded or saved, to skip saving unchanged macros
+    saved_macros: Macros,
+    /// Panel whose resize handle is being dragged with the mouse
+    dragging: Option<Panel>,
+    /// Runs commands for the terminal panel
+    terminal: TerminalExecutor,
+    /// Agents started from the launcher that have not finished yet
+    agent_runs: Vec<AgentRun>,
+    /// When running agents were last checked
+    last_agent_poll: Instant,
+    /// Delay before the which-key popup appears after a prefix key
+    which_key_timeout: Duration,
+    /// Cached cheat-sheet entries generated from the key map
+    cheat_sheet_entries: Vec<KeyBindingEntry>,
+    /// Startup phases, completed when the first frame is drawn
+    startup: Option<StartupProfile>,
+    /// Whether a frame was drawn yet
+    painted: bool,
+    /// Writes recovery copies of unsaved buffers, if enabled
+    autosave: Option<Autosave>,
+    /// Reports changes in the workspace, if it is watched
+    watcher: Option<FileWatcher>,
 }
 
 impl TuiApp {
-    /// Create new TUI application
+    /// Create new TUI application with the key bindings of the user config
+    ///
+    /// Fails before touching the terminal if the config is invalid. Panel
+    /// sizes are those saved for the working directory. With
+    /// `editor.restore_session` the session is saved on exit.
     pub async fn new() -> Result<Self> {
+        let config = ConfigLoader::new()?.load().await?;
+        let keybinds = KeyMap::from_config(&config.keybindings)?;
+
+        let mut app = Self::with_renderer(Renderer::new()?)?;
+        app.set_keymap(keybinds);
+        app.state.set_focus_config(config.focus);
+        app.state.set_preload_config(config.preload);
+        app.set_syntax_config(config.syntax);
+        if let Ok(dir) = std::env::current_dir() {
+            if let Err(e) = app.state.sidebar_load_directory(&dir) {
+                warn!("Failed to list {}: {}", dir.display(), e);
+            }
+            app.set_workspace(dir);
+            app.watch_workspace();
+        }
+        if config.editor.restore_session {
+            app.set_session_file(Session::default_path().ok());
+        }
+        app.set_macro_file(Macros::default_path().ok());
+        app.set_autosave(
+            Autosave::default_dir()
+                .and_then(|dir| Autosave::from_delay_ms(dir, config.editor.auto_save_delay)),
+        );
+        Ok(app)
+    }
+}
+
+impl<B: Backend> TuiApp<B> {
+    /// Create a TUI application drawing to `backend`
+    ///
+    /// The terminal is left untouched; events are fed through
+    /// [`handle_event`](Self::handle_event) instead of [`run`](Self::run).
+    pub fn with_backend(backend: B) -> Result<Self> {
+        Self::with_renderer(Renderer::with_backend(backend)?)
+    }
+
+    fn with_renderer(renderer: Renderer<B>) -> Result<Self> {
         let config = EditorConfig::default();
         let state = EditorState::new(config)?;
-        let renderer = Renderer::new()?;
-        let event_loop = EventLoop::new(Duration::from_millis(250));
         let keybinds = KeyMap::default();
         let theme = Theme::default();
         let layout_config = LayoutConfig::default();
+        let cheat_sheet_entries = keybinds.all_bindings();
 
         Ok(Self {
             state,
             renderer,
-            event_loop,
             keybinds,
             theme,
+            highlighter: SyntaxHighlighter::new(),
+            syntax_config: SyntaxConfig::default(),
             layout_config,
+            workspace: None,
+            saved_panel_sizes: PanelSizes::default(),
+            saved_history: SavedHistory::default(),
+            saved_marks: GlobalMarks::new(),
+            session_file: None,
+            macro_file: None,
+            saved_macros: Macros::new(),
+            dragging: None,
+            terminal: TerminalExecutor::new(
+                std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            ),
+            agent_runs: Vec::new(),
+            last_agent_poll: Instant::now(),
+            which_key_timeout: Duration::from_millis(500),
+            cheat_sheet_entries,
+            startup: None,
+            painted: false,
+            autosave: None,
+            watcher: None,
         })
     }
 
+    /// Replace the key bindings
+    pub fn set_keymap(&mut self, keybinds: KeyMap) {
+        self.cheat_sheet_entries = keybinds.all_bindings();
+        self.keybinds = keybinds;
+    }
+
+    /// Set where rainbow delimiters and scope highlighting are shown
+    pub fn set_syntax_config(&mut self, config: SyntaxConfig) {
+        self.syntax_config = config;
+    }
+
+    /// Structural highlighting enabled for the current buffer
+    fn structure_highlight(&self) -> StructureHighlight {
+        let ext = self.state.buffer.language();
+        StructureHighlight {
+            rainbow_delimiters: self.syntax_config.rainbow_delimiters(ext),
+            scope: self.syntax_config.scope_highlight(ext),
+        }
+    }
+
+    /// Set the delay before the which-key popup appears
+    pub fn set_which_key_timeout(&mut self, timeout: Duration) {
+        self.which_key_timeout = timeout;
+    }
+
+    /// Use the panel sizes, command histories and global marks saved for
+    /// `workspace`, saving changes there
+    ///
+    /// Terminal commands run in `workspace` from then on.
+    pub fn set_workspace(&mut self, workspace: PathBuf) {
+        let sizes = PanelSizes::load(&workspace);
+        self.state.set_panel_sizes(sizes);
+        self.saved_panel_sizes = sizes;
+        let history = SavedHistory::load(&workspace);
+        self.state
+            .set_command_history(history.palette.clone(), history.ex.clone());
+        self.terminal.set_history(history.terminal.clone());
+        self.saved_history = history;
+        let marks = SavedMarks::load(&workspace).marks(&workspace);
+        self.state.set_global_marks(marks.clone());
+        self.saved_marks = marks;
+        self.terminal.set_current_dir(workspace.clone());
+        self.state.file_metadata.set_workspace(workspace.clone());
+        self.workspace = Some(workspace);
+    }
+
+    /// Follow changes in the workspace, keeping sidebar metadata current
+    ///
+    /// Needs a tokio runtime; without one the workspace is not watched.
+    pub fn watch_workspace(&mut self) {
+        let Some(workspace) = &self.workspace else {
+            return;
+        };
+        let watcher = FileWatcher::new().and_then(|mut watcher| {
+            watcher.watch(workspace, true)?;
+            Ok(watcher)
+        });
+        match watcher {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => warn!("Failed to watch {}: {}", workspace.display(), e),
+        }
+    }
+
+    /// Apply the file changes reported since the last tick
+    fn poll_file_events(&mut self) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        while let Some(event) = watcher.try_next_event() {
+            self.state.file_changed(&event);
+        }
+    }
+
+    /// Time the rest of startup in `profile`, up to the first frame
+    pub fn set_startup_profile(&mut self, profile: StartupProfile) {
+        self.startup = Some(profile);
+    }
+
+    /// Startup profile set with [`Self::set_startup_profile`]
+    pub fn take_startup_profile(&mut self) -> Option<StartupProfile> {
+        self.startup.take()
+    }
+
+    /// Write recovery copies of unsaved buffers with `autosave`, or not at
+    /// all with `None`
+    pub fn set_autosave(&mut self, autosave: Option<Autosave>) {
+        self.autosave = autosave;
+    }
+
+    /// Reopen buffers recovered after a crash, deleting their copies
+    pub fn recover(&mut self, files: &[RecoveryFile]) -> Result<()> {
+        for file in files {
+            self.state.open_recovered(file)?;
+            file.discard()?;
+        }
+        Ok(())
+    }
+
+    /// Write the recovery copies that are due
+    fn autosave(&mut self, now: Instant) {
+        let Some(autosave) = &mut self.autosave else {
+            return;
+        };
+        // The active tab's buffer is the one being edited, not its copy
+        let active = self.state.active_tab_index;
+        let buffers = std::iter::once(&self.state.buffer).chain(
+            self.state
+                .tabs
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != active)
+                .map(|(_, tab)| &tab.buffer),
+        );
+        if let Err(e) = autosave.observe_all(buffers, now) {
+            warn!("Failed to write recovery copies: {}", e);
+        }
+    }
+
+    /// Save the session to `path` on exit, or not at all with `None`
+    pub fn set_session_file(&mut self, path: Option<PathBuf>) {
+        self.session_file = path;
+    }
+
+    /// Load macros from `path` and save them there on exit, or neither with
+    /// `None`
+    pub fn set_macro_file(&mut self, path: Option<PathBuf>) {
+        let macros = path.as_deref().map(Macros::load).unwrap_or_default();
+        self.state.set_macros(macros.clone());
+        self.saved_macros = macros;
+        self.macro_file = path;
+    }
+
+    /// Reopen the tabs and panels of a saved session
+    pub fn restore_session(&mut self, session: &Session) {
+        self.state.restore_session(session);
+        self.sync_layout();
+    }
+
+    /// Save the session of the workspace, if sessions are saved
+    pub fn save_session(&self) -> Result<()> {
+        let (Some(path), Some(workspace)) = (&self.session_file, &self.workspace) else {
+            return Ok(());
+        };
+        self.state.session(workspace).save_to(path)
+    }
+
+    /// Save the panel sizes for the workspace if they changed
+    fn save_panel_sizes(&mut self) {
+        let sizes = self.state.panel_sizes();
+        if sizes == self.saved_panel_sizes {
+            return;
+        }
+        self.saved_panel_sizes = sizes;
+        if let Some(workspace) = &self.workspace {
+            if let Err(e) = sizes.save(workspace) {
+                warn!("Failed to save panel sizes: {}", e);
+            }
+        }
+    }
+
+    /// Save the command palette, `:` and terminal histories for the
+    /// workspace if they changed
+    pub fn save_history(&mut self) {
+        let history = SavedHistory {
+            palette: self.state.palette_history().to_vec(),
+            ex: self.state.ex_history().to_vec(),
+            terminal: self.terminal.history_entries().to_vec(),
+        };
+        if history == self.saved_history {
+            return;
+        }
+        if let Some(workspace) = &self.workspace {
+            if let Err(e) = history.save(workspace) {
+                warn!("Failed to save command history: {}", e);
+            }
+        }
+        self.saved_history = history;
+    }
+
+    /// Save the global marks for the workspace if they changed
+    pub fn save_marks(&mut self) {
+        let marks = self.state.global_marks();
+        if *marks == self.saved_marks {
+            return;
+        }
+        if let Some(workspace) = &self.workspace {
+            if let Err(e) = SavedMarks::new(marks, workspace).save(workspace) {
+                warn!("Failed to save marks: {}", e);
+            }
+        }
+        self.saved_marks = marks.clone();
+    }
+
+    /// Save the recorded macros if they changed
+    ///
+    /// A macro still being recorded is left out.
+    pub fn save_macros(&mut self) {
+        let macros = self.state.macros();
+        if macros.same_registers(&self.saved_macros) {
+            return;
+        }
+        if let Some(path) = &self.macro_file {
+            if let Err(e) = macros.save(path) {
+                warn!("Failed to save macros: {}", e);
+            }
+        }
+        self.saved_macros = macros.clone();
+    }
+
+    /// Bring the layout in line with the editor state
+    fn sync_layout(&mut self) {
+        self.layout_config.show_command_palette = self.state.show_command_palette;
+        self.layout_config.show_sidebar = self.state.sidebar_visible;
+        self.layout_config.show_terminal =
+            self.state.terminal_visible || self.state.results_visible;
+        self.layout_config.panel_sizes = self.state.panel_sizes;
+        self.layout_config.show_blame = self.state.blame_visible;
+    }
+
     /// Run the application event loop
     pub async fn run(&mut self) -> Result<()> {
         info!("Starting TUI application");
+        let mut event_loop = EventLoop::new(Duration::from_millis(250));
+
+        while self.state.running {
+            let first_frame = !self.painted;
+            self.draw()?;
+            if first_frame {
+                // Draw again at once, with the git gutters left out of the
+                // first frame
+                continue;
+            }
+
+            // Handle events
+            if let Some(event) = event_loop.next().await {
+                self.handle_event(event)?;
+            }
+            self.start_pending_agent().await;
+            self.run_terminal_commands().await;
+            self.poll_agent_runs().await;
+        }
+
+        if let Err(e) = self.save_session() {
+            warn!("Failed to save session: {}", e);
+        }
+        self.save_history();
+        self.save_marks();
+        self.save_macros();
+        // Edits left unsaved on exit were discarded on purpose
+        if let Some(autosave) = &mut self.autosave {
+            if let Err(e) = autosave.clear() {
+                warn!("Failed to remove recovery copies: {}", e);
+            }
+        }
+        info!("TUI application shutting down");
+        Ok(())
+    }
+
+    /// Draw one frame
+    pub fn draw(&mut self) -> Result<()> {
+        // Update view scroll to keep cursor visible
+        let size = self.renderer.size()?;
+        self.state.reveal_cursor();
+        let cursor_pos = self.state.cursor.display_position(&self.state.buffer);
+        self.state
+            .view
+            .update_scroll(cursor_pos.line, cursor_pos.col, size);
+
+        self.highlighter.update(&self.state.buffer);
+        // Git is not run before the first frame is on screen
+        if self.painted {
+            self.state.refresh_blame();
+            self.state.refresh_line_changes();
+        }
+        self.state.receive_preloaded();
+
+        // Update layout config
+        self.sync_layout();
+        if let Some(copy) = &mut self.state.terminal_copy {
+            if let Some(area) = EditorLayout::calculate(size, &self.layout_config).terminal {
+                copy.set_height(TerminalPanel::copy_mode_rows(area));
+            }
+        }
+
+        // Reading statuses fills their cache, so they are read before the
+        // popups borrow the state
+        let mut file_tree = FileTree::new(PathBuf::new());
+        for item in &self.state.sidebar_items {
+            let entry = if item.is_dir {
+                FileEntry::directory(item.path.clone(), item.level).expanded(item.is_expanded)
+            } else {
+                FileEntry::file(item.path.clone(), item.level)
+            };
+            // Git status is read once the first frame is on screen
+            let status = if self.painted {
+                self.state.file_metadata.status(&item.path)
+            } else {
+                None
+            };
+            file_tree.add_entry(entry.status(status));
+        }
+        let file_status = match self.state.buffer.path() {
+            Some(path) if self.painted => self.state.file_metadata.status(path),
+            _ => None,
+        };
+
+        // Popups on top of the editor
+        let which_key_entries = match self.state.pending_prefix() {
+            Some(pending) if self.state.which_key_visible() => {
+                self.keybinds.continuations(pending.mode, &pending.key)
+            }
+            _ => Vec::new(),
+        };
+        let which_key_prefix = self
+            .state
+            .pending_prefix()
+            .map(|p| p.key.to_string())
+            .unwrap_or_default();
+        let overlay = if let Some(panel) = self.state.stash_panel() {
+            Overlay::Stash(panel)
+        } else if let Some(panel) = self.state.hunk_panel() {
+            Overlay::Hunks(panel)
+        } else if let Some(panel) = self.state.log_panel() {
+            Overlay::Log(panel)
+        } else if let Some(launcher) = self.state.agent_launcher() {
+            Overlay::AgentLauncher(launcher)
+        } else if let Some(search) = self.state.workspace_search() {
+            Overlay::WorkspaceSearch(search)
+        } else if let Some(prompt) = self.state.search_prompt() {
+            Overlay::Search(prompt)
+        } else if self.state.show_cheat_sheet() {
+            Overlay::CheatSheet {
+                query: self.state.cheat_sheet_query(),
+                entries: &self.cheat_sheet_entries,
+            }
+        } else if !which_key_entries.is_empty() {
+            let pending = self.state.pending_prefix().expect("pending prefix");
+            Overlay::WhichKey {
+                prefix: &which_key_prefix,
+                label: self
+                    .keybinds
+                    .prefix_label(pending.mode, &pending.key)
+                    .unwrap_or_default(),
+                entries: &which_key_entries,
+            }
+        } else {
+            Overlay::None
+        };
+
+        let search_matches = self.state.search_matches();
+
+        self.renderer.render(
+            &self.state.buffer,
+            &self.state.cursor,
+            &self.state.selection,
+            &self.state.view,
+            &self.highlighter,
+            self.structure_highlight(),
+            &search_matches,
+            self.state.mode,
+            &self.theme,
+            &self.layout_config,
+            Panels {
+                file_tree: Some(&file_tree),
+                sidebar_selected: self.state.sidebar_selected,
+                terminal_output: self.terminal.get_output(),
+                terminal_scroll: self.state.terminal_scroll,
+                terminal_failed: self
+                    .terminal
+                    .last_status()
+                    .is_some_and(|status| status.failed()),
+                results: self
+                    .state
+                    .shows_results()
+                    .then_some(self.state.agent_results()),
+                results_scroll: self.state.results_scroll(),
+                terminal_copy: self.state.terminal_copy(),
+                blame: self.state.blame(),
+                line_changes: self.state.line_changes(),
+                file_status,
+                command_input: self.state.command_input(),
+                command_prompt: self.state.command_line().prompt(),
+                history_search: self.state.history_search(),
+                recording: self.state.macros().recording(),
+            },
+            overlay,
+        )?;
+
+        if !self.painted {
+            self.painted = true;
+            if let Some(profile) = &mut self.startup {
+                profile.mark(FIRST_PAINT);
+                debug!("First frame drawn after {:?}", profile.elapsed());
+            }
+        }
+        Ok(())
+    }
+
+    /// Editor state
+    pub fn state(&self) -> &EditorState {
+        &self.state
+    }
+
+    /// Mutable editor state
+    pub fn state_mut(&mut self) -> &mut EditorState {
+        &mut self.state
+    }
+
+    /// Renderer the application draws with
+    pub fn renderer(&self) -> &Renderer<B> {
+        &self.renderer
+    }
+
+    /// Mutable renderer the application draws with
+    pub fn renderer_mut(&mut self) -> &mut Renderer<B> {
+        &mut self.renderer
+    }
+
+    /// Start the agent chosen in the launcher, if one is waiting
+    async fn start_pending_agent(&mut self) {
+        let Some(launcher) = &mut self.state.agent_launcher else {
+            return;
+        };
+        let Some((agent, task)) = launcher.take_launch() else {
+            return;
+        };
 
-        while self.state.running {
-            // Update view scroll to keep cursor visible
-            let size = self.renderer.size()?;
-            let cursor_pos = self.state.cursor.position(&self.state.buffer);
-            self.state
-                .view
-                .update_scroll(cursor_pos.line, cursor_pos.col, size);
-
-            // Update layout config
-            self.layout_config.show_command_palette = self.state.show_command_palette;
-
-            // Render
-            self.renderer.render(
-                &self.state.buffer,
-                &self.state.cursor,
-                &self.state.view,
-                self.state.mode,
-                &self.theme,
-                &self.layout_config,
-            )?;
+        let tmux = TmuxManager::new(&launcher.config().ait42_root);
+        match tmux.start_agent(&agent, &task).await {
+            Ok(session) => {
+                info!("Started {} in tmux session {}", agent, session);
+                launcher.set_status(format!("Started {} in tmux session {}", agent, session));
+                self.agent_runs.push(AgentRun {
+                    agent,
+                    task,
+                    session,
+                    ait42_root: launcher.config().ait42_root.clone(),
+                });
+            }
+            Err(e) => {
+                warn!("Failed to start {}: {}", agent, e);
+                launcher.set_status(format!("Failed to start {}: {}", agent, e));
+            }
+        }
+    }
 
-            // Handle events
-            if let Some(event) = self.event_loop.next().await {
-                self.handle_event(event)?;
+    /// Run the commands queued from the editor in the terminal panel
+    async fn run_terminal_commands(&mut self) {
+        let commands = self.state.take_terminal_commands();
+        if !commands.is_empty() {
+            // Follow the output of the new commands
+            self.state.terminal_scroll = 0;
+        }
+        for command in commands {
+            if let Err(e) = self.terminal.execute(&command).await {
+                warn!("Failed to run {}: {}", command, e);
             }
         }
+    }
 
-        info!("TUI application shutting down");
-        Ok(())
+    /// Report agents whose tmux session completed, failed or closed
+    async fn poll_agent_runs(&mut self) {
+        if self.agent_runs.is_empty() || self.last_agent_poll.elapsed() < AGENT_POLL_INTERVAL {
+            return;
+        }
+        self.last_agent_poll = Instant::now();
+
+        let mut sessions: HashMap<PathBuf, Option<Vec<TmuxSession>>> = HashMap::new();
+        let mut running = Vec::new();
+        for run in std::mem::take(&mut self.agent_runs) {
+            if !sessions.contains_key(&run.ait42_root) {
+                let listed = match TmuxManager::new(&run.ait42_root).list_sessions().await {
+                    Ok(listed) => Some(listed),
+                    Err(e) => {
+                        warn!("Cannot list agent sessions: {}", e);
+                        None
+                    }
+                };
+                sessions.insert(run.ait42_root.clone(), listed);
+            }
+            let Some(listed) = &sessions[&run.ait42_root] else {
+                running.push(run);
+                continue;
+            };
+
+            match listed.iter().find(|session| session.id == run.session) {
+                Some(session) if session.status == SessionStatus::Running => running.push(run),
+                Some(session) => self.state.agent_finished(
+                    &run.agent,
+                    &run.task,
+                    &session.status,
+                    &session.output,
+                ),
+                // The session closes when the agent exits
+                None => {
+                    self.state
+                        .agent_finished(&run.agent, &run.task, &SessionStatus::Completed, &[])
+                }
+            }
+        }
+        self.agent_runs = running;
     }
 
     /// Handle an event
-    fn handle_event(&mut self, event: EditorEvent) -> Result<()> {
+    pub fn handle_event(&mut self, event: EditorEvent) -> Result<()> {
         match event {
             EditorEvent::Key(key) => self.handle_key(key)?,
+            EditorEvent::Mouse(mouse) => self.handle_mouse(mouse)?,
+            EditorEvent::Text(text) | EditorEvent::Paste(text) => {
+                if let Some(launcher) = &mut self.state.agent_launcher {
+                    launcher.insert_text(&text);
+                } else if let Some(search) = &mut self.state.workspace_search {
+                    search.insert_text(&text);
+                } else if self.state.search_prompt().is_some() {
+                    self.state.search_prompt_text(&text);
+                } else if self.state.stash_panel().is_some()
+                    || self.state.hunk_panel().is_some()
+                    || self.state.log_panel().is_some()
+                {
+                    debug!("Ignoring text input while a git panel is open");
+                } else if self.state.show_cheat_sheet() {
+                    self.state.cheat_sheet_query.push_str(&text);
+                } else if self.state.terminal_copy().is_some() {
+                    debug!("Ignoring text input in terminal copy mode");
+                } else {
+                    self.state.macros.record_text(&text);
+                    self.state.insert_text(&text);
+                }
+            }
             EditorEvent::Resize(w, h) => {
                 debug!("Terminal resized: {}x{}", w, h);
             }
             EditorEvent::Tick => {
-                // Periodic refresh (no-op for now)
+                self.state
+                    .update_which_key(Instant::now(), self.which_key_timeout);
+                self.autosave(Instant::now());
+                self.poll_file_events();
             }
             EditorEvent::Quit => {
                 self.state.running = false;
             }
+        }
+
+        self.state
+            .open_requested_terminal_copy(self.terminal.get_output());
+        if let Some(text) = self.state.take_clipboard() {
+            if let Err(e) = self.renderer.copy_to_clipboard(&text) {
+                warn!("Failed to copy to the clipboard: {}", e);
+            }
+        }
+
+        // Sizes are saved once a drag ends rather than on every step
+        if self.dragging.is_none() {
+            self.save_panel_sizes();
+        }
+        Ok(())
+    }
+
+    /// Handle mouse input: dragging the edge of a panel resizes it
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        self.sync_layout();
+        let layout = EditorLayout::calculate(self.renderer.size()?, &self.layout_config);
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.dragging = layout.resize_handle_at(mouse.column, mouse.row);
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(panel) = self.dragging {
+                    let percent = layout.drag_percent(panel, mouse.column, mouse.row);
+                    self.state.panel_sizes.set_percent(panel, percent);
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.dragging = None,
             _ => {}
         }
         Ok(())
@@ -712,8 +3178,88 @@ impl TuiApp {
             return Ok(());
         }
 
-        // Look up command based on focused panel
+        // Panels capture all input while open
+        if self.state.stash_panel().is_some() {
+            self.state.stash_panel_input(key);
+            return Ok(());
+        }
+        if self.state.hunk_panel().is_some() {
+            self.state.hunk_panel_input(key);
+            return Ok(());
+        }
+        if self.state.log_panel().is_some() {
+            self.state.log_panel_input(key);
+            return Ok(());
+        }
+        if self.state.agent_launcher().is_some() {
+            self.state.agent_launcher_input(key);
+            return Ok(());
+        }
+        if self.state.workspace_search().is_some() {
+            self.state.workspace_search_input(key);
+            return Ok(());
+        }
+        if self.state.search_prompt().is_some() {
+            self.state.search_prompt_input(key);
+            return Ok(());
+        }
+        if self.state.show_cheat_sheet() {
+            self.state.cheat_sheet_input(key);
+            return Ok(());
+        }
+        if self.state.terminal_copy().is_some() {
+            self.state.terminal_copy_input(key);
+            return Ok(());
+        }
+
+        if self.state.register_prompt().is_some() {
+            return self.state.register_input(key);
+        }
+
         let key_binding = KeyBinding::from_key_event(key);
+
+        // Complete a pending prefix sequence
+        if let Some(pending) = self.state.take_pending_prefix() {
+            if key.code != KeyCode::Esc {
+                match self
+                    .keybinds
+                    .lookup_sequence(pending.mode, &pending.key, &key_binding)
+                {
+                    Some(command) => self.state.run_command(command)?,
+                    // Not a sequence after all: type the prefix, then the key
+                    None if pending.mode == Mode::Insert => {
+                        if let KeyCode::Char(ch) = pending.key.code {
+                            self.state.run_command(&EditorCommand::InsertChar(ch))?;
+                        }
+                        return self.handle_key(key);
+                    }
+                    None => debug!("Unbound sequence: {} {}", pending.key, key_binding),
+                }
+            }
+            return Ok(());
+        }
+
+        // A count before a command in normal mode, `0` on its own being a
+        // command
+        if let KeyCode::Char(digit @ '0'..='9') = key.code {
+            if self.state.mode == Mode::Normal
+                && self.state.focused_panel() == FocusedPanel::Editor
+                && key.modifiers.is_empty()
+                && (digit != '0' || self.state.pending_count().is_some())
+            {
+                self.state.push_count_digit(digit.to_digit(10).unwrap_or(0));
+                return Ok(());
+            }
+        }
+
+        if self.state.focused_panel() != FocusedPanel::Sidebar
+            && self.keybinds.is_prefix(self.state.mode, &key_binding)
+        {
+            self.state.begin_prefix(key_binding);
+            return Ok(());
+        }
+
+        // Look up command based on focused panel
         let command = match self.state.focused_panel() {
             FocusedPanel::Sidebar => {
                 // Try sidebar-specific bindings first, fall back to normal mode
@@ -727,12 +3273,12 @@ impl TuiApp {
         };
 
         if let Some(command) = command {
-            self.state.execute_command(command)?;
+            self.state.run_command(command)?;
         } else {
             // Handle character input in insert mode
             if self.state.mode == Mode::Insert || self.state.mode == Mode::Command {
                 if let KeyCode::Char(ch) = key.code {
-                    self.state.execute_command(&EditorCommand::InsertChar(ch))?;
+                    self.state.run_command(&EditorCommand::InsertChar(ch))?;
                 }
             } else {
                 debug!("Unbound key: {:?}", key);
@@ -756,6 +3302,8 @@ impl TuiApp {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ait42_git::repo::test_support::{commit_file, init_repo};
+    use crossterm::event::KeyModifiers;
 
     #[test]
     fn test_editor_state_creation() {
@@ -805,6 +3353,140 @@ mod tests {
         assert_eq!(new_pos.line, 1);
     }
 
+    #[test]
+    fn test_insert_multibyte_text() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+
+        state
+            .execute_command(&EditorCommand::EnterInsertMode)
+            .unwrap();
+        state.insert_text("日本");
+        state.execute_command(&EditorCommand::InsertChar('語')).unwrap();
+
+        assert_eq!(state.buffer.to_string(), "日本語");
+        assert_eq!(state.cursor.pos(), "日本語".len());
+        assert_eq!(state.cursor.display_position(&state.buffer).col, 6);
+
+        state.execute_command(&EditorCommand::Backspace).unwrap();
+        assert_eq!(state.buffer.to_string(), "日本");
+        assert_eq!(state.cursor.display_position(&state.buffer).col, 4);
+    }
+
+    #[test]
+    fn test_undo_coalesces_insert_session() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+
+        state
+            .execute_command(&EditorCommand::EnterInsertMode)
+            .unwrap();
+        for ch in "foo".chars() {
+            state.execute_command(&EditorCommand::InsertChar(ch)).unwrap();
+        }
+        // Moving the cursor starts a new undo step
+        state.execute_command(&EditorCommand::MoveLeft).unwrap();
+        state.execute_command(&EditorCommand::MoveRight).unwrap();
+        state.execute_command(&EditorCommand::InsertChar('!')).unwrap();
+        state
+            .execute_command(&EditorCommand::EnterNormalMode)
+            .unwrap();
+
+        // A second insert session is its own step as well
+        state
+            .execute_command(&EditorCommand::EnterInsertMode)
+            .unwrap();
+        state.insert_text(" bar");
+        state.execute_command(&EditorCommand::Backspace).unwrap();
+        state
+            .execute_command(&EditorCommand::EnterNormalMode)
+            .unwrap();
+        assert_eq!(state.buffer.to_string(), "foo! ba");
+
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), "foo! bar");
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), "foo!");
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), "foo");
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), "");
+        assert_eq!(state.cursor.pos(), 0);
+
+        state.execute_command(&EditorCommand::Redo).unwrap();
+        assert_eq!(state.buffer.to_string(), "foo");
+    }
+
+    #[test]
+    fn test_edits_outside_insert_mode_do_not_coalesce() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+
+        let id = state.buffer.id();
+        state
+            .apply_edit(Box::new(InsertCommand::new(id, 0, "a")))
+            .unwrap();
+        state
+            .apply_edit(Box::new(InsertCommand::new(id, 1, "b")))
+            .unwrap();
+        assert_eq!(state.buffer.to_string(), "ab");
+
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), "a");
+    }
+
+    #[test]
+    fn test_structural_commands() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+        let source = "fn f() { call(a, b) }";
+        state.buffer = Buffer::from_string(source.to_string(), Some("rs".to_string()));
+        state.cursor.set_pos(source.find("(a").unwrap() + 1);
+
+        state
+            .execute_command(&EditorCommand::SwapArgumentForward)
+            .unwrap();
+        assert_eq!(state.buffer.to_string(), "fn f() { call(b, a) }");
+        assert_eq!(state.cursor.pos(), source.find("b)").unwrap());
+
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), source);
+
+        // Expanding the selection twice reaches the call expression
+        state
+            .execute_command(&EditorCommand::SelectEnclosingNode)
+            .unwrap();
+        state
+            .execute_command(&EditorCommand::SelectEnclosingNode)
+            .unwrap();
+        assert_eq!(state.mode, Mode::Visual);
+        let range = state.target_range();
+        assert_eq!(&source[range], "(a, b)");
+
+        // Raising an argument replaces the whole call
+        state.execute_command(&EditorCommand::EnterNormalMode).unwrap();
+        state.cursor.set_pos(source.find("(a").unwrap() + 1);
+        state.execute_command(&EditorCommand::RaiseNode).unwrap();
+        assert_eq!(state.buffer.to_string(), "fn f() { a }");
+    }
+
+    #[test]
+    fn test_cursor_movement_over_wide_chars() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+        state.buffer.insert(0, "日本語\nabcdefg").unwrap();
+
+        state.execute_command(&EditorCommand::MoveRight).unwrap();
+        assert_eq!(state.cursor.pos(), "日".len());
+
+        state.execute_command(&EditorCommand::MoveDown).unwrap();
+        assert_eq!(state.cursor.display_position(&state.buffer).col, 2);
+
+        state.execute_command(&EditorCommand::MoveLineEnd).unwrap();
+        state.execute_command(&EditorCommand::MoveRight).unwrap();
+        assert_eq!(state.cursor.display_position(&state.buffer).col, 7);
+    }
+
     #[test]
     fn test_command_palette_toggle() {
         let config = EditorConfig::default();
@@ -868,6 +3550,133 @@ mod tests {
         assert!(state.sidebar_visible());
     }
 
+    #[test]
+    fn test_blame_reloads_after_save() {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+        let path = dir.path().join("a.txt");
+
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state.load_file(path).unwrap();
+        state.refresh_blame();
+        assert!(state.blame().is_empty());
+
+        state.execute_command(&EditorCommand::ToggleBlame).unwrap();
+        assert!(state.blame_visible());
+        state.refresh_blame();
+        assert_eq!(state.blame()[0].author, "Test");
+
+        // The saved line is blamed on the working tree
+        state.buffer.insert(4, "two\n").unwrap();
+        state.refresh_blame();
+        assert_eq!(state.blame().len(), 1);
+        state.execute_command(&EditorCommand::Save).unwrap();
+        state.refresh_blame();
+        assert_eq!(state.blame().len(), 2);
+        assert!(state.blame()[1].is_uncommitted());
+    }
+
+    #[test]
+    fn test_opening_a_file_preloads_its_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main.rs");
+        let util = dir.path().join("util.rs");
+        std::fs::write(&main, "mod util;\n").unwrap();
+        std::fs::write(&util, "pub fn f() {}\n").unwrap();
+
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state.open_location(&main, 1, 1).unwrap();
+        let start = Instant::now();
+        while !state.preloader.contains(&util) && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+            state.receive_preloaded();
+        }
+        assert!(state.preloader.contains(&util));
+
+        // Opening it takes the warm buffer
+        state.open_location(&util, 1, 1).unwrap();
+        assert!(!state.preloader.contains(&util));
+        assert_eq!(state.buffer.to_string(), "pub fn f() {}\n");
+        assert_eq!(state.tabs().len(), 3);
+    }
+
+    #[test]
+    fn test_line_changes_follow_edits() {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\ntwo\n", "initial");
+        let path = dir.path().join("a.txt");
+
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state.load_file(path).unwrap();
+        state.refresh_line_changes();
+        assert_eq!(state.line_changes(), &[None, None]);
+
+        state.buffer.insert(0, "zero\n").unwrap();
+        state.refresh_line_changes();
+        assert_eq!(state.line_changes(), &[Some(LineChange::Added), None, None]);
+
+        // Saving does not commit, so the line stays marked
+        state.execute_command(&EditorCommand::Save).unwrap();
+        state.refresh_line_changes();
+        assert_eq!(state.line_changes(), &[Some(LineChange::Added), None, None]);
+    }
+
+    #[test]
+    fn test_first_frame_is_drawn_before_git_runs() {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "zero\none\n").unwrap();
+
+        let backend = ratatui::backend::TestBackend::new(80, 24);
+        let mut app = TuiApp::with_backend(backend).unwrap();
+        app.set_startup_profile(StartupProfile::new());
+        app.load_file(path).unwrap();
+
+        app.draw().unwrap();
+        assert!(app.state().line_changes().is_empty());
+        app.draw().unwrap();
+        assert_eq!(app.state().line_changes(), &[Some(LineChange::Added), None]);
+
+        // Only the first frame ends the first paint phase
+        let profile = app.take_startup_profile().unwrap();
+        assert_eq!(profile.phases().len(), 1);
+        assert!(profile.first_paint().is_some());
+    }
+
+    #[test]
+    fn test_autosave_and_recovery() {
+        use ait42_core::autosave::recoverable;
+
+        let dir = tempfile::tempdir().unwrap();
+        let recovery = dir.path().join("recovery");
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, "one\n").unwrap();
+
+        let backend = ratatui::backend::TestBackend::new(80, 24);
+        let mut app = TuiApp::with_backend(backend).unwrap();
+        app.set_autosave(Some(Autosave::new(&recovery, Duration::ZERO)));
+        app.load_file(path.clone()).unwrap();
+        app.handle_event(EditorEvent::Tick).unwrap();
+        assert!(recoverable(&recovery).is_empty());
+
+        app.state.buffer.insert(0, "zero\n").unwrap();
+        app.handle_event(EditorEvent::Tick).unwrap();
+        let files = recoverable(&recovery);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].content, "zero\none\n");
+
+        // The next session reopens the edits and deletes the copy
+        let backend = ratatui::backend::TestBackend::new(80, 24);
+        let mut app = TuiApp::with_backend(backend).unwrap();
+        app.recover(&files).unwrap();
+        assert!(recoverable(&recovery).is_empty());
+        assert!(app.state().buffer().is_dirty());
+        assert_eq!(app.state().buffer().to_string(), "zero\none\n");
+        let tab = app.state().tabs().last().unwrap();
+        assert_eq!((tab.title.as_str(), tab.is_modified), ("notes.md", true));
+    }
+
     #[test]
     fn test_terminal_visibility() {
         let config = EditorConfig::default();
@@ -929,6 +3738,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sidebar_expand_and_collapse() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/widgets")).unwrap();
+        std::fs::write(dir.path().join("src/widgets/tree.rs"), "").unwrap();
+        std::fs::write(dir.path().join("src/a.rs"), "").unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state
+            .sidebar_load_directory(&dir.path().to_path_buf())
+            .unwrap();
+        let names = |state: &EditorState| -> Vec<(String, usize)> {
+            state
+                .sidebar_items()
+                .iter()
+                .map(|item| (item.name.clone(), item.level))
+                .collect()
+        };
+        assert_eq!(state.sidebar_items()[0].name, "src");
+
+        state.sidebar_toggle_expand();
+        assert_eq!(
+            names(&state),
+            [
+                ("src".into(), 0),
+                ("widgets".into(), 1),
+                ("a.rs".into(), 1),
+                ("README.md".into(), 0)
+            ]
+        );
+        assert!(!state.sidebar_items()[1].is_expanded);
+
+        state.sidebar_toggle_expand();
+        assert_eq!(names(&state), [("src".into(), 0), ("README.md".into(), 0)]);
+
+        state.sidebar_expand_all();
+        assert_eq!(
+            names(&state),
+            [
+                ("src".into(), 0),
+                ("widgets".into(), 1),
+                ("tree.rs".into(), 2),
+                ("a.rs".into(), 1),
+                ("README.md".into(), 0)
+            ]
+        );
+        assert!(state.sidebar_items()[1].is_expanded);
+    }
+
+    #[test]
+    fn test_sidebar_reload_uses_cached_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+        let root = dir.path().to_path_buf();
+
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state.sidebar_load_directory(&root).unwrap();
+        state.sidebar_load_directory(&root).unwrap();
+        assert_eq!(state.file_metadata.reads(), 2);
+
+        // A directory replacing a file is picked up once reported
+        std::fs::remove_file(root.join("README.md")).unwrap();
+        std::fs::create_dir(root.join("README.md")).unwrap();
+        state.file_changed(&FileEvent::Created(root.join("README.md")));
+        state.sidebar_load_directory(&root).unwrap();
+        assert_eq!(state.file_metadata.reads(), 3);
+        assert!(state.sidebar_items().iter().all(|item| item.is_dir));
+    }
+
     #[test]
     fn test_focus_cycle() {
         let config = EditorConfig::default();
@@ -953,6 +3833,235 @@ mod tests {
         assert_eq!(state.focused_panel(), FocusedPanel::Editor);
     }
 
+    #[test]
+    fn test_run_in_terminal_focuses_terminal() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+        state.execute_command(&EditorCommand::EnterInsertMode).unwrap();
+        state.insert_text("  cargo test  \n");
+        state.execute_command(&EditorCommand::EnterNormalMode).unwrap();
+        state.cursor.set_pos(0);
+
+        state.execute_command(&EditorCommand::RunInTerminal).unwrap();
+        assert_eq!(state.take_terminal_commands(), ["cargo test"]);
+        assert!(state.terminal_visible());
+        assert_eq!(state.focused_panel(), FocusedPanel::Terminal);
+
+        // Returning closes the terminal again, as it was opened for the command
+        state.execute_command(&EditorCommand::ReturnFocus).unwrap();
+        assert_eq!(state.focused_panel(), FocusedPanel::Editor);
+        assert!(!state.terminal_visible());
+
+        // Empty lines run nothing
+        state.cursor.set_pos(state.buffer.len_bytes());
+        state.execute_command(&EditorCommand::RunInTerminal).unwrap();
+        assert!(state.take_terminal_commands().is_empty());
+        assert!(!state.terminal_visible());
+    }
+
+    #[test]
+    fn test_agent_results_focus() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+        state.toggle_terminal();
+        state.focus_sidebar();
+
+        let output = vec!["line".to_string(); AGENT_RESULT_LINES + 10];
+        state.agent_finished("tester", "write tests", &SessionStatus::Completed, &output);
+        assert!(state.results_visible());
+        assert!(state.shows_results());
+        assert_eq!(state.focused_panel(), FocusedPanel::Results);
+        assert_eq!(state.agent_results()[0], "✓ tester finished: write tests");
+        assert_eq!(state.agent_results().len(), AGENT_RESULT_LINES + 2);
+
+        // A second run keeps the focus to return to from before the first
+        state.agent_finished(
+            "reviewer",
+            "review",
+            &SessionStatus::Failed("Error: boom".to_string()),
+            &[],
+        );
+        let failure = "✗ reviewer failed: review (Error: boom)".to_string();
+        assert!(state.agent_results().contains(&failure));
+        state.return_focus();
+        assert_eq!(state.focused_panel(), FocusedPanel::Sidebar);
+        assert!(!state.results_visible());
+        assert!(state.terminal_visible());
+
+        // Nothing left to return to
+        state.return_focus();
+        assert_eq!(state.focused_panel(), FocusedPanel::Sidebar);
+    }
+
+    #[test]
+    fn test_auto_focus_can_be_disabled() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+        state.set_focus_config(FocusConfig {
+            on_terminal_command: false,
+            on_agent_complete: false,
+        });
+        state.execute_command(&EditorCommand::EnterInsertMode).unwrap();
+        state.insert_text("ls");
+        state.execute_command(&EditorCommand::EnterNormalMode).unwrap();
+
+        state.execute_command(&EditorCommand::RunInTerminal).unwrap();
+        state.agent_finished("tester", "task", &SessionStatus::Completed, &[]);
+
+        // The panels open without taking focus
+        assert_eq!(state.take_terminal_commands(), ["ls"]);
+        assert!(state.terminal_visible());
+        assert!(state.results_visible());
+        assert!(!state.shows_results());
+        assert_eq!(state.focused_panel(), FocusedPanel::Editor);
+
+        state.return_focus();
+        assert!(state.terminal_visible());
+        assert!(state.results_visible());
+
+        // Results join the focus cycle once shown
+        state.toggle_sidebar();
+        state.focus_terminal();
+        state.focus_next_panel();
+        assert_eq!(state.focused_panel(), FocusedPanel::Results);
+        assert!(state.shows_results());
+        state.toggle_results();
+        assert_eq!(state.focused_panel(), FocusedPanel::Editor);
+    }
+
+    #[test]
+    fn test_incremental_search() {
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state.buffer = Buffer::from_string("one Two\ntwo three two".to_string(), None);
+        state.cursor.set_pos(1);
+
+        state.execute_command(&EditorCommand::Search).unwrap();
+        state.search_prompt_text("two");
+        // Smart case: lowercase matches every spelling, the cursor previews
+        assert_eq!(state.search_matches(), vec![4..7, 8..11, 18..21]);
+        assert_eq!(state.cursor.pos(), 4);
+
+        // Esc puts the cursor back and drops the highlights
+        state.search_prompt_input(KeyEvent::from(KeyCode::Esc));
+        assert!(state.search_prompt().is_none());
+        assert_eq!(state.cursor.pos(), 1);
+        assert!(state.search_matches().is_empty());
+
+        state.execute_command(&EditorCommand::Search).unwrap();
+        state.search_prompt_text("Two");
+        state.search_prompt_text("|two");
+        state.search_prompt_input(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        state.search_prompt_input(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(state.search_matches(), vec![4..7, 8..11, 18..21]);
+
+        state.execute_command(&EditorCommand::SearchNext).unwrap();
+        assert_eq!(state.cursor.pos(), 8);
+        state.execute_command(&EditorCommand::SearchPrevious).unwrap();
+        state.execute_command(&EditorCommand::SearchPrevious).unwrap();
+        assert_eq!(state.cursor.pos(), 18);
+    }
+
+    #[test]
+    fn test_replace_in_selection_is_one_undo_step() {
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        let original = "f(1) f(2)\nf(3)";
+        state.buffer = Buffer::from_string(original.to_string(), None);
+
+        state.execute_command(&EditorCommand::EnterVisualMode).unwrap();
+        state.selection.add_range(SelectionRange::new(
+            CursorPosition::new(0, 0),
+            CursorPosition::new(0, 9),
+        ));
+        state.execute_command(&EditorCommand::Replace).unwrap();
+        state.search_prompt_input(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        state.search_prompt_text(r"f\((\d)\)");
+        state.search_prompt_input(KeyEvent::from(KeyCode::Enter));
+        state.search_prompt_text("g($1)");
+        state.search_prompt_input(KeyEvent::from(KeyCode::Enter));
+
+        assert!(state.search_prompt().is_none());
+        assert_eq!(state.mode, Mode::Normal);
+        assert_eq!(state.buffer.to_string(), "g(1) g(2)\nf(3)");
+
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), original);
+    }
+
+    #[test]
+    fn test_convert_line_endings_and_encoding() {
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state.buffer = Buffer::from_string("one\ntwo\n".to_string(), None);
+        state.cursor.set_pos(5);
+
+        state
+            .execute_command(&EditorCommand::ConvertLineEndings)
+            .unwrap();
+        assert_eq!(state.buffer.to_string(), "one\r\ntwo\r\n");
+        assert_eq!(state.buffer.line_ending(), ait42_core::LineEnding::CrLf);
+        // The cursor stays on the same character
+        assert_eq!(state.cursor.pos(), 6);
+
+        state.execute_command(&EditorCommand::Undo).unwrap();
+        assert_eq!(state.buffer.to_string(), "one\ntwo\n");
+
+        state
+            .execute_command(&EditorCommand::ConvertEncoding)
+            .unwrap();
+        assert_eq!(state.buffer.encoding(), Encoding::Utf16Le);
+        assert!(state.buffer.has_bom());
+
+        // Latin-1 is skipped for text it cannot represent
+        state.buffer.insert(0, "日本").unwrap();
+        state
+            .execute_command(&EditorCommand::ConvertEncoding)
+            .unwrap();
+        state
+            .execute_command(&EditorCommand::ConvertEncoding)
+            .unwrap();
+        assert_eq!(state.buffer.encoding(), Encoding::Utf8);
+    }
+
+    #[test]
+    fn test_show_keybindings_toggles_cheat_sheet() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+
+        state
+            .execute_command(&EditorCommand::ShowKeybindings)
+            .unwrap();
+        assert!(state.show_cheat_sheet());
+
+        state.cheat_sheet_input(KeyEvent::from(KeyCode::Char('s')));
+        state.cheat_sheet_input(KeyEvent::from(KeyCode::Char('a')));
+        state.cheat_sheet_input(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(state.cheat_sheet_query(), "s");
+
+        state.cheat_sheet_input(KeyEvent::from(KeyCode::Esc));
+        assert!(!state.show_cheat_sheet());
+        assert_eq!(state.cheat_sheet_query(), "");
+    }
+
+    #[test]
+    fn test_which_key_appears_after_timeout() {
+        let config = EditorConfig::default();
+        let mut state = EditorState::new(config).unwrap();
+        let space = KeyBinding::new(KeyCode::Char(' '), crossterm::event::KeyModifiers::NONE);
+        let timeout = Duration::from_millis(500);
+
+        state.begin_prefix(space.clone());
+        state.update_which_key(Instant::now(), timeout);
+        assert!(!state.which_key_visible());
+
+        state.update_which_key(Instant::now() + timeout, timeout);
+        assert!(state.which_key_visible());
+
+        let pending = state.take_pending_prefix().unwrap();
+        assert_eq!(pending.key, space);
+        assert_eq!(pending.mode, Mode::Normal);
+        assert!(!state.which_key_visible());
+        assert!(state.pending_prefix().is_none());
+    }
+
     #[test]
     fn test_tab_closing_last_tab() {
         let config = EditorConfig::default();
@@ -965,4 +4074,39 @@ mod tests {
         state.close_tab(0).unwrap();
         assert_eq!(state.tabs().len(), 1);
     }
+
+    #[test]
+    fn test_session_restores_tabs_and_cursors() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        let main = dir.path().join("main.rs");
+        std::fs::write(&notes, "one\ntwo\nthree\n").unwrap();
+        std::fs::write(&main, "fn main() {\n    run();\n}\n").unwrap();
+
+        let mut state = EditorState::new(EditorConfig::default()).unwrap();
+        state.open_location(&notes, 3, 2).unwrap();
+        state.open_location(&main, 2, 5).unwrap();
+        state.prev_tab();
+        // Each tab keeps its own cursor
+        assert_eq!(state.cursor().position(state.buffer()).line, 2);
+        state.next_tab();
+        state.toggle_terminal();
+
+        let session = state.session(dir.path());
+        assert_eq!(session.tabs.len(), 2);
+        assert_eq!(session.active_tab, 1);
+        assert_eq!((session.tabs[0].line, session.tabs[0].column), (2, 1));
+        assert!(session.terminal_visible);
+
+        std::fs::remove_file(&notes).unwrap();
+        let mut restored = EditorState::new(EditorConfig::default()).unwrap();
+        restored.restore_session(&session);
+
+        // The missing file is skipped, the other reopened where it was left
+        assert_eq!(restored.tabs().len(), 1);
+        assert_eq!(restored.tabs()[0].path.as_deref(), Some(main.as_path()));
+        let position = restored.cursor().position(restored.buffer());
+        assert_eq!((position.line, position.col), (1, 4));
+        assert!(restored.terminal_visible());
+    }
 }
@@ -0,0 +1,336 @@
+//! Agent Launcher Widget
+//!
+//! Prompt for a task description with the agents recommended for it listed
+//! underneath. The ranking follows the text as it is typed, so the best
+//! agent is already selected when the task is complete; Enter starts it.
+
+use crate::theme::Theme;
+use ait42_ait42::{AIT42Config, AgentRegistry, Recommendation};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+/// Recommendations listed for a task
+const MAX_RECOMMENDATIONS: usize = 10;
+
+const HINTS: &str = "type the task  ↑/↓ select  Enter launch  Esc close";
+
+/// Agent launcher state
+#[derive(Debug)]
+pub struct AgentLauncherState {
+    config: AIT42Config,
+    registry: AgentRegistry,
+    task: String,
+    recommendations: Vec<Recommendation>,
+    selected: usize,
+    launch: Option<(String, String)>,
+    status: Option<String>,
+}
+
+impl AgentLauncherState {
+    /// Open the launcher with the agents of the configured AIT42 install
+    pub fn open() -> ait42_ait42::Result<Self> {
+        let config = AIT42Config::load()?;
+        let registry = AgentRegistry::load_from_directory(&config.agents_dir())?;
+        Ok(Self::with_registry(config, registry))
+    }
+
+    /// Open the launcher over an already loaded registry
+    pub fn with_registry(config: AIT42Config, registry: AgentRegistry) -> Self {
+        let mut state = Self {
+            config,
+            registry,
+            task: String::new(),
+            recommendations: Vec::new(),
+            selected: 0,
+            launch: None,
+            status: None,
+        };
+        state.update_recommendations();
+        state
+    }
+
+    /// AIT42 configuration the agents come from
+    pub fn config(&self) -> &AIT42Config {
+        &self.config
+    }
+
+    /// Task typed so far
+    pub fn task(&self) -> &str {
+        &self.task
+    }
+
+    /// Agents for the task, best first
+    ///
+    /// Lists every agent by name while nothing in the task matches.
+    pub fn recommendations(&self) -> &[Recommendation] {
+        &self.recommendations
+    }
+
+    /// Index of the selected agent
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Result of the last launch
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Report the result of a launch
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = Some(status.into());
+    }
+
+    /// Agent and task to start, once Enter was pressed
+    pub fn take_launch(&mut self) -> Option<(String, String)> {
+        self.launch.take()
+    }
+
+    fn update_recommendations(&mut self) {
+        self.recommendations = self.registry.recommend(&self.task, MAX_RECOMMENDATIONS);
+        if self.recommendations.is_empty() {
+            let mut names: Vec<&str> = self
+                .registry
+                .list()
+                .into_iter()
+                .map(|agent| agent.name.as_str())
+                .collect();
+            names.sort_unstable();
+            self.recommendations = names
+                .into_iter()
+                .map(|name| Recommendation {
+                    agent: name.to_string(),
+                    score: 0.0,
+                    rationale: String::new(),
+                })
+                .collect();
+        }
+        self.selected = 0;
+    }
+
+    /// Append typed or pasted text to the task
+    pub fn insert_text(&mut self, text: &str) {
+        self.task.push_str(&text.replace(['\r', '\n'], " "));
+        self.update_recommendations();
+    }
+
+    fn select_relative(&mut self, delta: isize) {
+        let last = self.recommendations.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Handle a key press; returns `false` when the launcher should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Down => self.select_relative(1),
+            KeyCode::Up => self.select_relative(-1),
+            KeyCode::Char('n') if ctrl => self.select_relative(1),
+            KeyCode::Char('p') if ctrl => self.select_relative(-1),
+            KeyCode::Backspace => {
+                self.task.pop();
+                self.update_recommendations();
+            }
+            KeyCode::Enter => match self.recommendations.get(self.selected) {
+                _ if self.task.trim().is_empty() => {
+                    self.status = Some("Describe the task first".to_string());
+                }
+                Some(choice) => {
+                    self.status = Some(format!("Starting {}...", choice.agent));
+                    self.launch = Some((choice.agent.clone(), self.task.trim().to_string()));
+                }
+                None => self.status = Some("No agents available".to_string()),
+            },
+            KeyCode::Char(ch) if !ctrl => self.insert_text(&ch.to_string()),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Agent launcher widget
+pub struct AgentLauncher<'a> {
+    state: &'a AgentLauncherState,
+    theme: &'a Theme,
+}
+
+impl<'a> AgentLauncher<'a> {
+    /// Create a launcher rendering `state`
+    pub fn new(state: &'a AgentLauncherState, theme: &'a Theme) -> Self {
+        Self { state, theme }
+    }
+
+    fn fg(&self, style: Style) -> Style {
+        Style::default().fg(style.fg.unwrap_or(self.theme.foreground))
+    }
+}
+
+impl<'a> Widget for AgentLauncher<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 30 || area.height < 6 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(" Launch agent ");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let text_style = Style::default().fg(self.theme.foreground);
+        let dim_style = self.fg(self.theme.comment);
+        let name_style = self.fg(self.theme.function).add_modifier(Modifier::BOLD);
+        let width = inner.width as usize;
+
+        // Task input
+        let (x, _) = buf.set_stringn(inner.x, inner.y, "Task: ", width, dim_style);
+        buf.set_stringn(
+            x,
+            inner.y,
+            format!("{}█", self.state.task()),
+            width.saturating_sub((x - inner.x) as usize),
+            text_style,
+        );
+
+        // Recommendations, one per line with the rationale after the name
+        let list_y = inner.y + 2;
+        let height = inner.height.saturating_sub(3) as usize;
+        let skip = self
+            .state
+            .selected()
+            .saturating_sub(height.saturating_sub(1));
+        for (row, (index, choice)) in self
+            .state
+            .recommendations()
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(height)
+            .enumerate()
+        {
+            let y = list_y + row as u16;
+            let selected = index == self.state.selected();
+            let style = |base: Style| {
+                if selected {
+                    base.patch(self.theme.selection)
+                } else {
+                    base
+                }
+            };
+            if selected {
+                buf.set_style(Rect::new(inner.x, y, inner.width, 1), self.theme.selection);
+            }
+
+            let (x, _) = buf.set_stringn(inner.x, y, &choice.agent, width, style(name_style));
+            if !choice.rationale.is_empty() {
+                let detail =
+                    format!("  {:>3}%  {}", (choice.score * 100.0).round(), choice.rationale);
+                buf.set_stringn(
+                    x,
+                    y,
+                    detail,
+                    width.saturating_sub((x - inner.x) as usize),
+                    style(dim_style),
+                );
+            }
+        }
+
+        // Footer: last launch result, or key hints
+        let footer = self.state.status().unwrap_or(HINTS);
+        buf.set_stringn(inner.x, inner.y + inner.height - 1, footer, width, dim_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn launcher() -> (TempDir, AgentLauncherState) {
+        let dir = TempDir::new().unwrap();
+        let agents = dir.path().join(".claude/agents");
+        fs::create_dir_all(&agents).unwrap();
+        for (name, description) in [
+            ("test-generator", "Generates unit tests"),
+            ("backend-developer", "Builds APIs and services"),
+            ("tech-writer", "Writes documentation"),
+        ] {
+            fs::write(
+                agents.join(format!("{}.md", name)),
+                format!("---\nname: {}\ndescription: \"{}\"\n---\n", name, description),
+            )
+            .unwrap();
+        }
+
+        let config = AIT42Config::new(dir.path().to_path_buf());
+        let registry = AgentRegistry::load_from_directory(&config.agents_dir()).unwrap();
+        (dir, AgentLauncherState::with_registry(config, registry))
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_recommendations_follow_the_task() {
+        let (_dir, mut launcher) = launcher();
+        assert_eq!(launcher.recommendations().len(), 3);
+        assert_eq!(launcher.recommendations()[0].agent, "backend-developer");
+
+        launcher.insert_text("write unit tests");
+        assert_eq!(launcher.recommendations()[0].agent, "test-generator");
+        assert!(!launcher.recommendations()[0].rationale.is_empty());
+
+        for _ in 0.."write unit tests".len() {
+            launcher.handle_key(key(KeyCode::Backspace));
+        }
+        assert_eq!(launcher.task(), "");
+        assert_eq!(launcher.recommendations().len(), 3);
+    }
+
+    #[test]
+    fn test_enter_launches_selected_agent() {
+        let (_dir, mut launcher) = launcher();
+        launcher.handle_key(key(KeyCode::Enter));
+        assert_eq!(launcher.take_launch(), None);
+        assert_eq!(launcher.status(), Some("Describe the task first"));
+
+        launcher.insert_text("update the documentation\n");
+        assert_eq!(launcher.recommendations()[0].agent, "tech-writer");
+        launcher.handle_key(key(KeyCode::Enter));
+        assert_eq!(
+            launcher.take_launch(),
+            Some(("tech-writer".to_string(), "update the documentation".to_string()))
+        );
+        assert_eq!(launcher.take_launch(), None);
+
+        assert!(!launcher.handle_key(key(KeyCode::Esc)));
+    }
+
+    #[test]
+    fn test_renders_task_and_agents() {
+        let (_dir, mut launcher) = launcher();
+        launcher.insert_text("api tests");
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 90, 12);
+        let mut buf = Buffer::empty(area);
+
+        AgentLauncher::new(&launcher, &theme).render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Launch agent"));
+        assert!(text.contains("Task: api tests"));
+        assert!(text.contains("test-generator"));
+        assert!(text.contains("Name matches"));
+    }
+}
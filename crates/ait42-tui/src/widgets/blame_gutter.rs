@@ -0,0 +1,214 @@
+//! Blame Gutter Widget
+//!
+//! Annotates each visible line with the author and age of the commit that
+//! last changed it. Blame is loaded once per buffer revision and dropped when
+//! the buffer is saved, since saving changes what git compares against.
+
+use super::{editor::ViewState, log_graph::age};
+use crate::theme::Theme;
+use ait42_core::{width, Buffer, BufferId, Cursor};
+use ait42_git::{blame, BlameLine, Repository};
+use ratatui::{buffer::Buffer as RatatuiBuffer, layout::Rect, widgets::Widget};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Blame of a buffer at one revision
+#[derive(Debug)]
+struct CachedBlame {
+    version: u64,
+    lines: Vec<BlameLine>,
+}
+
+/// Blame per buffer, keyed by buffer revision
+#[derive(Debug, Default)]
+pub struct BlameCache {
+    entries: HashMap<BufferId, CachedBlame>,
+}
+
+impl BlameCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Blame of `buffer` at its current revision, loading it on a miss
+    ///
+    /// Buffers without a file, and files git does not track, have no blame.
+    pub fn get(&mut self, buffer: &Buffer) -> &[BlameLine] {
+        if self.cached(buffer).is_none() {
+            let lines = load(buffer).unwrap_or_else(|e| {
+                debug!("No blame for buffer: {}", e);
+                Vec::new()
+            });
+            self.entries.insert(
+                buffer.id(),
+                CachedBlame {
+                    version: buffer.version(),
+                    lines,
+                },
+            );
+        }
+        &self.entries[&buffer.id()].lines
+    }
+
+    /// Blame of `buffer` at its current revision, if already loaded
+    pub fn cached(&self, buffer: &Buffer) -> Option<&[BlameLine]> {
+        self.entries
+            .get(&buffer.id())
+            .filter(|cached| cached.version == buffer.version())
+            .map(|cached| cached.lines.as_slice())
+    }
+
+    /// Drop the blame of buffer `id`, e.g. after it was saved
+    pub fn invalidate(&mut self, id: BufferId) {
+        self.entries.remove(&id);
+    }
+}
+
+/// Blame the file of `buffer` as it is on disk
+fn load(buffer: &Buffer) -> ait42_git::Result<Vec<BlameLine>> {
+    let Some(path) = buffer.path() else {
+        return Ok(Vec::new());
+    };
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let repo = Repository::open(path.parent().unwrap_or(&path))?;
+    blame::blame(&repo, &path)
+}
+
+/// Annotation for a line: author left, age right, `width` cells in total
+fn annotation(line: &BlameLine, width: usize) -> String {
+    let (author, when) = if line.is_uncommitted() {
+        ("Not committed", String::new())
+    } else {
+        (line.author.as_str(), age(line.timestamp))
+    };
+    // A space before the author, between the columns and after the age
+    let author_width = width.saturating_sub(when.len() + 3);
+    let (author, _) = width::slice_by_display(author, 0, author_width);
+    let padding = author_width.saturating_sub(width::str_width(author));
+    format!(" {}{} {} ", author, " ".repeat(padding), when)
+}
+
+/// Blame gutter widget
+pub struct BlameGutter<'a> {
+    lines: &'a [BlameLine],
+    buffer: &'a Buffer,
+    cursor: &'a Cursor,
+    view: &'a ViewState,
+    theme: &'a Theme,
+}
+
+impl<'a> BlameGutter<'a> {
+    /// Create a gutter annotating `buffer` with `lines`
+    pub fn new(
+        lines: &'a [BlameLine],
+        buffer: &'a Buffer,
+        cursor: &'a Cursor,
+        view: &'a ViewState,
+        theme: &'a Theme,
+    ) -> Self {
+        Self {
+            lines,
+            buffer,
+            cursor,
+            view,
+            theme,
+        }
+    }
+}
+
+impl Widget for BlameGutter<'_> {
+    fn render(self, area: Rect, buf: &mut RatatuiBuffer) {
+        if area.width < 8 {
+            return;
+        }
+
+        let cursor_line = self.cursor.position(self.buffer).line;
+        let rows = self
+            .view
+            .visible_lines(area.height as usize, self.buffer.len_lines());
+
+        for (i, &line_num) in rows.iter().enumerate() {
+            let Some(blamed) = self.lines.get(line_num).filter(|l| l.line == line_num) else {
+                continue;
+            };
+            let style = if line_num == cursor_line {
+                self.theme.line_number_active
+            } else {
+                self.theme.line_number
+            };
+            let text = annotation(blamed, area.width as usize);
+            buf.set_stringn(area.x, area.y + i as u16, text, area.width as usize, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_git::repo::test_support::{commit_file, init_repo};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn blamed(line: usize, sha: &str, author: &str, timestamp: i64) -> BlameLine {
+        BlameLine {
+            line,
+            sha: sha.to_string(),
+            author: author.to_string(),
+            email: String::new(),
+            timestamp,
+            summary: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_annotation() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let line = blamed(0, "abc", "Ada Lovelace", now - 3 * 86_400);
+        assert_eq!(annotation(&line, 17), " Ada Lovelace 3d ");
+
+        // Long names are cut to the gutter
+        let line = blamed(0, "abc", "Grace Brewster Hopper", now - 7200);
+        assert_eq!(annotation(&line, 17), " Grace Brewst 2h ");
+
+        let line = blamed(0, &"0".repeat(40), "Not Committed Yet", now);
+        assert_eq!(annotation(&line, 17), " Not committed   ");
+    }
+
+    #[test]
+    fn test_cache_per_revision_and_invalidation() {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+
+        let mut buffer = Buffer::from_file(&dir.path().join("a.txt")).unwrap();
+        let mut cache = BlameCache::new();
+        assert!(cache.cached(&buffer).is_none());
+        let lines = cache.get(&buffer);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].author, "Test");
+        assert!(cache.cached(&buffer).is_some());
+
+        // An edit is a new revision
+        buffer.insert(4, "two\n").unwrap();
+        assert!(cache.cached(&buffer).is_none());
+        assert_eq!(cache.get(&buffer).len(), 1);
+
+        // Saving keeps the revision, so the blame must be dropped to see
+        // the saved edit
+        buffer.save().unwrap();
+        assert!(cache.cached(&buffer).is_some());
+        cache.invalidate(buffer.id());
+        let lines = cache.get(&buffer);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].is_uncommitted());
+
+        // Buffers without a file have no blame
+        assert!(cache.get(&Buffer::new()).is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+//! Keybinding Cheat-Sheet Widget
+//!
+//! Searchable list of every binding in the active key map.
+
+use crate::keybinds::KeyBindingEntry;
+use crate::theme::Theme;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+/// Filter cheat-sheet entries by a fuzzy query over keys, description and context
+pub fn filter_entries<'e>(entries: &'e [KeyBindingEntry], query: &str) -> Vec<&'e KeyBindingEntry> {
+    if query.is_empty() {
+        return entries.iter().collect();
+    }
+
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, &KeyBindingEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let haystack = format!("{} {} {}", entry.context, entry.keys, entry.description);
+            matcher.fuzzy_match(&haystack, query).map(|score| (score, entry))
+        })
+        .collect();
+
+    // Stable sort keeps the key map order for equal scores
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Cheat-sheet panel widget
+pub struct CheatSheet<'a> {
+    query: &'a str,
+    entries: &'a [KeyBindingEntry],
+    scroll: usize,
+    theme: &'a Theme,
+}
+
+impl<'a> CheatSheet<'a> {
+    /// Create a cheat-sheet over `entries` filtered by `query`
+    pub fn new(query: &'a str, entries: &'a [KeyBindingEntry], theme: &'a Theme) -> Self {
+        Self {
+            query,
+            entries,
+            scroll: 0,
+            theme,
+        }
+    }
+
+    /// Set scroll offset into the filtered list
+    pub fn scroll(mut self, offset: usize) -> Self {
+        self.scroll = offset;
+        self
+    }
+
+    /// Centered area covering most of `area`
+    pub fn area(area: Rect) -> Rect {
+        let width = area.width.saturating_mul(4) / 5;
+        let height = area.height.saturating_mul(4) / 5;
+        Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+impl<'a> Widget for CheatSheet<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 20 || area.height < 4 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(" Keybindings ");
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // Search input
+        let input_style = Style::default()
+            .fg(self.theme.foreground)
+            .add_modifier(Modifier::BOLD);
+        buf.set_stringn(
+            inner.x,
+            inner.y,
+            format!("/ {}", self.query),
+            inner.width as usize,
+            input_style,
+        );
+
+        let matches = filter_entries(self.entries, self.query);
+        let list_y = inner.y + 2;
+        let visible = inner.height.saturating_sub(2) as usize;
+
+        let context_style = Style::default().fg(self.theme.comment.fg.unwrap_or(self.theme.foreground));
+        let key_style = Style::default()
+            .fg(self.theme.keyword.fg.unwrap_or(self.theme.foreground))
+            .add_modifier(Modifier::BOLD);
+        let desc_style = Style::default().fg(self.theme.foreground);
+
+        for (i, entry) in matches.iter().skip(self.scroll).take(visible).enumerate() {
+            let y = list_y + i as u16;
+            let mut x = inner.x;
+
+            for (text, style, width) in [
+                (entry.context, context_style, 10u16),
+                (entry.keys.as_str(), key_style, 18u16),
+                (entry.description, desc_style, inner.width),
+            ] {
+                if x >= inner.right() {
+                    break;
+                }
+                let max = width.min(inner.right() - x) as usize;
+                buf.set_stringn(x, y, text, max, style);
+                x = x.saturating_add(width);
+            }
+        }
+
+        let count_text = format!(" {}/{} ", matches.len(), self.entries.len());
+        let count_x = area.right().saturating_sub(count_text.len() as u16 + 1);
+        buf.set_string(count_x, area.y, count_text, context_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybinds::KeyMap;
+
+    #[test]
+    fn test_filter_empty_query_returns_all() {
+        let entries = KeyMap::default().all_bindings();
+        assert_eq!(filter_entries(&entries, "").len(), entries.len());
+    }
+
+    #[test]
+    fn test_filter_by_description() {
+        let entries = KeyMap::default().all_bindings();
+        let matches = filter_entries(&entries, "toggle terminal");
+
+        assert!(!matches.is_empty());
+        assert_eq!(
+            matches[0].command,
+            crate::keybinds::EditorCommand::ToggleTerminal
+        );
+    }
+
+    #[test]
+    fn test_filter_no_match() {
+        let entries = KeyMap::default().all_bindings();
+        assert!(filter_entries(&entries, "zzzzqqq").is_empty());
+    }
+
+    #[test]
+    fn test_cheat_sheet_area_is_centered() {
+        let area = CheatSheet::area(Rect::new(0, 0, 100, 50));
+        assert_eq!(area, Rect::new(10, 5, 80, 40));
+    }
+
+    #[test]
+    fn test_cheat_sheet_renders_entries() {
+        let theme = Theme::default();
+        let entries = KeyMap::default().all_bindings();
+        let area = Rect::new(0, 0, 80, 30);
+        let mut buf = Buffer::empty(area);
+
+        CheatSheet::new("save", &entries, &theme).render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Keybindings"));
+        assert!(text.contains("Save file"));
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Provides fuzzy search command interface.
 
+use crate::keybinds::EditorCommand;
 use crate::theme::Theme;
+use ait42_config::KeyBindingConfig;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use ratatui::{
@@ -12,6 +14,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Widget},
 };
+use unicode_width::UnicodeWidthStr;
 
 /// Command item
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,6 +50,10 @@ pub struct CommandPalette<'a> {
     input: &'a str,
     commands: &'a [Command],
     selected: usize,
+    /// Shown before the input
+    prompt: &'a str,
+    /// Query of a history search and the entry it found
+    history_search: Option<(&'a str, Option<&'a str>)>,
     theme: &'a Theme,
 }
 
@@ -57,6 +64,8 @@ impl<'a> CommandPalette<'a> {
             input,
             commands,
             selected: 0,
+            prompt: ">",
+            history_search: None,
             theme,
         }
     }
@@ -67,40 +76,65 @@ impl<'a> CommandPalette<'a> {
         self
     }
 
+    /// Set the prompt shown before the input (`>` by default)
+    pub fn prompt(mut self, prompt: &'a str) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    /// Show a history search for `query` in place of the input, with the
+    /// entry it found
+    pub fn history_search(mut self, query: &'a str, matched: Option<&'a str>) -> Self {
+        self.history_search = Some((query, matched));
+        self
+    }
+
     /// Filter and score commands based on input
     fn filter_commands(&self) -> Vec<Match> {
-        if self.input.is_empty() {
-            // Return all commands if no input
-            return self
-                .commands
-                .iter()
-                .map(|cmd| Match {
-                    command: cmd.clone(),
-                    score: 0,
-                })
-                .collect();
-        }
+        filter_commands(self.input, self.commands)
+    }
+}
 
-        let matcher = SkimMatcherV2::default();
-        let mut matches: Vec<Match> = Vec::new();
+/// Commands matching `input`, best first
+fn filter_commands(input: &str, commands: &[Command]) -> Vec<Match> {
+    if input.is_empty() {
+        // Return all commands if no input
+        return commands
+            .iter()
+            .map(|cmd| Match {
+                command: cmd.clone(),
+                score: 0,
+            })
+            .collect();
+    }
 
-        for command in self.commands {
-            // Search in both name and description
-            let name_score = matcher.fuzzy_match(&command.name, self.input);
-            let desc_score = matcher.fuzzy_match(&command.description, self.input);
+    let matcher = SkimMatcherV2::default();
+    let mut matches: Vec<Match> = Vec::new();
 
-            if let Some(score) = name_score.or(desc_score) {
-                matches.push(Match {
-                    command: command.clone(),
-                    score,
-                });
-            }
-        }
+    for command in commands {
+        // Search in both name and description
+        let name_score = matcher.fuzzy_match(&command.name, input);
+        let desc_score = matcher.fuzzy_match(&command.description, input);
 
-        // Sort by score (highest first)
-        matches.sort_by(|a, b| b.score.cmp(&a.score));
-        matches
+        if let Some(score) = name_score.or(desc_score) {
+            matches.push(Match {
+                command: command.clone(),
+                score,
+            });
+        }
     }
+
+    // Sort by score (highest first)
+    matches.sort_by(|a, b| b.score.cmp(&a.score));
+    matches
+}
+
+/// Command the palette shows first for `input`
+pub fn best_match(input: &str, commands: &[Command]) -> Option<Command> {
+    filter_commands(input, commands)
+        .into_iter()
+        .next()
+        .map(|m| m.command)
 }
 
 impl<'a> Widget for CommandPalette<'a> {
@@ -129,11 +163,17 @@ impl<'a> Widget for CommandPalette<'a> {
             .bg(self.theme.background)
             .add_modifier(Modifier::BOLD);
 
-        let input_text = format!("> {}", self.input);
-        buf.set_string(input_area.x, input_area.y, input_text, input_style);
+        let input_text = match self.history_search {
+            Some((query, matched)) => {
+                let failed = if matched.is_none() { "failed " } else { "" };
+                format!("({}reverse-i-search)`{}': {}", failed, query, matched.unwrap_or(""))
+            }
+            None => format!("{} {}", self.prompt, self.input),
+        };
+        buf.set_string(input_area.x, input_area.y, &input_text, input_style);
 
         // Render cursor in input
-        let cursor_x = input_area.x + 2 + self.input.len() as u16;
+        let cursor_x = input_area.x + input_text.width() as u16;
         if cursor_x < input_area.right() {
             buf.get_mut(cursor_x, input_area.y)
                 .set_bg(self.theme.cursor)
@@ -206,6 +246,17 @@ pub fn default_commands() -> Vec<Command> {
     ]
 }
 
+/// Commands the editor can run, by configuration name
+pub fn editor_commands() -> Vec<Command> {
+    KeyBindingConfig::COMMANDS
+        .iter()
+        .filter_map(|name| {
+            let command = EditorCommand::from_name(name)?;
+            Some(Command::new(*name, command.description(), "Editor"))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,4 +328,13 @@ mod tests {
 
         assert_eq!(palette.selected, 1);
     }
+
+    #[test]
+    fn test_best_match_and_editor_commands() {
+        let commands = editor_commands();
+        assert!(commands.iter().any(|c| c.name == "toggle_sidebar"));
+        assert_eq!(best_match("toggle_sidebar", &commands).unwrap().name, "toggle_sidebar");
+        assert_eq!(best_match("", &test_commands()).unwrap().name, "open_file");
+        assert_eq!(best_match("zzz", &test_commands()), None);
+    }
 }
@@ -0,0 +1,226 @@
+//! Diff Gutter
+//!
+//! Marks the lines of a buffer that differ from the file at HEAD. The file
+//! is read from git once per buffer and again after each save; edits only
+//! re-diff the buffer against that copy, once per buffer revision.
+
+use crate::theme::Theme;
+use ait42_core::{Buffer, BufferId};
+use ait42_git::{diff, Repository};
+use ratatui::style::Style;
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+use std::collections::HashMap;
+use std::ops::Range;
+use tracing::debug;
+
+/// How a line differs from HEAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineChange {
+    /// Line is new
+    Added,
+    /// Line replaces a line at HEAD
+    Modified,
+    /// Lines at HEAD were removed before this line, or after it on the
+    /// last line
+    Deleted,
+}
+
+impl LineChange {
+    /// Gutter mark
+    pub fn symbol(self) -> &'static str {
+        match self {
+            LineChange::Added | LineChange::Modified => "▎",
+            LineChange::Deleted => "▔",
+        }
+    }
+
+    /// Style of the mark in `theme`
+    pub fn style(self, theme: &Theme) -> Style {
+        match self {
+            LineChange::Added => theme.diff_added,
+            LineChange::Modified => theme.diff_modified,
+            LineChange::Deleted => theme.diff_deleted,
+        }
+    }
+}
+
+/// Change of each line of `current` compared to `base`
+///
+/// Where lines are replaced by more lines, the first are modified and the
+/// rest added.
+pub fn line_changes(base: &str, current: &str) -> Vec<Option<LineChange>> {
+    let old: Vec<&str> = base.lines().collect();
+    let new: Vec<&str> = current.lines().collect();
+    let mut changes = vec![None; new.len()];
+
+    for (old_range, new_range) in hunks(&old, &new) {
+        if new_range.is_empty() {
+            let line = new_range.start.min(new.len().saturating_sub(1));
+            if let Some(change) = changes.get_mut(line) {
+                change.get_or_insert(LineChange::Deleted);
+            }
+            continue;
+        }
+        let modified = new_range.start + old_range.len().min(new_range.len());
+        for line in new_range {
+            changes[line] = Some(if line < modified {
+                LineChange::Modified
+            } else {
+                LineChange::Added
+            });
+        }
+    }
+    changes
+}
+
+/// Runs of changed lines as `(old, new)` ranges
+fn hunks(old: &[&str], new: &[&str]) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut hunks: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    for op in capture_diff_slices(Algorithm::Myers, old, new) {
+        if op.tag() == DiffTag::Equal {
+            continue;
+        }
+        let (old_range, new_range) = (op.old_range(), op.new_range());
+        match hunks.last_mut() {
+            Some((old, new)) if old.end == old_range.start && new.end == new_range.start => {
+                old.end = old_range.end;
+                new.end = new_range.end;
+            }
+            _ => hunks.push((old_range, new_range)),
+        }
+    }
+    hunks
+}
+
+/// Line changes of a buffer
+#[derive(Debug)]
+struct Tracked {
+    /// The file at HEAD, or `None` if git does not track it
+    base: Option<String>,
+    /// Buffer revision `changes` were computed for
+    version: Option<u64>,
+    changes: Vec<Option<LineChange>>,
+}
+
+/// Line changes per buffer, kept up to date with the buffer revision
+#[derive(Debug, Default)]
+pub struct LineChangeCache {
+    entries: HashMap<BufferId, Tracked>,
+}
+
+impl LineChangeCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the changes of `buffer` up to date with its revision
+    ///
+    /// Reads the file at HEAD the first time a buffer is seen.
+    pub fn update(&mut self, buffer: &Buffer) {
+        let tracked = self.entries.entry(buffer.id()).or_insert_with(|| Tracked {
+            base: load(buffer).unwrap_or_else(|e| {
+                debug!("No HEAD version of buffer: {}", e);
+                None
+            }),
+            version: None,
+            changes: Vec::new(),
+        });
+        if tracked.version == Some(buffer.version()) {
+            return;
+        }
+        tracked.version = Some(buffer.version());
+        tracked.changes = match &tracked.base {
+            Some(base) => line_changes(base, &buffer.to_string()),
+            None => Vec::new(),
+        };
+    }
+
+    /// Change of each line of `buffer`, as of the last update
+    pub fn changes(&self, buffer: &Buffer) -> &[Option<LineChange>] {
+        self.entries
+            .get(&buffer.id())
+            .map(|tracked| tracked.changes.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Read the file at HEAD again for buffer `id` on its next update,
+    /// e.g. after it was saved
+    pub fn reload(&mut self, id: BufferId) {
+        self.entries.remove(&id);
+    }
+}
+
+/// Content of the file of `buffer` at HEAD
+fn load(buffer: &Buffer) -> ait42_git::Result<Option<String>> {
+    let Some(path) = buffer.path() else {
+        return Ok(None);
+    };
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let repo = Repository::open(path.parent().unwrap_or(&path))?;
+    diff::file_at(&repo, "HEAD", &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_git::repo::test_support::{commit_file, init_repo};
+    use std::fs;
+    use LineChange::*;
+
+    #[test]
+    fn test_line_changes() {
+        let base = "a\nb\nc\nd\n";
+        assert_eq!(line_changes(base, base), vec![None; 4]);
+        assert_eq!(
+            line_changes(base, "a\nB\nc\nnew\nd\n"),
+            vec![None, Some(Modified), None, Some(Added), None]
+        );
+        // More lines replace fewer: the first modified, the rest added
+        assert_eq!(
+            line_changes(base, "a\nB\nB2\nc\nd\n"),
+            vec![None, Some(Modified), Some(Added), None, None]
+        );
+        // Removed lines mark the line after them, or the last line
+        assert_eq!(line_changes(base, "a\nd\n"), vec![None, Some(Deleted)]);
+        assert_eq!(line_changes(base, "a\nb\n"), vec![None, Some(Deleted)]);
+        assert_eq!(line_changes("", "x\n"), vec![Some(Added)]);
+    }
+
+    #[test]
+    fn test_cache_follows_edits_and_saves() {
+        let (dir, repo) = init_repo();
+        let path = dir.path();
+        commit_file(&repo, "a.txt", "one\ntwo\n", "initial");
+
+        let mut buffer = Buffer::from_file(&path.join("a.txt")).unwrap();
+        let mut cache = LineChangeCache::new();
+        assert!(cache.changes(&buffer).is_empty());
+        cache.update(&buffer);
+        assert_eq!(cache.changes(&buffer), &[None, None]);
+
+        // Edits are diffed against the file at HEAD
+        buffer.insert(0, "zero\n").unwrap();
+        cache.update(&buffer);
+        assert_eq!(cache.changes(&buffer), &[Some(Added), None, None]);
+
+        // A commit made elsewhere shows after the next save
+        buffer.save().unwrap();
+        repo.run(&["commit", "-q", "-am", "add zero"]).unwrap();
+        cache.update(&buffer);
+        assert_eq!(cache.changes(&buffer), &[Some(Added), None, None]);
+        cache.reload(buffer.id());
+        cache.update(&buffer);
+        assert_eq!(cache.changes(&buffer), &[None, None, None]);
+
+        // Files git does not track have no marks
+        fs::write(path.join("b.txt"), "new\n").unwrap();
+        let untracked = Buffer::from_file(&path.join("b.txt")).unwrap();
+        cache.update(&untracked);
+        assert!(cache.changes(&untracked).is_empty());
+    }
+}
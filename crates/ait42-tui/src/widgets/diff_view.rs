@@ -0,0 +1,107 @@
+//! Diff View Widget
+//!
+//! Renders a unified diff, coloring file headers, hunk headers, added and
+//! removed lines from the theme.
+
+use crate::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::Widget,
+};
+
+/// Unified diff viewer
+pub struct DiffView<'a> {
+    patch: &'a str,
+    scroll: usize,
+    theme: &'a Theme,
+}
+
+impl<'a> DiffView<'a> {
+    /// Create a view of `patch`
+    pub fn new(patch: &'a str, theme: &'a Theme) -> Self {
+        Self {
+            patch,
+            scroll: 0,
+            theme,
+        }
+    }
+
+    /// Skip the first `lines` lines of the patch
+    pub fn scroll(mut self, lines: usize) -> Self {
+        self.scroll = lines;
+        self
+    }
+
+    /// Style of one patch line
+    pub fn line_style(&self, line: &str) -> Style {
+        let color = |style: Style| Style::default().fg(style.fg.unwrap_or(self.theme.foreground));
+        if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+            Style::default()
+                .fg(self.theme.foreground)
+                .add_modifier(Modifier::BOLD)
+        } else if line.starts_with('+') {
+            color(self.theme.string)
+        } else if line.starts_with('-') {
+            color(self.theme.keyword)
+        } else if line.starts_with("@@") {
+            color(self.theme.function)
+        } else {
+            Style::default().fg(self.theme.foreground)
+        }
+    }
+}
+
+impl<'a> Widget for DiffView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for (row, line) in self
+            .patch
+            .lines()
+            .skip(self.scroll)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            buf.set_stringn(
+                area.x,
+                area.y + row as u16,
+                line,
+                area.width as usize,
+                self.line_style(line),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_styles() {
+        let theme = Theme::default();
+        let view = DiffView::new("", &theme);
+
+        assert!(view
+            .line_style("+++ b/a.txt")
+            .add_modifier
+            .contains(Modifier::BOLD));
+        assert_eq!(view.line_style("+added").fg, theme.string.fg);
+        assert_eq!(view.line_style("-removed").fg, theme.keyword.fg);
+        assert_eq!(view.line_style("@@ -1 +1 @@").fg, theme.function.fg);
+        assert_eq!(view.line_style(" context").fg, Some(theme.foreground));
+    }
+
+    #[test]
+    fn test_render_scroll_and_clip() {
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 6, 2);
+        let mut buf = Buffer::empty(area);
+        DiffView::new("one\n-two\n+three-long\nfour", &theme)
+            .scroll(1)
+            .render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert_eq!(text, "-two  +three");
+    }
+}
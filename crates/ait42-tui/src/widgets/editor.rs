@@ -2,8 +2,12 @@
 //!
 //! Renders the main text editing area with cursor, selection, and line numbers.
 
-use crate::theme::Theme;
-use ait42_core::{Buffer, Cursor, Selection};
+use super::diff_gutter::LineChange;
+use crate::{
+    syntax::{StructureHighlight, SyntaxHighlighter},
+    theme::Theme,
+};
+use ait42_core::{Buffer, Cursor, FoldMap, LocalMarks, Selection};
 use ratatui::{
     buffer::Buffer as RatatuiBuffer,
     layout::Rect,
@@ -11,7 +15,8 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use ait42_core::width;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// View state for scrolling
 #[derive(Debug, Clone, Default)]
@@ -20,6 +25,10 @@ pub struct ViewState {
     pub scroll_line: usize,
     /// Horizontal scroll offset
     pub scroll_col: usize,
+    /// Closed folds, whose lines are skipped when drawing
+    pub folds: FoldMap,
+    /// Lowercase marks of the buffer
+    pub marks: LocalMarks,
 }
 
 impl ViewState {
@@ -33,11 +42,19 @@ impl ViewState {
         let visible_lines = viewport.height as usize;
         let visible_cols = viewport.width as usize;
 
-        // Vertical scrolling
+        // Vertical scrolling, counting folded regions as one line
+        self.scroll_line = self.folds.visible_line(self.scroll_line);
         if cursor_line < self.scroll_line {
             self.scroll_line = cursor_line;
-        } else if cursor_line >= self.scroll_line + visible_lines {
-            self.scroll_line = cursor_line - visible_lines + 1;
+        } else if self.folds.visible_count(self.scroll_line..cursor_line + 1) > visible_lines {
+            let mut top = cursor_line;
+            for _ in 1..visible_lines {
+                match self.folds.prev_visible(top) {
+                    Some(line) => top = line,
+                    None => break,
+                }
+            }
+            self.scroll_line = top;
         }
 
         // Horizontal scrolling
@@ -47,6 +64,23 @@ impl ViewState {
             self.scroll_col = cursor_col - visible_cols + 1;
         }
     }
+
+    /// Buffer lines shown on the first `rows` rows, skipping folded lines
+    pub fn visible_lines(&self, rows: usize, line_count: usize) -> Vec<usize> {
+        let mut lines = Vec::with_capacity(rows);
+        let mut line = self.folds.visible_line(self.scroll_line);
+        while lines.len() < rows && line < line_count {
+            lines.push(line);
+            line = self.folds.next_visible(line);
+        }
+        lines
+    }
+
+    /// Row `line` is drawn on, counted from the top of the view
+    pub fn row_of(&self, line: usize) -> Option<usize> {
+        let top = self.folds.visible_line(self.scroll_line);
+        (line >= top && !self.folds.is_hidden(line)).then(|| self.folds.visible_count(top..line))
+    }
 }
 
 /// Editor widget for rendering text buffer
@@ -56,6 +90,10 @@ pub struct EditorWidget<'a> {
     selection: Option<&'a Selection>,
     view: &'a ViewState,
     theme: &'a Theme,
+    highlighter: Option<&'a SyntaxHighlighter>,
+    structure: StructureHighlight,
+    search_matches: &'a [std::ops::Range<usize>],
+    line_changes: &'a [Option<LineChange>],
     show_line_numbers: bool,
 }
 
@@ -73,6 +111,10 @@ impl<'a> EditorWidget<'a> {
             selection: None,
             view,
             theme,
+            highlighter: None,
+            structure: StructureHighlight::default(),
+            search_matches: &[],
+            line_changes: &[],
             show_line_numbers: true,
         }
     }
@@ -83,6 +125,32 @@ impl<'a> EditorWidget<'a> {
         self
     }
 
+    /// Color tokens from `highlighter`, which must be up to date with the buffer
+    pub fn highlighter(mut self, highlighter: &'a SyntaxHighlighter) -> Self {
+        self.highlighter = Some(highlighter);
+        self
+    }
+
+    /// Color brackets and tint the scope under the cursor, as far as
+    /// `structure` enables them; needs a highlighter
+    pub fn structure(mut self, structure: StructureHighlight) -> Self {
+        self.structure = structure;
+        self
+    }
+
+    /// Highlight search matches, given as sorted byte ranges into the buffer
+    pub fn search_matches(mut self, matches: &'a [std::ops::Range<usize>]) -> Self {
+        self.search_matches = matches;
+        self
+    }
+
+    /// Mark lines changed since HEAD in the line number gutter, given per
+    /// buffer line
+    pub fn line_changes(mut self, changes: &'a [Option<LineChange>]) -> Self {
+        self.line_changes = changes;
+        self
+    }
+
     /// Set whether to show line numbers
     pub fn show_line_numbers(mut self, show: bool) -> Self {
         self.show_line_numbers = show;
@@ -97,10 +165,9 @@ impl<'a> EditorWidget<'a> {
 
         let line_count = self.buffer.len_lines();
         let cursor_line = self.cursor.position(self.buffer).line;
-        let start_line = self.view.scroll_line;
-        let end_line = (start_line + area.height as usize).min(line_count);
+        let rows = self.view.visible_lines(area.height as usize, line_count);
 
-        for (i, line_num) in (start_line..end_line).enumerate() {
+        for (i, &line_num) in rows.iter().enumerate() {
             let y = area.y + i as u16;
             if y >= area.y + area.height {
                 break;
@@ -115,6 +182,27 @@ impl<'a> EditorWidget<'a> {
 
             let line_str = format!("{:>4} ", line_num + 1);
             buf.set_string(area.x, y, line_str, style);
+
+            // The mark takes the spacing column before the text
+            if let Some(change) = self.line_changes.get(line_num).copied().flatten() {
+                buf.set_string(area.right() - 1, y, change.symbol(), change.style(self.theme));
+            }
+        }
+    }
+
+    /// Apply `style` to the cells of display columns `cols` on row `y`
+    fn highlight_cells(
+        &self,
+        area: Rect,
+        y: u16,
+        cols: std::ops::Range<usize>,
+        style: Style,
+        buf: &mut RatatuiBuffer,
+    ) {
+        let start = cols.start.max(self.view.scroll_col) - self.view.scroll_col;
+        let end = cols.end.saturating_sub(self.view.scroll_col);
+        for col in start..end.min(area.width as usize) {
+            buf.get_mut(area.x + col as u16, y).set_style(style);
         }
     }
 }
@@ -137,13 +225,30 @@ impl<'a> Widget for EditorWidget<'a> {
         let lines: Vec<&str> = content.lines().collect();
         let line_count = lines.len().max(1);
 
-        let cursor_pos = self.cursor.position(self.buffer);
-        let start_line = self.view.scroll_line;
-        let end_line = (start_line + area.height as usize).min(line_count);
+        let cursor_pos = self.cursor.display_position(self.buffer);
+        let rows = self.view.visible_lines(area.height as usize, line_count);
+        let start_line = rows.first().copied().unwrap_or(self.view.scroll_line);
+        let end_line = rows.last().map_or(start_line, |last| last + 1);
+        let highlights = self
+            .highlighter
+            .map(|highlighter| highlighter.highlights(start_line..end_line))
+            .unwrap_or_default();
+        let delimiters = self
+            .highlighter
+            .filter(|_| self.structure.rainbow_delimiters)
+            .map(|highlighter| highlighter.delimiters(start_line..end_line))
+            .unwrap_or_default();
+        let scope = self
+            .highlighter
+            .filter(|_| self.structure.scope)
+            .and_then(|highlighter| highlighter.scope(self.cursor.pos()))
+            .unwrap_or_default();
 
         // Render visible lines
-        for (i, line_idx) in (start_line..end_line).enumerate() {
+        for (i, &line_idx) in rows.iter().enumerate() {
             let y = area.y + i as u16;
+            // Highlights are per buffer line, folded ones included
+            let row = line_idx - start_line;
             if y >= area.y + area.height {
                 break;
             }
@@ -154,63 +259,116 @@ impl<'a> Widget for EditorWidget<'a> {
                 ""
             };
 
-            // Apply horizontal scrolling
-            let visible_text = if self.view.scroll_col < line_text.len() {
-                &line_text[self.view.scroll_col..]
-            } else {
-                ""
-            };
-
-            // Truncate to fit viewport
+            // Apply horizontal scrolling in display columns so wide
+            // characters are never split across the viewport edge
             let max_width = area.width as usize;
-            let display_text = truncate_to_width(visible_text, max_width);
+            let (display_text, padding) =
+                width::slice_by_display(line_text, self.view.scroll_col, max_width);
 
-            // Apply syntax highlighting (basic for now, Phase 2 will add proper highlighting)
             let style = Style::default().fg(self.theme.foreground);
-            buf.set_string(area.x, y, display_text, style);
+            buf.set_stringn(
+                area.x + padding as u16,
+                y,
+                display_text,
+                max_width - padding,
+                style,
+            );
+
+            // Summarize a closed fold after its first line
+            if let Some(fold) = self.view.folds.folded_at(line_idx) {
+                let col = width::str_width(line_text) + 1;
+                if let Some(x) = col
+                    .checked_sub(self.view.scroll_col)
+                    .filter(|&x| x < max_width)
+                {
+                    let summary = format!("⋯ {} lines", fold.hidden_lines());
+                    buf.set_stringn(
+                        area.x + x as u16,
+                        y,
+                        summary,
+                        max_width - x,
+                        self.theme.comment,
+                    );
+                }
+            }
 
-            // Render cursor on current line
-            if line_idx == cursor_pos.line {
-                let cursor_col = cursor_pos.col.saturating_sub(self.view.scroll_col);
-                if cursor_col < area.width as usize {
-                    let cursor_x = area.x + cursor_col as u16;
-                    if cursor_x < area.right() {
-                        // Set cursor background
-                        buf.get_mut(cursor_x, y)
-                            .set_bg(self.theme.cursor)
-                            .set_fg(self.theme.background)
-                            .set_style(Style::default().add_modifier(Modifier::BOLD));
+            // Tint the scope under the cursor
+            if scope.contains(&line_idx) {
+                let tint = Style::default().bg(self.theme.scope_background);
+                for x in area.left()..area.right() {
+                    buf.get_mut(x, y).set_style(tint);
+                }
+            }
+
+            // Color syntax tokens
+            for span in highlights.get(row).into_iter().flatten() {
+                let cols = width::byte_to_display_col(line_text, span.bytes.start)
+                    ..width::byte_to_display_col(line_text, span.bytes.end);
+                self.highlight_cells(area, y, cols, span.kind.style(self.theme), buf);
+            }
+
+            // Color brackets by depth
+            for delimiter in delimiters.get(row).into_iter().flatten() {
+                let cols = width::byte_to_display_col(line_text, delimiter.bytes.start)
+                    ..width::byte_to_display_col(line_text, delimiter.bytes.end);
+                let style = self.theme.delimiter(delimiter.depth);
+                self.highlight_cells(area, y, cols, style, buf);
+            }
+
+            // Highlight search matches on this line
+            if let Some(line_start) = self.buffer.line_col_to_pos(line_idx, 0) {
+                let line_end = line_start + line_text.len();
+                let first = self.search_matches.partition_point(|m| m.end <= line_start);
+                for m in self.search_matches[first..]
+                    .iter()
+                    .take_while(|m| m.start < line_end)
+                {
+                    let start = m.start.max(line_start) - line_start;
+                    let end = m.end.min(line_end) - line_start;
+                    let cols = width::byte_to_display_col(line_text, start)
+                        ..width::byte_to_display_col(line_text, end);
+                    self.highlight_cells(area, y, cols, self.theme.search_match, buf);
+                }
+            }
+
+            // Highlight selection
+            if let Some(selection) = self.selection {
+                for range in selection.ranges() {
+                    if let Some(span) = range.display_span(self.buffer, line_idx) {
+                        self.highlight_cells(area, y, span, self.theme.selection, buf);
                     }
                 }
             }
-        }
 
-        // Render empty lines indicator (~)
-        for i in end_line..start_line + area.height as usize {
-            let y = area.y + (i - start_line) as u16;
-            if y < area.bottom() {
-                let style = self.theme.line_number;
-                buf.set_string(area.x, y, "~", style);
+            // Render cursor on current line, covering both cells of a wide char
+            if line_idx == cursor_pos.line {
+                let cursor_width = line_text
+                    .get(width::display_col_to_byte(line_text, cursor_pos.col)..)
+                    .and_then(|rest| rest.graphemes(true).next())
+                    .map(width::grapheme_width)
+                    .unwrap_or(1)
+                    .max(1);
+                let cursor_style = Style::default()
+                    .bg(self.theme.cursor)
+                    .fg(self.theme.background)
+                    .add_modifier(Modifier::BOLD);
+                self.highlight_cells(
+                    area,
+                    y,
+                    cursor_pos.col..cursor_pos.col + cursor_width,
+                    cursor_style,
+                    buf,
+                );
             }
         }
-    }
-}
-
-/// Truncate string to fit within width (accounting for unicode)
-fn truncate_to_width(s: &str, max_width: usize) -> &str {
-    let mut width = 0;
-    let mut byte_idx = 0;
 
-    for (idx, ch) in s.char_indices() {
-        let ch_width = ch.width().unwrap_or(0);
-        if width + ch_width > max_width {
-            break;
+        // Render empty lines indicator (~)
+        for i in rows.len()..area.height as usize {
+            let y = area.y + i as u16;
+            let style = self.theme.line_number;
+            buf.set_string(area.x, y, "~", style);
         }
-        width += ch_width;
-        byte_idx = idx + ch.len_utf8();
     }
-
-    &s[..byte_idx]
 }
 
 /// Scrollbar widget
@@ -267,9 +425,9 @@ mod tests {
 
     #[test]
     fn test_truncate_to_width() {
-        assert_eq!(truncate_to_width("hello world", 5), "hello");
-        assert_eq!(truncate_to_width("hello", 10), "hello");
-        assert_eq!(truncate_to_width("", 5), "");
+        assert_eq!(width::slice_by_display("hello world", 0, 5).0, "hello");
+        assert_eq!(width::slice_by_display("hello", 0, 10).0, "hello");
+        assert_eq!(width::slice_by_display("", 0, 5).0, "");
     }
 
     #[test]
@@ -300,6 +458,187 @@ mod tests {
         assert_eq!(view.scroll_col, 0);
     }
 
+    fn render_to_string(buffer: &Buffer, cursor: &Cursor, view: &ViewState, width: u16) -> String {
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, width, 1);
+        let mut buf = RatatuiBuffer::empty(area);
+        EditorWidget::new(buffer, cursor, view, &theme).render(area, &mut buf);
+        buf.content().iter().map(|c| c.symbol()).collect()
+    }
+
+    #[test]
+    fn test_render_wide_chars_fit_viewport() {
+        let buffer = Buffer::from_string("日本語テキスト".to_string(), None);
+        let cursor = Cursor::default();
+        let view = ViewState::new();
+
+        let rendered = render_to_string(&buffer, &cursor, &view, 5);
+        // Only two 2-cell characters fit in five cells
+        assert!(rendered.starts_with("日"));
+        assert!(rendered.contains("本"));
+        assert!(!rendered.contains("語"));
+    }
+
+    #[test]
+    fn test_render_horizontal_scroll_inside_wide_char() {
+        let buffer = Buffer::from_string("日本語".to_string(), None);
+        let cursor = Cursor::default();
+        let view = ViewState {
+            scroll_line: 0,
+            scroll_col: 1,
+            ..ViewState::default()
+        };
+
+        let rendered = render_to_string(&buffer, &cursor, &view, 6);
+        assert!(rendered.starts_with(' '));
+        assert!(!rendered.contains("日"));
+        // Wide characters occupy their cell plus a blank continuation cell
+        assert_eq!(rendered.chars().nth(1), Some('本'));
+        assert_eq!(rendered.chars().nth(3), Some('語'));
+    }
+
+    #[test]
+    fn test_cursor_covers_wide_char() {
+        let buffer = Buffer::from_string("a日b".to_string(), None);
+        let cursor = Cursor::new(1);
+        let view = ViewState::new();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = RatatuiBuffer::empty(area);
+        EditorWidget::new(&buffer, &cursor, &view, &theme).render(area, &mut buf);
+
+        assert_eq!(buf.get(1, 0).bg, theme.cursor);
+        assert_eq!(buf.get(2, 0).bg, theme.cursor);
+        assert_ne!(buf.get(3, 0).bg, theme.cursor);
+    }
+
+    #[test]
+    fn test_render_syntax_highlighting() {
+        let buffer = Buffer::from_string("let x = 42;".to_string(), Some("rs".to_string()));
+        let mut highlighter = SyntaxHighlighter::new();
+        highlighter.update(&buffer);
+        let cursor = Cursor::new(4);
+        let view = ViewState::new();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 20, 1);
+        let mut buf = RatatuiBuffer::empty(area);
+        EditorWidget::new(&buffer, &cursor, &view, &theme)
+            .highlighter(&highlighter)
+            .render(area, &mut buf);
+
+        assert_eq!(buf.get(0, 0).fg, theme.keyword.fg.unwrap());
+        assert_eq!(buf.get(2, 0).fg, theme.keyword.fg.unwrap());
+        assert_eq!(buf.get(8, 0).fg, theme.number.fg.unwrap());
+        assert_eq!(buf.get(6, 0).fg, theme.foreground);
+    }
+
+    #[test]
+    fn test_render_structure_highlighting() {
+        let source = "fn f() {\n    g(1);\n}\nconst A: u8 = 0;";
+        let buffer = Buffer::from_string(source.to_string(), Some("rs".to_string()));
+        let mut highlighter = SyntaxHighlighter::new();
+        highlighter.update(&buffer);
+        let cursor = Cursor::new(source.find("g(").unwrap());
+        let view = ViewState::new();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 20, 4);
+        let render = |structure| {
+            let mut buf = RatatuiBuffer::empty(area);
+            EditorWidget::new(&buffer, &cursor, &view, &theme)
+                .highlighter(&highlighter)
+                .structure(structure)
+                .render(area, &mut buf);
+            buf
+        };
+
+        let buf = render(StructureHighlight {
+            rainbow_delimiters: true,
+            scope: true,
+        });
+        // `(` of the parameters and `(` of the call one level deeper
+        assert_eq!(buf.get(4, 0).fg, theme.rainbow[0]);
+        assert_eq!(buf.get(5, 1).fg, theme.rainbow[1]);
+        // The function body is tinted, up to the edge of the area
+        assert_eq!(buf.get(19, 1).bg, theme.scope_background);
+        assert_eq!(buf.get(0, 2).bg, theme.scope_background);
+        assert_eq!(buf.get(0, 3).bg, theme.background);
+
+        let buf = render(StructureHighlight::default());
+        assert_eq!(buf.get(5, 1).fg, theme.foreground);
+        assert_eq!(buf.get(19, 1).bg, theme.background);
+    }
+
+    #[test]
+    fn test_render_search_matches() {
+        let buffer = Buffer::from_string("ab 日ab\nxab".to_string(), None);
+        let cursor = Cursor::new(2);
+        let view = ViewState::new();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 10, 2);
+        let mut buf = RatatuiBuffer::empty(area);
+        let matches = [0..2, 6..8, 10..12];
+        EditorWidget::new(&buffer, &cursor, &view, &theme)
+            .search_matches(&matches)
+            .render(area, &mut buf);
+
+        let highlighted = |x, y| buf.get(x, y).bg == theme.search_match.bg.unwrap();
+        assert!(highlighted(0, 0) && highlighted(1, 0));
+        // Display columns, not bytes, after the wide character
+        assert!(!highlighted(4, 0));
+        assert!(highlighted(5, 0) && highlighted(6, 0));
+        assert!(!highlighted(0, 1));
+        assert!(highlighted(1, 1) && highlighted(2, 1));
+    }
+
+    #[test]
+    fn test_render_line_change_marks() {
+        let buffer = Buffer::from_string("a\nb\nc".to_string(), None);
+        let cursor = Cursor::default();
+        let view = ViewState::new();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 5, 3);
+        let mut buf = RatatuiBuffer::empty(area);
+        let changes = [Some(LineChange::Added), None, Some(LineChange::Deleted)];
+        EditorWidget::new(&buffer, &cursor, &view, &theme)
+            .line_changes(&changes)
+            .render_line_numbers(area, &mut buf);
+
+        assert_eq!(buf.get(4, 0).symbol(), "▎");
+        assert_eq!(buf.get(4, 0).fg, theme.diff_added.fg.unwrap());
+        assert_eq!(buf.get(3, 0).symbol(), "1");
+        assert_eq!(buf.get(4, 1).symbol(), " ");
+        assert_eq!(buf.get(4, 2).symbol(), "▔");
+        assert_eq!(buf.get(4, 2).fg, theme.diff_deleted.fg.unwrap());
+    }
+
+    #[test]
+    fn test_render_skips_folded_lines() {
+        let buffer = Buffer::from_string("a {\n    b\n    c\n}\nd".to_string(), None);
+        let cursor = Cursor::default();
+        let mut view = ViewState::new();
+        view.folds.fold_at(&buffer.to_string(), 1);
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 20, 4);
+        let mut buf = RatatuiBuffer::empty(area);
+        EditorWidget::new(&buffer, &cursor, &view, &theme).render(area, &mut buf);
+
+        let row = |y| (0..20).map(|x| buf.get(x, y).symbol()).collect::<String>();
+        assert_eq!(row(0).trim_end(), "a { ⋯ 2 lines");
+        assert_eq!(row(1).trim_end(), "}");
+        assert_eq!(row(2).trim_end(), "d");
+        assert_eq!(row(3).trim_end(), "~");
+
+        assert_eq!(view.visible_lines(10, 5), [0, 3, 4]);
+        assert_eq!(view.row_of(3), Some(1));
+        assert_eq!(view.row_of(2), None);
+
+        // The cursor stays in view with folded lines counted once
+        view.update_scroll(4, 0, Rect::new(0, 0, 20, 3));
+        assert_eq!(view.scroll_line, 0);
+        view.update_scroll(4, 0, Rect::new(0, 0, 20, 2));
+        assert_eq!(view.scroll_line, 3);
+    }
+
     #[test]
     fn test_editor_widget_creation() {
         let buffer = Buffer::new();
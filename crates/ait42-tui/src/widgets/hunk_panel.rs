@@ -0,0 +1,357 @@
+//! Hunk Panel Widget
+//!
+//! Lists the unstaged or the staged hunks of the repository next to the
+//! lines of the selected one, with keys to stage or unstage single hunks.
+
+use crate::{theme::Theme, widgets::DiffView};
+use ait42_git::{hunk, Hunk, Repository};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::path::Path;
+
+/// Key hints shown in the panel footer
+const HINTS: &str = "j/k select  J/K scroll  s stage  u unstage  tab staged/unstaged  q close";
+
+/// Hunk staging state
+#[derive(Debug)]
+pub struct HunkPanelState {
+    repo: Repository,
+    /// Whether the staged hunks are listed rather than the unstaged ones
+    staged: bool,
+    hunks: Vec<Hunk>,
+    selected: usize,
+    diff: String,
+    diff_scroll: usize,
+    status: Option<String>,
+}
+
+impl HunkPanelState {
+    /// Open the panel on the unstaged hunks of the repository containing
+    /// `path`
+    pub fn open(path: &Path) -> ait42_git::Result<Self> {
+        let mut state = Self {
+            repo: Repository::open(path)?,
+            staged: false,
+            hunks: Vec::new(),
+            selected: 0,
+            diff: String::new(),
+            diff_scroll: 0,
+            status: None,
+        };
+        state.refresh();
+        Ok(state)
+    }
+
+    /// Listed hunks
+    pub fn hunks(&self) -> &[Hunk] {
+        &self.hunks
+    }
+
+    /// Whether the staged hunks are listed
+    pub fn is_staged(&self) -> bool {
+        self.staged
+    }
+
+    /// Index of the selected hunk
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Lines of the selected hunk
+    pub fn diff(&self) -> &str {
+        &self.diff
+    }
+
+    /// Result of the last action
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Reload the hunks and the selected lines
+    pub fn refresh(&mut self) {
+        match hunk::hunks(&self.repo, self.staged, None) {
+            Ok(hunks) => self.hunks = hunks,
+            Err(e) => {
+                self.hunks.clear();
+                self.status = Some(e.to_string());
+            }
+        }
+        self.selected = self.selected.min(self.hunks.len().saturating_sub(1));
+        self.load_diff();
+    }
+
+    fn load_diff(&mut self) {
+        self.diff_scroll = 0;
+        self.diff = match self.hunks.get(self.selected) {
+            Some(hunk) => {
+                let mut diff = hunk.header();
+                for line in &hunk.lines {
+                    diff.push('\n');
+                    diff.push_str(line);
+                }
+                diff
+            }
+            None => String::new(),
+        };
+    }
+
+    /// Move the selection by `delta` hunks
+    pub fn select_relative(&mut self, delta: isize) {
+        if self.hunks.is_empty() {
+            return;
+        }
+        let last = self.hunks.len() - 1;
+        let selected = self.selected.saturating_add_signed(delta).min(last);
+        if selected != self.selected {
+            self.selected = selected;
+            self.load_diff();
+        }
+    }
+
+    /// Scroll the hunk lines by `delta` lines
+    pub fn scroll_diff(&mut self, delta: isize) {
+        let max = self.diff.lines().count().saturating_sub(1);
+        self.diff_scroll = self.diff_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    /// Switch between the unstaged and the staged hunks
+    pub fn toggle_staged(&mut self) {
+        self.staged = !self.staged;
+        self.selected = 0;
+        self.status = None;
+        self.refresh();
+    }
+
+    /// Stage the selected hunk, or unstage it when listing staged hunks
+    fn act(&mut self, stage: bool) {
+        if stage == self.staged {
+            self.status = Some(if stage {
+                "Already staged; tab lists unstaged hunks".to_string()
+            } else {
+                "Not staged; tab lists staged hunks".to_string()
+            });
+            return;
+        }
+        let Some(selected) = self.hunks.get(self.selected) else {
+            return;
+        };
+        let (path, index) = (Path::new(&selected.path), selected.index);
+        let result = if stage {
+            hunk::stage(&self.repo, path, index)
+        } else {
+            hunk::unstage(&self.repo, path, index)
+        };
+        self.status = Some(match result {
+            Ok(()) => format!(
+                "{} hunk {} of {}",
+                if stage { "Staged" } else { "Unstaged" },
+                index + 1,
+                selected.path
+            ),
+            Err(e) => e.to_string(),
+        });
+        self.refresh();
+    }
+
+    /// Handle a key press; returns `false` when the panel should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Char('j') | KeyCode::Down => self.select_relative(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_relative(-1),
+            KeyCode::Char('J') | KeyCode::PageDown => self.scroll_diff(10),
+            KeyCode::Char('K') | KeyCode::PageUp => self.scroll_diff(-10),
+            KeyCode::Char('s') => self.act(true),
+            KeyCode::Char('u') => self.act(false),
+            KeyCode::Tab => self.toggle_staged(),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Hunk panel widget
+pub struct HunkPanel<'a> {
+    state: &'a HunkPanelState,
+    theme: &'a Theme,
+}
+
+impl<'a> HunkPanel<'a> {
+    /// Create a panel rendering `state`
+    pub fn new(state: &'a HunkPanelState, theme: &'a Theme) -> Self {
+        Self { state, theme }
+    }
+}
+
+impl<'a> Widget for HunkPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 30 || area.height < 6 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        let title = if self.state.is_staged() {
+            " Staged Hunks "
+        } else {
+            " Unstaged Hunks "
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let body_height = inner.height.saturating_sub(1);
+        let list_width = (inner.width / 3).max(20).min(inner.width);
+        let list_area = Rect::new(inner.x, inner.y, list_width, body_height);
+        let diff_area = Rect::new(
+            inner.x + list_width + 1,
+            inner.y,
+            inner.width.saturating_sub(list_width + 1),
+            body_height,
+        );
+
+        let text_style = Style::default().fg(self.theme.foreground);
+        let dim_style = Style::default().fg(self.theme.comment.fg.unwrap_or(self.theme.foreground));
+
+        // Hunk list
+        if self.state.hunks().is_empty() {
+            buf.set_stringn(
+                list_area.x,
+                list_area.y,
+                "No changes",
+                list_area.width as usize,
+                dim_style,
+            );
+        }
+        let skip = self
+            .state
+            .selected()
+            .saturating_sub(list_area.height.saturating_sub(1) as usize);
+        for (row, (i, hunk)) in self
+            .state
+            .hunks()
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(list_area.height as usize)
+            .enumerate()
+        {
+            let style = if i == self.state.selected() {
+                self.theme.selection.add_modifier(Modifier::BOLD)
+            } else {
+                text_style
+            };
+            let label =
+                format!("{}:{} +{} -{}", hunk.path, hunk.new_start, hunk.added(), hunk.removed());
+            buf.set_stringn(
+                list_area.x,
+                list_area.y + row as u16,
+                format!("{:width$}", label, width = list_area.width as usize),
+                list_area.width as usize,
+                style,
+            );
+        }
+
+        // Separator
+        for y in inner.y..inner.y + body_height {
+            buf.set_string(inner.x + list_width, y, "│", self.theme.border);
+        }
+
+        // Lines of the selected hunk
+        DiffView::new(self.state.diff(), self.theme)
+            .scroll(self.state.diff_scroll)
+            .render(diff_area, buf);
+
+        // Footer: last action result, or key hints
+        let footer = self.state.status().unwrap_or(HINTS);
+        buf.set_stringn(inner.x, inner.y + body_height, footer, inner.width as usize, dim_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_git::repo::test_support::{commit_file, init_repo};
+    use crossterm::event::KeyModifiers;
+    use std::fs;
+
+    fn numbered(changed: &[usize]) -> String {
+        (1..=25)
+            .map(|n| {
+                if changed.contains(&n) {
+                    format!("changed {}\n", n)
+                } else {
+                    format!("line {}\n", n)
+                }
+            })
+            .collect()
+    }
+
+    /// Repository with two unstaged hunks in `a.txt`
+    fn repo_with_hunks() -> tempfile::TempDir {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", &numbered(&[]), "initial");
+        fs::write(dir.path().join("a.txt"), numbered(&[2, 18])).unwrap();
+        dir
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_panel_stages_and_unstages_hunks() {
+        let dir = repo_with_hunks();
+        let mut panel = HunkPanelState::open(dir.path()).unwrap();
+        assert!(!panel.is_staged());
+        assert_eq!(panel.hunks().len(), 2);
+        assert!(panel.diff().contains("+changed 2"));
+
+        panel.handle_key(key(KeyCode::Char('j')));
+        assert!(panel.diff().contains("+changed 18"));
+        panel.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(panel.status(), Some("Staged hunk 2 of a.txt"));
+        assert_eq!(panel.hunks().len(), 1);
+        assert_eq!(panel.selected(), 0);
+
+        // Unstaging from the unstaged list is refused
+        panel.handle_key(key(KeyCode::Char('u')));
+        assert_eq!(panel.hunks().len(), 1);
+
+        panel.handle_key(key(KeyCode::Tab));
+        assert!(panel.is_staged());
+        assert_eq!(panel.hunks().len(), 1);
+        assert!(panel.diff().contains("+changed 18"));
+        panel.handle_key(key(KeyCode::Char('u')));
+        assert_eq!(panel.status(), Some("Unstaged hunk 1 of a.txt"));
+        assert!(panel.hunks().is_empty());
+        assert!(panel.diff().is_empty());
+
+        assert!(!panel.handle_key(key(KeyCode::Char('q'))));
+    }
+
+    #[test]
+    fn test_panel_renders_list_and_lines() {
+        let dir = repo_with_hunks();
+        let panel = HunkPanelState::open(dir.path()).unwrap();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 90, 20);
+        let mut buf = Buffer::empty(area);
+
+        HunkPanel::new(&panel, &theme).render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Unstaged Hunks"));
+        assert!(text.contains("a.txt:1 +1 -1"));
+        assert!(text.contains("a.txt:15 +1 -1"));
+        assert!(text.contains("+changed 2"));
+    }
+}
@@ -0,0 +1,606 @@
+//! Log Graph Widget
+//!
+//! Commit history with branch topology, refs, author and date. Commits can
+//! be checked out, cherry-picked or reverted, and an interactive rebase can
+//! be planned onto the selected commit. When an operation stops on a
+//! conflict or an `edit` step, the panel shows what is pending and offers to
+//! continue, skip or abort it.
+
+use crate::theme::Theme;
+use ait42_git::{
+    history, log, rebase, Commit, GraphRow, OperationStatus, RebaseAction, RebasePlan, Repository,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of commits loaded into the graph
+const LOG_LIMIT: usize = 500;
+
+const LOG_HINTS: &str =
+    "j/k select  c checkout  p cherry-pick  r revert  i rebase onto  a all refs  q close";
+const PLAN_HINTS: &str =
+    "j/k select  J/K move  p pick  e edit  s squash  f fixup  d drop  Enter run  Esc cancel";
+const PAUSED_HINTS: &str = "C continue  S skip  A abort";
+
+/// A rebase plan being edited
+#[derive(Debug)]
+struct PlanEditor {
+    plan: RebasePlan,
+    selected: usize,
+}
+
+/// History browser state
+#[derive(Debug)]
+pub struct LogGraphState {
+    repo: Repository,
+    rows: Vec<GraphRow>,
+    /// Index into `rows`, always a row with a commit
+    selected: usize,
+    all_refs: bool,
+    plan: Option<PlanEditor>,
+    operation: OperationStatus,
+    status: Option<String>,
+}
+
+impl LogGraphState {
+    /// Open the panel for the repository containing `path`
+    pub fn open(path: &Path) -> ait42_git::Result<Self> {
+        let mut state = Self {
+            repo: Repository::open(path)?,
+            rows: Vec::new(),
+            selected: 0,
+            all_refs: false,
+            plan: None,
+            operation: OperationStatus::Completed,
+            status: None,
+        };
+        state.refresh();
+        Ok(state)
+    }
+
+    /// Graph lines, newest first
+    pub fn rows(&self) -> &[GraphRow] {
+        &self.rows
+    }
+
+    /// Selected commit
+    pub fn selected_commit(&self) -> Option<&Commit> {
+        self.rows.get(self.selected)?.commit.as_ref()
+    }
+
+    /// Rebase plan being edited, with the selected step
+    pub fn plan(&self) -> Option<(&RebasePlan, usize)> {
+        self.plan
+            .as_ref()
+            .map(|editor| (&editor.plan, editor.selected))
+    }
+
+    /// State of the current multi-step operation
+    pub fn operation(&self) -> &OperationStatus {
+        &self.operation
+    }
+
+    /// Result of the last action
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Reload the graph and the operation state, keeping the selected commit
+    pub fn refresh(&mut self) {
+        let selected_sha = self.selected_commit().map(|c| c.sha.clone());
+
+        match log::graph(&self.repo, LOG_LIMIT, self.all_refs) {
+            Ok(rows) => self.rows = rows,
+            Err(e) => {
+                self.rows.clear();
+                self.status = Some(e.to_string());
+            }
+        }
+        match history::status(&self.repo) {
+            Ok(operation) => self.operation = operation,
+            Err(e) => self.status = Some(e.to_string()),
+        }
+
+        self.selected = selected_sha
+            .and_then(|sha| {
+                self.rows
+                    .iter()
+                    .position(|r| r.commit.as_ref().is_some_and(|c| c.sha == sha))
+            })
+            .or_else(|| self.rows.iter().position(|r| r.commit.is_some()))
+            .unwrap_or(0);
+    }
+
+    /// Move the selection by `delta` commits, skipping graph-only lines
+    pub fn select_relative(&mut self, delta: isize) {
+        let commits: Vec<usize> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.commit.is_some())
+            .map(|(i, _)| i)
+            .collect();
+        let Some(current) = commits.iter().position(|&i| i == self.selected) else {
+            return;
+        };
+        let target = current.saturating_add_signed(delta).min(commits.len() - 1);
+        self.selected = commits[target];
+    }
+
+    /// Record the outcome of an action and reload
+    fn report(&mut self, result: ait42_git::Result<String>) {
+        self.status = Some(match result {
+            Ok(message) => message,
+            Err(e) => e.to_string(),
+        });
+        self.refresh();
+    }
+
+    fn describe(operation: &OperationStatus, done: String) -> String {
+        match operation {
+            OperationStatus::Completed => done,
+            OperationStatus::Paused {
+                operation,
+                conflicts,
+                ..
+            } if conflicts.is_empty() => format!("{} stopped; amend and continue", operation),
+            OperationStatus::Paused {
+                operation,
+                conflicts,
+                ..
+            } => format!(
+                "{} stopped: resolve {} and stage it, then continue",
+                operation,
+                conflicts.join(", ")
+            ),
+        }
+    }
+
+    /// Check out the selected commit, preferring a local branch at it
+    pub fn checkout_selected(&mut self) {
+        let Some(commit) = self.selected_commit() else {
+            return;
+        };
+        let target = commit
+            .branches()
+            .next()
+            .map(str::to_string)
+            .unwrap_or_else(|| commit.sha.clone());
+        let result =
+            history::checkout(&self.repo, &target).map(|()| format!("Checked out {}", target));
+        self.report(result);
+    }
+
+    fn apply_selected(
+        &mut self,
+        verb: &str,
+        op: fn(&Repository, &str) -> ait42_git::Result<OperationStatus>,
+    ) {
+        let Some(commit) = self.selected_commit() else {
+            return;
+        };
+        let (sha, short) = (commit.sha.clone(), commit.short_sha().to_string());
+        let result = op(&self.repo, &sha)
+            .map(|status| Self::describe(&status, format!("{} {}", verb, short)));
+        self.report(result);
+    }
+
+    /// Start planning a rebase of the commits after the selected one
+    pub fn plan_rebase(&mut self) {
+        let Some(commit) = self.selected_commit() else {
+            return;
+        };
+        match RebasePlan::new(&self.repo, &commit.sha) {
+            Ok(plan) if plan.steps.is_empty() => {
+                self.status = Some("No commits after the selected one".to_string());
+            }
+            Ok(plan) => {
+                // Steps are oldest first; start on the commit just above the base
+                self.plan = Some(PlanEditor { plan, selected: 0 });
+                self.status = None;
+            }
+            Err(e) => self.status = Some(e.to_string()),
+        }
+    }
+
+    /// Run the plan being edited
+    pub fn run_plan(&mut self) {
+        let Some(editor) = self.plan.take() else {
+            return;
+        };
+        let count = editor.plan.steps.len();
+        let result = rebase::start(&self.repo, &editor.plan)
+            .map(|status| Self::describe(&status, format!("Rebased {} commits", count)));
+        if result.is_err() {
+            // Keep the plan so it can be fixed and retried
+            self.plan = Some(editor);
+        }
+        self.report(result);
+    }
+
+    fn resume(&mut self, skip: bool) {
+        let result = if skip {
+            history::skip(&self.repo)
+        } else {
+            history::continue_operation(&self.repo)
+        }
+        .map(|status| Self::describe(&status, "Operation completed".to_string()));
+        self.report(result);
+    }
+
+    fn abort(&mut self) {
+        let result = history::abort(&self.repo).map(|()| "Operation aborted".to_string());
+        self.report(result);
+    }
+
+    fn handle_plan_key(&mut self, key: KeyEvent) {
+        let Some(editor) = &mut self.plan else {
+            return;
+        };
+        let last = editor.plan.steps.len().saturating_sub(1);
+        let action = match key.code {
+            KeyCode::Esc => {
+                self.plan = None;
+                return;
+            }
+            KeyCode::Enter => return self.run_plan(),
+            KeyCode::Char('j') | KeyCode::Down => {
+                editor.selected = (editor.selected + 1).min(last);
+                return;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                editor.selected = editor.selected.saturating_sub(1);
+                return;
+            }
+            KeyCode::Char('J') => {
+                editor.selected = editor.plan.move_step(editor.selected, 1);
+                return;
+            }
+            KeyCode::Char('K') => {
+                editor.selected = editor.plan.move_step(editor.selected, -1);
+                return;
+            }
+            KeyCode::Char('p') => RebaseAction::Pick,
+            KeyCode::Char('e') => RebaseAction::Edit,
+            KeyCode::Char('s') => RebaseAction::Squash,
+            KeyCode::Char('f') => RebaseAction::Fixup,
+            KeyCode::Char('d') => RebaseAction::Drop,
+            _ => return,
+        };
+        editor.plan.set_action(editor.selected, action);
+    }
+
+    /// Handle a key press; returns `false` when the panel should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.plan.is_some() {
+            self.handle_plan_key(key);
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Char('j') | KeyCode::Down => self.select_relative(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_relative(-1),
+            KeyCode::PageDown => self.select_relative(10),
+            KeyCode::PageUp => self.select_relative(-10),
+            KeyCode::Char('c') => self.checkout_selected(),
+            KeyCode::Char('p') => self.apply_selected("Cherry-picked", history::cherry_pick),
+            KeyCode::Char('r') => self.apply_selected("Reverted", history::revert),
+            KeyCode::Char('i') => self.plan_rebase(),
+            KeyCode::Char('a') => {
+                self.all_refs = !self.all_refs;
+                self.refresh();
+            }
+            KeyCode::Char('C') if self.operation.is_paused() => self.resume(false),
+            KeyCode::Char('S') if self.operation.is_paused() => self.resume(true),
+            KeyCode::Char('A') if self.operation.is_paused() => self.abort(),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Compact age of a commit, e.g. `5m`, `3d`
+pub(crate) fn age(timestamp: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(timestamp);
+    let secs = (now - timestamp).max(0);
+    match secs {
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3600),
+        s if s < 86_400 * 60 => format!("{}d", s / 86_400),
+        s if s < 86_400 * 730 => format!("{}mo", s / (86_400 * 30)),
+        s => format!("{}y", s / (86_400 * 365)),
+    }
+}
+
+/// Log graph widget
+pub struct LogGraph<'a> {
+    state: &'a LogGraphState,
+    theme: &'a Theme,
+}
+
+impl<'a> LogGraph<'a> {
+    /// Create a panel rendering `state`
+    pub fn new(state: &'a LogGraphState, theme: &'a Theme) -> Self {
+        Self { state, theme }
+    }
+
+    fn fg(&self, style: Style) -> Style {
+        Style::default().fg(style.fg.unwrap_or(self.theme.foreground))
+    }
+
+    fn render_log(&self, area: Rect, buf: &mut Buffer) {
+        let text_style = Style::default().fg(self.theme.foreground);
+        let graph_style = self.fg(self.theme.function);
+        let ref_style = self.fg(self.theme.keyword).add_modifier(Modifier::BOLD);
+        let dim_style = self.fg(self.theme.comment);
+
+        if self.state.rows().is_empty() {
+            buf.set_stringn(area.x, area.y, "No commits", area.width as usize, dim_style);
+            return;
+        }
+
+        let height = area.height as usize;
+        let skip = self.state.selected.saturating_sub(height.saturating_sub(1));
+        for (row, (index, line)) in self
+            .state
+            .rows()
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(height)
+            .enumerate()
+        {
+            let y = area.y + row as u16;
+            let right = area.x + area.width;
+            let selected = index == self.state.selected;
+            if selected {
+                buf.set_style(Rect::new(area.x, y, area.width, 1), self.theme.selection);
+            }
+            let style = |base: Style| {
+                if selected {
+                    base.patch(self.theme.selection)
+                } else {
+                    base
+                }
+            };
+
+            let (x, _) =
+                buf.set_stringn(area.x, y, &line.graph, area.width as usize, style(graph_style));
+            let Some(commit) = &line.commit else {
+                continue;
+            };
+
+            let mut x = x + 1;
+            let mut put = |text: &str, base: Style, x: &mut u16| {
+                if *x < right {
+                    let (end, _) = buf.set_stringn(*x, y, text, (right - *x) as usize, style(base));
+                    *x = end + 1;
+                }
+            };
+            put(commit.short_sha(), dim_style, &mut x);
+            let labels: Vec<String> = commit.labels().collect();
+            if !labels.is_empty() {
+                put(&format!("({})", labels.join(", ")), ref_style, &mut x);
+            }
+            put(&commit.subject, text_style, &mut x);
+            put(&format!("{} {}", commit.author, age(commit.timestamp)), dim_style, &mut x);
+        }
+    }
+
+    fn render_plan(&self, plan: &RebasePlan, selected: usize, area: Rect, buf: &mut Buffer) {
+        let dim_style = self.fg(self.theme.comment);
+        let header = format!("Rebase onto {} (oldest first)", &plan.onto[..7]);
+        buf.set_stringn(area.x, area.y, header, area.width as usize, dim_style);
+
+        let height = area.height.saturating_sub(1) as usize;
+        let skip = selected.saturating_sub(height.saturating_sub(1));
+        for (row, (index, step)) in plan
+            .steps
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(height)
+            .enumerate()
+        {
+            let action_style = match step.action {
+                RebaseAction::Pick => self.fg(self.theme.string),
+                RebaseAction::Drop => dim_style.add_modifier(Modifier::CROSSED_OUT),
+                _ => self.fg(self.theme.keyword),
+            };
+            let style = if index == selected {
+                action_style.patch(self.theme.selection)
+            } else {
+                action_style
+            };
+            let line = format!("{:<6} {} {}", step.action.keyword(), &step.sha[..7], step.subject);
+            buf.set_stringn(
+                area.x,
+                area.y + 1 + row as u16,
+                format!("{:width$}", line, width = area.width as usize),
+                area.width as usize,
+                style,
+            );
+        }
+    }
+}
+
+impl<'a> Widget for LogGraph<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 30 || area.height < 6 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        let title = if self.state.all_refs {
+            " History (all refs) "
+        } else {
+            " History "
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let paused = self.state.operation().is_paused();
+        let footer_height = if paused { 2 } else { 1 };
+        let body =
+            Rect::new(inner.x, inner.y, inner.width, inner.height.saturating_sub(footer_height));
+
+        match self.state.plan() {
+            Some((plan, selected)) => self.render_plan(plan, selected, body, buf),
+            None => self.render_log(body, buf),
+        }
+
+        let dim_style = self.fg(self.theme.comment);
+        let mut y = inner.y + body.height;
+        if let OperationStatus::Paused {
+            operation,
+            conflicts,
+            ..
+        } = self.state.operation()
+        {
+            let banner = if conflicts.is_empty() {
+                format!("{} in progress  {}", operation, PAUSED_HINTS)
+            } else {
+                format!(
+                    "{} paused, conflicts: {}  {}",
+                    operation,
+                    conflicts.join(", "),
+                    PAUSED_HINTS
+                )
+            };
+            buf.set_stringn(
+                inner.x,
+                y,
+                banner,
+                inner.width as usize,
+                self.fg(self.theme.keyword).add_modifier(Modifier::BOLD),
+            );
+            y += 1;
+        }
+
+        let hints = if self.state.plan().is_some() {
+            PLAN_HINTS
+        } else {
+            LOG_HINTS
+        };
+        let footer = self.state.status().unwrap_or(hints);
+        buf.set_stringn(inner.x, y, footer, inner.width as usize, dim_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_git::repo::test_support::{commit_file, init_repo};
+    use crossterm::event::KeyModifiers;
+    use std::fs;
+
+    fn repo_with_history() -> tempfile::TempDir {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "base\n", "base");
+        commit_file(&repo, "b.txt", "b\n", "add b");
+        commit_file(&repo, "a.txt", "changed\n", "change a");
+        dir
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn subjects(state: &LogGraphState) -> Vec<String> {
+        state
+            .rows()
+            .iter()
+            .filter_map(|r| r.commit.as_ref())
+            .map(|c| c.subject.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_revert_and_selection() {
+        let dir = repo_with_history();
+        let mut panel = LogGraphState::open(dir.path()).unwrap();
+        assert_eq!(subjects(&panel), ["change a", "add b", "base"]);
+        assert!(panel.selected_commit().unwrap().is_head());
+
+        panel.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(panel.selected_commit().unwrap().subject, "add b");
+        panel.handle_key(key(KeyCode::Char('r')));
+        assert!(panel.status().unwrap().starts_with("Reverted"));
+        assert!(!dir.path().join("b.txt").exists());
+
+        // Selection follows the same commit after the reload
+        assert_eq!(panel.selected_commit().unwrap().subject, "add b");
+        assert!(!panel.handle_key(key(KeyCode::Char('q'))));
+    }
+
+    #[test]
+    fn test_rebase_plan_squash() {
+        let dir = repo_with_history();
+        let mut panel = LogGraphState::open(dir.path()).unwrap();
+
+        panel.select_relative(2);
+        panel.handle_key(key(KeyCode::Char('i')));
+        let (plan, selected) = panel.plan().unwrap();
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(selected, 0);
+
+        panel.handle_key(key(KeyCode::Char('j')));
+        panel.handle_key(key(KeyCode::Char('f')));
+        panel.handle_key(key(KeyCode::Enter));
+        assert!(panel.plan().is_none());
+        assert_eq!(subjects(&panel), ["add b", "base"]);
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "changed\n");
+    }
+
+    #[test]
+    fn test_conflict_pause_and_abort() {
+        let dir = repo_with_history();
+        let mut panel = LogGraphState::open(dir.path()).unwrap();
+
+        // Reverting "base" conflicts with the later change to a.txt
+        panel.select_relative(2);
+        panel.handle_key(key(KeyCode::Char('r')));
+        match panel.operation() {
+            OperationStatus::Paused { conflicts, .. } => assert_eq!(conflicts, &["a.txt"]),
+            other => panic!("expected a pause, got {:?}", other),
+        }
+
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 100, 12);
+        let mut buf = Buffer::empty(area);
+        LogGraph::new(&panel, &theme).render(area, &mut buf);
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("revert paused, conflicts: a.txt"));
+        assert!(text.contains("(HEAD -> main)"));
+
+        panel.handle_key(key(KeyCode::Char('A')));
+        assert_eq!(panel.operation(), &OperationStatus::Completed);
+        assert_eq!(subjects(&panel).len(), 3);
+    }
+
+    #[test]
+    fn test_age() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        assert_eq!(age(now - 120), "2m");
+        assert_eq!(age(now - 3 * 86_400), "3d");
+    }
+}
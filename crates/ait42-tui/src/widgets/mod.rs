@@ -2,16 +2,40 @@
 //!
 //! Reusable UI components for the editor.
 
+pub mod agent_launcher;
+pub mod blame_gutter;
+pub mod cheat_sheet;
 pub mod command_palette;
+pub mod diff_gutter;
+pub mod diff_view;
 pub mod editor;
+pub mod hunk_panel;
+pub mod log_graph;
+pub mod search_prompt;
 pub mod sidebar;
+pub mod stash_panel;
 pub mod statusline;
 pub mod tab_bar;
+pub mod terminal_copy;
 pub mod terminal_panel;
+pub mod which_key;
+pub mod workspace_search;
 
+pub use agent_launcher::{AgentLauncher, AgentLauncherState};
+pub use blame_gutter::{BlameCache, BlameGutter};
+pub use diff_gutter::{LineChange, LineChangeCache};
+pub use cheat_sheet::CheatSheet;
 pub use command_palette::CommandPalette;
+pub use diff_view::DiffView;
 pub use editor::EditorWidget;
+pub use hunk_panel::{HunkPanel, HunkPanelState};
+pub use log_graph::{LogGraph, LogGraphState};
+pub use search_prompt::{SearchField, SearchPrompt, SearchPromptState};
 pub use sidebar::{FileEntry, FileEntryType, FileTree, Sidebar};
+pub use stash_panel::{StashPanel, StashPanelState};
 pub use statusline::StatusLine;
 pub use tab_bar::{Tab, TabBar};
+pub use terminal_copy::{CopyPosition, CopySelection, TerminalCopyState, Yank};
 pub use terminal_panel::TerminalPanel;
+pub use which_key::WhichKeyPopup;
+pub use workspace_search::{SearchResult, WorkspaceSearch, WorkspaceSearchState};
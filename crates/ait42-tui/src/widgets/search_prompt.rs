@@ -0,0 +1,329 @@
+//! Search Prompt Widget
+//!
+//! One-line prompt drawn over the status line for searching the buffer and,
+//! optionally, replacing the matches. The pattern is compiled as it is typed
+//! so matches can be highlighted incrementally; Enter accepts and Esc
+//! cancels. Case is ignored unless the pattern contains an uppercase letter.
+
+use crate::theme::Theme;
+use ait42_core::{SearchOptions, SearchQuery};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+use std::ops::Range;
+
+/// Field of the prompt being edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchField {
+    Pattern,
+    Replacement,
+}
+
+/// Search prompt state
+#[derive(Debug)]
+pub struct SearchPromptState {
+    pattern: String,
+    replacement: Option<String>,
+    field: SearchField,
+    regex: bool,
+    origin: usize,
+    scope: Option<Range<usize>>,
+    query: Option<SearchQuery>,
+    error: Option<String>,
+    accepted: bool,
+}
+
+impl SearchPromptState {
+    /// Prompt for a search starting at byte offset `origin`
+    pub fn search(origin: usize) -> Self {
+        Self {
+            pattern: String::new(),
+            replacement: None,
+            field: SearchField::Pattern,
+            regex: false,
+            origin,
+            scope: None,
+            query: None,
+            error: None,
+            accepted: false,
+        }
+    }
+
+    /// Prompt for a pattern and its replacement, limited to `scope` if set
+    pub fn replace(origin: usize, scope: Option<Range<usize>>) -> Self {
+        Self {
+            replacement: Some(String::new()),
+            scope,
+            ..Self::search(origin)
+        }
+    }
+
+    /// Pattern typed so far
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Replacement typed so far, `None` for a plain search
+    pub fn replacement(&self) -> Option<&str> {
+        self.replacement.as_deref()
+    }
+
+    /// Field receiving input
+    pub fn field(&self) -> SearchField {
+        self.field
+    }
+
+    /// Whether the pattern is a regex
+    pub fn is_regex(&self) -> bool {
+        self.regex
+    }
+
+    /// Cursor position when the prompt was opened
+    pub fn origin(&self) -> usize {
+        self.origin
+    }
+
+    /// Range replacements are limited to
+    pub fn scope(&self) -> Option<Range<usize>> {
+        self.scope.clone()
+    }
+
+    /// Compiled pattern, `None` while empty or invalid
+    pub fn query(&self) -> Option<&SearchQuery> {
+        self.query.as_ref()
+    }
+
+    /// Why the pattern does not compile
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Whether the prompt was closed with Enter rather than Esc
+    pub fn is_accepted(&self) -> bool {
+        self.accepted
+    }
+
+    fn compile(&mut self) {
+        self.error = None;
+        self.query = None;
+        if self.pattern.is_empty() {
+            return;
+        }
+        let options = SearchOptions::smart_case(&self.pattern, self.regex);
+        match SearchQuery::new(&self.pattern, options) {
+            Ok(query) => self.query = Some(query),
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// Append typed or pasted text to the field being edited
+    pub fn insert_text(&mut self, text: &str) {
+        let text = text.replace(['\r', '\n'], "");
+        match (self.field, &mut self.replacement) {
+            (SearchField::Replacement, Some(replacement)) => replacement.push_str(&text),
+            _ => {
+                self.pattern.push_str(&text);
+                self.compile();
+            }
+        }
+    }
+
+    fn toggle_field(&mut self) {
+        if self.replacement.is_some() {
+            self.field = match self.field {
+                SearchField::Pattern => SearchField::Replacement,
+                SearchField::Replacement => SearchField::Pattern,
+            };
+        }
+    }
+
+    /// Handle a key press; returns `false` when the prompt should close
+    ///
+    /// In a replace prompt, Enter on the pattern moves on to the
+    /// replacement. Ctrl+R switches between literal and regex patterns.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Enter => {
+                if self.replacement.is_some() && self.field == SearchField::Pattern {
+                    self.field = SearchField::Replacement;
+                } else {
+                    self.accepted = true;
+                    return false;
+                }
+            }
+            KeyCode::Tab => self.toggle_field(),
+            KeyCode::Backspace => match (self.field, &mut self.replacement) {
+                (SearchField::Replacement, Some(replacement)) => {
+                    replacement.pop();
+                }
+                _ => {
+                    self.pattern.pop();
+                    self.compile();
+                }
+            },
+            KeyCode::Char('r') if ctrl => {
+                self.regex = !self.regex;
+                self.compile();
+            }
+            KeyCode::Char(ch) if !ctrl => self.insert_text(&ch.to_string()),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Search prompt widget
+pub struct SearchPrompt<'a> {
+    state: &'a SearchPromptState,
+    matches: usize,
+    theme: &'a Theme,
+}
+
+impl<'a> SearchPrompt<'a> {
+    /// Create a prompt rendering `state`
+    pub fn new(state: &'a SearchPromptState, theme: &'a Theme) -> Self {
+        Self {
+            state,
+            matches: 0,
+            theme,
+        }
+    }
+
+    /// Number of matches to report
+    pub fn matches(mut self, matches: usize) -> Self {
+        self.matches = matches;
+        self
+    }
+
+    /// Text after the prompt, with a block cursor on the field being edited
+    fn input(&self) -> String {
+        let cursor = |field| {
+            if self.state.field() == field {
+                "█"
+            } else {
+                ""
+            }
+        };
+        match self.state.replacement() {
+            Some(replacement) => format!(
+                "Replace: {}{} → {}{}",
+                self.state.pattern(),
+                cursor(SearchField::Pattern),
+                replacement,
+                cursor(SearchField::Replacement)
+            ),
+            None => format!("/{}{}", self.state.pattern(), cursor(SearchField::Pattern)),
+        }
+    }
+
+    fn summary(&self) -> String {
+        let mode = if self.state.is_regex() {
+            "regex"
+        } else {
+            "text"
+        };
+        match self.state.error() {
+            // Regex errors span several lines, the last one says what is wrong
+            Some(error) => format!("{}  [{}]", error.lines().last().unwrap_or(error), mode),
+            None if self.state.pattern().is_empty() => format!("[{}]", mode),
+            None if self.matches == 1 => format!("1 match  [{}]", mode),
+            None => format!("{} matches  [{}]", self.matches, mode),
+        }
+    }
+}
+
+impl<'a> Widget for SearchPrompt<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let text_style = Style::default()
+            .fg(self.theme.foreground)
+            .bg(self.theme.background);
+        let summary_style = match self.state.error() {
+            Some(_) => Style::default().fg(self.theme.keyword.fg.unwrap_or(self.theme.foreground)),
+            None => Style::default().fg(self.theme.comment.fg.unwrap_or(self.theme.foreground)),
+        };
+        let row = Rect::new(area.x, area.y, area.width, 1);
+        buf.set_style(row, text_style);
+
+        let width = area.width as usize;
+        let (x, _) = buf.set_stringn(area.x, area.y, self.input(), width, text_style);
+
+        // Summary right-aligned, dropped if the input leaves no room
+        let summary = self.summary();
+        let summary_width = summary.chars().count() as u16;
+        let used = x - area.x;
+        if used + summary_width + 2 <= area.width {
+            let x = area.right() - summary_width - 1;
+            buf.set_stringn(x, area.y, summary, summary_width as usize, summary_style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_pattern_compiles_as_typed() {
+        let mut prompt = SearchPromptState::search(0);
+        assert!(prompt.query().is_none());
+
+        prompt.insert_text("a(");
+        assert!(prompt.query().is_some());
+
+        // The same text is invalid as a regex
+        prompt.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert!(prompt.is_regex());
+        assert!(prompt.query().is_none());
+        assert!(prompt.error().is_some());
+
+        prompt.handle_key(key(KeyCode::Backspace));
+        assert_eq!(prompt.pattern(), "a");
+        assert!(prompt.error().is_none());
+
+        assert!(!prompt.handle_key(key(KeyCode::Enter)));
+        assert!(prompt.is_accepted());
+    }
+
+    #[test]
+    fn test_replace_prompt_fields() {
+        let mut prompt = SearchPromptState::replace(0, Some(2..8));
+        prompt.insert_text("foo");
+        assert!(prompt.handle_key(key(KeyCode::Enter)));
+        assert_eq!(prompt.field(), SearchField::Replacement);
+
+        prompt.insert_text("bar\n");
+        prompt.handle_key(key(KeyCode::Backspace));
+        assert_eq!(prompt.pattern(), "foo");
+        assert_eq!(prompt.replacement(), Some("ba"));
+        assert_eq!(prompt.scope(), Some(2..8));
+
+        assert!(!prompt.handle_key(key(KeyCode::Esc)));
+        assert!(!prompt.is_accepted());
+    }
+
+    #[test]
+    fn test_renders_input_and_count() {
+        let mut prompt = SearchPromptState::search(0);
+        prompt.insert_text("needle");
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 60, 1);
+        let mut buf = Buffer::empty(area);
+
+        SearchPrompt::new(&prompt, &theme)
+            .matches(3)
+            .render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.starts_with("/needle█"));
+        assert!(text.contains("3 matches  [text]"));
+    }
+}
@@ -1,8 +1,10 @@
 //! Sidebar Widget
 //!
-//! Displays a file tree hierarchy with expand/collapse controls and file type icons.
+//! Displays a file tree hierarchy with expand/collapse controls, file type icons
+//! and git status badges.
 
 use crate::theme::Theme;
+use ait42_git::FileStatus;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -29,6 +31,8 @@ pub struct FileEntry {
     pub level: usize,
     /// Whether this directory is expanded (only relevant for directories)
     pub expanded: bool,
+    /// Git status badge shown at the right edge
+    pub status: Option<FileStatus>,
 }
 
 impl FileEntry {
@@ -39,6 +43,7 @@ impl FileEntry {
             entry_type,
             level,
             expanded: false,
+            status: None,
         }
     }
 
@@ -58,6 +63,12 @@ impl FileEntry {
         self
     }
 
+    /// Set git status
+    pub fn status(mut self, status: Option<FileStatus>) -> Self {
+        self.status = status;
+        self
+    }
+
     /// Get the file name for display
     fn display_name(&self) -> String {
         self.path
@@ -241,6 +252,18 @@ impl<'a> Sidebar<'a> {
 
         // Render the text
         buf.set_string(area.x, y, &display_line, style);
+
+        // Render the git status badge over the end of the line
+        if let Some(status) = entry.status {
+            if area.width >= 2 {
+                buf.get_mut(area.right() - 2, y)
+                    .set_char(' ')
+                    .set_style(style);
+                buf.get_mut(area.right() - 1, y)
+                    .set_char(status.badge())
+                    .set_style(style.patch(self.theme.git_status(status)));
+            }
+        }
     }
 }
 
@@ -379,4 +402,25 @@ mod tests {
 
         assert!(!sidebar.show_header);
     }
+
+    #[test]
+    fn test_status_badge() {
+        let theme = Theme::default();
+        let mut tree = FileTree::new(PathBuf::from("/project"));
+        tree.add_entry(FileEntry::file(PathBuf::from("/project/main.rs"), 0));
+        tree.add_entry(
+            FileEntry::file(PathBuf::from("/project/new.rs"), 0)
+                .status(Some(FileStatus::Untracked)),
+        );
+
+        let area = Rect::new(0, 0, 20, 4);
+        let mut buf = Buffer::empty(area);
+        Sidebar::new(&tree, 0, &theme)
+            .hide_header()
+            .render(area, &mut buf);
+
+        assert_eq!(buf.get(19, 0).symbol(), " ");
+        assert_eq!(buf.get(19, 1).symbol(), "?");
+        assert_eq!(buf.get(19, 1).fg, theme.git_untracked.fg.unwrap());
+    }
 }
@@ -0,0 +1,311 @@
+//! Stash Panel Widget
+//!
+//! Lists the repository's stashes next to the patch of the selected one, with
+//! keys to apply, pop, drop or create stashes.
+
+use crate::{theme::Theme, widgets::DiffView};
+use ait42_git::{stash, Repository, StashEntry};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::path::Path;
+
+/// Key hints shown in the panel footer
+const HINTS: &str = "j/k select  J/K scroll  a apply  p pop  d drop  s stash  q close";
+
+/// Stash browser state
+#[derive(Debug)]
+pub struct StashPanelState {
+    repo: Repository,
+    entries: Vec<StashEntry>,
+    selected: usize,
+    diff: String,
+    diff_scroll: usize,
+    status: Option<String>,
+}
+
+impl StashPanelState {
+    /// Open the panel for the repository containing `path`
+    pub fn open(path: &Path) -> ait42_git::Result<Self> {
+        let mut state = Self {
+            repo: Repository::open(path)?,
+            entries: Vec::new(),
+            selected: 0,
+            diff: String::new(),
+            diff_scroll: 0,
+            status: None,
+        };
+        state.refresh();
+        Ok(state)
+    }
+
+    /// Stashes, most recent first
+    pub fn entries(&self) -> &[StashEntry] {
+        &self.entries
+    }
+
+    /// Index of the selected stash
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Patch of the selected stash
+    pub fn diff(&self) -> &str {
+        &self.diff
+    }
+
+    /// Result of the last action
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Reload the stash list and the selected patch
+    pub fn refresh(&mut self) {
+        match stash::list(&self.repo) {
+            Ok(entries) => self.entries = entries,
+            Err(e) => {
+                self.entries.clear();
+                self.status = Some(e.to_string());
+            }
+        }
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+        self.load_diff();
+    }
+
+    fn load_diff(&mut self) {
+        self.diff_scroll = 0;
+        self.diff = if self.entries.is_empty() {
+            String::new()
+        } else {
+            stash::show(&self.repo, self.selected).unwrap_or_else(|e| e.to_string())
+        };
+    }
+
+    /// Move the selection by `delta` entries
+    pub fn select_relative(&mut self, delta: isize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let last = self.entries.len() - 1;
+        let selected = self.selected.saturating_add_signed(delta).min(last);
+        if selected != self.selected {
+            self.selected = selected;
+            self.load_diff();
+        }
+    }
+
+    /// Scroll the patch by `delta` lines
+    pub fn scroll_diff(&mut self, delta: isize) {
+        let max = self.diff.lines().count().saturating_sub(1);
+        self.diff_scroll = self.diff_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    /// Run a stash operation on the selected entry and report the outcome
+    fn act(&mut self, verb: &str, op: fn(&Repository, usize) -> ait42_git::Result<()>) {
+        let Some(entry) = self.entries.get(self.selected) else {
+            return;
+        };
+        let reference = entry.reference();
+        self.status = Some(match op(&self.repo, self.selected) {
+            Ok(()) => format!("{} {}", verb, reference),
+            Err(e) => e.to_string(),
+        });
+        self.refresh();
+    }
+
+    /// Stash the working tree, including untracked files
+    pub fn stash_changes(&mut self) {
+        self.status = Some(match stash::push(&self.repo, None, true) {
+            Ok(true) => "Stashed local changes".to_string(),
+            Ok(false) => "No local changes to stash".to_string(),
+            Err(e) => e.to_string(),
+        });
+        self.selected = 0;
+        self.refresh();
+    }
+
+    /// Handle a key press; returns `false` when the panel should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Char('j') | KeyCode::Down => self.select_relative(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_relative(-1),
+            KeyCode::Char('J') | KeyCode::PageDown => self.scroll_diff(10),
+            KeyCode::Char('K') | KeyCode::PageUp => self.scroll_diff(-10),
+            KeyCode::Char('a') => self.act("Applied", stash::apply),
+            KeyCode::Char('p') => self.act("Popped", stash::pop),
+            KeyCode::Char('d') => self.act("Dropped", stash::drop),
+            KeyCode::Char('s') => self.stash_changes(),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Stash panel widget
+pub struct StashPanel<'a> {
+    state: &'a StashPanelState,
+    theme: &'a Theme,
+}
+
+impl<'a> StashPanel<'a> {
+    /// Create a panel rendering `state`
+    pub fn new(state: &'a StashPanelState, theme: &'a Theme) -> Self {
+        Self { state, theme }
+    }
+}
+
+impl<'a> Widget for StashPanel<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 30 || area.height < 6 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(" Stashes ");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let body_height = inner.height.saturating_sub(1);
+        let list_width = (inner.width / 3).max(20).min(inner.width);
+        let list_area = Rect::new(inner.x, inner.y, list_width, body_height);
+        let diff_area = Rect::new(
+            inner.x + list_width + 1,
+            inner.y,
+            inner.width.saturating_sub(list_width + 1),
+            body_height,
+        );
+
+        let text_style = Style::default().fg(self.theme.foreground);
+        let dim_style = Style::default().fg(self.theme.comment.fg.unwrap_or(self.theme.foreground));
+
+        // Stash list
+        if self.state.entries().is_empty() {
+            buf.set_stringn(
+                list_area.x,
+                list_area.y,
+                "No stashes",
+                list_area.width as usize,
+                dim_style,
+            );
+        }
+        let skip = self
+            .state
+            .selected()
+            .saturating_sub(list_area.height.saturating_sub(1) as usize);
+        for (row, entry) in self
+            .state
+            .entries()
+            .iter()
+            .skip(skip)
+            .take(list_area.height as usize)
+            .enumerate()
+        {
+            let style = if entry.index == self.state.selected() {
+                self.theme.selection.add_modifier(Modifier::BOLD)
+            } else {
+                text_style
+            };
+            let label = format!("{} {}", entry.index, entry.message);
+            buf.set_stringn(
+                list_area.x,
+                list_area.y + row as u16,
+                format!("{:width$}", label, width = list_area.width as usize),
+                list_area.width as usize,
+                style,
+            );
+        }
+
+        // Separator
+        for y in inner.y..inner.y + body_height {
+            buf.set_string(inner.x + list_width, y, "│", self.theme.border);
+        }
+
+        // Patch of the selected stash
+        DiffView::new(self.state.diff(), self.theme)
+            .scroll(self.state.diff_scroll)
+            .render(diff_area, buf);
+
+        // Footer: last action result, or key hints
+        let footer = self.state.status().unwrap_or(HINTS);
+        buf.set_stringn(inner.x, inner.y + body_height, footer, inner.width as usize, dim_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_git::repo::test_support::{commit_file, init_repo};
+    use crossterm::event::KeyModifiers;
+    use std::fs;
+
+    fn repo_with_stash() -> tempfile::TempDir {
+        let (dir, repo) = init_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+        fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        repo.run(&["stash", "push", "-q", "-m", "parked"]).unwrap();
+        dir
+    }
+
+    fn key(ch: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_panel_actions() {
+        let dir = repo_with_stash();
+        let mut panel = StashPanelState::open(dir.path()).unwrap();
+
+        assert_eq!(panel.entries().len(), 1);
+        assert!(panel.diff().contains("+two"));
+
+        fs::write(dir.path().join("b.txt"), "new\n").unwrap();
+        assert!(panel.handle_key(key('s')));
+        assert_eq!(panel.entries().len(), 2);
+        assert_eq!(panel.status(), Some("Stashed local changes"));
+
+        panel.handle_key(key('j'));
+        assert_eq!(panel.selected(), 1);
+        panel.handle_key(key('p'));
+        assert_eq!(panel.status(), Some("Popped stash@{1}"));
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "two\n");
+        assert_eq!(panel.entries().len(), 1);
+        assert_eq!(panel.selected(), 0);
+
+        panel.handle_key(key('d'));
+        assert!(panel.entries().is_empty());
+        assert!(panel.diff().is_empty());
+
+        assert!(!panel.handle_key(key('q')));
+    }
+
+    #[test]
+    fn test_panel_renders_list_and_diff() {
+        let dir = repo_with_stash();
+        let panel = StashPanelState::open(dir.path()).unwrap();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 90, 20);
+        let mut buf = Buffer::empty(area);
+
+        StashPanel::new(&panel, &theme).render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Stashes"));
+        assert!(text.contains("0 parked"));
+        assert!(text.contains("+two"));
+    }
+
+    #[test]
+    fn test_open_outside_repository() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(StashPanelState::open(dir.path()).is_err());
+    }
+}
@@ -3,6 +3,8 @@
 //! Displays editor state, cursor position, and file information.
 
 use crate::{keybinds::Mode, theme::Theme};
+use ait42_core::{Encoding, LineEnding};
+use ait42_git::FileStatus;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -17,8 +19,13 @@ pub struct StatusLine<'a> {
     mode: Mode,
     file_path: Option<&'a Path>,
     dirty: bool,
+    git_status: Option<FileStatus>,
     cursor_pos: (usize, usize), // (line, col)
     file_type: Option<&'a str>,
+    /// Encoding, whether there is a byte order mark, and line ending
+    file_format: Option<(Encoding, bool, LineEnding)>,
+    /// Register a macro is being recorded into
+    recording: Option<char>,
     total_lines: usize,
     theme: &'a Theme,
 }
@@ -35,8 +42,11 @@ impl<'a> StatusLine<'a> {
             mode,
             file_path: None,
             dirty: false,
+            git_status: None,
             cursor_pos,
             file_type: None,
+            file_format: None,
+            recording: None,
             total_lines,
             theme,
         }
@@ -54,12 +64,31 @@ impl<'a> StatusLine<'a> {
         self
     }
 
+    /// Set git status of the file
+    pub fn git_status(mut self, status: Option<FileStatus>) -> Self {
+        self.git_status = status;
+        self
+    }
+
     /// Set file type
     pub fn file_type(mut self, file_type: &'a str) -> Self {
         self.file_type = Some(file_type);
         self
     }
 
+    /// Set encoding and line ending, shown as e.g. `UTF-8 LF`
+    pub fn file_format(mut self, encoding: Encoding, bom: bool, line_ending: LineEnding) -> Self {
+        self.file_format = Some((encoding, bom, line_ending));
+        self
+    }
+
+    /// Set the register a macro is being recorded into, shown as
+    /// `recording @q`
+    pub fn recording(mut self, register: Option<char>) -> Self {
+        self.recording = register;
+        self
+    }
+
     /// Get mode style
     fn mode_style(&self) -> Style {
         match self.mode {
@@ -78,6 +107,14 @@ impl<'a> StatusLine<'a> {
         let mode_text = format!(" {} ", self.mode.as_str());
         spans.push(Span::styled(mode_text, self.mode_style()));
 
+        // Macro recording
+        if let Some(register) = self.recording {
+            spans.push(Span::styled(
+                format!(" recording @{}", register),
+                Style::default().fg(self.theme.keyword.fg.unwrap()),
+            ));
+        }
+
         // Spacing
         spans.push(Span::raw(" "));
 
@@ -92,6 +129,11 @@ impl<'a> StatusLine<'a> {
 
         spans.push(Span::styled(file_name, Style::default().fg(self.theme.foreground)));
 
+        // Git status badge
+        if let Some(status) = self.git_status {
+            spans.push(Span::styled(format!(" {}", status.badge()), self.theme.git_status(status)));
+        }
+
         // Dirty indicator
         if self.dirty {
             spans.push(Span::styled(" [+]", Style::default().fg(self.theme.keyword.fg.unwrap())));
@@ -104,6 +146,16 @@ impl<'a> StatusLine<'a> {
     fn right_section(&self) -> Vec<Span<'a>> {
         let mut spans = Vec::new();
 
+        // Encoding and line ending
+        if let Some((encoding, bom, line_ending)) = self.file_format {
+            let bom = if bom { " BOM" } else { "" };
+            spans.push(Span::styled(
+                format!("{}{} {}", encoding, bom, line_ending.name()),
+                Style::default().fg(self.theme.comment.fg.unwrap()),
+            ));
+            spans.push(Span::raw(" │ "));
+        }
+
         // File type
         if let Some(ft) = self.file_type {
             spans.push(Span::styled(ft, Style::default().fg(self.theme.comment.fg.unwrap())));
@@ -224,4 +276,39 @@ mod tests {
         let status2 = StatusLine::new(Mode::Normal, (99, 49), 100, &theme);
         assert_eq!(status2.cursor_pos, (99, 49));
     }
+
+    #[test]
+    fn test_git_status_badge() {
+        let theme = Theme::default();
+        let path = PathBuf::from("/tmp/test.rs");
+        let status = StatusLine::new(Mode::Normal, (0, 0), 1, &theme)
+            .file_path(&path)
+            .git_status(Some(FileStatus::Modified));
+
+        let spans = status.left_section();
+        let badge = spans.iter().find(|span| span.content == " M").unwrap();
+        assert_eq!(badge.style, theme.git_modified);
+    }
+
+    #[test]
+    fn test_file_format() {
+        let theme = Theme::default();
+        let status = StatusLine::new(Mode::Normal, (0, 0), 1, &theme).file_format(
+            Encoding::Utf16Le,
+            true,
+            LineEnding::CrLf,
+        );
+
+        let spans = status.right_section();
+        assert_eq!(spans[0].content, "UTF-16LE BOM CRLF");
+    }
+
+    #[test]
+    fn test_recording() {
+        let theme = Theme::default();
+        let status = StatusLine::new(Mode::Normal, (0, 0), 1, &theme).recording(Some('q'));
+
+        let spans = status.left_section();
+        assert_eq!(spans[1].content, " recording @q");
+    }
 }
@@ -1,8 +1,10 @@
 //! Tab Bar Widget
 //!
-//! Displays the tab bar with open files, active tab highlighting, and window controls.
+//! Displays the tab bar with open files, active tab highlighting, git status
+//! badges, and window controls.
 
 use crate::theme::Theme;
+use ait42_git::FileStatus;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -18,6 +20,8 @@ pub struct Tab {
     pub title: String,
     /// Whether the file has been modified
     pub modified: bool,
+    /// Git status of the file, shown as a badge after the title
+    pub status: Option<FileStatus>,
 }
 
 impl Tab {
@@ -26,6 +30,7 @@ impl Tab {
         Self {
             title: title.into(),
             modified: false,
+            status: None,
         }
     }
 
@@ -35,10 +40,23 @@ impl Tab {
         self
     }
 
+    /// Set git status
+    pub fn status(mut self, status: Option<FileStatus>) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Git status badge with its leading space, if any
+    fn badge(&self) -> String {
+        self.status
+            .map(|status| format!(" {}", status.badge()))
+            .unwrap_or_default()
+    }
+
     /// Get display width of the tab
     fn display_width(&self) -> usize {
-        // " title * " or " title  "
-        let base = self.title.len() + 3; // space + title + space
+        // " title * " or " title  ", with " M" after the title if changed
+        let base = self.title.len() + 3 + self.badge().len(); // space + title + space
         if self.modified {
             base + 2 // + " *"
         } else {
@@ -49,7 +67,7 @@ impl Tab {
     /// Truncate title if needed to fit max_width
     fn truncated_title(&self, max_width: usize) -> String {
         let suffix = if self.modified { " * " } else { "  " };
-        let suffix_len = suffix.len() + 1; // +1 for leading space
+        let suffix_len = suffix.len() + 1 + self.badge().len(); // +1 for leading space
 
         if max_width <= suffix_len {
             return String::from("…");
@@ -182,7 +200,8 @@ impl<'a> Widget for TabBar<'a> {
             };
 
             let modified_marker = if tab.modified { " *" } else { "" };
-            let tab_text = format!(" {}{} ", title, modified_marker);
+            let badge = tab.badge();
+            let tab_text = format!(" {}{}{} ", title, badge, modified_marker);
 
             // Calculate tab width and check if it fits
             let tab_width = tab_text.len() as u16;
@@ -190,8 +209,13 @@ impl<'a> Widget for TabBar<'a> {
                 break;
             }
 
-            // Render the tab
+            // Render the tab, the badge in the color of its status
             buf.set_string(x_offset, area.y, &tab_text, style);
+            if let Some(status) = tab.status {
+                let badge_x = x_offset + (1 + title.len() + badge.len() - 1) as u16;
+                buf.get_mut(badge_x, area.y)
+                    .set_style(style.patch(self.theme.git_status(status)));
+            }
 
             // Add separator if not the last visible tab
             x_offset += tab_width;
@@ -233,6 +257,29 @@ mod tests {
 
         let tab_modified = Tab::new("test.rs").modified(true);
         assert_eq!(tab_modified.display_width(), 12); // " test.rs * "
+
+        let tab_status = Tab::new("test.rs").status(Some(FileStatus::Modified));
+        assert_eq!(tab_status.display_width(), 12); // " test.rs M  "
+    }
+
+    #[test]
+    fn test_tab_status_badge() {
+        let theme = Theme::default();
+        let tabs = vec![
+            Tab::new("a.rs").status(Some(FileStatus::Conflicted)),
+            Tab::new("b.rs"),
+        ];
+
+        let area = Rect::new(0, 0, 40, 1);
+        let mut buf = Buffer::empty(area);
+        TabBar::new(&tabs, 1, &theme)
+            .hide_controls()
+            .render(area, &mut buf);
+
+        // " a.rs ! │ b.rs "
+        assert_eq!(buf.get(6, 0).symbol(), "!");
+        assert_eq!(buf.get(6, 0).fg, theme.git_conflicted.fg.unwrap());
+        assert_eq!(buf.get(2, 0).fg, theme.comment.fg.unwrap());
     }
 
     #[test]
@@ -0,0 +1,574 @@
+//! Terminal Copy Mode
+//!
+//! Frozen copy of the lines in the terminal panel, browsed with Vim keys so
+//! command and agent output can be yanked without leaving the editor. `h`
+//! `j` `k` `l`, `w` `b`, `0` `^` `$`, `g` `G`, Ctrl+U/D and Ctrl+B/F move;
+//! `v` and `V` select characters or whole lines; `/` and `?` search the
+//! scrollback forwards and backwards, `n` and `N` repeat. `"{register}`
+//! picks a register, then `y` or Enter yanks the selection, or the cursor
+//! line without one, and leaves. `q` and Esc leave without yanking.
+//!
+//! Drawn by [`TerminalPanel::copy_mode`](super::TerminalPanel::copy_mode).
+
+use crate::registers::Registers;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Position in the frozen lines, the column counted in characters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CopyPosition {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl CopyPosition {
+    pub fn new(line: usize, col: usize) -> Self {
+        Self { line, col }
+    }
+}
+
+/// What a selection covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopySelection {
+    /// Characters from the anchor to the cursor (`v`)
+    Chars,
+    /// Whole lines from the anchor to the cursor (`V`)
+    Lines,
+}
+
+/// Text yanked in copy mode, with the register it goes to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Yank {
+    /// Register picked with `"`, the unnamed one if `None`
+    pub register: Option<char>,
+    pub text: String,
+}
+
+/// What typed keys go to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Input {
+    Keys,
+    /// Search typed after `/` (forwards) or `?`
+    Search {
+        query: String,
+        forward: bool,
+    },
+    /// Register name after `"`
+    Register,
+}
+
+/// Copy mode state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalCopyState {
+    lines: Vec<String>,
+    cursor: CopyPosition,
+    /// Where the selection started, and what it covers
+    selection: Option<(CopyPosition, CopySelection)>,
+    /// Lines scrolled up from the bottom
+    scroll_offset: usize,
+    /// Lines shown at once, as last drawn
+    height: usize,
+    input: Input,
+    /// Register the next yank goes to
+    register: Option<char>,
+    /// Last search and whether it went forwards
+    last_search: Option<(String, bool)>,
+    /// Outcome of the last key, shown until the next one
+    message: Option<String>,
+    yank: Option<Yank>,
+}
+
+impl TerminalCopyState {
+    /// Copy mode over `lines`, scrolled up by `scroll_offset` with the cursor
+    /// on the last line shown
+    pub fn new(lines: Vec<String>, scroll_offset: usize) -> Self {
+        let line = lines.len().saturating_sub(scroll_offset + 1);
+        Self {
+            lines,
+            cursor: CopyPosition::new(line, 0),
+            selection: None,
+            scroll_offset,
+            height: 1,
+            input: Input::Keys,
+            register: None,
+            last_search: None,
+            message: None,
+            yank: None,
+        }
+    }
+
+    /// Frozen lines
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Cursor position
+    pub fn cursor(&self) -> CopyPosition {
+        self.cursor
+    }
+
+    /// Lines scrolled up from the bottom
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    /// Set how many lines are shown at once, scrolling to the cursor
+    pub fn set_height(&mut self, height: usize) {
+        self.height = height.max(1);
+        self.scroll_to_cursor();
+    }
+
+    /// Take the text yanked by the last key
+    pub fn take_yank(&mut self) -> Option<Yank> {
+        self.yank.take()
+    }
+
+    /// Ordered ends of the selection, both included, and what it covers
+    pub fn selection(&self) -> Option<(CopyPosition, CopyPosition, CopySelection)> {
+        let (anchor, kind) = self.selection?;
+        Some((anchor.min(self.cursor), anchor.max(self.cursor), kind))
+    }
+
+    /// Whether the character at `line`, `col` is selected
+    pub fn is_selected(&self, line: usize, col: usize) -> bool {
+        match self.selection() {
+            Some((start, end, CopySelection::Lines)) => (start.line..=end.line).contains(&line),
+            Some((start, end, CopySelection::Chars)) => {
+                (start..=end).contains(&CopyPosition::new(line, col))
+            }
+            None => false,
+        }
+    }
+
+    /// Line shown below the output: the mode and position, a message, or
+    /// the search being typed
+    pub fn status(&self) -> String {
+        match &self.input {
+            Input::Search { query, forward } => {
+                format!("{}{}█", if *forward { '/' } else { '?' }, query)
+            }
+            Input::Register => "\"█".to_string(),
+            Input::Keys => {
+                let mode = match self.selection {
+                    Some((_, CopySelection::Chars)) => "-- COPY (VISUAL) --",
+                    Some((_, CopySelection::Lines)) => "-- COPY (VISUAL LINE) --",
+                    None => "-- COPY --",
+                };
+                let mut status =
+                    format!("{} [{}/{}]", mode, self.cursor.line + 1, self.lines.len());
+                if let Some(register) = self.register {
+                    status.push_str(&format!(" \"{}", register));
+                }
+                if let Some(message) = &self.message {
+                    status.push_str("  ");
+                    status.push_str(message);
+                }
+                status
+            }
+        }
+    }
+
+    /// Handle a key press; returns `false` when copy mode should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match std::mem::replace(&mut self.input, Input::Keys) {
+            Input::Search { mut query, forward } => {
+                match key.code {
+                    KeyCode::Enter => self.search(query, forward),
+                    KeyCode::Esc => {}
+                    KeyCode::Backspace => {
+                        query.pop();
+                        self.input = Input::Search { query, forward };
+                    }
+                    KeyCode::Char(ch) if !ctrl => {
+                        query.push(ch);
+                        self.input = Input::Search { query, forward };
+                    }
+                    _ => self.input = Input::Search { query, forward },
+                }
+                return true;
+            }
+            Input::Register => {
+                match key.code {
+                    KeyCode::Char(ch) if Registers::is_register(ch) => self.register = Some(ch),
+                    _ => self.message = Some("Not a register".to_string()),
+                }
+                return true;
+            }
+            Input::Keys => {}
+        }
+
+        self.message = None;
+        let CopyPosition { line, col } = self.cursor;
+        let page = self.height as isize;
+        match key.code {
+            KeyCode::Esc if self.selection.is_some() => self.selection = None,
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Char('u') if ctrl => self.move_lines(-(page / 2).max(1)),
+            KeyCode::Char('d') if ctrl => self.move_lines((page / 2).max(1)),
+            KeyCode::Char('b') if ctrl => self.move_lines(-page),
+            KeyCode::Char('f') if ctrl => self.move_lines(page),
+            _ if ctrl => {}
+            KeyCode::PageUp => self.move_lines(-page),
+            KeyCode::PageDown => self.move_lines(page),
+            KeyCode::Char('h') | KeyCode::Left => self.move_to(line, col.saturating_sub(1)),
+            KeyCode::Char('l') | KeyCode::Right => self.move_to(line, col + 1),
+            KeyCode::Char('j') | KeyCode::Down => self.move_lines(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_lines(-1),
+            KeyCode::Char('0') | KeyCode::Home => self.move_to(line, 0),
+            KeyCode::Char('$') | KeyCode::End => self.move_to(line, usize::MAX),
+            KeyCode::Char('^') => {
+                let indent = self
+                    .chars(line)
+                    .iter()
+                    .take_while(|c| c.is_whitespace())
+                    .count();
+                self.move_to(line, indent);
+            }
+            KeyCode::Char('w') => self.word_forward(),
+            KeyCode::Char('b') => self.word_backward(),
+            KeyCode::Char('g') => self.move_to(0, 0),
+            KeyCode::Char('G') => self.move_to(usize::MAX, 0),
+            KeyCode::Char('v') => self.toggle_selection(CopySelection::Chars),
+            KeyCode::Char('V') => self.toggle_selection(CopySelection::Lines),
+            KeyCode::Char('/') => {
+                self.input = Input::Search {
+                    query: String::new(),
+                    forward: true,
+                }
+            }
+            KeyCode::Char('?') => {
+                self.input = Input::Search {
+                    query: String::new(),
+                    forward: false,
+                }
+            }
+            KeyCode::Char('n') => self.repeat_search(true),
+            KeyCode::Char('N') => self.repeat_search(false),
+            KeyCode::Char('"') => self.input = Input::Register,
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.yank();
+                return false;
+            }
+            KeyCode::Char('Y') => {
+                self.selection = None;
+                self.yank();
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn chars(&self, line: usize) -> Vec<char> {
+        self.lines
+            .get(line)
+            .map(|text| text.chars().collect())
+            .unwrap_or_default()
+    }
+
+    /// Move to `line` and `col`, clamped to the text, scrolling to show it
+    fn move_to(&mut self, line: usize, col: usize) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let line = line.min(self.lines.len() - 1);
+        let col = col.min(self.chars(line).len().saturating_sub(1));
+        self.cursor = CopyPosition::new(line, col);
+        self.scroll_to_cursor();
+    }
+
+    fn move_lines(&mut self, delta: isize) {
+        self.move_to(self.cursor.line.saturating_add_signed(delta), self.cursor.col);
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        let len = self.lines.len();
+        if len == 0 {
+            return;
+        }
+        let end = len - self.scroll_offset.min(len);
+        let start = end.saturating_sub(self.height);
+        if self.cursor.line < start {
+            self.scroll_offset = len - (self.cursor.line + self.height).min(len);
+        } else if self.cursor.line >= end {
+            self.scroll_offset = len - (self.cursor.line + 1);
+        }
+    }
+
+    /// Move to the start of the next word, on a later line if need be
+    fn word_forward(&mut self) {
+        let CopyPosition { mut line, mut col } = self.cursor;
+        let chars = self.chars(line);
+        if let Some(class) = chars.get(col).map(|&ch| char_class(ch)) {
+            while class != CharClass::Space
+                && chars.get(col).map(|&ch| char_class(ch)) == Some(class)
+            {
+                col += 1;
+            }
+        }
+        loop {
+            let chars = self.chars(line);
+            while chars.get(col).is_some_and(|ch| ch.is_whitespace()) {
+                col += 1;
+            }
+            if col < chars.len() || line + 1 >= self.lines.len() {
+                break;
+            }
+            line += 1;
+            col = 0;
+        }
+        self.move_to(line, col);
+    }
+
+    /// Move to the start of the word before the cursor, on an earlier line
+    /// if need be
+    fn word_backward(&mut self) {
+        let CopyPosition { mut line, col } = self.cursor;
+        let mut col = col.min(self.chars(line).len());
+        loop {
+            let chars = self.chars(line);
+            while col > 0 && chars[col - 1].is_whitespace() {
+                col -= 1;
+            }
+            if col > 0 {
+                let class = char_class(chars[col - 1]);
+                while col > 0 && char_class(chars[col - 1]) == class {
+                    col -= 1;
+                }
+                break;
+            }
+            if line == 0 {
+                break;
+            }
+            line -= 1;
+            col = self.chars(line).len();
+        }
+        self.move_to(line, col);
+    }
+
+    fn toggle_selection(&mut self, kind: CopySelection) {
+        self.selection = match self.selection {
+            Some((_, selected)) if selected == kind => None,
+            Some((anchor, _)) => Some((anchor, kind)),
+            None => Some((self.cursor, kind)),
+        };
+    }
+
+    /// Search for `query`, repeating the last search if it is empty
+    fn search(&mut self, query: String, forward: bool) {
+        if !query.is_empty() {
+            self.last_search = Some((query, forward));
+        }
+        self.repeat_search(true);
+    }
+
+    /// Repeat the last search, the other way round unless `same_direction`
+    fn repeat_search(&mut self, same_direction: bool) {
+        let Some((query, forward)) = self.last_search.clone() else {
+            self.message = Some("No previous search".to_string());
+            return;
+        };
+        match self.find(&query, forward == same_direction) {
+            Some(found) => self.move_to(found.line, found.col),
+            None => self.message = Some(format!("Pattern not found: {}", query)),
+        }
+    }
+
+    /// Next match of `query` from the cursor, wrapping around the ends
+    ///
+    /// Case is ignored unless `query` has an uppercase letter.
+    fn find(&self, query: &str, forward: bool) -> Option<CopyPosition> {
+        let query: Vec<char> = query.chars().collect();
+        let len = self.lines.len();
+        if query.is_empty() || len == 0 {
+            return None;
+        }
+        let ignore_case = !query.iter().any(|ch| ch.is_uppercase());
+        let CopyPosition { line: from, col } = self.cursor;
+
+        for step in 0..=len {
+            let line = if forward {
+                (from + step) % len
+            } else {
+                (from + len - step % len) % len
+            };
+            let chars = self.chars(line);
+            let mut cols =
+                (0..chars.len()).filter(|&at| matches_at(&chars, &query, at, ignore_case));
+            let found = match (forward, step) {
+                (true, 0) => cols.find(|&at| at > col),
+                (true, s) if s == len => cols.find(|&at| at <= col),
+                (true, _) => cols.next(),
+                (false, 0) => cols.rfind(|&at| at < col),
+                (false, s) if s == len => cols.rfind(|&at| at >= col),
+                (false, _) => cols.next_back(),
+            };
+            if let Some(at) = found {
+                return Some(CopyPosition::new(line, at));
+            }
+        }
+        None
+    }
+
+    /// Yank the selection, or the cursor line without one
+    fn yank(&mut self) {
+        let text = match self.selection() {
+            Some((start, end, CopySelection::Chars)) => (start.line..=end.line)
+                .map(|line| {
+                    let chars = self.chars(line);
+                    let from = if line == start.line { start.col } else { 0 };
+                    let to = if line == end.line {
+                        end.col + 1
+                    } else {
+                        chars.len()
+                    };
+                    chars[from.min(chars.len())..to.min(chars.len())]
+                        .iter()
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Some((start, end, CopySelection::Lines)) => self.lines[start.line..=end.line]
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect(),
+            None => match self.lines.get(self.cursor.line) {
+                Some(line) => format!("{}\n", line),
+                None => return,
+            },
+        };
+        self.yank = Some(Yank {
+            register: self.register.take(),
+            text,
+        });
+    }
+}
+
+/// Kind of character, a word being a run of one kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(ch: char) -> CharClass {
+    if ch.is_whitespace() {
+        CharClass::Space
+    } else if ch.is_alphanumeric() || ch == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// Whether `query` occurs in `chars` at `at`
+fn matches_at(chars: &[char], query: &[char], at: usize, ignore_case: bool) -> bool {
+    let Some(window) = chars.get(at..at + query.len()) else {
+        return false;
+    };
+    window.iter().zip(query).all(|(a, b)| {
+        if ignore_case {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy_mode() -> TerminalCopyState {
+        let lines = [
+            "$ cargo test",
+            "error: one failed",
+            "  at src/lib.rs:10",
+            "✗ 101 in 1.0s",
+        ];
+        let mut copy = TerminalCopyState::new(lines.iter().map(|l| l.to_string()).collect(), 0);
+        copy.set_height(2);
+        copy
+    }
+
+    fn keys(copy: &mut TerminalCopyState, keys: &str) -> bool {
+        let mut open = true;
+        for ch in keys.chars() {
+            let code = match ch {
+                '\n' => KeyCode::Enter,
+                '\x1b' => KeyCode::Esc,
+                _ => KeyCode::Char(ch),
+            };
+            open = copy.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+        open
+    }
+
+    #[test]
+    fn test_moves_and_scrolls() {
+        let mut copy = copy_mode();
+        assert_eq!(copy.cursor(), CopyPosition::new(3, 0));
+        assert_eq!(copy.scroll_offset(), 0);
+
+        keys(&mut copy, "kk");
+        assert_eq!(copy.cursor(), CopyPosition::new(1, 0));
+        assert_eq!(copy.scroll_offset(), 1);
+        keys(&mut copy, "ww");
+        assert_eq!(copy.cursor(), CopyPosition::new(1, 7));
+        keys(&mut copy, "b$");
+        assert_eq!(copy.cursor(), CopyPosition::new(1, 16));
+        keys(&mut copy, "j^");
+        assert_eq!(copy.cursor(), CopyPosition::new(2, 2));
+        keys(&mut copy, "g");
+        assert_eq!(copy.scroll_offset(), 2);
+        keys(&mut copy, "G");
+        assert_eq!(copy.cursor(), CopyPosition::new(3, 0));
+        assert_eq!(copy.scroll_offset(), 0);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut copy = copy_mode();
+        keys(&mut copy, "?ERROR\n");
+        assert!(copy.status().contains("Pattern not found"));
+        keys(&mut copy, "?error\n");
+        assert_eq!(copy.cursor(), CopyPosition::new(1, 0));
+
+        keys(&mut copy, "/s\n");
+        assert_eq!(copy.cursor(), CopyPosition::new(2, 5));
+        keys(&mut copy, "n");
+        assert_eq!(copy.cursor(), CopyPosition::new(2, 14));
+        keys(&mut copy, "N");
+        assert_eq!(copy.cursor(), CopyPosition::new(2, 5));
+    }
+
+    #[test]
+    fn test_yank() {
+        let mut copy = copy_mode();
+        keys(&mut copy, "kkvjlly");
+        assert_eq!(
+            copy.take_yank(),
+            Some(Yank {
+                register: None,
+                text: "error: one failed\n  a".to_string(),
+            })
+        );
+
+        let mut copy = copy_mode();
+        assert!(keys(&mut copy, "\"aVk"));
+        assert!(copy.is_selected(2, 0));
+        assert!(!copy.is_selected(1, 0));
+        assert!(!keys(&mut copy, "\n"));
+        let yank = copy.take_yank().unwrap();
+        assert_eq!(yank.register, Some('a'));
+        assert_eq!(yank.text, "  at src/lib.rs:10\n✗ 101 in 1.0s\n");
+
+        // Without a selection the cursor line is yanked; q leaves empty-handed
+        let mut copy = copy_mode();
+        assert!(!keys(&mut copy, "y"));
+        assert_eq!(copy.take_yank().unwrap().text, "✗ 101 in 1.0s\n");
+        assert!(!keys(&mut copy, "vq"));
+        assert_eq!(copy.take_yank(), None);
+    }
+}
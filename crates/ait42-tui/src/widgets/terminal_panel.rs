@@ -1,7 +1,12 @@
 //! Terminal Panel Widget
 //!
 //! Displays terminal output with scrolling support and prompt display.
+//! Status lines after commands and the prompt after a failed command are
+//! colored by outcome. In copy mode the frozen lines are drawn instead, with
+//! the cursor and selection, and the copy mode status in place of the prompt.
 
+use super::terminal_copy::{CopyPosition, TerminalCopyState};
+use crate::terminal_executor::CommandStatus;
 use crate::theme::Theme;
 use ratatui::{
     buffer::Buffer,
@@ -9,6 +14,7 @@ use ratatui::{
     style::{Modifier, Style},
     widgets::Widget,
 };
+use unicode_width::UnicodeWidthChar;
 
 /// Terminal panel widget for displaying command output
 pub struct TerminalPanel<'a> {
@@ -18,10 +24,16 @@ pub struct TerminalPanel<'a> {
     scroll_offset: usize,
     /// Theme for styling
     theme: &'a Theme,
+    /// Header text
+    title: &'a str,
     /// Whether to show the "TERMINAL" header
     show_header: bool,
     /// Whether to show the prompt
     show_prompt: bool,
+    /// Whether the last command failed
+    failed: bool,
+    /// Copy mode drawn in place of the output
+    copy: Option<&'a TerminalCopyState>,
 }
 
 impl<'a> TerminalPanel<'a> {
@@ -31,17 +43,32 @@ impl<'a> TerminalPanel<'a> {
             output,
             scroll_offset: 0,
             theme,
+            title: " TERMINAL ",
             show_header: true,
             show_prompt: true,
+            failed: false,
+            copy: None,
         }
     }
 
+    /// Rows of `area` copy mode shows lines in, below the header and above
+    /// the status
+    pub fn copy_mode_rows(area: Rect) -> usize {
+        area.height.saturating_sub(3) as usize
+    }
+
     /// Set scroll offset (0 = bottom, positive values scroll up)
     pub fn scroll_offset(mut self, offset: usize) -> Self {
         self.scroll_offset = offset;
         self
     }
 
+    /// Replace the "TERMINAL" header text
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
     /// Hide the "TERMINAL" header
     pub fn hide_header(mut self) -> Self {
         self.show_header = false;
@@ -54,13 +81,29 @@ impl<'a> TerminalPanel<'a> {
         self
     }
 
+    /// Color the prompt for a failed last command
+    pub fn failed(mut self, failed: bool) -> Self {
+        self.failed = failed;
+        self
+    }
+
+    /// Draw copy mode, if any, in place of the output and prompt
+    pub fn copy_mode(mut self, copy: Option<&'a TerminalCopyState>) -> Self {
+        if let Some(copy) = copy {
+            self.output = copy.lines();
+            self.scroll_offset = copy.scroll_offset();
+        }
+        self.copy = copy;
+        self
+    }
+
     /// Render the header
     fn render_header(&self, area: Rect, buf: &mut Buffer) -> u16 {
         if !self.show_header || area.height < 2 {
             return 0;
         }
 
-        let header = " TERMINAL ";
+        let header = self.title;
         let style = Style::default()
             .fg(self.theme.foreground)
             .add_modifier(Modifier::BOLD);
@@ -86,8 +129,13 @@ impl<'a> TerminalPanel<'a> {
         }
 
         let prompt = "$ ";
+        let color = if self.failed {
+            self.theme.diff_deleted.fg
+        } else {
+            self.theme.function.fg
+        };
         let style = Style::default()
-            .fg(self.theme.function.fg.unwrap_or(self.theme.foreground))
+            .fg(color.unwrap_or(self.theme.foreground))
             .add_modifier(Modifier::BOLD);
 
         buf.set_string(area.x, y, prompt, style);
@@ -111,7 +159,12 @@ impl<'a> TerminalPanel<'a> {
             return;
         }
 
-        let style = Style::default().fg(self.theme.foreground);
+        let color = match CommandStatus::parse_summary(line) {
+            Some(true) => self.theme.diff_deleted.fg,
+            Some(false) => self.theme.diff_added.fg,
+            None => None,
+        };
+        let style = Style::default().fg(color.unwrap_or(self.theme.foreground));
         let max_width = area.width as usize;
 
         // Handle long lines - truncate with ellipsis
@@ -124,6 +177,45 @@ impl<'a> TerminalPanel<'a> {
         buf.set_string(area.x, y, &display_line, style);
     }
 
+    /// Render line `index` in copy mode, with the cursor and selection
+    fn render_copy_line(
+        &self,
+        copy: &TerminalCopyState,
+        index: usize,
+        y: u16,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let line = &copy.lines()[index];
+        let text_style = Style::default().fg(self.theme.foreground);
+        let cursor_style = Style::default()
+            .fg(self.theme.background)
+            .bg(self.theme.cursor);
+
+        let mut x = area.x;
+        for (col, ch) in line.chars().enumerate() {
+            let width = ch.width().unwrap_or(0) as u16;
+            if width == 0 {
+                continue;
+            }
+            if x + width > area.right() {
+                break;
+            }
+            let style = if copy.cursor() == CopyPosition::new(index, col) {
+                cursor_style
+            } else if copy.is_selected(index, col) {
+                self.theme.selection
+            } else {
+                text_style
+            };
+            buf.set_string(x, y, ch.to_string(), style);
+            x += width;
+        }
+        if line.is_empty() && copy.cursor().line == index {
+            buf.get_mut(area.x, y).set_style(cursor_style);
+        }
+    }
+
     /// Calculate which lines should be visible
     fn calculate_visible_range(&self, content_height: usize) -> (usize, usize) {
         let total_lines = self.output.len();
@@ -169,7 +261,11 @@ impl<'a> Widget for TerminalPanel<'a> {
         }
 
         // Calculate space for content
-        let prompt_height = if self.show_prompt { 1 } else { 0 };
+        let prompt_height = if self.show_prompt || self.copy.is_some() {
+            1
+        } else {
+            0
+        };
         let content_height = area
             .bottom()
             .saturating_sub(y_offset)
@@ -186,7 +282,10 @@ impl<'a> Widget for TerminalPanel<'a> {
             .skip(start_index)
             .take(end_index - start_index)
         {
-            self.render_line(line, y_offset, area, buf);
+            match self.copy {
+                Some(copy) => self.render_copy_line(copy, i, y_offset, area, buf),
+                None => self.render_line(line, y_offset, area, buf),
+            }
             y_offset += 1;
 
             if y_offset >= area.bottom() {
@@ -194,9 +293,14 @@ impl<'a> Widget for TerminalPanel<'a> {
             }
         }
 
-        // Render prompt at the bottom
-        if self.show_prompt {
-            let prompt_y = area.bottom().saturating_sub(1);
+        // Render prompt, or the copy mode status, at the bottom
+        let prompt_y = area.bottom().saturating_sub(1);
+        if let Some(copy) = self.copy {
+            let style = Style::default()
+                .fg(self.theme.keyword.fg.unwrap_or(self.theme.foreground))
+                .add_modifier(Modifier::BOLD);
+            buf.set_stringn(area.x, prompt_y, copy.status(), area.width as usize, style);
+        } else if self.show_prompt {
             self.render_prompt(prompt_y, area, buf);
         }
     }
@@ -328,6 +432,48 @@ mod tests {
         assert!(panel.output[0].len() > 100);
     }
 
+    #[test]
+    fn test_status_colors() {
+        let theme = Theme::default();
+        let output = vec!["$ false".to_string(), "✗ 1 in 0.0s".to_string()];
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+
+        TerminalPanel::new(&output, &theme)
+            .failed(true)
+            .render(area, &mut buf);
+
+        assert_eq!(buf.get(0, 2).fg, theme.foreground);
+        assert_eq!(buf.get(0, 3).fg, theme.diff_deleted.fg.unwrap());
+        // Prompt on the last row
+        assert_eq!(buf.get(0, 4).symbol(), "$");
+        assert_eq!(buf.get(0, 4).fg, theme.diff_deleted.fg.unwrap());
+    }
+
+    #[test]
+    fn test_copy_mode() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let theme = Theme::default();
+        let output = vec!["first".to_string(), "second".to_string()];
+        let mut copy = TerminalCopyState::new(output, 0);
+        for ch in ['v', 'l'] {
+            copy.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+        }
+        let area = Rect::new(0, 0, 30, 5);
+        let mut buf = Buffer::empty(area);
+
+        TerminalPanel::new(&[], &theme)
+            .copy_mode(Some(&copy))
+            .render(area, &mut buf);
+
+        assert_eq!(buf.get(0, 3).symbol(), "s");
+        assert_eq!(Some(buf.get(0, 3).bg), theme.selection.bg);
+        assert_eq!(buf.get(1, 3).bg, theme.cursor);
+        let status: String = (0..10).map(|x| buf.get(x, 4).symbol()).collect();
+        assert_eq!(status, "-- COPY (V");
+    }
+
     #[test]
     fn test_builder_pattern() {
         let theme = Theme::default();
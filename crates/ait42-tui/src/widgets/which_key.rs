@@ -0,0 +1,143 @@
+//! Which-Key Popup Widget
+//!
+//! Lists the continuations of a pending prefix key with their descriptions.
+
+use crate::keybinds::KeyBindingEntry;
+use crate::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+/// Which-key popup widget
+pub struct WhichKeyPopup<'a> {
+    prefix: &'a str,
+    label: &'a str,
+    entries: &'a [KeyBindingEntry],
+    theme: &'a Theme,
+}
+
+impl<'a> WhichKeyPopup<'a> {
+    /// Create a popup for `prefix` listing `entries`
+    pub fn new(prefix: &'a str, entries: &'a [KeyBindingEntry], theme: &'a Theme) -> Self {
+        Self {
+            prefix,
+            label: "",
+            entries,
+            theme,
+        }
+    }
+
+    /// Set the prefix group label shown in the title
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Area the popup occupies when anchored to the bottom of `area`
+    pub fn area(&self, area: Rect) -> Rect {
+        let height = (self.entries.len() as u16 + 2).min(area.height);
+        let width = area.width.min(60);
+        Rect::new(
+            area.x + area.width.saturating_sub(width),
+            area.y + area.height.saturating_sub(height),
+            width,
+            height,
+        )
+    }
+
+    fn title(&self) -> String {
+        if self.label.is_empty() {
+            format!(" {} ", self.prefix)
+        } else {
+            format!(" {} ({}) ", self.prefix, self.label)
+        }
+    }
+}
+
+impl<'a> Widget for WhichKeyPopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 10 || area.height < 3 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(self.title());
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let key_width = self
+            .entries
+            .iter()
+            .map(|e| unicode_width::UnicodeWidthStr::width(e.keys.as_str()))
+            .max()
+            .unwrap_or(0);
+        let key_style = Style::default()
+            .fg(self.theme.keyword.fg.unwrap_or(self.theme.foreground))
+            .add_modifier(Modifier::BOLD);
+        let desc_style = Style::default().fg(self.theme.foreground);
+
+        for (i, entry) in self.entries.iter().take(inner.height as usize).enumerate() {
+            let y = inner.y + i as u16;
+            buf.set_stringn(inner.x, y, &entry.keys, inner.width as usize, key_style);
+
+            let desc_x = inner.x + key_width as u16 + 2;
+            if desc_x < inner.right() {
+                buf.set_stringn(
+                    desc_x,
+                    y,
+                    format!("→ {}", entry.description),
+                    (inner.right() - desc_x) as usize,
+                    desc_style,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybinds::{KeyBinding, KeyMap, Mode};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn leader_entries() -> Vec<KeyBindingEntry> {
+        let keymap = KeyMap::default();
+        let space = KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE);
+        keymap.continuations(Mode::Normal, &space)
+    }
+
+    #[test]
+    fn test_popup_area_anchored_bottom_right() {
+        let theme = Theme::default();
+        let entries = leader_entries();
+        let popup = WhichKeyPopup::new("Space", &entries, &theme);
+
+        let area = popup.area(Rect::new(0, 0, 100, 40));
+        assert_eq!(area.bottom(), 40);
+        assert_eq!(area.right(), 100);
+        assert_eq!(area.height, entries.len() as u16 + 2);
+    }
+
+    #[test]
+    fn test_popup_renders_descriptions() {
+        let theme = Theme::default();
+        let entries = leader_entries();
+        let popup = WhichKeyPopup::new("Space", &entries, &theme).label("leader");
+
+        let area = Rect::new(0, 0, 50, entries.len() as u16 + 2);
+        let mut buf = Buffer::empty(area);
+        popup.render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Save file"));
+        assert!(text.contains("leader"));
+    }
+}
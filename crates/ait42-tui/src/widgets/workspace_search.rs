@@ -0,0 +1,380 @@
+//! Workspace Search Widget
+//!
+//! Quick-open panel for project-wide grep: type a pattern, press Enter to
+//! search every file of the workspace, then pick a match and press Enter
+//! again to open the file at that line. Editing the pattern makes the next
+//! Enter search again.
+
+use crate::theme::Theme;
+use ait42_fs::{Grep, GrepMatch, GrepOptions, GrepSummary};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::path::{Path, PathBuf};
+
+/// Matching lines listed at most
+const MAX_RESULTS: usize = 500;
+
+const HINTS: &str = "Enter search/open  ↑/↓ select  Ctrl+R regex  Esc close";
+
+/// A match with the file it is in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// Path relative to the workspace root
+    pub path: String,
+    pub found: GrepMatch,
+}
+
+/// Workspace search state
+#[derive(Debug)]
+pub struct WorkspaceSearchState {
+    root: PathBuf,
+    pattern: String,
+    regex: bool,
+    /// Pattern and regex flag of the results shown
+    searched: Option<(String, bool)>,
+    results: Vec<SearchResult>,
+    summary: Option<GrepSummary>,
+    selected: usize,
+    open: Option<(PathBuf, usize, usize)>,
+    status: Option<String>,
+}
+
+impl WorkspaceSearchState {
+    /// Open the panel for the workspace at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            pattern: String::new(),
+            regex: false,
+            searched: None,
+            results: Vec::new(),
+            summary: None,
+            selected: 0,
+            open: None,
+            status: None,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Pattern typed so far
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether the pattern is a regex
+    pub fn is_regex(&self) -> bool {
+        self.regex
+    }
+
+    /// Matches of the last search, by path and line
+    pub fn results(&self) -> &[SearchResult] {
+        &self.results
+    }
+
+    /// Totals of the last search
+    pub fn summary(&self) -> Option<&GrepSummary> {
+        self.summary.as_ref()
+    }
+
+    /// Index of the selected match
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Why the last search failed
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// File, 1-based line and column to open, once a match was chosen
+    pub fn take_open(&mut self) -> Option<(PathBuf, usize, usize)> {
+        self.open.take()
+    }
+
+    /// Whether the results were found with the current pattern
+    fn is_current(&self) -> bool {
+        self.searched.as_ref() == Some(&(self.pattern.clone(), self.regex))
+    }
+
+    /// Search the workspace for the pattern
+    pub fn search(&mut self) {
+        self.results.clear();
+        self.summary = None;
+        self.selected = 0;
+        self.status = None;
+        self.searched = Some((self.pattern.clone(), self.regex));
+
+        let options = GrepOptions {
+            regex: self.regex,
+            max_results: MAX_RESULTS,
+            ..Default::default()
+        };
+        let found = Grep::new(&self.pattern, options).and_then(|grep| grep.collect(&self.root));
+        match found {
+            Ok((files, summary)) => {
+                self.results = files
+                    .into_iter()
+                    .flat_map(|file| {
+                        let path = file.path;
+                        file.matches.into_iter().map(move |found| SearchResult {
+                            path: path.clone(),
+                            found,
+                        })
+                    })
+                    .collect();
+                self.summary = Some(summary);
+            }
+            Err(e) => self.status = Some(e.to_string()),
+        }
+    }
+
+    /// Append typed or pasted text to the pattern
+    pub fn insert_text(&mut self, text: &str) {
+        self.pattern.push_str(&text.replace(['\r', '\n'], ""));
+    }
+
+    fn select_relative(&mut self, delta: isize) {
+        let last = self.results.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Handle a key press; returns `false` when the panel should close
+    ///
+    /// Enter searches when the pattern changed since the last search and
+    /// otherwise opens the selected match, closing the panel.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Down => self.select_relative(1),
+            KeyCode::Up => self.select_relative(-1),
+            KeyCode::Char('n') if ctrl => self.select_relative(1),
+            KeyCode::Char('p') if ctrl => self.select_relative(-1),
+            KeyCode::Char('r') if ctrl => self.regex = !self.regex,
+            KeyCode::Backspace => {
+                self.pattern.pop();
+            }
+            KeyCode::Enter if self.pattern.is_empty() => {}
+            KeyCode::Enter if !self.is_current() => self.search(),
+            KeyCode::Enter => {
+                if let Some(result) = self.results.get(self.selected) {
+                    let path = self.root.join(&result.path);
+                    self.open = Some((path, result.found.line, result.found.column));
+                    return false;
+                }
+            }
+            KeyCode::Char(ch) if !ctrl => self.insert_text(&ch.to_string()),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Workspace search widget
+pub struct WorkspaceSearch<'a> {
+    state: &'a WorkspaceSearchState,
+    theme: &'a Theme,
+}
+
+impl<'a> WorkspaceSearch<'a> {
+    /// Create a panel rendering `state`
+    pub fn new(state: &'a WorkspaceSearchState, theme: &'a Theme) -> Self {
+        Self { state, theme }
+    }
+
+    fn fg(&self, style: Style) -> Style {
+        Style::default().fg(style.fg.unwrap_or(self.theme.foreground))
+    }
+
+    fn footer(&self) -> String {
+        if let Some(status) = self.state.status() {
+            // Regex errors span several lines, the last one says what is wrong
+            return status.lines().last().unwrap_or(status).to_string();
+        }
+        match self.state.summary() {
+            Some(summary) if self.state.is_current() => format!(
+                "{} matches in {} of {} files{}  {}",
+                summary.matches,
+                summary.files_matched,
+                summary.files_searched,
+                if summary.truncated { " (limit reached)" } else { "" },
+                HINTS
+            ),
+            _ => HINTS.to_string(),
+        }
+    }
+}
+
+impl<'a> Widget for WorkspaceSearch<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 30 || area.height < 6 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(" Search workspace ");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let text_style = Style::default().fg(self.theme.foreground);
+        let dim_style = self.fg(self.theme.comment);
+        let path_style = self.fg(self.theme.function);
+        let match_style = self.theme.search_match;
+        let width = inner.width as usize;
+
+        // Pattern input
+        let mode = if self.state.is_regex() { "regex" } else { "text" };
+        let (x, _) = buf.set_stringn(inner.x, inner.y, format!("[{}] ", mode), width, dim_style);
+        buf.set_stringn(
+            x,
+            inner.y,
+            format!("{}█", self.state.pattern()),
+            width.saturating_sub((x - inner.x) as usize),
+            text_style,
+        );
+
+        // Matches, one per line as path:line: text
+        let list_y = inner.y + 2;
+        let height = inner.height.saturating_sub(3) as usize;
+        let skip = self
+            .state
+            .selected()
+            .saturating_sub(height.saturating_sub(1));
+        for (row, (index, result)) in self
+            .state
+            .results()
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(height)
+            .enumerate()
+        {
+            let y = list_y + row as u16;
+            let selected = index == self.state.selected();
+            let style = |base: Style| {
+                if selected {
+                    base.patch(self.theme.selection)
+                } else {
+                    base
+                }
+            };
+            if selected {
+                buf.set_style(Rect::new(inner.x, y, inner.width, 1), self.theme.selection);
+            }
+
+            let location = format!("{}:{}: ", result.path, result.found.line);
+            let (mut x, _) = buf.set_stringn(inner.x, y, location, width, style(path_style));
+
+            // Line text with the matches highlighted, leading indent dropped
+            let text = &result.found.text;
+            let indent = text.len() - text.trim_start().len();
+            let mut last = indent;
+            for range in &result.found.ranges {
+                for (span, span_style) in [
+                    (last..range.start.max(last), text_style),
+                    (range.start.max(last)..range.end, match_style),
+                ] {
+                    let room = inner.right().saturating_sub(x) as usize;
+                    if room == 0 || span.is_empty() {
+                        continue;
+                    }
+                    (x, _) = buf.set_stringn(x, y, &text[span], room, style(span_style));
+                }
+                last = range.end.max(last);
+            }
+            let room = inner.right().saturating_sub(x) as usize;
+            if room > 0 && last < text.len() {
+                buf.set_stringn(x, y, &text[last..], room, style(text_style));
+            }
+        }
+
+        let footer = self.footer();
+        buf.set_stringn(inner.x, inner.y + inner.height - 1, footer, width, dim_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "pub fn a() {}\n    let needle = 1;\n")
+            .unwrap();
+        fs::write(dir.path().join("README.md"), "needle in a haystack\n").unwrap();
+        dir
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_enter_searches_then_opens() {
+        let dir = workspace();
+        let mut search = WorkspaceSearchState::new(dir.path());
+        search.insert_text("needle");
+
+        assert!(search.handle_key(key(KeyCode::Enter)));
+        let paths: Vec<&str> = search.results().iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["README.md", "src/lib.rs"]);
+        assert_eq!(search.summary().unwrap().matches, 2);
+
+        search.handle_key(key(KeyCode::Down));
+        assert!(!search.handle_key(key(KeyCode::Enter)));
+        assert_eq!(search.take_open(), Some((dir.path().join("src/lib.rs"), 2, 9)));
+    }
+
+    #[test]
+    fn test_edited_pattern_searches_again() {
+        let dir = workspace();
+        let mut search = WorkspaceSearchState::new(dir.path());
+        search.insert_text("nee");
+        search.handle_key(key(KeyCode::Enter));
+        assert_eq!(search.results().len(), 2);
+
+        search.insert_text("dle in");
+        assert!(search.handle_key(key(KeyCode::Enter)));
+        assert_eq!(search.take_open(), None);
+        assert_eq!(search.results().len(), 1);
+
+        search.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        search.insert_text("(");
+        search.handle_key(key(KeyCode::Enter));
+        assert!(search.results().is_empty());
+        assert!(search.status().is_some());
+    }
+
+    #[test]
+    fn test_renders_matches() {
+        let dir = workspace();
+        let mut search = WorkspaceSearchState::new(dir.path());
+        search.insert_text("needle");
+        search.search();
+        let theme = Theme::default();
+        let area = Rect::new(0, 0, 80, 10);
+        let mut buf = Buffer::empty(area);
+
+        WorkspaceSearch::new(&search, &theme).render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("[text] needle█"));
+        assert!(text.contains("src/lib.rs:2: let needle = 1;"));
+        assert!(text.contains("2 matches in 2 of 2 files"));
+    }
+}
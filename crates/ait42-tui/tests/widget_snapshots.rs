@@ -0,0 +1,136 @@
+//! Golden-file snapshot tests for widgets under each theme variant
+//!
+//! Each widget is rendered into an off-screen buffer at several terminal sizes
+//! and serialized as its text rows followed by the style runs of each row, so
+//! both layout overflow and styling changes show up in the snapshot diff.
+//!
+//! Review changes with `cargo insta review`, or accept them with
+//! `cargo insta test --accept -p ait42-tui`.
+
+use ait42_tui::{
+    keybinds::Mode,
+    theme::Theme,
+    themes::ThemeVariant,
+    widgets::{DiffView, FileTree, Sidebar, StatusLine, Tab, TabBar},
+};
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    widgets::Widget,
+};
+use std::{fmt::Write, path::Path};
+
+const PATCH: &str = "\
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,4 @@
+ fn main() {
+-    println!(\"Hello\");
++    let name = std::env::args().nth(1).unwrap_or_default();
++    println!(\"Hello, {name}\");
+ }
+";
+
+/// Render `widget` into a `width`x`height` buffer
+fn render(widget: impl Widget, width: u16, height: u16) -> Buffer {
+    let area = Rect::new(0, 0, width, height);
+    let mut buf = Buffer::empty(area);
+    widget.render(area, &mut buf);
+    buf
+}
+
+/// Serialize a buffer as its rows followed by its non-default style runs
+fn serialize(buf: &Buffer) -> String {
+    let area = buf.area;
+    let blank = Cell::default().style();
+    let mut out = String::new();
+
+    for y in 0..area.height {
+        let row: String = (0..area.width).map(|x| buf.get(x, y).symbol()).collect();
+        writeln!(out, "|{}|", row).unwrap();
+    }
+
+    out.push_str("--- styles ---\n");
+    for y in 0..area.height {
+        let mut x = 0;
+        while x < area.width {
+            let style = buf.get(x, y).style();
+            let start = x;
+            while x < area.width && buf.get(x, y).style() == style {
+                x += 1;
+            }
+            if style != blank {
+                writeln!(
+                    out,
+                    "{}:{}..{} fg={:?} bg={:?} mod={:?}",
+                    y, start, x, style.fg, style.bg, style.add_modifier
+                )
+                .unwrap();
+            }
+        }
+    }
+    out
+}
+
+/// Snapshot `draw` under every theme variant at every size
+fn snapshot_all(name: &str, sizes: &[(u16, u16)], draw: impl Fn(&Theme, u16, u16) -> Buffer) {
+    for variant in ThemeVariant::all() {
+        let theme = Theme::from_variant(*variant);
+        let slug = variant.name().to_lowercase().replace(' ', "_");
+        for &(width, height) in sizes {
+            let mut settings = insta::Settings::clone_current();
+            settings.set_snapshot_suffix(format!("{}_{}x{}", slug, width, height));
+            settings.bind(|| {
+                insta::assert_snapshot!(name, serialize(&draw(&theme, width, height)));
+            });
+        }
+    }
+}
+
+#[test]
+fn snapshot_statusline() {
+    let sizes = [(40, 1), (80, 1), (120, 1)];
+    let modes = [Mode::Normal, Mode::Insert, Mode::Visual, Mode::Command];
+
+    for mode in modes {
+        let name = format!("statusline_{:?}", mode).to_lowercase();
+        snapshot_all(&name, &sizes, |theme, width, height| {
+            let widget = StatusLine::new(mode, (42, 17), 1280, theme)
+                .file_path(Path::new("crates/ait42-core/src/buffer.rs"))
+                .dirty(true)
+                .file_type("rust");
+            render(widget, width, height)
+        });
+    }
+}
+
+#[test]
+fn snapshot_tab_bar() {
+    let tabs = vec![
+        Tab::new("main.rs"),
+        Tab::new("lib.rs").modified(true),
+        Tab::new("a_very_long_file_name_that_overflows.rs"),
+        Tab::new("Cargo.toml"),
+    ];
+
+    snapshot_all("tab_bar", &[(30, 1), (80, 1), (120, 1)], |theme, width, height| {
+        render(TabBar::new(&tabs, 1, theme), width, height)
+    });
+}
+
+#[test]
+fn snapshot_sidebar() {
+    let tree = FileTree::example();
+
+    snapshot_all("sidebar", &[(20, 8), (30, 12), (40, 20)], |theme, width, height| {
+        render(Sidebar::new(&tree, 1, theme), width, height)
+    });
+}
+
+#[test]
+fn snapshot_diff_view() {
+    snapshot_all("diff_view", &[(40, 8), (80, 12)], |theme, width, height| {
+        render(DiffView::new(PATCH, theme), width, height)
+    });
+}
@@ -0,0 +1,69 @@
+//! Installing Updates
+//!
+//! Replaces an executable with a verified download. The new file is written
+//! next to the old one and renamed over it, so an interrupted update never
+//! leaves a half-written executable behind. The running process keeps using
+//! the old file until it is restarted.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+/// Replace the executable at `exe` with `contents`, keeping its permissions
+pub fn install_executable(contents: &[u8], exe: &Path) -> Result<()> {
+    let staged = sibling(exe, "update");
+    fs::write(&staged, contents)?;
+    if let Err(e) = replace(&staged, exe) {
+        let _ = fs::remove_file(&staged);
+        return Err(e);
+    }
+    tracing::info!("Installed update to {}", exe.display());
+    Ok(())
+}
+
+fn replace(staged: &Path, exe: &Path) -> Result<()> {
+    fs::set_permissions(staged, fs::metadata(exe)?.permissions())?;
+
+    // Windows cannot replace a running executable, but can rename it
+    if cfg!(windows) {
+        let old = sibling(exe, "old");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old)?;
+    }
+    fs::rename(staged, exe)?;
+    Ok(())
+}
+
+/// `exe` with `.suffix` appended to its file name
+fn sibling(exe: &Path, suffix: &str) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    exe.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_replaces_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("ait42");
+        fs::write(&exe, b"old").unwrap();
+
+        install_executable(b"new", &exe).unwrap();
+        assert_eq!(fs::read(&exe).unwrap(), b"new");
+        assert!(!dir.path().join("ait42.update").exists());
+    }
+
+    #[test]
+    fn test_install_missing_executable_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("missing");
+
+        assert!(install_executable(b"new", &exe).is_err());
+        assert!(!dir.path().join("missing.update").exists());
+    }
+}
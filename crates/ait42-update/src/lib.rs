@@ -0,0 +1,215 @@
+//! AIT42 Updates
+//!
+//! Update checks and self-updates shared by the TUI and the GUI. Each
+//! release channel publishes a manifest (`<endpoint>/<channel>.json`) naming
+//! the newest version, its release notes and a download per platform. A
+//! download is only installed once its minisign signature has been verified
+//! against the public key built into the binary.
+
+pub mod install;
+pub mod manifest;
+
+// Re-exports
+pub use install::install_executable;
+pub use manifest::{Artifact, Channel, Manifest, Release};
+
+use ait42_config::UpdatesConfig;
+use minisign_verify::{PublicKey, Signature};
+use semver::Version;
+use thiserror::Error;
+
+/// Public key release downloads are signed with, set when building releases
+pub const PUBLIC_KEY: Option<&str> = option_env!("AIT42_UPDATE_PUBKEY");
+
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    #[error("Unknown update channel: {0} (must be stable or beta)")]
+    UnknownChannel(String),
+
+    #[error("Failed to fetch {url}: {source}")]
+    Http {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Invalid update manifest: {0}")]
+    Manifest(String),
+
+    #[error("Invalid version: {0}")]
+    Version(#[from] semver::Error),
+
+    #[error("Release {version} has no download for {platform}")]
+    NoArtifact { version: String, platform: String },
+
+    #[error("This build has no update signing key, updates must be installed manually")]
+    NoPublicKey,
+
+    #[error("Signature verification failed: {0}")]
+    Signature(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, UpdateError>;
+
+/// Checks a channel for new releases and downloads them
+#[derive(Debug, Clone)]
+pub struct UpdateClient {
+    endpoint: String,
+    channel: Channel,
+    current: Version,
+    platform: String,
+    public_key: Option<String>,
+    http: reqwest::Client,
+}
+
+impl UpdateClient {
+    /// Client for the channel in `config`, updating `artifact` (the binary
+    /// name, e.g. "ait42") from `current_version`
+    pub fn new(config: &UpdatesConfig, artifact: &str, current_version: &str) -> Result<Self> {
+        Ok(Self {
+            endpoint: config.endpoint.trim_end_matches('/').to_string(),
+            channel: config.channel.parse()?,
+            current: Version::parse(current_version)?,
+            platform: platform_key(artifact),
+            public_key: PUBLIC_KEY.map(str::to_string),
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Use `public_key` instead of the built-in key
+    pub fn with_public_key(mut self, public_key: impl Into<String>) -> Self {
+        self.public_key = Some(public_key.into());
+        self
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    /// Newest release above the running version, if any
+    ///
+    /// The beta channel also considers stable releases, so a beta user is
+    /// never left behind a stable release.
+    pub async fn check(&self) -> Result<Option<Release>> {
+        let mut newest: Option<Release> = None;
+        for channel in self.channel.sources() {
+            let manifest = self.fetch_manifest(*channel).await?;
+            let Some(release) =
+                manifest.release_for(self.channel, &self.current, &self.platform)?
+            else {
+                continue;
+            };
+            if newest.as_ref().map_or(true, |n| release.version > n.version) {
+                newest = Some(release);
+            }
+        }
+        Ok(newest)
+    }
+
+    /// Download `release` and verify its signature
+    pub async fn download(&self, release: &Release) -> Result<Vec<u8>> {
+        let public_key = self.public_key.as_deref().ok_or(UpdateError::NoPublicKey)?;
+        let url = &release.artifact.url;
+        let http_error = |source| UpdateError::Http {
+            url: url.clone(),
+            source,
+        };
+        let bytes = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(http_error)?
+            .bytes()
+            .await
+            .map_err(http_error)?;
+
+        verify_signature(&bytes, &release.artifact.signature, public_key)?;
+        tracing::info!("Verified update {} ({} bytes)", release.version, bytes.len());
+        Ok(bytes.to_vec())
+    }
+
+    async fn fetch_manifest(&self, channel: Channel) -> Result<Manifest> {
+        let url = format!("{}/{}.json", self.endpoint, channel);
+        tracing::debug!("Fetching update manifest {}", url);
+        let http_error = |source| UpdateError::Http {
+            url: url.clone(),
+            source,
+        };
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(http_error)?;
+        let text = response.text().await.map_err(http_error)?;
+        serde_json::from_str(&text).map_err(|e| UpdateError::Manifest(format!("{}: {}", url, e)))
+    }
+}
+
+/// Verify a minisign `signature` (the contents of a `.minisig` file) of
+/// `data` against a base64 minisign `public_key`
+pub fn verify_signature(data: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let public_key = PublicKey::from_base64(public_key.trim())
+        .map_err(|e| UpdateError::Signature(format!("invalid public key: {}", e)))?;
+    let signature = Signature::decode(signature)
+        .map_err(|e| UpdateError::Signature(format!("invalid signature: {}", e)))?;
+    public_key
+        .verify(data, &signature, false)
+        .map_err(|e| UpdateError::Signature(e.to_string()))
+}
+
+/// Manifest key of `artifact` built for this platform, e.g.
+/// "ait42-linux-x86_64" or "ait42-darwin-aarch64"
+pub fn platform_key(artifact: &str) -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    format!("{}-{}-{}", artifact, os, std::env::consts::ARCH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key\n\
+        RUQBAgMEBQYHCKGYpTrlv1Hhxa9nvNrj5UoswvSqUo3DC+ADaJemRg9w4Pq1q1+esrlVEhvTTTz3e29qfTXEipsPGHQSrXKfjw4=\n\
+        trusted comment: timestamp:1700000000\tfile:ait42\n\
+        xxGoobb31DMOA2UtV/Kslsza1MrjFojtS6yzs5vmhFUcCN66tuG1VBa7AgR2S04xU5Eswl9IpwDoUb++n3F4BA==\n";
+
+    #[test]
+    fn test_verify_signature() {
+        assert!(verify_signature(b"ait42 update payload", SIGNATURE, TEST_KEY).is_ok());
+        assert!(matches!(
+            verify_signature(b"tampered payload", SIGNATURE, TEST_KEY),
+            Err(UpdateError::Signature(_))
+        ));
+        assert!(verify_signature(b"ait42 update payload", "not a signature", TEST_KEY).is_err());
+    }
+
+    #[test]
+    fn test_client_rejects_unknown_channel() {
+        let config = UpdatesConfig {
+            channel: "nightly".to_string(),
+            ..UpdatesConfig::default()
+        };
+        assert!(matches!(
+            UpdateClient::new(&config, "ait42", "0.1.0"),
+            Err(UpdateError::UnknownChannel(_))
+        ));
+    }
+
+    #[test]
+    fn test_platform_key() {
+        let key = platform_key("ait42");
+        assert!(key.starts_with("ait42-"));
+        assert!(key.ends_with(std::env::consts::ARCH));
+    }
+}
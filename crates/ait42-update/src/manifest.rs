@@ -0,0 +1,198 @@
+//! Release Manifests
+//!
+//! The manifest of a channel describes its newest release:
+//!
+//! ```json
+//! {
+//!   "version": "0.2.0",
+//!   "notes": "Faster startup",
+//!   "pub_date": "2025-01-01T00:00:00Z",
+//!   "platforms": {
+//!     "ait42-linux-x86_64": { "url": "https://...", "signature": "untrusted comment: ..." }
+//!   }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, UpdateError};
+
+/// Release channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Stable,
+    Beta,
+}
+
+impl Channel {
+    /// Channels whose manifests are checked, newest release wins
+    pub fn sources(&self) -> &'static [Channel] {
+        match self {
+            Channel::Stable => &[Channel::Stable],
+            Channel::Beta => &[Channel::Beta, Channel::Stable],
+        }
+    }
+
+    /// Whether releases numbered `version` are offered on this channel;
+    /// pre-releases (e.g. 0.2.0-beta.1) only are on beta
+    pub fn accepts(&self, version: &Version) -> bool {
+        match self {
+            Channel::Stable => version.pre.is_empty(),
+            Channel::Beta => true,
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = UpdateError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stable" => Ok(Channel::Stable),
+            "beta" => Ok(Channel::Beta),
+            other => Err(UpdateError::UnknownChannel(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Channel::Stable => "stable",
+            Channel::Beta => "beta",
+        })
+    }
+}
+
+/// Newest release published on a channel
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+    pub version: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub pub_date: Option<String>,
+    /// Downloads keyed by [`crate::platform_key`]
+    #[serde(default)]
+    pub platforms: HashMap<String, Artifact>,
+}
+
+/// Download of a release for one platform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Artifact {
+    pub url: String,
+    /// Contents of the minisign `.minisig` file for the download
+    pub signature: String,
+}
+
+/// Release that can be installed over the running version
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Release {
+    #[schemars(with = "String")]
+    pub version: Version,
+    pub notes: String,
+    pub pub_date: Option<String>,
+    pub artifact: Artifact,
+}
+
+impl Manifest {
+    /// The manifest's release if `channel` offers it, it is newer than
+    /// `current`, and it has a download for `platform`
+    pub fn release_for(
+        &self,
+        channel: Channel,
+        current: &Version,
+        platform: &str,
+    ) -> Result<Option<Release>> {
+        let version = Version::parse(self.version.trim_start_matches('v'))?;
+        if !channel.accepts(&version) || &version <= current {
+            return Ok(None);
+        }
+        let Some(artifact) = self.platforms.get(platform) else {
+            return Err(UpdateError::NoArtifact {
+                version: version.to_string(),
+                platform: platform.to_string(),
+            });
+        };
+        Ok(Some(Release {
+            version,
+            notes: self.notes.clone(),
+            pub_date: self.pub_date.clone(),
+            artifact: artifact.clone(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(version: &str) -> Manifest {
+        serde_json::from_value(serde_json::json!({
+            "version": version,
+            "notes": "Release notes",
+            "platforms": {
+                "ait42-linux-x86_64": { "url": "https://example.com/ait42", "signature": "sig" }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_release_for_newer_version() {
+        let current = Version::parse("0.1.0").unwrap();
+        let release = manifest("v0.2.0")
+            .release_for(Channel::Stable, &current, "ait42-linux-x86_64")
+            .unwrap()
+            .unwrap();
+        assert_eq!(release.version, Version::parse("0.2.0").unwrap());
+        assert_eq!(release.notes, "Release notes");
+        assert_eq!(release.artifact.url, "https://example.com/ait42");
+
+        // Same or older versions are not offered
+        for version in ["0.1.0", "0.0.9"] {
+            assert!(manifest(version)
+                .release_for(Channel::Stable, &current, "ait42-linux-x86_64")
+                .unwrap()
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn test_prereleases_only_on_beta() {
+        let current = Version::parse("0.1.0").unwrap();
+        let beta = manifest("0.2.0-beta.1");
+        assert!(beta
+            .release_for(Channel::Stable, &current, "ait42-linux-x86_64")
+            .unwrap()
+            .is_none());
+        assert!(beta
+            .release_for(Channel::Beta, &current, "ait42-linux-x86_64")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_missing_platform() {
+        let current = Version::parse("0.1.0").unwrap();
+        assert!(matches!(
+            manifest("0.2.0").release_for(Channel::Stable, &current, "ait42-windows-x86_64"),
+            Err(UpdateError::NoArtifact { .. })
+        ));
+    }
+
+    #[test]
+    fn test_channel_parse() {
+        assert_eq!("beta".parse::<Channel>().unwrap(), Channel::Beta);
+        assert_eq!(Channel::Stable.to_string(), "stable");
+        assert!("nightly".parse::<Channel>().is_err());
+        assert_eq!(Channel::Beta.sources(), &[Channel::Beta, Channel::Stable]);
+    }
+}
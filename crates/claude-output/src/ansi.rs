@@ -0,0 +1,207 @@
+//! ANSI/VT escape sequence stripping
+//!
+//! Output captured with `tmux pipe-pane` is the raw byte stream the
+//! terminal saw: colours, cursor movement, window titles, spinners redrawn
+//! with carriage returns. [`strip_ansi`] reduces it to the text a reader
+//! would see, so it can be shown in the UI or parsed.
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+/// 8-bit String Terminator
+const ST: char = '\u{9c}';
+
+/// Strip escape sequences and control characters from terminal output
+///
+/// Removes:
+/// - CSI sequences (`ESC [ … final`, or the 8-bit `0x9B` form), e.g. colours
+///   and cursor movement
+/// - OSC, DCS, SOS, PM and APC strings up to their BEL or String Terminator,
+///   e.g. window titles and hyperlinks
+/// - Other escape sequences such as charset selection (`ESC ( B`) and
+///   keypad modes (`ESC =`)
+/// - Control characters other than newline and tab
+///
+/// A carriage return not followed by a newline starts the line over, as a
+/// spinner redrawing itself does, and a backspace removes the character
+/// before it. A sequence cut off by the end of `text` is dropped.
+pub fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ESC => match chars.peek().copied() {
+                Some('[') => {
+                    chars.next();
+                    skip_csi(&mut chars);
+                }
+                Some(']' | 'P' | 'X' | '^' | '_') => {
+                    chars.next();
+                    skip_string(&mut chars);
+                }
+                // Intermediate bytes, then a final byte: ESC ( B, ESC # 8
+                Some('\x20'..='\x2f') => {
+                    while chars.next_if(|c| ('\x20'..='\x2f').contains(c)).is_some() {}
+                    chars.next_if(|c| ('\x30'..='\x7e').contains(c));
+                }
+                Some('\x30'..='\x7e') => {
+                    chars.next();
+                }
+                // A lone ESC; whatever follows is processed on its own
+                _ => {}
+            },
+            '\u{9b}' => skip_csi(&mut chars),
+            '\u{9d}' | '\u{90}' | '\u{98}' | '\u{9e}' | '\u{9f}' => skip_string(&mut chars),
+            '\r' => {
+                if chars
+                    .peek()
+                    .is_some_and(|&next| next != '\n' && next != '\r')
+                {
+                    let line_start = result.rfind('\n').map_or(0, |i| i + 1);
+                    result.truncate(line_start);
+                }
+            }
+            '\x08' => {
+                if !result.ends_with('\n') {
+                    result.pop();
+                }
+            }
+            '\n' | '\t' => result.push(ch),
+            c if c.is_control() => {}
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Skip the rest of a CSI sequence after its introducer
+///
+/// Stops early at a control character, which the terminal would have
+/// executed rather than treated as part of the sequence.
+fn skip_csi(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    while let Some(&c) = chars.peek() {
+        match c {
+            // Parameter and intermediate bytes
+            '\x20'..='\x3f' => {
+                chars.next();
+            }
+            // Final byte
+            '\x40'..='\x7e' => {
+                chars.next();
+                return;
+            }
+            _ => return,
+        }
+    }
+}
+
+/// Skip the rest of an OSC/DCS/SOS/PM/APC string after its introducer
+fn skip_string(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    while let Some(c) = chars.next() {
+        match c {
+            BEL | ST => return,
+            ESC if chars.peek() == Some(&'\\') => {
+                chars.next();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_strips_colours_and_cursor_movement() {
+        assert_eq!(strip_ansi("\x1b[1;32mok\x1b[0m done"), "ok done");
+        assert_eq!(strip_ansi("\x1b[?25l\x1b[2K\x1b[1Gline\x1b[?25h"), "line");
+        assert_eq!(strip_ansi("\u{9b}31mred\u{9b}m"), "red");
+    }
+
+    #[test]
+    fn test_strips_strings_and_short_sequences() {
+        assert_eq!(strip_ansi("\x1b]0;claude\x07title"), "title");
+        assert_eq!(strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip_ansi("\x1bPq#0;2;0;0;0\x1b\\after"), "after");
+        assert_eq!(strip_ansi("\x1b(Bplain\x1b=\x1b>\x1b7\x1b8"), "plain");
+        assert_eq!(strip_ansi("\x1b#8x"), "x");
+    }
+
+    #[test]
+    fn test_carriage_returns_and_backspaces() {
+        assert_eq!(strip_ansi("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(strip_ansi("done\n⠋ Thinking\r⠙ Thinking\rAnswer"), "done\nAnswer");
+        assert_eq!(strip_ansi("abx\x08c"), "abc");
+        assert_eq!(strip_ansi("a\n\x08b"), "a\nb");
+        assert_eq!(strip_ansi("partial\r"), "partial");
+        assert_eq!(strip_ansi("a\r\r\nb"), "a\nb");
+    }
+
+    #[test]
+    fn test_truncated_sequences() {
+        assert_eq!(strip_ansi("text\x1b"), "text");
+        assert_eq!(strip_ansi("text\x1b[38;5"), "text");
+        assert_eq!(strip_ansi("text\x1b]0;unterminated title"), "text");
+        // A control character ends a CSI sequence and is executed
+        assert_eq!(strip_ansi("a\x1b[3\nb"), "a\nb");
+    }
+
+    /// A complete escape sequence
+    fn sequence() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "[0-9;?]{0,8}[@-~]".prop_map(|s| format!("\x1b[{}", s)),
+            "[0-9;?]{0,8}[@-~]".prop_map(|s| format!("\u{9b}{}", s)),
+            "[ -~]{0,16}".prop_map(|s| format!("\x1b]{}\x07", s)),
+            "[ -~]{0,16}".prop_map(|s| format!("\x1b]{}\x1b\\", s)),
+            "[ -/]{1,2}[0-~]".prop_map(|s| format!("\x1b{}", s)),
+            "[0-9=>A-OQ-WYZa-z]".prop_map(|s| format!("\x1b{}", s)),
+        ]
+    }
+
+    /// Text without control characters
+    fn plain() -> impl Strategy<Value = String> {
+        "[^\\p{Cc}]{0,32}"
+    }
+
+    fn is_subsequence(sub: &str, of: &str) -> bool {
+        let mut of = of.chars();
+        sub.chars().all(|c| of.any(|o| o == c))
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_output_has_no_control_chars(input in any::<String>()) {
+            let stripped = strip_ansi(&input);
+            prop_assert!(stripped.chars().all(|c| !c.is_control() || c == '\n' || c == '\t'));
+            prop_assert!(is_subsequence(&stripped, &input));
+        }
+
+        #[test]
+        fn fuzz_strip_is_idempotent(input in any::<String>()) {
+            let stripped = strip_ansi(&input);
+            prop_assert_eq!(strip_ansi(&stripped), stripped);
+        }
+
+        #[test]
+        fn fuzz_plain_text_is_kept(input in plain()) {
+            prop_assert_eq!(strip_ansi(&input), input);
+        }
+
+        #[test]
+        fn fuzz_sequences_are_removed(
+            parts in prop::collection::vec((plain(), sequence()), 0..8),
+            tail in plain(),
+        ) {
+            let garbled: String = parts
+                .iter()
+                .map(|(text, seq)| format!("{}{}", text, seq))
+                .collect();
+            let expected: String = parts.iter().map(|(text, _)| text.as_str()).collect();
+            prop_assert_eq!(strip_ansi(&(garbled + &tail)), expected + &tail);
+        }
+    }
+}
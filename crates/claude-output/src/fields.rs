@@ -0,0 +1,370 @@
+//! `KEY: value` fields of structured replies
+//!
+//! Prompts such as the task analysis ask for one `KEY: value` line per
+//! field. Replies decorate them with Markdown (`**SUBTASKS:** 5`, `- KEY:`),
+//! brackets copied from the template (`[Linear]`), full-width colons or
+//! units (`4 subtasks`, `85%`), and the terminal output they are read from
+//! also holds the prompt itself. [`Fields`] finds every occurrence and the
+//! typed getters use the last one that parses.
+
+use omega_theory::ComplexityClass;
+
+/// `COMPLEXITY_CLASS:` - Logarithmic, Linear, Quadratic or Exponential
+pub const COMPLEXITY_CLASS: &str = "COMPLEXITY_CLASS";
+/// `SUBTASKS:` - recommended number of subtasks
+pub const SUBTASKS: &str = "SUBTASKS";
+/// `INSTANCES:` - recommended number of parallel instances
+pub const INSTANCES: &str = "INSTANCES";
+/// `CONFIDENCE:` - confidence from 0.0 to 1.0
+pub const CONFIDENCE: &str = "CONFIDENCE";
+/// `REASONING:` - free text, may span lines
+pub const REASONING: &str = "REASONING";
+
+/// Fields of the task analysis reply, in the order the prompt asks for them
+pub const ANALYSIS_KEYS: [&str; 5] = [COMPLEXITY_CLASS, SUBTASKS, INSTANCES, CONFIDENCE, REASONING];
+
+/// Characters decorating a field line, e.g. `- **KEY:** value`
+const DECORATION: &[char] = &['*', '_', '`', '#', '>', '-', '•'];
+
+/// Occurrences of `KEY: value` fields in a reply
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fields {
+    /// Key and value in order of appearance; a value runs until the next
+    /// field line or the end of the text
+    entries: Vec<(String, String)>,
+}
+
+impl Fields {
+    /// Find the fields named in `keys`
+    ///
+    /// Keys are matched case-insensitively at the start of a line, after
+    /// any Markdown decoration. `text` should already be stripped of escape
+    /// sequences (see [`crate::strip_ansi`]).
+    pub fn parse(text: &str, keys: &[&str]) -> Self {
+        let mut entries: Vec<(String, String)> = Vec::new();
+        for line in text.lines() {
+            match field_line(line, keys) {
+                Some((key, value)) => entries.push((key.to_string(), value.to_string())),
+                None => {
+                    if let Some((_, value)) = entries.last_mut() {
+                        value.push('\n');
+                        value.push_str(line);
+                    }
+                }
+            }
+        }
+        for (_, value) in &mut entries {
+            let len = value.trim_end().len();
+            value.truncate(len);
+        }
+        Self { entries }
+    }
+
+    /// Values of `key`, in order of appearance
+    pub fn values<'a>(&'a self, key: &'a str) -> impl DoubleEndedIterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The last value of `key` that `parse` accepts
+    pub fn parse_last<T>(&self, key: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+        self.values(key)
+            .rev()
+            .find_map(|value| parse(first_line(value)))
+    }
+
+    /// The last value of `key` holding a whole number, e.g. `5` or `約5個`
+    pub fn number(&self, key: &str) -> Option<usize> {
+        self.parse_last(key, parse_number)
+    }
+
+    /// The last value of `key` holding a fraction from 0 to 1, e.g. `0.85`
+    /// or `85%`
+    pub fn fraction(&self, key: &str) -> Option<f64> {
+        self.parse_last(key, parse_fraction)
+    }
+}
+
+/// Typed fields of a task analysis reply
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    pub complexity_class: Option<ComplexityClass>,
+    pub subtasks: Option<usize>,
+    pub instances: Option<usize>,
+    pub confidence: Option<f64>,
+    pub reasoning: Option<String>,
+}
+
+impl Analysis {
+    /// Read the analysis fields from a reply
+    ///
+    /// A field is `None` when no occurrence of it parses, e.g. when only
+    /// the prompt's `[Logarithmic/Linear/Quadratic/Exponential]` template
+    /// is present.
+    pub fn parse(text: &str) -> Self {
+        let fields = Fields::parse(text, &ANALYSIS_KEYS);
+        let analysis = Self {
+            complexity_class: fields.parse_last(COMPLEXITY_CLASS, parse_complexity_class),
+            subtasks: fields.number(SUBTASKS),
+            instances: fields.number(INSTANCES),
+            confidence: fields.fraction(CONFIDENCE),
+            reasoning: fields
+                .values(REASONING)
+                .rev()
+                .find(|value| !value.is_empty() && !is_template(value))
+                .map(str::to_string),
+        };
+        analysis
+    }
+}
+
+/// Key and value of a field line
+fn field_line<'a>(line: &'a str, keys: &[&'a str]) -> Option<(&'a str, &'a str)> {
+    let line = line.trim_start_matches(|c: char| c.is_whitespace() || DECORATION.contains(&c));
+    keys.iter().find_map(|&key| {
+        let rest = line
+            .get(..key.len())
+            .filter(|head| head.eq_ignore_ascii_case(key))
+            .map(|_| &line[key.len()..])?;
+        let rest = rest.trim_start_matches(|c: char| DECORATION.contains(&c) || c == ' ');
+        let value = rest.strip_prefix(':').or_else(|| rest.strip_prefix('：'))?;
+        Some((key, strip_decoration(value)))
+    })
+}
+
+/// `value` without surrounding whitespace and emphasis
+fn strip_decoration(value: &str) -> &str {
+    value.trim_matches(|c: char| c.is_whitespace() || DECORATION.contains(&c))
+}
+
+fn first_line(value: &str) -> &str {
+    value.lines().next().unwrap_or_default()
+}
+
+/// Whether `value` is a bracketed placeholder, like the prompt's, rather
+/// than an answer
+fn is_template(value: &str) -> bool {
+    let line = first_line(value);
+    line.starts_with('[') && line.ends_with(']')
+}
+
+/// The first number in `value` and the character after it, accepting
+/// full-width digits
+///
+/// A range such as `2-3` or the template's `0.0-1.0` is not an answer and
+/// yields `None`.
+fn first_number(value: &str) -> Option<(String, Option<char>)> {
+    let mut chars = value
+        .chars()
+        .map(normalize_digit)
+        .skip_while(|c| !c.is_ascii_digit())
+        .peekable();
+    let mut number = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+        number.push(c);
+    }
+    let next = chars.find(|c| !c.is_whitespace());
+    if number.is_empty() || matches!(next, Some('-' | '~' | '〜' | '–')) {
+        return None;
+    }
+    Some((number.trim_end_matches('.').to_string(), next))
+}
+
+/// The first whole number in `value`
+fn parse_number(value: &str) -> Option<usize> {
+    first_number(value)?.0.parse().ok()
+}
+
+/// The first number in `value` as a fraction from 0 to 1; a percentage is
+/// divided by 100
+fn parse_fraction(value: &str) -> Option<f64> {
+    let (number, unit) = first_number(value)?;
+    let mut fraction: f64 = number.parse().ok()?;
+    if matches!(unit, Some('%' | '％')) {
+        fraction /= 100.0;
+    }
+    (0.0..=1.0).contains(&fraction).then_some(fraction)
+}
+
+fn normalize_digit(c: char) -> char {
+    match c {
+        '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+        '．' => '.',
+        _ => c,
+    }
+}
+
+/// The one complexity class named in `value`
+///
+/// Accepts the class name (`Linear`) or its notation (`Ω(n)`, `O(n^2)`).
+/// A value naming several classes, like the prompt's template, is rejected.
+fn parse_complexity_class(value: &str) -> Option<ComplexityClass> {
+    let lower = value.to_lowercase();
+    let named: Vec<ComplexityClass> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter_map(|word| match word {
+            "constant" => Some(ComplexityClass::Constant),
+            "logarithmic" => Some(ComplexityClass::Logarithmic),
+            "linear" => Some(ComplexityClass::Linear),
+            "linearithmic" => Some(ComplexityClass::Linearithmic),
+            "quadratic" => Some(ComplexityClass::Quadratic),
+            "exponential" => Some(ComplexityClass::Exponential),
+            _ => None,
+        })
+        .collect();
+    if let Some(&first) = named.first() {
+        return named.iter().all(|&class| class == first).then_some(first);
+    }
+
+    let notation: String = lower
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .skip_while(|&c| c != '(')
+        .collect();
+    match notation.as_str() {
+        "(1)" => Some(ComplexityClass::Constant),
+        "(logn)" => Some(ComplexityClass::Logarithmic),
+        "(n)" => Some(ComplexityClass::Linear),
+        "(nlogn)" => Some(ComplexityClass::Linearithmic),
+        "(n²)" | "(n^2)" => Some(ComplexityClass::Quadratic),
+        "(2ⁿ)" | "(2^n)" => Some(ComplexityClass::Exponential),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi::strip_ansi;
+    use proptest::prelude::*;
+
+    /// The template lines of the analysis prompt, as echoed in the output
+    const PROMPT: &str = "COMPLEXITY_CLASS: [Logarithmic/Linear/Quadratic/Exponential]\n\
+                          SUBTASKS: [推奨サブタスク数（数値のみ）]\n\
+                          INSTANCES: [推奨並列実行インスタンス数（数値のみ）]\n\
+                          CONFIDENCE: [信頼度 0.0-1.0]\n\
+                          REASONING: [なぜこの複雑度クラスと分解数が適切か、詳細な理由を説明]\n";
+
+    #[test]
+    fn test_analysis_after_echoed_prompt() {
+        let output = format!(
+            "{}\n\
+             **COMPLEXITY_CLASS:** Quadratic\n\
+             - **SUBTASKS**: ６\n\
+             INSTANCES：4 instances\n\
+             Confidence: 85%\n\
+             REASONING: Migrations touch\n  every table.\n",
+            PROMPT
+        );
+        assert_eq!(
+            Analysis::parse(&output),
+            Analysis {
+                complexity_class: Some(ComplexityClass::Quadratic),
+                subtasks: Some(6),
+                instances: Some(4),
+                confidence: Some(0.85),
+                reasoning: Some("Migrations touch\n  every table.".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_only_prompt_yields_nothing() {
+        assert_eq!(Analysis::parse(PROMPT), Analysis::default());
+        assert_eq!(Analysis::parse(""), Analysis::default());
+    }
+
+    #[test]
+    fn test_complexity_class_values() {
+        assert_eq!(parse_complexity_class("[Linear]"), Some(ComplexityClass::Linear));
+        assert_eq!(parse_complexity_class("Ω(n log n)"), Some(ComplexityClass::Linearithmic));
+        assert_eq!(parse_complexity_class("O(n^2)"), Some(ComplexityClass::Quadratic));
+        assert_eq!(parse_complexity_class("Linear (Ω(n))"), Some(ComplexityClass::Linear));
+        assert_eq!(parse_complexity_class("Linear or Quadratic"), None);
+        assert_eq!(parse_complexity_class("unknown"), None);
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(parse_number("約5個"), Some(5));
+        assert_eq!(parse_number("１２"), Some(12));
+        assert_eq!(parse_number("many"), None);
+        assert_eq!(parse_fraction("0.7 (moderate)"), Some(0.7));
+        assert_eq!(parse_fraction("90％"), Some(0.9));
+        assert_eq!(parse_fraction("0.0-1.0"), None);
+        assert_eq!(parse_number("2〜3"), None);
+        assert_eq!(parse_fraction("7"), None);
+    }
+
+    #[test]
+    fn test_fields_ignore_keys_inside_lines() {
+        let fields =
+            Fields::parse("The SUBTASKS: line\nSUBTASKS_TOTAL: 3\nSUBTASKS: 2", &[SUBTASKS]);
+        assert_eq!(fields.values(SUBTASKS).collect::<Vec<_>>(), vec!["2"]);
+    }
+
+    fn complexity_class() -> impl Strategy<Value = ComplexityClass> {
+        prop_oneof![
+            Just(ComplexityClass::Logarithmic),
+            Just(ComplexityClass::Linear),
+            Just(ComplexityClass::Quadratic),
+            Just(ComplexityClass::Exponential),
+        ]
+    }
+
+    /// Markdown decoration around a key
+    fn decorated(key: &'static str) -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just(format!("{}:", key)),
+            Just(format!("**{}:**", key)),
+            Just(format!("- **{}**:", key)),
+            Just(format!("`{}`：", key)),
+            Just(format!("### {}:", key.to_lowercase())),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_fields_never_panic(input in any::<String>()) {
+            let _ = Analysis::parse(&input);
+        }
+
+        #[test]
+        fn fuzz_analysis_round_trips_through_terminal(
+            class in complexity_class(),
+            subtasks in 1usize..20,
+            instances in 1usize..10,
+            percent in 0u32..=100,
+            reasoning in "[a-zA-Z][a-zA-Z .,]{0,40}",
+            keys in (
+                decorated(COMPLEXITY_CLASS),
+                decorated(SUBTASKS),
+                decorated(INSTANCES),
+                decorated(CONFIDENCE),
+                decorated(REASONING),
+            ),
+            echo_prompt in any::<bool>(),
+        ) {
+            let reply = format!(
+                "{} {:?}\n{} {}\n{} {}\n{} {}\n{} {}\n",
+                keys.0, class, keys.1, subtasks, keys.2, instances,
+                keys.3, f64::from(percent) / 100.0, keys.4, reasoning.trim(),
+            );
+            // Coloured the way the CLI renders Markdown, with CRLF endings
+            let garbled = format!(
+                "{}\x1b[1m{}\x1b[0m",
+                if echo_prompt { PROMPT } else { "" },
+                reply.replace('\n', "\x1b[K\r\n").replace("**", "\x1b[1m**\x1b[22m"),
+            );
+
+            let analysis = Analysis::parse(&strip_ansi(&garbled));
+            prop_assert_eq!(analysis.complexity_class, Some(class));
+            prop_assert_eq!(analysis.subtasks, Some(subtasks));
+            prop_assert_eq!(analysis.instances, Some(instances));
+            prop_assert_eq!(analysis.confidence, Some(f64::from(percent) / 100.0));
+            prop_assert_eq!(analysis.reasoning.as_deref(), Some(reasoning.trim()));
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! JSON extraction from free-form replies
+//!
+//! Agents asked for JSON wrap it in a fenced block, surround it with prose,
+//! or echo the prompt's example before answering. [`extract_json`] finds the
+//! last candidate that deserializes into the expected type.
+
+use serde::de::DeserializeOwned;
+
+const FENCE: &str = "```";
+
+/// Contents of the ```json fenced blocks in `text`, in order
+///
+/// The language tag is matched case-insensitively. A block the output ends
+/// in before its closing fence runs to the end of `text`.
+pub fn json_blocks(text: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find(FENCE) {
+        let after = &rest[open + FENCE.len()..];
+        let is_json = after
+            .get(..4)
+            .is_some_and(|tag| tag.eq_ignore_ascii_case("json"));
+        if !is_json {
+            // A closing fence, or a block in another language
+            rest = after;
+            continue;
+        }
+        let body = &after[4..];
+        let end = body.find(FENCE).unwrap_or(body.len());
+        blocks.push(body[..end].trim());
+        rest = body.get(end + FENCE.len()..).unwrap_or("");
+    }
+    blocks
+}
+
+/// Balanced `{…}` and `[…]` spans of `text`, the last-ending first
+///
+/// Brackets inside JSON strings are skipped. Nested spans are included
+/// after the span enclosing them, so a reply whose outer value does not
+/// parse can still yield an inner one.
+pub fn json_spans(text: &str) -> Vec<&str> {
+    let mut spans = Vec::new();
+    let mut open: Vec<(usize, u8)> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, byte) in text.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                // JSON strings never span lines; this quote was prose
+                b'\n' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' if !open.is_empty() => in_string = true,
+            b'{' | b'[' => open.push((i, byte)),
+            b'}' | b']' => {
+                let expected = if byte == b'}' { b'{' } else { b'[' };
+                match open.pop() {
+                    Some((start, opener)) if opener == expected => {
+                        spans.push((start, i + 1));
+                    }
+                    // A stray closer; start over
+                    _ => open.clear(),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    spans.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    spans
+        .into_iter()
+        .map(|(start, end)| &text[start..end])
+        .collect()
+}
+
+/// The last JSON value in `text` that deserializes into `T`
+///
+/// Candidates are the ```json fenced blocks and the balanced spans of the
+/// whole text, tried from the one ending last, so the answer wins over an
+/// example echoed from the prompt and a bare value is found next to a block
+/// with a typo.
+pub fn extract_json<T: DeserializeOwned>(text: &str) -> Option<T> {
+    let end =
+        |candidate: &str| candidate.as_ptr() as usize - text.as_ptr() as usize + candidate.len();
+    let mut candidates = json_blocks(text);
+    candidates.extend(json_spans(text));
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(end(candidate)));
+    candidates
+        .into_iter()
+        .find_map(|candidate| serde_json::from_str(candidate).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi::strip_ansi;
+    use proptest::prelude::*;
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Plan {
+        steps: Vec<String>,
+    }
+
+    #[test]
+    fn test_json_blocks() {
+        let text = "```JSON\n{\"a\": 1}\n```\n```rust\nfn main() {}\n```\n\
+                    ```json\n[2]\n```\n```json\n[3";
+        assert_eq!(json_blocks(text), vec!["{\"a\": 1}", "[2]", "[3"]);
+        assert!(json_blocks("no fences").is_empty());
+    }
+
+    #[test]
+    fn test_json_spans_skip_brackets_in_strings() {
+        let text = r#"Use {"note": "a } b", "list": [1, 2]} then {x"#;
+        assert_eq!(json_spans(text), vec![r#"{"note": "a } b", "list": [1, 2]}"#, "[1, 2]"]);
+        // A quote in prose does not swallow the rest of the line's brackets
+        assert_eq!(json_spans(r#"it's "quoted" [1]"#), vec!["[1]"]);
+    }
+
+    #[test]
+    fn test_extract_json_prefers_the_answer() {
+        let output = "Reply like ```json\n{\"steps\": [\"...\"]}\n```\n\n\
+                      Here is the plan:\n```json\n{\"steps\": [\"a\", \"b\"],}\n```\n\
+                      Fixed: {\"steps\": [\"a\", \"b\"]} Done.";
+        assert_eq!(
+            extract_json::<Plan>(output),
+            Some(Plan {
+                steps: vec!["a".to_string(), "b".to_string()]
+            })
+        );
+        assert_eq!(extract_json::<Plan>("[1, 2]"), None);
+        assert_eq!(extract_json::<Value>("x [1, {\"a\": 2}"), Some(json!({"a": 2})));
+    }
+
+    /// An arbitrary JSON value, with brackets and quotes in its strings
+    fn json_value() -> impl Strategy<Value = Value> {
+        let leaf = prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::from),
+            any::<i64>().prop_map(Value::from),
+            "[a-z0-9 {}\\[\\]\"\\\\,:]{0,12}".prop_map(Value::from),
+        ];
+        leaf.prop_recursive(4, 32, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(Value::from),
+                prop::collection::btree_map("[a-z]{1,6}", inner, 0..4)
+                    .prop_map(|map| Value::Object(map.into_iter().collect())),
+            ]
+        })
+    }
+
+    /// Prose without brackets or control characters
+    fn prose() -> impl Strategy<Value = String> {
+        "[^\\p{Cc}{}\\[\\]]{0,32}"
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_extract_json_never_panics(input in any::<String>()) {
+            let _ = extract_json::<Value>(&input);
+        }
+
+        #[test]
+        fn fuzz_extract_json_finds_object_in_prose(
+            value in json_value(),
+            before in prose(),
+            after in prose(),
+            fenced in any::<bool>(),
+        ) {
+            let object = json!({ "value": value });
+            let text = if fenced {
+                format!("{}\n```json\n{:#}\n```\n{}", before, object, after)
+            } else {
+                format!("{}{}{}", before, object, after)
+            };
+            prop_assert_eq!(extract_json::<Value>(&text), Some(object));
+        }
+
+        #[test]
+        fn fuzz_extract_json_from_garbled_terminal_output(
+            value in json_value(),
+            before in prose(),
+            cuts in prop::collection::vec(any::<prop::sample::Index>(), 0..6),
+        ) {
+            let object = json!({ "value": value });
+            let clean = format!("{}{}", before, object);
+            // Colour codes between arbitrary characters, as a terminal
+            // renderer highlighting the reply would emit
+            let mut chars: Vec<String> = clean.chars().map(String::from).collect();
+            for cut in cuts {
+                let at = cut.index(chars.len());
+                chars[at].insert_str(0, "\x1b[1;36m");
+            }
+            let garbled = chars.concat() + "\x1b[0m\r\n";
+            prop_assert_eq!(extract_json::<Value>(&strip_ansi(&garbled)), Some(object));
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Parsing of Claude CLI output
+//!
+//! Agents run in tmux and their output is read back from the pane log, so
+//! it arrives as a terminal byte stream rather than clean text. This crate
+//! turns it into something typed:
+//!
+//! - [`strip_ansi`] removes escape sequences and replays carriage returns
+//!   and backspaces
+//! - [`extract_json`] finds the JSON value a reply was asked for, fenced or
+//!   not
+//! - [`Fields`] and [`Analysis`] read `KEY: value` replies such as the task
+//!   analysis's `COMPLEXITY_CLASS:`/`SUBTASKS:` lines
+//!
+//! # Example
+//!
+//! ```
+//! use claude_output::{strip_ansi, Analysis};
+//! use omega_theory::ComplexityClass;
+//!
+//! let output = "\x1b[1mCOMPLEXITY_CLASS:\x1b[0m Linear\r\nSUBTASKS: 4\r\n";
+//! let analysis = Analysis::parse(&strip_ansi(output));
+//! assert_eq!(analysis.complexity_class, Some(ComplexityClass::Linear));
+//! assert_eq!(analysis.subtasks, Some(4));
+//! ```
+
+pub mod ansi;
+pub mod fields;
+pub mod json;
+
+pub use ansi::strip_ansi;
+pub use fields::{Analysis, Fields};
+pub use json::{extract_json, json_blocks, json_spans};
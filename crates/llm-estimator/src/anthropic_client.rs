@@ -1,17 +1,18 @@
 //! Anthropic Claude API client for complexity estimation
 
 use crate::error::{EstimatorError, Result};
-use crate::prompt_builder::PromptBuilder;
-use crate::response_parser::{ComplexityEstimate, ResponseParser};
+use crate::provider::LlmProvider;
+use crate::response_parser::ComplexityEstimate;
 use anthropic_sdk::Client;
+use async_trait::async_trait;
 use serde_json::json;
 use std::time::Duration;
-use tracing::{debug, info};
+use tracing::info;
 
-/// Configuration for the Anthropic client
+/// Configuration for the LLM clients
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
-    /// Claude model to use (default: claude-sonnet-4-5-20250929)
+    /// Model to use (default: claude-sonnet-4-5-20250929)
     pub model: String,
 
     /// Temperature for sampling (0.0-1.0, default: 0.3 for consistency)
@@ -145,22 +146,19 @@ impl AnthropicClient {
         current_subtasks: usize,
         context: Option<&str>,
     ) -> Result<ComplexityEstimate> {
-        debug!(
-            "Estimating complexity for task: {} (current subtasks: {})",
-            task_description, current_subtasks
-        );
-
-        // Build prompt
-        let mut builder = PromptBuilder::new()
-            .with_task(task_description)
-            .with_current_subtasks(current_subtasks);
-
-        if let Some(ctx) = context {
-            builder = builder.with_context(ctx);
-        }
-
-        let prompt = builder.build();
+        crate::provider::estimate_complexity(self, task_description, current_subtasks, context)
+            .await
+    }
 
+    /// Send a single-turn prompt and return the raw response text
+    ///
+    /// Shared by complexity estimation and the editor's other LLM features
+    /// (such as assisted refactoring), which parse the text themselves.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError` if the API request fails or times out
+    pub async fn complete(&self, prompt: &str) -> Result<String> {
         // Build messages array
         let messages = json!([
             {
@@ -199,18 +197,7 @@ impl AnthropicClient {
 
         // Extract collected response
         let content_text = response_text.lock().await.clone();
-
-        debug!("Received response: {}", content_text);
-
-        // Parse response
-        let estimate = ResponseParser::parse(&content_text)?;
-
-        info!(
-            "Estimated complexity: {} (confidence: {:.2})",
-            estimate.complexity_class, estimate.confidence
-        );
-
-        Ok(estimate)
+        Ok(content_text)
     }
 
     /// Get the current model name
@@ -224,6 +211,21 @@ impl AnthropicClient {
     }
 }
 
+#[async_trait]
+impl LlmProvider for AnthropicClient {
+    fn name(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        AnthropicClient::complete(self, prompt).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
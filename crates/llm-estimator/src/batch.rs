@@ -0,0 +1,264 @@
+//! Batch estimation
+//!
+//! [`CachedEstimator::estimate_batch`](crate::CachedEstimator::estimate_batch)
+//! estimates many tasks at once. Identical tasks are estimated once, at most
+//! [`BatchConfig::max_concurrency`] API calls run at the same time, and a
+//! token bucket keeps calls under [`BatchConfig::requests_per_second`]. A
+//! failing task does not fail the batch; its error is returned in its place.
+
+use crate::error::{EstimatorError, Result};
+use crate::response_parser::ComplexityEstimate;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Task to estimate in a batch
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Task {
+    /// The task description to analyze
+    pub description: String,
+
+    /// Current number of subtasks (0 if new task)
+    pub current_subtasks: usize,
+
+    /// Optional additional context
+    pub context: Option<String>,
+}
+
+impl Task {
+    /// Create a new task without subtasks or context
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            current_subtasks: 0,
+            context: None,
+        }
+    }
+
+    /// Set the current number of subtasks
+    #[must_use]
+    pub fn with_subtasks(mut self, current_subtasks: usize) -> Self {
+        self.current_subtasks = current_subtasks;
+        self
+    }
+
+    /// Set additional context
+    #[must_use]
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+}
+
+/// Limits for batch estimation
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchConfig {
+    /// Maximum number of API calls in flight
+    pub max_concurrency: usize,
+
+    /// Sustained API calls per second
+    pub requests_per_second: f64,
+
+    /// Number of API calls that may start at once before the rate applies
+    pub burst: u32,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 4,
+            requests_per_second: 2.0,
+            burst: 4,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Check that the limits allow progress
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::InvalidConfig` if the concurrency or burst is
+    /// zero, or the rate is not a positive number
+    pub fn validate(&self) -> Result<()> {
+        if self.max_concurrency == 0 {
+            return Err(EstimatorError::InvalidConfig(
+                "max_concurrency must be at least 1".to_string(),
+            ));
+        }
+        if self.burst == 0 {
+            return Err(EstimatorError::InvalidConfig("burst must be at least 1".to_string()));
+        }
+        if !(self.requests_per_second.is_finite() && self.requests_per_second > 0.0) {
+            return Err(EstimatorError::InvalidConfig(format!(
+                "requests_per_second must be positive, got {}",
+                self.requests_per_second
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Result of a batch, in the order of the submitted tasks
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    /// Estimate or error for each task
+    ///
+    /// Errors are shared by duplicates of the failed task.
+    pub results: Vec<std::result::Result<ComplexityEstimate, Arc<EstimatorError>>>,
+
+    /// Number of distinct tasks that were estimated
+    pub unique_tasks: usize,
+}
+
+impl BatchOutcome {
+    /// Number of tasks that were estimated
+    #[must_use]
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|result| result.is_ok()).count()
+    }
+
+    /// Indices and errors of the tasks that failed
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &EstimatorError)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, result)| result.as_ref().err().map(|e| (i, &**e)))
+    }
+}
+
+/// Token bucket limiting the rate of API calls
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    rate: f64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Create a full bucket for `config`
+    pub(crate) fn new(config: &BatchConfig) -> Self {
+        let capacity = f64::from(config.burst);
+        Self {
+            rate: config.requests_per_second,
+            capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a call may be made
+    pub(crate) async fn acquire(&self) {
+        loop {
+            match self.try_acquire_at(Instant::now()) {
+                Ok(()) => return,
+                Err(wait) => {
+                    debug!("Rate limited, waiting {:?}", wait);
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+    }
+
+    /// Take a token at `now`, or return how long until one is available
+    fn try_acquire_at(&self, now: Instant) -> std::result::Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let elapsed = now
+            .saturating_duration_since(bucket.refilled_at)
+            .as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_validation() {
+        assert!(BatchConfig::default().validate().is_ok());
+
+        let invalid = [
+            BatchConfig {
+                max_concurrency: 0,
+                ..BatchConfig::default()
+            },
+            BatchConfig {
+                burst: 0,
+                ..BatchConfig::default()
+            },
+            BatchConfig {
+                requests_per_second: 0.0,
+                ..BatchConfig::default()
+            },
+            BatchConfig {
+                requests_per_second: f64::NAN,
+                ..BatchConfig::default()
+            },
+        ];
+        for config in invalid {
+            assert!(matches!(config.validate(), Err(EstimatorError::InvalidConfig(_))));
+        }
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(&BatchConfig {
+            requests_per_second: 2.0,
+            burst: 2,
+            ..BatchConfig::default()
+        });
+        let start = limiter.bucket.lock().unwrap().refilled_at;
+
+        // The burst is available at once, then calls wait for the rate
+        assert!(limiter.try_acquire_at(start).is_ok());
+        assert!(limiter.try_acquire_at(start).is_ok());
+        assert_eq!(limiter.try_acquire_at(start), Err(Duration::from_millis(500)));
+
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at(later).is_ok());
+        assert!(limiter.try_acquire_at(later).is_err());
+
+        // Idle time refills no more than the burst
+        let idle = later + Duration::from_secs(60);
+        assert!(limiter.try_acquire_at(idle).is_ok());
+        assert!(limiter.try_acquire_at(idle).is_ok());
+        assert!(limiter.try_acquire_at(idle).is_err());
+    }
+
+    #[test]
+    fn test_outcome_failures() {
+        let estimate = ComplexityEstimate {
+            complexity_class: "Ω(1)".to_string(),
+            reasoning: "Trivial".to_string(),
+            recommended_subtasks: 1,
+            confidence: 0.9,
+        };
+        let outcome = BatchOutcome {
+            results: vec![Ok(estimate), Err(Arc::new(EstimatorError::Timeout(30)))],
+            unique_tasks: 2,
+        };
+
+        assert_eq!(outcome.succeeded(), 1);
+        let failures: Vec<_> = outcome.failures().collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 1);
+        assert!(matches!(failures[0].1, EstimatorError::Timeout(30)));
+    }
+}
@@ -1,11 +1,17 @@
-//! In-memory caching layer for complexity estimates
+//! Caching layer for complexity estimates
+//!
+//! Estimates are kept in memory and, with a [`DiskCache`] attached, on disk
+//! so they survive restarts.
 
-use crate::anthropic_client::AnthropicClient;
+use crate::batch::{BatchConfig, BatchOutcome, RateLimiter, Task};
+use crate::disk_cache::DiskCache;
 use crate::error::Result;
+use crate::provider::{self, LlmProvider};
 use crate::response_parser::ComplexityEstimate;
+use futures::stream::{self, StreamExt};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Cache statistics
 #[derive(Debug, Clone, Default)]
@@ -21,6 +27,18 @@ pub struct CacheStats {
 
     /// Current cache size
     pub cache_size: usize,
+
+    /// Number of cache hits answered by the on-disk cache
+    pub disk_hits: usize,
+
+    /// Number of estimates in the on-disk cache
+    pub disk_size: usize,
+
+    /// Cache hits over all sessions, as recorded by the on-disk cache
+    pub lifetime_hits: u64,
+
+    /// Cache misses over all sessions, as recorded by the on-disk cache
+    pub lifetime_misses: u64,
 }
 
 impl CacheStats {
@@ -32,14 +50,40 @@ impl CacheStats {
             self.cache_hits as f64 / self.total_requests as f64
         }
     }
+
+    /// Calculate cache miss rate (0.0-1.0)
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Counts never reach 2^52
+    pub fn miss_rate(&self) -> f64 {
+        if self.total_requests == 0 {
+            0.0
+        } else {
+            self.cache_misses as f64 / self.total_requests as f64
+        }
+    }
+
+    /// Calculate cache hit rate over all sessions (0.0-1.0)
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Counts never reach 2^52
+    pub fn lifetime_hit_rate(&self) -> f64 {
+        let total = self.lifetime_hits + self.lifetime_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.lifetime_hits as f64 / total as f64
+        }
+    }
 }
 
-/// Cached estimator with in-memory cache
+/// Cached estimator with in-memory and optional on-disk cache
 pub struct CachedEstimator {
-    client: AnthropicClient,
+    client: Box<dyn LlmProvider>,
     cache: Arc<Mutex<HashMap<String, ComplexityEstimate>>>,
     stats: Arc<Mutex<CacheStats>>,
     max_cache_size: usize,
+    disk: Option<DiskCache>,
+    batch: BatchConfig,
+    limiter: RateLimiter,
 }
 
 impl CachedEstimator {
@@ -47,8 +91,8 @@ impl CachedEstimator {
     ///
     /// # Arguments
     ///
-    /// * `client` - The Anthropic client to use for API calls
-    pub fn new(client: AnthropicClient) -> Self {
+    /// * `client` - The LLM provider to use for API calls
+    pub fn new(client: impl LlmProvider + 'static) -> Self {
         Self::with_max_size(client, 1000) // Default: cache up to 1000 entries
     }
 
@@ -56,21 +100,48 @@ impl CachedEstimator {
     ///
     /// # Arguments
     ///
-    /// * `client` - The Anthropic client to use for API calls
+    /// * `client` - The LLM provider to use for API calls
     /// * `max_size` - Maximum number of entries to cache
-    pub fn with_max_size(client: AnthropicClient, max_size: usize) -> Self {
+    pub fn with_max_size(client: impl LlmProvider + 'static, max_size: usize) -> Self {
         info!("Initialized cached estimator with max size: {}", max_size);
+        let batch = BatchConfig::default();
         Self {
-            client,
+            client: Box::new(client),
             cache: Arc::new(Mutex::new(HashMap::new())),
             stats: Arc::new(Mutex::new(CacheStats::default())),
             max_cache_size: max_size,
+            disk: None,
+            limiter: RateLimiter::new(&batch),
+            batch,
         }
     }
 
+    /// Also keep estimates in `disk`, so they survive restarts
+    ///
+    /// Memory misses are looked up on disk before calling the API. Failures
+    /// of the on-disk cache are logged and otherwise ignored.
+    #[must_use]
+    pub fn with_disk_cache(mut self, disk: DiskCache) -> Self {
+        self.disk = Some(disk);
+        self
+    }
+
+    /// Use `config` to limit API calls of [`CachedEstimator::estimate_batch`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::InvalidConfig` if the limits are invalid
+    pub fn with_batch_config(mut self, config: BatchConfig) -> Result<Self> {
+        config.validate()?;
+        self.limiter = RateLimiter::new(&config);
+        self.batch = config;
+        Ok(self)
+    }
+
     /// Estimate complexity with caching
     ///
-    /// Checks cache first, falls back to API call if not found
+    /// Checks the in-memory cache, then the on-disk cache, and falls back to
+    /// an API call if not found
     ///
     /// # Arguments
     ///
@@ -113,6 +184,91 @@ impl CachedEstimator {
         task_description: &str,
         current_subtasks: usize,
         context: Option<&str>,
+    ) -> Result<ComplexityEstimate> {
+        self.estimate_limited(task_description, current_subtasks, context, None)
+            .await
+    }
+
+    /// Estimate complexity of many tasks
+    ///
+    /// Duplicate tasks are estimated once. Cache misses are sent to the API
+    /// within the limits of the [`BatchConfig`]; the rate limit applies
+    /// across batches. A failed task does not fail the batch, its error is
+    /// returned in its place.
+    ///
+    /// # Arguments
+    ///
+    /// * `tasks` - The tasks to analyze
+    ///
+    /// # Returns
+    ///
+    /// A `BatchOutcome` with an estimate or error for each task, in order
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cache or stats lock was poisoned
+    pub async fn estimate_batch(&self, tasks: &[Task]) -> BatchOutcome {
+        let mut unique: Vec<&Task> = Vec::new();
+        let mut slots: HashMap<&Task, usize> = HashMap::new();
+        let positions: Vec<usize> = tasks
+            .iter()
+            .map(|task| {
+                *slots.entry(task).or_insert_with(|| {
+                    unique.push(task);
+                    unique.len() - 1
+                })
+            })
+            .collect();
+
+        info!(
+            "Estimating batch of {} tasks ({} unique, concurrency {})",
+            tasks.len(),
+            unique.len(),
+            self.batch.max_concurrency
+        );
+
+        let mut estimates: Vec<Option<_>> = (0..unique.len()).map(|_| None).collect();
+        let mut pending = stream::iter(unique.iter().enumerate())
+            .map(|(i, task)| async move {
+                let result = self
+                    .estimate_limited(
+                        &task.description,
+                        task.current_subtasks,
+                        task.context.as_deref(),
+                        Some(&self.limiter),
+                    )
+                    .await
+                    .map_err(Arc::new);
+                (i, result)
+            })
+            .buffer_unordered(self.batch.max_concurrency);
+        while let Some((i, result)) = pending.next().await {
+            if let Err(e) = &result {
+                warn!("Batch estimate failed for task: {} ({})", unique[i].description, e);
+            }
+            estimates[i] = Some(result);
+        }
+
+        BatchOutcome {
+            results: positions
+                .into_iter()
+                .map(|i| {
+                    estimates[i]
+                        .clone()
+                        .expect("every unique task is estimated")
+                })
+                .collect(),
+            unique_tasks: unique.len(),
+        }
+    }
+
+    /// Estimate with caching, waiting for `limiter` before calling the API
+    async fn estimate_limited(
+        &self,
+        task_description: &str,
+        current_subtasks: usize,
+        context: Option<&str>,
+        limiter: Option<&RateLimiter>,
     ) -> Result<ComplexityEstimate> {
         // Generate cache key
         let cache_key = self.generate_cache_key(task_description, current_subtasks, context);
@@ -130,37 +286,82 @@ impl CachedEstimator {
                 debug!("Cache hit for task: {}", task_description);
                 let mut stats = self.stats.lock().unwrap();
                 stats.cache_hits += 1;
+                self.record_on_disk(true);
                 return Ok(cached_estimate.clone());
             }
         }
 
+        // Check on-disk cache
+        let task_hash = DiskCache::task_hash(self.client.model(), &cache_key);
+        if let Some(estimate) = self.get_from_disk(&task_hash) {
+            debug!("Disk cache hit for task: {}", task_description);
+            self.insert_in_memory(cache_key, estimate.clone());
+            let mut stats = self.stats.lock().unwrap();
+            stats.cache_hits += 1;
+            stats.disk_hits += 1;
+            self.record_on_disk(true);
+            return Ok(estimate);
+        }
+
         // Cache miss - call API
         debug!("Cache miss for task: {}", task_description);
-        let estimate = self
-            .client
-            .estimate_complexity_with_context(task_description, current_subtasks, context)
-            .await?;
-
-        // Update cache with LRU-style eviction
-        {
-            let mut cache = self.cache.lock().unwrap();
+        if let Some(limiter) = limiter {
+            limiter.acquire().await;
+        }
+        let estimate = provider::estimate_complexity(
+            &*self.client,
+            task_description,
+            current_subtasks,
+            context,
+        )
+        .await?;
 
-            // If cache is full, remove oldest entry (simple eviction strategy)
-            if cache.len() >= self.max_cache_size {
-                if let Some(first_key) = cache.keys().next().cloned() {
-                    cache.remove(&first_key);
-                    debug!("Evicted cache entry to make room");
-                }
+        self.insert_in_memory(cache_key, estimate.clone());
+        if let Some(disk) = &self.disk {
+            if let Err(e) = disk.insert(&task_hash, &estimate) {
+                warn!("Failed to store estimate in disk cache: {}", e);
             }
+        }
+        self.stats.lock().unwrap().cache_misses += 1;
+        self.record_on_disk(false);
+
+        Ok(estimate)
+    }
 
-            cache.insert(cache_key, estimate.clone());
+    /// Insert into the in-memory cache with LRU-style eviction
+    fn insert_in_memory(&self, cache_key: String, estimate: ComplexityEstimate) {
+        let mut cache = self.cache.lock().unwrap();
 
-            let mut stats = self.stats.lock().unwrap();
-            stats.cache_misses += 1;
-            stats.cache_size = cache.len();
+        // If cache is full, remove oldest entry (simple eviction strategy)
+        if cache.len() >= self.max_cache_size && !cache.contains_key(&cache_key) {
+            if let Some(first_key) = cache.keys().next().cloned() {
+                cache.remove(&first_key);
+                debug!("Evicted cache entry to make room");
+            }
         }
 
-        Ok(estimate)
+        cache.insert(cache_key, estimate);
+
+        let mut stats = self.stats.lock().unwrap();
+        stats.cache_size = cache.len();
+    }
+
+    /// Look up an estimate in the on-disk cache, if any
+    fn get_from_disk(&self, task_hash: &str) -> Option<ComplexityEstimate> {
+        let disk = self.disk.as_ref()?;
+        disk.get(task_hash).unwrap_or_else(|e| {
+            warn!("Failed to read disk cache: {}", e);
+            None
+        })
+    }
+
+    /// Count a hit or miss towards the hit rate across sessions
+    fn record_on_disk(&self, hit: bool) {
+        if let Some(disk) = &self.disk {
+            if let Err(e) = disk.record(hit) {
+                warn!("Failed to record cache statistics: {}", e);
+            }
+        }
     }
 
     /// Generate a cache key from task description and parameters
@@ -176,19 +377,37 @@ impl CachedEstimator {
         }
     }
 
-    /// Clear the cache
+    /// Clear the cache, including the on-disk cache
     pub fn clear_cache(&self) {
         let mut cache = self.cache.lock().unwrap();
         cache.clear();
         let mut stats = self.stats.lock().unwrap();
         stats.cache_size = 0;
+        if let Some(disk) = &self.disk {
+            if let Err(e) = disk.clear() {
+                warn!("Failed to clear disk cache: {}", e);
+            }
+        }
         info!("Cache cleared");
     }
 
     /// Get current cache statistics
+    ///
+    /// With an on-disk cache, also reports its size and the hits and misses
+    /// recorded over all sessions.
     pub fn stats(&self) -> CacheStats {
-        let stats = self.stats.lock().unwrap();
-        stats.clone()
+        let mut stats = self.stats.lock().unwrap().clone();
+        if let Some(disk) = &self.disk {
+            stats.disk_size = disk.len();
+            match disk.counts() {
+                Ok(counts) => {
+                    stats.lifetime_hits = counts.hits;
+                    stats.lifetime_misses = counts.misses;
+                }
+                Err(e) => warn!("Failed to read cache statistics: {}", e),
+            }
+        }
+        stats
     }
 
     /// Get the number of entries currently in cache
@@ -207,26 +426,18 @@ impl CachedEstimator {
         estimate: ComplexityEstimate,
     ) {
         let cache_key = self.generate_cache_key(task_description, current_subtasks, None);
-        let mut cache = self.cache.lock().unwrap();
-
-        // If cache is full, remove an entry (simple eviction strategy)
-        if cache.len() >= self.max_cache_size {
-            if let Some(first_key) = cache.keys().next().cloned() {
-                cache.remove(&first_key);
-                debug!("Evicted cache entry to make room");
-            }
-        }
-
-        cache.insert(cache_key, estimate);
-        let mut stats = self.stats.lock().unwrap();
-        stats.cache_size = cache.len();
+        self.insert_in_memory(cache_key, estimate);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::anthropic_client::ClientConfig;
+    use crate::anthropic_client::{AnthropicClient, ClientConfig};
+    use crate::disk_cache::DiskCacheConfig;
+    use crate::error::EstimatorError;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     fn create_test_client() -> AnthropicClient {
         // Create client with dummy key for tests (won't make real API calls)
@@ -237,6 +448,28 @@ mod tests {
         .unwrap()
     }
 
+    /// Provider failing prompts that mention "flaky", counting its calls
+    struct ScriptedProvider(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn name(&self) -> &'static str {
+            "scripted"
+        }
+
+        fn model(&self) -> &'static str {
+            "scripted-model"
+        }
+
+        async fn complete(&self, prompt: &str) -> Result<String> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            if prompt.contains("flaky") {
+                return Err(EstimatorError::ApiError("503 Service Unavailable".to_string()));
+            }
+            Ok(r#"{"complexity_class": "Ω(n)", "reasoning": "CRUD", "recommended_subtasks": 4, "confidence": 0.9}"#.to_string())
+        }
+    }
+
     fn create_test_estimate() -> ComplexityEstimate {
         ComplexityEstimate {
             complexity_class: "Ω(n)".to_string(),
@@ -340,11 +573,100 @@ mod tests {
             cache_hits: 7,
             cache_misses: 3,
             cache_size: 5,
+            ..CacheStats::default()
         };
 
         assert_eq!(stats.hit_rate(), 0.7);
+        assert_eq!(stats.miss_rate(), 0.3);
 
         stats.total_requests = 0;
         assert_eq!(stats.hit_rate(), 0.0);
+        assert_eq!(stats.miss_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_cache_stats_lifetime_hit_rate() {
+        let mut stats = CacheStats::default();
+        assert_eq!(stats.lifetime_hit_rate(), 0.0);
+
+        stats.lifetime_hits = 3;
+        stats.lifetime_misses = 1;
+        assert_eq!(stats.lifetime_hit_rate(), 0.75);
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let open_disk = || DiskCache::open(dir.path(), DiskCacheConfig::default()).unwrap();
+
+        let estimator = CachedEstimator::new(create_test_client()).with_disk_cache(open_disk());
+        let model = estimator.client.model().to_string();
+        let key = estimator.generate_cache_key("Test task", 0, None);
+        estimator
+            .disk
+            .as_ref()
+            .unwrap()
+            .insert(&DiskCache::task_hash(&model, &key), &create_test_estimate())
+            .unwrap();
+        drop(estimator);
+
+        // A new estimator answers from disk without calling the API
+        let estimator = CachedEstimator::new(create_test_client()).with_disk_cache(open_disk());
+        let estimate = estimator.estimate("Test task", 0).await.unwrap();
+        assert_eq!(estimate, create_test_estimate());
+        assert_eq!(estimator.cache_size(), 1);
+
+        let stats = estimator.stats();
+        assert_eq!(stats.cache_hits, 1);
+        assert_eq!(stats.disk_hits, 1);
+        assert_eq!(stats.disk_size, 1);
+        assert_eq!(stats.lifetime_hits, 1);
+        assert_eq!(stats.lifetime_hit_rate(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_batch() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let estimator = CachedEstimator::new(ScriptedProvider(calls.clone()))
+            .with_batch_config(BatchConfig {
+                max_concurrency: 2,
+                requests_per_second: 1000.0,
+                burst: 10,
+            })
+            .unwrap();
+        let tasks = [
+            Task::new("Add an endpoint"),
+            Task::new("Call a flaky service"),
+            Task::new("Add an endpoint"),
+            Task::new("Add an endpoint").with_context("No tests"),
+        ];
+
+        let outcome = estimator.estimate_batch(&tasks).await;
+
+        // Duplicates are estimated once and the failure stays with its task
+        assert_eq!(outcome.unique_tasks, 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(outcome.results.len(), 4);
+        assert_eq!(outcome.succeeded(), 3);
+        let failures: Vec<_> = outcome.failures().map(|(i, _)| i).collect();
+        assert_eq!(failures, vec![1]);
+        assert_eq!(outcome.results[0].as_ref().unwrap().recommended_subtasks, 4);
+
+        // Cached estimates are not requested again
+        let outcome = estimator.estimate_batch(&tasks[..1]).await;
+        assert_eq!(outcome.succeeded(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_invalid_batch_config() {
+        let config = BatchConfig {
+            max_concurrency: 0,
+            ..BatchConfig::default()
+        };
+        assert!(matches!(
+            CachedEstimator::new(create_test_client()).with_batch_config(config),
+            Err(EstimatorError::InvalidConfig(_))
+        ));
     }
 }
@@ -0,0 +1,336 @@
+//! Persistent on-disk cache for complexity estimates
+//!
+//! Estimates are stored in a sled database keyed by a SHA-256 hash of the
+//! model and task, so they survive restarts. Entries expire after a TTL and
+//! the oldest are evicted once the cache grows past its size limit. Hit and
+//! miss counts are stored alongside, giving hit rates across sessions.
+
+use crate::error::Result;
+use crate::response_parser::ComplexityEstimate;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// Tree holding the estimates
+const ESTIMATES_TREE: &str = "estimates";
+
+/// Tree holding the hit and miss counters
+const STATS_TREE: &str = "stats";
+
+const HITS_KEY: &str = "hits";
+const MISSES_KEY: &str = "misses";
+
+/// Limits of the on-disk cache
+#[derive(Debug, Clone)]
+pub struct DiskCacheConfig {
+    /// How long an estimate stays valid
+    pub ttl: Duration,
+
+    /// Maximum number of estimates kept; the oldest are evicted first
+    pub max_entries: usize,
+}
+
+impl Default for DiskCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(30 * 24 * 60 * 60), // 30 days
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// Hit and miss counts recorded by a [`DiskCache`] over all sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskCacheCounts {
+    /// Requests answered from a cache
+    pub hits: u64,
+
+    /// Requests that needed an API call
+    pub misses: u64,
+}
+
+/// Estimate as stored on disk
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEstimate {
+    estimate: ComplexityEstimate,
+    /// Seconds since the Unix epoch
+    stored_at: u64,
+}
+
+/// Persistent cache of complexity estimates
+pub struct DiskCache {
+    db: sled::Db,
+    estimates: sled::Tree,
+    stats: sled::Tree,
+    config: DiskCacheConfig,
+}
+
+impl DiskCache {
+    /// Open the cache stored in the directory `path`, creating it if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::CacheError` if the database cannot be opened,
+    /// e.g. because another process holds it
+    pub fn open(path: impl AsRef<Path>, config: DiskCacheConfig) -> Result<Self> {
+        let db = sled::open(path.as_ref())?;
+        let estimates = db.open_tree(ESTIMATES_TREE)?;
+        let stats = db.open_tree(STATS_TREE)?;
+        info!(
+            "Opened estimate cache at {} ({} entries)",
+            path.as_ref().display(),
+            estimates.len()
+        );
+        Ok(Self {
+            db,
+            estimates,
+            stats,
+            config,
+        })
+    }
+
+    /// Hash identifying the estimate of `task_key` by `model`
+    #[must_use]
+    pub fn task_hash(model: &str, task_key: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(model.as_bytes());
+        hasher.update([0]);
+        hasher.update(task_key.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Look up a valid estimate
+    ///
+    /// An expired or unreadable entry is removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::CacheError` if the database fails
+    pub fn get(&self, hash: &str) -> Result<Option<ComplexityEstimate>> {
+        self.get_at(hash, now_secs())
+    }
+
+    fn get_at(&self, hash: &str, now: u64) -> Result<Option<ComplexityEstimate>> {
+        match self.estimates.get(hash)? {
+            Some(bytes) => match serde_json::from_slice::<StoredEstimate>(&bytes) {
+                Ok(stored) if !self.is_expired(stored.stored_at, now) => Ok(Some(stored.estimate)),
+                Ok(_) => {
+                    debug!("Cached estimate {} expired", hash);
+                    self.estimates.remove(hash)?;
+                    Ok(None)
+                }
+                Err(e) => {
+                    warn!("Discarding unreadable cached estimate {}: {}", hash, e);
+                    self.estimates.remove(hash)?;
+                    Ok(None)
+                }
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Store `estimate`, evicting the oldest entries beyond the size limit
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::CacheError` if the database fails
+    pub fn insert(&self, hash: &str, estimate: &ComplexityEstimate) -> Result<()> {
+        self.insert_at(hash, estimate, now_secs())
+    }
+
+    fn insert_at(&self, hash: &str, estimate: &ComplexityEstimate, now: u64) -> Result<()> {
+        let stored = StoredEstimate {
+            estimate: estimate.clone(),
+            stored_at: now,
+        };
+        self.estimates.insert(hash, serde_json::to_vec(&stored)?)?;
+        if self.estimates.len() > self.config.max_entries {
+            self.evict(now)?;
+        }
+        Ok(())
+    }
+
+    /// Remove expired entries, then the oldest until within the size limit
+    fn evict(&self, now: u64) -> Result<()> {
+        let mut entries = Vec::new();
+        for entry in &self.estimates {
+            let (key, value) = entry?;
+            let stored_at = serde_json::from_slice::<StoredEstimate>(&value)
+                .map_or(0, |stored| stored.stored_at);
+            entries.push((stored_at, key));
+        }
+        entries.sort_by_key(|(stored_at, _)| *stored_at);
+
+        let excess = entries.len().saturating_sub(self.config.max_entries);
+        let mut evicted = 0;
+        for (i, (stored_at, key)) in entries.into_iter().enumerate() {
+            if i >= excess && !self.is_expired(stored_at, now) {
+                break;
+            }
+            self.estimates.remove(key)?;
+            evicted += 1;
+        }
+        debug!("Evicted {} cached estimates", evicted);
+        Ok(())
+    }
+
+    fn is_expired(&self, stored_at: u64, now: u64) -> bool {
+        now.saturating_sub(stored_at) >= self.config.ttl.as_secs()
+    }
+
+    /// Number of estimates stored, including expired ones not yet removed
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.estimates.len()
+    }
+
+    /// Whether no estimates are stored
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.estimates.is_empty()
+    }
+
+    /// Record whether a request was answered from a cache
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::CacheError` if the database fails
+    pub fn record(&self, hit: bool) -> Result<()> {
+        let key = if hit { HITS_KEY } else { MISSES_KEY };
+        self.stats.update_and_fetch(key, |old| {
+            let count = old.map_or(0, decode_count);
+            Some((count + 1).to_be_bytes().to_vec())
+        })?;
+        Ok(())
+    }
+
+    /// Hits and misses recorded over all sessions
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::CacheError` if the database fails
+    pub fn counts(&self) -> Result<DiskCacheCounts> {
+        let read = |key: &str| -> Result<u64> {
+            Ok(self.stats.get(key)?.map_or(0, |bytes| decode_count(&bytes)))
+        };
+        Ok(DiskCacheCounts {
+            hits: read(HITS_KEY)?,
+            misses: read(MISSES_KEY)?,
+        })
+    }
+
+    /// Remove all estimates, keeping the hit and miss counts
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::CacheError` if the database fails
+    pub fn clear(&self) -> Result<()> {
+        self.estimates.clear()?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+fn decode_count(bytes: &[u8]) -> u64 {
+    bytes.try_into().map_or(0, u64::from_be_bytes)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_estimate(subtasks: usize) -> ComplexityEstimate {
+        ComplexityEstimate {
+            complexity_class: "Ω(n)".to_string(),
+            reasoning: "Test estimate".to_string(),
+            recommended_subtasks: subtasks,
+            confidence: 0.8,
+        }
+    }
+
+    fn open_test_cache(dir: &tempfile::TempDir, max_entries: usize) -> DiskCache {
+        let config = DiskCacheConfig {
+            ttl: Duration::from_secs(100),
+            max_entries,
+        };
+        DiskCache::open(dir.path().join("estimates"), config).unwrap()
+    }
+
+    #[test]
+    fn test_task_hash() {
+        let hash = DiskCache::task_hash("sonnet", "Task A:0");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, DiskCache::task_hash("sonnet", "Task A:0"));
+        assert_ne!(hash, DiskCache::task_hash("opus", "Task A:0"));
+        assert_ne!(hash, DiskCache::task_hash("sonnet", "Task B:0"));
+    }
+
+    #[test]
+    fn test_estimates_survive_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let cache = open_test_cache(&dir, 10);
+            assert_eq!(cache.get("a").unwrap(), None);
+            cache.insert("a", &create_test_estimate(3)).unwrap();
+            cache.record(false).unwrap();
+            cache.record(true).unwrap();
+        }
+
+        let cache = open_test_cache(&dir, 10);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("a").unwrap(), Some(create_test_estimate(3)));
+        cache.record(true).unwrap();
+        assert_eq!(cache.counts().unwrap(), DiskCacheCounts { hits: 2, misses: 1 });
+
+        cache.clear().unwrap();
+        assert!(cache.is_empty());
+        assert_eq!(cache.counts().unwrap().hits, 2);
+    }
+
+    #[test]
+    fn test_expired_estimates_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open_test_cache(&dir, 10);
+        cache
+            .insert_at("a", &create_test_estimate(3), 1_000)
+            .unwrap();
+
+        assert!(cache.get_at("a", 1_099).unwrap().is_some());
+        assert_eq!(cache.get_at("a", 1_100).unwrap(), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_oldest_estimates_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = open_test_cache(&dir, 2);
+        cache
+            .insert_at("a", &create_test_estimate(1), 1_000)
+            .unwrap();
+        cache
+            .insert_at("b", &create_test_estimate(2), 1_010)
+            .unwrap();
+        cache
+            .insert_at("c", &create_test_estimate(3), 1_020)
+            .unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_at("a", 1_020).unwrap(), None);
+        assert!(cache.get_at("b", 1_020).unwrap().is_some());
+
+        // Expired entries go as well, even within the limit
+        cache
+            .insert_at("d", &create_test_estimate(4), 1_125)
+            .unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get_at("d", 1_125).unwrap().is_some());
+    }
+}
@@ -5,8 +5,8 @@ use thiserror::Error;
 /// Main error type for the LLM estimator
 #[derive(Error, Debug)]
 pub enum EstimatorError {
-    /// Anthropic API errors
-    #[error("Anthropic API error: {0}")]
+    /// LLM provider API errors
+    #[error("LLM API error: {0}")]
     ApiError(String),
 
     /// Response parsing failed
@@ -28,6 +28,10 @@ pub enum EstimatorError {
     /// JSON serialization/deserialization error
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
+
+    /// On-disk cache error
+    #[error("Cache error: {0}")]
+    CacheError(#[from] sled::Error),
 }
 
 /// Errors that occur during response parsing
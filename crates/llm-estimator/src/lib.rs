@@ -1,12 +1,16 @@
 //! LLM-based complexity estimation engine for AIT42 Editor
 //!
-//! This crate provides automatic task complexity classification using
-//! Anthropic's Claude API with Big Omega notation.
+//! This crate provides automatic task complexity classification with Big
+//! Omega notation, using Anthropic's Claude API, OpenAI or a local Ollama
+//! server.
 //!
 //! # Features
 //!
 //! - **LLM-powered analysis**: Uses Claude Sonnet 4.5 for intelligent classification
+//! - **Pluggable providers**: Any `LlmProvider` (Anthropic, OpenAI, Ollama) can estimate
 //! - **In-memory caching**: Reduces API calls and improves performance
+//! - **Persistent caching**: Optional on-disk cache with TTL and size limits
+//! - **Batch estimation**: Deduplicated, concurrency- and rate-limited batches
 //! - **Robust parsing**: Handles various LLM output formats (JSON, markdown, explanations)
 //! - **Error handling**: Comprehensive validation and error recovery
 //! - **Configurable**: Adjustable model, temperature, and timeout settings
@@ -47,7 +51,13 @@
 //!                      │ Cache miss
 //!                      ▼
 //! ┌─────────────────────────────────────────────────────────┐
-//! │                  AnthropicClient                        │
+//! │                    DiskCache (optional)                 │
+//! │  (sled database keyed by task hash, TTL, size limit)    │
+//! └────────────────────┬────────────────────────────────────┘
+//!                      │ Cache miss
+//!                      ▼
+//! ┌─────────────────────────────────────────────────────────┐
+//! │     LlmProvider (Anthropic / OpenAI / Ollama)           │
 //! │  (API calls, timeout handling, model config)            │
 //! └────────────────────┬────────────────────────────────────┘
 //!                      │ Build prompt
@@ -58,7 +68,7 @@
 //! └────────────────────┬────────────────────────────────────┘
 //!                      │ Generate prompt
 //!                      ▼
-//!                 Claude API
+//!                  LLM API
 //!                      │ JSON response
 //!                      ▼
 //! ┌─────────────────────────────────────────────────────────┐
@@ -75,7 +85,10 @@
 //!
 //! # Environment Variables
 //!
-//! - `ANTHROPIC_API_KEY`: Required for API access (get from console.anthropic.com)
+//! - `ANTHROPIC_API_KEY`: Required for the Anthropic provider (get from console.anthropic.com)
+//! - `OPENAI_API_KEY`: Required for the OpenAI provider
+//! - `OPENAI_BASE_URL`: Optional OpenAI-compatible endpoint
+//! - `OLLAMA_HOST`: Optional Ollama server address (default: `http://localhost:11434`)
 //!
 //! # Performance
 //!
@@ -109,16 +122,26 @@
 #![allow(clippy::module_name_repetitions)]
 
 mod anthropic_client;
+mod batch;
 mod cache;
+mod disk_cache;
 mod error;
+mod ollama_client;
+mod openai_client;
 mod prompt_builder;
+mod provider;
 mod response_parser;
 
 // Public API
 pub use anthropic_client::{AnthropicClient, ClientConfig};
+pub use batch::{BatchConfig, BatchOutcome, Task};
 pub use cache::{CacheStats, CachedEstimator};
+pub use disk_cache::{DiskCache, DiskCacheConfig, DiskCacheCounts};
 pub use error::{EstimatorError, ParseError, Result};
+pub use ollama_client::OllamaClient;
+pub use openai_client::OpenAiClient;
 pub use prompt_builder::PromptBuilder;
+pub use provider::{provider_from_env, provider_with_config, LlmProvider, PROVIDERS};
 pub use response_parser::{ComplexityEstimate, ResponseParser};
 
 // Re-export ComplexityClass from omega-theory for convenience
@@ -133,6 +156,8 @@ mod tests {
         // Verify all public types are accessible
         let _: AnthropicClient;
         let _: CachedEstimator;
+        let _: OpenAiClient;
+        let _: OllamaClient;
         let _: ComplexityEstimate;
         let _: ComplexityClass;
     }
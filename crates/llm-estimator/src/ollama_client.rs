@@ -0,0 +1,157 @@
+//! Ollama client for locally hosted models
+//!
+//! Needs no API key, so complexity estimation also works offline against a
+//! local Ollama server.
+
+use crate::anthropic_client::ClientConfig;
+use crate::error::{EstimatorError, Result};
+use crate::provider::{post_json, LlmProvider};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tracing::info;
+
+/// Ollama generate API client
+#[derive(Debug)]
+pub struct OllamaClient {
+    base_url: String,
+    config: ClientConfig,
+    http: reqwest::Client,
+}
+
+impl OllamaClient {
+    /// Address of a local Ollama server
+    pub const DEFAULT_BASE_URL: &'static str = "http://localhost:11434";
+
+    /// Model used unless configured otherwise
+    pub const DEFAULT_MODEL: &'static str = "llama3.1";
+
+    /// Create a client for the Ollama server at `base_url`
+    ///
+    /// `config.model` must be a model pulled on that server.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::InvalidConfig` if the base URL or model is empty
+    pub fn with_config(base_url: &str, config: ClientConfig) -> Result<Self> {
+        if base_url.trim().is_empty() {
+            return Err(EstimatorError::InvalidConfig(
+                "Ollama base URL cannot be empty".to_string(),
+            ));
+        }
+        if config.model.is_empty() {
+            return Err(EstimatorError::InvalidConfig("Ollama model cannot be empty".to_string()));
+        }
+
+        info!("Initialized Ollama client at {} with model: {}", base_url, config.model);
+
+        Ok(Self {
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            config,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Create a client for a local server with the default model
+    ///
+    /// # Errors
+    ///
+    /// Same as [`OllamaClient::with_config`]
+    pub fn local() -> Result<Self> {
+        let config = ClientConfig {
+            model: Self::DEFAULT_MODEL.to_string(),
+            ..ClientConfig::default()
+        };
+        Self::with_config(Self::DEFAULT_BASE_URL, config)
+    }
+
+    /// Get the server address
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request_body(&self, prompt: &str) -> Value {
+        json!({
+            "model": self.config.model,
+            "prompt": prompt,
+            "stream": false,
+            "options": {
+                "temperature": self.config.temperature,
+                "num_predict": self.config.max_tokens
+            }
+        })
+    }
+
+    fn response_text(response: &Value) -> Result<String> {
+        response["response"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| {
+                EstimatorError::ApiError(format!("Unexpected Ollama response: {response}"))
+            })
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaClient {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        let url = format!("{}/api/generate", self.base_url);
+        let response =
+            post_json(&self.http, &url, None, &self.request_body(prompt), self.config.timeout_secs)
+                .await?;
+        Self::response_text(&response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_client() {
+        let client = OllamaClient::local().unwrap();
+        assert_eq!(client.base_url(), "http://localhost:11434");
+        assert_eq!(client.model(), "llama3.1");
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(matches!(
+            OllamaClient::with_config(" ", ClientConfig::default()),
+            Err(EstimatorError::InvalidConfig(_))
+        ));
+        let config = ClientConfig {
+            model: String::new(),
+            ..ClientConfig::default()
+        };
+        assert!(matches!(
+            OllamaClient::with_config("http://localhost:11434", config),
+            Err(EstimatorError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_request_body() {
+        let client = OllamaClient::local().unwrap();
+        let body = client.request_body("Classify this task");
+        assert_eq!(body["model"], "llama3.1");
+        assert_eq!(body["prompt"], "Classify this task");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["options"]["num_predict"], 1024);
+    }
+
+    #[test]
+    fn test_response_text() {
+        let response = json!({ "model": "llama3.1", "response": "{}", "done": true });
+        assert_eq!(OllamaClient::response_text(&response).unwrap(), "{}");
+        assert!(OllamaClient::response_text(&json!({ "error": "model not found" })).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! OpenAI chat completions client
+//!
+//! Also works with OpenAI-compatible servers (vLLM, LM Studio, Azure
+//! proxies) by pointing the base URL at them.
+
+use crate::anthropic_client::ClientConfig;
+use crate::error::{EstimatorError, Result};
+use crate::provider::{post_json, LlmProvider};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tracing::info;
+
+/// OpenAI chat completions client
+#[derive(Debug)]
+pub struct OpenAiClient {
+    api_key: String,
+    base_url: String,
+    config: ClientConfig,
+    http: reqwest::Client,
+}
+
+impl OpenAiClient {
+    /// Default API endpoint
+    pub const DEFAULT_BASE_URL: &'static str = "https://api.openai.com/v1";
+
+    /// Model used unless configured otherwise
+    pub const DEFAULT_MODEL: &'static str = "gpt-4o-mini";
+
+    /// Create a new OpenAI client
+    ///
+    /// `config.model` should name an OpenAI model, e.g.
+    /// [`OpenAiClient::DEFAULT_MODEL`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::InvalidConfig` if the API key is empty
+    pub fn with_config(api_key: String, config: ClientConfig) -> Result<Self> {
+        if api_key.is_empty() {
+            return Err(EstimatorError::InvalidConfig("API key cannot be empty".to_string()));
+        }
+
+        info!("Initialized OpenAI client with model: {}", config.model);
+
+        Ok(Self {
+            api_key,
+            base_url: Self::DEFAULT_BASE_URL.to_string(),
+            config,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Create a client from environment variables
+    ///
+    /// Reads `OPENAI_API_KEY`, and `OPENAI_BASE_URL` if set
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::InvalidConfig` if `OPENAI_API_KEY` is not set
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_with_config(ClientConfig {
+            model: Self::DEFAULT_MODEL.to_string(),
+            ..ClientConfig::default()
+        })
+    }
+
+    /// Create a client from environment variables like [`Self::from_env`],
+    /// with `config` instead of the defaults
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError::InvalidConfig` if `OPENAI_API_KEY` is not set
+    pub fn from_env_with_config(config: ClientConfig) -> Result<Self> {
+        let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| {
+            EstimatorError::InvalidConfig("OPENAI_API_KEY environment variable is not set".into())
+        })?;
+        let client = Self::with_config(api_key, config)?;
+
+        Ok(match std::env::var("OPENAI_BASE_URL") {
+            Ok(base_url) if !base_url.is_empty() => client.with_base_url(&base_url),
+            _ => client,
+        })
+    }
+
+    /// Use `model` instead of the configured model
+    #[must_use]
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.config.model = model.to_string();
+        self
+    }
+
+    /// Send requests to `base_url`, e.g. an OpenAI-compatible server
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Get the API endpoint
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request_body(&self, prompt: &str) -> Value {
+        json!({
+            "model": self.config.model,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": prompt
+                }
+            ],
+            "temperature": self.config.temperature,
+            "max_tokens": self.config.max_tokens
+        })
+    }
+
+    fn response_text(response: &Value) -> Result<String> {
+        response["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| {
+                EstimatorError::ApiError(format!("Unexpected OpenAI response: {response}"))
+            })
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiClient {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let response = post_json(
+            &self.http,
+            &url,
+            Some(&self.api_key),
+            &self.request_body(prompt),
+            self.config.timeout_secs,
+        )
+        .await?;
+        Self::response_text(&response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> OpenAiClient {
+        let config = ClientConfig {
+            model: OpenAiClient::DEFAULT_MODEL.to_string(),
+            ..ClientConfig::default()
+        };
+        OpenAiClient::with_config("sk-test".to_string(), config).unwrap()
+    }
+
+    #[test]
+    fn test_client_creation_with_empty_key() {
+        assert!(matches!(
+            OpenAiClient::with_config(String::new(), ClientConfig::default()),
+            Err(EstimatorError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_request_body() {
+        let client = client().with_model("gpt-4o");
+        let body = client.request_body("Classify this task");
+        assert_eq!(body["model"], "gpt-4o");
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["messages"][0]["content"], "Classify this task");
+        assert_eq!(body["max_tokens"], 1024);
+    }
+
+    #[test]
+    fn test_base_url() {
+        assert_eq!(client().base_url(), OpenAiClient::DEFAULT_BASE_URL);
+        let client = client().with_base_url("http://localhost:8000/v1/");
+        assert_eq!(client.base_url(), "http://localhost:8000/v1");
+    }
+
+    #[test]
+    fn test_response_text() {
+        let response = json!({
+            "choices": [{ "message": { "role": "assistant", "content": "{\"ok\": true}" } }]
+        });
+        assert_eq!(OpenAiClient::response_text(&response).unwrap(), "{\"ok\": true}");
+
+        let error = json!({ "error": { "message": "rate limited" } });
+        assert!(matches!(OpenAiClient::response_text(&error), Err(EstimatorError::ApiError(_))));
+    }
+}
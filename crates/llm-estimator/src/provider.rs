@@ -0,0 +1,270 @@
+//! LLM provider abstraction
+//!
+//! Complexity estimation only needs a single-turn completion, which every
+//! backend offers. [`LlmProvider`] is implemented by the Anthropic, OpenAI
+//! and Ollama clients; [`provider_from_env`] creates the one a config names,
+//! so estimation also works without an Anthropic key, e.g. against a local
+//! Ollama server.
+
+use crate::anthropic_client::{AnthropicClient, ClientConfig};
+use crate::error::{EstimatorError, Result};
+use crate::ollama_client::OllamaClient;
+use crate::openai_client::OpenAiClient;
+use crate::prompt_builder::PromptBuilder;
+use crate::response_parser::{ComplexityEstimate, ResponseParser};
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Providers [`provider_from_env`] can create
+pub const PROVIDERS: [&str; 3] = ["anthropic", "openai", "ollama"];
+
+/// Backend answering single-turn prompts
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Provider name, one of [`PROVIDERS`]
+    fn name(&self) -> &'static str;
+
+    /// Model answering the prompts
+    fn model(&self) -> &str;
+
+    /// Send a single-turn prompt and return the raw response text
+    ///
+    /// # Errors
+    ///
+    /// Returns `EstimatorError` if the request fails or times out
+    async fn complete(&self, prompt: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl<P: LlmProvider + ?Sized> LlmProvider for Box<P> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn model(&self) -> &str {
+        (**self).model()
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        (**self).complete(prompt).await
+    }
+}
+
+/// Create the provider called `name`, reading credentials from the environment
+///
+/// `model` and `base_url` override the provider's defaults. The Anthropic
+/// provider reads `ANTHROPIC_API_KEY`, the OpenAI provider `OPENAI_API_KEY`
+/// (and `OPENAI_BASE_URL`), and the Ollama provider `OLLAMA_HOST`.
+///
+/// # Errors
+///
+/// Returns `EstimatorError::InvalidConfig` for an unknown provider or if the
+/// OpenAI key is not set, and `EstimatorError::MissingApiKey` if the
+/// Anthropic key is not set
+pub fn provider_from_env(
+    name: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+) -> Result<Box<dyn LlmProvider>> {
+    provider_with_config(name, model, base_url, &ClientConfig::default())
+}
+
+/// Create the provider called `name` like [`provider_from_env`], with the
+/// temperature, response length and timeout of `config`
+///
+/// The model is `model`, or the provider's default; `config.model` is
+/// ignored since it names an Anthropic model.
+///
+/// # Errors
+///
+/// Same as [`provider_from_env`]
+pub fn provider_with_config(
+    name: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    config: &ClientConfig,
+) -> Result<Box<dyn LlmProvider>> {
+    let config = |default_model: &str| ClientConfig {
+        model: model.unwrap_or(default_model).to_string(),
+        ..config.clone()
+    };
+    let provider: Box<dyn LlmProvider> = match name {
+        "anthropic" => {
+            let api_key =
+                std::env::var("ANTHROPIC_API_KEY").map_err(|_| EstimatorError::MissingApiKey)?;
+            let default_model = ClientConfig::default().model;
+            Box::new(AnthropicClient::with_config(api_key, config(&default_model))?)
+        }
+        "openai" => {
+            let mut client =
+                OpenAiClient::from_env_with_config(config(OpenAiClient::DEFAULT_MODEL))?;
+            if let Some(base_url) = base_url {
+                client = client.with_base_url(base_url);
+            }
+            Box::new(client)
+        }
+        "ollama" => {
+            let base_url = base_url
+                .map(str::to_string)
+                .or_else(|| std::env::var("OLLAMA_HOST").ok())
+                .unwrap_or_else(|| OllamaClient::DEFAULT_BASE_URL.to_string());
+            Box::new(OllamaClient::with_config(&base_url, config(OllamaClient::DEFAULT_MODEL))?)
+        }
+        other => {
+            return Err(EstimatorError::InvalidConfig(format!(
+                "Unknown LLM provider: {} (must be one of: {})",
+                other,
+                PROVIDERS.join(", ")
+            )))
+        }
+    };
+    info!("Using LLM provider {} with model {}", provider.name(), provider.model());
+    Ok(provider)
+}
+
+/// Estimate task complexity with `provider`
+///
+/// # Errors
+///
+/// Returns `EstimatorError` if the request fails or times out, or the
+/// response cannot be parsed
+pub(crate) async fn estimate_complexity<P: LlmProvider + ?Sized>(
+    provider: &P,
+    task_description: &str,
+    current_subtasks: usize,
+    context: Option<&str>,
+) -> Result<ComplexityEstimate> {
+    debug!(
+        "Estimating complexity with {} for task: {} (current subtasks: {})",
+        provider.name(),
+        task_description,
+        current_subtasks
+    );
+
+    // Build prompt
+    let mut builder = PromptBuilder::new()
+        .with_task(task_description)
+        .with_current_subtasks(current_subtasks);
+
+    if let Some(ctx) = context {
+        builder = builder.with_context(ctx);
+    }
+
+    let prompt = builder.build();
+    let content_text = provider.complete(&prompt).await?;
+
+    debug!("Received response: {}", content_text);
+
+    // Parse response
+    let estimate = ResponseParser::parse(&content_text)?;
+
+    info!(
+        "Estimated complexity: {} (confidence: {:.2})",
+        estimate.complexity_class, estimate.confidence
+    );
+
+    Ok(estimate)
+}
+
+/// POST `body` as JSON to `url` and return the JSON response
+///
+/// Shared by the HTTP providers; `bearer` is sent as the `Authorization`
+/// header when given.
+///
+/// # Errors
+///
+/// Returns `EstimatorError::Timeout` if the request times out, and
+/// `EstimatorError::ApiError` if it fails or the server answers with an error
+pub(crate) async fn post_json(
+    http: &reqwest::Client,
+    url: &str,
+    bearer: Option<&str>,
+    body: &serde_json::Value,
+    timeout_secs: u64,
+) -> Result<serde_json::Value> {
+    let mut request = http
+        .post(url)
+        .timeout(Duration::from_secs(timeout_secs))
+        .json(body);
+    if let Some(token) = bearer {
+        request = request.bearer_auth(token);
+    }
+    let map_err = |e: reqwest::Error| {
+        if e.is_timeout() {
+            EstimatorError::Timeout(timeout_secs)
+        } else {
+            EstimatorError::ApiError(e.to_string())
+        }
+    };
+
+    let response = request.send().await.map_err(map_err)?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(EstimatorError::ApiError(format!("{}: {}", status, text.trim())));
+    }
+    response.json().await.map_err(map_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider answering every prompt with a fixed response
+    struct FixedProvider(&'static str);
+
+    #[async_trait]
+    impl LlmProvider for FixedProvider {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn model(&self) -> &'static str {
+            "fixed-model"
+        }
+
+        async fn complete(&self, _prompt: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_with_any_provider() {
+        let provider: Box<dyn LlmProvider> = Box::new(FixedProvider(
+            r#"{"complexity_class": "Ω(n)", "reasoning": "CRUD", "recommended_subtasks": 4, "confidence": 0.9}"#,
+        ));
+        let estimate = estimate_complexity(&provider, "Add an endpoint", 0, None)
+            .await
+            .unwrap();
+        assert_eq!(estimate.complexity_class, "Ω(n)");
+        assert_eq!(estimate.recommended_subtasks, 4);
+        assert_eq!(provider.name(), "fixed");
+    }
+
+    #[test]
+    fn test_provider_with_config() {
+        let config = ClientConfig {
+            max_tokens: 4096,
+            timeout_secs: 120,
+            ..ClientConfig::default()
+        };
+        let provider =
+            provider_with_config("ollama", None, Some("http://gpu:11434"), &config).unwrap();
+        assert_eq!(provider.name(), "ollama");
+        assert_eq!(provider.model(), OllamaClient::DEFAULT_MODEL);
+    }
+
+    #[test]
+    fn test_provider_from_env() {
+        let provider =
+            provider_from_env("ollama", Some("qwen2.5"), Some("http://gpu:11434")).unwrap();
+        assert_eq!(provider.name(), "ollama");
+        assert_eq!(provider.model(), "qwen2.5");
+
+        assert!(matches!(
+            provider_from_env("gemini", None, None),
+            Err(EstimatorError::InvalidConfig(_))
+        ));
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides the `ComplexityClass` enum for categorizing tasks
 //! based on their computational complexity using Big Omega notation.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::ops::RangeInclusive;
@@ -22,7 +23,7 @@ use std::ops::RangeInclusive;
 /// assert_eq!(subtasks, 3..=5);
 /// assert_eq!(complexity.to_string(), "Ω(n)");
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum ComplexityClass {
     /// Ω(1) - Constant time complexity
     ///
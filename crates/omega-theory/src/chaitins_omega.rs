@@ -1,38 +1,158 @@
 //! Chaitin's Omega (Ω) constant implementation
 //!
-//! **PLACEHOLDER for v1.7.0**
+//! Chaitin's Omega is the probability that a randomly constructed program
+//! halts. It is algorithmically random: no program can compute more than
+//! finitely many of its bits. It can, however, be bounded from below by
+//! running ever more programs for ever more steps.
 //!
-//! This module will provide functionality related to Chaitin's Omega constant,
-//! a real number representing the probability that a randomly constructed program
-//! will halt (in algorithmic information theory).
+//! This module provides
 //!
-//! # Planned Features
+//! - [`ChaitinsOmega`]: a bounded estimator of Ω for a small tape machine,
+//!   enumerating all programs up to a length and running each for a step
+//!   budget,
+//! - [`kolmogorov_estimate`]: an upper bound on the Kolmogorov complexity of
+//!   data, its size after LZ77 compression,
+//! - [`TaskRandomness`]: the compression ratio of a task description, a
+//!   "task randomness" signal for the optimizer. Descriptions that repeat
+//!   themselves compress well; unstructured ones do not.
 //!
-//! - Approximation algorithms for Chaitin's Omega
-//! - Halting probability computation for program complexity
-//! - Integration with Kolmogorov complexity analysis
+//! # The machine
 //!
-//! # Background
+//! Programs are sequences of 3-bit tokens ending with an `END` token, so no
+//! program is a prefix of another. The other seven tokens operate on a tape
+//! of wrapping `u8` cells that extends to the right:
 //!
-//! Chaitin's Omega is a transcendental, algorithmically random number that
-//! characterizes the halting problem. It provides a theoretical foundation
-//! for measuring program complexity and randomness.
+//! | Token | Effect |
+//! |-------|--------|
+//! | `+` `-` | Increment, decrement the current cell |
+//! | `<` `>` | Move left (not past the first cell), move right |
+//! | `[` `]` | Loop while the current cell is non-zero |
+//! | `0` | Clear the current cell |
 //!
-//! # Example (Future API)
+//! A program with n instructions is 3(n + 1) bits long and is drawn with
+//! probability 2^-3(n + 1). Programs with unbalanced brackets never halt.
 //!
-//! ```ignore
-//! use omega_theory::chaitins_omega;
+//! # Examples
 //!
-//! // Approximate halting probability for a given program encoding
-//! let omega_approx = chaitins_omega::approximate_omega(bits);
 //! ```
+//! use omega_theory::chaitins_omega::{ChaitinsOmega, TaskRandomness};
+//!
+//! let estimate = ChaitinsOmega::new().with_max_length(3).estimate();
+//! assert!(estimate.lower <= estimate.upper);
+//!
+//! let randomness = TaskRandomness::measure("Add tests for the lexer and tests for the parser");
+//! assert!(randomness.ratio < 1.0);
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Probability of drawing a given token
+const TOKEN_WEIGHT: f64 = 0.125;
+
+/// Shortest repetition LZ77 encodes as a match
+const MIN_MATCH: usize = 3;
+
+/// Earlier occurrences of a prefix checked for the longest match
+const MAX_CANDIDATES: usize = 64;
+
+/// Cost of a literal byte: a flag bit and the byte
+const LITERAL_BITS: usize = 9;
+
+/// Machine instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Inc,
+    Dec,
+    Left,
+    Right,
+    Open,
+    Close,
+    Clear,
+}
+
+impl Op {
+    const ALL: [Op; 7] = [
+        Op::Inc,
+        Op::Dec,
+        Op::Left,
+        Op::Right,
+        Op::Open,
+        Op::Close,
+        Op::Clear,
+    ];
+}
+
+/// How a program run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// The program halted
+    Halted,
+    /// The program revisited a state, so it never halts
+    Diverged,
+    /// The program was still running when the step budget ran out
+    Undecided,
+}
+
+/// Bounds on Chaitin's Omega for the machine described in the module docs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OmegaEstimate {
+    /// Probability of the programs seen to halt
+    pub lower: f64,
+
+    /// One minus the probability of the programs proven never to halt
+    pub upper: f64,
+
+    /// Programs that were run or rejected for unbalanced brackets
+    pub programs: u64,
+
+    /// Programs that halted within the step budget
+    pub halted: u64,
+
+    /// Programs proven never to halt
+    pub diverged: u64,
+
+    /// Programs still running when the step budget ran out
+    pub undecided: u64,
+}
+
+impl OmegaEstimate {
+    /// Middle of the bounds
+    #[must_use]
+    pub fn midpoint(&self) -> f64 {
+        (self.lower + self.upper) / 2.0
+    }
+
+    /// Distance between the bounds
+    #[must_use]
+    pub fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
+}
 
-/// Placeholder structure for Chaitin's Omega computations
+/// Bounded estimator of Chaitin's Omega
+///
+/// Longer programs and more steps tighten the bounds, at exponential cost:
+/// there are 7ⁿ programs with n instructions.
 ///
-/// **This will be implemented in v1.7.0**
-#[allow(dead_code)]
+/// # Examples
+///
+/// ```
+/// use omega_theory::chaitins_omega::ChaitinsOmega;
+///
+/// let short = ChaitinsOmega::new().with_max_length(2).estimate();
+/// let long = ChaitinsOmega::new().with_max_length(4).estimate();
+/// assert!(long.lower >= short.lower);
+/// assert!(long.upper <= short.upper);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct ChaitinsOmega {
-    // Future fields for Omega approximation state
+    /// Longest program enumerated, in instructions
+    pub max_length: usize,
+
+    /// Steps each program may run before it is given up on
+    pub max_steps: usize,
 }
 
 impl Default for ChaitinsOmega {
@@ -42,32 +162,390 @@ impl Default for ChaitinsOmega {
 }
 
 impl ChaitinsOmega {
-    /// Placeholder constructor
-    ///
-    /// **This will be implemented in v1.7.0**
-    #[allow(dead_code)]
+    /// Estimator for programs of up to 6 instructions and 256 steps
     #[must_use]
     pub fn new() -> Self {
-        unimplemented!("ChaitinsOmega::new will be implemented in v1.7.0")
+        Self {
+            max_length: 6,
+            max_steps: 256,
+        }
+    }
+
+    /// Set the longest program enumerated
+    #[must_use]
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Set the step budget of each program
+    #[must_use]
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Enumerate and run all programs within the bounds
+    #[must_use]
+    pub fn estimate(&self) -> OmegaEstimate {
+        let mut estimate = OmegaEstimate {
+            lower: 0.0,
+            upper: 1.0,
+            programs: 0,
+            halted: 0,
+            diverged: 0,
+            undecided: 0,
+        };
+        self.explore(&mut Vec::new(), 0, 1.0, &mut estimate);
+        estimate
+    }
+
+    /// Classify all programs starting with `prefix`, which has `depth`
+    /// unclosed loops and is drawn with probability `weight`
+    fn explore(
+        &self,
+        prefix: &mut Vec<Op>,
+        depth: usize,
+        weight: f64,
+        estimate: &mut OmegaEstimate,
+    ) {
+        // The prefix followed by END
+        let program_weight = weight * TOKEN_WEIGHT;
+        estimate.programs += 1;
+        let outcome = if depth == 0 {
+            run(prefix, self.max_steps)
+        } else {
+            Outcome::Diverged
+        };
+        match outcome {
+            Outcome::Halted => {
+                estimate.halted += 1;
+                estimate.lower += program_weight;
+            }
+            Outcome::Diverged => {
+                estimate.diverged += 1;
+                estimate.upper -= program_weight;
+            }
+            Outcome::Undecided => estimate.undecided += 1,
+        }
+
+        if prefix.len() == self.max_length {
+            return;
+        }
+        for op in Op::ALL {
+            if op == Op::Close && depth == 0 {
+                // No program continuing this prefix is balanced, however long
+                estimate.upper -= program_weight;
+                continue;
+            }
+            let depth = match op {
+                Op::Open => depth + 1,
+                Op::Close => depth - 1,
+                _ => depth,
+            };
+            prefix.push(op);
+            self.explore(prefix, depth, program_weight, estimate);
+            prefix.pop();
+        }
+    }
+}
+
+/// Run a program with balanced brackets for up to `max_steps` steps
+fn run(program: &[Op], max_steps: usize) -> Outcome {
+    let mut matching = vec![0; program.len()];
+    let mut open = Vec::new();
+    for (pc, op) in program.iter().enumerate() {
+        match op {
+            Op::Open => open.push(pc),
+            Op::Close => {
+                let start = open.pop().expect("brackets are balanced");
+                matching[start] = pc;
+                matching[pc] = start;
+            }
+            _ => {}
+        }
+    }
+
+    let mut tape = vec![0_u8];
+    let mut ptr = 0;
+    let mut pc = 0;
+    // States at backward jumps; the machine is deterministic, so revisiting
+    // one means it loops forever
+    let mut seen = HashSet::new();
+    for _ in 0..max_steps {
+        let Some(op) = program.get(pc) else {
+            return Outcome::Halted;
+        };
+        match op {
+            Op::Inc => tape[ptr] = tape[ptr].wrapping_add(1),
+            Op::Dec => tape[ptr] = tape[ptr].wrapping_sub(1),
+            Op::Left => ptr = ptr.saturating_sub(1),
+            Op::Right => {
+                ptr += 1;
+                if ptr == tape.len() {
+                    tape.push(0);
+                }
+            }
+            Op::Open if tape[ptr] == 0 => pc = matching[pc],
+            Op::Close if tape[ptr] != 0 => {
+                let mut cells = tape.clone();
+                while cells.len() > ptr + 1 && cells.last() == Some(&0) {
+                    cells.pop();
+                }
+                if !seen.insert((pc, ptr, cells)) {
+                    return Outcome::Diverged;
+                }
+                pc = matching[pc];
+            }
+            Op::Clear => tape[ptr] = 0,
+            Op::Open | Op::Close => {}
+        }
+        pc += 1;
+    }
+    if pc == program.len() {
+        Outcome::Halted
+    } else {
+        Outcome::Undecided
+    }
+}
+
+/// Upper bound on the Kolmogorov complexity of `data`, in bits
+///
+/// The size of `data` after greedy LZ77 compression: a literal byte costs 9
+/// bits, a repetition of an earlier run of at least 3 bytes costs a flag
+/// bit, its distance and its Elias gamma coded length.
+///
+/// # Examples
+///
+/// ```
+/// use omega_theory::chaitins_omega::kolmogorov_estimate;
+///
+/// let repetitive = kolmogorov_estimate(&b"abc".repeat(20));
+/// let varied = kolmogorov_estimate(b"the quick brown fox jumps over the lazy dog, twice");
+/// assert!(repetitive < varied);
+/// ```
+#[must_use]
+pub fn kolmogorov_estimate(data: &[u8]) -> usize {
+    let key = |i: usize| [data[i], data[i + 1], data[i + 2]];
+
+    let mut bits = 0;
+    let mut earlier: HashMap<[u8; MIN_MATCH], Vec<usize>> = HashMap::new();
+    let mut i = 0;
+    while i < data.len() {
+        let mut best = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            if let Some(starts) = earlier.get(&key(i)) {
+                for &start in starts.iter().rev().take(MAX_CANDIDATES) {
+                    let len = data[start..]
+                        .iter()
+                        .zip(&data[i..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    if len > best.0 {
+                        best = (len, start);
+                    }
+                }
+            }
+        }
+
+        let step = if best.0 >= MIN_MATCH {
+            // The distance is one of 1..=i
+            let distance_bits = (usize::BITS - (i - 1).leading_zeros()) as usize;
+            bits += 1 + distance_bits + gamma_bits(best.0 - MIN_MATCH + 1);
+            best.0
+        } else {
+            bits += LITERAL_BITS;
+            1
+        };
+        for j in i..i + step {
+            if j + MIN_MATCH <= data.len() {
+                earlier.entry(key(j)).or_default().push(j);
+            }
+        }
+        i += step;
+    }
+    bits
+}
+
+/// Length of the Elias gamma code of `n` ≥ 1
+fn gamma_bits(n: usize) -> usize {
+    2 * (usize::BITS - 1 - n.leading_zeros()) as usize + 1
+}
+
+/// Compressed size of `data` relative to its size, from 0.0 to 1.0
+///
+/// 1.0 means `data` did not compress at all, which is always the case for
+/// very short data. Empty data has ratio 0.0.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn compression_ratio(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    (kolmogorov_estimate(data) as f64 / (data.len() * 8) as f64).min(1.0)
+}
+
+/// Randomness of a task description, for the optimizer
+///
+/// Case and whitespace are normalized before compressing, so formatting
+/// does not count as information. Descriptions of a few words are barely
+/// compressible; compare ratios of descriptions of similar length.
+///
+/// # Examples
+///
+/// ```
+/// use omega_theory::chaitins_omega::TaskRandomness;
+///
+/// let repetitive = TaskRandomness::measure(
+///     "Rename the field in the user model, the user view and the user API",
+/// );
+/// let varied = TaskRandomness::measure(
+///     "Migrate auth to OAuth, fix flaky CI, bump deps and document webhooks",
+/// );
+/// assert!(repetitive.ratio < varied.ratio);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskRandomness {
+    /// Length of the normalized description, in bytes
+    pub length: usize,
+
+    /// [`kolmogorov_estimate`] of the normalized description
+    pub complexity_bits: usize,
+
+    /// [`compression_ratio`] of the normalized description
+    pub ratio: f64,
+}
+
+impl TaskRandomness {
+    /// Measure the randomness of `description`
+    #[must_use]
+    pub fn measure(description: &str) -> Self {
+        let normalized = description
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let data = normalized.as_bytes();
+        Self {
+            length: data.len(),
+            complexity_bits: kolmogorov_estimate(data),
+            ratio: compression_ratio(data),
+        }
     }
 
-    /// Placeholder for Omega approximation
-    ///
-    /// **This will be implemented in v1.7.0**
-    #[allow(dead_code)]
+    /// How much of the description repeats itself, `1.0 - ratio`
     #[must_use]
-    pub fn approximate(&self, _precision: usize) -> f64 {
-        unimplemented!("ChaitinsOmega::approximate will be implemented in v1.7.0")
+    pub fn redundancy(&self) -> f64 {
+        1.0 - self.ratio
     }
 }
 
+/// Randomness of `description`, from 0.0 (fully repetitive) to 1.0
+///
+/// Shorthand for [`TaskRandomness::measure`]`(description).ratio`.
+#[must_use]
+pub fn task_randomness(description: &str) -> f64 {
+    TaskRandomness::measure(description).ratio
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn parse(source: &str) -> Vec<Op> {
+        source
+            .chars()
+            .map(|c| match c {
+                '+' => Op::Inc,
+                '-' => Op::Dec,
+                '<' => Op::Left,
+                '>' => Op::Right,
+                '[' => Op::Open,
+                ']' => Op::Close,
+                '0' => Op::Clear,
+                _ => panic!("unknown instruction {c}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_outcomes() {
+        assert_eq!(run(&parse(""), 0), Outcome::Halted);
+        assert_eq!(run(&parse("+>+<-"), 10), Outcome::Halted);
+        // Skipped loop
+        assert_eq!(run(&parse("[+]"), 10), Outcome::Halted);
+        // Same state on every iteration
+        assert_eq!(run(&parse("+[]"), 10), Outcome::Diverged);
+        assert_eq!(run(&parse("+[<]"), 10), Outcome::Diverged);
+        // Never repeats, the tape keeps growing
+        assert_eq!(run(&parse("+[>+]"), 1_000), Outcome::Undecided);
+    }
+
+    #[test]
+    fn test_step_budget() {
+        // Counts down from 255 in 512 steps
+        let program = parse("-[-]");
+        assert_eq!(run(&program, 100), Outcome::Undecided);
+        assert_eq!(run(&program, 1_000), Outcome::Halted);
+    }
+
     #[test]
-    #[should_panic(expected = "will be implemented in v1.7.0")]
-    fn test_chaitins_omega_placeholder() {
-        let _ = ChaitinsOmega::new();
+    #[allow(clippy::float_cmp)]
+    fn test_exact_bounds_for_short_programs() {
+        let estimate = ChaitinsOmega::new().with_max_length(0).estimate();
+        assert_eq!(estimate.lower, 0.125);
+        assert_eq!(estimate.upper, 1.0);
+        assert_eq!(estimate.programs, 1);
+
+        // "+", "-", "<", ">" and "0" halt, "[" never does and no program
+        // starting with "]" does
+        let estimate = ChaitinsOmega::new().with_max_length(1).estimate();
+        assert_eq!(estimate.lower, 0.125 + 5.0 / 64.0);
+        assert_eq!(estimate.upper, 1.0 - 0.125 - 1.0 / 64.0);
+        assert_eq!(estimate.halted, 6);
+        assert_eq!(estimate.diverged, 1);
+    }
+
+    #[test]
+    fn test_bounds_tighten() {
+        let mut previous = ChaitinsOmega::new().with_max_length(1).estimate();
+        for max_length in 2..=4 {
+            let estimate = ChaitinsOmega::new()
+                .with_max_length(max_length)
+                .with_max_steps(64)
+                .estimate();
+            assert!(estimate.lower >= previous.lower);
+            assert!(estimate.upper <= previous.upper);
+            assert!(estimate.lower <= estimate.upper);
+            assert_eq!(estimate.programs, estimate.halted + estimate.diverged + estimate.undecided);
+            previous = estimate;
+        }
+        assert!(previous.width() < 0.5);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_kolmogorov_estimate() {
+        assert_eq!(kolmogorov_estimate(b""), 0);
+        assert_eq!(kolmogorov_estimate(b"ab"), 18);
+        // 3 literals, then one match of 27 bytes at distance 3
+        assert_eq!(kolmogorov_estimate(&b"abc".repeat(10)), 27 + 1 + 2 + 9);
+
+        assert_eq!(compression_ratio(b""), 0.0);
+        assert_eq!(compression_ratio(b"xyz"), 1.0);
+        assert!(compression_ratio(&b"abc".repeat(100)) < 0.1);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_task_randomness() {
+        let spaced = TaskRandomness::measure("Fix   the\tLogin  bug");
+        assert_eq!(spaced, TaskRandomness::measure("fix the login bug"));
+        assert_eq!(spaced.length, 17);
+
+        let repetitive = "update tests, update docs, update tests, update docs";
+        assert!(task_randomness(repetitive) < 0.5);
+        assert!(TaskRandomness::measure(repetitive).redundancy() > 0.5);
+        assert_eq!(task_randomness(""), 0.0);
     }
 }
@@ -4,8 +4,8 @@
 //! mathematical concepts related to "Omega" (Ω):
 //!
 //! - **Big Omega (Ω) Notation**: Asymptotic lower bounds for algorithm complexity
-//! - **Prime Omega (Ω)**: Number-theoretic function counting prime factors (v1.7.0+)
-//! - **Chaitin's Omega (Ω)**: Algorithmic information theory and halting probability (v1.7.0+)
+//! - **Prime Omega (Ω)**: Number-theoretic functions counting prime factors
+//! - **Chaitin's Omega (Ω)**: Halting probability bounds and compression-based task randomness
 //!
 //! # Version 1.6.0 Features
 //!
@@ -37,8 +37,6 @@
 //! # Future Roadmap (v1.7.0+)
 //!
 //! - LLM-based task description analysis for automatic complexity classification
-//! - Prime Omega function for number-theoretic complexity measures
-//! - Chaitin's Omega for algorithmic randomness and halting analysis
 //! - Integration with AIT42 multi-agent system for adaptive task decomposition
 //!
 //! # Architecture
@@ -46,8 +44,8 @@
 //! ```text
 //! omega-theory/
 //! ├── big_omega.rs          ✅ v1.6.0 (ComplexityClass enum)
-//! ├── prime_omega.rs        🔜 v1.7.0 (Prime factorization)
-//! └── chaitins_omega.rs     🔜 v1.7.0 (Algorithmic information)
+//! ├── prime_omega.rs        ✅ v1.7.0 (Prime factorization, Ω(n) and ω(n))
+//! └── chaitins_omega.rs     ✅ v1.7.0 (Halting probability, Kolmogorov complexity)
 //! ```
 
 #![warn(missing_docs)]
@@ -61,6 +59,8 @@ pub mod prime_omega;
 
 // Re-export primary types for convenience
 pub use big_omega::ComplexityClass;
+pub use chaitins_omega::{ChaitinsOmega, OmegaEstimate, TaskRandomness};
+pub use prime_omega::{PrimeOmega, PrimeOmegaProfile};
 
 #[cfg(test)]
 mod tests {
@@ -76,6 +76,7 @@ mod tests {
     fn test_module_structure() {
         // Verify all modules compile
         let _ = big_omega::ComplexityClass::Constant;
-        // prime_omega and chaitins_omega are placeholders (will panic if called)
+        assert_eq!(prime_omega::omega(12), 3);
+        assert_eq!(chaitins_omega::kolmogorov_estimate(b""), 0);
     }
 }
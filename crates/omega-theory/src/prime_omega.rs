@@ -1,41 +1,499 @@
 //! Prime Omega (Ω) function implementation
 //!
-//! **PLACEHOLDER for v1.7.0**
+//! This module implements the number-theoretic prime Omega functions:
 //!
-//! This module will implement the number-theoretic prime Omega function Ω(n),
-//! which counts the number of prime factors of n (with multiplicity).
+//! - Ω(n) counts the prime factors of n with multiplicity
+//! - ω(n) counts the distinct prime factors of n
 //!
-//! # Planned Features
+//! Numbers are factorized with trial division for small factors, the
+//! Miller-Rabin primality test and Brent's variant of Pollard's rho for the
+//! rest, so even 64-bit semiprimes factorize in microseconds.
 //!
-//! - Prime factorization algorithms
-//! - Ω(n) computation for arbitrary integers
-//! - Integration with Chaitin's Omega for algorithmic information theory
+//! [`PrimeOmegaProfile`] summarizes the factorization of a workload size
+//! (files to touch, endpoints to add, ...) for task decomposition: a number
+//! with many small prime factors splits evenly into many subtask counts, a
+//! large prime does not split evenly at all.
 //!
-//! # Example (Future API)
+//! # Examples
 //!
-//! ```ignore
-//! use omega_theory::prime_omega;
+//! ```
+//! use omega_theory::prime_omega::{self, PrimeOmega};
 //!
 //! // Ω(12) = Ω(2² × 3) = 3 (two 2's and one 3)
 //! assert_eq!(prime_omega::omega(12), 3);
+//! assert_eq!(prime_omega::omega_distinct(12), 2);
+//!
+//! assert_eq!(360_u64.factorize(), vec![(2, 3), (3, 2), (5, 1)]);
 //! ```
 
-/// Placeholder function for prime Omega computation
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Factors below this bound are found by trial division
+const TRIAL_DIVISION_BOUND: u128 = 1_000;
+
+/// Miller-Rabin bases, deterministic for n < 3.3 × 10²⁴
+///
+/// Larger numbers pass with probability below 4⁻¹⁶ if composite.
+const WITNESSES: [u128; 16] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+/// Prime factorization and prime Omega functions
+///
+/// The factorization of 0 and 1 is empty, so Ω and ω are 0 for both.
+///
+/// # Examples
+///
+/// ```
+/// use omega_theory::prime_omega::PrimeOmega;
+///
+/// assert_eq!(1024_u64.omega(), 10);
+/// assert_eq!(1024_u64.omega_distinct(), 1);
+/// assert!(97_u128.is_prime());
+/// ```
+pub trait PrimeOmega: Copy {
+    /// Prime factors with their exponents, in ascending order
+    fn factorize(self) -> Vec<(Self, u32)>;
+
+    /// Whether the number is prime
+    fn is_prime(self) -> bool;
+
+    /// Ω(n): number of prime factors counted with multiplicity
+    fn omega(self) -> usize {
+        self.factorize().iter().map(|&(_, exp)| exp as usize).sum()
+    }
+
+    /// ω(n): number of distinct prime factors
+    fn omega_distinct(self) -> usize {
+        self.factorize().len()
+    }
+}
+
+impl PrimeOmega for u128 {
+    fn factorize(self) -> Vec<(Self, u32)> {
+        let mut primes = Vec::new();
+        factor_into(self, &mut primes);
+        primes.sort_unstable();
+
+        let mut factors: Vec<(u128, u32)> = Vec::new();
+        for p in primes {
+            match factors.last_mut() {
+                Some((last, exp)) if *last == p => *exp += 1,
+                _ => factors.push((p, 1)),
+            }
+        }
+        factors
+    }
+
+    fn is_prime(self) -> bool {
+        is_prime(self)
+    }
+}
+
+impl PrimeOmega for u64 {
+    #[allow(clippy::cast_possible_truncation)] // Factors of a u64 fit in a u64
+    fn factorize(self) -> Vec<(Self, u32)> {
+        u128::from(self)
+            .factorize()
+            .into_iter()
+            .map(|(p, exp)| (p as u64, exp))
+            .collect()
+    }
+
+    fn is_prime(self) -> bool {
+        is_prime(u128::from(self))
+    }
+}
+
+/// Ω(n): number of prime factors of `n` counted with multiplicity
 ///
-/// **This will be implemented in v1.7.0**
-#[allow(dead_code)]
+/// # Examples
+///
+/// ```
+/// use omega_theory::prime_omega;
+///
+/// assert_eq!(prime_omega::omega(1), 0);
+/// assert_eq!(prime_omega::omega(12), 3);
+/// ```
+#[must_use]
+pub fn omega(n: u64) -> usize {
+    n.omega()
+}
+
+/// ω(n): number of distinct prime factors of `n`
+///
+/// # Examples
+///
+/// ```
+/// use omega_theory::prime_omega;
+///
+/// assert_eq!(prime_omega::omega_distinct(12), 2);
+/// ```
 #[must_use]
-pub fn omega(_n: u64) -> usize {
-    unimplemented!("prime_omega::omega will be implemented in v1.7.0")
+pub fn omega_distinct(n: u64) -> usize {
+    n.omega_distinct()
+}
+
+/// Prime factor profile of a workload size, for task decomposition
+///
+/// # Examples
+///
+/// ```
+/// use omega_theory::prime_omega::PrimeOmegaProfile;
+///
+/// // 12 files split evenly into 2, 3, 4 or 6 subtasks
+/// let profile = PrimeOmegaProfile::new(12);
+/// assert_eq!(profile.even_splits(2..=6), vec![2, 3, 4, 6]);
+///
+/// // 13 files do not split evenly
+/// assert!(PrimeOmegaProfile::new(13).even_splits(2..=6).is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PrimeOmegaProfile {
+    /// The profiled number
+    pub n: u64,
+
+    /// Prime factors with their exponents, in ascending order
+    pub factors: Vec<(u64, u32)>,
+
+    /// Ω(n): prime factors counted with multiplicity
+    pub omega: usize,
+
+    /// ω(n): distinct prime factors
+    pub omega_distinct: usize,
+}
+
+impl PrimeOmegaProfile {
+    /// Factorize `n` and profile it
+    #[must_use]
+    pub fn new(n: u64) -> Self {
+        let factors = n.factorize();
+        Self {
+            n,
+            omega: factors.iter().map(|&(_, exp)| exp as usize).sum(),
+            omega_distinct: factors.len(),
+            factors,
+        }
+    }
+
+    /// Whether `n` is prime
+    #[must_use]
+    pub fn is_prime(&self) -> bool {
+        self.omega == 1
+    }
+
+    /// Whether no prime divides `n` more than once
+    #[must_use]
+    pub fn is_squarefree(&self) -> bool {
+        self.omega == self.omega_distinct
+    }
+
+    /// Largest prime factor, `None` for 0 and 1
+    #[must_use]
+    pub fn largest_factor(&self) -> Option<u64> {
+        self.factors.last().map(|&(p, _)| p)
+    }
+
+    /// All divisors of `n` in ascending order, empty for 0
+    #[must_use]
+    pub fn divisors(&self) -> Vec<u64> {
+        if self.n == 0 {
+            return Vec::new();
+        }
+        let mut divisors = vec![1_u64];
+        for &(p, exp) in &self.factors {
+            let mut next = Vec::with_capacity(divisors.len() * (exp as usize + 1));
+            for &d in &divisors {
+                let mut power = d;
+                next.push(power);
+                for _ in 0..exp {
+                    power *= p;
+                    next.push(power);
+                }
+            }
+            divisors = next;
+        }
+        divisors.sort_unstable();
+        divisors
+    }
+
+    /// Subtask counts in `range` that split `n` into equal parts
+    #[must_use]
+    pub fn even_splits(&self, range: RangeInclusive<usize>) -> Vec<usize> {
+        self.divisors()
+            .into_iter()
+            .filter_map(|d| usize::try_from(d).ok())
+            .filter(|d| range.contains(d))
+            .collect()
+    }
+
+    /// How finely `n` decomposes, from 0.0 (0, 1 or a prime) to 1.0 (a power
+    /// of two)
+    ///
+    /// Ω(n) relative to the most prime factors a number of this size can
+    /// have, log₂(n).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn smoothness(&self) -> f64 {
+        if self.omega <= 1 {
+            return 0.0;
+        }
+        (self.omega as f64 / (self.n as f64).log2()).min(1.0)
+    }
+}
+
+/// Push the prime factors of `n` to `primes`, with multiplicity
+fn factor_into(mut n: u128, primes: &mut Vec<u128>) {
+    if n < 2 {
+        return;
+    }
+    let mut d = 2;
+    while d < TRIAL_DIVISION_BOUND && d * d <= n {
+        while n % d == 0 {
+            primes.push(d);
+            n /= d;
+        }
+        d += if d == 2 { 1 } else { 2 };
+    }
+    split(n, primes);
+}
+
+/// Push the prime factors of `n`, which has no factors below the trial
+/// division bound, to `primes`
+fn split(n: u128, primes: &mut Vec<u128>) {
+    if n < 2 {
+        return;
+    }
+    if is_prime(n) {
+        primes.push(n);
+        return;
+    }
+    let d = pollard_brent(n);
+    split(d, primes);
+    split(n / d, primes);
+}
+
+/// Miller-Rabin primality test
+fn is_prime(n: u128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in WITNESSES {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    WITNESSES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// A non-trivial factor of the odd composite `n`, with Brent's variant of
+/// Pollard's rho
+#[allow(clippy::many_single_char_names)] // Named as in Brent's paper
+fn pollard_brent(n: u128) -> u128 {
+    /// Steps between gcd computations
+    const BATCH: u64 = 128;
+
+    for c in 1.. {
+        let f = |x: u128| add_mod(mul_mod(x, x, n), c, n);
+        let (mut x, mut y, mut ys) = (2, 2, 2);
+        let (mut g, mut q, mut r) = (1, 1, 1_u64);
+
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+
+        // The batch overshot; retrace it one step at a time
+        if g == n {
+            loop {
+                ys = f(ys);
+                g = gcd(x.abs_diff(ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!("some polynomial finds a factor of a composite")
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// (a + b) mod m, for a, b < m
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// (a × b) mod m, for a, b < m
+fn mul_mod(mut a: u128, mut b: u128, m: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    // Double and add, for moduli beyond 64 bits
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+/// (base ^ exp) mod m
+fn pow_mod(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut result = 1;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Ω by trial division, for checking small numbers
+    fn naive_omega(mut n: u64) -> usize {
+        let mut count = 0;
+        let mut d = 2;
+        while d * d <= n {
+            while n % d == 0 {
+                count += 1;
+                n /= d;
+            }
+            d += 1;
+        }
+        count + usize::from(n > 1)
+    }
+
+    #[test]
+    fn test_small_numbers() {
+        assert_eq!(omega(0), 0);
+        assert_eq!(omega(1), 0);
+        assert_eq!(omega(2), 1);
+        assert_eq!(omega(12), 3);
+        assert_eq!(omega_distinct(12), 2);
+        assert_eq!(omega(1024), 10);
+        assert_eq!(omega_distinct(30), 3);
+
+        for n in 0..5_000 {
+            assert_eq!(omega(n), naive_omega(n), "Ω({n})");
+        }
+    }
+
+    #[test]
+    fn test_primality() {
+        assert!(!0_u64.is_prime());
+        assert!(!1_u64.is_prime());
+        assert!(2_u64.is_prime());
+        assert!(1_000_000_007_u64.is_prime());
+        assert!(18_446_744_073_709_551_557_u64.is_prime()); // Largest 64-bit prime
+        assert!(!3_215_031_751_u64.is_prime()); // Strong pseudoprime to bases 2, 3, 5, 7
+        assert!(u128::from(u64::MAX).saturating_add(14).is_prime()); // 2^64 + 13
+    }
+
+    #[test]
+    fn test_large_factorizations() {
+        // Semiprime of two 32-bit primes
+        assert_eq!(
+            18_446_743_979_220_271_189_u64.factorize(),
+            vec![(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+        assert_eq!(
+            u64::MAX.factorize(),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65_537, 1),
+                (6_700_417, 1)
+            ]
+        );
+
+        // 2^64 + 1 = 274177 × 67280421310721
+        let n = u128::from(u64::MAX) + 2;
+        assert_eq!(n.factorize(), vec![(274_177, 1), (67_280_421_310_721, 1)]);
+        assert_eq!((n * 9).omega(), 4);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_profile() {
+        let profile = PrimeOmegaProfile::new(360);
+        assert_eq!(profile.factors, vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(profile.omega, 6);
+        assert_eq!(profile.omega_distinct, 3);
+        assert!(!profile.is_prime());
+        assert!(!profile.is_squarefree());
+        assert_eq!(profile.largest_factor(), Some(5));
+        assert_eq!(profile.divisors().len(), 24);
+        assert_eq!(profile.even_splits(3..=8), vec![3, 4, 5, 6, 8]);
+
+        let prime = PrimeOmegaProfile::new(97);
+        assert!(prime.is_prime());
+        assert!(prime.is_squarefree());
+        assert_eq!(prime.divisors(), vec![1, 97]);
+        assert_eq!(prime.smoothness(), 0.0);
+
+        assert_eq!(PrimeOmegaProfile::new(64).smoothness(), 1.0);
+        assert_eq!(PrimeOmegaProfile::new(0).divisors(), Vec::<u64>::new());
+        assert_eq!(PrimeOmegaProfile::new(1).largest_factor(), None);
+    }
+
     #[test]
-    #[should_panic(expected = "will be implemented in v1.7.0")]
-    fn test_omega_placeholder() {
-        let _ = omega(12);
+    fn test_profile_serialization() {
+        let profile = PrimeOmegaProfile::new(12);
+        let json = serde_json::to_string(&profile).unwrap();
+        let restored: PrimeOmegaProfile = serde_json::from_str(&json).unwrap();
+        assert_eq!(profile, restored);
     }
 }
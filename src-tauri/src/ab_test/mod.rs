@@ -42,6 +42,7 @@
 
 use anyhow::Result;
 use omega_theory::ComplexityClass;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{info, warn};
@@ -101,7 +102,7 @@ pub struct OptimizationResult {
 }
 
 /// Result of testing a single strategy on all test cases
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StrategyMetrics {
     /// Strategy name
     pub name: String,
@@ -129,7 +130,7 @@ pub struct StrategyMetrics {
 }
 
 /// Metrics for a specific complexity class
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComplexityMetrics {
     /// Complexity class name (e.g., "Ω(n)")
     pub complexity: String,
@@ -145,7 +146,7 @@ pub struct ComplexityMetrics {
 }
 
 /// Result of running a single test case
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestCaseResult {
     /// Test case ID
     pub test_case_id: String,
@@ -179,7 +180,7 @@ pub struct TestCaseResult {
 }
 
 /// Complete A/B test result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ABTestResult {
     /// Metrics for Strategy A (v1.5.0)
     pub strategy_a_metrics: StrategyMetrics,
@@ -195,7 +196,7 @@ pub struct ABTestResult {
 }
 
 /// Test execution metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestMetadata {
     /// Number of test cases
     pub total_test_cases: usize,
@@ -24,10 +24,11 @@
 //! 95% confidence interval for the mean difference
 
 use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Statistical comparison between two strategies
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComparisonStats {
     /// Accuracy difference (B - A)
     pub accuracy_diff: f64,
@@ -0,0 +1,545 @@
+//! Agent Output Streams
+//!
+//! Output of agent executions is delivered to the frontend as `agent-output`
+//! events. Each execution's output is split into chunks numbered from 1, and
+//! the most recent chunks are kept so that a frontend that reloads or misses
+//! events can catch up from the last chunk it has seen.
+//!
+//! Listeners acknowledge the chunks they have handled. At most
+//! [`MAX_UNACKED_CHUNKS`] chunks of an execution are in flight; output that
+//! arrives meanwhile is merged into the pending chunks and sent once the
+//! frontend catches up, so a slow listener gets fewer, larger events.
+//!
+//! Agents running in tmux are followed through the log their pane is piped
+//! to, until the session ends; the log is rotated as it grows and compressed
+//! once the session ended (see [`crate::instance_log`]). Published output is
+//! also matched against the alert rules (see [`crate::output_alerts`]).
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::instance_log::{self, LogTail};
+use crate::output_alerts;
+use crate::state::AppState;
+use crate::sync::lock;
+use crate::tools::ToolRegistry;
+
+/// Event emitted for each chunk of agent output
+pub const AGENT_OUTPUT_EVENT: &str = "agent-output";
+
+/// Chunks of an execution emitted but not yet acknowledged
+const MAX_UNACKED_CHUNKS: u64 = 16;
+
+/// Largest chunk pending output is merged into
+const MAX_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Output kept per execution for listeners that reconnect
+const MAX_RETAINED_BYTES: usize = 1024 * 1024;
+
+/// Finished executions whose output is kept
+const MAX_FINISHED_STREAMS: usize = 32;
+
+/// How often a tmux session's log is checked for new output
+const TAIL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Status of an agent execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl OutputStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Consecutive output of an execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputChunk {
+    pub execution_id: String,
+    /// Position in the execution's output, starting at 1
+    pub seq: u64,
+    pub output: String,
+    /// Status of the execution after this chunk
+    pub status: OutputStatus,
+}
+
+/// Output of an execution after a given chunk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputReplay {
+    pub execution_id: String,
+    pub agent_name: String,
+    pub chunks: Vec<OutputChunk>,
+    /// Whether some of the requested output is no longer kept
+    pub truncated: bool,
+    pub status: OutputStatus,
+}
+
+struct Stream {
+    agent_name: String,
+    chunks: VecDeque<OutputChunk>,
+    retained_bytes: usize,
+    next_seq: u64,
+    /// Last chunk emitted
+    emitted: u64,
+    /// Last chunk acknowledged
+    acked: u64,
+    status: OutputStatus,
+    finished_at: Option<Instant>,
+}
+
+impl Stream {
+    fn new(agent_name: &str) -> Self {
+        Self {
+            agent_name: agent_name.to_string(),
+            chunks: VecDeque::new(),
+            retained_bytes: 0,
+            next_seq: 1,
+            emitted: 0,
+            acked: 0,
+            status: OutputStatus::Running,
+            finished_at: None,
+        }
+    }
+
+    fn push(&mut self, execution_id: &str, output: &str, status: OutputStatus) {
+        if self.status != OutputStatus::Running {
+            return;
+        }
+        if output.is_empty() && status == OutputStatus::Running {
+            return;
+        }
+
+        let emitted = self.emitted;
+        match self.chunks.back_mut() {
+            // Nobody has seen the last chunk yet: grow it instead
+            Some(last)
+                if last.seq > emitted && last.output.len() + output.len() <= MAX_CHUNK_BYTES =>
+            {
+                last.output.push_str(output);
+                last.status = status;
+            }
+            _ => {
+                self.chunks.push_back(OutputChunk {
+                    execution_id: execution_id.to_string(),
+                    seq: self.next_seq,
+                    output: output.to_string(),
+                    status,
+                });
+                self.next_seq += 1;
+            }
+        }
+
+        self.retained_bytes += output.len();
+        while self.retained_bytes > MAX_RETAINED_BYTES && self.chunks.len() > 1 {
+            if let Some(dropped) = self.chunks.pop_front() {
+                self.retained_bytes -= dropped.output.len();
+            }
+        }
+
+        self.status = status;
+        if status != OutputStatus::Running {
+            self.finished_at = Some(Instant::now());
+        }
+    }
+
+    /// Chunks that can be emitted without exceeding the unacknowledged limit
+    fn ready(&mut self) -> Vec<OutputChunk> {
+        let limit = self.acked + MAX_UNACKED_CHUNKS;
+        let ready: Vec<OutputChunk> = self
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.seq > self.emitted && chunk.seq <= limit)
+            .cloned()
+            .collect();
+        if let Some(last) = ready.last() {
+            self.emitted = last.seq;
+        }
+        ready
+    }
+
+    fn replay(&self, execution_id: &str, after_seq: u64) -> OutputReplay {
+        let chunks: Vec<OutputChunk> = self
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.seq > after_seq)
+            .cloned()
+            .collect();
+        let first_kept = self.chunks.front().map_or(self.next_seq, |chunk| chunk.seq);
+        OutputReplay {
+            execution_id: execution_id.to_string(),
+            agent_name: self.agent_name.clone(),
+            chunks,
+            truncated: first_kept > after_seq + 1,
+            status: self.status,
+        }
+    }
+}
+
+/// Output of the agent executions started in this session
+#[derive(Default)]
+pub struct AgentOutputStreams {
+    streams: Mutex<HashMap<String, Stream>>,
+}
+
+impl AgentOutputStreams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start collecting the output of `execution_id`
+    pub fn start(&self, execution_id: &str, agent_name: &str) -> Result<(), String> {
+        let mut streams = lock(&self.streams);
+        if streams.contains_key(execution_id) {
+            return Err(format!("Output of {} is already being streamed", execution_id));
+        }
+        streams.insert(execution_id.to_string(), Stream::new(agent_name));
+
+        // Forget the oldest finished executions
+        let mut finished: Vec<(Instant, String)> = streams
+            .iter()
+            .filter_map(|(id, stream)| stream.finished_at.map(|at| (at, id.clone())))
+            .collect();
+        if finished.len() > MAX_FINISHED_STREAMS {
+            finished.sort();
+            for (_, id) in &finished[..finished.len() - MAX_FINISHED_STREAMS] {
+                streams.remove(id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the output of `execution_id` is collected
+    pub fn contains(&self, execution_id: &str) -> bool {
+        lock(&self.streams).contains_key(execution_id)
+    }
+
+    /// Append output; returns the chunks to emit now
+    ///
+    /// Output after the execution finished is ignored.
+    pub fn push(&self, execution_id: &str, output: &str, status: OutputStatus) -> Vec<OutputChunk> {
+        match lock(&self.streams).get_mut(execution_id) {
+            Some(stream) => {
+                stream.push(execution_id, output, status);
+                stream.ready()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Record that the listener handled the chunks up to `seq`; returns
+    /// the chunks that were held back and can be emitted now
+    pub fn ack(&self, execution_id: &str, seq: u64) -> Result<Vec<OutputChunk>, String> {
+        let mut streams = lock(&self.streams);
+        let stream = streams
+            .get_mut(execution_id)
+            .ok_or_else(|| format!("No output for execution {}", execution_id))?;
+        stream.acked = stream.acked.max(seq.min(stream.emitted));
+        Ok(stream.ready())
+    }
+
+    /// Output of `execution_id` after chunk `after_seq` (all kept output if `None`)
+    pub fn replay(&self, execution_id: &str, after_seq: Option<u64>) -> Option<OutputReplay> {
+        lock(&self.streams)
+            .get(execution_id)
+            .map(|stream| stream.replay(execution_id, after_seq.unwrap_or(0)))
+    }
+
+    /// Like [`Self::replay`], for a listener that (re)connects: the replayed
+    /// chunks count as delivered, and later output is emitted again
+    pub fn subscribe(&self, execution_id: &str, after_seq: Option<u64>) -> Option<OutputReplay> {
+        let mut streams = lock(&self.streams);
+        let stream = streams.get_mut(execution_id)?;
+        let replay = stream.replay(execution_id, after_seq.unwrap_or(0));
+        stream.emitted = stream.next_seq - 1;
+        stream.acked = stream.emitted;
+        Some(replay)
+    }
+}
+
+/// Emit chunks returned by [`AgentOutputStreams`]
+pub fn emit_chunks(app: &tauri::AppHandle, chunks: Vec<OutputChunk>) {
+    let state = app.state::<AppState>();
+    for chunk in chunks {
+        if let Err(e) = app.emit_all(AGENT_OUTPUT_EVENT, &chunk) {
+            tracing::warn!("Failed to emit agent output: {}", e);
+        }
+        state.remote.publish(AGENT_OUTPUT_EVENT, &chunk);
+    }
+}
+
+/// Record and emit output of `execution_id`, raising the alerts it triggers
+pub fn publish(app: &tauri::AppHandle, execution_id: &str, output: &str, status: OutputStatus) {
+    let chunks = app
+        .state::<AppState>()
+        .agent_output
+        .push(execution_id, output, status);
+    emit_chunks(app, chunks);
+    output_alerts::check(app, execution_id, None, output, status);
+}
+
+/// Log a tmux session's pane is piped to while it is followed
+pub fn session_log_path(session_id: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}.output.log", session_id))
+}
+
+/// Stream the output of the agent running in tmux session `session_id`
+///
+/// The session's pane is piped to [`session_log_path`] unless it already is
+/// piped somewhere; the log is read from its start, so following a session
+/// again (after a restart of the app) replays its earlier output.
+pub fn follow_tmux_session(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    agent_name: &str,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    state.agent_output.start(session_id, agent_name)?;
+
+    let log_path = session_log_path(session_id);
+    let piped = state
+        .tools
+        .command("tmux")
+        .arg("pipe-pane")
+        .arg("-t")
+        .arg(session_id)
+        .arg("-o")
+        .arg(format!("cat >> {}", log_path.display()))
+        .output()
+        .map_err(|e| e.to_string())
+        .and_then(|output| {
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+            }
+        });
+    if let Err(e) = piped {
+        let message = format!("Failed to capture output of {}: {}", session_id, e);
+        publish(app, session_id, &message, OutputStatus::Failed);
+        return Err(message);
+    }
+
+    let tools = state.tools.clone();
+    let (task_app, execution_id) = (app.clone(), session_id.to_string());
+    tauri::async_runtime::spawn(async move {
+        tail_tmux_session(&tools, &execution_id, &log_path, TAIL_INTERVAL, |output, status| {
+            publish(&task_app, &execution_id, &output, status)
+        })
+        .await;
+        tracing::info!("Agent session {} has ended", execution_id);
+        if let Err(e) = instance_log::compress(&log_path).await {
+            tracing::warn!("Failed to compress {}: {}", log_path.display(), e);
+        }
+    });
+    Ok(())
+}
+
+/// Follow the log `session_id` is piped to until the session ends
+///
+/// `on_output` receives each batch of new output while the session runs,
+/// then the rest of the log with [`OutputStatus::Completed`].
+async fn tail_tmux_session(
+    tools: &ToolRegistry,
+    session_id: &str,
+    log_path: &Path,
+    poll_interval: Duration,
+    mut on_output: impl FnMut(String, OutputStatus),
+) {
+    let mut tail = LogTail::new(log_path);
+    loop {
+        let alive = tools
+            .command("tmux")
+            .arg("has-session")
+            .arg("-t")
+            .arg(session_id)
+            .output()
+            .is_ok_and(|output| output.status.success());
+
+        let output = tail.read().await;
+        if !alive {
+            on_output(output, OutputStatus::Completed);
+            return;
+        }
+        if !output.is_empty() {
+            on_output(output, OutputStatus::Running);
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{FakeScenario, FakeTools, SessionScript};
+    use std::sync::Arc;
+
+    fn seqs(chunks: &[OutputChunk]) -> Vec<u64> {
+        chunks.iter().map(|chunk| chunk.seq).collect()
+    }
+
+    #[test]
+    fn test_chunks_are_numbered() {
+        let streams = AgentOutputStreams::new();
+        streams.start("e1", "bug-fixer").unwrap();
+        assert!(streams.start("e1", "bug-fixer").is_err());
+
+        assert_eq!(seqs(&streams.push("e1", "one\n", OutputStatus::Running)), [1]);
+        assert!(streams.push("e1", "", OutputStatus::Running).is_empty());
+        let last = streams.push("e1", "two\n", OutputStatus::Completed);
+        assert_eq!(seqs(&last), [2]);
+        assert_eq!(last[0].status, OutputStatus::Completed);
+
+        // Nothing is added once the execution finished
+        assert!(streams.push("e1", "late", OutputStatus::Running).is_empty());
+        assert!(streams
+            .push("unknown", "x", OutputStatus::Running)
+            .is_empty());
+
+        let replay = streams.replay("e1", None).unwrap();
+        assert_eq!(replay.agent_name, "bug-fixer");
+        assert_eq!(replay.status, OutputStatus::Completed);
+        assert_eq!(seqs(&replay.chunks), [1, 2]);
+        assert!(!replay.truncated);
+    }
+
+    #[test]
+    fn test_backpressure_merges_pending_output() {
+        let streams = AgentOutputStreams::new();
+        streams.start("e1", "bug-fixer").unwrap();
+
+        for i in 0..MAX_UNACKED_CHUNKS {
+            assert_eq!(
+                seqs(&streams.push("e1", &format!("{}\n", i), OutputStatus::Running)),
+                [i + 1]
+            );
+        }
+        // The window is full: later output waits in one chunk
+        assert!(streams.push("e1", "a\n", OutputStatus::Running).is_empty());
+        assert!(streams.push("e1", "b\n", OutputStatus::Running).is_empty());
+
+        let released = streams.ack("e1", 1).unwrap();
+        assert_eq!(seqs(&released), [MAX_UNACKED_CHUNKS + 1]);
+        assert_eq!(released[0].output, "a\nb\n");
+
+        // Chunks that were never emitted cannot be acknowledged
+        assert!(streams.ack("e1", 1000).unwrap().is_empty());
+        for seq in MAX_UNACKED_CHUNKS + 2..=2 * MAX_UNACKED_CHUNKS + 1 {
+            assert_eq!(seqs(&streams.push("e1", "c\n", OutputStatus::Running)), [seq]);
+        }
+        assert!(streams.push("e1", "d\n", OutputStatus::Running).is_empty());
+        assert!(streams.ack("unknown", 1).is_err());
+    }
+
+    #[test]
+    fn test_subscribe_replays_missed_chunks() {
+        let streams = AgentOutputStreams::new();
+        streams.start("e1", "bug-fixer").unwrap();
+        for _ in 0..19 {
+            let chunks = streams.push("e1", "x", OutputStatus::Running);
+            streams.ack("e1", chunks[0].seq).unwrap();
+        }
+
+        let replay = streams.subscribe("e1", Some(17)).unwrap();
+        assert_eq!(seqs(&replay.chunks), [18, 19]);
+        assert!(!replay.truncated);
+
+        // Replayed chunks count as delivered
+        assert_eq!(seqs(&streams.push("e1", "y", OutputStatus::Running)), [20]);
+        assert!(streams.subscribe("unknown", None).is_none());
+    }
+
+    #[test]
+    fn test_old_output_is_dropped() {
+        let streams = AgentOutputStreams::new();
+        streams.start("e1", "bug-fixer").unwrap();
+        let big = "x".repeat(MAX_CHUNK_BYTES);
+        for seq in 1..=(MAX_RETAINED_BYTES / MAX_CHUNK_BYTES + 2) as u64 {
+            streams.push("e1", &big, OutputStatus::Running);
+            streams.ack("e1", seq).unwrap();
+        }
+
+        let replay = streams.replay("e1", None).unwrap();
+        assert!(replay.truncated);
+        assert!(replay.chunks.iter().map(|c| c.output.len()).sum::<usize>() <= MAX_RETAINED_BYTES);
+        let last = replay.chunks.last().unwrap().seq;
+        assert!(!streams.replay("e1", Some(last - 1)).unwrap().truncated);
+    }
+
+    #[test]
+    fn test_finished_streams_are_forgotten() {
+        let streams = AgentOutputStreams::new();
+        for i in 0..=MAX_FINISHED_STREAMS {
+            let id = format!("e{}", i);
+            streams.start(&id, "bug-fixer").unwrap();
+            streams.push(&id, "done", OutputStatus::Completed);
+        }
+        streams.start("running", "bug-fixer").unwrap();
+
+        assert!(!streams.contains("e0"));
+        assert!(streams.contains("e1"));
+        assert!(streams.contains("running"));
+    }
+
+    #[tokio::test]
+    async fn test_tail_tmux_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("out.log");
+        let scenario = FakeScenario::new().default_session(
+            SessionScript::new()
+                .startup_polls(2)
+                .output("one\n")
+                .output("two\n"),
+        );
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::new(scenario)));
+        for args in [
+            vec![
+                "new-session".to_string(),
+                "-d".into(),
+                "-s".into(),
+                "s1".into(),
+            ],
+            vec![
+                "pipe-pane".to_string(),
+                "-t".into(),
+                "s1".into(),
+                "-o".into(),
+                format!("cat >> {}", log.display()),
+            ],
+        ] {
+            tools.command("tmux").args(args).output().unwrap();
+        }
+
+        let mut outputs = Vec::new();
+        tail_tmux_session(&tools, "s1", &log, Duration::ZERO, |output, status| {
+            outputs.push((output, status))
+        })
+        .await;
+
+        assert_eq!(
+            outputs,
+            vec![
+                ("one\n".to_string(), OutputStatus::Running),
+                ("two\n".to_string(), OutputStatus::Running),
+                (String::new(), OutputStatus::Completed),
+            ]
+        );
+    }
+}
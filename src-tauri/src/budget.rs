@@ -0,0 +1,506 @@
+//! Spend Guard
+//!
+//! Estimates what LLM estimations, chats and competitions cost and keeps a
+//! ledger of the estimates, so the daily and per-session limits in
+//! `ait42.budget` can be enforced before anything is launched. There is no
+//! billing data to read back: tokens are approximated from the text sent
+//! and the output an operation is expected to produce.
+//!
+//! Operations that would go over a limit are refused with a notification
+//! offering an override. Approving it lifts the limit for the rest of the
+//! day or the session. Crossing the `warn_at` fraction of a limit emits a
+//! `budget-warning` event once per day or session.
+//!
+//! The ledger is stored in `~/.ait42/budget/spend.json`.
+
+use ait42_config::BudgetConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::notifications::{NotificationAction, NotificationCenter, NotificationLevel};
+use crate::sync::lock;
+
+/// Event emitted when spend crosses the warning fraction of a limit
+pub const BUDGET_WARNING_EVENT: &str = "budget-warning";
+
+/// Days of spend kept in the ledger
+const LEDGER_DAYS: i64 = 31;
+
+/// Average characters per token of English text and code
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Rough number of tokens in `text`
+pub fn estimate_tokens(text: &str) -> u64 {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN) as u64
+}
+
+/// Kind of operation that spends tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Estimation,
+    Chat,
+    Competition,
+    Debate,
+    Assist,
+}
+
+/// Limit an operation is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetScope {
+    Day,
+    Session,
+}
+
+/// Tokens an operation is expected to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenEstimate {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenEstimate {
+    /// One LLM call sending `prompt` and answering in at most `max_tokens`
+    pub fn completion(prompt: &str, max_tokens: u64) -> Self {
+        Self {
+            input_tokens: estimate_tokens(prompt),
+            output_tokens: max_tokens,
+        }
+    }
+
+    /// `runs` agent runs on `task`, each producing the configured output
+    pub fn agent_runs(task: &str, runs: usize, policy: &BudgetConfig) -> Self {
+        Self {
+            input_tokens: estimate_tokens(task) * runs as u64,
+            output_tokens: policy.agent_run_tokens * runs as u64,
+        }
+    }
+
+    /// Cost in USD at the configured token prices
+    pub fn cost_usd(&self, policy: &BudgetConfig) -> f64 {
+        (self.input_tokens as f64 * policy.input_usd_per_mtok
+            + self.output_tokens as f64 * policy.output_usd_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// Estimated spend of one operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendRecord {
+    /// Local date, YYYY-MM-DD
+    pub day: String,
+    pub session_id: Option<String>,
+    pub operation: Operation,
+    pub tokens: TokenEstimate,
+    pub cost_usd: f64,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+}
+
+/// Spend against the configured limits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    pub day: String,
+    pub spent_today_usd: f64,
+    pub daily_limit_usd: Option<f64>,
+    pub session_id: Option<String>,
+    pub spent_in_session_usd: f64,
+    pub session_limit_usd: Option<f64>,
+    pub warn_at: f64,
+}
+
+/// Payload of [`BUDGET_WARNING_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetWarning {
+    pub scope: BudgetScope,
+    pub message: String,
+    pub status: BudgetStatus,
+}
+
+/// An operation refused because it would go over a limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExceeded {
+    pub scope: BudgetScope,
+    pub session_id: Option<String>,
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+    pub cost_usd: f64,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let limit = match self.scope {
+            BudgetScope::Day => "daily",
+            BudgetScope::Session => "session",
+        };
+        write!(
+            f,
+            "Budget exceeded: ${:.2} spent of the ${:.2} {} limit, this needs ${:.2} more. \
+             Approve an override to continue.",
+            self.spent_usd, self.limit_usd, limit, self.cost_usd
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Overrides {
+    /// Day the daily limit is lifted for
+    day: Option<String>,
+    sessions: HashSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct Warned {
+    day: Option<String>,
+    sessions: HashSet<String>,
+}
+
+/// Ledger of estimated spend with the limits' overrides
+#[derive(Debug, Default)]
+pub struct SpendGuard {
+    path: Option<PathBuf>,
+    records: Mutex<Vec<SpendRecord>>,
+    overrides: Mutex<Overrides>,
+    warned: Mutex<Warned>,
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+impl SpendGuard {
+    /// Guard keeping its ledger in memory only
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Guard over the ledger at `path`, starting empty if it cannot be read
+    pub fn load(path: PathBuf) -> Self {
+        let records = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable spend ledger {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path: Some(path),
+            records: Mutex::new(records),
+            ..Self::default()
+        }
+    }
+
+    /// Guard over the ledger in the user's home directory
+    pub fn load_default() -> Self {
+        let path = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join(".ait42")
+            .join("budget")
+            .join("spend.json");
+        Self::load(path)
+    }
+
+    /// Spend on `day`, and in `session_id` if given
+    pub fn status(&self, policy: &BudgetConfig, day: &str, session_id: Option<&str>) -> BudgetStatus {
+        let records = lock(&self.records);
+        let spent_today_usd = records.iter().filter(|r| r.day == day).map(|r| r.cost_usd).sum();
+        let spent_in_session_usd = session_id
+            .map(|id| {
+                records
+                    .iter()
+                    .filter(|r| r.session_id.as_deref() == Some(id))
+                    .map(|r| r.cost_usd)
+                    .sum()
+            })
+            .unwrap_or(0.0);
+        BudgetStatus {
+            day: day.to_string(),
+            spent_today_usd,
+            daily_limit_usd: policy.daily_limit_usd,
+            session_id: session_id.map(str::to_string),
+            spent_in_session_usd,
+            session_limit_usd: policy.session_limit_usd,
+            warn_at: policy.warn_at,
+        }
+    }
+
+    /// Check that spending `cost_usd` stays within the limits not overridden
+    pub fn check(
+        &self,
+        policy: &BudgetConfig,
+        day: &str,
+        session_id: Option<&str>,
+        cost_usd: f64,
+    ) -> Result<BudgetStatus, BudgetExceeded> {
+        let status = self.status(policy, day, session_id);
+        let overrides = lock(&self.overrides);
+
+        let daily = policy
+            .daily_limit_usd
+            .filter(|_| overrides.day.as_deref() != Some(day))
+            .map(|limit| (BudgetScope::Day, status.spent_today_usd, limit));
+        let session = policy
+            .session_limit_usd
+            .filter(|_| session_id.is_some_and(|id| !overrides.sessions.contains(id)))
+            .map(|limit| (BudgetScope::Session, status.spent_in_session_usd, limit));
+
+        for (scope, spent_usd, limit_usd) in daily.into_iter().chain(session) {
+            if spent_usd + cost_usd > limit_usd {
+                return Err(BudgetExceeded {
+                    scope,
+                    session_id: session_id.map(str::to_string),
+                    spent_usd,
+                    limit_usd,
+                    cost_usd,
+                });
+            }
+        }
+        Ok(status)
+    }
+
+    /// Add a spend record to the ledger
+    ///
+    /// Returns the limits whose warning fraction was crossed for the first
+    /// time on that day or in that session.
+    pub fn record(&self, policy: &BudgetConfig, record: SpendRecord) -> Vec<BudgetScope> {
+        let before = self.status(policy, &record.day, record.session_id.as_deref());
+        let crossed = |spent: f64, limit: Option<f64>| {
+            limit.is_some_and(|limit| {
+                let threshold = limit * policy.warn_at;
+                spent < threshold && spent + record.cost_usd >= threshold
+            })
+        };
+
+        let mut scopes = Vec::new();
+        let mut warned = lock(&self.warned);
+        if crossed(before.spent_today_usd, policy.daily_limit_usd)
+            && warned.day.as_deref() != Some(record.day.as_str())
+        {
+            warned.day = Some(record.day.clone());
+            scopes.push(BudgetScope::Day);
+        }
+        if let Some(id) = &record.session_id {
+            if crossed(before.spent_in_session_usd, policy.session_limit_usd)
+                && warned.sessions.insert(id.clone())
+            {
+                scopes.push(BudgetScope::Session);
+            }
+        }
+        drop(warned);
+
+        let mut records = lock(&self.records);
+        records.push(record);
+        let oldest = (chrono::Local::now() - chrono::Duration::days(LEDGER_DAYS))
+            .format("%Y-%m-%d")
+            .to_string();
+        records.retain(|r| r.day >= oldest);
+        if let Some(path) = &self.path {
+            let saved = std::fs::create_dir_all(path.parent().unwrap_or(path))
+                .and_then(|_| std::fs::write(path, serde_json::to_vec_pretty(&*records)?));
+            if let Err(e) = saved {
+                tracing::warn!("Failed to save spend ledger {}: {}", path.display(), e);
+            }
+        }
+        scopes
+    }
+
+    /// Lift the daily limit for `day`, or the session limit for `session_id`
+    pub fn approve_override(&self, scope: BudgetScope, day: &str, session_id: Option<&str>) -> Result<(), String> {
+        let mut overrides = lock(&self.overrides);
+        match (scope, session_id) {
+            (BudgetScope::Day, _) => overrides.day = Some(day.to_string()),
+            (BudgetScope::Session, Some(id)) => {
+                overrides.sessions.insert(id.to_string());
+            }
+            (BudgetScope::Session, None) => {
+                return Err("A session override needs a session id".to_string())
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check the budget before launching an operation and record its spend
+///
+/// Operations that would go over a limit are refused and a notification
+/// offering the override is raised; the error tells the user why.
+pub(crate) fn authorize(
+    app: &tauri::AppHandle,
+    guard: &SpendGuard,
+    notifications: &NotificationCenter,
+    policy: &BudgetConfig,
+    operation: Operation,
+    session_id: Option<&str>,
+    tokens: TokenEstimate,
+) -> Result<(), String> {
+    let day = today();
+    let cost_usd = tokens.cost_usd(policy);
+
+    if let Err(exceeded) = guard.check(policy, &day, session_id, cost_usd) {
+        tracing::warn!("Refusing {:?}: {}", operation, exceeded);
+        notifications.notify(
+            app,
+            NotificationLevel::Warning,
+            "budget",
+            exceeded.to_string(),
+            vec![NotificationAction {
+                label: "Allow anyway".to_string(),
+                command: "approve_budget_override".to_string(),
+                args: serde_json::json!({
+                    "scope": exceeded.scope,
+                    "sessionId": exceeded.session_id,
+                }),
+            }],
+        );
+        return Err(exceeded.to_string());
+    }
+
+    record_spend(app, guard, notifications, policy, operation, session_id, tokens);
+    Ok(())
+}
+
+/// Record the spend of an operation and raise warnings for limits it nears
+fn record_spend(
+    app: &tauri::AppHandle,
+    guard: &SpendGuard,
+    notifications: &NotificationCenter,
+    policy: &BudgetConfig,
+    operation: Operation,
+    session_id: Option<&str>,
+    tokens: TokenEstimate,
+) {
+    let record = SpendRecord {
+        day: today(),
+        session_id: session_id.map(str::to_string),
+        operation,
+        tokens,
+        cost_usd: tokens.cost_usd(policy),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let day = record.day.clone();
+
+    for scope in guard.record(policy, record) {
+        let status = guard.status(policy, &day, session_id);
+        let (spent, limit, what) = match scope {
+            BudgetScope::Day => (status.spent_today_usd, status.daily_limit_usd, "today"),
+            BudgetScope::Session => (
+                status.spent_in_session_usd,
+                status.session_limit_usd,
+                "in this session",
+            ),
+        };
+        let message = format!(
+            "${:.2} of the ${:.2} AI budget spent {}",
+            spent,
+            limit.unwrap_or_default(),
+            what
+        );
+        notifications.notify(app, NotificationLevel::Warning, "budget", message.clone(), vec![]);
+        let warning = BudgetWarning {
+            scope,
+            message,
+            status,
+        };
+        if let Err(e) = app.emit_all(BUDGET_WARNING_EVENT, &warning) {
+            tracing::warn!("Failed to emit budget warning: {}", e);
+        }
+    }
+}
+
+/// Local date the daily limit currently applies to
+pub(crate) fn current_day() -> String {
+    today()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> BudgetConfig {
+        BudgetConfig {
+            daily_limit_usd: Some(1.0),
+            session_limit_usd: Some(0.5),
+            ..BudgetConfig::default()
+        }
+    }
+
+    fn spend(session_id: Option<&str>, cost_usd: f64) -> SpendRecord {
+        SpendRecord {
+            day: today(),
+            session_id: session_id.map(str::to_string),
+            operation: Operation::Chat,
+            tokens: TokenEstimate::default(),
+            cost_usd,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_token_estimates() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcde"), 2);
+
+        let policy = BudgetConfig::default();
+        let tokens = TokenEstimate::agent_runs("abcd", 3, &policy);
+        assert_eq!(tokens.input_tokens, 3);
+        assert_eq!(tokens.output_tokens, 60_000);
+
+        let tokens = TokenEstimate {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+        };
+        assert!((tokens.cost_usd(&policy) - 4.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_limits_and_overrides() {
+        let guard = SpendGuard::in_memory();
+        let policy = policy();
+        let day = today();
+
+        guard.record(&policy, spend(Some("a"), 0.4));
+        assert!(guard.check(&policy, &day, Some("b"), 0.4).is_ok());
+        let exceeded = guard.check(&policy, &day, Some("a"), 0.2).unwrap_err();
+        assert_eq!(exceeded.scope, BudgetScope::Session);
+        assert!(exceeded.to_string().contains("$0.40 spent of the $0.50 session limit"));
+
+        guard.approve_override(BudgetScope::Session, &day, Some("a")).unwrap();
+        assert!(guard.check(&policy, &day, Some("a"), 0.2).is_ok());
+        assert!(guard.approve_override(BudgetScope::Session, &day, None).is_err());
+
+        guard.record(&policy, spend(None, 0.5));
+        let exceeded = guard.check(&policy, &day, None, 0.2).unwrap_err();
+        assert_eq!(exceeded.scope, BudgetScope::Day);
+        guard.approve_override(BudgetScope::Day, &day, None).unwrap();
+        assert!(guard.check(&policy, &day, None, 0.2).is_ok());
+
+        // Without limits nothing is refused
+        let unlimited = BudgetConfig::default();
+        assert!(guard.check(&unlimited, &day, Some("b"), 100.0).is_ok());
+    }
+
+    #[test]
+    fn test_warns_once_when_crossing_fraction() {
+        let guard = SpendGuard::in_memory();
+        let policy = policy();
+
+        assert!(guard.record(&policy, spend(Some("a"), 0.3)).is_empty());
+        assert_eq!(guard.record(&policy, spend(Some("a"), 0.15)), [BudgetScope::Session]);
+        assert_eq!(guard.record(&policy, spend(Some("b"), 0.4)), [BudgetScope::Day]);
+        assert!(guard.record(&policy, spend(Some("b"), 0.05)).is_empty());
+
+        let status = guard.status(&policy, &today(), Some("b"));
+        assert!((status.spent_today_usd - 0.9).abs() < 1e-9);
+        assert!((status.spent_in_session_usd - 0.45).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,215 @@
+//! Clipboard History
+//!
+//! Recent copies and cuts from the editor, most recent first, so an older
+//! one can be pasted again from the clipboard history picker. Plugins and
+//! agent runs push the text they produce as well. Pushing text that is
+//! already in the history moves it back to the top.
+//!
+//! The history holds at most `clipboard.history_size` entries. It is only
+//! kept across restarts when `clipboard.persist` is on, in
+//! `~/.ait42/clipboard.json`; turning persistence off deletes that file on
+//! the next change.
+
+use ait42_config::ClipboardConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::sync::lock;
+
+/// Where copied text came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardSource {
+    Editor,
+    Agent,
+    Plugin,
+}
+
+/// Copied text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardEntry {
+    pub id: String,
+    pub text: String,
+    pub source: ClipboardSource,
+    pub copied_at: String,
+}
+
+/// Bounded history of copied text, most recent first
+#[derive(Debug, Default)]
+pub struct ClipboardHistory {
+    path: Option<PathBuf>,
+    entries: Mutex<VecDeque<ClipboardEntry>>,
+}
+
+impl ClipboardHistory {
+    /// History that is never saved
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// History saved at `path`, starting empty if it cannot be read
+    pub fn load(path: PathBuf) -> Self {
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable clipboard history {}: {}", path.display(), e);
+                VecDeque::new()
+            }),
+            Err(_) => VecDeque::new(),
+        };
+        Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// History saved in the user's home directory
+    pub fn load_default() -> Self {
+        let path = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join(".ait42")
+            .join("clipboard.json");
+        Self::load(path)
+    }
+
+    /// Entries, most recent first
+    pub fn entries(&self) -> Vec<ClipboardEntry> {
+        lock(&self.entries).iter().cloned().collect()
+    }
+
+    /// Add `text` to the top of the history
+    ///
+    /// Blank text is ignored. Returns the new entry.
+    pub fn push(
+        &self,
+        text: String,
+        source: ClipboardSource,
+        config: &ClipboardConfig,
+    ) -> Option<ClipboardEntry> {
+        if text.trim().is_empty() {
+            return None;
+        }
+        let entry = ClipboardEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            text,
+            source,
+            copied_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let mut entries = lock(&self.entries);
+        entries.retain(|e| e.text != entry.text);
+        entries.push_front(entry.clone());
+        entries.truncate(config.history_size);
+        self.save(&entries, config);
+        Some(entry)
+    }
+
+    /// Remove the entry `id`, returning whether there was one
+    pub fn remove(&self, id: &str, config: &ClipboardConfig) -> bool {
+        let mut entries = lock(&self.entries);
+        let count = entries.len();
+        entries.retain(|e| e.id != id);
+        let removed = entries.len() != count;
+        if removed {
+            self.save(&entries, config);
+        }
+        removed
+    }
+
+    /// Remove every entry
+    pub fn clear(&self, config: &ClipboardConfig) {
+        let mut entries = lock(&self.entries);
+        entries.clear();
+        self.save(&entries, config);
+    }
+
+    fn save(&self, entries: &VecDeque<ClipboardEntry>, config: &ClipboardConfig) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = if config.persist {
+            std::fs::create_dir_all(path.parent().unwrap_or(path))
+                .and_then(|_| std::fs::write(path, serde_json::to_vec_pretty(entries)?))
+        } else {
+            match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            }
+        };
+        if let Err(e) = saved {
+            tracing::warn!("Failed to save clipboard history {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(history_size: usize, persist: bool) -> ClipboardConfig {
+        ClipboardConfig {
+            history_size,
+            persist,
+        }
+    }
+
+    fn texts(history: &ClipboardHistory) -> Vec<String> {
+        history.entries().into_iter().map(|e| e.text).collect()
+    }
+
+    #[test]
+    fn test_bounded_and_most_recent_first() {
+        let history = ClipboardHistory::in_memory();
+        let config = config(3, false);
+        for text in ["a", "b", "c", "d"] {
+            history.push(text.to_string(), ClipboardSource::Editor, &config);
+        }
+        assert_eq!(texts(&history), ["d", "c", "b"]);
+
+        // Copying text again moves it to the top
+        history.push("b".to_string(), ClipboardSource::Agent, &config);
+        assert_eq!(texts(&history), ["b", "d", "c"]);
+        assert_eq!(history.entries()[0].source, ClipboardSource::Agent);
+
+        assert!(history
+            .push("  \n".to_string(), ClipboardSource::Plugin, &config)
+            .is_none());
+        assert_eq!(history.entries().len(), 3);
+    }
+
+    #[test]
+    fn test_remove_and_clear() {
+        let history = ClipboardHistory::in_memory();
+        let config = config(10, false);
+        let entry = history
+            .push("a".to_string(), ClipboardSource::Editor, &config)
+            .unwrap();
+        history.push("b".to_string(), ClipboardSource::Editor, &config);
+
+        assert!(history.remove(&entry.id, &config));
+        assert!(!history.remove(&entry.id, &config));
+        assert_eq!(texts(&history), ["b"]);
+
+        history.clear(&config);
+        assert!(history.entries().is_empty());
+    }
+
+    #[test]
+    fn test_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clipboard.json");
+
+        let history = ClipboardHistory::load(path.clone());
+        history.push("kept".to_string(), ClipboardSource::Editor, &config(10, true));
+        assert_eq!(texts(&ClipboardHistory::load(path.clone())), ["kept"]);
+
+        // Turning persistence off deletes the saved history
+        history.push("forgotten".to_string(), ClipboardSource::Editor, &config(10, false));
+        assert!(!path.exists());
+        assert!(ClipboardHistory::load(path).entries().is_empty());
+    }
+}
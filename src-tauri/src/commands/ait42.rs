@@ -1,45 +1,113 @@
-use ait42_ait42::{config::AIT42Config, AgentExecutor, AgentRegistry, Coordinator, ExecutionMode};
+use ait42_ait42::manager::watch_agent_dirs;
+use ait42_ait42::{
+    config::AIT42Config, recommend, AgentExecutor, AgentMetadata, AgentRegistry, AgentSource,
+    AgentStats, Coordinator, ExecutionMode, Recommendation, SessionStatus,
+};
+use claude_output::{strip_ansi, Analysis};
+use omega_theory::ComplexityClass;
 /**
  * AIT42 Agent Commands
  *
  * Tauri commands for executing AIT42 AI agents with Tmux support
  */
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::sync::{Arc, Mutex};
 use tauri::{Manager, State};
 use tracing::{error, info, warn};
 
+use crate::agent_output::{self, OutputReplay, OutputStatus};
+use crate::budget::{Operation, TokenEstimate};
+use crate::commands::budget::{authorize_spend, complete_within_budget};
+use crate::commands::session_history;
+use crate::confirmation::DangerousAction;
+use crate::executions;
+use crate::feature_flags;
+use crate::injection;
+use crate::instance_log::{self, LogTail};
+use crate::naming::NameAllocator;
+use crate::notifications::NotificationLevel;
+use crate::output_alerts::{self, AgentAlert};
+use crate::sanitize;
+use crate::session_journal::{self, JournalEvent};
+use crate::session_stream::emit_session_event;
 use crate::state::AppState;
+use crate::tools::ToolRegistry;
 use crate::utils::AIT42Installer;
 
 /**
  * Agent information
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AgentInfo {
     pub name: String,
     pub description: String,
     pub category: String,
     pub tools: Vec<String>,
+    /// Version declared by the agent's definition
+    #[serde(default)]
+    pub version: Option<String>,
+    /// How often the agent's work was kept, if any runs were recorded
+    pub stats: Option<AgentStats>,
+    /// Whether the agent is defined globally or by the project
+    #[serde(default)]
+    pub source: AgentSource,
+    /// Definition file of the agent
+    #[serde(default)]
+    pub path: String,
+    /// Global definition file replaced by the project's definition
+    #[serde(default)]
+    pub overrides: Option<String>,
+}
+
+impl AgentInfo {
+    fn new(
+        registry: &AgentRegistry,
+        agent: &AgentMetadata,
+        stats: &mut std::collections::BTreeMap<String, AgentStats>,
+    ) -> Self {
+        Self {
+            name: agent.name.clone(),
+            description: agent.description.clone(),
+            category: format!("{:?}", agent.category),
+            tools: agent.tools.clone(),
+            version: agent.version.clone(),
+            stats: stats.remove(&agent.name),
+            source: agent.source,
+            path: agent.file_path.to_string_lossy().to_string(),
+            overrides: registry
+                .shadowed(&agent.name)
+                .map(|path| path.to_string_lossy().to_string()),
+        }
+    }
 }
 
 /**
  * Agent execution request
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AgentExecutionRequest {
     pub agent_name: String,
     pub task: String,
     pub context: Option<String>,
+    /// ID to run under, so the caller can cancel before the agent finishes
+    #[serde(default)]
+    pub execution_id: Option<String>,
+    /// Session the run belongs to; the prompt injection scan of `context`
+    /// is recorded on it
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Workspace of the session
+    #[serde(default)]
+    pub workspace_path: Option<String>,
 }
 
 /**
  * Agent execution response
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AgentExecutionResponse {
     pub execution_id: String,
     pub agent_name: String,
@@ -51,11 +119,21 @@ pub struct AgentExecutionResponse {
 /**
  * Parallel execution request
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParallelExecutionRequest {
     pub agents: Vec<String>,
     pub task: String,
     pub context: Option<String>,
+    /// ID to run under, so the caller can cancel before the agents finish
+    #[serde(default)]
+    pub execution_id: Option<String>,
+    /// Session the run belongs to; the prompt injection scan of `context`
+    /// is recorded on it
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Workspace of the session
+    #[serde(default)]
+    pub workspace_path: Option<String>,
 }
 
 /**
@@ -63,7 +141,7 @@ pub struct ParallelExecutionRequest {
  *
  * Executes multiple Claude Code instances in parallel worktrees
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeCodeCompetitionRequest {
     pub task: String,
@@ -74,7 +152,7 @@ pub struct ClaudeCodeCompetitionRequest {
 }
 
 /// Runtime allocation request for multi-runtime competition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RuntimeAllocationRequest {
     pub runtime: String, // "claude" | "codex" | "gemini"
@@ -83,7 +161,7 @@ pub struct RuntimeAllocationRequest {
 }
 
 /// Multi-runtime competition request (competition / ensemble)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MultiRuntimeCompetitionRequest {
     pub task: String,
@@ -194,13 +272,6 @@ fi"#,
     }
 }
 
-fn escape_for_shell(input: &str) -> String {
-    input
-        .replace('\\', "\\\\")
-        .replace('\n', "\\n")
-        .replace('\'', "'\\''")
-}
-
 fn escape_single_quotes(input: &str) -> String {
     input.replace('\'', "'\\''")
 }
@@ -208,7 +279,7 @@ fn escape_single_quotes(input: &str) -> String {
 /**
  * Claude Code Competition Result
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeCodeCompetitionResult {
     pub competition_id: String,
@@ -221,12 +292,14 @@ pub struct ClaudeCodeCompetitionResult {
 /**
  * Individual Claude Code Instance Result
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeCodeInstanceResult {
     pub instance_id: String,
     pub instance_number: usize,
     pub worktree_path: String,
+    /// Branch checked out in the worktree, as actually created
+    pub branch: String,
     pub tmux_session_id: String,
     pub status: String, // "starting", "running", "completed", "failed", "timeout"
     pub output: String,
@@ -238,39 +311,70 @@ pub struct ClaudeCodeInstanceResult {
     pub model: Option<String>,
 }
 
-/// Initialize AIT42 agent registry if not already initialized
-async fn ensure_registry_initialized(state: &State<'_, AppState>) -> Result<(), String> {
+/// Initialize the AIT42 agent registry for the current workspace
+///
+/// The registry holds the global agents and those of the workspace's
+/// `.ait42/agents`, and is reloaded when the workspace changes. While it is
+/// loaded, changes to agent files reload it too.
+pub(crate) async fn ensure_registry_initialized(state: &State<'_, AppState>) -> Result<(), String> {
+    let project_dir = AgentRegistry::project_agents_dir(&state.working_dir.lock().await);
     let mut registry_guard = state
         .agent_registry
         .lock()
         .map_err(|e| format!("Failed to lock agent registry: {}", e))?;
 
-    if registry_guard.is_some() {
+    if registry_guard
+        .as_ref()
+        .is_some_and(|registry| registry.project_dir() == Some(project_dir.as_path()))
+    {
         return Ok(());
     }
 
-    // Initialize registry
-    let config = match AIT42Config::load() {
-        Ok(config) => config,
-        Err(e) => {
-            warn!("Failed to load AIT42 config: {}. Using default.", e);
-            // Try to detect AIT42 root from common locations
-            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-            let ait42_root = std::path::PathBuf::from(&home)
-                .join("Programming/AI/02_Workspace/05_Client/03_Sun/AIT42");
-            AIT42Config::new(ait42_root)
-        }
-    };
-
-    let registry = AgentRegistry::load_from_directory(&config.agents_dir())
+    let mut registry = AgentRegistry::new(load_agent_config().agents_dir());
+    registry.set_project_dir(Some(project_dir));
+    registry
+        .load_agents()
         .map_err(|e| format!("Failed to load agent registry: {}", e))?;
 
+    watch_registry(state, &registry);
     *registry_guard = Some(registry);
     Ok(())
 }
 
+/// Reload the agent registry whenever files in its directories change
+///
+/// Replaces the watch of the previous registry. A failure to watch is
+/// logged; the agents then stay as loaded.
+fn watch_registry(state: &AppState, registry: &AgentRegistry) {
+    let mut dirs = vec![registry.agents_dir()];
+    dirs.extend(registry.project_dir().filter(|dir| dir.is_dir()));
+
+    let agents = Arc::clone(&state.agent_registry);
+    let watch = watch_agent_dirs(&dirs, move || {
+        let mut registry = agents.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(registry) = registry.as_mut() {
+            match registry.load_agents() {
+                Ok(()) => info!("Reloaded {} agents", registry.count()),
+                Err(e) => warn!("Failed to reload agents: {}", e),
+            }
+        }
+    });
+
+    let watch = match watch {
+        Ok(watch) => Some(watch),
+        Err(e) => {
+            warn!("Agents will not reload on changes: {}", e);
+            None
+        }
+    };
+    let mut current = state.agent_watch.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(previous) = std::mem::replace(&mut *current, watch) {
+        previous.abort();
+    }
+}
+
 /// Get registry (must be initialized first)
-fn get_registry<'a>(
+pub(crate) fn get_registry<'a>(
     state: &'a State<'a, AppState>,
 ) -> Result<std::sync::MutexGuard<'a, Option<AgentRegistry>>, String> {
     // Note: This is a synchronous function, but we need async initialization
@@ -310,7 +414,16 @@ async fn ensure_coordinator_initialized(state: &State<'_, AppState>) -> Result<(
     }
 
     // Initialize coordinator
-    let config = match AIT42Config::load() {
+    let coordinator = Coordinator::new(load_agent_config())
+        .map_err(|e| format!("Failed to initialize coordinator: {}", e))?;
+
+    *coordinator_guard = Some(coordinator);
+    Ok(())
+}
+
+/// Load the AIT42 config, falling back to the default install location
+pub(crate) fn load_agent_config() -> AIT42Config {
+    match AIT42Config::load() {
         Ok(config) => config,
         Err(e) => {
             warn!("Failed to load AIT42 config: {}. Using default.", e);
@@ -319,13 +432,7 @@ async fn ensure_coordinator_initialized(state: &State<'_, AppState>) -> Result<(
                 .join("Programming/AI/02_Workspace/05_Client/03_Sun/AIT42");
             AIT42Config::new(ait42_root)
         }
-    };
-
-    let coordinator =
-        Coordinator::new(config).map_err(|e| format!("Failed to initialize coordinator: {}", e))?;
-
-    *coordinator_guard = Some(coordinator);
-    Ok(())
+    }
 }
 
 /// Get coordinator (must be initialized first)
@@ -348,15 +455,11 @@ pub async fn list_agents(state: State<'_, AppState>) -> Result<Vec<AgentInfo>, S
         .ok_or_else(|| "Agent registry not initialized".to_string())?;
 
     let agents = registry.list();
+    let mut stats = crate::commands::telemetry::agent_stats();
     let mut agent_infos = Vec::new();
 
     for agent in agents {
-        agent_infos.push(AgentInfo {
-            name: agent.name.clone(),
-            description: agent.description.clone(),
-            category: format!("{:?}", agent.category),
-            tools: agent.tools.clone(),
-        });
+        agent_infos.push(AgentInfo::new(registry, agent, &mut stats));
     }
 
     info!("Listed {} agents", agent_infos.len());
@@ -381,12 +484,79 @@ pub async fn get_agent_info(
         .get(&agent_name)
         .ok_or_else(|| format!("Agent not found: {}", agent_name))?;
 
-    Ok(AgentInfo {
-        name: agent.name.clone(),
-        description: agent.description.clone(),
-        category: format!("{:?}", agent.category),
-        tools: agent.tools.clone(),
-    })
+    Ok(AgentInfo::new(registry, agent, &mut crate::commands::telemetry::agent_stats()))
+}
+
+/// Agents shown to the LLM when it re-ranks recommendations
+const LLM_CANDIDATES: usize = 10;
+
+/**
+ * Recommend agents for a task description
+ *
+ * Agents are ranked by keyword match against their names, categories and
+ * descriptions. With `use_llm`, the best candidates are re-ranked by the LLM
+ * provider in `ait42.llm_provider`; the keyword ranking is returned if that
+ * fails or would go over the AI budget.
+ */
+#[tauri::command]
+pub async fn recommend_agents(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    task: String,
+    limit: Option<usize>,
+    use_llm: Option<bool>,
+) -> Result<Vec<Recommendation>, String> {
+    let limit = limit.unwrap_or(5);
+    ensure_registry_initialized(&state).await?;
+    let (ranked, candidates) = {
+        let registry_guard = get_registry(&state)?;
+        let registry = registry_guard
+            .as_ref()
+            .ok_or_else(|| "Agent registry not initialized".to_string())?;
+
+        let ranked = registry.recommend(&task, LLM_CANDIDATES.max(limit));
+        let candidates: Vec<AgentMetadata> = if ranked.is_empty() {
+            registry.list().into_iter().cloned().collect()
+        } else {
+            ranked
+                .iter()
+                .filter_map(|r| registry.get(&r.agent).cloned())
+                .collect()
+        };
+        (ranked, candidates)
+    };
+
+    if use_llm.unwrap_or(false) && !candidates.is_empty() {
+        match rank_with_llm(&app, &state, &task, &candidates).await {
+            Ok(choices) if !choices.is_empty() => {
+                info!("LLM recommended {} agents", choices.len());
+                return Ok(choices.into_iter().take(limit).collect());
+            }
+            Ok(_) => warn!("LLM recommended none of the candidate agents"),
+            Err(e) => warn!("LLM agent ranking failed, using keyword ranking: {}", e),
+        }
+    }
+
+    Ok(ranked.into_iter().take(limit).collect())
+}
+
+async fn rank_with_llm(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    task: &str,
+    candidates: &[AgentMetadata],
+) -> Result<Vec<Recommendation>, String> {
+    let candidates: Vec<&AgentMetadata> = candidates.iter().collect();
+    let config = llm_estimator::ClientConfig {
+        max_tokens: 512,
+        timeout_secs: 30,
+        ..llm_estimator::ClientConfig::default()
+    };
+    let prompt = recommend::llm_prompt(task, &candidates);
+
+    let response =
+        complete_within_budget(app, state, Operation::Assist, None, &prompt, config).await?;
+    recommend::apply_llm_ranking(&response, &candidates).map_err(|e| e.to_string())
 }
 
 /**
@@ -394,6 +564,7 @@ pub async fn get_agent_info(
  */
 #[tauri::command]
 pub async fn execute_agent(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     request: AgentExecutionRequest,
 ) -> Result<AgentExecutionResponse, String> {
@@ -409,16 +580,28 @@ pub async fn execute_agent(
         }
     }
 
-    let execution_id = uuid::Uuid::new_v4().to_string();
+    let execution_id = new_execution_id(request.execution_id.as_deref());
+    let request_task = sanitize::task(&request.task)?;
+    let running = state.executions.start(
+        &execution_id,
+        std::slice::from_ref(&request.agent_name),
+        &state.tools,
+    )?;
 
-    info!("Executing agent: {} with task: {}", request.agent_name, request.task);
+    info!("Executing agent: {} with task: {}", request.agent_name, request_task);
 
-    // Build task with context if provided
+    // Build task with context if provided, screened for prompt injection
     let task = if let Some(ref context) = request.context {
-        format!("{}\n\nContext:\n{}", request.task, context)
+        let session = request
+            .workspace_path
+            .as_deref()
+            .zip(request.session_id.as_deref());
+        let context = screen_context(&app, &state, "agent context", context, session);
+        format!("{}\n\nContext:\n{}", request_task, context)
     } else {
-        request.task.clone()
+        request_task
     };
+    let task = sanitize::prompt(&task, sanitize::MAX_PROMPT_CHARS);
 
     // Create coordinator and executor for this execution
     let config = match AIT42Config::load() {
@@ -437,13 +620,36 @@ pub async fn execute_agent(
     let mut executor = AgentExecutor::new(coordinator);
     let mode = ExecutionMode::Single(request.agent_name.clone());
 
-    match executor.execute(mode, &task).await {
+    let outcome = tokio::select! {
+        outcome = executor.execute(mode, &task) => outcome,
+        _ = running.cancelled() => {
+            info!("Agent execution {} cancelled", execution_id);
+            record_output(
+                &app,
+                &execution_id,
+                &request.agent_name,
+                "",
+                OutputStatus::Cancelled,
+            );
+            return Ok(AgentExecutionResponse {
+                execution_id,
+                agent_name: request.agent_name,
+                status: "cancelled".to_string(),
+                output: None,
+                error: Some("Cancelled".to_string()),
+            });
+        }
+    };
+
+    match outcome {
         Ok(results) => {
             if results.is_empty() {
                 return Err("No execution results returned".to_string());
             }
 
             let result = &results[0];
+            let status = output_status(&result.status);
+            record_output(&app, &execution_id, &result.agent_name, &result.output, status);
             Ok(AgentExecutionResponse {
                 execution_id,
                 agent_name: result.agent_name.clone(),
@@ -454,6 +660,13 @@ pub async fn execute_agent(
         }
         Err(e) => {
             error!("Agent execution failed: {}", e);
+            record_output(
+                &app,
+                &execution_id,
+                &request.agent_name,
+                &e.to_string(),
+                OutputStatus::Failed,
+            );
             Ok(AgentExecutionResponse {
                 execution_id,
                 agent_name: request.agent_name,
@@ -470,6 +683,7 @@ pub async fn execute_agent(
  */
 #[tauri::command]
 pub async fn execute_parallel(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     request: ParallelExecutionRequest,
 ) -> Result<Vec<AgentExecutionResponse>, String> {
@@ -488,13 +702,24 @@ pub async fn execute_parallel(
     }
 
     info!("Executing {} agents in parallel", request.agents.len());
+    let request_task = sanitize::task(&request.task)?;
+    let execution_id = new_execution_id(request.execution_id.as_deref());
+    let running = state
+        .executions
+        .start(&execution_id, &request.agents, &state.tools)?;
 
-    // Build task with context if provided
+    // Build task with context if provided, screened for prompt injection
     let task = if let Some(ref context) = request.context {
-        format!("{}\n\nContext:\n{}", request.task, context)
+        let session = request
+            .workspace_path
+            .as_deref()
+            .zip(request.session_id.as_deref());
+        let context = screen_context(&app, &state, "agent context", context, session);
+        format!("{}\n\nContext:\n{}", request_task, context)
     } else {
-        request.task.clone()
+        request_task
     };
+    let task = sanitize::prompt(&task, sanitize::MAX_PROMPT_CHARS);
 
     // Create coordinator and executor for this execution
     let config = match AIT42Config::load() {
@@ -513,12 +738,27 @@ pub async fn execute_parallel(
     let mut executor = AgentExecutor::new(coordinator);
     let mode = ExecutionMode::Parallel(request.agents.clone());
 
-    match executor.execute(mode, &task).await {
+    let outcome = tokio::select! {
+        outcome = executor.execute(mode, &task) => outcome,
+        _ = running.cancelled() => {
+            info!("Parallel execution {} cancelled", execution_id);
+            return Err(format!("Execution {} was cancelled", execution_id));
+        }
+    };
+
+    match outcome {
         Ok(results) => {
             let responses: Vec<AgentExecutionResponse> = results
                 .into_iter()
                 .map(|result| {
                     let execution_id = uuid::Uuid::new_v4().to_string();
+                    record_output(
+                        &app,
+                        &execution_id,
+                        &result.agent_name,
+                        &result.output,
+                        output_status(&result.status),
+                    );
                     AgentExecutionResponse {
                         execution_id,
                         agent_name: result.agent_name.clone(),
@@ -538,39 +778,221 @@ pub async fn execute_parallel(
     }
 }
 
+/// Scan `content` for prompt injection before it is sent to an agent
+///
+/// Applies `ait42.injection_scan`, warns the user about findings and records
+/// the scan on the session, given as `(workspace_path, session_id)`. Returns
+/// the content to send.
+pub(crate) fn screen_context(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    source: &str,
+    content: &str,
+    session: Option<(&str, &str)>,
+) -> String {
+    let config = state
+        .config
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .ait42
+        .injection_scan
+        .clone();
+    let (screened, scan) = injection::guard(source, content, &config);
+    let Some(scan) = scan else {
+        return screened;
+    };
+
+    if !scan.is_clean() {
+        warn!("{}", scan.summary());
+        state.notifications.notify(
+            app,
+            NotificationLevel::Warning,
+            "injection",
+            scan.summary(),
+            vec![],
+        );
+    }
+    if let Some((workspace_path, session_id)) = session {
+        if let Err(e) =
+            session_history::record_context_scan(state, workspace_path, session_id, scan)
+        {
+            warn!("Failed to record context scan on session {}: {}", session_id, e);
+        }
+    }
+    screened
+}
+
+/// ID of a new execution, `requested` by the caller or generated
+fn new_execution_id(requested: Option<&str>) -> String {
+    requested
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+fn output_status(status: &SessionStatus) -> OutputStatus {
+    match status {
+        SessionStatus::Failed(_) => OutputStatus::Failed,
+        _ => OutputStatus::Completed,
+    }
+}
+
+/// Keep the output of a finished execution for `get_agent_output`
+fn record_output(
+    app: &tauri::AppHandle,
+    execution_id: &str,
+    agent_name: &str,
+    output: &str,
+    status: OutputStatus,
+) {
+    let state = app.state::<AppState>();
+    if let Err(e) = state.agent_output.start(execution_id, agent_name) {
+        warn!("{}", e);
+        return;
+    }
+    agent_output::publish(app, execution_id, output, status);
+}
+
 /**
  * Get output from a running or completed agent execution
+ *
+ * Only the most recent output of long executions is kept.
  */
 #[tauri::command]
 pub async fn get_agent_output(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     execution_id: String,
 ) -> Result<AgentExecutionResponse, String> {
-    // TODO: Integrate with ait42-ait42::stream for real-time output
-    // For now, return a mock response
+    let replay = state
+        .agent_output
+        .replay(&execution_id, None)
+        .ok_or_else(|| format!("No output for execution {}", execution_id))?;
     Ok(AgentExecutionResponse {
         execution_id,
-        agent_name: "code-reviewer".to_string(),
-        status: "completed".to_string(),
-        output: Some("Code review completed successfully.".to_string()),
+        agent_name: replay.agent_name,
+        status: replay.status.as_str().to_string(),
+        output: Some(replay.chunks.iter().map(|c| c.output.as_str()).collect()),
         error: None,
     })
 }
 
 /**
- * Cancel a running agent execution
+ * Start listening to the `agent-output` events of an execution
+ *
+ * Returns the output after chunk `after_seq` (all kept output if `None`),
+ * so a listener that reconnects passes the last `seq` it handled. Agents
+ * running in an `ait42-` tmux session started elsewhere are followed from
+ * their first subscription.
  */
 #[tauri::command]
-pub async fn cancel_agent_execution(
-    _state: State<'_, AppState>,
+pub async fn subscribe_agent_output(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
     execution_id: String,
+    after_seq: Option<u64>,
+) -> Result<OutputReplay, String> {
+    if !state.agent_output.contains(&execution_id) && execution_id.starts_with("ait42-") {
+        let alive = state
+            .tools
+            .command("tmux")
+            .arg("has-session")
+            .arg("-t")
+            .arg(&execution_id)
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if alive {
+            agent_output::follow_tmux_session(
+                &app,
+                &execution_id,
+                &session_agent_name(&execution_id),
+            )?;
+        }
+    }
+
+    state
+        .agent_output
+        .subscribe(&execution_id, after_seq)
+        .ok_or_else(|| format!("No output for execution {}", execution_id))
+}
+
+/**
+ * Acknowledge the `agent-output` chunks of an execution up to `seq`
+ *
+ * Output beyond a window of unacknowledged chunks is held back and merged
+ * until the listener acknowledges what it has handled.
+ */
+#[tauri::command]
+pub async fn ack_agent_output(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    execution_id: String,
+    seq: u64,
 ) -> Result<(), String> {
-    // TODO: Integrate with ait42-ait42::executor for cancellation
-    // For now, just return success
-    tracing::info!("Cancelled agent execution: {}", execution_id);
+    let chunks = state.agent_output.ack(&execution_id, seq)?;
+    agent_output::emit_chunks(&app, chunks);
     Ok(())
 }
 
+/**
+ * Cancel a running agent execution
+ *
+ * `execution_id` is the ID an `execute_agent` or `execute_parallel` call
+ * runs under, or an `ait42-` tmux session. The command awaiting the
+ * execution returns, and the tmux sessions of its agents are killed. With
+ * `workspace_path`, the session history instances that ran in those tmux
+ * sessions are marked cancelled.
+ *
+ * Returns the tmux sessions killed.
+ */
+#[tauri::command]
+pub async fn cancel_agent_execution(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    execution_id: String,
+    workspace_path: Option<String>,
+) -> Result<Vec<String>, String> {
+    let killed = match state.executions.cancel(&execution_id, &state.tools) {
+        Some(killed) => killed,
+        // A session from create_tmux_session, or one started before a restart
+        None if execution_id.starts_with("ait42-") => {
+            if !executions::kill_session(&state.tools, &execution_id) {
+                return Err(format!("Execution {} is not running", execution_id));
+            }
+            if state.agent_output.contains(&execution_id) {
+                agent_output::publish(&app, &execution_id, "", OutputStatus::Cancelled);
+            }
+            vec![execution_id.clone()]
+        }
+        None => return Err(format!("Execution {} is not running", execution_id)),
+    };
+
+    if let Some(workspace_path) = workspace_path.filter(|p| !p.trim().is_empty()) {
+        session_history::mark_cancelled(&state, &workspace_path, &killed)?;
+    }
+    info!(
+        "Cancelled agent execution {} ({} tmux sessions killed)",
+        execution_id,
+        killed.len()
+    );
+    Ok(killed)
+}
+
+/**
+ * List the alerts raised on agent output, oldest first
+ *
+ * With `execution_id`, only the alerts of that execution or competition
+ * instance's tmux session. Alerts are also emitted as `agent-alert` events
+ * when raised; listing them restores the warning badges after a reload.
+ */
+#[tauri::command]
+pub async fn list_agent_alerts(
+    state: State<'_, AppState>,
+    execution_id: Option<String>,
+) -> Result<Vec<AgentAlert>, String> {
+    Ok(state.output_alerts.list(execution_id.as_deref()))
+}
+
 //
 // ============================================================
 // Tmux Session Management
@@ -578,7 +1000,7 @@ pub async fn cancel_agent_execution(
 //
 
 /// Tmux session information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TmuxSession {
     pub session_id: String,
     pub agent_name: String,
@@ -587,7 +1009,7 @@ pub struct TmuxSession {
 }
 
 /// Tmux execution request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TmuxExecutionRequest {
     pub agent_name: String,
     pub task: String,
@@ -595,9 +1017,13 @@ pub struct TmuxExecutionRequest {
 }
 
 /// Create a tmux session for agent execution
+///
+/// The session's output is streamed as `agent-output` events, with the
+/// session ID as execution ID.
 #[tauri::command]
 pub async fn create_tmux_session(
-    _state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
     request: TmuxExecutionRequest,
 ) -> Result<TmuxSession, String> {
     let timestamp = std::time::SystemTime::now()
@@ -605,10 +1031,10 @@ pub async fn create_tmux_session(
         .map_err(|e| e.to_string())?
         .as_secs();
 
-    let session_id = format!("ait42-{}-{}", request.agent_name, timestamp);
+    let task = sanitize::task(&request.task)?;
 
     // Check if tmux is available
-    let tmux_check = Command::new("tmux").arg("-V").output();
+    let tmux_check = state.tools.command("tmux").arg("-V").output();
 
     match tmux_check {
         Err(_) => return Err("Tmux is not installed or not in PATH".to_string()),
@@ -616,16 +1042,18 @@ pub async fn create_tmux_session(
         _ => {}
     }
 
+    let current_dir = std::env::current_dir().map_err(|e| e.to_string())?;
+    let session_id = NameAllocator::new(&state.tools, &current_dir)
+        .session(&format!("ait42-{}-{}", request.agent_name, timestamp))?;
+
     // Create new tmux session
-    let mut cmd = Command::new("tmux");
+    let mut cmd = state.tools.command("tmux");
     cmd.arg("new-session")
         .arg("-d") // Detached
         .arg("-s")
         .arg(&session_id)
         .arg("-c")
-        .arg(std::env::current_dir().map_err(|e| e.to_string())?)
-        .arg("echo")
-        .arg(format!("🚀 Starting agent: {} for task: {}", request.agent_name, request.task));
+        .arg(&current_dir);
 
     let output = cmd.output().map_err(|e| e.to_string())?;
 
@@ -638,6 +1066,27 @@ pub async fn create_tmux_session(
 
     tracing::info!("Created tmux session: {}", session_id);
 
+    // Capture the pane before anything is written to it
+    agent_output::follow_tmux_session(&app, &session_id, &request.agent_name)?;
+    let banner = format!("🚀 Starting agent: {} for task: {}", request.agent_name, task);
+    let output = state
+        .tools
+        .command("tmux")
+        .arg("send-keys")
+        .arg("-t")
+        .arg(&session_id)
+        .arg(format!("echo '{}'", escape_single_quotes(&banner)))
+        .arg("Enter")
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        warn!(
+            "Failed to start agent in {}: {}",
+            session_id,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     Ok(TmuxSession {
         session_id,
         agent_name: request.agent_name,
@@ -648,8 +1097,10 @@ pub async fn create_tmux_session(
 
 /// List all AIT42 tmux sessions
 #[tauri::command]
-pub async fn list_tmux_sessions(_state: State<'_, AppState>) -> Result<Vec<TmuxSession>, String> {
-    let output = Command::new("tmux")
+pub async fn list_tmux_sessions(state: State<'_, AppState>) -> Result<Vec<TmuxSession>, String> {
+    let output = state
+        .tools
+        .command("tmux")
         .arg("list-sessions")
         .arg("-F")
         .arg("#{session_name}")
@@ -664,33 +1115,36 @@ pub async fn list_tmux_sessions(_state: State<'_, AppState>) -> Result<Vec<TmuxS
     let sessions: Vec<TmuxSession> = sessions_output
         .lines()
         .filter(|line| line.starts_with("ait42-"))
-        .map(|session_name| {
-            let parts: Vec<&str> = session_name.split('-').collect();
-            let agent_name = if parts.len() >= 3 {
-                parts[1..parts.len() - 1].join("-")
-            } else {
-                "unknown".to_string()
-            };
-
-            TmuxSession {
-                session_id: session_name.to_string(),
-                agent_name,
-                status: "running".to_string(),
-                created_at: chrono::Utc::now().to_rfc3339(),
-            }
+        .map(|session_name| TmuxSession {
+            session_id: session_name.to_string(),
+            agent_name: session_agent_name(session_name),
+            status: "running".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
         })
         .collect();
 
     Ok(sessions)
 }
 
+/// Agent of an `ait42-<agent>-<timestamp>` session
+fn session_agent_name(session_name: &str) -> String {
+    let parts: Vec<&str> = session_name.split('-').collect();
+    if parts.len() >= 3 {
+        parts[1..parts.len() - 1].join("-")
+    } else {
+        "unknown".to_string()
+    }
+}
+
 /// Capture output from a tmux session
 #[tauri::command]
 pub async fn capture_tmux_output(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     session_id: String,
 ) -> Result<String, String> {
-    let output = Command::new("tmux")
+    let output = state
+        .tools
+        .command("tmux")
         .arg("capture-pane")
         .arg("-p")
         .arg("-t")
@@ -713,11 +1167,13 @@ pub async fn capture_tmux_output(
 /// Send keys/command to a tmux session
 #[tauri::command]
 pub async fn send_tmux_keys(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     session_id: String,
     keys: String,
 ) -> Result<(), String> {
-    let output = Command::new("tmux")
+    let output = state
+        .tools
+        .command("tmux")
         .arg("send-keys")
         .arg("-t")
         .arg(&session_id)
@@ -734,12 +1190,23 @@ pub async fn send_tmux_keys(
 }
 
 /// Kill a tmux session
+///
+/// Requires a `confirmation` token from `request_confirmation` for the session.
 #[tauri::command]
 pub async fn kill_tmux_session(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     session_id: String,
+    confirmation: Option<String>,
 ) -> Result<(), String> {
-    let output = Command::new("tmux")
+    state.confirmations.consume(
+        confirmation.as_deref(),
+        &DangerousAction::KillTmuxSession {
+            session_id: session_id.clone(),
+        },
+    )?;
+    let output = state
+        .tools
+        .command("tmux")
         .arg("kill-session")
         .arg("-t")
         .arg(&session_id)
@@ -760,12 +1227,57 @@ pub async fn kill_tmux_session(
 // ============================================================
 //
 
+/// How often agent tmux sessions are checked
+const SESSION_POLL_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_millis(500);
+
+/// Output of a finished instance sent with its final event, from the end of
+/// its log
+const MAX_FINAL_OUTPUT_BYTES: u64 = 1024 * 1024;
+
 /// Monitor tmux session output and emit events to frontend
+///
+/// New output is matched against the alert rules as it arrives.
 async fn monitor_tmux_session(
     app: tauri::AppHandle,
+    tools: ToolRegistry,
+    competition_id: String,
     session_id: String,
     instance_number: usize,
     log_file_path: String,
+) {
+    watch_tmux_session(
+        &tools,
+        &session_id,
+        instance_number,
+        &log_file_path,
+        SESSION_POLL_INTERVAL,
+        |payload| {
+            // The final payload repeats the end of the log, already matched
+            let (output, status) = match payload["status"].as_str() {
+                Some("running") => {
+                    (payload["output"].as_str().unwrap_or(""), OutputStatus::Running)
+                }
+                _ => ("", OutputStatus::Completed),
+            };
+            output_alerts::check(&app, &session_id, Some(instance_number), output, status);
+            emit_session_event(&app, &competition_id, "competition-output", payload)
+        },
+    )
+    .await;
+}
+
+/// Follow a competition instance's tmux session until it ends
+///
+/// `emit` receives a `competition-output` payload for each batch of new
+/// output while the session runs, and one with the end of the log once it
+/// ends; the log is compressed then.
+async fn watch_tmux_session(
+    tools: &ToolRegistry,
+    session_id: &str,
+    instance_number: usize,
+    log_file_path: &str,
+    poll_interval: tokio::time::Duration,
+    mut emit: impl FnMut(serde_json::Value) -> tauri::Result<()>,
 ) {
     tracing::info!(
         "🔍 Starting monitoring for session {} (instance {})",
@@ -775,119 +1287,76 @@ async fn monitor_tmux_session(
 
     let mut last_output = String::new();
     let mut last_line_count = 0;
-    let mut last_log_size = 0;
-
-    // Helper function to strip ANSI codes using regex-like approach
-    // Remove ANSI escape sequences: \x1b[...m, \x1b[?..., etc.
-    let strip_ansi = |text: &str| -> String {
-        let mut result = String::with_capacity(text.len());
-        let mut chars = text.chars().peekable();
-
-        while let Some(ch) = chars.next() {
-            if ch == '\x1b' {
-                // Skip ANSI escape sequence
-                if let Some(&'[') = chars.peek() {
-                    chars.next(); // consume '['
-                                  // Skip until we find a letter (end of escape sequence)
-                    while let Some(&next_ch) = chars.peek() {
-                        if next_ch.is_alphabetic() || next_ch == 'm' {
-                            chars.next();
-                            break;
-                        }
-                        chars.next();
-                    }
-                } else if let Some(&'%') = chars.peek() {
-                    chars.next(); // consume '%'
-                } else if let Some(&'(') = chars.peek() {
-                    chars.next(); // consume '('
-                } else if let Some(&')') = chars.peek() {
-                    chars.next(); // consume ')'
-                } else if let Some(&'#') = chars.peek() {
-                    chars.next(); // consume '#'
-                } else if let Some(&'\\') = chars.peek() {
-                    chars.next(); // consume '\'
-                } else {
-                    // Not an ANSI sequence, keep the character
-                    result.push(ch);
-                }
-            } else {
-                result.push(ch);
-            }
-        }
-
-        result
-    };
+    let mut log = LogTail::new(Path::new(log_file_path));
+    let mut first_emission = true;
 
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        tokio::time::sleep(poll_interval).await;
 
         // Check if session still exists
-        let check_output = Command::new("tmux")
+        let check_output = tools
+            .command("tmux")
             .arg("has-session")
             .arg("-t")
-            .arg(&session_id)
+            .arg(session_id)
             .output();
 
         match check_output {
             Ok(output) if output.status.success() => {
                 // Session exists, try to read from log file first (more reliable for Claude output)
-                if let Ok(log_contents) = tokio::fs::read_to_string(&log_file_path).await {
-                    let log_size = log_contents.len();
+                if tokio::fs::try_exists(log_file_path).await.unwrap_or(false) {
+                    // Only what was appended since the last poll is read
+                    let new_content = log.read().await;
 
-                    if log_size > last_log_size {
-                        // New content in log file
-                        let new_content = &log_contents[last_log_size..];
+                    if !new_content.trim().is_empty() {
+                        // Strip ANSI codes before sending
+                        let cleaned_content = strip_ansi(&new_content);
 
-                        if !new_content.trim().is_empty() {
-                            // Strip ANSI codes before sending
-                            let cleaned_content = strip_ansi(new_content);
-
-                            // First event emission timing log
-                            if last_log_size == 0 {
-                                tracing::info!(
-                                    "🕐 First event emission for instance {} at {:?}",
-                                    instance_number,
-                                    std::time::SystemTime::now()
-                                );
-                            }
-
-                            let payload = serde_json::json!({
-                                "instance": instance_number,
-                                "output": cleaned_content,
-                                "status": "running"
-                            });
-
-                            // Log the exact payload being sent for debugging
+                        // First event emission timing log
+                        if first_emission {
+                            first_emission = false;
                             tracing::info!(
-                                "📤 Preparing to emit event 'competition-output' (incremental) with payload: instance={}, output_length={}, status=\"running\", preview=\"{}...\"",
+                                "🕐 First event emission for instance {} at {:?}",
                                 instance_number,
-                                cleaned_content.len(),
-                                cleaned_content.chars().take(50).collect::<String>().replace('\n', "\\n")
+                                std::time::SystemTime::now()
                             );
-
-                            match app.emit_all("competition-output", payload.clone()) {
-                                Ok(_) => tracing::info!(
-                                    "✅ Sent {} bytes (incremental) for instance {}",
-                                    cleaned_content.len(),
-                                    instance_number
-                                ),
-                                Err(e) => tracing::error!(
-                                    "❌ Failed to emit incremental output for instance {}: {}",
-                                    instance_number,
-                                    e
-                                ),
-                            }
                         }
 
-                        last_log_size = log_size;
+                        let payload = serde_json::json!({
+                            "instance": instance_number,
+                            "output": cleaned_content,
+                            "status": "running"
+                        });
+
+                        // Log the exact payload being sent for debugging
+                        tracing::info!(
+                            "📤 Preparing to emit event 'competition-output' (incremental) with payload: instance={}, output_length={}, status=\"running\", preview=\"{}...\"",
+                            instance_number,
+                            cleaned_content.len(),
+                            cleaned_content.chars().take(50).collect::<String>().replace('\n', "\\n")
+                        );
+
+                        match emit(payload.clone()) {
+                            Ok(_) => tracing::info!(
+                                "✅ Sent {} bytes (incremental) for instance {}",
+                                cleaned_content.len(),
+                                instance_number
+                            ),
+                            Err(e) => tracing::error!(
+                                "❌ Failed to emit incremental output for instance {}: {}",
+                                instance_number,
+                                e
+                            ),
+                        }
                     }
                 } else {
                     // Fallback to tmux capture-pane if log file not available yet
-                    let capture_output = Command::new("tmux")
+                    let capture_output = tools
+                        .command("tmux")
                         .arg("capture-pane")
                         .arg("-p")
                         .arg("-t")
-                        .arg(&session_id)
+                        .arg(session_id)
                         .arg("-S")
                         .arg("-")
                         .output();
@@ -915,7 +1384,7 @@ async fn monitor_tmux_session(
                                         "status": "running"
                                     });
 
-                                    match app.emit_all("competition-output", payload) {
+                                    match emit(payload) {
                                         Ok(_) => tracing::debug!(
                                             "📤 Sent {} bytes (tmux fallback) for instance {}",
                                             content_len,
@@ -939,8 +1408,10 @@ async fn monitor_tmux_session(
                 // Session no longer exists - completed or failed
                 tracing::info!("Tmux session {} has ended", session_id);
 
-                // Send final output from log file (ALWAYS send full content on completion)
-                match tokio::fs::read_to_string(&log_file_path).await {
+                // Send final output from log file, up to its last MAX_FINAL_OUTPUT_BYTES
+                let final_read =
+                    instance_log::read_last(Path::new(log_file_path), MAX_FINAL_OUTPUT_BYTES).await;
+                match final_read {
                     Ok(final_output) => {
                         if !final_output.trim().is_empty() {
                             // Strip ANSI codes before sending
@@ -956,7 +1427,7 @@ async fn monitor_tmux_session(
                             tracing::info!("📤 Emitting event 'competition-output': instance={}, output_len={}, status=completed",
                                 instance_number, cleaned_output.len());
 
-                            match app.emit_all("competition-output", payload) {
+                            match emit(payload) {
                                 Ok(_) => tracing::info!(
                                     "✅ Sent final output for instance {} ({} bytes)",
                                     instance_number,
@@ -977,7 +1448,7 @@ async fn monitor_tmux_session(
                                 "output": "⚠️ No output captured",
                                 "status": "completed"
                             });
-                            let _ = app.emit_all("competition-output", payload);
+                            let _ = emit(payload);
                         }
                     }
                     Err(e) => {
@@ -994,9 +1465,12 @@ async fn monitor_tmux_session(
                             "status": "error",
                             "error": e.to_string()
                         });
-                        let _ = app.emit_all("competition-output", payload);
+                        let _ = emit(payload);
                     }
                 }
+                if let Err(e) = instance_log::compress(Path::new(log_file_path)).await {
+                    tracing::warn!("Failed to compress log of instance {}: {}", instance_number, e);
+                }
 
                 break;
             }
@@ -1034,9 +1508,7 @@ pub async fn execute_multi_runtime_competition(
     state: State<'_, AppState>,
     request: MultiRuntimeCompetitionRequest,
 ) -> Result<ClaudeCodeCompetitionResult, String> {
-    if request.task.trim().is_empty() {
-        return Err("Task cannot be empty".to_string());
-    }
+    let task = sanitize::task(&request.task)?;
 
     if request.allocations.is_empty() {
         return Err("At least one runtime allocation is required".to_string());
@@ -1044,7 +1516,14 @@ pub async fn execute_multi_runtime_competition(
 
     let mode = match request.mode.to_lowercase().as_str() {
         "competition" => "competition".to_string(),
-        "ensemble" => "ensemble".to_string(),
+        "ensemble" => {
+            let config = state
+                .config
+                .lock()
+                .map_err(|e| format!("Failed to lock config: {}", e))?;
+            feature_flags::require(&config, feature_flags::ENSEMBLE_MODE)?;
+            "ensemble".to_string()
+        }
         other => return Err(format!("Invalid mode: {}", other)),
     };
 
@@ -1082,7 +1561,7 @@ pub async fn execute_multi_runtime_competition(
     run_multi_runtime_competition(
         app_handle,
         state,
-        request.task,
+        task,
         mode,
         timeout_seconds,
         request.preserve_worktrees,
@@ -1103,6 +1582,14 @@ async fn run_multi_runtime_competition(
     let competition_id = uuid::Uuid::new_v4().to_string();
     let short_id = &competition_id[..8];
     let total_instances = plans.len();
+
+    authorize_spend(
+        &app_handle,
+        &state,
+        Operation::Competition,
+        Some(&competition_id),
+        |policy| TokenEstimate::agent_runs(&task, total_instances, policy),
+    )?;
     let task_preview: String = task.chars().take(80).collect();
 
     tracing::info!(
@@ -1202,23 +1689,30 @@ async fn run_multi_runtime_competition(
 
     let enhanced_task = format!(
         "{task}\n\n{instructions}",
-        task = task,
+        task = sanitize::prompt(&task, sanitize::MAX_PROMPT_CHARS),
         instructions = "あなたはこのタスクを完遂する開発者です。以下の手順で実行してください：\n\\
 1. タスクの要件を分析\n\\
 2. 必要なファイルやコードを特定\n\\
 3. 具体的な実装を提案・実行\n\\
 4. テストと検証\n\n質問はせず、直接実装してください。"
     );
-    let escaped_task = escape_for_shell(&enhanced_task);
+    let escaped_task = sanitize::echo_escaped(&enhanced_task);
 
+    let mut names = NameAllocator::new(&state.tools, &project_root);
     let mut instances = Vec::new();
 
     for (idx, plan) in plans.iter().enumerate() {
         let instance_number = idx + 1;
         let instance_id = format!("{}-instance-{}", competition_id, instance_number);
-        let worktree_path = competition_dir.join(format!("instance-{}", instance_number));
-        let branch_name =
-            format!("{}-{}-{}-{}", plan.runtime.as_str(), mode, short_id, instance_number);
+        let worktree_path =
+            names.directory(&competition_dir.join(format!("instance-{}", instance_number)))?;
+        let branch_name = names.branch(&format!(
+            "{}-{}-{}-{}",
+            plan.runtime.as_str(),
+            mode,
+            short_id,
+            instance_number
+        ))?;
 
         tracing::info!(
             "Creating worktree {} (runtime: {}) at {}",
@@ -1227,7 +1721,7 @@ async fn run_multi_runtime_competition(
             worktree_path.display()
         );
 
-        let mut cmd = Command::new("git");
+        let mut cmd = state.tools.command("git");
         cmd.arg("worktree")
             .arg("add")
             .arg("-b")
@@ -1281,8 +1775,13 @@ async fn run_multi_runtime_competition(
             }
         }
 
-        let session_id =
-            format!("ait42-{}-{}-{}-{}", plan.runtime.as_str(), mode, short_id, instance_number);
+        let session_id = names.session(&format!(
+            "ait42-{}-{}-{}-{}",
+            plan.runtime.as_str(),
+            mode,
+            short_id,
+            instance_number
+        ))?;
         let output_log_path = worktree_path.join(format!(
             ".{}-output-{}.log",
             plan.runtime.log_suffix(),
@@ -1290,7 +1789,9 @@ async fn run_multi_runtime_competition(
         ));
         let output_log_path_str = output_log_path.to_string_lossy().to_string();
 
-        let tmux_output = Command::new("tmux")
+        let tmux_output = state
+            .tools
+            .command("tmux")
             .arg("new-session")
             .arg("-d")
             .arg("-s")
@@ -1305,7 +1806,9 @@ async fn run_multi_runtime_competition(
             return Err(format!("Failed to create tmux session {}: {}", instance_number, error));
         }
 
-        let pipe_output = Command::new("tmux")
+        let pipe_output = state
+            .tools
+            .command("tmux")
             .arg("pipe-pane")
             .arg("-t")
             .arg(&session_id)
@@ -1327,7 +1830,9 @@ async fn run_multi_runtime_competition(
             plan.runtime
                 .build_command(&escaped_task, &plan.model, &source_ait42)?;
 
-        let send_output = Command::new("tmux")
+        let send_output = state
+            .tools
+            .command("tmux")
             .arg("send-keys")
             .arg("-t")
             .arg(&session_id)
@@ -1350,8 +1855,21 @@ async fn run_multi_runtime_competition(
             instance_number,
             session_id
         );
+        session_journal::record_instance_started(
+            &state,
+            &competition_id,
+            JournalEvent::InstanceStarted {
+                instance_id: instance_number as u32,
+                agent_name: plan.runtime.as_str().to_string(),
+                worktree_path: worktree_path.to_string_lossy().to_string(),
+                tmux_session_id: session_id.clone(),
+                log: Some(output_log_path_str.clone()),
+            },
+        );
 
         let app = app_handle.clone();
+        let monitor_tools = state.tools.clone();
+        let monitor_competition_id = competition_id.clone();
         let monitor_session_id = session_id.clone();
         let monitor_log_path = output_log_path_str.clone();
         let ready_signal_clone = Arc::clone(&ready_signal_received);
@@ -1389,13 +1907,22 @@ async fn run_multi_runtime_competition(
                 std::time::SystemTime::now()
             );
 
-            monitor_tmux_session(app, monitor_session_id, instance_number, monitor_log_path).await;
+            monitor_tmux_session(
+                app,
+                monitor_tools,
+                monitor_competition_id,
+                monitor_session_id,
+                instance_number,
+                monitor_log_path,
+            )
+            .await;
         });
 
         instances.push(ClaudeCodeInstanceResult {
             instance_id,
             instance_number,
             worktree_path: worktree_path.to_string_lossy().to_string(),
+            branch: branch_name,
             tmux_session_id: session_id,
             status: "running".to_string(),
             output: String::new(),
@@ -1446,70 +1973,110 @@ pub async fn cancel_competition(
     competition_id: String,
     cleanup_worktrees: bool,
 ) -> Result<(), String> {
+    state.session_streams.ensure_writable(&competition_id)?;
     tracing::info!("Cancelling competition: {}", competition_id);
 
+    let working_dir = state.working_dir.lock().await;
+    let project_root = working_dir.clone();
+    drop(working_dir);
+
+    // Sessions and worktrees the competition recorded, under the names
+    // actually allocated for them
+    let recorded =
+        session_history::find_session(&state, &project_root.to_string_lossy(), &competition_id)
+            .map(|session| session.instances)
+            .unwrap_or_default();
+
     // Kill all tmux sessions for this competition
     let session_pattern = format!("claude-code-comp-{}", &competition_id[..8]);
     let competition_short = &competition_id[..8];
+    let mut sessions: Vec<String> = recorded
+        .iter()
+        .map(|instance| instance.tmux_session_id.clone())
+        .filter(|id| !id.is_empty())
+        .collect();
 
-    let list_output = Command::new("tmux")
+    let list_output = state
+        .tools
+        .command("tmux")
         .arg("list-sessions")
         .arg("-F")
         .arg("#{session_name}")
         .output()
         .map_err(|e| e.to_string())?;
 
+    // Fall back to name patterns for sessions that were never recorded
     if list_output.status.success() {
-        let sessions = String::from_utf8_lossy(&list_output.stdout);
-        for session in sessions.lines() {
+        for session in String::from_utf8_lossy(&list_output.stdout).lines() {
             let matches_legacy = session.contains(&session_pattern);
             let matches_multi =
                 session.starts_with("ait42-") && session.contains(competition_short);
 
-            if matches_legacy || matches_multi {
-                let _ = Command::new("tmux")
-                    .arg("kill-session")
-                    .arg("-t")
-                    .arg(session)
-                    .output();
-
-                tracing::info!("Killed session: {}", session);
+            if (matches_legacy || matches_multi) && !sessions.iter().any(|s| s == session) {
+                sessions.push(session.to_string());
             }
         }
     }
 
+    for session in &sessions {
+        let killed = state
+            .tools
+            .command("tmux")
+            .arg("kill-session")
+            .arg("-t")
+            .arg(format!("={}", session))
+            .output()
+            .is_ok_and(|output| output.status.success());
+
+        if killed {
+            tracing::info!("Killed session: {}", session);
+        }
+    }
+    if let Err(e) =
+        session_history::mark_cancelled(&state, &project_root.to_string_lossy(), &sessions)
+    {
+        tracing::warn!("Failed to mark competition {} cancelled: {}", competition_id, e);
+    }
+
     // Cleanup worktrees if requested
     if cleanup_worktrees {
-        // Get project root for worktree cleanup
-        let working_dir = state.working_dir.lock().await;
-        let project_root = working_dir.clone();
-        drop(working_dir);
-
         // 🔥 FIX: Use src-tauri/.worktrees to match creation path (line 1126)
         let ait42_worktrees = project_root.join("src-tauri").join(".worktrees");
         let competition_dir =
             format!("{}/competition-{}", ait42_worktrees.display(), &competition_id[..8]);
 
-        // Remove all worktrees
+        // Remove the recorded worktrees and anything else left in the directory
+        let mut worktrees: Vec<PathBuf> = recorded
+            .iter()
+            .map(|instance| PathBuf::from(&instance.worktree_path))
+            .filter(|path| path.starts_with(&competition_dir))
+            .collect();
         if let Ok(entries) = std::fs::read_dir(&competition_dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.is_dir() {
-                    let path_str = path.to_string_lossy().to_string();
-
-                    // Remove git worktree
-                    let _ = Command::new("git")
-                        .arg("worktree")
-                        .arg("remove")
-                        .arg("--force")
-                        .arg(&path_str)
-                        .output();
-
-                    tracing::info!("Removed worktree: {}", path_str);
+                if path.is_dir() && !worktrees.contains(&path) {
+                    worktrees.push(path);
                 }
             }
         }
 
+        for path in &worktrees {
+            let path_str = path.to_string_lossy().to_string();
+
+            // Remove git worktree
+            let _ = state
+                .tools
+                .command("git")
+                .arg("worktree")
+                .arg("remove")
+                .arg("--force")
+                .arg(&path_str)
+                .current_dir(&project_root)
+                .output();
+
+            tracing::info!("Removed worktree: {}", path_str);
+        }
+
         // Remove competition directory
         let _ = std::fs::remove_dir_all(&competition_dir);
         tracing::info!("Cleaned up competition directory: {}", competition_dir);
@@ -1525,7 +2092,7 @@ pub async fn cancel_competition(
 //
 
 /// Role definition for debate participants
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleDefinition {
     pub id: String,
@@ -1533,30 +2100,209 @@ pub struct RoleDefinition {
     pub system_prompt: String,
 }
 
+/// Participants a debate accepts
+const DEBATE_PARTICIPANTS: std::ops::RangeInclusive<usize> = 2..=6;
+
+/// Debate rounds a request may ask for, not counting the synthesis round
+const DEBATE_ROUNDS: std::ops::RangeInclusive<u8> = 1..=5;
+
+/// Prompt of the first round, before there is anything to respond to
+const FIRST_ROUND_TEMPLATE: &str = "{role_prompt}\n\n{task}";
+
+/// Prompt of the later rounds
+const ROUND_TEMPLATE: &str =
+    "{role_prompt}\n\n--- Previous Round Context ---\n{context}\n\n--- Your Task ---\n{task}";
+
+/// Prompt of the synthesis round
+const SYNTHESIS_TEMPLATE: &str = "You moderated a debate between {participants} on the task \
+     below. Merge their positions into one final answer: state what they agree on, settle \
+     their disagreements with reasons, and give a concrete plan.\n\n\
+     --- Debate ---\n{context}\n\n--- Task ---\n{task}";
+
+pub(crate) fn default_debate_rounds() -> u8 {
+    2
+}
+
+fn default_synthesis() -> bool {
+    true
+}
+
 /// Debate execution request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DebateRequest {
     pub task: String,
-    pub roles: Vec<RoleDefinition>, // 3 roles (Architect, Pragmatist, Innovator)
+    pub roles: Vec<RoleDefinition>, // 2-6 roles (e.g. Architect, Pragmatist, Innovator)
     pub model: String,              // "sonnet", "haiku", "opus"
     pub timeout_seconds: u64,       // Per-round timeout (default: 800s = 13.3 min)
     pub preserve_worktrees: bool,   // Keep worktrees after completion
+    /// Debate rounds, 1-5, before the synthesis round
+    #[serde(default = "default_debate_rounds")]
+    pub rounds: u8,
+    /// Prompt template of each round, in order; rounds without one use the
+    /// default. `{role_prompt}`, `{task}`, `{context}` (the earlier rounds),
+    /// `{round}` and `{rounds}` are filled in.
+    #[serde(default)]
+    pub round_prompts: Vec<String>,
+    /// End with a round in which a moderator merges the positions
+    #[serde(default = "default_synthesis")]
+    pub synthesis: bool,
+    /// Prompt template of the synthesis round; `{participants}`, `{task}`
+    /// and `{context}` are filled in
+    #[serde(default)]
+    pub synthesis_prompt: Option<String>,
+}
+
+impl DebateRequest {
+    fn validate(&self) -> Result<(), String> {
+        if !DEBATE_PARTICIPANTS.contains(&self.roles.len()) {
+            return Err(format!(
+                "Debate mode requires {} to {} roles, got {}",
+                DEBATE_PARTICIPANTS.start(),
+                DEBATE_PARTICIPANTS.end(),
+                self.roles.len()
+            ));
+        }
+        if !DEBATE_ROUNDS.contains(&self.rounds) {
+            return Err(format!(
+                "Debate mode runs {} to {} rounds, got {}",
+                DEBATE_ROUNDS.start(),
+                DEBATE_ROUNDS.end(),
+                self.rounds
+            ));
+        }
+        if self.round_prompts.len() > usize::from(self.rounds) {
+            return Err(format!(
+                "{} round prompts given for {} rounds",
+                self.round_prompts.len(),
+                self.rounds
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rounds including the synthesis round
+    fn total_rounds(&self) -> u8 {
+        self.rounds + u8::from(self.synthesis)
+    }
+
+    /// Agent runs over the whole debate
+    fn agent_runs(&self) -> usize {
+        self.roles.len() * usize::from(self.rounds) + usize::from(self.synthesis)
+    }
+
+    fn is_synthesis(&self, round: u8) -> bool {
+        round > self.rounds
+    }
+
+    /// Status while `round` runs
+    fn round_status(&self, round: u8) -> String {
+        if self.is_synthesis(round) {
+            "synthesis".to_string()
+        } else {
+            format!("round_{}", round)
+        }
+    }
+
+    /// Who speaks in `round`: every role, or the moderator in the synthesis
+    fn participants(&self, round: u8) -> Vec<RoleDefinition> {
+        if !self.is_synthesis(round) {
+            return self.roles.clone();
+        }
+        vec![RoleDefinition {
+            id: "synthesis".to_string(),
+            name: "Moderator".to_string(),
+            system_prompt: String::new(),
+        }]
+    }
+
+    /// Prompt of `role` in `round`, given what was said in earlier rounds
+    fn prompt(
+        &self,
+        round: u8,
+        role: &RoleDefinition,
+        task: &str,
+        context: Option<&str>,
+    ) -> String {
+        let context = context.unwrap_or_default();
+        if self.is_synthesis(round) {
+            let names: Vec<&str> = self.roles.iter().map(|r| r.name.as_str()).collect();
+            let template = self
+                .synthesis_prompt
+                .as_deref()
+                .unwrap_or(SYNTHESIS_TEMPLATE);
+            return fill_template(
+                template,
+                &[
+                    ("participants", &names.join(", ")),
+                    ("task", task),
+                    ("context", context),
+                ],
+            );
+        }
+        let default = if round == 1 {
+            FIRST_ROUND_TEMPLATE
+        } else {
+            ROUND_TEMPLATE
+        };
+        let template = self
+            .round_prompts
+            .get(usize::from(round) - 1)
+            .map(String::as_str)
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(default);
+        fill_template(
+            template,
+            &[
+                ("role_prompt", &role.system_prompt),
+                ("task", task),
+                ("context", context),
+                ("round", &round.to_string()),
+                ("rounds", &self.rounds.to_string()),
+            ],
+        )
+    }
+}
+
+/// Replace each `{name}` in `template` with its value, in one pass so
+/// values containing braces are left alone
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let placeholder = rest[start + 1..]
+            .find('}')
+            .map(|end| &rest[start + 1..start + 1 + end])
+            .and_then(|name| values.iter().find(|(key, _)| *key == name));
+        match placeholder {
+            Some((name, value)) => {
+                filled.push_str(value);
+                rest = &rest[start + name.len() + 2..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
 }
 
 /// Debate execution result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DebateResult {
     pub debate_id: String,
-    pub status: String, // "started", "round_1", "round_2", "round_3", "completed", "failed"
+    pub status: String, // "started", "round_N", "synthesis", "completed", "failed"
     pub message: String,
     pub worktree_path: String,
     pub branch: String,
 }
 
 /// Round output (result from one agent in one round)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RoundOutput {
     pub round: u8,
@@ -1570,14 +2316,20 @@ pub struct RoundOutput {
 }
 
 /// Debate status (complete state)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DebateStatus {
     pub debate_id: String,
-    pub current_round: u8, // 1, 2, or 3
-    pub total_rounds: u8,  // Always 3
-    pub status: String,    // "started", "round_1", "round_2", "round_3", "completed", "failed"
+    pub current_round: u8, // 1 to total_rounds
+    pub total_rounds: u8,  // Debate rounds, plus the synthesis round if any
+    pub status: String,    // "started", "round_N", "synthesis", "completed", "failed"
     pub round_outputs: Vec<RoundOutput>,
+    /// Roles speaking in each debate round
+    #[serde(default)]
+    pub participants: usize,
+    /// Whether the last round is the synthesis round
+    #[serde(default)]
+    pub synthesis: bool,
     pub worktree_path: String,
     pub context_files: Vec<String>,
     pub started_at: String,
@@ -1586,20 +2338,26 @@ pub struct DebateStatus {
 
 /// Execute Claude Code Debate
 ///
-/// Creates a single git worktree and executes 3 rounds of debate sequentially
+/// Creates a single git worktree and executes the debate rounds sequentially,
+/// every role speaking once per round, then the synthesis round if requested
 #[tauri::command]
 pub async fn execute_debate(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    request: DebateRequest,
+    mut request: DebateRequest,
 ) -> Result<DebateResult, String> {
     // Validation
-    if request.roles.len() != 3 {
-        return Err("Debate mode requires exactly 3 roles".to_string());
-    }
+    request.validate()?;
 
-    if request.task.trim().is_empty() {
-        return Err("Task cannot be empty".to_string());
+    request.task = sanitize::task(&request.task)?;
+    for role in &mut request.roles {
+        role.system_prompt = sanitize::text("Role prompt", &role.system_prompt)?;
+    }
+    for template in &mut request.round_prompts {
+        *template = sanitize::text("Round prompt", template)?;
+    }
+    if let Some(template) = &mut request.synthesis_prompt {
+        *template = sanitize::text("Synthesis prompt", template)?;
     }
 
     let valid_models = ["sonnet", "haiku", "opus"];
@@ -1610,9 +2368,15 @@ pub async fn execute_debate(
     let debate_id = uuid::Uuid::new_v4().to_string();
     let started_at = chrono::Utc::now();
 
+    authorize_spend(&app_handle, &state, Operation::Debate, Some(&debate_id), |policy| {
+        TokenEstimate::agent_runs(&request.task, request.agent_runs(), policy)
+    })?;
+
     tracing::info!(
-        "Starting debate: {} with 3 roles, model: {}, task: {}",
+        "Starting debate: {} with {} roles, {} rounds, model: {}, task: {}",
         debate_id,
+        request.roles.len(),
+        request.total_rounds(),
         request.model,
         request.task.chars().take(50).collect::<String>()
     );
@@ -1634,12 +2398,16 @@ pub async fn execute_debate(
     std::fs::create_dir_all(&context_dir).map_err(|e| e.to_string())?;
 
     // Create single worktree for debate
-    let worktree_path = format!("{}/debate-workspace", debate_dir);
-    let branch_name = format!("debate-{}", &debate_id[..8]);
+    let mut names = NameAllocator::new(&state.tools, &project_root);
+    let worktree_path = names
+        .directory(Path::new(&format!("{}/debate-workspace", debate_dir)))?
+        .to_string_lossy()
+        .to_string();
+    let branch_name = names.branch(&format!("debate-{}", &debate_id[..8]))?;
 
     tracing::info!("Creating debate worktree at {}", worktree_path);
 
-    let mut cmd = Command::new("git");
+    let mut cmd = state.tools.command("git");
     cmd.arg("worktree")
         .arg("add")
         .arg("-b")
@@ -1700,9 +2468,11 @@ pub async fn execute_debate(
     let initial_status = DebateStatus {
         debate_id: debate_id.clone(),
         current_round: 0,
-        total_rounds: 3,
+        total_rounds: request.total_rounds(),
         status: "started".to_string(),
         round_outputs: Vec::new(),
+        participants: request.roles.len(),
+        synthesis: request.synthesis,
         worktree_path: worktree_path.clone(),
         context_files: Vec::new(),
         started_at: started_at.to_rfc3339(),
@@ -1725,10 +2495,12 @@ pub async fn execute_debate(
     let context_dir_clone = context_dir.clone();
     let debates_clone = Arc::clone(&state.debates);
     let working_dir_clone = Arc::clone(&state.working_dir);
+    let tools = state.tools.clone();
 
     tauri::async_runtime::spawn(async move {
         if let Err(e) = execute_debate_rounds(
             app,
+            tools,
             debates_clone,
             working_dir_clone,
             debate_id_clone,
@@ -1751,9 +2523,10 @@ pub async fn execute_debate(
     })
 }
 
-/// Execute all 3 debate rounds sequentially
+/// Execute the debate rounds sequentially
 async fn execute_debate_rounds(
     app: tauri::AppHandle,
+    tools: ToolRegistry,
     debates: Arc<Mutex<HashMap<String, DebateStatus>>>,
     working_dir: Arc<tokio::sync::Mutex<std::path::PathBuf>>,
     debate_id: String,
@@ -1761,13 +2534,15 @@ async fn execute_debate_rounds(
     worktree_path: String,
     context_dir: String,
 ) -> Result<(), String> {
-    tracing::info!("Starting 3-round debate execution for {}", debate_id);
+    let total_rounds = request.total_rounds();
+    tracing::info!("Starting {}-round debate execution for {}", total_rounds, debate_id);
 
     // Helper function to update debate status and persist to session history
     let update_status = |round: u8, status: String| {
         let debate_id_clone = debate_id.clone();
         let working_dir_clone = working_dir.clone();
         let debates_clone = debates.clone();
+        let app_clone = app.clone();
 
         // Spawn async task for persistence
         tauri::async_runtime::spawn(async move {
@@ -1824,173 +2599,143 @@ async fn execute_debate_rounds(
                 total_lines_added: Some(0),
                 total_lines_deleted: Some(0),
                 runtime_mix: None,
+                artifacts: Vec::new(),
+                deleted_at: None,
+                feature_flags: None,
+                origin: None,
+                issue: None,
+                agents: None,
+                context_scans: Vec::new(),
+                complexity_class: None,
+                subtask_count: None,
             };
 
-            // Persist to session history using direct file operations
-            use sha2::{Digest, Sha256};
-
-            let mut hasher = Sha256::new();
-            hasher.update(workspace_path.as_bytes());
-            let hash = format!("{:x}", hasher.finalize())[..16].to_string();
-
-            let home_dir = dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("/tmp"));
-            let sessions_file = home_dir
-                .join(".ait42")
-                .join("sessions")
-                .join(format!("{}.json", hash));
-
-            // Load existing sessions
-            let mut sessions: Vec<crate::commands::session_history::WorktreeSession> =
-                if sessions_file.exists() {
-                    if let Ok(content) = std::fs::read_to_string(&sessions_file) {
-                        serde_json::from_str(&content).unwrap_or_else(|_| Vec::new())
-                    } else {
-                        Vec::new()
-                    }
-                } else {
-                    Vec::new()
-                };
-
-            // Update or insert session
-            if let Some(existing) = sessions.iter_mut().find(|s| s.id == session.id) {
-                *existing = session;
-            } else {
-                sessions.push(session);
-            }
-
-            // Save back to file
-            if let Ok(content) = serde_json::to_string_pretty(&sessions) {
-                // Ensure directory exists
-                if let Some(parent) = sessions_file.parent() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
-                if let Err(e) = std::fs::write(&sessions_file, content) {
-                    tracing::error!("Failed to persist debate status: {}", e);
+            // Update or insert session, keeping reports attached to it
+            let state = app_clone.state::<AppState>();
+            let result = session_history::modify_sessions(&state, &workspace_path, |sessions| {
+                if let Some(existing) = sessions.iter_mut().find(|s| s.id == session.id) {
+                    let artifacts = std::mem::take(&mut existing.artifacts);
+                    *existing = session_history::WorktreeSession {
+                        artifacts,
+                        deleted_at: existing.deleted_at.take(),
+                        feature_flags: existing.feature_flags.take(),
+                        origin: existing.origin.take(),
+                        issue: existing.issue.take(),
+                        agents: existing.agents.take(),
+                        context_scans: std::mem::take(&mut existing.context_scans),
+                        complexity_class: existing.complexity_class.take(),
+                        subtask_count: existing.subtask_count,
+                        ..session
+                    };
                 } else {
-                    tracing::info!(
-                        "Persisted debate status update: {} - {}",
-                        debate_id_clone,
-                        status
-                    );
+                    sessions.push(session_history::WorktreeSession {
+                        origin: Some(session_history::session_origin(&workspace_path)),
+                        ..session
+                    });
                 }
+                Ok(())
+            });
+
+            match result {
+                Ok(()) => tracing::info!(
+                    "Persisted debate status update: {} - {}",
+                    debate_id_clone,
+                    status
+                ),
+                Err(e) => tracing::error!("Failed to persist debate status: {}", e),
             }
         });
     };
 
-    // Set global timeout for entire debate (3 rounds * timeout_seconds)
-    let total_timeout = tokio::time::Duration::from_secs(request.timeout_seconds * 3);
+    // Set global timeout for entire debate (rounds * timeout_seconds)
+    let total_timeout =
+        tokio::time::Duration::from_secs(request.timeout_seconds * u64::from(total_rounds));
     let debate_start_time = tokio::time::Instant::now();
 
-    // Round 1: Independent proposals
-    update_status(1, "round_1".to_string());
-    emit_debate_status(&app, &debate_id, 1, "round_1");
-
-    if debate_start_time.elapsed() > total_timeout {
-        tracing::error!("Debate {} timed out before Round 1", debate_id);
-        update_status(1, "failed".to_string());
-        return Err("Debate timed out".to_string());
-    }
-
-    execute_round(
-        app.clone(),
-        debate_id.clone(),
-        1,
-        request.clone(),
-        worktree_path.clone(),
-        context_dir.clone(),
-        None, // No previous context
-    )
-    .await?;
-
-    // Round 2: Critical analysis with Round 1 context
-    update_status(2, "round_2".to_string());
-    emit_debate_status(&app, &debate_id, 2, "round_2");
-
-    if debate_start_time.elapsed() > total_timeout {
-        tracing::error!("Debate {} timed out before Round 2", debate_id);
-        update_status(2, "failed".to_string());
-        return Err("Debate timed out".to_string());
-    }
-
-    let round1_context = load_round_context(&context_dir, 1)?;
-    execute_round(
-        app.clone(),
-        debate_id.clone(),
-        2,
-        request.clone(),
-        worktree_path.clone(),
-        context_dir.clone(),
-        Some(round1_context),
-    )
-    .await?;
+    // Round 1 proposes independently; every later round sees the earlier ones
+    for round in 1..=total_rounds {
+        let status = request.round_status(round);
+        update_status(round, status.clone());
+        emit_debate_status(&app, &debate_id, round, &status);
 
-    // Round 3: Consensus formation with Round 1+2 context
-    update_status(3, "round_3".to_string());
-    emit_debate_status(&app, &debate_id, 3, "round_3");
+        if debate_start_time.elapsed() > total_timeout {
+            tracing::error!("Debate {} timed out before round {}", debate_id, round);
+            update_status(round, "failed".to_string());
+            return Err("Debate timed out".to_string());
+        }
 
-    if debate_start_time.elapsed() > total_timeout {
-        tracing::error!("Debate {} timed out before Round 3", debate_id);
-        update_status(3, "failed".to_string());
-        return Err("Debate timed out".to_string());
+        let context = if round == 1 {
+            None
+        } else {
+            Some(load_debate_context(&context_dir, round - 1)?)
+        };
+        let result = execute_round(
+            app.clone(),
+            &tools,
+            &debates,
+            debate_id.clone(),
+            round,
+            &request,
+            worktree_path.clone(),
+            context_dir.clone(),
+            context,
+        )
+        .await;
+        if let Err(e) = result {
+            update_status(round, "failed".to_string());
+            emit_debate_status(&app, &debate_id, round, "failed");
+            return Err(e);
+        }
     }
 
-    let round2_context = load_round_context(&context_dir, 2)?;
-    let combined_context = format!(
-        "{}\n\n--- Round 2 ---\n\n{}",
-        load_round_context(&context_dir, 1)?,
-        round2_context
-    );
-    execute_round(
-        app.clone(),
-        debate_id.clone(),
-        3,
-        request.clone(),
-        worktree_path.clone(),
-        context_dir.clone(),
-        Some(combined_context),
-    )
-    .await?;
-
     // Debate completed - update final status
-    update_status(3, "completed".to_string());
-    emit_debate_status(&app, &debate_id, 3, "completed");
+    update_status(total_rounds, "completed".to_string());
+    emit_debate_status(&app, &debate_id, total_rounds, "completed");
     tracing::info!("Debate {} completed successfully", debate_id);
 
     Ok(())
 }
 
-/// Execute a single round with all 3 roles
+/// Execute a single round, every participant speaking in turn
 async fn execute_round(
     app: tauri::AppHandle,
+    tools: &ToolRegistry,
+    debates: &Mutex<HashMap<String, DebateStatus>>,
     debate_id: String,
     round: u8,
-    request: DebateRequest,
+    request: &DebateRequest,
     worktree_path: String,
     context_dir: String,
     previous_context: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("Executing round {} for debate {}", round, debate_id);
 
+    let mut names = NameAllocator::new(tools, &worktree_path);
     let mut round_outputs = Vec::new();
 
-    for (index, role) in request.roles.iter().enumerate() {
+    for (index, role) in request.participants(round).iter().enumerate() {
         let started_at = chrono::Utc::now();
 
         // Build prompt with context
-        let prompt = if let Some(ref context) = previous_context {
-            format!(
-                "{}\n\n--- Previous Round Context ---\n{}\n\n--- Your Task ---\n{}",
-                role.system_prompt, context, request.task
-            )
-        } else {
-            format!("{}\n\n{}", role.system_prompt, request.task)
-        };
+        let task = sanitize::prompt(&request.task, sanitize::MAX_PROMPT_CHARS);
+        let prompt = request.prompt(round, role, &task, previous_context.as_deref());
 
         // Create tmux session
-        let session_id = format!("claude-debate-{}-r{}-{}", &debate_id[..8], round, index + 1);
-        let output_log_path = format!("{}/.claude-round{}-{}.log", worktree_path, round, role.id);
+        let session_id = names.session(&format!(
+            "claude-debate-{}-r{}-{}",
+            &debate_id[..8],
+            round,
+            index + 1
+        ))?;
+        let output_log_path = format!(
+            "{}/.claude-round{}-{}.log",
+            worktree_path,
+            round,
+            sanitize::file_component(&role.id)
+        );
 
-        let tmux_output = Command::new("tmux")
+        let tmux_output = tools
+            .command("tmux")
             .arg("new-session")
             .arg("-d")
             .arg("-s")
@@ -2006,7 +2751,8 @@ async fn execute_round(
         }
 
         // Enable pipe-pane for output capture
-        let _ = Command::new("tmux")
+        let _ = tools
+            .command("tmux")
             .arg("pipe-pane")
             .arg("-t")
             .arg(&session_id)
@@ -2016,17 +2762,15 @@ async fn execute_round(
 
         // Send Claude Code command using echo -e for proper multiline handling
         // Use --permission-mode bypassPermissions to auto-approve changes and prevent interactive prompts
-        let escaped_prompt = prompt
-            .replace('\\', "\\\\") // Escape backslashes first
-            .replace('\n', "\\n") // Escape newlines
-            .replace('\'', "'\\''"); // Escape single quotes
+        let escaped_prompt = sanitize::echo_escaped(&prompt);
 
         let claude_cmd = format!(
             "echo -e '{}' | claude --model {} --print --permission-mode bypassPermissions",
             escaped_prompt, request.model
         );
 
-        let send_output = Command::new("tmux")
+        let send_output = tools
+            .command("tmux")
             .arg("send-keys")
             .arg("-t")
             .arg(&session_id)
@@ -2044,37 +2788,8 @@ async fn execute_round(
 
         // Wait for completion (poll tmux session)
         let timeout = tokio::time::Duration::from_secs(request.timeout_seconds);
-        let start_time = tokio::time::Instant::now();
-
-        loop {
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-            // Check if session still exists
-            let check_output = Command::new("tmux")
-                .arg("has-session")
-                .arg("-t")
-                .arg(&session_id)
-                .output();
-
-            match check_output {
-                Ok(output) if output.status.success() => {
-                    // Session still running
-                    if start_time.elapsed() > timeout {
-                        // Timeout - kill session
-                        let _ = Command::new("tmux")
-                            .arg("kill-session")
-                            .arg("-t")
-                            .arg(&session_id)
-                            .output();
-
-                        return Err(format!("Round {} role {} timed out", round, role.name));
-                    }
-                }
-                _ => {
-                    // Session completed
-                    break;
-                }
-            }
+        if !wait_for_session(tools, &session_id, timeout, SESSION_POLL_INTERVAL).await {
+            return Err(format!("Round {} role {} timed out", round, role.name));
         }
 
         // Read output
@@ -2085,7 +2800,21 @@ async fn execute_round(
         let completed_at = chrono::Utc::now();
         let execution_time_ms = (completed_at - started_at).num_milliseconds() as u64;
 
-        round_outputs.push(output.clone());
+        round_outputs.push(format!("--- {} ---\n{}", role.name, output));
+        if let Ok(mut debates) = debates.lock() {
+            if let Some(debate) = debates.get_mut(&debate_id) {
+                debate.round_outputs.push(RoundOutput {
+                    round,
+                    role_id: role.id.clone(),
+                    role_name: role.name.clone(),
+                    output: output.clone(),
+                    status: "completed".to_string(),
+                    started_at: started_at.to_rfc3339(),
+                    completed_at: Some(completed_at.to_rfc3339()),
+                    execution_time_ms,
+                });
+            }
+        }
 
         // Emit round output event
         let payload = serde_json::json!({
@@ -2097,14 +2826,14 @@ async fn execute_round(
             "status": "completed",
             "executionTimeMs": execution_time_ms
         });
-        let _ = app.emit_all("debate-round-output", payload);
+        let _ = emit_session_event(&app, &debate_id, "debate-round-output", payload);
 
         tracing::info!("Round {} role {} completed in {}ms", round, role.name, execution_time_ms);
     }
 
     // Save round context to file
     let context_file_path = format!("{}/round{}.txt", context_dir, round);
-    let combined_output = round_outputs.join("\n\n--- Next Role ---\n\n");
+    let combined_output = round_outputs.join("\n\n");
     tokio::fs::write(&context_file_path, combined_output)
         .await
         .map_err(|e| format!("Failed to save context: {}", e))?;
@@ -2114,6 +2843,49 @@ async fn execute_round(
     Ok(())
 }
 
+/// Wait for a tmux session to end, killing it after `timeout`
+///
+/// Returns `false` if the session timed out.
+async fn wait_for_session(
+    tools: &ToolRegistry,
+    session_id: &str,
+    timeout: tokio::time::Duration,
+    poll_interval: tokio::time::Duration,
+) -> bool {
+    let start_time = tokio::time::Instant::now();
+
+    loop {
+        tokio::time::sleep(poll_interval).await;
+
+        // Check if session still exists
+        let check_output = tools
+            .command("tmux")
+            .arg("has-session")
+            .arg("-t")
+            .arg(session_id)
+            .output();
+
+        match check_output {
+            Ok(output) if output.status.success() => {
+                // Session still running
+                if start_time.elapsed() > timeout {
+                    // Timeout - kill session
+                    let _ = tools
+                        .command("tmux")
+                        .arg("kill-session")
+                        .arg("-t")
+                        .arg(session_id)
+                        .output();
+
+                    return false;
+                }
+            }
+            // Session completed
+            _ => return true,
+        }
+    }
+}
+
 /// Load context from a specific round
 fn load_round_context(context_dir: &str, round: u8) -> Result<String, String> {
     let context_file_path = format!("{}/round{}.txt", context_dir, round);
@@ -2121,6 +2893,17 @@ fn load_round_context(context_dir: &str, round: u8) -> Result<String, String> {
         .map_err(|e| format!("Failed to load round {} context: {}", round, e))
 }
 
+/// Load the context of rounds 1 to `rounds`, each under its own heading
+fn load_debate_context(context_dir: &str, rounds: u8) -> Result<String, String> {
+    let rounds = (1..=rounds)
+        .map(|round| {
+            let context = load_round_context(context_dir, round)?;
+            Ok(format!("=== Round {} ===\n\n{}", round, context))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(rounds.join("\n\n"))
+}
+
 /// Emit debate status event
 fn emit_debate_status(app: &tauri::AppHandle, debate_id: &str, current_round: u8, status: &str) {
     let payload = serde_json::json!({
@@ -2128,7 +2911,7 @@ fn emit_debate_status(app: &tauri::AppHandle, debate_id: &str, current_round: u8
         "currentRound": current_round,
         "status": status
     });
-    let _ = app.emit_all("debate-status", payload);
+    let _ = emit_session_event(app, debate_id, "debate-status", payload);
 }
 
 /// Get debate status
@@ -2155,12 +2938,15 @@ pub async fn cancel_debate(
     debate_id: String,
     cleanup_worktrees: bool,
 ) -> Result<(), String> {
+    state.session_streams.ensure_writable(&debate_id)?;
     tracing::info!("Cancelling debate: {}", debate_id);
 
     // Kill all tmux sessions for this debate
     let session_pattern = format!("claude-debate-{}", &debate_id[..8]);
 
-    let list_output = Command::new("tmux")
+    let list_output = state
+        .tools
+        .command("tmux")
         .arg("list-sessions")
         .arg("-F")
         .arg("#{session_name}")
@@ -2171,7 +2957,9 @@ pub async fn cancel_debate(
         let sessions = String::from_utf8_lossy(&list_output.stdout);
         for session in sessions.lines() {
             if session.contains(&session_pattern) {
-                let _ = Command::new("tmux")
+                let _ = state
+                    .tools
+                    .command("tmux")
                     .arg("kill-session")
                     .arg("-t")
                     .arg(session)
@@ -2193,9 +2981,16 @@ pub async fn cancel_debate(
         let ait42_worktrees = project_root.join("src-tauri").join(".worktrees");
         let debate_dir = format!("{}/debate-{}", ait42_worktrees.display(), &debate_id[..8]);
 
-        // Remove worktree
-        let worktree_path = format!("{}/debate-workspace", debate_dir);
-        let _ = Command::new("git")
+        // Remove the worktree the debate recorded, which may carry a suffix
+        let worktree_path = state
+            .debates
+            .lock()
+            .ok()
+            .and_then(|debates| debates.get(&debate_id).map(|d| d.worktree_path.clone()))
+            .unwrap_or_else(|| format!("{}/debate-workspace", debate_dir));
+        let _ = state
+            .tools
+            .command("git")
             .arg("worktree")
             .arg("remove")
             .arg("--force")
@@ -2217,7 +3012,7 @@ pub async fn cancel_debate(
 //
 
 /// Task analysis request for Claude Code
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeCodeAnalysisRequest {
     pub task: String,
@@ -2226,7 +3021,7 @@ pub struct ClaudeCodeAnalysisRequest {
 }
 
 /// Task analysis response from Claude Code
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeCodeAnalysisResponse {
     pub analysis_id: String,
@@ -2249,9 +3044,7 @@ pub async fn analyze_task_with_claude_code(
     request: ClaudeCodeAnalysisRequest,
 ) -> Result<ClaudeCodeAnalysisResponse, String> {
     // Validation
-    if request.task.trim().is_empty() {
-        return Err("Task cannot be empty".to_string());
-    }
+    let task = sanitize::task(&request.task)?;
 
     let valid_models = ["sonnet", "haiku", "opus"];
     if !valid_models.contains(&request.model.as_str()) {
@@ -2263,7 +3056,7 @@ pub async fn analyze_task_with_claude_code(
     tracing::info!(
         "Starting Claude Code meta-analysis: {} for task: {}",
         analysis_id,
-        request.task.chars().take(50).collect::<String>()
+        task.chars().take(50).collect::<String>()
     );
 
     let working_dir = state.working_dir.lock().await;
@@ -2275,10 +3068,13 @@ pub async fn analyze_task_with_claude_code(
     std::fs::create_dir_all(&analysis_dir).map_err(|e| e.to_string())?;
 
     // Create tmux session
-    let session_id = format!("claude-analysis-{}", &analysis_id[..8]);
+    let session_id = NameAllocator::new(&state.tools, &base_path)
+        .session(&format!("claude-analysis-{}", &analysis_id[..8]))?;
     let output_log_path = format!("{}/.claude-analysis.log", analysis_dir);
 
-    let tmux_output = Command::new("tmux")
+    let tmux_output = state
+        .tools
+        .command("tmux")
         .arg("new-session")
         .arg("-d")
         .arg("-s")
@@ -2294,7 +3090,9 @@ pub async fn analyze_task_with_claude_code(
     }
 
     // Enable pipe-pane to capture output
-    let _ = Command::new("tmux")
+    let _ = state
+        .tools
+        .command("tmux")
         .arg("pipe-pane")
         .arg("-t")
         .arg(&session_id)
@@ -2332,21 +3130,20 @@ REASONING: [なぜこの複雑度クラスと分解数が適切か、詳細な
 - Linear: 2-5
 - Quadratic: 3-8
 - Exponential: 5-10"#,
-        request.task
+        sanitize::prompt(&task, sanitize::MAX_PROMPT_CHARS)
     );
 
     // Send Claude Code command using echo -e for proper multiline handling
-    let escaped_prompt = analysis_prompt
-        .replace('\\', "\\\\") // Escape backslashes first
-        .replace('\n', "\\n") // Escape newlines
-        .replace('\'', "'\\''"); // Escape single quotes
+    let escaped_prompt = sanitize::echo_escaped(&analysis_prompt);
 
     let claude_cmd = format!(
         "echo -e '{}' | claude --model {} --print --permission-mode bypassPermissions",
         escaped_prompt, request.model
     );
 
-    let send_output = Command::new("tmux")
+    let send_output = state
+        .tools
+        .command("tmux")
         .arg("send-keys")
         .arg("-t")
         .arg(&session_id)
@@ -2364,37 +3161,8 @@ REASONING: [なぜこの複雑度クラスと分解数が適切か、詳細な
 
     // Wait for completion with timeout
     let timeout = tokio::time::Duration::from_secs(request.timeout_seconds);
-    let start_time = tokio::time::Instant::now();
-
-    loop {
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-        // Check if session still exists
-        let check_output = Command::new("tmux")
-            .arg("has-session")
-            .arg("-t")
-            .arg(&session_id)
-            .output();
-
-        match check_output {
-            Ok(output) if output.status.success() => {
-                // Session still running
-                if start_time.elapsed() > timeout {
-                    // Timeout - kill session
-                    let _ = Command::new("tmux")
-                        .arg("kill-session")
-                        .arg("-t")
-                        .arg(&session_id)
-                        .output();
-
-                    return Err("Analysis timed out".to_string());
-                }
-            }
-            _ => {
-                // Session completed
-                break;
-            }
-        }
+    if !wait_for_session(&state.tools, &session_id, timeout, SESSION_POLL_INTERVAL).await {
+        return Err("Analysis timed out".to_string());
     }
 
     // Read output
@@ -2426,88 +3194,32 @@ fn parse_analysis_output(
     output: &str,
     analysis_id: &str,
 ) -> Result<ClaudeCodeAnalysisResponse, String> {
-    let mut complexity_class = String::new();
-    let mut subtasks = 0;
-    let mut instances = 0;
-    let mut confidence = 0.0;
-    let mut reasoning = String::new();
-
-    // Parse structured output
-    for line in output.lines() {
-        let line = line.trim();
-
-        if line.starts_with("COMPLEXITY_CLASS:") {
-            complexity_class = line
-                .strip_prefix("COMPLEXITY_CLASS:")
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        } else if line.starts_with("SUBTASKS:") {
-            if let Ok(val) = line
-                .strip_prefix("SUBTASKS:")
-                .unwrap_or("0")
-                .trim()
-                .parse::<usize>()
-            {
-                subtasks = val;
-            }
-        } else if line.starts_with("INSTANCES:") {
-            if let Ok(val) = line
-                .strip_prefix("INSTANCES:")
-                .unwrap_or("0")
-                .trim()
-                .parse::<usize>()
-            {
-                instances = val;
-            }
-        } else if line.starts_with("CONFIDENCE:") {
-            if let Ok(val) = line
-                .strip_prefix("CONFIDENCE:")
-                .unwrap_or("0.0")
-                .trim()
-                .parse::<f64>()
-            {
-                confidence = val;
-            }
-        } else if line.starts_with("REASONING:") {
-            reasoning = line
-                .strip_prefix("REASONING:")
-                .unwrap_or("")
-                .trim()
-                .to_string();
-
-            // Collect multi-line reasoning
-            let reasoning_start = output.find("REASONING:").unwrap_or(0);
-            reasoning = output[reasoning_start..]
-                .strip_prefix("REASONING:")
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        }
-    }
-
-    // Validation
-    let valid_classes = ["Logarithmic", "Linear", "Quadratic", "Exponential"];
-    if !valid_classes.contains(&complexity_class.as_str()) {
+    let text = strip_ansi(output);
+    let analysis = Analysis::parse(&text);
+
+    let complexity_class = match analysis.complexity_class {
+        Some(
+            class @ (ComplexityClass::Logarithmic
+            | ComplexityClass::Linear
+            | ComplexityClass::Quadratic
+            | ComplexityClass::Exponential),
+        ) => format!("{:?}", class),
         // Fallback: try to infer from output
-        complexity_class = infer_complexity_class(output);
-    }
-
-    if subtasks == 0 {
-        subtasks = infer_subtasks(output, &complexity_class);
-    }
-
-    if instances == 0 {
-        instances = calculate_instances_from_complexity(&complexity_class, subtasks);
-    }
-
-    if confidence == 0.0 {
-        confidence = 0.7; // Default confidence
-    }
-
-    if reasoning.is_empty() {
-        reasoning = extract_reasoning(output);
-    }
+        _ => infer_complexity_class(&text),
+    };
+    let subtasks = analysis
+        .subtasks
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| infer_subtasks(&text, &complexity_class));
+    let instances = analysis
+        .instances
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| calculate_instances_from_complexity(&complexity_class, subtasks));
+    // Default confidence
+    let confidence = analysis.confidence.filter(|&c| c > 0.0).unwrap_or(0.7);
+    let reasoning = analysis
+        .reasoning
+        .unwrap_or_else(|| extract_reasoning(&text));
 
     Ok(ClaudeCodeAnalysisResponse {
         analysis_id: analysis_id.to_string(),
@@ -2586,3 +3298,258 @@ fn extract_reasoning(output: &str) -> String {
         lines.join(" ").chars().take(200).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{FakeScenario, FakeTools, SessionExit, SessionScript};
+
+    const NO_WAIT: tokio::time::Duration = tokio::time::Duration::ZERO;
+
+    /// Launch an agent in a fake session piping to `log`, as pipelines do
+    fn launch(tools: &ToolRegistry, session: &str, log: &Path) {
+        for args in [
+            vec![
+                "new-session".to_string(),
+                "-d".into(),
+                "-s".into(),
+                session.into(),
+            ],
+            vec![
+                "pipe-pane".to_string(),
+                "-t".into(),
+                session.into(),
+                "-o".into(),
+                format!("cat >> {}", log.display()),
+            ],
+            vec![
+                "send-keys".to_string(),
+                "-t".into(),
+                session.into(),
+                "claude --print && exit".into(),
+                "Enter".into(),
+            ],
+        ] {
+            assert!(tools
+                .command("tmux")
+                .args(args)
+                .output()
+                .unwrap()
+                .status
+                .success());
+        }
+    }
+
+    async fn watch(tools: &ToolRegistry, session: &str, log: &Path) -> Vec<serde_json::Value> {
+        let mut events = Vec::new();
+        watch_tmux_session(tools, session, 1, &log.to_string_lossy(), NO_WAIT, |payload| {
+            events.push(payload);
+            Ok(())
+        })
+        .await;
+        events
+    }
+
+    #[tokio::test]
+    async fn test_watch_streams_output_after_slow_startup() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("out.log");
+        let fake = Arc::new(FakeTools::new(
+            FakeScenario::new().default_session(
+                SessionScript::new()
+                    .startup_polls(5)
+                    .output("one\n")
+                    .output("two\n"),
+            ),
+        ));
+        let tools = ToolRegistry::with_runner(fake.clone());
+        launch(&tools, "ait42-claude-competition-1", &log);
+
+        let events = watch(&tools, "ait42-claude-competition-1", &log).await;
+
+        let outputs: Vec<_> = events
+            .iter()
+            .map(|e| (e["output"].as_str(), e["status"].as_str()))
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                (Some("one\n"), Some("running")),
+                (Some("two\n"), Some("running")),
+                (Some("one\ntwo\n"), Some("completed")),
+            ]
+        );
+        assert!(fake.sessions().is_empty());
+        // The finished log is kept compressed
+        assert!(!log.exists());
+        assert!(dir.path().join("out.log.gz").exists());
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_partial_output_of_vanished_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("out.log");
+        let scenario = FakeScenario::new().default_session(
+            SessionScript::new()
+                .output("partial")
+                .exit(SessionExit::Vanish),
+        );
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::new(scenario)));
+        launch(&tools, "s1", &log);
+
+        let events = watch(&tools, "s1", &log).await;
+
+        let last = events.last().unwrap();
+        assert_eq!(last["status"], "completed");
+        assert_eq!(last["output"], "partial");
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_missing_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::default()));
+        launch(&tools, "s1", &dir.path().join("never-written.log"));
+
+        let events = watch(&tools, "s1", &dir.path().join("never-written.log")).await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["status"], "error");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let scenario = FakeScenario::new()
+            .session("failing", SessionScript::new().exit(SessionExit::Exit { code: 2 }))
+            .session(
+                "hung",
+                SessionScript::new()
+                    .output("thinking")
+                    .exit(SessionExit::Hang),
+            )
+            .session("gone", SessionScript::new().exit(SessionExit::Vanish));
+        let fake = Arc::new(FakeTools::new(scenario));
+        let tools = ToolRegistry::with_runner(fake.clone());
+        let timeout = tokio::time::Duration::from_millis(20);
+
+        for session in ["ok", "failing", "hung", "gone"] {
+            launch(&tools, session, &dir.path().join(format!("{}.log", session)));
+        }
+
+        assert!(wait_for_session(&tools, "ok", timeout, NO_WAIT).await);
+        assert!(wait_for_session(&tools, "gone", timeout, NO_WAIT).await);
+        assert!(!wait_for_session(&tools, "failing", timeout, NO_WAIT).await);
+        assert!(!wait_for_session(&tools, "hung", timeout, NO_WAIT).await);
+        assert!(fake.sessions().is_empty(), "timed out sessions are killed");
+    }
+
+    fn debate_request(roles: usize, rounds: u8) -> DebateRequest {
+        DebateRequest {
+            task: "Design the cache".to_string(),
+            roles: (1..=roles)
+                .map(|i| RoleDefinition {
+                    id: format!("role-{}", i),
+                    name: format!("Role {}", i),
+                    system_prompt: format!("You are role {}.", i),
+                })
+                .collect(),
+            model: "sonnet".to_string(),
+            timeout_seconds: 800,
+            preserve_worktrees: false,
+            rounds,
+            round_prompts: Vec::new(),
+            synthesis: true,
+            synthesis_prompt: None,
+        }
+    }
+
+    #[test]
+    fn test_debate_request_limits() {
+        assert!(debate_request(2, 1).validate().is_ok());
+        assert!(debate_request(6, 5).validate().is_ok());
+        assert!(debate_request(1, 3).validate().is_err());
+        assert!(debate_request(7, 3).validate().is_err());
+        assert!(debate_request(3, 0).validate().is_err());
+        assert!(debate_request(3, 6).validate().is_err());
+
+        let too_many_prompts = DebateRequest {
+            round_prompts: vec!["{task}".to_string(); 3],
+            ..debate_request(3, 2)
+        };
+        assert!(too_many_prompts.validate().is_err());
+
+        // Requests from before rounds were configurable keep their 3 rounds:
+        // two debate rounds and the synthesis
+        let request: DebateRequest = serde_json::from_value(serde_json::json!({
+            "task": "t",
+            "roles": [],
+            "model": "sonnet",
+            "timeoutSeconds": 800,
+            "preserveWorktrees": false,
+        }))
+        .unwrap();
+        assert_eq!(request.rounds, 2);
+        assert_eq!(request.total_rounds(), 3);
+    }
+
+    #[test]
+    fn test_debate_rounds_and_prompts() {
+        let request = DebateRequest {
+            round_prompts: vec![
+                String::new(),
+                "Round {round}/{rounds}: rebut {context}".to_string(),
+            ],
+            ..debate_request(4, 2)
+        };
+        assert_eq!(request.total_rounds(), 3);
+        assert_eq!(request.agent_runs(), 9);
+        assert_eq!(request.round_status(2), "round_2");
+        assert_eq!(request.round_status(3), "synthesis");
+        assert_eq!(request.participants(1).len(), 4);
+        assert_eq!(request.participants(3)[0].id, "synthesis");
+
+        let role = &request.roles[0];
+        assert_eq!(
+            request.prompt(1, role, "Design the cache", None),
+            "You are role 1.\n\nDesign the cache"
+        );
+        assert_eq!(
+            request.prompt(2, role, "Design the cache", Some("{task} said")),
+            "Round 2/2: rebut {task} said"
+        );
+        let synthesis =
+            request.prompt(3, &request.participants(3)[0], "Design the cache", Some("ctx"));
+        assert!(synthesis.contains("between Role 1, Role 2, Role 3, Role 4"));
+        assert!(synthesis.ends_with("--- Debate ---\nctx\n\n--- Task ---\nDesign the cache"));
+
+        let without_synthesis = DebateRequest {
+            synthesis: false,
+            ..debate_request(2, 2)
+        };
+        assert_eq!(without_synthesis.total_rounds(), 2);
+        assert_eq!(
+            fill_template("{a} {b} {unknown} {", &[("a", "1"), ("b", "{a}")]),
+            "1 {a} {unknown} {"
+        );
+    }
+
+    #[test]
+    fn test_parse_analysis_output() {
+        let output = "COMPLEXITY_CLASS: [Logarithmic/Linear/Quadratic/Exponential]\r\n\
+                      \x1b[1mCOMPLEXITY_CLASS:\x1b[22m Quadratic\x1b[K\r\n\
+                      SUBTASKS: 6\r\n\
+                      CONFIDENCE: 0.8\r\n\
+                      REASONING: Schema changes ripple through every service.\r\n";
+        let response = parse_analysis_output(output, "a1").unwrap();
+        assert_eq!(response.complexity_class, "Quadratic");
+        assert_eq!(response.recommended_subtasks, 6);
+        assert_eq!(response.recommended_instances, 3);
+        assert_eq!(response.confidence, 0.8);
+        assert_eq!(response.reasoning, "Schema changes ripple through every service.");
+        assert_eq!(response.raw_output, output);
+
+        let response = parse_analysis_output("Mostly linear work.", "a2").unwrap();
+        assert_eq!(response.complexity_class, "Linear");
+        assert_eq!(response.confidence, 0.7);
+    }
+}
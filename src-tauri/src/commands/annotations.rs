@@ -0,0 +1,290 @@
+//! Annotation Commands
+//!
+//! Notes attached to line ranges of workspace files. They are stored in the
+//! workspace's `.ait42/annotations.json`, with paths relative to the
+//! workspace so they survive moving it, and can be handed to agents as
+//! context ("address the TODO note on line 42").
+//!
+//! Line numbers are 1-based and inclusive, like the editor gutter. Notes
+//! are not moved when the file is edited around them.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::state::AppState;
+
+/// Annotations file, relative to the workspace root
+const ANNOTATIONS_FILE: &str = ".ait42/annotations.json";
+
+/// Annotated lines quoted in agent context, per annotation
+const MAX_CONTEXT_LINES: usize = 20;
+
+/// Note attached to a line range of a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    pub id: String,
+    /// Path relative to the workspace root
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub text: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn annotations_file(workspace: &Path) -> PathBuf {
+    workspace.join(ANNOTATIONS_FILE)
+}
+
+fn load(workspace: &Path) -> Result<Vec<Annotation>, String> {
+    let file = annotations_file(workspace);
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse annotations: {}", e))
+}
+
+fn save(workspace: &Path, annotations: &[Annotation]) -> Result<(), String> {
+    let file = annotations_file(workspace);
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(annotations).map_err(|e| e.to_string())?;
+    fs::write(&file, json).map_err(|e| format!("Failed to save annotations: {}", e))
+}
+
+/// Serializes read-modify-write updates of annotation files
+static ANNOTATION_WRITES: Mutex<()> = Mutex::new(());
+
+/// Load, modify and save the annotations of a workspace as one update
+fn modify<T>(
+    workspace: &Path,
+    f: impl FnOnce(&mut Vec<Annotation>) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = ANNOTATION_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    let mut annotations = load(workspace)?;
+    let result = f(&mut annotations)?;
+    save(workspace, &annotations)?;
+    Ok(result)
+}
+
+/// `path` relative to the workspace, for absolute or relative input
+fn relative_path(workspace: &Path, path: &str) -> Result<String, String> {
+    let path = Path::new(path);
+    let relative = if path.is_absolute() {
+        path.strip_prefix(workspace)
+            .map_err(|_| format!("{} is not inside the workspace", path.display()))?
+    } else {
+        path
+    };
+    let inside = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !inside || relative.as_os_str().is_empty() {
+        return Err(format!("{} is not inside the workspace", path.display()));
+    }
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+fn check_range(start_line: u32, end_line: u32) -> Result<(), String> {
+    if start_line == 0 || end_line < start_line {
+        return Err(format!("Invalid line range {}-{}", start_line, end_line));
+    }
+    Ok(())
+}
+
+/// Describe `annotations` for an agent, quoting the annotated lines
+fn format_context(workspace: &Path, annotations: &[Annotation]) -> String {
+    let mut context = String::from("Notes attached to the code:\n");
+    for annotation in annotations {
+        let lines = if annotation.start_line == annotation.end_line {
+            format!("line {}", annotation.start_line)
+        } else {
+            format!("lines {}-{}", annotation.start_line, annotation.end_line)
+        };
+        context.push_str(&format!("\n{} ({}): {}\n", annotation.path, lines, annotation.text));
+
+        let Ok(content) = fs::read_to_string(workspace.join(&annotation.path)) else {
+            continue;
+        };
+        let quoted: Vec<&str> = content
+            .lines()
+            .skip(annotation.start_line.saturating_sub(1) as usize)
+            .take(annotation.end_line.saturating_sub(annotation.start_line) as usize + 1)
+            .take(MAX_CONTEXT_LINES)
+            .collect();
+        if !quoted.is_empty() {
+            context.push_str(&format!("```\n{}\n```\n", quoted.join("\n")));
+        }
+    }
+    context
+}
+
+async fn workspace(state: &AppState) -> PathBuf {
+    state.working_dir.lock().await.clone()
+}
+
+/// List annotations, of one file or of the whole workspace
+#[tauri::command]
+pub async fn list_annotations(
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<Vec<Annotation>, String> {
+    let workspace = workspace(&state).await;
+    let mut annotations = load(&workspace)?;
+    if let Some(path) = path {
+        let path = relative_path(&workspace, &path)?;
+        annotations.retain(|annotation| annotation.path == path);
+    }
+    annotations.sort_by(|a, b| (&a.path, a.start_line).cmp(&(&b.path, b.start_line)));
+    Ok(annotations)
+}
+
+/// Attach a note to lines `start_line` to `end_line` of a file
+#[tauri::command]
+pub async fn add_annotation(
+    state: State<'_, AppState>,
+    path: String,
+    start_line: u32,
+    end_line: u32,
+    text: String,
+) -> Result<Annotation, String> {
+    check_range(start_line, end_line)?;
+    let workspace = workspace(&state).await;
+    let now = Utc::now().to_rfc3339();
+    let annotation = Annotation {
+        id: uuid::Uuid::new_v4().to_string(),
+        path: relative_path(&workspace, &path)?,
+        start_line,
+        end_line,
+        text,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    modify(&workspace, |annotations| {
+        annotations.push(annotation.clone());
+        Ok(annotation)
+    })
+}
+
+/// Change the text or line range of a note
+#[tauri::command]
+pub async fn update_annotation(
+    state: State<'_, AppState>,
+    id: String,
+    text: Option<String>,
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+) -> Result<Annotation, String> {
+    let workspace = workspace(&state).await;
+    modify(&workspace, |annotations| {
+        let annotation = annotations
+            .iter_mut()
+            .find(|annotation| annotation.id == id)
+            .ok_or_else(|| format!("Annotation not found: {}", id))?;
+
+        let start_line = start_line.unwrap_or(annotation.start_line);
+        let end_line = end_line.unwrap_or(annotation.end_line);
+        check_range(start_line, end_line)?;
+        annotation.start_line = start_line;
+        annotation.end_line = end_line;
+        if let Some(text) = text {
+            annotation.text = text;
+        }
+        annotation.updated_at = Utc::now().to_rfc3339();
+        Ok(annotation.clone())
+    })
+}
+
+/// Remove a note
+#[tauri::command]
+pub async fn delete_annotation(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let workspace = workspace(&state).await;
+    modify(&workspace, |annotations| {
+        let count = annotations.len();
+        annotations.retain(|annotation| annotation.id != id);
+        if annotations.len() == count {
+            return Err(format!("Annotation not found: {}", id));
+        }
+        Ok(())
+    })
+}
+
+/// Agent context describing the given notes, or all of them
+#[tauri::command]
+pub async fn get_annotation_context(
+    state: State<'_, AppState>,
+    ids: Option<Vec<String>>,
+) -> Result<String, String> {
+    let workspace = workspace(&state).await;
+    let mut annotations = load(&workspace)?;
+    if let Some(ids) = ids {
+        annotations.retain(|annotation| ids.contains(&annotation.id));
+    }
+    if annotations.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format_context(&workspace, &annotations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(path: &str, start_line: u32, end_line: u32, text: &str) -> Annotation {
+        Annotation {
+            id: uuid::Uuid::new_v4().to_string(),
+            path: path.to_string(),
+            start_line,
+            end_line,
+            text: text.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load(dir.path()).unwrap().is_empty());
+
+        let annotations = vec![annotation("src/main.rs", 1, 2, "note")];
+        save(dir.path(), &annotations).unwrap();
+        assert_eq!(load(dir.path()).unwrap(), annotations);
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let workspace = Path::new("/work/project");
+        assert_eq!(relative_path(workspace, "/work/project/src/a.rs").unwrap(), "src/a.rs");
+        assert_eq!(relative_path(workspace, "src/a.rs").unwrap(), "src/a.rs");
+        assert!(relative_path(workspace, "/elsewhere/a.rs").is_err());
+        assert!(relative_path(workspace, "../a.rs").is_err());
+        assert!(check_range(0, 1).is_err());
+        assert!(check_range(3, 2).is_err());
+    }
+
+    #[test]
+    fn test_format_context_quotes_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.rs"), "one\ntwo\nthree\n").unwrap();
+
+        let context = format_context(
+            dir.path(),
+            &[
+                annotation("a.rs", 2, 3, "TODO: handle errors"),
+                annotation("missing.rs", 42, 42, "check this"),
+            ],
+        );
+        assert!(context.contains("a.rs (lines 2-3): TODO: handle errors\n```\ntwo\nthree\n```"));
+        assert!(context.contains("missing.rs (line 42): check this"));
+    }
+}
@@ -0,0 +1,689 @@
+//! API Schema Commands
+//!
+//! Machine-readable description of the backend surface: every Tauri command
+//! with its arguments and response, and every event the backend emits with
+//! its payload, as JSON Schemas generated from the Rust types with
+//! `schemars`. The frontend and third-party integrations can validate their
+//! calls against it instead of a hand-maintained copy.
+//!
+//! Arguments are keyed by the camelCase names `invoke` expects. Commands
+//! that fail reject with a string message. Types shared by several commands
+//! are described once under `definitions` and referenced from there.
+//!
+//! [`API_VERSION`] is bumped whenever a command or event is removed or an
+//! existing argument, response or payload changes incompatibly.
+
+use std::collections::BTreeMap;
+
+use ait42_ait42::{AgentChange, AgentStats, Recommendation, ReviewReport};
+use ait42_config::{
+    BudgetConfig, CheckpointConfig, LlmProviderConfig, SessionsConfig, UpdatesConfig,
+};
+use ait42_fs::{GrepOptions, GrepSummary, TodoItem};
+use ait42_git::{
+    BlameLine, Bump, Checkpoint, ConflictFile, DiffRange, FileResolution, GraphRow, Hunk,
+    MergeConflict, OperationStatus, RebasePlan, Resolution, StashEntry,
+};
+use ait42_lsp::WatchdogConfig;
+use ait42_update::Release;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::{JsonSchema, Map};
+use serde::Serialize;
+
+use crate::ab_test::ABTestResult;
+use crate::agent_output::{OutputChunk, OutputReplay, AGENT_OUTPUT_EVENT};
+use crate::budget::{BudgetScope, BudgetStatus, BudgetWarning, BUDGET_WARNING_EVENT};
+use crate::clipboard_history::{ClipboardEntry, ClipboardSource};
+use crate::commands::ait42::{
+    AgentExecutionRequest, AgentExecutionResponse, AgentInfo, ClaudeCodeAnalysisRequest,
+    ClaudeCodeAnalysisResponse, ClaudeCodeCompetitionRequest, ClaudeCodeCompetitionResult,
+    DebateRequest, DebateResult, DebateStatus, MultiRuntimeCompetitionRequest,
+    ParallelExecutionRequest, TmuxExecutionRequest, TmuxSession,
+};
+use crate::commands::annotations::Annotation;
+use crate::commands::auto_mode::{AutoDecision, AutoRunResult};
+use crate::commands::changelog::ChangelogDraft;
+use crate::commands::commit_message::CommitMessageDraft;
+use crate::commands::competition::{
+    CompetitionRanking, CompetitionSummary, WinnerMerge, COMPETITION_SCORED_EVENT,
+    COMPETITION_SUMMARIZED_EVENT,
+};
+use crate::commands::editor::{BufferInfo, TextRange};
+use crate::commands::file::{self, OpenFileResponse};
+use crate::commands::git::{self, GitCommit, GitStatus};
+use crate::commands::issues::IssueTrackerStatus;
+use crate::commands::lsp::{LspCompletionItem, LspDiagnostic, LspHoverInfo, LspLocation};
+use crate::commands::optimizer::{
+    CalculateInstancesResponse, ComplexityInfoResponse, EstimateCacheStatsResponse,
+    OptimizeTaskResponse,
+};
+use crate::commands::quick_fix::{DiagnosticExplanation, DiagnosticQuickAction};
+use crate::commands::refactor::{RefactorEdit, RefactorProposalResponse};
+use crate::commands::review::ReviewDiffResult;
+use crate::commands::search::{SearchResults, SEARCH_RESULTS_EVENT};
+use crate::commands::session_history::{ChatMessage, SessionArtifact, WorktreeSession};
+use crate::commands::team_feed::FeedEntry;
+#[cfg(feature = "terminal")]
+use crate::commands::terminal::{CommandHistoryEntry, CommandHistoryFilter, TerminalInfo};
+use crate::commands::workspace::{WorkspaceInfo, WORKSPACE_CHANGED_EVENT};
+use crate::commands::worktree::{self, FileDiff};
+use crate::confirmation::DangerousAction;
+use crate::feature_flags::{self, FeatureFlagState};
+use crate::file_drop::{FILES_DROPPED_EVENT, WORKSPACE_DROPPED_EVENT};
+use crate::issues::{Issue, IssueLink, IssueProvider};
+use crate::mcp::McpStatus;
+use crate::notifications::{Notification, NOTIFICATION_EVENT};
+use crate::output_alerts::{AgentAlert, AGENT_ALERT_EVENT};
+use crate::plugin::PluginInfo;
+use crate::remote::RemoteStatus;
+use crate::session_journal::JournalEntry;
+use crate::session_store::SyncReport;
+use crate::session_stream::{ObservedSession, SessionEvent, StreamTarget, OBSERVED_EVENT};
+
+/// Version of the command and event protocol
+pub const API_VERSION: u32 = 1;
+
+/// Commands and events of the backend
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSchema {
+    pub api_version: u32,
+    /// Version of the editor that generated the schema
+    pub app_version: String,
+    /// JSON Schema dialect of all schemas
+    pub json_schema: String,
+    pub commands: Vec<CommandSchema>,
+    pub events: Vec<EventSchema>,
+    /// Shared types, referenced as `#/definitions/<name>`
+    pub definitions: Map<String, Schema>,
+}
+
+/// A Tauri command
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandSchema {
+    /// Name passed to `invoke`
+    pub name: String,
+    /// Command module, for grouping
+    pub module: String,
+    pub args: Vec<ArgSchema>,
+    /// Value the command resolves with
+    pub response: Schema,
+    /// Feature flag the command needs, if any
+    pub feature_flag: Option<String>,
+}
+
+/// An argument of a command
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArgSchema {
+    /// Key of the argument in the `invoke` payload
+    pub name: String,
+    /// Whether the argument must be given
+    pub required: bool,
+    pub schema: Schema,
+}
+
+/// An event emitted to the frontend
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSchema {
+    /// Name to `listen` to
+    pub name: String,
+    pub payload: Schema,
+}
+
+impl ArgSchema {
+    fn new<T: JsonSchema>(gen: &mut SchemaGenerator, name: &str, type_name: &str) -> Self {
+        Self {
+            name: camel_case(name),
+            required: !type_name.starts_with("Option"),
+            schema: gen.subschema_for::<T>(),
+        }
+    }
+}
+
+impl EventSchema {
+    fn new<T: JsonSchema>(gen: &mut SchemaGenerator, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            payload: gen.subschema_for::<T>(),
+        }
+    }
+}
+
+/// Tauri v1 maps snake_case command arguments to camelCase keys
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for ch in name.chars() {
+        if ch == '_' {
+            upper = true;
+        } else if upper {
+            out.push(ch.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Schemas of commands declared by module as `name(arg: Type, ...) -> Response;`,
+/// with the injected `State`, `AppHandle` and `Window` arguments left out
+macro_rules! command_schemas {
+    ($gen:ident; $(
+        $module:ident {
+            $($name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty;)*
+        }
+    )*) => {
+        vec![$($(
+            {
+                // Fails to compile when a listed command does not exist
+                let _ = crate::commands::$module::$name;
+                CommandSchema {
+                    name: stringify!($name).to_string(),
+                    module: stringify!($module).to_string(),
+                    args: vec![$(ArgSchema::new::<$ty>(
+                        &mut *$gen,
+                        stringify!($arg),
+                        stringify!($ty),
+                    )),*],
+                    response: $gen.subschema_for::<$ret>(),
+                    feature_flag: feature_flags::gating_flag(stringify!($name))
+                        .map(str::to_string),
+                }
+            }
+        ),*),*]
+    };
+}
+
+fn commands(gen: &mut SchemaGenerator) -> Vec<CommandSchema> {
+    #[allow(unused_mut)]
+    let mut commands = command_schemas! { gen;
+    api_schema {
+        get_api_schema() -> ApiSchema;
+    }
+    ait42 {
+        list_agents() -> Vec<AgentInfo>;
+        get_agent_info(agent_name: String) -> AgentInfo;
+        recommend_agents(
+            task: String,
+            limit: Option<usize>,
+            use_llm: Option<bool>,
+        ) -> Vec<Recommendation>;
+        execute_agent(request: AgentExecutionRequest) -> AgentExecutionResponse;
+        execute_parallel(request: ParallelExecutionRequest) -> Vec<AgentExecutionResponse>;
+        get_agent_output(execution_id: String) -> AgentExecutionResponse;
+        subscribe_agent_output(execution_id: String, after_seq: Option<u64>) -> OutputReplay;
+        ack_agent_output(execution_id: String, seq: u64) -> ();
+        cancel_agent_execution(execution_id: String, workspace_path: Option<String>) -> Vec<String>;
+        list_agent_alerts(execution_id: Option<String>) -> Vec<AgentAlert>;
+        create_tmux_session(request: TmuxExecutionRequest) -> TmuxSession;
+        list_tmux_sessions() -> Vec<TmuxSession>;
+        capture_tmux_output(session_id: String) -> String;
+        send_tmux_keys(session_id: String, keys: String) -> ();
+        kill_tmux_session(session_id: String, confirmation: Option<String>) -> ();
+        execute_claude_code_competition(
+            request: ClaudeCodeCompetitionRequest,
+        ) -> ClaudeCodeCompetitionResult;
+        execute_multi_runtime_competition(
+            request: MultiRuntimeCompetitionRequest,
+        ) -> ClaudeCodeCompetitionResult;
+        get_competition_status(competition_id: String) -> ClaudeCodeCompetitionResult;
+        cancel_competition(competition_id: String, cleanup_worktrees: bool) -> ();
+        execute_debate(request: DebateRequest) -> DebateResult;
+        get_debate_status(debate_id: String) -> DebateStatus;
+        cancel_debate(debate_id: String, cleanup_worktrees: bool) -> ();
+        analyze_task_with_claude_code(
+            request: ClaudeCodeAnalysisRequest,
+        ) -> ClaudeCodeAnalysisResponse;
+    }
+    annotations {
+        list_annotations(path: Option<String>) -> Vec<Annotation>;
+        add_annotation(path: String, start_line: u32, end_line: u32, text: String) -> Annotation;
+        update_annotation(
+            id: String,
+            text: Option<String>,
+            start_line: Option<u32>,
+            end_line: Option<u32>,
+        ) -> Annotation;
+        delete_annotation(id: String) -> ();
+        get_annotation_context(ids: Option<Vec<String>>) -> String;
+    }
+    auto_mode {
+        explain_execution_plan(task: String) -> AutoDecision;
+        run_auto_task(task: String, workspace_path: Option<String>) -> AutoRunResult;
+    }
+    budget {
+        get_budget_status(session_id: Option<String>) -> BudgetStatus;
+        get_budget_config() -> BudgetConfig;
+        set_budget_config(config: BudgetConfig) -> ();
+        approve_budget_override(scope: BudgetScope, session_id: Option<String>) -> BudgetStatus;
+    }
+    changelog {
+        generate_changelog(bump: Option<Bump>, polish: Option<bool>) -> ChangelogDraft;
+    }
+    checkpoint {
+        create_checkpoint(message: Option<String>) -> Option<Checkpoint>;
+        list_checkpoints(limit: Option<usize>) -> Vec<Checkpoint>;
+        restore_checkpoint(sha: String, paths: Option<Vec<String>>) -> Option<Checkpoint>;
+        get_checkpoint_config() -> CheckpointConfig;
+        set_checkpoint_config(config: CheckpointConfig) -> ();
+    }
+    clipboard_history {
+        get_clipboard_history() -> Vec<ClipboardEntry>;
+        push_clipboard_history(
+            text: String,
+            source: Option<ClipboardSource>,
+        ) -> Option<ClipboardEntry>;
+        remove_clipboard_entry(id: String) -> ();
+        clear_clipboard_history() -> ();
+    }
+    commit_message {
+        generate_commit_message(regenerate: Option<bool>) -> CommitMessageDraft;
+    }
+    competition {
+        score_competition(workspace_path: String, session_id: String) -> CompetitionRanking;
+        summarize_competition(workspace_path: String, session_id: String) -> CompetitionSummary;
+        merge_competition_winner(workspace_path: String, session_id: String) -> WinnerMerge;
+    }
+    confirmation {
+        request_confirmation(action: DangerousAction) -> String;
+    }
+    editor {
+        insert_text(buffer_id: String, position: usize, text: String) -> ();
+        delete_text(buffer_id: String, range: TextRange) -> ();
+        replace_text(buffer_id: String, range: TextRange, text: String) -> ();
+        apply_content(buffer_id: String, content: String) -> usize;
+        undo(buffer_id: String) -> ();
+        redo(buffer_id: String) -> ();
+        get_buffer_content(buffer_id: String) -> String;
+        get_buffer_info(buffer_id: String) -> BufferInfo;
+        close_buffer(buffer_id: String, force: bool) -> ();
+        list_buffers() -> Vec<String>;
+    }
+    features {
+        list_feature_flags() -> Vec<FeatureFlagState>;
+        set_feature_flag(name: String, enabled: bool) -> ();
+    }
+    file {
+        open_file(path: String) -> OpenFileResponse;
+        save_file(path: String, content: String) -> ();
+        read_directory(path: String) -> Vec<file::FileNode>;
+        create_file(path: String) -> ();
+        create_directory(path: String) -> ();
+        delete_path(path: String, confirmation: Option<String>) -> ();
+        rename_path(old_path: String, new_path: String) -> ();
+    }
+    git {
+        git_status() -> GitStatus;
+        git_add(files: Vec<String>) -> ();
+        git_reset(files: Vec<String>) -> ();
+        git_commit(message: String) -> String;
+        git_push(
+            remote: Option<String>,
+            branch: Option<String>,
+            confirmation: Option<String>,
+        ) -> ();
+        git_pull(remote: Option<String>, branch: Option<String>) -> ();
+        git_log(limit: Option<usize>) -> Vec<GitCommit>;
+        git_branches() -> Vec<String>;
+        git_checkout(branch: String) -> ();
+        git_create_branch(name: String) -> ();
+        git_init(path: String) -> String;
+        git_list_worktrees() -> Vec<git::WorktreeInfo>;
+        git_create_worktree(path: String, branch: String, create_branch: bool) -> git::WorktreeInfo;
+        git_remove_worktree(path: String, force: bool) -> ();
+        git_prune_worktrees() -> ();
+        git_stash_list() -> Vec<StashEntry>;
+        git_stash_push(message: Option<String>, include_untracked: bool) -> bool;
+        git_stash_apply(index: usize) -> ();
+        git_stash_pop(index: usize) -> ();
+        git_stash_drop(index: usize) -> ();
+        git_stash_show(index: usize) -> String;
+        git_diff_hunks(staged: bool, path: Option<String>) -> Vec<Hunk>;
+        git_stage_hunk(path: String, index: usize) -> ();
+        git_unstage_hunk(path: String, index: usize) -> ();
+        git_log_graph(limit: Option<usize>, all: Option<bool>) -> Vec<GraphRow>;
+        git_blame(path: String) -> Vec<BlameLine>;
+        git_cherry_pick(sha: String) -> OperationStatus;
+        git_revert(sha: String) -> OperationStatus;
+        git_rebase_plan(onto: String) -> RebasePlan;
+        git_rebase_start(plan: RebasePlan) -> OperationStatus;
+        git_operation_status() -> OperationStatus;
+        git_operation_continue() -> OperationStatus;
+        git_operation_skip() -> OperationStatus;
+        git_operation_abort() -> ();
+        git_conflicted_files(repo_path: Option<String>) -> Vec<String>;
+        git_load_conflicts(path: String, repo_path: Option<String>) -> ConflictFile;
+        git_restore_conflict_base(path: String, repo_path: Option<String>) -> ConflictFile;
+        git_resolve_conflicts(
+            path: String,
+            resolutions: Vec<Resolution>,
+            repo_path: Option<String>,
+        ) -> Vec<String>;
+        get_merge_conflicts(repo_path: Option<String>) -> Vec<MergeConflict>;
+        resolve_conflict(
+            path: String,
+            resolution: FileResolution,
+            repo_path: Option<String>,
+        ) -> Vec<String>;
+    }
+    issues {
+        get_issue_trackers(workspace_path: String) -> Vec<IssueTrackerStatus>;
+        set_issue_tracker_token(provider: IssueProvider, token: Option<String>) -> ();
+        list_issues(
+            workspace_path: String,
+            provider: IssueProvider,
+            query: Option<String>,
+        ) -> Vec<Issue>;
+        get_issue(workspace_path: String, provider: IssueProvider, key: String) -> Issue;
+        link_session_issue(
+            workspace_path: String,
+            session_id: String,
+            issue: Option<IssueLink>,
+        ) -> WorktreeSession;
+        post_session_report(workspace_path: String, session_id: String) -> String;
+    }
+    lsp {
+        start_lsp_server(language: String) -> ();
+        stop_lsp_server(language: String) -> ();
+        restart_lsp_server(language: String) -> ();
+        get_running_lsp_servers() -> Vec<String>;
+        lsp_detach_buffer(file_path: String) -> ();
+        lsp_attach_buffer(file_path: String, content: String) -> ();
+        get_detached_lsp_buffers() -> Vec<String>;
+        get_lsp_watchdog_config() -> WatchdogConfig;
+        set_lsp_watchdog_config(config: WatchdogConfig) -> ();
+        lsp_did_open(file_path: String, content: String, language_id: String) -> ();
+        lsp_did_change(file_path: String, content: String, version: i32) -> ();
+        lsp_did_save(file_path: String, content: Option<String>) -> ();
+        lsp_did_close(file_path: String) -> ();
+        lsp_completion(file_path: String, line: u32, character: u32) -> Vec<LspCompletionItem>;
+        lsp_hover(file_path: String, line: u32, character: u32) -> Option<LspHoverInfo>;
+        lsp_goto_definition(file_path: String, line: u32, character: u32) -> Vec<LspLocation>;
+        lsp_diagnostics(file_path: String) -> Vec<LspDiagnostic>;
+    }
+    notifications {
+        list_notifications() -> Vec<Notification>;
+        dismiss_notification(id: u64) -> ();
+        clear_notifications() -> ();
+    }
+    observer {
+        export_session_events(session_id: String, target: String) -> StreamTarget;
+        stop_session_export(session_id: String) -> Vec<StreamTarget>;
+        observe_session(
+            session_id: String,
+            source: String,
+            session_store: Option<String>,
+        ) -> Option<WorktreeSession>;
+        get_observed_session(session_id: String) -> Option<WorktreeSession>;
+        list_observed_sessions() -> Vec<ObservedSession>;
+        stop_observing(session_id: String) -> ();
+    }
+    mcp {
+        get_mcp_status() -> McpStatus;
+        start_mcp_server(bind: Option<String>) -> McpStatus;
+        stop_mcp_server() -> bool;
+    }
+    remote {
+        get_remote_status() -> RemoteStatus;
+        start_remote_server(bind: Option<String>) -> RemoteStatus;
+        stop_remote_server() -> bool;
+    }
+    optimizer {
+        optimize_task(task_description: String, current_subtasks: usize) -> OptimizeTaskResponse;
+        calculate_instances(
+            complexity_class: String,
+            subtask_count: usize,
+        ) -> CalculateInstancesResponse;
+        get_complexity_info(complexity_class: String) -> ComplexityInfoResponse;
+        get_estimate_cache_stats() -> EstimateCacheStatsResponse;
+        get_llm_provider_config() -> LlmProviderConfig;
+        set_llm_provider_config(config: LlmProviderConfig) -> ();
+        run_ab_test() -> ABTestResult;
+    }
+    plugin {
+        list_plugins() -> Vec<PluginInfo>;
+        get_plugin(plugin_id: String) -> Option<PluginInfo>;
+        enable_plugin(plugin_id: String) -> ();
+        disable_plugin(plugin_id: String) -> ();
+        install_plugin(source_path: String) -> String;
+        uninstall_plugin(plugin_id: String) -> ();
+    }
+    quick_fix {
+        diagnostic_quick_actions(
+            buffer_id: String,
+            diagnostic: LspDiagnostic,
+        ) -> Vec<DiagnosticQuickAction>;
+        explain_diagnostic(buffer_id: String, diagnostic: LspDiagnostic) -> DiagnosticExplanation;
+    }
+    quick_task {
+        submit_quick_task(task: String) -> ();
+        dismiss_quick_task() -> ();
+        get_quick_task_shortcut() -> String;
+        set_quick_task_shortcut(shortcut: String) -> ();
+    }
+    refactor {
+        extract_variable(buffer_id: String, range: TextRange, name: String) -> RefactorEdit;
+        extract_function(buffer_id: String, range: TextRange, name: String) -> RefactorEdit;
+        ai_refactor(
+            buffer_id: String,
+            range: TextRange,
+            instruction: String,
+        ) -> RefactorProposalResponse;
+    }
+    review {
+        review_diff(
+            range: Option<DiffRange>,
+            session_id: Option<String>,
+            workspace_path: Option<String>,
+        ) -> ReviewDiffResult;
+        get_review_report() -> Option<ReviewReport>;
+        clear_review() -> ();
+    }
+    search {
+        workspace_search(
+            search_id: String,
+            pattern: String,
+            options: Option<GrepOptions>,
+        ) -> GrepSummary;
+    }
+    session_history {
+        create_session(workspace_path: String, session: WorktreeSession) -> WorktreeSession;
+        update_session(workspace_path: String, session: WorktreeSession) -> WorktreeSession;
+        get_session(workspace_path: String, session_id: String) -> WorktreeSession;
+        get_session_journal(session_id: String) -> Vec<JournalEntry>;
+        get_session_agent_changes(workspace_path: String, session_id: String) -> Vec<AgentChange>;
+        get_all_sessions(workspace_path: String) -> Vec<WorktreeSession>;
+        delete_session(workspace_path: String, session_id: String) -> ();
+        list_deleted_sessions(workspace_path: String) -> Vec<WorktreeSession>;
+        restore_session(workspace_path: String, session_id: String) -> WorktreeSession;
+        purge_sessions(workspace_path: Option<String>, dry_run: bool) -> Vec<WorktreeSession>;
+        migrate_workspace(old_path: String, workspace_path: Option<String>) -> usize;
+        get_sessions_config() -> SessionsConfig;
+        set_sessions_config(config: SessionsConfig) -> ();
+        sync_sessions() -> SyncReport;
+        encrypt_sessions() -> usize;
+        add_chat_message(
+            workspace_path: String,
+            session_id: String,
+            message: ChatMessage,
+        ) -> WorktreeSession;
+        update_instance_status(
+            workspace_path: String,
+            session_id: String,
+            instance_id: u32,
+            new_status: String,
+        ) -> WorktreeSession;
+    }
+    system {
+        open_in_vscode(path: String) -> String;
+        open_terminal(path: String) -> String;
+        open_in_finder(path: String) -> String;
+        copy_to_clipboard(text: String) -> String;
+    }
+    team_feed {
+        get_team_feed(workspace_path: String, limit: Option<usize>) -> Vec<FeedEntry>;
+        get_team_artifact(
+            workspace_path: String,
+            session_id: String,
+            artifact_id: String,
+        ) -> SessionArtifact;
+    }
+    telemetry {
+        get_agent_stats() -> BTreeMap<String, AgentStats>;
+    }
+    todos {
+        list_todos(refresh: Option<bool>) -> Vec<TodoItem>;
+        update_todos(path: String) -> Vec<TodoItem>;
+    }
+    update {
+        check_for_updates() -> Option<Release>;
+        install_update() -> ();
+        get_updates_config() -> UpdatesConfig;
+        set_updates_config(config: UpdatesConfig) -> ();
+    }
+    workspace {
+        select_workspace(path: Option<String>) -> WorkspaceInfo;
+        get_workspace() -> WorkspaceInfo;
+    }
+    worktree {
+        list_worktrees(competition_id: String) -> Vec<worktree::WorktreeInfo>;
+        get_worktree_files(
+            worktree_path: String,
+            max_depth: Option<usize>,
+        ) -> Vec<worktree::FileNode>;
+        delete_worktree(worktree_id: String, confirmation: Option<String>) -> ();
+        get_file_diff(worktree_path: String, file_path: String) -> FileDiff;
+        get_worktree_diff(worktree_path: String) -> Vec<FileDiff>;
+        get_semantic_diff(worktree_path: String, file_path: String) -> worktree::SemanticFileDiff;
+    }
+    };
+
+    #[cfg(feature = "terminal")]
+    commands.extend(command_schemas! { gen;
+    terminal {
+        execute_command(command: String) -> String;
+        get_terminal_output() -> Vec<String>;
+        get_terminal_tail(lines: usize) -> Vec<String>;
+        clear_terminal() -> ();
+        get_current_directory() -> String;
+        set_current_directory(path: String) -> ();
+        get_command_history(filter: Option<CommandHistoryFilter>) -> Vec<CommandHistoryEntry>;
+        get_terminal_info() -> TerminalInfo;
+    }
+    });
+
+    commands
+}
+
+fn events(gen: &mut SchemaGenerator) -> Vec<EventSchema> {
+    vec![
+        EventSchema::new::<Notification>(gen, NOTIFICATION_EVENT),
+        EventSchema::new::<BudgetWarning>(gen, BUDGET_WARNING_EVENT),
+        EventSchema::new::<WorkspaceInfo>(gen, WORKSPACE_CHANGED_EVENT),
+        EventSchema::new::<SearchResults<'static>>(gen, SEARCH_RESULTS_EVENT),
+        EventSchema::new::<Vec<String>>(gen, FILES_DROPPED_EVENT),
+        EventSchema::new::<String>(gen, WORKSPACE_DROPPED_EVENT),
+        EventSchema::new::<SessionEvent>(gen, OBSERVED_EVENT),
+        EventSchema::new::<OutputChunk>(gen, AGENT_OUTPUT_EVENT),
+        EventSchema::new::<AgentAlert>(gen, AGENT_ALERT_EVENT),
+        EventSchema::new::<CompetitionRanking>(gen, COMPETITION_SCORED_EVENT),
+        EventSchema::new::<CompetitionSummary>(gen, COMPETITION_SUMMARIZED_EVENT),
+        // Competition and debate progress, built as free-form JSON
+        EventSchema::new::<serde_json::Value>(gen, "competition-output"),
+        EventSchema::new::<serde_json::Value>(gen, "debate-round-output"),
+        EventSchema::new::<serde_json::Value>(gen, "debate-status"),
+    ]
+}
+
+/// Generate the schema of the backend surface
+pub fn api_schema() -> ApiSchema {
+    let settings = SchemaSettings::draft07();
+    let json_schema = settings.meta_schema.clone().unwrap_or_default();
+    let mut gen = settings.into_generator();
+    let commands = commands(&mut gen);
+    let events = events(&mut gen);
+
+    ApiSchema {
+        api_version: API_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        json_schema,
+        commands,
+        events,
+        definitions: gen.take_definitions(),
+    }
+}
+
+/// Schema of all commands, their arguments and responses, and all events
+///
+/// # Returns
+/// * `Ok(schema)` - Commands and events with JSON Schemas of their types
+#[tauri::command]
+pub async fn get_api_schema() -> Result<ApiSchema, String> {
+    Ok(api_schema())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("workspace_path"), "workspacePath");
+        assert_eq!(camel_case("include_untracked"), "includeUntracked");
+        assert_eq!(camel_case("sha"), "sha");
+    }
+
+    #[test]
+    #[cfg(feature = "terminal")]
+    fn test_schema_covers_registered_commands() {
+        use std::collections::BTreeSet;
+
+        let main = include_str!("../main.rs");
+        let start = main.find("generate_handler![").unwrap();
+        let end = start + main[start..].find(']').unwrap();
+        let registered: BTreeSet<&str> = main[start..end]
+            .lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter_map(|line| line.strip_prefix("commands::"))
+            .collect();
+
+        let schema = api_schema();
+        let described: BTreeSet<&str> = schema.commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(described.len(), schema.commands.len(), "duplicate commands");
+        assert_eq!(registered, described);
+    }
+
+    #[test]
+    fn test_schema_describes_arguments_and_types() {
+        let schema = api_schema();
+        let command = |name: &str| schema.commands.iter().find(|c| c.name == name).unwrap();
+
+        let feed = command("get_team_feed");
+        let args: Vec<(&str, bool)> = feed
+            .args
+            .iter()
+            .map(|a| (a.name.as_str(), a.required))
+            .collect();
+        assert_eq!(args, vec![("workspacePath", true), ("limit", false)]);
+        assert_eq!(command("observe_session").feature_flag.as_deref(), Some("observer_mode"));
+
+        for name in [
+            "WorktreeSession",
+            "FeedEntry",
+            "WorktreeFileNode",
+            "CompetitionWorktreeInfo",
+        ] {
+            assert!(schema.definitions.contains_key(name), "{} is not defined", name);
+        }
+        assert!(schema.events.iter().any(|e| e.name == NOTIFICATION_EVENT));
+
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["apiVersion"], API_VERSION);
+        assert_eq!(json["commands"][0]["args"], serde_json::json!([]));
+    }
+}
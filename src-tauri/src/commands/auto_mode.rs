@@ -0,0 +1,886 @@
+//! Coordinator Auto Mode
+//!
+//! When `ait42.auto_coordinator` is enabled, a raw task can be handed to the
+//! Coordinator instead of choosing a mode by hand. The task's complexity is
+//! analyzed (with the LLM optimizer when an API key is set, otherwise from
+//! the Ω-theory default), the `[ait42.auto_mode]` thresholds pick single,
+//! parallel, competition, ensemble or debate execution, and the chosen mode
+//! is launched. Every step of the decision is recorded on the session as a
+//! `decision` artifact.
+
+use ait42_config::{AutoModeConfig, ModeRule};
+use omega_theory::ComplexityClass;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tauri::State;
+use tracing::{info, warn};
+
+use crate::commands::ait42::{
+    default_debate_rounds, ensure_registry_initialized, execute_agent, execute_debate,
+    execute_multi_runtime_competition, execute_parallel, get_registry, AgentExecutionRequest,
+    AgentExecutionResponse, ClaudeCodeCompetitionResult, DebateRequest, DebateResult,
+    MultiRuntimeCompetitionRequest, ParallelExecutionRequest, RoleDefinition,
+    RuntimeAllocationRequest,
+};
+use crate::commands::optimizer::OptimizerState;
+use crate::commands::session_history::{
+    outcome_history, upsert_session, SessionArtifact, WorktreeInstance, WorktreeSession,
+};
+use crate::feature_flags;
+use crate::optimizer::MemoryModel;
+use crate::sanitize;
+use crate::state::AppState;
+
+/// Agents run side by side in parallel mode at most
+const MAX_PARALLEL_AGENTS: usize = 3;
+
+/// Roles taking part in a debate
+const DEBATE_ROLES: usize = 3;
+
+/// Rounds every debate role speaks in
+const DEBATE_ROUNDS: usize = 3;
+
+/// Execution mode chosen by the Coordinator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoMode {
+    Single,
+    Parallel,
+    Competition,
+    Ensemble,
+    Debate,
+}
+
+impl fmt::Display for AutoMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AutoMode::Single => "single",
+            AutoMode::Parallel => "parallel",
+            AutoMode::Competition => "competition",
+            AutoMode::Ensemble => "ensemble",
+            AutoMode::Debate => "debate",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Complexity analysis of a task
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAnalysis {
+    pub complexity_class: String,
+    pub subtasks: usize,
+    /// Instances the instance calculator recommends
+    pub instances: usize,
+    /// Confidence of the LLM estimate; `None` without an LLM analysis
+    pub confidence: Option<f64>,
+    pub reasoning: String,
+}
+
+impl TaskAnalysis {
+    fn new(
+        class: ComplexityClass,
+        subtasks: usize,
+        confidence: Option<f64>,
+        reasoning: String,
+        state: &OptimizerState,
+    ) -> Self {
+        let instances = state
+            .calculator()
+            .calculate_instances(class, subtasks)
+            .recommended_instances;
+        Self {
+            complexity_class: format!("{:?}", class),
+            subtasks,
+            instances,
+            confidence,
+            reasoning,
+        }
+    }
+}
+
+/// Mode chosen for a task and how it was reached
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoDecision {
+    pub task: String,
+    pub mode: AutoMode,
+    /// Competition or ensemble instances, debate roles, or agents
+    pub instances: usize,
+    /// Agents to run in single and parallel mode
+    pub agents: Vec<String>,
+    pub analysis: TaskAnalysis,
+    /// Runs times the configured cost per run
+    pub estimated_cost_usd: f64,
+    /// Steps of the decision, in order
+    pub trace: Vec<String>,
+}
+
+impl AutoDecision {
+    /// Markdown report of the decision
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Auto mode: {}\n\n**Task:** {}\n\n", self.mode, self.task.trim());
+        for (index, step) in self.trace.iter().enumerate() {
+            out.push_str(&format!("{}. {}\n", index + 1, step));
+        }
+        out
+    }
+}
+
+impl AutoMode {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "single" => Some(AutoMode::Single),
+            "parallel" => Some(AutoMode::Parallel),
+            "competition" => Some(AutoMode::Competition),
+            "ensemble" => Some(AutoMode::Ensemble),
+            "debate" => Some(AutoMode::Debate),
+            _ => None,
+        }
+    }
+
+    /// Agent or instance runs of a plan with `instances`
+    fn runs(self, instances: usize) -> usize {
+        match self {
+            AutoMode::Debate => instances * DEBATE_ROUNDS,
+            _ => instances,
+        }
+    }
+}
+
+/// Why `rule` does not apply to `analysis`, or `None` if it does
+fn rule_mismatch(rule: &ModeRule, analysis: &TaskAnalysis) -> Option<String> {
+    if !rule.complexity.is_empty()
+        && !rule
+            .complexity
+            .iter()
+            .any(|class| class.eq_ignore_ascii_case(&analysis.complexity_class))
+    {
+        return Some(format!(
+            "complexity {} not in [{}]",
+            analysis.complexity_class,
+            rule.complexity.join(", ")
+        ));
+    }
+    if let Some(min) = rule.min_subtasks.filter(|min| analysis.subtasks < *min) {
+        return Some(format!("{} subtasks < {}", analysis.subtasks, min));
+    }
+    if let Some(max) = rule.max_subtasks.filter(|max| analysis.subtasks > *max) {
+        return Some(format!("{} subtasks > {}", analysis.subtasks, max));
+    }
+    if rule.min_confidence.is_some() || rule.max_confidence.is_some() {
+        let Some(confidence) = analysis.confidence else {
+            return Some("no confidence estimate".to_string());
+        };
+        if let Some(min) = rule.min_confidence.filter(|min| confidence < *min) {
+            return Some(format!("confidence {:.2} < {:.2}", confidence, min));
+        }
+        if let Some(max) = rule.max_confidence.filter(|max| confidence > *max) {
+            return Some(format!("confidence {:.2} > {:.2}", confidence, max));
+        }
+    }
+    None
+}
+
+/// First configured rule matching `analysis`, as mode and instance count
+fn matching_rule(
+    analysis: &TaskAnalysis,
+    policy: &AutoModeConfig,
+    trace: &mut Vec<String>,
+) -> Option<(AutoMode, Option<usize>)> {
+    for (index, rule) in policy.rules.iter().enumerate() {
+        let Some(mode) = AutoMode::parse(&rule.mode) else {
+            trace.push(format!("Rule {} skipped: unknown mode {:?}", index + 1, rule.mode));
+            continue;
+        };
+        match rule_mismatch(rule, analysis) {
+            Some(reason) => {
+                trace.push(format!("Rule {} ({}) skipped: {}", index + 1, mode, reason))
+            }
+            None => {
+                trace.push(format!("Rule {} matches: {}", index + 1, mode));
+                return Some((mode, rule.instances));
+            }
+        }
+    }
+    None
+}
+
+/// Mode selected by the subtask and confidence thresholds
+fn threshold_mode(
+    analysis: &TaskAnalysis,
+    policy: &AutoModeConfig,
+    trace: &mut Vec<String>,
+) -> AutoMode {
+    let subtasks = analysis.subtasks;
+    match analysis.confidence {
+        Some(confidence) if confidence < policy.debate_below_confidence => {
+            trace.push(format!(
+                "Confidence {:.2} is below {:.2}: debate the approach first",
+                confidence, policy.debate_below_confidence
+            ));
+            AutoMode::Debate
+        }
+        _ if subtasks <= policy.single_max_subtasks => {
+            trace.push(format!(
+                "{} subtask(s) <= single_max_subtasks ({}): one agent",
+                subtasks, policy.single_max_subtasks
+            ));
+            AutoMode::Single
+        }
+        _ if subtasks <= policy.parallel_max_subtasks => {
+            trace.push(format!(
+                "{} subtasks <= parallel_max_subtasks ({}): agents in parallel",
+                subtasks, policy.parallel_max_subtasks
+            ));
+            AutoMode::Parallel
+        }
+        _ if subtasks >= policy.ensemble_min_subtasks => {
+            trace.push(format!(
+                "{} subtasks >= ensemble_min_subtasks ({}): split across an ensemble",
+                subtasks, policy.ensemble_min_subtasks
+            ));
+            AutoMode::Ensemble
+        }
+        _ => {
+            trace.push(format!(
+                "{} subtasks between parallel_max_subtasks ({}) and ensemble_min_subtasks ({}): \
+                 competing instances",
+                subtasks, policy.parallel_max_subtasks, policy.ensemble_min_subtasks
+            ));
+            AutoMode::Competition
+        }
+    }
+}
+
+/// Pick the execution mode for `analysis` under `policy`
+///
+/// `agents` are the agents recommended for the task, best first; single and
+/// parallel runs need at least one.
+pub fn decide(
+    task: &str,
+    analysis: TaskAnalysis,
+    agents: &[String],
+    policy: &AutoModeConfig,
+) -> AutoDecision {
+    let mut trace = vec![format!(
+        "Complexity {} with {} subtasks, {} instance(s) recommended{}",
+        analysis.complexity_class,
+        analysis.subtasks,
+        analysis.instances,
+        match analysis.confidence {
+            Some(confidence) => format!(" (confidence {:.2})", confidence),
+            None => " (no LLM estimate)".to_string(),
+        }
+    )];
+
+    let (mut mode, requested) = match matching_rule(&analysis, policy, &mut trace) {
+        Some(choice) => choice,
+        None => (threshold_mode(&analysis, policy, &mut trace), None),
+    };
+
+    let mut chosen = Vec::new();
+    if matches!(mode, AutoMode::Single | AutoMode::Parallel) {
+        if agents.is_empty() {
+            trace
+                .push("No agent matches the task: run competing Claude Code instances".to_string());
+            mode = AutoMode::Competition;
+        } else {
+            let count = match mode {
+                AutoMode::Single => 1,
+                _ => requested
+                    .unwrap_or(analysis.subtasks)
+                    .clamp(2, MAX_PARALLEL_AGENTS)
+                    .min(agents.len()),
+            };
+            chosen = agents[..count].to_vec();
+            if mode == AutoMode::Parallel && chosen.len() < 2 {
+                trace.push("Only one agent matches the task: run it alone".to_string());
+                mode = AutoMode::Single;
+            }
+        }
+    }
+
+    let mut instances = match mode {
+        AutoMode::Single | AutoMode::Parallel => chosen.len(),
+        AutoMode::Debate => DEBATE_ROLES,
+        AutoMode::Competition | AutoMode::Ensemble => {
+            requested.unwrap_or(analysis.instances).clamp(2, 10)
+        }
+    };
+
+    // Scale the plan down until it fits the cost ceiling
+    let cost =
+        |mode: AutoMode, instances: usize| mode.runs(instances) as f64 * policy.cost_per_run_usd;
+    if let Some(ceiling) = policy.max_cost_usd {
+        let estimate = cost(mode, instances);
+        if estimate > ceiling {
+            let affordable = (ceiling / policy.cost_per_run_usd).floor() as usize;
+            let before = format!("{} x{}", mode, instances);
+            match mode {
+                AutoMode::Competition | AutoMode::Ensemble if affordable >= 2 => {
+                    instances = affordable;
+                }
+                AutoMode::Parallel if affordable >= 2 => {
+                    chosen.truncate(affordable);
+                    instances = chosen.len();
+                }
+                _ if affordable >= 1 && !agents.is_empty() => {
+                    mode = AutoMode::Single;
+                    chosen = agents[..1].to_vec();
+                    instances = 1;
+                }
+                _ => {}
+            }
+            if cost(mode, instances) <= ceiling {
+                trace.push(format!(
+                    "Estimated ${:.2} for {} exceeds the ${:.2} ceiling: reduced to {} x{}",
+                    estimate, before, ceiling, mode, instances
+                ));
+            } else {
+                trace.push(format!(
+                    "Estimated ${:.2} exceeds the ${:.2} ceiling and no cheaper plan is possible",
+                    cost(mode, instances),
+                    ceiling
+                ));
+            }
+        }
+    }
+
+    if !chosen.is_empty() {
+        trace.push(format!("Agents: {}", chosen.join(", ")));
+    } else if matches!(mode, AutoMode::Competition | AutoMode::Ensemble) {
+        trace.push(format!("{} instances of {}", instances, policy.model));
+    }
+    let estimated_cost_usd = cost(mode, instances);
+    trace.push(format!("Launching {} mode (estimated ${:.2})", mode, estimated_cost_usd));
+
+    AutoDecision {
+        task: task.to_string(),
+        mode,
+        instances,
+        agents: chosen,
+        analysis,
+        estimated_cost_usd,
+        trace,
+    }
+}
+
+/// Result of an auto mode run
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoRunResult {
+    /// Session the run and its decision are recorded on
+    pub session_id: String,
+    pub decision: AutoDecision,
+    /// Agent outputs of a single or parallel run
+    pub agent_results: Vec<AgentExecutionResponse>,
+    pub competition: Option<ClaudeCodeCompetitionResult>,
+    pub debate: Option<DebateResult>,
+}
+
+async fn analyze(task: &str, optimizer: &OptimizerState, memory: &MemoryModel) -> TaskAnalysis {
+    match optimizer.analyze(task, memory).await {
+        Ok(result) => TaskAnalysis::new(
+            result.complexity_class,
+            result.recommended_subtasks,
+            Some(result.confidence),
+            result.reasoning,
+            optimizer,
+        ),
+        Err(e) => {
+            warn!("LLM complexity analysis unavailable, using default: {}", e);
+            let class = ComplexityClass::from_description(task);
+            TaskAnalysis::new(
+                class,
+                *class.to_subtask_range().start(),
+                None,
+                format!("LLM analysis unavailable: {}", e),
+                optimizer,
+            )
+        }
+    }
+}
+
+/// Analyze `task` and decide how to run it, without launching anything
+async fn plan(
+    state: &State<'_, AppState>,
+    optimizer: &OptimizerState,
+    task: &str,
+) -> Result<AutoDecision, String> {
+    sanitize::task(task)?;
+    let (ait42, ensemble_enabled) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("Failed to lock config: {}", e))?;
+        (
+            config.ait42.clone(),
+            feature_flags::enabled(&config, feature_flags::ENSEMBLE_MODE),
+        )
+    };
+    let workspace = state.working_dir.lock().await.clone();
+    let memory = outcome_history(state, &workspace.to_string_lossy());
+    let analysis = analyze(task, optimizer, &memory).await;
+    ensure_registry_initialized(state).await?;
+    let mut agents: Vec<String> = {
+        let registry_guard = get_registry(state)?;
+        let registry = registry_guard
+            .as_ref()
+            .ok_or_else(|| "Agent registry not initialized".to_string())?;
+        registry
+            .recommend(task, MAX_PARALLEL_AGENTS)
+            .into_iter()
+            .map(|r| r.agent)
+            .collect()
+    };
+    if agents.is_empty() {
+        agents.extend(ait42.default_agent);
+    }
+
+    let mut decision = decide(task, analysis, &agents, &ait42.auto_mode);
+    if decision.mode == AutoMode::Ensemble && !ensemble_enabled {
+        decision.mode = AutoMode::Competition;
+        decision
+            .trace
+            .push("ensemble_mode feature is disabled: competition instead".to_string());
+    }
+    info!("Auto mode chose {} for task", decision.mode);
+    Ok(decision)
+}
+
+/// Roles of an auto mode debate
+fn debate_roles() -> Vec<RoleDefinition> {
+    [
+        (
+            "architect",
+            "Technical Architect",
+            "# あなたのロール: Technical Architect (技術アーキテクト)\n\n\
+             長期的な保守性、スケーラビリティ、設計原則の観点から議論してください。",
+        ),
+        (
+            "pragmatist",
+            "Pragmatist",
+            "# あなたのロール: Pragmatist (現実主義者)\n\n\
+             実装期間、リスク、段階的に進められるかの観点から議論してください。",
+        ),
+        (
+            "innovator",
+            "Innovator",
+            "# あなたのロール: Innovator (革新者)\n\n\
+             新しい技術や手法、ユーザー体験の向上の観点から議論してください。",
+        ),
+    ]
+    .into_iter()
+    .map(|(id, name, prompt)| RoleDefinition {
+        id: id.to_string(),
+        name: name.to_string(),
+        system_prompt: prompt.to_string(),
+    })
+    .collect()
+}
+
+fn session_for(id: &str, decision: &AutoDecision, model: Option<String>) -> WorktreeSession {
+    let now = chrono::Utc::now().to_rfc3339();
+    WorktreeSession {
+        id: id.to_string(),
+        r#type: decision.mode.to_string(),
+        task: decision.task.clone(),
+        status: "running".to_string(),
+        created_at: now.clone(),
+        updated_at: now.clone(),
+        completed_at: None,
+        instances: Vec::new(),
+        chat_history: Vec::new(),
+        model,
+        timeout_seconds: None,
+        preserve_worktrees: None,
+        winner_id: None,
+        runtime_mix: None,
+        total_duration: None,
+        total_files_changed: None,
+        total_lines_added: None,
+        total_lines_deleted: None,
+        artifacts: vec![SessionArtifact {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: "decision".to_string(),
+            title: format!("Auto mode: {}", decision.mode),
+            content: decision.to_markdown(),
+            created_at: now,
+        }],
+        deleted_at: None,
+        feature_flags: None,
+        origin: None,
+        issue: None,
+        agents: None,
+        context_scans: Vec::new(),
+        complexity_class: Some(decision.analysis.complexity_class.clone()),
+        subtask_count: Some(decision.analysis.subtasks as u32),
+    }
+}
+
+fn agent_instance(
+    index: usize,
+    workspace: &str,
+    result: &AgentExecutionResponse,
+) -> WorktreeInstance {
+    WorktreeInstance {
+        instance_id: index as u32,
+        worktree_path: workspace.to_string(),
+        branch: String::new(),
+        agent_name: result.agent_name.clone(),
+        agent_version: None,
+        touched_modules: None,
+        diff_summary: None,
+        status: if result.error.is_some() {
+            "failed".to_string()
+        } else {
+            "completed".to_string()
+        },
+        tmux_session_id: String::new(),
+        output: result.output.clone().or_else(|| result.error.clone()),
+        start_time: None,
+        end_time: Some(chrono::Utc::now().to_rfc3339()),
+        files_changed: None,
+        lines_added: None,
+        lines_deleted: None,
+        runtime: None,
+        model: None,
+        runtime_label: None,
+    }
+}
+
+/// Explain how auto mode would run a task, without running it
+///
+/// Works whether or not auto mode is enabled, so policies can be tried out
+/// before turning it on.
+#[tauri::command]
+pub async fn explain_execution_plan(
+    state: State<'_, AppState>,
+    optimizer: State<'_, OptimizerState>,
+    task: String,
+) -> Result<AutoDecision, String> {
+    plan(&state, &optimizer, &task).await
+}
+
+/// Run a task in the mode the Coordinator picks for it
+///
+/// The session, with the decision attached, is stored for `workspace_path`,
+/// or for the current working directory if none is given.
+#[tauri::command]
+pub async fn run_auto_task(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    optimizer: State<'_, OptimizerState>,
+    task: String,
+    workspace_path: Option<String>,
+) -> Result<AutoRunResult, String> {
+    let enabled = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .ait42
+        .auto_coordinator;
+    if !enabled {
+        return Err("Auto mode is disabled (ait42.auto_coordinator = false)".to_string());
+    }
+    launch(app_handle, state, &optimizer, task, workspace_path).await
+}
+
+/// Plan `task` and launch it in the chosen mode, recording the session
+///
+/// Does not check whether auto mode is enabled; callers decide that.
+pub(crate) async fn launch(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    optimizer: &OptimizerState,
+    task: String,
+    workspace_path: Option<String>,
+) -> Result<AutoRunResult, String> {
+    let policy = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .ait42
+        .auto_mode
+        .clone();
+    let decision = plan(&state, optimizer, &task).await?;
+    let workspace = match workspace_path {
+        Some(path) if !path.trim().is_empty() => path,
+        _ => state.working_dir.lock().await.to_string_lossy().to_string(),
+    };
+
+    let mut result = AutoRunResult {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        decision: decision.clone(),
+        agent_results: Vec::new(),
+        competition: None,
+        debate: None,
+    };
+
+    let mut session = match decision.mode {
+        AutoMode::Single => {
+            let response = execute_agent(
+                app_handle.clone(),
+                state.clone(),
+                AgentExecutionRequest {
+                    agent_name: decision.agents[0].clone(),
+                    task: task.clone(),
+                    context: None,
+                    execution_id: None,
+                    session_id: None,
+                    workspace_path: None,
+                },
+            )
+            .await?;
+            result.agent_results.push(response);
+            session_for(&result.session_id, &decision, None)
+        }
+        AutoMode::Parallel => {
+            result.agent_results = execute_parallel(
+                app_handle.clone(),
+                state.clone(),
+                ParallelExecutionRequest {
+                    agents: decision.agents.clone(),
+                    task: task.clone(),
+                    context: None,
+                    execution_id: None,
+                    session_id: None,
+                    workspace_path: None,
+                },
+            )
+            .await?;
+            session_for(&result.session_id, &decision, None)
+        }
+        AutoMode::Competition | AutoMode::Ensemble => {
+            let competition = execute_multi_runtime_competition(
+                app_handle,
+                state.clone(),
+                MultiRuntimeCompetitionRequest {
+                    task: task.clone(),
+                    allocations: vec![RuntimeAllocationRequest {
+                        runtime: "claude".to_string(),
+                        count: decision.instances,
+                        model: policy.model.clone(),
+                    }],
+                    timeout_seconds: policy.timeout_secs,
+                    preserve_worktrees: false,
+                    mode: decision.mode.to_string(),
+                },
+            )
+            .await?;
+            result.session_id = competition.competition_id.clone();
+            let mut session = session_for(&result.session_id, &decision, Some(policy.model));
+            session.runtime_mix = Some(vec!["claude".to_string()]);
+            session.instances = competition
+                .instances
+                .iter()
+                .map(|instance| WorktreeInstance {
+                    instance_id: instance.instance_number as u32,
+                    worktree_path: instance.worktree_path.clone(),
+                    branch: instance.branch.clone(),
+                    agent_name: format!("Claude Code #{}", instance.instance_number),
+                    agent_version: None,
+                    touched_modules: None,
+                    diff_summary: None,
+                    status: instance.status.clone(),
+                    tmux_session_id: instance.tmux_session_id.clone(),
+                    output: None,
+                    start_time: Some(instance.started_at.clone()),
+                    end_time: None,
+                    files_changed: None,
+                    lines_added: None,
+                    lines_deleted: None,
+                    runtime: instance.runtime.clone(),
+                    model: instance.model.clone(),
+                    runtime_label: None,
+                })
+                .collect();
+            result.competition = Some(competition);
+            session
+        }
+        AutoMode::Debate => {
+            let debate = execute_debate(
+                app_handle,
+                state.clone(),
+                DebateRequest {
+                    task: task.clone(),
+                    roles: debate_roles(),
+                    model: policy.model.clone(),
+                    timeout_seconds: policy.timeout_secs,
+                    preserve_worktrees: false,
+                    rounds: default_debate_rounds(),
+                    round_prompts: Vec::new(),
+                    synthesis: true,
+                    synthesis_prompt: None,
+                },
+            )
+            .await?;
+            result.session_id = debate.debate_id.clone();
+            result.debate = Some(debate);
+            session_for(&result.session_id, &decision, Some(policy.model))
+        }
+    };
+
+    if !result.agent_results.is_empty() {
+        session.instances = result
+            .agent_results
+            .iter()
+            .enumerate()
+            .map(|(index, response)| agent_instance(index, &workspace, response))
+            .collect();
+        let failed = session.instances.iter().any(|i| i.status == "failed");
+        session.status = if failed { "failed" } else { "completed" }.to_string();
+        session.completed_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    if let Err(e) = upsert_session(&state, &workspace, session) {
+        warn!("Failed to record auto mode session {}: {}", result.session_id, e);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis(subtasks: usize, instances: usize, confidence: Option<f64>) -> TaskAnalysis {
+        TaskAnalysis {
+            complexity_class: "Linear".to_string(),
+            subtasks,
+            instances,
+            confidence,
+            reasoning: String::new(),
+        }
+    }
+
+    fn agents() -> Vec<String> {
+        ["backend-developer", "test-generator", "tech-writer"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_mode_follows_thresholds() {
+        let policy = AutoModeConfig::default();
+        let mode = |subtasks, confidence| {
+            decide("task", analysis(subtasks, 4, confidence), &agents(), &policy).mode
+        };
+
+        assert_eq!(mode(1, Some(0.9)), AutoMode::Single);
+        assert_eq!(mode(3, Some(0.9)), AutoMode::Parallel);
+        assert_eq!(mode(5, Some(0.9)), AutoMode::Competition);
+        assert_eq!(mode(8, None), AutoMode::Ensemble);
+        assert_eq!(mode(8, Some(0.3)), AutoMode::Debate);
+    }
+
+    #[test]
+    fn test_decision_trace_and_fallbacks() {
+        let policy = AutoModeConfig::default();
+
+        let decision = decide("task", analysis(3, 4, Some(0.8)), &agents(), &policy);
+        assert_eq!(decision.agents, ["backend-developer", "test-generator", "tech-writer"]);
+        assert_eq!(decision.instances, 3);
+        assert!(decision.trace[0].contains("confidence 0.80"));
+        assert_eq!(decision.estimated_cost_usd, 1.5);
+        assert_eq!(decision.trace.last().unwrap(), "Launching parallel mode (estimated $1.50)");
+        assert!(decision.to_markdown().starts_with("# Auto mode: parallel"));
+
+        // Without agents, Claude Code instances compete instead
+        let decision = decide("task", analysis(1, 1, None), &[], &policy);
+        assert_eq!(decision.mode, AutoMode::Competition);
+        assert_eq!(decision.instances, 2);
+
+        let decision = decide("task", analysis(2, 1, None), &agents()[..1], &policy);
+        assert_eq!(decision.mode, AutoMode::Single);
+        assert_eq!(decision.agents, ["backend-developer"]);
+    }
+
+    fn rule(mode: &str) -> ModeRule {
+        ModeRule {
+            mode: mode.to_string(),
+            ..ModeRule::default()
+        }
+    }
+
+    #[test]
+    fn test_rules_take_precedence_in_order() {
+        let policy = AutoModeConfig {
+            rules: vec![
+                ModeRule {
+                    complexity: vec!["quadratic".to_string()],
+                    ..rule("ensemble")
+                },
+                ModeRule {
+                    min_confidence: Some(0.9),
+                    ..rule("single")
+                },
+                ModeRule {
+                    min_subtasks: Some(2),
+                    instances: Some(4),
+                    ..rule("competition")
+                },
+            ],
+            ..AutoModeConfig::default()
+        };
+
+        let decision = decide("task", analysis(3, 2, Some(0.8)), &agents(), &policy);
+        assert_eq!(decision.mode, AutoMode::Competition);
+        assert_eq!(decision.instances, 4);
+        assert_eq!(
+            decision.trace[1],
+            "Rule 1 (ensemble) skipped: complexity Linear not in [quadratic]"
+        );
+        assert_eq!(decision.trace[2], "Rule 2 (single) skipped: confidence 0.80 < 0.90");
+        assert_eq!(decision.trace[3], "Rule 3 matches: competition");
+
+        // A confidence bound never matches a heuristic estimate
+        let decision = decide("task", analysis(1, 1, None), &agents(), &policy);
+        assert_eq!(decision.mode, AutoMode::Single);
+        assert_eq!(decision.trace[2], "Rule 2 (single) skipped: no confidence estimate");
+        assert!(decision.trace[3].starts_with("Rule 3 (competition) skipped"));
+
+        let policy = AutoModeConfig {
+            rules: vec![rule("solo")],
+            ..AutoModeConfig::default()
+        };
+        let decision = decide("task", analysis(5, 3, Some(0.9)), &agents(), &policy);
+        assert_eq!(decision.trace[1], "Rule 1 skipped: unknown mode \"solo\"");
+        assert_eq!(decision.mode, AutoMode::Competition);
+    }
+
+    #[test]
+    fn test_cost_ceiling_scales_plan_down() {
+        let policy = AutoModeConfig {
+            max_cost_usd: Some(2.0),
+            ..AutoModeConfig::default()
+        };
+
+        let decision = decide("task", analysis(5, 8, Some(0.9)), &agents(), &policy);
+        assert_eq!(decision.mode, AutoMode::Competition);
+        assert_eq!(decision.instances, 4);
+        assert_eq!(decision.estimated_cost_usd, 2.0);
+        assert!(decision
+            .trace
+            .iter()
+            .any(|step| step.contains("exceeds the $2.00 ceiling: reduced to competition x4")));
+
+        // Three debate roles over three rounds cost too much: one agent instead
+        let decision = decide("task", analysis(5, 8, Some(0.2)), &agents(), &policy);
+        assert_eq!(decision.mode, AutoMode::Single);
+        assert_eq!(decision.agents, ["backend-developer"]);
+
+        let policy = AutoModeConfig {
+            max_cost_usd: Some(0.1),
+            ..AutoModeConfig::default()
+        };
+        let decision = decide("task", analysis(1, 1, None), &[], &policy);
+        assert_eq!(decision.instances, 2);
+        assert!(decision
+            .trace
+            .iter()
+            .any(|step| step.contains("no cheaper plan is possible")));
+    }
+}
@@ -0,0 +1,145 @@
+//! Budget Commands
+//!
+//! Tauri commands for the AI spend limits in `ait42.budget`, plus the checks
+//! run before LLM estimations, chats, competitions and editor assists are
+//! launched.
+
+use ait42_config::BudgetConfig;
+use llm_estimator::{provider_with_config, ClientConfig};
+use tauri::State;
+
+use crate::budget::{self, BudgetScope, BudgetStatus, Operation, TokenEstimate};
+use crate::state::AppState;
+
+/// Current budget settings
+pub(crate) fn budget_policy(state: &AppState) -> Result<BudgetConfig, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    Ok(config.ait42.budget.clone())
+}
+
+/// Refuse `operation` if it would go over a limit, otherwise record its spend
+///
+/// `tokens` estimates the operation's tokens under the current settings.
+pub(crate) fn authorize_spend(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    operation: Operation,
+    session_id: Option<&str>,
+    tokens: impl FnOnce(&BudgetConfig) -> TokenEstimate,
+) -> Result<(), String> {
+    let policy = budget_policy(state)?;
+    let tokens = tokens(&policy);
+    budget::authorize(
+        app,
+        &state.spend_guard,
+        &state.notifications,
+        &policy,
+        operation,
+        session_id,
+        tokens,
+    )
+}
+
+/// Have the LLM provider in `ait42.llm_provider` complete `prompt` unless
+/// that would go over a limit
+///
+/// `config` sets the response length and timeout; the spend is checked and
+/// recorded like [`authorize_spend`].
+pub(crate) async fn complete_within_budget(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    operation: Operation,
+    session_id: Option<&str>,
+    prompt: &str,
+    config: ClientConfig,
+) -> Result<String, String> {
+    let provider = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .ait42
+        .llm_provider
+        .clone();
+    let client = provider_with_config(
+        &provider.name,
+        provider.model.as_deref(),
+        provider.base_url.as_deref(),
+        &config,
+    )
+    .map_err(|e| {
+        format!(
+            "Cannot use the {} provider. Check ait42.llm_provider and its API key: {}",
+            provider.name, e
+        )
+    })?;
+    let max_tokens = config.max_tokens.max(0) as u64;
+    authorize_spend(app, state, operation, session_id, |_| {
+        TokenEstimate::completion(prompt, max_tokens)
+    })?;
+    client.complete(prompt).await.map_err(|e| e.to_string())
+}
+
+/// Get today's spend, and the spend of a session if given
+#[tauri::command]
+pub async fn get_budget_status(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BudgetStatus, String> {
+    let policy = budget_policy(&state)?;
+    Ok(state
+        .spend_guard
+        .status(&policy, &budget::current_day(), session_id.as_deref()))
+}
+
+/// Get the spend limits
+#[tauri::command]
+pub async fn get_budget_config(state: State<'_, AppState>) -> Result<BudgetConfig, String> {
+    budget_policy(&state)
+}
+
+/// Update the spend limits
+#[tauri::command]
+pub async fn set_budget_config(
+    config: BudgetConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    for limit in [config.daily_limit_usd, config.session_limit_usd].into_iter().flatten() {
+        if limit <= 0.0 {
+            return Err(format!("Invalid budget limit: {} (must be positive)", limit));
+        }
+    }
+    if !(0.0..=1.0).contains(&config.warn_at) {
+        return Err(format!("Invalid budget warn_at: {} (must be 0.0-1.0)", config.warn_at));
+    }
+    state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .ait42
+        .budget = config;
+    Ok(())
+}
+
+/// Lift a limit after the user confirmed going over it
+///
+/// The daily limit is lifted until the end of the day, a session limit
+/// until the session ends. Invoked by the action of the notification raised
+/// when an operation is refused.
+#[tauri::command]
+pub async fn approve_budget_override(
+    scope: BudgetScope,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BudgetStatus, String> {
+    let day = budget::current_day();
+    state
+        .spend_guard
+        .approve_override(scope, &day, session_id.as_deref())?;
+    tracing::info!("Budget override approved for {:?} {:?}", scope, session_id);
+
+    let policy = budget_policy(&state)?;
+    Ok(state.spend_guard.status(&policy, &day, session_id.as_deref()))
+}
@@ -0,0 +1,193 @@
+//! Changelog Commands
+//!
+//! Drafts the CHANGELOG.md section of the next release from the Conventional
+//! Commits since the last tag, optionally reworded by the LLM backend. The
+//! section comes back as an edit of the CHANGELOG.md buffer for the user to
+//! review; the frontend applies it with `replace_text` and nothing is written
+//! to disk here.
+
+use ait42_core::Buffer;
+use ait42_git::changelog::{self, Bump, Changelog, ChangelogEntry};
+use llm_estimator::ClientConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{info, warn};
+
+use crate::budget::Operation;
+use crate::commands::budget::complete_within_budget;
+use crate::commands::editor::TextRange;
+use crate::commands::git::open_repository;
+use crate::commands::refactor::RefactorEdit;
+use crate::state::AppState;
+
+/// Changelog file at the repository root
+const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Response budget for a reworded changelog section
+const CHANGELOG_MAX_TOKENS: i32 = 4096;
+
+/// Timeout for a changelog request in seconds
+const CHANGELOG_TIMEOUT_SECS: u64 = 90;
+
+/// Drafted changelog section
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangelogDraft {
+    /// Tag the release follows; `None` if the history has no tags
+    pub previous_tag: Option<String>,
+    /// Version increase the commits call for
+    pub suggested_bump: Bump,
+    /// Version increase the section was written for
+    pub bump: Bump,
+    /// Version of the section; `None` if the previous tag is not a semantic
+    /// version, in which case the section is headed "Unreleased"
+    pub version: Option<String>,
+    /// Commits in the section, newest first
+    pub commits: Vec<ChangelogEntry>,
+    /// Markdown section
+    pub section: String,
+    /// Whether the LLM reworded the section
+    pub polished: bool,
+    /// Path of CHANGELOG.md
+    pub path: String,
+    /// Buffer holding CHANGELOG.md; an unnamed buffer to save as `path` when
+    /// the file does not exist yet
+    pub buffer_id: String,
+    /// Edit inserting the section into the buffer
+    pub edit: RefactorEdit,
+}
+
+/// Edit inserting `section` into the changelog `content`
+fn changelog_edit(content: &str, section: &str) -> RefactorEdit {
+    let (offset, text) = changelog::insert_section(content, section);
+    RefactorEdit {
+        range: TextRange {
+            start: offset,
+            end: offset,
+        },
+        cursor: offset + text.len(),
+        replacement: text,
+    }
+}
+
+/// Ask the LLM to reword `section`
+///
+/// Returns `None` when the reply did not keep the section's structure.
+async fn polish_section(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    section: &str,
+) -> Result<Option<String>, String> {
+    let config = ClientConfig {
+        max_tokens: CHANGELOG_MAX_TOKENS,
+        timeout_secs: CHANGELOG_TIMEOUT_SECS,
+        ..ClientConfig::default()
+    };
+    let prompt = changelog::polish_prompt(section);
+
+    info!("Requesting changelog wording ({} bytes)", section.len());
+    let response =
+        complete_within_budget(app, state, Operation::Assist, None, &prompt, config).await?;
+    Ok(changelog::parse_polished(section, &response))
+}
+
+/// Draft the CHANGELOG.md section for the commits since the last tag
+///
+/// # Arguments
+/// * `bump` - Version increase to write the section for; defaults to the
+///   suggested one
+/// * `polish` - Have the LLM provider in `ait42.llm_provider` reword the
+///   entries, unless that would go over the AI budget
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(draft)` - Section and the buffer edit adding it, for review
+/// * `Err(message)` - No commits since the last tag, or the request failed
+#[tauri::command]
+pub async fn generate_changelog(
+    app: tauri::AppHandle,
+    bump: Option<Bump>,
+    polish: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ChangelogDraft, String> {
+    let repo = open_repository(&state).await?;
+    let log = Changelog::since_last_tag(&repo).map_err(|e| e.to_string())?;
+    let suggested_bump = log.bump().ok_or_else(|| match &log.previous_tag {
+        Some(tag) => format!("No commits since {}", tag),
+        None => "The repository has no commits".to_string(),
+    })?;
+    let bump = bump.unwrap_or(suggested_bump);
+    let version = log.next_version(bump);
+
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let mut section = log.render(version.as_deref().unwrap_or("Unreleased"), &date);
+    let mut polished = false;
+    if polish.unwrap_or(false) {
+        match polish_section(&app, &state, &section).await? {
+            Some(reworded) => {
+                section = reworded;
+                polished = true;
+            }
+            None => warn!("Reworded changelog changed its structure; keeping the original"),
+        }
+    }
+
+    let path = repo.root().join(CHANGELOG_FILE);
+    let (buffer_id, content) = {
+        let mut editor = state
+            .editor
+            .lock()
+            .map_err(|e| format!("Failed to lock editor: {}", e))?;
+        let buffers = editor.buffers_mut();
+        let id = if path.exists() {
+            buffers
+                .open_file(&path)
+                .map_err(|e| format!("Failed to open {}: {}", CHANGELOG_FILE, e))?
+        } else {
+            buffers.add_buffer(Buffer::from_string(String::new(), Some("md".to_string())))
+        };
+        let content = buffers
+            .get(id)
+            .map(|buffer| buffer.to_string())
+            .unwrap_or_default();
+        (id, content)
+    };
+
+    info!(
+        "Drafted changelog for {} ({} commits, {:?} bump)",
+        version.as_deref().unwrap_or("unreleased changes"),
+        log.entries.len(),
+        bump
+    );
+    Ok(ChangelogDraft {
+        previous_tag: log.previous_tag,
+        suggested_bump,
+        bump,
+        version,
+        commits: log.entries,
+        edit: changelog_edit(&content, &section),
+        section,
+        polished,
+        path: path.to_string_lossy().to_string(),
+        buffer_id: buffer_id.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changelog_edit_inserts_above_previous_release() {
+        let content = "# Changelog\n\n## v1.0.0 (2026-01-01)\n\n- a\n";
+        let edit = changelog_edit(content, "## v1.1.0 (2026-02-01)\n\n- b\n");
+        assert_eq!((edit.range.start, edit.range.end), (13, 13));
+        assert_eq!(edit.replacement, "## v1.1.0 (2026-02-01)\n\n- b\n\n");
+        assert_eq!(edit.cursor, 13 + edit.replacement.len());
+
+        let edit = changelog_edit("", "## Unreleased (2026-02-01)\n");
+        assert_eq!(edit.range.start, 0);
+        assert_eq!(edit.replacement, "# Changelog\n\n## Unreleased (2026-02-01)\n");
+    }
+}
@@ -0,0 +1,119 @@
+//! Checkpoint Commands
+//!
+//! Tauri commands for working tree checkpoints stored under
+//! `refs/ait42/checkpoints`, plus the timer that takes them automatically.
+
+use ait42_config::CheckpointConfig;
+use ait42_git::{checkpoint, Checkpoint, GitError, Repository};
+use std::time::Duration;
+use tauri::{Manager, State};
+use tracing::{debug, warn};
+
+use crate::commands::git::open_repository;
+use crate::state::AppState;
+
+/// Number of checkpoints returned when no limit is given
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+/// Take a checkpoint of the working tree now
+///
+/// # Returns
+/// * `Ok(Some(checkpoint))` - The new checkpoint
+/// * `Ok(None)` - Nothing changed since the last checkpoint
+#[tauri::command]
+pub async fn create_checkpoint(
+    message: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<Checkpoint>, String> {
+    let repo = open_repository(&state).await?;
+    let message = message.unwrap_or_else(|| "Manual checkpoint".to_string());
+    checkpoint::create(&repo, &message).map_err(|e| e.to_string())
+}
+
+/// List checkpoints, newest first
+#[tauri::command]
+pub async fn list_checkpoints(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Checkpoint>, String> {
+    let repo = open_repository(&state).await?;
+    checkpoint::list(&repo, limit.unwrap_or(DEFAULT_LIST_LIMIT)).map_err(|e| e.to_string())
+}
+
+/// Restore files from a checkpoint into the working tree
+///
+/// Open buffers for restored files must be reloaded by the frontend.
+///
+/// # Arguments
+/// * `sha` - Checkpoint commit
+/// * `paths` - Files to restore (all files when omitted)
+///
+/// # Returns
+/// * `Ok(Some(checkpoint))` - Checkpoint of the state before the restore
+/// * `Ok(None)` - The working tree already matched the latest checkpoint
+#[tauri::command]
+pub async fn restore_checkpoint(
+    sha: String,
+    paths: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<Option<Checkpoint>, String> {
+    let repo = open_repository(&state).await?;
+    checkpoint::restore(&repo, &sha, &paths.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Get the automatic checkpoint settings
+#[tauri::command]
+pub async fn get_checkpoint_config(state: State<'_, AppState>) -> Result<CheckpointConfig, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    Ok(config.checkpoints.clone())
+}
+
+/// Update the automatic checkpoint settings
+#[tauri::command]
+pub async fn set_checkpoint_config(
+    config: CheckpointConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if config.interval_secs == 0 {
+        return Err("Checkpoint interval must be at least one second".to_string());
+    }
+    state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .checkpoints = config;
+    Ok(())
+}
+
+/// Take automatic checkpoints while they are enabled
+///
+/// Runs for the lifetime of the app. Workspaces that are not git
+/// repositories are skipped silently, and nothing is taken while scheduled
+/// runs are paused from the tray.
+pub async fn run_checkpoint_timer(app: tauri::AppHandle) {
+    loop {
+        let state = app.state::<AppState>();
+        let config = match state.config.lock() {
+            Ok(config) => config.checkpoints.clone(),
+            Err(e) => e.into_inner().checkpoints.clone(),
+        };
+        tokio::time::sleep(Duration::from_secs(config.interval_secs.max(1))).await;
+        if !config.enabled || state.scheduled_runs.is_paused() {
+            continue;
+        }
+
+        let working_dir = state.working_dir.lock().await.clone();
+        let result = Repository::open(&working_dir)
+            .and_then(|repo| checkpoint::create(&repo, "Automatic checkpoint"));
+        match result {
+            Ok(_) => {}
+            Err(GitError::NotARepository(path)) => {
+                debug!("Skipping checkpoint, not a git repository: {}", path.display())
+            }
+            Err(e) => warn!("Automatic checkpoint failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Clipboard History Commands
+//!
+//! Tauri commands for the clipboard history ring. The editor pushes its
+//! copies and cuts; plugins and agent runs push through the same
+//! `push_clipboard_history` command with their own source.
+
+use ait42_config::ClipboardConfig;
+use tauri::State;
+
+use crate::clipboard_history::{ClipboardEntry, ClipboardSource};
+use crate::state::AppState;
+
+fn clipboard_config(state: &AppState) -> ClipboardConfig {
+    match state.config.lock() {
+        Ok(config) => config.clipboard.clone(),
+        Err(e) => e.into_inner().clipboard.clone(),
+    }
+}
+
+/// Entries of the clipboard history, most recent first
+#[tauri::command]
+pub async fn get_clipboard_history(
+    state: State<'_, AppState>,
+) -> Result<Vec<ClipboardEntry>, String> {
+    Ok(state.clipboard_history.entries())
+}
+
+/// Add copied text to the top of the clipboard history
+///
+/// # Arguments
+/// * `text` - Copied text, ignored if blank
+/// * `source` - Who copied it, the editor unless given
+///
+/// # Returns
+/// * `Ok(Some(entry))` - The new entry
+/// * `Ok(None)` - The text was blank
+#[tauri::command]
+pub async fn push_clipboard_history(
+    state: State<'_, AppState>,
+    text: String,
+    source: Option<ClipboardSource>,
+) -> Result<Option<ClipboardEntry>, String> {
+    let config = clipboard_config(&state);
+    Ok(state
+        .clipboard_history
+        .push(text, source.unwrap_or(ClipboardSource::Editor), &config))
+}
+
+/// Remove one entry from the clipboard history
+#[tauri::command]
+pub async fn remove_clipboard_entry(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let config = clipboard_config(&state);
+    if !state.clipboard_history.remove(&id, &config) {
+        return Err(format!("Clipboard entry not found: {}", id));
+    }
+    Ok(())
+}
+
+/// Remove every entry from the clipboard history
+#[tauri::command]
+pub async fn clear_clipboard_history(state: State<'_, AppState>) -> Result<(), String> {
+    let config = clipboard_config(&state);
+    state.clipboard_history.clear(&config);
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Commit Message Commands
+//!
+//! Drafts a commit message for the staged changes with the LLM backend. The
+//! draft is returned for the user to edit and then committed with
+//! `git_commit`; nothing is committed here. Drafts are cached by a hash of
+//! the staged diff, so asking again for the same changes costs nothing.
+
+use ait42_git::commit::{self, DEFAULT_DIFF_BUDGET};
+use ait42_git::CommitMessage;
+use llm_estimator::ClientConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::State;
+use tracing::{debug, info, warn};
+
+use crate::budget::Operation;
+use crate::commands::budget::complete_within_budget;
+use crate::commands::git::open_repository;
+use crate::state::AppState;
+
+/// Response budget for a commit message
+const COMMIT_MESSAGE_MAX_TOKENS: i32 = 1024;
+
+/// Timeout for a commit message request in seconds
+const COMMIT_MESSAGE_TIMEOUT_SECS: u64 = 60;
+
+/// Drafts kept in the cache
+const MAX_CACHED_MESSAGES: usize = 64;
+
+/// Drafted commit message
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMessageDraft {
+    /// Subject line
+    pub subject: String,
+    /// Body bullet points
+    pub body: Vec<String>,
+    /// Complete message, ready to edit and commit
+    pub message: String,
+    /// Whether the subject follows Conventional Commits
+    pub conventional: bool,
+    /// Whether the diff sent to the LLM was shortened
+    pub truncated: bool,
+    /// Whether the draft came from the cache
+    pub cached: bool,
+}
+
+impl CommitMessageDraft {
+    fn new(message: &CommitMessage, truncated: bool, cached: bool) -> Self {
+        Self {
+            subject: message.subject.clone(),
+            body: message.body.clone(),
+            message: message.to_string(),
+            conventional: message.is_conventional(),
+            truncated,
+            cached,
+        }
+    }
+}
+
+fn diff_hash(diff: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(diff.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Draft a commit message for the staged changes
+///
+/// # Arguments
+/// * `regenerate` - Ask the LLM again even if a draft is cached
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(draft)` - Message for the user to review and edit
+/// * `Err(message)` - Nothing staged, no API key, over the AI budget, or the
+///   request failed
+#[tauri::command]
+pub async fn generate_commit_message(
+    app: tauri::AppHandle,
+    regenerate: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<CommitMessageDraft, String> {
+    let repo = open_repository(&state).await?;
+    let changes = commit::staged_changes(&repo).map_err(|e| e.to_string())?;
+    let (prompt, truncated) = commit::message_prompt(&changes, DEFAULT_DIFF_BUDGET);
+    let key = diff_hash(&changes.diff);
+
+    if !regenerate.unwrap_or(false) {
+        let cache = state
+            .commit_messages
+            .lock()
+            .map_err(|e| format!("Failed to lock commit message cache: {}", e))?;
+        if let Some(message) = cache.get(&key) {
+            debug!("Using cached commit message for diff {}", &key[..12]);
+            return Ok(CommitMessageDraft::new(message, truncated, true));
+        }
+    }
+
+    let config = ClientConfig {
+        max_tokens: COMMIT_MESSAGE_MAX_TOKENS,
+        timeout_secs: COMMIT_MESSAGE_TIMEOUT_SECS,
+        ..ClientConfig::default()
+    };
+    info!(
+        "Requesting commit message ({} bytes of diff{})",
+        changes.diff.len(),
+        if truncated { ", shortened" } else { "" }
+    );
+    let response =
+        complete_within_budget(&app, &state, Operation::Assist, None, &prompt, config).await?;
+    let message = CommitMessage::parse(&response).map_err(|e| e.to_string())?;
+    if !message.is_conventional() {
+        warn!("Drafted subject is not a conventional commit: {}", message.subject);
+    }
+
+    let mut cache = state
+        .commit_messages
+        .lock()
+        .map_err(|e| format!("Failed to lock commit message cache: {}", e))?;
+    if cache.len() >= MAX_CACHED_MESSAGES && !cache.contains_key(&key) {
+        if let Some(evicted) = cache.keys().next().cloned() {
+            cache.remove(&evicted);
+        }
+    }
+    cache.insert(key, message.clone());
+
+    Ok(CommitMessageDraft::new(&message, truncated, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draft_from_message() {
+        let message = CommitMessage {
+            subject: "fix(lsp): restart crashed servers".to_string(),
+            body: vec!["Detect exit".to_string()],
+        };
+        let draft = CommitMessageDraft::new(&message, false, true);
+        assert!(draft.conventional);
+        assert!(draft.cached);
+        assert_eq!(draft.message, "fix(lsp): restart crashed servers\n\n- Detect exit");
+    }
+
+    #[test]
+    fn test_diff_hash_is_stable() {
+        assert_eq!(diff_hash("a"), diff_hash("a"));
+        assert_ne!(diff_hash("a"), diff_hash("b"));
+        assert_eq!(diff_hash("a").len(), 64);
+    }
+}
@@ -0,0 +1,950 @@
+//! Competition Winner Commands
+//!
+//! Scores the instances of a finished competition, marks the best one as
+//! winner, and merges the winner's branch into the workspace. Scoring (see
+//! `ait42_ait42::scoring`) runs each worktree's tests, measures its diff
+//! against the workspace and, with `[competition] llm_evaluation`, has an
+//! LLM rate the diffs. With `[competition] auto_select_winner` it starts by
+//! itself once the last instance of a competition has finished.
+//!
+//! Finished competitions are also summarized for comparing the instances:
+//! each instance's diff stats and the modules it touched are recorded and,
+//! with `[competition] summarize_diffs`, an LLM describes each diff in a
+//! paragraph.
+
+use ait42_ait42::scoring::{self, Candidate, Evaluation, Score};
+use ait42_config::CompetitionConfig;
+use ait42_git::{commit, diff, history, OperationStatus, Repository};
+use llm_estimator::ClientConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Manager, State};
+use tracing::{info, warn};
+
+use crate::budget::Operation;
+use crate::commands::budget::complete_within_budget;
+use crate::commands::session_history::{
+    find_session, is_active, modify_sessions, SessionArtifact, WorktreeSession,
+};
+use crate::commands::telemetry;
+use crate::state::AppState;
+use crate::sync::lock;
+
+/// Emitted with a [`CompetitionRanking`] when a competition has been scored
+pub const COMPETITION_SCORED_EVENT: &str = "competition-scored";
+
+/// Emitted with a [`CompetitionSummary`] when a competition's diffs have
+/// been summarized
+pub const COMPETITION_SUMMARIZED_EVENT: &str = "competition-summarized";
+
+/// Diff sent to the LLM, in bytes, shared among the instances
+const EVALUATION_DIFF_BUDGET: usize = 64 * 1024;
+
+/// Response budget for the LLM evaluation
+const EVALUATION_MAX_TOKENS: i32 = 1024;
+
+/// Response budget for the LLM summaries
+const SUMMARY_MAX_TOKENS: i32 = 2048;
+
+/// Timeout for LLM requests in seconds
+const LLM_TIMEOUT_SECS: u64 = 120;
+
+/// Test commands by the project file that implies them, checked in order
+const TEST_COMMANDS: &[(&str, &str)] = &[
+    ("Cargo.toml", "cargo test"),
+    ("package.json", "npm test"),
+    ("go.mod", "go test ./..."),
+    ("pyproject.toml", "pytest"),
+];
+
+/// Sessions being scored, so a competition is never scored twice at once
+static SCORING: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Sessions being summarized, so a competition is never summarized twice at
+/// once
+static SUMMARIZING: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Ranking of a competition's instances
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompetitionRanking {
+    pub session_id: String,
+    /// Best first
+    pub scores: Vec<Score>,
+    /// Winner of the session; one the user chose earlier is kept
+    pub winner_id: Option<u32>,
+    /// Command run in each worktree to check its tests
+    pub test_command: Option<String>,
+    /// LLM ratings, when LLM evaluation is on
+    pub evaluations: Vec<Evaluation>,
+}
+
+impl CompetitionRanking {
+    /// The ranking as Markdown, for attaching to the session
+    fn to_markdown(&self) -> String {
+        let percent = |part: Option<f64>| match part {
+            Some(part) => format!("{:.0}", part * 100.0),
+            None => "-".to_string(),
+        };
+        let mut out = "# Competition ranking\n\n".to_string();
+        match &self.test_command {
+            Some(command) => out.push_str(&format!("Tests: `{}`\n\n", command)),
+            None => out.push_str("Tests: none found\n\n"),
+        }
+        out.push_str("| Rank | Instance | Total | Tests | Diff size | LLM |\n");
+        out.push_str("|---|---|---|---|---|---|\n");
+        for (rank, score) in self.scores.iter().enumerate() {
+            out.push_str(&format!(
+                "| {} | #{}{} | {} | {} | {} | {} |\n",
+                rank + 1,
+                score.instance_id,
+                if score.eligible {
+                    ""
+                } else {
+                    " (not eligible)"
+                },
+                percent(Some(score.total)),
+                percent(score.tests),
+                percent(score.diff_size),
+                percent(score.llm),
+            ));
+        }
+        for evaluation in &self.evaluations {
+            out.push_str(&format!(
+                "\n- #{} rated {}/10: {}",
+                evaluation.instance_id, evaluation.score, evaluation.reason
+            ));
+        }
+        if let Some(winner) = self.winner_id {
+            out.push_str(&format!("\n\nWinner: #{}\n", winner));
+        }
+        out
+    }
+}
+
+/// What one instance of a finished competition changed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceDiffSummary {
+    pub instance_id: u32,
+    pub files_changed: u32,
+    pub lines_added: u32,
+    pub lines_deleted: u32,
+    /// Directories, or root files, the diff touched
+    pub touched_modules: Vec<String>,
+    /// Paragraph describing the diff, when an LLM wrote one
+    pub summary: Option<String>,
+}
+
+/// Diffs of a finished competition's instances
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompetitionSummary {
+    pub session_id: String,
+    /// Instances whose worktree could be measured
+    pub instances: Vec<InstanceDiffSummary>,
+}
+
+/// Result of merging a competition's winner
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WinnerMerge {
+    pub instance_id: u32,
+    pub branch: String,
+    /// Commit made of the winner's uncommitted work, if it had any
+    pub committed: Option<String>,
+    /// Paused when the merge hit conflicts to resolve in the git panel
+    pub status: OperationStatus,
+}
+
+fn competition_config(state: &AppState) -> CompetitionConfig {
+    match state.config.lock() {
+        Ok(config) => config.competition.clone(),
+        Err(e) => e.into_inner().competition.clone(),
+    }
+}
+
+/// Test command implied by the project files in `root`
+fn detect_test_command(root: &Path) -> Option<String> {
+    TEST_COMMANDS
+        .iter()
+        .find(|(file, _)| root.join(file).is_file())
+        .map(|(_, command)| command.to_string())
+}
+
+/// Whether `command` succeeds in `worktree` within `timeout`
+async fn run_tests(worktree: &Path, command: &str, timeout: Duration) -> bool {
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(worktree)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .status();
+    match tokio::time::timeout(timeout, status).await {
+        Ok(Ok(status)) => status.success(),
+        Ok(Err(e)) => {
+            warn!("Failed to run tests in {}: {}", worktree.display(), e);
+            false
+        }
+        Err(_) => {
+            warn!("Tests in {} timed out after {:?}", worktree.display(), timeout);
+            false
+        }
+    }
+}
+
+/// Have the configured LLM complete `prompt` for competition `session_id`,
+/// answering in up to `max_tokens`
+///
+/// Refused when the call would go over the AI budget.
+async fn ask_llm(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    prompt: &str,
+    max_tokens: i32,
+) -> Result<String, String> {
+    let config = ClientConfig {
+        max_tokens,
+        timeout_secs: LLM_TIMEOUT_SECS,
+        ..ClientConfig::default()
+    };
+    let state = app.state::<AppState>();
+    complete_within_budget(app, &state, Operation::Competition, Some(session_id), prompt, config)
+        .await
+}
+
+async fn evaluate_with_llm(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    task: &str,
+    diffs: &[(u32, String)],
+) -> Result<Vec<Evaluation>, String> {
+    let prompt = scoring::evaluation_prompt(task, diffs);
+    let response = ask_llm(app, session_id, &prompt, EVALUATION_MAX_TOKENS).await?;
+    let instance_ids: Vec<u32> = diffs.iter().map(|(id, _)| *id).collect();
+    scoring::parse_evaluations(&response, &instance_ids).map_err(|e| e.to_string())
+}
+
+async fn summarize_with_llm(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    task: &str,
+    diffs: &[(u32, String)],
+) -> Result<Vec<(u32, String)>, String> {
+    let prompt = scoring::summary_prompt(task, diffs);
+    let response = ask_llm(app, session_id, &prompt, SUMMARY_MAX_TOKENS).await?;
+    let instance_ids: Vec<u32> = diffs.iter().map(|(id, _)| *id).collect();
+    scoring::parse_summaries(&response, &instance_ids).map_err(|e| e.to_string())
+}
+
+/// Cut `diffs` down to their share of the LLM's diff budget
+fn budget_diffs(diffs: &mut [(u32, String)]) {
+    if diffs.is_empty() {
+        return;
+    }
+    let budget = EVALUATION_DIFF_BUDGET / diffs.len();
+    for (_, patch) in diffs.iter_mut() {
+        *patch = diff::budget_diff(patch, budget).text;
+    }
+}
+
+/// Store the measured diff sizes of the instances and their totals
+fn record_stats(session: &mut WorktreeSession, stats: &[(u32, diff::DiffStat)]) {
+    for instance in session.instances.iter_mut() {
+        if let Some((_, stat)) = stats.iter().find(|(id, _)| *id == instance.instance_id) {
+            instance.files_changed = Some(stat.files as u32);
+            instance.lines_added = Some(stat.insertions as u32);
+            instance.lines_deleted = Some(stat.deletions as u32);
+        }
+    }
+    session.total_files_changed = Some(stats.iter().map(|(_, s)| s.files as u32).sum());
+    session.total_lines_added = Some(stats.iter().map(|(_, s)| s.insertions as u32).sum());
+    session.total_lines_deleted = Some(stats.iter().map(|(_, s)| s.deletions as u32).sum());
+}
+
+/// Store the measured diff sizes, the ranking and, unless one was chosen
+/// already, the winner
+fn record_ranking(
+    session: &mut WorktreeSession,
+    ranking: &mut CompetitionRanking,
+    stats: &[(u32, diff::DiffStat)],
+) {
+    record_stats(session, stats);
+
+    session.winner_id = session.winner_id.or(scoring::winner(&ranking.scores));
+    ranking.winner_id = session.winner_id;
+
+    session.artifacts.retain(|a| a.kind != "ranking");
+    session.artifacts.push(SessionArtifact {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: "ranking".to_string(),
+        title: "Competition ranking".to_string(),
+        content: ranking.to_markdown(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    });
+    session.updated_at = chrono::Utc::now().to_rfc3339();
+}
+
+/// Score the instances of a finished competition and record the winner
+///
+/// Without an LLM evaluation, e.g. when it would go over the AI budget, the
+/// instances are ranked by their tests and diff sizes.
+async fn rank_competition(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    workspace_path: &str,
+    session_id: &str,
+) -> Result<CompetitionRanking, String> {
+    let session = find_session(state, workspace_path, session_id)
+        .filter(|s| !s.is_deleted())
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    if session.r#type != "competition" {
+        return Err(format!("Session {} is not a competition", session_id));
+    }
+    if session.instances.iter().any(|i| is_active(&i.status)) {
+        return Err(format!("Session {} still has running instances", session_id));
+    }
+
+    let config = competition_config(state);
+    let workspace = Repository::open(workspace_path).map_err(|e| e.to_string())?;
+    let test_command = config
+        .test_command
+        .clone()
+        .or_else(|| detect_test_command(workspace.root()));
+    let test_timeout = Duration::from_secs(config.test_timeout_secs);
+    info!(
+        "Scoring competition {} ({} instances, tests: {})",
+        session_id,
+        session.instances.len(),
+        test_command.as_deref().unwrap_or("none")
+    );
+
+    let mut candidates = Vec::new();
+    let mut stats = Vec::new();
+    let mut diffs = Vec::new();
+    for instance in &session.instances {
+        let worktree = Repository::open(&instance.worktree_path);
+        let stat = worktree
+            .as_ref()
+            .map_err(|e| e.to_string())
+            .and_then(|wt| diff::worktree_stat(wt, &workspace).map_err(|e| e.to_string()));
+        let stat = match stat {
+            Ok(stat) => stat,
+            Err(e) => {
+                warn!("Cannot measure instance {}: {}", instance.instance_id, e);
+                diff::DiffStat::default()
+            }
+        };
+        stats.push((instance.instance_id, stat));
+
+        let mut candidate = Candidate {
+            instance_id: instance.instance_id,
+            completed: instance.status == "completed",
+            lines_changed: stat.lines(),
+            tests_passed: None,
+            llm_score: None,
+        };
+        // Only instances that could win are worth testing and rating
+        if candidate.completed && candidate.lines_changed > 0 {
+            let root = Path::new(&instance.worktree_path);
+            if let Some(command) = &test_command {
+                candidate.tests_passed = Some(run_tests(root, command, test_timeout).await);
+            }
+            if config.llm_evaluation {
+                if let Ok(patch) = worktree.and_then(|wt| diff::worktree_diff(&wt, &workspace)) {
+                    diffs.push((instance.instance_id, patch));
+                }
+            }
+        }
+        candidates.push(candidate);
+    }
+
+    let mut evaluations = Vec::new();
+    if !diffs.is_empty() {
+        budget_diffs(&mut diffs);
+        match evaluate_with_llm(app, session_id, &session.task, &diffs).await {
+            Ok(found) => evaluations = found,
+            Err(e) => warn!("LLM evaluation of competition {} failed: {}", session_id, e),
+        }
+    }
+    for evaluation in &evaluations {
+        if let Some(c) = candidates
+            .iter_mut()
+            .find(|c| c.instance_id == evaluation.instance_id)
+        {
+            c.llm_score = Some(evaluation.score);
+        }
+    }
+
+    let mut ranking = CompetitionRanking {
+        session_id: session_id.to_string(),
+        scores: scoring::rank(&candidates),
+        winner_id: None,
+        test_command,
+        evaluations,
+    };
+    modify_sessions(state, workspace_path, |sessions| {
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id && !s.is_deleted())
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        let previous = session.clone();
+        record_ranking(session, &mut ranking, &stats);
+        telemetry::record_winner(Some(&previous), session);
+        Ok(())
+    })?;
+    info!("Competition {} scored, winner: {:?}", session_id, ranking.winner_id);
+    Ok(ranking)
+}
+
+/// Score a competition, at most once at a time, and announce the ranking
+async fn score(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+    session_id: &str,
+) -> Result<CompetitionRanking, String> {
+    if !lock(&SCORING).insert(session_id.to_string()) {
+        return Err(format!("Session {} is already being scored", session_id));
+    }
+    let state = app.state::<AppState>();
+    let ranking = rank_competition(app, &state, workspace_path, session_id).await;
+    lock(&SCORING).remove(session_id);
+
+    let ranking = ranking?;
+    if let Err(e) = app.emit_all(COMPETITION_SCORED_EVENT, &ranking) {
+        warn!("Failed to emit {}: {}", COMPETITION_SCORED_EVENT, e);
+    }
+    Ok(ranking)
+}
+
+/// Store the diff stats, touched modules and summaries of the instances
+///
+/// A summary found earlier is kept when the LLM wrote none this time.
+fn record_summary(session: &mut WorktreeSession, summary: &CompetitionSummary) {
+    let stats: Vec<(u32, diff::DiffStat)> = summary
+        .instances
+        .iter()
+        .map(|found| {
+            let stat = diff::DiffStat {
+                files: found.files_changed as usize,
+                insertions: found.lines_added as usize,
+                deletions: found.lines_deleted as usize,
+            };
+            (found.instance_id, stat)
+        })
+        .collect();
+    record_stats(session, &stats);
+    for instance in session.instances.iter_mut() {
+        if let Some(found) = summary
+            .instances
+            .iter()
+            .find(|found| found.instance_id == instance.instance_id)
+        {
+            instance.touched_modules = Some(found.touched_modules.clone());
+            instance.diff_summary = found.summary.clone().or(instance.diff_summary.take());
+        }
+    }
+    session.updated_at = chrono::Utc::now().to_rfc3339();
+}
+
+/// Measure what each instance of a finished competition changed and, with
+/// `[competition] summarize_diffs`, have an LLM describe it
+///
+/// The descriptions are skipped when the LLM call would go over the AI
+/// budget.
+async fn summarize_competition_diffs(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    workspace_path: &str,
+    session_id: &str,
+) -> Result<CompetitionSummary, String> {
+    let session = find_session(state, workspace_path, session_id)
+        .filter(|s| !s.is_deleted())
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    if session.r#type != "competition" {
+        return Err(format!("Session {} is not a competition", session_id));
+    }
+    if session.instances.iter().any(|i| is_active(&i.status)) {
+        return Err(format!("Session {} still has running instances", session_id));
+    }
+
+    let config = competition_config(state);
+    let workspace = Repository::open(workspace_path).map_err(|e| e.to_string())?;
+    let mut instances = Vec::new();
+    let mut diffs = Vec::new();
+    for instance in &session.instances {
+        let measured = Repository::open(&instance.worktree_path).and_then(|worktree| {
+            let stat = diff::worktree_stat(&worktree, &workspace)?;
+            let files = diff::worktree_files(&worktree, &workspace)?;
+            Ok((worktree, stat, files))
+        });
+        let (worktree, stat, files) = match measured {
+            Ok(measured) => measured,
+            Err(e) => {
+                // The worktree may have been cleaned up already
+                warn!("Cannot summarize instance {}: {}", instance.instance_id, e);
+                continue;
+            }
+        };
+        if config.summarize_diffs && stat.lines() > 0 {
+            match diff::worktree_diff(&worktree, &workspace) {
+                Ok(patch) => diffs.push((instance.instance_id, patch)),
+                Err(e) => warn!("Cannot diff instance {}: {}", instance.instance_id, e),
+            }
+        }
+        instances.push(InstanceDiffSummary {
+            instance_id: instance.instance_id,
+            files_changed: stat.files as u32,
+            lines_added: stat.insertions as u32,
+            lines_deleted: stat.deletions as u32,
+            touched_modules: diff::touched_modules(&files),
+            summary: None,
+        });
+    }
+
+    if !diffs.is_empty() {
+        budget_diffs(&mut diffs);
+        match summarize_with_llm(app, session_id, &session.task, &diffs).await {
+            Ok(found) => {
+                for (instance_id, text) in found {
+                    if let Some(summary) =
+                        instances.iter_mut().find(|i| i.instance_id == instance_id)
+                    {
+                        summary.summary = Some(text);
+                    }
+                }
+            }
+            Err(e) => warn!("LLM summary of competition {} failed: {}", session_id, e),
+        }
+    }
+
+    let summary = CompetitionSummary {
+        session_id: session_id.to_string(),
+        instances,
+    };
+    modify_sessions(state, workspace_path, |sessions| {
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id && !s.is_deleted())
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        record_summary(session, &summary);
+        Ok(())
+    })?;
+    info!(
+        "Competition {} summarized ({} instances measured)",
+        session_id,
+        summary.instances.len()
+    );
+    Ok(summary)
+}
+
+/// Summarize a competition, at most once at a time, and announce the
+/// summary
+async fn summarize(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+    session_id: &str,
+) -> Result<CompetitionSummary, String> {
+    if !lock(&SUMMARIZING).insert(session_id.to_string()) {
+        return Err(format!("Session {} is already being summarized", session_id));
+    }
+    let state = app.state::<AppState>();
+    let summary = summarize_competition_diffs(app, &state, workspace_path, session_id).await;
+    lock(&SUMMARIZING).remove(session_id);
+
+    let summary = summary?;
+    if let Err(e) = app.emit_all(COMPETITION_SUMMARIZED_EVENT, &summary) {
+        warn!("Failed to emit {}: {}", COMPETITION_SUMMARIZED_EVENT, e);
+    }
+    Ok(summary)
+}
+
+/// Whether `session` is a competition that just finished and has not been
+/// scored or decided yet
+fn awaits_scoring(session: &WorktreeSession) -> bool {
+    session.r#type == "competition"
+        && session.status != "cancelled"
+        && !session.is_deleted()
+        && session.winner_id.is_none()
+        && !session.instances.is_empty()
+        && !session.instances.iter().any(|i| is_active(&i.status))
+        && !session.artifacts.iter().any(|a| a.kind == "ranking")
+}
+
+/// Start scoring `session` in the background once all its instances have
+/// finished, if `[competition] auto_select_winner` is set
+pub(crate) fn schedule_scoring(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+    session: &WorktreeSession,
+) {
+    let enabled = competition_config(&app.state::<AppState>()).auto_select_winner;
+    if !enabled || !awaits_scoring(session) {
+        return;
+    }
+    let app = app.clone();
+    let workspace_path = workspace_path.to_string();
+    let session_id = session.id.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = score(&app, &workspace_path, &session_id).await {
+            warn!("Failed to score competition {}: {}", session_id, e);
+        }
+    });
+}
+
+/// Whether `session` is a competition that just finished and has not been
+/// summarized yet
+fn awaits_summary(session: &WorktreeSession) -> bool {
+    session.r#type == "competition"
+        && session.status != "cancelled"
+        && !session.is_deleted()
+        && !session.instances.is_empty()
+        && !session.instances.iter().any(|i| is_active(&i.status))
+        && session
+            .instances
+            .iter()
+            .all(|i| i.touched_modules.is_none())
+}
+
+/// Start summarizing `session` in the background once all its instances
+/// have finished
+pub(crate) fn schedule_summaries(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+    session: &WorktreeSession,
+) {
+    if !awaits_summary(session) {
+        return;
+    }
+    let app = app.clone();
+    let workspace_path = workspace_path.to_string();
+    let session_id = session.id.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = summarize(&app, &workspace_path, &session_id).await {
+            warn!("Failed to summarize competition {}: {}", session_id, e);
+        }
+    });
+}
+
+/// Score the instances of a finished competition
+///
+/// Runs each worktree's tests, measures its diff and optionally has an LLM
+/// rate it, then marks the best instance as winner unless one was chosen
+/// already. The ranking is attached to the session as a `ranking` artifact
+/// and emitted as `competition-scored`.
+///
+/// # Arguments
+/// * `workspace_path` - Workspace of the session
+/// * `session_id` - Competition to score
+///
+/// # Returns
+/// * `Ok(ranking)` - Instances, best first
+/// * `Err(message)` - Not a competition, still running, or already being scored
+#[tauri::command]
+pub async fn score_competition(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+) -> Result<CompetitionRanking, String> {
+    state.session_streams.ensure_writable(&session_id)?;
+    score(&app, &workspace_path, &session_id).await
+}
+
+/// Summarize the diffs of a finished competition's instances
+///
+/// Records each instance's diff stats and the modules it touched and, with
+/// `[competition] summarize_diffs`, a paragraph the LLM provider in
+/// `ait42.llm_provider` wrote about its diff, unless that would go over the
+/// AI budget. Runs by itself when a competition finishes; this runs it
+/// again, e.g. after setting the provider's API key or raising a limit. The
+/// summary is emitted as `competition-summarized`.
+///
+/// # Arguments
+/// * `workspace_path` - Workspace of the session
+/// * `session_id` - Competition to summarize
+///
+/// # Returns
+/// * `Ok(summary)` - Instances whose worktree could be measured
+/// * `Err(message)` - Not a competition, still running, or already being
+///   summarized
+#[tauri::command]
+pub async fn summarize_competition(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+) -> Result<CompetitionSummary, String> {
+    state.session_streams.ensure_writable(&session_id)?;
+    summarize(&app, &workspace_path, &session_id).await
+}
+
+/// Merge the winning branch of a competition into the workspace
+///
+/// Uncommitted work in the winner's worktree is committed to its branch
+/// first. The merge always creates a merge commit; on conflicts it pauses
+/// and is continued or aborted like any other merge.
+///
+/// # Arguments
+/// * `workspace_path` - Workspace of the session, where the branch is merged
+/// * `session_id` - Competition whose winner to merge
+///
+/// # Returns
+/// * `Ok(merge)` - The merged branch and the state of the merge
+/// * `Err(message)` - No winner yet, or another operation is in progress
+#[tauri::command]
+pub async fn merge_competition_winner(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+) -> Result<WinnerMerge, String> {
+    state.session_streams.ensure_writable(&session_id)?;
+    let session = find_session(&state, &workspace_path, &session_id)
+        .filter(|s| !s.is_deleted())
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let winner_id = session
+        .winner_id
+        .ok_or_else(|| format!("Session {} has no winner yet", session_id))?;
+    let winner = session
+        .instances
+        .iter()
+        .find(|i| i.instance_id == winner_id)
+        .ok_or_else(|| format!("Instance {} not found in session {}", winner_id, session_id))?;
+
+    let workspace = Repository::open(&workspace_path).map_err(|e| e.to_string())?;
+    if let Some(operation) = history::in_progress(&workspace) {
+        return Err(format!("Finish the {} in progress before merging", operation));
+    }
+
+    let summary: String = session
+        .task
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .take(60)
+        .collect();
+    let short_id = session_id.get(..8).unwrap_or(&session_id);
+    // The worktree may have been cleaned up already; its branch remains
+    let committed = match Repository::open(&winner.worktree_path) {
+        Ok(worktree) => commit::commit_all(
+            &worktree,
+            &format!("Competition {} instance #{}: {}", short_id, winner_id, summary),
+        )
+        .map_err(|e| e.to_string())?,
+        Err(_) => None,
+    };
+
+    let status = history::merge(
+        &workspace,
+        &winner.branch,
+        &format!("Merge winner of competition {}: {}", short_id, summary),
+    )
+    .map_err(|e| e.to_string())?;
+    info!(
+        "Merged {} (instance #{} of {}) into {}: {:?}",
+        winner.branch, winner_id, session_id, workspace_path, status
+    );
+
+    Ok(WinnerMerge {
+        instance_id: winner_id,
+        branch: winner.branch.clone(),
+        committed,
+        status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session_history::WorktreeInstance;
+
+    fn instance(instance_id: u32, status: &str) -> WorktreeInstance {
+        WorktreeInstance {
+            instance_id,
+            worktree_path: format!("/tmp/instance-{}", instance_id),
+            branch: format!("competition-{}", instance_id),
+            agent_name: "claude".to_string(),
+            agent_version: None,
+            touched_modules: None,
+            diff_summary: None,
+            status: status.to_string(),
+            tmux_session_id: format!("ait42-claude-competition-{}", instance_id),
+            output: None,
+            start_time: None,
+            end_time: None,
+            files_changed: None,
+            lines_added: None,
+            lines_deleted: None,
+            runtime: None,
+            model: None,
+            runtime_label: None,
+        }
+    }
+
+    fn session(statuses: &[&str]) -> WorktreeSession {
+        WorktreeSession {
+            id: "c0ffee00-session".to_string(),
+            r#type: "competition".to_string(),
+            task: "Fix the bug".to_string(),
+            status: "running".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            completed_at: None,
+            instances: statuses
+                .iter()
+                .zip(1..)
+                .map(|(status, id)| instance(id, status))
+                .collect(),
+            chat_history: Vec::new(),
+            model: None,
+            timeout_seconds: None,
+            preserve_worktrees: None,
+            winner_id: None,
+            runtime_mix: None,
+            total_duration: None,
+            total_files_changed: None,
+            total_lines_added: None,
+            total_lines_deleted: None,
+            artifacts: Vec::new(),
+            deleted_at: None,
+            feature_flags: None,
+            origin: None,
+            issue: None,
+            agents: None,
+            context_scans: Vec::new(),
+            complexity_class: None,
+            subtask_count: None,
+        }
+    }
+
+    #[test]
+    fn test_detect_test_command() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(detect_test_command(dir.path()), None);
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(detect_test_command(dir.path()).as_deref(), Some("npm test"));
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(detect_test_command(dir.path()).as_deref(), Some("cargo test"));
+    }
+
+    #[tokio::test]
+    async fn test_run_tests() {
+        let dir = tempfile::tempdir().unwrap();
+        let timeout = Duration::from_secs(5);
+        assert!(run_tests(dir.path(), "true", timeout).await);
+        assert!(!run_tests(dir.path(), "exit 3", timeout).await);
+        assert!(!run_tests(dir.path(), "sleep 5", Duration::from_millis(50)).await);
+    }
+
+    #[test]
+    fn test_awaits_scoring() {
+        assert!(!awaits_scoring(&session(&["completed", "running"])));
+        let mut finished = session(&["completed", "failed"]);
+        assert!(awaits_scoring(&finished));
+
+        finished.winner_id = Some(1);
+        assert!(!awaits_scoring(&finished));
+        let mut ensemble = session(&["completed", "completed"]);
+        ensemble.r#type = "ensemble".to_string();
+        assert!(!awaits_scoring(&ensemble));
+    }
+
+    #[test]
+    fn test_record_ranking_keeps_chosen_winner() {
+        let stat = |files, insertions, deletions| diff::DiffStat {
+            files,
+            insertions,
+            deletions,
+        };
+        let stats = [(1, stat(1, 10, 2)), (2, stat(2, 3, 0))];
+        let candidates: Vec<Candidate> = stats
+            .iter()
+            .map(|(id, stat)| Candidate {
+                instance_id: *id,
+                completed: true,
+                lines_changed: stat.lines(),
+                tests_passed: None,
+                llm_score: None,
+            })
+            .collect();
+        let mut ranking = CompetitionRanking {
+            session_id: "c0ffee00-session".to_string(),
+            scores: scoring::rank(&candidates),
+            winner_id: None,
+            test_command: None,
+            evaluations: Vec::new(),
+        };
+
+        let mut scored = session(&["completed", "completed"]);
+        record_ranking(&mut scored, &mut ranking, &stats);
+        assert_eq!(scored.winner_id, Some(2));
+        assert_eq!(ranking.winner_id, Some(2));
+        assert_eq!(scored.instances[0].lines_added, Some(10));
+        assert_eq!(scored.total_files_changed, Some(3));
+        assert!(!awaits_scoring(&scored));
+
+        let mut chosen = session(&["completed", "completed"]);
+        chosen.winner_id = Some(1);
+        record_ranking(&mut chosen, &mut ranking, &stats);
+        record_ranking(&mut chosen, &mut ranking, &stats);
+        assert_eq!(chosen.winner_id, Some(1));
+        assert_eq!(ranking.winner_id, Some(1));
+        let rankings: Vec<&SessionArtifact> = chosen
+            .artifacts
+            .iter()
+            .filter(|a| a.kind == "ranking")
+            .collect();
+        assert_eq!(rankings.len(), 1);
+        assert!(rankings[0].content.contains("| 1 | #2 |"));
+        assert!(rankings[0].content.contains("Winner: #1"));
+    }
+
+    #[test]
+    fn test_record_summary() {
+        let mut finished = session(&["completed", "failed"]);
+        assert!(awaits_summary(&finished));
+        finished.instances[1].diff_summary = Some("Earlier summary".to_string());
+
+        let summary = CompetitionSummary {
+            session_id: finished.id.clone(),
+            instances: vec![
+                InstanceDiffSummary {
+                    instance_id: 1,
+                    files_changed: 2,
+                    lines_added: 12,
+                    lines_deleted: 3,
+                    touched_modules: vec!["src/auth".to_string()],
+                    summary: Some("Retries the login.".to_string()),
+                },
+                InstanceDiffSummary {
+                    instance_id: 2,
+                    files_changed: 0,
+                    lines_added: 0,
+                    lines_deleted: 0,
+                    touched_modules: Vec::new(),
+                    summary: None,
+                },
+            ],
+        };
+        record_summary(&mut finished, &summary);
+        assert!(!awaits_summary(&finished));
+        let first = &finished.instances[0];
+        assert_eq!(first.touched_modules.as_deref(), Some(&["src/auth".to_string()][..]));
+        assert_eq!(first.diff_summary.as_deref(), Some("Retries the login."));
+        assert_eq!(first.lines_added, Some(12));
+        assert_eq!(finished.total_files_changed, Some(2));
+        // An instance the LLM wrote nothing for keeps its earlier summary
+        assert_eq!(finished.instances[1].diff_summary.as_deref(), Some("Earlier summary"));
+
+        assert!(!awaits_summary(&session(&["completed", "running"])));
+    }
+}
@@ -0,0 +1,35 @@
+//! Confirmation Commands
+//!
+//! Tauri command issuing the tokens destructive commands require. The user
+//! confirms in a native dialog, outside the webview.
+
+use tauri::State;
+
+use crate::confirmation::DangerousAction;
+use crate::state::AppState;
+
+/// Ask the user to confirm `action` and issue a token for it
+///
+/// # Arguments
+/// * `action` - The destructive command and its target
+///
+/// # Returns
+/// * `Ok(token)` - Pass as `confirmation` to the command, within a minute
+/// * `Err(message)` - The user declined
+#[tauri::command]
+pub async fn request_confirmation(
+    action: DangerousAction,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tauri::api::dialog::ask(Some(&window), action.title(), action.message(), move |confirmed| {
+        let _ = tx.send(confirmed);
+    });
+
+    if !rx.await.unwrap_or(false) {
+        tracing::info!("User declined {:?}", action);
+        return Err("Cancelled by user".to_string());
+    }
+    Ok(state.confirmations.issue(action))
+}
@@ -2,7 +2,8 @@
 //!
 //! Tauri commands for text editing operations: insert, delete, undo, redo, etc.
 
-use ait42_core::BufferId;
+use ait42_core::{minimal_commands, Command};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use tauri::State;
@@ -19,7 +20,7 @@ pub struct Position {
 }
 
 /// Text range (start, end)
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TextRange {
     pub start: usize,
@@ -150,6 +151,51 @@ pub async fn replace_text(
     Ok(())
 }
 
+/// Replace buffer content with `content`, changing only the hunks that differ
+///
+/// For whole-file content proposed by an LLM: formatting between the changes
+/// is kept and each hunk is applied as its own edit.
+///
+/// # Arguments
+/// * `buffer_id` - Buffer ID (UUID string)
+/// * `content` - New buffer content
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(count)` - Number of hunks applied
+/// * `Err(message)` - Error message
+#[tauri::command]
+pub async fn apply_content(
+    buffer_id: String,
+    content: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let buffer_id = Uuid::parse_str(&buffer_id)
+        .map_err(|e| format!("Invalid buffer ID: {}", e))?;
+
+    let mut editor = state
+        .editor
+        .lock()
+        .map_err(|e| format!("Failed to lock editor: {}", e))?;
+
+    // Get buffer
+    let buffer = editor
+        .buffers_mut()
+        .get_mut(buffer_id)
+        .ok_or_else(|| format!("Buffer not found: {}", buffer_id))?;
+
+    // Apply changed hunks, back to front
+    let original = buffer.to_string();
+    let mut commands = minimal_commands(buffer.id(), &original, &content);
+    for command in &mut commands {
+        command
+            .execute(buffer)
+            .map_err(|e| format!("Failed to apply edit: {}", e))?;
+    }
+
+    Ok(commands.len())
+}
+
 /// Undo last edit
 ///
 /// # Arguments
@@ -280,7 +326,7 @@ pub async fn get_buffer_info(
     })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BufferInfo {
     pub buffer_id: String,
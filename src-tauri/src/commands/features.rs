@@ -0,0 +1,39 @@
+//! Feature Flag Commands
+//!
+//! Tauri commands for listing and switching experimental features.
+
+use tauri::State;
+
+use crate::feature_flags::{self, FeatureFlagState};
+use crate::state::AppState;
+
+/// List every feature flag with its current state
+#[tauri::command]
+pub async fn list_feature_flags(
+    state: State<'_, AppState>,
+) -> Result<Vec<FeatureFlagState>, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    Ok(feature_flags::list(&config))
+}
+
+/// Turn a feature flag on or off
+///
+/// Takes effect immediately; gated panels appear or disappear once the
+/// frontend reloads the flag list.
+#[tauri::command]
+pub async fn set_feature_flag(
+    name: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    feature_flags::set(&mut config, &name, enabled)?;
+    tracing::info!("Feature {} {}", name, if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
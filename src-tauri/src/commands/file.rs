@@ -3,14 +3,16 @@
 //! Tauri commands for file operations: open, save, read directory, create file, etc.
 
 use ait42_core::{Buffer, BufferId};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tauri::State;
 
+use crate::confirmation::DangerousAction;
 use crate::state::AppState;
 
 /// File node for directory tree display
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FileNode {
     pub name: String,
@@ -58,7 +60,7 @@ pub async fn open_file(
     })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenFileResponse {
     pub buffer_id: String,
@@ -242,20 +244,29 @@ pub async fn create_directory(path: String) -> Result<(), String> {
 ///
 /// # Arguments
 /// * `path` - Path to delete
+/// * `confirmation` - Token from `request_confirmation` for this path
 ///
 /// # Returns
 /// * `Ok(())` - Deleted successfully
 /// * `Err(message)` - Error message
 #[tauri::command]
-pub async fn delete_path(path: String) -> Result<(), String> {
-    let path_buf = PathBuf::from(&path);
+pub async fn delete_path(
+    path: String,
+    confirmation: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .confirmations
+        .consume(confirmation.as_deref(), &DangerousAction::DeletePath { path: path.clone() })?;
+    remove_path(Path::new(&path))
+}
 
-    if path_buf.is_dir() {
-        std::fs::remove_dir_all(&path_buf)
-            .map_err(|e| format!("Failed to delete directory: {}", e))?;
+/// Delete a file or directory without confirmation
+pub(crate) fn remove_path(path: &Path) -> Result<(), String> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path).map_err(|e| format!("Failed to delete directory: {}", e))?;
     } else {
-        std::fs::remove_file(&path_buf)
-            .map_err(|e| format!("Failed to delete file: {}", e))?;
+        std::fs::remove_file(path).map_err(|e| format!("Failed to delete file: {}", e))?;
     }
 
     Ok(())
@@ -334,7 +345,7 @@ mod tests {
         assert_eq!(entries.len(), 0); // Empty directory
 
         // Delete directory
-        assert!(delete_path(path.clone()).await.is_ok());
+        assert!(remove_path(&test_dir).is_ok());
         assert!(!test_dir.exists());
     }
 }
@@ -260,7 +260,7 @@ mod file_command_tests {
         let test_file = temp_dir.path().join("delete_me.txt");
         fs::write(&test_file, "Delete me").unwrap();
 
-        let result = delete_path(test_file.to_string_lossy().to_string()).await;
+        let result = remove_path(&test_file);
 
         assert!(result.is_ok());
         assert!(!test_file.exists());
@@ -273,7 +273,7 @@ mod file_command_tests {
         fs::create_dir(&test_dir).unwrap();
         fs::write(test_dir.join("file.txt"), "").unwrap();
 
-        let result = delete_path(test_dir.to_string_lossy().to_string()).await;
+        let result = remove_path(&test_dir);
 
         assert!(result.is_ok());
         assert!(!test_dir.exists());
@@ -281,7 +281,7 @@ mod file_command_tests {
 
     #[tokio::test]
     async fn test_delete_nonexistent_path() {
-        let result = delete_path("/nonexistent/path".to_string()).await;
+        let result = remove_path(std::path::Path::new("/nonexistent/path"));
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Failed to delete"));
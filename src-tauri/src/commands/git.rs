@@ -4,17 +4,25 @@
  * Tauri commands for Git operations using git2-rs
  */
 
+use ait42_git::{
+    blame, commit, conflict, history, hunk, log, rebase, stash, BlameLine, ConflictFile,
+    FileResolution, GraphRow, Hunk, MergeConflict, OperationStatus, RebasePlan, Repository,
+    Resolution, StashEntry,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::process::Command;
 use tauri::State;
 
+use crate::confirmation::DangerousAction;
+use crate::sanitize;
 use crate::state::AppState;
 
 /**
  * Git file status
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GitFileStatus {
     pub path: String,
     pub status: String, // "modified", "added", "deleted", "untracked", "renamed"
@@ -23,7 +31,7 @@ pub struct GitFileStatus {
 /**
  * Git status response
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GitStatus {
     pub branch: String,
     pub ahead: usize,
@@ -34,7 +42,7 @@ pub struct GitStatus {
 /**
  * Git commit info
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GitCommit {
     pub sha: String,
     pub author: String,
@@ -90,22 +98,32 @@ pub async fn git_reset(
 #[tauri::command]
 pub async fn git_commit(
     message: String,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> Result<String, String> {
-    // TODO: Implement git commit
-    println!("[Git] Committing with message: {}", message);
-    Ok(String::from("abc1234")) // Return commit SHA
+    let repo = open_repository(&state).await?;
+    commit::commit(&repo, &message).map_err(|e| e.to_string())
 }
 
 /**
  * Push to remote
+ *
+ * Requires a `confirmation` token from `request_confirmation` for the same
+ * remote and branch.
  */
 #[tauri::command]
 pub async fn git_push(
     remote: Option<String>,
     branch: Option<String>,
-    _state: State<'_, AppState>,
+    confirmation: Option<String>,
+    state: State<'_, AppState>,
 ) -> Result<(), String> {
+    state.confirmations.consume(
+        confirmation.as_deref(),
+        &DangerousAction::GitPush {
+            remote: remote.clone(),
+            branch: branch.clone(),
+        },
+    )?;
     let remote = remote.unwrap_or_else(|| String::from("origin"));
     let branch = branch.unwrap_or_else(|| String::from("main"));
 
@@ -162,11 +180,10 @@ pub async fn git_branches(_state: State<'_, AppState>) -> Result<Vec<String>, St
 #[tauri::command]
 pub async fn git_checkout(
     branch: String,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> Result<(), String> {
-    // TODO: Implement git checkout
-    println!("[Git] Checking out branch: {}", branch);
-    Ok(())
+    let repo = open_repository(&state).await?;
+    history::checkout(&repo, &branch).map_err(|e| e.to_string())
 }
 
 /**
@@ -211,7 +228,7 @@ pub async fn git_init(path: String) -> Result<String, String> {
 //
 
 /// Worktree information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorktreeInfo {
     pub path: String,
     pub branch: String,
@@ -302,6 +319,7 @@ pub async fn git_create_worktree(
     branch: String,
     create_branch: bool,
 ) -> Result<WorktreeInfo, String> {
+    sanitize::branch_name(&branch)?;
     let working_dir = state.working_dir.lock().await;
 
     let mut cmd = Command::new("git");
@@ -399,6 +417,300 @@ pub async fn git_prune_worktrees(state: State<'_, AppState>) -> Result<(), Strin
     Ok(())
 }
 
+//
+// ============================================================
+// Git Stash Management
+// ============================================================
+//
+
+/// Open the repository containing the working directory
+pub(crate) async fn open_repository(state: &AppState) -> Result<Repository, String> {
+    let working_dir = state.working_dir.lock().await;
+    Repository::open(&*working_dir).map_err(|e| e.to_string())
+}
+
+/// List stashes, most recent first
+#[tauri::command]
+pub async fn git_stash_list(state: State<'_, AppState>) -> Result<Vec<StashEntry>, String> {
+    let repo = open_repository(&state).await?;
+    stash::list(&repo).map_err(|e| e.to_string())
+}
+
+/// Stash local changes
+///
+/// Returns `false` when there was nothing to stash.
+#[tauri::command]
+pub async fn git_stash_push(
+    state: State<'_, AppState>,
+    message: Option<String>,
+    include_untracked: bool,
+) -> Result<bool, String> {
+    let repo = open_repository(&state).await?;
+    stash::push(&repo, message.as_deref(), include_untracked).map_err(|e| e.to_string())
+}
+
+/// Apply a stash, keeping it in the list
+#[tauri::command]
+pub async fn git_stash_apply(state: State<'_, AppState>, index: usize) -> Result<(), String> {
+    let repo = open_repository(&state).await?;
+    stash::apply(&repo, index).map_err(|e| e.to_string())
+}
+
+/// Apply a stash and remove it from the list
+#[tauri::command]
+pub async fn git_stash_pop(state: State<'_, AppState>, index: usize) -> Result<(), String> {
+    let repo = open_repository(&state).await?;
+    stash::pop(&repo, index).map_err(|e| e.to_string())
+}
+
+/// Remove a stash without applying it
+#[tauri::command]
+pub async fn git_stash_drop(state: State<'_, AppState>, index: usize) -> Result<(), String> {
+    let repo = open_repository(&state).await?;
+    stash::drop(&repo, index).map_err(|e| e.to_string())
+}
+
+/// Get the patch of a stash
+#[tauri::command]
+pub async fn git_stash_show(state: State<'_, AppState>, index: usize) -> Result<String, String> {
+    let repo = open_repository(&state).await?;
+    stash::show(&repo, index).map_err(|e| e.to_string())
+}
+
+//
+// ============================================================
+// Hunk Staging
+// ============================================================
+//
+
+/// List the hunks of the working tree against the index, or of the index
+/// against HEAD when `staged` is set, for one file or for all
+#[tauri::command]
+pub async fn git_diff_hunks(
+    state: State<'_, AppState>,
+    staged: bool,
+    path: Option<String>,
+) -> Result<Vec<Hunk>, String> {
+    let repo = open_repository(&state).await?;
+    hunk::hunks(&repo, staged, path.as_deref().map(Path::new)).map_err(|e| e.to_string())
+}
+
+/// Stage one hunk of a file, by its index in the unstaged hunks
+#[tauri::command]
+pub async fn git_stage_hunk(
+    state: State<'_, AppState>,
+    path: String,
+    index: usize,
+) -> Result<(), String> {
+    let repo = open_repository(&state).await?;
+    hunk::stage(&repo, Path::new(&path), index).map_err(|e| e.to_string())
+}
+
+/// Unstage one hunk of a file, by its index in the staged hunks
+#[tauri::command]
+pub async fn git_unstage_hunk(
+    state: State<'_, AppState>,
+    path: String,
+    index: usize,
+) -> Result<(), String> {
+    let repo = open_repository(&state).await?;
+    hunk::unstage(&repo, Path::new(&path), index).map_err(|e| e.to_string())
+}
+
+//
+// ============================================================
+// History Graph and Rewriting
+// ============================================================
+//
+
+/// Get the commit graph of HEAD, or of all refs
+#[tauri::command]
+pub async fn git_log_graph(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+    all: Option<bool>,
+) -> Result<Vec<GraphRow>, String> {
+    let repo = open_repository(&state).await?;
+    log::graph(&repo, limit.unwrap_or(200), all.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+/// Get the commit that last changed each line of a file
+///
+/// `path` is relative to the repository root or absolute. Lines changed
+/// since HEAD are blamed on the all-zero commit.
+#[tauri::command]
+pub async fn git_blame(state: State<'_, AppState>, path: String) -> Result<Vec<BlameLine>, String> {
+    let repo = open_repository(&state).await?;
+    blame::blame(&repo, Path::new(&path)).map_err(|e| e.to_string())
+}
+
+/// Apply a commit on top of HEAD
+#[tauri::command]
+pub async fn git_cherry_pick(
+    state: State<'_, AppState>,
+    sha: String,
+) -> Result<OperationStatus, String> {
+    let repo = open_repository(&state).await?;
+    history::cherry_pick(&repo, &sha).map_err(|e| e.to_string())
+}
+
+/// Commit the inverse of a commit on top of HEAD
+#[tauri::command]
+pub async fn git_revert(
+    state: State<'_, AppState>,
+    sha: String,
+) -> Result<OperationStatus, String> {
+    let repo = open_repository(&state).await?;
+    history::revert(&repo, &sha).map_err(|e| e.to_string())
+}
+
+/// Build a rebase plan picking every commit after `onto`
+#[tauri::command]
+pub async fn git_rebase_plan(
+    state: State<'_, AppState>,
+    onto: String,
+) -> Result<RebasePlan, String> {
+    let repo = open_repository(&state).await?;
+    RebasePlan::new(&repo, &onto).map_err(|e| e.to_string())
+}
+
+/// Run an edited rebase plan
+#[tauri::command]
+pub async fn git_rebase_start(
+    state: State<'_, AppState>,
+    plan: RebasePlan,
+) -> Result<OperationStatus, String> {
+    let repo = open_repository(&state).await?;
+    rebase::start(&repo, &plan).map_err(|e| e.to_string())
+}
+
+/// Get the state of a paused merge, cherry-pick, revert or rebase
+#[tauri::command]
+pub async fn git_operation_status(state: State<'_, AppState>) -> Result<OperationStatus, String> {
+    let repo = open_repository(&state).await?;
+    history::status(&repo).map_err(|e| e.to_string())
+}
+
+/// Continue the paused operation after conflicts are resolved
+#[tauri::command]
+pub async fn git_operation_continue(
+    state: State<'_, AppState>,
+) -> Result<OperationStatus, String> {
+    let repo = open_repository(&state).await?;
+    history::continue_operation(&repo).map_err(|e| e.to_string())
+}
+
+/// Skip the current commit of the paused operation
+#[tauri::command]
+pub async fn git_operation_skip(state: State<'_, AppState>) -> Result<OperationStatus, String> {
+    let repo = open_repository(&state).await?;
+    history::skip(&repo).map_err(|e| e.to_string())
+}
+
+/// Abort the paused operation
+#[tauri::command]
+pub async fn git_operation_abort(state: State<'_, AppState>) -> Result<(), String> {
+    let repo = open_repository(&state).await?;
+    history::abort(&repo).map_err(|e| e.to_string())
+}
+
+//
+// ============================================================
+// Conflict Resolution
+// ============================================================
+//
+
+/// Open the repository at `repo_path`, or the working directory's
+///
+/// Agent worktrees (such as an ensemble's integration worktree) pass their
+/// own path so their conflicts are resolved the same way as the workspace's.
+async fn open_conflict_repository(
+    state: &AppState,
+    repo_path: Option<String>,
+) -> Result<Repository, String> {
+    match repo_path {
+        Some(path) => Repository::open(path).map_err(|e| e.to_string()),
+        None => open_repository(state).await,
+    }
+}
+
+/// List files with unresolved conflicts
+#[tauri::command]
+pub async fn git_conflicted_files(
+    state: State<'_, AppState>,
+    repo_path: Option<String>,
+) -> Result<Vec<String>, String> {
+    let repo = open_conflict_repository(&state, repo_path).await?;
+    history::conflicted_files(&repo).map_err(|e| e.to_string())
+}
+
+/// Split a conflicted file into its ours/base/theirs hunks
+#[tauri::command]
+pub async fn git_load_conflicts(
+    state: State<'_, AppState>,
+    path: String,
+    repo_path: Option<String>,
+) -> Result<ConflictFile, String> {
+    let repo = open_conflict_repository(&state, repo_path).await?;
+    conflict::load(&repo, &path).map_err(|e| e.to_string())
+}
+
+/// Rewrite a conflicted file's markers with the base included, dropping edits
+#[tauri::command]
+pub async fn git_restore_conflict_base(
+    state: State<'_, AppState>,
+    path: String,
+    repo_path: Option<String>,
+) -> Result<ConflictFile, String> {
+    let repo = open_conflict_repository(&state, repo_path).await?;
+    conflict::restore_base(&repo, &path).map_err(|e| e.to_string())
+}
+
+/// Apply one resolution per hunk, stage the file, and return the files still
+/// in conflict
+#[tauri::command]
+pub async fn git_resolve_conflicts(
+    state: State<'_, AppState>,
+    path: String,
+    resolutions: Vec<Resolution>,
+    repo_path: Option<String>,
+) -> Result<Vec<String>, String> {
+    let repo = open_conflict_repository(&state, repo_path).await?;
+    let content = conflict::load(&repo, &path)
+        .and_then(|file| file.resolve(&resolutions))
+        .map_err(|e| e.to_string())?;
+    conflict::mark_resolved(&repo, &path, &content).map_err(|e| e.to_string())?;
+    history::conflicted_files(&repo).map_err(|e| e.to_string())
+}
+
+/// List conflicted files with their base, ours and theirs versions and
+/// their conflict hunks, for a three-way view
+///
+/// Pass the worktree a competition or debate branch is merged in as
+/// `repo_path`; the working directory's repository is used otherwise.
+#[tauri::command]
+pub async fn get_merge_conflicts(
+    state: State<'_, AppState>,
+    repo_path: Option<String>,
+) -> Result<Vec<MergeConflict>, String> {
+    let repo = open_conflict_repository(&state, repo_path).await?;
+    conflict::merge_conflicts(&repo).map_err(|e| e.to_string())
+}
+
+/// Resolve a whole file with our version, theirs, or custom content, stage
+/// it, and return the files still in conflict
+#[tauri::command]
+pub async fn resolve_conflict(
+    state: State<'_, AppState>,
+    path: String,
+    resolution: FileResolution,
+    repo_path: Option<String>,
+) -> Result<Vec<String>, String> {
+    let repo = open_conflict_repository(&state, repo_path).await?;
+    conflict::resolve_file(&repo, &path, &resolution).map_err(|e| e.to_string())?;
+    history::conflicted_files(&repo).map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,301 @@
+//! Issue Tracker Commands
+//!
+//! Tauri commands for picking a GitHub or Jira issue as the task of a run
+//! (see `issues`), linking sessions to the issue they work on, and posting
+//! a session's report back to that issue as a comment.
+
+use ait42_config::IssuesConfig;
+use ait42_git::Repository;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::info;
+
+use crate::commands::session_history::{find_session, modify_sessions, WorktreeSession};
+use crate::issues::{self, Issue, IssueClient, IssueLink, IssueProvider};
+use crate::state::AppState;
+
+/// An issue tracker and whether it is ready to use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueTrackerStatus {
+    pub provider: IssueProvider,
+    /// GitHub repository or Jira project issues are listed from
+    pub project: Option<String>,
+    /// Whether the tracker is configured (always true for GitHub with a
+    /// github.com origin)
+    pub configured: bool,
+    /// Whether an API token is available
+    pub has_token: bool,
+}
+
+fn issues_config(state: &AppState) -> IssuesConfig {
+    match state.config.lock() {
+        Ok(config) => config.issues.clone(),
+        Err(e) => e.into_inner().issues.clone(),
+    }
+}
+
+/// Repository identity of the workspace's origin, e.g. `github.com/org/repo`
+fn remote_id(workspace_path: &str) -> Option<String> {
+    Repository::open(workspace_path)
+        .ok()?
+        .remote_id()
+        .ok()
+        .flatten()
+}
+
+fn client(
+    state: &AppState,
+    workspace_path: &str,
+    provider: IssueProvider,
+) -> Result<IssueClient, String> {
+    IssueClient::new(provider, &issues_config(state), remote_id(workspace_path).as_deref())
+}
+
+/// Plain-text report of `session`
+///
+/// Uses only `*` bullets, which GitHub Markdown and Jira markup both render
+/// as a list.
+fn session_report(session: &WorktreeSession) -> String {
+    let short_id = session.id.get(..8).unwrap_or(&session.id);
+    let mut lines = vec![
+        format!("AIT42 {} session {}: {}", session.r#type, short_id, session.status),
+        String::new(),
+        format!("Task: {}", session.task.lines().next().unwrap_or_default()),
+    ];
+
+    if !session.instances.is_empty() {
+        lines.push(String::new());
+    }
+    for instance in &session.instances {
+        let mut line = format!("* Instance #{} ({}", instance.instance_id, instance.agent_name);
+        if let Some(model) = &instance.model {
+            line.push_str(&format!(", {}", model));
+        }
+        line.push_str(&format!("): {}", instance.status));
+        if let (Some(added), Some(deleted)) = (instance.lines_added, instance.lines_deleted) {
+            line.push_str(&format!(", +{} -{}", added, deleted));
+        }
+        lines.push(line);
+    }
+
+    lines.push(String::new());
+    let winner = session
+        .winner_id
+        .and_then(|id| session.instances.iter().find(|i| i.instance_id == id));
+    if let Some(winner) = winner {
+        lines.push(format!("Winner: instance #{} on branch {}", winner.instance_id, winner.branch));
+    }
+    if let (Some(files), Some(added), Some(deleted)) = (
+        session.total_files_changed,
+        session.total_lines_added,
+        session.total_lines_deleted,
+    ) {
+        lines.push(format!("Changes: {} files, +{} -{}", files, added, deleted));
+    }
+    if !session.artifacts.is_empty() {
+        let titles: Vec<&str> = session.artifacts.iter().map(|a| a.title.as_str()).collect();
+        lines.push(format!("Reports: {}", titles.join(", ")));
+    }
+    lines.join("\n").trim_end().to_string()
+}
+
+/// Issue trackers, with whether each is configured and has a token
+///
+/// # Arguments
+/// * `workspace_path` - Workspace whose origin implies the GitHub repository
+#[tauri::command]
+pub async fn get_issue_trackers(
+    state: State<'_, AppState>,
+    workspace_path: String,
+) -> Result<Vec<IssueTrackerStatus>, String> {
+    let config = issues_config(&state);
+    let github_repo = issues::github_repo(&config, remote_id(&workspace_path).as_deref());
+    let trackers = [
+        (IssueProvider::Github, github_repo),
+        (IssueProvider::Jira, config.jira_project.clone()),
+    ];
+    Ok(trackers
+        .into_iter()
+        .map(|(provider, project)| {
+            let configured = match provider {
+                IssueProvider::Github => project.is_some(),
+                IssueProvider::Jira => config.jira_url.is_some(),
+            };
+            // An unreadable keychain counts as no token; saving one reports why
+            let has_token = matches!(issues::load_token(provider), Ok(Some(_)));
+            IssueTrackerStatus {
+                provider,
+                project,
+                configured,
+                has_token,
+            }
+        })
+        .collect())
+}
+
+/// Save the API token of an issue tracker in the OS keychain
+///
+/// # Arguments
+/// * `provider` - `github` or `jira`
+/// * `token` - Token to save; `None` or empty removes the saved token
+#[tauri::command]
+pub async fn set_issue_tracker_token(
+    provider: IssueProvider,
+    token: Option<String>,
+) -> Result<(), String> {
+    issues::store_token(provider, token.as_deref())?;
+    info!("Updated {:?} issue tracker token", provider);
+    Ok(())
+}
+
+/// Open issues of a tracker, most recently updated first
+///
+/// # Arguments
+/// * `workspace_path` - Workspace whose origin implies the GitHub repository
+/// * `provider` - `github` or `jira`
+/// * `query` - Only issues matching this text
+#[tauri::command]
+pub async fn list_issues(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    provider: IssueProvider,
+    query: Option<String>,
+) -> Result<Vec<Issue>, String> {
+    client(&state, &workspace_path, provider)?
+        .list(query.as_deref())
+        .await
+}
+
+/// One issue, with its description and the task text for a run
+///
+/// # Arguments
+/// * `provider` - `github` or `jira`
+/// * `key` - `#42` on GitHub, `PROJ-7` on Jira
+#[tauri::command]
+pub async fn get_issue(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    provider: IssueProvider,
+    key: String,
+) -> Result<Issue, String> {
+    client(&state, &workspace_path, provider)?.get(&key).await
+}
+
+/// Link a session to the issue it works on, or unlink it
+///
+/// # Arguments
+/// * `issue` - Issue to link; `None` removes the link
+#[tauri::command]
+pub async fn link_session_issue(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+    issue: Option<IssueLink>,
+) -> Result<WorktreeSession, String> {
+    state.session_streams.ensure_writable(&session_id)?;
+    modify_sessions(&state, &workspace_path, |sessions| {
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id && !s.is_deleted())
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        session.issue = issue;
+        session.updated_at = chrono::Utc::now().to_rfc3339();
+        Ok(session.clone())
+    })
+}
+
+/// Post a session's report as a comment on its linked issue
+///
+/// # Returns
+/// * `Ok(url)` - URL of the comment (empty if the tracker did not say)
+/// * `Err(message)` - The session is not linked to an issue, or posting failed
+#[tauri::command]
+pub async fn post_session_report(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+) -> Result<String, String> {
+    let session = find_session(&state, &workspace_path, &session_id)
+        .filter(|s| !s.is_deleted())
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let issue = session
+        .issue
+        .as_ref()
+        .ok_or_else(|| format!("Session {} is not linked to an issue", session_id))?;
+    client(&state, &workspace_path, issue.provider)?
+        .comment(&issue.key, &session_report(&session))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session_history::{SessionArtifact, WorktreeInstance};
+
+    fn instance(instance_id: u32, status: &str) -> WorktreeInstance {
+        WorktreeInstance {
+            instance_id,
+            worktree_path: format!("/tmp/wt-{}", instance_id),
+            branch: format!("ait42/{}", instance_id),
+            agent_name: "claude".to_string(),
+            agent_version: None,
+            touched_modules: None,
+            diff_summary: None,
+            status: status.to_string(),
+            tmux_session_id: format!("ait42-{}", instance_id),
+            output: None,
+            start_time: None,
+            end_time: None,
+            files_changed: None,
+            lines_added: None,
+            lines_deleted: None,
+            runtime: None,
+            model: None,
+            runtime_label: None,
+        }
+    }
+
+    #[test]
+    fn test_session_report() {
+        let mut session: WorktreeSession = serde_json::from_value(serde_json::json!({
+            "id": "1a2b3c4d-5e6f",
+            "type": "competition",
+            "task": "Fix the login bug\n\nDetails",
+            "status": "completed",
+            "createdAt": "2026-01-01T00:00:00Z",
+            "updatedAt": "2026-01-01T00:00:00Z",
+            "instances": [],
+            "chatHistory": [],
+        }))
+        .unwrap();
+        session.instances = vec![
+            WorktreeInstance {
+                model: Some("sonnet".to_string()),
+                lines_added: Some(10),
+                lines_deleted: Some(2),
+                ..instance(1, "completed")
+            },
+            instance(2, "failed"),
+        ];
+        session.winner_id = Some(1);
+        session.artifacts.push(SessionArtifact {
+            id: "r".to_string(),
+            kind: "ranking".to_string(),
+            title: "Competition ranking".to_string(),
+            content: String::new(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        });
+
+        assert_eq!(
+            session_report(&session),
+            "AIT42 competition session 1a2b3c4d: completed\n\n\
+             Task: Fix the login bug\n\n\
+             * Instance #1 (claude, sonnet): completed, +10 -2\n\
+             * Instance #2 (claude): failed\n\n\
+             Winner: instance #1 on branch ait42/1\n\
+             Reports: Competition ranking"
+        );
+    }
+}
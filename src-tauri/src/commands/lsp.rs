@@ -2,14 +2,20 @@
 //!
 //! Tauri commands for Language Server Protocol integration.
 
+use crate::commands::review::review_diagnostics;
+use crate::notifications::{NotificationAction, NotificationLevel};
 use crate::state::AppState;
+use ait42_lsp::{ProcessSampler, WatchdogConfig};
 use lsp_types::{Position, Url};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tauri::State;
+use std::time::Duration;
+use tauri::{Manager, State};
+use tracing::{debug, warn};
 
 /// LSP diagnostic information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LspDiagnostic {
     pub message: String,
     pub severity: u8, // 1=Error, 2=Warning, 3=Information, 4=Hint
@@ -22,7 +28,7 @@ pub struct LspDiagnostic {
 }
 
 /// Completion item information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LspCompletionItem {
     pub label: String,
     pub kind: Option<u8>,
@@ -33,13 +39,13 @@ pub struct LspCompletionItem {
 }
 
 /// Hover information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LspHoverInfo {
     pub contents: String,
 }
 
 /// Location information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LspLocation {
     pub uri: String,
     pub start_line: u32,
@@ -64,6 +70,7 @@ pub async fn start_lsp_server(
 /// Stop LSP server for a specific language
 #[tauri::command]
 pub async fn stop_lsp_server(language: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.lsp_watchdog.lock().await.forget(&language);
     state
         .lsp_manager
         .stop_server(&language)
@@ -71,12 +78,140 @@ pub async fn stop_lsp_server(language: String, state: State<'_, AppState>) -> Re
         .map_err(|e| format!("Failed to stop LSP server for {}: {}", language, e))
 }
 
+/// Restart LSP server for a specific language
+///
+/// The frontend should send `lsp_did_open` for the language's open buffers
+/// afterwards.
+#[tauri::command]
+pub async fn restart_lsp_server(
+    language: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.lsp_watchdog.lock().await.forget(&language);
+    state
+        .lsp_manager
+        .restart_server(&language)
+        .await
+        .map_err(|e| format!("Failed to restart LSP server for {}: {}", language, e))
+}
+
 /// Get list of running LSP servers
 #[tauri::command]
 pub async fn get_running_lsp_servers(state: State<'_, AppState>) -> Result<Vec<String>, String> {
     Ok(state.lsp_manager.running_servers().await)
 }
 
+/// Detach a buffer from its LSP server
+///
+/// The server forgets the document, and LSP requests for it return empty
+/// results until it is attached again.
+#[tauri::command]
+pub async fn lsp_detach_buffer(file_path: String, state: State<'_, AppState>) -> Result<(), String> {
+    state
+        .lsp_manager
+        .detach_document(Path::new(&file_path))
+        .await
+        .map_err(|e| format!("Failed to detach {} from LSP: {}", file_path, e))
+}
+
+/// Attach a previously detached buffer to its LSP server
+#[tauri::command]
+pub async fn lsp_attach_buffer(
+    file_path: String,
+    content: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .lsp_manager
+        .attach_document(Path::new(&file_path), content)
+        .await
+        .map_err(|e| format!("Failed to attach {} to LSP: {}", file_path, e))
+}
+
+/// Get file paths of buffers detached from LSP
+#[tauri::command]
+pub async fn get_detached_lsp_buffers(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(state
+        .lsp_manager
+        .detached_documents()
+        .await
+        .into_iter()
+        .filter_map(|uri| uri.to_file_path().ok())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Get the LSP watchdog thresholds
+#[tauri::command]
+pub async fn get_lsp_watchdog_config(
+    state: State<'_, AppState>,
+) -> Result<WatchdogConfig, String> {
+    Ok(state.lsp_watchdog.lock().await.config().clone())
+}
+
+/// Update the LSP watchdog thresholds
+#[tauri::command]
+pub async fn set_lsp_watchdog_config(
+    config: WatchdogConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if config.interval_secs == 0 {
+        return Err("Watchdog interval must be at least one second".to_string());
+    }
+    state.lsp_watchdog.lock().await.set_config(config);
+    Ok(())
+}
+
+/// Periodically sample language servers and report sustained overuse
+///
+/// Runs for the lifetime of the app. Each report goes to the notification
+/// center with an action that restarts the offending server.
+pub async fn run_lsp_watchdog(app: tauri::AppHandle) {
+    let mut sampler = ProcessSampler::new();
+
+    loop {
+        let state = app.state::<AppState>();
+        let config = state.lsp_watchdog.lock().await.config().clone();
+        tokio::time::sleep(Duration::from_secs(config.interval_secs.max(1))).await;
+        if !config.enabled {
+            continue;
+        }
+
+        let processes = state.lsp_manager.server_processes().await;
+        if processes.is_empty() {
+            continue;
+        }
+        let pids: Vec<u32> = processes.iter().map(|(_, pid)| *pid).collect();
+        let samples = sampler.sample(&pids);
+
+        let events: Vec<_> = {
+            let mut watchdog = state.lsp_watchdog.lock().await;
+            processes
+                .iter()
+                .filter_map(|(language, pid)| {
+                    let sample = samples.get(pid)?;
+                    watchdog.observe(language, *sample)
+                })
+                .collect()
+        };
+
+        for event in events {
+            warn!("LSP watchdog: {}", event);
+            state.notifications.notify(
+                &app,
+                NotificationLevel::Warning,
+                "lsp",
+                format!("{}. Restarting it may help.", event),
+                vec![NotificationAction {
+                    label: "Restart server".to_string(),
+                    command: "restart_lsp_server".to_string(),
+                    args: serde_json::json!({ "language": event.language }),
+                }],
+            );
+        }
+    }
+}
+
 /// Notify LSP server that a document was opened
 #[tauri::command]
 pub async fn lsp_did_open(
@@ -87,6 +222,11 @@ pub async fn lsp_did_open(
 ) -> Result<(), String> {
     let path = Path::new(&file_path);
 
+    // Detached buffers are invisible to the server
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(());
+    }
+
     // Ensure server is running for this language
     let client = state
         .lsp_manager
@@ -115,6 +255,10 @@ pub async fn lsp_did_change(
 ) -> Result<(), String> {
     let path = Path::new(&file_path);
 
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(());
+    }
+
     // Get language from file extension
     let language = state
         .lsp_manager
@@ -155,6 +299,10 @@ pub async fn lsp_did_save(
 ) -> Result<(), String> {
     let path = Path::new(&file_path);
 
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(());
+    }
+
     // Get language from file extension
     let language = state
         .lsp_manager
@@ -184,6 +332,10 @@ pub async fn lsp_did_save(
 pub async fn lsp_did_close(file_path: String, state: State<'_, AppState>) -> Result<(), String> {
     let path = Path::new(&file_path);
 
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(());
+    }
+
     // Get language from file extension
     let language = state
         .lsp_manager
@@ -218,6 +370,10 @@ pub async fn lsp_completion(
 ) -> Result<Vec<LspCompletionItem>, String> {
     let path = Path::new(&file_path);
 
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(Vec::new());
+    }
+
     // Get language from file extension
     let language = state
         .lsp_manager
@@ -299,6 +455,10 @@ pub async fn lsp_hover(
 ) -> Result<Option<LspHoverInfo>, String> {
     let path = Path::new(&file_path);
 
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(None);
+    }
+
     // Get language from file extension
     let language = state
         .lsp_manager
@@ -355,6 +515,10 @@ pub async fn lsp_goto_definition(
 ) -> Result<Vec<LspLocation>, String> {
     let path = Path::new(&file_path);
 
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(Vec::new());
+    }
+
     // Get language from file extension
     let language = state
         .lsp_manager
@@ -409,12 +573,38 @@ pub async fn lsp_goto_definition(
 }
 
 /// Get diagnostics for a specific file
+///
+/// Findings from the last code review of the file are included with the
+/// source `code-review`, and are returned even when no language server is
+/// running for the file.
 #[tauri::command]
 pub async fn lsp_diagnostics(
     file_path: String,
     state: State<'_, AppState>,
 ) -> Result<Vec<LspDiagnostic>, String> {
-    let path = Path::new(&file_path);
+    let review = review_diagnostics(&state, Path::new(&file_path));
+    let mut diagnostics = match server_diagnostics(&file_path, &state).await {
+        Ok(diagnostics) => diagnostics,
+        Err(e) if !review.is_empty() => {
+            debug!("Showing review findings only for {}: {}", file_path, e);
+            Vec::new()
+        }
+        Err(e) => return Err(e),
+    };
+    diagnostics.extend(review);
+    Ok(diagnostics)
+}
+
+/// Diagnostics published by the language server for `file_path`
+async fn server_diagnostics(
+    file_path: &str,
+    state: &AppState,
+) -> Result<Vec<LspDiagnostic>, String> {
+    let path = Path::new(file_path);
+
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(Vec::new());
+    }
 
     // Get language from file extension
     let language = state
@@ -0,0 +1,258 @@
+//! MCP Commands
+//!
+//! Tauri commands for starting and stopping the MCP server (see `mcp`), and
+//! the editor side of its resources and tools, backed by the same commands
+//! the UI uses.
+
+use ait42_config::{EditGuardConfig, McpConfig};
+use ait42_git::Repository;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{Manager, State};
+
+use crate::commands::ait42::{execute_agent, AgentExecutionRequest};
+use crate::commands::editor::{get_buffer_content, replace_text, TextRange};
+use crate::commands::lsp::{lsp_diagnostics, LspDiagnostic};
+use crate::commands::session_history::get_all_sessions;
+use crate::edit_guard;
+use crate::mcp::{
+    McpBackend, McpStatus, Resource, ResourceContent, ResourceInfo, ToolCall, TOKEN_ENV,
+};
+use crate::remote::token_from;
+use crate::state::AppState;
+
+fn mcp_config(state: &AppState) -> McpConfig {
+    match state.config.lock() {
+        Ok(config) => config.mcp.clone(),
+        Err(e) => e.into_inner().mcp.clone(),
+    }
+}
+
+fn edit_guard_config(state: &AppState) -> EditGuardConfig {
+    match state.config.lock() {
+        Ok(config) => config.ait42.edit_guard.clone(),
+        Err(e) => e.into_inner().ait42.edit_guard.clone(),
+    }
+}
+
+/// The running editor, as seen by MCP clients
+struct EditorContext {
+    app: tauri::AppHandle,
+}
+
+impl EditorContext {
+    fn state(&self) -> State<'_, AppState> {
+        self.app.state::<AppState>()
+    }
+
+    /// Paths of the open buffers backed by a file
+    fn open_files(&self) -> Result<Vec<String>, String> {
+        let state = self.state();
+        let editor = state
+            .editor
+            .lock()
+            .map_err(|e| format!("Failed to lock editor: {}", e))?;
+        let buffers = editor.buffers();
+        Ok(buffers
+            .buffer_ids()
+            .into_iter()
+            .filter_map(|id| buffers.get(id)?.path())
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Path of the file behind buffer `buffer_id`, `None` for unsaved buffers
+    fn buffer_path(&self, buffer_id: &str) -> Result<Option<PathBuf>, String> {
+        let state = self.state();
+        let editor = state
+            .editor
+            .lock()
+            .map_err(|e| format!("Failed to lock editor: {}", e))?;
+        let buffers = editor.buffers();
+        let id = buffers
+            .buffer_ids()
+            .into_iter()
+            .find(|id| id.to_string() == buffer_id)
+            .ok_or_else(|| format!("Buffer not found: {}", buffer_id))?;
+        Ok(buffers
+            .get(id)
+            .and_then(|b| b.path())
+            .map(|p| p.to_path_buf()))
+    }
+
+    /// Check an edit with the edit guard, asking the user to approve risky ones
+    async fn guard_edit(&self, buffer_id: &str, text: &str) -> Result<(), String> {
+        let path = self.buffer_path(buffer_id)?;
+        let workspace = self.state().working_dir.lock().await.clone();
+        let config = edit_guard_config(&self.state());
+        let risks = edit_guard::check(&workspace, path.as_deref(), text, &config);
+        if risks.is_empty() {
+            return Ok(());
+        }
+
+        let target = match &path {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => format!("buffer {}", buffer_id),
+        };
+        if edit_guard::approve(&self.app, &target, &risks).await {
+            tracing::info!("User approved risky MCP edit of {}: {:?}", target, risks);
+            return Ok(());
+        }
+        let reasons: Vec<String> = risks.iter().map(ToString::to_string).collect();
+        tracing::warn!("Blocked MCP edit of {}: {}", target, reasons.join(", "));
+        Err(format!("Edit of {} was not approved: it {}", target, reasons.join(" and ")))
+    }
+
+    async fn workspace(&self) -> String {
+        self.state()
+            .working_dir
+            .lock()
+            .await
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+#[async_trait]
+impl McpBackend for EditorContext {
+    async fn buffers(&self) -> Result<Vec<ResourceInfo>, String> {
+        let state = self.state();
+        let editor = state
+            .editor
+            .lock()
+            .map_err(|e| format!("Failed to lock editor: {}", e))?;
+        let buffers = editor.buffers();
+        Ok(buffers
+            .buffer_ids()
+            .into_iter()
+            .filter_map(|id| {
+                let buffer = buffers.get(id)?;
+                Some(ResourceInfo {
+                    uri: Resource::Buffer(id.to_string()).uri(),
+                    name: buffer
+                        .path()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "untitled".to_string()),
+                    description: buffer.language().map(|l| format!("{} buffer", l)),
+                    mime_type: "text/plain".to_string(),
+                })
+            })
+            .collect())
+    }
+
+    async fn read(&self, resource: &Resource) -> Result<ResourceContent, String> {
+        match resource {
+            Resource::Buffer(id) => {
+                Ok(ResourceContent::text(get_buffer_content(id.clone(), self.state()).await?))
+            }
+            Resource::Diagnostics => {
+                let mut diagnostics: BTreeMap<String, Vec<LspDiagnostic>> = BTreeMap::new();
+                for path in self.open_files()? {
+                    match lsp_diagnostics(path.clone(), self.state()).await {
+                        Ok(found) if !found.is_empty() => {
+                            diagnostics.insert(path, found);
+                        }
+                        Ok(_) => {}
+                        Err(e) => tracing::debug!("No diagnostics for {}: {}", path, e),
+                    }
+                }
+                ResourceContent::json(&diagnostics)
+            }
+            Resource::GitStatus => {
+                let repo = Repository::open(self.workspace().await).map_err(|e| e.to_string())?;
+                let status = repo
+                    .run(&["status", "--porcelain=v1", "--branch"])
+                    .map_err(|e| e.to_string())?;
+                Ok(ResourceContent::text(status))
+            }
+            Resource::Sessions => {
+                let workspace = self.workspace().await;
+                ResourceContent::json(&get_all_sessions(self.state(), workspace).await?)
+            }
+        }
+    }
+
+    async fn call(&self, call: ToolCall) -> Result<String, String> {
+        match call {
+            ToolCall::ApplyEdit(edit) => {
+                self.guard_edit(&edit.buffer_id, &edit.text).await?;
+                let range = TextRange {
+                    start: edit.start,
+                    end: edit.end,
+                };
+                replace_text(edit.buffer_id.clone(), range, edit.text, self.state()).await?;
+                tracing::info!("MCP client edited buffer {}", edit.buffer_id);
+                Ok(format!(
+                    "Replaced bytes {}..{} of buffer {}",
+                    edit.start, edit.end, edit.buffer_id
+                ))
+            }
+            ToolCall::RunTask(run) => {
+                let request = AgentExecutionRequest {
+                    agent_name: run.agent_name,
+                    task: run.task,
+                    context: run.context,
+                    execution_id: None,
+                    session_id: None,
+                    workspace_path: None,
+                };
+                let response = execute_agent(self.app.clone(), self.state(), request).await?;
+                match response.error {
+                    Some(error) => Err(error),
+                    None => Ok(response.output.unwrap_or_default()),
+                }
+            }
+        }
+    }
+}
+
+fn start(app: &tauri::AppHandle, bind: Option<String>) -> Result<McpStatus, String> {
+    let state = app.state::<AppState>();
+    let config = mcp_config(&state);
+    let bind = bind.filter(|b| !b.trim().is_empty()).unwrap_or(config.bind);
+    let token = token_from(TOKEN_ENV, config.token.as_deref());
+    let backend = Arc::new(EditorContext { app: app.clone() });
+    state.mcp.start(&bind, token, backend)
+}
+
+/// Start the MCP server if `[mcp] enabled` is set
+pub fn start_mcp_on_startup(app: &tauri::AppHandle) {
+    if !mcp_config(&app.state::<AppState>()).enabled {
+        return;
+    }
+    if let Err(e) = start(app, None) {
+        tracing::warn!("Failed to start MCP server: {}", e);
+    }
+}
+
+/// State of the MCP server, with its URL and token while running
+#[tauri::command]
+pub async fn get_mcp_status(state: State<'_, AppState>) -> Result<McpStatus, String> {
+    Ok(state.mcp.status())
+}
+
+/// Start the MCP server
+///
+/// The token is taken from `AIT42_MCP_TOKEN`, then `[mcp] token`; without
+/// either, a random one is generated and shown in the status.
+///
+/// # Arguments
+/// * `bind` - Address to listen on (default: `[mcp] bind`)
+#[tauri::command]
+pub async fn start_mcp_server(
+    app: tauri::AppHandle,
+    bind: Option<String>,
+) -> Result<McpStatus, String> {
+    start(&app, bind)
+}
+
+/// Stop the MCP server
+///
+/// # Returns
+/// * `Ok(false)` - The server was not running
+#[tauri::command]
+pub async fn stop_mcp_server(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.mcp.stop())
+}
@@ -4,15 +4,40 @@
 
 pub mod file;
 pub mod editor;
+pub mod annotations;
+pub mod todos;
+pub mod search;
+pub mod refactor;
+pub mod quick_fix;
 pub mod lsp;
+pub mod mcp;
+pub mod notifications;
+pub mod budget;
 pub mod git;
+pub mod commit_message;
+pub mod changelog;
+pub mod competition;
+pub mod issues;
+pub mod review;
+pub mod checkpoint;
 pub mod plugin;
 pub mod ait42;
+pub mod auto_mode;
+pub mod quick_task;
 pub mod worktree;
 pub mod optimizer;
 pub mod session_history;
+pub mod team_feed;
+pub mod observer;
+pub mod remote;
+pub mod telemetry;
 pub mod workspace;
 pub mod system;
+pub mod clipboard_history;
+pub mod confirmation;
+pub mod update;
+pub mod features;
+pub mod api_schema;
 
 #[cfg(feature = "terminal")]
 pub mod terminal;
@@ -20,15 +45,40 @@ pub mod terminal;
 // Re-export commands
 pub use file::*;
 pub use editor::*;
+pub use annotations::*;
+pub use todos::*;
+pub use search::*;
+pub use refactor::*;
+pub use quick_fix::*;
 pub use lsp::*;
+pub use mcp::*;
+pub use notifications::*;
+pub use budget::*;
 pub use git::*;
+pub use commit_message::*;
+pub use changelog::*;
+pub use competition::*;
+pub use issues::*;
+pub use review::*;
+pub use checkpoint::*;
 pub use plugin::*;
 pub use ait42::*;
+pub use auto_mode::*;
+pub use quick_task::*;
 pub use worktree::*;
 pub use optimizer::*;
 pub use session_history::*;
+pub use team_feed::*;
+pub use observer::*;
+pub use remote::*;
+pub use telemetry::*;
 pub use workspace::*;
 pub use system::*;
+pub use clipboard_history::*;
+pub use confirmation::*;
+pub use update::*;
+pub use features::*;
+pub use api_schema::*;
 
 #[cfg(feature = "terminal")]
 pub use terminal::*;
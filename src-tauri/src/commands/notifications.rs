@@ -0,0 +1,30 @@
+//! Notification Commands
+//!
+//! Tauri commands for reading and dismissing notification center entries.
+
+use crate::notifications::Notification;
+use crate::state::AppState;
+use tauri::State;
+
+/// Get all notifications, oldest first
+#[tauri::command]
+pub async fn list_notifications(state: State<'_, AppState>) -> Result<Vec<Notification>, String> {
+    Ok(state.notifications.list())
+}
+
+/// Dismiss a single notification
+#[tauri::command]
+pub async fn dismiss_notification(id: u64, state: State<'_, AppState>) -> Result<(), String> {
+    if state.notifications.dismiss(id) {
+        Ok(())
+    } else {
+        Err(format!("Notification not found: {}", id))
+    }
+}
+
+/// Dismiss all notifications
+#[tauri::command]
+pub async fn clear_notifications(state: State<'_, AppState>) -> Result<(), String> {
+    state.notifications.clear();
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! Observer Commands
+//!
+//! Tauri commands for exporting a competition's or debate's event stream and
+//! for following another machine's stream in read-only observer mode.
+
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::commands::session_history::{read_sessions_file, WorktreeSession};
+use crate::session_stream::{ObservedSession, StreamTarget};
+use crate::state::AppState;
+
+/// Export the events of a session from now on
+///
+/// # Arguments
+/// * `session_id` - Competition or debate ID
+/// * `target` - File to append events to, or `tcp://host:port` of an observer
+#[tauri::command]
+pub async fn export_session_events(
+    session_id: String,
+    target: String,
+    state: State<'_, AppState>,
+) -> Result<StreamTarget, String> {
+    let target = StreamTarget::parse(&target)?;
+    state.session_streams.export(&session_id, target.clone())?;
+    tracing::info!("Exporting events of session {} to {:?}", session_id, target);
+    Ok(target)
+}
+
+/// Stop exporting the events of a session
+#[tauri::command]
+pub async fn stop_session_export(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<StreamTarget>, String> {
+    Ok(state.session_streams.stop_export(&session_id))
+}
+
+/// Open another machine's session in observer mode
+///
+/// # Arguments
+/// * `session_id` - Competition or debate ID on the other machine
+/// * `source` - Exported events file, or `tcp://host:port` to listen on
+/// * `session_store` - Copy of the other machine's sessions file
+///
+/// # Returns
+/// * `Ok(Some(session))` - The session as recorded in `session_store`
+/// * `Ok(None)` - No session store given, or it has no such session yet
+#[tauri::command]
+pub async fn observe_session(
+    app: tauri::AppHandle,
+    session_id: String,
+    source: String,
+    session_store: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<WorktreeSession>, String> {
+    let observed = ObservedSession {
+        session_id: session_id.clone(),
+        source: StreamTarget::parse(&source)?,
+        session_store: session_store.filter(|p| !p.trim().is_empty()).map(PathBuf::from),
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let session = observed_session_record(&observed)?;
+    state.session_streams.observe(app, observed)?;
+    tracing::info!("Observing session {} from {}", session_id, source);
+    Ok(session)
+}
+
+/// Re-read an observed session from its session store
+#[tauri::command]
+pub async fn get_observed_session(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<WorktreeSession>, String> {
+    let observed = state
+        .session_streams
+        .observed_session(&session_id)
+        .ok_or_else(|| format!("Session {} is not being observed", session_id))?;
+    observed_session_record(&observed)
+}
+
+/// Sessions open in observer mode
+#[tauri::command]
+pub async fn list_observed_sessions(
+    state: State<'_, AppState>,
+) -> Result<Vec<ObservedSession>, String> {
+    Ok(state.session_streams.observed())
+}
+
+/// Stop observing a session
+#[tauri::command]
+pub async fn stop_observing(session_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    if state.session_streams.stop_observing(&session_id) {
+        Ok(())
+    } else {
+        Err(format!("Session {} is not being observed", session_id))
+    }
+}
+
+fn observed_session_record(observed: &ObservedSession) -> Result<Option<WorktreeSession>, String> {
+    let Some(store) = &observed.session_store else {
+        return Ok(None);
+    };
+    Ok(read_sessions_file(store)?
+        .into_iter()
+        .find(|s| s.id == observed.session_id))
+}
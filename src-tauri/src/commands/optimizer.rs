@@ -6,19 +6,38 @@
 //! to the TypeScript frontend via Tauri IPC.
 
 use crate::ab_test::{ABTestResult, ABTestRunner};
+use crate::budget::{Operation, TokenEstimate};
+use crate::commands::budget::authorize_spend;
+use crate::commands::session_history::outcome_history;
 use crate::optimizer::{
-    ComplexityClass, ComplexityEstimate, InstanceCalculation, InstanceCalculator,
-    MemoryAdjustment, OptimizationResult, OptimizerError, SubtaskOptimizer,
+    ComplexityClass, InstanceCalculation, InstanceCalculator, MemoryModel, OptimizationResult,
+    OptimizerError, SubtaskOptimizer,
 };
+use crate::state::AppState;
+use ait42_config::LlmProviderConfig;
+use llm_estimator::{provider_from_env, ClientConfig, DiskCache, DiskCacheConfig, PromptBuilder};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::State;
 use tokio::sync::Mutex;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+
+/// Location of the on-disk complexity estimate cache
+fn estimate_cache_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".ait42")
+        .join("cache")
+        .join("estimates")
+}
 
 /// Shared optimizer instance (lazy-initialized)
 pub struct OptimizerState {
     optimizer: Arc<Mutex<Option<SubtaskOptimizer>>>,
+    /// LLM backend the optimizer is (or will be) built with
+    provider: Mutex<LlmProviderConfig>,
     calculator: InstanceCalculator,
 }
 
@@ -26,23 +45,53 @@ impl OptimizerState {
     pub fn new() -> Self {
         Self {
             optimizer: Arc::new(Mutex::new(None)),
+            provider: Mutex::new(LlmProviderConfig::default()),
             calculator: InstanceCalculator::new(),
         }
     }
 
+    /// Estimate with `provider` from now on
+    ///
+    /// The optimizer is rebuilt on next use if the provider changed.
+    pub(crate) async fn set_provider(&self, provider: LlmProviderConfig) {
+        // Same lock order as ensure_initialized
+        let mut optimizer_guard = self.optimizer.lock().await;
+        let mut current = self.provider.lock().await;
+        if *current != provider {
+            info!("Switching LLM provider to {}", provider.name);
+            *current = provider;
+            *optimizer_guard = None;
+        }
+    }
+
     /// Initialize the optimizer if needed (called once at startup or first use)
     async fn ensure_initialized(&self) -> Result<(), String> {
         let mut optimizer_guard = self.optimizer.lock().await;
 
         // Initialize on first use
         if optimizer_guard.is_none() {
-            debug!("Initializing SubtaskOptimizer from environment");
-            let optimizer = SubtaskOptimizer::from_env().map_err(|e| {
+            let provider = self.provider.lock().await.clone();
+            debug!("Initializing SubtaskOptimizer with {} provider", provider.name);
+            let client = provider_from_env(
+                &provider.name,
+                provider.model.as_deref(),
+                provider.base_url.as_deref(),
+            )
+            .map_err(|e| {
                 format!(
-                    "Failed to initialize optimizer. Please ensure ANTHROPIC_API_KEY is set: {}",
-                    e
+                    "Failed to initialize optimizer with the {} provider. Check ait42.llm_provider and its API key: {}",
+                    provider.name, e
                 )
             })?;
+            let optimizer = SubtaskOptimizer::with_provider(client);
+            let optimizer = match DiskCache::open(estimate_cache_dir(), DiskCacheConfig::default())
+            {
+                Ok(disk) => optimizer.with_disk_cache(disk),
+                Err(e) => {
+                    warn!("Estimate cache unavailable, caching in memory only: {}", e);
+                    optimizer
+                }
+            };
             *optimizer_guard = Some(optimizer);
             info!("SubtaskOptimizer initialized successfully");
         }
@@ -50,6 +99,28 @@ impl OptimizerState {
         Ok(())
     }
 
+    /// Analyze `task` with the LLM-backed optimizer, learning from `memory`
+    pub(crate) async fn analyze(
+        &self,
+        task: &str,
+        memory: &MemoryModel,
+    ) -> Result<OptimizationResult, String> {
+        self.ensure_initialized().await?;
+        let optimizer_guard = self.optimizer.lock().await;
+        let optimizer = optimizer_guard
+            .as_ref()
+            .ok_or_else(|| "Optimizer not initialized".to_string())?;
+        optimizer
+            .optimize_with_memory(task, 0, memory)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Instance calculator shared by the commands
+    pub(crate) fn calculator(&self) -> &InstanceCalculator {
+        &self.calculator
+    }
+
     /// Clone the Arc<Mutex<>> for thread-safe access
     fn get_optimizer_handle(&self) -> Arc<Mutex<Option<SubtaskOptimizer>>> {
         Arc::clone(&self.optimizer)
@@ -63,7 +134,7 @@ impl Default for OptimizerState {
 }
 
 /// Response for optimize_task command
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OptimizeTaskResponse {
     /// Detected complexity class (e.g., "Linear", "Quadratic")
@@ -88,7 +159,7 @@ pub struct OptimizeTaskResponse {
 }
 
 /// Memory adjustment details
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MemoryAdjustmentResponse {
     pub historical_success_rate: f64,
@@ -97,7 +168,7 @@ pub struct MemoryAdjustmentResponse {
 }
 
 /// LLM estimate details
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ComplexityEstimateResponse {
     pub complexity_class: String,
@@ -107,7 +178,7 @@ pub struct ComplexityEstimateResponse {
 }
 
 /// Response for calculate_instances command
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CalculateInstancesResponse {
     /// Recommended number of Claude Code instances
@@ -124,7 +195,7 @@ pub struct CalculateInstancesResponse {
 }
 
 /// Response for get_complexity_info command
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ComplexityInfoResponse {
     /// Complexity class name (e.g., "Linear")
@@ -143,6 +214,41 @@ pub struct ComplexityInfoResponse {
     pub examples: Vec<String>,
 }
 
+/// Response for get_estimate_cache_stats command
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateCacheStatsResponse {
+    /// Estimation requests this session
+    pub total_requests: usize,
+
+    /// Requests answered from a cache this session
+    pub cache_hits: usize,
+
+    /// Requests that needed an API call this session
+    pub cache_misses: usize,
+
+    /// Cache hits answered by the on-disk cache
+    pub disk_hits: usize,
+
+    /// Estimates held in memory
+    pub cache_size: usize,
+
+    /// Estimates stored on disk
+    pub disk_size: usize,
+
+    /// Hit rate this session (0.0-1.0)
+    pub hit_rate: f64,
+
+    /// Cache hits over all sessions
+    pub lifetime_hits: u64,
+
+    /// Cache misses over all sessions
+    pub lifetime_misses: u64,
+
+    /// Hit rate over all sessions (0.0-1.0)
+    pub lifetime_hit_rate: f64,
+}
+
 /// Optimize subtask count for a task
 ///
 /// Uses LLM analysis + Ω-theory to recommend optimal subtask decomposition.
@@ -167,11 +273,44 @@ pub struct ComplexityInfoResponse {
 /// });
 /// console.log(`Recommended: ${result.recommended_subtasks} subtasks`);
 /// ```
+///
+/// Refused when the estimation would go over the AI budget.
 #[tauri::command]
 pub async fn optimize_task(
+    app: tauri::AppHandle,
     task_description: String,
     current_subtasks: usize,
     state: State<'_, OptimizerState>,
+    app_state: State<'_, AppState>,
+) -> Result<OptimizeTaskResponse, String> {
+    if !task_description.trim().is_empty() {
+        let prompt = PromptBuilder::new()
+            .with_task(&task_description)
+            .with_current_subtasks(current_subtasks)
+            .build();
+        let max_tokens = ClientConfig::default().max_tokens.max(0) as u64;
+        authorize_spend(
+            &app,
+            &app_state,
+            Operation::Estimation,
+            None,
+            |_| TokenEstimate::completion(&prompt, max_tokens),
+        )?;
+    }
+
+    let workspace = app_state.working_dir.lock().await.clone();
+    let memory = outcome_history(&app_state, &workspace.to_string_lossy());
+    estimate_subtasks(task_description, current_subtasks, memory, state).await
+}
+
+/// Optimize subtask count without checking the AI budget
+///
+/// The recommendation is adjusted by the session outcomes in `memory`.
+pub(crate) async fn estimate_subtasks(
+    task_description: String,
+    current_subtasks: usize,
+    memory: MemoryModel,
+    state: State<'_, OptimizerState>,
 ) -> Result<OptimizeTaskResponse, String> {
     info!(
         "Optimizing task: '{}' (current subtasks: {})",
@@ -198,14 +337,14 @@ pub async fn optimize_task(
             .ok_or_else(|| "Optimizer not initialized".to_string())?;
 
         optimizer
-            .optimize_subtask_count(&task_description, current_subtasks)
+            .optimize_with_memory(&task_description, current_subtasks, &memory)
             .await
     }
     .map_err(|e| match e {
             OptimizerError::EstimationFailed(est_err) => {
                 error!("LLM estimation failed: {}", est_err);
                 format!(
-                    "Failed to analyze task complexity. Please check your API key and network connection: {}",
+                    "Failed to analyze task complexity. Please check your LLM provider settings and network connection: {}",
                     est_err
                 )
             }
@@ -383,7 +522,9 @@ pub fn get_complexity_info(
 // ============================================================================
 
 /// Parse complexity class string to enum
-fn parse_complexity_class(class_str: &str) -> Result<ComplexityClass, String> {
+///
+/// Accepts class names and Ω-notation, e.g. "Linear" or "Ω(n)".
+pub(crate) fn parse_complexity_class(class_str: &str) -> Result<ComplexityClass, String> {
     match class_str.to_lowercase().as_str() {
         "constant" => Ok(ComplexityClass::Constant),
         "logarithmic" => Ok(ComplexityClass::Logarithmic),
@@ -391,6 +532,12 @@ fn parse_complexity_class(class_str: &str) -> Result<ComplexityClass, String> {
         "linearithmic" => Ok(ComplexityClass::Linearithmic),
         "quadratic" => Ok(ComplexityClass::Quadratic),
         "exponential" => Ok(ComplexityClass::Exponential),
+        "ω(1)" => Ok(ComplexityClass::Constant),
+        "ω(log n)" => Ok(ComplexityClass::Logarithmic),
+        "ω(n)" => Ok(ComplexityClass::Linear),
+        "ω(n log n)" => Ok(ComplexityClass::Linearithmic),
+        "ω(n²)" => Ok(ComplexityClass::Quadratic),
+        "ω(2^n)" => Ok(ComplexityClass::Exponential),
         _ => Err(format!(
             "Invalid complexity class '{}'. Valid values: Constant, Logarithmic, Linear, Linearithmic, Quadratic, Exponential",
             class_str
@@ -447,6 +594,7 @@ mod tests {
             parse_complexity_class("QUADRATIC").unwrap(),
             ComplexityClass::Quadratic
         );
+        assert_eq!(parse_complexity_class("Ω(n log n)").unwrap(), ComplexityClass::Linearithmic);
         assert!(parse_complexity_class("Invalid").is_err());
     }
 
@@ -487,6 +635,26 @@ mod tests {
         assert!(state.optimizer.lock().unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_set_provider_resets_optimizer() {
+        let state = OptimizerState::new();
+        let ollama = LlmProviderConfig {
+            name: "ollama".to_string(),
+            model: None,
+            base_url: Some("http://localhost:11434".to_string()),
+        };
+        state.set_provider(ollama.clone()).await;
+        state.ensure_initialized().await.unwrap();
+        assert!(state.optimizer.lock().await.is_some());
+
+        // Unchanged provider keeps the optimizer and its cache
+        state.set_provider(ollama).await;
+        assert!(state.optimizer.lock().await.is_some());
+
+        state.set_provider(LlmProviderConfig::default()).await;
+        assert!(state.optimizer.lock().await.is_none());
+    }
+
     #[test]
     fn test_convert_instance_calculation() {
         let calc = InstanceCalculation {
@@ -506,6 +674,80 @@ mod tests {
 
 // ===== A/B Testing Commands =====
 
+/// Get hit rates of the complexity estimate cache
+///
+/// Estimates are cached on disk under `~/.ait42/cache/estimates`, so the
+/// lifetime figures cover earlier sessions.
+///
+/// # Returns
+/// * `Ok(response)` - Cache statistics
+/// * `Err(message)` - The optimizer could not be initialized
+#[tauri::command]
+pub async fn get_estimate_cache_stats(
+    state: State<'_, OptimizerState>,
+) -> Result<EstimateCacheStatsResponse, String> {
+    state.ensure_initialized().await?;
+    let optimizer_guard = state.optimizer.lock().await;
+    let optimizer = optimizer_guard
+        .as_ref()
+        .ok_or_else(|| "Optimizer not initialized".to_string())?;
+
+    let stats = optimizer.cache_stats();
+    Ok(EstimateCacheStatsResponse {
+        total_requests: stats.total_requests,
+        cache_hits: stats.cache_hits,
+        cache_misses: stats.cache_misses,
+        disk_hits: stats.disk_hits,
+        cache_size: stats.cache_size,
+        disk_size: stats.disk_size,
+        hit_rate: stats.hit_rate(),
+        lifetime_hits: stats.lifetime_hits,
+        lifetime_misses: stats.lifetime_misses,
+        lifetime_hit_rate: stats.lifetime_hit_rate(),
+    })
+}
+
+/// Get the LLM backend used for complexity estimation and the editor's
+/// other AI features
+#[tauri::command]
+pub async fn get_llm_provider_config(
+    app_state: State<'_, AppState>,
+) -> Result<LlmProviderConfig, String> {
+    let config = app_state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    Ok(config.ait42.llm_provider.clone())
+}
+
+/// Change the LLM backend used for complexity estimation and the editor's
+/// other AI features
+///
+/// The optimizer switches to it on its next estimation. API keys are read
+/// from the environment (`ANTHROPIC_API_KEY`, `OPENAI_API_KEY`).
+#[tauri::command]
+pub async fn set_llm_provider_config(
+    config: LlmProviderConfig,
+    state: State<'_, OptimizerState>,
+    app_state: State<'_, AppState>,
+) -> Result<(), String> {
+    if !LlmProviderConfig::PROVIDERS.contains(&config.name.as_str()) {
+        return Err(format!(
+            "Invalid LLM provider: {} (must be one of: {})",
+            config.name,
+            LlmProviderConfig::PROVIDERS.join(", ")
+        ));
+    }
+    app_state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .ait42
+        .llm_provider = config.clone();
+    state.set_provider(config).await;
+    Ok(())
+}
+
 /// Run A/B test comparing v1.5.0 (keyword) vs v1.6.0 (LLM + Ω-theory)
 ///
 /// This command executes the complete A/B testing framework on 30 ground truth test cases,
@@ -13,8 +13,7 @@ use crate::plugin::PluginInfo;
  */
 #[tauri::command]
 pub async fn list_plugins(state: State<'_, AppState>) -> Result<Vec<PluginInfo>, String> {
-    let manager = state.plugin_manager.lock()
-        .map_err(|e| format!("Failed to lock plugin manager: {}", e))?;
+    let manager = state.plugins()?;
 
     Ok(manager.list_plugins())
 }
@@ -27,8 +26,7 @@ pub async fn get_plugin(
     plugin_id: String,
     state: State<'_, AppState>,
 ) -> Result<Option<PluginInfo>, String> {
-    let manager = state.plugin_manager.lock()
-        .map_err(|e| format!("Failed to lock plugin manager: {}", e))?;
+    let manager = state.plugins()?;
 
     Ok(manager.get_plugin(&plugin_id).cloned())
 }
@@ -41,8 +39,7 @@ pub async fn enable_plugin(
     plugin_id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let mut manager = state.plugin_manager.lock()
-        .map_err(|e| format!("Failed to lock plugin manager: {}", e))?;
+    let mut manager = state.plugins()?;
 
     manager.enable_plugin(&plugin_id)
 }
@@ -55,8 +52,7 @@ pub async fn disable_plugin(
     plugin_id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let mut manager = state.plugin_manager.lock()
-        .map_err(|e| format!("Failed to lock plugin manager: {}", e))?;
+    let mut manager = state.plugins()?;
 
     manager.disable_plugin(&plugin_id)
 }
@@ -69,8 +65,7 @@ pub async fn install_plugin(
     source_path: String,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
-    let mut manager = state.plugin_manager.lock()
-        .map_err(|e| format!("Failed to lock plugin manager: {}", e))?;
+    let mut manager = state.plugins()?;
 
     let path = std::path::PathBuf::from(source_path);
     manager.install_plugin(&path)
@@ -84,8 +79,7 @@ pub async fn uninstall_plugin(
     plugin_id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let mut manager = state.plugin_manager.lock()
-        .map_err(|e| format!("Failed to lock plugin manager: {}", e))?;
+    let mut manager = state.plugins()?;
 
     manager.uninstall_plugin(&plugin_id)
 }
@@ -0,0 +1,399 @@
+//! Diagnostic Quick Action Commands
+//!
+//! The quick-action menu of a diagnostic: the language server's quick fixes,
+//! a suppression comment for the tool that reported it, and an LLM
+//! explanation with a proposed patch. Like the refactoring commands, none of
+//! these modify the buffer; the frontend applies the returned edits with
+//! `replace_text`, and shows an explanation's proposal as a diff for review
+//! first.
+
+use ait42_core::quick_fix::{self, Explanation};
+use ait42_core::Language;
+use lsp_types::{
+    CodeActionOrCommand, DocumentChangeOperation, DocumentChanges, NumberOrString, OneOf, Position,
+    TextDocumentEdit, TextEdit, Url, WorkspaceEdit,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::commands::editor::TextRange;
+use crate::commands::lsp::LspDiagnostic;
+use crate::commands::refactor::{complete_assisted_edit, RefactorEdit, RefactorProposalResponse};
+use crate::commands::review::REVIEW_SOURCE;
+use crate::state::AppState;
+
+/// What a quick action does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum QuickActionKind {
+    /// Quick fix offered by the language server
+    Fix,
+    /// Comment or attribute silencing the diagnostic
+    Suppress,
+    /// Ask the LLM with `explain_diagnostic`
+    Explain,
+}
+
+/// Entry of a diagnostic's quick-action menu
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticQuickAction {
+    pub title: String,
+    pub kind: QuickActionKind,
+    /// Whether the server marked the fix as the one to apply by default
+    pub is_preferred: bool,
+    /// Edits to apply in order with `replace_text`, back to front; empty for
+    /// `explain`
+    pub edits: Vec<RefactorEdit>,
+}
+
+/// LLM explanation of a diagnostic
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticExplanation {
+    pub explanation: String,
+    /// Proposed fix for review, if the model gave one
+    pub proposal: Option<RefactorProposalResponse>,
+}
+
+/// Buffer a quick action is for
+struct BufferSnapshot {
+    content: String,
+    path: Option<PathBuf>,
+    extension: Option<String>,
+    language: Option<Language>,
+}
+
+impl BufferSnapshot {
+    fn read(buffer_id: &str, state: &AppState) -> Result<Self, String> {
+        let id = Uuid::parse_str(buffer_id).map_err(|e| format!("Invalid buffer ID: {}", e))?;
+        let editor = state
+            .editor
+            .lock()
+            .map_err(|e| format!("Failed to lock editor: {}", e))?;
+        let buffer = editor
+            .buffers()
+            .get(id)
+            .ok_or_else(|| format!("Buffer not found: {}", id))?;
+
+        Ok(Self {
+            content: buffer.to_string(),
+            path: buffer.path().map(Path::to_path_buf),
+            extension: buffer.language().map(str::to_string),
+            language: Language::for_buffer(buffer),
+        })
+    }
+
+    /// Name of the file in diff headers
+    fn label(&self) -> String {
+        self.path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string())
+    }
+}
+
+fn to_core(diagnostic: &LspDiagnostic) -> quick_fix::Diagnostic {
+    quick_fix::Diagnostic {
+        message: diagnostic.message.clone(),
+        line: diagnostic.start_line as usize,
+        code: diagnostic.code.clone(),
+        source: diagnostic.source.clone(),
+    }
+}
+
+/// Byte offset of an LSP position (line, UTF-16 column) in `content`
+///
+/// Positions past the end of a line or of the content are clamped to it.
+fn byte_offset(content: &str, position: Position) -> usize {
+    let line_start = if position.line == 0 {
+        0
+    } else {
+        match content.match_indices('\n').nth(position.line as usize - 1) {
+            Some((i, _)) => i + 1,
+            None => return content.len(),
+        }
+    };
+    let line = content[line_start..].split('\n').next().unwrap_or("");
+
+    let mut utf16 = 0;
+    for (i, c) in line.char_indices() {
+        if utf16 >= position.character {
+            return line_start + i;
+        }
+        utf16 += c.len_utf16() as u32;
+    }
+    line_start + line.len()
+}
+
+/// Edits of `edit` as byte ranges of `content`, back to front
+///
+/// `None` if the edit changes other files or creates, renames or deletes
+/// files, since those cannot be applied to the buffer alone.
+fn buffer_edits(edit: &WorkspaceEdit, uri: &Url, content: &str) -> Option<Vec<RefactorEdit>> {
+    // Servers send either `documentChanges` or `changes`
+    let text_edits: Vec<&TextEdit> = if let Some(document_changes) = &edit.document_changes {
+        let documents: Vec<&TextDocumentEdit> = match document_changes {
+            DocumentChanges::Edits(documents) => documents.iter().collect(),
+            DocumentChanges::Operations(operations) => operations
+                .iter()
+                .map(|operation| match operation {
+                    DocumentChangeOperation::Edit(document) => Some(document),
+                    DocumentChangeOperation::Op(_) => None,
+                })
+                .collect::<Option<_>>()?,
+        };
+        if documents
+            .iter()
+            .any(|document| document.text_document.uri != *uri)
+        {
+            return None;
+        }
+        documents
+            .into_iter()
+            .flat_map(|document| &document.edits)
+            .map(|edit| match edit {
+                OneOf::Left(edit) => edit,
+                OneOf::Right(annotated) => &annotated.text_edit,
+            })
+            .collect()
+    } else {
+        let changes = edit.changes.as_ref();
+        if changes.is_some_and(|changes| changes.keys().any(|changed| changed != uri)) {
+            return None;
+        }
+        changes
+            .and_then(|changes| changes.get(uri))
+            .into_iter()
+            .flatten()
+            .collect()
+    };
+
+    let mut edits: Vec<RefactorEdit> = text_edits
+        .into_iter()
+        .map(|edit| {
+            let start = byte_offset(content, edit.range.start);
+            let end = byte_offset(content, edit.range.end).max(start);
+            RefactorEdit {
+                range: TextRange { start, end },
+                replacement: edit.new_text.clone(),
+                cursor: start + edit.new_text.len(),
+            }
+        })
+        .collect();
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+    Some(edits)
+}
+
+/// Quick fixes the language server offers for `diagnostic`
+async fn server_fixes(
+    path: &Path,
+    content: &str,
+    diagnostic: &LspDiagnostic,
+    state: &AppState,
+) -> Result<Vec<DiagnosticQuickAction>, String> {
+    if state.lsp_manager.is_detached(path).await {
+        return Ok(Vec::new());
+    }
+    let Some(language) = state.lsp_manager.detect_language(path) else {
+        return Ok(Vec::new());
+    };
+    let Some(client) = state.lsp_manager.get_client(&language).await else {
+        return Ok(Vec::new());
+    };
+    let uri =
+        Url::from_file_path(path).map_err(|_| format!("Invalid file path: {}", path.display()))?;
+
+    let range = lsp_types::Range::new(
+        Position::new(diagnostic.start_line, diagnostic.start_character),
+        Position::new(diagnostic.end_line, diagnostic.end_character),
+    );
+    // The server's own copy carries the data it needs to compute fixes
+    let published = client
+        .diagnostics(&uri)
+        .await
+        .map_err(|e| format!("Failed to get diagnostics: {}", e))?
+        .into_iter()
+        .find(|published| published.range == range && published.message == diagnostic.message);
+    let server_diagnostic = published.unwrap_or_else(|| lsp_types::Diagnostic {
+        range,
+        code: diagnostic.code.clone().map(NumberOrString::String),
+        source: diagnostic.source.clone(),
+        message: diagnostic.message.clone(),
+        ..Default::default()
+    });
+
+    let actions = client
+        .code_actions(uri.clone(), range, vec![server_diagnostic])
+        .await
+        .map_err(|e| format!("Failed to get code actions: {}", e))?;
+
+    Ok(actions
+        .into_iter()
+        .filter_map(|action| match action {
+            CodeActionOrCommand::CodeAction(action) => Some(action),
+            // Bare commands run on the server, not in the buffer
+            CodeActionOrCommand::Command(_) => None,
+        })
+        .filter(|action| action.disabled.is_none())
+        .filter_map(|action| {
+            let edits = buffer_edits(action.edit.as_ref()?, &uri, content)?;
+            (!edits.is_empty()).then(|| DiagnosticQuickAction {
+                title: action.title,
+                kind: QuickActionKind::Fix,
+                is_preferred: action.is_preferred.unwrap_or(false),
+                edits,
+            })
+        })
+        .collect())
+}
+
+/// Get the quick actions for a diagnostic
+///
+/// Server fixes come first, the preferred one leading, then a suppression
+/// if the reporting tool has one, then the LLM explanation. A server that is
+/// not running or fails only leaves its fixes out.
+///
+/// # Arguments
+/// * `buffer_id` - Buffer ID (UUID string)
+/// * `diagnostic` - Diagnostic as returned by `lsp_diagnostics`
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(actions)` - Menu entries, in order
+/// * `Err(message)` - Error message
+#[tauri::command]
+pub async fn diagnostic_quick_actions(
+    buffer_id: String,
+    diagnostic: LspDiagnostic,
+    state: State<'_, AppState>,
+) -> Result<Vec<DiagnosticQuickAction>, String> {
+    let buffer = BufferSnapshot::read(&buffer_id, &state)?;
+    let mut actions = Vec::new();
+
+    // Review findings are not the server's to fix
+    if let Some(path) = &buffer.path {
+        if diagnostic.source.as_deref() != Some(REVIEW_SOURCE) {
+            match server_fixes(path, &buffer.content, &diagnostic, &state).await {
+                Ok(fixes) => actions.extend(fixes),
+                Err(e) => debug!("No server fixes for {}: {}", path.display(), e),
+            }
+            actions.sort_by_key(|action| !action.is_preferred);
+        }
+    }
+
+    let core_diagnostic = to_core(&diagnostic);
+    let suppression = buffer
+        .extension
+        .as_deref()
+        .and_then(|ext| quick_fix::suppression_edit(&buffer.content, ext, &core_diagnostic));
+    if let Some(edit) = suppression {
+        actions.push(DiagnosticQuickAction {
+            title: format!("Suppress: {}", edit.replacement.trim()),
+            kind: QuickActionKind::Suppress,
+            is_preferred: false,
+            edits: vec![edit.into()],
+        });
+    }
+
+    actions.push(DiagnosticQuickAction {
+        title: "Explain with AI".to_string(),
+        kind: QuickActionKind::Explain,
+        is_preferred: false,
+        edits: Vec::new(),
+    });
+    Ok(actions)
+}
+
+/// Ask the LLM to explain a diagnostic and propose a fix
+///
+/// Uses the LLM provider in `ait42.llm_provider` and is refused when the
+/// call would go over the AI budget.
+///
+/// # Arguments
+/// * `buffer_id` - Buffer ID (UUID string)
+/// * `diagnostic` - Diagnostic as returned by `lsp_diagnostics`
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(explanation)` - Explanation, with a diff and edits for review
+/// * `Err(message)` - Error message
+#[tauri::command]
+pub async fn explain_diagnostic(
+    app: tauri::AppHandle,
+    buffer_id: String,
+    diagnostic: LspDiagnostic,
+    state: State<'_, AppState>,
+) -> Result<DiagnosticExplanation, String> {
+    let buffer = BufferSnapshot::read(&buffer_id, &state)?;
+    let core_diagnostic = to_core(&diagnostic);
+    let prompt = quick_fix::explain_prompt(&buffer.content, &core_diagnostic, buffer.language)
+        .map_err(|e| e.to_string())?;
+
+    let label = buffer.label();
+    info!("Requesting explanation of a diagnostic in {}: {}", label, diagnostic.message);
+    let response = complete_assisted_edit(&app, &state, &prompt).await?;
+
+    let explanation = Explanation::from_response(&buffer.content, &response, &label);
+    Ok(DiagnosticExplanation {
+        explanation: explanation.explanation,
+        proposal: explanation.proposal.map(RefactorProposalResponse::from),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::OptionalVersionedTextDocumentIdentifier;
+    use std::collections::HashMap;
+
+    fn text_edit(line: u32, start: u32, end: u32, text: &str) -> TextEdit {
+        TextEdit::new(
+            lsp_types::Range::new(Position::new(line, start), Position::new(line, end)),
+            text.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_byte_offset() {
+        let content = "ab\n😀x\n";
+        assert_eq!(byte_offset(content, Position::new(0, 1)), 1);
+        // The emoji is two UTF-16 units and four bytes
+        assert_eq!(byte_offset(content, Position::new(1, 2)), 7);
+        assert_eq!(byte_offset(content, Position::new(1, 99)), 8);
+        assert_eq!(byte_offset(content, Position::new(9, 0)), content.len());
+    }
+
+    #[test]
+    fn test_buffer_edits() {
+        let uri = Url::parse("file:///tmp/a.rs").unwrap();
+        let other = Url::parse("file:///tmp/b.rs").unwrap();
+        let content = "let x = 1;\nlet y = 2;\n";
+
+        let changes = HashMap::from([(
+            uri.clone(),
+            vec![text_edit(0, 4, 5, "_x"), text_edit(1, 4, 5, "_y")],
+        )]);
+        let edits = buffer_edits(&WorkspaceEdit::new(changes), &uri, content).unwrap();
+        assert_eq!(edits.len(), 2);
+        assert_eq!((edits[0].range.start, edits[0].range.end), (15, 16));
+        assert_eq!(edits[1].replacement, "_x");
+
+        let document_changes = WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier {
+                    uri: other.clone(),
+                    version: None,
+                },
+                edits: vec![OneOf::Left(text_edit(0, 0, 0, "use b;\n"))],
+            }])),
+            ..Default::default()
+        };
+        assert!(buffer_edits(&document_changes, &uri, content).is_none());
+        assert!(buffer_edits(&document_changes, &other, content).is_some());
+    }
+}
@@ -0,0 +1,83 @@
+//! Quick Task Commands
+//!
+//! Tauri commands behind the quick task window: submitting a task to the
+//! Coordinator and configuring the global shortcut that opens the window.
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::auto_mode;
+use crate::commands::optimizer::OptimizerState;
+use crate::notifications::NotificationLevel;
+use crate::quick_task;
+use crate::sanitize;
+use crate::state::AppState;
+
+/// Run a task captured in the quick task window
+///
+/// The window is hidden right away. The Coordinator then picks the execution
+/// mode and launches it against the current workspace, recording a session
+/// as auto mode does; the outcome is posted to the notification center.
+/// Quick tasks always go through the Coordinator, since the window offers no
+/// mode choice.
+#[tauri::command]
+pub async fn submit_quick_task(app: AppHandle, task: String) -> Result<(), String> {
+    let task = sanitize::task(&task)?;
+    quick_task::hide_window(&app);
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let optimizer = app.state::<OptimizerState>();
+        let result = auto_mode::launch(app.clone(), state.clone(), &optimizer, task, None).await;
+
+        let (level, message) = match result {
+            Ok(run) => (
+                NotificationLevel::Info,
+                format!("Quick task started in {} mode", run.decision.mode),
+            ),
+            Err(e) => (NotificationLevel::Error, format!("Quick task failed: {}", e)),
+        };
+        state
+            .notifications
+            .notify(&app, level, "quick-task", message, vec![]);
+    });
+    Ok(())
+}
+
+/// Hide the quick task window without running anything
+#[tauri::command]
+pub async fn dismiss_quick_task(app: AppHandle) -> Result<(), String> {
+    quick_task::hide_window(&app);
+    Ok(())
+}
+
+/// Get the global shortcut opening the quick task window
+#[tauri::command]
+pub async fn get_quick_task_shortcut(state: State<'_, AppState>) -> Result<String, String> {
+    Ok(state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .keybindings
+        .quick_task
+        .clone())
+}
+
+/// Change the global shortcut opening the quick task window
+///
+/// An empty shortcut disables it. The shortcut is only saved once it has
+/// been registered, so a taken or invalid shortcut leaves the old one active.
+#[tauri::command]
+pub async fn set_quick_task_shortcut(
+    app: AppHandle,
+    shortcut: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    quick_task::set_shortcut(&app, &shortcut)?;
+    state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .keybindings
+        .quick_task = shortcut.trim().to_string();
+    Ok(())
+}
@@ -0,0 +1,231 @@
+//! Refactoring Commands
+//!
+//! Extract variable/function for buffers whose language server has no
+//! refactoring support, and an LLM-assisted refactor that returns a diff for
+//! review. None of these commands modify the buffer; the frontend applies the
+//! returned edit with `replace_text` once the user accepts it.
+
+use ait42_core::refactor::{self, RefactorProposal};
+use ait42_core::{StructuralEdit, SyntaxTree};
+use llm_estimator::ClientConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::budget::Operation;
+use crate::commands::budget::complete_within_budget;
+use crate::commands::editor::TextRange;
+use crate::state::AppState;
+
+/// Response budget for assisted refactors, which return the whole file
+const AI_REFACTOR_MAX_TOKENS: i32 = 8192;
+
+/// Timeout for assisted refactors in seconds
+const AI_REFACTOR_TIMEOUT_SECS: u64 = 120;
+
+/// Text edit proposed by a refactoring
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefactorEdit {
+    /// Byte range to replace
+    pub range: TextRange,
+    /// Replacement text
+    pub replacement: String,
+    /// Cursor byte offset after applying the edit
+    pub cursor: usize,
+}
+
+impl From<StructuralEdit> for RefactorEdit {
+    fn from(edit: StructuralEdit) -> Self {
+        Self {
+            range: TextRange {
+                start: edit.range.start,
+                end: edit.range.end,
+            },
+            replacement: edit.replacement,
+            cursor: edit.cursor,
+        }
+    }
+}
+
+/// LLM-proposed refactor for review
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefactorProposalResponse {
+    /// Unified diff of the proposal
+    pub diff: String,
+    /// Complete proposed buffer content
+    pub proposed: String,
+    /// Minimal edit that applies the proposal
+    pub edit: RefactorEdit,
+    /// Changed hunks only, back to front: applying them in order with
+    /// `replace_text` leaves unrelated code between them untouched
+    pub hunks: Vec<RefactorEdit>,
+}
+
+impl From<RefactorProposal> for RefactorProposalResponse {
+    fn from(proposal: RefactorProposal) -> Self {
+        let edit = proposal.to_edit().into();
+        let hunks = proposal
+            .to_edits()
+            .into_iter()
+            .rev()
+            .map(RefactorEdit::from)
+            .collect();
+        Self {
+            diff: proposal.diff,
+            proposed: proposal.proposed,
+            edit,
+            hunks,
+        }
+    }
+}
+
+/// Have the configured LLM complete an assisted edit, which answers with
+/// the whole file
+///
+/// Refused when the call would go over the AI budget.
+pub(crate) async fn complete_assisted_edit(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    prompt: &str,
+) -> Result<String, String> {
+    let config = ClientConfig {
+        max_tokens: AI_REFACTOR_MAX_TOKENS,
+        timeout_secs: AI_REFACTOR_TIMEOUT_SECS,
+        ..ClientConfig::default()
+    };
+    complete_within_budget(app, state, Operation::Assist, None, prompt, config).await
+}
+
+/// Buffer content and syntax tree for a refactoring
+fn parse_buffer(buffer_id: &str, state: &AppState) -> Result<(String, SyntaxTree), String> {
+    let buffer_id = Uuid::parse_str(buffer_id).map_err(|e| format!("Invalid buffer ID: {}", e))?;
+
+    let editor = state
+        .editor
+        .lock()
+        .map_err(|e| format!("Failed to lock editor: {}", e))?;
+
+    let buffer = editor
+        .buffers()
+        .get(buffer_id)
+        .ok_or_else(|| format!("Buffer not found: {}", buffer_id))?;
+
+    let tree = SyntaxTree::for_buffer(buffer)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| {
+            format!(
+                "Refactoring is not supported for {} files",
+                buffer.language().unwrap_or("plain text")
+            )
+        })?;
+
+    Ok((buffer.to_string(), tree))
+}
+
+/// Extract the selected expression into a local variable
+///
+/// # Arguments
+/// * `buffer_id` - Buffer ID (UUID string)
+/// * `range` - Selected expression (byte offsets)
+/// * `name` - Variable name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(edit)` - Edit to apply
+/// * `Err(message)` - Why the selection cannot be extracted
+#[tauri::command]
+pub async fn extract_variable(
+    buffer_id: String,
+    range: TextRange,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<RefactorEdit, String> {
+    let (source, tree) = parse_buffer(&buffer_id, &state)?;
+    refactor::extract_variable(&tree, &source, range.into(), &name)
+        .map(RefactorEdit::from)
+        .map_err(|e| e.to_string())
+}
+
+/// Extract the selected lines into a new function
+///
+/// # Arguments
+/// * `buffer_id` - Buffer ID (UUID string)
+/// * `range` - Selected lines (byte offsets)
+/// * `name` - Function name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(edit)` - Edit to apply
+/// * `Err(message)` - Why the selection cannot be extracted
+#[tauri::command]
+pub async fn extract_function(
+    buffer_id: String,
+    range: TextRange,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<RefactorEdit, String> {
+    let (source, tree) = parse_buffer(&buffer_id, &state)?;
+    refactor::extract_function(&tree, &source, range.into(), &name)
+        .map(RefactorEdit::from)
+        .map_err(|e| e.to_string())
+}
+
+/// Ask the LLM to refactor the selection and return the result as a diff
+///
+/// Works for any file type. Uses the LLM provider in `ait42.llm_provider`
+/// and is refused when the call would go over the AI budget.
+///
+/// # Arguments
+/// * `buffer_id` - Buffer ID (UUID string)
+/// * `range` - Selected code (byte offsets)
+/// * `instruction` - What to do, e.g. "extract the loop into a function"
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(proposal)` - Diff and edit for review
+/// * `Err(message)` - Error message
+#[tauri::command]
+pub async fn ai_refactor(
+    app: tauri::AppHandle,
+    buffer_id: String,
+    range: TextRange,
+    instruction: String,
+    state: State<'_, AppState>,
+) -> Result<RefactorProposalResponse, String> {
+    let (source, language, label) = {
+        let id = Uuid::parse_str(&buffer_id).map_err(|e| format!("Invalid buffer ID: {}", e))?;
+        let editor = state
+            .editor
+            .lock()
+            .map_err(|e| format!("Failed to lock editor: {}", e))?;
+        let buffer = editor
+            .buffers()
+            .get(id)
+            .ok_or_else(|| format!("Buffer not found: {}", id))?;
+
+        let label = buffer
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        (buffer.to_string(), ait42_core::Language::for_buffer(buffer), label)
+    };
+
+    let range: std::ops::Range<usize> = range.into();
+    if range.end > source.len() || range.start > range.end {
+        return Err(format!("Invalid range: {:?}", range));
+    }
+
+    let prompt = refactor::ai_refactor_prompt(&source, range, &instruction, language)
+        .map_err(|e| e.to_string())?;
+
+    info!("Requesting assisted refactor for {}: {}", label, instruction);
+    let response = complete_assisted_edit(&app, &state, &prompt).await?;
+
+    let proposal =
+        RefactorProposal::from_response(&source, &response, &label).map_err(|e| e.to_string())?;
+    Ok(proposal.into())
+}
@@ -0,0 +1,132 @@
+//! Remote Control Commands
+//!
+//! Tauri commands for starting and stopping the remote-control server (see
+//! `remote`), and the mapping of its clients' requests onto the editor's own
+//! commands, so a CI bot or chatops client gets the same behaviour as the UI.
+
+use ait42_config::RemoteConfig;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{Manager, State};
+
+use crate::commands::ait42::{
+    cancel_agent_execution, execute_agent, get_agent_output, list_agents, AgentExecutionRequest,
+};
+use crate::commands::session_history::{get_all_sessions, get_session};
+use crate::file_drop::FILES_DROPPED_EVENT;
+use crate::remote::{resolve_token, Dispatch, RemoteRequest, RemoteStatus};
+use crate::state::AppState;
+
+fn remote_config(state: &AppState) -> RemoteConfig {
+    match state.config.lock() {
+        Ok(config) => config.remote.clone(),
+        Err(e) => e.into_inner().remote.clone(),
+    }
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<serde_json::Value, String> {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+/// Run a remote client's request with the matching command
+async fn run_request(
+    app: tauri::AppHandle,
+    request: RemoteRequest,
+) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    match request {
+        RemoteRequest::OpenFile { path } => {
+            if !Path::new(&path).is_file() {
+                return Err(format!("Not a file: {}", path));
+            }
+            // Opened like a dropped file, so buffers and tabs stay in step
+            app.emit_all(FILES_DROPPED_EVENT, vec![path.clone()])
+                .map_err(|e| e.to_string())?;
+            to_value(serde_json::json!({ "path": path }))
+        }
+        RemoteRequest::ListAgents => to_value(list_agents(state).await?),
+        RemoteRequest::RunAgent {
+            agent_name,
+            task,
+            context,
+            execution_id,
+        } => {
+            let request = AgentExecutionRequest {
+                agent_name,
+                task,
+                context,
+                execution_id,
+                session_id: None,
+                workspace_path: None,
+            };
+            to_value(execute_agent(app.clone(), state, request).await?)
+        }
+        RemoteRequest::CancelAgent { execution_id } => {
+            to_value(cancel_agent_execution(app.clone(), state, execution_id, None).await?)
+        }
+        RemoteRequest::GetAgentOutput { execution_id } => {
+            to_value(get_agent_output(state, execution_id).await?)
+        }
+        RemoteRequest::ListSessions { workspace_path } => {
+            to_value(get_all_sessions(state, workspace_path).await?)
+        }
+        RemoteRequest::GetSession {
+            workspace_path,
+            session_id,
+        } => to_value(get_session(state, workspace_path, session_id).await?),
+        RemoteRequest::Subscribe { .. } | RemoteRequest::Unsubscribe => {
+            Err("Subscriptions are handled by the connection".to_string())
+        }
+    }
+}
+
+fn start(app: &tauri::AppHandle, bind: Option<String>) -> Result<RemoteStatus, String> {
+    let state = app.state::<AppState>();
+    let config = remote_config(&state);
+    let bind = bind.filter(|b| !b.trim().is_empty()).unwrap_or(config.bind);
+    let token = resolve_token(config.token.as_deref());
+    let handle = app.clone();
+    let dispatch: Dispatch =
+        Arc::new(move |request| Box::pin(run_request(handle.clone(), request)));
+    state.remote.start(&bind, token, dispatch)
+}
+
+/// Start the remote-control server if `[remote] enabled` is set
+pub fn start_remote_on_startup(app: &tauri::AppHandle) {
+    if !remote_config(&app.state::<AppState>()).enabled {
+        return;
+    }
+    if let Err(e) = start(app, None) {
+        tracing::warn!("Failed to start remote control: {}", e);
+    }
+}
+
+/// State of the remote-control server, with its token while running
+#[tauri::command]
+pub async fn get_remote_status(state: State<'_, AppState>) -> Result<RemoteStatus, String> {
+    Ok(state.remote.status())
+}
+
+/// Start the remote-control server
+///
+/// The token is taken from `AIT42_REMOTE_TOKEN`, then `[remote] token`;
+/// without either, a random one is generated and shown in the status.
+///
+/// # Arguments
+/// * `bind` - Address to listen on (default: `[remote] bind`)
+#[tauri::command]
+pub async fn start_remote_server(
+    app: tauri::AppHandle,
+    bind: Option<String>,
+) -> Result<RemoteStatus, String> {
+    start(&app, bind)
+}
+
+/// Stop the remote-control server, disconnecting its clients
+///
+/// # Returns
+/// * `Ok(false)` - The server was not running
+#[tauri::command]
+pub async fn stop_remote_server(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.remote.stop())
+}
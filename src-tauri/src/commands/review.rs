@@ -0,0 +1,282 @@
+//! Code Review Commands
+//!
+//! Sends a commit range or the current branch's changes to the code-review
+//! agent. Findings from the last review are kept in memory and returned by
+//! `lsp_diagnostics` next to language server diagnostics, so they show up in
+//! the problems list and the gutter. A review can also be attached to a
+//! worktree session as a Markdown report.
+
+use ait42_ait42::review::{self, REVIEW_AGENT};
+use ait42_ait42::{AgentExecutor, Coordinator, ReviewReport, SessionStatus};
+use ait42_git::diff::{self, DiffRange};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use tracing::info;
+
+use crate::commands::ait42::{load_agent_config, screen_context};
+use crate::commands::git::open_repository;
+use crate::commands::lsp::LspDiagnostic;
+use crate::commands::session_history::{attach_artifact, SessionArtifact};
+use crate::state::AppState;
+
+/// Diff sent to the reviewer, in bytes
+const REVIEW_DIFF_BUDGET: usize = 48 * 1024;
+
+/// Diagnostic source of review findings
+pub(crate) const REVIEW_SOURCE: &str = "code-review";
+
+/// Last review, with the repository its paths are relative to
+#[derive(Debug, Clone)]
+pub struct StoredReview {
+    pub root: PathBuf,
+    pub report: ReviewReport,
+}
+
+impl StoredReview {
+    /// Findings for `path` as diagnostics
+    fn diagnostics(&self, path: &Path) -> Vec<LspDiagnostic> {
+        let relative = path
+            .strip_prefix(&self.root)
+            .ok()
+            .map(Path::to_path_buf)
+            .or_else(|| {
+                let root = self.root.canonicalize().ok()?;
+                let path = path.canonicalize().ok()?;
+                path.strip_prefix(root).ok().map(Path::to_path_buf)
+            });
+        let Some(relative) = relative else {
+            return Vec::new();
+        };
+
+        self.report
+            .findings
+            .iter()
+            .filter(|finding| Path::new(&finding.file) == relative)
+            .map(|finding| {
+                // Cover the whole line; findings without one go on the first
+                let line = finding.line.map_or(0, |line| line - 1);
+                let message = match &finding.suggestion {
+                    Some(suggestion) => format!("{}\nSuggestion: {}", finding.message, suggestion),
+                    None => finding.message.clone(),
+                };
+                LspDiagnostic {
+                    message,
+                    severity: finding.severity.lsp_severity(),
+                    start_line: line,
+                    start_character: 0,
+                    end_line: line + 1,
+                    end_character: 0,
+                    code: Some(finding.severity.to_string()),
+                    source: Some(REVIEW_SOURCE.to_string()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Result of a review
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewDiffResult {
+    pub report: ReviewReport,
+    /// One-line summary, e.g. `2 findings (1 high, 1 low)`
+    pub summary: String,
+    /// Repository root the finding paths are relative to
+    pub root: String,
+    /// Whether the diff sent to the agent was shortened
+    pub truncated: bool,
+    /// Artifact created on the session, if one was given
+    pub artifact_id: Option<String>,
+}
+
+/// Review findings from the last review for `path`
+pub(crate) fn review_diagnostics(state: &AppState, path: &Path) -> Vec<LspDiagnostic> {
+    state
+        .review
+        .lock()
+        .ok()
+        .and_then(|review| review.as_ref().map(|review| review.diagnostics(path)))
+        .unwrap_or_default()
+}
+
+/// Review a diff with the code-review agent
+///
+/// # Arguments
+/// * `range` - Changes to review; defaults to the current branch against
+///   its base branch
+/// * `session_id` - Session to attach the report and the diff's prompt
+///   injection scan to
+/// * `workspace_path` - Workspace of the session
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(result)` - Findings, most severe first
+/// * `Err(message)` - Empty diff, agent failure, or unreadable output
+#[tauri::command]
+pub async fn review_diff(
+    app: tauri::AppHandle,
+    range: Option<DiffRange>,
+    session_id: Option<String>,
+    workspace_path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ReviewDiffResult, String> {
+    let repo = open_repository(&state).await?;
+    let range = match range {
+        Some(range) => range,
+        None => DiffRange::Branch {
+            base: diff::default_base(&repo).map_err(|e| e.to_string())?,
+        },
+    };
+    let label = range.label();
+
+    let patch = diff::range_diff(&repo, &range).map_err(|e| e.to_string())?;
+    if patch.trim().is_empty() {
+        return Err(format!("Nothing to review in {}", label));
+    }
+    let budgeted = diff::budget_diff(&patch, REVIEW_DIFF_BUDGET);
+    let session = workspace_path.as_deref().zip(session_id.as_deref());
+    let screened = screen_context(&app, &state, "review diff", &budgeted.text, session);
+    let task = review::review_task(&screened, &label);
+
+    info!(
+        "Reviewing {} ({} bytes of diff{})",
+        label,
+        patch.len(),
+        if budgeted.truncated {
+            ", shortened"
+        } else {
+            ""
+        }
+    );
+    let coordinator = Coordinator::new(load_agent_config())
+        .map_err(|e| format!("Failed to initialize coordinator: {}", e))?;
+    let mut executor = AgentExecutor::new(coordinator);
+    let result = executor
+        .execute_single(REVIEW_AGENT, &task)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let SessionStatus::Failed(reason) = &result.status {
+        return Err(format!("Review failed: {}", reason));
+    }
+
+    let report = ReviewReport::from_output(&label, &result.agent_name, &result.output)
+        .map_err(|e| e.to_string())?;
+    let summary = report.summary();
+    info!("Review of {}: {}", label, summary);
+
+    let artifact_id = match (session_id, workspace_path) {
+        (Some(session_id), Some(workspace_path)) => {
+            let artifact = SessionArtifact {
+                id: uuid::Uuid::new_v4().to_string(),
+                kind: "review".to_string(),
+                title: format!("Code review: {}", label),
+                content: report.to_markdown(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+            };
+            let id = artifact.id.clone();
+            attach_artifact(&state, &workspace_path, &session_id, artifact)?;
+            Some(id)
+        }
+        _ => None,
+    };
+
+    let root = repo.root().to_path_buf();
+    *state
+        .review
+        .lock()
+        .map_err(|e| format!("Failed to lock review: {}", e))? = Some(StoredReview {
+        root: root.clone(),
+        report: report.clone(),
+    });
+
+    Ok(ReviewDiffResult {
+        report,
+        summary,
+        root: root.to_string_lossy().into_owned(),
+        truncated: budgeted.truncated,
+        artifact_id,
+    })
+}
+
+/// Findings of the last review, if any
+#[tauri::command]
+pub async fn get_review_report(state: State<'_, AppState>) -> Result<Option<ReviewReport>, String> {
+    let review = state
+        .review
+        .lock()
+        .map_err(|e| format!("Failed to lock review: {}", e))?;
+    Ok(review.as_ref().map(|review| review.report.clone()))
+}
+
+/// Forget the last review and remove its diagnostics
+#[tauri::command]
+pub async fn clear_review(state: State<'_, AppState>) -> Result<(), String> {
+    *state
+        .review
+        .lock()
+        .map_err(|e| format!("Failed to lock review: {}", e))? = None;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ait42_ait42::{ReviewFinding, ReviewSeverity};
+
+    fn review(root: &Path) -> StoredReview {
+        StoredReview {
+            root: root.to_path_buf(),
+            report: ReviewReport {
+                range: "main...HEAD".to_string(),
+                agent: REVIEW_AGENT.to_string(),
+                findings: vec![
+                    ReviewFinding {
+                        file: "src/lib.rs".to_string(),
+                        line: Some(10),
+                        severity: ReviewSeverity::High,
+                        message: "Unchecked unwrap".to_string(),
+                        suggestion: Some("Return the error".to_string()),
+                    },
+                    ReviewFinding {
+                        file: "src/main.rs".to_string(),
+                        line: None,
+                        severity: ReviewSeverity::Low,
+                        message: "Missing docs".to_string(),
+                        suggestion: None,
+                    },
+                ],
+            },
+        }
+    }
+
+    #[test]
+    fn test_findings_become_diagnostics() {
+        let root = Path::new("/work/repo");
+        let review = review(root);
+
+        let diagnostics = review.diagnostics(&root.join("src/lib.rs"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].start_line, 9);
+        assert_eq!(diagnostics[0].end_line, 10);
+        assert_eq!(diagnostics[0].severity, 1);
+        assert_eq!(diagnostics[0].source.as_deref(), Some(REVIEW_SOURCE));
+        assert_eq!(diagnostics[0].message, "Unchecked unwrap\nSuggestion: Return the error");
+
+        let diagnostics = review.diagnostics(&root.join("src/main.rs"));
+        assert_eq!(diagnostics[0].start_line, 0);
+        assert_eq!(diagnostics[0].severity, 3);
+    }
+
+    #[test]
+    fn test_other_files_have_no_findings() {
+        let review = review(Path::new("/work/repo"));
+        assert!(review
+            .diagnostics(Path::new("/work/repo/src/other.rs"))
+            .is_empty());
+        assert!(review
+            .diagnostics(Path::new("/elsewhere/src/lib.rs"))
+            .is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Workspace Search Commands
+//!
+//! Project-wide grep over the current workspace. Matches are sent to the
+//! frontend as `workspace-search-results` events, one per file, while the
+//! walk is still running; the command resolves with the totals once it
+//! finishes. Starting a new search stops the one still running.
+
+use ait42_fs::{FileMatches, Grep, GrepOptions, GrepSummary};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{Manager, State};
+
+use crate::state::AppState;
+
+/// Event emitted for each file with matches
+pub const SEARCH_RESULTS_EVENT: &str = "workspace-search-results";
+
+/// Incremented by every search; older searches stop once it moves on
+static SEARCH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Payload of a `workspace-search-results` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SearchResults<'a> {
+    /// ID the frontend gave the search
+    search_id: &'a str,
+    #[serde(flatten)]
+    file: FileMatches,
+}
+
+/// Search the files of `root`, passing each file with matches to `emit`
+fn run_search(
+    root: &Path,
+    pattern: &str,
+    options: GrepOptions,
+    emit: impl Fn(FileMatches) -> bool + Sync,
+) -> Result<GrepSummary, String> {
+    let grep = Grep::new(pattern, options).map_err(|e| e.to_string())?;
+    grep.run(root, emit)
+        .map_err(|e| format!("Failed to search {}: {}", root.display(), e))
+}
+
+/// Search every file of the workspace for `pattern`
+///
+/// Respects `.gitignore` and skips binary files. Results arrive as
+/// `workspace-search-results` events tagged with `search_id`.
+///
+/// # Arguments
+/// * `search_id` - ID echoed in the result events
+/// * `pattern` - Text, or a regex with `options.regex`
+/// * `options` - Case, whole word, globs and limits
+///
+/// # Returns
+/// * `Ok(summary)` - Totals; `truncated` if the result limit was hit or a
+///   newer search replaced this one
+#[tauri::command]
+pub async fn workspace_search(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    search_id: String,
+    pattern: String,
+    options: Option<GrepOptions>,
+) -> Result<GrepSummary, String> {
+    let generation = SEARCH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let root = state.working_dir.lock().await.clone();
+    tracing::info!("Searching {} for {:?}", root.display(), pattern);
+
+    tokio::task::spawn_blocking(move || {
+        run_search(&root, &pattern, options.unwrap_or_default(), |file| {
+            if SEARCH_GENERATION.load(Ordering::SeqCst) != generation {
+                return false;
+            }
+            let results = SearchResults {
+                search_id: &search_id,
+                file,
+            };
+            if let Err(e) = app.emit_all(SEARCH_RESULTS_EVENT, results) {
+                tracing::warn!("Failed to emit search results: {}", e);
+            }
+            true
+        })
+    })
+    .await
+    .map_err(|e| format!("Search failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_run_search_emits_files_with_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "let needle = 1;\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "nothing here\n").unwrap();
+
+        let emitted = Mutex::new(Vec::new());
+        let summary = run_search(dir.path(), "needle", GrepOptions::default(), |file| {
+            emitted.lock().unwrap().push(file);
+            true
+        })
+        .unwrap();
+
+        let emitted = emitted.into_inner().unwrap();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].path, "a.rs");
+        assert_eq!(emitted[0].matches[0].column, 5);
+        assert_eq!(summary.files_searched, 2);
+
+        let options = GrepOptions {
+            regex: true,
+            ..Default::default()
+        };
+        let err = run_search(dir.path(), "(", options, |_| true).unwrap_err();
+        assert!(err.starts_with("Invalid search pattern"));
+    }
+}
@@ -2,17 +2,55 @@
  * Session History Management - Persistent storage for worktree sessions
  * Inspired by Vibe Kanban's SQLite-based persistence
  *
- * Sessions are now workspace-specific, stored in ~/.ait42/sessions/{workspace_hash}.json
+ * Sessions are now workspace-specific, stored in ~/.ait42/sessions/{workspace_id}.json
+ *
+ * A workspace is identified by the ID in its `.ait42/workspace-id` file, which
+ * moves with the directory. Workspaces without one fall back to a hash of their
+ * path until sessions are first saved, which creates the file and moves the
+ * hash-keyed sessions over. `migrate_workspace` re-links sessions stored under
+ * the hash of a path the workspace was moved away from.
+ *
+ * Deleting a session only marks it deleted; it can be restored until the
+ * retention policy in the `sessions` config purges it.
+ *
+ * With encryption at rest enabled, files are stored encrypted with a key from
+ * the OS keychain (see `session_crypto`) and decrypted transparently on read.
+ *
+ * Storage goes through the `session_store` backend chosen by the `sessions`
+ * config; `sync_sessions` replicates local sessions to a shared store, where
+ * the team feed shows them to teammates. Each session records its origin
+ * (author, branch and repository) when first stored for that purpose.
+ *
+ * Sessions also record the complexity class and subtask count they ran
+ * with. Once finished, their outcomes feed the subtask optimizer's
+ * memory-based adjustment (see `optimizer::memory`).
  */
+use ait42_ait42::{AgentChange, AgentPin};
+use ait42_config::SessionsConfig;
+use ait42_git::{diff, Repository};
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
-use tauri::State;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{Manager, State};
 
+use crate::budget::{Operation, TokenEstimate};
+use crate::commands::budget::authorize_spend;
+use crate::commands::optimizer::parse_complexity_class;
+use crate::feature_flags;
+use crate::injection::ContextScan;
+use crate::issues::IssueLink;
+use crate::optimizer::{MemoryModel, SessionOutcome};
+use crate::session_crypto::{self, EncryptedSessions, SessionCipher};
+use crate::session_journal::{self, JournalEntry};
+use crate::session_store::{self, SessionStore, SyncReport};
 use crate::state::AppState;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WorktreeInstance {
     pub instance_id: u32,
@@ -30,9 +68,18 @@ pub struct WorktreeInstance {
     pub runtime: Option<String>,
     pub model: Option<String>,
     pub runtime_label: Option<String>,
+    /// Version of the agent definition the instance ran
+    #[serde(default)]
+    pub agent_version: Option<String>,
+    /// Directories, or root files, the instance's diff touched
+    #[serde(default)]
+    pub touched_modules: Option<Vec<String>>,
+    /// Paragraph describing the instance's diff, written by an LLM
+    #[serde(default)]
+    pub diff_summary: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatMessage {
     pub id: String,
@@ -42,13 +89,25 @@ pub struct ChatMessage {
     pub instance_id: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Report attached to a session, such as a code review
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionArtifact {
+    pub id: String,
+    pub kind: String, // review
+    pub title: String,
+    /// Markdown body
+    pub content: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WorktreeSession {
     pub id: String,
     pub r#type: String, // competition | ensemble | debate
     pub task: String,
-    pub status: String, // running | completed | failed | paused
+    pub status: String, // running | completed | failed | paused | cancelled
     pub created_at: String,
     pub updated_at: String,
     pub completed_at: Option<String>,
@@ -63,6 +122,96 @@ pub struct WorktreeSession {
     pub total_files_changed: Option<u32>,
     pub total_lines_added: Option<u32>,
     pub total_lines_deleted: Option<u32>,
+    #[serde(default)]
+    pub artifacts: Vec<SessionArtifact>,
+    /// When the session was deleted; deleted sessions can be restored
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    /// Feature flags in effect when the session was created
+    #[serde(default)]
+    pub feature_flags: Option<BTreeMap<String, bool>>,
+    /// Who ran the session and where
+    #[serde(default)]
+    pub origin: Option<SessionOrigin>,
+    /// Issue the session works on, picked from an issue tracker
+    #[serde(default)]
+    pub issue: Option<IssueLink>,
+    /// Definitions of the registry agents the instances ran, recorded when
+    /// the session is first stored
+    #[serde(default)]
+    pub agents: Option<BTreeMap<String, AgentPin>>,
+    /// Prompt injection scans of the content attached to the session's agent
+    /// tasks, kept for auditing
+    #[serde(default)]
+    pub context_scans: Vec<ContextScan>,
+    /// Complexity class the optimizer assigned to the task
+    #[serde(default)]
+    pub complexity_class: Option<String>,
+    /// Subtasks the task was split into; the instances if not recorded
+    #[serde(default)]
+    pub subtask_count: Option<u32>,
+}
+
+/// Who ran a session, on which branch of which repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOrigin {
+    /// Git identity of the user, `Name <email>`
+    pub author: Option<String>,
+    /// Branch checked out in the workspace
+    pub branch: Option<String>,
+    /// Remote identity of the repository, e.g. `github.com/org/repo`
+    pub repository: Option<String>,
+}
+
+impl WorktreeSession {
+    /// Whether the session was deleted and awaits purging
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Outcome the subtask optimizer learns from
+    ///
+    /// Only finished sessions with a recorded complexity class have one.
+    pub fn outcome(&self) -> Option<SessionOutcome> {
+        let success = match self.status.as_str() {
+            "completed" => true,
+            "failed" => false,
+            _ => return None,
+        };
+        let complexity_class = parse_complexity_class(self.complexity_class.as_deref()?).ok()?;
+        let subtasks = self
+            .subtask_count
+            .map_or(self.instances.len(), |count| count as usize);
+        (subtasks > 0).then_some(SessionOutcome {
+            complexity_class,
+            subtasks,
+            success,
+            duration_secs: self.total_duration,
+            files_changed: self.total_files_changed,
+        })
+    }
+}
+
+/// Outcomes of the finished sessions of a workspace, oldest first
+///
+/// Sessions that cannot be loaded leave the optimizer without history
+/// rather than failing the optimization.
+pub(crate) fn outcome_history(state: &AppState, workspace_path: &str) -> MemoryModel {
+    let mut sessions = match load_sessions(state, workspace_path) {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            tracing::warn!("Session history unavailable to the optimizer: {}", e);
+            return MemoryModel::default();
+        }
+    };
+    sessions.retain(|s| !s.is_deleted());
+    sessions.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    let outcomes = sessions
+        .iter()
+        .filter_map(WorktreeSession::outcome)
+        .collect();
+    MemoryModel::new(outcomes)
 }
 
 /// Generate a stable hash from workspace path for file naming
@@ -75,8 +224,6 @@ pub struct WorktreeSession {
 ///
 /// Falls back to cleaned path if canonicalization fails (e.g., path doesn't exist yet)
 fn workspace_hash(workspace_path: &str) -> String {
-    use std::path::Path;
-
     // Attempt to canonicalize the path (resolves symlinks, converts to absolute path)
     let normalized_path = match fs::canonicalize(Path::new(workspace_path)) {
         Ok(canonical) => canonical.to_string_lossy().to_string(),
@@ -92,69 +239,209 @@ fn workspace_hash(workspace_path: &str) -> String {
     format!("{:x}", hasher.finalize())[..16].to_string() // Use first 16 chars for readability
 }
 
-/// Get path to sessions storage file for a specific workspace
-/// Uses user's home directory to avoid read-only file system errors in macOS app bundles
-/// Format: ~/.ait42/sessions/{workspace_hash}.json
+/// File in a workspace holding its identity, relative to the workspace root
+const WORKSPACE_ID_FILE: &str = ".ait42/workspace-id";
+
+/// Directory holding the sessions storage files of all workspaces
 ///
-/// Fallback behavior:
-/// - Primary: Uses home directory (~/.ait42/sessions/)
-/// - Fallback: Uses /tmp directory if home directory cannot be determined
-///   (This may cause session data loss on system reboot, but prevents crashes)
-fn get_sessions_file_path(_state: &AppState, workspace_path: &str) -> PathBuf {
-    // Use home directory instead of working directory to avoid read-only issues
+/// Uses the home directory to avoid read-only file system errors in macOS
+/// app bundles, falling back to /tmp if it cannot be determined (this may
+/// lose sessions on reboot, but prevents crashes).
+fn sessions_dir() -> PathBuf {
     let home_dir = dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("/tmp"));
-    let hash = workspace_hash(workspace_path);
-    home_dir
-        .join(".ait42")
-        .join("sessions")
-        .join(format!("{}.json", hash))
+    home_dir.join(".ait42").join("sessions")
 }
 
-/// Ensure .ait42/sessions directory exists in user's home directory
-fn ensure_storage_dir(_state: &AppState) -> Result<(), String> {
-    let home_dir = dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("/tmp"));
-    let sessions_dir = home_dir.join(".ait42").join("sessions");
+/// Directory holding the session journals, inside the local store's
+pub(crate) fn journal_dir(state: &AppState) -> PathBuf {
+    let dir = sessions_config(state).path.unwrap_or_else(sessions_dir);
+    dir.join(session_journal::JOURNAL_DIR)
+}
 
-    if !sessions_dir.exists() {
-        fs::create_dir_all(&sessions_dir).map_err(|e| {
-            format!("Failed to create .ait42/sessions directory at {:?}: {}", sessions_dir, e)
-        })?;
+/// ID stored in the workspace's identity file, if it has a valid one
+fn read_workspace_id(workspace_path: &str) -> Option<String> {
+    let content = fs::read_to_string(Path::new(workspace_path).join(WORKSPACE_ID_FILE)).ok()?;
+    uuid::Uuid::parse_str(content.trim())
+        .ok()
+        .map(|id| id.to_string())
+}
+
+/// ID of the workspace, creating its identity file if needed
+///
+/// Returns `None` if the workspace directory does not exist or the file
+/// cannot be written; its sessions then stay keyed by path hash.
+fn ensure_workspace_id(workspace_path: &str) -> Option<String> {
+    if let Some(id) = read_workspace_id(workspace_path) {
+        return Some(id);
+    }
+    let root = Path::new(workspace_path);
+    if !root.is_dir() {
+        return None;
     }
 
+    let id = uuid::Uuid::new_v4().to_string();
+    let id_file = root.join(WORKSPACE_ID_FILE);
+    let written = id_file
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&id_file, format!("{}\n", id)));
+    match written {
+        Ok(()) => Some(id),
+        Err(e) => {
+            tracing::warn!("Failed to write {}: {}", id_file.display(), e);
+            None
+        }
+    }
+}
+
+/// Storage key of a workspace's sessions in `store`
+///
+/// The workspace ID; the hash of its path is used instead while its
+/// hash-keyed sessions have not been moved over yet.
+fn storage_key(store: &dyn SessionStore, workspace_path: &str) -> Result<String, String> {
+    let by_hash = workspace_hash(workspace_path);
+    match read_workspace_id(workspace_path) {
+        Some(id) if store.contains(&id)? || !store.contains(&by_hash)? => Ok(id),
+        _ => Ok(by_hash),
+    }
+}
+
+/// Key the workspace's sessions by its ID, moving hash-keyed sessions over
+fn link_workspace(store: &dyn SessionStore, workspace_path: &str) -> Result<(), String> {
+    let Some(id) = ensure_workspace_id(workspace_path) else {
+        return Ok(());
+    };
+    let moved = store
+        .rename(&workspace_hash(workspace_path), &id)
+        .map_err(|e| format!("Failed to move sessions of {}: {}", workspace_path, e))?;
+    if moved {
+        tracing::info!("Sessions of {} are now keyed by workspace ID {}", workspace_path, id);
+    }
     Ok(())
 }
 
+/// Origin of sessions run in the workspace now
+///
+/// Fields git cannot tell, such as the branch of a detached HEAD, are left
+/// empty.
+pub(crate) fn session_origin(workspace_path: &str) -> SessionOrigin {
+    let Ok(repo) = Repository::open(workspace_path) else {
+        return SessionOrigin::default();
+    };
+    let config = |key| repo.config(key).ok().flatten();
+    let author = match (config("user.name"), config("user.email")) {
+        (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+        (name, email) => name.or(email),
+    };
+    SessionOrigin {
+        author,
+        branch: repo.current_branch().ok().flatten(),
+        repository: repo.remote_id().ok().flatten(),
+    }
+}
+
+/// Store holding the sessions of this machine, as set in the config
+fn local_store(state: &AppState) -> Result<Box<dyn SessionStore>, String> {
+    let config = sessions_config(state);
+    let dir = config.path.unwrap_or_else(sessions_dir);
+    session_store::open(&config.backend, &dir)
+}
+
+/// Store shared with teammates, as set in the config
+///
+/// Fails if no shared store is configured.
+pub(crate) fn shared_store(state: &AppState) -> Result<Box<dyn SessionStore>, String> {
+    let shared = sessions_config(state)
+        .shared
+        .ok_or("No shared session store is configured (set [sessions.shared])")?;
+    session_store::open(&shared.backend, &shared.path)
+}
+
 /// Load all sessions from disk for a specific workspace
 fn load_sessions(state: &AppState, workspace_path: &str) -> Result<Vec<WorktreeSession>, String> {
-    let sessions_file = get_sessions_file_path(state, workspace_path);
+    let store = local_store(state)?;
+    store.load(&storage_key(store.as_ref(), workspace_path)?)
+}
 
+/// Read a sessions storage file, such as a copy from another machine
+pub(crate) fn read_sessions_file(sessions_file: &Path) -> Result<Vec<WorktreeSession>, String> {
     if !sessions_file.exists() {
         return Ok(Vec::new());
     }
 
-    let content = fs::read_to_string(&sessions_file).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(sessions_file).map_err(|e| e.to_string())?;
+    decode_sessions(&content, session_crypto::for_reading)
+}
 
+/// Parse the contents of a sessions file, decrypting it if needed
+///
+/// `cipher` is only asked for when the file is encrypted.
+fn decode_sessions(
+    content: &str,
+    cipher: impl FnOnce() -> Result<Arc<SessionCipher>, String>,
+) -> Result<Vec<WorktreeSession>, String> {
     if content.trim().is_empty() {
         return Ok(Vec::new());
     }
 
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse sessions: {}", e))
+    if let Some(envelope) = EncryptedSessions::parse(content) {
+        let plaintext = cipher()?.decrypt(&envelope)?;
+        return serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Failed to parse sessions: {}", e));
+    }
+
+    serde_json::from_str(content).map_err(|e| format!("Failed to parse sessions: {}", e))
 }
 
-/// Save all sessions to disk for a specific workspace
-fn save_sessions(
+/// Serialize sessions for storage, encrypted when `cipher` is given
+fn encode_sessions(
+    sessions: &[WorktreeSession],
+    cipher: Option<&SessionCipher>,
+) -> Result<String, String> {
+    let json = serde_json::to_string_pretty(sessions).map_err(|e| e.to_string())?;
+    match cipher {
+        Some(cipher) => serde_json::to_string_pretty(&cipher.encrypt(json.as_bytes())?)
+            .map_err(|e| e.to_string()),
+        None => Ok(json),
+    }
+}
+
+/// Serializes read-modify-write updates of session files
+///
+/// Monitoring tasks update sessions concurrently; without a single writer two
+/// updates can read the same file and the later save drops the earlier one.
+pub(crate) static SESSION_WRITES: Mutex<()> = Mutex::new(());
+
+/// Load, modify and save the sessions of a workspace as one update
+pub(crate) fn modify_sessions<T>(
     state: &AppState,
     workspace_path: &str,
-    sessions: &[WorktreeSession],
-) -> Result<(), String> {
-    ensure_storage_dir(state)?;
+    update: impl FnOnce(&mut Vec<WorktreeSession>) -> Result<T, String>,
+) -> Result<T, String> {
+    let store = local_store(state)?;
+    link_workspace(store.as_ref(), workspace_path)?;
+    store.update(&storage_key(store.as_ref(), workspace_path)?, update)
+}
 
-    let sessions_file = get_sessions_file_path(state, workspace_path);
-    let content = serde_json::to_string_pretty(sessions).map_err(|e| e.to_string())?;
+/// Load, modify and save a sessions storage file as one update
+///
+/// The file is replaced atomically, so readers never see a partial write.
+/// Nothing is written when `update` fails.
+pub(crate) fn modify_sessions_file<T>(
+    sessions_file: &Path,
+    update: impl FnOnce(&mut Vec<WorktreeSession>) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = SESSION_WRITES.lock().unwrap_or_else(|e| e.into_inner());
 
-    fs::write(&sessions_file, content).map_err(|e| e.to_string())?;
+    let mut sessions = read_sessions_file(sessions_file)?;
+    let result = update(&mut sessions)?;
 
-    Ok(())
+    let content = encode_sessions(&sessions, session_crypto::active().as_deref())?;
+    let temp_file = sessions_file.with_extension("json.tmp");
+    fs::write(&temp_file, content).map_err(|e| e.to_string())?;
+    fs::rename(&temp_file, sessions_file).map_err(|e| e.to_string())?;
+
+    Ok(result)
 }
 
 /// Create a new session
@@ -175,11 +462,102 @@ pub async fn create_session(
         );
     }
 
-    let mut sessions = load_sessions(&state, &workspace_path)?;
-    sessions.push(session.clone());
-    save_sessions(&state, &workspace_path, &sessions)?;
+    upsert_session(&state, &workspace_path, session)
+}
 
-    Ok(session)
+/// Store `session`, replacing a stored session with the same ID
+///
+/// Artifacts, context scans and diff summaries of the replaced session are
+/// kept, so a session the backend recorded before the frontend created it
+/// keeps its reports. A deleted
+/// session stays deleted. The feature flags in effect, the origin and the
+/// agent definitions are recorded the first time a session is stored.
+pub(crate) fn upsert_session(
+    state: &AppState,
+    workspace_path: &str,
+    mut session: WorktreeSession,
+) -> Result<WorktreeSession, String> {
+    let flags = {
+        let config = state.config.lock().unwrap_or_else(|e| e.into_inner());
+        feature_flags::snapshot(&config)
+    };
+    let pins = current_pins(state, &session);
+    // Journaled first, so the journal has it even if the store cannot be written
+    session_journal::record_session(state, &session);
+    modify_sessions(state, workspace_path, |sessions| {
+        let existing = sessions.iter_mut().find(|s| s.id == session.id);
+        let recorded = existing.as_ref().and_then(|e| e.feature_flags.clone());
+        session.feature_flags = recorded.or(session.feature_flags).or(Some(flags));
+        let agents = existing.as_ref().and_then(|e| e.agents.clone());
+        pin_agents(&mut session, agents, pins);
+        let origin = existing.as_ref().and_then(|e| e.origin.clone());
+        session.origin = origin
+            .or(session.origin)
+            .or_else(|| Some(session_origin(workspace_path)));
+        if let Some(existing) = existing {
+            session.issue = session.issue.or(existing.issue.take());
+            session.complexity_class = session
+                .complexity_class
+                .or(existing.complexity_class.take());
+            session.subtask_count = session.subtask_count.or(existing.subtask_count);
+            let mut artifacts = std::mem::take(&mut existing.artifacts);
+            artifacts.append(&mut session.artifacts);
+            session.artifacts = artifacts;
+            let mut scans = std::mem::take(&mut existing.context_scans);
+            scans.append(&mut session.context_scans);
+            session.context_scans = scans;
+            keep_diff_summaries(&mut session, existing);
+            session.deleted_at = existing.deleted_at.take();
+            *existing = session.clone();
+        } else {
+            sessions.push(session.clone());
+        }
+        Ok(session)
+    })
+}
+
+/// Current definitions of the registry agents `session`'s instances run
+///
+/// Instances named after a runtime rather than an agent are skipped, as are
+/// all of them while the registry is not loaded.
+fn current_pins(state: &AppState, session: &WorktreeSession) -> BTreeMap<String, AgentPin> {
+    let registry = state
+        .agent_registry
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let Some(registry) = registry.as_ref() else {
+        return BTreeMap::new();
+    };
+    session
+        .instances
+        .iter()
+        .filter_map(|instance| AgentPin::from_registry(registry, &instance.agent_name))
+        .map(|pin| (pin.name.clone(), pin))
+        .collect()
+}
+
+/// Record the agent definitions `session` runs
+///
+/// Definitions `recorded` for the session before, or sent with it, are kept;
+/// `current` ones are added for agents without one. Instances get the version
+/// of their agent's definition.
+fn pin_agents(
+    session: &mut WorktreeSession,
+    recorded: Option<BTreeMap<String, AgentPin>>,
+    current: BTreeMap<String, AgentPin>,
+) {
+    let mut pins = recorded.or(session.agents.take()).unwrap_or_default();
+    for (name, pin) in current {
+        pins.entry(name).or_insert(pin);
+    }
+    for instance in &mut session.instances {
+        if instance.agent_version.is_none() {
+            instance.agent_version = pins
+                .get(&instance.agent_name)
+                .and_then(|pin| pin.version.clone());
+        }
+    }
+    session.agents = (!pins.is_empty()).then_some(pins);
 }
 
 /// Update an existing session
@@ -196,18 +574,53 @@ pub async fn update_session(
         tracing::error!("Attempted to update session with empty workspace path");
         return Err("Cannot update session: workspace path is empty.".to_string());
     }
+    state.session_streams.ensure_writable(&session.id)?;
+    let pins = current_pins(&state, &session);
+    session_journal::record_session(&state, &session);
 
-    let mut sessions = load_sessions(&state, &workspace_path)?;
-
-    if let Some(existing) = sessions.iter_mut().find(|s| s.id == session.id) {
+    modify_sessions(&state, &workspace_path, |sessions| {
+        let existing = sessions
+            .iter_mut()
+            .find(|s| s.id == session.id && !s.is_deleted())
+            .ok_or_else(|| format!("Session {} not found", session.id))?;
+        crate::commands::telemetry::record_winner(Some(existing), &session);
+        let mut session = session;
+        session.feature_flags = existing.feature_flags.take().or(session.feature_flags);
+        session.origin = existing.origin.take().or(session.origin);
+        pin_agents(&mut session, existing.agents.take(), pins);
+        session.issue = session.issue.or(existing.issue.take());
+        session.complexity_class = session
+            .complexity_class
+            .or(existing.complexity_class.take());
+        session.subtask_count = session.subtask_count.or(existing.subtask_count);
+        // Scans are recorded by the backend only
+        session.context_scans = std::mem::take(&mut existing.context_scans);
+        keep_diff_summaries(&mut session, existing);
         *existing = session.clone();
-    } else {
-        return Err(format!("Session {} not found", session.id));
-    }
-
-    save_sessions(&state, &workspace_path, &sessions)?;
+        Ok(session)
+    })
+}
 
-    Ok(session)
+/// Keep the diff summaries recorded for `existing` that `session` lacks,
+/// as they are recorded by the backend
+fn keep_diff_summaries(session: &mut WorktreeSession, existing: &mut WorktreeSession) {
+    for instance in &mut session.instances {
+        let Some(recorded) = existing
+            .instances
+            .iter_mut()
+            .find(|i| i.instance_id == instance.instance_id)
+        else {
+            continue;
+        };
+        instance.touched_modules = instance
+            .touched_modules
+            .take()
+            .or(recorded.touched_modules.take());
+        instance.diff_summary = instance
+            .diff_summary
+            .take()
+            .or(recorded.diff_summary.take());
+    }
 }
 
 /// Get a specific session by ID
@@ -228,11 +641,53 @@ pub async fn get_session(
 
     sessions
         .into_iter()
-        .find(|s| s.id == session_id)
+        .find(|s| s.id == session_id && !s.is_deleted())
         .ok_or_else(|| format!("Session {} not found", session_id))
 }
 
-/// Get all sessions for a specific workspace
+/// Events journaled for a session, oldest first
+///
+/// The journal is kept apart from the session store, so it is there even
+/// for sessions the store failed to save.
+#[tauri::command]
+pub async fn get_session_journal(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<JournalEntry>, String> {
+    session_journal::read_journal(&journal_dir(&state), &session_id)
+}
+
+/// Compare the agent definitions a session ran with the current ones
+///
+/// Re-running the session uses the current definitions; the frontend warns
+/// when any changed or were removed and shows the diffs.
+#[tauri::command]
+pub async fn get_session_agent_changes(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+) -> Result<Vec<AgentChange>, String> {
+    let session = find_session(&state, &workspace_path, &session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let Some(pins) = session.agents else {
+        return Ok(Vec::new());
+    };
+
+    crate::commands::ait42::ensure_registry_initialized(&state).await?;
+    let registry = state
+        .agent_registry
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let registry = registry
+        .as_ref()
+        .ok_or_else(|| "Agent registry not initialized".to_string())?;
+    Ok(pins
+        .values()
+        .map(|pin| pin.compare(AgentPin::from_registry(registry, &pin.name).as_ref()))
+        .collect())
+}
+
+/// Get all sessions for a specific workspace, except deleted ones
 #[tauri::command]
 pub async fn get_all_sessions(
     state: State<'_, AppState>,
@@ -248,10 +703,15 @@ pub async fn get_all_sessions(
         return Ok(Vec::new()); // Return empty array instead of error for graceful degradation
     }
 
-    load_sessions(&state, &workspace_path)
+    let mut sessions = load_sessions(&state, &workspace_path)?;
+    sessions.retain(|s| !s.is_deleted());
+    Ok(sessions)
 }
 
 /// Delete a session
+///
+/// The session is only marked deleted and can be restored with
+/// `restore_session` until the retention policy purges it.
 #[tauri::command]
 pub async fn delete_session(
     state: State<'_, AppState>,
@@ -264,17 +724,383 @@ pub async fn delete_session(
     if workspace_path.is_empty() || workspace_path.trim().is_empty() {
         return Err("Cannot delete session: workspace path is empty.".to_string());
     }
+    state.session_streams.ensure_writable(&session_id)?;
+
+    modify_sessions(&state, &workspace_path, |sessions| {
+        if let Some(session) = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id && !s.is_deleted())
+        {
+            session.deleted_at = Some(Utc::now().to_rfc3339());
+        }
+        Ok(())
+    })
+}
+
+/// Get the deleted sessions of a workspace that can still be restored
+#[tauri::command]
+pub async fn list_deleted_sessions(
+    state: State<'_, AppState>,
+    workspace_path: String,
+) -> Result<Vec<WorktreeSession>, String> {
+    if workspace_path.trim().is_empty() {
+        return Ok(Vec::new());
+    }
 
     let mut sessions = load_sessions(&state, &workspace_path)?;
-    sessions.retain(|s| s.id != session_id);
-    save_sessions(&state, &workspace_path, &sessions)?;
+    sessions.retain(WorktreeSession::is_deleted);
+    Ok(sessions)
+}
+
+/// Restore a deleted session
+#[tauri::command]
+pub async fn restore_session(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+) -> Result<WorktreeSession, String> {
+    tracing::info!("Restoring session: {} for workspace: {}", session_id, workspace_path);
+
+    if workspace_path.trim().is_empty() {
+        return Err("Cannot restore session: workspace path is empty.".to_string());
+    }
+
+    modify_sessions(&state, &workspace_path, |sessions| {
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id && s.is_deleted())
+            .ok_or_else(|| format!("Deleted session {} not found", session_id))?;
+        session.deleted_at = None;
+        Ok(session.clone())
+    })
+}
+
+/// Purge sessions the retention policy no longer keeps
+///
+/// # Arguments
+/// * `workspace_path` - Workspace to purge, all workspaces when omitted
+/// * `dry_run` - Only report what would be purged
+///
+/// # Returns
+/// * `Ok(sessions)` - The purged sessions, or those that would be purged
+#[tauri::command]
+pub async fn purge_sessions(
+    state: State<'_, AppState>,
+    workspace_path: Option<String>,
+    dry_run: bool,
+) -> Result<Vec<WorktreeSession>, String> {
+    let policy = sessions_config(&state);
+    let store = local_store(&state)?;
+    let keys = match workspace_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => vec![storage_key(store.as_ref(), &path)?],
+        None => store.keys()?,
+    };
+
+    let mut purged = Vec::new();
+    for key in keys {
+        purged.extend(purge_sessions_in(store.as_ref(), &key, &policy, Utc::now(), dry_run)?);
+    }
+    tracing::info!(
+        "{} {} expired sessions",
+        if dry_run { "Would purge" } else { "Purged" },
+        purged.len()
+    );
+    Ok(purged)
+}
+
+/// Re-link the sessions of a workspace that was moved from `old_path`
+///
+/// Sessions saved before the workspace had an identity file are keyed by the
+/// hash of its path, so they no longer show up once the directory moves.
+///
+/// # Arguments
+/// * `old_path` - Where the workspace used to be
+/// * `workspace_path` - Where it is now, the current workspace when omitted
+///
+/// # Returns
+/// * `Ok(count)` - Number of sessions re-linked
+#[tauri::command]
+pub async fn migrate_workspace(
+    state: State<'_, AppState>,
+    old_path: String,
+    workspace_path: Option<String>,
+) -> Result<usize, String> {
+    let workspace_path = match workspace_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => path,
+        None => state.working_dir.lock().await.to_string_lossy().to_string(),
+    };
+    let store = local_store(&state)?;
+    let migrated = migrate_sessions_in(store.as_ref(), &old_path, &workspace_path)?;
+    tracing::info!("Re-linked {} sessions from {} to {}", migrated, old_path, workspace_path);
+    Ok(migrated)
+}
+
+/// Move the hash-keyed sessions of `old_path` to the workspace at
+/// `workspace_path`, pointing their worktree paths at the new location
+///
+/// Sessions the workspace already has are kept as they are.
+fn migrate_sessions_in(
+    store: &dyn SessionStore,
+    old_path: &str,
+    workspace_path: &str,
+) -> Result<usize, String> {
+    if workspace_hash(old_path) == workspace_hash(workspace_path) {
+        return Err(format!("{} is the current workspace location", old_path));
+    }
+    let old_key = workspace_hash(old_path);
+    if !store.contains(&old_key)? {
+        return Err(format!("No sessions found for {}", old_path));
+    }
+
+    link_workspace(store, workspace_path)?;
+    let moved = store.load(&old_key)?;
+    let old_root = Path::new(old_path.trim_end_matches('/'));
+    let new_root = Path::new(workspace_path);
+
+    let new_key = storage_key(store, workspace_path)?;
+    let migrated = store.update(&new_key, |sessions| {
+        let mut migrated = 0;
+        for mut session in moved {
+            if sessions.iter().any(|s| s.id == session.id) {
+                continue;
+            }
+            for instance in &mut session.instances {
+                if let Ok(rest) = Path::new(&instance.worktree_path).strip_prefix(old_root) {
+                    instance.worktree_path = new_root.join(rest).to_string_lossy().to_string();
+                }
+            }
+            sessions.push(session);
+            migrated += 1;
+        }
+        Ok(migrated)
+    })?;
+
+    store.remove(&old_key)?;
+    Ok(migrated)
+}
 
+/// Get the session retention settings
+#[tauri::command]
+pub async fn get_sessions_config(state: State<'_, AppState>) -> Result<SessionsConfig, String> {
+    Ok(sessions_config(&state))
+}
+
+/// Update the session retention settings
+#[tauri::command]
+pub async fn set_sessions_config(
+    config: SessionsConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if config.max_age_days == Some(0) {
+        return Err("Maximum session age must be at least one day".to_string());
+    }
+    if !SessionsConfig::BACKENDS.contains(&config.backend.as_str()) {
+        return Err(format!("Unknown session backend: {}", config.backend));
+    }
+    state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .sessions = config;
     Ok(())
 }
 
+/// Largest diff of a winner shared with the team, in bytes
+const MAX_SHARED_DIFF_BYTES: usize = 256 * 1024;
+
+/// Attach the diff of the winner to the completed sessions in `store`
+///
+/// Teammates cannot read the winner's worktree, so its uncommitted changes
+/// are kept as a `diff` artifact. Sessions that have one already, or whose
+/// worktree is gone, are left alone. Returns the number of diffs attached.
+fn attach_winner_diffs(store: &dyn SessionStore) -> Result<usize, String> {
+    let mut attached = 0;
+    for key in store.keys()? {
+        attached += store.update(&key, |sessions| {
+            let mut attached = 0;
+            for session in sessions.iter_mut().filter(|s| s.status == "completed") {
+                if session.artifacts.iter().any(|a| a.kind == "diff") {
+                    continue;
+                }
+                let Some(winner) = session
+                    .winner_id
+                    .and_then(|id| session.instances.iter().find(|i| i.instance_id == id))
+                else {
+                    continue;
+                };
+                let Some(patch) = Repository::open(&winner.worktree_path)
+                    .and_then(|repo| repo.run(&["diff", "HEAD"]))
+                    .ok()
+                    .filter(|patch| !patch.trim().is_empty())
+                else {
+                    continue;
+                };
+
+                let budgeted = diff::budget_diff(&patch, MAX_SHARED_DIFF_BYTES);
+                session.artifacts.push(SessionArtifact {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    kind: "diff".to_string(),
+                    title: format!("Diff of {} (#{})", winner.agent_name, winner.instance_id),
+                    content: budgeted.text,
+                    created_at: Utc::now().to_rfc3339(),
+                });
+                attached += 1;
+            }
+            Ok(attached)
+        })?;
+    }
+    Ok(attached)
+}
+
+/// Replicate the sessions of this machine to the shared store
+///
+/// The diff of each winner is attached to its session first, so teammates
+/// can read it. Sessions the shared store already has are replaced only by
+/// newer copies, and nothing is removed from it. Fails if no shared store
+/// is configured.
+#[tauri::command]
+pub async fn sync_sessions(state: State<'_, AppState>) -> Result<SyncReport, String> {
+    let remote = shared_store(&state)?;
+    let local = local_store(&state)?;
+
+    let diffs = attach_winner_diffs(local.as_ref())?;
+    let report = session_store::replicate(local.as_ref(), remote.as_ref())?;
+    tracing::info!(
+        "Synced sessions of {} workspaces to the {} shared store: {} added, {} updated, {} diffs attached",
+        report.workspaces,
+        remote.backend(),
+        report.added,
+        report.updated,
+        diffs
+    );
+    Ok(report)
+}
+
+/// Encrypt the sessions of all workspaces at rest
+///
+/// Creates the key in the OS keychain if needed and rewrites the sessions
+/// of every workspace encrypted. Sessions written afterwards are encrypted
+/// too. Returns the number of workspaces that were rewritten.
+#[tauri::command]
+pub async fn encrypt_sessions(state: State<'_, AppState>) -> Result<usize, String> {
+    session_crypto::enable()?;
+
+    let store = local_store(&state)?;
+    let keys = store.keys()?;
+    for key in &keys {
+        store.update(key, |_| Ok(()))?;
+    }
+    let migrated = keys.len();
+
+    state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .sessions
+        .encrypt_at_rest = true;
+    tracing::info!("Encrypted the sessions of {} workspaces", migrated);
+    Ok(migrated)
+}
+
+/// Enable session encryption at startup when the config asks for it
+pub fn init_session_encryption(app: &tauri::AppHandle) {
+    if !sessions_config(&app.state::<AppState>()).encrypt_at_rest {
+        return;
+    }
+    if let Err(e) = session_crypto::enable() {
+        tracing::error!("Session encryption unavailable: {}", e);
+    }
+}
+
+/// Purge expired sessions of all workspaces at startup and once a day
+///
+/// Runs for the lifetime of the app. Purges are skipped while scheduled runs
+/// are paused from the tray.
+pub async fn run_session_retention(app: tauri::AppHandle) {
+    loop {
+        let state = app.state::<AppState>();
+        let policy = sessions_config(&state);
+        if policy.auto_purge && !state.scheduled_runs.is_paused() {
+            let result = local_store(&state).and_then(|store| {
+                store.keys()?.iter().try_fold(0, |count, key| {
+                    let purged =
+                        purge_sessions_in(store.as_ref(), key, &policy, Utc::now(), false)?;
+                    Ok(count + purged.len())
+                })
+            });
+            match result {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Purged {} expired sessions", count),
+                Err(e) => tracing::warn!("Session purge failed: {}", e),
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(24 * 60 * 60)).await;
+    }
+}
+
+fn sessions_config(state: &AppState) -> SessionsConfig {
+    match state.config.lock() {
+        Ok(config) => config.sessions.clone(),
+        Err(e) => e.into_inner().sessions.clone(),
+    }
+}
+
+/// Remove the sessions under `key` that `policy` no longer keeps
+fn purge_sessions_in(
+    store: &dyn SessionStore,
+    key: &str,
+    policy: &SessionsConfig,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<Vec<WorktreeSession>, String> {
+    if dry_run {
+        let sessions = store.load(key)?;
+        return Ok(sessions
+            .into_iter()
+            .filter(|s| is_expired(s, policy, now))
+            .collect());
+    }
+    if !store.contains(key)? {
+        return Ok(Vec::new());
+    }
+
+    store.update(key, |sessions| {
+        let (expired, kept): (Vec<_>, Vec<_>) = std::mem::take(sessions)
+            .into_iter()
+            .partition(|s| is_expired(s, policy, now));
+        *sessions = kept;
+        Ok(expired)
+    })
+}
+
+/// Whether `policy` no longer keeps `session`
+///
+/// Deleted sessions expire `deleted_retention_days` after their deletion.
+/// With `max_age_days` set, sessions that are not running expire that long
+/// after their last update.
+fn is_expired(session: &WorktreeSession, policy: &SessionsConfig, now: DateTime<Utc>) -> bool {
+    let older_than = |timestamp: &str, days: u32| {
+        DateTime::parse_from_rfc3339(timestamp)
+            .map(|t| now - t.with_timezone(&Utc) > Duration::days(days.into()))
+            .unwrap_or(false)
+    };
+
+    if let Some(deleted_at) = &session.deleted_at {
+        return older_than(deleted_at, policy.deleted_retention_days);
+    }
+    match policy.max_age_days {
+        Some(days) => session.status != "running" && older_than(&session.updated_at, days),
+        None => false,
+    }
+}
+
 /// Add chat message to a session
+///
+/// User messages are sent on to the session's instance, so they are refused
+/// when that would go over the AI budget.
 #[tauri::command]
 pub async fn add_chat_message(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     workspace_path: String,
     session_id: String,
@@ -291,23 +1117,148 @@ pub async fn add_chat_message(
     if workspace_path.is_empty() || workspace_path.trim().is_empty() {
         return Err("Cannot add chat message: workspace path is empty.".to_string());
     }
+    state.session_streams.ensure_writable(&session_id)?;
 
-    let mut sessions = load_sessions(&state, &workspace_path)?;
-
-    if let Some(session) = sessions.iter_mut().find(|s| s.id == session_id) {
+    modify_sessions(&state, &workspace_path, |sessions| {
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        if message.role == "user" {
+            authorize_spend(&app, &state, Operation::Chat, Some(&session_id), |policy| {
+                TokenEstimate::agent_runs(&message.content, 1, policy)
+            })?;
+        }
         session.chat_history.push(message);
         session.updated_at = chrono::Utc::now().to_rfc3339();
-        let result = session.clone();
-        save_sessions(&state, &workspace_path, &sessions)?;
-        Ok(result)
-    } else {
-        Err(format!("Session {} not found", session_id))
+        Ok(session.clone())
+    })
+}
+
+/// Look up a stored session
+pub(crate) fn find_session(
+    state: &AppState,
+    workspace_path: &str,
+    session_id: &str,
+) -> Option<WorktreeSession> {
+    load_sessions(state, workspace_path)
+        .ok()?
+        .into_iter()
+        .find(|s| s.id == session_id)
+}
+
+/// Look up an instance of a stored session
+pub(crate) fn find_instance(
+    state: &AppState,
+    workspace_path: &str,
+    session_id: &str,
+    instance_id: u32,
+) -> Option<WorktreeInstance> {
+    find_session(state, workspace_path, session_id)?
+        .instances
+        .into_iter()
+        .find(|i| i.instance_id == instance_id)
+}
+
+/// Append `artifact` to a session and save it
+pub(crate) fn attach_artifact(
+    state: &AppState,
+    workspace_path: &str,
+    session_id: &str,
+    artifact: SessionArtifact,
+) -> Result<WorktreeSession, String> {
+    if workspace_path.trim().is_empty() {
+        return Err("Cannot attach artifact: workspace path is empty.".to_string());
     }
+
+    modify_sessions(state, workspace_path, |sessions| {
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        session.artifacts.push(artifact);
+        session.updated_at = chrono::Utc::now().to_rfc3339();
+        Ok(session.clone())
+    })
+}
+
+/// Append `scan` to a session's context scans and save it
+pub(crate) fn record_context_scan(
+    state: &AppState,
+    workspace_path: &str,
+    session_id: &str,
+    scan: ContextScan,
+) -> Result<WorktreeSession, String> {
+    if workspace_path.trim().is_empty() {
+        return Err("Cannot record context scan: workspace path is empty.".to_string());
+    }
+
+    modify_sessions(state, workspace_path, |sessions| {
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        session.context_scans.push(scan);
+        session.updated_at = chrono::Utc::now().to_rfc3339();
+        Ok(session.clone())
+    })
+}
+
+/// Mark the instances running in `tmux_sessions` as cancelled
+///
+/// A session is marked cancelled too once none of its instances is still
+/// active. Returns the sessions that changed.
+pub(crate) fn mark_cancelled(
+    state: &AppState,
+    workspace_path: &str,
+    tmux_sessions: &[String],
+) -> Result<Vec<WorktreeSession>, String> {
+    if tmux_sessions.is_empty() {
+        return Ok(Vec::new());
+    }
+    modify_sessions(state, workspace_path, |sessions| Ok(cancel_instances(sessions, tmux_sessions)))
+}
+
+/// Whether an instance or session with `status` has yet to finish
+pub(crate) fn is_active(status: &str) -> bool {
+    matches!(status, "idle" | "running" | "paused")
+}
+
+fn cancel_instances(
+    sessions: &mut [WorktreeSession],
+    tmux_sessions: &[String],
+) -> Vec<WorktreeSession> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut changed = Vec::new();
+    for session in sessions.iter_mut() {
+        let mut cancelled = false;
+        for instance in session.instances.iter_mut() {
+            if is_active(&instance.status) && tmux_sessions.contains(&instance.tmux_session_id) {
+                instance.status = "cancelled".to_string();
+                instance.end_time = Some(now.clone());
+                cancelled = true;
+            }
+        }
+        if !cancelled {
+            continue;
+        }
+        if is_active(&session.status) && !session.instances.iter().any(|i| is_active(&i.status)) {
+            session.status = "cancelled".to_string();
+            session.completed_at = Some(now.clone());
+        }
+        session.updated_at = now.clone();
+        changed.push(session.clone());
+    }
+    changed
 }
 
 /// Update instance status within a session
+///
+/// Once the last instance of a competition finishes, scoring of the
+/// competition starts in the background (see `competition`).
 #[tauri::command]
 pub async fn update_instance_status(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     workspace_path: String,
     session_id: String,
@@ -326,24 +1277,410 @@ pub async fn update_instance_status(
     if workspace_path.is_empty() || workspace_path.trim().is_empty() {
         return Err("Cannot update instance status: workspace path is empty.".to_string());
     }
+    state.session_streams.ensure_writable(&session_id)?;
+    session_journal::record_instance_status(&state, &session_id, instance_id, &new_status);
 
-    let mut sessions = load_sessions(&state, &workspace_path)?;
+    let session = modify_sessions(&state, &workspace_path, |sessions| {
+        set_instance_status(sessions, &session_id, instance_id, new_status)
+    })?;
+    crate::commands::competition::schedule_scoring(&app, &workspace_path, &session);
+    crate::commands::competition::schedule_summaries(&app, &workspace_path, &session);
+    Ok(session)
+}
 
-    if let Some(session) = sessions.iter_mut().find(|s| s.id == session_id) {
-        if let Some(instance) = session
-            .instances
-            .iter_mut()
-            .find(|i| i.instance_id == instance_id)
-        {
-            instance.status = new_status;
-            session.updated_at = chrono::Utc::now().to_rfc3339();
-            let result = session.clone();
-            save_sessions(&state, &workspace_path, &sessions)?;
-            Ok(result)
-        } else {
-            Err(format!("Instance {} not found in session {}", instance_id, session_id))
+/// Set the status of an instance of a session
+fn set_instance_status(
+    sessions: &mut [WorktreeSession],
+    session_id: &str,
+    instance_id: u32,
+    new_status: String,
+) -> Result<WorktreeSession, String> {
+    let session = sessions
+        .iter_mut()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let instance = session
+        .instances
+        .iter_mut()
+        .find(|i| i.instance_id == instance_id)
+        .ok_or_else(|| format!("Instance {} not found in session {}", instance_id, session_id))?;
+
+    instance.status = new_status;
+    session.updated_at = chrono::Utc::now().to_rfc3339();
+    Ok(session.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(instance_id: u32) -> WorktreeInstance {
+        WorktreeInstance {
+            instance_id,
+            worktree_path: format!("/tmp/instance-{}", instance_id),
+            branch: format!("competition-{}", instance_id),
+            agent_name: "claude".to_string(),
+            agent_version: None,
+            touched_modules: None,
+            diff_summary: None,
+            status: "running".to_string(),
+            tmux_session_id: format!("ait42-claude-competition-{}", instance_id),
+            output: None,
+            start_time: None,
+            end_time: None,
+            files_changed: None,
+            lines_added: None,
+            lines_deleted: None,
+            runtime: None,
+            model: None,
+            runtime_label: None,
         }
-    } else {
-        Err(format!("Session {} not found", session_id))
+    }
+
+    fn session(id: &str, instances: u32) -> WorktreeSession {
+        WorktreeSession {
+            id: id.to_string(),
+            r#type: "competition".to_string(),
+            task: "stress".to_string(),
+            status: "running".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            completed_at: None,
+            instances: (1..=instances).map(instance).collect(),
+            chat_history: Vec::new(),
+            model: None,
+            timeout_seconds: None,
+            preserve_worktrees: None,
+            winner_id: None,
+            runtime_mix: None,
+            total_duration: None,
+            total_files_changed: None,
+            total_lines_added: None,
+            total_lines_deleted: None,
+            artifacts: Vec::new(),
+            deleted_at: None,
+            feature_flags: None,
+            origin: None,
+            issue: None,
+            agents: None,
+            context_scans: Vec::new(),
+            complexity_class: None,
+            subtask_count: None,
+        }
+    }
+
+    #[test]
+    fn test_workspace_id_keys_sessions_and_survives_moves() {
+        let home = tempfile::tempdir().unwrap();
+        let sessions_dir = home.path().join("sessions");
+        let store = session_store::open("json", &sessions_dir).unwrap();
+        let store = store.as_ref();
+        let workspace = home.path().join("project");
+        fs::create_dir_all(&workspace).unwrap();
+        let workspace_path = workspace.to_string_lossy().to_string();
+
+        // Sessions saved before the workspace had an identity file
+        let by_hash = storage_key(store, &workspace_path).unwrap();
+        assert_eq!(by_hash, workspace_hash(&workspace_path));
+        store
+            .update(&by_hash, |sessions| {
+                sessions.push(session("s1", 1));
+                Ok(())
+            })
+            .unwrap();
+
+        link_workspace(store, &workspace_path).unwrap();
+        let id = read_workspace_id(&workspace_path).expect("identity file was created");
+        assert_eq!(storage_key(store, &workspace_path).unwrap(), id);
+        assert!(sessions_dir.join(format!("{}.json", id)).exists());
+        assert!(!store.contains(&by_hash).unwrap());
+
+        // The identity file moves with the workspace
+        let moved = home.path().join("renamed");
+        fs::rename(&workspace, &moved).unwrap();
+        let moved_path = moved.to_string_lossy().to_string();
+        assert_eq!(storage_key(store, &moved_path).unwrap(), id);
+        assert_eq!(store.load(&id).unwrap()[0].id, "s1");
+    }
+
+    #[test]
+    fn test_migrate_workspace_relinks_hash_keyed_sessions() {
+        let home = tempfile::tempdir().unwrap();
+        let store = session_store::open("jsonl", &home.path().join("sessions")).unwrap();
+        let store = store.as_ref();
+        let old = home.path().join("old");
+        let new = home.path().join("new");
+        fs::create_dir_all(&new).unwrap();
+        let old_path = old.to_string_lossy().to_string();
+        let new_path = new.to_string_lossy().to_string();
+
+        let mut moved = session("s1", 1);
+        moved.instances[0].worktree_path = old
+            .join(".worktrees/competition-1/instance-1")
+            .to_string_lossy()
+            .to_string();
+        store
+            .update(&storage_key(store, &old_path).unwrap(), |sessions| {
+                sessions.push(moved);
+                sessions.push(session("s2", 1));
+                Ok(())
+            })
+            .unwrap();
+        store
+            .update(&storage_key(store, &new_path).unwrap(), |sessions| {
+                sessions.push(session("s2", 2));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(migrate_sessions_in(store, &old_path, &new_path).unwrap(), 1);
+
+        let sessions = store.load(&storage_key(store, &new_path).unwrap()).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let relinked = sessions.iter().find(|s| s.id == "s1").unwrap();
+        assert_eq!(
+            Path::new(&relinked.instances[0].worktree_path),
+            new.join(".worktrees/competition-1/instance-1")
+        );
+        // The workspace's own copy of s2 wins
+        let kept = sessions.iter().find(|s| s.id == "s2").unwrap();
+        assert_eq!(kept.instances.len(), 2);
+
+        let err = migrate_sessions_in(store, &old_path, &new_path).unwrap_err();
+        assert!(err.contains("No sessions found"));
+        assert!(migrate_sessions_in(store, &new_path, &new_path).is_err());
+    }
+
+    #[test]
+    fn test_concurrent_instance_updates_are_not_lost() {
+        const INSTANCES: u32 = 64;
+        let dir = tempfile::tempdir().unwrap();
+        let file = Arc::new(dir.path().join("sessions.json"));
+        modify_sessions_file(&file, |sessions| {
+            sessions.push(session("s1", INSTANCES));
+            Ok(())
+        })
+        .unwrap();
+
+        let writers = (1..=INSTANCES).map(|instance_id| {
+            let file = Arc::clone(&file);
+            std::thread::spawn(move || {
+                modify_sessions_file(&file, |sessions| {
+                    set_instance_status(sessions, "s1", instance_id, "completed".to_string())
+                })
+                .unwrap();
+            })
+        });
+        let readers = (0..8).map(|_| {
+            let file = Arc::clone(&file);
+            std::thread::spawn(move || {
+                for _ in 0..20 {
+                    let sessions = read_sessions_file(&file).expect("never sees a partial write");
+                    assert_eq!(sessions.len(), 1);
+                }
+            })
+        });
+        let handles: Vec<_> = writers.chain(readers).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let sessions = read_sessions_file(&file).unwrap();
+        assert!(sessions[0]
+            .instances
+            .iter()
+            .all(|i| i.status == "completed"));
+        assert!(!file.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_failed_update_leaves_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("sessions.json");
+        modify_sessions_file(&file, |sessions| {
+            sessions.push(session("s1", 1));
+            Ok(())
+        })
+        .unwrap();
+        let before = fs::read_to_string(&file).unwrap();
+
+        let err = modify_sessions_file(&file, |sessions| {
+            sessions.clear();
+            set_instance_status(sessions, "s1", 1, "failed".to_string())
+        })
+        .unwrap_err();
+
+        assert_eq!(err, "Session s1 not found");
+        assert_eq!(fs::read_to_string(&file).unwrap(), before);
+    }
+
+    #[test]
+    fn test_encoded_sessions_round_trip() {
+        let cipher = SessionCipher::new(&[3; 32]).unwrap();
+        let sessions = vec![session("s1", 2)];
+
+        let plain = encode_sessions(&sessions, None).unwrap();
+        let decoded = decode_sessions(&plain, || Err("no key needed".to_string())).unwrap();
+        assert_eq!(decoded[0].id, "s1");
+
+        let encrypted = encode_sessions(&sessions, Some(&cipher)).unwrap();
+        assert!(!encrypted.contains("stress"));
+        let cipher = Arc::new(cipher);
+        let decoded = decode_sessions(&encrypted, || Ok(Arc::clone(&cipher))).unwrap();
+        assert_eq!(decoded[0].instances.len(), 2);
+
+        let err = decode_sessions(&encrypted, || Err("no key".to_string())).unwrap_err();
+        assert_eq!(err, "no key");
+    }
+
+    fn days_ago(days: i64) -> String {
+        (Utc::now() - Duration::days(days)).to_rfc3339()
+    }
+
+    #[test]
+    fn test_cancel_instances() {
+        let mut sessions = vec![session("s1", 2), session("s2", 1)];
+        sessions[0].instances[1].status = "completed".to_string();
+        let killed = vec!["ait42-claude-competition-1".to_string()];
+
+        let changed = cancel_instances(&mut sessions, &killed);
+        let ids: Vec<&str> = changed.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["s1", "s2"]);
+        assert_eq!(sessions[0].status, "cancelled");
+        assert_eq!(sessions[0].instances[0].status, "cancelled");
+        assert_eq!(sessions[0].instances[1].status, "completed");
+        assert!(sessions[0].completed_at.is_some());
+
+        // Sessions with other instances still running keep running
+        let mut sessions = vec![session("s3", 2)];
+        cancel_instances(&mut sessions, &killed);
+        assert_eq!(sessions[0].instances[0].status, "cancelled");
+        assert_eq!(sessions[0].status, "running");
+        assert!(cancel_instances(&mut sessions, &killed).is_empty());
+    }
+
+    #[test]
+    fn test_session_outcome() {
+        // Running sessions and sessions without a class teach nothing
+        let mut session = session("s1", 2);
+        session.complexity_class = Some("Ω(n)".to_string());
+        assert_eq!(session.outcome(), None);
+        session.status = "completed".to_string();
+        session.total_duration = Some(90);
+        let outcome = session.outcome().unwrap();
+        assert_eq!(outcome.complexity_class, crate::optimizer::ComplexityClass::Linear);
+        assert_eq!(outcome.subtasks, 2);
+        assert!(outcome.success);
+        assert_eq!(outcome.duration_secs, Some(90));
+
+        session.status = "failed".to_string();
+        session.subtask_count = Some(4);
+        let outcome = session.outcome().unwrap();
+        assert_eq!(outcome.subtasks, 4);
+        assert!(!outcome.success);
+
+        session.complexity_class = None;
+        assert_eq!(session.outcome(), None);
+    }
+
+    #[test]
+    fn test_pin_agents_keeps_recorded_definitions() {
+        let pin = |name: &str, version: &str| AgentPin {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            path: PathBuf::from(format!("/agents/{}.md", name)),
+            definition: format!("{} {}", name, version),
+        };
+        let mut pinned = session("s1", 2);
+        pinned.instances[1].agent_name = "reviewer".to_string();
+
+        let current = BTreeMap::from([("claude".to_string(), pin("claude", "1.0.0"))]);
+        pin_agents(&mut pinned, None, current);
+        assert_eq!(pinned.instances[0].agent_version.as_deref(), Some("1.0.0"));
+        assert_eq!(pinned.instances[1].agent_version, None);
+
+        // Definitions changed since are not recorded over the first ones
+        let recorded = pinned.agents.take();
+        let current = BTreeMap::from([
+            ("claude".to_string(), pin("claude", "2.0.0")),
+            ("reviewer".to_string(), pin("reviewer", "0.1.0")),
+        ]);
+        pin_agents(&mut pinned, recorded, current);
+        let agents = pinned.agents.as_ref().unwrap();
+        assert_eq!(agents["claude"], pin("claude", "1.0.0"));
+        assert_eq!(agents["reviewer"], pin("reviewer", "0.1.0"));
+        assert_eq!(pinned.instances[0].agent_version.as_deref(), Some("1.0.0"));
+        assert_eq!(pinned.instances[1].agent_version.as_deref(), Some("0.1.0"));
+
+        let mut unpinned = session("s2", 1);
+        pin_agents(&mut unpinned, None, BTreeMap::new());
+        assert!(unpinned.agents.is_none());
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let policy = SessionsConfig {
+            deleted_retention_days: 30,
+            max_age_days: Some(90),
+            auto_purge: true,
+            ..SessionsConfig::default()
+        };
+        let now = Utc::now();
+        let aged = |status: &str, updated_days: i64, deleted_days: Option<i64>| WorktreeSession {
+            status: status.to_string(),
+            updated_at: days_ago(updated_days),
+            deleted_at: deleted_days.map(days_ago),
+            ..session("s1", 0)
+        };
+
+        assert!(!is_expired(&aged("completed", 1, Some(29)), &policy, now));
+        assert!(is_expired(&aged("completed", 1, Some(31)), &policy, now));
+        assert!(!is_expired(&aged("completed", 89, None), &policy, now));
+        assert!(is_expired(&aged("completed", 91, None), &policy, now));
+        assert!(!is_expired(&aged("running", 91, None), &policy, now));
+
+        let keep_forever = SessionsConfig::default();
+        assert!(!is_expired(&aged("completed", 1000, None), &keep_forever, now));
+    }
+
+    #[test]
+    fn test_purge_dry_run_and_purge() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = session_store::open("json", dir.path()).unwrap();
+        let store = store.as_ref();
+        store
+            .update("ws", |sessions| {
+                sessions.push(WorktreeSession {
+                    deleted_at: Some(days_ago(40)),
+                    ..session("old", 0)
+                });
+                sessions.push(WorktreeSession {
+                    deleted_at: Some(days_ago(1)),
+                    ..session("recent", 0)
+                });
+                sessions.push(session("live", 0));
+                Ok(())
+            })
+            .unwrap();
+        let policy = SessionsConfig::default();
+
+        let would = purge_sessions_in(store, "ws", &policy, Utc::now(), true).unwrap();
+        assert_eq!(would.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["old"]);
+        assert_eq!(store.load("ws").unwrap().len(), 3);
+
+        let purged = purge_sessions_in(store, "ws", &policy, Utc::now(), false).unwrap();
+        assert_eq!(purged.len(), 1);
+        let ids: Vec<_> = store
+            .load("ws")
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, vec!["recent", "live"]);
+
+        assert!(purge_sessions_in(store, "missing", &policy, Utc::now(), false)
+            .unwrap()
+            .is_empty());
+        assert!(!store.contains("missing").unwrap());
     }
 }
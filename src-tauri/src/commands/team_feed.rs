@@ -0,0 +1,295 @@
+//! Team Feed Commands
+//!
+//! Recent sessions that teammates synced to the shared session store (see
+//! `sync_sessions`) for the repository of the workspace, with their winner,
+//! stats and the reports and diffs attached to them. Sessions are matched by
+//! the remote identity recorded in their origin, so clones of the same
+//! repository see each other's sessions wherever they are checked out.
+//!
+//! The shared store is only read here: teammates' sessions and artifacts can
+//! be viewed but not changed.
+
+use ait42_git::Repository;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::State;
+
+use crate::commands::session_history::{shared_store, SessionArtifact, WorktreeSession};
+use crate::session_store::SessionStore;
+use crate::state::AppState;
+
+/// Sessions listed when the frontend does not ask for a number
+const DEFAULT_FEED_LIMIT: usize = 100;
+
+/// Winning instance of a feed session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedWinner {
+    pub instance_id: u32,
+    pub agent_name: String,
+    pub model: Option<String>,
+    pub runtime: Option<String>,
+}
+
+/// Report or diff attached to a feed session, without its content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedArtifact {
+    pub id: String,
+    pub kind: String,
+    pub title: String,
+    pub created_at: String,
+}
+
+/// A teammate's session in the feed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedEntry {
+    pub session_id: String,
+    pub r#type: String,
+    pub task: String,
+    pub status: String,
+    pub author: Option<String>,
+    pub branch: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub winner: Option<FeedWinner>,
+    pub instances: usize,
+    pub total_duration: Option<u64>,
+    pub total_files_changed: Option<u32>,
+    pub total_lines_added: Option<u32>,
+    pub total_lines_deleted: Option<u32>,
+    pub artifacts: Vec<FeedArtifact>,
+}
+
+impl FeedEntry {
+    fn new(session: &WorktreeSession) -> Self {
+        let origin = session.origin.clone().unwrap_or_default();
+        let winner = session
+            .winner_id
+            .and_then(|id| session.instances.iter().find(|i| i.instance_id == id))
+            .map(|winner| FeedWinner {
+                instance_id: winner.instance_id,
+                agent_name: winner.agent_name.clone(),
+                model: winner.model.clone().or_else(|| session.model.clone()),
+                runtime: winner.runtime.clone(),
+            });
+        let artifacts = session
+            .artifacts
+            .iter()
+            .map(|artifact| FeedArtifact {
+                id: artifact.id.clone(),
+                kind: artifact.kind.clone(),
+                title: artifact.title.clone(),
+                created_at: artifact.created_at.clone(),
+            })
+            .collect();
+
+        Self {
+            session_id: session.id.clone(),
+            r#type: session.r#type.clone(),
+            task: session.task.clone(),
+            status: session.status.clone(),
+            author: origin.author,
+            branch: origin.branch,
+            created_at: session.created_at.clone(),
+            updated_at: session.updated_at.clone(),
+            winner,
+            instances: session.instances.len(),
+            total_duration: session.total_duration,
+            total_files_changed: session.total_files_changed,
+            total_lines_added: session.total_lines_added,
+            total_lines_deleted: session.total_lines_deleted,
+            artifacts,
+        }
+    }
+}
+
+/// Remote identity of the repository of `workspace_path`
+fn repository_of(workspace_path: &str) -> Result<String, String> {
+    let repo = Repository::open(workspace_path).map_err(|e| e.to_string())?;
+    repo.remote_id().map_err(|e| e.to_string())?.ok_or_else(|| {
+        format!(
+            "{} has no origin remote; the team feed needs one to find teammates' sessions",
+            workspace_path
+        )
+    })
+}
+
+/// Sessions in `store` run in clones of `repository`, except deleted ones
+fn repository_sessions(
+    store: &dyn SessionStore,
+    repository: &str,
+) -> Result<Vec<WorktreeSession>, String> {
+    let mut sessions = Vec::new();
+    for key in store.keys()? {
+        sessions.extend(store.load(&key)?.into_iter().filter(|session| {
+            !session.is_deleted()
+                && session
+                    .origin
+                    .as_ref()
+                    .and_then(|origin| origin.repository.as_deref())
+                    == Some(repository)
+        }));
+    }
+    Ok(sessions)
+}
+
+/// The `limit` most recently updated sessions, newest first
+fn feed_entries(sessions: &[WorktreeSession], limit: usize) -> Vec<FeedEntry> {
+    let updated = |session: &WorktreeSession| {
+        DateTime::parse_from_rfc3339(&session.updated_at)
+            .map(|t| t.with_timezone(&Utc))
+            .ok()
+    };
+    let mut sessions: Vec<&WorktreeSession> = sessions.iter().collect();
+    sessions.sort_by_key(|session| std::cmp::Reverse(updated(session)));
+    sessions
+        .into_iter()
+        .take(limit)
+        .map(FeedEntry::new)
+        .collect()
+}
+
+/// Recent sessions of the repository in the shared session store
+///
+/// # Arguments
+/// * `workspace_path` - Workspace whose repository the feed is for
+/// * `limit` - Most sessions listed (default: 100)
+///
+/// # Returns
+/// * `Ok(entries)` - Sessions of all authors, most recently updated first
+/// * `Err(message)` - No shared store configured, or no origin remote
+#[tauri::command]
+pub async fn get_team_feed(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<FeedEntry>, String> {
+    let repository = repository_of(&workspace_path)?;
+    let store = shared_store(&state)?;
+    let sessions = repository_sessions(store.as_ref(), &repository)?;
+    tracing::info!("Team feed of {}: {} sessions", repository, sessions.len());
+    Ok(feed_entries(&sessions, limit.unwrap_or(DEFAULT_FEED_LIMIT)))
+}
+
+/// Report or diff attached to a session in the team feed
+///
+/// # Arguments
+/// * `workspace_path` - Workspace whose repository the session belongs to
+/// * `session_id` - Session from the feed
+/// * `artifact_id` - Artifact of that session
+#[tauri::command]
+pub async fn get_team_artifact(
+    state: State<'_, AppState>,
+    workspace_path: String,
+    session_id: String,
+    artifact_id: String,
+) -> Result<SessionArtifact, String> {
+    let repository = repository_of(&workspace_path)?;
+    let store = shared_store(&state)?;
+    repository_sessions(store.as_ref(), &repository)?
+        .into_iter()
+        .find(|session| session.id == session_id)
+        .ok_or_else(|| format!("Session {} not found in the team feed", session_id))?
+        .artifacts
+        .into_iter()
+        .find(|artifact| artifact.id == artifact_id)
+        .ok_or_else(|| format!("Artifact {} not found", artifact_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session_history::SessionOrigin;
+    use crate::session_store;
+
+    fn session(id: &str, repository: &str, author: &str, updated_at: &str) -> WorktreeSession {
+        let mut session: WorktreeSession = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "type": "competition",
+            "task": format!("task {}", id),
+            "status": "completed",
+            "createdAt": "2026-01-01T00:00:00Z",
+            "updatedAt": updated_at,
+            "winnerId": 2,
+            "instances": [
+                {
+                    "instanceId": 2,
+                    "worktreePath": "/tmp/wt-2",
+                    "branch": "ait42/2",
+                    "agentName": "backend-developer",
+                    "status": "completed",
+                    "tmuxSessionId": "ait42-2",
+                    "model": "sonnet",
+                },
+            ],
+            "chatHistory": [],
+            "artifacts": [
+                {
+                    "id": format!("{}-diff", id),
+                    "kind": "diff",
+                    "title": "Diff",
+                    "content": "+added\n",
+                    "createdAt": updated_at,
+                },
+            ],
+        }))
+        .unwrap();
+        session.origin = Some(SessionOrigin {
+            author: Some(author.to_string()),
+            branch: Some("main".to_string()),
+            repository: Some(repository.to_string()),
+        });
+        session
+    }
+
+    #[test]
+    fn test_feed_lists_sessions_of_the_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = session_store::open("jsonl", dir.path()).unwrap();
+        let store = store.as_ref();
+        store
+            .update("alice-workspace", |sessions| {
+                sessions.push(session(
+                    "a1",
+                    "github.com/org/repo",
+                    "Alice",
+                    "2026-01-02T00:00:00Z",
+                ));
+                sessions.push(session(
+                    "other",
+                    "github.com/org/other",
+                    "Alice",
+                    "2026-01-05T00:00:00Z",
+                ));
+                Ok(())
+            })
+            .unwrap();
+        store
+            .update("bob-workspace", |sessions| {
+                sessions.push(session("b1", "github.com/org/repo", "Bob", "2026-01-03T00:00:00Z"));
+                sessions.push(WorktreeSession {
+                    deleted_at: Some("2026-01-04T00:00:00Z".to_string()),
+                    ..session("b2", "github.com/org/repo", "Bob", "2026-01-04T00:00:00Z")
+                });
+                Ok(())
+            })
+            .unwrap();
+
+        let sessions = repository_sessions(store, "github.com/org/repo").unwrap();
+        let feed = feed_entries(&sessions, 10);
+        let ids: Vec<&str> = feed.iter().map(|e| e.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["b1", "a1"]);
+        assert_eq!(feed[0].author.as_deref(), Some("Bob"));
+        assert_eq!(feed[0].branch.as_deref(), Some("main"));
+
+        let winner = feed[0].winner.as_ref().unwrap();
+        assert_eq!(winner.agent_name, "backend-developer");
+        assert_eq!(winner.model.as_deref(), Some("sonnet"));
+        assert_eq!(feed[0].artifacts[0].kind, "diff");
+
+        assert_eq!(feed_entries(&sessions, 1).len(), 1);
+    }
+}
@@ -0,0 +1,122 @@
+//! Agent Telemetry Commands
+//!
+//! Tracks whether the work of each agent run was kept, partially applied or
+//! discarded, and aggregates the results per agent. Nothing is reported by
+//! the agents themselves: confirming a winner marks the winner as kept and
+//! the other instances as discarded, and deleting a worktree compares its
+//! changes with the main checkout to see how much of them was merged.
+//!
+//! Recording never fails the command that triggered it; problems are logged.
+//! Outcomes are stored in `~/.ait42/telemetry/agent-outcomes.json`.
+
+use ait42_ait42::{AgentStats, Outcome, OutcomeLog, OutcomeRecord};
+use ait42_git::{diff, Repository};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use crate::commands::session_history::{WorktreeInstance, WorktreeSession};
+
+/// Location of the outcome log
+fn outcome_log_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".ait42")
+        .join("telemetry")
+        .join("agent-outcomes.json")
+}
+
+fn record_all(records: Vec<OutcomeRecord>) {
+    if records.is_empty() {
+        return;
+    }
+    let mut log = match OutcomeLog::load(outcome_log_path()) {
+        Ok(log) => log,
+        Err(e) => {
+            warn!("Failed to load agent outcomes: {}", e);
+            return;
+        }
+    };
+
+    let mut changed = false;
+    for record in records {
+        debug!(
+            "Agent {} in session {} instance {}: {:?}",
+            record.agent, record.session_id, record.instance_id, record.outcome
+        );
+        changed |= log.record(record);
+    }
+    if changed {
+        if let Err(e) = log.save() {
+            warn!("Failed to save agent outcomes: {}", e);
+        }
+    }
+}
+
+/// Record outcomes when a session's winner has just been chosen
+pub(crate) fn record_winner(previous: Option<&WorktreeSession>, session: &WorktreeSession) {
+    let Some(winner) = session.winner_id else {
+        return;
+    };
+    if previous.and_then(|p| p.winner_id) == Some(winner) {
+        return;
+    }
+
+    info!("Recording outcomes for session {} (winner #{})", session.id, winner);
+    let records = session
+        .instances
+        .iter()
+        .map(|instance| {
+            let outcome = if instance.instance_id == winner {
+                Outcome::Kept
+            } else {
+                Outcome::Discarded
+            };
+            OutcomeRecord::new(&instance.agent_name, &session.id, instance.instance_id, outcome)
+        })
+        .collect();
+    record_all(records);
+}
+
+/// Record how much of a worktree's changes made it into `base_path`
+///
+/// Must be called before the worktree is removed.
+pub(crate) fn record_worktree_removal(
+    session_id: &str,
+    instance: &WorktreeInstance,
+    worktree_path: &Path,
+    base_path: &Path,
+) {
+    let adoption = Repository::open(worktree_path)
+        .and_then(|worktree| diff::adoption(&worktree, &Repository::open(base_path)?));
+    let outcome = match adoption {
+        Ok(adoption) => Outcome::from_adoption(adoption.changed, adoption.adopted),
+        Err(e) => {
+            warn!("Could not compare worktree {:?} with {:?}: {}", worktree_path, base_path, e);
+            Outcome::Discarded
+        }
+    };
+    record_all(vec![OutcomeRecord::new(
+        &instance.agent_name,
+        session_id,
+        instance.instance_id,
+        outcome,
+    )]);
+}
+
+/// Success metrics per agent, empty if none are recorded
+pub(crate) fn agent_stats() -> BTreeMap<String, AgentStats> {
+    match OutcomeLog::load(outcome_log_path()) {
+        Ok(log) => log.stats(),
+        Err(e) => {
+            warn!("Failed to load agent outcomes: {}", e);
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Get success metrics for every agent with a recorded outcome
+#[tauri::command]
+pub async fn get_agent_stats() -> Result<BTreeMap<String, AgentStats>, String> {
+    Ok(agent_stats())
+}
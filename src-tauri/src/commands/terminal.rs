@@ -1,9 +1,15 @@
 //! Terminal Commands
 //!
 //! Tauri commands for terminal operations: execute commands, get output, etc.
+//!
+//! The command history is saved per workspace in `.ait42/history.toml`, the
+//! file the TUI keeps its histories in.
 
 #![cfg(feature = "terminal")]
 
+use ait42_tui::terminal_executor::{HistoryFilter, TerminalExecutor, TerminalHistoryEntry};
+use ait42_tui::SavedHistory;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -31,6 +37,8 @@ pub async fn execute_command(
         .await
         .map_err(|e| format!("Failed to execute command: {}", e))?;
 
+    save_history(&state, &terminal).await;
+
     let output = terminal
         .get_output()
         .iter()
@@ -136,27 +144,39 @@ pub async fn set_current_directory(
     Ok(())
 }
 
+/// Save the terminal history for the workspace, keeping the other
+/// histories saved there
+async fn save_history(state: &AppState, terminal: &TerminalExecutor) {
+    let workspace = state.working_dir.lock().await.clone();
+    let mut saved = SavedHistory::load(&workspace);
+    saved.terminal = terminal.history_entries().to_vec();
+    if let Err(e) = saved.save(&workspace) {
+        tracing::warn!("Failed to save terminal history: {}", e);
+    }
+}
+
 /// Get command history
 ///
 /// # Arguments
+/// * `filter` - Which commands to return, all of them if omitted
 /// * `state` - Application state
 ///
 /// # Returns
-/// * `Ok(history)` - Array of command strings (most recent first)
-/// * `Err(message)` - Error message
+/// * `Ok(history)` - Matching commands (most recent first)
+/// * `Err(message)` - Error message, e.g. for an invalid `since` time
 #[tauri::command]
-pub async fn get_command_history(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+pub async fn get_command_history(
+    filter: Option<CommandHistoryFilter>,
+    state: State<'_, AppState>,
+) -> Result<Vec<CommandHistoryEntry>, String> {
+    let filter = filter.unwrap_or_default().into_filter()?;
     let terminal = state.terminal.lock().await;
 
-    // Reverse to get most recent first
-    let history: Vec<String> = terminal
-        .history()
-        .iter()
-        .rev()
-        .cloned()
-        .collect();
-
-    Ok(history)
+    Ok(terminal
+        .get_command_history(&filter)
+        .into_iter()
+        .map(CommandHistoryEntry::from)
+        .collect())
 }
 
 /// Get terminal info
@@ -171,37 +191,111 @@ pub async fn get_command_history(state: State<'_, AppState>) -> Result<Vec<Strin
 pub async fn get_terminal_info(state: State<'_, AppState>) -> Result<TerminalInfo, String> {
     let terminal = state.terminal.lock().await;
 
+    let last_status = terminal.last_status();
     Ok(TerminalInfo {
         current_dir: terminal.current_dir().to_string_lossy().to_string(),
         output_lines: terminal.get_output().len(),
         history_size: terminal.history().len(),
         timeout_seconds: terminal.timeout().as_secs(),
+        last_exit_code: last_status.and_then(|status| status.exit_code),
+        last_duration_ms: last_status.map(|status| status.duration.as_millis() as u64),
+        last_failed: last_status.is_some_and(|status| status.failed()),
+        last_status: last_status.map(|status| status.summary()),
     })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Command in the terminal history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandHistoryEntry {
+    pub command: String,
+    /// When the command last ran (RFC 3339)
+    pub ran_at: String,
+    /// `None` if the command was killed or is still running
+    pub exit_code: Option<i32>,
+    /// How long the command took
+    pub duration_ms: Option<u64>,
+}
+
+impl From<&TerminalHistoryEntry> for CommandHistoryEntry {
+    fn from(entry: &TerminalHistoryEntry) -> Self {
+        let ran_at = chrono::DateTime::from_timestamp(entry.timestamp as i64, 0)
+            .unwrap_or_default()
+            .to_rfc3339();
+        Self {
+            command: entry.command.clone(),
+            ran_at,
+            exit_code: entry.exit_code,
+            duration_ms: entry.duration_ms,
+        }
+    }
+}
+
+/// Which commands `get_command_history` returns
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommandHistoryFilter {
+    /// Text the command must contain
+    pub query: Option<String>,
+    /// Only commands that exited with a non-zero code
+    pub failed_only: bool,
+    /// Only commands run at or after this time (RFC 3339)
+    pub since: Option<String>,
+    /// Most commands returned
+    pub limit: Option<usize>,
+}
+
+impl CommandHistoryFilter {
+    fn into_filter(self) -> Result<HistoryFilter, String> {
+        let since = match self.since {
+            Some(since) => {
+                let time = chrono::DateTime::parse_from_rfc3339(&since)
+                    .map_err(|e| format!("Invalid time {}: {}", since, e))?;
+                Some(time.timestamp().max(0) as u64)
+            }
+            None => None,
+        };
+        Ok(HistoryFilter {
+            query: self.query.filter(|query| !query.is_empty()),
+            failed_only: self.failed_only,
+            since,
+            limit: self.limit,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TerminalInfo {
     pub current_dir: String,
     pub output_lines: usize,
     pub history_size: usize,
     pub timeout_seconds: u64,
+    /// Exit code of the last command, `None` if it was killed or none ran
+    pub last_exit_code: Option<i32>,
+    /// Wall-clock duration of the last command
+    pub last_duration_ms: Option<u64>,
+    /// Whether the last command exited with a non-zero code or was killed
+    pub last_failed: bool,
+    /// Status line shown after the last command, e.g. "✗ 1 in 2.3s"
+    pub last_status: Option<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use ait42_core::{Editor, EditorConfig, EditorState};
-    use ait42_tui::terminal_executor::TerminalExecutor;
     use std::sync::{Arc, Mutex};
 
-    fn create_test_state() -> AppState {
-        AppState::default_with_dir().unwrap()
+    fn create_test_state() -> (tempfile::TempDir, AppState) {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::new(dir.path().to_path_buf()).unwrap();
+        (dir, state)
     }
 
     #[tokio::test]
     async fn test_execute_echo() {
-        let state = create_test_state();
+        let (_dir, state) = create_test_state();
 
         let result = execute_command("echo test".to_string(), State::from(&state))
             .await
@@ -212,25 +306,96 @@ mod tests {
 
     #[tokio::test]
     async fn test_terminal_history() {
-        let state = create_test_state();
+        let (_dir, state) = create_test_state();
 
         // Execute some commands
         let _ = execute_command("echo hello".to_string(), State::from(&state)).await;
         let _ = execute_command("echo world".to_string(), State::from(&state)).await;
 
         // Get history
-        let history = get_command_history(State::from(&state))
+        let history = get_command_history(None, State::from(&state))
             .await
             .unwrap();
 
         assert_eq!(history.len(), 2);
-        assert_eq!(history[0], "echo world"); // Most recent first
-        assert_eq!(history[1], "echo hello");
+        assert_eq!(history[0].command, "echo world"); // Most recent first
+        assert_eq!(history[1].command, "echo hello");
+        assert_eq!(history[0].exit_code, Some(0));
+        assert!(history[0].duration_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_terminal_info_reports_last_status() {
+        let (_dir, state) = create_test_state();
+        let info = get_terminal_info(State::from(&state)).await.unwrap();
+        assert_eq!(info.last_status, None);
+
+        let output = execute_command("nonexistent_command_12345".to_string(), State::from(&state))
+            .await
+            .unwrap();
+        let info = get_terminal_info(State::from(&state)).await.unwrap();
+        assert!(info.last_failed);
+        assert_eq!(info.last_exit_code, Some(127));
+        assert!(info.last_duration_ms.is_some());
+        let status = info.last_status.unwrap();
+        assert!(status.starts_with("✗ 127 in "));
+        assert!(output.ends_with(&status));
+    }
+
+    #[tokio::test]
+    async fn test_command_history_filter() {
+        let (_dir, state) = create_test_state();
+        let _ = execute_command("echo hello".to_string(), State::from(&state)).await;
+        let _ = execute_command("nonexistent_command_12345".to_string(), State::from(&state)).await;
+
+        let filter = CommandHistoryFilter {
+            failed_only: true,
+            ..CommandHistoryFilter::default()
+        };
+        let failed = get_command_history(Some(filter), State::from(&state))
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].command, "nonexistent_command_12345");
+
+        let filter = CommandHistoryFilter {
+            query: Some("hello".to_string()),
+            since: Some("2000-01-01T00:00:00Z".to_string()),
+            ..CommandHistoryFilter::default()
+        };
+        let matched = get_command_history(Some(filter), State::from(&state))
+            .await
+            .unwrap();
+        assert_eq!(matched.len(), 1);
+
+        let filter = CommandHistoryFilter {
+            since: Some("yesterday".to_string()),
+            ..CommandHistoryFilter::default()
+        };
+        let invalid = get_command_history(Some(filter), State::from(&state)).await;
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_history_saved_per_workspace() {
+        let (dir, state) = create_test_state();
+        let _ = execute_command("echo saved".to_string(), State::from(&state)).await;
+
+        let saved = SavedHistory::load(dir.path());
+        assert_eq!(saved.terminal.len(), 1);
+        assert_eq!(saved.terminal[0].command, "echo saved");
+
+        // A new session in the workspace starts from the saved history
+        let state = AppState::new(dir.path().to_path_buf()).unwrap();
+        let history = get_command_history(None, State::from(&state))
+            .await
+            .unwrap();
+        assert_eq!(history[0].command, "echo saved");
     }
 
     #[tokio::test]
     async fn test_clear_terminal() {
-        let state = create_test_state();
+        let (_dir, state) = create_test_state();
 
         // Execute command to generate output
         let _ = execute_command("echo test".to_string(), State::from(&state)).await;
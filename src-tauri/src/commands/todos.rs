@@ -0,0 +1,129 @@
+//! TODO Commands
+//!
+//! Lists TODO/FIXME/HACK comments across the workspace. The workspace is
+//! scanned once, when the list is first requested, and saved files are
+//! rescanned one at a time after that. Changing the workspace or the
+//! `[todos]` tags in the config starts a new scan.
+
+use ait42_fs::{TodoIndex, TodoItem};
+use std::path::{Path, PathBuf};
+use std::sync::MutexGuard;
+use tauri::State;
+
+use crate::state::AppState;
+
+/// Whether `index` was built for `workspace` and `tags`
+fn is_current(index: &TodoIndex, workspace: &Path, tags: &[String]) -> bool {
+    index.root() == workspace && index.tags() == tags
+}
+
+fn lock_index(state: &AppState) -> Result<MutexGuard<'_, Option<TodoIndex>>, String> {
+    state
+        .todos
+        .lock()
+        .map_err(|e| format!("Failed to lock TODO index: {}", e))
+}
+
+/// Run `f` on the index of the current workspace, scanning it first if
+/// there is none yet, it is stale, or `rescan` is set
+async fn with_index<T>(
+    state: &AppState,
+    rescan: bool,
+    f: impl FnOnce(&mut TodoIndex) -> Result<T, String>,
+) -> Result<T, String> {
+    let workspace: PathBuf = state.working_dir.lock().await.clone();
+    let tags = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .todos
+        .tags
+        .clone();
+
+    let stale = rescan
+        || !lock_index(state)?
+            .as_ref()
+            .is_some_and(|index| is_current(index, &workspace, &tags));
+    if stale {
+        let index = tokio::task::spawn_blocking(move || TodoIndex::scan(&workspace, &tags))
+            .await
+            .map_err(|e| format!("TODO scan failed: {}", e))?
+            .map_err(|e| format!("Failed to scan for TODOs: {}", e))?;
+        *lock_index(state)? = Some(index);
+    }
+
+    let mut todos = lock_index(state)?;
+    let index = todos.as_mut().ok_or("TODO index is not built")?;
+    f(index)
+}
+
+/// List the tagged comments of the workspace, ordered by path and line
+///
+/// # Arguments
+/// * `refresh` - Rescan the whole workspace instead of using the index
+#[tauri::command]
+pub async fn list_todos(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<Vec<TodoItem>, String> {
+    with_index(&state, refresh.unwrap_or(false), |index| Ok(index.items())).await
+}
+
+/// Rescan one saved or deleted file and return the updated list
+#[tauri::command]
+pub async fn update_todos(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<TodoItem>, String> {
+    with_index(&state, false, |index| {
+        index
+            .update_file(Path::new(&path))
+            .map_err(|e| format!("Failed to scan {}: {}", path, e))?;
+        Ok(index.items())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_goes_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let tags = vec!["TODO".to_string()];
+        let index = TodoIndex::scan(dir.path(), &tags).unwrap();
+
+        assert!(is_current(&index, dir.path(), &tags));
+        assert!(!is_current(&index, Path::new("/elsewhere"), &tags));
+        assert!(!is_current(&index, dir.path(), &["FIXME".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_update_todos() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "// TODO: first\n").unwrap();
+        let state = AppState::new(dir.path().to_path_buf()).unwrap();
+
+        let items = with_index(&state, false, |index| Ok(index.items()))
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].text, "first");
+
+        std::fs::write(dir.path().join("a.rs"), "// TODO: first\n// FIXME: second\n").unwrap();
+        let items = with_index(&state, false, |index| Ok(index.items()))
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1, "the index is only updated on save");
+
+        let path = dir.path().join("a.rs");
+        let items = with_index(&state, false, |index| {
+            index.update_file(&path).map_err(|e| e.to_string())?;
+            Ok(index.items())
+        })
+        .await
+        .unwrap();
+        assert_eq!(items.len(), 2);
+    }
+}
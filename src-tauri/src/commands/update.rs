@@ -0,0 +1,110 @@
+//! Update Commands
+//!
+//! Checks the configured release channel for a newer editor and installs it.
+//! The frontend shows the release notes from `check_for_updates` and only
+//! calls `install_update` once the user accepts them.
+
+use ait42_config::UpdatesConfig;
+use ait42_update::{Release, UpdateClient};
+use tauri::{Manager, State};
+
+use crate::notifications::NotificationLevel;
+use crate::state::AppState;
+
+/// Name of the GUI binary in release manifests
+const ARTIFACT: &str = "ait42-editor";
+
+fn updates_config(state: &AppState) -> UpdatesConfig {
+    match state.config.lock() {
+        Ok(config) => config.updates.clone(),
+        Err(e) => e.into_inner().updates.clone(),
+    }
+}
+
+fn client(state: &AppState) -> Result<UpdateClient, String> {
+    UpdateClient::new(&updates_config(state), ARTIFACT, env!("CARGO_PKG_VERSION"))
+        .map_err(|e| e.to_string())
+}
+
+/// Newest release on the configured channel, if newer than this build
+#[tauri::command]
+pub async fn check_for_updates(state: State<'_, AppState>) -> Result<Option<Release>, String> {
+    client(&state)?.check().await.map_err(|e| e.to_string())
+}
+
+/// Download, verify and install the newest release, then restart
+///
+/// Nothing is installed unless the download's signature checks out.
+#[tauri::command]
+pub async fn install_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let client = client(&state)?;
+    let release = client
+        .check()
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Already up to date")?;
+    let contents = client.download(&release).await.map_err(|e| e.to_string())?;
+
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate executable: {}", e))?;
+    tauri::async_runtime::spawn_blocking(move || ait42_update::install_executable(&contents, &exe))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    tracing::info!("Updated to {}, restarting", release.version);
+    app.restart();
+    Ok(())
+}
+
+/// Get the update settings
+#[tauri::command]
+pub async fn get_updates_config(state: State<'_, AppState>) -> Result<UpdatesConfig, String> {
+    Ok(updates_config(&state))
+}
+
+/// Update the update settings
+#[tauri::command]
+pub async fn set_updates_config(
+    config: UpdatesConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if !UpdatesConfig::CHANNELS.contains(&config.channel.as_str()) {
+        return Err(format!("Unknown update channel: {}", config.channel));
+    }
+    state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?
+        .updates = config;
+    Ok(())
+}
+
+/// Check for an update at startup if enabled, and notify if one is found
+pub async fn check_on_startup(app: tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    if !updates_config(&state).check_on_startup {
+        return;
+    }
+    let release = match client(&state) {
+        Ok(client) => client.check().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    match release {
+        Ok(Some(release)) => state.notifications.notify(
+            &app,
+            NotificationLevel::Info,
+            "updates",
+            format!(
+                "AIT42 Editor {} is available. Review the release notes in Settings to install it.",
+                release.version
+            ),
+            vec![],
+        ),
+        Ok(None) => tracing::debug!("No update available"),
+        // Offline starts are common, so this is not worth a notification
+        Err(e) => tracing::warn!("Update check failed: {}", e),
+    }
+}
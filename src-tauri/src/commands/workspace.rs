@@ -1,5 +1,6 @@
 use crate::state::AppState;
 use crate::utils::AIT42Installer;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 /**
@@ -9,26 +10,39 @@ use std::path::PathBuf;
  */
 use tauri::{Manager, State};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceInfo {
     pub path: String,
     pub is_git_repo: bool,
 }
 
-/// Open a folder selection dialog and set it as the working directory
+/// Event emitted with the new [`WorkspaceInfo`] when the workspace changes
+pub const WORKSPACE_CHANGED_EVENT: &str = "workspace-changed";
+
+/// Set the working directory to `path`, or to a folder picked in a dialog
+///
+/// `path` is given when a folder was dropped onto the window.
 #[tauri::command]
 pub async fn select_workspace(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
+    path: Option<String>,
 ) -> Result<WorkspaceInfo, String> {
     use tauri::api::dialog::blocking::FileDialogBuilder;
 
-    // Show folder selection dialog
-    let selected = FileDialogBuilder::new()
-        .set_title("プロジェクトフォルダを選択")
-        .pick_folder();
+    let selected = match path {
+        Some(path) => Some(PathBuf::from(path)),
+        // Show folder selection dialog
+        None => FileDialogBuilder::new()
+            .set_title("プロジェクトフォルダを選択")
+            .pick_folder(),
+    };
 
     if let Some(path) = selected {
+        if !path.is_dir() {
+            return Err(format!("フォルダではありません: {}", path.display()));
+        }
+
         // Check if it's a git repository
         let is_git_repo = path.join(".git").exists();
 
@@ -44,6 +58,18 @@ pub async fn select_workspace(
         *working_dir = path.clone();
         drop(working_dir);
 
+        // Language servers started from now on use the new root
+        if let Err(e) = state.lsp_manager.set_workspace_root(&path) {
+            tracing::warn!("Failed to set LSP workspace root: {}", e);
+        }
+
+        // The terminal history is kept per workspace
+        #[cfg(feature = "terminal")]
+        {
+            let history = ait42_tui::SavedHistory::load(&path).terminal;
+            state.terminal.lock().await.set_history(history);
+        }
+
         tracing::info!("📁 Workspace set to: {}", path.display());
 
         // Auto-install AIT42 system if not already present
@@ -117,10 +143,14 @@ pub async fn select_workspace(
         // Save to config
         save_workspace_config(&path)?;
 
-        Ok(WorkspaceInfo {
+        let info = WorkspaceInfo {
             path: path.to_string_lossy().to_string(),
             is_git_repo,
-        })
+        };
+        if let Err(e) = app.emit_all(WORKSPACE_CHANGED_EVENT, &info) {
+            tracing::warn!("Failed to emit workspace change: {}", e);
+        }
+        Ok(info)
     } else {
         Err("フォルダが選択されませんでした".to_string())
     }
@@ -3,6 +3,7 @@
 //! This module provides Tauri commands for visualizing and managing git worktrees
 //! created for Claude Code competitions.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -10,7 +11,11 @@ use std::process::Command;
 use chrono::{DateTime, Utc};
 use tracing::{info, warn, error};
 
-#[derive(Serialize, Clone, Debug)]
+use crate::commands::{session_history, telemetry};
+use crate::confirmation::DangerousAction;
+
+#[derive(Serialize, JsonSchema, Clone, Debug)]
+#[schemars(rename = "CompetitionWorktreeInfo")]
 pub struct WorktreeInfo {
     pub id: String,
     pub path: String,
@@ -20,7 +25,8 @@ pub struct WorktreeInfo {
     pub changed_files: usize,
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, JsonSchema, Clone, Debug)]
+#[schemars(rename = "WorktreeFileNode")]
 pub struct FileNode {
     pub name: String,
     pub path: String,
@@ -146,11 +152,20 @@ pub async fn get_worktree_files(
 }
 
 /// Delete a worktree
+///
+/// Requires a `confirmation` token from `request_confirmation` for the worktree.
 #[tauri::command]
 pub async fn delete_worktree(
     state: tauri::State<'_, crate::state::AppState>,
     worktree_id: String,
+    confirmation: Option<String>,
 ) -> Result<(), String> {
+    state.confirmations.consume(
+        confirmation.as_deref(),
+        &DangerousAction::DeleteWorktree {
+            worktree_id: worktree_id.clone(),
+        },
+    )?;
     info!("Deleting worktree: {}", worktree_id);
 
     // Parse worktree_id (format: "{competition_id}-instance-{num}")
@@ -172,12 +187,28 @@ pub async fn delete_worktree(
     drop(working_dir);
 
     let competition_dir = base_path.join(".worktrees").join(format!("competition-{}", &competition_id[..competition_id.len().min(8)]));
-    let worktree_path = competition_dir.join(format!("instance-{}", instance_num));
+
+    // Prefer the worktree and tmux session the run recorded, whose names may
+    // carry a collision suffix
+    let workspace_path = base_path.to_string_lossy();
+    let instance = instance_num.parse().ok().and_then(|instance_id| {
+        session_history::find_instance(&state, &workspace_path, competition_id, instance_id)
+    });
+    let worktree_path = instance
+        .as_ref()
+        .map(|instance| PathBuf::from(&instance.worktree_path))
+        .filter(|path| path.starts_with(&competition_dir))
+        .unwrap_or_else(|| competition_dir.join(format!("instance-{}", instance_num)));
 
     if !worktree_path.exists() {
         return Err("Worktree does not exist".to_string());
     }
 
+    // Note how much of the agent's work survives before it is deleted
+    if let Some(instance) = &instance {
+        telemetry::record_worktree_removal(competition_id, instance, &worktree_path, &base_path);
+    }
+
     // Remove git worktree first
     let output = Command::new("git")
         .arg("worktree")
@@ -199,7 +230,16 @@ pub async fn delete_worktree(
     }
 
     // Kill associated tmux session if exists
-    let session_id = format!("claude-code-comp-{}-{}", &competition_id[..competition_id.len().min(8)], instance_num);
+    let session_id = instance
+        .map(|instance| instance.tmux_session_id)
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| {
+            format!(
+                "claude-code-comp-{}-{}",
+                &competition_id[..competition_id.len().min(8)],
+                instance_num
+            )
+        });
     let _ = Command::new("tmux")
         .arg("kill-session")
         .arg("-t")
@@ -385,7 +425,7 @@ fn build_file_tree(
 }
 
 /// Diff hunk representing a change in a file
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, JsonSchema, Clone, Debug)]
 pub struct DiffHunk {
     pub old_start: usize,
     pub old_lines: usize,
@@ -395,7 +435,7 @@ pub struct DiffHunk {
 }
 
 /// Individual line in a diff
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, JsonSchema, Clone, Debug)]
 pub struct DiffLine {
     pub line_type: String, // "add", "delete", "context"
     pub content: String,
@@ -404,7 +444,7 @@ pub struct DiffLine {
 }
 
 /// File diff information
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, JsonSchema, Clone, Debug)]
 pub struct FileDiff {
     pub file_path: String,
     pub old_path: Option<String>,
@@ -481,6 +521,132 @@ pub async fn get_worktree_diff(
     parse_multi_file_diff(&diff_output)
 }
 
+/// Item-level change in a semantic diff
+#[derive(Serialize, JsonSchema, Clone, Debug)]
+pub struct SemanticChange {
+    pub kind: String, // "added", "removed", "modified", "moved", "renamed"
+    pub item_kind: String,
+    pub name: String,
+    pub old_name: Option<String>,
+    pub old_start: Option<usize>,
+    pub new_start: Option<usize>,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Semantic diff of a file, grouped by function, type and method
+#[derive(Serialize, JsonSchema, Clone, Debug)]
+pub struct SemanticFileDiff {
+    pub file_path: String,
+    pub language: String,
+    pub changes: Vec<SemanticChange>,
+    pub other: Vec<DiffLine>, // Changed lines outside any item
+}
+
+/// Get semantic diff for a specific file in a worktree
+///
+/// Changes are grouped by item, with pure moves and renames detected.
+/// Fails for files without a tree-sitter grammar; the textual diff from
+/// `get_file_diff` still works for those.
+#[tauri::command]
+pub async fn get_semantic_diff(
+    worktree_path: String,
+    file_path: String,
+) -> Result<SemanticFileDiff, String> {
+    info!("Getting semantic diff for file: {} in {}", file_path, worktree_path);
+
+    let worktree = PathBuf::from(&worktree_path);
+
+    // Security: validate path
+    let canonical = worktree.canonicalize()
+        .map_err(|e| format!("Invalid worktree path: {}", e))?;
+    let path = worktree_file(&canonical, &file_path)?;
+
+    let language = ait42_core::syntax::Language::from_path(&path)
+        .ok_or_else(|| format!("Semantic diff is not supported for {}", file_path))?;
+
+    // Committed version; missing for added files
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&canonical)
+        .arg("show")
+        .arg(format!("HEAD:./{}", file_path))
+        .output()
+        .map_err(|e| format!("Failed to run git show: {}", e))?;
+    let old = if output.status.success() {
+        String::from_utf8_lossy(&output.stdout).to_string()
+    } else {
+        String::new()
+    };
+
+    // Working copy; missing for deleted files
+    let new = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", file_path, e)),
+    };
+
+    let diff = ait42_core::semantic_diff(language, &old, &new).map_err(|e| e.to_string())?;
+
+    Ok(SemanticFileDiff {
+        file_path,
+        language: language.name().to_string(),
+        changes: diff.changes.into_iter().map(semantic_change).collect(),
+        other: diff.other.iter().map(diff_line).collect(),
+    })
+}
+
+/// Path of `file_path` inside `worktree`, rejecting paths that escape it
+fn worktree_file(worktree: &Path, file_path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(file_path);
+    let escapes = relative.components().any(|component| {
+        !matches!(component, std::path::Component::Normal(_) | std::path::Component::CurDir)
+    });
+    if escapes {
+        return Err(format!("Invalid file path: {}", file_path));
+    }
+    Ok(worktree.join(relative))
+}
+
+fn semantic_change(change: ait42_core::ItemChange) -> SemanticChange {
+    use ait42_core::ItemChangeKind;
+
+    let kind = match change.kind {
+        ItemChangeKind::Added => "added",
+        ItemChangeKind::Removed => "removed",
+        ItemChangeKind::Modified => "modified",
+        ItemChangeKind::Moved => "moved",
+        ItemChangeKind::Renamed => "renamed",
+    };
+
+    SemanticChange {
+        kind: kind.to_string(),
+        item_kind: change.item_kind.to_string(),
+        name: change.name,
+        old_name: change.old_name,
+        old_start: change.old_lines.map(|lines| lines.start + 1),
+        new_start: change.new_lines.map(|lines| lines.start + 1),
+        lines: change.lines.iter().map(diff_line).collect(),
+    }
+}
+
+/// Convert a zero-based line change to a diff line
+fn diff_line(line: &ait42_core::LineChange) -> DiffLine {
+    use ait42_core::LineTag;
+
+    let line_type = match line.tag {
+        LineTag::Added => "add",
+        LineTag::Removed => "delete",
+        LineTag::Context => "context",
+    };
+
+    DiffLine {
+        line_type: line_type.to_string(),
+        content: line.text.clone(),
+        old_line_num: line.old_line.map(|n| n + 1),
+        new_line_num: line.new_line.map(|n| n + 1),
+    }
+}
+
 /// Parse unified diff format
 fn parse_diff(diff: &str, file_path: &str) -> Result<FileDiff, String> {
     let mut hunks = Vec::new();
@@ -622,4 +788,35 @@ mod tests {
         assert!("../secret-instance-1".contains(".."));
         assert!("/etc/passwd-instance-1".contains('/'));
     }
+
+    #[test]
+    fn test_worktree_file_stays_inside() {
+        let worktree = Path::new("/work/tree");
+        assert_eq!(worktree_file(worktree, "src/main.rs").unwrap(), worktree.join("src/main.rs"));
+        assert!(worktree_file(worktree, "../other/main.rs").is_err());
+        assert!(worktree_file(worktree, "src/../../main.rs").is_err());
+        assert!(worktree_file(worktree, "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_semantic_change_conversion() {
+        let diff = ait42_core::semantic_diff(
+            ait42_core::syntax::Language::Rust,
+            "fn a() {\n    1\n}\n",
+            "fn a() {\n    2\n}\n",
+        )
+        .unwrap();
+
+        let change = semantic_change(diff.changes[0].clone());
+        assert_eq!(change.kind, "modified");
+        assert_eq!(change.name, "a");
+        assert_eq!(change.new_start, Some(1));
+        let changed: Vec<_> = change
+            .lines
+            .iter()
+            .filter(|line| line.line_type != "context")
+            .map(|line| (line.line_type.as_str(), line.old_line_num, line.new_line_num))
+            .collect();
+        assert_eq!(changed, vec![("delete", Some(2), None), ("add", None, Some(2))]);
+    }
 }
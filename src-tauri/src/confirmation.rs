@@ -0,0 +1,197 @@
+//! Confirmation of Destructive Commands
+//!
+//! Commands that destroy work (pushing, deleting files or worktrees, killing
+//! agent sessions) only run with a confirmation token. Tokens are issued by
+//! `request_confirmation` after the user approves a native dialog, which
+//! scripts in the webview cannot answer. Each token is bound to one action,
+//! used once, and expires shortly after it is issued, so a compromised
+//! webview script cannot destroy work without the user noticing.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long an issued token stays valid
+pub const CONFIRMATION_TTL: Duration = Duration::from_secs(60);
+
+/// A command that requires confirmation, with the target it acts on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DangerousAction {
+    #[serde(rename_all = "camelCase")]
+    GitPush {
+        remote: Option<String>,
+        branch: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteWorktree { worktree_id: String },
+    #[serde(rename_all = "camelCase")]
+    DeletePath { path: String },
+    #[serde(rename_all = "camelCase")]
+    KillTmuxSession { session_id: String },
+}
+
+impl DangerousAction {
+    /// Title of the confirmation dialog
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::GitPush { .. } => "Push to remote?",
+            Self::DeleteWorktree { .. } => "Delete worktree?",
+            Self::DeletePath { .. } => "Delete from disk?",
+            Self::KillTmuxSession { .. } => "Stop agent session?",
+        }
+    }
+
+    /// Message of the confirmation dialog, naming the target
+    pub fn message(&self) -> String {
+        match self {
+            Self::GitPush { remote, branch } => format!(
+                "Push {} to {}?",
+                branch.as_deref().unwrap_or("main"),
+                remote.as_deref().unwrap_or("origin")
+            ),
+            Self::DeleteWorktree { worktree_id } => {
+                format!("Delete worktree {} and any uncommitted changes in it?", worktree_id)
+            }
+            Self::DeletePath { path } => format!("Permanently delete {}?", path),
+            Self::KillTmuxSession { session_id } => format!(
+                "Kill tmux session {}? The agent running in it will be stopped.",
+                session_id
+            ),
+        }
+    }
+}
+
+struct PendingConfirmation {
+    action: DangerousAction,
+    issued_at: Instant,
+}
+
+/// Tokens issued for confirmed actions and not yet used
+pub struct ConfirmationGate {
+    pending: Mutex<HashMap<String, PendingConfirmation>>,
+    ttl: Duration,
+}
+
+impl ConfirmationGate {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Issue a token allowing `action` once
+    ///
+    /// Only call this after the user has confirmed `action`.
+    pub fn issue(&self, action: DangerousAction) -> String {
+        let token = uuid::Uuid::new_v4().to_string();
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|_, p| p.issued_at.elapsed() < self.ttl);
+        pending.insert(
+            token.clone(),
+            PendingConfirmation {
+                action,
+                issued_at: Instant::now(),
+            },
+        );
+        token
+    }
+
+    /// Use up `token`, which must have been issued for `action`
+    pub fn consume(&self, token: Option<&str>, action: &DangerousAction) -> Result<(), String> {
+        let token = token.ok_or_else(|| format!("{} requires confirmation", action.title()))?;
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let confirmation = pending
+            .remove(token)
+            .ok_or_else(|| "Invalid or already used confirmation token".to_string())?;
+
+        if confirmation.issued_at.elapsed() >= self.ttl {
+            return Err("Confirmation expired, please confirm again".to_string());
+        }
+        if &confirmation.action != action {
+            return Err("Confirmation token was issued for a different action".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for ConfirmationGate {
+    fn default() -> Self {
+        Self::new(CONFIRMATION_TTL)
+    }
+}
+
+impl std::fmt::Debug for ConfirmationGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfirmationGate")
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delete(path: &str) -> DangerousAction {
+        DangerousAction::DeletePath {
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_token_is_single_use() {
+        let gate = ConfirmationGate::default();
+        let token = gate.issue(delete("/tmp/a"));
+
+        assert!(gate.consume(Some(&token), &delete("/tmp/a")).is_ok());
+        assert!(gate.consume(Some(&token), &delete("/tmp/a")).is_err());
+    }
+
+    #[test]
+    fn test_token_is_bound_to_action() {
+        let gate = ConfirmationGate::default();
+        let token = gate.issue(delete("/tmp/a"));
+
+        let err = gate.consume(Some(&token), &delete("/home")).unwrap_err();
+        assert!(err.contains("different action"));
+
+        let kill = DangerousAction::KillTmuxSession {
+            session_id: "s1".to_string(),
+        };
+        let token = gate.issue(kill);
+        assert!(gate.consume(Some(&token), &delete("/tmp/a")).is_err());
+    }
+
+    #[test]
+    fn test_missing_unknown_and_expired_tokens() {
+        let gate = ConfirmationGate::new(Duration::ZERO);
+
+        let err = gate.consume(None, &delete("/tmp/a")).unwrap_err();
+        assert!(err.contains("requires confirmation"));
+        assert!(gate.consume(Some("forged"), &delete("/tmp/a")).is_err());
+
+        let token = gate.issue(delete("/tmp/a"));
+        let err = gate.consume(Some(&token), &delete("/tmp/a")).unwrap_err();
+        assert!(err.contains("expired"));
+    }
+
+    #[test]
+    fn test_action_wire_format() {
+        let action: DangerousAction = serde_json::from_value(serde_json::json!({
+            "kind": "deleteWorktree",
+            "worktreeId": "abc-instance-1",
+        }))
+        .unwrap();
+        assert_eq!(
+            action,
+            DangerousAction::DeleteWorktree {
+                worktree_id: "abc-instance-1".to_string()
+            }
+        );
+        assert!(action.message().contains("abc-instance-1"));
+    }
+}
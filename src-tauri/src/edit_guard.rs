@@ -0,0 +1,255 @@
+//! Edit Guard
+//!
+//! Agents can edit the workspace without the user reviewing each change,
+//! e.g. through the MCP server's `apply_edit` tool. Before such an edit is
+//! applied it is checked for
+//!
+//! - paths outside the workspace,
+//! - protected paths (`ait42.edit_guard.protected_paths`, such as CI
+//!   workflows and secrets files),
+//! - binary payloads: control characters or long base64 runs.
+//!
+//! A risky edit is only applied after the user approves it in a native
+//! dialog, which scripts in the webview cannot answer.
+
+use ait42_config::EditGuardConfig;
+use glob::{MatchOptions, Pattern};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use tauri::Manager;
+
+use crate::injection::{self, FindingKind};
+
+/// Why an edit needs approval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum EditRisk {
+    /// The edited file is not inside the workspace
+    OutsideWorkspace,
+    /// The edited file matches a protected path
+    ProtectedPath { pattern: String },
+    /// The new text looks like binary data
+    BinaryPayload { reason: String },
+}
+
+impl std::fmt::Display for EditRisk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutsideWorkspace => write!(f, "writes outside the workspace"),
+            Self::ProtectedPath { pattern } => write!(f, "touches a protected path ({})", pattern),
+            Self::BinaryPayload { reason } => write!(f, "carries a binary payload ({})", reason),
+        }
+    }
+}
+
+/// Check an edit writing `text` to `path` in `workspace`
+///
+/// `path` is `None` for buffers not backed by a file, whose edits are only
+/// checked for binary payloads. Returns nothing when the guard is disabled.
+pub fn check(
+    workspace: &Path,
+    path: Option<&Path>,
+    text: &str,
+    config: &EditGuardConfig,
+) -> Vec<EditRisk> {
+    if !config.enabled {
+        return Vec::new();
+    }
+
+    let mut risks = Vec::new();
+    if let Some(path) = path {
+        let workspace = resolve(workspace, workspace);
+        match resolve(&workspace, path).strip_prefix(&workspace) {
+            Ok(relative) => {
+                if let Some(pattern) = protected_by(relative, &config.protected_paths) {
+                    risks.push(EditRisk::ProtectedPath { pattern });
+                }
+            }
+            Err(_) => risks.push(EditRisk::OutsideWorkspace),
+        }
+    }
+    if let Some(reason) = binary_payload(text, config.max_blob_chars) {
+        risks.push(EditRisk::BinaryPayload { reason });
+    }
+    risks
+}
+
+/// Ask the user whether to apply a risky edit to `target`
+///
+/// Returns `false` if the user declines or no window can show the dialog.
+pub async fn approve(app: &tauri::AppHandle, target: &str, risks: &[EditRisk]) -> bool {
+    let Some(window) = app.get_window("main") else {
+        return false;
+    };
+    let reasons: Vec<String> = risks.iter().map(|risk| format!("- {}", risk)).collect();
+    let message = format!(
+        "An agent wants to edit {}. The edit\n{}\n\nApply it anyway?",
+        target,
+        reasons.join("\n")
+    );
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tauri::api::dialog::ask(Some(&window), "Apply risky edit?", message, move |approved| {
+        let _ = tx.send(approved);
+    });
+    rx.await.unwrap_or(false)
+}
+
+/// Absolute form of `path`, relative paths taken from `base`
+///
+/// Symlinks are resolved up to the deepest existing ancestor, and the rest
+/// is appended with `.` and `..` removed lexically, so new files can escape
+/// neither with `../` nor through a symlinked directory.
+fn resolve(base: &Path, path: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => {
+                resolved.push(other);
+                if let Ok(canonical) = resolved.canonicalize() {
+                    resolved = canonical;
+                }
+            }
+        }
+    }
+    resolved
+}
+
+/// First protected pattern matching the workspace-relative `path`
+fn protected_by(path: &Path, patterns: &[String]) -> Option<String> {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    patterns
+        .iter()
+        .find(|pattern| match Pattern::new(pattern) {
+            Ok(glob) => glob.matches_path_with(path, options),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid protected path {}: {}", pattern, e);
+                false
+            }
+        })
+        .cloned()
+}
+
+/// Why `text` looks like binary data, if it does
+fn binary_payload(text: &str, max_blob_chars: usize) -> Option<String> {
+    if let Some(c) = text
+        .chars()
+        .find(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\x0c'))
+    {
+        return Some(format!("control character U+{:04X}", c as u32));
+    }
+    injection::scan(text, max_blob_chars)
+        .into_iter()
+        .find(|finding| finding.kind == FindingKind::EncodedBlob)
+        .map(|finding| format!("base64 run on line {}", finding.line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EditGuardConfig {
+        EditGuardConfig {
+            max_blob_chars: 64,
+            ..EditGuardConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_plain_edits_pass() {
+        let workspace = Path::new("/work/project");
+        let risks = check(workspace, Some(Path::new("src/main.rs")), "fn main() {}\n", &config());
+        assert!(risks.is_empty());
+        assert!(check(workspace, None, "let x = 1;\n\ttabbed\r\n", &config()).is_empty());
+    }
+
+    #[test]
+    fn test_paths_outside_workspace() {
+        let workspace = Path::new("/work/project");
+        for path in ["../other/file.rs", "src/../../escape.rs", "/etc/passwd"] {
+            assert_eq!(
+                check(workspace, Some(Path::new(path)), "", &config()),
+                vec![EditRisk::OutsideWorkspace],
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_new_files_under_symlinked_dirs() {
+        let outside = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path();
+        std::os::unix::fs::symlink(outside.path(), workspace.join("link")).unwrap();
+
+        for path in ["link/new.rs", "link/sub/new.rs"] {
+            assert_eq!(
+                check(workspace, Some(Path::new(path)), "", &config()),
+                vec![EditRisk::OutsideWorkspace],
+                "{}",
+                path
+            );
+        }
+        assert!(check(workspace, Some(Path::new("src/new.rs")), "", &config()).is_empty());
+    }
+
+    #[test]
+    fn test_protected_paths() {
+        let workspace = Path::new("/work/project");
+        let risk = |path: &str| check(workspace, Some(Path::new(path)), "", &config());
+
+        assert_eq!(
+            risk(".github/workflows/ci.yml"),
+            vec![EditRisk::ProtectedPath {
+                pattern: ".github/workflows/**".to_string()
+            }]
+        );
+        assert!(!risk(".env").is_empty());
+        assert!(!risk(".env.production").is_empty());
+        assert!(!risk("deploy/certs/server.pem").is_empty());
+        assert!(!risk("/work/project/config/secrets.toml").is_empty());
+        assert!(risk(".github/CODEOWNERS").is_empty());
+        assert!(risk("src/environment.rs").is_empty());
+    }
+
+    #[test]
+    fn test_binary_payloads() {
+        let workspace = Path::new("/work/project");
+        let risks = check(workspace, None, "header\0\x01\x02", &config());
+        assert!(
+            matches!(&risks[..], [EditRisk::BinaryPayload { reason }] if reason.contains("U+0000"))
+        );
+
+        let blob = format!("const DATA = \"{}\";\n", "QUJD".repeat(40));
+        let risks = check(workspace, None, &blob, &config());
+        assert!(
+            matches!(&risks[..], [EditRisk::BinaryPayload { reason }] if reason.contains("base64"))
+        );
+    }
+
+    #[test]
+    fn test_disabled_guard() {
+        let config = EditGuardConfig {
+            enabled: false,
+            ..config()
+        };
+        let risks = check(Path::new("/work"), Some(Path::new("/etc/passwd")), "\0", &config);
+        assert!(risks.is_empty());
+    }
+}
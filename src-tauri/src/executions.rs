@@ -0,0 +1,196 @@
+//! Running Executions
+//!
+//! Cancellation tokens of the agent executions in flight, keyed by execution
+//! ID. Commands running an agent register the execution for as long as they
+//! await it and stop awaiting when its token is cancelled. Cancelling also
+//! kills the tmux sessions the execution started, so the agents themselves
+//! stop rather than running on unobserved.
+//!
+//! Agents are started in tmux sessions named `ait42-<agent>-...`, and the
+//! session name is only known once the agent finished. The agent sessions
+//! that already existed when the execution started are remembered, and any
+//! newer session of the execution's agents is taken to be its own.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::tools::ToolRegistry;
+use crate::tray;
+
+struct Execution {
+    token: CancellationToken,
+    agents: Vec<String>,
+    /// Agent sessions that existed before the execution started
+    existing_sessions: HashSet<String>,
+}
+
+impl Execution {
+    fn owns_session(&self, session: &str) -> bool {
+        !self.existing_sessions.contains(session)
+            && self
+                .agents
+                .iter()
+                .any(|agent| session.starts_with(&format!("ait42-{}-", agent)))
+    }
+}
+
+/// Executions that can be cancelled
+#[derive(Default)]
+pub struct RunningExecutions {
+    executions: Mutex<HashMap<String, Execution>>,
+}
+
+/// Registration of a running execution, removed when dropped
+pub struct RunningExecution<'a> {
+    executions: &'a RunningExecutions,
+    execution_id: String,
+    token: CancellationToken,
+}
+
+impl RunningExecution<'_> {
+    /// Completes once the execution is cancelled
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+}
+
+impl Drop for RunningExecution<'_> {
+    fn drop(&mut self) {
+        self.executions.lock().remove(&self.execution_id);
+    }
+}
+
+impl RunningExecutions {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Execution>> {
+        self.executions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register `execution_id`, running `agents`, until the returned guard
+    /// is dropped
+    pub fn start(
+        &self,
+        execution_id: &str,
+        agents: &[String],
+        tools: &ToolRegistry,
+    ) -> Result<RunningExecution<'_>, String> {
+        let existing_sessions = tray::agent_sessions(tools).into_iter().collect();
+        let mut executions = self.lock();
+        if executions.contains_key(execution_id) {
+            return Err(format!("Execution {} is already running", execution_id));
+        }
+
+        let token = CancellationToken::new();
+        executions.insert(
+            execution_id.to_string(),
+            Execution {
+                token: token.clone(),
+                agents: agents.to_vec(),
+                existing_sessions,
+            },
+        );
+        Ok(RunningExecution {
+            executions: self,
+            execution_id: execution_id.to_string(),
+            token,
+        })
+    }
+
+    /// Whether `execution_id` is registered and not yet cancelled
+    #[cfg(test)]
+    pub fn is_running(&self, execution_id: &str) -> bool {
+        self.lock()
+            .get(execution_id)
+            .is_some_and(|execution| !execution.token.is_cancelled())
+    }
+
+    /// Cancel `execution_id` and kill the tmux sessions it started
+    ///
+    /// # Returns
+    /// * `Some(sessions)` - The sessions killed
+    /// * `None` - No such execution is running
+    pub fn cancel(&self, execution_id: &str, tools: &ToolRegistry) -> Option<Vec<String>> {
+        let mut executions = self.lock();
+        let execution = executions.get(execution_id)?;
+        if execution.token.is_cancelled() {
+            return None;
+        }
+        execution.token.cancel();
+
+        let sessions: Vec<String> = tray::agent_sessions(tools)
+            .into_iter()
+            .filter(|session| execution.owns_session(session))
+            .collect();
+        // Entries are removed by their guard; this one is done either way
+        executions.remove(execution_id);
+        drop(executions);
+
+        Some(
+            sessions
+                .into_iter()
+                .filter(|session| kill_session(tools, session))
+                .collect(),
+        )
+    }
+}
+
+/// Kill the tmux session named exactly `session`
+pub fn kill_session(tools: &ToolRegistry, session: &str) -> bool {
+    tools
+        .command("tmux")
+        .arg("kill-session")
+        .arg("-t")
+        .arg(format!("={}", session))
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::FakeTools;
+    use std::sync::Arc;
+
+    fn new_session(tools: &ToolRegistry, name: &str) {
+        tools
+            .command("tmux")
+            .args(["new-session", "-d", "-s", name])
+            .output()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_fires_token_and_kills_new_sessions() {
+        let fake = Arc::new(FakeTools::default());
+        let tools = ToolRegistry::with_runner(fake.clone());
+        new_session(&tools, "ait42-bug-fixer-100");
+
+        let executions = RunningExecutions::default();
+        let running = executions
+            .start("e1", &["bug-fixer".to_string()], &tools)
+            .unwrap();
+        assert!(executions.start("e1", &[], &tools).is_err());
+        new_session(&tools, "ait42-bug-fixer-200");
+        new_session(&tools, "ait42-reviewer-200");
+        assert!(executions.is_running("e1"));
+
+        let killed = executions.cancel("e1", &tools).unwrap();
+        assert_eq!(killed, vec!["ait42-bug-fixer-200"]);
+        running.cancelled().await;
+        assert!(!executions.is_running("e1"));
+        assert!(executions.cancel("e1", &tools).is_none());
+
+        let mut sessions = fake.sessions();
+        sessions.sort();
+        assert_eq!(sessions, vec!["ait42-bug-fixer-100", "ait42-reviewer-200"]);
+    }
+
+    #[test]
+    fn test_guard_unregisters_execution() {
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::default()));
+        let executions = RunningExecutions::default();
+        drop(executions.start("e1", &[], &tools).unwrap());
+        assert!(!executions.is_running("e1"));
+        assert!(executions.cancel("e1", &tools).is_none());
+    }
+}
@@ -0,0 +1,191 @@
+//! Feature Flags
+//!
+//! Experimental capabilities can be switched on or off at runtime through
+//! the `[features]` table of the config. Flags gate whole commands (calls to
+//! a gated command fail while its flag is off), checks inside shared
+//! commands, and UI panels, which the frontend hides based on
+//! `list_feature_flags`. The flags in effect when a session is created are
+//! recorded on it, so a run can be reproduced with the same features.
+
+use std::collections::BTreeMap;
+
+use ait42_config::Config;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{Invoke, Manager};
+
+use crate::state::AppState;
+
+/// Ensemble mode: an integration agent merges the results of parallel runs
+pub const ENSEMBLE_MODE: &str = "ensemble_mode";
+
+/// Observer mode: following and exporting the events of a running session
+pub const OBSERVER_MODE: &str = "observer_mode";
+
+/// Debugger panel driven by the Debug Adapter Protocol
+pub const DAP_DEBUGGING: &str = "dap_debugging";
+
+/// Experimental capability that can be switched on or off
+#[derive(Debug, Clone, Copy)]
+pub struct FeatureFlag {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Whether the feature is on when the config does not mention it
+    pub default: bool,
+    /// Commands that fail while the flag is off
+    pub commands: &'static [&'static str],
+}
+
+/// Every known flag
+pub const FLAGS: &[FeatureFlag] = &[
+    FeatureFlag {
+        name: ENSEMBLE_MODE,
+        description: "Ensemble mode: an integration agent merges the results of parallel runs",
+        default: true,
+        // Ensembles share their command with competitions, which check the flag
+        commands: &[],
+    },
+    FeatureFlag {
+        name: OBSERVER_MODE,
+        description: "Observer mode: follow and export the events of a running session",
+        default: true,
+        commands: &[
+            "export_session_events",
+            "stop_session_export",
+            "observe_session",
+            "get_observed_session",
+            "list_observed_sessions",
+            "stop_observing",
+        ],
+    },
+    FeatureFlag {
+        name: DAP_DEBUGGING,
+        description: "Debugger panel driven by the Debug Adapter Protocol",
+        default: false,
+        commands: &[],
+    },
+];
+
+/// State of a flag as shown to the user
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagState {
+    pub name: String,
+    pub description: String,
+    pub enabled: bool,
+    pub default: bool,
+}
+
+fn find(name: &str) -> Option<&'static FeatureFlag> {
+    FLAGS.iter().find(|flag| flag.name == name)
+}
+
+/// Whether the flag `name` is on; unknown flags are off
+pub fn enabled(config: &Config, name: &str) -> bool {
+    find(name).is_some_and(|flag| config.features.get(name).copied().unwrap_or(flag.default))
+}
+
+/// Fail with a readable error unless the flag `name` is on
+pub fn require(config: &Config, name: &str) -> Result<(), String> {
+    if enabled(config, name) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The {} feature is disabled. Enable it in Settings or under [features] in the config.",
+            name
+        ))
+    }
+}
+
+/// Every known flag with its current state
+pub fn list(config: &Config) -> Vec<FeatureFlagState> {
+    FLAGS
+        .iter()
+        .map(|flag| FeatureFlagState {
+            name: flag.name.to_string(),
+            description: flag.description.to_string(),
+            enabled: enabled(config, flag.name),
+            default: flag.default,
+        })
+        .collect()
+}
+
+/// Turn the flag `name` on or off
+pub fn set(config: &mut Config, name: &str, enabled: bool) -> Result<(), String> {
+    if find(name).is_none() {
+        return Err(format!("Unknown feature flag: {}", name));
+    }
+    config.features.insert(name.to_string(), enabled);
+    Ok(())
+}
+
+/// State of every known flag, recorded on sessions
+pub fn snapshot(config: &Config) -> BTreeMap<String, bool> {
+    FLAGS
+        .iter()
+        .map(|flag| (flag.name.to_string(), enabled(config, flag.name)))
+        .collect()
+}
+
+/// Flag gating `command`, if any
+pub fn gating_flag(command: &str) -> Option<&'static str> {
+    FLAGS
+        .iter()
+        .find(|flag| flag.commands.contains(&command))
+        .map(|flag| flag.name)
+}
+
+/// Wrap an invoke handler so calls to gated commands fail while their flag
+/// is off
+pub fn gate(
+    handler: impl Fn(Invoke) + Send + Sync + 'static,
+) -> impl Fn(Invoke) + Send + Sync + 'static {
+    move |invoke: Invoke| {
+        if let Some(flag) = gating_flag(invoke.message.command()) {
+            let allowed = {
+                let window = invoke.message.window();
+                let state = window.state::<AppState>();
+                let config = state.config.lock().unwrap_or_else(|e| e.into_inner());
+                require(&config, flag)
+            };
+            if let Err(e) = allowed {
+                invoke.resolver.reject(e);
+                return;
+            }
+        }
+        handler(invoke)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_overrides() {
+        let mut config = Config::default();
+        assert!(enabled(&config, ENSEMBLE_MODE));
+        assert!(!enabled(&config, DAP_DEBUGGING));
+        assert!(!enabled(&config, "unknown"));
+
+        set(&mut config, ENSEMBLE_MODE, false).unwrap();
+        set(&mut config, DAP_DEBUGGING, true).unwrap();
+        assert!(!enabled(&config, ENSEMBLE_MODE));
+        assert!(enabled(&config, DAP_DEBUGGING));
+        assert!(require(&config, ENSEMBLE_MODE)
+            .unwrap_err()
+            .contains("ensemble_mode"));
+        assert!(set(&mut config, "unknown", true).is_err());
+
+        let snapshot = snapshot(&config);
+        assert_eq!(snapshot.len(), FLAGS.len());
+        assert!(!snapshot[ENSEMBLE_MODE]);
+        assert!(snapshot[OBSERVER_MODE]);
+    }
+
+    #[test]
+    fn test_gating_flag() {
+        assert_eq!(gating_flag("observe_session"), Some(OBSERVER_MODE));
+        assert_eq!(gating_flag("open_file"), None);
+    }
+}
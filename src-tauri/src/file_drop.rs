@@ -0,0 +1,96 @@
+//! File Drop
+//!
+//! Handles files and folders dragged from the OS onto the main window.
+//! Dropped files are announced with [`FILES_DROPPED_EVENT`] so the editor
+//! opens a tab for each; a dropped folder is announced with
+//! [`WORKSPACE_DROPPED_EVENT`] so the editor can offer to switch to it with
+//! `select_workspace`. The paths themselves are not opened here, keeping
+//! buffers and tabs in step through the usual `open_file` path.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{FileDropEvent, GlobalWindowEvent, WindowEvent};
+
+/// Event carrying the files dropped onto the window
+pub const FILES_DROPPED_EVENT: &str = "files-dropped";
+
+/// Event carrying a folder dropped onto the window
+pub const WORKSPACE_DROPPED_EVENT: &str = "workspace-dropped";
+
+/// Dropped paths split into files and folders
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DroppedPaths {
+    pub files: Vec<String>,
+    pub directories: Vec<String>,
+}
+
+impl DroppedPaths {
+    /// Sort dropped paths into files and folders, skipping paths that no
+    /// longer exist
+    pub fn classify(paths: &[PathBuf]) -> Self {
+        let mut dropped = Self::default();
+        for path in paths {
+            let name = path.to_string_lossy().to_string();
+            if path.is_dir() {
+                dropped.directories.push(name);
+            } else if path.is_file() {
+                dropped.files.push(name);
+            }
+        }
+        dropped
+    }
+}
+
+/// Announce paths dropped onto the main window
+pub fn handle_window_event(event: &GlobalWindowEvent) {
+    let WindowEvent::FileDrop(FileDropEvent::Dropped(paths)) = event.event() else {
+        return;
+    };
+    let window = event.window();
+    if window.label() != "main" {
+        return;
+    }
+
+    let dropped = DroppedPaths::classify(paths);
+    tracing::info!(
+        "Dropped {} files and {} folders",
+        dropped.files.len(),
+        dropped.directories.len()
+    );
+    if !dropped.files.is_empty() {
+        if let Err(e) = window.emit(FILES_DROPPED_EVENT, &dropped.files) {
+            tracing::warn!("Failed to emit dropped files: {}", e);
+        }
+    }
+    // Only one workspace can be open, so only the first folder is offered
+    if let Some(directory) = dropped.directories.first() {
+        if let Err(e) = window.emit(WORKSPACE_DROPPED_EVENT, directory) {
+            tracing::warn!("Failed to emit dropped folder: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_splits_files_and_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        let folder = dir.path().join("project");
+        std::fs::write(&file, "fn main() {}").unwrap();
+        std::fs::create_dir(&folder).unwrap();
+
+        let dropped =
+            DroppedPaths::classify(&[file.clone(), folder.clone(), dir.path().join("missing.txt")]);
+        assert_eq!(
+            dropped,
+            DroppedPaths {
+                files: vec![file.to_string_lossy().to_string()],
+                directories: vec![folder.to_string_lossy().to_string()],
+            }
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Instance Logs
+//!
+//! Agents running in tmux have their pane piped to a log with `cat >>`,
+//! which grows for as long as the agent writes. [`LogTail`] reads only what
+//! was appended since its last read, seeking to where it stopped, and
+//! rotates the log once it passes [`MAX_LOG_BYTES`]: the log is copied to
+//! `<log>.1`, older copies shifting to `.2` and beyond up to
+//! [`ROTATED_LOGS`], and truncated in place, since the pipe keeps appending
+//! to the same file. Output written while the copy is truncated is lost.
+//!
+//! Once the agent finished, [`read_last`] reads the end of its output
+//! without loading the whole log and [`compress`] gzips the log and its
+//! rotated copies.
+
+use flate2::{write::GzEncoder, Compression};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// Size a log is rotated at
+pub const MAX_LOG_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Rotated copies kept of a log
+pub const ROTATED_LOGS: usize = 2;
+
+/// Copy `n` of the rotated log `path`, 1 being the most recent
+pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// Reads what is appended to a log, without splitting UTF-8 characters
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    pending: Vec<u8>,
+    max_bytes: u64,
+}
+
+impl LogTail {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            pending: Vec::new(),
+            max_bytes: MAX_LOG_BYTES,
+        }
+    }
+
+    /// Rotate the log at `max_bytes` instead of [`MAX_LOG_BYTES`]
+    #[cfg(test)]
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Output appended since the last read, empty if the log does not exist
+    pub async fn read(&mut self) -> String {
+        let Ok(mut file) = tokio::fs::File::open(&self.path).await else {
+            return String::new();
+        };
+        let len = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            // Truncated or replaced: start over
+            self.offset = 0;
+            self.pending.clear();
+        }
+        if len > self.offset && file.seek(SeekFrom::Start(self.offset)).await.is_ok() {
+            if let Ok(read) = file.read_to_end(&mut self.pending).await {
+                self.offset += read as u64;
+            }
+        }
+        if self.offset > self.max_bytes {
+            self.rotate().await;
+        }
+
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            // Keep an incomplete character at the end for the next read
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        let bytes: Vec<u8> = self.pending.drain(..complete).collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Copy the log to its first rotation and empty it
+    ///
+    /// Output appended after the last read is read from the copy.
+    async fn rotate(&mut self) {
+        for n in (1..ROTATED_LOGS).rev() {
+            let from = rotated_path(&self.path, n);
+            if tokio::fs::try_exists(&from).await.unwrap_or(false) {
+                if let Err(e) = tokio::fs::rename(&from, rotated_path(&self.path, n + 1)).await {
+                    tracing::warn!("Failed to rotate {}: {}", from.display(), e);
+                }
+            }
+        }
+
+        let rotated = rotated_path(&self.path, 1);
+        let copied = match tokio::fs::copy(&self.path, &rotated).await {
+            Ok(copied) => copied,
+            Err(e) => {
+                tracing::warn!("Failed to rotate {}: {}", self.path.display(), e);
+                return;
+            }
+        };
+        if copied > self.offset {
+            if let Ok(mut file) = tokio::fs::File::open(&rotated).await {
+                if file.seek(SeekFrom::Start(self.offset)).await.is_ok() {
+                    let _ = file.read_to_end(&mut self.pending).await;
+                }
+            }
+        }
+        let truncated = match tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&self.path)
+            .await
+        {
+            Ok(file) => file.set_len(0).await,
+            Err(e) => Err(e),
+        };
+        match truncated {
+            Ok(()) => {
+                tracing::debug!("Rotated {} at {} bytes", self.path.display(), copied);
+                self.offset = 0;
+            }
+            Err(e) => tracing::warn!("Failed to truncate {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// Last `max_bytes` of a file, fewer if it is smaller
+async fn read_end(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes)))
+        .await?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).await?;
+    Ok(bytes)
+}
+
+/// Last `max_bytes` of the output in the log `path`
+///
+/// Output rotated out recently is included when the log holds less. The
+/// result starts at a character boundary.
+pub async fn read_last(path: &Path, max_bytes: u64) -> std::io::Result<String> {
+    let mut bytes = read_end(path, max_bytes).await?;
+    let missing = max_bytes.saturating_sub(bytes.len() as u64);
+    if missing > 0 {
+        if let Ok(mut older) = read_end(&rotated_path(path, 1), missing).await {
+            older.append(&mut bytes);
+            bytes = older;
+        }
+    }
+    // Skip a character cut at the start
+    let start = bytes
+        .iter()
+        .take(3)
+        .take_while(|&&b| b & 0b1100_0000 == 0b1000_0000)
+        .count();
+    Ok(String::from_utf8_lossy(&bytes[start..]).into_owned())
+}
+
+/// Gzip `path` into `<path>.gz`, removing it
+fn gzip(path: &Path) -> std::io::Result<()> {
+    let mut compressed = path.as_os_str().to_os_string();
+    compressed.push(".gz");
+    let mut input = std::fs::File::open(path)?;
+    let mut encoder = GzEncoder::new(std::fs::File::create(compressed)?, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    std::fs::remove_file(path)
+}
+
+/// Gzip the log `path` of a finished agent and its rotated copies
+///
+/// Files that do not exist are skipped.
+pub async fn compress(path: &Path) -> std::io::Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let rotated = (1..=ROTATED_LOGS).map(|n| rotated_path(&path, n));
+        for file in std::iter::once(path.clone()).chain(rotated) {
+            if file.exists() {
+                gzip(&file)?;
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::{Read, Write};
+
+    fn append(path: &Path, text: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_log_tail_keeps_split_characters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.log");
+        let mut tail = LogTail::new(&path);
+        assert_eq!(tail.read().await, "");
+
+        let text = "résumé";
+        std::fs::write(&path, &text.as_bytes()[..2]).unwrap();
+        assert_eq!(tail.read().await, "r");
+        std::fs::write(&path, text).unwrap();
+        assert_eq!(tail.read().await, "ésumé");
+
+        std::fs::write(&path, "new").unwrap();
+        assert_eq!(tail.read().await, "new");
+    }
+
+    #[tokio::test]
+    async fn test_log_tail_rotates_large_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.log");
+        let mut tail = LogTail::new(&path).with_max_bytes(8);
+
+        append(&path, "one\n");
+        assert_eq!(tail.read().await, "one\n");
+        append(&path, "two\nthree\n");
+        assert_eq!(tail.read().await, "two\nthree\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 1)).unwrap(), "one\ntwo\nthree\n");
+
+        // Reading goes on in the emptied log; older copies shift
+        append(&path, "four\nfive\n");
+        assert_eq!(tail.read().await, "four\nfive\n");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 1)).unwrap(), "four\nfive\n");
+        assert!(rotated_path(&path, 2).exists());
+        append(&path, "six\nseven\n");
+        tail.read().await;
+        assert!(!rotated_path(&path, ROTATED_LOGS + 1).exists());
+    }
+
+    #[tokio::test]
+    async fn test_read_last() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.log");
+        assert!(read_last(&path, 8).await.is_err());
+
+        std::fs::write(&path, "héllo").unwrap();
+        assert_eq!(read_last(&path, 64).await.unwrap(), "héllo");
+        // The cut é is skipped
+        assert_eq!(read_last(&path, 4).await.unwrap(), "llo");
+
+        // The most recent rotation makes up for a short log
+        std::fs::write(rotated_path(&path, 1), "older\n").unwrap();
+        std::fs::write(&path, "new\n").unwrap();
+        assert_eq!(read_last(&path, 7).await.unwrap(), "er\nnew\n");
+    }
+
+    #[tokio::test]
+    async fn test_compress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.log");
+        std::fs::write(&path, "output\n".repeat(100)).unwrap();
+        std::fs::write(rotated_path(&path, 1), "older\n").unwrap();
+
+        compress(&path).await.unwrap();
+
+        assert!(!path.exists());
+        assert!(!rotated_path(&path, 1).exists());
+        let mut output = String::new();
+        GzDecoder::new(std::fs::File::open(dir.path().join("out.log.gz")).unwrap())
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, "output\n".repeat(100));
+        assert!(dir.path().join("out.log.1.gz").exists());
+    }
+}
@@ -0,0 +1,571 @@
+//! Issue Trackers
+//!
+//! Fetches issues from GitHub or Jira so one can be picked as the task of a
+//! competition, debate or agent run, and posts comments back to them (see
+//! `post_session_report`). Which repository or Jira project is used comes
+//! from the `[issues]` config; API tokens are read from `GITHUB_TOKEN` and
+//! `JIRA_API_TOKEN`, else from the OS keychain, where the editor stores
+//! them.
+
+use ait42_config::IssuesConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::session_crypto::KEYCHAIN_SERVICE;
+
+/// Issues listed at most per request
+const PAGE_SIZE: u32 = 50;
+
+/// Jira fields read for an issue
+const JIRA_FIELDS: &str = "summary,description,status,labels,updated";
+
+/// An issue tracker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueProvider {
+    Github,
+    Jira,
+}
+
+impl IssueProvider {
+    /// Environment variable holding the API token
+    pub fn token_env(self) -> &'static str {
+        match self {
+            IssueProvider::Github => "GITHUB_TOKEN",
+            IssueProvider::Jira => "JIRA_API_TOKEN",
+        }
+    }
+
+    fn keychain_account(self) -> &'static str {
+        match self {
+            IssueProvider::Github => "github-token",
+            IssueProvider::Jira => "jira-token",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            IssueProvider::Github => "GitHub",
+            IssueProvider::Jira => "Jira",
+        }
+    }
+}
+
+/// An issue, as shown in the picker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    pub provider: IssueProvider,
+    /// `#42` on GitHub, `PROJ-7` on Jira
+    pub key: String,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+    /// open, closed, or the name of the Jira status
+    pub state: String,
+    pub labels: Vec<String>,
+    pub updated_at: Option<String>,
+    /// Task description for a run working on this issue
+    pub task: String,
+}
+
+impl Issue {
+    /// Fill in `task` from the key, title, body and URL
+    fn with_task(mut self) -> Self {
+        let mut task = format!("{} {}", self.key, self.title);
+        if !self.body.trim().is_empty() {
+            task.push_str("\n\n");
+            task.push_str(self.body.trim());
+        }
+        task.push_str(&format!("\n\nIssue: {}", self.url));
+        self.task = task;
+        self
+    }
+}
+
+/// Issue a session works on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueLink {
+    pub provider: IssueProvider,
+    pub key: String,
+    pub title: String,
+    pub url: String,
+}
+
+/// API token of `provider`: the environment variable, else the keychain
+pub fn load_token(provider: IssueProvider) -> Result<Option<String>, String> {
+    if let Some(token) = std::env::var(provider.token_env())
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+    {
+        return Ok(Some(token));
+    }
+    match keychain_entry(provider)?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} token from OS keychain: {}", provider.label(), e)),
+    }
+}
+
+/// Store the API token of `provider` in the keychain, or remove it
+pub fn store_token(provider: IssueProvider, token: Option<&str>) -> Result<(), String> {
+    let entry = keychain_entry(provider)?;
+    let result = match token.map(str::trim).filter(|token| !token.is_empty()) {
+        Some(token) => entry.set_password(token),
+        None => match entry.delete_password() {
+            Err(keyring::Error::NoEntry) => Ok(()),
+            result => result,
+        },
+    };
+    result.map_err(|e| format!("Failed to store {} token in OS keychain: {}", provider.label(), e))
+}
+
+fn keychain_entry(provider: IssueProvider) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, provider.keychain_account())
+        .map_err(|e| format!("Failed to open OS keychain: {}", e))
+}
+
+/// GitHub repository to use: the configured one, else the workspace's
+/// origin remote (as from `Repository::remote_id`) if it is on github.com
+pub fn github_repo(config: &IssuesConfig, remote_id: Option<&str>) -> Option<String> {
+    config.github_repo.clone().or_else(|| {
+        remote_id?
+            .strip_prefix("github.com/")
+            .filter(|repo| repo.split('/').count() == 2)
+            .map(str::to_string)
+    })
+}
+
+/// Fetches and comments on issues of one tracker
+pub struct IssueClient {
+    provider: IssueProvider,
+    /// API base URL
+    endpoint: String,
+    /// `owner/name` on GitHub, the project key (if any) on Jira
+    project: Option<String>,
+    email: Option<String>,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl IssueClient {
+    /// Client for `provider` as set in `config`
+    ///
+    /// `remote_id` is the workspace's repository identity, used when no
+    /// GitHub repository is configured.
+    pub fn new(
+        provider: IssueProvider,
+        config: &IssuesConfig,
+        remote_id: Option<&str>,
+    ) -> Result<Self, String> {
+        let token = load_token(provider)?.ok_or_else(|| {
+            format!(
+                "No {} token: set {} or save one in the editor",
+                provider.label(),
+                provider.token_env()
+            )
+        })?;
+        let (endpoint, project) = match provider {
+            IssueProvider::Github => {
+                let repo = github_repo(config, remote_id).ok_or(
+                    "No GitHub repository: set [issues] github_repo or add a github.com origin",
+                )?;
+                (config.github_api_url.clone(), Some(repo))
+            }
+            IssueProvider::Jira => {
+                let url = config
+                    .jira_url
+                    .clone()
+                    .ok_or("No Jira site: set [issues] jira_url")?;
+                (url, config.jira_project.clone())
+            }
+        };
+        Ok(Self {
+            provider,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            project,
+            email: config.jira_email.clone(),
+            token,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Open issues, most recently updated first, optionally matching `query`
+    pub async fn list(&self, query: Option<&str>) -> Result<Vec<Issue>, String> {
+        let query = query.map(str::trim).filter(|q| !q.is_empty());
+        match self.provider {
+            IssueProvider::Github => {
+                let repo = self.github_project();
+                let Some(query) = query else {
+                    let url = format!("{}/repos/{}/issues", self.endpoint, repo);
+                    let per_page = PAGE_SIZE.to_string();
+                    let params = [
+                        ("state", "open"),
+                        ("sort", "updated"),
+                        ("per_page", per_page.as_str()),
+                    ];
+                    let body = self.send(self.http.get(&url).query(&params), &url).await?;
+                    return parse_github_issues(&body);
+                };
+                let url = format!("{}/search/issues", self.endpoint);
+                let q = format!("{} repo:{} is:issue is:open", query, repo);
+                let per_page = PAGE_SIZE.to_string();
+                let params = [
+                    ("q", q.as_str()),
+                    ("sort", "updated"),
+                    ("per_page", per_page.as_str()),
+                ];
+                let body = self.send(self.http.get(&url).query(&params), &url).await?;
+                let search: GithubSearch =
+                    serde_json::from_str(&body).map_err(|e| invalid_response(&url, e))?;
+                Ok(search
+                    .items
+                    .into_iter()
+                    .filter_map(GithubIssue::into_issue)
+                    .collect())
+            }
+            IssueProvider::Jira => {
+                let url = format!("{}/rest/api/2/search", self.endpoint);
+                let jql = jira_jql(self.project.as_deref(), query);
+                let max_results = PAGE_SIZE.to_string();
+                let params = [
+                    ("jql", jql.as_str()),
+                    ("maxResults", max_results.as_str()),
+                    ("fields", JIRA_FIELDS),
+                ];
+                let body = self.send(self.http.get(&url).query(&params), &url).await?;
+                parse_jira_issues(&self.endpoint, &body)
+            }
+        }
+    }
+
+    /// One issue, by `#42`/`42` on GitHub or `PROJ-7` on Jira
+    pub async fn get(&self, key: &str) -> Result<Issue, String> {
+        let url = self.issue_url(key)?;
+        match self.provider {
+            IssueProvider::Github => {
+                let body = self.send(self.http.get(&url), &url).await?;
+                let issue: GithubIssue =
+                    serde_json::from_str(&body).map_err(|e| invalid_response(&url, e))?;
+                issue
+                    .into_issue()
+                    .ok_or_else(|| format!("{} is a pull request, not an issue", key))
+            }
+            IssueProvider::Jira => {
+                let request = self.http.get(&url).query(&[("fields", JIRA_FIELDS)]);
+                let body = self.send(request, &url).await?;
+                let issue: JiraIssue =
+                    serde_json::from_str(&body).map_err(|e| invalid_response(&url, e))?;
+                Ok(issue.into_issue(&self.endpoint))
+            }
+        }
+    }
+
+    /// Comment `body` on the issue and return the comment's URL
+    pub async fn comment(&self, key: &str, body: &str) -> Result<String, String> {
+        let issue_url = self.issue_url(key)?;
+        let url = match self.provider {
+            IssueProvider::Github => format!("{}/comments", issue_url),
+            IssueProvider::Jira => format!("{}/comment", issue_url),
+        };
+        let request = self
+            .http
+            .post(&url)
+            .json(&serde_json::json!({ "body": body }));
+        let response = self.send(request, &url).await?;
+        let created: serde_json::Value =
+            serde_json::from_str(&response).map_err(|e| invalid_response(&url, e))?;
+        // GitHub links the comment; Jira only returns its ID
+        let comment_url = match self.provider {
+            IssueProvider::Github => created["html_url"].as_str().map(str::to_string),
+            IssueProvider::Jira => created["id"].as_str().map(|id| {
+                format!("{}/browse/{}?focusedCommentId={}", self.endpoint, key.trim(), id)
+            }),
+        };
+        tracing::info!("Commented on {} issue {}", self.provider.label(), key);
+        Ok(comment_url.unwrap_or_default())
+    }
+
+    fn github_project(&self) -> &str {
+        self.project.as_deref().unwrap_or_default()
+    }
+
+    /// API URL of the issue `key`
+    fn issue_url(&self, key: &str) -> Result<String, String> {
+        let key = key.trim();
+        match self.provider {
+            IssueProvider::Github => {
+                let number: u64 = key
+                    .trim_start_matches('#')
+                    .parse()
+                    .map_err(|_| format!("Invalid GitHub issue number: {}", key))?;
+                Ok(format!("{}/repos/{}/issues/{}", self.endpoint, self.github_project(), number))
+            }
+            IssueProvider::Jira => {
+                if !is_jira_key(key) {
+                    return Err(format!("Invalid Jira issue key: {}", key));
+                }
+                Ok(format!("{}/rest/api/2/issue/{}", self.endpoint, key))
+            }
+        }
+    }
+
+    /// Send `request` with credentials and return the response body
+    async fn send(&self, request: reqwest::RequestBuilder, url: &str) -> Result<String, String> {
+        let accept = match self.provider {
+            IssueProvider::Github => "application/vnd.github+json",
+            IssueProvider::Jira => "application/json",
+        };
+        let request = request
+            .header(reqwest::header::USER_AGENT, "ait42-editor")
+            .header(reqwest::header::ACCEPT, accept);
+        let request = match &self.email {
+            Some(email) if self.provider == IssueProvider::Jira => {
+                request.basic_auth(email, Some(&self.token))
+            }
+            _ => request.bearer_auth(&self.token),
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response of {}: {}", url, e))?;
+        if !status.is_success() {
+            return Err(format!(
+                "{} returned {}: {}",
+                self.provider.label(),
+                status,
+                body.chars().take(200).collect::<String>()
+            ));
+        }
+        Ok(body)
+    }
+}
+
+fn invalid_response(url: &str, error: serde_json::Error) -> String {
+    format!("Unexpected response from {}: {}", url, error)
+}
+
+/// Whether `key` looks like `PROJ-7`
+fn is_jira_key(key: &str) -> bool {
+    key.split_once('-').is_some_and(|(project, number)| {
+        !project.is_empty()
+            && project
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// JQL listing open issues of `project` (or assigned to the user), most
+/// recently updated first, optionally matching `query`
+fn jira_jql(project: Option<&str>, query: Option<&str>) -> String {
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut clauses = vec![match project {
+        Some(project) => format!("project = {}", quote(project)),
+        None => "assignee = currentUser()".to_string(),
+    }];
+    clauses.push("statusCategory != Done".to_string());
+    if let Some(query) = query {
+        clauses.push(format!("text ~ {}", quote(query)));
+    }
+    format!("{} ORDER BY updated DESC", clauses.join(" AND "))
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubIssue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    labels: Vec<GithubLabel>,
+    #[serde(default)]
+    updated_at: Option<String>,
+    /// Present on pull requests, which the issues API also returns
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubLabel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubSearch {
+    items: Vec<GithubIssue>,
+}
+
+impl GithubIssue {
+    fn into_issue(self) -> Option<Issue> {
+        if self.pull_request.is_some() {
+            return None;
+        }
+        Some(
+            Issue {
+                provider: IssueProvider::Github,
+                key: format!("#{}", self.number),
+                title: self.title,
+                body: self.body.unwrap_or_default(),
+                url: self.html_url,
+                state: self.state,
+                labels: self.labels.into_iter().map(|label| label.name).collect(),
+                updated_at: self.updated_at,
+                task: String::new(),
+            }
+            .with_task(),
+        )
+    }
+}
+
+/// Issues in a GitHub issue list, without pull requests
+fn parse_github_issues(body: &str) -> Result<Vec<Issue>, String> {
+    let issues: Vec<GithubIssue> =
+        serde_json::from_str(body).map_err(|e| format!("Unexpected GitHub response: {}", e))?;
+    Ok(issues
+        .into_iter()
+        .filter_map(GithubIssue::into_issue)
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraIssue {
+    key: String,
+    fields: JiraFields,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraFields {
+    summary: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    status: Option<JiraStatus>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    updated: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraStatus {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraSearch {
+    issues: Vec<JiraIssue>,
+}
+
+impl JiraIssue {
+    fn into_issue(self, site: &str) -> Issue {
+        Issue {
+            provider: IssueProvider::Jira,
+            url: format!("{}/browse/{}", site, self.key),
+            key: self.key,
+            title: self.fields.summary,
+            body: self.fields.description.unwrap_or_default(),
+            state: self
+                .fields
+                .status
+                .map(|status| status.name)
+                .unwrap_or_default(),
+            labels: self.fields.labels,
+            updated_at: self.fields.updated,
+            task: String::new(),
+        }
+        .with_task()
+    }
+}
+
+/// Issues in a Jira search result from `site`
+fn parse_jira_issues(site: &str, body: &str) -> Result<Vec<Issue>, String> {
+    let search: JiraSearch =
+        serde_json::from_str(body).map_err(|e| format!("Unexpected Jira response: {}", e))?;
+    Ok(search
+        .issues
+        .into_iter()
+        .map(|issue| issue.into_issue(site))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github_issues_skips_pull_requests() {
+        let body = r#"[
+            {"number": 42, "title": "Login fails", "body": "Steps:\n1. log in",
+             "html_url": "https://github.com/org/repo/issues/42", "state": "open",
+             "labels": [{"name": "bug"}], "updated_at": "2026-01-02T00:00:00Z"},
+            {"number": 43, "title": "Fix login", "body": null,
+             "html_url": "https://github.com/org/repo/pull/43", "state": "open",
+             "pull_request": {"url": "..."}}
+        ]"#;
+        let issues = parse_github_issues(body).unwrap();
+        assert_eq!(issues.len(), 1);
+        let issue = &issues[0];
+        assert_eq!(issue.key, "#42");
+        assert_eq!(issue.labels, ["bug"]);
+        assert_eq!(
+            issue.task,
+            "#42 Login fails\n\nSteps:\n1. log in\n\nIssue: https://github.com/org/repo/issues/42"
+        );
+        assert!(parse_github_issues("{}").is_err());
+    }
+
+    #[test]
+    fn test_parse_jira_issues() {
+        let body = r#"{"issues": [{"key": "PROJ-7", "fields": {
+            "summary": "Export is slow", "description": null,
+            "status": {"name": "In Progress"}, "labels": []}}]}"#;
+        let issues = parse_jira_issues("https://example.atlassian.net", body).unwrap();
+        assert_eq!(issues[0].key, "PROJ-7");
+        assert_eq!(issues[0].state, "In Progress");
+        assert_eq!(issues[0].url, "https://example.atlassian.net/browse/PROJ-7");
+        assert_eq!(
+            issues[0].task,
+            "PROJ-7 Export is slow\n\nIssue: https://example.atlassian.net/browse/PROJ-7"
+        );
+    }
+
+    #[test]
+    fn test_jira_jql_and_keys() {
+        assert_eq!(
+            jira_jql(Some("PROJ"), Some("say \"hi\"")),
+            "project = \"PROJ\" AND statusCategory != Done AND text ~ \"say \\\"hi\\\"\" \
+             ORDER BY updated DESC"
+        );
+        assert_eq!(
+            jira_jql(None, None),
+            "assignee = currentUser() AND statusCategory != Done ORDER BY updated DESC"
+        );
+        assert!(is_jira_key("PROJ-7"));
+        assert!(!is_jira_key("PROJ-"));
+        assert!(!is_jira_key("../x-1"));
+    }
+
+    #[test]
+    fn test_github_repo_falls_back_to_origin() {
+        let mut config = IssuesConfig::default();
+        assert_eq!(github_repo(&config, Some("github.com/org/repo")).as_deref(), Some("org/repo"));
+        assert_eq!(github_repo(&config, Some("gitlab.com/org/repo")), None);
+        assert_eq!(github_repo(&config, None), None);
+
+        config.github_repo = Some("other/repo".to_string());
+        assert_eq!(
+            github_repo(&config, Some("github.com/org/repo")).as_deref(),
+            Some("other/repo")
+        );
+    }
+}
@@ -4,10 +4,35 @@
 use tracing::info;
 
 mod ab_test;
+mod agent_output;
+mod budget;
+mod clipboard_history;
 mod commands;
+mod confirmation;
+mod edit_guard;
+mod executions;
+mod feature_flags;
+mod file_drop;
+mod injection;
+mod instance_log;
+mod issues;
+mod mcp;
+mod naming;
+mod notifications;
 mod optimizer;
+mod output_alerts;
 mod plugin;
+mod quick_task;
+mod remote;
+mod sanitize;
+mod session_crypto;
+mod session_journal;
+mod session_store;
+mod session_stream;
 mod state;
+mod sync;
+mod tools;
+mod tray;
 mod utils;
 
 use commands::optimizer::OptimizerState;
@@ -24,22 +49,34 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::create_file,
             commands::create_directory,
             commands::delete_path,
+            commands::request_confirmation,
             commands::rename_path,
             commands::read_directory,
             // Editor operations
             commands::insert_text,
             commands::delete_text,
             commands::replace_text,
+            commands::apply_content,
             commands::undo,
             commands::redo,
             commands::get_buffer_content,
             commands::get_buffer_info,
             commands::close_buffer,
             commands::list_buffers,
+            // Refactoring
+            commands::extract_variable,
+            commands::extract_function,
+            commands::ai_refactor,
             // LSP operations
             commands::start_lsp_server,
             commands::stop_lsp_server,
+            commands::restart_lsp_server,
             commands::get_running_lsp_servers,
+            commands::lsp_detach_buffer,
+            commands::lsp_attach_buffer,
+            commands::get_detached_lsp_buffers,
+            commands::get_lsp_watchdog_config,
+            commands::set_lsp_watchdog_config,
             commands::lsp_did_open,
             commands::lsp_did_change,
             commands::lsp_did_save,
@@ -48,6 +85,18 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::lsp_hover,
             commands::lsp_goto_definition,
             commands::lsp_diagnostics,
+            // Diagnostic quick actions
+            commands::diagnostic_quick_actions,
+            commands::explain_diagnostic,
+            // Notification center
+            commands::list_notifications,
+            commands::dismiss_notification,
+            commands::clear_notifications,
+            // Budget
+            commands::get_budget_status,
+            commands::get_budget_config,
+            commands::set_budget_config,
+            commands::approve_budget_override,
             // Git operations
             commands::git_status,
             commands::git_add,
@@ -60,6 +109,51 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::git_checkout,
             commands::git_create_branch,
             commands::git_init,
+            commands::git_stash_list,
+            commands::git_stash_push,
+            commands::git_stash_apply,
+            commands::git_stash_pop,
+            commands::git_stash_drop,
+            commands::git_stash_show,
+            commands::git_diff_hunks,
+            commands::git_stage_hunk,
+            commands::git_unstage_hunk,
+            commands::git_log_graph,
+            commands::git_blame,
+            commands::git_cherry_pick,
+            commands::git_revert,
+            commands::git_rebase_plan,
+            commands::git_rebase_start,
+            commands::git_operation_status,
+            commands::git_operation_continue,
+            commands::git_operation_skip,
+            commands::git_operation_abort,
+            commands::git_conflicted_files,
+            commands::git_load_conflicts,
+            commands::git_restore_conflict_base,
+            commands::git_resolve_conflicts,
+            commands::get_merge_conflicts,
+            commands::resolve_conflict,
+            // Annotation operations
+            commands::list_annotations,
+            commands::add_annotation,
+            commands::update_annotation,
+            commands::delete_annotation,
+            commands::get_annotation_context,
+            commands::list_todos,
+            commands::update_todos,
+            commands::workspace_search,
+            commands::generate_commit_message,
+            commands::generate_changelog,
+            commands::review_diff,
+            commands::get_review_report,
+            commands::clear_review,
+            // Checkpoint operations
+            commands::create_checkpoint,
+            commands::list_checkpoints,
+            commands::restore_checkpoint,
+            commands::get_checkpoint_config,
+            commands::set_checkpoint_config,
             // Git worktree operations
             commands::git_list_worktrees,
             commands::git_create_worktree,
@@ -75,10 +169,21 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             // AIT42 Agent operations
             commands::list_agents,
             commands::get_agent_info,
+            commands::recommend_agents,
+            commands::explain_execution_plan,
+            commands::run_auto_task,
+            commands::submit_quick_task,
+            commands::dismiss_quick_task,
+            commands::get_quick_task_shortcut,
+            commands::set_quick_task_shortcut,
+            commands::get_agent_stats,
             commands::execute_agent,
             commands::execute_parallel,
             commands::get_agent_output,
+            commands::subscribe_agent_output,
+            commands::ack_agent_output,
             commands::cancel_agent_execution,
+            commands::list_agent_alerts,
             // AIT42 Tmux operations
             commands::create_tmux_session,
             commands::list_tmux_sessions,
@@ -90,6 +195,16 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::execute_multi_runtime_competition,
             commands::get_competition_status,
             commands::cancel_competition,
+            commands::score_competition,
+            commands::summarize_competition,
+            commands::merge_competition_winner,
+            // Issue trackers
+            commands::get_issue_trackers,
+            commands::set_issue_tracker_token,
+            commands::list_issues,
+            commands::get_issue,
+            commands::link_session_issue,
+            commands::post_session_report,
             // AIT42 Debate operations
             commands::execute_debate,
             commands::get_debate_status,
@@ -102,6 +217,7 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::delete_worktree,
             commands::get_file_diff,
             commands::get_worktree_diff,
+            commands::get_semantic_diff,
             // Terminal operations
             commands::execute_command,
             commands::get_terminal_output,
@@ -115,24 +231,66 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::optimize_task,
             commands::calculate_instances,
             commands::get_complexity_info,
+            commands::get_estimate_cache_stats,
+            commands::get_llm_provider_config,
+            commands::set_llm_provider_config,
             // A/B Testing operations (v1.6.0)
             commands::run_ab_test,
             // Session History operations (v1.6.0)
             commands::create_session,
             commands::update_session,
             commands::get_session,
+            commands::get_session_journal,
+            commands::get_session_agent_changes,
             commands::get_all_sessions,
             commands::delete_session,
+            commands::list_deleted_sessions,
+            commands::restore_session,
+            commands::purge_sessions,
+            commands::get_sessions_config,
+            commands::set_sessions_config,
+            commands::encrypt_sessions,
+            commands::migrate_workspace,
+            commands::sync_sessions,
+            commands::get_team_feed,
+            commands::get_team_artifact,
+            commands::check_for_updates,
+            commands::install_update,
+            commands::get_updates_config,
+            commands::set_updates_config,
+            commands::list_feature_flags,
+            commands::set_feature_flag,
             commands::add_chat_message,
             commands::update_instance_status,
+            // Observer mode
+            commands::export_session_events,
+            commands::stop_session_export,
+            commands::observe_session,
+            commands::get_observed_session,
+            commands::list_observed_sessions,
+            commands::stop_observing,
+            // Remote control
+            commands::get_remote_status,
+            commands::start_remote_server,
+            commands::stop_remote_server,
+            // MCP server
+            commands::get_mcp_status,
+            commands::start_mcp_server,
+            commands::stop_mcp_server,
             // Workspace operations
             commands::select_workspace,
             commands::get_workspace,
+            // API schema
+            commands::get_api_schema,
             // System integration operations
             commands::open_in_vscode,
             commands::open_terminal,
             commands::open_in_finder,
-            commands::copy_to_clipboard
+            commands::copy_to_clipboard,
+            commands::get_clipboard_history,
+            commands::push_clipboard_history,
+            commands::remove_clipboard_entry,
+            commands::clear_clipboard_history
         ]
     }
 
@@ -145,22 +303,34 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::create_file,
             commands::create_directory,
             commands::delete_path,
+            commands::request_confirmation,
             commands::rename_path,
             commands::read_directory,
             // Editor operations
             commands::insert_text,
             commands::delete_text,
             commands::replace_text,
+            commands::apply_content,
             commands::undo,
             commands::redo,
             commands::get_buffer_content,
             commands::get_buffer_info,
             commands::close_buffer,
             commands::list_buffers,
+            // Refactoring
+            commands::extract_variable,
+            commands::extract_function,
+            commands::ai_refactor,
             // LSP operations
             commands::start_lsp_server,
             commands::stop_lsp_server,
+            commands::restart_lsp_server,
             commands::get_running_lsp_servers,
+            commands::lsp_detach_buffer,
+            commands::lsp_attach_buffer,
+            commands::get_detached_lsp_buffers,
+            commands::get_lsp_watchdog_config,
+            commands::set_lsp_watchdog_config,
             commands::lsp_did_open,
             commands::lsp_did_change,
             commands::lsp_did_save,
@@ -169,6 +339,18 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::lsp_hover,
             commands::lsp_goto_definition,
             commands::lsp_diagnostics,
+            // Diagnostic quick actions
+            commands::diagnostic_quick_actions,
+            commands::explain_diagnostic,
+            // Notification center
+            commands::list_notifications,
+            commands::dismiss_notification,
+            commands::clear_notifications,
+            // Budget
+            commands::get_budget_status,
+            commands::get_budget_config,
+            commands::set_budget_config,
+            commands::approve_budget_override,
             // Git operations
             commands::git_status,
             commands::git_add,
@@ -181,6 +363,51 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::git_checkout,
             commands::git_create_branch,
             commands::git_init,
+            commands::git_stash_list,
+            commands::git_stash_push,
+            commands::git_stash_apply,
+            commands::git_stash_pop,
+            commands::git_stash_drop,
+            commands::git_stash_show,
+            commands::git_diff_hunks,
+            commands::git_stage_hunk,
+            commands::git_unstage_hunk,
+            commands::git_log_graph,
+            commands::git_blame,
+            commands::git_cherry_pick,
+            commands::git_revert,
+            commands::git_rebase_plan,
+            commands::git_rebase_start,
+            commands::git_operation_status,
+            commands::git_operation_continue,
+            commands::git_operation_skip,
+            commands::git_operation_abort,
+            commands::git_conflicted_files,
+            commands::git_load_conflicts,
+            commands::git_restore_conflict_base,
+            commands::git_resolve_conflicts,
+            commands::get_merge_conflicts,
+            commands::resolve_conflict,
+            // Annotation operations
+            commands::list_annotations,
+            commands::add_annotation,
+            commands::update_annotation,
+            commands::delete_annotation,
+            commands::get_annotation_context,
+            commands::list_todos,
+            commands::update_todos,
+            commands::workspace_search,
+            commands::generate_commit_message,
+            commands::generate_changelog,
+            commands::review_diff,
+            commands::get_review_report,
+            commands::clear_review,
+            // Checkpoint operations
+            commands::create_checkpoint,
+            commands::list_checkpoints,
+            commands::restore_checkpoint,
+            commands::get_checkpoint_config,
+            commands::set_checkpoint_config,
             // Git worktree operations
             commands::git_list_worktrees,
             commands::git_create_worktree,
@@ -196,10 +423,21 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             // AIT42 Agent operations
             commands::list_agents,
             commands::get_agent_info,
+            commands::recommend_agents,
+            commands::explain_execution_plan,
+            commands::run_auto_task,
+            commands::submit_quick_task,
+            commands::dismiss_quick_task,
+            commands::get_quick_task_shortcut,
+            commands::set_quick_task_shortcut,
+            commands::get_agent_stats,
             commands::execute_agent,
             commands::execute_parallel,
             commands::get_agent_output,
+            commands::subscribe_agent_output,
+            commands::ack_agent_output,
             commands::cancel_agent_execution,
+            commands::list_agent_alerts,
             // AIT42 Tmux operations
             commands::create_tmux_session,
             commands::list_tmux_sessions,
@@ -211,6 +449,16 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::execute_multi_runtime_competition,
             commands::get_competition_status,
             commands::cancel_competition,
+            commands::score_competition,
+            commands::summarize_competition,
+            commands::merge_competition_winner,
+            // Issue trackers
+            commands::get_issue_trackers,
+            commands::set_issue_tracker_token,
+            commands::list_issues,
+            commands::get_issue,
+            commands::link_session_issue,
+            commands::post_session_report,
             // AIT42 Debate operations
             commands::execute_debate,
             commands::get_debate_status,
@@ -225,24 +473,66 @@ fn generate_handler() -> impl Fn(tauri::Invoke) + Send + Sync + 'static {
             commands::optimize_task,
             commands::calculate_instances,
             commands::get_complexity_info,
+            commands::get_estimate_cache_stats,
+            commands::get_llm_provider_config,
+            commands::set_llm_provider_config,
             // A/B Testing operations (v1.6.0)
             commands::run_ab_test,
             // Session History operations (v1.6.0)
             commands::create_session,
             commands::update_session,
             commands::get_session,
+            commands::get_session_journal,
+            commands::get_session_agent_changes,
             commands::get_all_sessions,
             commands::delete_session,
+            commands::list_deleted_sessions,
+            commands::restore_session,
+            commands::purge_sessions,
+            commands::get_sessions_config,
+            commands::set_sessions_config,
+            commands::encrypt_sessions,
+            commands::migrate_workspace,
+            commands::sync_sessions,
+            commands::get_team_feed,
+            commands::get_team_artifact,
+            commands::check_for_updates,
+            commands::install_update,
+            commands::get_updates_config,
+            commands::set_updates_config,
+            commands::list_feature_flags,
+            commands::set_feature_flag,
             commands::add_chat_message,
             commands::update_instance_status,
+            // Observer mode
+            commands::export_session_events,
+            commands::stop_session_export,
+            commands::observe_session,
+            commands::get_observed_session,
+            commands::list_observed_sessions,
+            commands::stop_observing,
+            // Remote control
+            commands::get_remote_status,
+            commands::start_remote_server,
+            commands::stop_remote_server,
+            // MCP server
+            commands::get_mcp_status,
+            commands::start_mcp_server,
+            commands::stop_mcp_server,
             // Workspace operations
             commands::select_workspace,
             commands::get_workspace,
+            // API schema
+            commands::get_api_schema,
             // System integration operations
             commands::open_in_vscode,
             commands::open_terminal,
             commands::open_in_finder,
-            commands::copy_to_clipboard
+            commands::copy_to_clipboard,
+            commands::get_clipboard_history,
+            commands::push_clipboard_history,
+            commands::remove_clipboard_entry,
+            commands::clear_clipboard_history
         ]
     }
 }
@@ -272,7 +562,9 @@ fn main() {
 
     info!("Working directory set to: {}", working_dir.display());
 
+    let started = std::time::Instant::now();
     let app_state = AppState::new(working_dir).expect("Failed to initialize application state");
+    info!("Application state ready in {:?}", started.elapsed());
 
     // Initialize optimizer state (lazy initialization on first use)
     let optimizer_state = OptimizerState::new();
@@ -280,8 +572,23 @@ fn main() {
     tauri::Builder::default()
         .manage(app_state)
         .manage(optimizer_state)
-        .invoke_handler(generate_handler())
-        .setup(|_app| {
+        .invoke_handler(feature_flags::gate(generate_handler()))
+        .system_tray(tray::build())
+        .on_system_tray_event(tray::handle_event)
+        .on_window_event(|event| {
+            file_drop::handle_window_event(&event);
+            tray::handle_window_event(event);
+        })
+        .setup(|app| {
+            tauri::async_runtime::spawn(commands::run_lsp_watchdog(app.handle()));
+            tauri::async_runtime::spawn(commands::run_checkpoint_timer(app.handle()));
+            commands::init_session_encryption(&app.handle());
+            tauri::async_runtime::spawn(commands::run_session_retention(app.handle()));
+            tauri::async_runtime::spawn(tray::run_monitor(app.handle()));
+            quick_task::init(&app.handle());
+            tauri::async_runtime::spawn(commands::check_on_startup(app.handle()));
+            commands::start_remote_on_startup(&app.handle());
+            commands::start_mcp_on_startup(&app.handle());
             info!("AIT42 Editor GUI initialized successfully");
             Ok(())
         })
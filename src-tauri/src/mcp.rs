@@ -0,0 +1,733 @@
+//! MCP Server
+//!
+//! Model Context Protocol server through which external agents use the
+//! editor as a context and action provider. It speaks JSON-RPC 2.0 over the
+//! Streamable HTTP transport: each message is POSTed to `/mcp` and answered
+//! with a JSON response, or `202 Accepted` for notifications. Clients
+//! authenticate with `Authorization: Bearer <token>`, and requests from web
+//! pages on other hosts are refused.
+//!
+//! Resources:
+//! - `ait42://buffers/<id>` - content of an open buffer
+//! - `ait42://diagnostics` - diagnostics of the open files, by path
+//! - `ait42://git/status` - `git status` of the workspace
+//! - `ait42://sessions` - competition, ensemble and debate sessions
+//!
+//! Tools:
+//! - `apply_edit` - replace a byte range of an open buffer; risky edits are
+//!   only applied once the user approves them (see `edit_guard`)
+//! - `run_task` - run an agent on a task and return its output
+//!
+//! The editor side of each resource and tool is provided by an
+//! [`McpBackend`], so the protocol can be tested without a running app.
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+use crate::remote::tokens_match;
+
+/// Environment variable holding the token, taking precedence over the config
+pub const TOKEN_ENV: &str = "AIT42_MCP_TOKEN";
+
+/// Protocol versions understood, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// MCP: the requested resource does not exist
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// Editor context offered as an MCP resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resource {
+    Buffer(String),
+    Diagnostics,
+    GitStatus,
+    Sessions,
+}
+
+impl Resource {
+    pub fn uri(&self) -> String {
+        match self {
+            Self::Buffer(id) => format!("ait42://buffers/{}", id),
+            Self::Diagnostics => "ait42://diagnostics".to_string(),
+            Self::GitStatus => "ait42://git/status".to_string(),
+            Self::Sessions => "ait42://sessions".to_string(),
+        }
+    }
+
+    pub fn parse(uri: &str) -> Option<Self> {
+        match uri.strip_prefix("ait42://")? {
+            "diagnostics" => Some(Self::Diagnostics),
+            "git/status" => Some(Self::GitStatus),
+            "sessions" => Some(Self::Sessions),
+            path => path
+                .strip_prefix("buffers/")
+                .filter(|id| !id.is_empty())
+                .map(|id| Self::Buffer(id.to_string())),
+        }
+    }
+}
+
+/// Resource as listed to clients
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceInfo {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub mime_type: String,
+}
+
+/// Content of a resource
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceContent {
+    pub mime_type: String,
+    pub text: String,
+}
+
+impl ResourceContent {
+    pub fn text(text: String) -> Self {
+        Self {
+            mime_type: "text/plain".to_string(),
+            text,
+        }
+    }
+
+    pub fn json(value: &impl Serialize) -> Result<Self, String> {
+        Ok(Self {
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string_pretty(value).map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+/// Arguments of the `apply_edit` tool
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+pub struct ApplyEditArgs {
+    /// ID of an open buffer, as in `ait42://buffers/<id>`
+    pub buffer_id: String,
+    /// Byte offset where the replaced range starts
+    pub start: usize,
+    /// Byte offset where the replaced range ends; equal to `start` to insert
+    pub end: usize,
+    /// Text to put in place of the range
+    pub text: String,
+}
+
+/// Arguments of the `run_task` tool
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+pub struct RunTaskArgs {
+    /// Agent to run, as listed by the editor
+    pub agent_name: String,
+    /// What the agent should do
+    pub task: String,
+    /// Additional context for the agent
+    #[serde(default)]
+    pub context: Option<String>,
+}
+
+/// Call of one of the tools
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "name", content = "arguments", rename_all = "snake_case")]
+pub enum ToolCall {
+    ApplyEdit(ApplyEditArgs),
+    RunTask(RunTaskArgs),
+}
+
+/// Editor side of the resources and tools
+#[async_trait]
+pub trait McpBackend: Send + Sync {
+    /// Open buffers
+    async fn buffers(&self) -> Result<Vec<ResourceInfo>, String>;
+
+    async fn read(&self, resource: &Resource) -> Result<ResourceContent, String>;
+
+    /// Run a tool, returning its output
+    async fn call(&self, call: ToolCall) -> Result<String, String>;
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcMessage {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InitializeParams {
+    #[serde(default)]
+    protocol_version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReadParams {
+    uri: String,
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn static_resources() -> Vec<ResourceInfo> {
+    let resource =
+        |resource: Resource, name: &str, description: &str, mime_type: &str| ResourceInfo {
+            uri: resource.uri(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            mime_type: mime_type.to_string(),
+        };
+    vec![
+        resource(
+            Resource::Diagnostics,
+            "Diagnostics",
+            "Language server and code review diagnostics of the open files, by path",
+            "application/json",
+        ),
+        resource(
+            Resource::GitStatus,
+            "Git status",
+            "Branch and changed files of the workspace",
+            "text/plain",
+        ),
+        resource(
+            Resource::Sessions,
+            "Sessions",
+            "Competition, ensemble and debate sessions of the workspace",
+            "application/json",
+        ),
+    ]
+}
+
+fn tool<T: JsonSchema>(name: &str, description: &str) -> Value {
+    let schema = schemars::gen::SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<T>();
+    json!({ "name": name, "description": description, "inputSchema": schema })
+}
+
+fn tools() -> Vec<Value> {
+    vec![
+        tool::<ApplyEditArgs>("apply_edit", "Replace a byte range of an open buffer with new text"),
+        tool::<RunTaskArgs>("run_task", "Run an AIT42 agent on a task and return its output"),
+    ]
+}
+
+fn initialize(params: Value) -> Result<Value, RpcError> {
+    let params: InitializeParams = parse_params(params)?;
+    // Answer in the client's version if supported, else in the newest one
+    let version = params
+        .protocol_version
+        .as_deref()
+        .and_then(|requested| PROTOCOL_VERSIONS.iter().find(|v| **v == requested))
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    Ok(json!({
+        "protocolVersion": version,
+        "capabilities": { "resources": {}, "tools": {} },
+        "serverInfo": { "name": "ait42-editor", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Read the open buffers, diagnostics, git status and sessions of the \
+            AIT42 editor as resources; edit buffers and run agents with the tools.",
+    }))
+}
+
+async fn dispatch(
+    backend: &dyn McpBackend,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    let internal = |e: String| RpcError::new(INTERNAL_ERROR, e);
+    match method {
+        "initialize" => initialize(params),
+        "ping" => Ok(json!({})),
+        "resources/list" => {
+            let mut resources = static_resources();
+            resources.extend(backend.buffers().await.map_err(internal)?);
+            Ok(json!({ "resources": resources }))
+        }
+        "resources/templates/list" => Ok(json!({
+            "resourceTemplates": [{
+                "uriTemplate": "ait42://buffers/{bufferId}",
+                "name": "Buffer",
+                "description": "Content of an open buffer",
+                "mimeType": "text/plain",
+            }],
+        })),
+        "resources/read" => {
+            let params: ReadParams = parse_params(params)?;
+            let resource = Resource::parse(&params.uri).ok_or_else(|| {
+                RpcError::new(RESOURCE_NOT_FOUND, format!("Resource not found: {}", params.uri))
+            })?;
+            let content = backend.read(&resource).await.map_err(internal)?;
+            Ok(json!({
+                "contents": [{
+                    "uri": params.uri,
+                    "mimeType": content.mime_type,
+                    "text": content.text,
+                }],
+            }))
+        }
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let call: ToolCall = parse_params(params)?;
+            // Failures of the tool itself are reported to the model, not as
+            // protocol errors
+            let (text, is_error) = match backend.call(call).await {
+                Ok(output) => (output, false),
+                Err(e) => (e, true),
+            };
+            Ok(json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error,
+            }))
+        }
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    }
+}
+
+async fn handle_one(backend: &dyn McpBackend, message: Value) -> Option<Value> {
+    let message: RpcMessage = match serde_json::from_value(message) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, INVALID_REQUEST, &e.to_string())),
+    };
+    // Responses to requests of ours (we send none) need no reply
+    let method = message.method?;
+    // Notifications, such as `notifications/initialized`, get no reply
+    let id = message.id?;
+    if message.jsonrpc != "2.0" {
+        return Some(error_response(id, INVALID_REQUEST, "Only JSON-RPC 2.0 is supported"));
+    }
+
+    Some(match dispatch(backend, &method, message.params).await {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error_response(id, e.code, &e.message),
+    })
+}
+
+/// Handle a JSON-RPC message or batch, returning the reply if one is due
+pub async fn handle_message(backend: &dyn McpBackend, message: Value) -> Option<Value> {
+    match message {
+        Value::Array(batch) if batch.is_empty() => {
+            Some(error_response(Value::Null, INVALID_REQUEST, "Empty batch"))
+        }
+        Value::Array(batch) => {
+            let mut replies = Vec::new();
+            for message in batch {
+                replies.extend(handle_one(backend, message).await);
+            }
+            (!replies.is_empty()).then_some(Value::Array(replies))
+        }
+        message => handle_one(backend, message).await,
+    }
+}
+
+/// State of the server as shown to the user
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct McpStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    /// Endpoint to configure in MCP clients
+    pub url: Option<String>,
+    /// Token clients must present, while running
+    pub token: Option<String>,
+}
+
+struct Endpoint {
+    backend: Arc<dyn McpBackend>,
+    token: String,
+}
+
+struct Running {
+    address: SocketAddr,
+    token: String,
+    task: tauri::async_runtime::JoinHandle<()>,
+    /// Dropped on stop, which shuts the server down
+    _shutdown: watch::Sender<()>,
+}
+
+/// The MCP server, while running
+#[derive(Default)]
+pub struct McpServer {
+    server: Mutex<Option<Running>>,
+}
+
+impl McpServer {
+    /// Listen on `bind` for clients presenting `token`
+    pub fn start(
+        &self,
+        bind: &str,
+        token: String,
+        backend: Arc<dyn McpBackend>,
+    ) -> Result<McpStatus, String> {
+        let mut server = self.server.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = server.as_ref() {
+            return Err(format!("MCP server is already listening on {}", running.address));
+        }
+
+        let listener = std::net::TcpListener::bind(bind)
+            .and_then(|listener| {
+                listener.set_nonblocking(true)?;
+                Ok(listener)
+            })
+            .map_err(|e| format!("Failed to listen on {}: {}", bind, e))?;
+        let address = listener.local_addr().map_err(|e| e.to_string())?;
+        if !address.ip().is_loopback() {
+            tracing::warn!("MCP server is reachable from the network on {}", address);
+        }
+
+        let endpoint = Arc::new(Endpoint {
+            backend,
+            token: token.clone(),
+        });
+        let router = Router::new()
+            .route("/mcp", post(handle_post))
+            .with_state(endpoint);
+        let (shutdown, mut stopped) = watch::channel(());
+        let task = tauri::async_runtime::spawn(async move {
+            let listener = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::error!("Failed to start MCP server: {}", e);
+                    return;
+                }
+            };
+            let stopped = async move {
+                let _ = stopped.changed().await;
+            };
+            if let Err(e) = axum::serve(listener, router)
+                .with_graceful_shutdown(stopped)
+                .await
+            {
+                tracing::error!("MCP server failed: {}", e);
+            }
+        });
+        tracing::info!("MCP server listening on {}", address);
+        *server = Some(Running {
+            address,
+            token,
+            task,
+            _shutdown: shutdown,
+        });
+        drop(server);
+        Ok(self.status())
+    }
+
+    /// Stop the server
+    pub fn stop(&self) -> bool {
+        match self.server.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(server) => {
+                server.task.abort();
+                tracing::info!("MCP server on {} stopped", server.address);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn status(&self) -> McpStatus {
+        match self
+            .server
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            Some(server) => McpStatus {
+                running: true,
+                address: Some(server.address.to_string()),
+                url: Some(format!("http://{}/mcp", server.address)),
+                token: Some(server.token.clone()),
+            },
+            None => McpStatus {
+                running: false,
+                address: None,
+                url: None,
+                token: None,
+            },
+        }
+    }
+}
+
+/// Whether a request from `origin` may be served
+///
+/// Clients other than browsers send no origin. Pages on other hosts are
+/// refused, so a website cannot reach the server through DNS rebinding.
+fn is_local_origin(origin: Option<&HeaderValue>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    let Some(authority) = origin.to_str().ok().and_then(|o| o.split("://").nth(1)) else {
+        return false;
+    };
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+async fn handle_post(
+    State(endpoint): State<Arc<Endpoint>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !is_local_origin(headers.get(header::ORIGIN)) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !bearer.is_some_and(|given| tokens_match(given, &endpoint.token)) {
+        return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response();
+    }
+
+    let message: Value = match serde_json::from_slice(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            let error = error_response(Value::Null, PARSE_ERROR, &e.to_string());
+            return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+        }
+    };
+    match handle_message(endpoint.backend.as_ref(), message).await {
+        Some(reply) => Json(reply).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[derive(Default)]
+    struct FakeBackend {
+        calls: Mutex<Vec<ToolCall>>,
+    }
+
+    #[async_trait]
+    impl McpBackend for FakeBackend {
+        async fn buffers(&self) -> Result<Vec<ResourceInfo>, String> {
+            Ok(vec![ResourceInfo {
+                uri: Resource::Buffer("b1".to_string()).uri(),
+                name: "main.rs".to_string(),
+                description: None,
+                mime_type: "text/plain".to_string(),
+            }])
+        }
+
+        async fn read(&self, resource: &Resource) -> Result<ResourceContent, String> {
+            match resource {
+                Resource::Buffer(id) if id == "b1" => {
+                    Ok(ResourceContent::text("fn main() {}".into()))
+                }
+                Resource::Buffer(id) => Err(format!("Buffer not found: {}", id)),
+                _ => ResourceContent::json(&json!([])),
+            }
+        }
+
+        async fn call(&self, call: ToolCall) -> Result<String, String> {
+            self.calls.lock().unwrap().push(call.clone());
+            match call {
+                ToolCall::ApplyEdit(_) => Ok("Edited".to_string()),
+                ToolCall::RunTask(run) => Err(format!("Agent not found: {}", run.agent_name)),
+            }
+        }
+    }
+
+    async fn request(backend: &FakeBackend, method: &str, params: Value) -> Value {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        handle_message(backend, message).await.unwrap()
+    }
+
+    #[test]
+    fn test_resource_uris() {
+        for resource in [
+            Resource::Buffer("b1".to_string()),
+            Resource::Diagnostics,
+            Resource::GitStatus,
+            Resource::Sessions,
+        ] {
+            assert_eq!(Resource::parse(&resource.uri()), Some(resource));
+        }
+        assert_eq!(Resource::parse("ait42://buffers/"), None);
+        assert_eq!(Resource::parse("file:///etc/passwd"), None);
+    }
+
+    #[test]
+    fn test_local_origins() {
+        let origin = |o: &'static str| Some(HeaderValue::from_static(o));
+        assert!(is_local_origin(None));
+        assert!(is_local_origin(origin("http://localhost:1420").as_ref()));
+        assert!(is_local_origin(origin("tauri://localhost").as_ref()));
+        assert!(is_local_origin(origin("http://[::1]:8080").as_ref()));
+        assert!(!is_local_origin(origin("https://evil.example").as_ref()));
+        assert!(!is_local_origin(origin("http://localhost.evil.example").as_ref()));
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_list() {
+        let backend = FakeBackend::default();
+        let reply =
+            request(&backend, "initialize", json!({ "protocolVersion": "2024-11-05" })).await;
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        assert!(reply["result"]["capabilities"]["tools"].is_object());
+        let reply =
+            request(&backend, "initialize", json!({ "protocolVersion": "1999-01-01" })).await;
+        assert_eq!(reply["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+        let reply = request(&backend, "resources/list", Value::Null).await;
+        let uris: Vec<&str> = reply["result"]["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["uri"].as_str().unwrap())
+            .collect();
+        assert!(uris.contains(&"ait42://git/status"));
+        assert!(uris.contains(&"ait42://buffers/b1"));
+
+        let reply = request(&backend, "tools/list", Value::Null).await;
+        let tools = reply["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools[0]["name"], "apply_edit");
+        assert_eq!(tools[0]["inputSchema"]["type"], "object");
+        assert!(tools[1]["inputSchema"]["properties"]["agent_name"].is_object());
+
+        // Notifications and unknown methods
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_message(&backend, notification).await.is_none());
+        let reply = request(&backend, "sampling/createMessage", Value::Null).await;
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_read_and_call() {
+        let backend = FakeBackend::default();
+        let reply =
+            request(&backend, "resources/read", json!({ "uri": "ait42://buffers/b1" })).await;
+        assert_eq!(reply["result"]["contents"][0]["text"], "fn main() {}");
+        let reply = request(&backend, "resources/read", json!({ "uri": "ait42://nope" })).await;
+        assert_eq!(reply["error"]["code"], RESOURCE_NOT_FOUND);
+
+        let edit = json!({
+            "name": "apply_edit",
+            "arguments": { "buffer_id": "b1", "start": 0, "end": 2, "text": "pub fn" },
+        });
+        let reply = request(&backend, "tools/call", edit).await;
+        assert_eq!(reply["result"]["content"][0]["text"], "Edited");
+        assert_eq!(reply["result"]["isError"], false);
+
+        let run = json!({ "name": "run_task", "arguments": { "agent_name": "x", "task": "t" } });
+        let reply = request(&backend, "tools/call", run).await;
+        assert_eq!(reply["result"]["isError"], true);
+
+        let unknown = json!({ "name": "rm_rf", "arguments": {} });
+        let reply = request(&backend, "tools/call", unknown).await;
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+
+        let calls = backend.calls.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            calls[0],
+            ToolCall::ApplyEdit(ApplyEditArgs {
+                buffer_id: "b1".to_string(),
+                start: 0,
+                end: 2,
+                text: "pub fn".to_string(),
+            })
+        );
+    }
+
+    async fn post(address: &str, token: Option<&str>, body: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+        let auth = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n{}\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            address,
+            auth,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_http_transport() {
+        let server = McpServer::default();
+        let status = server
+            .start("127.0.0.1:0", "secret".to_string(), Arc::new(FakeBackend::default()))
+            .unwrap();
+        let address = status.address.unwrap();
+        assert!(server
+            .start("127.0.0.1:0", "x".to_string(), Arc::new(FakeBackend::default()))
+            .is_err());
+
+        let ping = r#"{"jsonrpc": "2.0", "id": 1, "method": "ping"}"#;
+        assert!(post(&address, None, ping).await.starts_with("HTTP/1.1 401"));
+        assert!(post(&address, Some("wrong"), ping)
+            .await
+            .starts_with("HTTP/1.1 401"));
+
+        let response = post(&address, Some("secret"), ping).await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let reply: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(reply, json!({ "jsonrpc": "2.0", "id": 1, "result": {} }));
+
+        let initialized = r#"{"jsonrpc": "2.0", "method": "notifications/initialized"}"#;
+        let response = post(&address, Some("secret"), initialized).await;
+        assert!(response.starts_with("HTTP/1.1 202"));
+        let response = post(&address, Some("secret"), "{").await;
+        assert!(response.starts_with("HTTP/1.1 400"));
+
+        assert!(server.stop());
+        assert!(!server.status().running);
+    }
+}
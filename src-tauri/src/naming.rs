@@ -0,0 +1,204 @@
+//! Branch and Session Naming
+//!
+//! Agent pipelines name their branches, tmux sessions and worktree
+//! directories after the first characters of a run's UUID. Those names can
+//! already be taken, by a collision or by a re-run next to preserved
+//! worktrees, and git or tmux then fail with cryptic errors. A
+//! [`NameAllocator`] checks each name before it is used and appends an
+//! incrementing suffix (`-2`, `-3`, ...) until it finds a free one. The
+//! names handed out are recorded with the session, so cleanup acts on what
+//! was actually created rather than on a guessed pattern.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::sanitize;
+use crate::tools::ToolRegistry;
+
+/// Highest suffix tried before giving up
+const MAX_SUFFIX: u32 = 99;
+
+/// Hands out branch, session and directory names that are not in use
+pub struct NameAllocator<'a> {
+    tools: &'a ToolRegistry,
+    repo: PathBuf,
+    claimed: HashSet<String>,
+}
+
+impl<'a> NameAllocator<'a> {
+    /// Allocator for names in the repository at `repo`
+    pub fn new(tools: &'a ToolRegistry, repo: impl Into<PathBuf>) -> Self {
+        Self {
+            tools,
+            repo: repo.into(),
+            claimed: HashSet::new(),
+        }
+    }
+
+    /// Free branch name based on `base`
+    pub fn branch(&mut self, base: &str) -> Result<String, String> {
+        let base = sanitize::branch_slug(base);
+        let (tools, repo) = (self.tools, self.repo.clone());
+        self.claim("branch", &base, |name| branch_exists(tools, &repo, name))
+    }
+
+    /// Free tmux session name based on `base`
+    pub fn session(&mut self, base: &str) -> Result<String, String> {
+        let base = sanitize::tmux_session_name(base);
+        let tools = self.tools;
+        self.claim("tmux session", &base, |name| Ok(session_exists(tools, name)))
+    }
+
+    /// Free directory path based on `base`, e.g. a worktree directory
+    pub fn directory(&mut self, base: &Path) -> Result<PathBuf, String> {
+        let name =
+            self.claim("directory", &base.to_string_lossy(), |name| Ok(Path::new(name).exists()))?;
+        Ok(PathBuf::from(name))
+    }
+
+    /// First of `base`, `base-2`, `base-3`, ... that is neither taken nor
+    /// already handed out
+    fn claim(
+        &mut self,
+        kind: &str,
+        base: &str,
+        mut taken: impl FnMut(&str) -> Result<bool, String>,
+    ) -> Result<String, String> {
+        for suffix in 1..=MAX_SUFFIX {
+            let name = if suffix == 1 {
+                base.to_string()
+            } else {
+                format!("{}-{}", base, suffix)
+            };
+            if self.claimed.contains(&name) || taken(&name)? {
+                continue;
+            }
+            if suffix > 1 {
+                tracing::info!("{} {} is taken, using {}", kind, base, name);
+            }
+            self.claimed.insert(name.clone());
+            return Ok(name);
+        }
+        Err(format!("No free {} name based on {} ({} tried)", kind, base, MAX_SUFFIX))
+    }
+}
+
+/// Whether the repository at `repo` has a local branch `name`
+fn branch_exists(tools: &ToolRegistry, repo: &Path, name: &str) -> Result<bool, String> {
+    let output = tools
+        .command("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("refs/heads/{}", name))
+        .current_dir(repo)
+        .output()
+        .map_err(|e| format!("Failed to check branch {}: {}", name, e))?;
+    Ok(output.status.success())
+}
+
+/// Whether a tmux session is named exactly `name`
+///
+/// Without the `=` prefix tmux would also match sessions that merely start
+/// with `name`.
+fn session_exists(tools: &ToolRegistry, name: &str) -> bool {
+    tools
+        .command("tmux")
+        .arg("has-session")
+        .arg("-t")
+        .arg(format!("={}", name))
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::FakeTools;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_branch_suffix_skips_existing_branches() {
+        let fake = Arc::new(FakeTools::default());
+        let tools = ToolRegistry::with_runner(fake.clone());
+        let repo = tempfile::tempdir().unwrap();
+        tools
+            .command("git")
+            .args([
+                "worktree",
+                "add",
+                "-b",
+                "claude-competition-abcd1234-1",
+                "wt1",
+            ])
+            .current_dir(repo.path())
+            .output()
+            .unwrap();
+
+        let mut names = NameAllocator::new(&tools, repo.path());
+        assert_eq!(
+            names.branch("claude-competition-abcd1234-1").unwrap(),
+            "claude-competition-abcd1234-1-2"
+        );
+        // Names handed out earlier count as taken too
+        assert_eq!(
+            names.branch("claude-competition-abcd1234-1").unwrap(),
+            "claude-competition-abcd1234-1-3"
+        );
+        assert_eq!(
+            names.branch("claude-competition-abcd1234-2").unwrap(),
+            "claude-competition-abcd1234-2"
+        );
+    }
+
+    #[test]
+    fn test_session_suffix_uses_exact_match() {
+        let fake = Arc::new(FakeTools::default());
+        let tools = ToolRegistry::with_runner(fake.clone());
+        tools
+            .command("tmux")
+            .args(["new-session", "-d", "-s", "ait42-claude-1"])
+            .output()
+            .unwrap();
+
+        let mut names = NameAllocator::new(&tools, ".");
+        assert_eq!(names.session("ait42-claude-1").unwrap(), "ait42-claude-1-2");
+        assert_eq!(names.session("ait42-claude").unwrap(), "ait42-claude");
+        assert_eq!(names.session("ait42.claude:2").unwrap(), "ait42-claude-2");
+
+        let checked: Vec<_> = fake
+            .invocations()
+            .into_iter()
+            .filter(|i| i.arg(0) == Some("has-session"))
+            .map(|i| i.flag_value("-t").unwrap().to_string())
+            .collect();
+        assert!(checked.iter().all(|target| target.starts_with('=')));
+    }
+
+    #[test]
+    fn test_directory_suffix() {
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::default()));
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("instance-1")).unwrap();
+
+        let mut names = NameAllocator::new(&tools, dir.path());
+        assert_eq!(
+            names.directory(&dir.path().join("instance-1")).unwrap(),
+            dir.path().join("instance-1-2")
+        );
+    }
+
+    #[test]
+    fn test_gives_up_when_every_suffix_is_taken() {
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::default()));
+        let mut names = NameAllocator::new(&tools, ".");
+        for _ in 0..MAX_SUFFIX {
+            names.session("busy").unwrap();
+        }
+        assert!(names
+            .session("busy")
+            .unwrap_err()
+            .contains("No free tmux session"));
+    }
+}
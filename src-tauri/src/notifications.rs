@@ -0,0 +1,165 @@
+//! Notification Center
+//!
+//! Keeps a bounded log of notifications raised by background subsystems and
+//! forwards each one to the frontend as a `notification` event. Notifications
+//! can carry actions; the frontend renders them as buttons and invokes the
+//! matching command when one is clicked.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::Manager;
+
+/// Event name used to push notifications to the frontend
+pub const NOTIFICATION_EVENT: &str = "notification";
+
+/// Number of notifications kept in the log
+const MAX_NOTIFICATIONS: usize = 200;
+
+/// Notification severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Action offered by a notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationAction {
+    /// Button label
+    pub label: String,
+    /// Tauri command to invoke
+    pub command: String,
+    /// Command arguments
+    pub args: serde_json::Value,
+}
+
+/// Entry in the notification center
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub id: u64,
+    pub level: NotificationLevel,
+    /// Subsystem that raised the notification, e.g. "lsp"
+    pub source: String,
+    pub message: String,
+    pub actions: Vec<NotificationAction>,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+}
+
+/// Bounded, thread-safe notification log
+#[derive(Debug, Default)]
+pub struct NotificationCenter {
+    entries: Mutex<VecDeque<Notification>>,
+    next_id: AtomicU64,
+}
+
+impl NotificationCenter {
+    /// Create an empty notification center
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a notification and return it
+    pub fn push(
+        &self,
+        level: NotificationLevel,
+        source: impl Into<String>,
+        message: impl Into<String>,
+        actions: Vec<NotificationAction>,
+    ) -> Notification {
+        let notification = Notification {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            level,
+            source: source.into(),
+            message: message.into(),
+            actions,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == MAX_NOTIFICATIONS {
+            entries.pop_front();
+        }
+        entries.push_back(notification.clone());
+
+        notification
+    }
+
+    /// Record a notification and emit it to the frontend
+    pub fn notify(
+        &self,
+        app: &tauri::AppHandle,
+        level: NotificationLevel,
+        source: impl Into<String>,
+        message: impl Into<String>,
+        actions: Vec<NotificationAction>,
+    ) {
+        let notification = self.push(level, source, message, actions);
+        if let Err(e) = app.emit_all(NOTIFICATION_EVENT, &notification) {
+            tracing::warn!("Failed to emit notification {}: {}", notification.id, e);
+        }
+    }
+
+    /// All notifications, oldest first
+    pub fn list(&self) -> Vec<Notification> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().cloned().collect()
+    }
+
+    /// Remove a notification, returning whether it existed
+    pub fn dismiss(&self, id: u64) -> bool {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let before = entries.len();
+        entries.retain(|n| n.id != id);
+        entries.len() != before
+    }
+
+    /// Remove all notifications
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_dismiss() {
+        let center = NotificationCenter::new();
+        let first = center.push(NotificationLevel::Info, "lsp", "started", vec![]);
+        let second = center.push(NotificationLevel::Warning, "lsp", "slow", vec![]);
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(center.list().len(), 2);
+
+        assert!(center.dismiss(first.id));
+        assert!(!center.dismiss(first.id));
+        assert_eq!(center.list()[0].message, "slow");
+
+        center.clear();
+        assert!(center.list().is_empty());
+    }
+
+    #[test]
+    fn test_log_is_bounded() {
+        let center = NotificationCenter::new();
+        for i in 0..MAX_NOTIFICATIONS + 5 {
+            center.push(NotificationLevel::Info, "test", i.to_string(), vec![]);
+        }
+
+        let entries = center.list();
+        assert_eq!(entries.len(), MAX_NOTIFICATIONS);
+        assert_eq!(entries[0].message, "5");
+    }
+}
@@ -0,0 +1,300 @@
+//! Memory-Based Learning (v1.7.0)
+//!
+//! Adjusts subtask recommendations using the outcomes of past sessions.
+//!
+//! Outcomes are grouped by complexity class. Within a class, each subtask
+//! count in the Ω bounds gets an exponentially weighted success rate, so
+//! recent sessions count more than old ones. Counts with few sessions are
+//! pulled towards the success rate of the whole class. When another count
+//! clearly outperforms the recommended one, the recommendation moves towards
+//! it by at most [`MAX_ADJUSTMENT`] subtasks.
+
+use omega_theory::ComplexityClass;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+use super::subtask_optimizer::MemoryAdjustment;
+
+/// Weight lost per newer session of the same class (0.0-1.0)
+pub const DECAY: f64 = 0.3;
+
+/// Sessions of a class needed before its history is used
+pub const MIN_SAMPLES: usize = 3;
+
+/// Largest change to a recommendation, in subtasks
+pub const MAX_ADJUSTMENT: i32 = 3;
+
+/// Weight of the class-wide success rate in each count's estimate
+const PRIOR_WEIGHT: f64 = 1.0;
+
+/// How much better another count must do before it is recommended
+const MIN_IMPROVEMENT: f64 = 0.1;
+
+/// Actual outcome of a finished session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionOutcome {
+    /// Complexity class the task was assigned
+    pub complexity_class: ComplexityClass,
+
+    /// Number of subtasks the session ran
+    pub subtasks: usize,
+
+    /// Whether the session completed
+    pub success: bool,
+
+    /// Duration of the session in seconds, if recorded
+    pub duration_secs: Option<u64>,
+
+    /// Files changed by the session, if recorded
+    pub files_changed: Option<u32>,
+}
+
+/// Outcomes of past sessions, oldest first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryModel {
+    outcomes: Vec<SessionOutcome>,
+}
+
+/// Exponentially weighted success of a set of outcomes
+#[derive(Debug, Clone, Copy, Default)]
+struct Weighted {
+    weight: f64,
+    successes: f64,
+}
+
+impl Weighted {
+    fn add(&mut self, weight: f64, success: bool) {
+        self.weight += weight;
+        if success {
+            self.successes += weight;
+        }
+    }
+
+    /// Success rate, pulled towards `prior` when there is little weight
+    fn rate(&self, prior: f64) -> f64 {
+        (self.successes + PRIOR_WEIGHT * prior) / (self.weight + PRIOR_WEIGHT)
+    }
+}
+
+impl MemoryModel {
+    /// Create a model from outcomes ordered oldest first
+    pub fn new(outcomes: Vec<SessionOutcome>) -> Self {
+        Self { outcomes }
+    }
+
+    /// Record the outcome of a session that just finished
+    #[cfg(test)]
+    pub fn record(&mut self, outcome: SessionOutcome) {
+        self.outcomes.push(outcome);
+    }
+
+    /// Number of recorded outcomes
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Whether no outcomes are recorded
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+
+    /// Outcomes of `class` with their weights, newest weighing 1.0
+    fn weighted(
+        &self,
+        class: ComplexityClass,
+    ) -> impl Iterator<Item = (f64, &SessionOutcome)> + '_ {
+        self.outcomes
+            .iter()
+            .rev()
+            .filter(move |outcome| outcome.complexity_class == class)
+            .scan(1.0, |weight, outcome| {
+                let current = *weight;
+                *weight *= 1.0 - DECAY;
+                Some((current, outcome))
+            })
+    }
+
+    /// Exponentially weighted success rate of `class`
+    ///
+    /// Returns `None` while fewer than [`MIN_SAMPLES`] sessions are recorded.
+    pub fn success_rate(&self, class: ComplexityClass) -> Option<f64> {
+        let mut total = Weighted::default();
+        let mut samples = 0;
+        for (weight, outcome) in self.weighted(class) {
+            total.add(weight, outcome.success);
+            samples += 1;
+        }
+        (samples >= MIN_SAMPLES).then(|| total.successes / total.weight)
+    }
+
+    /// Adjustment of `recommended` subtasks for a task of `class`
+    ///
+    /// The adjusted count stays within `bounds`. Returns `None` while the
+    /// class has too little history.
+    pub fn adjust(
+        &self,
+        class: ComplexityClass,
+        recommended: usize,
+        bounds: &RangeInclusive<usize>,
+    ) -> Option<MemoryAdjustment> {
+        let overall = self.success_rate(class)?;
+        let recommended = recommended.clamp(*bounds.start(), *bounds.end());
+
+        let mut by_count = vec![Weighted::default(); bounds.end() - bounds.start() + 1];
+        let mut samples = 0;
+        let mut durations = Vec::new();
+        let mut files = Vec::new();
+        for (weight, outcome) in self.weighted(class) {
+            samples += 1;
+            durations.extend(outcome.duration_secs);
+            files.extend(outcome.files_changed);
+            if bounds.contains(&outcome.subtasks) {
+                by_count[outcome.subtasks - bounds.start()].add(weight, outcome.success);
+            }
+        }
+
+        let rate = |count: usize| by_count[count - bounds.start()].rate(overall);
+        let current = rate(recommended);
+        let reachable = recommended
+            .saturating_sub(MAX_ADJUSTMENT as usize)
+            .max(*bounds.start())
+            ..=(recommended + MAX_ADJUSTMENT as usize).min(*bounds.end());
+        // Prefer the count closest to the recommendation among equals
+        let best = reachable
+            .filter(|&count| rate(count) >= current + MIN_IMPROVEMENT)
+            .max_by(|&a, &b| {
+                rate(a)
+                    .total_cmp(&rate(b))
+                    .then(b.abs_diff(recommended).cmp(&a.abs_diff(recommended)))
+            });
+
+        let mut reasoning = format!(
+            "{} past {} sessions succeeded {:.0}% of the time",
+            samples,
+            class,
+            overall * 100.0
+        );
+        if !durations.is_empty() {
+            let mean = durations.iter().sum::<u64>() / durations.len() as u64;
+            reasoning.push_str(&format!(", averaging {}s", mean));
+        }
+        if !files.is_empty() {
+            let mean = files.iter().sum::<u32>() as f64 / files.len() as f64;
+            reasoning.push_str(&format!(" and {:.1} files changed", mean));
+        }
+        let adjustment = match best {
+            Some(best) => {
+                reasoning.push_str(&format!(
+                    "; {} subtasks succeeded {:.0}% vs {:.0}% for {}",
+                    best,
+                    rate(best) * 100.0,
+                    current * 100.0,
+                    recommended
+                ));
+                best as i32 - recommended as i32
+            }
+            None => {
+                reasoning.push_str(&format!("; keeping {} subtasks", recommended));
+                0
+            }
+        };
+
+        Some(MemoryAdjustment {
+            historical_success_rate: overall,
+            adjustment,
+            reasoning,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(subtasks: usize, success: bool) -> SessionOutcome {
+        SessionOutcome {
+            complexity_class: ComplexityClass::Linear,
+            subtasks,
+            success,
+            duration_secs: Some(60),
+            files_changed: Some(3),
+        }
+    }
+
+    #[test]
+    fn test_too_little_history() {
+        let model = MemoryModel::new(vec![outcome(3, false), outcome(5, true)]);
+        assert_eq!(model.success_rate(ComplexityClass::Linear), None);
+        assert!(model.adjust(ComplexityClass::Linear, 3, &(3..=5)).is_none());
+        assert!(MemoryModel::default().is_empty());
+    }
+
+    #[test]
+    fn test_recent_outcomes_weigh_more() {
+        let improving =
+            MemoryModel::new(vec![outcome(4, false), outcome(4, false), outcome(4, true)]);
+        let declining =
+            MemoryModel::new(vec![outcome(4, true), outcome(4, false), outcome(4, false)]);
+
+        let improving = improving.success_rate(ComplexityClass::Linear).unwrap();
+        let declining = declining.success_rate(ComplexityClass::Linear).unwrap();
+        assert!(improving > 1.0 / 3.0);
+        assert!(declining < 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_adjusts_towards_successful_count() {
+        let mut model = MemoryModel::default();
+        for _ in 0..4 {
+            model.record(outcome(3, false));
+            model.record(outcome(5, true));
+        }
+
+        let adjustment = model.adjust(ComplexityClass::Linear, 3, &(3..=5)).unwrap();
+        assert_eq!(adjustment.adjustment, 2);
+        assert!(adjustment.historical_success_rate > 0.4);
+        assert!(adjustment.reasoning.contains("5 subtasks"));
+        assert!(adjustment
+            .reasoning
+            .contains("averaging 60s and 3.0 files changed"));
+
+        // Other classes have no history
+        assert!(model
+            .adjust(ComplexityClass::Quadratic, 7, &(5..=10))
+            .is_none());
+    }
+
+    #[test]
+    fn test_keeps_successful_recommendation() {
+        let model = MemoryModel::new(vec![outcome(4, true), outcome(4, true), outcome(5, true)]);
+        let adjustment = model.adjust(ComplexityClass::Linear, 4, &(3..=5)).unwrap();
+        assert_eq!(adjustment.adjustment, 0);
+        assert_eq!(adjustment.historical_success_rate, 1.0);
+    }
+
+    #[test]
+    fn test_adjustment_is_bounded() {
+        let mut model = MemoryModel::default();
+        for _ in 0..5 {
+            model.record(SessionOutcome {
+                complexity_class: ComplexityClass::Exponential,
+                ..outcome(11, true)
+            });
+            model.record(SessionOutcome {
+                complexity_class: ComplexityClass::Exponential,
+                ..outcome(15, true)
+            });
+            model.record(SessionOutcome {
+                complexity_class: ComplexityClass::Exponential,
+                ..outcome(8, false)
+            });
+        }
+
+        let adjustment = model
+            .adjust(ComplexityClass::Exponential, 8, &(8..=15))
+            .unwrap();
+        assert_eq!(adjustment.adjustment, MAX_ADJUSTMENT);
+    }
+}
@@ -43,6 +43,7 @@
 //! | Exponential | Ω(2^n) | 8-15 | Combinatorial, backtracking |
 
 pub mod instance_calculator;
+pub mod memory;
 pub mod subtask_optimizer;
 
 #[cfg(test)]
@@ -50,6 +51,7 @@ mod tests;
 
 // Re-export main types
 pub use instance_calculator::{InstanceCalculation, InstanceCalculator};
+pub use memory::{MemoryModel, SessionOutcome};
 pub use subtask_optimizer::{
     MemoryAdjustment, OptimizerError, OptimizationResult, SubtaskOptimizer,
 };
@@ -69,6 +71,8 @@ mod module_tests {
         let _: OptimizationResult;
         let _: OptimizerError;
         let _: MemoryAdjustment;
+        let _: MemoryModel;
+        let _: SessionOutcome;
         let _: ComplexityClass;
         let _: ComplexityEstimate;
         let _: InstanceCalculator;
@@ -8,8 +8,9 @@
 //! ```text
 //! SubtaskOptimizer
 //!     ├── CachedEstimator (LLM-based analysis)
-//!     │   └── AnthropicClient (Claude API)
-//!     └── ComplexityClass::to_subtask_range() (Ω-theory rules)
+//!     │   └── LlmProvider (Anthropic, OpenAI or Ollama)
+//!     ├── ComplexityClass::to_subtask_range() (Ω-theory rules)
+//!     └── MemoryModel (history-based adjustment)
 //! ```
 //!
 //! # Performance
@@ -19,9 +20,13 @@
 //! - Cache miss: ~1-2s (LLM API call)
 //! - Memory: ~1KB per cached result
 
-use llm_estimator::{AnthropicClient, CachedEstimator, ComplexityEstimate, EstimatorError};
+use llm_estimator::{
+    AnthropicClient, CachedEstimator, ComplexityEstimate, DiskCache, EstimatorError, LlmProvider,
+};
 use omega_theory::ComplexityClass;
 use serde::{Deserialize, Serialize};
+
+use super::memory::MemoryModel;
 use std::time::Duration;
 use thiserror::Error;
 use tracing::{debug, info, warn};
@@ -42,10 +47,10 @@ pub enum OptimizerError {
     InvalidInput(String),
 }
 
-/// Memory-based adjustment for future optimization (v1.7.0+)
+/// Memory-based adjustment of a recommendation (v1.7.0+)
 ///
-/// This is a placeholder structure for the memory-based learning system
-/// that will be implemented in v1.7.0.
+/// Computed by [`MemoryModel::adjust`] from the outcomes of past sessions
+/// of the same complexity class.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MemoryAdjustment {
     /// Historical success rate for similar tasks
@@ -73,7 +78,7 @@ pub struct OptimizationResult {
     /// Human-readable reasoning for the recommendation
     pub reasoning: String,
 
-    /// Memory-based adjustment (v1.7.0+ placeholder)
+    /// Memory-based adjustment, if the complexity class has enough history
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memory_adjustment: Option<MemoryAdjustment>,
 
@@ -151,6 +156,17 @@ impl SubtaskOptimizer {
         })
     }
 
+    /// Create optimizer estimating with `provider`
+    ///
+    /// Lets estimation run without an Anthropic key, e.g. against OpenAI or
+    /// a local Ollama server.
+    pub fn with_provider(provider: impl LlmProvider + 'static) -> Self {
+        Self {
+            estimator: CachedEstimator::new(provider),
+            timeout: Duration::from_millis(500),
+        }
+    }
+
     /// Create optimizer with custom timeout
     ///
     /// # Arguments
@@ -203,6 +219,25 @@ impl SubtaskOptimizer {
         &self,
         task_description: &str,
         current_subtasks: usize,
+    ) -> Result<OptimizationResult, OptimizerError> {
+        self.optimize_with_memory(task_description, current_subtasks, &MemoryModel::default())
+            .await
+    }
+
+    /// Optimize subtask count, learning from past session outcomes
+    ///
+    /// Like [`optimize_subtask_count`](Self::optimize_subtask_count), then
+    /// moves the recommendation within the Ω bounds towards the subtask
+    /// count that succeeded most often for the same complexity class.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`optimize_subtask_count`](Self::optimize_subtask_count)
+    pub async fn optimize_with_memory(
+        &self,
+        task_description: &str,
+        current_subtasks: usize,
+        memory: &MemoryModel,
     ) -> Result<OptimizationResult, OptimizerError> {
         // Validate input
         if task_description.trim().is_empty() {
@@ -262,15 +297,26 @@ impl SubtaskOptimizer {
         };
 
         // Build comprehensive reasoning
-        let reasoning = self.build_reasoning(
+        let mut reasoning = self.build_reasoning(
             &complexity_class,
             &llm_estimate,
             recommended_subtasks,
             &omega_range,
         );
 
-        // Memory adjustment placeholder (v1.7.0+)
-        let memory_adjustment = None; // Future: query historical success rates
+        // Learn from past sessions of the same class (v1.7.0+)
+        let memory_adjustment = memory.adjust(complexity_class, recommended_subtasks, &omega_range);
+        let recommended_subtasks = match &memory_adjustment {
+            Some(adjustment) => {
+                let adjusted = recommended_subtasks
+                    .saturating_add_signed(adjustment.adjustment as isize)
+                    .clamp(*omega_range.start(), *omega_range.end());
+                debug!("Memory adjustment: {} → {}", recommended_subtasks, adjusted);
+                reasoning.push_str(&format!("\nMemory: {}", adjustment.reasoning));
+                adjusted
+            }
+            None => recommended_subtasks,
+        };
 
         Ok(OptimizationResult {
             complexity_class,
@@ -316,6 +362,13 @@ impl SubtaskOptimizer {
         reasoning
     }
 
+    /// Also keep estimates in `disk`, so they survive restarts
+    #[must_use]
+    pub fn with_disk_cache(mut self, disk: DiskCache) -> Self {
+        self.estimator = self.estimator.with_disk_cache(disk);
+        self
+    }
+
     /// Get cache statistics
     pub fn cache_stats(&self) -> llm_estimator::CacheStats {
         self.estimator.stats()
@@ -377,6 +430,14 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_optimizer_with_provider() {
+        // Ollama needs no API key
+        let provider = llm_estimator::OllamaClient::local().unwrap();
+        let optimizer = SubtaskOptimizer::with_provider(provider);
+        assert_eq!(optimizer.cache_stats().total_requests, 0);
+    }
+
     #[test]
     fn test_optimizer_with_custom_timeout() {
         let timeout = Duration::from_secs(10);
@@ -517,7 +578,36 @@ mod tests {
         assert_eq!(result.confidence, 0.88);
         assert!(!result.reasoning.is_empty());
         assert!(result.llm_estimate.is_some());
-        assert!(result.memory_adjustment.is_none()); // v1.7.0 placeholder
+        assert!(result.memory_adjustment.is_none()); // No history yet
+    }
+
+    #[tokio::test]
+    async fn test_optimization_learns_from_history() {
+        use crate::optimizer::memory::SessionOutcome;
+
+        let optimizer = create_test_optimizer();
+        optimizer.insert_cached("Learning task", 0, create_mock_estimate("Ω(n)", 3, 0.8));
+
+        let mut memory = MemoryModel::default();
+        for _ in 0..4 {
+            for (subtasks, success) in [(3, false), (5, true)] {
+                memory.record(SessionOutcome {
+                    complexity_class: ComplexityClass::Linear,
+                    subtasks,
+                    success,
+                    duration_secs: None,
+                    files_changed: None,
+                });
+            }
+        }
+
+        let result = optimizer
+            .optimize_with_memory("Learning task", 0, &memory)
+            .await
+            .unwrap();
+        assert_eq!(result.recommended_subtasks, 5);
+        assert_eq!(result.memory_adjustment.unwrap().adjustment, 2);
+        assert!(result.reasoning.contains("Memory:"));
     }
 
     #[tokio::test]
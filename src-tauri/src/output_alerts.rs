@@ -0,0 +1,383 @@
+//! Output Alerts
+//!
+//! Rules from `ait42.alerts` are matched against agent output while it
+//! streams in, e.g. "ERROR", a failed `cargo test` or "rate limit". Output is
+//! matched line by line, an unfinished line being held back until the rest of
+//! it arrives, and each rule fires at most once per execution.
+//!
+//! Every alert is emitted as an `agent-alert` event, which the frontend shows
+//! as a warning badge on the agent or competition instance. Depending on the
+//! rule's action the alert also raises a notification or cancels the agent.
+
+use ait42_config::AlertRuleConfig;
+use ait42_core::{SearchOptions, SearchQuery};
+use claude_output::strip_ansi;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::agent_output::{self, OutputStatus};
+use crate::executions;
+use crate::notifications::NotificationLevel;
+use crate::state::AppState;
+use crate::sync::lock;
+
+/// Event emitted for each alert raised
+pub const AGENT_ALERT_EVENT: &str = "agent-alert";
+
+/// Longest unfinished line held back, in bytes; longer ones are matched as is
+const MAX_PENDING_LINE: usize = 64 * 1024;
+
+/// Finished executions remembered, so their late output raises nothing
+const MAX_FINISHED_MATCHERS: usize = 32;
+
+/// Alerts kept for `list_agent_alerts`
+const MAX_ALERTS: usize = 200;
+
+/// What an alert does besides marking the agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertAction {
+    Notify,
+    Badge,
+    Cancel,
+}
+
+impl AlertAction {
+    /// Action named by a rule's `action`, `Notify` if unknown
+    fn from_name(name: &str) -> Self {
+        match name {
+            "badge" => Self::Badge,
+            "cancel" => Self::Cancel,
+            _ => Self::Notify,
+        }
+    }
+}
+
+/// Alert raised by a rule on agent output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentAlert {
+    /// Execution whose output matched, the tmux session for competition
+    /// instances
+    pub execution_id: String,
+    /// Competition instance, if the output is one's
+    pub instance: Option<usize>,
+    /// Name of the rule
+    pub rule: String,
+    pub action: AlertAction,
+    /// Line that matched
+    pub line: String,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+}
+
+/// Rule that matched a line of output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertMatch {
+    pub rule: String,
+    pub action: AlertAction,
+    pub line: String,
+}
+
+struct AlertRule {
+    name: String,
+    query: SearchQuery,
+    action: AlertAction,
+    fired: bool,
+}
+
+/// Matches the output of one execution against the alert rules
+pub struct AlertMatcher {
+    rules: Vec<AlertRule>,
+    /// Unfinished last line of the output so far
+    pending: String,
+}
+
+impl AlertMatcher {
+    /// Matcher for `rules`, leaving out the ones whose regex is invalid
+    pub fn new(rules: &[AlertRuleConfig]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| {
+                let options = SearchOptions {
+                    regex: rule.regex,
+                    case_insensitive: !rule.case_sensitive,
+                    whole_word: false,
+                };
+                match SearchQuery::new(&rule.pattern, options) {
+                    Ok(query) => Some(AlertRule {
+                        name: rule.name.clone(),
+                        query,
+                        action: AlertAction::from_name(&rule.action),
+                        fired: false,
+                    }),
+                    Err(e) => {
+                        tracing::warn!("Ignoring alert {}: {}", rule.name, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self {
+            rules,
+            pending: String::new(),
+        }
+    }
+
+    /// Match the lines `output` completes, appended to the output before
+    pub fn feed(&mut self, output: &str) -> Vec<AlertMatch> {
+        if self.rules.is_empty() {
+            return Vec::new();
+        }
+        self.pending.push_str(output);
+        let lines = match self.pending.rfind('\n') {
+            Some(end) => {
+                let rest = self.pending.split_off(end + 1);
+                std::mem::replace(&mut self.pending, rest)
+            }
+            None if self.pending.len() > MAX_PENDING_LINE => std::mem::take(&mut self.pending),
+            None => return Vec::new(),
+        };
+        self.match_lines(&lines)
+    }
+
+    /// Match the unfinished last line once the output ended
+    pub fn finish(&mut self) -> Vec<AlertMatch> {
+        let line = std::mem::take(&mut self.pending);
+        let matches = self.match_lines(&line);
+        self.rules.clear();
+        matches
+    }
+
+    fn match_lines(&mut self, text: &str) -> Vec<AlertMatch> {
+        let mut matches = Vec::new();
+        for line in strip_ansi(text).lines() {
+            for rule in self.rules.iter_mut().filter(|rule| !rule.fired) {
+                if rule.query.next_match(line, 0).is_some() {
+                    rule.fired = true;
+                    matches.push(AlertMatch {
+                        rule: rule.name.clone(),
+                        action: rule.action,
+                        line: line.trim().to_string(),
+                    });
+                }
+            }
+        }
+        matches
+    }
+}
+
+#[derive(Default)]
+struct Matchers {
+    by_execution: HashMap<String, AlertMatcher>,
+    /// Finished executions, oldest first
+    finished: VecDeque<String>,
+}
+
+/// Alert matchers of the executions followed, and the alerts raised
+#[derive(Default)]
+pub struct OutputAlerts {
+    matchers: Mutex<Matchers>,
+    alerts: Mutex<VecDeque<AgentAlert>>,
+}
+
+impl OutputAlerts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match output of `execution_id` as it arrives
+    ///
+    /// `rules` gives the rules when the execution's first output arrives.
+    /// Output after the execution finished raises nothing.
+    pub fn scan(
+        &self,
+        execution_id: &str,
+        output: &str,
+        status: OutputStatus,
+        rules: impl FnOnce() -> Vec<AlertRuleConfig>,
+    ) -> Vec<AlertMatch> {
+        let mut matchers = lock(&self.matchers);
+        if matchers.finished.iter().any(|id| id == execution_id) {
+            return Vec::new();
+        }
+        let matcher = matchers
+            .by_execution
+            .entry(execution_id.to_string())
+            .or_insert_with(|| AlertMatcher::new(&rules()));
+        let mut matches = matcher.feed(output);
+        if status == OutputStatus::Running {
+            return matches;
+        }
+
+        matches.extend(matcher.finish());
+        matchers.by_execution.remove(execution_id);
+        matchers.finished.push_back(execution_id.to_string());
+        if matchers.finished.len() > MAX_FINISHED_MATCHERS {
+            matchers.finished.pop_front();
+        }
+        matches
+    }
+
+    fn record(&self, alert: AgentAlert) {
+        let mut alerts = lock(&self.alerts);
+        if alerts.len() == MAX_ALERTS {
+            alerts.pop_front();
+        }
+        alerts.push_back(alert);
+    }
+
+    /// Alerts raised, oldest first, only the ones of `execution_id` if given
+    pub fn list(&self, execution_id: Option<&str>) -> Vec<AgentAlert> {
+        lock(&self.alerts)
+            .iter()
+            .filter(|alert| execution_id.map_or(true, |id| alert.execution_id == id))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Match output of `execution_id`, a competition `instance` if given,
+/// against `ait42.alerts` and raise the alerts it triggers
+pub fn check(
+    app: &tauri::AppHandle,
+    execution_id: &str,
+    instance: Option<usize>,
+    output: &str,
+    status: OutputStatus,
+) {
+    let state = app.state::<AppState>();
+    let matches = state
+        .output_alerts
+        .scan(execution_id, output, status, || lock(&state.config).ait42.alerts.clone());
+    for found in matches {
+        raise(
+            app,
+            AgentAlert {
+                execution_id: execution_id.to_string(),
+                instance,
+                rule: found.rule,
+                action: found.action,
+                line: found.line,
+                created_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+    }
+}
+
+/// Record and emit `alert`, then notify or cancel as its rule says
+fn raise(app: &tauri::AppHandle, alert: AgentAlert) {
+    tracing::warn!("Alert {} on {}: {}", alert.rule, alert.execution_id, alert.line);
+    let state = app.state::<AppState>();
+    state.output_alerts.record(alert.clone());
+    if let Err(e) = app.emit_all(AGENT_ALERT_EVENT, &alert) {
+        tracing::warn!("Failed to emit alert {}: {}", alert.rule, e);
+    }
+    state.remote.publish(AGENT_ALERT_EVENT, &alert);
+
+    let message = match alert.action {
+        AlertAction::Badge => return,
+        AlertAction::Notify => format!("{}: {}", alert.rule, alert.line),
+        AlertAction::Cancel => {
+            cancel(app, &alert.execution_id);
+            format!("Cancelled {} ({}): {}", alert.execution_id, alert.rule, alert.line)
+        }
+    };
+    state
+        .notifications
+        .notify(app, NotificationLevel::Warning, "alerts", message, vec![]);
+}
+
+/// Cancel `execution_id`, or kill the tmux session of that name
+fn cancel(app: &tauri::AppHandle, execution_id: &str) {
+    let state = app.state::<AppState>();
+    // The command awaiting a registered execution records its cancellation
+    if state
+        .executions
+        .cancel(execution_id, &state.tools)
+        .is_some()
+    {
+        return;
+    }
+    if executions::kill_session(&state.tools, execution_id)
+        && state.agent_output.contains(execution_id)
+    {
+        agent_output::publish(app, execution_id, "", OutputStatus::Cancelled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, pattern: &str, regex: bool, action: &str) -> AlertRuleConfig {
+        AlertRuleConfig {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            regex,
+            case_sensitive: false,
+            action: action.to_string(),
+        }
+    }
+
+    fn rules() -> Vec<AlertRuleConfig> {
+        vec![
+            rule("Errors", "ERROR", false, "badge"),
+            rule("Tests failed", r"^test result: FAILED", true, "notify"),
+            rule("Rate limited", "rate limit", false, "cancel"),
+            rule("Broken", "(", true, "notify"),
+        ]
+    }
+
+    fn names(matches: &[AlertMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_matches_complete_lines() {
+        let mut matcher = AlertMatcher::new(&rules());
+        // The invalid regex is left out
+        assert_eq!(matcher.rules.len(), 3);
+
+        assert!(matcher.feed("compiling\nerr").is_empty());
+        let matches = matcher.feed("or: mismatched types\n");
+        assert_eq!(names(&matches), ["Errors"]);
+        assert_eq!(matches[0].line, "error: mismatched types");
+        assert_eq!(matches[0].action, AlertAction::Badge);
+
+        // Each rule fires once, colors are ignored
+        assert!(matcher.feed("another error\n").is_empty());
+        let matches = matcher.feed("\x1b[31mtest result: FAILED\x1b[0m. 3 passed\nRate limit");
+        assert_eq!(names(&matches), ["Tests failed"]);
+        assert_eq!(names(&matcher.finish()), ["Rate limited"]);
+        assert!(matcher.feed("rate limit\n").is_empty());
+    }
+
+    #[test]
+    fn test_case_sensitive_rules() {
+        let mut config = rule("Errors", "ERROR", false, "notify");
+        config.case_sensitive = true;
+        let mut matcher = AlertMatcher::new(&[config]);
+        assert!(matcher.feed("error: lowercase\n").is_empty());
+        assert_eq!(names(&matcher.feed("ERROR: uppercase\n")), ["Errors"]);
+    }
+
+    #[test]
+    fn test_scan_per_execution() {
+        let alerts = OutputAlerts::new();
+        let matches = alerts.scan("e1", "ERROR\n", OutputStatus::Running, rules);
+        assert_eq!(names(&matches), ["Errors"]);
+        let matches = alerts.scan("e2", "ERROR\n", OutputStatus::Running, rules);
+        assert_eq!(names(&matches), ["Errors"]);
+
+        // Output after the end raises nothing
+        let matches = alerts.scan("e1", "rate limit", OutputStatus::Completed, Vec::new);
+        assert_eq!(names(&matches), ["Rate limited"]);
+        assert!(alerts
+            .scan("e1", "test result: FAILED\n", OutputStatus::Completed, rules)
+            .is_empty());
+    }
+}
@@ -8,6 +8,7 @@
  * - Plugin discovery and loading
  */
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -15,7 +16,7 @@ use std::path::{Path, PathBuf};
 /**
  * Plugin metadata
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PluginManifest {
     pub id: String,
     pub name: String,
@@ -32,7 +33,7 @@ pub struct PluginManifest {
 /**
  * Plugin state
  */
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum PluginState {
     Installed,
     Enabled,
@@ -43,7 +44,7 @@ pub enum PluginState {
 /**
  * Plugin information
  */
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PluginInfo {
     pub manifest: PluginManifest,
     pub state: PluginState,
@@ -59,6 +60,7 @@ pub struct PluginInfo {
 pub struct PluginManager {
     plugins: HashMap<String, PluginInfo>,
     plugins_dir: PathBuf,
+    initialized: bool,
 }
 
 impl PluginManager {
@@ -69,9 +71,17 @@ impl PluginManager {
         Self {
             plugins: HashMap::new(),
             plugins_dir,
+            initialized: false,
         }
     }
 
+    /**
+     * Whether plugins were discovered yet
+     */
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
     /**
      * Initialize plugin manager and discover plugins
      */
@@ -84,6 +94,7 @@ impl PluginManager {
 
         // Discover plugins
         self.discover_plugins()?;
+        self.initialized = true;
 
         Ok(())
     }
@@ -291,6 +302,32 @@ mod tests {
         let manager = PluginManager::new(temp_dir.clone());
         assert_eq!(manager.plugins.len(), 0);
         assert_eq!(manager.plugins_dir, temp_dir);
+        assert!(!manager.is_initialized());
+    }
+
+    #[test]
+    fn test_initialize_discovers_plugins() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let plugin_dir = temp_dir.path().join("plugins").join("hello");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(
+            plugin_dir.join("plugin.json"),
+            r#"{
+                "id": "hello",
+                "name": "Hello",
+                "version": "1.0.0",
+                "author": "Test Author",
+                "description": "Says hello",
+                "entry_point": "main.js"
+            }"#,
+        )
+        .unwrap();
+
+        let mut manager = PluginManager::new(temp_dir.path().join("plugins"));
+        assert!(manager.list_plugins().is_empty());
+        manager.initialize().unwrap();
+        assert!(manager.is_initialized());
+        assert!(manager.get_plugin("hello").is_some());
     }
 
     #[test]
@@ -0,0 +1,96 @@
+//! Quick Task Window
+//!
+//! A global shortcut (`keybindings.quick_task`) pops up a small window for
+//! capturing a task while working in another app. The submitted task goes
+//! through the Coordinator, which picks the execution mode, and runs against
+//! the current workspace like any other session.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, GlobalShortcutManager, Manager, WindowBuilder, WindowUrl};
+
+use crate::state::AppState;
+
+/// Label of the quick task window
+pub const QUICK_TASK_WINDOW: &str = "quick-task";
+
+/// Shortcut currently registered for the quick task window
+static REGISTERED: Mutex<Option<String>> = Mutex::new(None);
+
+/// Register the shortcut from the config, logging if it cannot be registered
+pub fn init(app: &AppHandle) {
+    let shortcut = match app.state::<AppState>().config.lock() {
+        Ok(config) => config.keybindings.quick_task.clone(),
+        Err(e) => e.into_inner().keybindings.quick_task.clone(),
+    };
+    if let Err(e) = set_shortcut(app, &shortcut) {
+        tracing::warn!("Quick task shortcut unavailable: {}", e);
+    }
+}
+
+/// Replace the registered shortcut with `shortcut`, or remove it if empty
+///
+/// The previous shortcut stays registered if `shortcut` cannot be.
+pub fn set_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let shortcut = shortcut.trim();
+    let mut registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+    if registered.as_deref() == Some(shortcut) {
+        return Ok(());
+    }
+
+    let mut manager = app.global_shortcut_manager();
+    if !shortcut.is_empty() {
+        let handle = app.clone();
+        manager
+            .register(shortcut, move || open_window(&handle))
+            .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))?;
+        tracing::info!("Quick task shortcut: {}", shortcut);
+    }
+    if let Some(previous) = registered.take() {
+        if let Err(e) = manager.unregister(&previous) {
+            tracing::warn!("Failed to unregister shortcut {}: {}", previous, e);
+        }
+    }
+    if !shortcut.is_empty() {
+        *registered = Some(shortcut.to_string());
+    }
+    Ok(())
+}
+
+/// Show the quick task window, creating it on first use
+pub fn open_window(app: &AppHandle) {
+    let window = match app.get_window(QUICK_TASK_WINDOW) {
+        Some(window) => window,
+        None => {
+            let url = WindowUrl::App("index.html#quick-task".into());
+            let built = WindowBuilder::new(app, QUICK_TASK_WINDOW, url)
+                .title("Quick Task")
+                .inner_size(560.0, 132.0)
+                .resizable(false)
+                .decorations(false)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .center()
+                .build();
+            match built {
+                Ok(window) => window,
+                Err(e) => {
+                    tracing::warn!("Failed to open quick task window: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+    if let Err(e) = window.show().and_then(|_| window.set_focus()) {
+        tracing::warn!("Failed to show quick task window: {}", e);
+    }
+}
+
+/// Hide the quick task window until the shortcut is pressed again
+pub fn hide_window(app: &AppHandle) {
+    if let Some(window) = app.get_window(QUICK_TASK_WINDOW) {
+        if let Err(e) = window.hide() {
+            tracing::warn!("Failed to hide quick task window: {}", e);
+        }
+    }
+}
@@ -0,0 +1,612 @@
+//! Remote Control Server
+//!
+//! Optional WebSocket server through which external orchestration (CI bots,
+//! chatops) drives the editor: open files, run agents, query sessions and
+//! follow the events the backend emits. Clients authenticate with a token,
+//! sent as `Authorization: Bearer <token>` or as a `token` query parameter.
+//!
+//! Every message is a JSON object. Requests carry an `id` that is echoed in
+//! the reply, a `method` and its `params`:
+//!
+//! ```text
+//! -> {"id": 1, "method": "run_agent", "params": {"agentName": "bug-fixer", "task": "..."}}
+//! <- {"id": 1, "result": {...}}
+//! <- {"id": 2, "error": "Agent not found: bug-fixr"}
+//! ```
+//!
+//! After `subscribe`, the client also receives events as
+//! `{"event": "agent-output", "payload": {...}}`. Requests run concurrently,
+//! so replies may arrive out of order. A client too slow to keep up with
+//! the events gets a `lagged` event with the number it missed.
+
+use futures::future::BoxFuture;
+use futures::{SinkExt, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Environment variable holding the token, taking precedence over the config
+pub const TOKEN_ENV: &str = "AIT42_REMOTE_TOKEN";
+
+/// Events buffered per client before it counts as lagging
+const EVENT_BUFFER: usize = 256;
+
+/// Replies buffered per client while earlier ones are being sent
+const REPLY_BUFFER: usize = 64;
+
+/// Request of a remote client
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum RemoteRequest {
+    /// Open a file in a new editor tab
+    OpenFile { path: String },
+    /// Agents that can be run
+    ListAgents,
+    /// Run an agent and reply once it finished
+    #[serde(rename_all = "camelCase")]
+    RunAgent {
+        agent_name: String,
+        task: String,
+        #[serde(default)]
+        context: Option<String>,
+        /// ID to run under, for `cancel_agent` while it runs
+        #[serde(default)]
+        execution_id: Option<String>,
+    },
+    /// Cancel a running agent execution
+    #[serde(rename_all = "camelCase")]
+    CancelAgent { execution_id: String },
+    /// Output of an agent execution so far
+    #[serde(rename_all = "camelCase")]
+    GetAgentOutput { execution_id: String },
+    /// Competition, ensemble and debate sessions of a workspace
+    #[serde(rename_all = "camelCase")]
+    ListSessions { workspace_path: String },
+    /// One session of a workspace
+    #[serde(rename_all = "camelCase")]
+    GetSession {
+        workspace_path: String,
+        session_id: String,
+    },
+    /// Receive the named events, or all events if none are named
+    Subscribe {
+        #[serde(default)]
+        events: Vec<String>,
+    },
+    /// Stop receiving events
+    Unsubscribe,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientMessage {
+    id: u64,
+    #[serde(flatten)]
+    request: RemoteRequest,
+}
+
+/// Message sent to a remote client
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+enum ServerMessage {
+    Result { id: u64, result: serde_json::Value },
+    Error { id: Option<u64>, error: String },
+    Event(RemoteEvent),
+}
+
+impl ServerMessage {
+    fn reply(id: u64, result: Result<serde_json::Value, String>) -> Self {
+        match result {
+            Ok(result) => Self::Result { id, result },
+            Err(error) => Self::Error {
+                id: Some(id),
+                error,
+            },
+        }
+    }
+
+    fn to_message(&self) -> Message {
+        Message::Text(serde_json::to_string(self).unwrap_or_default())
+    }
+}
+
+/// Event forwarded to subscribed clients
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+/// Runs the requests of remote clients, other than (un)subscribing
+pub type Dispatch = Arc<
+    dyn Fn(RemoteRequest) -> BoxFuture<'static, Result<serde_json::Value, String>> + Send + Sync,
+>;
+
+/// State of the server as shown to the user
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    /// Token clients must present, while running
+    pub token: Option<String>,
+    pub clients: usize,
+}
+
+struct Server {
+    address: SocketAddr,
+    token: String,
+    clients: Arc<AtomicUsize>,
+    task: tauri::async_runtime::JoinHandle<()>,
+    /// Dropped on stop, which disconnects the clients
+    _shutdown: watch::Sender<()>,
+}
+
+/// The remote-control server and the events it forwards
+pub struct RemoteControl {
+    events: broadcast::Sender<RemoteEvent>,
+    server: Mutex<Option<Server>>,
+}
+
+impl Default for RemoteControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RemoteControl {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Self {
+            events,
+            server: Mutex::new(None),
+        }
+    }
+
+    /// Forward an event to the subscribed clients
+    pub fn publish(&self, event: &str, payload: &impl Serialize) {
+        if self.events.receiver_count() == 0 {
+            return;
+        }
+        match serde_json::to_value(payload) {
+            Ok(payload) => {
+                let _ = self.events.send(RemoteEvent {
+                    event: event.to_string(),
+                    payload,
+                });
+            }
+            Err(e) => tracing::warn!("Failed to serialize {} for remote clients: {}", event, e),
+        }
+    }
+
+    /// Listen on `bind` for clients presenting `token`
+    pub fn start(
+        &self,
+        bind: &str,
+        token: String,
+        dispatch: Dispatch,
+    ) -> Result<RemoteStatus, String> {
+        let mut server = self.server.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = server.as_ref() {
+            return Err(format!("Remote control is already listening on {}", running.address));
+        }
+
+        let listener = std::net::TcpListener::bind(bind)
+            .and_then(|listener| {
+                listener.set_nonblocking(true)?;
+                Ok(listener)
+            })
+            .map_err(|e| format!("Failed to listen on {}: {}", bind, e))?;
+        let address = listener.local_addr().map_err(|e| e.to_string())?;
+        if !address.ip().is_loopback() {
+            tracing::warn!("Remote control is reachable from the network on {}", address);
+        }
+
+        let clients = Arc::new(AtomicUsize::new(0));
+        let (shutdown, stopped) = watch::channel(());
+        let task = tauri::async_runtime::spawn(accept(
+            listener,
+            token.clone(),
+            dispatch,
+            self.events.clone(),
+            Arc::clone(&clients),
+            stopped,
+        ));
+        tracing::info!("Remote control listening on {}", address);
+        *server = Some(Server {
+            address,
+            token,
+            clients,
+            task,
+            _shutdown: shutdown,
+        });
+        drop(server);
+        Ok(self.status())
+    }
+
+    /// Stop the server, disconnecting its clients
+    pub fn stop(&self) -> bool {
+        match self.server.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(server) => {
+                server.task.abort();
+                tracing::info!("Remote control on {} stopped", server.address);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn status(&self) -> RemoteStatus {
+        match self
+            .server
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            Some(server) => RemoteStatus {
+                running: true,
+                address: Some(server.address.to_string()),
+                token: Some(server.token.clone()),
+                clients: server.clients.load(Ordering::Relaxed),
+            },
+            None => RemoteStatus {
+                running: false,
+                address: None,
+                token: None,
+                clients: 0,
+            },
+        }
+    }
+}
+
+/// Token from the environment, else from the config, else a random one
+pub fn resolve_token(configured: Option<&str>) -> String {
+    token_from(TOKEN_ENV, configured)
+}
+
+/// Token from the environment variable `env`, else `configured`, else a
+/// random one
+pub(crate) fn token_from(env: &str, configured: Option<&str>) -> String {
+    choose_token(std::env::var(env).ok(), configured)
+}
+
+/// `from_env` unless blank, else `configured`, else a random token
+fn choose_token(from_env: Option<String>, configured: Option<&str>) -> String {
+    from_env
+        .filter(|token| !token.trim().is_empty())
+        .or_else(|| configured.map(str::to_string))
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string())
+}
+
+/// Compare without leaking where the first difference is
+pub(crate) fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Whether the handshake `request` carries `token`
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let bearer = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    });
+    bearer
+        .or(query)
+        .is_some_and(|given| tokens_match(given, token))
+}
+
+async fn accept(
+    listener: std::net::TcpListener,
+    token: String,
+    dispatch: Dispatch,
+    events: broadcast::Sender<RemoteEvent>,
+    clients: Arc<AtomicUsize>,
+    stopped: watch::Receiver<()>,
+) {
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to start remote control: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("Failed to accept remote client: {}", e);
+                continue;
+            }
+        };
+
+        let (token, stopped) = (token.clone(), stopped.clone());
+        let (dispatch, events, clients) =
+            (Arc::clone(&dispatch), events.clone(), Arc::clone(&clients));
+        tauri::async_runtime::spawn(async move {
+            // tungstenite's handshake callback fixes the error type
+            #[allow(clippy::result_large_err)]
+            let authorize = |request: &Request, response: Response| {
+                if is_authorized(request, &token) {
+                    Ok(response)
+                } else {
+                    let mut denied =
+                        ErrorResponse::new(Some("Invalid or missing token".to_string()));
+                    *denied.status_mut() = StatusCode::UNAUTHORIZED;
+                    Err(denied)
+                }
+            };
+            let ws = match tokio_tungstenite::accept_hdr_async(stream, authorize).await {
+                Ok(ws) => ws,
+                Err(e) => {
+                    tracing::warn!("Rejected remote client {}: {}", peer, e);
+                    return;
+                }
+            };
+
+            tracing::info!("Remote client {} connected", peer);
+            clients.fetch_add(1, Ordering::Relaxed);
+            serve(ws, dispatch, events, stopped).await;
+            clients.fetch_sub(1, Ordering::Relaxed);
+            tracing::info!("Remote client {} disconnected", peer);
+        });
+    }
+}
+
+/// Next event of a subscription; never resolves without one
+async fn next_event(
+    subscription: &mut Option<(broadcast::Receiver<RemoteEvent>, Vec<String>)>,
+) -> Result<RemoteEvent, broadcast::error::RecvError> {
+    match subscription {
+        Some((events, _)) => events.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn serve(
+    ws: tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
+    dispatch: Dispatch,
+    events: broadcast::Sender<RemoteEvent>,
+    mut stopped: watch::Receiver<()>,
+) {
+    let (mut sink, mut stream) = ws.split();
+    let (replies, mut pending) = mpsc::channel::<ServerMessage>(REPLY_BUFFER);
+    let mut subscription = None;
+
+    loop {
+        let message = tokio::select! {
+            incoming = stream.next() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // Pings are answered by the WebSocket layer
+                    Some(Ok(_)) => continue,
+                };
+                match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage {
+                        id,
+                        request: RemoteRequest::Subscribe { events: names },
+                    }) => {
+                        subscription = Some((events.subscribe(), names));
+                        ServerMessage::reply(id, Ok(serde_json::Value::Null))
+                    }
+                    Ok(ClientMessage { id, request: RemoteRequest::Unsubscribe }) => {
+                        subscription = None;
+                        ServerMessage::reply(id, Ok(serde_json::Value::Null))
+                    }
+                    Ok(ClientMessage { id, request }) => {
+                        let (dispatch, replies) = (Arc::clone(&dispatch), replies.clone());
+                        tauri::async_runtime::spawn(async move {
+                            let result = dispatch(request).await;
+                            let _ = replies.send(ServerMessage::reply(id, result)).await;
+                        });
+                        continue;
+                    }
+                    Err(e) => ServerMessage::Error {
+                        id: serde_json::from_str::<serde_json::Value>(&text)
+                            .ok()
+                            .and_then(|value| value["id"].as_u64()),
+                        error: format!("Invalid request: {}", e),
+                    },
+                }
+            }
+            Some(reply) = pending.recv() => reply,
+            _ = stopped.changed() => break,
+            event = next_event(&mut subscription) => match event {
+                Ok(event) => {
+                    let wanted = subscription.as_ref().is_some_and(|(_, names)| {
+                        names.is_empty() || names.contains(&event.event)
+                    });
+                    if !wanted {
+                        continue;
+                    }
+                    ServerMessage::Event(event)
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    ServerMessage::Event(RemoteEvent {
+                        event: "lagged".to_string(),
+                        payload: serde_json::json!({ "missed": missed }),
+                    })
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+
+        if sink.send(message.to_message()).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    type Client = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    fn echo_dispatch() -> Dispatch {
+        Arc::new(|request| {
+            Box::pin(async move {
+                match request {
+                    RemoteRequest::OpenFile { path } => Ok(serde_json::json!({ "path": path })),
+                    _ => Err("Not supported".to_string()),
+                }
+            })
+        })
+    }
+
+    async fn connect(address: &str, token: Option<&str>) -> Result<Client, String> {
+        let mut request = format!("ws://{}/", address).into_client_request().unwrap();
+        if let Some(token) = token {
+            request
+                .headers_mut()
+                .insert("Authorization", format!("Bearer {}", token).parse().unwrap());
+        }
+        tokio_tungstenite::connect_async(request)
+            .await
+            .map(|(ws, _)| ws)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn call(client: &mut Client, request: serde_json::Value) -> serde_json::Value {
+        client
+            .send(Message::Text(request.to_string()))
+            .await
+            .unwrap();
+        receive(client).await
+    }
+
+    async fn receive(client: &mut Client) -> serde_json::Value {
+        loop {
+            match client.next().await {
+                Some(Ok(Message::Text(text))) => return serde_json::from_str(&text).unwrap(),
+                Some(Ok(_)) => continue,
+                other => panic!("Connection closed: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_requests() {
+        let message: ClientMessage = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "method": "run_agent",
+            "params": { "agentName": "bug-fixer", "task": "Fix it" },
+        }))
+        .unwrap();
+        assert_eq!(message.id, 7);
+        assert_eq!(
+            message.request,
+            RemoteRequest::RunAgent {
+                agent_name: "bug-fixer".to_string(),
+                task: "Fix it".to_string(),
+                context: None,
+                execution_id: None,
+            }
+        );
+
+        let message: ClientMessage =
+            serde_json::from_str(r#"{"id": 8, "method": "list_agents"}"#).unwrap();
+        assert_eq!(message.request, RemoteRequest::ListAgents);
+        assert!(serde_json::from_str::<ClientMessage>(r#"{"id": 9, "method": "rm_rf"}"#).is_err());
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret2", "secret"));
+        let from_env = || Some("from-env".to_string());
+        assert_eq!(choose_token(from_env(), Some("configured")), "from-env");
+        assert_eq!(choose_token(Some(" ".to_string()), Some("configured")), "configured");
+        assert_eq!(choose_token(None, Some("configured")), "configured");
+        assert_eq!(choose_token(None, None).len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_server_requires_token() {
+        let remote = RemoteControl::new();
+        let status = remote
+            .start("127.0.0.1:0", "secret".to_string(), echo_dispatch())
+            .unwrap();
+        let address = status.address.unwrap();
+        assert!(remote
+            .start("127.0.0.1:0", "x".to_string(), echo_dispatch())
+            .is_err());
+
+        assert!(connect(&address, None).await.is_err());
+        assert!(connect(&address, Some("wrong")).await.is_err());
+        let request = format!("ws://{}/?token=secret", address);
+        assert!(tokio_tungstenite::connect_async(request).await.is_ok());
+
+        assert!(remote.stop());
+        assert!(!remote.status().running);
+    }
+
+    #[tokio::test]
+    async fn test_requests_and_events() {
+        let remote = RemoteControl::new();
+        let status = remote
+            .start("127.0.0.1:0", "secret".to_string(), echo_dispatch())
+            .unwrap();
+        let mut client = connect(&status.address.unwrap(), Some("secret"))
+            .await
+            .unwrap();
+
+        let reply = call(
+            &mut client,
+            serde_json::json!({ "id": 1, "method": "open_file", "params": { "path": "/a.rs" } }),
+        )
+        .await;
+        assert_eq!(reply, serde_json::json!({ "id": 1, "result": { "path": "/a.rs" } }));
+
+        let reply =
+            call(&mut client, serde_json::json!({ "id": 2, "method": "list_agents" })).await;
+        assert_eq!(reply["error"], "Not supported");
+
+        let reply = call(&mut client, serde_json::json!({ "id": 3, "method": "nope" })).await;
+        assert_eq!(reply["id"], 3);
+        assert!(reply["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid request"));
+
+        // Only subscribed events are forwarded
+        remote.publish("agent-output", &serde_json::json!({ "seq": 0 }));
+        let reply = call(
+            &mut client,
+            serde_json::json!({
+                "id": 4,
+                "method": "subscribe",
+                "params": { "events": ["agent-output"] },
+            }),
+        )
+        .await;
+        assert_eq!(reply["id"], 4);
+        remote.publish("debate-status", &serde_json::json!({}));
+        remote.publish("agent-output", &serde_json::json!({ "seq": 1 }));
+        assert_eq!(
+            receive(&mut client).await,
+            serde_json::json!({ "event": "agent-output", "payload": { "seq": 1 } })
+        );
+        assert_eq!(remote.status().clients, 1);
+    }
+}
@@ -0,0 +1,271 @@
+//! Input Sanitization
+//!
+//! Task descriptions and other user text flow into agent prompts, shell
+//! commands typed into tmux with `send-keys`, log file names, git branch
+//! names and tmux session names. Each destination has its own rules, so
+//! text is checked once when it enters a command ([`task`]) and then escaped
+//! or reduced for the place it is used.
+//!
+//! Control characters are rejected outright: typed into a tmux pane, a
+//! carriage return or Ctrl-C acts as a keypress and can run commands the
+//! user never wrote.
+
+/// Longest task sent to an agent, in characters
+pub const MAX_PROMPT_CHARS: usize = 16_000;
+
+/// Longest slug used in branch, session or file names
+const MAX_SLUG_LEN: usize = 48;
+
+/// Check a task description entering a command
+///
+/// Rejects empty text and control characters other than newline and tab;
+/// Windows line endings are normalized to `\n`.
+pub fn task(input: &str) -> Result<String, String> {
+    text("Task", input)
+}
+
+/// Check user text such as a role prompt, naming it `label` in errors
+pub fn text(label: &str, input: &str) -> Result<String, String> {
+    let normalized = input.replace("\r\n", "\n");
+    if normalized.trim().is_empty() {
+        return Err(format!("{} cannot be empty", label));
+    }
+    if let Some(c) = normalized
+        .chars()
+        .find(|c| c.is_control() && *c != '\n' && *c != '\t')
+    {
+        return Err(format!("{} contains a control character (U+{:04X})", label, c as u32));
+    }
+    Ok(normalized)
+}
+
+/// Cap a prompt at `max_chars`, noting the truncation for the agent
+pub fn prompt(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}\n\n[Task truncated: showing {} of {} characters]", kept, max_chars, total)
+}
+
+/// Escape text for `echo -e '...'` in a shell command
+///
+/// Backslashes are doubled so `echo -e` prints them literally, newlines
+/// become `\n` so the command stays on one line, and single quotes close,
+/// escape and reopen the quoted string.
+pub fn echo_escaped(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\'', "'\\''")
+}
+
+/// Lowercase slug of `text` made of ASCII letters, digits and single dashes
+fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+        if slug.len() >= MAX_SLUG_LEN {
+            break;
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Branch-safe slug of `text`, e.g. a task or an ID, never empty
+///
+/// The result is always a valid git ref component.
+pub fn branch_slug(text: &str) -> String {
+    let slug = slug(text);
+    if slug.is_empty() {
+        "task".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Check a branch name given by the user against git's ref name rules
+pub fn branch_name(name: &str) -> Result<&str, String> {
+    let invalid = |reason: &str| Err(format!("Invalid branch name '{}': {}", name, reason));
+
+    if name.is_empty() {
+        return invalid("empty");
+    }
+    if name.starts_with('-') {
+        return invalid("starts with '-'");
+    }
+    if name
+        .chars()
+        .any(|c| c.is_control() || " ~^:?*[\\".contains(c))
+    {
+        return invalid("contains a space, control or special character");
+    }
+    if name.contains("..") || name.contains("@{") || name.contains("//") {
+        return invalid("contains '..', '@{' or '//'");
+    }
+    if name == "@" || name.ends_with('/') || name.ends_with(".lock") || name.ends_with('.') {
+        return invalid("bad ending");
+    }
+    if name
+        .split('/')
+        .any(|part| part.is_empty() || part.starts_with('.'))
+    {
+        return invalid("empty or hidden path component");
+    }
+    Ok(name)
+}
+
+/// tmux session name built from `name`
+///
+/// tmux treats `.` and `:` in targets as window and pane separators, so
+/// only ASCII letters, digits, `-` and `_` are kept.
+pub fn tmux_session_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "ait42".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// File name component built from `name`, such as a role ID
+///
+/// Never contains a path separator and never starts with a dot.
+pub fn file_component(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_SLUG_LEN)
+        .collect();
+    if cleaned.is_empty() {
+        "unnamed".to_string()
+    } else {
+        cleaned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_task_validation() {
+        assert_eq!(task("Fix the bug\r\nin main").unwrap(), "Fix the bug\nin main");
+        assert_eq!(task("tabs\tare fine").unwrap(), "tabs\tare fine");
+        assert!(task("   \n ").unwrap_err().contains("empty"));
+        assert!(task("run\rrm -rf ~").unwrap_err().contains("U+000D"));
+        assert!(task("ctrl-c \u{3}").is_err());
+        assert!(task("escape \u{1b}[2J").is_err());
+    }
+
+    #[test]
+    fn test_prompt_truncation() {
+        assert_eq!(prompt("short", 10), "short");
+
+        let capped = prompt("ありがとうございます", 3);
+        assert!(capped.starts_with("ありが\n\n[Task truncated: showing 3 of 10"));
+    }
+
+    #[test]
+    fn test_branch_slug() {
+        assert_eq!(branch_slug("Add OAuth login!"), "add-oauth-login");
+        assert_eq!(branch_slug("../../etc/passwd"), "etc-passwd");
+        assert_eq!(branch_slug("日本語"), "task");
+    }
+
+    #[test]
+    fn test_branch_name() {
+        assert!(branch_name("feature/login").is_ok());
+        assert!(branch_name("--upload-pack=evil").is_err());
+        assert!(branch_name("a..b").is_err());
+        assert!(branch_name("main.lock").is_err());
+        assert!(branch_name("a b").is_err());
+        assert!(branch_name("feature/.hidden").is_err());
+    }
+
+    #[test]
+    fn test_destination_names() {
+        assert_eq!(tmux_session_name("ait42-my.agent:1"), "ait42-my-agent-1");
+        assert_eq!(file_component("../critic"), "___critic");
+        assert_eq!(file_component(""), "unnamed");
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_task_never_passes_control_chars(input in any::<String>()) {
+            if let Ok(task) = task(&input) {
+                prop_assert!(!task.trim().is_empty());
+                prop_assert!(task.chars().all(|c| !c.is_control() || c == '\n' || c == '\t'));
+            }
+        }
+
+        #[test]
+        fn fuzz_prompt_is_capped(input in any::<String>(), max in 0usize..64) {
+            let capped = prompt(&input, max);
+            if input.chars().count() <= max {
+                prop_assert_eq!(capped, input);
+            } else {
+                prop_assert!(capped.contains("[Task truncated"));
+                prop_assert!(capped.starts_with(&input.chars().take(max).collect::<String>()));
+            }
+        }
+
+        #[test]
+        fn fuzz_echo_escaped_stays_quoted(input in any::<String>()) {
+            let escaped = echo_escaped(&input);
+            prop_assert!(!escaped.contains('\n'));
+            // Every quote is part of the close-escape-reopen sequence
+            prop_assert_eq!(escaped.matches('\'').count(), 3 * input.matches('\'').count());
+            prop_assert_eq!(escaped.matches("'\\''").count(), input.matches('\'').count());
+        }
+
+        #[test]
+        fn fuzz_branch_slug_is_valid_ref(input in any::<String>()) {
+            let slug = branch_slug(&input);
+            prop_assert!(branch_name(&slug).is_ok());
+            prop_assert!(slug.len() <= MAX_SLUG_LEN);
+        }
+
+        #[test]
+        fn fuzz_branch_name_rejects_options(input in "-.*") {
+            prop_assert!(branch_name(&input).is_err());
+        }
+
+        #[test]
+        fn fuzz_tmux_session_name_is_safe(input in any::<String>()) {
+            let name = tmux_session_name(&input);
+            prop_assert!(!name.is_empty());
+            prop_assert!(name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        }
+
+        #[test]
+        fn fuzz_file_component_stays_in_directory(input in any::<String>()) {
+            let name = file_component(&input);
+            prop_assert!(!name.is_empty());
+            prop_assert!(!name.contains('/') && !name.contains('\\'));
+            prop_assert!(!name.starts_with('.'));
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Session Encryption at Rest
+//!
+//! Chat histories and agent output stored with sessions may contain
+//! proprietary code. When encryption is enabled, each sessions file is
+//! written as an [`EncryptedSessions`] envelope: the JSON session list
+//! encrypted with AES-256-GCM under a key kept in the OS keychain.
+//!
+//! The session store reads both plain and encrypted files, so existing files
+//! keep working until `encrypt_sessions` migrates them. Once a cipher is
+//! active every file written is encrypted.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// Keychain service holding the editor's secrets
+pub(crate) const KEYCHAIN_SERVICE: &str = "ait42-editor";
+
+/// Keychain account holding the sessions key
+const KEYCHAIN_ACCOUNT: &str = "sessions-key";
+
+/// Envelope format written by this build
+const ENVELOPE_VERSION: u32 = 1;
+
+/// Cipher used by the session store, once encryption is active
+static SESSION_CIPHER: RwLock<Option<Arc<SessionCipher>>> = RwLock::new(None);
+
+/// An encrypted sessions file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedSessions {
+    pub encrypted: u32,
+    pub algorithm: String,
+    /// Base64 nonce, unique per write
+    pub nonce: String,
+    /// Base64 ciphertext with authentication tag
+    pub ciphertext: String,
+}
+
+impl EncryptedSessions {
+    /// Parse `content` if it is an encrypted envelope rather than a plain
+    /// session list
+    pub fn parse(content: &str) -> Option<Self> {
+        if !content.trim_start().starts_with('{') {
+            return None;
+        }
+        serde_json::from_str(content).ok()
+    }
+}
+
+/// Where the sessions key is kept
+pub trait KeyStore: Send + Sync {
+    /// The stored key, if one exists
+    fn load(&self) -> Result<Option<Vec<u8>>, String>;
+
+    /// Store `key`, replacing any existing key
+    fn store(&self, key: &[u8]) -> Result<(), String>;
+}
+
+/// Key stored in the OS keychain (Keychain, Credential Manager, Secret Service)
+#[derive(Debug, Default)]
+pub struct OsKeychain;
+
+impl OsKeychain {
+    fn entry(&self) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+            .map_err(|e| format!("Failed to open OS keychain: {}", e))
+    }
+}
+
+impl KeyStore for OsKeychain {
+    fn load(&self) -> Result<Option<Vec<u8>>, String> {
+        match self.entry()?.get_password() {
+            Ok(encoded) => BASE64
+                .decode(encoded)
+                .map(Some)
+                .map_err(|e| format!("Sessions key in OS keychain is corrupt: {}", e)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read sessions key from OS keychain: {}", e)),
+        }
+    }
+
+    fn store(&self, key: &[u8]) -> Result<(), String> {
+        self.entry()?
+            .set_password(&BASE64.encode(key))
+            .map_err(|e| format!("Failed to store sessions key in OS keychain: {}", e))
+    }
+}
+
+/// Encrypts and decrypts sessions files
+pub struct SessionCipher {
+    cipher: Aes256Gcm,
+}
+
+impl SessionCipher {
+    /// Cipher using `key`, which must be 32 bytes
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        if key.len() != 32 {
+            return Err(format!("Sessions key must be 32 bytes, got {}", key.len()));
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        })
+    }
+
+    /// Cipher using the stored key
+    pub fn load(store: &dyn KeyStore) -> Result<Option<Self>, String> {
+        store.load()?.map(|key| Self::new(&key)).transpose()
+    }
+
+    /// Cipher using the stored key, generating and storing one if needed
+    pub fn load_or_create(store: &dyn KeyStore) -> Result<Self, String> {
+        if let Some(cipher) = Self::load(store)? {
+            return Ok(cipher);
+        }
+        let key = Aes256Gcm::generate_key(OsRng);
+        store.store(&key)?;
+        Self::new(&key)
+    }
+
+    /// Encrypt a serialized session list into an envelope
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<EncryptedSessions, String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| "Failed to encrypt sessions".to_string())?;
+
+        Ok(EncryptedSessions {
+            encrypted: ENVELOPE_VERSION,
+            algorithm: "AES-256-GCM".to_string(),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    /// Decrypt an envelope back into the serialized session list
+    pub fn decrypt(&self, envelope: &EncryptedSessions) -> Result<Vec<u8>, String> {
+        if envelope.encrypted > ENVELOPE_VERSION {
+            return Err(format!(
+                "Sessions were encrypted by a newer version (format {})",
+                envelope.encrypted
+            ));
+        }
+        let nonce = BASE64
+            .decode(&envelope.nonce)
+            .map_err(|e| format!("Invalid sessions nonce: {}", e))?;
+        if nonce.len() != 12 {
+            return Err("Invalid sessions nonce length".to_string());
+        }
+        let ciphertext = BASE64
+            .decode(&envelope.ciphertext)
+            .map_err(|e| format!("Invalid sessions ciphertext: {}", e))?;
+
+        self.cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| "Failed to decrypt sessions: wrong key or corrupted file".to_string())
+    }
+}
+
+impl std::fmt::Debug for SessionCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionCipher").finish_non_exhaustive()
+    }
+}
+
+/// Encrypt every sessions file written from now on with `cipher`
+pub fn install(cipher: SessionCipher) {
+    let mut current = SESSION_CIPHER.write().unwrap_or_else(|e| e.into_inner());
+    *current = Some(Arc::new(cipher));
+}
+
+/// The active cipher, if encryption is enabled
+pub fn active() -> Option<Arc<SessionCipher>> {
+    SESSION_CIPHER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Enable encryption with the key in the OS keychain, creating it if needed
+pub fn enable() -> Result<Arc<SessionCipher>, String> {
+    if let Some(cipher) = active() {
+        return Ok(cipher);
+    }
+    install(SessionCipher::load_or_create(&OsKeychain)?);
+    active().ok_or_else(|| "Failed to enable session encryption".to_string())
+}
+
+/// Cipher for reading an encrypted file
+///
+/// Falls back to the key in the OS keychain when encryption has not been
+/// enabled in this run, and keeps it active so the file stays encrypted when
+/// it is written back.
+pub fn for_reading() -> Result<Arc<SessionCipher>, String> {
+    if let Some(cipher) = active() {
+        return Ok(cipher);
+    }
+    let cipher = SessionCipher::load(&OsKeychain)?.ok_or_else(|| {
+        "Sessions are encrypted but no key was found in the OS keychain".to_string()
+    })?;
+    install(cipher);
+    active().ok_or_else(|| "Failed to enable session encryption".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryKeyStore {
+        key: Mutex<Option<Vec<u8>>>,
+    }
+
+    impl KeyStore for MemoryKeyStore {
+        fn load(&self) -> Result<Option<Vec<u8>>, String> {
+            Ok(self.key.lock().unwrap().clone())
+        }
+
+        fn store(&self, key: &[u8]) -> Result<(), String> {
+            *self.key.lock().unwrap() = Some(key.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = SessionCipher::new(&[7; 32]).unwrap();
+        let envelope = cipher.encrypt(br#"[{"id":"s1"}]"#).unwrap();
+
+        assert!(!envelope.ciphertext.contains("s1"));
+        assert_eq!(cipher.decrypt(&envelope).unwrap(), br#"[{"id":"s1"}]"#);
+
+        // Each write uses a fresh nonce
+        assert_ne!(cipher.encrypt(b"[]").unwrap().nonce, cipher.encrypt(b"[]").unwrap().nonce);
+    }
+
+    #[test]
+    fn test_wrong_key_and_tampering_are_rejected() {
+        let cipher = SessionCipher::new(&[7; 32]).unwrap();
+        let mut envelope = cipher.encrypt(b"[]").unwrap();
+
+        let other = SessionCipher::new(&[8; 32]).unwrap();
+        assert!(other.decrypt(&envelope).is_err());
+
+        envelope.ciphertext = BASE64.encode(b"not the ciphertext at all");
+        assert!(cipher.decrypt(&envelope).is_err());
+
+        assert!(SessionCipher::new(&[7; 16]).is_err());
+    }
+
+    #[test]
+    fn test_load_or_create_reuses_stored_key() {
+        let store = MemoryKeyStore::default();
+        assert!(SessionCipher::load(&store).unwrap().is_none());
+
+        let first = SessionCipher::load_or_create(&store).unwrap();
+        let envelope = first.encrypt(b"[]").unwrap();
+
+        let second = SessionCipher::load_or_create(&store).unwrap();
+        assert_eq!(second.decrypt(&envelope).unwrap(), b"[]");
+    }
+
+    #[test]
+    fn test_parse_envelope() {
+        let cipher = SessionCipher::new(&[1; 32]).unwrap();
+        let json = serde_json::to_string(&cipher.encrypt(b"[]").unwrap()).unwrap();
+
+        assert!(EncryptedSessions::parse(&json).is_some());
+        assert!(EncryptedSessions::parse("[]").is_none());
+        assert!(EncryptedSessions::parse("{\"id\":\"s1\"}").is_none());
+    }
+}
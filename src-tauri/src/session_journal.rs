@@ -0,0 +1,546 @@
+//! Session Journals
+//!
+//! Besides the session store, the lifecycle of each competition, ensemble or
+//! debate is appended to a JSON lines journal, `journal/{session id}.jsonl`
+//! next to the store: its creation, each instance starting with the log its
+//! output goes to, a reference to every chunk of output emitted, status
+//! changes and its completion. Entries are never rewritten, so a journal
+//! reconstructs the session's history exactly for replays, and still tells
+//! what happened when writing to the store failed.
+//!
+//! The state a journal leads to is kept in memory while the session runs, so
+//! only changes are appended; it is read back from the journal on first use.
+//!
+//! While session encryption is active each entry is written as an encrypted
+//! envelope, as the session store does, so tasks and agent names are not
+//! left in plain text next to encrypted sessions.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::commands::session_history::{self, WorktreeSession};
+use crate::session_crypto::{self, EncryptedSessions, SessionCipher};
+use crate::state::AppState;
+
+/// Directory of the journals, relative to the sessions directory
+pub const JOURNAL_DIR: &str = "journal";
+
+/// Session statuses after which nothing more happens
+const FINISHED_STATUSES: &[&str] = &["completed", "failed", "cancelled"];
+
+/// Something that happened to a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum JournalEvent {
+    /// The session was first recorded
+    #[serde(rename_all = "camelCase")]
+    Created {
+        session_type: String,
+        task: String,
+        status: String,
+    },
+    /// An instance was launched
+    #[serde(rename_all = "camelCase")]
+    InstanceStarted {
+        instance_id: u32,
+        agent_name: String,
+        worktree_path: String,
+        tmux_session_id: String,
+        /// Log the instance's output is piped to, if known
+        log: Option<String>,
+    },
+    /// Output was emitted; the output itself is in the instance's log
+    #[serde(rename_all = "camelCase")]
+    OutputChunk {
+        instance_id: Option<u32>,
+        /// Frontend event the output was sent with
+        source: String,
+        /// Position among the session's chunks, starting at 1
+        seq: u64,
+        bytes: usize,
+    },
+    /// The session, or one of its instances, changed status
+    #[serde(rename_all = "camelCase")]
+    StatusChanged {
+        instance_id: Option<u32>,
+        status: String,
+    },
+    /// The session finished with `status`
+    Completed { status: String },
+}
+
+/// Entry of a session journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub session_id: String,
+    /// RFC 3339 timestamp
+    pub recorded_at: String,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Session as reconstructed from its journal
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalState {
+    pub created: bool,
+    pub status: Option<String>,
+    /// Status of each instance started
+    pub instances: BTreeMap<u32, String>,
+    /// Chunks of output emitted
+    pub output_chunks: u64,
+    pub completed: bool,
+}
+
+impl JournalState {
+    /// State after `event`
+    pub fn apply(&mut self, event: &JournalEvent) {
+        match event {
+            JournalEvent::Created { status, .. } => {
+                self.created = true;
+                self.status = Some(status.clone());
+            }
+            JournalEvent::InstanceStarted { instance_id, .. } => {
+                self.instances
+                    .entry(*instance_id)
+                    .or_insert_with(|| "running".to_string());
+            }
+            JournalEvent::OutputChunk { seq, .. } => self.output_chunks = *seq,
+            JournalEvent::StatusChanged {
+                instance_id: Some(id),
+                status,
+            } => {
+                self.instances.insert(*id, status.clone());
+            }
+            JournalEvent::StatusChanged {
+                instance_id: None,
+                status,
+            } => self.status = Some(status.clone()),
+            JournalEvent::Completed { status } => {
+                self.status = Some(status.clone());
+                self.completed = true;
+            }
+        }
+    }
+
+    /// Events taking the journal from this state to `session`
+    fn changes(&self, session: &WorktreeSession) -> Vec<JournalEvent> {
+        let mut events = Vec::new();
+        if !self.created {
+            events.push(JournalEvent::Created {
+                session_type: session.r#type.clone(),
+                task: session.task.clone(),
+                status: session.status.clone(),
+            });
+        } else if self.status.as_deref() != Some(session.status.as_str()) {
+            events.push(JournalEvent::StatusChanged {
+                instance_id: None,
+                status: session.status.clone(),
+            });
+        }
+        for instance in &session.instances {
+            let known = self.instances.get(&instance.instance_id);
+            if known.is_none() {
+                events.push(JournalEvent::InstanceStarted {
+                    instance_id: instance.instance_id,
+                    agent_name: instance.agent_name.clone(),
+                    worktree_path: instance.worktree_path.clone(),
+                    tmux_session_id: instance.tmux_session_id.clone(),
+                    log: None,
+                });
+            }
+            if known.map_or("running", String::as_str) != instance.status {
+                events.push(JournalEvent::StatusChanged {
+                    instance_id: Some(instance.instance_id),
+                    status: instance.status.clone(),
+                });
+            }
+        }
+        if !self.completed && FINISHED_STATUSES.contains(&session.status.as_str()) {
+            events.push(JournalEvent::Completed {
+                status: session.status.clone(),
+            });
+        }
+        events
+    }
+}
+
+/// State a journal leads to
+pub fn reconstruct(entries: &[JournalEntry]) -> JournalState {
+    let mut state = JournalState::default();
+    for entry in entries {
+        state.apply(&entry.event);
+    }
+    state
+}
+
+/// Journal file of `session_id` in `dir`
+fn journal_file(dir: &Path, session_id: &str) -> Result<PathBuf, String> {
+    if session_id.is_empty() || session_id.starts_with('.') || session_id.contains(['/', '\\']) {
+        return Err(format!("Invalid session ID for a journal: {:?}", session_id));
+    }
+    Ok(dir.join(format!("{}.jsonl", session_id)))
+}
+
+/// Content of the journal file `file`, empty if there is none
+fn read_file(file: &Path) -> Result<String, String> {
+    match std::fs::read_to_string(file) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", file.display(), e)),
+    }
+}
+
+/// Entries of the journal of `session_id` in `dir`, empty if there is none
+///
+/// Lines that cannot be read, such as one cut short by a crash, are skipped.
+pub fn read_journal(dir: &Path, session_id: &str) -> Result<Vec<JournalEntry>, String> {
+    let content = read_file(&journal_file(dir, session_id)?)?;
+    Ok(parse_journal(&content, session_id))
+}
+
+fn parse_journal(content: &str, session_id: &str) -> Vec<JournalEntry> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match decode_entry(line, session_crypto::for_reading) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("Skipping journal entry of {}: {}", session_id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parse a journal line, decrypting it if needed
+///
+/// `cipher` is only asked for when the line is encrypted.
+fn decode_entry(
+    line: &str,
+    cipher: impl FnOnce() -> Result<Arc<SessionCipher>, String>,
+) -> Result<JournalEntry, String> {
+    let json = match EncryptedSessions::parse(line) {
+        Some(envelope) => cipher()?.decrypt(&envelope)?,
+        None => line.as_bytes().to_vec(),
+    };
+    serde_json::from_slice(&json).map_err(|e| e.to_string())
+}
+
+/// Serialize a journal entry as one line, encrypted when `cipher` is given
+fn encode_entry(entry: &JournalEntry, cipher: Option<&SessionCipher>) -> Result<String, String> {
+    let json = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    match cipher {
+        Some(cipher) => {
+            serde_json::to_string(&cipher.encrypt(json.as_bytes())?).map_err(|e| e.to_string())
+        }
+        None => Ok(json),
+    }
+}
+
+/// Journals of running sessions, with the state each leads to
+#[derive(Default)]
+pub struct SessionJournals {
+    states: Mutex<HashMap<String, JournalState>>,
+}
+
+impl SessionJournals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the `events` of `session_id` to its journal in `dir`
+    ///
+    /// A finished session's state is forgotten; it is read back from the
+    /// journal if the session changes again.
+    pub fn append(
+        &self,
+        dir: &Path,
+        session_id: &str,
+        events: impl FnOnce(&JournalState) -> Vec<JournalEvent>,
+    ) -> Result<(), String> {
+        let file = journal_file(dir, session_id)?;
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        // A line cut short by a crash is ended before appending
+        let mut lines = String::new();
+        let state = match states.entry(session_id.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let content = read_file(&file)?;
+                if !content.is_empty() && !content.ends_with('\n') {
+                    lines.push('\n');
+                }
+                entry.insert(reconstruct(&parse_journal(&content, session_id)))
+            }
+        };
+        let events = events(state);
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let recorded_at = chrono::Utc::now().to_rfc3339();
+        let cipher = session_crypto::active();
+        for event in &events {
+            let entry = JournalEntry {
+                session_id: session_id.to_string(),
+                recorded_at: recorded_at.clone(),
+                event: event.clone(),
+            };
+            lines.push_str(&encode_entry(&entry, cipher.as_deref())?);
+            lines.push('\n');
+        }
+        std::fs::create_dir_all(dir)
+            .and_then(|_| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&file)?
+                    .write_all(lines.as_bytes())
+            })
+            .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+
+        for event in &events {
+            state.apply(event);
+        }
+        if state.completed {
+            states.remove(session_id);
+        }
+        Ok(())
+    }
+}
+
+/// Journal the launch of an instance of `session_id`, unless the session
+/// already recorded it, logging failures
+///
+/// `started` is an [`JournalEvent::InstanceStarted`].
+pub fn record_instance_started(state: &AppState, session_id: &str, started: JournalEvent) {
+    let dir = session_history::journal_dir(state);
+    let result = state
+        .session_journals
+        .append(&dir, session_id, |journaled| match &started {
+            JournalEvent::InstanceStarted { instance_id, .. }
+                if journaled.instances.contains_key(instance_id) =>
+            {
+                Vec::new()
+            }
+            _ => vec![started],
+        });
+    if let Err(e) = result {
+        tracing::warn!("Failed to journal session {}: {}", session_id, e);
+    }
+}
+
+/// Journal how `session` changed since it was last journaled, logging
+/// failures
+pub fn record_session(state: &AppState, session: &WorktreeSession) {
+    let dir = session_history::journal_dir(state);
+    let result = state
+        .session_journals
+        .append(&dir, &session.id, |journaled| journaled.changes(session));
+    if let Err(e) = result {
+        tracing::warn!("Failed to journal session {}: {}", session.id, e);
+    }
+}
+
+/// Journal instance `instance_id` of `session_id` changing to `status`,
+/// logging failures
+pub fn record_instance_status(state: &AppState, session_id: &str, instance_id: u32, status: &str) {
+    let dir = session_history::journal_dir(state);
+    let result = state
+        .session_journals
+        .append(&dir, session_id, |journaled| {
+            if journaled.instances.get(&instance_id).map(String::as_str) == Some(status) {
+                return Vec::new();
+            }
+            vec![JournalEvent::StatusChanged {
+                instance_id: Some(instance_id),
+                status: status.to_string(),
+            }]
+        });
+    if let Err(e) = result {
+        tracing::warn!("Failed to journal session {}: {}", session_id, e);
+    }
+}
+
+/// Journal a reference to output `event` of `session_id` carries, if any
+pub fn record_output(state: &AppState, session_id: &str, event: &str, payload: &serde_json::Value) {
+    let Some(output) = payload["output"]
+        .as_str()
+        .filter(|output| !output.is_empty())
+    else {
+        return;
+    };
+    let instance_id = payload["instance"].as_u64().map(|id| id as u32);
+    let dir = session_history::journal_dir(state);
+    let result = state
+        .session_journals
+        .append(&dir, session_id, |journaled| {
+            vec![JournalEvent::OutputChunk {
+                instance_id,
+                source: event.to_string(),
+                seq: journaled.output_chunks + 1,
+                bytes: output.len(),
+            }]
+        });
+    if let Err(e) = result {
+        tracing::warn!("Failed to journal output of session {}: {}", session_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(status: &str, instances: &[(u32, &str)]) -> WorktreeSession {
+        let instances: Vec<_> = instances
+            .iter()
+            .map(|(id, status)| {
+                serde_json::json!({
+                    "instanceId": id,
+                    "worktreePath": format!("/tmp/s1-{}", id),
+                    "branch": format!("competition-{}", id),
+                    "agentName": "claude",
+                    "status": status,
+                    "tmuxSessionId": format!("ait42-s1-{}", id),
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "id": "s1",
+            "type": "competition",
+            "task": "Fix the cache",
+            "status": status,
+            "createdAt": "2026-01-01T00:00:00Z",
+            "updatedAt": "2026-01-01T00:00:00Z",
+            "instances": instances,
+            "chatHistory": [],
+        }))
+        .unwrap()
+    }
+
+    fn events(dir: &Path) -> Vec<JournalEvent> {
+        read_journal(dir, "s1")
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect()
+    }
+
+    #[test]
+    fn test_journal_records_changes_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let journals = SessionJournals::new();
+        let record = |session: &WorktreeSession| {
+            journals
+                .append(dir.path(), "s1", |journaled| journaled.changes(session))
+                .unwrap()
+        };
+
+        record(&session("running", &[(1, "running")]));
+        record(&session("running", &[(1, "running")]));
+        journals
+            .append(dir.path(), "s1", |journaled| {
+                vec![JournalEvent::OutputChunk {
+                    instance_id: Some(1),
+                    source: "competition-output".to_string(),
+                    seq: journaled.output_chunks + 1,
+                    bytes: 4,
+                }]
+            })
+            .unwrap();
+        record(&session("completed", &[(1, "completed")]));
+
+        let journal = events(dir.path());
+        assert_eq!(journal.len(), 6);
+        assert!(matches!(journal[0], JournalEvent::Created { .. }));
+        assert!(matches!(journal[1], JournalEvent::InstanceStarted { instance_id: 1, .. }));
+        assert!(matches!(journal[2], JournalEvent::OutputChunk { seq: 1, .. }));
+        assert_eq!(
+            journal[3..],
+            [
+                JournalEvent::StatusChanged {
+                    instance_id: None,
+                    status: "completed".to_string()
+                },
+                JournalEvent::StatusChanged {
+                    instance_id: Some(1),
+                    status: "completed".to_string()
+                },
+                JournalEvent::Completed {
+                    status: "completed".to_string()
+                },
+            ]
+        );
+
+        let state = reconstruct(&read_journal(dir.path(), "s1").unwrap());
+        assert!(state.completed);
+        assert_eq!(state.instances[&1], "completed");
+        assert_eq!(state.output_chunks, 1);
+    }
+
+    #[test]
+    fn test_journal_is_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        SessionJournals::new()
+            .append(dir.path(), "s1", |journaled| {
+                journaled.changes(&session("running", &[(1, "running")]))
+            })
+            .unwrap();
+
+        // A crash cut the last line short
+        let file = journal_file(dir.path(), "s1").unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&file)
+            .unwrap()
+            .write_all(b"{\"sessionId\":\"s1\",\"rec")
+            .unwrap();
+
+        // A new process picks up where the journal left off
+        SessionJournals::new()
+            .append(dir.path(), "s1", |journaled| {
+                journaled.changes(&session("running", &[(1, "failed"), (2, "running")]))
+            })
+            .unwrap();
+        let journal = events(dir.path());
+        assert_eq!(journal.len(), 4);
+        assert!(matches!(
+            journal[2],
+            JournalEvent::StatusChanged {
+                instance_id: Some(1),
+                ..
+            }
+        ));
+        assert!(matches!(journal[3], JournalEvent::InstanceStarted { instance_id: 2, .. }));
+
+        assert!(read_journal(dir.path(), "../s1").is_err());
+        assert!(read_journal(dir.path(), "unknown").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encrypted_entries_round_trip() {
+        let cipher = SessionCipher::new(&[5; 32]).unwrap();
+        let entry = JournalEntry {
+            session_id: "s1".to_string(),
+            recorded_at: "2026-01-01T00:00:00Z".to_string(),
+            event: JournalState::default()
+                .changes(&session("running", &[]))
+                .remove(0),
+        };
+
+        let plain = encode_entry(&entry, None).unwrap();
+        assert_eq!(decode_entry(&plain, || Err("no key needed".to_string())).unwrap(), entry);
+
+        let encrypted = encode_entry(&entry, Some(&cipher)).unwrap();
+        assert!(!encrypted.contains("Fix the cache"));
+        assert!(!encrypted.contains('\n'));
+        let cipher = Arc::new(cipher);
+        assert_eq!(decode_entry(&encrypted, || Ok(Arc::clone(&cipher))).unwrap(), entry);
+        assert_eq!(decode_entry(&encrypted, || Err("no key".to_string())).unwrap_err(), "no key");
+    }
+}
@@ -0,0 +1,507 @@
+/**
+ * Session Store - Storage backends for worktree session history
+ *
+ * Sessions are grouped by a storage key per workspace (its workspace ID, or
+ * the hash of its path). A `SessionStore` holds the sessions of every key;
+ * `session_history` decides which key a workspace uses.
+ *
+ * Backends:
+ * - `json`: one pretty-printed JSON array per workspace, `{key}.json`
+ * - `jsonl`: one session per line, `{key}.jsonl`, easy to diff, append to and
+ *   copy between air-gapped machines
+ *
+ * With encryption at rest enabled, the `json` backend encrypts whole files
+ * and the `jsonl` backend encrypts each line (see `session_crypto`).
+ *
+ * `replicate` copies the sessions of one store into another, such as a
+ * shared directory for team-wide history.
+ */
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::session_history::{
+    modify_sessions_file, read_sessions_file, WorktreeSession, SESSION_WRITES,
+};
+use crate::session_crypto::{self, EncryptedSessions};
+
+/// Storage backend for the sessions of all workspaces
+///
+/// Implementations must serialize `modify` and `rename` against each other,
+/// and must not let readers observe a partial write.
+pub trait SessionStore: Send + Sync {
+    /// Backend name, as used in the `sessions.backend` config
+    fn backend(&self) -> &'static str;
+
+    /// Whether sessions are stored under `key`
+    fn contains(&self, key: &str) -> Result<bool, String>;
+
+    /// Sessions stored under `key`, empty if there are none
+    fn load(&self, key: &str) -> Result<Vec<WorktreeSession>, String>;
+
+    /// Load, modify and save the sessions under `key` as one update
+    ///
+    /// Nothing is written when `update` fails.
+    fn modify(
+        &self,
+        key: &str,
+        update: &mut dyn FnMut(&mut Vec<WorktreeSession>) -> Result<(), String>,
+    ) -> Result<(), String>;
+
+    /// Move the sessions under `from` to `to`, unless `to` already has some
+    ///
+    /// Returns whether anything was moved.
+    fn rename(&self, from: &str, to: &str) -> Result<bool, String>;
+
+    /// Remove all sessions under `key`
+    fn remove(&self, key: &str) -> Result<(), String>;
+
+    /// Keys that have sessions stored
+    fn keys(&self) -> Result<Vec<String>, String>;
+}
+
+impl<'a> dyn SessionStore + 'a {
+    /// Like [`SessionStore::modify`], returning what `update` returns
+    pub fn update<T>(
+        &self,
+        key: &str,
+        update: impl FnOnce(&mut Vec<WorktreeSession>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut update = Some(update);
+        let mut result = None;
+        self.modify(key, &mut |sessions| {
+            let update = update.take().ok_or("Session update ran twice")?;
+            result = Some(update(sessions)?);
+            Ok(())
+        })?;
+        result.ok_or_else(|| "Session update did not run".to_string())
+    }
+}
+
+/// Open the store of `backend` in `dir`, creating the directory if needed
+pub fn open(backend: &str, dir: &Path) -> Result<Box<dyn SessionStore>, String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create sessions directory at {:?}: {}", dir, e))?;
+    match backend {
+        "json" => Ok(Box::new(JsonStore::new(dir))),
+        "jsonl" => Ok(Box::new(JsonlStore::new(dir))),
+        other => Err(format!("Unknown session backend: {}", other)),
+    }
+}
+
+/// Reject keys that would escape the store directory
+fn check_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.starts_with('.') || key.contains(['/', '\\']) {
+        return Err(format!("Invalid session storage key: {:?}", key));
+    }
+    Ok(())
+}
+
+/// Keys of the files in `dir` with extension `ext`
+fn keys_in(dir: &Path, ext: &str) -> Result<Vec<String>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut keys: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == ext))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect();
+    keys.sort();
+    Ok(keys)
+}
+
+/// Move `from` to `to` unless `to` exists, under the write lock
+fn rename_file(from: &Path, to: &Path) -> Result<bool, String> {
+    let _guard = SESSION_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    if !from.exists() || to.exists() {
+        return Ok(false);
+    }
+    fs::rename(from, to)
+        .map_err(|e| format!("Failed to move {} to {}: {}", from.display(), to.display(), e))?;
+    Ok(true)
+}
+
+/// Remove `file` if it exists, under the write lock
+fn remove_file(file: &Path) -> Result<(), String> {
+    let _guard = SESSION_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    match fs::remove_file(file) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", file.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// One JSON array per workspace
+pub struct JsonStore {
+    dir: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn file(&self, key: &str) -> Result<PathBuf, String> {
+        check_key(key)?;
+        Ok(self.dir.join(format!("{}.json", key)))
+    }
+}
+
+impl SessionStore for JsonStore {
+    fn backend(&self) -> &'static str {
+        "json"
+    }
+
+    fn contains(&self, key: &str) -> Result<bool, String> {
+        Ok(self.file(key)?.exists())
+    }
+
+    fn load(&self, key: &str) -> Result<Vec<WorktreeSession>, String> {
+        read_sessions_file(&self.file(key)?)
+    }
+
+    fn modify(
+        &self,
+        key: &str,
+        update: &mut dyn FnMut(&mut Vec<WorktreeSession>) -> Result<(), String>,
+    ) -> Result<(), String> {
+        modify_sessions_file(&self.file(key)?, update)
+    }
+
+    fn rename(&self, from: &str, to: &str) -> Result<bool, String> {
+        rename_file(&self.file(from)?, &self.file(to)?)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        remove_file(&self.file(key)?)
+    }
+
+    fn keys(&self) -> Result<Vec<String>, String> {
+        keys_in(&self.dir, "json")
+    }
+}
+
+/// One session per line
+///
+/// When the same session ID appears on several lines the last one wins, so
+/// lines appended by hand or by other tools take effect.
+pub struct JsonlStore {
+    dir: PathBuf,
+}
+
+impl JsonlStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn file(&self, key: &str) -> Result<PathBuf, String> {
+        check_key(key)?;
+        Ok(self.dir.join(format!("{}.jsonl", key)))
+    }
+
+    fn read(file: &Path) -> Result<Vec<WorktreeSession>, String> {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let mut sessions: Vec<WorktreeSession> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let session = decode_line(line)
+                .map_err(|e| format!("{}:{}: {}", file.display(), index + 1, e))?;
+            match sessions.iter_mut().find(|s| s.id == session.id) {
+                Some(existing) => *existing = session,
+                None => sessions.push(session),
+            }
+        }
+        Ok(sessions)
+    }
+}
+
+/// Parse a line of a JSONL store, decrypting it if needed
+fn decode_line(line: &str) -> Result<WorktreeSession, String> {
+    let json = match EncryptedSessions::parse(line) {
+        Some(envelope) => session_crypto::for_reading()?.decrypt(&envelope)?,
+        None => line.as_bytes().to_vec(),
+    };
+    serde_json::from_slice(&json).map_err(|e| format!("Failed to parse session: {}", e))
+}
+
+/// Serialize a session as one line, encrypted when a cipher is active
+fn encode_line(session: &WorktreeSession) -> Result<String, String> {
+    let json = serde_json::to_string(session).map_err(|e| e.to_string())?;
+    match session_crypto::active() {
+        Some(cipher) => {
+            serde_json::to_string(&cipher.encrypt(json.as_bytes())?).map_err(|e| e.to_string())
+        }
+        None => Ok(json),
+    }
+}
+
+impl SessionStore for JsonlStore {
+    fn backend(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn contains(&self, key: &str) -> Result<bool, String> {
+        Ok(self.file(key)?.exists())
+    }
+
+    fn load(&self, key: &str) -> Result<Vec<WorktreeSession>, String> {
+        Self::read(&self.file(key)?)
+    }
+
+    fn modify(
+        &self,
+        key: &str,
+        update: &mut dyn FnMut(&mut Vec<WorktreeSession>) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let file = self.file(key)?;
+        let _guard = SESSION_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut sessions = Self::read(&file)?;
+        update(&mut sessions)?;
+
+        let mut content = String::new();
+        for session in &sessions {
+            content.push_str(&encode_line(session)?);
+            content.push('\n');
+        }
+        let temp_file = file.with_extension("jsonl.tmp");
+        fs::write(&temp_file, content).map_err(|e| e.to_string())?;
+        fs::rename(&temp_file, &file).map_err(|e| e.to_string())
+    }
+
+    fn rename(&self, from: &str, to: &str) -> Result<bool, String> {
+        rename_file(&self.file(from)?, &self.file(to)?)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        remove_file(&self.file(key)?)
+    }
+
+    fn keys(&self) -> Result<Vec<String>, String> {
+        keys_in(&self.dir, "jsonl")
+    }
+}
+
+/// Outcome of replicating one store into another
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// Workspaces that had sessions to replicate
+    pub workspaces: usize,
+    /// Sessions the target did not have
+    pub added: usize,
+    /// Sessions the target had an older copy of
+    pub updated: usize,
+    /// Sessions the target was already up to date with
+    pub unchanged: usize,
+}
+
+/// Copy the sessions of every workspace in `from` into `to`
+///
+/// A session the target already has is replaced only if the source copy was
+/// updated later, so replicating from several machines keeps the newest copy
+/// of each session. Sessions are never removed from the target; deletions
+/// replicate as sessions marked deleted.
+pub fn replicate(from: &dyn SessionStore, to: &dyn SessionStore) -> Result<SyncReport, String> {
+    let mut report = SyncReport::default();
+    for key in from.keys()? {
+        let local = from.load(&key)?;
+        if local.is_empty() {
+            continue;
+        }
+        report.workspaces += 1;
+
+        let mut changed = false;
+        let mut counts = (0, 0, 0);
+        to.modify(&key, &mut |remote| {
+            counts = (0, 0, 0);
+            for session in &local {
+                match remote.iter_mut().find(|s| s.id == session.id) {
+                    Some(existing) if is_newer(session, existing) => {
+                        *existing = session.clone();
+                        counts.1 += 1;
+                    }
+                    Some(_) => counts.2 += 1,
+                    None => {
+                        remote.push(session.clone());
+                        counts.0 += 1;
+                    }
+                }
+            }
+            changed = counts.0 + counts.1 > 0;
+            Ok(())
+        })?;
+        report.added += counts.0;
+        report.updated += counts.1;
+        report.unchanged += counts.2;
+        if changed {
+            tracing::debug!("Replicated sessions of {} to {} store", key, to.backend());
+        }
+    }
+    Ok(report)
+}
+
+/// Whether `a` was updated after `b`
+///
+/// Timestamps that do not parse compare as older than any that do.
+fn is_newer(a: &WorktreeSession, b: &WorktreeSession) -> bool {
+    let parse = |s: &WorktreeSession| {
+        DateTime::parse_from_rfc3339(&s.updated_at)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    };
+    parse(a) > parse(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, updated_at: &str) -> WorktreeSession {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "type": "competition",
+            "task": "sync",
+            "status": "completed",
+            "createdAt": "2026-01-01T00:00:00Z",
+            "updatedAt": updated_at,
+            "instances": [],
+            "chatHistory": [],
+        }))
+        .unwrap()
+    }
+
+    fn ids(store: &dyn SessionStore, key: &str) -> Vec<String> {
+        store.load(key).unwrap().into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn test_backends_store_and_rename() {
+        let dir = tempfile::tempdir().unwrap();
+        for backend in ["json", "jsonl"] {
+            let store = open(backend, &dir.path().join(backend)).unwrap();
+            assert_eq!(store.backend(), backend);
+            assert!(!store.contains("hash").unwrap());
+
+            let count = store
+                .update("hash", |sessions| {
+                    sessions.push(session("s1", "2026-01-01T00:00:00Z"));
+                    sessions.push(session("s2", "2026-01-01T00:00:00Z"));
+                    Ok(sessions.len())
+                })
+                .unwrap();
+            assert_eq!(count, 2);
+            assert_eq!(ids(store.as_ref(), "hash"), vec!["s1", "s2"]);
+
+            // A failed update writes nothing
+            let err = store.update("hash", |sessions| {
+                sessions.clear();
+                Err::<(), _>("nope".to_string())
+            });
+            assert_eq!(err.unwrap_err(), "nope");
+            assert_eq!(store.load("hash").unwrap().len(), 2);
+
+            assert!(store.rename("hash", "id").unwrap());
+            assert!(!store.rename("hash", "id").unwrap());
+            assert_eq!(store.keys().unwrap(), vec!["id"]);
+
+            store.remove("id").unwrap();
+            assert!(store.keys().unwrap().is_empty());
+            assert!(store.load("../escape").is_err());
+        }
+        assert!(open("sqlite", dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_jsonl_later_lines_win() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JsonlStore::new(dir.path());
+        let lines = [
+            serde_json::to_string(&session("s1", "2026-01-01T00:00:00Z")).unwrap(),
+            String::new(),
+            serde_json::to_string(&WorktreeSession {
+                status: "failed".to_string(),
+                ..session("s1", "2026-01-02T00:00:00Z")
+            })
+            .unwrap(),
+        ];
+        fs::write(dir.path().join("ws.jsonl"), lines.join("\n")).unwrap();
+
+        let sessions = store.load("ws").unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].status, "failed");
+
+        fs::write(dir.path().join("bad.jsonl"), "{not json}\n").unwrap();
+        assert!(store.load("bad").unwrap_err().contains("bad.jsonl:1"));
+    }
+
+    #[test]
+    fn test_replicate_keeps_newest_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = JsonStore::new(dir.path().join("local"));
+        let shared = JsonlStore::new(dir.path().join("shared"));
+        fs::create_dir_all(dir.path().join("local")).unwrap();
+        fs::create_dir_all(dir.path().join("shared")).unwrap();
+
+        let local: &dyn SessionStore = &local;
+        let shared: &dyn SessionStore = &shared;
+        local
+            .update("ws", |sessions| {
+                sessions.push(session("new", "2026-01-01T00:00:00Z"));
+                sessions.push(session("newer-here", "2026-03-01T00:00:00Z"));
+                sessions.push(session("newer-there", "2026-01-01T00:00:00Z"));
+                Ok(())
+            })
+            .unwrap();
+        shared
+            .update("ws", |sessions| {
+                sessions.push(session("newer-here", "2026-02-01T00:00:00Z"));
+                sessions.push(session("newer-there", "2026-02-01T00:00:00Z"));
+                sessions.push(session("remote-only", "2026-01-01T00:00:00Z"));
+                Ok(())
+            })
+            .unwrap();
+
+        let report = replicate(local, shared).unwrap();
+        assert_eq!(
+            report,
+            SyncReport {
+                workspaces: 1,
+                added: 1,
+                updated: 1,
+                unchanged: 1,
+            }
+        );
+
+        let remote = shared.load("ws").unwrap();
+        assert_eq!(remote.len(), 4);
+        let updated_at = |id: &str| {
+            remote
+                .iter()
+                .find(|s| s.id == id)
+                .map(|s| s.updated_at.clone())
+                .unwrap()
+        };
+        assert_eq!(updated_at("newer-here"), "2026-03-01T00:00:00Z");
+        assert_eq!(updated_at("newer-there"), "2026-02-01T00:00:00Z");
+
+        // Replicating again changes nothing
+        let again = replicate(local, shared).unwrap();
+        assert_eq!(again.unchanged, 3);
+    }
+}
@@ -0,0 +1,464 @@
+//! Session Event Streams
+//!
+//! Competitions and debates can export the events they emit as JSON lines,
+//! either appended to a file (for example on a shared drive) or written to a
+//! TCP socket. Another machine opens the stream in observer mode: its events
+//! are replayed to the frontend as `observed-session-event` events, and the
+//! session itself is read from a copy of the exporting machine's session
+//! store.
+//!
+//! Observed sessions are read-only. Commands that cancel or modify a session
+//! refuse those that are being observed.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
+
+use crate::session_journal;
+use crate::state::AppState;
+use crate::sync::lock;
+
+/// Event emitted for each event read from an observed stream
+pub const OBSERVED_EVENT: &str = "observed-session-event";
+
+/// How often a followed file is checked for new events
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait for an observer's socket to accept the connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One event of a competition or debate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    pub session_id: String,
+    /// Name of the frontend event, e.g. "competition-output"
+    pub event: String,
+    pub payload: serde_json::Value,
+    /// RFC 3339 timestamp
+    pub emitted_at: String,
+}
+
+/// Where an event stream is written to or read from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase", tag = "kind", content = "location")]
+pub enum StreamTarget {
+    File(PathBuf),
+    /// `host:port`
+    Socket(String),
+}
+
+impl StreamTarget {
+    /// Parse `tcp://host:port` as a socket and anything else as a file path
+    pub fn parse(target: &str) -> Result<Self, String> {
+        let target = target.trim();
+        if target.is_empty() {
+            return Err("Stream target cannot be empty".to_string());
+        }
+        match target.strip_prefix("tcp://") {
+            Some("") => Err(format!("Invalid socket address: {}", target)),
+            Some(addr) => Ok(Self::Socket(addr.to_string())),
+            None => Ok(Self::File(PathBuf::from(target))),
+        }
+    }
+}
+
+/// Session followed in observer mode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservedSession {
+    pub session_id: String,
+    pub source: StreamTarget,
+    /// Copy of the exporting machine's session store
+    pub session_store: Option<PathBuf>,
+    /// RFC 3339 timestamp
+    pub started_at: String,
+}
+
+struct Export {
+    target: StreamTarget,
+    socket: Option<TcpStream>,
+}
+
+impl Export {
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        match (&self.target, &mut self.socket) {
+            (_, Some(socket)) => socket.write_all(line.as_bytes()),
+            (StreamTarget::File(path), None) => std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(line.as_bytes()),
+            (StreamTarget::Socket(addr), None) => Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                format!("Not connected to {}", addr),
+            )),
+        }
+    }
+}
+
+struct Observer {
+    session: ObservedSession,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// Event exports of local sessions and the sessions being observed
+#[derive(Default)]
+pub struct SessionStreams {
+    exports: Mutex<HashMap<String, Vec<Export>>>,
+    observers: Mutex<HashMap<String, Observer>>,
+}
+
+impl SessionStreams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Export the events of `session_id` to `target` from now on
+    pub fn export(&self, session_id: &str, target: StreamTarget) -> Result<(), String> {
+        let socket = match &target {
+            StreamTarget::File(path) => {
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent).map_err(|e| {
+                        format!("Failed to create {}: {}", parent.display(), e)
+                    })?;
+                }
+                None
+            }
+            StreamTarget::Socket(addr) => Some(connect(addr)?),
+        };
+        lock(&self.exports)
+            .entry(session_id.to_string())
+            .or_default()
+            .push(Export { target, socket });
+        Ok(())
+    }
+
+    /// Stop exporting the events of `session_id`, returning the targets
+    pub fn stop_export(&self, session_id: &str) -> Vec<StreamTarget> {
+        lock(&self.exports)
+            .remove(session_id)
+            .map(|exports| exports.into_iter().map(|e| e.target).collect())
+            .unwrap_or_default()
+    }
+
+    /// Write an event to the exports of its session
+    ///
+    /// Exports that fail are logged and dropped.
+    pub fn publish(&self, event: &SessionEvent) {
+        let mut exports = lock(&self.exports);
+        let Some(targets) = exports.get_mut(&event.session_id) else {
+            return;
+        };
+        let line = match serde_json::to_string(event) {
+            Ok(json) => json + "\n",
+            Err(e) => {
+                tracing::warn!("Failed to serialize session event: {}", e);
+                return;
+            }
+        };
+        targets.retain_mut(|export| match export.write_line(&line) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(
+                    "Stopped exporting session {} to {:?}: {}",
+                    event.session_id,
+                    export.target,
+                    e
+                );
+                false
+            }
+        });
+        if targets.is_empty() {
+            exports.remove(&event.session_id);
+        }
+    }
+
+    /// Whether `session_id` is open in observer mode
+    pub fn is_observed(&self, session_id: &str) -> bool {
+        lock(&self.observers).contains_key(session_id)
+    }
+
+    /// Refuse changes to sessions open in observer mode
+    pub fn ensure_writable(&self, session_id: &str) -> Result<(), String> {
+        if self.is_observed(session_id) {
+            Err(format!(
+                "Session {} is open in observer mode and cannot be changed",
+                session_id
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sessions open in observer mode
+    pub fn observed(&self) -> Vec<ObservedSession> {
+        lock(&self.observers).values().map(|o| o.session.clone()).collect()
+    }
+
+    /// The observed session `session_id`
+    pub fn observed_session(&self, session_id: &str) -> Option<ObservedSession> {
+        lock(&self.observers).get(session_id).map(|o| o.session.clone())
+    }
+
+    /// Start following the events of `session.session_id`
+    pub fn observe(&self, app: tauri::AppHandle, session: ObservedSession) -> Result<(), String> {
+        let mut observers = lock(&self.observers);
+        if observers.contains_key(&session.session_id) {
+            return Err(format!("Session {} is already being observed", session.session_id));
+        }
+
+        let session_id = session.session_id.clone();
+        let task = match session.source.clone() {
+            StreamTarget::File(path) => {
+                tauri::async_runtime::spawn(follow_file(app, path, session_id))
+            }
+            StreamTarget::Socket(addr) => {
+                let listener = std::net::TcpListener::bind(&addr)
+                    .and_then(|listener| {
+                        listener.set_nonblocking(true)?;
+                        Ok(listener)
+                    })
+                    .map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+                tauri::async_runtime::spawn(listen(app, listener, session_id))
+            }
+        };
+        observers.insert(session.session_id.clone(), Observer { session, task });
+        Ok(())
+    }
+
+    /// Stop following `session_id`
+    pub fn stop_observing(&self, session_id: &str) -> bool {
+        match lock(&self.observers).remove(session_id) {
+            Some(observer) => {
+                observer.task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn connect(addr: &str) -> Result<TcpStream, String> {
+    use std::net::ToSocketAddrs;
+
+    let mut last_error = format!("No address found for {}", addr);
+    let addrs = addr
+        .to_socket_addrs()
+        .map_err(|e| format!("Invalid socket address {}: {}", addr, e))?;
+    for socket_addr in addrs {
+        match TcpStream::connect_timeout(&socket_addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = format!("Failed to connect to {}: {}", addr, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// Parse one exported line, keeping only events of `session_id`
+pub fn parse_event(line: &str, session_id: &str) -> Option<SessionEvent> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    match serde_json::from_str::<SessionEvent>(line) {
+        Ok(event) if event.session_id == session_id => Some(event),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::debug!("Skipping malformed session event: {}", e);
+            None
+        }
+    }
+}
+
+fn replay(app: &tauri::AppHandle, line: &str, session_id: &str) {
+    if let Some(event) = parse_event(line, session_id) {
+        if let Err(e) = app.emit_all(OBSERVED_EVENT, &event) {
+            tracing::warn!("Failed to emit observed event: {}", e);
+        }
+    }
+}
+
+/// Replay a file's events, then keep replaying what is appended to it
+async fn follow_file(app: tauri::AppHandle, path: PathBuf, session_id: String) {
+    let mut offset = 0u64;
+    let mut pending = String::new();
+
+    loop {
+        if let Ok(mut file) = tokio::fs::File::open(&path).await {
+            let len = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+            if len < offset {
+                // Truncated or replaced: start over
+                offset = 0;
+                pending.clear();
+            }
+            if len > offset && file.seek(std::io::SeekFrom::Start(offset)).await.is_ok() {
+                let mut bytes = Vec::new();
+                if let Ok(read) = file.read_to_end(&mut bytes).await {
+                    offset += read as u64;
+                    pending.push_str(&String::from_utf8_lossy(&bytes));
+                    // Keep an incomplete last line for the next read
+                    while let Some(end) = pending.find('\n') {
+                        let line: String = pending.drain(..=end).collect();
+                        replay(&app, &line, &session_id);
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+    }
+}
+
+/// Replay the events written by exporters connecting to `listener`
+async fn listen(app: tauri::AppHandle, listener: std::net::TcpListener, session_id: String) {
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to listen for session {}: {}", session_id, e);
+            return;
+        }
+    };
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("Failed to accept session stream: {}", e);
+                continue;
+            }
+        };
+        tracing::info!("Observing session {} from {}", session_id, peer);
+
+        let app = app.clone();
+        let session_id = session_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stream).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                replay(&app, &line, &session_id);
+            }
+            tracing::info!("Session stream from {} closed", peer);
+        });
+    }
+}
+
+/// Emit a session's event to the frontend and to its exports, journaling
+/// the output it carries
+pub(crate) fn emit_session_event(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    event: &str,
+    payload: serde_json::Value,
+) -> tauri::Result<()> {
+    let result = app.emit_all(event, payload.clone());
+    let state = app.state::<AppState>();
+    state.remote.publish(event, &payload);
+    session_journal::record_output(&state, session_id, event, &payload);
+    state.session_streams.publish(&SessionEvent {
+        session_id: session_id.to_string(),
+        event: event.to_string(),
+        payload,
+        emitted_at: chrono::Utc::now().to_rfc3339(),
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(session_id: &str, output: &str) -> SessionEvent {
+        SessionEvent {
+            session_id: session_id.to_string(),
+            event: "competition-output".to_string(),
+            payload: serde_json::json!({ "instance": 1, "output": output }),
+            emitted_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            StreamTarget::parse("tcp://10.0.0.2:7420").unwrap(),
+            StreamTarget::Socket("10.0.0.2:7420".to_string())
+        );
+        assert_eq!(
+            StreamTarget::parse("/mnt/shared/run.jsonl").unwrap(),
+            StreamTarget::File(PathBuf::from("/mnt/shared/run.jsonl"))
+        );
+        assert!(StreamTarget::parse("  ").is_err());
+        assert!(StreamTarget::parse("tcp://").is_err());
+    }
+
+    #[test]
+    fn test_file_export_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("streams").join("a.jsonl");
+        let streams = SessionStreams::new();
+
+        // Nothing is written before the export starts
+        streams.publish(&event("a", "early"));
+        streams.export("a", StreamTarget::File(path.clone())).unwrap();
+        streams.publish(&event("a", "hello"));
+        streams.publish(&event("b", "other session"));
+        streams.publish(&event("a", "world"));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let outputs: Vec<_> = contents
+            .lines()
+            .filter_map(|line| parse_event(line, "a"))
+            .map(|e| e.payload["output"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(outputs, ["hello", "world"]);
+        assert!(contents.lines().all(|line| parse_event(line, "b").is_none()));
+
+        assert_eq!(streams.stop_export("a"), [StreamTarget::File(path.clone())]);
+        streams.publish(&event("a", "late"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_socket_export() {
+        use std::io::BufRead;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let streams = SessionStreams::new();
+        streams.export("a", StreamTarget::Socket(addr)).unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        streams.publish(&event("a", "hello"));
+        let mut line = String::new();
+        std::io::BufReader::new(stream).read_line(&mut line).unwrap();
+        assert_eq!(parse_event(&line, "a").unwrap().payload["output"], "hello");
+
+        assert!(StreamTarget::parse("tcp://127.0.0.1:1")
+            .map(|target| streams.export("c", target))
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_event_skips_garbage() {
+        assert!(parse_event("", "a").is_none());
+        assert!(parse_event("not json", "a").is_none());
+        let line = serde_json::to_string(&event("a", "x")).unwrap();
+        assert!(parse_event(&line, "a").is_some());
+        assert!(parse_event(&line, "b").is_none());
+    }
+
+    #[test]
+    fn test_unobserved_sessions_are_writable() {
+        let streams = SessionStreams::new();
+        assert!(!streams.is_observed("a"));
+        assert!(streams.ensure_writable("a").is_ok());
+        assert!(!streams.stop_observing("a"));
+        assert!(streams.observed().is_empty());
+    }
+}
@@ -3,19 +3,37 @@
 //! Manages the global application state shared between Tauri commands.
 //! Uses Arc<Mutex<T>> for thread-safe access.
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::collections::HashMap;
 use ait42_config::Config;
+use ait42_fs::TodoIndex;
+use ait42_git::CommitMessage;
 use ait42_core::{Editor, EditorConfig, EditorState, buffer::BufferManager};
-use ait42_lsp::{LspConfig, LspManager};
+use ait42_lsp::{LspConfig, LspManager, ResourceWatchdog};
 use ait42_ait42::{AgentRegistry, AgentExecutor, Coordinator, config::AIT42Config};
+use crate::agent_output::AgentOutputStreams;
+use crate::budget::SpendGuard;
+use crate::clipboard_history::ClipboardHistory;
+use crate::confirmation::ConfirmationGate;
+use crate::executions::RunningExecutions;
+use crate::mcp::McpServer;
+use crate::notifications::NotificationCenter;
+use crate::output_alerts::OutputAlerts;
 use crate::plugin::PluginManager;
+use crate::remote::RemoteControl;
+use crate::session_journal::SessionJournals;
+use crate::session_stream::SessionStreams;
+use crate::tools::ToolRegistry;
+use crate::tray::ScheduledRuns;
 use crate::commands::ait42::{DebateStatus, RoundOutput};
+use crate::commands::review::StoredReview;
 
 // Import TerminalExecutor from ait42-tui if available
 // Note: This will compile if ait42-tui is in dependencies
 #[cfg(feature = "terminal")]
 use ait42_tui::terminal_executor::TerminalExecutor;
+#[cfg(feature = "terminal")]
+use ait42_tui::SavedHistory;
 
 /// Application-wide state
 pub struct AppState {
@@ -34,7 +52,20 @@ pub struct AppState {
     /// LSP manager for multiple language servers
     pub lsp_manager: Arc<LspManager>,
 
-    /// Plugin manager for extensibility
+    /// Watchdog for language servers using excessive CPU or memory
+    pub lsp_watchdog: Arc<tokio::sync::Mutex<ResourceWatchdog>>,
+
+    /// Notification center for background events
+    pub notifications: Arc<NotificationCenter>,
+
+    /// Estimated AI spend checked against the budget limits
+    pub spend_guard: Arc<SpendGuard>,
+
+    /// Recently copied text that can be pasted again
+    pub clipboard_history: ClipboardHistory,
+
+    /// Plugin manager for extensibility, discovering plugins on first use
+    /// (see [`AppState::plugins`])
     pub plugin_manager: Arc<Mutex<PluginManager>>,
 
     /// Working directory for git operations - uses tokio::sync::Mutex for async
@@ -43,6 +74,45 @@ pub struct AppState {
     /// Debate status tracking - uses Arc<Mutex> for thread-safe access
     pub debates: Arc<Mutex<HashMap<String, DebateStatus>>>,
 
+    /// Drafted commit messages keyed by staged diff hash
+    pub commit_messages: Mutex<HashMap<String, CommitMessage>>,
+
+    /// Findings of the last code review, shown as diagnostics
+    pub review: Mutex<Option<StoredReview>>,
+
+    /// TODO comments of the workspace, built when first listed
+    pub todos: Mutex<Option<TodoIndex>>,
+
+    /// Exported event streams and sessions open in observer mode
+    pub session_streams: Arc<SessionStreams>,
+
+    /// Lifecycle journals of running sessions
+    pub session_journals: SessionJournals,
+
+    /// Output of agent executions, kept for listeners that reconnect
+    pub agent_output: Arc<AgentOutputStreams>,
+
+    /// Alert rules matched against agent output, and the alerts raised
+    pub output_alerts: OutputAlerts,
+
+    /// Agent executions in flight, with their cancellation tokens
+    pub executions: RunningExecutions,
+
+    /// WebSocket server for remote control by CI bots and chatops
+    pub remote: RemoteControl,
+
+    /// MCP server offering editor context and actions to external agents
+    pub mcp: McpServer,
+
+    /// Runs tmux and git for agent pipelines, or simulates them in chaos mode
+    pub tools: ToolRegistry,
+
+    /// Tokens for destructive commands the user confirmed
+    pub confirmations: ConfirmationGate,
+
+    /// Pauses automatic checkpoints and session purging from the tray
+    pub scheduled_runs: ScheduledRuns,
+
     /// Terminal executor (optional feature) - uses tokio::sync::Mutex for async
     #[cfg(feature = "terminal")]
     pub terminal: Arc<tokio::sync::Mutex<TerminalExecutor>>,
@@ -50,6 +120,9 @@ pub struct AppState {
     /// AIT42 agent registry for discovering and managing agents
     pub agent_registry: Arc<Mutex<Option<AgentRegistry>>>,
 
+    /// Reloads the agent registry when agent files change
+    pub agent_watch: Mutex<Option<tokio::task::JoinHandle<()>>>,
+
     /// AIT42 agent executor for running agents
     pub agent_executor: Arc<tokio::sync::Mutex<Option<AgentExecutor>>>,
 
@@ -73,39 +146,80 @@ impl AppState {
 
         // Initialize LSP manager with default configuration
         let lsp_config = LspConfig::default();
+        let lsp_watchdog = ResourceWatchdog::new(lsp_config.watchdog.clone());
         let lsp_manager = LspManager::new(lsp_config);
+        if let Err(e) = lsp_manager.set_workspace_root(&working_dir) {
+            tracing::warn!("Failed to set LSP workspace root: {}", e);
+        }
 
-        // Initialize plugin manager
+        // Plugins are discovered when first listed, not at startup
         let plugins_dir = std::env::current_dir()
             .unwrap_or_else(|_| std::path::PathBuf::from("."))
             .join("plugins");
-        let mut plugin_manager = PluginManager::new(plugins_dir);
-        plugin_manager
-            .initialize()
-            .unwrap_or_else(|e| eprintln!("Failed to initialize plugin manager: {}", e));
+        let plugin_manager = PluginManager::new(plugins_dir);
 
         // Initialize AIT42 agent system (lazy initialization - will be initialized on first use)
         let agent_registry = Arc::new(Mutex::new(None));
         let agent_executor = Arc::new(tokio::sync::Mutex::new(None));
         let coordinator = Arc::new(tokio::sync::Mutex::new(None));
 
+        // The terminal picks up the commands run in the workspace before
+        #[cfg(feature = "terminal")]
+        let terminal = {
+            let mut terminal = TerminalExecutor::new(working_dir.clone());
+            terminal.set_history(SavedHistory::load(&working_dir).terminal);
+            terminal
+        };
+
         Ok(Self {
             editor: Arc::new(Mutex::new(editor)),
             editor_state: Arc::new(Mutex::new(editor_state)),
             buffer_manager: Mutex::new(BufferManager::new()),
             config: Mutex::new(Config::default()),
             lsp_manager: Arc::new(lsp_manager),
+            lsp_watchdog: Arc::new(tokio::sync::Mutex::new(lsp_watchdog)),
+            notifications: Arc::new(NotificationCenter::new()),
+            spend_guard: Arc::new(SpendGuard::load_default()),
+            clipboard_history: ClipboardHistory::load_default(),
             plugin_manager: Arc::new(Mutex::new(plugin_manager)),
             working_dir: Arc::new(tokio::sync::Mutex::new(working_dir.clone())),
             debates: Arc::new(Mutex::new(HashMap::new())),
+            commit_messages: Mutex::new(HashMap::new()),
+            review: Mutex::new(None),
+            todos: Mutex::new(None),
+            session_streams: Arc::new(SessionStreams::new()),
+            session_journals: SessionJournals::new(),
+            agent_output: Arc::new(AgentOutputStreams::new()),
+            output_alerts: OutputAlerts::new(),
+            executions: RunningExecutions::default(),
+            remote: RemoteControl::new(),
+            mcp: McpServer::default(),
+            tools: ToolRegistry::from_env(),
+            confirmations: ConfirmationGate::default(),
+            scheduled_runs: ScheduledRuns::default(),
             #[cfg(feature = "terminal")]
-            terminal: Arc::new(tokio::sync::Mutex::new(TerminalExecutor::new(working_dir))),
+            terminal: Arc::new(tokio::sync::Mutex::new(terminal)),
             agent_registry,
+            agent_watch: Mutex::new(None),
             agent_executor,
             coordinator,
         })
     }
 
+    /// Plugin manager, discovering plugins the first time it is used
+    pub fn plugins(&self) -> Result<MutexGuard<'_, PluginManager>, String> {
+        let mut manager = self
+            .plugin_manager
+            .lock()
+            .map_err(|e| format!("Failed to lock plugin manager: {}", e))?;
+        if !manager.is_initialized() {
+            let started = std::time::Instant::now();
+            manager.initialize()?;
+            tracing::debug!("Discovered plugins in {:?}", started.elapsed());
+        }
+        Ok(manager)
+    }
+
     /// Create default application state
     ///
     /// Uses current working directory for terminal.
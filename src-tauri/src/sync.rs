@@ -0,0 +1,11 @@
+//! Synchronization Helpers
+
+use std::sync::{Mutex, MutexGuard};
+
+/// Lock `mutex`, recovering the data if a thread panicked while holding it
+///
+/// State behind these locks stays consistent between statements, so a
+/// panic elsewhere must not take down every later caller.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,760 @@
+//! Scriptable Fake Tools
+//!
+//! `FakeTools` stands in for tmux and git so competition, debate and session
+//! monitoring logic can be exercised deterministically. Sessions advance one
+//! step each time they are checked with `tmux has-session`: after a number of
+//! startup polls the agent writes one output chunk per poll to the session's
+//! `pipe-pane` log, then exits, hangs or disappears as its [`SessionScript`]
+//! says. [`ToolFault`]s make individual invocations slow, fail with a nonzero
+//! exit, or fail to start at all. Branches created with `git worktree add -b`
+//! are tracked, so name collisions fail as they would against a real
+//! repository.
+//!
+//! A scenario is plain JSON, so the same faults can be injected into a
+//! running editor through `AIT42_FAKE_TOOLS`:
+//!
+//! ```json
+//! {
+//!   "faults": [{ "program": "git", "args": ["worktree", "add"], "times": 1, "exitCode": 128 }],
+//!   "sessions": [{ "session": "-r2-", "output": ["partial"], "exit": { "kind": "vanish" } }],
+//!   "defaultSession": { "startupPolls": 2, "output": ["done\n"] }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{exit_status, ToolInvocation, ToolRunner};
+
+/// How a simulated agent finishes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionExit {
+    /// The agent exits with `code`
+    ///
+    /// Agents are launched as `cmd && exit`, so only a zero code closes the
+    /// session; otherwise the shell stays open like a hung agent.
+    Exit { code: i32 },
+    /// The session disappears, as if it was killed or tmux crashed
+    Vanish,
+    /// The agent never finishes
+    Hang,
+}
+
+impl Default for SessionExit {
+    fn default() -> Self {
+        SessionExit::Exit { code: 0 }
+    }
+}
+
+/// Behaviour of the agent launched in a fake tmux session
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionScript {
+    /// `has-session` checks that pass before the agent writes anything
+    pub startup_polls: u32,
+    /// Chunks appended to the session log, one per `has-session` check
+    pub output: Vec<String>,
+    /// What the agent does once all output is written
+    pub exit: SessionExit,
+}
+
+#[cfg(test)]
+impl SessionScript {
+    /// Agent that exits successfully without output
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait `polls` checks before writing output
+    pub fn startup_polls(mut self, polls: u32) -> Self {
+        self.startup_polls = polls;
+        self
+    }
+
+    /// Append an output chunk
+    pub fn output(mut self, chunk: impl Into<String>) -> Self {
+        self.output.push(chunk.into());
+        self
+    }
+
+    /// Set how the agent finishes
+    pub fn exit(mut self, exit: SessionExit) -> Self {
+        self.exit = exit;
+        self
+    }
+}
+
+/// Session script applied to sessions whose name contains `session`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRule {
+    pub session: String,
+    #[serde(flatten)]
+    pub script: SessionScript,
+}
+
+/// Fault injected into matching invocations
+///
+/// A fault matches invocations of `program` whose arguments start with
+/// `args` and, if set, have an argument containing `contains`. It delays the
+/// invocation by `delay_ms`, then fails to start it (`spawn_error`), returns
+/// the given exit code and output (`exit_code`), or lets it run normally.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ToolFault {
+    pub program: String,
+    pub args: Vec<String>,
+    pub contains: Option<String>,
+    /// Number of matching invocations affected, all if unset
+    pub times: Option<u32>,
+    pub delay_ms: u64,
+    pub spawn_error: Option<String>,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[cfg(test)]
+impl ToolFault {
+    /// Fault matching invocations of `program` starting with `args`
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Only match invocations with an argument containing `text`
+    pub fn contains(mut self, text: &str) -> Self {
+        self.contains = Some(text.to_string());
+        self
+    }
+
+    /// Only affect the first `times` matching invocations
+    pub fn times(mut self, times: u32) -> Self {
+        self.times = Some(times);
+        self
+    }
+
+    /// Delay matching invocations
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay_ms = delay.as_millis() as u64;
+        self
+    }
+
+    /// Fail with `code`, writing `stderr`
+    pub fn exit_code(mut self, code: i32, stderr: &str) -> Self {
+        self.exit_code = Some(code);
+        self.stderr = stderr.to_string();
+        self
+    }
+
+    /// Fail to start, as if the program was not installed
+    pub fn spawn_error(mut self, message: &str) -> Self {
+        self.spawn_error = Some(message.to_string());
+        self
+    }
+}
+
+impl ToolFault {
+    fn matches(&self, invocation: &ToolInvocation) -> bool {
+        self.program == invocation.program
+            && invocation.args.starts_with(&self.args)
+            && self
+                .contains
+                .as_ref()
+                .map_or(true, |text| invocation.args.iter().any(|a| a.contains(text)))
+    }
+}
+
+/// Faults and session scripts of a fake tool run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FakeScenario {
+    pub faults: Vec<ToolFault>,
+    pub sessions: Vec<SessionRule>,
+    pub default_session: SessionScript,
+}
+
+impl FakeScenario {
+    /// Scenario where every tool succeeds and agents exit immediately
+    #[cfg(test)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a scenario from a JSON file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid scenario {}: {}", path.display(), e))
+    }
+
+    /// Add a fault
+    #[cfg(test)]
+    pub fn fault(mut self, fault: ToolFault) -> Self {
+        self.faults.push(fault);
+        self
+    }
+
+    /// Script sessions whose name contains `pattern`
+    #[cfg(test)]
+    pub fn session(mut self, pattern: &str, script: SessionScript) -> Self {
+        self.sessions.push(SessionRule {
+            session: pattern.to_string(),
+            script,
+        });
+        self
+    }
+
+    /// Script sessions no rule matches
+    #[cfg(test)]
+    pub fn default_session(mut self, script: SessionScript) -> Self {
+        self.default_session = script;
+        self
+    }
+
+    fn script_for(&self, session: &str) -> SessionScript {
+        self.sessions
+            .iter()
+            .find(|rule| session.contains(&rule.session))
+            .map(|rule| rule.script.clone())
+            .unwrap_or_else(|| self.default_session.clone())
+    }
+}
+
+/// A simulated tmux session
+#[derive(Debug)]
+struct FakeSession {
+    script: SessionScript,
+    log: Option<PathBuf>,
+    captured: String,
+    sent: Vec<String>,
+    polls: u32,
+    written: usize,
+}
+
+impl FakeSession {
+    /// Advance one poll, returning whether the session still exists
+    fn poll(&mut self) -> bool {
+        if self.sent.is_empty() {
+            return true;
+        }
+
+        self.polls += 1;
+        if self.polls <= self.script.startup_polls {
+            return true;
+        }
+
+        if let Some(chunk) = self.script.output.get(self.written).cloned() {
+            self.written += 1;
+            self.write(&chunk);
+            return true;
+        }
+
+        match self.script.exit {
+            SessionExit::Exit { code } => code != 0,
+            SessionExit::Vanish => false,
+            SessionExit::Hang => true,
+        }
+    }
+
+    fn write(&mut self, chunk: &str) {
+        self.captured.push_str(chunk);
+        let Some(log) = &self.log else {
+            return;
+        };
+        let result = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log)
+            .and_then(|mut file| file.write_all(chunk.as_bytes()));
+        if let Err(e) = result {
+            tracing::warn!("Fake session failed to write {}: {}", log.display(), e);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    /// Session names in creation order
+    order: Vec<String>,
+    sessions: HashMap<String, FakeSession>,
+    branches: HashSet<String>,
+    invocations: Vec<ToolInvocation>,
+    fault_hits: Vec<u32>,
+}
+
+impl FakeState {
+    fn remove(&mut self, name: &str) -> bool {
+        self.order.retain(|n| n != name);
+        self.sessions.remove(name).is_some()
+    }
+}
+
+/// Fake tmux and git driven by a [`FakeScenario`]
+#[derive(Debug, Default)]
+pub struct FakeTools {
+    scenario: FakeScenario,
+    state: Mutex<FakeState>,
+}
+
+impl FakeTools {
+    /// Fake tools following `scenario`
+    pub fn new(scenario: FakeScenario) -> Self {
+        Self {
+            state: Mutex::new(FakeState {
+                fault_hits: vec![0; scenario.faults.len()],
+                ..FakeState::default()
+            }),
+            scenario,
+        }
+    }
+
+    /// Every invocation so far, in order
+    #[cfg(test)]
+    pub fn invocations(&self) -> Vec<ToolInvocation> {
+        self.state.lock().unwrap().invocations.clone()
+    }
+
+    /// Names of the sessions that currently exist
+    #[cfg(test)]
+    pub fn sessions(&self) -> Vec<String> {
+        self.state.lock().unwrap().order.clone()
+    }
+
+    /// Commands sent to `session` with `send-keys`
+    #[cfg(test)]
+    pub fn sent_keys(&self, session: &str) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .sessions
+            .get(session)
+            .map(|s| s.sent.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record `invocation` and pick the fault to inject, if any
+    fn take_fault(&self, invocation: &ToolInvocation) -> Option<ToolFault> {
+        let mut state = self.state.lock().unwrap();
+        state.invocations.push(invocation.clone());
+
+        let index = self
+            .scenario
+            .faults
+            .iter()
+            .zip(&state.fault_hits)
+            .position(|(fault, hits)| {
+                fault.matches(invocation) && fault.times.map_or(true, |t| *hits < t)
+            })?;
+        state.fault_hits[index] += 1;
+        Some(self.scenario.faults[index].clone())
+    }
+
+    fn tmux(&self, invocation: &ToolInvocation) -> Output {
+        let mut state = self.state.lock().unwrap();
+        // `=name` asks tmux for an exact match, which is how the fake matches
+        let target = invocation.flag_value("-t").unwrap_or_default();
+        let target = target.strip_prefix('=').unwrap_or(target).to_string();
+        let missing = || failure(1, &format!("can't find session: {}\n", target));
+
+        match invocation.arg(0).unwrap_or_default() {
+            "-V" => success("tmux 3.4 (fake)\n"),
+            "new-session" => {
+                let name = invocation.flag_value("-s").unwrap_or_default().to_string();
+                if state.sessions.contains_key(&name) {
+                    return failure(1, &format!("duplicate session: {}\n", name));
+                }
+                let session = FakeSession {
+                    script: self.scenario.script_for(&name),
+                    log: None,
+                    captured: String::new(),
+                    sent: Vec::new(),
+                    polls: 0,
+                    written: 0,
+                };
+                state.order.push(name.clone());
+                state.sessions.insert(name, session);
+                success("")
+            }
+            "pipe-pane" => match state.sessions.get_mut(&target) {
+                Some(session) => {
+                    session.log = invocation
+                        .args
+                        .last()
+                        .and_then(|cmd| cmd.strip_prefix("cat >> "))
+                        .map(|path| resolve(invocation, path));
+                    success("")
+                }
+                None => missing(),
+            },
+            "send-keys" => match state.sessions.get_mut(&target) {
+                Some(session) => {
+                    let keys = invocation
+                        .args
+                        .iter()
+                        .skip_while(|a| *a != "-t")
+                        .skip(2)
+                        .filter(|a| *a != "Enter")
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    session.sent.push(keys);
+                    success("")
+                }
+                None => missing(),
+            },
+            "has-session" => match state.sessions.get_mut(&target).map(FakeSession::poll) {
+                Some(true) => success(""),
+                Some(false) => {
+                    state.remove(&target);
+                    missing()
+                }
+                None => missing(),
+            },
+            "capture-pane" => match state.sessions.get(&target) {
+                Some(session) => success(&session.captured),
+                None => missing(),
+            },
+            "kill-session" => {
+                if state.remove(&target) {
+                    success("")
+                } else {
+                    missing()
+                }
+            }
+            "list-sessions" => {
+                if state.order.is_empty() {
+                    failure(1, "no server running\n")
+                } else {
+                    success(&format!("{}\n", state.order.join("\n")))
+                }
+            }
+            _ => success(""),
+        }
+    }
+
+    fn git(&self, invocation: &ToolInvocation) -> Output {
+        let mut state = self.state.lock().unwrap();
+        let worktree = invocation.arg(0) == Some("worktree");
+        let path = invocation.args.last().map(|p| resolve(invocation, p));
+
+        match (invocation.arg(0), invocation.arg(1)) {
+            (Some("rev-parse"), _) => {
+                let branch = invocation
+                    .args
+                    .last()
+                    .and_then(|r| r.strip_prefix("refs/heads/"));
+                return match branch {
+                    Some(branch) if state.branches.contains(branch) => success(""),
+                    _ => failure(1, ""),
+                };
+            }
+            (Some("branch"), Some("-D")) => {
+                let branch = invocation.arg(2).unwrap_or_default();
+                return if state.branches.remove(branch) {
+                    success("")
+                } else {
+                    failure(1, &format!("error: branch '{}' not found.\n", branch))
+                };
+            }
+            _ => {}
+        }
+
+        match (worktree, invocation.arg(1), path) {
+            (true, Some("add"), Some(path)) => {
+                if let Some(branch) = invocation.flag_value("-b") {
+                    if !state.branches.insert(branch.to_string()) {
+                        return failure(
+                            128,
+                            &format!("fatal: a branch named '{}' already exists\n", branch),
+                        );
+                    }
+                }
+                match std::fs::create_dir_all(&path) {
+                    Ok(()) => success(""),
+                    Err(e) => failure(
+                        128,
+                        &format!("fatal: could not create {}: {}\n", path.display(), e),
+                    ),
+                }
+            }
+            (true, Some("remove"), Some(path)) => match std::fs::remove_dir_all(&path) {
+                Ok(()) => success(""),
+                Err(_) => {
+                    failure(128, &format!("fatal: '{}' is not a working tree\n", path.display()))
+                }
+            },
+            _ => success(""),
+        }
+    }
+}
+
+impl ToolRunner for FakeTools {
+    fn run(&self, invocation: &ToolInvocation) -> io::Result<Output> {
+        if let Some(fault) = self.take_fault(invocation) {
+            if fault.delay_ms > 0 {
+                std::thread::sleep(Duration::from_millis(fault.delay_ms));
+            }
+            if let Some(message) = fault.spawn_error {
+                return Err(io::Error::new(io::ErrorKind::NotFound, message));
+            }
+            if let Some(code) = fault.exit_code {
+                return Ok(Output {
+                    status: exit_status(code),
+                    stdout: fault.stdout.into_bytes(),
+                    stderr: fault.stderr.into_bytes(),
+                });
+            }
+        }
+
+        Ok(match invocation.program.as_str() {
+            "tmux" => self.tmux(invocation),
+            "git" => self.git(invocation),
+            _ => success(""),
+        })
+    }
+}
+
+/// Resolve `path` against the invocation's working directory
+fn resolve(invocation: &ToolInvocation, path: &str) -> PathBuf {
+    match &invocation.current_dir {
+        Some(dir) => dir.join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+fn success(stdout: &str) -> Output {
+    Output {
+        status: exit_status(0),
+        stdout: stdout.as_bytes().to_vec(),
+        stderr: Vec::new(),
+    }
+}
+
+fn failure(code: i32, stderr: &str) -> Output {
+    Output {
+        status: exit_status(code),
+        stdout: Vec::new(),
+        stderr: stderr.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+    use std::sync::Arc;
+
+    fn launch(tools: &ToolRegistry, session: &str, log: &Path) {
+        tools
+            .command("tmux")
+            .args(["new-session", "-d", "-s", session])
+            .output()
+            .unwrap();
+        tools
+            .command("tmux")
+            .args(["pipe-pane", "-t", session, "-o"])
+            .arg(format!("cat >> {}", log.display()))
+            .output()
+            .unwrap();
+        tools
+            .command("tmux")
+            .args([
+                "send-keys",
+                "-t",
+                session,
+                "claude --print && exit",
+                "Enter",
+            ])
+            .output()
+            .unwrap();
+    }
+
+    fn alive(tools: &ToolRegistry, session: &str) -> bool {
+        tools
+            .command("tmux")
+            .args(["has-session", "-t", session])
+            .output()
+            .unwrap()
+            .status
+            .success()
+    }
+
+    #[test]
+    fn test_session_writes_output_after_startup() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("out.log");
+        let fake = Arc::new(FakeTools::new(
+            FakeScenario::new().default_session(
+                SessionScript::new()
+                    .startup_polls(1)
+                    .output("one\n")
+                    .output("two\n"),
+            ),
+        ));
+        let tools = ToolRegistry::with_runner(fake.clone());
+
+        launch(&tools, "s1", &log);
+        assert_eq!(fake.sent_keys("s1"), vec!["claude --print && exit"]);
+
+        assert!(alive(&tools, "s1"));
+        assert!(!log.exists());
+        assert!(alive(&tools, "s1"));
+        assert!(alive(&tools, "s1"));
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "one\ntwo\n");
+        assert!(!alive(&tools, "s1"));
+        assert!(fake.sessions().is_empty());
+    }
+
+    #[test]
+    fn test_session_exits() {
+        let dir = tempfile::tempdir().unwrap();
+        let scenario = FakeScenario::new()
+            .session("fail", SessionScript::new().exit(SessionExit::Exit { code: 1 }))
+            .session(
+                "gone",
+                SessionScript::new()
+                    .output("partial")
+                    .exit(SessionExit::Vanish),
+            );
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::new(scenario)));
+
+        launch(&tools, "fail", &dir.path().join("fail.log"));
+        launch(&tools, "gone", &dir.path().join("gone.log"));
+
+        for _ in 0..5 {
+            assert!(alive(&tools, "fail"), "a failed `cmd && exit` keeps its shell");
+        }
+        assert!(alive(&tools, "gone"));
+        assert!(!alive(&tools, "gone"));
+        assert_eq!(std::fs::read_to_string(dir.path().join("gone.log")).unwrap(), "partial");
+    }
+
+    #[test]
+    fn test_faults() {
+        let scenario = FakeScenario::new()
+            .fault(
+                ToolFault::new("git", &["worktree", "add"])
+                    .times(1)
+                    .exit_code(128, "locked"),
+            )
+            .fault(ToolFault::new("tmux", &["-V"]).spawn_error("tmux: not found"));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wt");
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::new(scenario)));
+
+        let add = || {
+            tools
+                .command("git")
+                .args(["worktree", "add", "-b", "b"])
+                .arg(&path)
+                .output()
+        };
+        let first = add().unwrap();
+        assert_eq!(first.status.code(), Some(128));
+        assert_eq!(first.stderr, b"locked");
+        assert!(add().unwrap().status.success());
+        assert!(path.is_dir());
+
+        let err = tools.command("tmux").arg("-V").output().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_list_and_kill_sessions() {
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::default()));
+        let list = || {
+            tools
+                .command("tmux")
+                .args(["list-sessions", "-F", "#{session_name}"])
+                .output()
+                .unwrap()
+        };
+        assert!(!list().status.success());
+
+        for name in ["a", "b"] {
+            tools
+                .command("tmux")
+                .args(["new-session", "-d", "-s", name])
+                .output()
+                .unwrap();
+        }
+        assert_eq!(list().stdout, b"a\nb\n");
+
+        let kill = |name: &str| {
+            tools
+                .command("tmux")
+                .args(["kill-session", "-t", name])
+                .output()
+                .unwrap()
+        };
+        assert!(kill("a").status.success());
+        assert!(!kill("a").status.success());
+        assert_eq!(list().stdout, b"b\n");
+    }
+
+    #[test]
+    fn test_branches_collide() {
+        let dir = tempfile::tempdir().unwrap();
+        let tools = ToolRegistry::with_runner(Arc::new(FakeTools::default()));
+        let add = |branch: &str, path: &str| {
+            tools
+                .command("git")
+                .args(["worktree", "add", "-b", branch, path])
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+        };
+        let exists = |branch: &str| {
+            tools
+                .command("git")
+                .args(["rev-parse", "--verify", "--quiet"])
+                .arg(format!("refs/heads/{}", branch))
+                .output()
+                .unwrap()
+                .status
+                .success()
+        };
+
+        assert!(add("b1", "wt1").status.success());
+        assert!(exists("b1"));
+        let duplicate = add("b1", "wt2");
+        assert_eq!(duplicate.status.code(), Some(128));
+        assert!(String::from_utf8_lossy(&duplicate.stderr).contains("already exists"));
+
+        tools
+            .command("git")
+            .args(["branch", "-D", "b1"])
+            .output()
+            .unwrap();
+        assert!(!exists("b1"));
+        assert!(add("b1", "wt2").status.success());
+    }
+
+    #[test]
+    fn test_scenario_json() {
+        let scenario: FakeScenario = serde_json::from_str(
+            r#"{
+                "faults": [{ "program": "git", "args": ["worktree", "add"], "times": 1, "exitCode": 128 }],
+                "sessions": [{ "session": "-r2-", "output": ["partial"], "exit": { "kind": "vanish" } }],
+                "defaultSession": { "startupPolls": 2, "output": ["done\n"] }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.faults[0].exit_code, Some(128));
+        assert_eq!(scenario.script_for("claude-debate-1234-r2-1").exit, SessionExit::Vanish);
+        assert_eq!(scenario.script_for("other").startup_polls, 2);
+        assert_eq!(scenario.script_for("other").exit, SessionExit::Exit { code: 0 });
+    }
+}
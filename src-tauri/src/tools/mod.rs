@@ -0,0 +1,228 @@
+//! External Tool Invocation
+//!
+//! Agent pipelines drive tmux, git and the agent CLIs (which run inside tmux
+//! sessions) through a [`ToolRegistry`] instead of spawning processes
+//! directly. Normally the registry runs the real programs; in tests, or in
+//! chaos mode when `AIT42_FAKE_TOOLS` names a scenario file, it is backed by
+//! [`FakeTools`], which simulates slow startup, partial output, nonzero exits
+//! and sessions that disappear.
+
+pub mod fake;
+
+pub use fake::{FakeScenario, FakeTools};
+#[cfg(test)]
+pub use fake::{SessionExit, SessionScript};
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output};
+use std::sync::Arc;
+
+/// Environment variable naming a fake tool scenario (chaos mode)
+pub const FAKE_TOOLS_ENV: &str = "AIT42_FAKE_TOOLS";
+
+/// One invocation of an external program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolInvocation {
+    pub program: String,
+    pub args: Vec<String>,
+    pub current_dir: Option<PathBuf>,
+}
+
+impl ToolInvocation {
+    /// Argument at `index`, if any
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
+    /// Value following the flag `flag`, e.g. the session of `-t NAME`
+    pub fn flag_value(&self, flag: &str) -> Option<&str> {
+        let index = self.args.iter().position(|a| a == flag)?;
+        self.arg(index + 1)
+    }
+}
+
+/// Runs external programs
+pub trait ToolRunner: Send + Sync {
+    /// Run `invocation` to completion and collect its output
+    fn run(&self, invocation: &ToolInvocation) -> io::Result<Output>;
+}
+
+/// Runs the real programs found on `PATH`
+#[derive(Debug, Default)]
+pub struct SystemTools;
+
+impl ToolRunner for SystemTools {
+    fn run(&self, invocation: &ToolInvocation) -> io::Result<Output> {
+        let mut cmd = Command::new(&invocation.program);
+        cmd.args(&invocation.args);
+        if let Some(dir) = &invocation.current_dir {
+            cmd.current_dir(dir);
+        }
+        cmd.output()
+    }
+}
+
+/// Shared handle to the tool runner used by agent pipelines
+#[derive(Clone)]
+pub struct ToolRegistry {
+    runner: Arc<dyn ToolRunner>,
+}
+
+impl ToolRegistry {
+    /// Registry running the real programs
+    pub fn system() -> Self {
+        Self::with_runner(Arc::new(SystemTools))
+    }
+
+    /// Registry backed by `runner`
+    pub fn with_runner(runner: Arc<dyn ToolRunner>) -> Self {
+        Self { runner }
+    }
+
+    /// Registry for the application
+    ///
+    /// Uses the fake scenario named by `AIT42_FAKE_TOOLS` when set, and the
+    /// real programs otherwise. A scenario that cannot be loaded still
+    /// selects the fake tools, so chaos mode never falls back to running
+    /// real agents.
+    pub fn from_env() -> Self {
+        let Some(path) = std::env::var_os(FAKE_TOOLS_ENV) else {
+            return Self::system();
+        };
+
+        let scenario = match FakeScenario::load(Path::new(&path)) {
+            Ok(scenario) => scenario,
+            Err(e) => {
+                tracing::error!("Failed to load fake tool scenario: {}", e);
+                FakeScenario::default()
+            }
+        };
+        tracing::warn!("Chaos mode: tmux and git are simulated by {:?}", path);
+        Self::with_runner(Arc::new(FakeTools::new(scenario)))
+    }
+
+    /// Start building an invocation of `program`
+    pub fn command(&self, program: &str) -> ToolCommand {
+        ToolCommand {
+            runner: Arc::clone(&self.runner),
+            invocation: ToolInvocation {
+                program: program.to_string(),
+                args: Vec::new(),
+                current_dir: None,
+            },
+        }
+    }
+}
+
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+impl std::fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolRegistry").finish_non_exhaustive()
+    }
+}
+
+/// Builder mirroring the parts of `std::process::Command` pipelines use
+pub struct ToolCommand {
+    runner: Arc<dyn ToolRunner>,
+    invocation: ToolInvocation,
+}
+
+impl ToolCommand {
+    /// Append an argument
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.invocation
+            .args
+            .push(arg.as_ref().to_string_lossy().into_owned());
+        self
+    }
+
+    /// Append several arguments
+    #[cfg(test)]
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    /// Set the working directory
+    pub fn current_dir(&mut self, dir: impl AsRef<Path>) -> &mut Self {
+        self.invocation.current_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Run the program and collect its output
+    pub fn output(&mut self) -> io::Result<Output> {
+        self.runner.run(&self.invocation)
+    }
+}
+
+/// Exit status with the given code, for simulated processes
+pub(crate) fn exit_status(code: i32) -> ExitStatus {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatus::from_raw((code & 0xff) << 8)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatus::from_raw(code as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_status_codes() {
+        assert!(exit_status(0).success());
+        assert_eq!(exit_status(3).code(), Some(3));
+    }
+
+    #[test]
+    fn test_command_builds_invocation() {
+        let fake = Arc::new(FakeTools::default());
+        let tools = ToolRegistry::with_runner(fake.clone());
+
+        tools
+            .command("git")
+            .arg("status")
+            .args(["--short", "--branch"])
+            .current_dir("/tmp")
+            .output()
+            .unwrap();
+
+        let invocation = &fake.invocations()[0];
+        assert_eq!(invocation.program, "git");
+        assert_eq!(invocation.args, vec!["status", "--short", "--branch"]);
+        assert_eq!(invocation.current_dir.as_deref(), Some(Path::new("/tmp")));
+    }
+
+    #[test]
+    fn test_flag_value() {
+        let invocation = ToolInvocation {
+            program: "tmux".to_string(),
+            args: vec![
+                "has-session".to_string(),
+                "-t".to_string(),
+                "s1".to_string(),
+            ],
+            current_dir: None,
+        };
+        assert_eq!(invocation.flag_value("-t"), Some("s1"));
+        assert_eq!(invocation.flag_value("-s"), None);
+    }
+}
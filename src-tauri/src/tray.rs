@@ -0,0 +1,296 @@
+//! System Tray
+//!
+//! Lets long agent runs be monitored with the main window closed. The tray
+//! shows how many agent sessions are running, offers to reopen the window,
+//! pause scheduled runs (automatic checkpoints and session purging) or
+//! cancel every agent session, and raises a native notification when a
+//! session finishes. Closing the main window hides it; the app keeps
+//! running until Quit is chosen from the tray.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem,
+};
+
+use crate::notifications::NotificationLevel;
+use crate::state::AppState;
+use crate::tools::ToolRegistry;
+
+/// How often running sessions are counted
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Name prefixes of tmux sessions started for agents
+const AGENT_SESSION_PREFIXES: &[&str] = &[
+    "ait42-",
+    "claude-debate-",
+    "claude-analysis-",
+    "claude-code-comp-",
+];
+
+const STATUS_ITEM: &str = "status";
+const OPEN_ITEM: &str = "open";
+const PAUSE_ITEM: &str = "pause";
+const CANCEL_ALL_ITEM: &str = "cancel_all";
+const QUIT_ITEM: &str = "quit";
+
+/// Whether scheduled background runs are paused from the tray
+#[derive(Debug, Default)]
+pub struct ScheduledRuns {
+    paused: AtomicBool,
+}
+
+impl ScheduledRuns {
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Flip between paused and running, returning whether runs are now paused
+    pub fn toggle(&self) -> bool {
+        !self.paused.fetch_xor(true, Ordering::Relaxed)
+    }
+}
+
+/// Tray icon with its menu
+pub fn build() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(STATUS_ITEM, status_text(0)).disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(OPEN_ITEM, "Open AIT42 Editor"))
+        .add_item(CustomMenuItem::new(PAUSE_ITEM, "Pause Scheduled Runs"))
+        .add_item(CustomMenuItem::new(CANCEL_ALL_ITEM, "Cancel All Sessions"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(QUIT_ITEM, "Quit"));
+    SystemTray::new()
+        .with_menu(menu)
+        .with_tooltip(&status_text(0))
+}
+
+/// Handle a click on the tray icon or one of its menu items
+pub fn handle_event(app: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => show_main_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            OPEN_ITEM => show_main_window(app),
+            PAUSE_ITEM => toggle_scheduled_runs(app),
+            CANCEL_ALL_ITEM => confirm_cancel_all(app),
+            QUIT_ITEM => app.exit(0),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Hide the main window instead of closing it, so monitoring continues
+pub fn handle_window_event(event: tauri::GlobalWindowEvent) {
+    if let tauri::WindowEvent::CloseRequested { api, .. } = event.event() {
+        if event.window().label() != "main" {
+            return;
+        }
+        if let Err(e) = event.window().hide() {
+            tracing::warn!("Failed to hide window: {}", e);
+            return;
+        }
+        api.prevent_close();
+    }
+}
+
+/// Keep the running count up to date and notify when sessions finish
+///
+/// Runs for the lifetime of the app.
+pub async fn run_monitor(app: AppHandle) {
+    let mut running = HashSet::new();
+    loop {
+        let tools = app.state::<AppState>().tools.clone();
+        let current: HashSet<String> =
+            tauri::async_runtime::spawn_blocking(move || agent_sessions(&tools))
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+
+        for session in running.difference(&current) {
+            notify_finished(&app, session);
+        }
+        if current.len() != running.len() {
+            update_status(&app, current.len());
+        }
+        running = current;
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Names of the agent tmux sessions that currently exist
+pub fn agent_sessions(tools: &ToolRegistry) -> Vec<String> {
+    let output = match tools
+        .command("tmux")
+        .arg("list-sessions")
+        .arg("-F")
+        .arg("#{session_name}")
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        // No tmux server means no sessions
+        _ => return Vec::new(),
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|name| is_agent_session(name))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Kill every agent tmux session, returning how many were killed
+pub fn cancel_all(tools: &ToolRegistry) -> usize {
+    agent_sessions(tools)
+        .into_iter()
+        .filter(|session| {
+            tools
+                .command("tmux")
+                .arg("kill-session")
+                .arg("-t")
+                .arg(format!("={}", session))
+                .output()
+                .is_ok_and(|output| output.status.success())
+        })
+        .count()
+}
+
+fn is_agent_session(name: &str) -> bool {
+    AGENT_SESSION_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+fn status_text(running: usize) -> String {
+    match running {
+        0 => "No agents running".to_string(),
+        1 => "1 agent running".to_string(),
+        n => format!("{} agents running", n),
+    }
+}
+
+fn update_status(app: &AppHandle, running: usize) {
+    let tray = app.tray_handle();
+    let text = status_text(running);
+    if let Err(e) = tray.get_item(STATUS_ITEM).set_title(&text) {
+        tracing::warn!("Failed to update tray menu: {}", e);
+    }
+    if let Err(e) = tray.set_tooltip(&text) {
+        tracing::warn!("Failed to update tray tooltip: {}", e);
+    }
+}
+
+fn notify_finished(app: &AppHandle, session: &str) {
+    let message = format!("Agent session {} finished", session);
+    app.state::<AppState>().notifications.notify(
+        app,
+        NotificationLevel::Info,
+        "agents",
+        message.clone(),
+        vec![],
+    );
+
+    let shown = tauri::api::notification::Notification::new(&app.config().tauri.bundle.identifier)
+        .title("AIT42 Editor")
+        .body(message)
+        .show();
+    if let Err(e) = shown {
+        tracing::warn!("Failed to show notification: {}", e);
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+    let shown = window
+        .show()
+        .and_then(|_| window.unminimize())
+        .and_then(|_| window.set_focus());
+    if let Err(e) = shown {
+        tracing::warn!("Failed to show main window: {}", e);
+    }
+}
+
+fn toggle_scheduled_runs(app: &AppHandle) {
+    let paused = app.state::<AppState>().scheduled_runs.toggle();
+    tracing::info!("Scheduled runs {}", if paused { "paused" } else { "resumed" });
+
+    let title = if paused {
+        "Resume Scheduled Runs"
+    } else {
+        "Pause Scheduled Runs"
+    };
+    if let Err(e) = app.tray_handle().get_item(PAUSE_ITEM).set_title(title) {
+        tracing::warn!("Failed to update tray menu: {}", e);
+    }
+}
+
+/// Ask before killing every agent session, since their work is lost
+fn confirm_cancel_all(app: &AppHandle) {
+    let app = app.clone();
+    tauri::api::dialog::ask(
+        None::<&tauri::Window>,
+        "Cancel all sessions?",
+        "Kill every running agent session? Agents will be stopped.",
+        move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            let killed = cancel_all(&app.state::<AppState>().tools);
+            tracing::info!("Cancelled {} agent sessions from the tray", killed);
+            update_status(&app, 0);
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::FakeTools;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_cancel_all_only_kills_agent_sessions() {
+        let fake = Arc::new(FakeTools::default());
+        let tools = ToolRegistry::with_runner(fake.clone());
+        for name in [
+            "ait42-claude-competition-1234-1",
+            "claude-debate-1234-r1-1",
+            "work",
+        ] {
+            tools
+                .command("tmux")
+                .args(["new-session", "-d", "-s", name])
+                .output()
+                .unwrap();
+        }
+
+        assert_eq!(agent_sessions(&tools).len(), 2);
+        assert_eq!(cancel_all(&tools), 2);
+        assert_eq!(fake.sessions(), vec!["work"]);
+        assert!(agent_sessions(&tools).is_empty());
+    }
+
+    #[test]
+    fn test_scheduled_runs_toggle() {
+        let runs = ScheduledRuns::default();
+        assert!(!runs.is_paused());
+        assert!(runs.toggle());
+        assert!(runs.is_paused());
+        assert!(!runs.toggle());
+        assert!(!runs.is_paused());
+    }
+
+    #[test]
+    fn test_status_text() {
+        assert_eq!(status_text(0), "No agents running");
+        assert_eq!(status_text(1), "1 agent running");
+        assert_eq!(status_text(3), "3 agents running");
+    }
+}
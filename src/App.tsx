@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { FileText, Settings, Users, Layout, Sparkles, Trophy, MessageSquare, LayoutDashboard, FolderOpen } from 'lucide-react';
 import { Sidebar } from './components/Sidebar/Sidebar';
 import { EditorContainer } from '@/components/Editor';
@@ -10,9 +11,11 @@ import { EnsembleDialog } from '@/components/AI/EnsembleDialog';
 import { DebateDialog } from '@/components/AI/DebateDialog';
 import DebateStatusPanel from '@/components/AI/DebateStatusPanel';
 import { SessionHistory } from '@/components/SessionHistory';
+import { DebugPanel } from '@/components/Debug';
 import { useEditorStore } from '@/store/editorStore';
 import { useSessionHistoryStore } from '@/store/sessionHistoryStore';
 import { useFileTreeStore } from '@/store/fileTreeStore';
+import { useFeatureFlagStore } from '@/store/featureFlagStore';
 import { tauriApi } from '@/services/tauri';
 import { RuntimeAllocation } from '@/types/worktree';
 import { getRuntimeDefinition } from '@/config/runtimes';
@@ -93,8 +96,18 @@ function App() {
 
   // Get active file from editor store
   const getActiveTab = useEditorStore((state) => state.getActiveTab);
+  const addTab = useEditorStore((state) => state.addTab);
   const activeFile = getActiveTab();
 
+  // Feature flags gating experimental panels
+  const fetchFeatureFlags = useFeatureFlagStore((state) => state.fetchFlags);
+  const ensembleEnabled = useFeatureFlagStore((state) => state.isEnabled('ensemble_mode'));
+  const dapEnabled = useFeatureFlagStore((state) => state.isEnabled('dap_debugging'));
+
+  useEffect(() => {
+    fetchFeatureFlags();
+  }, [fetchFeatureFlags]);
+
   // Get session history store methods
   const sessionHistorySetWorkspacePath = useSessionHistoryStore((state) => state.setWorkspacePath);
 
@@ -161,10 +174,10 @@ function App() {
     }
   }, [workspacePath, isGitRepo, loadDirectoryIntoFileTree]);
 
-  // Handle workspace selection
-  const handleSelectWorkspace = async () => {
+  // Handle workspace selection (a dropped folder is passed as path)
+  const handleSelectWorkspace = async (path?: string) => {
     try {
-      const workspace = await tauriApi.selectWorkspace();
+      const workspace = await tauriApi.selectWorkspace(path);
       setWorkspacePath(workspace.path);
 
       // 自動的にgit initを実行（Gitリポジトリでない場合）
@@ -206,6 +219,29 @@ function App() {
     }
   };
 
+  // Open dropped files as tabs and offer to switch to a dropped folder
+  useEffect(() => {
+    const unlistenFiles = listen<string[]>('files-dropped', async (event) => {
+      for (const path of event.payload) {
+        try {
+          await addTab(path);
+        } catch (error) {
+          alert(`ファイルを開けませんでした:\n${path}\n\n${error}`);
+        }
+      }
+    });
+    const unlistenWorkspace = listen<string>('workspace-dropped', (event) => {
+      if (window.confirm(`ワークスペースを切り替えますか？\n${event.payload}`)) {
+        handleSelectWorkspace(event.payload);
+      }
+    });
+
+    return () => {
+      unlistenFiles.then((unlisten) => unlisten());
+      unlistenWorkspace.then((unlisten) => unlisten());
+    };
+  }, [addTab]);
+
   // Handle competition start (競争モード)
   const handleCompetitionStart = async (competitionId: string, allocations: RuntimeAllocation[], task: string) => {
     const newInstances = buildInstancesFromAllocations(competitionId, task, allocations, 'competition');
@@ -400,14 +436,16 @@ function App() {
             </button>
 
             {/* Ensemble Mode Button */}
-            <button
-              onClick={() => setShowEnsembleDialog(true)}
-              className="px-3 py-1.5 rounded-md text-sm font-medium transition-all bg-gradient-to-r from-purple-600 to-blue-600 hover:from-purple-500 hover:to-blue-500 text-white shadow-md hover:shadow-lg"
-              title="アンサンブルモード: 統合AIが結果を統合"
-            >
-              <Sparkles className="w-4 h-4 inline-block mr-1.5" />
-              アンサンブル
-            </button>
+            {ensembleEnabled && (
+              <button
+                onClick={() => setShowEnsembleDialog(true)}
+                className="px-3 py-1.5 rounded-md text-sm font-medium transition-all bg-gradient-to-r from-purple-600 to-blue-600 hover:from-purple-500 hover:to-blue-500 text-white shadow-md hover:shadow-lg"
+                title="アンサンブルモード: 統合AIが結果を統合"
+              >
+                <Sparkles className="w-4 h-4 inline-block mr-1.5" />
+                アンサンブル
+              </button>
+            )}
 
             {/* Debate Mode Button */}
             <button
@@ -473,7 +511,7 @@ function App() {
 
         <div className="flex items-center space-x-2">
           <button
-            onClick={handleSelectWorkspace}
+            onClick={() => handleSelectWorkspace()}
             className="p-2 rounded hover:bg-gray-700"
             title="プロジェクトフォルダを開く"
           >
@@ -499,6 +537,7 @@ function App() {
             <div className="flex-1">
               <EditorContainer onWorkspaceSelect={handleSelectWorkspace} />
             </div>
+            {dapEnabled && <DebugPanel />}
           </>
         )}
 
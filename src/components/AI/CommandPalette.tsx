@@ -5,8 +5,15 @@
  */
 
 import React, { useState, useEffect, useRef } from 'react';
-import { Search, Sparkles, X } from 'lucide-react';
-import { tauriApi, AgentInfo, AgentExecutionResponse } from '@/services/tauri';
+import { Search, Sparkles, StickyNote, X } from 'lucide-react';
+import { useAnnotationStore } from '@/store/annotationStore';
+import { useClipboardStore } from '@/store/clipboardStore';
+import {
+  tauriApi,
+  AgentInfo,
+  AgentExecutionResponse,
+  AgentRecommendation,
+} from '@/services/tauri';
 
 export interface CommandPaletteProps {
   /** Whether the palette is visible */
@@ -32,6 +39,9 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
   const [selectedIndex, setSelectedIndex] = useState(0);
   const [isExecuting, setIsExecuting] = useState(false);
   const [executionResult, setExecutionResult] = useState<AgentExecutionResponse | null>(null);
+  const [recommendations, setRecommendations] = useState<AgentRecommendation[]>([]);
+  const [includeNotes, setIncludeNotes] = useState(false);
+  const noteCount = useAnnotationStore((state) => state.annotations.length);
   const inputRef = useRef<HTMLInputElement>(null);
   const taskInputRef = useRef<HTMLTextAreaElement>(null);
 
@@ -42,6 +52,7 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
       setQuery('');
       setTask('');
       setExecutionResult(null);
+      setRecommendations([]);
       setSelectedIndex(0);
       // Focus input after a short delay
       setTimeout(() => {
@@ -50,10 +61,32 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
     }
   }, [isOpen]);
 
-  // Filter agents based on query
+  // Recommend agents for the task once typing pauses
+  useEffect(() => {
+    if (!isOpen || task.trim() === '') {
+      setRecommendations([]);
+      return;
+    }
+    const timer = setTimeout(() => {
+      tauriApi
+        .recommendAgents(task.trim(), 3)
+        .then(setRecommendations)
+        .catch((error) => console.error('Failed to recommend agents:', error));
+    }, 400);
+    return () => clearTimeout(timer);
+  }, [task, isOpen]);
+
+  // Filter agents based on query; without one, recommended agents come first
   useEffect(() => {
     if (query.trim() === '') {
-      setFilteredAgents(agents);
+      const recommended = recommendations
+        .map((r) => agents.find((agent) => agent.name === r.agent))
+        .filter((agent): agent is AgentInfo => agent !== undefined);
+      const rest = agents.filter((agent) => !recommended.includes(agent));
+      setFilteredAgents([...recommended, ...rest]);
+      if (recommended.length > 0) {
+        setSelectedIndex(0);
+      }
     } else {
       const lowerQuery = query.toLowerCase();
       const filtered = agents.filter(
@@ -65,7 +98,7 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
       setFilteredAgents(filtered);
       setSelectedIndex(0);
     }
-  }, [query, agents]);
+  }, [query, agents, recommendations]);
 
   const loadAgents = async () => {
     try {
@@ -101,12 +134,19 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
     setIsExecuting(true);
 
     try {
+      // Workspace notes let tasks refer to them ("address the TODO note on line 42")
+      const notes = includeNotes ? await tauriApi.getAnnotationContext() : '';
+      const context = [initialContext, notes].filter(Boolean).join('\n\n') || undefined;
       const response = await tauriApi.executeAgent({
         agentName: selectedAgent.name,
         task: task.trim(),
-        context: initialContext,
+        context,
       });
       setExecutionResult(response);
+      // Agent output can be pasted from the clipboard history
+      if (response.output) {
+        useClipboardStore.getState().push(response.output, 'agent');
+      }
     } catch (error) {
       console.error('Failed to execute agent:', error);
       setExecutionResult({
@@ -120,6 +160,9 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
     }
   };
 
+  const recommendationFor = (agentName: string) =>
+    query.trim() === '' ? recommendations.find((r) => r.agent === agentName) : undefined;
+
   if (!isOpen) return null;
 
   return (
@@ -182,10 +225,26 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
                   <div className="flex-1">
                     <div className="font-medium text-text-primary text-sm">{agent.name}</div>
                     <div className="text-xs text-text-secondary mt-1">{agent.description}</div>
+                    {recommendationFor(agent.name) && (
+                      <div className="text-xs text-accent-primary mt-1">
+                        おすすめ: {recommendationFor(agent.name)?.rationale}
+                      </div>
+                    )}
                     <div className="flex items-center gap-2 mt-2">
                       <span className="text-xs px-2 py-0.5 bg-accent-secondary/20 text-accent-secondary rounded">
                         {agent.category}
                       </span>
+                      {agent.source === 'project' && (
+                        <span
+                          className="text-xs px-2 py-0.5 bg-accent-primary/20 text-accent-primary rounded"
+                          title={agent.overrides ? `上書き: ${agent.overrides}` : agent.path}
+                        >
+                          プロジェクト{agent.overrides ? '（上書き）' : ''}
+                        </span>
+                      )}
+                      {agent.version && (
+                        <span className="text-xs text-text-tertiary">v{agent.version}</span>
+                      )}
                       <span className="text-xs text-text-tertiary">
                         {agent.tools.length} 個のツール
                       </span>
@@ -211,6 +270,17 @@ export const CommandPalette: React.FC<CommandPaletteProps> = ({
               className="w-full px-3 py-2 bg-editor-bg text-text-primary placeholder-text-tertiary border border-editor-border rounded focus:outline-none focus:ring-2 focus:ring-accent-primary/50 resize-none"
               rows={3}
             />
+            {noteCount > 0 && (
+              <label className="flex items-center gap-2 mt-2 text-xs text-text-secondary cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={includeNotes}
+                  onChange={(e) => setIncludeNotes(e.target.checked)}
+                />
+                <StickyNote size={12} />
+                ノート ({noteCount}) をコンテキストに含める
+              </label>
+            )}
             <div className="flex items-center justify-between mt-3">
               <span className="text-xs text-text-tertiary">
                 <kbd className="px-1.5 py-0.5 bg-editor-elevated rounded border border-editor-border">⌘</kbd> +{' '}
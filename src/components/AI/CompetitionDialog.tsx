@@ -6,11 +6,12 @@
  */
 
 import React, { useState, useEffect, useRef, useMemo } from 'react';
-import { Trophy, X, Settings as SettingsIcon, Cpu, Sparkles, Loader2 } from 'lucide-react';
-import { tauriApi, MultiRuntimeCompetitionRequest } from '@/services/tauri';
+import { Trophy, X, Settings as SettingsIcon, Cpu, Sparkles, Loader2, ListTodo } from 'lucide-react';
+import { tauriApi, MultiRuntimeCompetitionRequest, Issue } from '@/services/tauri';
 import { ModeIndicator } from './ModeIndicator';
 import { CompetitiveFlowDiagram } from './CompetitiveFlowDiagram';
 import { ModeTooltip } from './ModeTooltip';
+import { IssuePicker } from './IssuePicker';
 import { useTaskOptimizer } from '@/hooks/useTaskOptimizer';
 import { RuntimeAllocation, AgentRuntime, WorktreeSession, WorktreeInstance } from '@/types/worktree';
 import { RUNTIME_DEFINITIONS, getRuntimeDefinition } from '@/config/runtimes';
@@ -82,10 +83,12 @@ export const CompetitionDialog: React.FC<CompetitionDialogProps> = ({
   const [showAdvanced, setShowAdvanced] = useState(false);
   const [isStarting, setIsStarting] = useState(false);
   const [validationError, setValidationError] = useState<string | null>(null);
+  const [showIssuePicker, setShowIssuePicker] = useState(false);
+  const [selectedIssue, setSelectedIssue] = useState<Issue | null>(null);
 
   const isMountedRef = useRef(true);
   const { state: optimizerState, analyze, isAnalyzing } = useTaskOptimizer();
-  const { createSession, loadSessions } = useSessionHistoryStore();
+  const { createSession, loadSessions, workspacePath } = useSessionHistoryStore();
 
   const totalInstances = useMemo(
     () => runtimeAllocations.reduce((sum, allocation) => sum + allocation.count, 0),
@@ -114,6 +117,8 @@ export const CompetitionDialog: React.FC<CompetitionDialogProps> = ({
       setShowAdvanced(false);
       setIsStarting(false);
       setValidationError(null);
+      setShowIssuePicker(false);
+      setSelectedIssue(null);
     }
   }, [isOpen]);
 
@@ -221,7 +226,7 @@ export const CompetitionDialog: React.FC<CompetitionDialogProps> = ({
       const sessionInstances: WorktreeInstance[] = result.instances.map((inst) => ({
         instanceId: inst.instanceNumber,
         worktreePath: inst.worktreePath,
-        branch: `${inst.runtime}-competition-${inst.instanceNumber}`,
+        branch: inst.branch,
         agentName: inst.runtime || 'unknown',
         status: 'running',
         tmuxSessionId: inst.tmuxSessionId,
@@ -243,6 +248,16 @@ export const CompetitionDialog: React.FC<CompetitionDialogProps> = ({
         timeoutSeconds,
         preserveWorktrees,
         runtimeMix: runtimeMix as any,
+        complexityClass: optimizerState.optimization?.complexityClass,
+        subtaskCount: optimizerState.optimization?.recommendedSubtasks,
+        issue: selectedIssue
+          ? {
+              provider: selectedIssue.provider,
+              key: selectedIssue.key,
+              title: selectedIssue.title,
+              url: selectedIssue.url,
+            }
+          : undefined,
       };
 
       // Save session to database
@@ -294,7 +309,43 @@ export const CompetitionDialog: React.FC<CompetitionDialogProps> = ({
 
           {/* Task Input */}
           <div>
-            <label className="block text-sm font-medium text-text-primary mb-2">タスク説明</label>
+            <div className="flex items-center justify-between mb-2">
+              <label className="text-sm font-medium text-text-primary">タスク説明</label>
+              <button
+                onClick={() => setShowIssuePicker(!showIssuePicker)}
+                className="flex items-center gap-1 text-xs text-text-secondary hover:text-text-primary transition-colors"
+              >
+                <ListTodo size={14} />
+                課題から選択
+              </button>
+            </div>
+            {showIssuePicker && (
+              <div className="mb-3">
+                <IssuePicker
+                  workspacePath={workspacePath}
+                  onSelect={(issue) => {
+                    setTask(issue.task);
+                    setSelectedIssue(issue);
+                    setShowIssuePicker(false);
+                    setValidationError(null);
+                  }}
+                />
+              </div>
+            )}
+            {selectedIssue && (
+              <div className="flex items-center gap-2 mb-2 text-xs text-text-tertiary">
+                <span>
+                  リンク先の課題: <span className="font-mono">{selectedIssue.key}</span> {selectedIssue.title}
+                </span>
+                <button
+                  onClick={() => setSelectedIssue(null)}
+                  className="p-0.5 hover:bg-editor-border/30 rounded transition-colors"
+                  title="リンクを解除"
+                >
+                  <X size={12} />
+                </button>
+              </div>
+            )}
             <textarea
               value={task}
               onChange={(e) => {
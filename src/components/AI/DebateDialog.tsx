@@ -1,8 +1,8 @@
 /**
  * DebateDialog - Claude Code Debate Mode (Collaborative Dialogue)
  *
- * Launches 3 role-based Claude Code agents in sequential rounds
- * for multi-perspective debate and consensus formation
+ * Launches 2-6 role-based Claude Code agents in 1-5 sequential rounds
+ * for multi-perspective debate, optionally followed by a synthesis round
  */
 
 import React, { useState, useEffect } from 'react';
@@ -220,6 +220,8 @@ export const DebateDialog: React.FC<DebateDialogProps> = ({
   const [selectedModel, setSelectedModel] = useState<ClaudeModel>('sonnet');
   const [timeoutSeconds, setTimeoutSeconds] = useState(800);
   const [preserveWorktrees, setPreserveWorktrees] = useState(false);
+  const [rounds, setRounds] = useState(2);
+  const [synthesis, setSynthesis] = useState(true);
   const [showAdvanced, setShowAdvanced] = useState(false);
   const [isStarting, setIsStarting] = useState(false);
 
@@ -231,6 +233,8 @@ export const DebateDialog: React.FC<DebateDialogProps> = ({
       setSelectedModel('sonnet');
       setTimeoutSeconds(800);
       setPreserveWorktrees(false);
+      setRounds(2);
+      setSynthesis(true);
       setShowAdvanced(false);
       setIsStarting(false);
     }
@@ -272,6 +276,8 @@ export const DebateDialog: React.FC<DebateDialogProps> = ({
         model: selectedModel,
         timeoutSeconds,
         preserveWorktrees,
+        rounds,
+        synthesis,
       };
 
       const result = await tauriApi.executeDebate(request);
@@ -441,6 +447,42 @@ export const DebateDialog: React.FC<DebateDialogProps> = ({
 
             {showAdvanced && (
               <div className="mt-4 p-4 bg-gray-900 rounded-lg border border-gray-700 space-y-4">
+                {/* Rounds */}
+                <div>
+                  <label className="block text-xs font-medium text-gray-300 mb-2">
+                    ディベートラウンド数
+                  </label>
+                  <input
+                    type="number"
+                    min="1"
+                    max="5"
+                    value={rounds}
+                    onChange={(e) => {
+                      const value = parseInt(e.target.value, 10);
+                      setRounds(isNaN(value) ? 2 : Math.min(5, Math.max(1, value)));
+                    }}
+                    className="w-full px-3 py-2 bg-gray-850 text-gray-100 border border-gray-700 rounded focus:outline-none focus:ring-2 focus:ring-blue-500/50"
+                  />
+                </div>
+
+                {/* Synthesis */}
+                <div className="flex items-start gap-3">
+                  <input
+                    type="checkbox"
+                    checked={synthesis}
+                    onChange={(e) => setSynthesis(e.target.checked)}
+                    className="mt-1"
+                  />
+                  <div className="flex-1">
+                    <label className="text-xs font-medium text-gray-300">
+                      最後に統合ラウンドを実行
+                    </label>
+                    <div className="text-xs text-gray-500 mt-1">
+                      モデレーターが全ラウンドの議論をまとめ、最終提案を作成します
+                    </div>
+                  </div>
+                </div>
+
                 {/* Timeout */}
                 <div>
                   <label className="block text-xs font-medium text-gray-300 mb-2">
@@ -496,8 +538,15 @@ export const DebateDialog: React.FC<DebateDialogProps> = ({
             </div>
             <div className="text-xs text-gray-300 space-y-1">
               <div>📝 <strong>Round 1 (独立提案)</strong>: 各ロールが独立して提案</div>
-              <div>🔍 <strong>Round 2 (批判的分析)</strong>: Round 1の提案を批判的に分析</div>
-              <div>🤝 <strong>Round 3 (合意形成)</strong>: 統合された最終提案を作成</div>
+              {rounds > 1 && (
+                <div>
+                  🔍 <strong>Round 2{rounds > 2 ? `-${rounds}` : ''} (批判的分析)</strong>:
+                  前ラウンドの提案を批判的に分析
+                </div>
+              )}
+              {synthesis && (
+                <div>🤝 <strong>Synthesis (合意形成)</strong>: 統合された最終提案を作成</div>
+              )}
             </div>
           </div>
         </div>
@@ -509,7 +558,8 @@ export const DebateDialog: React.FC<DebateDialogProps> = ({
               'ディベートテーマを入力してください'
             ) : (
               <>
-                {currentPreset.roles.length} ロール × 3 ラウンド × {MODEL_INFO[selectedModel].label}
+                {currentPreset.roles.length} ロール × {rounds} ラウンド
+                {synthesis && ' + 統合'} × {MODEL_INFO[selectedModel].label}
               </>
             )}
           </div>
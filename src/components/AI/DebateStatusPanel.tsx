@@ -136,13 +136,14 @@ const DebateStatusPanel: React.FC<DebateStatusPanelProps> = ({
     };
   }, [debateId]);
 
+  // Debate statuses are "round_N" and "synthesis" while running
+  const isRunning = (statusStr: string) =>
+    statusStr.startsWith('round_') || statusStr === 'synthesis';
+
   // Status color mapping
   const getStatusColor = (statusStr: string) => {
-    switch (statusStr) {
+    switch (isRunning(statusStr) ? 'running' : statusStr) {
       case 'running':
-      case 'round_1':
-      case 'round_2':
-      case 'round_3':
         return 'text-blue-400 bg-blue-500/10 border-blue-500/30';
       case 'completed':
         return 'text-green-400 bg-green-500/10 border-green-500/30';
@@ -154,11 +155,8 @@ const DebateStatusPanel: React.FC<DebateStatusPanelProps> = ({
   };
 
   const getStatusIcon = (statusStr: string) => {
-    switch (statusStr) {
+    switch (isRunning(statusStr) ? 'running' : statusStr) {
       case 'running':
-      case 'round_1':
-      case 'round_2':
-      case 'round_3':
         return <Loader2 className="w-5 h-5 animate-spin" />;
       case 'completed':
         return <CheckCircle2 className="w-5 h-5" />;
@@ -309,8 +307,9 @@ const DebateStatusPanel: React.FC<DebateStatusPanelProps> = ({
 
       {/* Round Progress */}
       <div className="flex-1 overflow-y-auto p-4 space-y-4">
-        {[1, 2, 3].map((roundNum) => {
+        {Array.from({ length: status.totalRounds }, (_, i) => i + 1).map((roundNum) => {
           const roundOutputs = getRoundOutputs(roundNum);
+          const isSynthesis = status.synthesis && roundNum === status.totalRounds;
           const isExpanded = expandedRounds.has(roundNum);
           const isCurrent = status.currentRound === roundNum;
           const isPast = status.currentRound > roundNum;
@@ -342,13 +341,11 @@ const DebateStatusPanel: React.FC<DebateStatusPanelProps> = ({
                   </div>
                   <div className="text-left">
                     <h3 className="text-lg font-semibold text-white">
-                      Round {roundNum}
-                      {roundNum === 1 && ' - 独立提案'}
-                      {roundNum === 2 && ' - 批判的分析'}
-                      {roundNum === 3 && ' - コンセンサス形成'}
+                      {isSynthesis ? 'Synthesis - コンセンサス形成' : `Round ${roundNum}`}
+                      {!isSynthesis && (roundNum === 1 ? ' - 独立提案' : ' - 批判的分析')}
                     </h3>
                     <p className="text-sm text-gray-400">
-                      {roundOutputs.length} / 3 roles{' '}
+                      {roundOutputs.length} / {isSynthesis ? 1 : status.participants} roles{' '}
                       {isFuture ? '(未実行)' : isCurrent ? '(実行中)' : '(完了)'}
                     </p>
                   </div>
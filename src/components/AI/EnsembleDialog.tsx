@@ -168,7 +168,7 @@ export const EnsembleDialog: React.FC<EnsembleDialogProps> = ({ isOpen, onClose,
       const sessionInstances: WorktreeInstance[] = result.instances.map((inst) => ({
         instanceId: inst.instanceNumber,
         worktreePath: inst.worktreePath,
-        branch: `${inst.runtime}-ensemble-${inst.instanceNumber}`,
+        branch: inst.branch,
         agentName: inst.runtime || 'unknown',
         status: 'running',
         tmuxSessionId: inst.tmuxSessionId,
@@ -190,6 +190,8 @@ export const EnsembleDialog: React.FC<EnsembleDialogProps> = ({ isOpen, onClose,
         timeoutSeconds,
         preserveWorktrees,
         runtimeMix: runtimeMix as any,
+        complexityClass: optimizerState.optimization?.complexityClass,
+        subtaskCount: optimizerState.optimization?.recommendedSubtasks,
       };
 
       // Save session to database
@@ -0,0 +1,162 @@
+/**
+ * IssuePicker - Pick a GitHub or Jira issue as the task of a run
+ *
+ * Lists open issues of the configured trackers ([issues] in the config).
+ * A tracker without an API token asks for one, which is saved in the OS keychain.
+ */
+
+import React, { useCallback, useEffect, useState } from 'react';
+import { Search, Loader2, KeyRound } from 'lucide-react';
+import { tauriApi, Issue, IssueTrackerStatus } from '@/services/tauri';
+import { IssueProvider } from '@/types/worktree';
+
+export interface IssuePickerProps {
+  /** Workspace whose origin implies the GitHub repository */
+  workspacePath: string;
+  /** Callback when an issue is picked */
+  onSelect: (issue: Issue) => void;
+}
+
+const PROVIDER_LABELS: Record<IssueProvider, string> = {
+  github: 'GitHub',
+  jira: 'Jira',
+};
+
+export const IssuePicker: React.FC<IssuePickerProps> = ({ workspacePath, onSelect }) => {
+  const [trackers, setTrackers] = useState<IssueTrackerStatus[]>([]);
+  const [provider, setProvider] = useState<IssueProvider | null>(null);
+  const [query, setQuery] = useState('');
+  const [token, setToken] = useState('');
+  const [issues, setIssues] = useState<Issue[]>([]);
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadTrackers = useCallback(async () => {
+    try {
+      const found = (await tauriApi.getIssueTrackers(workspacePath)).filter((t) => t.configured);
+      setTrackers(found);
+      setProvider((current) => current ?? found[0]?.provider ?? null);
+    } catch (err) {
+      setError(String(err));
+    }
+  }, [workspacePath]);
+
+  useEffect(() => {
+    loadTrackers();
+  }, [loadTrackers]);
+
+  const tracker = trackers.find((t) => t.provider === provider);
+
+  const search = async () => {
+    if (!provider) return;
+    setIsLoading(true);
+    setError(null);
+    try {
+      setIssues(await tauriApi.listIssues(workspacePath, provider, query.trim() || undefined));
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  const saveToken = async () => {
+    if (!provider || !token.trim()) return;
+    try {
+      await tauriApi.setIssueTrackerToken(provider, token.trim());
+      setToken('');
+      await loadTrackers();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  if (trackers.length === 0) {
+    return (
+      <div className="px-4 py-3 text-xs text-text-tertiary bg-editor-bg border border-editor-border rounded-lg">
+        課題トラッカーが設定されていません。設定ファイルの <code className="font-mono">[issues]</code>{' '}
+        で GitHub リポジトリまたは Jira サイトを指定してください。
+        {error && <div className="text-red-400 mt-1">{error}</div>}
+      </div>
+    );
+  }
+
+  return (
+    <div className="p-4 bg-editor-bg border border-editor-border rounded-lg space-y-3">
+      <div className="flex gap-2">
+        <select
+          value={provider ?? ''}
+          onChange={(e) => {
+            setProvider(e.target.value as IssueProvider);
+            setIssues([]);
+          }}
+          className="px-3 py-2 bg-editor-surface text-text-primary border border-editor-border rounded focus:outline-none focus:ring-2 focus:ring-accent-primary/50"
+        >
+          {trackers.map((t) => (
+            <option key={t.provider} value={t.provider}>
+              {PROVIDER_LABELS[t.provider]}
+              {t.project ? ` (${t.project})` : ''}
+            </option>
+          ))}
+        </select>
+        <input
+          value={query}
+          onChange={(e) => setQuery(e.target.value)}
+          onKeyDown={(e) => e.key === 'Enter' && search()}
+          placeholder="課題を検索..."
+          disabled={!tracker?.hasToken}
+          className="flex-1 px-3 py-2 bg-editor-surface text-text-primary placeholder-text-tertiary border border-editor-border rounded focus:outline-none focus:ring-2 focus:ring-accent-primary/50 disabled:opacity-50"
+        />
+        <button
+          onClick={search}
+          disabled={!tracker?.hasToken || isLoading}
+          className="px-3 py-2 bg-editor-surface border border-editor-border rounded hover:bg-editor-border/30 disabled:opacity-50 transition-colors"
+          title="検索"
+        >
+          {isLoading ? <Loader2 size={16} className="animate-spin" /> : <Search size={16} />}
+        </button>
+      </div>
+
+      {tracker && !tracker.hasToken && (
+        <div className="flex gap-2">
+          <input
+            type="password"
+            value={token}
+            onChange={(e) => setToken(e.target.value)}
+            placeholder={`${PROVIDER_LABELS[tracker.provider]} APIトークン`}
+            className="flex-1 px-3 py-2 bg-editor-surface text-text-primary placeholder-text-tertiary border border-editor-border rounded focus:outline-none focus:ring-2 focus:ring-accent-primary/50"
+          />
+          <button
+            onClick={saveToken}
+            disabled={!token.trim()}
+            className="flex items-center gap-1 px-3 py-2 text-sm bg-editor-surface border border-editor-border rounded hover:bg-editor-border/30 disabled:opacity-50 transition-colors"
+          >
+            <KeyRound size={14} />
+            キーチェーンに保存
+          </button>
+        </div>
+      )}
+
+      {error && <div className="text-xs text-red-400">{error}</div>}
+
+      {issues.length > 0 && (
+        <ul className="max-h-48 overflow-y-auto divide-y divide-editor-border border border-editor-border rounded">
+          {issues.map((issue) => (
+            <li key={issue.key}>
+              <button
+                onClick={() => onSelect(issue)}
+                className="w-full text-left px-3 py-2 hover:bg-editor-border/30 transition-colors"
+              >
+                <span className="font-mono text-xs text-text-tertiary mr-2">{issue.key}</span>
+                <span className="text-sm text-text-primary">{issue.title}</span>
+                {issue.labels.length > 0 && (
+                  <span className="ml-2 text-xs text-text-tertiary">{issue.labels.join(', ')}</span>
+                )}
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+};
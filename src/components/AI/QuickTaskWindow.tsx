@@ -0,0 +1,73 @@
+import React, { useEffect, useRef, useState } from 'react';
+import { Zap } from 'lucide-react';
+import { tauriApi } from '@/services/tauri';
+
+/**
+ * Contents of the quick task window opened by the global shortcut.
+ * Enter hands the task to the Coordinator, Escape dismisses the window.
+ */
+export const QuickTaskWindow: React.FC = () => {
+  const [task, setTask] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [isSubmitting, setIsSubmitting] = useState(false);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    // The window is reused, so refocus whenever the shortcut shows it again
+    const focusInput = () => inputRef.current?.focus();
+    focusInput();
+    window.addEventListener('focus', focusInput);
+    return () => window.removeEventListener('focus', focusInput);
+  }, []);
+
+  const handleSubmit = async () => {
+    if (!task.trim() || isSubmitting) return;
+    setIsSubmitting(true);
+    setError(null);
+    try {
+      await tauriApi.submitQuickTask(task);
+      setTask('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsSubmitting(false);
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (e.key === 'Enter') {
+      e.preventDefault();
+      handleSubmit();
+    } else if (e.key === 'Escape') {
+      e.preventDefault();
+      setError(null);
+      tauriApi.dismissQuickTask();
+    }
+  };
+
+  return (
+    <div className="h-screen flex flex-col justify-center gap-2 px-4 bg-gray-900 text-gray-100 border border-gray-700 rounded-lg">
+      <div className="flex items-center gap-3">
+        <Zap size={20} className="text-yellow-400 shrink-0" />
+        <input
+          ref={inputRef}
+          type="text"
+          value={task}
+          onChange={(e) => setTask(e.target.value)}
+          onKeyDown={handleKeyDown}
+          disabled={isSubmitting}
+          placeholder="Describe a task for the agents..."
+          aria-label="Quick task"
+          className="flex-1 bg-transparent text-lg outline-none placeholder-gray-500"
+        />
+      </div>
+      {error ? (
+        <p className="text-sm text-red-400 truncate">{error}</p>
+      ) : (
+        <p className="text-xs text-gray-500">
+          Enter to run in the mode the Coordinator picks · Esc to close
+        </p>
+      )}
+    </div>
+  );
+};
@@ -15,3 +15,6 @@ export { CompetitionMonitorPanel } from './CompetitionMonitorPanel';
 
 export { default as MultiAgentPanel } from './MultiAgentPanel';
 export type { MultiAgentPanelProps, ClaudeCodeInstance } from './MultiAgentPanel';
+
+export { IssuePicker } from './IssuePicker';
+export type { IssuePickerProps } from './IssuePicker';
@@ -0,0 +1,142 @@
+/**
+ * Annotations Panel Component
+ *
+ * Lists the notes attached to files across the workspace, grouped by file.
+ * Clicking a note opens its file; notes can be edited or removed in place.
+ */
+
+import React, { useEffect, useMemo } from 'react';
+import { Pencil, StickyNote, Trash2, X } from 'lucide-react';
+import { useAnnotationStore } from '@/store/annotationStore';
+import { useEditorStore } from '@/store/editorStore';
+import type { Annotation } from '@/services/tauri';
+
+const lineLabel = (annotation: Annotation) =>
+  annotation.startLine === annotation.endLine
+    ? `Ln ${annotation.startLine}`
+    : `Ln ${annotation.startLine}-${annotation.endLine}`;
+
+export const AnnotationsPanel: React.FC = () => {
+  const {
+    annotations,
+    error,
+    showAnnotationsPanel,
+    fetchAnnotations,
+    updateAnnotation,
+    removeAnnotation,
+    hideAnnotationsPanel,
+    absolutePath,
+  } = useAnnotationStore();
+  const { addTab } = useEditorStore();
+
+  useEffect(() => {
+    if (showAnnotationsPanel) {
+      fetchAnnotations();
+    }
+  }, [showAnnotationsPanel, fetchAnnotations]);
+
+  const byFile = useMemo(() => {
+    const groups = new Map<string, Annotation[]>();
+    for (const annotation of annotations) {
+      groups.set(annotation.path, [...(groups.get(annotation.path) ?? []), annotation]);
+    }
+    for (const group of groups.values()) {
+      group.sort((a, b) => a.startLine - b.startLine);
+    }
+    return [...groups.entries()].sort(([a], [b]) => a.localeCompare(b));
+  }, [annotations]);
+
+  const handleEdit = (annotation: Annotation) => {
+    const text = window.prompt('Note', annotation.text);
+    if (text?.trim() && text.trim() !== annotation.text) {
+      updateAnnotation(annotation.id, text.trim());
+    }
+  };
+
+  const handleOpen = async (annotation: Annotation) => {
+    try {
+      await addTab(absolutePath(annotation));
+    } catch (err) {
+      console.error('Failed to open annotated file:', err);
+    }
+  };
+
+  if (!showAnnotationsPanel) {
+    return null;
+  }
+
+  return (
+    <div className="h-full flex flex-col bg-[#1E1E1E] text-[#CCCCCC] border-t border-[#2A2D2E]">
+      {/* Header */}
+      <div className="flex items-center justify-between border-b border-[#2A2D2E]">
+        <h3 className="text-[13px] font-semibold px-3 py-2">
+          NOTES
+          <span className="ml-2 text-[11px] text-[#858585] font-normal">
+            {annotations.length} {annotations.length === 1 ? 'note' : 'notes'}
+          </span>
+        </h3>
+        <button
+          className="p-2 hover:bg-[#3E3E42] rounded transition-colors mx-1"
+          onClick={hideAnnotationsPanel}
+          title="Close notes panel"
+        >
+          <X size={16} />
+        </button>
+      </div>
+
+      {error && <div className="px-3 py-2 text-[12px] text-[#F48771]">{error}</div>}
+
+      {/* Notes list */}
+      <div className="flex-1 overflow-y-auto">
+        {byFile.length === 0 ? (
+          <div className="flex items-center justify-center h-full text-[#858585] text-[13px]">
+            No notes. Select lines and press Cmd+Alt+N to add one.
+          </div>
+        ) : (
+          byFile.map(([path, group]) => (
+            <div key={path}>
+              <div className="px-3 py-1 text-[12px] font-semibold text-[#CCCCCC] bg-[#252526]">
+                {path}
+              </div>
+              {group.map((annotation) => (
+                <div
+                  key={annotation.id}
+                  className="group flex items-start gap-2 px-3 py-2 cursor-pointer hover:bg-[#2A2D2E] transition-colors"
+                  onClick={() => handleOpen(annotation)}
+                >
+                  <StickyNote size={14} className="text-[#D7BA7D] mt-0.5 flex-shrink-0" />
+                  <div className="flex-1 min-w-0">
+                    <div className="text-[13px] whitespace-pre-wrap break-words">
+                      {annotation.text}
+                    </div>
+                    <div className="text-[11px] text-[#858585] mt-1">{lineLabel(annotation)}</div>
+                  </div>
+                  <button
+                    className="p-1 opacity-0 group-hover:opacity-100 hover:bg-[#3E3E42] rounded transition-all"
+                    onClick={(e) => {
+                      e.stopPropagation();
+                      handleEdit(annotation);
+                    }}
+                    title="Edit note"
+                  >
+                    <Pencil size={12} />
+                  </button>
+                  <button
+                    className="p-1 opacity-0 group-hover:opacity-100 hover:bg-[#3E3E42] rounded transition-all"
+                    onClick={(e) => {
+                      e.stopPropagation();
+                      removeAnnotation(annotation.id);
+                    }}
+                    title="Delete note"
+                  >
+                    <Trash2 size={12} />
+                  </button>
+                </div>
+              ))}
+            </div>
+          ))
+        )}
+      </div>
+    </div>
+  );
+};
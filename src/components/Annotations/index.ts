@@ -0,0 +1,5 @@
+/**
+ * Annotation Components
+ */
+
+export { AnnotationsPanel } from './AnnotationsPanel';
@@ -0,0 +1,170 @@
+/**
+ * ClipboardHistoryPicker - Paste an earlier copy
+ *
+ * Cmd+Shift+V/Ctrl+Shift+V overlay listing the clipboard history, most
+ * recent first. Enter inserts the selected entry at the cursor.
+ */
+
+import React, { useEffect, useMemo, useRef, useState } from 'react';
+import { Bot, ClipboardList, Puzzle, Trash2, X } from 'lucide-react';
+import { useClipboardStore } from '@/store/clipboardStore';
+import type { ClipboardEntry } from '@/services/tauri';
+
+export interface ClipboardHistoryPickerProps {
+  /** Called with the text of the chosen entry */
+  onSelect: (text: string) => void;
+}
+
+/** First line of an entry, with a count of the lines left out */
+const preview = (entry: ClipboardEntry) => {
+  const lines = entry.text.split('\n');
+  const first = lines.find((line) => line.trim() !== '') ?? '';
+  return lines.length > 1 ? `${first.trim()}  (+${lines.length - 1} lines)` : first.trim();
+};
+
+const SourceIcon: React.FC<{ entry: ClipboardEntry }> = ({ entry }) => {
+  switch (entry.source) {
+    case 'agent':
+      return <Bot size={14} className="text-accent-primary flex-shrink-0" />;
+    case 'plugin':
+      return <Puzzle size={14} className="text-[#D7BA7D] flex-shrink-0" />;
+    default:
+      return <ClipboardList size={14} className="text-text-tertiary flex-shrink-0" />;
+  }
+};
+
+export const ClipboardHistoryPicker: React.FC<ClipboardHistoryPickerProps> = ({ onSelect }) => {
+  const { entries, showPicker, closePicker, remove, clear } = useClipboardStore();
+  const [query, setQuery] = useState('');
+  const [selectedIndex, setSelectedIndex] = useState(0);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    if (showPicker) {
+      setQuery('');
+      setSelectedIndex(0);
+      setTimeout(() => inputRef.current?.focus(), 0);
+    }
+  }, [showPicker]);
+
+  const filtered = useMemo(() => {
+    const lowerQuery = query.toLowerCase();
+    return entries.filter((entry) => entry.text.toLowerCase().includes(lowerQuery));
+  }, [entries, query]);
+
+  useEffect(() => {
+    setSelectedIndex(0);
+  }, [query]);
+
+  const choose = (entry: ClipboardEntry | undefined) => {
+    if (!entry) return;
+    closePicker();
+    onSelect(entry.text);
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'Escape') {
+      e.preventDefault();
+      closePicker();
+    } else if (e.key === 'ArrowDown') {
+      e.preventDefault();
+      setSelectedIndex((prev) => Math.min(prev + 1, filtered.length - 1));
+    } else if (e.key === 'ArrowUp') {
+      e.preventDefault();
+      setSelectedIndex((prev) => Math.max(prev - 1, 0));
+    } else if (e.key === 'Enter') {
+      e.preventDefault();
+      choose(filtered[selectedIndex]);
+    }
+  };
+
+  if (!showPicker) return null;
+
+  const selected = filtered[selectedIndex];
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-start justify-center pt-[20vh] bg-black/60 backdrop-blur-sm"
+      onClick={closePicker}
+    >
+      <div
+        className="w-full max-w-2xl bg-editor-elevated border border-editor-border rounded-xl shadow-2xl overflow-hidden"
+        onClick={(e) => e.stopPropagation()}
+        onKeyDown={handleKeyDown}
+      >
+        {/* Header */}
+        <div className="flex items-center gap-3 px-4 py-3 border-b border-editor-border bg-editor-surface">
+          <ClipboardList size={20} className="text-accent-primary" />
+          <h2 className="text-sm font-semibold text-text-primary">Clipboard History</h2>
+          <button
+            onClick={clear}
+            disabled={entries.length === 0}
+            className="ml-auto text-xs text-text-tertiary hover:text-text-primary disabled:opacity-40"
+            title="Clear history"
+          >
+            Clear
+          </button>
+          <button
+            onClick={closePicker}
+            className="p-1 hover:bg-editor-border/30 rounded transition-colors"
+            title="Close (Esc)"
+          >
+            <X size={16} className="text-text-tertiary" />
+          </button>
+        </div>
+
+        {/* Filter */}
+        <input
+          ref={inputRef}
+          type="text"
+          value={query}
+          onChange={(e) => setQuery(e.target.value)}
+          placeholder="Filter copied text..."
+          className="w-full px-4 py-3 bg-editor-bg text-text-primary placeholder-text-tertiary border-b border-editor-border focus:outline-none"
+        />
+
+        {/* Entries */}
+        <div className="max-h-64 overflow-y-auto">
+          {filtered.length === 0 ? (
+            <div className="px-4 py-6 text-center text-sm text-text-tertiary">
+              {entries.length === 0 ? 'Nothing copied yet.' : 'No copied text matches.'}
+            </div>
+          ) : (
+            filtered.map((entry, index) => (
+              <div
+                key={entry.id}
+                className={`group flex items-center gap-3 px-4 py-2 cursor-pointer ${
+                  index === selectedIndex ? 'bg-accent-primary/20' : 'hover:bg-editor-hover/50'
+                }`}
+                onClick={() => choose(entry)}
+                onMouseEnter={() => setSelectedIndex(index)}
+              >
+                <SourceIcon entry={entry} />
+                <span className="flex-1 min-w-0 truncate font-mono text-xs text-text-primary">
+                  {preview(entry)}
+                </span>
+                <button
+                  className="p-1 opacity-0 group-hover:opacity-100 hover:bg-editor-border/30 rounded transition-all"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    remove(entry.id);
+                  }}
+                  title="Remove from history"
+                >
+                  <Trash2 size={12} className="text-text-tertiary" />
+                </button>
+              </div>
+            ))
+          )}
+        </div>
+
+        {/* Full text of the selected entry */}
+        {selected && selected.text.includes('\n') && (
+          <pre className="max-h-40 overflow-auto px-4 py-3 border-t border-editor-border bg-editor-bg text-xs text-text-secondary font-mono whitespace-pre">
+            {selected.text}
+          </pre>
+        )}
+      </div>
+    </div>
+  );
+};
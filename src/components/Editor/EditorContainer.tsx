@@ -5,18 +5,23 @@
  * handling tab switching and content updates.
  */
 
-import React, { useRef, useCallback, useMemo } from 'react';
+import React, { useRef, useCallback, useMemo, useEffect } from 'react';
 import { FileText, FolderOpen } from 'lucide-react';
 import { TabBar } from './TabBar';
 import { EditorPane } from './EditorPane';
 import { Terminal } from '@/components/Terminal';
 import { DiagnosticsPanel } from '@/components/Diagnostics';
 import { GitPanel } from '@/components/Git';
+import { AnnotationsPanel } from '@/components/Annotations';
+import { TodosPanel } from '@/components/Todos';
 import { useEditorStore } from '@/store/editorStore';
 import { useTerminalStore, MIN_TERMINAL_HEIGHT } from '@/store/terminalStore';
 import { useLspStore } from '@/store/lspStore';
 import { useGitStore } from '@/store/gitStore';
+import { useAnnotationStore } from '@/store/annotationStore';
+import { useTodoStore } from '@/store/todoStore';
 import { open } from '@tauri-apps/api/dialog';
+import { listen } from '@tauri-apps/api/event';
 
 interface EmptyStateProps {
   onFileOpen?: (path: string) => void;
@@ -168,6 +173,26 @@ export const EditorContainer: React.FC<EditorContainerProps> = ({ onFileOpen, on
   } = useTerminalStore();
   const { showDiagnosticsPanel } = useLspStore();
   const { showGitPanel } = useGitStore();
+  const { showAnnotationsPanel, fetchAnnotations } = useAnnotationStore();
+  const { showTodosPanel, loaded: todosLoaded, fetchTodos } = useTodoStore();
+
+  // Load notes for the gutter markers, again whenever the workspace changes
+  useEffect(() => {
+    fetchAnnotations();
+    const unlisten = listen('workspace-changed', () => fetchAnnotations());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [fetchAnnotations]);
+
+  // Rescan TODOs of a new workspace, once they have been listed
+  useEffect(() => {
+    if (!todosLoaded) return;
+    const unlisten = listen('workspace-changed', () => fetchTodos());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [todosLoaded, fetchTodos]);
 
   const containerRef = useRef<HTMLDivElement>(null);
   const isResizing = useRef(false);
@@ -238,6 +263,8 @@ export const EditorContainer: React.FC<EditorContainerProps> = ({ onFileOpen, on
   // OPTIMIZED: Memoize panel height calculations
   const diagnosticsPanelHeight = 200; // Fixed height for diagnostics panel
   const gitPanelHeight = 300; // Fixed height for git panel
+  const annotationsPanelHeight = 200; // Fixed height for notes panel
+  const todosPanelHeight = 200; // Fixed height for TODOs panel
 
   const editorHeight = useMemo(() => {
     let totalBottomPanelHeight = 0;
@@ -251,6 +278,14 @@ export const EditorContainer: React.FC<EditorContainerProps> = ({ onFileOpen, on
       totalBottomPanelHeight += gitPanelHeight;
       separatorCount += 1;
     }
+    if (showAnnotationsPanel) {
+      totalBottomPanelHeight += annotationsPanelHeight;
+      separatorCount += 1;
+    }
+    if (showTodosPanel) {
+      totalBottomPanelHeight += todosPanelHeight;
+      separatorCount += 1;
+    }
     if (isTerminalVisible) {
       totalBottomPanelHeight += terminalHeight;
       separatorCount += 1;
@@ -259,7 +294,14 @@ export const EditorContainer: React.FC<EditorContainerProps> = ({ onFileOpen, on
     return totalBottomPanelHeight > 0
       ? `calc(100% - ${totalBottomPanelHeight}px - ${separatorCount}px)`
       : '100%';
-  }, [showDiagnosticsPanel, showGitPanel, isTerminalVisible, terminalHeight]);
+  }, [
+    showDiagnosticsPanel,
+    showGitPanel,
+    showAnnotationsPanel,
+    showTodosPanel,
+    isTerminalVisible,
+    terminalHeight,
+  ]);
 
   return (
     <div ref={containerRef} className="flex flex-col h-full">
@@ -304,6 +346,26 @@ export const EditorContainer: React.FC<EditorContainerProps> = ({ onFileOpen, on
         </>
       )}
 
+      {/* Notes Panel section */}
+      {showAnnotationsPanel && (
+        <>
+          <div className="h-1 bg-[#2D2D30]" />
+          <div style={{ height: annotationsPanelHeight }}>
+            <AnnotationsPanel />
+          </div>
+        </>
+      )}
+
+      {/* TODOs Panel section */}
+      {showTodosPanel && (
+        <>
+          <div className="h-1 bg-[#2D2D30]" />
+          <div style={{ height: todosPanelHeight }}>
+            <TodosPanel />
+          </div>
+        </>
+      )}
+
       {/* Terminal section */}
       {isTerminalVisible && (
         <>
@@ -8,13 +8,18 @@
  * - Keyboard shortcuts (Cmd+S to save)
  * - Cursor Dark theme
  * - Auto-save on content change
+ * - Annotation markers in the gutter (Cmd+Alt+N adds a note)
  */
 
-import React, { useRef, useCallback, useEffect } from 'react';
+import React, { useRef, useCallback, useEffect, useMemo, useState } from 'react';
 import Editor, { Monaco, OnMount } from '@monaco-editor/react';
 import type { editor as MonacoEditor } from 'monaco-editor';
 import { registerCursorDarkTheme } from '@/themes/monaco-cursor-dark';
 import { useMonacoLsp } from '@/hooks/useMonacoLsp';
+import { useAnnotationStore } from '@/store/annotationStore';
+import { useTodoStore } from '@/store/todoStore';
+import { useClipboardStore } from '@/store/clipboardStore';
+import { ClipboardHistoryPicker } from './ClipboardHistoryPicker';
 
 export interface EditorPaneProps {
   /** Buffer/tab ID */
@@ -47,6 +52,21 @@ export const EditorPane: React.FC<EditorPaneProps> = ({
 }) => {
   const editorRef = useRef<MonacoEditor.IStandaloneCodeEditor | null>(null);
   const monacoRef = useRef<Monaco | null>(null);
+  const annotationDecorationsRef = useRef<MonacoEditor.IEditorDecorationsCollection | null>(null);
+  const [isMounted, setIsMounted] = useState(false);
+  const allAnnotations = useAnnotationStore((state) => state.annotations);
+  const workspaceRoot = useAnnotationStore((state) => state.workspaceRoot);
+  const annotations = useMemo(
+    () => useAnnotationStore.getState().annotationsFor(filePath),
+    [allAnnotations, workspaceRoot, filePath]
+  );
+  const addAnnotation = useAnnotationStore((state) => state.addAnnotation);
+  const revealTarget = useTodoStore((state) => state.revealTarget);
+  const clearReveal = useTodoStore((state) => state.clearReveal);
+  const pushClipboard = useClipboardStore((state) => state.push);
+  const openClipboardPicker = useClipboardStore((state) => state.openPicker);
+  const filePathRef = useRef(filePath);
+  filePathRef.current = filePath;
 
   /**
    * LSP Integration
@@ -65,6 +85,7 @@ export const EditorPane: React.FC<EditorPaneProps> = ({
   const handleEditorDidMount: OnMount = useCallback((editor, monaco) => {
     editorRef.current = editor;
     monacoRef.current = monaco;
+    setIsMounted(true);
 
     // Register Cursor Dark theme
     registerCursorDarkTheme(monaco);
@@ -76,6 +97,47 @@ export const EditorPane: React.FC<EditorPaneProps> = ({
       notifyDidSave();
     });
 
+    // Keep copies and cuts in the clipboard history
+    const recordCopy = (event: ClipboardEvent) => {
+      const selection = editor.getSelection();
+      const text =
+        event.clipboardData?.getData('text/plain') ||
+        (selection ? editor.getModel()?.getValueInRange(selection) : '') ||
+        '';
+      pushClipboard(text);
+    };
+    editor.getDomNode()?.addEventListener('copy', recordCopy);
+    editor.getDomNode()?.addEventListener('cut', recordCopy);
+
+    // Paste an earlier copy
+    editor.addAction({
+      id: 'clipboard.pasteFromHistory',
+      label: 'Paste from Clipboard History...',
+      contextMenuGroupId: '9_cutcopypaste',
+      keybindings: [monaco.KeyMod.CtrlCmd | monaco.KeyMod.Shift | monaco.KeyCode.KeyV],
+      run: () => openClipboardPicker(),
+    });
+
+    // Attach a note to the selected lines
+    editor.addAction({
+      id: 'annotations.add',
+      label: 'Add Note...',
+      contextMenuGroupId: 'annotations',
+      keybindings: [monaco.KeyMod.CtrlCmd | monaco.KeyMod.Alt | monaco.KeyCode.KeyN],
+      run: () => {
+        const selection = editor.getSelection();
+        if (!selection) return;
+        const text = window.prompt('Note');
+        if (!text?.trim()) return;
+        // A selection ending at column 1 does not include that line
+        const endLine =
+          selection.endColumn === 1 && selection.endLineNumber > selection.startLineNumber
+            ? selection.endLineNumber - 1
+            : selection.endLineNumber;
+        addAnnotation(filePathRef.current, selection.startLineNumber, endLine, text.trim());
+      },
+    });
+
     // Add AI context menu actions
     if (onAIAction) {
       // Helper function to get selected text
@@ -191,7 +253,21 @@ export const EditorPane: React.FC<EditorPaneProps> = ({
         indentation: true,
       },
     });
-  }, [onSave, onAIAction]);
+  }, [onSave, onAIAction, addAnnotation, pushClipboard, openClipboardPicker]);
+
+  /**
+   * Insert an entry picked from the clipboard history
+   */
+  const handlePasteFromHistory = useCallback((text: string) => {
+    const editor = editorRef.current;
+    if (!editor) return;
+    const selections = editor.getSelections() ?? [];
+    editor.executeEdits(
+      'clipboard-history',
+      selections.map((range) => ({ range, text, forceMoveMarkers: true }))
+    );
+    editor.focus();
+  }, []);
 
   /**
    * Handle content change
@@ -205,6 +281,44 @@ export const EditorPane: React.FC<EditorPaneProps> = ({
     [onChange]
   );
 
+  /**
+   * Show annotation markers for the current file
+   */
+  useEffect(() => {
+    const editor = editorRef.current;
+    const monaco = monacoRef.current;
+    if (!editor || !monaco) return;
+
+    const decorations = annotations.map((annotation) => ({
+      range: new monaco.Range(annotation.startLine, 1, annotation.endLine, 1),
+      options: {
+        isWholeLine: true,
+        glyphMarginClassName: 'annotation-glyph',
+        glyphMarginHoverMessage: { value: annotation.text },
+        linesDecorationsClassName: 'annotation-line',
+      },
+    }));
+    if (annotationDecorationsRef.current) {
+      annotationDecorationsRef.current.set(decorations);
+    } else {
+      annotationDecorationsRef.current = editor.createDecorationsCollection(decorations);
+    }
+  }, [annotations, isMounted]);
+
+  /**
+   * Jump to a TODO picked in the TODOs panel
+   */
+  useEffect(() => {
+    const editor = editorRef.current;
+    if (!editor || !revealTarget || revealTarget.path !== filePath) return;
+
+    const position = { lineNumber: revealTarget.line, column: revealTarget.column };
+    editor.setPosition(position);
+    editor.revealPositionInCenter(position);
+    editor.focus();
+    clearReveal();
+  }, [revealTarget, filePath, isMounted, clearReveal]);
+
   /**
    * Focus editor when buffer changes
    */
@@ -227,6 +341,7 @@ export const EditorPane: React.FC<EditorPaneProps> = ({
           automaticLayout: true,
           wordWrap: 'off',
           lineNumbers: 'on',
+          glyphMargin: true,
           renderLineHighlight: 'all',
           renderWhitespace: 'selection',
           tabSize: 2,
@@ -278,6 +393,7 @@ export const EditorPane: React.FC<EditorPaneProps> = ({
           </div>
         }
       />
+      <ClipboardHistoryPicker onSelect={handlePasteFromHistory} />
     </div>
   );
 };
@@ -7,6 +7,7 @@
  * - Commit interface
  * - Push/pull operations
  * - Commit history
+ * - Merge conflict resolution
  */
 
 import React, { useState, useEffect } from 'react';
@@ -23,6 +24,7 @@ import {
   FileText,
 } from 'lucide-react';
 import { useGitStore, type GitFileStatus } from '@/store/gitStore';
+import { MergeConflictView } from './MergeConflictView';
 
 /**
  * Branch selector and management
@@ -454,7 +456,7 @@ const HistoryView: React.FC = () => {
  */
 export const GitPanel: React.FC = () => {
   const { showGitPanel, hideGit, fetchStatus } = useGitStore();
-  const [activeTab, setActiveTab] = useState<'changes' | 'history'>('changes');
+  const [activeTab, setActiveTab] = useState<'changes' | 'history' | 'conflicts'>('changes');
 
   useEffect(() => {
     if (showGitPanel) {
@@ -505,12 +507,23 @@ export const GitPanel: React.FC = () => {
         >
           History
         </button>
+        <button
+          className={`px-3 py-2 text-[12px] transition-colors ${
+            activeTab === 'conflicts'
+              ? 'text-[#CCCCCC] border-b-2 border-[#007ACC]'
+              : 'text-[#858585] hover:text-[#CCCCCC]'
+          }`}
+          onClick={() => setActiveTab('conflicts')}
+        >
+          Conflicts
+        </button>
       </div>
 
       {/* Content */}
       <div className="flex-1 overflow-hidden">
         {activeTab === 'changes' && <ChangesView />}
         {activeTab === 'history' && <HistoryView />}
+        {activeTab === 'conflicts' && <MergeConflictView onAllResolved={fetchStatus} />}
       </div>
     </div>
   );
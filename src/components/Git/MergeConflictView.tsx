@@ -0,0 +1,351 @@
+/**
+ * Merge Conflict View
+ *
+ * Three-way view (ours/base/theirs) of files with conflict markers.
+ * Every hunk is resolved from the keyboard:
+ * - j/k: next/previous hunk, ]/[: next/previous file
+ * - o/t/b: take ours/theirs/both, u: undo the choice
+ * - e: edit the hunk inline (Ctrl+Enter saves, Escape cancels)
+ * - m: mark the file resolved once every hunk is
+ *
+ * Works on the workspace or, through `repoPath`, on an agent's worktree such
+ * as the integration worktree of an ensemble session.
+ */
+
+import React, { useState, useEffect, useCallback, useRef } from 'react';
+import { Check, GitMerge, RefreshCw, X } from 'lucide-react';
+import {
+  tauriApi,
+  type ConflictFile,
+  type ConflictHunk,
+  type ConflictResolution,
+} from '@/services/tauri';
+
+interface MergeConflictViewProps {
+  /** Worktree to resolve in; defaults to the workspace */
+  repoPath?: string;
+  /** Called when the view is dismissed */
+  onClose?: () => void;
+  /** Called once no file is left in conflict */
+  onAllResolved?: () => void;
+}
+
+const hunksOf = (file: ConflictFile): ConflictHunk[] =>
+  file.segments.flatMap((segment) => (segment.kind === 'conflict' ? [segment] : []));
+
+const resolvedText = (hunk: ConflictHunk, resolution: ConflictResolution): string => {
+  switch (resolution.kind) {
+    case 'ours':
+      return hunk.ours;
+    case 'theirs':
+      return hunk.theirs;
+    case 'both':
+      return hunk.ours + hunk.theirs;
+    case 'custom':
+      return resolution.text;
+  }
+};
+
+const resolutionLabel: Record<ConflictResolution['kind'], string> = {
+  ours: 'Ours',
+  theirs: 'Theirs',
+  both: 'Both',
+  custom: 'Edited',
+};
+
+const Side: React.FC<{ title: string; text: string | null; active: boolean }> = ({
+  title,
+  text,
+  active,
+}) => (
+  <div className="flex-1 min-w-0 border-r border-[#2A2D2E] last:border-r-0">
+    <div
+      className={`px-2 py-1 text-[11px] truncate ${active ? 'text-[#CCCCCC]' : 'text-[#858585]'}`}
+    >
+      {title}
+    </div>
+    <pre className="px-2 pb-2 text-[12px] font-mono whitespace-pre-wrap break-all text-[#CCCCCC]">
+      {text === null ? (
+        <span className="italic text-[#858585]">Base not in markers</span>
+      ) : (
+        text
+      )}
+    </pre>
+  </div>
+);
+
+export const MergeConflictView: React.FC<MergeConflictViewProps> = ({
+  repoPath,
+  onClose,
+  onAllResolved,
+}) => {
+  const [files, setFiles] = useState<string[]>([]);
+  const [selectedPath, setSelectedPath] = useState<string | null>(null);
+  const [file, setFile] = useState<ConflictFile | null>(null);
+  const [resolutions, setResolutions] = useState<(ConflictResolution | null)[]>([]);
+  const [current, setCurrent] = useState(0);
+  const [editing, setEditing] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const containerRef = useRef<HTMLDivElement>(null);
+
+  const hunks = file ? hunksOf(file) : [];
+  const allResolved = hunks.length > 0 && resolutions.every((r) => r !== null);
+
+  const showFile = useCallback((loaded: ConflictFile) => {
+    setFile(loaded);
+    setResolutions(hunksOf(loaded).map(() => null));
+    setCurrent(0);
+    setEditing(null);
+  }, []);
+
+  const refreshFiles = useCallback(async () => {
+    try {
+      const conflicted = await tauriApi.gitConflictedFiles(repoPath);
+      setFiles(conflicted);
+      setSelectedPath((selected) =>
+        selected && conflicted.includes(selected) ? selected : conflicted[0] ?? null
+      );
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  }, [repoPath]);
+
+  useEffect(() => {
+    refreshFiles();
+  }, [refreshFiles]);
+
+  useEffect(() => {
+    if (!selectedPath) {
+      setFile(null);
+      return;
+    }
+    tauriApi
+      .gitLoadConflicts(selectedPath, repoPath)
+      .then(showFile)
+      .catch((err) => setError(String(err)));
+  }, [selectedPath, repoPath, showFile]);
+
+  const resolve = (index: number, resolution: ConflictResolution | null) => {
+    setResolutions((previous) => previous.map((r, i) => (i === index ? resolution : r)));
+    // Move on to the next hunk still waiting for a choice
+    if (resolution) {
+      const next = resolutions.findIndex((r, i) => i > index && r === null);
+      if (next !== -1) setCurrent(next);
+    }
+  };
+
+  const markResolved = async () => {
+    if (!file || !allResolved) return;
+    try {
+      const remaining = await tauriApi.gitResolveConflicts(
+        file.path,
+        resolutions as ConflictResolution[],
+        repoPath
+      );
+      setFiles(remaining);
+      setSelectedPath(remaining[0] ?? null);
+      setError(null);
+      if (remaining.length === 0) onAllResolved?.();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const restoreBase = async () => {
+    if (!file) return;
+    if (!window.confirm(`Rewrite ${file.path} from the index? Unsaved hand edits are lost.`)) return;
+    try {
+      showFile(await tauriApi.gitRestoreConflictBase(file.path, repoPath));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const selectFile = (offset: number) => {
+    if (files.length === 0) return;
+    const index = selectedPath ? files.indexOf(selectedPath) : -1;
+    setSelectedPath(files[(index + offset + files.length) % files.length]);
+  };
+
+  const startEditing = () => {
+    const hunk = hunks[current];
+    if (!hunk) return;
+    const resolution = resolutions[current];
+    setEditing(resolution ? resolvedText(hunk, resolution) : hunk.ours);
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (editing !== null || e.metaKey || e.altKey) return;
+    const keys: Record<string, () => void> = {
+      j: () => setCurrent((i) => Math.min(i + 1, hunks.length - 1)),
+      ArrowDown: () => setCurrent((i) => Math.min(i + 1, hunks.length - 1)),
+      k: () => setCurrent((i) => Math.max(i - 1, 0)),
+      ArrowUp: () => setCurrent((i) => Math.max(i - 1, 0)),
+      ']': () => selectFile(1),
+      '[': () => selectFile(-1),
+      o: () => resolve(current, { kind: 'ours' }),
+      t: () => resolve(current, { kind: 'theirs' }),
+      b: () => resolve(current, { kind: 'both' }),
+      u: () => resolve(current, null),
+      e: startEditing,
+      m: markResolved,
+      Escape: () => onClose?.(),
+    };
+    const action = e.ctrlKey ? undefined : keys[e.key];
+    if (action) {
+      e.preventDefault();
+      action();
+    }
+  };
+
+  const handleEditKeyDown = (e: React.KeyboardEvent<HTMLTextAreaElement>) => {
+    if (e.key === 'Enter' && (e.ctrlKey || e.metaKey)) {
+      e.preventDefault();
+      resolve(current, { kind: 'custom', text: editing ?? '' });
+      setEditing(null);
+      containerRef.current?.focus();
+    } else if (e.key === 'Escape') {
+      e.preventDefault();
+      e.stopPropagation();
+      setEditing(null);
+      containerRef.current?.focus();
+    }
+  };
+
+  // Keep keyboard focus on the view so shortcuts work right away
+  useEffect(() => {
+    containerRef.current?.focus();
+  }, [file]);
+
+  return (
+    <div
+      ref={containerRef}
+      tabIndex={0}
+      onKeyDown={handleKeyDown}
+      className="h-full flex flex-col bg-[#1E1E1E] text-[#CCCCCC] outline-none"
+    >
+      {/* Header */}
+      <div className="flex items-center gap-2 px-3 py-2 border-b border-[#2A2D2E]">
+        <GitMerge size={16} className="text-[#C586C0]" />
+        <span className="text-[13px] font-semibold flex-1 truncate">
+          {file ? file.path : 'Merge conflicts'}
+          {hunks.length > 0 && (
+            <span className="ml-2 text-[11px] text-[#858585] font-normal">
+              {resolutions.filter((r) => r !== null).length}/{hunks.length} resolved
+            </span>
+          )}
+        </span>
+        <button
+          className="p-1 hover:bg-[#3E3E42] rounded transition-colors"
+          onClick={refreshFiles}
+          title="Refresh conflicted files"
+        >
+          <RefreshCw size={14} />
+        </button>
+        <button
+          className="flex items-center gap-1 px-2 py-1 text-[12px] rounded bg-[#0E639C] hover:bg-[#1177BB] disabled:opacity-40 disabled:cursor-not-allowed transition-colors"
+          onClick={markResolved}
+          disabled={!allResolved}
+          title="Mark resolved (m)"
+        >
+          <Check size={12} />
+          Mark resolved
+        </button>
+        {onClose && (
+          <button
+            className="p-1 hover:bg-[#3E3E42] rounded transition-colors"
+            onClick={onClose}
+            title="Close (Escape)"
+          >
+            <X size={16} />
+          </button>
+        )}
+      </div>
+
+      {error && (
+        <div className="px-3 py-2 text-[12px] text-[#F48771] border-b border-[#2A2D2E]">{error}</div>
+      )}
+
+      <div className="flex-1 flex min-h-0">
+        {/* Conflicted files */}
+        <div className="w-48 flex-shrink-0 overflow-y-auto border-r border-[#2A2D2E]">
+          {files.length === 0 ? (
+            <div className="px-3 py-2 text-[12px] text-[#858585]">No conflicts</div>
+          ) : (
+            files.map((path) => (
+              <button
+                key={path}
+                className={`w-full px-3 py-1.5 text-[12px] text-left truncate transition-colors ${
+                  path === selectedPath ? 'bg-[#37373D] text-[#CCCCCC]' : 'hover:bg-[#2A2D2E] text-[#858585]'
+                }`}
+                onClick={() => setSelectedPath(path)}
+                title={path}
+              >
+                {path}
+              </button>
+            ))
+          )}
+        </div>
+
+        {/* Hunks */}
+        <div className="flex-1 overflow-y-auto">
+          {file && hunks.length === 0 && (
+            <div className="px-3 py-2 text-[12px] text-[#858585]">
+              No conflict markers left in this file. Stage it from Source Control.
+            </div>
+          )}
+          {file && hunks.some((hunk) => hunk.base === null) && (
+            <div className="flex items-center gap-2 px-3 py-2 text-[12px] text-[#858585] border-b border-[#2A2D2E]">
+              <span className="flex-1">The markers do not include the merge base.</span>
+              <button className="text-[#4EC9B0] hover:underline" onClick={restoreBase}>
+                Show base
+              </button>
+            </div>
+          )}
+          {hunks.map((hunk, index) => {
+            const resolution = resolutions[index];
+            const active = index === current;
+            return (
+              <div
+                key={index}
+                className={`m-2 rounded border ${active ? 'border-[#007ACC]' : 'border-[#2A2D2E]'}`}
+                onClick={() => setCurrent(index)}
+              >
+                <div className="flex">
+                  <Side title={`Ours · ${hunk.ours_label}`} text={hunk.ours} active={active} />
+                  <Side title="Base" text={hunk.base} active={active} />
+                  <Side title={`Theirs · ${hunk.theirs_label}`} text={hunk.theirs} active={active} />
+                </div>
+                <div className="border-t border-[#2A2D2E] px-2 py-1">
+                  {active && editing !== null ? (
+                    <textarea
+                      className="w-full min-h-[80px] px-2 py-1 bg-[#3E3E42] text-[12px] font-mono rounded border border-[#454545] focus:border-[#007ACC] focus:outline-none"
+                      value={editing}
+                      onChange={(e) => setEditing(e.target.value)}
+                      onKeyDown={handleEditKeyDown}
+                      autoFocus
+                    />
+                  ) : resolution ? (
+                    <>
+                      <div className="text-[11px] text-[#4EC9B0]">
+                        Result · {resolutionLabel[resolution.kind]}
+                      </div>
+                      <pre className="text-[12px] font-mono whitespace-pre-wrap break-all">
+                        {resolvedText(hunk, resolution)}
+                      </pre>
+                    </>
+                  ) : (
+                    <div className="text-[11px] text-[#858585]">
+                      o ours · t theirs · b both · e edit
+                    </div>
+                  )}
+                </div>
+              </div>
+            );
+          })}
+        </div>
+      </div>
+    </div>
+  );
+};
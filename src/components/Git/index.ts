@@ -3,3 +3,4 @@
  */
 
 export { GitPanel } from './GitPanel';
+export { MergeConflictView } from './MergeConflictView';
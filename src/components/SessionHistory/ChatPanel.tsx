@@ -9,15 +9,16 @@ import React, { useState, useRef, useEffect } from 'react';
 import { useSessionHistoryStore } from '@/store/sessionHistoryStore';
 import { tauriApi } from '@/services/tauri';
 import type { WorktreeSession, ChatMessage } from '@/types/worktree';
-import { Send, Terminal, User, Bot, AlertCircle, Loader, MessageSquare, SplitSquareHorizontal, MonitorPlay, AlertTriangle, Archive } from 'lucide-react';
+import { Send, Terminal, User, Bot, AlertCircle, Loader, MessageSquare, SplitSquareHorizontal, MonitorPlay, AlertTriangle, Archive, GitMerge } from 'lucide-react';
 import { getRuntimeDefinition } from '@/config/runtimes';
 import { TerminalView } from './TerminalView';
+import { MergeConflictView } from '@/components/Git';
 
 interface ChatPanelProps {
   session: WorktreeSession;
 }
 
-type ViewMode = 'chat' | 'terminal' | 'split';
+type ViewMode = 'chat' | 'terminal' | 'split' | 'conflicts';
 
 export const ChatPanel: React.FC<ChatPanelProps> = ({ session }) => {
   const [message, setMessage] = useState('');
@@ -425,6 +426,26 @@ export const ChatPanel: React.FC<ChatPanelProps> = ({ session }) => {
               <SplitSquareHorizontal className="w-3.5 h-3.5" />
               Split
             </button>
+            <button
+              onClick={() => setViewMode('conflicts')}
+              className={`
+                px-3 py-1 rounded text-xs font-medium transition-all flex items-center gap-1.5
+                ${
+                  viewMode === 'conflicts'
+                    ? 'bg-blue-500 text-white shadow-sm'
+                    : 'text-gray-600 hover:bg-gray-100'
+                }
+              `}
+              title={
+                selectedInstance?.instanceId === session.integrationInstanceId
+                  ? 'Resolve merge conflicts in the integration worktree'
+                  : "Resolve merge conflicts in this instance's worktree"
+              }
+              disabled={!selectedInstance?.worktreePath}
+            >
+              <GitMerge className="w-3.5 h-3.5" />
+              Conflicts
+            </button>
           </div>
           {!selectedInstance?.tmuxSessionId && (
             <span className="text-xs text-amber-600">
@@ -460,6 +481,16 @@ export const ChatPanel: React.FC<ChatPanelProps> = ({ session }) => {
         />
       )}
 
+      {viewMode === 'conflicts' && selectedInstance?.worktreePath && (
+        <div className="flex-1 overflow-hidden">
+          <MergeConflictView
+            key={selectedInstance.worktreePath}
+            repoPath={selectedInstance.worktreePath}
+            onClose={() => setViewMode('chat')}
+          />
+        </div>
+      )}
+
       {viewMode === 'split' && selectedInstance?.tmuxSessionId && (
         <div ref={splitContainerRef} className="flex-1 flex overflow-hidden">
           {/* Chat Panel */}
@@ -7,7 +7,9 @@
 import React, { useState, useEffect } from 'react';
 import { useSessionHistoryStore } from '@/store/sessionHistoryStore';
 import { useWorktreeStore } from '@/store/worktreeStore';
-import type { WorktreeSession, WorktreeInstance } from '@/types/worktree';
+import { listen } from '@tauri-apps/api/event';
+import { tauriApi, type AgentChange, type CompetitionSummary } from '@/services/tauri';
+import type { ContextScan, WorktreeSession, WorktreeInstance } from '@/types/worktree';
 import {
   X,
   FileText,
@@ -19,6 +21,9 @@ import {
   AlertCircle,
   Loader,
   Calendar,
+  Send,
+  AlertTriangle,
+  ShieldAlert,
 } from 'lucide-react';
 import { WorktreeExplorer } from '@/components/Worktree/WorktreeExplorer';
 import { WinnerSelectionPanel } from './WinnerSelectionPanel';
@@ -73,6 +78,17 @@ const formatDuration = (seconds?: number): string => {
   return parts.join(' ');
 };
 
+/**
+ * Tailwind classes for a line of a unified diff
+ */
+const diffLineClass = (line: string): string => {
+  if (line.startsWith('+++') || line.startsWith('---')) return 'text-gray-500';
+  if (line.startsWith('+')) return 'text-green-700 bg-green-50';
+  if (line.startsWith('-')) return 'text-red-700 bg-red-50';
+  if (line.startsWith('@@')) return 'text-blue-600';
+  return 'text-gray-800';
+};
+
 /**
  * Format date to readable string
  */
@@ -86,7 +102,8 @@ export const SessionDetailView: React.FC<SessionDetailViewProps> = ({
   onClose,
 }) => {
   const [activeTab, setActiveTab] = useState<TabType>('overview');
-  const { getSession, sessions, isLoading } = useSessionHistoryStore();
+  const [isPostingReport, setIsPostingReport] = useState(false);
+  const { getSession, loadSessions, sessions, isLoading, workspacePath } = useSessionHistoryStore();
 
   // Get session from store (reactive to changes)
   const session = sessions.find((s) => s.id === sessionId) || null;
@@ -101,6 +118,32 @@ export const SessionDetailView: React.FC<SessionDetailViewProps> = ({
     }
   }, [sessionId, session, getSession]);
 
+  // Reload once the backend has summarized the competition's diffs
+  useEffect(() => {
+    const unlisten = listen<CompetitionSummary>('competition-summarized', (event) => {
+      if (event.payload.sessionId === sessionId) {
+        loadSessions();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [sessionId, loadSessions]);
+
+  const handlePostReport = async () => {
+    if (!session?.issue) return;
+    setIsPostingReport(true);
+    try {
+      const url = await tauriApi.postSessionReport(workspacePath, session.id);
+      alert(`✅ Posted the session report to ${session.issue.key}${url ? `\n${url}` : ''}`);
+    } catch (error) {
+      console.error('Failed to post session report:', error);
+      alert(`Failed to post session report: ${error}`);
+    } finally {
+      setIsPostingReport(false);
+    }
+  };
+
   if (isLoading || !session) {
     return (
       <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
@@ -135,6 +178,27 @@ export const SessionDetailView: React.FC<SessionDetailViewProps> = ({
               <span className="inline-flex items-center gap-1 px-2.5 py-0.5 rounded-full bg-gray-100 text-gray-800 text-xs font-medium">
                 {session.status.toUpperCase()}
               </span>
+              {session.issue && (
+                <span className="inline-flex items-center gap-2">
+                  <a
+                    href={session.issue.url}
+                    target="_blank"
+                    rel="noreferrer"
+                    className="font-mono text-xs text-blue-600 hover:underline"
+                    title={session.issue.title}
+                  >
+                    {session.issue.key}
+                  </a>
+                  <button
+                    onClick={handlePostReport}
+                    disabled={isPostingReport}
+                    className="inline-flex items-center gap-1 px-2 py-0.5 text-xs rounded border border-gray-300 hover:bg-gray-200 disabled:opacity-50 transition-colors"
+                  >
+                    <Send className="w-3 h-3" />
+                    {isPostingReport ? 'Posting...' : 'Post report to issue'}
+                  </button>
+                </span>
+              )}
             </div>
           </div>
           <button
@@ -187,6 +251,9 @@ export const SessionDetailView: React.FC<SessionDetailViewProps> = ({
 const OverviewTab: React.FC<{ session: WorktreeSession }> = ({ session }) => {
   return (
     <div className="p-6 space-y-6">
+      <AgentChangesBanner session={session} />
+      <ContextScansPanel scans={session.contextScans ?? []} />
+
       {/* Summary Cards */}
       <div className="grid grid-cols-4 gap-4">
         <div className="bg-blue-50 border border-blue-200 rounded-lg p-4">
@@ -231,7 +298,14 @@ const OverviewTab: React.FC<{ session: WorktreeSession }> = ({ session }) => {
                     {instance.instanceId}
                   </span>
                   <div>
-                    <div className="font-medium text-gray-900">{instance.agentName}</div>
+                    <div className="font-medium text-gray-900">
+                      {instance.agentName}
+                      {instance.agentVersion && (
+                        <span className="ml-2 text-xs font-normal text-gray-500">
+                          v{instance.agentVersion}
+                        </span>
+                      )}
+                    </div>
                     <div className="text-sm text-gray-600">{instance.branch}</div>
                   </div>
                 </div>
@@ -270,6 +344,143 @@ const OverviewTab: React.FC<{ session: WorktreeSession }> = ({ session }) => {
                   )}
                 </div>
               )}
+
+              {instance.touchedModules && instance.touchedModules.length > 0 && (
+                <div className="flex flex-wrap gap-1 mt-2">
+                  {instance.touchedModules.map((module) => (
+                    <code
+                      key={module}
+                      className="px-2 py-0.5 bg-gray-100 rounded text-xs font-mono text-gray-700"
+                    >
+                      {module}
+                    </code>
+                  ))}
+                </div>
+              )}
+
+              {instance.diffSummary && (
+                <p className="text-sm text-gray-700 mt-2">{instance.diffSummary}</p>
+              )}
+            </div>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+};
+
+/**
+ * Warning that agent definitions changed since the session ran
+ *
+ * Re-running the session uses the current definitions, so the diffs from the
+ * recorded ones can be expanded.
+ */
+const AgentChangesBanner: React.FC<{ session: WorktreeSession }> = ({ session }) => {
+  const { workspacePath } = useSessionHistoryStore();
+  const [changes, setChanges] = useState<AgentChange[]>([]);
+  const [showDiffs, setShowDiffs] = useState(false);
+
+  useEffect(() => {
+    if (!session.agents || !workspacePath) return;
+    tauriApi
+      .getSessionAgentChanges(workspacePath, session.id)
+      .then((all) => setChanges(all.filter((change) => change.status !== 'unchanged')))
+      .catch((error) => console.error('Failed to compare session agents:', error));
+  }, [workspacePath, session.id, session.agents]);
+
+  if (changes.length === 0) return null;
+
+  const version = (v?: string | null) => (v ? `v${v}` : 'unversioned');
+
+  return (
+    <div className="bg-yellow-50 border border-yellow-300 rounded-lg p-4">
+      <div className="flex items-start justify-between gap-4">
+        <div className="flex items-start gap-2">
+          <AlertTriangle className="w-5 h-5 text-yellow-600 flex-shrink-0 mt-0.5" />
+          <div className="text-sm text-yellow-900">
+            <p className="font-medium">
+              Agent definitions changed since this session ran; re-running it uses the current
+              ones.
+            </p>
+            <ul className="mt-1 space-y-0.5">
+              {changes.map((change) => (
+                <li key={change.name}>
+                  <span className="font-mono">{change.name}</span>{' '}
+                  {change.status === 'removed'
+                    ? `${version(change.pinnedVersion)} → removed`
+                    : `${version(change.pinnedVersion)} → ${version(change.currentVersion)}`}
+                </li>
+              ))}
+            </ul>
+          </div>
+        </div>
+        {changes.some((change) => change.diff) && (
+          <button
+            onClick={() => setShowDiffs(!showDiffs)}
+            className="px-2 py-1 text-xs rounded border border-yellow-400 text-yellow-900 hover:bg-yellow-100 transition-colors"
+          >
+            {showDiffs ? 'Hide diffs' : 'Show diffs'}
+          </button>
+        )}
+      </div>
+      {showDiffs &&
+        changes
+          .filter((change) => change.diff)
+          .map((change) => (
+            <pre
+              key={change.name}
+              className="mt-3 p-3 bg-white border rounded text-xs font-mono overflow-auto max-h-80"
+            >
+              {change.diff.split('\n').map((line, i) => (
+                <div key={i} className={diffLineClass(line)}>
+                  {line || ' '}
+                </div>
+              ))}
+            </pre>
+          ))}
+    </div>
+  );
+};
+
+const FINDING_LABELS: Record<string, string> = {
+  instruction: 'Instruction to the model',
+  hidden_unicode: 'Hidden Unicode',
+  encoded_blob: 'Encoded blob',
+};
+
+/**
+ * Prompt injection findings in content sent to the session's agents
+ *
+ * Clean scans are only counted; scans with findings list them.
+ */
+const ContextScansPanel: React.FC<{ scans: ContextScan[] }> = ({ scans }) => {
+  const flagged = scans.filter((scan) => scan.findings.length > 0);
+  if (flagged.length === 0) return null;
+
+  return (
+    <div className="bg-red-50 border border-red-200 rounded-lg p-4">
+      <div className="flex items-start gap-2">
+        <ShieldAlert className="w-5 h-5 text-red-600 flex-shrink-0 mt-0.5" />
+        <div className="text-sm text-red-900 flex-1">
+          <p className="font-medium">
+            Possible prompt injection in {flagged.length} of {scans.length} scanned contexts
+          </p>
+          {flagged.map((scan) => (
+            <div key={scan.scannedAt + scan.source} className="mt-2">
+              <div className="text-xs text-red-700">
+                {scan.source} · {scan.policy === 'strip' ? 'stripped' : 'flagged'} ·{' '}
+                {formatDate(scan.scannedAt)}
+              </div>
+              <ul className="mt-1 space-y-0.5">
+                {scan.findings.map((finding, i) => (
+                  <li key={i}>
+                    <span className="text-xs text-red-700">
+                      line {finding.line}, {FINDING_LABELS[finding.kind] ?? finding.kind}:
+                    </span>{' '}
+                    <code className="font-mono text-xs break-all">{finding.excerpt}</code>
+                  </li>
+                ))}
+              </ul>
             </div>
           ))}
         </div>
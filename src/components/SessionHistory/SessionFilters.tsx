@@ -8,6 +8,8 @@ import { useSessionHistoryStore } from '@/store/sessionHistoryStore';
 import type { SessionType, SessionStatus, SessionSortOptions } from '@/types/worktree';
 import { Search, SlidersHorizontal, ArrowUpDown } from 'lucide-react';
 
+const STATUS_FILTERS: SessionStatus[] = ['running', 'paused', 'completed', 'failed', 'cancelled'];
+
 export const SessionFilters: React.FC = () => {
   const { filters, sortOptions, setFilters, setSortOptions } = useSessionHistoryStore();
 
@@ -65,11 +67,13 @@ export const SessionFilters: React.FC = () => {
           <SlidersHorizontal className="w-4 h-4 text-gray-500" />
           <span className="text-sm font-medium text-gray-700">種類:</span>
 
-          {(['competition', 'ensemble', 'debate'] as SessionType[]).map((type) => {
+          {(['competition', 'ensemble', 'debate', 'single', 'parallel'] as SessionType[]).map((type) => {
             const typeLabels = {
               competition: '競争',
               ensemble: 'アンサンブル',
               debate: 'ディベート',
+              single: '単独',
+              parallel: '並列',
             };
             return (
               <button
@@ -95,12 +99,13 @@ export const SessionFilters: React.FC = () => {
         <div className="flex items-center gap-2 border-l pl-4">
           <span className="text-sm font-medium text-gray-700">状態:</span>
 
-          {(['running', 'paused', 'completed', 'failed'] as SessionStatus[]).map((status) => {
+          {STATUS_FILTERS.map((status) => {
             const statusLabels = {
               running: '実行中',
               paused: '一時停止',
               completed: '完了',
               failed: '失敗',
+              cancelled: 'キャンセル',
             };
             return (
               <button
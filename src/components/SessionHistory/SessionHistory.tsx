@@ -2,19 +2,22 @@
  * Session History Component
  *
  * Main component for session history management with Kanban board
- * Combines filters, board, and detail view
+ * Combines filters, board, and detail view, and the team feed of
+ * teammates' sessions
  */
 import React, { useState, useEffect } from 'react';
-import { FolderOpen } from 'lucide-react';
+import { FolderOpen, LayoutGrid, Users } from 'lucide-react';
 import { KanbanBoard } from './KanbanBoard';
 import { SessionFilters } from './SessionFilters';
 import { SessionDetailView } from './SessionDetailView';
+import { TeamFeed } from './TeamFeed';
 import { useSessionHistoryStore } from '@/store/sessionHistoryStore';
 import { tauriApi } from '@/services/tauri';
 
 export const SessionHistory: React.FC = () => {
   const [selectedSessionId, setSelectedSessionId] = useState<string | null>(null);
   const [isValidWorkspace, setIsValidWorkspace] = useState<boolean>(false);
+  const [view, setView] = useState<'board' | 'team'>('board');
   const workspacePath = useSessionHistoryStore((state) => state.workspacePath);
 
   // Check if workspace is valid (has .git directory)
@@ -67,16 +70,44 @@ export const SessionHistory: React.FC = () => {
     );
   }
 
+  const viewTab = (id: typeof view, label: string, Icon: typeof Users) => (
+    <button
+      onClick={() => setView(id)}
+      className={`inline-flex items-center gap-1.5 px-3 py-2 text-sm border-b-2 transition-colors ${
+        view === id
+          ? 'border-blue-500 text-blue-600 font-medium'
+          : 'border-transparent text-gray-600 hover:text-gray-900'
+      }`}
+    >
+      <Icon className="w-4 h-4" />
+      {label}
+    </button>
+  );
+
   return (
     <div className="h-full flex flex-col">
-      {/* Filters */}
-      <SessionFilters />
-
-      {/* Kanban Board */}
-      <div className="flex-1 overflow-hidden">
-        <KanbanBoard onSelectSession={handleSelectSession} />
+      {/* View Tabs */}
+      <div className="bg-white border-b border-gray-200 px-4 flex gap-2">
+        {viewTab('board', 'My sessions', LayoutGrid)}
+        {viewTab('team', 'Team feed', Users)}
       </div>
 
+      {view === 'team' ? (
+        <div className="flex-1 overflow-hidden">
+          <TeamFeed workspacePath={workspacePath} />
+        </div>
+      ) : (
+        <>
+          {/* Filters */}
+          <SessionFilters />
+
+          {/* Kanban Board */}
+          <div className="flex-1 overflow-hidden">
+            <KanbanBoard onSelectSession={handleSelectSession} />
+          </div>
+        </>
+      )}
+
       {/* Detail View Modal */}
       {selectedSessionId && (
         <SessionDetailView sessionId={selectedSessionId} onClose={handleCloseDetail} />
@@ -0,0 +1,233 @@
+/**
+ * Team Feed Component
+ *
+ * Recent sessions teammates synced to the shared session store for the same
+ * repository, with their winner and stats. Reports and diffs attached to a
+ * session open read-only. Filtering by author and branch happens locally.
+ */
+import React, { useCallback, useEffect, useMemo, useState } from 'react';
+import { FileText, GitBranch, RefreshCw, Trophy, User, X } from 'lucide-react';
+import { tauriApi } from '@/services/tauri';
+import type { FeedArtifact, FeedEntry } from '@/services/tauri';
+import type { SessionArtifact } from '@/types/worktree';
+
+interface TeamFeedProps {
+  workspacePath: string;
+}
+
+const ALL = '';
+
+/**
+ * Format date to relative time or absolute
+ */
+const formatDate = (dateStr: string): string => {
+  const date = new Date(dateStr);
+  const diffMins = Math.floor((Date.now() - date.getTime()) / 60000);
+
+  if (diffMins < 1) return 'Just now';
+  if (diffMins < 60) return `${diffMins}m ago`;
+  if (diffMins < 1440) return `${Math.floor(diffMins / 60)}h ago`;
+  if (diffMins < 10080) return `${Math.floor(diffMins / 1440)}d ago`;
+
+  return date.toLocaleDateString();
+};
+
+/**
+ * Color of a line of a unified diff
+ */
+const diffLineClass = (line: string): string => {
+  if (line.startsWith('+++') || line.startsWith('---')) return 'text-gray-500';
+  if (line.startsWith('+')) return 'text-green-700 bg-green-50';
+  if (line.startsWith('-')) return 'text-red-700 bg-red-50';
+  if (line.startsWith('@@')) return 'text-blue-600';
+  return 'text-gray-800';
+};
+
+const uniqueSorted = (values: (string | null)[]): string[] =>
+  [...new Set(values.filter((v): v is string => !!v))].sort((a, b) => a.localeCompare(b));
+
+export const TeamFeed: React.FC<TeamFeedProps> = ({ workspacePath }) => {
+  const [entries, setEntries] = useState<FeedEntry[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [isLoading, setIsLoading] = useState(false);
+  const [author, setAuthor] = useState(ALL);
+  const [branch, setBranch] = useState(ALL);
+  const [artifact, setArtifact] = useState<SessionArtifact | null>(null);
+
+  const load = useCallback(async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      setEntries(await tauriApi.getTeamFeed(workspacePath));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsLoading(false);
+    }
+  }, [workspacePath]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const authors = useMemo(() => uniqueSorted(entries.map((e) => e.author)), [entries]);
+  const branches = useMemo(() => uniqueSorted(entries.map((e) => e.branch)), [entries]);
+  const visible = useMemo(
+    () =>
+      entries.filter(
+        (e) => (author === ALL || e.author === author) && (branch === ALL || e.branch === branch),
+      ),
+    [entries, author, branch],
+  );
+
+  const openArtifact = async (entry: FeedEntry, item: FeedArtifact) => {
+    try {
+      setArtifact(await tauriApi.getTeamArtifact(workspacePath, entry.sessionId, item.id));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  return (
+    <div className="h-full flex flex-col">
+      {/* Filters */}
+      <div className="bg-white border-b border-gray-200 p-4 flex items-center gap-3">
+        <User className="w-4 h-4 text-gray-500" />
+        <select
+          value={author}
+          onChange={(e) => setAuthor(e.target.value)}
+          className="border border-gray-300 rounded-lg px-2 py-1 text-sm"
+        >
+          <option value={ALL}>All authors</option>
+          {authors.map((a) => (
+            <option key={a} value={a}>
+              {a}
+            </option>
+          ))}
+        </select>
+        <GitBranch className="w-4 h-4 text-gray-500" />
+        <select
+          value={branch}
+          onChange={(e) => setBranch(e.target.value)}
+          className="border border-gray-300 rounded-lg px-2 py-1 text-sm"
+        >
+          <option value={ALL}>All branches</option>
+          {branches.map((b) => (
+            <option key={b} value={b}>
+              {b}
+            </option>
+          ))}
+        </select>
+        <span className="text-xs text-gray-500">
+          {visible.length} of {entries.length} sessions
+        </span>
+        <button
+          onClick={load}
+          disabled={isLoading}
+          className="ml-auto p-1.5 rounded hover:bg-gray-100 disabled:opacity-50"
+          title="Refresh"
+        >
+          <RefreshCw className={`w-4 h-4 text-gray-600 ${isLoading ? 'animate-spin' : ''}`} />
+        </button>
+      </div>
+
+      {error && (
+        <div className="mx-4 mt-4 p-3 rounded-lg bg-red-50 border border-red-200 text-sm text-red-700">
+          {error}
+        </div>
+      )}
+
+      {/* Feed */}
+      <div className="flex-1 overflow-y-auto p-4 space-y-3">
+        {!isLoading && !error && visible.length === 0 && (
+          <p className="text-sm text-gray-500 text-center py-8">
+            No sessions from the team yet. Sessions appear here once synced to the shared store.
+          </p>
+        )}
+        {visible.map((entry) => (
+          <div
+            key={`${entry.author}-${entry.sessionId}`}
+            className="bg-white rounded-lg border shadow-sm p-4"
+          >
+            <div className="flex items-start justify-between gap-3 mb-2">
+              <h3 className="text-sm font-semibold text-gray-900 line-clamp-2">{entry.task}</h3>
+              <span className="shrink-0 text-xs text-gray-500">{formatDate(entry.updatedAt)}</span>
+            </div>
+
+            <div className="flex flex-wrap items-center gap-x-4 gap-y-1 text-xs text-gray-600">
+              <span className="inline-flex items-center gap-1">
+                <User className="w-3.5 h-3.5" />
+                {entry.author ?? 'Unknown'}
+              </span>
+              {entry.branch && (
+                <span className="inline-flex items-center gap-1">
+                  <GitBranch className="w-3.5 h-3.5" />
+                  {entry.branch}
+                </span>
+              )}
+              <span className="capitalize">
+                {entry.type} · {entry.status}
+              </span>
+              <span>{entry.instances} instances</span>
+              {entry.winner && (
+                <span className="inline-flex items-center gap-1 text-purple-700">
+                  <Trophy className="w-3.5 h-3.5" />
+                  {entry.winner.agentName}
+                  {entry.winner.model && ` (${entry.winner.model})`}
+                </span>
+              )}
+              {entry.totalFilesChanged != null && (
+                <span>
+                  {entry.totalFilesChanged} files
+                  <span className="text-green-600 ml-1">+{entry.totalLinesAdded ?? 0}</span>
+                  <span className="text-red-600 ml-1">-{entry.totalLinesDeleted ?? 0}</span>
+                </span>
+              )}
+            </div>
+
+            {entry.artifacts.length > 0 && (
+              <div className="mt-3 pt-3 border-t border-gray-100 flex flex-wrap gap-2">
+                {entry.artifacts.map((item) => (
+                  <button
+                    key={item.id}
+                    onClick={() => openArtifact(entry, item)}
+                    className="inline-flex items-center gap-1 px-2 py-1 rounded border border-gray-200 text-xs text-gray-700 hover:bg-gray-50"
+                  >
+                    <FileText className="w-3.5 h-3.5" />
+                    {item.title}
+                  </button>
+                ))}
+              </div>
+            )}
+          </div>
+        ))}
+      </div>
+
+      {/* Read-only artifact viewer */}
+      {artifact && (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/40 p-8">
+          <div className="bg-white rounded-lg shadow-xl w-full max-w-4xl max-h-full flex flex-col">
+            <div className="flex items-center justify-between px-4 py-3 border-b border-gray-200">
+              <h3 className="text-sm font-semibold text-gray-900">
+                {artifact.title}
+                <span className="ml-2 text-xs font-normal text-gray-500">read-only</span>
+              </h3>
+              <button onClick={() => setArtifact(null)} className="p-1 rounded hover:bg-gray-100">
+                <X className="w-4 h-4 text-gray-600" />
+              </button>
+            </div>
+            <pre className="flex-1 overflow-auto p-4 text-xs font-mono whitespace-pre">
+              {artifact.kind === 'diff'
+                ? artifact.content.split('\n').map((line, i) => (
+                    <div key={i} className={diffLineClass(line)}>
+                      {line || ' '}
+                    </div>
+                  ))
+                : artifact.content}
+            </pre>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+};
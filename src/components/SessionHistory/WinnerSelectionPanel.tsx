@@ -6,8 +6,9 @@
 import React, { useState, useEffect } from 'react';
 import type { WorktreeSession, EvaluationScore, CompetitionEvaluation } from '@/types/worktree';
 import { evaluateCompetition, formatScore, getRankDisplay } from '@/utils/competitionEvaluator';
-import { Trophy, Award, CheckCircle, Info } from 'lucide-react';
+import { Trophy, Award, CheckCircle, Info, GitMerge } from 'lucide-react';
 import { useSessionHistoryStore } from '@/store/sessionHistoryStore';
+import { tauriApi } from '@/services/tauri';
 
 interface WinnerSelectionPanelProps {
   session: WorktreeSession;
@@ -17,7 +18,8 @@ export const WinnerSelectionPanel: React.FC<WinnerSelectionPanelProps> = ({ sess
   const [evaluation, setEvaluation] = useState<CompetitionEvaluation | null>(null);
   const [selectedWinnerId, setSelectedWinnerId] = useState<number | null>(null);
   const [isConfirming, setIsConfirming] = useState(false);
-  const { updateSession } = useSessionHistoryStore();
+  const [isMerging, setIsMerging] = useState(false);
+  const { updateSession, workspacePath } = useSessionHistoryStore();
 
   // Evaluate on mount or when instances change
   useEffect(() => {
@@ -63,6 +65,25 @@ export const WinnerSelectionPanel: React.FC<WinnerSelectionPanelProps> = ({ sess
     }
   };
 
+  const handleMergeWinner = async () => {
+    setIsMerging(true);
+    try {
+      const result = await tauriApi.mergeCompetitionWinner(workspacePath, session.id);
+      if (result.status.state === 'paused') {
+        alert(
+          `⚠️ Merge of ${result.branch} stopped on conflicts: ${result.status.conflicts.join(', ')}`,
+        );
+      } else {
+        alert(`✅ Merged ${result.branch} into the workspace`);
+      }
+    } catch (error) {
+      console.error('Failed to merge winner:', error);
+      alert(`Failed to merge winner: ${error}`);
+    } finally {
+      setIsMerging(false);
+    }
+  };
+
   if (!evaluation) {
     return (
       <div className="p-6 text-center text-gray-500">
@@ -140,6 +161,26 @@ export const WinnerSelectionPanel: React.FC<WinnerSelectionPanelProps> = ({ sess
           </button>
         </div>
       )}
+
+      {/* Merge Button */}
+      {isWinnerConfirmed && (
+        <div className="flex justify-end gap-3 pt-4 border-t">
+          <button
+            onClick={handleMergeWinner}
+            disabled={isMerging || !workspacePath}
+            className="
+              flex items-center gap-2 px-6 py-3 rounded-lg font-semibold
+              bg-green-600 text-white
+              hover:bg-green-700
+              disabled:opacity-50 disabled:cursor-not-allowed
+              transition-colors
+            "
+          >
+            <GitMerge className="w-5 h-5" />
+            {isMerging ? 'Merging...' : `Merge Instance #${session.winnerId} into Workspace`}
+          </button>
+        </div>
+      )}
     </div>
   );
 };
@@ -10,3 +10,4 @@ export { SessionCard } from './SessionCard';
 export { SessionFilters } from './SessionFilters';
 export { SessionDetailView } from './SessionDetailView';
 export { ChatPanel } from './ChatPanel';
+export { TeamFeed } from './TeamFeed';
@@ -0,0 +1,40 @@
+/**
+ * Feature Flag Section Component
+ *
+ * Switches experimental features on or off
+ */
+import { useFeatureFlagStore } from '@/store/featureFlagStore';
+
+export function FeatureFlagSection() {
+  const { flags, error, setFlag } = useFeatureFlagStore();
+
+  if (flags.length === 0) return null;
+
+  return (
+    <section>
+      <h3 className="text-md font-semibold text-gray-200 mb-3">Experimental Features</h3>
+      <div className="space-y-3">
+        {flags.map((flag) => (
+          <div key={flag.name} className="flex items-center justify-between gap-4">
+            <label className="text-sm text-gray-300" title={flag.name}>
+              {flag.description}
+            </label>
+            <button
+              onClick={() => setFlag(flag.name, !flag.enabled)}
+              className={`w-12 h-6 shrink-0 rounded-full transition-colors ${
+                flag.enabled ? 'bg-[#007ACC]' : 'bg-gray-600'
+              }`}
+            >
+              <div
+                className={`w-5 h-5 bg-white rounded-full transition-transform ${
+                  flag.enabled ? 'translate-x-6' : 'translate-x-1'
+                }`}
+              />
+            </button>
+          </div>
+        ))}
+        {error && <p className="text-sm text-red-400">{error}</p>}
+      </div>
+    </section>
+  );
+}
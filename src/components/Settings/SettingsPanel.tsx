@@ -5,6 +5,8 @@
  */
 import { X } from 'lucide-react';
 import { useSettingsStore } from '@/store/settingsStore';
+import { UpdateSection } from './UpdateSection';
+import { FeatureFlagSection } from './FeatureFlagSection';
 
 export function SettingsPanel() {
   const {
@@ -180,6 +182,12 @@ export function SettingsPanel() {
             </div>
           </section>
 
+          {/* Update Settings */}
+          <UpdateSection />
+
+          {/* Experimental Features */}
+          <FeatureFlagSection />
+
           {/* Actions */}
           <div className="flex justify-end pt-4 border-t border-gray-700">
             <button
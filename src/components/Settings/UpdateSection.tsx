@@ -0,0 +1,134 @@
+/**
+ * Update Section Component
+ *
+ * Release channel selection and update checks. The release notes of a new
+ * version are shown before it can be installed.
+ */
+import { useEffect, useState } from 'react';
+import { tauriApi, UpdateRelease, UpdatesConfig } from '@/services/tauri';
+
+export function UpdateSection() {
+  const [config, setConfig] = useState<UpdatesConfig | null>(null);
+  const [release, setRelease] = useState<UpdateRelease | null>(null);
+  const [status, setStatus] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    tauriApi.getUpdatesConfig().then(setConfig).catch((error) => setStatus(String(error)));
+  }, []);
+
+  const saveConfig = async (next: UpdatesConfig) => {
+    try {
+      await tauriApi.setUpdatesConfig(next);
+      setConfig(next);
+      setRelease(null);
+      setStatus(null);
+    } catch (error) {
+      setStatus(String(error));
+    }
+  };
+
+  const handleCheck = async () => {
+    setBusy(true);
+    setStatus(null);
+    try {
+      const found = await tauriApi.checkForUpdates();
+      setRelease(found);
+      if (!found) setStatus('最新バージョンです');
+    } catch (error) {
+      setStatus(String(error));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleInstall = async () => {
+    if (!release) return;
+    if (!window.confirm(`${release.version} をインストールして再起動しますか？`)) return;
+    setBusy(true);
+    setStatus('ダウンロード中...');
+    try {
+      await tauriApi.installUpdate();
+    } catch (error) {
+      setStatus(String(error));
+      setBusy(false);
+    }
+  };
+
+  if (!config) return null;
+
+  return (
+    <section>
+      <h3 className="text-md font-semibold text-gray-200 mb-3">Updates</h3>
+      <div className="space-y-3">
+        {/* Channel */}
+        <div className="flex items-center justify-between">
+          <label className="text-sm text-gray-300">Channel</label>
+          <select
+            value={config.channel}
+            onChange={(e) =>
+              saveConfig({ ...config, channel: e.target.value as UpdatesConfig['channel'] })
+            }
+            className="w-24 px-2 py-1 bg-[#2D2D30] border border-gray-600 rounded text-gray-200 text-sm"
+          >
+            <option value="stable">Stable</option>
+            <option value="beta">Beta</option>
+          </select>
+        </div>
+
+        {/* Check on Startup */}
+        <div className="flex items-center justify-between">
+          <label className="text-sm text-gray-300">Check on Startup</label>
+          <button
+            onClick={() => saveConfig({ ...config, check_on_startup: !config.check_on_startup })}
+            className={`w-12 h-6 rounded-full transition-colors ${
+              config.check_on_startup ? 'bg-[#007ACC]' : 'bg-gray-600'
+            }`}
+          >
+            <div
+              className={`w-5 h-5 bg-white rounded-full transition-transform ${
+                config.check_on_startup ? 'translate-x-6' : 'translate-x-1'
+              }`}
+            />
+          </button>
+        </div>
+
+        {/* Release Notes */}
+        {release && (
+          <div className="p-3 bg-[#2D2D30] border border-gray-600 rounded">
+            <div className="text-sm font-semibold text-gray-200">
+              {release.version}
+              {release.pub_date && (
+                <span className="ml-2 font-normal text-gray-400">{release.pub_date}</span>
+              )}
+            </div>
+            <pre className="mt-2 max-h-40 overflow-y-auto whitespace-pre-wrap text-xs text-gray-300">
+              {release.notes || 'No release notes'}
+            </pre>
+          </div>
+        )}
+
+        <div className="flex items-center justify-end gap-3">
+          {status && <span className="text-sm text-gray-400">{status}</span>}
+          {release ? (
+            <button
+              onClick={handleInstall}
+              disabled={busy}
+              className="px-4 py-2 bg-[#007ACC] hover:bg-[#1C8CD9] disabled:opacity-50 text-white rounded transition-colors"
+            >
+              Install and Restart
+            </button>
+          ) : (
+            <button
+              onClick={handleCheck}
+              disabled={busy}
+              className="px-4 py-2 bg-[#3E3E42] hover:bg-[#505050] disabled:opacity-50 text-white rounded transition-colors"
+            >
+              Check for Updates
+            </button>
+          )}
+        </div>
+      </div>
+    </section>
+  );
+}
@@ -10,10 +10,12 @@
  */
 
 import React, { useEffect } from 'react';
-import { AlertCircle, AlertTriangle, GitBranch, Upload, Download } from 'lucide-react';
+import { AlertCircle, AlertTriangle, GitBranch, Upload, Download, StickyNote, ListTodo } from 'lucide-react';
 import { useEditorStore } from '@/store/editorStore';
 import { useLspStore } from '@/store/lspStore';
 import { useGitStore } from '@/store/gitStore';
+import { useAnnotationStore } from '@/store/annotationStore';
+import { useTodoStore } from '@/store/todoStore';
 import { getFileIcon } from '@/utils/monaco';
 
 /**
@@ -37,6 +39,9 @@ export const StatusBar: React.FC = () => {
     showGitPanel,
   } = useGitStore();
 
+  const { annotations, toggleAnnotationsPanel, showAnnotationsPanel } = useAnnotationStore();
+  const { todos, loaded: todosLoaded, toggleTodosPanel, showTodosPanel } = useTodoStore();
+
   // Fetch Git status on mount
   useEffect(() => {
     fetchStatus();
@@ -123,6 +128,34 @@ export const StatusBar: React.FC = () => {
             )}
           </button>
         )}
+
+        {/* Notes button */}
+        <button
+          className={`flex items-center gap-1.5 px-2.5 py-1 rounded-lg transition-all duration-200 ${
+            showAnnotationsPanel
+              ? 'bg-accent-primary/20 text-accent-primary border border-accent-primary/30'
+              : 'bg-editor-hover/30 text-text-secondary hover:bg-editor-hover/50 hover:text-text-primary border border-transparent'
+          }`}
+          onClick={toggleAnnotationsPanel}
+          title="Toggle Notes"
+        >
+          <StickyNote size={13} />
+          {annotations.length > 0 && <span className="font-semibold">{annotations.length}</span>}
+        </button>
+
+        {/* TODOs button */}
+        <button
+          className={`flex items-center gap-1.5 px-2.5 py-1 rounded-lg transition-all duration-200 ${
+            showTodosPanel
+              ? 'bg-accent-primary/20 text-accent-primary border border-accent-primary/30'
+              : 'bg-editor-hover/30 text-text-secondary hover:bg-editor-hover/50 hover:text-text-primary border border-transparent'
+          }`}
+          onClick={toggleTodosPanel}
+          title="Toggle TODOs"
+        >
+          <ListTodo size={13} />
+          {todosLoaded && todos.length > 0 && <span className="font-semibold">{todos.length}</span>}
+        </button>
       </div>
 
       {/* Right side - Editor settings with subtle badges */}
@@ -35,7 +35,11 @@ export const Terminal: React.FC<TerminalProps> = ({
   const xtermRef = useRef<XTerm | null>(null);
   const fitAddonRef = useRef<FitAddon | null>(null);
   const [currentDir, setCurrentDir] = useState<string>('');
-  const [commandHistory, setCommandHistory] = useState<string[]>([]);
+  // Commands run in the workspace, most recent first. Kept in a ref so the
+  // input handler registered on mount sees updates
+  const commandHistoryRef = useRef<string[]>([]);
+  // Whether the last command failed, coloring the prompt red
+  const lastFailedRef = useRef(false);
   const [homeDir, setHomeDir] = useState<string>('/Users');
 
   /**
@@ -103,7 +107,7 @@ export const Terminal: React.FC<TerminalProps> = ({
       try {
         // Display welcome message first
         xterm.writeln('\x1b[1;32mAIT42 Editor Terminal\x1b[0m');
-        xterm.writeln('\x1b[90mType commands and press Enter to execute\x1b[0m');
+        xterm.writeln('\x1b[90mType commands and press Enter to execute, Ctrl+R to search history\x1b[0m');
         xterm.writeln('');
 
         // Set initial directory if provided
@@ -120,8 +124,7 @@ export const Terminal: React.FC<TerminalProps> = ({
         setHomeDir(home);
 
         // Load command history
-        const history = await tauriApi.getCommandHistory();
-        setCommandHistory(history);
+        await loadCommandHistory();
 
         // Notify parent that terminal is ready
         if (onReady) {
@@ -144,9 +147,73 @@ export const Terminal: React.FC<TerminalProps> = ({
     let currentInput = '';
     let currentHistoryIndex = -1;
 
+    // Reverse-i-search (Ctrl+R): the query and the index of the command it
+    // found, -1 if none
+    let search: { query: string; index: number } | null = null;
+
+    const findInHistory = (query: string, from: number): number => {
+      const history = commandHistoryRef.current;
+      for (let i = from; i < history.length; i++) {
+        if (history[i].includes(query)) {
+          return i;
+        }
+      }
+      return -1;
+    };
+
+    const renderSearch = () => {
+      if (!search) return;
+      const match = search.index >= 0 ? commandHistoryRef.current[search.index] : '';
+      const failed = search.index < 0 && search.query ? 'failed ' : '';
+      xterm.write(`\r\x1b[K(${failed}reverse-i-search)\`${search.query}': ${match}`);
+    };
+
+    // Leave the search, taking the command found as input if `accept`
+    const endSearch = (accept: boolean) => {
+      if (!search) return;
+      if (accept && search.index >= 0) {
+        currentInput = commandHistoryRef.current[search.index];
+        currentHistoryIndex = -1;
+      }
+      search = null;
+      xterm.write('\r\x1b[K');
+      displayPrompt(xterm, currentDir);
+      xterm.write(currentInput);
+    };
+
     xterm.onData((data) => {
       const code = data.charCodeAt(0);
 
+      if (search) {
+        if (data === '\x12') {
+          // Ctrl+R again - next older match
+          const older = findInHistory(search.query, search.index + 1);
+          if (older >= 0) {
+            search.index = older;
+          }
+          renderSearch();
+          return;
+        } else if (code === 127) {
+          search.query = search.query.slice(0, -1);
+          search.index = findInHistory(search.query, 0);
+          renderSearch();
+          return;
+        } else if (data === '\x07' || data === '\x03') {
+          // Ctrl+G / Ctrl+C - cancel, keeping the typed input
+          endSearch(false);
+          return;
+        } else if (code >= 32 && code < 127) {
+          search.query += data;
+          search.index = findInHistory(search.query, Math.max(search.index, 0));
+          renderSearch();
+          return;
+        }
+
+        // Any other key takes the match; Enter runs it as well
+        endSearch(true);
+        if (code !== 13) return;
+      }
+
       // Handle special keys
       if (code === 13) {
         // Enter key - execute command
@@ -165,6 +232,10 @@ export const Terminal: React.FC<TerminalProps> = ({
         } else {
           displayPrompt(xterm, currentDir);
         }
+      } else if (data === '\x12') {
+        // Ctrl+R - search the history backwards
+        search = { query: '', index: -1 };
+        renderSearch();
       } else if (code === 127) {
         // Backspace
         if (currentInput.length > 0) {
@@ -176,6 +247,7 @@ export const Terminal: React.FC<TerminalProps> = ({
         // Handle arrow up/down for history navigation
         if (data === '\x1b[A') {
           // Arrow up - previous command
+          const commandHistory = commandHistoryRef.current;
           if (commandHistory.length > 0 && currentHistoryIndex < commandHistory.length - 1) {
             currentHistoryIndex++;
             const historicalCommand = commandHistory[currentHistoryIndex];
@@ -191,7 +263,7 @@ export const Terminal: React.FC<TerminalProps> = ({
           // Arrow down - next command
           if (currentHistoryIndex > 0) {
             currentHistoryIndex--;
-            const historicalCommand = commandHistory[currentHistoryIndex];
+            const historicalCommand = commandHistoryRef.current[currentHistoryIndex];
 
             // Clear current line
             xterm.write('\r\x1b[K');
@@ -230,6 +302,14 @@ export const Terminal: React.FC<TerminalProps> = ({
     };
   }, [initialDir, onReady]);
 
+  /**
+   * Reload the command history saved for the workspace
+   */
+  const loadCommandHistory = async () => {
+    const history = await tauriApi.getCommandHistory();
+    commandHistoryRef.current = history.map((entry) => entry.command);
+  };
+
   /**
    * Display command prompt with full path information
    */
@@ -239,8 +319,22 @@ export const Terminal: React.FC<TerminalProps> = ({
       ? `~${dir.slice(home.length)}`
       : dir;
 
-    // Show full path with color coding
-    xterm.write(`\x1b[1;34m${displayPath}\x1b[0m \x1b[1;32m❯\x1b[0m `);
+    // Show full path with color coding, the prompt red after a failure
+    const promptColor = lastFailedRef.current ? '31' : '32';
+    xterm.write(`\x1b[1;34m${displayPath}\x1b[0m \x1b[1;${promptColor}m❯\x1b[0m `);
+  };
+
+  /**
+   * Color the status line shown after a command, e.g. "✗ 1 in 2.3s"
+   */
+  const formatOutputLine = (line: string): string => {
+    if (/^✓ \S+ in [\d.]+s$/.test(line)) {
+      return `\x1b[32m${line}\x1b[0m`;
+    }
+    if (/^✗ \S+ in [\d.]+s$/.test(line)) {
+      return `\x1b[31m${line}\x1b[0m`;
+    }
+    return line;
   };
 
   /**
@@ -258,7 +352,7 @@ export const Terminal: React.FC<TerminalProps> = ({
       if (output) {
         const lines = output.split('\n');
         for (const line of lines) {
-          xterm.writeln(line);
+          xterm.writeln(formatOutputLine(line));
         }
       }
 
@@ -275,9 +369,12 @@ export const Terminal: React.FC<TerminalProps> = ({
 
       setCurrentDir(newDir);
 
+      // Color the next prompt by how the command ended
+      const info = await tauriApi.getTerminalInfo();
+      lastFailedRef.current = info.lastFailed;
+
       // Update command history
-      const history = await tauriApi.getCommandHistory();
-      setCommandHistory(history);
+      await loadCommandHistory();
     } catch (error) {
       console.error('Command execution error:', error);
       xterm.writeln(`\x1b[1;31mError: ${error}\x1b[0m`);
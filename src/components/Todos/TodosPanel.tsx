@@ -0,0 +1,147 @@
+/**
+ * TODOs Panel Component
+ *
+ * Lists TODO/FIXME/HACK comments across the workspace, grouped by file or
+ * by tag. Clicking an entry opens its file at the comment.
+ */
+
+import React, { useEffect, useMemo, useState } from 'react';
+import { ListTodo, RefreshCw, X } from 'lucide-react';
+import { useTodoStore } from '@/store/todoStore';
+import { useEditorStore } from '@/store/editorStore';
+import type { TodoItem } from '@/services/tauri';
+
+type GroupBy = 'file' | 'tag';
+
+const TAG_COLORS: Record<string, string> = {
+  TODO: 'text-[#569CD6]',
+  FIXME: 'text-[#F48771]',
+  HACK: 'text-[#D7BA7D]',
+};
+
+const tagColor = (tag: string) => TAG_COLORS[tag] ?? 'text-[#C586C0]';
+
+export const TodosPanel: React.FC = () => {
+  const {
+    todos,
+    error,
+    isLoading,
+    showTodosPanel,
+    fetchTodos,
+    hideTodosPanel,
+    reveal,
+    absolutePath,
+  } = useTodoStore();
+  const { addTab } = useEditorStore();
+  const [groupBy, setGroupBy] = useState<GroupBy>('file');
+
+  useEffect(() => {
+    if (showTodosPanel) {
+      fetchTodos();
+    }
+  }, [showTodosPanel, fetchTodos]);
+
+  const groups = useMemo(() => {
+    const byKey = new Map<string, TodoItem[]>();
+    for (const todo of todos) {
+      const key = groupBy === 'file' ? todo.path : todo.tag;
+      byKey.set(key, [...(byKey.get(key) ?? []), todo]);
+    }
+    return [...byKey.entries()].sort(([a], [b]) => a.localeCompare(b));
+  }, [todos, groupBy]);
+
+  const handleOpen = async (todo: TodoItem) => {
+    try {
+      await addTab(absolutePath(todo));
+      reveal(todo);
+    } catch (err) {
+      console.error('Failed to open file:', err);
+    }
+  };
+
+  if (!showTodosPanel) {
+    return null;
+  }
+
+  return (
+    <div className="h-full flex flex-col bg-[#1E1E1E] text-[#CCCCCC] border-t border-[#2A2D2E]">
+      {/* Header */}
+      <div className="flex items-center justify-between border-b border-[#2A2D2E]">
+        <h3 className="text-[13px] font-semibold px-3 py-2">
+          TODOS
+          <span className="ml-2 text-[11px] text-[#858585] font-normal">
+            {todos.length} {todos.length === 1 ? 'item' : 'items'}
+          </span>
+        </h3>
+        <div className="flex items-center gap-1 mx-1">
+          {(['file', 'tag'] as const).map((mode) => (
+            <button
+              key={mode}
+              className={`px-2 py-1 text-[11px] rounded transition-colors ${
+                groupBy === mode ? 'bg-[#3E3E42] text-white' : 'hover:bg-[#2A2D2E]'
+              }`}
+              onClick={() => setGroupBy(mode)}
+              title={`Group by ${mode}`}
+            >
+              {mode === 'file' ? 'File' : 'Tag'}
+            </button>
+          ))}
+          <button
+            className="p-2 hover:bg-[#3E3E42] rounded transition-colors"
+            onClick={() => fetchTodos(true)}
+            disabled={isLoading}
+            title="Rescan workspace"
+          >
+            <RefreshCw size={14} className={isLoading ? 'animate-spin' : ''} />
+          </button>
+          <button
+            className="p-2 hover:bg-[#3E3E42] rounded transition-colors"
+            onClick={hideTodosPanel}
+            title="Close TODOs panel"
+          >
+            <X size={16} />
+          </button>
+        </div>
+      </div>
+
+      {error && <div className="px-3 py-2 text-[12px] text-[#F48771]">{error}</div>}
+
+      {/* TODO list */}
+      <div className="flex-1 overflow-y-auto">
+        {groups.length === 0 ? (
+          <div className="flex items-center justify-center h-full text-[#858585] text-[13px]">
+            {isLoading ? 'Scanning workspace...' : 'No TODO comments found.'}
+          </div>
+        ) : (
+          groups.map(([key, group]) => (
+            <div key={key}>
+              <div className="px-3 py-1 text-[12px] font-semibold text-[#CCCCCC] bg-[#252526]">
+                {key}
+                <span className="ml-2 text-[11px] text-[#858585] font-normal">{group.length}</span>
+              </div>
+              {group.map((todo) => (
+                <div
+                  key={`${todo.path}:${todo.line}`}
+                  className="flex items-start gap-2 px-3 py-1.5 cursor-pointer hover:bg-[#2A2D2E] transition-colors"
+                  onClick={() => handleOpen(todo)}
+                >
+                  <ListTodo size={14} className={`${tagColor(todo.tag)} mt-0.5 flex-shrink-0`} />
+                  <div className="flex-1 min-w-0 text-[13px] truncate">
+                    {groupBy === 'file' && (
+                      <span className={`${tagColor(todo.tag)} font-semibold mr-2`}>{todo.tag}</span>
+                    )}
+                    {todo.author && <span className="text-[#858585] mr-2">({todo.author})</span>}
+                    {todo.text}
+                  </div>
+                  <div className="text-[11px] text-[#858585] flex-shrink-0">
+                    {groupBy === 'tag' ? `${todo.path}:${todo.line}` : `Ln ${todo.line}`}
+                  </div>
+                </div>
+              ))}
+            </div>
+          ))
+        )}
+      </div>
+    </div>
+  );
+};
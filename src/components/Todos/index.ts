@@ -0,0 +1,5 @@
+/**
+ * TODO Components
+ */
+
+export { TodosPanel } from './TodosPanel';
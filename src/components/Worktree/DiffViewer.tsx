@@ -1,11 +1,38 @@
 /**
  * Diff Viewer Component
  *
- * Display git diff with syntax highlighting
+ * Display git diff with syntax highlighting, either line by line or grouped
+ * by function/class with moves and renames detected (semantic view)
  */
 import React, { useState, useEffect } from 'react';
-import { FileText, Plus, Minus, AlertCircle, Loader2, ChevronRight, ChevronDown } from 'lucide-react';
-import { worktreeApi, type FileDiff, type DiffLine } from '@/services/worktree';
+import {
+  FileText,
+  Plus,
+  Minus,
+  AlertCircle,
+  Loader2,
+  ChevronRight,
+  ChevronDown,
+  AlignLeft,
+  Braces,
+} from 'lucide-react';
+import {
+  worktreeApi,
+  type FileDiff,
+  type DiffLine,
+  type SemanticChange,
+  type SemanticFileDiff,
+} from '@/services/worktree';
+
+type DiffMode = 'textual' | 'semantic';
+
+const CHANGE_STYLES: Record<SemanticChange['kind'], string> = {
+  added: 'bg-green-500/20 text-green-300',
+  removed: 'bg-red-500/20 text-red-300',
+  modified: 'bg-yellow-500/20 text-yellow-300',
+  moved: 'bg-blue-500/20 text-blue-300',
+  renamed: 'bg-purple-500/20 text-purple-300',
+};
 
 interface DiffViewerProps {
   worktreePath: string;
@@ -17,14 +44,36 @@ export const DiffViewer: React.FC<DiffViewerProps> = ({ worktreePath, filePath }
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [expandedFiles, setExpandedFiles] = useState<Set<string>>(new Set());
+  const [mode, setMode] = useState<DiffMode>('textual');
+  // Semantic diffs by file path, or the error message if unavailable
+  const [semanticDiffs, setSemanticDiffs] = useState<Record<string, SemanticFileDiff | string>>({});
 
   useEffect(() => {
     loadDiff();
   }, [worktreePath, filePath]);
 
+  // Load semantic diffs lazily, for expanded files only
+  useEffect(() => {
+    if (mode !== 'semantic') return;
+
+    expandedFiles.forEach((path) => {
+      if (path in semanticDiffs) return;
+      worktreeApi
+        .getSemanticDiff(worktreePath, path)
+        .then((diff) => setSemanticDiffs((prev) => ({ ...prev, [path]: diff })))
+        .catch((err) =>
+          setSemanticDiffs((prev) => ({
+            ...prev,
+            [path]: err instanceof Error ? err.message : 'Semantic diff unavailable',
+          }))
+        );
+    });
+  }, [mode, expandedFiles, worktreePath]);
+
   const loadDiff = async () => {
     setIsLoading(true);
     setError(null);
+    setSemanticDiffs({});
 
     try {
       if (filePath) {
@@ -82,6 +131,132 @@ export const DiffViewer: React.FC<DiffViewerProps> = ({ worktreePath, filePath }
     }
   };
 
+  const renderLines = (lines: DiffLine[]) => (
+    <div className="font-mono text-xs">
+      {lines.map((line, lineIdx) => (
+        <div
+          key={lineIdx}
+          className={`flex items-start ${getLineColor(line.line_type)} transition-colors`}
+        >
+          {/* Line Numbers */}
+          <div className="flex-shrink-0 flex items-center space-x-1 px-2 py-1 text-gray-500 select-none">
+            <span className="w-10 text-right">
+              {line.old_line_num || ''}
+            </span>
+            <span className="w-10 text-right">
+              {line.new_line_num || ''}
+            </span>
+          </div>
+
+          {/* Icon */}
+          <div className="flex-shrink-0 w-6 flex items-center justify-center py-1">
+            {getLineIcon(line.line_type)}
+          </div>
+
+          {/* Content */}
+          <div className="flex-1 py-1 pr-4 overflow-x-auto whitespace-pre text-gray-200">
+            {line.content}
+          </div>
+        </div>
+      ))}
+    </div>
+  );
+
+  const renderHunks = (fileDiff: FileDiff) =>
+    fileDiff.hunks.map((hunk, hunkIdx) => (
+      <div key={hunkIdx} className="border-t border-gray-700">
+        {/* Hunk Header */}
+        <div className="px-4 py-2 bg-gray-850 text-xs font-mono text-gray-400">
+          @@ -{hunk.old_start},{hunk.old_lines} +{hunk.new_start},{hunk.new_lines} @@
+        </div>
+
+        {/* Hunk Lines */}
+        {renderLines(hunk.lines)}
+      </div>
+    ));
+
+  const describeChange = (change: SemanticChange) => {
+    switch (change.kind) {
+      case 'moved':
+        return change.old_name
+          ? `moved from ${change.old_name}, unchanged`
+          : `moved from line ${change.old_start}, unchanged`;
+      case 'renamed':
+        return `renamed from ${change.old_name}, body unchanged`;
+      default:
+        return null;
+    }
+  };
+
+  const renderSemantic = (fileDiff: FileDiff) => {
+    const semantic = semanticDiffs[fileDiff.file_path];
+
+    if (semantic === undefined) {
+      return (
+        <div className="flex items-center px-4 py-3 text-xs text-gray-400 border-t border-gray-700">
+          <Loader2 className="w-3 h-3 mr-2 animate-spin" />
+          Loading semantic diff...
+        </div>
+      );
+    }
+
+    // No grammar for this file: fall back to the textual diff
+    if (typeof semantic === 'string') {
+      return (
+        <>
+          <div className="px-4 py-2 text-xs text-gray-500 border-t border-gray-700">
+            {semantic}. Showing textual diff.
+          </div>
+          {renderHunks(fileDiff)}
+        </>
+      );
+    }
+
+    if (semantic.changes.length === 0 && semantic.other.length === 0) {
+      return (
+        <div className="px-4 py-3 text-xs text-gray-400 border-t border-gray-700">
+          No semantic changes (whitespace or formatting only)
+        </div>
+      );
+    }
+
+    return (
+      <>
+        {semantic.changes.map((change, changeIdx) => {
+          const description = describeChange(change);
+          return (
+            <div key={changeIdx} className="border-t border-gray-700">
+              {/* Item Header */}
+              <div className="flex items-center space-x-2 px-4 py-2 bg-gray-850 text-xs">
+                <span className={`px-1.5 py-0.5 rounded font-semibold ${CHANGE_STYLES[change.kind]}`}>
+                  {change.kind}
+                </span>
+                <span className="font-mono text-gray-200">{change.name}</span>
+                <span className="text-gray-500">{change.item_kind}</span>
+                {description && <span className="text-gray-400 italic">{description}</span>}
+                <span className="flex-1" />
+                <span className="font-mono text-gray-500">
+                  L{change.new_start ?? change.old_start}
+                </span>
+              </div>
+
+              {change.lines.length > 0 && renderLines(change.lines)}
+            </div>
+          );
+        })}
+
+        {semantic.other.length > 0 && (
+          <div className="border-t border-gray-700">
+            <div className="px-4 py-2 bg-gray-850 text-xs text-gray-400">
+              Outside functions and types
+            </div>
+            {renderLines(semantic.other)}
+          </div>
+        )}
+      </>
+    );
+  };
+
   // Loading state
   if (isLoading) {
     return (
@@ -121,10 +296,34 @@ export const DiffViewer: React.FC<DiffViewerProps> = ({ worktreePath, filePath }
   return (
     <div className="h-full flex flex-col bg-gray-900">
       {/* Header */}
-      <div className="flex-shrink-0 px-4 py-3 border-b border-gray-700 bg-gray-800">
+      <div className="flex-shrink-0 flex items-center justify-between px-4 py-3 border-b border-gray-700 bg-gray-800">
         <h3 className="text-sm font-semibold text-gray-300">
           Git Diff ({diffs.length} {diffs.length === 1 ? 'file' : 'files'})
         </h3>
+
+        {/* View Toggle */}
+        <div className="flex items-center rounded border border-gray-600 text-xs overflow-hidden">
+          <button
+            onClick={() => setMode('textual')}
+            className={`flex items-center px-2 py-1 ${
+              mode === 'textual' ? 'bg-blue-500 text-white' : 'text-gray-400 hover:bg-gray-700'
+            }`}
+            title="Line-by-line diff"
+          >
+            <AlignLeft className="w-3 h-3 mr-1" />
+            Textual
+          </button>
+          <button
+            onClick={() => setMode('semantic')}
+            className={`flex items-center px-2 py-1 ${
+              mode === 'semantic' ? 'bg-blue-500 text-white' : 'text-gray-400 hover:bg-gray-700'
+            }`}
+            title="Changes grouped by function and class, with moves and renames detected"
+          >
+            <Braces className="w-3 h-3 mr-1" />
+            Semantic
+          </button>
+        </div>
       </div>
 
       {/* Diff Content */}
@@ -162,45 +361,8 @@ export const DiffViewer: React.FC<DiffViewerProps> = ({ worktreePath, filePath }
                 </div>
               </div>
 
-              {/* Hunks */}
-              {isExpanded && fileDiff.hunks.map((hunk, hunkIdx) => (
-                <div key={hunkIdx} className="border-t border-gray-700">
-                  {/* Hunk Header */}
-                  <div className="px-4 py-2 bg-gray-850 text-xs font-mono text-gray-400">
-                    @@ -{hunk.old_start},{hunk.old_lines} +{hunk.new_start},{hunk.new_lines} @@
-                  </div>
-
-                  {/* Hunk Lines */}
-                  <div className="font-mono text-xs">
-                    {hunk.lines.map((line, lineIdx) => (
-                      <div
-                        key={lineIdx}
-                        className={`flex items-start ${getLineColor(line.line_type)} transition-colors`}
-                      >
-                        {/* Line Numbers */}
-                        <div className="flex-shrink-0 flex items-center space-x-1 px-2 py-1 text-gray-500 select-none">
-                          <span className="w-10 text-right">
-                            {line.old_line_num || ''}
-                          </span>
-                          <span className="w-10 text-right">
-                            {line.new_line_num || ''}
-                          </span>
-                        </div>
-
-                        {/* Icon */}
-                        <div className="flex-shrink-0 w-6 flex items-center justify-center py-1">
-                          {getLineIcon(line.line_type)}
-                        </div>
-
-                        {/* Content */}
-                        <div className="flex-1 py-1 pr-4 overflow-x-auto whitespace-pre text-gray-200">
-                          {line.content}
-                        </div>
-                      </div>
-                    ))}
-                  </div>
-                </div>
-              ))}
+              {/* Hunks or Items */}
+              {isExpanded && (mode === 'semantic' ? renderSemantic(fileDiff) : renderHunks(fileDiff))}
             </div>
           );
         })}
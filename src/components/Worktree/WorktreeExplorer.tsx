@@ -206,9 +206,8 @@ export const WorktreeExplorer: React.FC<WorktreeExplorerProps> = ({ competitionI
   const handleDelete = async (id: string, e: React.MouseEvent) => {
     e.stopPropagation();
 
-    if (confirm('Are you sure you want to delete this worktree?')) {
-      await deleteWorktree(id);
-    }
+    // The backend asks for confirmation in a native dialog
+    await deleteWorktree(id);
   };
 
   // Quick action handlers
@@ -0,0 +1,107 @@
+/**
+ * useAgentOutput Hook - Live output of an agent execution
+ *
+ * Follows the `agent-output` events of one execution. Each chunk is
+ * acknowledged once handled, so the backend never runs far ahead of the UI.
+ * Missed chunks (after a reload, or a gap in the sequence numbers) are
+ * fetched again from the last chunk seen.
+ *
+ * @module hooks/useAgentOutput
+ */
+
+import { useEffect, useRef, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { tauriApi, type AgentOutputChunk } from '@/services/tauri';
+
+// ============================================================================
+// Hook Interface
+// ============================================================================
+
+interface UseAgentOutputReturn {
+  /** Output received so far */
+  output: string;
+
+  /** Status of the execution */
+  status: AgentOutputChunk['status'] | null;
+
+  /** Whether the beginning of the output is no longer kept by the backend */
+  truncated: boolean;
+
+  /** Error subscribing to the output */
+  error: string | null;
+}
+
+// ============================================================================
+// Hook Implementation
+// ============================================================================
+
+/**
+ * Custom React hook streaming the output of `executionId`
+ *
+ * @param executionId - Execution to follow, or null for none
+ */
+export function useAgentOutput(executionId: string | null): UseAgentOutputReturn {
+  const [output, setOutput] = useState('');
+  const [status, setStatus] = useState<AgentOutputChunk['status'] | null>(null);
+  const [truncated, setTruncated] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const lastSeq = useRef(0);
+
+  useEffect(() => {
+    setOutput('');
+    setStatus(null);
+    setTruncated(false);
+    setError(null);
+    lastSeq.current = 0;
+    if (!executionId) return;
+
+    let cancelled = false;
+    let resyncing = false;
+
+    const apply = (chunks: AgentOutputChunk[]) => {
+      const fresh = chunks.filter((chunk) => chunk.seq > lastSeq.current);
+      if (fresh.length === 0) return;
+      lastSeq.current = fresh[fresh.length - 1].seq;
+      setOutput((previous) => previous + fresh.map((chunk) => chunk.output).join(''));
+      setStatus(fresh[fresh.length - 1].status);
+    };
+
+    const resync = async () => {
+      resyncing = true;
+      try {
+        const replay = await tauriApi.subscribeAgentOutput(executionId, lastSeq.current);
+        if (cancelled) return;
+        if (replay.truncated) setTruncated(true);
+        setStatus(replay.status);
+        apply(replay.chunks);
+      } catch (err) {
+        if (!cancelled) setError(err instanceof Error ? err.message : String(err));
+      } finally {
+        resyncing = false;
+      }
+    };
+
+    const unlisten = listen<AgentOutputChunk>('agent-output', (event) => {
+      const chunk = event.payload;
+      if (cancelled || chunk.executionId !== executionId) return;
+      if (chunk.seq > lastSeq.current + 1) {
+        // Missed some output: fetch it, which also delivers this chunk
+        if (!resyncing) resync();
+        return;
+      }
+      apply([chunk]);
+      tauriApi.ackAgentOutput(executionId, chunk.seq).catch((err) => {
+        console.error('Failed to acknowledge agent output:', err);
+      });
+    });
+
+    resync();
+
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, [executionId]);
+
+  return { output, status, truncated, error };
+}
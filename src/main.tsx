@@ -1,10 +1,11 @@
 import React from 'react';
 import ReactDOM from 'react-dom/client';
 import App from './App';
+import { QuickTaskWindow } from './components/AI/QuickTaskWindow';
 import './index.css';
 
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
-    <App />
+    {window.location.hash === '#quick-task' ? <QuickTaskWindow /> : <App />}
   </React.StrictMode>,
 );
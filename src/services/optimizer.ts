@@ -40,7 +40,7 @@ export interface OptimizeTaskResponse {
 }
 
 /**
- * Memory-based adjustment from historical data (v1.7.0+ placeholder)
+ * Memory-based adjustment learned from past session outcomes (v1.7.0+)
  */
 export interface MemoryAdjustment {
   /** Historical success rate for similar tasks (0.0-1.0) */
@@ -111,6 +111,64 @@ export interface ComplexityInfo {
   examples: string[];
 }
 
+/**
+ * Response from get_estimate_cache_stats command
+ *
+ * Hit rates of the complexity estimate cache, this session and over all sessions.
+ */
+export interface EstimateCacheStats {
+  /** Estimation requests this session */
+  totalRequests: number;
+
+  /** Requests answered from a cache this session */
+  cacheHits: number;
+
+  /** Requests that needed an API call this session */
+  cacheMisses: number;
+
+  /** Cache hits answered by the on-disk cache */
+  diskHits: number;
+
+  /** Estimates held in memory */
+  cacheSize: number;
+
+  /** Estimates stored on disk */
+  diskSize: number;
+
+  /** Hit rate this session (0.0-1.0) */
+  hitRate: number;
+
+  /** Cache hits over all sessions */
+  lifetimeHits: number;
+
+  /** Cache misses over all sessions */
+  lifetimeMisses: number;
+
+  /** Hit rate over all sessions (0.0-1.0) */
+  lifetimeHitRate: number;
+}
+
+/**
+ * LLM backend used for complexity estimation (`ait42.llm_provider`)
+ *
+ * API keys are read from the environment: ANTHROPIC_API_KEY or OPENAI_API_KEY.
+ */
+export interface LlmProviderConfig {
+  /** Provider: 'anthropic', 'openai' or 'ollama' */
+  name: LlmProviderName;
+
+  /** Model to use, the provider's default if null */
+  model: string | null;
+
+  /** API endpoint (OpenAI-compatible server, Ollama host), the provider's default if null */
+  base_url: string | null;
+}
+
+/**
+ * Supported LLM providers
+ */
+export type LlmProviderName = 'anthropic' | 'openai' | 'ollama';
+
 /**
  * Valid complexity class values
  */
@@ -268,6 +326,53 @@ export async function getComplexityInfo(
   }
 }
 
+/**
+ * Get hit rates of the complexity estimate cache
+ *
+ * Estimates are cached on disk, so the lifetime figures cover earlier sessions.
+ *
+ * @returns Cache statistics
+ *
+ * @throws {Error} If the optimizer cannot be initialized
+ */
+export async function getEstimateCacheStats(): Promise<EstimateCacheStats> {
+  try {
+    return await invoke<EstimateCacheStats>('get_estimate_cache_stats');
+  } catch (error) {
+    throw new Error(`Failed to get estimate cache stats: ${error}`);
+  }
+}
+
+/**
+ * Get the LLM backend used for complexity estimation
+ *
+ * @returns Provider settings
+ */
+export async function getLlmProviderConfig(): Promise<LlmProviderConfig> {
+  try {
+    return await invoke<LlmProviderConfig>('get_llm_provider_config');
+  } catch (error) {
+    throw new Error(`Failed to get LLM provider config: ${error}`);
+  }
+}
+
+/**
+ * Change the LLM backend used for complexity estimation
+ *
+ * Takes effect on the next estimation.
+ *
+ * @param config - Provider settings
+ *
+ * @throws {Error} If the provider is unknown
+ */
+export async function setLlmProviderConfig(config: LlmProviderConfig): Promise<void> {
+  try {
+    await invoke('set_llm_provider_config', { config });
+  } catch (error) {
+    throw new Error(`Failed to set LLM provider config: ${error}`);
+  }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -360,6 +465,9 @@ export default {
   optimizeTask,
   calculateInstances,
   getComplexityInfo,
+  getEstimateCacheStats,
+  getLlmProviderConfig,
+  setLlmProviderConfig,
   analyzeTask,
   isValidComplexityClass,
   getAllComplexityClasses,
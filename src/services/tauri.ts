@@ -2,7 +2,7 @@
  * Tauri command bindings for communication with Rust backend
  */
 import { invoke } from '@tauri-apps/api/tauri';
-import { AgentRuntime } from '@/types/worktree';
+import { AgentRuntime, IssueLink, IssueProvider, WorktreeSession } from '@/types/worktree';
 
 /**
  * Response from open_file command
@@ -32,6 +32,30 @@ export interface TerminalInfo {
   outputLines: number;
   historySize: number;
   timeoutSeconds: number;
+  lastExitCode: number | null; // null if killed or nothing ran yet
+  lastDurationMs: number | null;
+  lastFailed: boolean;
+  lastStatus: string | null; // e.g. "✗ 1 in 2.3s"
+}
+
+/**
+ * Command in the terminal history
+ */
+export interface CommandHistoryEntry {
+  command: string;
+  ranAt: string;
+  exitCode: number | null; // null if the command was killed
+  durationMs: number | null;
+}
+
+/**
+ * Which commands getCommandHistory returns
+ */
+export interface CommandHistoryFilter {
+  query?: string;
+  failedOnly?: boolean;
+  since?: string; // RFC 3339
+  limit?: number;
 }
 
 /**
@@ -78,6 +102,45 @@ export interface LspLocation {
   endCharacter: number;
 }
 
+/**
+ * Edit for a buffer, applied with `replace_text`
+ */
+export interface RefactorEdit {
+  range: { start: number; end: number }; // byte offsets
+  replacement: string;
+  cursor: number;
+}
+
+/**
+ * LLM-proposed change for review
+ */
+export interface RefactorProposal {
+  diff: string;
+  proposed: string;
+  edit: RefactorEdit;
+  hunks: RefactorEdit[]; // back to front
+}
+
+export type QuickActionKind = 'fix' | 'suppress' | 'explain';
+
+/**
+ * Entry of a diagnostic's quick-action menu
+ */
+export interface DiagnosticQuickAction {
+  title: string;
+  kind: QuickActionKind;
+  isPreferred: boolean;
+  edits: RefactorEdit[]; // back to front, empty for 'explain'
+}
+
+/**
+ * LLM explanation of a diagnostic
+ */
+export interface DiagnosticExplanation {
+  explanation: string;
+  proposal?: RefactorProposal;
+}
+
 /**
  * Git file status
  */
@@ -107,6 +170,100 @@ export interface GitCommit {
   timestamp: number;
 }
 
+/**
+ * Note attached to a line range of a workspace file
+ */
+export interface Annotation {
+  id: string;
+  path: string; // relative to the workspace root
+  startLine: number; // 1-based, inclusive
+  endLine: number;
+  text: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+/**
+ * TODO/FIXME/HACK style comment found in a workspace file
+ */
+export interface TodoItem {
+  path: string; // relative to the workspace root
+  line: number; // 1-based
+  column: number; // 1-based column of the tag
+  tag: string;
+  author: string | null; // name in parentheses, as in TODO(ren)
+  text: string;
+}
+
+/**
+ * Who pushed an entry to the clipboard history
+ */
+export type ClipboardSource = 'editor' | 'agent' | 'plugin';
+
+/**
+ * Copied text kept in the clipboard history
+ */
+export interface ClipboardEntry {
+  id: string;
+  text: string;
+  source: ClipboardSource;
+  copiedAt: string;
+}
+
+/**
+ * Conflicting region of a file, split into its three sides
+ */
+export interface ConflictHunk {
+  ours_label: string;
+  ours: string;
+  base: string | null; // null unless the markers include the merge base
+  theirs_label: string;
+  theirs: string;
+}
+
+/**
+ * Part of a conflicted file
+ */
+export type ConflictSegment =
+  | { kind: 'text'; text: string }
+  | ({ kind: 'conflict' } & ConflictHunk);
+
+/**
+ * Conflicted file split into agreed text and conflict hunks
+ */
+export interface ConflictFile {
+  path: string;
+  segments: ConflictSegment[];
+}
+
+/**
+ * How a conflict hunk is resolved
+ */
+export type ConflictResolution =
+  | { kind: 'ours' }
+  | { kind: 'theirs' }
+  | { kind: 'both' }
+  | { kind: 'custom'; text: string };
+
+/**
+ * Conflicted file with its three versions from the index
+ */
+export interface MergeConflict {
+  path: string;
+  base: string | null; // null if the file did not exist in the merge base
+  ours: string | null; // null if our side deleted the file
+  theirs: string | null; // null if their side deleted the file
+  working: ConflictFile; // no segments if the file was deleted
+}
+
+/**
+ * How a whole conflicted file is resolved
+ */
+export type FileResolution =
+  | { kind: 'ours' }
+  | { kind: 'theirs' }
+  | { kind: 'custom'; content: string };
+
 /**
  * Plugin information
  */
@@ -134,6 +291,70 @@ export interface AgentInfo {
   description: string;
   category: string;
   tools: string[];
+  /** Version declared by the definition's frontmatter */
+  version?: string | null;
+  stats?: AgentStats | null;
+  /** 'project' for agents defined in the workspace's .ait42/agents */
+  source: 'global' | 'project';
+  /** Definition file */
+  path: string;
+  /** Global definition file a project agent of the same name replaces */
+  overrides?: string | null;
+}
+
+/**
+ * How often an agent's work was kept, partially applied, or discarded
+ */
+export interface AgentStats {
+  runs: number;
+  kept: number;
+  partial: number;
+  discarded: number;
+  successRate: number; // 0-1, partial runs count half
+}
+
+/**
+ * How an agent's definition changed since a session ran it
+ */
+export interface AgentChange {
+  name: string;
+  status: 'unchanged' | 'changed' | 'removed';
+  pinnedVersion?: string | null;
+  currentVersion?: string | null;
+  /** Unified diff from the session's definition to the current one */
+  diff: string;
+}
+
+/**
+ * Entry of a session's lifecycle journal
+ */
+export type JournalEntry = {
+  sessionId: string;
+  /** RFC 3339 timestamp */
+  recordedAt: string;
+} & (
+  | { event: 'created'; sessionType: string; task: string; status: string }
+  | {
+      event: 'instance-started';
+      instanceId: number;
+      agentName: string;
+      worktreePath: string;
+      tmuxSessionId: string;
+      /** Log the instance's output is piped to, if known */
+      log?: string | null;
+    }
+  | { event: 'output-chunk'; instanceId?: number | null; source: string; seq: number; bytes: number }
+  | { event: 'status-changed'; instanceId?: number | null; status: string }
+  | { event: 'completed'; status: string }
+);
+
+/**
+ * Agent suggested for a task description
+ */
+export interface AgentRecommendation {
+  agent: string;
+  score: number; // 0-1
+  rationale: string;
 }
 
 /**
@@ -143,6 +364,11 @@ export interface AgentExecutionRequest {
   agentName: string;
   task: string;
   context?: string;
+  /** ID to run under, so the execution can be cancelled while it runs */
+  executionId?: string;
+  /** Session to record the prompt injection scan of `context` on */
+  sessionId?: string;
+  workspacePath?: string;
 }
 
 /**
@@ -151,11 +377,49 @@ export interface AgentExecutionRequest {
 export interface AgentExecutionResponse {
   executionId: string;
   agentName: string;
-  status: 'started' | 'running' | 'completed' | 'failed';
+  status: 'started' | 'running' | 'completed' | 'failed' | 'cancelled';
   output?: string;
   error?: string;
 }
 
+/**
+ * Chunk of agent output, emitted as an `agent-output` event
+ */
+export interface AgentOutputChunk {
+  executionId: string;
+  /** Position in the execution's output, starting at 1 */
+  seq: number;
+  output: string;
+  status: 'running' | 'completed' | 'failed' | 'cancelled';
+}
+
+/**
+ * Output of an execution after a given chunk
+ */
+export interface AgentOutputReplay {
+  executionId: string;
+  agentName: string;
+  chunks: AgentOutputChunk[];
+  /** Whether some of the requested output is no longer kept */
+  truncated: boolean;
+  status: AgentOutputChunk['status'];
+}
+
+/**
+ * Alert raised by an `ait42.alerts` rule on agent output, emitted as an
+ * `agent-alert` event
+ */
+export interface AgentAlert {
+  /** Execution whose output matched, the tmux session for competition instances */
+  executionId: string;
+  instance: number | null;
+  rule: string;
+  action: 'notify' | 'badge' | 'cancel';
+  /** Line that matched */
+  line: string;
+  createdAt: string;
+}
+
 /**
  * Parallel execution request
  */
@@ -163,6 +427,8 @@ export interface ParallelExecutionRequest {
   agents: string[];
   task: string;
   context?: string;
+  /** ID to run under, so the execution can be cancelled while it runs */
+  executionId?: string;
 }
 
 /**
@@ -261,6 +527,7 @@ export interface ClaudeCodeInstanceResult {
   instanceId: string;
   instanceNumber: number;
   worktreePath: string;
+  branch: string;
   tmuxSessionId: string;
   status: string;  // "starting", "running", "completed", "failed", "timeout"
   output: string;
@@ -286,10 +553,14 @@ export interface RoleDefinition {
  */
 export interface DebateRequest {
   task: string;
-  roles: RoleDefinition[];  // 3 roles (Architect, Pragmatist, Innovator)
+  roles: RoleDefinition[];  // 2-6 roles (e.g. Architect, Pragmatist, Innovator)
   model: string;  // "sonnet", "haiku", "opus"
   timeoutSeconds: number;  // Per-round timeout (default: 800s)
   preserveWorktrees: boolean;  // Keep worktrees after completion
+  rounds?: number;  // 1-5 debate rounds (default: 3)
+  roundPrompts?: string[];  // Prompt template per round; empty uses the default
+  synthesis?: boolean;  // Finish with a synthesis round (default: true)
+  synthesisPrompt?: string;  // Template of the synthesis round
 }
 
 /**
@@ -297,12 +568,260 @@ export interface DebateRequest {
  */
 export interface DebateResult {
   debateId: string;
-  status: string;  // "started", "round_1", "round_2", "round_3", "completed", "failed"
+  status: string;  // "started", "round_N", "synthesis", "completed", "failed"
   message: string;
   worktreePath: string;
   branch: string;
 }
 
+/**
+ * Execution mode picked by the Coordinator in auto mode
+ */
+export type AutoMode = 'single' | 'parallel' | 'competition' | 'ensemble' | 'debate';
+
+/**
+ * Complexity analysis behind an auto mode decision
+ */
+export interface TaskAnalysis {
+  complexityClass: string;
+  subtasks: number;
+  instances: number;
+  confidence: number | null; // null without an LLM estimate
+  reasoning: string;
+}
+
+/**
+ * Mode chosen for a task and the steps that led to it
+ */
+export interface AutoDecision {
+  task: string;
+  mode: AutoMode;
+  instances: number;
+  agents: string[]; // single and parallel mode
+  analysis: TaskAnalysis;
+  estimatedCostUsd: number;
+  trace: string[];
+}
+
+/**
+ * Result of running a task in auto mode
+ */
+export interface AutoRunResult {
+  sessionId: string;
+  decision: AutoDecision;
+  agentResults: AgentExecutionResponse[];
+  competition: ClaudeCodeCompetitionResult | null;
+  debate: DebateResult | null;
+}
+
+/**
+ * Limit an AI operation is checked against
+ */
+export type BudgetScope = 'day' | 'session';
+
+/**
+ * AI spend limits (ait42.budget)
+ */
+export interface BudgetConfig {
+  daily_limit_usd: number | null;
+  session_limit_usd: number | null;
+  warn_at: number;
+  input_usd_per_mtok: number;
+  output_usd_per_mtok: number;
+  agent_run_tokens: number;
+}
+
+/**
+ * Estimated AI spend against the configured limits
+ */
+export interface BudgetStatus {
+  day: string;
+  spentTodayUsd: number;
+  dailyLimitUsd: number | null;
+  sessionId: string | null;
+  spentInSessionUsd: number;
+  sessionLimitUsd: number | null;
+  warnAt: number;
+}
+
+/**
+ * Payload of the `budget-warning` event
+ */
+export interface BudgetWarning {
+  scope: BudgetScope;
+  message: string;
+  status: BudgetStatus;
+}
+
+/**
+ * Where a session's event stream is written to or read from
+ */
+export type StreamTarget =
+  | { kind: 'file'; location: string }
+  | { kind: 'socket'; location: string };
+
+/**
+ * Session followed in observer mode
+ */
+export interface ObservedSession {
+  sessionId: string;
+  source: StreamTarget;
+  sessionStore: string | null;
+  startedAt: string;
+}
+
+/**
+ * Payload of the `observed-session-event` event
+ */
+export interface ObservedSessionEvent {
+  sessionId: string;
+  event: string; // e.g. competition-output, debate-status
+  payload: unknown;
+  emittedAt: string;
+}
+
+/**
+ * State of the remote-control server
+ */
+export interface RemoteStatus {
+  running: boolean;
+  address: string | null;
+  token: string | null; // while running
+  clients: number;
+}
+
+/**
+ * State of the MCP server
+ */
+export interface McpStatus {
+  running: boolean;
+  address: string | null;
+  url: string | null; // endpoint for MCP clients, e.g. http://127.0.0.1:7422/mcp
+  token: string | null; // while running
+}
+
+/**
+ * Score of a competition instance, each part from 0 to 1
+ */
+export interface CompetitionScore {
+  instanceId: number;
+  total: number;
+  tests: number | null; // null when no tests were run
+  diffSize: number | null; // 1 for the smallest diff
+  llm: number | null; // null without LLM evaluation
+  eligible: boolean; // finished and changed something
+}
+
+/**
+ * Ranking of a finished competition, also the payload of `competition-scored`
+ */
+export interface CompetitionRanking {
+  sessionId: string;
+  scores: CompetitionScore[]; // best first
+  winnerId: number | null;
+  testCommand: string | null;
+  evaluations: { instanceId: number; score: number; reason: string }[];
+}
+
+/**
+ * What one instance of a finished competition changed
+ */
+export interface InstanceDiffSummary {
+  instanceId: number;
+  filesChanged: number;
+  linesAdded: number;
+  linesDeleted: number;
+  touchedModules: string[]; // directories, or root files, the diff touched
+  summary: string | null; // null when no LLM summary was written
+}
+
+/**
+ * Diffs of a finished competition's instances, also the payload of
+ * `competition-summarized`
+ */
+export interface CompetitionSummary {
+  sessionId: string;
+  instances: InstanceDiffSummary[]; // instances whose worktree could be measured
+}
+
+/**
+ * Result of merging a competition's winner into the workspace
+ */
+export interface WinnerMerge {
+  instanceId: number;
+  branch: string;
+  committed: string | null; // commit of the winner's uncommitted work
+  status:
+    | { state: 'completed' }
+    | { state: 'paused'; operation: string; current: string | null; conflicts: string[] };
+}
+
+/**
+ * Issue from GitHub or Jira, as listed in the issue picker
+ */
+export interface Issue {
+  provider: IssueProvider;
+  key: string; // #42 on GitHub, PROJ-7 on Jira
+  title: string;
+  body: string;
+  url: string;
+  state: string;
+  labels: string[];
+  updatedAt: string | null;
+  task: string; // task description for a run working on the issue
+}
+
+/**
+ * Issue tracker and whether it is ready to use
+ */
+export interface IssueTrackerStatus {
+  provider: IssueProvider;
+  project: string | null; // GitHub repository or Jira project
+  configured: boolean;
+  hasToken: boolean;
+}
+
+/**
+ * Winning instance of a session in the team feed
+ */
+export interface FeedWinner {
+  instanceId: number;
+  agentName: string;
+  model: string | null;
+  runtime: string | null;
+}
+
+/**
+ * Report or diff attached to a session in the team feed, without its content
+ */
+export interface FeedArtifact {
+  id: string;
+  kind: string; // review | decision | diff
+  title: string;
+  createdAt: string;
+}
+
+/**
+ * A teammate's session synced to the shared session store
+ */
+export interface FeedEntry {
+  sessionId: string;
+  type: string;
+  task: string;
+  status: string;
+  author: string | null;
+  branch: string | null;
+  createdAt: string;
+  updatedAt: string;
+  winner: FeedWinner | null;
+  instances: number;
+  totalDuration: number | null;
+  totalFilesChanged: number | null;
+  totalLinesAdded: number | null;
+  totalLinesDeleted: number | null;
+  artifacts: FeedArtifact[];
+}
+
 /**
  * Round output (result from one agent in one round)
  */
@@ -322,9 +841,11 @@ export interface RoundOutput {
  */
 export interface DebateStatus {
   debateId: string;
-  currentRound: number;  // 1, 2, or 3
-  totalRounds: number;  // Always 3
-  status: string;  // "started", "round_1", "round_2", "round_3", "completed", "failed"
+  currentRound: number;  // 1-based
+  totalRounds: number;  // Debate rounds plus the synthesis round
+  status: string;  // "started", "round_N", "synthesis", "completed", "failed"
+  participants: number;  // Roles taking part in each debate round
+  synthesis: boolean;  // Whether the last round is a synthesis round
   roundOutputs: RoundOutput[];
   worktreePath: string;
   contextFiles: string[];
@@ -332,6 +853,25 @@ export interface DebateStatus {
   completedAt: string | null;
 }
 
+/**
+ * Destructive command awaiting the user's confirmation
+ */
+export type DangerousAction =
+  | { kind: 'gitPush'; remote?: string; branch?: string }
+  | { kind: 'deleteWorktree'; worktreeId: string }
+  | { kind: 'deletePath'; path: string }
+  | { kind: 'killTmuxSession'; sessionId: string };
+
+/**
+ * Ask the user to confirm a destructive command in a native dialog
+ *
+ * Resolves to the single-use token the command requires; rejects if the
+ * user declines.
+ */
+export async function requestConfirmation(action: DangerousAction): Promise<string> {
+  return invoke<string>('request_confirmation', { action });
+}
+
 /**
  * Type-safe Tauri command wrappers
  */
@@ -398,7 +938,8 @@ export const tauriApi = {
    */
   async deletePath(path: string): Promise<void> {
     try {
-      await invoke('delete_path', { path });
+      const confirmation = await requestConfirmation({ kind: 'deletePath', path });
+      await invoke('delete_path', { path, confirmation });
     } catch (error) {
       throw new Error(`Failed to delete: ${error}`);
     }
@@ -488,11 +1029,13 @@ export const tauriApi = {
   },
 
   /**
-   * Get command history
+   * Get command history, most recent first
    */
-  async getCommandHistory(): Promise<string[]> {
+  async getCommandHistory(filter?: CommandHistoryFilter): Promise<CommandHistoryEntry[]> {
     try {
-      const history = await invoke<string[]>('get_command_history');
+      const history = await invoke<CommandHistoryEntry[]>('get_command_history', {
+        filter: filter ?? null
+      });
       return history;
     } catch (error) {
       throw new Error(`Failed to get command history: ${error}`);
@@ -665,6 +1208,37 @@ export const tauriApi = {
     }
   },
 
+  /**
+   * Get the quick actions for a diagnostic: server fixes, suppression, explanation
+   */
+  async diagnosticQuickActions(
+    bufferId: string,
+    diagnostic: LspDiagnostic
+  ): Promise<DiagnosticQuickAction[]> {
+    try {
+      return await invoke<DiagnosticQuickAction[]>('diagnostic_quick_actions', {
+        bufferId,
+        diagnostic,
+      });
+    } catch (error) {
+      throw new Error(`Failed to get quick actions: ${error}`);
+    }
+  },
+
+  /**
+   * Ask the LLM to explain a diagnostic and propose a fix as a diff
+   */
+  async explainDiagnostic(
+    bufferId: string,
+    diagnostic: LspDiagnostic
+  ): Promise<DiagnosticExplanation> {
+    try {
+      return await invoke<DiagnosticExplanation>('explain_diagnostic', { bufferId, diagnostic });
+    } catch (error) {
+      throw new Error(`Failed to explain diagnostic: ${error}`);
+    }
+  },
+
   // ===== Git Commands =====
 
   /**
@@ -718,7 +1292,8 @@ export const tauriApi = {
    */
   async gitPush(remote?: string, branch?: string): Promise<void> {
     try {
-      await invoke('git_push', { remote, branch });
+      const confirmation = await requestConfirmation({ kind: 'gitPush', remote, branch });
+      await invoke('git_push', { remote, branch, confirmation });
     } catch (error) {
       throw new Error(`Failed to push: ${error}`);
     }
@@ -793,56 +1368,231 @@ export const tauriApi = {
     }
   },
 
-  // ===== Plugin Commands =====
-
   /**
-   * List all plugins
+   * List files with unresolved conflicts
+   *
+   * @param repoPath - Worktree to check instead of the workspace
    */
-  async listPlugins(): Promise<PluginInfo[]> {
+  async gitConflictedFiles(repoPath?: string): Promise<string[]> {
     try {
-      const plugins = await invoke<PluginInfo[]>('list_plugins');
-      return plugins;
+      return await invoke<string[]>('git_conflicted_files', { repoPath });
     } catch (error) {
-      throw new Error(`Failed to list plugins: ${error}`);
+      throw new Error(`Failed to list conflicted files: ${error}`);
     }
   },
 
   /**
-   * Get plugin by ID
+   * Split a conflicted file into its ours/base/theirs hunks
    */
-  async getPlugin(pluginId: string): Promise<PluginInfo | null> {
+  async gitLoadConflicts(path: string, repoPath?: string): Promise<ConflictFile> {
     try {
-      const plugin = await invoke<PluginInfo | null>('get_plugin', { pluginId });
-      return plugin;
+      return await invoke<ConflictFile>('git_load_conflicts', { path, repoPath });
     } catch (error) {
-      throw new Error(`Failed to get plugin: ${error}`);
+      throw new Error(`Failed to load conflicts: ${error}`);
     }
   },
 
   /**
-   * Enable a plugin
+   * Rewrite a conflicted file's markers with the merge base, dropping edits
    */
-  async enablePlugin(pluginId: string): Promise<void> {
+  async gitRestoreConflictBase(path: string, repoPath?: string): Promise<ConflictFile> {
     try {
-      await invoke('enable_plugin', { pluginId });
+      return await invoke<ConflictFile>('git_restore_conflict_base', { path, repoPath });
     } catch (error) {
-      throw new Error(`Failed to enable plugin: ${error}`);
+      throw new Error(`Failed to restore merge base: ${error}`);
     }
   },
 
   /**
-   * Disable a plugin
+   * Resolve every hunk of a file and stage it
+   *
+   * @returns Files still in conflict
    */
-  async disablePlugin(pluginId: string): Promise<void> {
+  async gitResolveConflicts(
+    path: string,
+    resolutions: ConflictResolution[],
+    repoPath?: string
+  ): Promise<string[]> {
     try {
-      await invoke('disable_plugin', { pluginId });
+      return await invoke<string[]>('git_resolve_conflicts', { path, resolutions, repoPath });
     } catch (error) {
-      throw new Error(`Failed to disable plugin: ${error}`);
+      throw new Error(`Failed to resolve conflicts: ${error}`);
     }
   },
 
   /**
-   * Install a plugin from path
+   * List conflicted files with their base, ours and theirs versions
+   *
+   * @param repoPath - Worktree a branch is merged in instead of the workspace
+   */
+  async getMergeConflicts(repoPath?: string): Promise<MergeConflict[]> {
+    try {
+      return await invoke<MergeConflict[]>('get_merge_conflicts', { repoPath });
+    } catch (error) {
+      throw new Error(`Failed to load merge conflicts: ${error}`);
+    }
+  },
+
+  /**
+   * Resolve a whole file with one side or custom content and stage it
+   *
+   * @returns Files still in conflict
+   */
+  async resolveConflict(
+    path: string,
+    resolution: FileResolution,
+    repoPath?: string
+  ): Promise<string[]> {
+    try {
+      return await invoke<string[]>('resolve_conflict', { path, resolution, repoPath });
+    } catch (error) {
+      throw new Error(`Failed to resolve conflict: ${error}`);
+    }
+  },
+
+  // ===== Annotation Commands =====
+
+  /**
+   * List annotations of one file, or of the whole workspace
+   */
+  async listAnnotations(path?: string): Promise<Annotation[]> {
+    try {
+      return await invoke<Annotation[]>('list_annotations', { path });
+    } catch (error) {
+      throw new Error(`Failed to list annotations: ${error}`);
+    }
+  },
+
+  /**
+   * Attach a note to a line range of a file
+   */
+  async addAnnotation(
+    path: string,
+    startLine: number,
+    endLine: number,
+    text: string
+  ): Promise<Annotation> {
+    try {
+      return await invoke<Annotation>('add_annotation', { path, startLine, endLine, text });
+    } catch (error) {
+      throw new Error(`Failed to add annotation: ${error}`);
+    }
+  },
+
+  /**
+   * Change the text or line range of a note
+   */
+  async updateAnnotation(
+    id: string,
+    changes: { text?: string; startLine?: number; endLine?: number }
+  ): Promise<Annotation> {
+    try {
+      return await invoke<Annotation>('update_annotation', { id, ...changes });
+    } catch (error) {
+      throw new Error(`Failed to update annotation: ${error}`);
+    }
+  },
+
+  /**
+   * Remove a note
+   */
+  async deleteAnnotation(id: string): Promise<void> {
+    try {
+      await invoke('delete_annotation', { id });
+    } catch (error) {
+      throw new Error(`Failed to delete annotation: ${error}`);
+    }
+  },
+
+  /**
+   * Agent context describing the given notes, or all of them
+   *
+   * @returns Empty string when there are no notes
+   */
+  async getAnnotationContext(ids?: string[]): Promise<string> {
+    try {
+      return await invoke<string>('get_annotation_context', { ids });
+    } catch (error) {
+      throw new Error(`Failed to get annotation context: ${error}`);
+    }
+  },
+
+  // ===== TODO Commands =====
+
+  /**
+   * List the tagged comments of the workspace
+   *
+   * @param refresh - Rescan the whole workspace instead of using the index
+   */
+  async listTodos(refresh?: boolean): Promise<TodoItem[]> {
+    try {
+      return await invoke<TodoItem[]>('list_todos', { refresh });
+    } catch (error) {
+      throw new Error(`Failed to list TODOs: ${error}`);
+    }
+  },
+
+  /**
+   * Rescan a saved file and return the updated list
+   */
+  async updateTodos(path: string): Promise<TodoItem[]> {
+    try {
+      return await invoke<TodoItem[]>('update_todos', { path });
+    } catch (error) {
+      throw new Error(`Failed to update TODOs: ${error}`);
+    }
+  },
+
+  // ===== Plugin Commands =====
+
+  /**
+   * List all plugins
+   */
+  async listPlugins(): Promise<PluginInfo[]> {
+    try {
+      const plugins = await invoke<PluginInfo[]>('list_plugins');
+      return plugins;
+    } catch (error) {
+      throw new Error(`Failed to list plugins: ${error}`);
+    }
+  },
+
+  /**
+   * Get plugin by ID
+   */
+  async getPlugin(pluginId: string): Promise<PluginInfo | null> {
+    try {
+      const plugin = await invoke<PluginInfo | null>('get_plugin', { pluginId });
+      return plugin;
+    } catch (error) {
+      throw new Error(`Failed to get plugin: ${error}`);
+    }
+  },
+
+  /**
+   * Enable a plugin
+   */
+  async enablePlugin(pluginId: string): Promise<void> {
+    try {
+      await invoke('enable_plugin', { pluginId });
+    } catch (error) {
+      throw new Error(`Failed to enable plugin: ${error}`);
+    }
+  },
+
+  /**
+   * Disable a plugin
+   */
+  async disablePlugin(pluginId: string): Promise<void> {
+    try {
+      await invoke('disable_plugin', { pluginId });
+    } catch (error) {
+      throw new Error(`Failed to disable plugin: ${error}`);
+    }
+  },
+
+  /**
+   * Install a plugin from path
    */
   async installPlugin(sourcePath: string): Promise<string> {
     try {
@@ -880,6 +1630,34 @@ export const tauriApi = {
     }
   },
 
+  /**
+   * Get success metrics for every agent with recorded outcomes
+   */
+  async getAgentStats(): Promise<Record<string, AgentStats>> {
+    try {
+      return await invoke<Record<string, AgentStats>>('get_agent_stats');
+    } catch (error) {
+      throw new Error(`Failed to get agent stats: ${error}`);
+    }
+  },
+
+  /**
+   * Rank agents for a task description, best first
+   *
+   * With `useLlm`, the keyword ranking is refined by the LLM when available.
+   */
+  async recommendAgents(
+    task: string,
+    limit?: number,
+    useLlm?: boolean
+  ): Promise<AgentRecommendation[]> {
+    try {
+      return await invoke<AgentRecommendation[]>('recommend_agents', { task, limit, useLlm });
+    } catch (error) {
+      throw new Error(`Failed to recommend agents: ${error}`);
+    }
+  },
+
   /**
    * Get information about a specific agent
    */
@@ -929,16 +1707,64 @@ export const tauriApi = {
   },
 
   /**
-   * Cancel a running agent execution
+   * Start listening to the `agent-output` events of an execution
+   *
+   * Returns the output after chunk `afterSeq`; pass the last `seq` handled
+   * when reconnecting.
+   */
+  async subscribeAgentOutput(executionId: string, afterSeq?: number): Promise<AgentOutputReplay> {
+    try {
+      return await invoke<AgentOutputReplay>('subscribe_agent_output', {
+        executionId,
+        afterSeq: afterSeq ?? null,
+      });
+    } catch (error) {
+      throw new Error(`Failed to subscribe to agent output: ${error}`);
+    }
+  },
+
+  /**
+   * Acknowledge the `agent-output` chunks of an execution up to `seq`
+   */
+  async ackAgentOutput(executionId: string, seq: number): Promise<void> {
+    try {
+      await invoke('ack_agent_output', { executionId, seq });
+    } catch (error) {
+      throw new Error(`Failed to acknowledge agent output: ${error}`);
+    }
+  },
+
+  /**
+   * Cancel a running agent execution, returning the tmux sessions killed
+   *
+   * With `workspacePath`, session history instances that ran in those
+   * sessions are marked cancelled.
    */
-  async cancelAgentExecution(executionId: string): Promise<void> {
+  async cancelAgentExecution(executionId: string, workspacePath?: string): Promise<string[]> {
     try {
-      await invoke('cancel_agent_execution', { executionId });
+      return await invoke<string[]>('cancel_agent_execution', {
+        executionId,
+        workspacePath: workspacePath ?? null,
+      });
     } catch (error) {
       throw new Error(`Failed to cancel agent execution: ${error}`);
     }
   },
 
+  /**
+   * List the alerts raised on agent output, oldest first, only those of
+   * `executionId` if given
+   */
+  async listAgentAlerts(executionId?: string): Promise<AgentAlert[]> {
+    try {
+      return await invoke<AgentAlert[]>('list_agent_alerts', {
+        executionId: executionId ?? null,
+      });
+    } catch (error) {
+      throw new Error(`Failed to list agent alerts: ${error}`);
+    }
+  },
+
   /**
    * Create a new tmux session for agent execution
    */
@@ -991,7 +1817,8 @@ export const tauriApi = {
    */
   async killTmuxSession(sessionId: string): Promise<void> {
     try {
-      await invoke('kill_tmux_session', { sessionId });
+      const confirmation = await requestConfirmation({ kind: 'killTmuxSession', sessionId });
+      await invoke('kill_tmux_session', { sessionId, confirmation });
     } catch (error) {
       throw new Error(`Failed to kill tmux session: ${error}`);
     }
@@ -1047,8 +1874,116 @@ export const tauriApi = {
     }
   },
 
+  // ===== Budget Commands =====
+
+  /**
+   * Get today's estimated AI spend, and a session's if given
+   */
+  async getBudgetStatus(sessionId?: string): Promise<BudgetStatus> {
+    try {
+      return await invoke<BudgetStatus>('get_budget_status', { sessionId });
+    } catch (error) {
+      throw new Error(`Failed to get budget status: ${error}`);
+    }
+  },
+
+  /**
+   * Get the AI spend limits
+   */
+  async getBudgetConfig(): Promise<BudgetConfig> {
+    try {
+      return await invoke<BudgetConfig>('get_budget_config');
+    } catch (error) {
+      throw new Error(`Failed to get budget config: ${error}`);
+    }
+  },
+
+  /**
+   * Update the AI spend limits
+   */
+  async setBudgetConfig(config: BudgetConfig): Promise<void> {
+    try {
+      await invoke('set_budget_config', { config });
+    } catch (error) {
+      throw new Error(`Failed to set budget config: ${error}`);
+    }
+  },
+
+  /**
+   * Allow going over a limit for the rest of the day or session
+   */
+  async approveBudgetOverride(scope: BudgetScope, sessionId?: string): Promise<BudgetStatus> {
+    try {
+      return await invoke<BudgetStatus>('approve_budget_override', { scope, sessionId });
+    } catch (error) {
+      throw new Error(`Failed to approve budget override: ${error}`);
+    }
+  },
+
   // ===== Claude Code Competition Commands =====
 
+  /**
+   * Explain which mode and instances auto mode would choose for a task,
+   * without running it
+   */
+  async explainExecutionPlan(task: string): Promise<AutoDecision> {
+    try {
+      return await invoke<AutoDecision>('explain_execution_plan', { task });
+    } catch (error) {
+      throw new Error(`Failed to explain execution plan: ${error}`);
+    }
+  },
+
+  /**
+   * Run a task in the mode the Coordinator picks, recording the decision
+   * on the session
+   */
+  async runAutoTask(task: string, workspacePath?: string): Promise<AutoRunResult> {
+    try {
+      return await invoke<AutoRunResult>('run_auto_task', { task, workspacePath });
+    } catch (error) {
+      throw new Error(`Failed to run auto task: ${error}`);
+    }
+  },
+
+  /**
+   * Hand a task from the quick task window to the Coordinator. Returns once
+   * the task is accepted; the outcome arrives as a notification.
+   */
+  async submitQuickTask(task: string): Promise<void> {
+    try {
+      await invoke('submit_quick_task', { task });
+    } catch (error) {
+      throw new Error(`Failed to submit quick task: ${error}`);
+    }
+  },
+
+  /**
+   * Hide the quick task window without running anything
+   */
+  async dismissQuickTask(): Promise<void> {
+    await invoke('dismiss_quick_task');
+  },
+
+  /**
+   * Get the global shortcut opening the quick task window
+   */
+  async getQuickTaskShortcut(): Promise<string> {
+    return await invoke<string>('get_quick_task_shortcut');
+  },
+
+  /**
+   * Change the global shortcut opening the quick task window; an empty
+   * shortcut disables it
+   */
+  async setQuickTaskShortcut(shortcut: string): Promise<void> {
+    try {
+      await invoke('set_quick_task_shortcut', { shortcut });
+    } catch (error) {
+      throw new Error(`Failed to set quick task shortcut: ${error}`);
+    }
+  },
+
   /**
    * Execute Claude Code Competition
    *
@@ -1136,62 +2071,179 @@ export const tauriApi = {
     }
   },
 
-  // ===== Claude Code Debate Commands =====
-
   /**
-   * Execute Claude Code Debate
-   *
-   * Creates a single git worktree and executes 3 rounds of debate sequentially
+   * Score a finished competition and mark its best instance as winner
    */
-  async executeDebate(
-    request: DebateRequest
-  ): Promise<DebateResult> {
+  async scoreCompetition(workspacePath: string, sessionId: string): Promise<CompetitionRanking> {
     try {
-      const result = await invoke<DebateResult>(
-        'execute_debate',
-        { request }
-      );
-      return result;
+      return await invoke<CompetitionRanking>('score_competition', { workspacePath, sessionId });
     } catch (error) {
-      throw new Error(`Failed to execute debate: ${error}`);
+      throw new Error(`Failed to score competition: ${error}`);
     }
   },
 
   /**
-   * Get debate status
+   * Summarize the diffs of a finished competition's instances again
    */
-  async getDebateStatus(
-    debateId: string
-  ): Promise<DebateStatus> {
+  async summarizeCompetition(workspacePath: string, sessionId: string): Promise<CompetitionSummary> {
     try {
-      const result = await invoke<DebateStatus>(
-        'get_debate_status',
-        { debateId }
-      );
-      return result;
+      return await invoke<CompetitionSummary>('summarize_competition', { workspacePath, sessionId });
     } catch (error) {
-      throw new Error(`Failed to get debate status: ${error}`);
+      throw new Error(`Failed to summarize competition: ${error}`);
     }
   },
 
   /**
-   * Cancel a running debate
+   * Merge the winning branch of a competition into the workspace
    */
-  async cancelDebate(
-    debateId: string,
-    cleanupWorktrees: boolean = true
-  ): Promise<void> {
+  async mergeCompetitionWinner(workspacePath: string, sessionId: string): Promise<WinnerMerge> {
     try {
-      await invoke('cancel_debate', { debateId, cleanupWorktrees });
+      return await invoke<WinnerMerge>('merge_competition_winner', { workspacePath, sessionId });
     } catch (error) {
-      throw new Error(`Failed to cancel debate: ${error}`);
+      throw new Error(`Failed to merge competition winner: ${error}`);
     }
   },
 
-  // ===== Session History Commands (v1.6.0) =====
+  // ===== Issue Tracker Commands =====
 
   /**
-   * Create a new worktree session
+   * Issue trackers, with whether each is configured and has a token
+   */
+  async getIssueTrackers(workspacePath: string): Promise<IssueTrackerStatus[]> {
+    try {
+      return await invoke<IssueTrackerStatus[]>('get_issue_trackers', { workspacePath });
+    } catch (error) {
+      throw new Error(`Failed to get issue trackers: ${error}`);
+    }
+  },
+
+  /**
+   * Save an issue tracker's API token in the OS keychain (null removes it)
+   */
+  async setIssueTrackerToken(provider: IssueProvider, token: string | null): Promise<void> {
+    try {
+      await invoke('set_issue_tracker_token', { provider, token });
+    } catch (error) {
+      throw new Error(`Failed to save issue tracker token: ${error}`);
+    }
+  },
+
+  /**
+   * Open issues of a tracker, most recently updated first
+   */
+  async listIssues(
+    workspacePath: string,
+    provider: IssueProvider,
+    query?: string,
+  ): Promise<Issue[]> {
+    try {
+      return await invoke<Issue[]>('list_issues', {
+        workspacePath,
+        provider,
+        query: query ?? null,
+      });
+    } catch (error) {
+      throw new Error(`Failed to list issues: ${error}`);
+    }
+  },
+
+  /**
+   * One issue, by #42 on GitHub or PROJ-7 on Jira
+   */
+  async getIssue(workspacePath: string, provider: IssueProvider, key: string): Promise<Issue> {
+    try {
+      return await invoke<Issue>('get_issue', { workspacePath, provider, key });
+    } catch (error) {
+      throw new Error(`Failed to get issue: ${error}`);
+    }
+  },
+
+  /**
+   * Link a session to the issue it works on (null unlinks it)
+   */
+  async linkSessionIssue(
+    workspacePath: string,
+    sessionId: string,
+    issue: IssueLink | null,
+  ): Promise<WorktreeSession> {
+    try {
+      return await invoke<WorktreeSession>('link_session_issue', {
+        workspacePath,
+        sessionId,
+        issue,
+      });
+    } catch (error) {
+      throw new Error(`Failed to link session to issue: ${error}`);
+    }
+  },
+
+  /**
+   * Post a session's report as a comment on its linked issue; returns the comment URL
+   */
+  async postSessionReport(workspacePath: string, sessionId: string): Promise<string> {
+    try {
+      return await invoke<string>('post_session_report', { workspacePath, sessionId });
+    } catch (error) {
+      throw new Error(`Failed to post session report: ${error}`);
+    }
+  },
+
+  // ===== Claude Code Debate Commands =====
+
+  /**
+   * Execute Claude Code Debate
+   *
+   * Creates a single git worktree and executes 3 rounds of debate sequentially
+   */
+  async executeDebate(
+    request: DebateRequest
+  ): Promise<DebateResult> {
+    try {
+      const result = await invoke<DebateResult>(
+        'execute_debate',
+        { request }
+      );
+      return result;
+    } catch (error) {
+      throw new Error(`Failed to execute debate: ${error}`);
+    }
+  },
+
+  /**
+   * Get debate status
+   */
+  async getDebateStatus(
+    debateId: string
+  ): Promise<DebateStatus> {
+    try {
+      const result = await invoke<DebateStatus>(
+        'get_debate_status',
+        { debateId }
+      );
+      return result;
+    } catch (error) {
+      throw new Error(`Failed to get debate status: ${error}`);
+    }
+  },
+
+  /**
+   * Cancel a running debate
+   */
+  async cancelDebate(
+    debateId: string,
+    cleanupWorktrees: boolean = true
+  ): Promise<void> {
+    try {
+      await invoke('cancel_debate', { debateId, cleanupWorktrees });
+    } catch (error) {
+      throw new Error(`Failed to cancel debate: ${error}`);
+    }
+  },
+
+  // ===== Session History Commands (v1.6.0) =====
+
+  /**
+   * Create a new worktree session
    */
   async createSession(workspacePath: string, session: import('@/types/worktree').WorktreeSession): Promise<import('@/types/worktree').WorktreeSession> {
     try {
@@ -1226,6 +2278,28 @@ export const tauriApi = {
     }
   },
 
+  /**
+   * Events journaled for a session, oldest first
+   */
+  async getSessionJournal(sessionId: string): Promise<JournalEntry[]> {
+    try {
+      return await invoke<JournalEntry[]>('get_session_journal', { sessionId });
+    } catch (error) {
+      throw new Error(`Failed to get session journal: ${error}`);
+    }
+  },
+
+  /**
+   * Compare the agent definitions a session ran with the current ones
+   */
+  async getSessionAgentChanges(workspacePath: string, sessionId: string): Promise<AgentChange[]> {
+    try {
+      return await invoke<AgentChange[]>('get_session_agent_changes', { workspacePath, sessionId });
+    } catch (error) {
+      throw new Error(`Failed to compare session agents: ${error}`);
+    }
+  },
+
   /**
    * Get all sessions for a workspace
    */
@@ -1291,14 +2365,199 @@ export const tauriApi = {
     }
   },
 
+  // ===== Observer Mode Commands =====
+
+  /**
+   * Export a session's events to a file or `tcp://host:port`
+   */
+  async exportSessionEvents(sessionId: string, target: string): Promise<StreamTarget> {
+    try {
+      return await invoke<StreamTarget>('export_session_events', { sessionId, target });
+    } catch (error) {
+      throw new Error(`Failed to export session events: ${error}`);
+    }
+  },
+
+  /**
+   * Stop exporting a session's events
+   */
+  async stopSessionExport(sessionId: string): Promise<StreamTarget[]> {
+    try {
+      return await invoke<StreamTarget[]>('stop_session_export', { sessionId });
+    } catch (error) {
+      throw new Error(`Failed to stop session export: ${error}`);
+    }
+  },
+
+  /**
+   * Follow another machine's session read-only
+   *
+   * `source` is an exported events file or `tcp://host:port` to listen on;
+   * `sessionStore` is a copy of that machine's sessions file.
+   */
+  async observeSession(
+    sessionId: string,
+    source: string,
+    sessionStore?: string
+  ): Promise<import('@/types/worktree').WorktreeSession | null> {
+    try {
+      return await invoke<import('@/types/worktree').WorktreeSession | null>('observe_session', {
+        sessionId,
+        source,
+        sessionStore,
+      });
+    } catch (error) {
+      throw new Error(`Failed to observe session: ${error}`);
+    }
+  },
+
+  /**
+   * Re-read an observed session from its session store
+   */
+  async getObservedSession(sessionId: string): Promise<import('@/types/worktree').WorktreeSession | null> {
+    try {
+      return await invoke<import('@/types/worktree').WorktreeSession | null>('get_observed_session', {
+        sessionId,
+      });
+    } catch (error) {
+      throw new Error(`Failed to get observed session: ${error}`);
+    }
+  },
+
+  /**
+   * List sessions open in observer mode
+   */
+  async listObservedSessions(): Promise<ObservedSession[]> {
+    try {
+      return await invoke<ObservedSession[]>('list_observed_sessions');
+    } catch (error) {
+      throw new Error(`Failed to list observed sessions: ${error}`);
+    }
+  },
+
+  /**
+   * Stop observing a session
+   */
+  async stopObserving(sessionId: string): Promise<void> {
+    try {
+      await invoke('stop_observing', { sessionId });
+    } catch (error) {
+      throw new Error(`Failed to stop observing: ${error}`);
+    }
+  },
+
+  // ===== Remote Control Commands =====
+
+  /**
+   * State of the remote-control server, with its token while running
+   */
+  async getRemoteStatus(): Promise<RemoteStatus> {
+    try {
+      return await invoke<RemoteStatus>('get_remote_status');
+    } catch (error) {
+      throw new Error(`Failed to get remote control status: ${error}`);
+    }
+  },
+
+  /**
+   * Start the remote-control server on `bind` (default: `[remote] bind`)
+   */
+  async startRemoteServer(bind?: string): Promise<RemoteStatus> {
+    try {
+      return await invoke<RemoteStatus>('start_remote_server', { bind: bind ?? null });
+    } catch (error) {
+      throw new Error(`Failed to start remote control: ${error}`);
+    }
+  },
+
+  /**
+   * Stop the remote-control server, disconnecting its clients
+   */
+  async stopRemoteServer(): Promise<boolean> {
+    try {
+      return await invoke<boolean>('stop_remote_server');
+    } catch (error) {
+      throw new Error(`Failed to stop remote control: ${error}`);
+    }
+  },
+
+  // ===== MCP Server Commands =====
+
+  /**
+   * State of the MCP server, with its URL and token while running
+   */
+  async getMcpStatus(): Promise<McpStatus> {
+    try {
+      return await invoke<McpStatus>('get_mcp_status');
+    } catch (error) {
+      throw new Error(`Failed to get MCP server status: ${error}`);
+    }
+  },
+
+  /**
+   * Start the MCP server on `bind` (default: `[mcp] bind`)
+   */
+  async startMcpServer(bind?: string): Promise<McpStatus> {
+    try {
+      return await invoke<McpStatus>('start_mcp_server', { bind: bind ?? null });
+    } catch (error) {
+      throw new Error(`Failed to start MCP server: ${error}`);
+    }
+  },
+
+  /**
+   * Stop the MCP server
+   */
+  async stopMcpServer(): Promise<boolean> {
+    try {
+      return await invoke<boolean>('stop_mcp_server');
+    } catch (error) {
+      throw new Error(`Failed to stop MCP server: ${error}`);
+    }
+  },
+
+  // ===== Team Feed Commands =====
+
+  /**
+   * Recent sessions of the workspace's repository in the shared session
+   * store, most recently updated first
+   */
+  async getTeamFeed(workspacePath: string, limit?: number): Promise<FeedEntry[]> {
+    try {
+      return await invoke<FeedEntry[]>('get_team_feed', { workspacePath, limit });
+    } catch (error) {
+      throw new Error(`Failed to load team feed: ${error}`);
+    }
+  },
+
+  /**
+   * Report or diff attached to a session in the team feed (read-only)
+   */
+  async getTeamArtifact(
+    workspacePath: string,
+    sessionId: string,
+    artifactId: string
+  ): Promise<import('@/types/worktree').SessionArtifact> {
+    try {
+      return await invoke<import('@/types/worktree').SessionArtifact>('get_team_artifact', {
+        workspacePath,
+        sessionId,
+        artifactId,
+      });
+    } catch (error) {
+      throw new Error(`Failed to load team artifact: ${error}`);
+    }
+  },
+
   // ===== Workspace Commands =====
 
   /**
-   * Select a workspace folder (must be a Git repository)
+   * Select a workspace folder (must be a Git repository). Without a path a
+   * folder selection dialog is shown.
    */
-  async selectWorkspace(): Promise<WorkspaceInfo> {
+  async selectWorkspace(path?: string): Promise<WorkspaceInfo> {
     try {
-      const result = await invoke<WorkspaceInfo>('select_workspace');
+      const result = await invoke<WorkspaceInfo>('select_workspace', { path });
       return result;
     } catch (error) {
       throw new Error(`Failed to select workspace: ${error}`);
@@ -1317,6 +2576,68 @@ export const tauriApi = {
     }
   },
 
+  // ===== Update Commands =====
+
+  /**
+   * Check the configured release channel for a newer version
+   */
+  async checkForUpdates(): Promise<UpdateRelease | null> {
+    try {
+      return await invoke<UpdateRelease | null>('check_for_updates');
+    } catch (error) {
+      throw new Error(`Failed to check for updates: ${error}`);
+    }
+  },
+
+  /**
+   * Download, verify and install the newest release, then restart the app
+   */
+  async installUpdate(): Promise<void> {
+    try {
+      await invoke('install_update');
+    } catch (error) {
+      throw new Error(`Failed to install update: ${error}`);
+    }
+  },
+
+  /**
+   * Get the update settings
+   */
+  async getUpdatesConfig(): Promise<UpdatesConfig> {
+    return await invoke<UpdatesConfig>('get_updates_config');
+  },
+
+  /**
+   * Update the update settings
+   */
+  async setUpdatesConfig(config: UpdatesConfig): Promise<void> {
+    try {
+      await invoke('set_updates_config', { config });
+    } catch (error) {
+      throw new Error(`Failed to save update settings: ${error}`);
+    }
+  },
+
+  // ===== Feature Flag Commands =====
+
+  /**
+   * List every feature flag with its current state
+   */
+  async listFeatureFlags(): Promise<FeatureFlag[]> {
+    return await invoke<FeatureFlag[]>('list_feature_flags');
+  },
+
+  /**
+   * Turn a feature flag on or off
+   */
+  async setFeatureFlag(name: string, enabled: boolean): Promise<void> {
+    try {
+      await invoke('set_feature_flag', { name, enabled });
+    } catch (error) {
+      throw new Error(`Failed to set feature flag: ${error}`);
+    }
+  },
+
   // ===== System Integration Commands =====
 
   /**
@@ -1366,6 +2687,72 @@ export const tauriApi = {
       throw new Error(`Failed to copy to clipboard: ${error}`);
     }
   },
+
+  // ===== Clipboard History Commands =====
+
+  /**
+   * Entries of the clipboard history, most recent first
+   */
+  async getClipboardHistory(): Promise<ClipboardEntry[]> {
+    try {
+      return await invoke<ClipboardEntry[]>('get_clipboard_history');
+    } catch (error) {
+      throw new Error(`Failed to get clipboard history: ${error}`);
+    }
+  },
+
+  /**
+   * Add copied text to the clipboard history
+   *
+   * Plugins and agent flows push their output here with their own source.
+   *
+   * @returns The new entry, or null if the text was blank
+   */
+  async pushClipboardHistory(
+    text: string,
+    source: ClipboardSource = 'editor'
+  ): Promise<ClipboardEntry | null> {
+    try {
+      return await invoke<ClipboardEntry | null>('push_clipboard_history', { text, source });
+    } catch (error) {
+      throw new Error(`Failed to push to clipboard history: ${error}`);
+    }
+  },
+
+  /**
+   * Remove one entry from the clipboard history
+   */
+  async removeClipboardEntry(id: string): Promise<void> {
+    try {
+      await invoke('remove_clipboard_entry', { id });
+    } catch (error) {
+      throw new Error(`Failed to remove clipboard entry: ${error}`);
+    }
+  },
+
+  /**
+   * Remove every entry from the clipboard history
+   */
+  async clearClipboardHistory(): Promise<void> {
+    try {
+      await invoke('clear_clipboard_history');
+    } catch (error) {
+      throw new Error(`Failed to clear clipboard history: ${error}`);
+    }
+  },
+
+  // ===== API Schema Commands =====
+
+  /**
+   * JSON Schemas of every backend command and event, generated from the Rust types
+   */
+  async getApiSchema(): Promise<ApiSchema> {
+    try {
+      return await invoke<ApiSchema>('get_api_schema');
+    } catch (error) {
+      throw new Error(`Failed to get API schema: ${error}`);
+    }
+  },
 };
 
 /**
@@ -1375,3 +2762,63 @@ export interface WorkspaceInfo {
   path: string;
   is_git_repo: boolean;
 }
+
+/**
+ * Experimental feature that can be switched on or off
+ */
+export interface FeatureFlag {
+  name: string;
+  description: string;
+  enabled: boolean;
+  default: boolean;
+}
+
+/**
+ * Update settings
+ */
+export interface UpdatesConfig {
+  channel: 'stable' | 'beta';
+  check_on_startup: boolean;
+  endpoint: string;
+}
+
+/**
+ * Release newer than the running version
+ */
+export interface UpdateRelease {
+  version: string;
+  notes: string;
+  pub_date: string | null;
+  artifact: {
+    url: string;
+    signature: string;
+  };
+}
+
+/**
+ * JSON Schema (draft-07) object or boolean schema
+ */
+export type JsonSchema = boolean | Record<string, unknown>;
+
+/**
+ * Backend command with the schemas of its arguments and response
+ */
+export interface ApiCommandSchema {
+  name: string;
+  module: string;
+  args: { name: string; required: boolean; schema: JsonSchema }[];
+  response: JsonSchema;
+  featureFlag: string | null;
+}
+
+/**
+ * Commands and events of the backend; `$ref`s point into `definitions`
+ */
+export interface ApiSchema {
+  apiVersion: number;
+  appVersion: string;
+  jsonSchema: string;
+  commands: ApiCommandSchema[];
+  events: { name: string; payload: JsonSchema }[];
+  definitions: Record<string, JsonSchema>;
+}
@@ -4,6 +4,7 @@
  * Provides type-safe wrappers for worktree-related Tauri commands
  */
 import { invoke } from '@tauri-apps/api/tauri';
+import { requestConfirmation } from './tauri';
 
 /**
  * Worktree information structure
@@ -64,6 +65,29 @@ export interface FileDiff {
   deletions: number;
 }
 
+/**
+ * Item-level change in a semantic diff
+ */
+export interface SemanticChange {
+  kind: 'added' | 'removed' | 'modified' | 'moved' | 'renamed';
+  item_kind: string;
+  name: string;
+  old_name?: string;
+  old_start?: number;
+  new_start?: number;
+  lines: DiffLine[];
+}
+
+/**
+ * Semantic diff structure, grouped by function, type and method
+ */
+export interface SemanticFileDiff {
+  file_path: string;
+  language: string;
+  changes: SemanticChange[];
+  other: DiffLine[]; // Changed lines outside any item
+}
+
 /**
  * Worktree API operations
  */
@@ -106,7 +130,8 @@ export const worktreeApi = {
    */
   deleteWorktree: async (worktreeId: string): Promise<void> => {
     try {
-      await invoke('delete_worktree', { worktreeId });
+      const confirmation = await requestConfirmation({ kind: 'deleteWorktree', worktreeId });
+      await invoke('delete_worktree', { worktreeId, confirmation });
     } catch (error) {
       throw new Error(`Failed to delete worktree: ${error}`);
     }
@@ -142,4 +167,22 @@ export const worktreeApi = {
       throw new Error(`Failed to get worktree diff: ${error}`);
     }
   },
+
+  /**
+   * Get semantic diff for a specific file in a worktree
+   *
+   * Fails for languages without a tree-sitter grammar.
+   *
+   * @param worktreePath - Absolute path to worktree
+   * @param filePath - Relative path to file within worktree
+   * @returns Changes grouped by item
+   */
+  getSemanticDiff: async (worktreePath: string, filePath: string): Promise<SemanticFileDiff> => {
+    try {
+      const diff = await invoke<SemanticFileDiff>('get_semantic_diff', { worktreePath, filePath });
+      return diff;
+    } catch (error) {
+      throw new Error(`Failed to get semantic diff: ${error}`);
+    }
+  },
 };
@@ -39,6 +39,9 @@ jest.mock('@monaco-editor/react', () => ({
         focus: jest.fn(),
         updateOptions: jest.fn(),
         addCommand: jest.fn(),
+        addAction: jest.fn(),
+        getSelection: jest.fn(),
+        createDecorationsCollection: jest.fn(() => ({ set: jest.fn() })),
       };
 
       const mockMonaco = {
@@ -48,9 +51,11 @@ jest.mock('@monaco-editor/react', () => ({
         },
         KeyMod: {
           CtrlCmd: 2048,
+          Alt: 512,
         },
         KeyCode: {
           KeyS: 49,
+          KeyN: 44,
         },
       };
 
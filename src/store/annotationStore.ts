@@ -0,0 +1,95 @@
+/**
+ * Annotation Store
+ *
+ * Notes attached to line ranges of workspace files, shown in the editor
+ * gutter and listed in the annotations panel
+ */
+import { create } from 'zustand';
+import { tauriApi, Annotation } from '@/services/tauri';
+
+interface AnnotationStore {
+  annotations: Annotation[];
+  /** Workspace the annotation paths are relative to */
+  workspaceRoot: string | null;
+  showAnnotationsPanel: boolean;
+  error: string | null;
+
+  // Actions
+  fetchAnnotations: () => Promise<void>;
+  addAnnotation: (path: string, startLine: number, endLine: number, text: string) => Promise<void>;
+  updateAnnotation: (id: string, text: string) => Promise<void>;
+  removeAnnotation: (id: string) => Promise<void>;
+  toggleAnnotationsPanel: () => void;
+  hideAnnotationsPanel: () => void;
+
+  /** Annotations of the file at the absolute path `path` */
+  annotationsFor: (path: string) => Annotation[];
+  /** Absolute path of an annotated file */
+  absolutePath: (annotation: Annotation) => string;
+}
+
+const message = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+export const useAnnotationStore = create<AnnotationStore>((set, get) => ({
+  annotations: [],
+  workspaceRoot: null,
+  showAnnotationsPanel: false,
+  error: null,
+
+  fetchAnnotations: async () => {
+    try {
+      const [annotations, workspace] = await Promise.all([
+        tauriApi.listAnnotations(),
+        tauriApi.getWorkspace(),
+      ]);
+      set({ annotations, workspaceRoot: workspace.path, error: null });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  addAnnotation: async (path, startLine, endLine, text) => {
+    try {
+      const annotation = await tauriApi.addAnnotation(path, startLine, endLine, text);
+      set({ annotations: [...get().annotations, annotation], error: null });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  updateAnnotation: async (id, text) => {
+    try {
+      const updated = await tauriApi.updateAnnotation(id, { text });
+      set({
+        annotations: get().annotations.map((a) => (a.id === id ? updated : a)),
+        error: null,
+      });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  removeAnnotation: async (id) => {
+    try {
+      await tauriApi.deleteAnnotation(id);
+      set({ annotations: get().annotations.filter((a) => a.id !== id), error: null });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  toggleAnnotationsPanel: () => {
+    set((state) => ({ showAnnotationsPanel: !state.showAnnotationsPanel }));
+  },
+
+  hideAnnotationsPanel: () => {
+    set({ showAnnotationsPanel: false });
+  },
+
+  annotationsFor: (path) => get().annotations.filter((a) => get().absolutePath(a) === path),
+
+  absolutePath: (annotation) => {
+    const root = get().workspaceRoot;
+    return root ? `${root.replace(/\/$/, '')}/${annotation.path}` : annotation.path;
+  },
+}));
@@ -0,0 +1,81 @@
+/**
+ * Clipboard Store
+ *
+ * Recently copied text, shown in the clipboard history picker so an older
+ * copy can be pasted again
+ */
+import { create } from 'zustand';
+import { tauriApi, ClipboardEntry, ClipboardSource } from '@/services/tauri';
+
+interface ClipboardStore {
+  entries: ClipboardEntry[];
+  showPicker: boolean;
+  error: string | null;
+
+  // Actions
+  fetchHistory: () => Promise<void>;
+  /** Add copied text to the top of the history */
+  push: (text: string, source?: ClipboardSource) => Promise<void>;
+  remove: (id: string) => Promise<void>;
+  clear: () => Promise<void>;
+  openPicker: () => void;
+  closePicker: () => void;
+}
+
+const message = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+export const useClipboardStore = create<ClipboardStore>((set, get) => ({
+  entries: [],
+  showPicker: false,
+  error: null,
+
+  fetchHistory: async () => {
+    try {
+      const entries = await tauriApi.getClipboardHistory();
+      set({ entries, error: null });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  push: async (text, source = 'editor') => {
+    try {
+      const entry = await tauriApi.pushClipboardHistory(text, source);
+      if (entry) {
+        set({
+          entries: [entry, ...get().entries.filter((e) => e.text !== entry.text)],
+          error: null,
+        });
+      }
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  remove: async (id) => {
+    try {
+      await tauriApi.removeClipboardEntry(id);
+      set({ entries: get().entries.filter((e) => e.id !== id), error: null });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  clear: async () => {
+    try {
+      await tauriApi.clearClipboardHistory();
+      set({ entries: [], error: null });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  openPicker: () => {
+    set({ showPicker: true });
+    get().fetchHistory();
+  },
+
+  closePicker: () => {
+    set({ showPicker: false });
+  },
+}));
@@ -8,6 +8,7 @@
 import { create } from 'zustand';
 import { persist, createJSONStorage } from 'zustand/middleware';
 import { tauriApi } from '@/services/tauri';
+import { useTodoStore } from '@/store/todoStore';
 
 /**
  * Represents an open editor tab
@@ -296,6 +297,7 @@ export const useEditorStore = create<EditorStore>()(
           set({
             tabs: tabs.map((t) => (t.id === id ? { ...t, isDirty: false } : t)),
           });
+          useTodoStore.getState().fileSaved(tab.path);
         } catch (error) {
           console.error('Failed to save file:', error);
           throw error;
@@ -308,9 +310,12 @@ export const useEditorStore = create<EditorStore>()(
 
         await Promise.all(
           dirtyTabs.map((tab) => {
-            return tauriApi.saveFile(tab.path, tab.content).catch((error: unknown) => {
-              console.error(`Failed to save ${tab.path}:`, error);
-            });
+            return tauriApi
+              .saveFile(tab.path, tab.content)
+              .then(() => useTodoStore.getState().fileSaved(tab.path))
+              .catch((error: unknown) => {
+                console.error(`Failed to save ${tab.path}:`, error);
+              });
           })
         );
 
@@ -0,0 +1,47 @@
+/**
+ * Feature Flag Store
+ *
+ * Mirrors the backend feature flags so panels of experimental features can
+ * be shown or hidden
+ */
+import { create } from 'zustand';
+import { tauriApi, FeatureFlag } from '@/services/tauri';
+
+interface FeatureFlagStore {
+  flags: FeatureFlag[];
+  error: string | null;
+
+  // Actions
+  fetchFlags: () => Promise<void>;
+  setFlag: (name: string, enabled: boolean) => Promise<void>;
+  isEnabled: (name: string) => boolean;
+}
+
+export const useFeatureFlagStore = create<FeatureFlagStore>((set, get) => ({
+  flags: [],
+  error: null,
+
+  fetchFlags: async () => {
+    try {
+      const flags = await tauriApi.listFeatureFlags();
+      set({ flags, error: null });
+    } catch (error) {
+      set({ error: error instanceof Error ? error.message : String(error) });
+    }
+  },
+
+  setFlag: async (name: string, enabled: boolean) => {
+    try {
+      await tauriApi.setFeatureFlag(name, enabled);
+      set({
+        flags: get().flags.map((flag) => (flag.name === name ? { ...flag, enabled } : flag)),
+        error: null,
+      });
+    } catch (error) {
+      set({ error: error instanceof Error ? error.message : String(error) });
+    }
+  },
+
+  // Flags that have not been loaded yet count as off
+  isEnabled: (name: string) => get().flags.some((flag) => flag.name === name && flag.enabled),
+}));
@@ -0,0 +1,97 @@
+/**
+ * TODO Store
+ *
+ * TODO/FIXME/HACK comments across the workspace, listed in the TODO panel
+ * and kept current as files are saved
+ */
+import { create } from 'zustand';
+import { tauriApi, TodoItem } from '@/services/tauri';
+
+/** Position the editor should scroll to once the file is open */
+export interface RevealTarget {
+  path: string; // absolute
+  line: number;
+  column: number;
+}
+
+interface TodoStore {
+  todos: TodoItem[];
+  /** Workspace the TODO paths are relative to */
+  workspaceRoot: string | null;
+  /** Whether the workspace has been scanned, so saves need rescanning */
+  loaded: boolean;
+  isLoading: boolean;
+  showTodosPanel: boolean;
+  revealTarget: RevealTarget | null;
+  error: string | null;
+
+  // Actions
+  fetchTodos: (refresh?: boolean) => Promise<void>;
+  /** Rescan a file after it was saved */
+  fileSaved: (path: string) => Promise<void>;
+  toggleTodosPanel: () => void;
+  hideTodosPanel: () => void;
+  /** Ask the editor to show the TODO once its file is open */
+  reveal: (todo: TodoItem) => void;
+  clearReveal: () => void;
+
+  /** Absolute path of the file a TODO is in */
+  absolutePath: (todo: TodoItem) => string;
+}
+
+const message = (error: unknown) => (error instanceof Error ? error.message : String(error));
+
+export const useTodoStore = create<TodoStore>((set, get) => ({
+  todos: [],
+  workspaceRoot: null,
+  loaded: false,
+  isLoading: false,
+  showTodosPanel: false,
+  revealTarget: null,
+  error: null,
+
+  fetchTodos: async (refresh) => {
+    set({ isLoading: true });
+    try {
+      const [todos, workspace] = await Promise.all([
+        tauriApi.listTodos(refresh),
+        tauriApi.getWorkspace(),
+      ]);
+      set({ todos, workspaceRoot: workspace.path, loaded: true, isLoading: false, error: null });
+    } catch (error) {
+      set({ isLoading: false, error: message(error) });
+    }
+  },
+
+  fileSaved: async (path) => {
+    // Nothing to keep current until the workspace has been scanned
+    if (!get().loaded) return;
+    try {
+      const todos = await tauriApi.updateTodos(path);
+      set({ todos, error: null });
+    } catch (error) {
+      set({ error: message(error) });
+    }
+  },
+
+  toggleTodosPanel: () => {
+    set((state) => ({ showTodosPanel: !state.showTodosPanel }));
+  },
+
+  hideTodosPanel: () => {
+    set({ showTodosPanel: false });
+  },
+
+  reveal: (todo) => {
+    set({ revealTarget: { path: get().absolutePath(todo), line: todo.line, column: todo.column } });
+  },
+
+  clearReveal: () => {
+    set({ revealTarget: null });
+  },
+
+  absolutePath: (todo) => {
+    const root = get().workspaceRoot;
+    return root ? `${root.replace(/\/$/, '')}/${todo.path}` : todo.path;
+  },
+}));
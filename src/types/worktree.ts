@@ -3,9 +3,16 @@
  * Inspired by Vibe Kanban's task-centric approach
  */
 
-export type SessionType = 'competition' | 'ensemble' | 'debate';
-export type SessionStatus = 'running' | 'completed' | 'failed' | 'paused';
-export type InstanceStatus = 'idle' | 'running' | 'completed' | 'failed' | 'paused' | 'archived';
+export type SessionType = 'competition' | 'ensemble' | 'debate' | 'single' | 'parallel';
+export type SessionStatus = 'running' | 'completed' | 'failed' | 'paused' | 'cancelled';
+export type InstanceStatus =
+  | 'idle'
+  | 'running'
+  | 'completed'
+  | 'failed'
+  | 'paused'
+  | 'archived'
+  | 'cancelled';
 export type AgentRuntime = 'claude' | 'codex' | 'gemini';
 
 export interface RuntimeAllocation {
@@ -33,6 +40,9 @@ export interface WorktreeInstance {
   runtime?: AgentRuntime;
   model?: string;
   runtimeLabel?: string;
+  agentVersion?: string; // Version of the agent definition the instance ran
+  touchedModules?: string[]; // Directories, or root files, the diff touched
+  diffSummary?: string; // Paragraph describing the diff, written by an LLM
 
   // Competition evaluation fields
   testsPassed?: number;
@@ -52,6 +62,72 @@ export interface ChatMessage {
   instanceId?: number; // Which instance this message is for
 }
 
+/**
+ * Report attached to a session, such as a code review
+ */
+export interface SessionArtifact {
+  id: string;
+  // decision: how auto mode chose the session's mode; ranking: scores of a competition
+  kind: 'review' | 'decision' | 'diff' | 'ranking';
+  title: string;
+  content: string; // Markdown, or a unified diff for kind 'diff'
+  createdAt: string;
+}
+
+/**
+ * Who ran a session, on which branch of which repository
+ */
+export interface SessionOrigin {
+  author?: string; // Name <email>
+  branch?: string;
+  repository?: string; // e.g. github.com/org/repo
+}
+
+/**
+ * Definition of a registry agent as a session ran it
+ */
+export interface AgentPin {
+  name: string;
+  version?: string | null;
+  path: string;
+  definition: string; // Whole definition file, prompt included
+}
+
+/**
+ * Suspicious content found by a prompt injection scan
+ */
+export interface InjectionFinding {
+  kind: 'instruction' | 'hidden_unicode' | 'encoded_blob';
+  line: number; // Line of the scanned content, starting at 1
+  excerpt: string; // Hidden characters spelled out as <U+200B>
+}
+
+/**
+ * Prompt injection scan of content sent to an agent
+ */
+export interface ContextScan {
+  source: string; // e.g. 'agent context', 'review diff'
+  policy: 'flag' | 'strip';
+  scannedAt: string;
+  chars: number;
+  findings: InjectionFinding[];
+}
+
+/**
+ * Issue tracker a task can be picked from
+ */
+export type IssueProvider = 'github' | 'jira';
+
+/**
+ * Issue a session works on
+ */
+export interface IssueLink {
+  provider: IssueProvider;
+  key: string; // #42 on GitHub, PROJ-7 on Jira
+  title: string;
+  url: string;
+}
+
 /**
  * Evaluation metrics for Competition mode
  */
@@ -130,6 +206,29 @@ export interface WorktreeSession {
   totalLinesAdded?: number;
   totalLinesDeleted?: number;
 
+  // Optimizer analysis the session ran with; finished sessions teach the
+  // optimizer which subtask counts succeed for each complexity class
+  complexityClass?: string;
+  subtaskCount?: number;
+
+  // Reports attached after the run
+  artifacts?: SessionArtifact[];
+
+  // Feature flags in effect when the session was created
+  featureFlags?: Record<string, boolean>;
+
+  // Who ran the session and where, recorded when it was first stored
+  origin?: SessionOrigin;
+
+  // Issue the session works on, picked from an issue tracker
+  issue?: IssueLink;
+
+  // Definitions of the registry agents the instances ran, by agent name
+  agents?: Record<string, AgentPin>;
+
+  // Prompt injection scans of content attached to agent tasks, for auditing
+  contextScans?: ContextScan[];
+
   // Ensemble mode specific: 統合フェーズの状態
   integrationPhase?: 'pending' | 'in_progress' | 'completed';
   // 統合AIのinstance ID